@@ -0,0 +1,662 @@
+// This example demonstrates how to stream textures to the GPU from a dedicated transfer queue,
+// while the render loop keeps drawing without waiting for them.
+//
+// The window shows a grid of tiles, each of which has its own texture. The textures are generated
+// on a separate thread, which stands in for reading and decoding files from disk. Whenever a
+// texture is ready, the render loop hands it to a `vulkano::transfer::Uploader`, which copies the
+// data into staging memory and records the copy to the image into a command buffer. Calling
+// `flush` once per frame submits all of the copies that were recorded during that frame to the
+// transfer queue. Recording and flushing never block, so the frame rate doesn't depend on how many
+// textures are being uploaded.
+//
+// Each upload returns a ticket. The render loop polls the tickets of the tiles every frame, and
+// starts drawing a tile once its upload has finished, which makes the textures pop in one after
+// the other. A ticket can also be turned into a future, so that the first submission that uses
+// the resource waits for the upload on the GPU instead. This is done for the placeholder texture
+// that is drawn for the tiles that haven't been uploaded yet.
+//
+// Many devices have a queue family that only supports transfer operations, which usually
+// corresponds to a DMA engine that can copy data at the same time as the graphics hardware is
+// rendering. If the device doesn't have one, the uploads are submitted to the graphics queue.
+
+use std::{
+    error::Error,
+    sync::{mpsc, Arc},
+    thread,
+    time::Duration,
+};
+use vulkano::{
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, CommandBufferBeginInfo, CommandBufferLevel,
+        CommandBufferUsage, RecordingCommandBuffer, RenderPassBeginInfo,
+    },
+    descriptor_set::{
+        allocator::StandardDescriptorSetAllocator, DescriptorSet, WriteDescriptorSet,
+    },
+    device::{
+        physical::PhysicalDeviceType, Device, DeviceCreateInfo, DeviceExtensions, DeviceFeatures,
+        QueueCreateInfo, QueueFlags,
+    },
+    format::Format,
+    image::{
+        sampler::{Filter, Sampler, SamplerAddressMode, SamplerCreateInfo},
+        view::ImageView,
+        Image, ImageCreateInfo, ImageSubresourceLayers, ImageUsage,
+    },
+    instance::{Instance, InstanceCreateFlags, InstanceCreateInfo},
+    memory::allocator::{AllocationCreateInfo, StandardMemoryAllocator},
+    pipeline::{
+        graphics::{
+            color_blend::{ColorBlendAttachmentState, ColorBlendState},
+            input_assembly::{InputAssemblyState, PrimitiveTopology},
+            multisample::MultisampleState,
+            rasterization::RasterizationState,
+            vertex_input::VertexInputState,
+            viewport::{Viewport, ViewportState},
+            GraphicsPipelineCreateInfo,
+        },
+        layout::PipelineDescriptorSetLayoutCreateInfo,
+        DynamicState, GraphicsPipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+        PipelineShaderStageCreateInfo,
+    },
+    render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass, Subpass},
+    swapchain::{
+        acquire_next_image, Surface, Swapchain, SwapchainCreateInfo, SwapchainPresentInfo,
+    },
+    sync::{self, GpuFuture, Sharing},
+    transfer::Uploader,
+    Validated, Version, VulkanError, VulkanLibrary,
+};
+use winit::{
+    event::{Event, WindowEvent},
+    event_loop::{ControlFlow, EventLoop},
+    window::WindowBuilder,
+};
+
+/// The number of tiles in each row and column of the grid.
+const GRID_SIZE: u32 = 4;
+
+/// The width and height of the texture of each tile, in texels.
+const TEXTURE_SIZE: u32 = 512;
+
+const TEXTURE_FORMAT: Format = Format::R8G8B8A8_SRGB;
+
+fn main() -> Result<(), impl Error> {
+    let event_loop = EventLoop::new().unwrap();
+
+    let library = VulkanLibrary::new().unwrap();
+    let required_extensions = Surface::required_extensions(&event_loop).unwrap();
+    let instance = Instance::new(
+        library,
+        InstanceCreateInfo {
+            flags: InstanceCreateFlags::ENUMERATE_PORTABILITY,
+            enabled_extensions: required_extensions,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let window = Arc::new(WindowBuilder::new().build(&event_loop).unwrap());
+    let surface = Surface::from_window(instance.clone(), window.clone()).unwrap();
+
+    let mut device_extensions = DeviceExtensions {
+        khr_swapchain: true,
+        ..DeviceExtensions::empty()
+    };
+    // The uploader tracks the completion of uploads with a timeline semaphore.
+    let device_features = DeviceFeatures {
+        timeline_semaphore: true,
+        ..DeviceFeatures::empty()
+    };
+    let (physical_device, graphics_family_index) = instance
+        .enumerate_physical_devices()
+        .unwrap()
+        .filter(|p| {
+            p.api_version() >= Version::V1_2 || p.supported_extensions().khr_timeline_semaphore
+        })
+        .filter(|p| p.supported_extensions().contains(&device_extensions))
+        .filter(|p| p.supported_features().contains(&device_features))
+        .filter_map(|p| {
+            p.queue_family_properties()
+                .iter()
+                .enumerate()
+                .position(|(i, q)| {
+                    q.queue_flags.intersects(QueueFlags::GRAPHICS)
+                        && p.surface_support(i as u32, &surface).unwrap_or(false)
+                })
+                .map(|i| (p, i as u32))
+        })
+        .min_by_key(|(p, _)| match p.properties().device_type {
+            PhysicalDeviceType::DiscreteGpu => 0,
+            PhysicalDeviceType::IntegratedGpu => 1,
+            PhysicalDeviceType::VirtualGpu => 2,
+            PhysicalDeviceType::Cpu => 3,
+            PhysicalDeviceType::Other => 4,
+            _ => 5,
+        })
+        .unwrap();
+
+    println!(
+        "Using device: {} (type: {:?})",
+        physical_device.properties().device_name,
+        physical_device.properties().device_type,
+    );
+
+    // A queue family that supports transfer operations, but not graphics or compute operations.
+    let transfer_family_index = physical_device
+        .queue_family_properties()
+        .iter()
+        .position(|q| {
+            q.queue_flags.intersects(QueueFlags::TRANSFER)
+                && !q
+                    .queue_flags
+                    .intersects(QueueFlags::GRAPHICS | QueueFlags::COMPUTE)
+        })
+        .map(|i| i as u32);
+
+    let mut queue_create_infos = vec![QueueCreateInfo {
+        queue_family_index: graphics_family_index,
+        ..Default::default()
+    }];
+
+    if let Some(queue_family_index) = transfer_family_index {
+        queue_create_infos.push(QueueCreateInfo {
+            queue_family_index,
+            ..Default::default()
+        });
+    }
+
+    // Timeline semaphores became a core part of Vulkan 1.2.
+    if physical_device.api_version() < Version::V1_2 {
+        device_extensions.khr_timeline_semaphore = true;
+    }
+
+    let (device, mut queues) = Device::new(
+        physical_device,
+        DeviceCreateInfo {
+            enabled_extensions: device_extensions,
+            enabled_features: device_features,
+            queue_create_infos,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let queue = queues.next().unwrap();
+    let transfer_queue = queues.next().unwrap_or_else(|| queue.clone());
+
+    println!(
+        "Uploading from queue family {}",
+        transfer_queue.queue_family_index(),
+    );
+
+    let (mut swapchain, images) = {
+        let surface_capabilities = device
+            .physical_device()
+            .surface_capabilities(&surface, Default::default())
+            .unwrap();
+        let image_format = device
+            .physical_device()
+            .surface_formats(&surface, Default::default())
+            .unwrap()[0]
+            .0;
+
+        Swapchain::new(
+            device.clone(),
+            surface,
+            SwapchainCreateInfo {
+                min_image_count: surface_capabilities.min_image_count.max(2),
+                image_format,
+                image_extent: window.inner_size().into(),
+                image_usage: ImageUsage::COLOR_ATTACHMENT,
+                composite_alpha: surface_capabilities
+                    .supported_composite_alpha
+                    .into_iter()
+                    .next()
+                    .unwrap(),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    };
+
+    let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+    let descriptor_set_allocator = Arc::new(StandardDescriptorSetAllocator::new(
+        device.clone(),
+        Default::default(),
+    ));
+    let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+        device.clone(),
+        Default::default(),
+    ));
+
+    let mut uploader = Uploader::new(
+        transfer_queue.clone(),
+        memory_allocator.clone(),
+        command_buffer_allocator.clone(),
+    )
+    .unwrap();
+
+    // Vulkano doesn't transfer the ownership of the textures from the transfer queue family to the
+    // graphics queue family, so they must be shared by both families instead.
+    let texture_sharing = if transfer_queue.queue_family_index() != graphics_family_index {
+        Sharing::Concurrent(
+            [graphics_family_index, transfer_queue.queue_family_index()]
+                .into_iter()
+                .collect(),
+        )
+    } else {
+        Sharing::Exclusive
+    };
+    let create_texture = move |extent: u32| {
+        Image::new(
+            memory_allocator.clone(),
+            ImageCreateInfo {
+                sharing: texture_sharing.clone(),
+                ..ImageCreateInfo::sampled_2d_no_mipmap(TEXTURE_FORMAT, [extent, extent])
+            },
+            AllocationCreateInfo::default(),
+        )
+        .unwrap()
+    };
+
+    let render_pass = vulkano::single_pass_renderpass!(
+        device.clone(),
+        attachments: {
+            color: {
+                format: swapchain.image_format(),
+                samples: 1,
+                load_op: Clear,
+                store_op: Store,
+            },
+        },
+        pass: {
+            color: [color],
+            depth_stencil: {},
+        },
+    )
+    .unwrap();
+
+    let pipeline = {
+        let vs = vs::load(device.clone())
+            .unwrap()
+            .entry_point("main")
+            .unwrap();
+        let fs = fs::load(device.clone())
+            .unwrap()
+            .entry_point("main")
+            .unwrap();
+        let stages = [
+            PipelineShaderStageCreateInfo::new(vs),
+            PipelineShaderStageCreateInfo::new(fs),
+        ];
+        let layout = PipelineLayout::new(
+            device.clone(),
+            PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+                .into_pipeline_layout_create_info(device.clone())
+                .unwrap(),
+        )
+        .unwrap();
+        let subpass = Subpass::from(render_pass.clone(), 0).unwrap();
+
+        GraphicsPipeline::new(
+            device.clone(),
+            None,
+            GraphicsPipelineCreateInfo {
+                stages: stages.into_iter().collect(),
+                vertex_input_state: Some(VertexInputState::default()),
+                input_assembly_state: Some(InputAssemblyState {
+                    topology: PrimitiveTopology::TriangleStrip,
+                    ..Default::default()
+                }),
+                viewport_state: Some(ViewportState::default()),
+                rasterization_state: Some(RasterizationState::default()),
+                multisample_state: Some(MultisampleState::default()),
+                color_blend_state: Some(ColorBlendState::with_attachment_states(
+                    subpass.num_color_attachments(),
+                    ColorBlendAttachmentState::default(),
+                )),
+                dynamic_state: [DynamicState::Viewport].into_iter().collect(),
+                subpass: Some(subpass.into()),
+                ..GraphicsPipelineCreateInfo::layout(layout)
+            },
+        )
+        .unwrap()
+    };
+
+    let sampler = Sampler::new(
+        device.clone(),
+        SamplerCreateInfo {
+            mag_filter: Filter::Linear,
+            min_filter: Filter::Linear,
+            address_mode: [SamplerAddressMode::ClampToEdge; 3],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let create_set = {
+        let set_layout = pipeline.layout().set_layouts()[0].clone();
+
+        move |image: Arc<Image>| {
+            DescriptorSet::new(
+                descriptor_set_allocator.clone(),
+                set_layout.clone(),
+                [WriteDescriptorSet::image_view_sampler(
+                    0,
+                    ImageView::new_default(image).unwrap(),
+                    sampler.clone(),
+                )],
+                [],
+            )
+            .unwrap()
+        }
+    };
+
+    // The placeholder is uploaded like the other textures. Instead of polling its ticket, the
+    // first frame waits for the upload on the GPU, by joining the future of the ticket.
+    let placeholder = create_texture(1);
+    let placeholder_ticket = uploader
+        .upload_image(
+            &[128, 128, 128, 255],
+            &placeholder,
+            ImageSubresourceLayers::from_parameters(TEXTURE_FORMAT, 1),
+        )
+        .unwrap();
+    uploader.flush().unwrap();
+    let placeholder_set = create_set(placeholder);
+
+    // Generates the textures of the tiles in the background, and sends each one to the render loop
+    // as soon as it is ready.
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for index in 0..GRID_SIZE * GRID_SIZE {
+            if sender.send((index, generate_texture(index))).is_err() {
+                break;
+            }
+        }
+    });
+
+    // The descriptor set of each tile, once the upload of its texture has finished.
+    let mut tile_sets = vec![None; (GRID_SIZE * GRID_SIZE) as usize];
+    // The tiles whose texture is being uploaded.
+    let mut pending_uploads = Vec::new();
+
+    let mut viewport = Viewport {
+        offset: [0.0, 0.0],
+        extent: [0.0, 0.0],
+        depth_range: 0.0..=1.0,
+    };
+    let mut framebuffers = window_size_dependent_setup(&images, render_pass.clone(), &mut viewport);
+
+    let mut recreate_swapchain = false;
+    let mut previous_frame_end = Some(placeholder_ticket.into_future().unwrap().boxed());
+
+    event_loop.run(move |event, elwt| {
+        elwt.set_control_flow(ControlFlow::Poll);
+
+        match event {
+            Event::WindowEvent {
+                event: WindowEvent::CloseRequested,
+                ..
+            } => {
+                elwt.exit();
+            }
+            Event::WindowEvent {
+                event: WindowEvent::Resized(_),
+                ..
+            } => {
+                recreate_swapchain = true;
+            }
+            Event::WindowEvent {
+                event: WindowEvent::RedrawRequested,
+                ..
+            } => {
+                let image_extent: [u32; 2] = window.inner_size().into();
+
+                if image_extent.contains(&0) {
+                    return;
+                }
+
+                previous_frame_end.as_mut().unwrap().cleanup_finished();
+
+                // Record the uploads of the textures that were generated since the last frame, and
+                // submit them all at once.
+                for (index, data) in receiver.try_iter() {
+                    let texture = create_texture(TEXTURE_SIZE);
+                    let ticket = uploader
+                        .upload_image(
+                            &data,
+                            &texture,
+                            ImageSubresourceLayers::from_parameters(TEXTURE_FORMAT, 1),
+                        )
+                        .unwrap();
+                    pending_uploads.push((index, texture, ticket));
+                }
+
+                uploader.flush().unwrap();
+
+                // Polling a ticket never blocks. Once an upload has finished, the tile is drawn
+                // with its texture from this frame onwards.
+                pending_uploads.retain(|(index, texture, ticket)| {
+                    if ticket.is_complete().unwrap() {
+                        tile_sets[*index as usize] = Some(create_set(texture.clone()));
+                        false
+                    } else {
+                        true
+                    }
+                });
+
+                if recreate_swapchain {
+                    let (new_swapchain, new_images) = swapchain
+                        .recreate(SwapchainCreateInfo {
+                            image_extent,
+                            ..swapchain.create_info()
+                        })
+                        .expect("failed to recreate swapchain");
+
+                    swapchain = new_swapchain;
+                    framebuffers = window_size_dependent_setup(
+                        &new_images,
+                        render_pass.clone(),
+                        &mut viewport,
+                    );
+                    recreate_swapchain = false;
+                }
+
+                let (image_index, suboptimal, acquire_future) =
+                    match acquire_next_image(swapchain.clone(), None).map_err(Validated::unwrap) {
+                        Ok(r) => r,
+                        Err(VulkanError::OutOfDate) => {
+                            recreate_swapchain = true;
+                            return;
+                        }
+                        Err(e) => panic!("failed to acquire next image: {e}"),
+                    };
+
+                if suboptimal {
+                    recreate_swapchain = true;
+                }
+
+                let mut builder = RecordingCommandBuffer::new(
+                    command_buffer_allocator.clone(),
+                    queue.queue_family_index(),
+                    CommandBufferLevel::Primary,
+                    CommandBufferBeginInfo {
+                        usage: CommandBufferUsage::OneTimeSubmit,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+
+                builder
+                    .begin_render_pass(
+                        RenderPassBeginInfo {
+                            clear_values: vec![Some([0.1, 0.1, 0.1, 1.0].into())],
+                            ..RenderPassBeginInfo::framebuffer(
+                                framebuffers[image_index as usize].clone(),
+                            )
+                        },
+                        Default::default(),
+                    )
+                    .unwrap()
+                    .set_viewport(0, [viewport.clone()].into_iter().collect())
+                    .unwrap()
+                    .bind_pipeline_graphics(pipeline.clone())
+                    .unwrap();
+
+                let tile_extent = 2.0 / GRID_SIZE as f32;
+
+                for (index, set) in tile_sets.iter().enumerate() {
+                    let (x, y) = (index as u32 % GRID_SIZE, index as u32 / GRID_SIZE);
+                    let set = set.as_ref().unwrap_or(&placeholder_set);
+
+                    builder
+                        .bind_descriptor_sets(
+                            PipelineBindPoint::Graphics,
+                            pipeline.layout().clone(),
+                            0,
+                            set.clone(),
+                        )
+                        .unwrap()
+                        .push_constants(
+                            pipeline.layout().clone(),
+                            0,
+                            vs::PushConstants {
+                                offset: [
+                                    -1.0 + (x as f32 + 0.05) * tile_extent,
+                                    -1.0 + (y as f32 + 0.05) * tile_extent,
+                                ],
+                                scale: [tile_extent * 0.9; 2],
+                            },
+                        )
+                        .unwrap();
+
+                    unsafe {
+                        builder.draw(4, 1, 0, 0).unwrap();
+                    }
+                }
+
+                builder.end_render_pass(Default::default()).unwrap();
+
+                let command_buffer = builder.end().unwrap();
+                let future = previous_frame_end
+                    .take()
+                    .unwrap()
+                    .join(acquire_future)
+                    .then_execute(queue.clone(), command_buffer)
+                    .unwrap()
+                    .then_swapchain_present(
+                        queue.clone(),
+                        SwapchainPresentInfo::swapchain_image_index(swapchain.clone(), image_index),
+                    )
+                    .then_signal_fence_and_flush();
+
+                match future.map_err(Validated::unwrap) {
+                    Ok(future) => {
+                        previous_frame_end = Some(future.boxed());
+                    }
+                    Err(VulkanError::OutOfDate) => {
+                        recreate_swapchain = true;
+                        previous_frame_end = Some(sync::now(device.clone()).boxed());
+                    }
+                    Err(e) => {
+                        println!("failed to flush future: {e}");
+                        previous_frame_end = Some(sync::now(device.clone()).boxed());
+                    }
+                }
+            }
+            Event::AboutToWait => window.request_redraw(),
+            _ => (),
+        }
+    })
+}
+
+/// Stands in for loading the texture of a tile from disk. Each tile gets a pattern of rings in a
+/// color of its own.
+fn generate_texture(index: u32) -> Vec<u8> {
+    // Reading and decoding a file takes a while.
+    thread::sleep(Duration::from_millis(250));
+
+    let hue = index as f32 / (GRID_SIZE * GRID_SIZE) as f32;
+    let color = [hue, (hue + 1.0 / 3.0) % 1.0, (hue + 2.0 / 3.0) % 1.0]
+        .map(|h| ((h * std::f32::consts::TAU).cos() * 0.5 + 0.5) * 255.0);
+    let center = TEXTURE_SIZE as f32 / 2.0;
+
+    (0..TEXTURE_SIZE * TEXTURE_SIZE)
+        .flat_map(|i| {
+            let dx = (i % TEXTURE_SIZE) as f32 - center;
+            let dy = (i / TEXTURE_SIZE) as f32 - center;
+            let shade = if ((dx * dx + dy * dy).sqrt() / 32.0) as u32 % 2 == 0 {
+                1.0
+            } else {
+                0.5
+            };
+            let [r, g, b] = color.map(|c| (c * shade) as u8);
+
+            [r, g, b, 255]
+        })
+        .collect()
+}
+
+/// This function is called once during initialization, then again whenever the window is resized.
+fn window_size_dependent_setup(
+    images: &[Arc<Image>],
+    render_pass: Arc<RenderPass>,
+    viewport: &mut Viewport,
+) -> Vec<Arc<Framebuffer>> {
+    let extent = images[0].extent();
+    viewport.extent = [extent[0] as f32, extent[1] as f32];
+
+    images
+        .iter()
+        .map(|image| {
+            let view = ImageView::new_default(image.clone()).unwrap();
+            Framebuffer::new(
+                render_pass.clone(),
+                FramebufferCreateInfo {
+                    attachments: vec![view],
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>()
+}
+
+mod vs {
+    vulkano_shaders::shader! {
+        ty: "vertex",
+        src: r"
+            #version 450
+
+            layout(push_constant) uniform PushConstants {
+                vec2 offset;
+                vec2 scale;
+            };
+
+            layout(location = 0) out vec2 tex_coords;
+
+            void main() {
+                tex_coords = vec2(gl_VertexIndex & 1, (gl_VertexIndex >> 1) & 1);
+                gl_Position = vec4(offset + tex_coords * scale, 0.0, 1.0);
+            }
+        ",
+    }
+}
+
+mod fs {
+    vulkano_shaders::shader! {
+        ty: "fragment",
+        src: r"
+            #version 450
+
+            layout(location = 0) in vec2 tex_coords;
+            layout(location = 0) out vec4 f_color;
+
+            layout(set = 0, binding = 0) uniform sampler2D tex;
+
+            void main() {
+                f_color = texture(tex, tex_coords);
+            }
+        ",
+    }
+}
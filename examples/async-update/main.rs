@@ -57,7 +57,7 @@ use vulkano::{
     image::{
         sampler::{Sampler, SamplerCreateInfo},
         view::ImageView,
-        Image, ImageCreateInfo, ImageType, ImageUsage,
+        Image, ImageCreateInfo, ImageUsage,
     },
     instance::{Instance, InstanceCreateFlags, InstanceCreateInfo},
     memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
@@ -306,13 +306,10 @@ fn main() -> Result<(), impl Error> {
     let textures = [(); 2].map(|_| {
         Image::new(
             memory_allocator.clone(),
-            ImageCreateInfo {
-                image_type: ImageType::Dim2d,
-                format: Format::R8G8B8A8_UNORM,
-                extent: [TRANSFER_GRANULARITY * 2, TRANSFER_GRANULARITY * 2, 1],
-                usage: ImageUsage::TRANSFER_DST | ImageUsage::SAMPLED,
-                ..Default::default()
-            },
+            ImageCreateInfo::sampled_2d_no_mipmap(
+                Format::R8G8B8A8_UNORM,
+                [TRANSFER_GRANULARITY * 2, TRANSFER_GRANULARITY * 2],
+            ),
             AllocationCreateInfo::default(),
         )
         .unwrap()
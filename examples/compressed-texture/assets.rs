@@ -0,0 +1,98 @@
+// Stands in for a set of texture files that were encoded ahead of time, one per format.
+//
+// Real applications encode their textures with an offline tool and load the file for the format
+// that was chosen at runtime. To keep this example self-contained, the texture is a simple
+// checkerboard in which every 4x4 block of texels has a single color, and the blocks are encoded
+// here with the simplest block modes of each format.
+
+use vulkano::format::Format;
+
+/// The width and height of the texture, in texels.
+pub const TEXTURE_SIZE: u32 = 64;
+
+/// The width and height of a cell of the checkerboard, in texels.
+const CELL_SIZE: u32 = 8;
+
+const COLORS: [[u8; 4]; 2] = [[230, 120, 40, 255], [40, 70, 140, 255]];
+
+/// Returns the texture data for `format`, laid out as it should be copied to the image.
+pub fn load_texture(format: Format) -> Vec<u8> {
+    let encode_block: fn([u8; 4]) -> [u8; 16] = match format {
+        Format::BC7_UNORM_BLOCK | Format::BC7_SRGB_BLOCK => encode_bc7,
+        Format::ASTC_4x4_UNORM_BLOCK | Format::ASTC_4x4_SRGB_BLOCK => encode_astc,
+        Format::ETC2_R8G8B8A8_UNORM_BLOCK | Format::ETC2_R8G8B8A8_SRGB_BLOCK => encode_etc2,
+        Format::R8G8B8A8_UNORM | Format::R8G8B8A8_SRGB => {
+            return (0..TEXTURE_SIZE * TEXTURE_SIZE)
+                .flat_map(|i| color_at(i % TEXTURE_SIZE, i / TEXTURE_SIZE))
+                .collect();
+        }
+        _ => panic!("no asset was shipped for {:?}", format),
+    };
+
+    assert_eq!(format.block_extent(), [4, 4, 1]);
+    let blocks = TEXTURE_SIZE / 4;
+
+    (0..blocks * blocks)
+        .flat_map(|i| encode_block(color_at(i % blocks * 4, i / blocks * 4)))
+        .collect()
+}
+
+fn color_at(x: u32, y: u32) -> [u8; 4] {
+    COLORS[((x / CELL_SIZE + y / CELL_SIZE) % 2) as usize]
+}
+
+// Mode 6 with both endpoints set to the color and all indices zero. The endpoints have 7 bits per
+// component, plus one bit that is shared by all components, so the result can be off by one.
+fn encode_bc7(color: [u8; 4]) -> [u8; 16] {
+    let mut bits = 0u128;
+    let mut offset = 0;
+    let mut write = |value: u8, count: u32| {
+        bits |= u128::from(value) << offset;
+        offset += count;
+    };
+
+    write(1 << 6, 7);
+
+    for component in color {
+        write(component >> 1, 7);
+        write(component >> 1, 7);
+    }
+
+    write(1, 1);
+    write(1, 1);
+
+    bits.to_le_bytes()
+}
+
+// A void-extent block, which has a single color with 16 bits per component.
+fn encode_astc(color: [u8; 4]) -> [u8; 16] {
+    let mut block = [0; 16];
+    block[..8].copy_from_slice(&0xFFFF_FFFF_FFFF_FDFCu64.to_le_bytes());
+
+    for (i, component) in color.into_iter().enumerate() {
+        let component = u16::from(component) * 257;
+        block[8 + i * 2..10 + i * 2].copy_from_slice(&component.to_le_bytes());
+    }
+
+    block
+}
+
+// An EAC alpha block followed by an ETC2 color block in individual mode. Individual mode stores
+// 4 bits per component and always adds a modifier of at least 2, so the color is approximate.
+fn encode_etc2(color: [u8; 4]) -> [u8; 16] {
+    // A base alpha, a multiplier of 1 and modifier table 0, in which index 4 adds 2.
+    let indices = (0..16).fold(0u64, |indices, i| indices | 4 << (i * 3));
+    let alpha = u64::from(color[3]) << 56 | 1 << 52 | indices;
+
+    // The same color in both halves of the block, modifier table 0 and all indices zero, which
+    // adds 2 to each component.
+    let [r, g, b] = [color[0], color[1], color[2]]
+        .map(|component| u64::from(component.saturating_sub(2).saturating_add(8) / 17));
+    let rgb = r << 60 | r << 56 | g << 52 | g << 48 | b << 44 | b << 40;
+
+    let mut block = [0; 16];
+    block[..8].copy_from_slice(&alpha.to_be_bytes());
+    block[8..].copy_from_slice(&rgb.to_be_bytes());
+
+    block
+}
@@ -0,0 +1,413 @@
+// This example demonstrates picking a compressed texture format that is supported by the device,
+// when the same texture is shipped pre-encoded in several formats.
+//
+// Block-compressed textures take up less memory and bandwidth than uncompressed ones, but there
+// is no single family of compressed formats that all devices support. Desktop GPUs support the BC
+// formats, while mobile GPUs usually support ASTC, ETC2 or both. An application can therefore ship
+// each texture encoded in several formats, and pick the best one at runtime. The
+// `Format::universal_alternatives` method returns a canonical list of such formats, from the most
+// to the least preferred, ending with an uncompressed format that is always supported.
+// `Format::best_supported_alternative` then returns the first one that the device supports.
+//
+// The chosen texture is uploaded, sampled to draw a quad, and the result is saved to a PNG file.
+
+use std::{fs::File, io::BufWriter, path::Path, sync::Arc};
+use vulkano::{
+    buffer::{Buffer, BufferCreateInfo, BufferUsage, Subbuffer},
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, CommandBufferBeginInfo, CommandBufferLevel,
+        CommandBufferUsage, CopyBufferToImageInfo, CopyImageToBufferInfo, RecordingCommandBuffer,
+        RenderPassBeginInfo,
+    },
+    descriptor_set::{
+        allocator::StandardDescriptorSetAllocator, DescriptorSet, WriteDescriptorSet,
+    },
+    device::{
+        physical::PhysicalDeviceType, Device, DeviceCreateInfo, DeviceExtensions, QueueCreateInfo,
+        QueueFlags,
+    },
+    format::{Format, FormatFeatures},
+    image::{
+        sampler::{Filter, Sampler, SamplerAddressMode, SamplerCreateInfo},
+        view::ImageView,
+        Image, ImageCreateInfo, ImageUsage,
+    },
+    instance::{Instance, InstanceCreateFlags, InstanceCreateInfo},
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
+    pipeline::{
+        graphics::{
+            color_blend::{ColorBlendAttachmentState, ColorBlendState},
+            input_assembly::InputAssemblyState,
+            multisample::MultisampleState,
+            rasterization::RasterizationState,
+            vertex_input::VertexInputState,
+            viewport::{Viewport, ViewportState},
+            GraphicsPipelineCreateInfo,
+        },
+        layout::PipelineDescriptorSetLayoutCreateInfo,
+        GraphicsPipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+        PipelineShaderStageCreateInfo,
+    },
+    render_pass::{Framebuffer, FramebufferCreateInfo, Subpass},
+    sync::{self, GpuFuture},
+    VulkanLibrary,
+};
+
+mod assets;
+
+const OUTPUT_EXTENT: [u32; 2] = [256, 256];
+
+fn main() {
+    let library = VulkanLibrary::new().unwrap();
+    let instance = Instance::new(
+        library,
+        InstanceCreateInfo {
+            flags: InstanceCreateFlags::ENUMERATE_PORTABILITY,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let device_extensions = DeviceExtensions::empty();
+    let (physical_device, queue_family_index) = instance
+        .enumerate_physical_devices()
+        .unwrap()
+        .filter(|p| p.supported_extensions().contains(&device_extensions))
+        .filter_map(|p| {
+            p.queue_family_properties()
+                .iter()
+                .position(|q| q.queue_flags.intersects(QueueFlags::GRAPHICS))
+                .map(|i| (p, i as u32))
+        })
+        .min_by_key(|(p, _)| match p.properties().device_type {
+            PhysicalDeviceType::DiscreteGpu => 0,
+            PhysicalDeviceType::IntegratedGpu => 1,
+            PhysicalDeviceType::VirtualGpu => 2,
+            PhysicalDeviceType::Cpu => 3,
+            PhysicalDeviceType::Other => 4,
+            _ => 5,
+        })
+        .unwrap();
+
+    println!(
+        "Using device: {} (type: {:?})",
+        physical_device.properties().device_name,
+        physical_device.properties().device_type,
+    );
+
+    // The texture is going to be uploaded and then sampled.
+    let texture_features = FormatFeatures::TRANSFER_DST | FormatFeatures::SAMPLED_IMAGE;
+
+    // The formats that the texture was shipped in, in order of preference.
+    let candidates = Format::BC7_SRGB_BLOCK.universal_alternatives();
+
+    // `preferred_tiling` checks both optimal and linear tiling at once. `None` means that the
+    // format can't be used for the texture at all.
+    for &format in candidates {
+        let tiling = physical_device
+            .format_properties(format)
+            .unwrap()
+            .preferred_tiling(texture_features);
+        println!("{:?}: {:?}", format, tiling);
+    }
+
+    // The last candidate is an uncompressed format that all devices support, so there is always a
+    // result.
+    let texture_format = candidates[0]
+        .best_supported_alternative(&physical_device, texture_features, candidates)
+        .unwrap();
+
+    println!("Using texture format: {:?}", texture_format);
+
+    let (device, mut queues) = Device::new(
+        physical_device,
+        DeviceCreateInfo {
+            queue_create_infos: vec![QueueCreateInfo {
+                queue_family_index,
+                ..Default::default()
+            }],
+            enabled_extensions: device_extensions,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let queue = queues.next().unwrap();
+
+    let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+    let descriptor_set_allocator = Arc::new(StandardDescriptorSetAllocator::new(
+        device.clone(),
+        Default::default(),
+    ));
+    let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+        device.clone(),
+        Default::default(),
+    ));
+
+    // In a real application, this would be read from the asset file for the chosen format.
+    let texture_data = assets::load_texture(texture_format);
+
+    let upload_buffer = Buffer::from_iter(
+        memory_allocator.clone(),
+        BufferCreateInfo {
+            usage: BufferUsage::TRANSFER_SRC,
+            ..Default::default()
+        },
+        AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+            ..Default::default()
+        },
+        texture_data,
+    )
+    .unwrap();
+
+    let texture = Image::new(
+        memory_allocator.clone(),
+        ImageCreateInfo::sampled_2d_no_mipmap(
+            texture_format,
+            [assets::TEXTURE_SIZE, assets::TEXTURE_SIZE],
+        ),
+        AllocationCreateInfo::default(),
+    )
+    .unwrap();
+
+    let output_image = Image::new(
+        memory_allocator.clone(),
+        ImageCreateInfo {
+            usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::TRANSFER_SRC,
+            ..ImageCreateInfo::color_attachment(Format::R8G8B8A8_SRGB, OUTPUT_EXTENT)
+        },
+        AllocationCreateInfo::default(),
+    )
+    .unwrap();
+
+    let render_pass = vulkano::single_pass_renderpass!(
+        device.clone(),
+        attachments: {
+            color: {
+                format: output_image.format(),
+                samples: 1,
+                load_op: DontCare,
+                store_op: Store,
+            },
+        },
+        pass: {
+            color: [color],
+            depth_stencil: {},
+        },
+    )
+    .unwrap();
+
+    let framebuffer = Framebuffer::new(
+        render_pass.clone(),
+        FramebufferCreateInfo {
+            attachments: vec![ImageView::new_default(output_image.clone()).unwrap()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let pipeline = {
+        let vs = vs::load(device.clone())
+            .unwrap()
+            .entry_point("main")
+            .unwrap();
+        let fs = fs::load(device.clone())
+            .unwrap()
+            .entry_point("main")
+            .unwrap();
+        let stages = [
+            PipelineShaderStageCreateInfo::new(vs),
+            PipelineShaderStageCreateInfo::new(fs),
+        ];
+        let layout = PipelineLayout::new(
+            device.clone(),
+            PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+                .into_pipeline_layout_create_info(device.clone())
+                .unwrap(),
+        )
+        .unwrap();
+        let subpass = Subpass::from(render_pass, 0).unwrap();
+
+        GraphicsPipeline::new(
+            device.clone(),
+            None,
+            GraphicsPipelineCreateInfo {
+                stages: stages.into_iter().collect(),
+                vertex_input_state: Some(VertexInputState::default()),
+                input_assembly_state: Some(InputAssemblyState::default()),
+                viewport_state: Some(ViewportState {
+                    viewports: [Viewport {
+                        offset: [0.0, 0.0],
+                        extent: [OUTPUT_EXTENT[0] as f32, OUTPUT_EXTENT[1] as f32],
+                        depth_range: 0.0..=1.0,
+                    }]
+                    .into_iter()
+                    .collect(),
+                    ..Default::default()
+                }),
+                rasterization_state: Some(RasterizationState::default()),
+                multisample_state: Some(MultisampleState::default()),
+                color_blend_state: Some(ColorBlendState::with_attachment_states(
+                    subpass.num_color_attachments(),
+                    ColorBlendAttachmentState::default(),
+                )),
+                subpass: Some(subpass.into()),
+                ..GraphicsPipelineCreateInfo::layout(layout)
+            },
+        )
+        .unwrap()
+    };
+
+    // Nearest filtering keeps the edges of the checkerboard sharp when it is magnified.
+    let sampler = Sampler::new(
+        device.clone(),
+        SamplerCreateInfo {
+            mag_filter: Filter::Nearest,
+            min_filter: Filter::Nearest,
+            address_mode: [SamplerAddressMode::Repeat; 3],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let set = DescriptorSet::new(
+        descriptor_set_allocator,
+        pipeline.layout().set_layouts()[0].clone(),
+        [WriteDescriptorSet::image_view_sampler(
+            0,
+            ImageView::new_default(texture.clone()).unwrap(),
+            sampler,
+        )],
+        [],
+    )
+    .unwrap();
+
+    let output_buffer: Subbuffer<[u8]> = Buffer::new_slice(
+        memory_allocator,
+        BufferCreateInfo {
+            usage: BufferUsage::TRANSFER_DST,
+            ..Default::default()
+        },
+        AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+            ..Default::default()
+        },
+        (OUTPUT_EXTENT[0] * OUTPUT_EXTENT[1] * 4) as u64,
+    )
+    .unwrap();
+
+    let mut builder = RecordingCommandBuffer::new(
+        command_buffer_allocator,
+        queue.queue_family_index(),
+        CommandBufferLevel::Primary,
+        CommandBufferBeginInfo {
+            usage: CommandBufferUsage::OneTimeSubmit,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // The data is copied the same way regardless of the format. For compressed formats, each
+    // block of texels is copied as a whole.
+    builder
+        .copy_buffer_to_image(CopyBufferToImageInfo::buffer_image(upload_buffer, texture))
+        .unwrap()
+        .begin_render_pass(
+            RenderPassBeginInfo {
+                clear_values: vec![None],
+                ..RenderPassBeginInfo::framebuffer(framebuffer)
+            },
+            Default::default(),
+        )
+        .unwrap()
+        .bind_pipeline_graphics(pipeline.clone())
+        .unwrap()
+        .bind_descriptor_sets(
+            PipelineBindPoint::Graphics,
+            pipeline.layout().clone(),
+            0,
+            set,
+        )
+        .unwrap();
+
+    unsafe {
+        builder.draw(3, 1, 0, 0).unwrap();
+    }
+
+    builder
+        .end_render_pass(Default::default())
+        .unwrap()
+        .copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(
+            output_image,
+            output_buffer.clone(),
+        ))
+        .unwrap();
+
+    let command_buffer = builder.end().unwrap();
+
+    sync::now(device)
+        .then_execute(queue, command_buffer)
+        .unwrap()
+        .then_signal_fence_and_flush()
+        .unwrap()
+        .wait(None)
+        .unwrap();
+
+    write_image_buffer_to_file(output_buffer, "compressed-texture.png", OUTPUT_EXTENT);
+}
+
+fn write_image_buffer_to_file(buffer: Subbuffer<[u8]>, path: &str, extent: [u32; 2]) {
+    let buffer_content = buffer.read().unwrap();
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(path);
+    let file = File::create(&path).unwrap();
+    let w = &mut BufWriter::new(file);
+    let mut encoder = png::Encoder::new(w, extent[0], extent[1]);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().unwrap();
+    writer.write_image_data(&buffer_content).unwrap();
+
+    if let Ok(path) = path.canonicalize() {
+        println!("Saved to {}", path.display());
+    }
+}
+
+// Outputs a triangle that covers the whole framebuffer, along with texture coordinates that map
+// the texture to the framebuffer once.
+mod vs {
+    vulkano_shaders::shader! {
+        ty: "vertex",
+        src: r"
+            #version 450
+
+            layout(location = 0) out vec2 v_tex_coords;
+
+            void main() {
+                v_tex_coords = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
+                gl_Position = vec4(v_tex_coords * 2.0 - 1.0, 0.0, 1.0);
+            }
+        ",
+    }
+}
+
+mod fs {
+    vulkano_shaders::shader! {
+        ty: "fragment",
+        src: r"
+            #version 450
+
+            layout(location = 0) in vec2 v_tex_coords;
+
+            layout(location = 0) out vec4 f_color;
+
+            layout(set = 0, binding = 0) uniform sampler2D tex;
+
+            void main() {
+                f_color = texture(tex, v_tex_coords);
+            }
+        ",
+    }
+}
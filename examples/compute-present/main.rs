@@ -0,0 +1,440 @@
+// This example demonstrates how to write to swapchain images directly from a compute shader,
+// without any graphics pipeline, render pass or framebuffer.
+//
+// The swapchain is created with the `STORAGE` image usage, so that each swapchain image can be
+// bound to a descriptor set as a storage image. A simple raymarcher writes every pixel of the
+// image, and the image is then presented from the same queue that executed the compute shader.
+//
+// Vulkano transitions the swapchain image to the `General` layout that storage images need, and
+// back to the `PresentSrc` layout before it is presented.
+
+use std::{error::Error, sync::Arc, time::Instant};
+use vulkano::{
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, CommandBufferBeginInfo, CommandBufferLevel,
+        CommandBufferUsage, RecordingCommandBuffer,
+    },
+    descriptor_set::{
+        allocator::StandardDescriptorSetAllocator, DescriptorSet, WriteDescriptorSet,
+    },
+    device::{
+        physical::PhysicalDeviceType, Device, DeviceCreateInfo, DeviceExtensions, DeviceFeatures,
+        QueueCreateInfo, QueueFlags,
+    },
+    format::FormatFeatures,
+    image::{view::ImageView, Image, ImageUsage},
+    instance::{Instance, InstanceCreateFlags, InstanceCreateInfo},
+    pipeline::{
+        compute::ComputePipelineCreateInfo, layout::PipelineDescriptorSetLayoutCreateInfo,
+        ComputePipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+        PipelineShaderStageCreateInfo,
+    },
+    swapchain::{
+        acquire_next_image, Surface, Swapchain, SwapchainCreateInfo, SwapchainPresentInfo,
+    },
+    sync::{self, GpuFuture},
+    Validated, VulkanError, VulkanLibrary,
+};
+use winit::{
+    event::{Event, WindowEvent},
+    event_loop::{ControlFlow, EventLoop},
+    window::WindowBuilder,
+};
+
+fn main() -> Result<(), impl Error> {
+    let event_loop = EventLoop::new().unwrap();
+
+    let library = VulkanLibrary::new().unwrap();
+    let required_extensions = Surface::required_extensions(&event_loop).unwrap();
+    let instance = Instance::new(
+        library,
+        InstanceCreateInfo {
+            flags: InstanceCreateFlags::ENUMERATE_PORTABILITY,
+            enabled_extensions: required_extensions,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let window = Arc::new(WindowBuilder::new().build(&event_loop).unwrap());
+    let surface = Surface::from_window(instance.clone(), window.clone()).unwrap();
+
+    let device_extensions = DeviceExtensions {
+        khr_swapchain: true,
+        ..DeviceExtensions::empty()
+    };
+    let device_features = DeviceFeatures {
+        // The storage image in the shader below is declared without a format, so that it can be
+        // used with whichever format the swapchain has.
+        shader_storage_image_write_without_format: true,
+        ..DeviceFeatures::empty()
+    };
+    let (physical_device, queue_family_index) = instance
+        .enumerate_physical_devices()
+        .unwrap()
+        .filter(|p| p.supported_extensions().contains(&device_extensions))
+        .filter(|p| p.supported_features().contains(&device_features))
+        .filter(|p| {
+            // The surface must allow its images to be used as storage images.
+            p.surface_capabilities(&surface, Default::default())
+                .map_or(false, |capabilities| {
+                    capabilities
+                        .supported_usage_flags
+                        .intersects(ImageUsage::STORAGE)
+                })
+        })
+        .filter_map(|p| {
+            // The compute shader is executed on the same queue that presents the image, so the
+            // queue family must support both.
+            p.queue_family_properties()
+                .iter()
+                .enumerate()
+                .position(|(i, q)| {
+                    q.queue_flags.intersects(QueueFlags::COMPUTE)
+                        && p.surface_support(i as u32, &surface).unwrap_or(false)
+                })
+                .map(|i| (p, i as u32))
+        })
+        .min_by_key(|(p, _)| match p.properties().device_type {
+            PhysicalDeviceType::DiscreteGpu => 0,
+            PhysicalDeviceType::IntegratedGpu => 1,
+            PhysicalDeviceType::VirtualGpu => 2,
+            PhysicalDeviceType::Cpu => 3,
+            PhysicalDeviceType::Other => 4,
+            _ => 5,
+        })
+        .expect("no suitable physical device found");
+
+    println!(
+        "Using device: {} (type: {:?})",
+        physical_device.properties().device_name,
+        physical_device.properties().device_type,
+    );
+
+    let (device, mut queues) = Device::new(
+        physical_device,
+        DeviceCreateInfo {
+            enabled_extensions: device_extensions,
+            enabled_features: device_features,
+            queue_create_infos: vec![QueueCreateInfo {
+                queue_family_index,
+                ..Default::default()
+            }],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let queue = queues.next().unwrap();
+
+    let (mut swapchain, images) = {
+        let surface_capabilities = device
+            .physical_device()
+            .surface_capabilities(&surface, Default::default())
+            .unwrap();
+
+        // sRGB formats usually can't be used for storage images, so we look for a surface format
+        // that can. The shader converts its output to sRGB itself instead.
+        let image_format = device
+            .physical_device()
+            .surface_formats(&surface, Default::default())
+            .unwrap()
+            .into_iter()
+            .map(|(format, _color_space)| format)
+            .find(|&format| {
+                device
+                    .physical_device()
+                    .format_properties(format)
+                    .unwrap()
+                    .optimal_tiling_features
+                    .intersects(FormatFeatures::STORAGE_IMAGE)
+            })
+            .expect("no surface format supports storage images");
+
+        Swapchain::new(
+            device.clone(),
+            surface,
+            SwapchainCreateInfo {
+                min_image_count: surface_capabilities.min_image_count.max(2),
+                image_format,
+                image_extent: window.inner_size().into(),
+                // Only storage usage is needed, because nothing is rendered to the images with a
+                // graphics pipeline.
+                image_usage: ImageUsage::STORAGE,
+                composite_alpha: surface_capabilities
+                    .supported_composite_alpha
+                    .into_iter()
+                    .next()
+                    .unwrap(),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    };
+
+    mod cs {
+        vulkano_shaders::shader! {
+            ty: "compute",
+            src: r"
+                #version 450
+
+                layout(local_size_x = 8, local_size_y = 8, local_size_z = 1) in;
+
+                layout(set = 0, binding = 0) uniform writeonly image2D img;
+
+                layout(push_constant) uniform PushConstants {
+                    float time;
+                } push_constants;
+
+                // The signed distance from `p` to the scene, which is a sphere resting on a
+                // plane.
+                float scene(vec3 p) {
+                    vec3 center = vec3(0.0, 0.5 + 0.25 * sin(push_constants.time), 0.0);
+                    float sphere = length(p - center) - 0.5;
+                    float plane = p.y;
+                    return min(sphere, plane);
+                }
+
+                vec3 normal(vec3 p) {
+                    vec2 e = vec2(0.001, 0.0);
+                    return normalize(vec3(
+                        scene(p + e.xyy) - scene(p - e.xyy),
+                        scene(p + e.yxy) - scene(p - e.yxy),
+                        scene(p + e.yyx) - scene(p - e.yyx)
+                    ));
+                }
+
+                void main() {
+                    ivec2 size = imageSize(img);
+                    ivec2 pixel = ivec2(gl_GlobalInvocationID.xy);
+
+                    if (pixel.x >= size.x || pixel.y >= size.y) {
+                        return;
+                    }
+
+                    vec2 uv = (vec2(pixel) - 0.5 * vec2(size)) / float(size.y);
+                    vec3 origin = vec3(0.0, 0.75, -2.5);
+                    vec3 direction = normalize(vec3(uv.x, -uv.y, 1.0));
+
+                    float distance = 0.0;
+                    bool hit = false;
+
+                    for (int i = 0; i < 128 && distance < 20.0; i++) {
+                        float step = scene(origin + direction * distance);
+
+                        if (step < 0.001) {
+                            hit = true;
+                            break;
+                        }
+
+                        distance += step;
+                    }
+
+                    vec3 color = vec3(0.5, 0.7, 1.0) - 0.5 * direction.y;
+
+                    if (hit) {
+                        vec3 p = origin + direction * distance;
+                        vec3 light = normalize(vec3(1.0, 1.0, -1.0));
+                        float diffuse = max(dot(normal(p), light), 0.0);
+                        color = vec3(1.0, 0.4, 0.2) * (0.1 + 0.9 * diffuse);
+                    }
+
+                    // Approximate the sRGB transfer function, because the swapchain format isn't
+                    // an sRGB format.
+                    color = pow(color, vec3(1.0 / 2.2));
+
+                    imageStore(img, pixel, vec4(color, 1.0));
+                }
+            ",
+        }
+    }
+
+    let pipeline = {
+        let cs = cs::load(device.clone())
+            .unwrap()
+            .entry_point("main")
+            .unwrap();
+        let stage = PipelineShaderStageCreateInfo::new(cs);
+        let layout = PipelineLayout::new(
+            device.clone(),
+            PipelineDescriptorSetLayoutCreateInfo::from_stages([&stage])
+                .into_pipeline_layout_create_info(device.clone())
+                .unwrap(),
+        )
+        .unwrap();
+
+        ComputePipeline::new(
+            device.clone(),
+            None,
+            ComputePipelineCreateInfo::stage_layout(stage, layout),
+        )
+        .unwrap()
+    };
+
+    let descriptor_set_allocator = Arc::new(StandardDescriptorSetAllocator::new(
+        device.clone(),
+        Default::default(),
+    ));
+    let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+        device.clone(),
+        Default::default(),
+    ));
+
+    let mut descriptor_sets =
+        window_size_dependent_setup(&images, &pipeline, &descriptor_set_allocator);
+
+    let start_time = Instant::now();
+    let mut recreate_swapchain = false;
+    let mut previous_frame_end = Some(sync::now(device.clone()).boxed());
+
+    event_loop.run(move |event, elwt| {
+        elwt.set_control_flow(ControlFlow::Poll);
+
+        match event {
+            Event::WindowEvent {
+                event: WindowEvent::CloseRequested,
+                ..
+            } => {
+                elwt.exit();
+            }
+            Event::WindowEvent {
+                event: WindowEvent::Resized(_),
+                ..
+            } => {
+                recreate_swapchain = true;
+            }
+            Event::WindowEvent {
+                event: WindowEvent::RedrawRequested,
+                ..
+            } => {
+                let image_extent: [u32; 2] = window.inner_size().into();
+
+                if image_extent.contains(&0) {
+                    return;
+                }
+
+                previous_frame_end.as_mut().unwrap().cleanup_finished();
+
+                if recreate_swapchain {
+                    let (new_swapchain, new_images) = swapchain
+                        .recreate(SwapchainCreateInfo {
+                            image_extent,
+                            ..swapchain.create_info()
+                        })
+                        .expect("failed to recreate swapchain");
+
+                    swapchain = new_swapchain;
+                    descriptor_sets = window_size_dependent_setup(
+                        &new_images,
+                        &pipeline,
+                        &descriptor_set_allocator,
+                    );
+                    recreate_swapchain = false;
+                }
+
+                let (image_index, suboptimal, acquire_future) =
+                    match acquire_next_image(swapchain.clone(), None).map_err(Validated::unwrap) {
+                        Ok(r) => r,
+                        Err(VulkanError::OutOfDate) => {
+                            recreate_swapchain = true;
+                            return;
+                        }
+                        Err(e) => panic!("failed to acquire next image: {e}"),
+                    };
+
+                if suboptimal {
+                    recreate_swapchain = true;
+                }
+
+                let mut builder = RecordingCommandBuffer::new(
+                    command_buffer_allocator.clone(),
+                    queue.queue_family_index(),
+                    CommandBufferLevel::Primary,
+                    CommandBufferBeginInfo {
+                        usage: CommandBufferUsage::OneTimeSubmit,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+
+                let push_constants = cs::PushConstants {
+                    time: start_time.elapsed().as_secs_f32(),
+                };
+
+                builder
+                    .bind_pipeline_compute(pipeline.clone())
+                    .unwrap()
+                    .bind_descriptor_sets(
+                        PipelineBindPoint::Compute,
+                        pipeline.layout().clone(),
+                        0,
+                        descriptor_sets[image_index as usize].clone(),
+                    )
+                    .unwrap()
+                    .push_constants(pipeline.layout().clone(), 0, push_constants)
+                    .unwrap();
+
+                let extent = swapchain.image_extent();
+
+                unsafe {
+                    builder
+                        .dispatch([(extent[0] + 7) / 8, (extent[1] + 7) / 8, 1])
+                        .unwrap();
+                }
+
+                let command_buffer = builder.end().unwrap();
+
+                let future = previous_frame_end
+                    .take()
+                    .unwrap()
+                    .join(acquire_future)
+                    .then_execute(queue.clone(), command_buffer)
+                    .unwrap()
+                    .then_swapchain_present(
+                        queue.clone(),
+                        SwapchainPresentInfo::swapchain_image_index(swapchain.clone(), image_index),
+                    )
+                    .then_signal_fence_and_flush();
+
+                match future.map_err(Validated::unwrap) {
+                    Ok(future) => {
+                        previous_frame_end = Some(future.boxed());
+                    }
+                    Err(VulkanError::OutOfDate) => {
+                        recreate_swapchain = true;
+                        previous_frame_end = Some(sync::now(device.clone()).boxed());
+                    }
+                    Err(e) => {
+                        panic!("failed to flush future: {e}");
+                    }
+                }
+            }
+            Event::AboutToWait => window.request_redraw(),
+            _ => (),
+        }
+    })
+}
+
+/// This function is called once during initialization, then again whenever the window is resized.
+fn window_size_dependent_setup(
+    images: &[Arc<Image>],
+    pipeline: &Arc<ComputePipeline>,
+    descriptor_set_allocator: &Arc<StandardDescriptorSetAllocator>,
+) -> Vec<Arc<DescriptorSet>> {
+    let layout = &pipeline.layout().set_layouts()[0];
+
+    images
+        .iter()
+        .map(|image| {
+            let view = ImageView::new_default(image.clone()).unwrap();
+
+            DescriptorSet::new(
+                descriptor_set_allocator.clone(),
+                layout.clone(),
+                [WriteDescriptorSet::image_view(0, view)],
+                [],
+            )
+            .unwrap()
+        })
+        .collect()
+}
@@ -0,0 +1,568 @@
+// This example demonstrates rendering to a cubemap and then sampling it, as is done when building
+// environment maps or when rendering a sky once and reusing it every frame.
+//
+// The scene is rendered into all six faces of a cube-compatible image in a single render pass,
+// using multiview: the render pass has a view mask of `0b111111`, and each view renders to the
+// array layer of the attachment with the same index. The shaders use `gl_ViewIndex` to know which
+// face they are rendering. The image is then viewed as an `ImageViewType::Cube` and sampled to
+// draw a skybox, which is saved to a PNG file.
+//
+// Without multiview, each face has to be rendered in a separate render pass instead.
+// `ImageView::cube_faces` creates the six views of the faces that are needed to do that.
+
+use std::{fs::File, io::BufWriter, path::Path, sync::Arc};
+use vulkano::{
+    buffer::{Buffer, BufferCreateInfo, BufferUsage, Subbuffer},
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, CommandBufferBeginInfo, CommandBufferLevel,
+        CommandBufferUsage, CopyImageToBufferInfo, RecordingCommandBuffer, RenderPassBeginInfo,
+    },
+    descriptor_set::{
+        allocator::StandardDescriptorSetAllocator, DescriptorSet, WriteDescriptorSet,
+    },
+    device::{
+        physical::PhysicalDeviceType, Device, DeviceCreateInfo, DeviceExtensions, DeviceFeatures,
+        QueueCreateInfo, QueueFlags,
+    },
+    format::Format,
+    image::{
+        sampler::{Filter, Sampler, SamplerAddressMode, SamplerCreateInfo},
+        view::{ImageView, ImageViewCreateInfo, ImageViewType},
+        Image, ImageCreateInfo, ImageLayout, ImageUsage, SampleCount,
+    },
+    instance::{Instance, InstanceCreateFlags, InstanceCreateInfo, InstanceExtensions},
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
+    pipeline::{
+        graphics::{
+            color_blend::{ColorBlendAttachmentState, ColorBlendState},
+            input_assembly::InputAssemblyState,
+            multisample::MultisampleState,
+            rasterization::RasterizationState,
+            vertex_input::VertexInputState,
+            viewport::{Viewport, ViewportState},
+            GraphicsPipelineCreateInfo,
+        },
+        layout::PipelineDescriptorSetLayoutCreateInfo,
+        GraphicsPipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+        PipelineShaderStageCreateInfo,
+    },
+    render_pass::{
+        AttachmentDescription, AttachmentLoadOp, AttachmentReference, AttachmentStoreOp,
+        Framebuffer, FramebufferCreateInfo, RenderPass, RenderPassCreateInfo, Subpass,
+        SubpassDescription,
+    },
+    shader::EntryPoint,
+    sync::{self, GpuFuture},
+    VulkanLibrary,
+};
+
+const CUBE_SIZE: u32 = 256;
+const OUTPUT_EXTENT: [u32; 2] = [800, 450];
+
+fn main() {
+    let library = VulkanLibrary::new().unwrap();
+    let instance = Instance::new(
+        library,
+        InstanceCreateInfo {
+            flags: InstanceCreateFlags::ENUMERATE_PORTABILITY,
+            enabled_extensions: InstanceExtensions {
+                // Required to get multiview limits.
+                khr_get_physical_device_properties2: true,
+                ..InstanceExtensions::empty()
+            },
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let device_extensions = DeviceExtensions::empty();
+    let features = DeviceFeatures {
+        multiview: true,
+        ..DeviceFeatures::empty()
+    };
+    let (physical_device, queue_family_index) = instance
+        .enumerate_physical_devices()
+        .unwrap()
+        .filter(|p| p.supported_extensions().contains(&device_extensions))
+        .filter(|p| p.supported_features().contains(&features))
+        .filter(|p| {
+            // One view is needed for each face of the cube. Every device that supports multiview
+            // supports at least six views, but this is checked to be sure.
+            p.properties().max_multiview_view_count.unwrap_or(0) >= 6
+        })
+        .filter_map(|p| {
+            p.queue_family_properties()
+                .iter()
+                .position(|q| q.queue_flags.intersects(QueueFlags::GRAPHICS))
+                .map(|i| (p, i as u32))
+        })
+        .min_by_key(|(p, _)| match p.properties().device_type {
+            PhysicalDeviceType::DiscreteGpu => 0,
+            PhysicalDeviceType::IntegratedGpu => 1,
+            PhysicalDeviceType::VirtualGpu => 2,
+            PhysicalDeviceType::Cpu => 3,
+            PhysicalDeviceType::Other => 4,
+            _ => 5,
+        })
+        .expect("no device supports six multiview views");
+
+    println!(
+        "Using device: {} (type: {:?})",
+        physical_device.properties().device_name,
+        physical_device.properties().device_type,
+    );
+
+    let (device, mut queues) = Device::new(
+        physical_device,
+        DeviceCreateInfo {
+            queue_create_infos: vec![QueueCreateInfo {
+                queue_family_index,
+                ..Default::default()
+            }],
+            enabled_extensions: device_extensions,
+            enabled_features: features,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let queue = queues.next().unwrap();
+
+    let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+    let descriptor_set_allocator = Arc::new(StandardDescriptorSetAllocator::new(
+        device.clone(),
+        Default::default(),
+    ));
+    let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+        device.clone(),
+        Default::default(),
+    ));
+
+    // A cube is made of six array layers, in the order +X, -X, +Y, -Y, +Z, -Z. To be able to
+    // create a cube view of them, the image must be square and have the `CUBE_COMPATIBLE` flag,
+    // which `ImageCreateInfo::cubemap` takes care of. The image is rendered to rather than
+    // uploaded to, so the usage is overridden.
+    let cube_image = Image::new(
+        memory_allocator.clone(),
+        ImageCreateInfo {
+            usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::SAMPLED,
+            ..ImageCreateInfo::cubemap(Format::R8G8B8A8_UNORM, CUBE_SIZE)
+        },
+        AllocationCreateInfo::default(),
+    )
+    .unwrap();
+
+    // For rendering, the six layers are viewed as a 2D array. With multiview, view `i` renders to
+    // layer `i` of this view.
+    let cube_attachment_view = ImageView::new(
+        cube_image.clone(),
+        ImageViewCreateInfo {
+            view_type: ImageViewType::Dim2dArray,
+            ..ImageViewCreateInfo::from_image(&cube_image)
+        },
+    )
+    .unwrap();
+
+    // For sampling, the same layers are viewed as a cube.
+    let cube_sampled_view = ImageView::new(
+        cube_image.clone(),
+        ImageViewCreateInfo {
+            view_type: ImageViewType::Cube,
+            ..ImageViewCreateInfo::from_image(&cube_image)
+        },
+    )
+    .unwrap();
+
+    let cube_render_pass = RenderPass::new(
+        device.clone(),
+        RenderPassCreateInfo {
+            attachments: vec![AttachmentDescription {
+                format: cube_image.format(),
+                samples: SampleCount::Sample1,
+                load_op: AttachmentLoadOp::DontCare,
+                store_op: AttachmentStoreOp::Store,
+                initial_layout: ImageLayout::Undefined,
+                final_layout: ImageLayout::ShaderReadOnlyOptimal,
+                ..Default::default()
+            }],
+            subpasses: vec![SubpassDescription {
+                // Render to all six faces.
+                view_mask: 0b111111,
+                color_attachments: vec![Some(AttachmentReference {
+                    attachment: 0,
+                    layout: ImageLayout::ColorAttachmentOptimal,
+                    ..Default::default()
+                })],
+                ..Default::default()
+            }],
+            correlated_view_masks: vec![0b111111],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let cube_framebuffer = Framebuffer::new(
+        cube_render_pass.clone(),
+        FramebufferCreateInfo {
+            attachments: vec![cube_attachment_view],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // Both passes draw a single triangle that covers the whole framebuffer, and only differ in
+    // their fragment shader.
+    let fullscreen_vs = fullscreen_vs::load(device.clone())
+        .unwrap()
+        .entry_point("main")
+        .unwrap();
+
+    let cube_pipeline = create_pipeline(
+        device.clone(),
+        fullscreen_vs.clone(),
+        scene_fs::load(device.clone())
+            .unwrap()
+            .entry_point("main")
+            .unwrap(),
+        Subpass::from(cube_render_pass, 0).unwrap(),
+        [CUBE_SIZE; 2],
+    );
+
+    let output_image = Image::new(
+        memory_allocator.clone(),
+        ImageCreateInfo {
+            usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::TRANSFER_SRC,
+            ..ImageCreateInfo::color_attachment(Format::R8G8B8A8_SRGB, OUTPUT_EXTENT)
+        },
+        AllocationCreateInfo::default(),
+    )
+    .unwrap();
+
+    let skybox_render_pass = vulkano::single_pass_renderpass!(
+        device.clone(),
+        attachments: {
+            color: {
+                format: output_image.format(),
+                samples: 1,
+                load_op: DontCare,
+                store_op: Store,
+            },
+        },
+        pass: {
+            color: [color],
+            depth_stencil: {},
+        },
+    )
+    .unwrap();
+
+    let skybox_framebuffer = Framebuffer::new(
+        skybox_render_pass.clone(),
+        FramebufferCreateInfo {
+            attachments: vec![ImageView::new_default(output_image.clone()).unwrap()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let skybox_pipeline = create_pipeline(
+        device.clone(),
+        fullscreen_vs,
+        skybox_fs::load(device.clone())
+            .unwrap()
+            .entry_point("main")
+            .unwrap(),
+        Subpass::from(skybox_render_pass, 0).unwrap(),
+        OUTPUT_EXTENT,
+    );
+
+    let sampler = Sampler::new(
+        device.clone(),
+        SamplerCreateInfo {
+            mag_filter: Filter::Linear,
+            min_filter: Filter::Linear,
+            address_mode: [SamplerAddressMode::ClampToEdge; 3],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let skybox_set = DescriptorSet::new(
+        descriptor_set_allocator,
+        skybox_pipeline.layout().set_layouts()[0].clone(),
+        [WriteDescriptorSet::image_view_sampler(
+            0,
+            cube_sampled_view,
+            sampler,
+        )],
+        [],
+    )
+    .unwrap();
+
+    let output_buffer: Subbuffer<[u8]> = Buffer::new_slice(
+        memory_allocator,
+        BufferCreateInfo {
+            usage: BufferUsage::TRANSFER_DST,
+            ..Default::default()
+        },
+        AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+            ..Default::default()
+        },
+        (OUTPUT_EXTENT[0] * OUTPUT_EXTENT[1] * 4) as u64,
+    )
+    .unwrap();
+
+    let mut builder = RecordingCommandBuffer::new(
+        command_buffer_allocator,
+        queue.queue_family_index(),
+        CommandBufferLevel::Primary,
+        CommandBufferBeginInfo {
+            usage: CommandBufferUsage::OneTimeSubmit,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // Render the scene into the cubemap. A single draw call is broadcast to all six views.
+    builder
+        .begin_render_pass(
+            RenderPassBeginInfo {
+                clear_values: vec![None],
+                ..RenderPassBeginInfo::framebuffer(cube_framebuffer)
+            },
+            Default::default(),
+        )
+        .unwrap()
+        .bind_pipeline_graphics(cube_pipeline)
+        .unwrap();
+
+    unsafe {
+        builder.draw(3, 1, 0, 0).unwrap();
+    }
+
+    builder.end_render_pass(Default::default()).unwrap();
+
+    // Draw the skybox by sampling the cubemap in the direction of each pixel.
+    builder
+        .begin_render_pass(
+            RenderPassBeginInfo {
+                clear_values: vec![None],
+                ..RenderPassBeginInfo::framebuffer(skybox_framebuffer)
+            },
+            Default::default(),
+        )
+        .unwrap()
+        .bind_pipeline_graphics(skybox_pipeline.clone())
+        .unwrap()
+        .bind_descriptor_sets(
+            PipelineBindPoint::Graphics,
+            skybox_pipeline.layout().clone(),
+            0,
+            skybox_set,
+        )
+        .unwrap()
+        .push_constants_typed(
+            skybox_pipeline.layout().clone(),
+            skybox_fs::PushConstants {
+                aspect_ratio: OUTPUT_EXTENT[0] as f32 / OUTPUT_EXTENT[1] as f32,
+            },
+        )
+        .unwrap();
+
+    unsafe {
+        builder.draw(3, 1, 0, 0).unwrap();
+    }
+
+    builder
+        .end_render_pass(Default::default())
+        .unwrap()
+        .copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(
+            output_image,
+            output_buffer.clone(),
+        ))
+        .unwrap();
+
+    let command_buffer = builder.end().unwrap();
+
+    sync::now(device)
+        .then_execute(queue, command_buffer)
+        .unwrap()
+        .then_signal_fence_and_flush()
+        .unwrap()
+        .wait(None)
+        .unwrap();
+
+    write_image_buffer_to_file(output_buffer, "cubemap.png", OUTPUT_EXTENT);
+}
+
+/// Creates a pipeline that draws a single triangle covering the whole framebuffer.
+fn create_pipeline(
+    device: Arc<Device>,
+    vs: EntryPoint,
+    fs: EntryPoint,
+    subpass: Subpass,
+    extent: [u32; 2],
+) -> Arc<GraphicsPipeline> {
+    let stages = [
+        PipelineShaderStageCreateInfo::new(vs),
+        PipelineShaderStageCreateInfo::new(fs),
+    ];
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+            .into_pipeline_layout_create_info(device.clone())
+            .unwrap(),
+    )
+    .unwrap();
+
+    GraphicsPipeline::new(
+        device,
+        None,
+        GraphicsPipelineCreateInfo {
+            stages: stages.into_iter().collect(),
+            vertex_input_state: Some(VertexInputState::default()),
+            input_assembly_state: Some(InputAssemblyState::default()),
+            viewport_state: Some(ViewportState {
+                viewports: [Viewport {
+                    offset: [0.0, 0.0],
+                    extent: [extent[0] as f32, extent[1] as f32],
+                    depth_range: 0.0..=1.0,
+                }]
+                .into_iter()
+                .collect(),
+                ..Default::default()
+            }),
+            rasterization_state: Some(RasterizationState::default()),
+            multisample_state: Some(MultisampleState::default()),
+            color_blend_state: Some(ColorBlendState::with_attachment_states(
+                subpass.num_color_attachments(),
+                ColorBlendAttachmentState::default(),
+            )),
+            subpass: Some(subpass.into()),
+            ..GraphicsPipelineCreateInfo::layout(layout)
+        },
+    )
+    .unwrap()
+}
+
+fn write_image_buffer_to_file(buffer: Subbuffer<[u8]>, path: &str, extent: [u32; 2]) {
+    let buffer_content = buffer.read().unwrap();
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(path);
+    let file = File::create(&path).unwrap();
+    let w = &mut BufWriter::new(file);
+    let mut encoder = png::Encoder::new(w, extent[0], extent[1]);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().unwrap();
+    writer.write_image_data(&buffer_content).unwrap();
+
+    if let Ok(path) = path.canonicalize() {
+        println!("Saved to {}", path.display());
+    }
+}
+
+// Outputs a triangle that covers the whole framebuffer, along with the normalized device
+// coordinates of each fragment.
+mod fullscreen_vs {
+    vulkano_shaders::shader! {
+        ty: "vertex",
+        src: r"
+            #version 450
+
+            layout(location = 0) out vec2 v_position;
+
+            void main() {
+                v_position = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2) * 2.0 - 1.0;
+                gl_Position = vec4(v_position, 0.0, 1.0);
+            }
+        ",
+    }
+}
+
+// Draws a procedural sky with a sun above a checkered ground. `gl_ViewIndex` is the index of the
+// cube face that is being rendered, which determines the direction that each fragment of the face
+// corresponds to.
+mod scene_fs {
+    vulkano_shaders::shader! {
+        ty: "fragment",
+        src: r"
+            #version 450
+            #extension GL_EXT_multiview : enable
+
+            layout(location = 0) in vec2 v_position;
+
+            layout(location = 0) out vec4 f_color;
+
+            // The direction in which the texel at `position` of `face` is sampled.
+            vec3 cube_direction(int face, vec2 position) {
+                float x = position.x;
+                float y = position.y;
+
+                switch (face) {
+                    case 0: return vec3(1.0, -y, -x);
+                    case 1: return vec3(-1.0, -y, x);
+                    case 2: return vec3(x, 1.0, y);
+                    case 3: return vec3(x, -1.0, -y);
+                    case 4: return vec3(x, -y, 1.0);
+                    default: return vec3(-x, -y, -1.0);
+                }
+            }
+
+            void main() {
+                vec3 direction = normalize(cube_direction(gl_ViewIndex, v_position));
+                vec3 sun_direction = normalize(vec3(0.3, 0.4, 1.0));
+
+                vec3 color;
+
+                if (direction.y >= 0.0) {
+                    color = mix(vec3(0.8, 0.9, 1.0), vec3(0.2, 0.4, 0.8), direction.y);
+                    color += vec3(1.0, 0.9, 0.6) * pow(max(dot(direction, sun_direction), 0.0), 256.0);
+                } else {
+                    // Project the direction onto a ground plane below the camera.
+                    vec2 ground = direction.xz / -direction.y;
+                    bool checker = ((int(floor(ground.x)) + int(floor(ground.y))) & 1) == 0;
+                    color = checker ? vec3(0.3, 0.5, 0.2) : vec3(0.2, 0.35, 0.15);
+                    color = mix(color, vec3(0.8, 0.9, 1.0), pow(1.0 + direction.y, 16.0));
+                }
+
+                f_color = vec4(color, 1.0);
+            }
+        ",
+    }
+}
+
+// Samples the cubemap in the direction of each pixel, as seen by a camera that looks slightly up
+// towards the sun.
+mod skybox_fs {
+    vulkano_shaders::shader! {
+        ty: "fragment",
+        src: r"
+            #version 450
+
+            layout(location = 0) in vec2 v_position;
+
+            layout(location = 0) out vec4 f_color;
+
+            layout(set = 0, binding = 0) uniform samplerCube skybox;
+
+            layout(push_constant) uniform PushConstants {
+                float aspect_ratio;
+            };
+
+            void main() {
+                vec3 forward = normalize(vec3(0.2, 0.15, 1.0));
+                vec3 right = normalize(cross(vec3(0.0, 1.0, 0.0), forward));
+                vec3 up = cross(forward, right);
+
+                // A vertical field of view of 90 degrees. In normalized device coordinates,
+                // y points down.
+                vec3 direction = forward
+                    + v_position.x * aspect_ratio * right
+                    - v_position.y * up;
+
+                f_color = texture(skybox, direction);
+            }
+        ",
+    }
+}
@@ -0,0 +1,173 @@
+// This example demonstrates how to print messages from a shader with `debugPrintfEXT`.
+//
+// Debug printf is implemented by the validation layer, so the Vulkan SDK (or at least the Khronos
+// validation layer) must be installed for this example to run. The layer instruments shaders that
+// call `debugPrintfEXT`, and reports the printed messages through the debug messengers of the
+// instance, where `DebugPrintfCollector` picks them up.
+
+use std::sync::Arc;
+use vulkano::{
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, CommandBufferBeginInfo, CommandBufferLevel,
+        CommandBufferUsage, RecordingCommandBuffer,
+    },
+    device::{
+        physical::PhysicalDeviceType, Device, DeviceCreateInfo, DeviceExtensions, QueueCreateInfo,
+        QueueFlags,
+    },
+    instance::{debug::DebugPrintfCollector, Instance, InstanceCreateFlags, InstanceCreateInfo},
+    pipeline::{
+        compute::ComputePipelineCreateInfo, layout::PipelineDescriptorSetLayoutCreateInfo,
+        ComputePipeline, PipelineLayout, PipelineShaderStageCreateInfo,
+    },
+    sync::{self, GpuFuture},
+    Version, VulkanLibrary,
+};
+
+fn main() {
+    let library = VulkanLibrary::new().unwrap();
+
+    // Enable the validation layer along with its debug printf feature, and the extensions needed
+    // to do so.
+    let mut instance_create_info = InstanceCreateInfo {
+        flags: InstanceCreateFlags::ENUMERATE_PORTABILITY,
+        ..Default::default()
+    };
+    DebugPrintfCollector::configure_instance(&mut instance_create_info);
+
+    let instance = Instance::new(library, instance_create_info)
+        .expect("failed to create the instance, is the validation layer installed?");
+
+    // The collector must be kept alive for as long as messages should be collected.
+    let collector = DebugPrintfCollector::new(instance.clone()).unwrap();
+
+    let (physical_device, queue_family_index) = instance
+        .enumerate_physical_devices()
+        .unwrap()
+        .filter(|p| {
+            // Shaders that use debug printf contain instructions of the `NonSemantic.DebugPrintf`
+            // instruction set, which requires this extension before Vulkan 1.3.
+            p.api_version() >= Version::V1_3
+                || p.supported_extensions().khr_shader_non_semantic_info
+        })
+        .filter_map(|p| {
+            p.queue_family_properties()
+                .iter()
+                .position(|q| q.queue_flags.intersects(QueueFlags::COMPUTE))
+                .map(|i| (p, i as u32))
+        })
+        .min_by_key(|(p, _)| match p.properties().device_type {
+            PhysicalDeviceType::DiscreteGpu => 0,
+            PhysicalDeviceType::IntegratedGpu => 1,
+            PhysicalDeviceType::VirtualGpu => 2,
+            PhysicalDeviceType::Cpu => 3,
+            PhysicalDeviceType::Other => 4,
+            _ => 5,
+        })
+        .unwrap();
+
+    println!(
+        "Using device: {} (type: {:?})",
+        physical_device.properties().device_name,
+        physical_device.properties().device_type,
+    );
+
+    let device_extensions = DeviceExtensions {
+        khr_shader_non_semantic_info: physical_device.api_version() < Version::V1_3,
+        ..DeviceExtensions::empty()
+    };
+
+    let (device, mut queues) = Device::new(
+        physical_device,
+        DeviceCreateInfo {
+            enabled_extensions: device_extensions,
+            queue_create_infos: vec![QueueCreateInfo {
+                queue_family_index,
+                ..Default::default()
+            }],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let queue = queues.next().unwrap();
+
+    let pipeline = {
+        mod cs {
+            vulkano_shaders::shader! {
+                ty: "compute",
+                // This inserts the `#extension GL_EXT_debug_printf : enable` directive, which
+                // makes `debugPrintfEXT` available.
+                enable_debug_printf: true,
+                src: r#"
+                    #version 450
+
+                    layout(local_size_x = 4, local_size_y = 1, local_size_z = 1) in;
+
+                    void main() {
+                        debugPrintfEXT(
+                            "Hello from workgroup %u, invocation %u",
+                            gl_WorkGroupID.x,
+                            gl_LocalInvocationID.x
+                        );
+                    }
+                "#,
+            }
+        }
+        let cs = cs::load(device.clone())
+            .unwrap()
+            .entry_point("main")
+            .unwrap();
+        let stage = PipelineShaderStageCreateInfo::new(cs);
+        let layout = PipelineLayout::new(
+            device.clone(),
+            PipelineDescriptorSetLayoutCreateInfo::from_stages([&stage])
+                .into_pipeline_layout_create_info(device.clone())
+                .unwrap(),
+        )
+        .unwrap();
+        ComputePipeline::new(
+            device.clone(),
+            None,
+            ComputePipelineCreateInfo::stage_layout(stage, layout),
+        )
+        .unwrap()
+    };
+
+    let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+        device.clone(),
+        Default::default(),
+    ));
+
+    let mut builder = RecordingCommandBuffer::new(
+        command_buffer_allocator,
+        queue.queue_family_index(),
+        CommandBufferLevel::Primary,
+        CommandBufferBeginInfo {
+            usage: CommandBufferUsage::OneTimeSubmit,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    builder.bind_pipeline_compute(pipeline).unwrap();
+
+    unsafe {
+        builder.dispatch([2, 1, 1]).unwrap();
+    }
+
+    let command_buffer = builder.end().unwrap();
+
+    let future = sync::now(device)
+        .then_execute(queue, command_buffer)
+        .unwrap()
+        .then_signal_fence_and_flush()
+        .unwrap();
+
+    // The messages are reported once the command buffer has finished executing.
+    future.wait(None).unwrap();
+
+    // In a real application, this would typically be done once per frame.
+    for message in collector.drain() {
+        println!("{message}");
+    }
+}
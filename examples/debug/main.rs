@@ -7,7 +7,7 @@ use vulkano::{
             DebugUtilsMessageSeverity, DebugUtilsMessageType, DebugUtilsMessenger,
             DebugUtilsMessengerCallback, DebugUtilsMessengerCreateInfo,
         },
-        Instance, InstanceCreateFlags, InstanceCreateInfo, InstanceExtensions,
+        Instance, InstanceCreateFlags, InstanceCreateInfo, InstanceExtensions, LayerFilter,
     },
     VulkanLibrary,
 };
@@ -45,8 +45,8 @@ fn main() {
         println!("\t{}", l.name());
     }
 
-    // NOTE: To simplify the example code we won't verify these layer(s) are actually in the layers
-    // list.
+    // Instead of verifying that the layer(s) are actually in the layers list ourselves, we let
+    // Vulkano drop any layers that are not installed, by using `LayerFilter::IgnoreMissing`.
     let layers = vec!["VK_LAYER_KHRONOS_validation".to_owned()];
 
     // Important: pass the extension(s) and layer(s) when creating the vulkano instance.
@@ -55,12 +55,15 @@ fn main() {
         InstanceCreateInfo {
             flags: InstanceCreateFlags::ENUMERATE_PORTABILITY,
             enabled_layers: layers,
+            enabled_layers_filter: LayerFilter::IgnoreMissing,
             enabled_extensions: extensions,
             ..Default::default()
         },
     )
     .expect("failed to create Vulkan instance");
 
+    println!("Enabled layers: {:?}", instance.enabled_layers());
+
     // After creating the instance we must register the debug callback.
     //
     // NOTE: If you let this debug_callback binding fall out of scope then the callback will stop
@@ -19,7 +19,7 @@ use vulkano::{
         QueueFlags,
     },
     format::Format,
-    image::{view::ImageView, Image, ImageCreateInfo, ImageType, ImageUsage},
+    image::{view::ImageView, Image, ImageCreateInfo, ImageUsage},
     instance::{Instance, InstanceCreateFlags, InstanceCreateInfo, InstanceExtensions},
     memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
     pipeline::{
@@ -214,11 +214,8 @@ fn main() {
     let image = Image::new(
         memory_allocator.clone(),
         ImageCreateInfo {
-            image_type: ImageType::Dim2d,
-            format: Format::R8G8B8A8_UNORM,
-            extent: [1024, 1024, 1],
             usage: ImageUsage::TRANSFER_SRC | ImageUsage::STORAGE,
-            ..Default::default()
+            ..ImageCreateInfo::storage_2d(Format::R8G8B8A8_UNORM, [1024, 1024])
         },
         AllocationCreateInfo::default(),
     )
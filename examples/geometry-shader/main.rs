@@ -0,0 +1,542 @@
+// This example demonstrates using a geometry shader to visualize the normals of a mesh.
+//
+// A sphere is drawn twice with the same vertex buffer. The first pipeline shades it normally. The
+// second pipeline has a geometry shader that takes each triangle of the sphere as input, and
+// outputs a short line along the normal of each of its three vertices instead. The result is
+// saved to a PNG file, so that the example can also run on a headless device.
+//
+// The execution modes of a geometry shader, such as the type of its input and output primitives
+// and the maximum number of vertices that it outputs, are declared with `layout` qualifiers in
+// GLSL. Vulkano reflects them in `EntryPointInfo::geometry`, and checks them against the device
+// limits and against the input assembly state when the pipeline is created.
+
+use std::{f32::consts::PI, fs::File, io::BufWriter, path::Path, sync::Arc};
+use vulkano::{
+    buffer::{Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer},
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, CommandBufferBeginInfo, CommandBufferLevel,
+        CommandBufferUsage, CopyImageToBufferInfo, RecordingCommandBuffer, RenderPassBeginInfo,
+    },
+    device::{
+        physical::PhysicalDeviceType, Device, DeviceCreateInfo, DeviceExtensions, DeviceFeatures,
+        QueueCreateInfo, QueueFlags,
+    },
+    format::Format,
+    image::{view::ImageView, Image, ImageCreateInfo, ImageUsage},
+    instance::{Instance, InstanceCreateFlags, InstanceCreateInfo},
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
+    pipeline::{
+        graphics::{
+            color_blend::{ColorBlendAttachmentState, ColorBlendState},
+            depth_stencil::{DepthState, DepthStencilState},
+            input_assembly::InputAssemblyState,
+            multisample::MultisampleState,
+            rasterization::RasterizationState,
+            vertex_input::{Vertex, VertexDefinition},
+            viewport::{Viewport, ViewportState},
+            GraphicsPipelineCreateInfo,
+        },
+        layout::PipelineDescriptorSetLayoutCreateInfo,
+        GraphicsPipeline, PipelineLayout, PipelineShaderStageCreateInfo,
+    },
+    render_pass::{Framebuffer, FramebufferCreateInfo, Subpass},
+    shader::EntryPoint,
+    sync::{self, GpuFuture},
+    VulkanLibrary,
+};
+
+const OUTPUT_EXTENT: [u32; 2] = [512, 512];
+const SPHERE_STACKS: u32 = 12;
+const SPHERE_SLICES: u32 = 24;
+
+fn main() {
+    let library = VulkanLibrary::new().unwrap();
+    let instance = Instance::new(
+        library,
+        InstanceCreateInfo {
+            flags: InstanceCreateFlags::ENUMERATE_PORTABILITY,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let device_extensions = DeviceExtensions::empty();
+    let features = DeviceFeatures {
+        geometry_shader: true,
+        ..DeviceFeatures::empty()
+    };
+    let (physical_device, queue_family_index) = instance
+        .enumerate_physical_devices()
+        .unwrap()
+        .filter(|p| p.supported_extensions().contains(&device_extensions))
+        .filter(|p| p.supported_features().contains(&features))
+        .filter_map(|p| {
+            p.queue_family_properties()
+                .iter()
+                .position(|q| q.queue_flags.intersects(QueueFlags::GRAPHICS))
+                .map(|i| (p, i as u32))
+        })
+        .min_by_key(|(p, _)| match p.properties().device_type {
+            PhysicalDeviceType::DiscreteGpu => 0,
+            PhysicalDeviceType::IntegratedGpu => 1,
+            PhysicalDeviceType::VirtualGpu => 2,
+            PhysicalDeviceType::Cpu => 3,
+            PhysicalDeviceType::Other => 4,
+            _ => 5,
+        })
+        .expect("no device supports geometry shaders");
+
+    println!(
+        "Using device: {} (type: {:?})",
+        physical_device.properties().device_name,
+        physical_device.properties().device_type,
+    );
+
+    let (device, mut queues) = Device::new(
+        physical_device,
+        DeviceCreateInfo {
+            queue_create_infos: vec![QueueCreateInfo {
+                queue_family_index,
+                ..Default::default()
+            }],
+            enabled_extensions: device_extensions,
+            enabled_features: features,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let queue = queues.next().unwrap();
+
+    let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+    let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+        device.clone(),
+        Default::default(),
+    ));
+
+    let (vertices, indices) = sphere(SPHERE_STACKS, SPHERE_SLICES);
+
+    let vertex_buffer = Buffer::from_iter(
+        memory_allocator.clone(),
+        BufferCreateInfo {
+            usage: BufferUsage::VERTEX_BUFFER,
+            ..Default::default()
+        },
+        AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
+                | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+            ..Default::default()
+        },
+        vertices,
+    )
+    .unwrap();
+    let index_buffer = Buffer::from_iter(
+        memory_allocator.clone(),
+        BufferCreateInfo {
+            usage: BufferUsage::INDEX_BUFFER,
+            ..Default::default()
+        },
+        AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
+                | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+            ..Default::default()
+        },
+        indices,
+    )
+    .unwrap();
+
+    let output_image = Image::new(
+        memory_allocator.clone(),
+        ImageCreateInfo {
+            usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::TRANSFER_SRC,
+            ..ImageCreateInfo::color_attachment(Format::R8G8B8A8_SRGB, OUTPUT_EXTENT)
+        },
+        AllocationCreateInfo::default(),
+    )
+    .unwrap();
+    let depth_image = Image::new(
+        memory_allocator.clone(),
+        ImageCreateInfo::depth_stencil_attachment(Format::D16_UNORM, OUTPUT_EXTENT),
+        AllocationCreateInfo::default(),
+    )
+    .unwrap();
+
+    let render_pass = vulkano::single_pass_renderpass!(
+        device.clone(),
+        attachments: {
+            color: {
+                format: output_image.format(),
+                samples: 1,
+                load_op: Clear,
+                store_op: Store,
+            },
+            depth_stencil: {
+                format: depth_image.format(),
+                samples: 1,
+                load_op: Clear,
+                store_op: DontCare,
+            },
+        },
+        pass: {
+            color: [color],
+            depth_stencil: {depth_stencil},
+        },
+    )
+    .unwrap();
+
+    let framebuffer = Framebuffer::new(
+        render_pass.clone(),
+        FramebufferCreateInfo {
+            attachments: vec![
+                ImageView::new_default(output_image.clone()).unwrap(),
+                ImageView::new_default(depth_image).unwrap(),
+            ],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let vs = vs::load(device.clone())
+        .unwrap()
+        .entry_point("main")
+        .unwrap();
+    let normals_gs = normals_gs::load(device.clone())
+        .unwrap()
+        .entry_point("main")
+        .unwrap();
+
+    // The execution modes that were declared in the geometry shader.
+    println!(
+        "Geometry shader execution modes: {:?}",
+        normals_gs.info().geometry.unwrap(),
+    );
+
+    let subpass = Subpass::from(render_pass, 0).unwrap();
+
+    let sphere_pipeline = create_pipeline(
+        device.clone(),
+        vs.clone(),
+        None,
+        shaded_fs::load(device.clone())
+            .unwrap()
+            .entry_point("main")
+            .unwrap(),
+        subpass.clone(),
+    );
+
+    // The geometry shader takes triangles as input, so the input assembly state must produce
+    // triangles too. A point list or line list topology would cause pipeline creation to fail.
+    let normals_pipeline = create_pipeline(
+        device.clone(),
+        vs,
+        Some(normals_gs),
+        normals_fs::load(device.clone())
+            .unwrap()
+            .entry_point("main")
+            .unwrap(),
+        subpass,
+    );
+
+    let output_buffer: Subbuffer<[u8]> = Buffer::new_slice(
+        memory_allocator,
+        BufferCreateInfo {
+            usage: BufferUsage::TRANSFER_DST,
+            ..Default::default()
+        },
+        AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+            ..Default::default()
+        },
+        (OUTPUT_EXTENT[0] * OUTPUT_EXTENT[1] * 4) as u64,
+    )
+    .unwrap();
+
+    let mut builder = RecordingCommandBuffer::new(
+        command_buffer_allocator,
+        queue.queue_family_index(),
+        CommandBufferLevel::Primary,
+        CommandBufferBeginInfo {
+            usage: CommandBufferUsage::OneTimeSubmit,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    builder
+        .begin_render_pass(
+            RenderPassBeginInfo {
+                clear_values: vec![Some([0.1, 0.1, 0.1, 1.0].into()), Some(1f32.into())],
+                ..RenderPassBeginInfo::framebuffer(framebuffer)
+            },
+            Default::default(),
+        )
+        .unwrap()
+        .bind_vertex_buffers(0, vertex_buffer)
+        .unwrap()
+        .bind_index_buffer(index_buffer.clone())
+        .unwrap();
+
+    // Draw the shaded sphere, then its normals on top of it with the same vertices and indices.
+    for pipeline in [sphere_pipeline, normals_pipeline] {
+        builder.bind_pipeline_graphics(pipeline).unwrap();
+
+        unsafe {
+            builder
+                .draw_indexed(index_buffer.len() as u32, 1, 0, 0, 0)
+                .unwrap();
+        }
+    }
+
+    builder
+        .end_render_pass(Default::default())
+        .unwrap()
+        .copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(
+            output_image,
+            output_buffer.clone(),
+        ))
+        .unwrap();
+
+    let command_buffer = builder.end().unwrap();
+
+    sync::now(device)
+        .then_execute(queue, command_buffer)
+        .unwrap()
+        .then_signal_fence_and_flush()
+        .unwrap()
+        .wait(None)
+        .unwrap();
+
+    write_image_buffer_to_file(output_buffer, "geometry-shader.png", OUTPUT_EXTENT);
+}
+
+#[derive(BufferContents, Vertex)]
+#[repr(C)]
+struct SphereVertex {
+    #[format(R32G32B32_SFLOAT)]
+    position: [f32; 3],
+    #[format(R32G32B32_SFLOAT)]
+    normal: [f32; 3],
+}
+
+/// Generates the vertices and triangle list indices of a unit sphere centered at the origin.
+fn sphere(stacks: u32, slices: u32) -> (Vec<SphereVertex>, Vec<u32>) {
+    let mut vertices = Vec::new();
+
+    for stack in 0..=stacks {
+        let theta = PI * stack as f32 / stacks as f32;
+
+        for slice in 0..=slices {
+            let phi = 2.0 * PI * slice as f32 / slices as f32;
+            let normal = [
+                theta.sin() * phi.cos(),
+                theta.cos(),
+                theta.sin() * phi.sin(),
+            ];
+
+            vertices.push(SphereVertex {
+                position: normal,
+                normal,
+            });
+        }
+    }
+
+    let mut indices = Vec::new();
+
+    for stack in 0..stacks {
+        for slice in 0..slices {
+            let top = stack * (slices + 1) + slice;
+            let bottom = top + slices + 1;
+
+            indices.extend([top, bottom, top + 1, top + 1, bottom, bottom + 1]);
+        }
+    }
+
+    (vertices, indices)
+}
+
+/// Creates a pipeline that draws the sphere, optionally with a geometry shader.
+fn create_pipeline(
+    device: Arc<Device>,
+    vs: EntryPoint,
+    gs: Option<EntryPoint>,
+    fs: EntryPoint,
+    subpass: Subpass,
+) -> Arc<GraphicsPipeline> {
+    let vertex_input_state = SphereVertex::per_vertex().definition(&vs).unwrap();
+    let stages: Vec<_> = [Some(vs), gs, Some(fs)]
+        .into_iter()
+        .flatten()
+        .map(PipelineShaderStageCreateInfo::new)
+        .collect();
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+            .into_pipeline_layout_create_info(device.clone())
+            .unwrap(),
+    )
+    .unwrap();
+
+    GraphicsPipeline::new(
+        device,
+        None,
+        GraphicsPipelineCreateInfo {
+            stages: stages.into_iter().collect(),
+            vertex_input_state: Some(vertex_input_state),
+            input_assembly_state: Some(InputAssemblyState::default()),
+            viewport_state: Some(ViewportState {
+                viewports: [Viewport {
+                    offset: [0.0, 0.0],
+                    extent: [OUTPUT_EXTENT[0] as f32, OUTPUT_EXTENT[1] as f32],
+                    depth_range: 0.0..=1.0,
+                }]
+                .into_iter()
+                .collect(),
+                ..Default::default()
+            }),
+            rasterization_state: Some(RasterizationState::default()),
+            depth_stencil_state: Some(DepthStencilState {
+                depth: Some(DepthState::simple()),
+                ..Default::default()
+            }),
+            multisample_state: Some(MultisampleState::default()),
+            color_blend_state: Some(ColorBlendState::with_attachment_states(
+                subpass.num_color_attachments(),
+                ColorBlendAttachmentState::default(),
+            )),
+            subpass: Some(subpass.into()),
+            ..GraphicsPipelineCreateInfo::layout(layout)
+        },
+    )
+    .unwrap()
+}
+
+fn write_image_buffer_to_file(buffer: Subbuffer<[u8]>, path: &str, extent: [u32; 2]) {
+    let buffer_content = buffer.read().unwrap();
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(path);
+    let file = File::create(&path).unwrap();
+    let w = &mut BufWriter::new(file);
+    let mut encoder = png::Encoder::new(w, extent[0], extent[1]);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().unwrap();
+    writer.write_image_data(&buffer_content).unwrap();
+
+    if let Ok(path) = path.canonicalize() {
+        println!("Saved to {}", path.display());
+    }
+}
+
+// Rotates the sphere so that it is seen slightly from above, and moves it in front of the camera.
+// The view-space position and normal are passed on, so that the geometry shader can extend lines
+// from them.
+mod vs {
+    vulkano_shaders::shader! {
+        ty: "vertex",
+        src: r"
+            #version 450
+
+            layout(location = 0) in vec3 position;
+            layout(location = 1) in vec3 normal;
+
+            layout(location = 0) out vec3 v_position;
+            layout(location = 1) out vec3 v_normal;
+
+            const mat3 ROTATION = mat3(
+                1.0, 0.0, 0.0,
+                0.0, 0.94, 0.34,
+                0.0, -0.34, 0.94
+            );
+
+            // A perspective projection with a vertical field of view of 60 degrees, a near plane
+            // at 0.1 and a far plane at 10. In normalized device coordinates, y points down.
+            vec4 project(vec3 p) {
+                float f = 1.0 / tan(radians(30.0));
+                return vec4(p.x * f, -p.y * f, (p.z - 0.1) * 10.0 / 9.9, p.z);
+            }
+
+            void main() {
+                v_position = ROTATION * position + vec3(0.0, 0.0, 3.0);
+                v_normal = ROTATION * normal;
+                gl_Position = project(v_position);
+            }
+        ",
+    }
+}
+
+// For each input triangle, outputs a line strip of two vertices for each of the triangle's
+// vertices, going from the vertex along its normal. Each line is ended with `EndPrimitive`, so
+// that the three lines aren't connected to each other.
+mod normals_gs {
+    vulkano_shaders::shader! {
+        ty: "geometry",
+        src: r"
+            #version 450
+
+            layout(triangles) in;
+            layout(line_strip, max_vertices = 6) out;
+
+            layout(location = 0) in vec3 v_position[];
+            layout(location = 1) in vec3 v_normal[];
+
+            const float NORMAL_LENGTH = 0.15;
+
+            // The same projection as in the vertex shader.
+            vec4 project(vec3 p) {
+                float f = 1.0 / tan(radians(30.0));
+                return vec4(p.x * f, -p.y * f, (p.z - 0.1) * 10.0 / 9.9, p.z);
+            }
+
+            void main() {
+                for (int i = 0; i < 3; i++) {
+                    gl_Position = project(v_position[i]);
+                    EmitVertex();
+
+                    gl_Position = project(v_position[i] + normalize(v_normal[i]) * NORMAL_LENGTH);
+                    EmitVertex();
+
+                    EndPrimitive();
+                }
+            }
+        ",
+    }
+}
+
+mod shaded_fs {
+    vulkano_shaders::shader! {
+        ty: "fragment",
+        src: r"
+            #version 450
+
+            layout(location = 0) in vec3 v_position;
+            layout(location = 1) in vec3 v_normal;
+
+            layout(location = 0) out vec4 f_color;
+
+            const vec3 LIGHT = vec3(0.0, 0.0, 1.0);
+
+            void main() {
+                vec3 dark_color = vec3(0.1, 0.15, 0.4);
+                vec3 regular_color = vec3(0.3, 0.45, 0.9);
+                float brightness = max(dot(normalize(v_normal), -normalize(LIGHT)), 0.0);
+
+                f_color = vec4(mix(dark_color, regular_color, brightness), 1.0);
+            }
+        ",
+    }
+}
+
+mod normals_fs {
+    vulkano_shaders::shader! {
+        ty: "fragment",
+        src: r"
+            #version 450
+
+            layout(location = 0) out vec4 f_color;
+
+            void main() {
+                f_color = vec4(1.0, 0.85, 0.2, 1.0);
+            }
+        ",
+    }
+}
@@ -25,7 +25,7 @@ use vulkano::{
     image::{
         sampler::{Filter, Sampler, SamplerAddressMode, SamplerCreateInfo},
         view::ImageView,
-        Image, ImageCreateInfo, ImageType, ImageUsage,
+        Image, ImageCreateInfo, ImageUsage,
     },
     instance::{Instance, InstanceCreateFlags, InstanceCreateInfo},
     memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
@@ -232,7 +232,7 @@ fn main() -> Result<(), impl Error> {
         let decoder = png::Decoder::new(png_bytes);
         let mut reader = decoder.read_info().unwrap();
         let info = reader.info();
-        let extent = [info.width, info.height, 1];
+        let extent = [info.width, info.height];
 
         let upload_buffer = Buffer::new_slice(
             memory_allocator.clone(),
@@ -255,13 +255,7 @@ fn main() -> Result<(), impl Error> {
 
         let image = Image::new(
             memory_allocator,
-            ImageCreateInfo {
-                image_type: ImageType::Dim2d,
-                format: Format::R8G8B8A8_SRGB,
-                extent,
-                usage: ImageUsage::TRANSFER_DST | ImageUsage::SAMPLED,
-                ..Default::default()
-            },
+            ImageCreateInfo::sampled_2d_no_mipmap(Format::R8G8B8A8_SRGB, extent),
             AllocationCreateInfo::default(),
         )
         .unwrap();
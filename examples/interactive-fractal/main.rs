@@ -12,7 +12,7 @@
 
 use crate::app::FractalApp;
 use std::{error::Error, time::Duration};
-use vulkano::{image::ImageUsage, swapchain::PresentMode, sync::GpuFuture};
+use vulkano::{image::ImageUsage, swapchain::PresentMode, sync::GpuFuture, VulkanError};
 use vulkano_util::{
     context::{VulkanoConfig, VulkanoContext},
     renderer::{VulkanoWindowRenderer, DEFAULT_IMAGE_FORMAT},
@@ -150,6 +150,8 @@ fn compute_then_render(
             app.place_over_frame
                 .recreate_framebuffers(swapchain_image_views)
         }) {
+            // The window is minimized or the swapchain is being recreated; skip this frame.
+            Err(VulkanError::OutOfDate) => return,
             Err(e) => {
                 println!("{e}");
                 return;
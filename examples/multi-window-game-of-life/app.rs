@@ -9,6 +9,7 @@ use vulkano::{
     },
     descriptor_set::allocator::StandardDescriptorSetAllocator,
     device::Queue,
+    image::sampler::cache::SamplerCache,
 };
 use vulkano_util::{
     context::{VulkanoConfig, VulkanoContext},
@@ -42,6 +43,7 @@ pub struct App {
     pub windows: VulkanoWindows,
     pub command_buffer_allocator: Arc<StandardCommandBufferAllocator>,
     pub descriptor_set_allocator: Arc<StandardDescriptorSetAllocator>,
+    pub sampler_cache: Arc<SamplerCache>,
     pub pipelines: HashMap<WindowId, RenderPipeline>,
 }
 
@@ -114,12 +116,16 @@ impl Default for App {
             context.device().clone(),
             Default::default(),
         ));
+        // The windows draw with the same sampler every frame, so it is only created once and
+        // shared between them.
+        let sampler_cache = Arc::new(SamplerCache::new(context.device().clone(), 16));
 
         App {
             context,
             windows: VulkanoWindows::default(),
             command_buffer_allocator,
             descriptor_set_allocator,
+            sampler_cache,
             pipelines: HashMap::new(),
         }
     }
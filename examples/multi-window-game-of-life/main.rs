@@ -18,6 +18,7 @@ use std::{
     error::Error,
     time::{Duration, Instant},
 };
+use vulkano::VulkanError;
 use vulkano_util::renderer::VulkanoWindowRenderer;
 use winit::{
     event::{ElementState, Event, MouseButton, WindowEvent},
@@ -203,6 +204,8 @@ fn compute_then_render(
                 .place_over_frame
                 .recreate_framebuffers(swapchain_image_views)
         }) {
+            // The window is minimized or the swapchain is being recreated; skip this frame.
+            Err(VulkanError::OutOfDate) => return,
             Err(e) => {
                 println!("{e}");
                 return;
@@ -11,7 +11,9 @@ use vulkano::{
     },
     device::Queue,
     image::{
-        sampler::{Filter, Sampler, SamplerAddressMode, SamplerCreateInfo, SamplerMipmapMode},
+        sampler::{
+            cache::SamplerCache, Filter, SamplerAddressMode, SamplerCreateInfo, SamplerMipmapMode,
+        },
         view::ImageView,
     },
     pipeline::{
@@ -38,6 +40,7 @@ pub struct PixelsDrawPipeline {
     pipeline: Arc<GraphicsPipeline>,
     command_buffer_allocator: Arc<StandardCommandBufferAllocator>,
     descriptor_set_allocator: Arc<StandardDescriptorSetAllocator>,
+    sampler_cache: Arc<SamplerCache>,
 }
 
 impl PixelsDrawPipeline {
@@ -92,22 +95,22 @@ impl PixelsDrawPipeline {
             pipeline,
             command_buffer_allocator: app.command_buffer_allocator.clone(),
             descriptor_set_allocator: app.descriptor_set_allocator.clone(),
+            sampler_cache: app.sampler_cache.clone(),
         }
     }
 
     fn create_image_sampler_nearest(&self, image: Arc<ImageView>) -> Arc<DescriptorSet> {
         let layout = &self.pipeline.layout().set_layouts()[0];
-        let sampler = Sampler::new(
-            self.gfx_queue.device().clone(),
-            SamplerCreateInfo {
+        let sampler = self
+            .sampler_cache
+            .get_or_create(SamplerCreateInfo {
                 mag_filter: Filter::Nearest,
                 min_filter: Filter::Nearest,
                 address_mode: [SamplerAddressMode::Repeat; 3],
                 mipmap_mode: SamplerMipmapMode::Nearest,
                 ..Default::default()
-            },
-        )
-        .unwrap();
+            })
+            .unwrap();
 
         DescriptorSet::new(
             self.descriptor_set_allocator.clone(),
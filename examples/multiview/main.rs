@@ -2,6 +2,10 @@
 // the framebuffer in one render pass. This can significantly improve performance in cases where
 // multiple perspectives or cameras are very similar like in virtual reality or other types of
 // stereoscopic rendering where the left and right eye only differ in a small position offset.
+//
+// By default, the example renders with a render pass and framebuffer. Run it with the
+// `--dynamic-rendering` argument to render with dynamic rendering instead, which takes the view
+// mask as part of `RenderingInfo` rather than from a subpass description.
 
 use std::{fs::File, io::BufWriter, path::Path, sync::Arc};
 use vulkano::{
@@ -9,7 +13,7 @@ use vulkano::{
     command_buffer::{
         allocator::StandardCommandBufferAllocator, BufferImageCopy, CommandBufferBeginInfo,
         CommandBufferLevel, CommandBufferUsage, CopyImageToBufferInfo, RecordingCommandBuffer,
-        RenderPassBeginInfo,
+        RenderPassBeginInfo, RenderingAttachmentInfo, RenderingInfo,
     },
     device::{
         physical::PhysicalDeviceType, Device, DeviceCreateInfo, DeviceExtensions, DeviceFeatures,
@@ -17,8 +21,7 @@ use vulkano::{
     },
     format::Format,
     image::{
-        view::ImageView, Image, ImageCreateInfo, ImageLayout, ImageSubresourceLayers, ImageType,
-        ImageUsage, SampleCount,
+        view::ImageView, Image, ImageCreateInfo, ImageLayout, ImageType, ImageUsage, SampleCount,
     },
     instance::{Instance, InstanceCreateFlags, InstanceCreateInfo, InstanceExtensions},
     memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
@@ -28,6 +31,7 @@ use vulkano::{
             input_assembly::InputAssemblyState,
             multisample::MultisampleState,
             rasterization::RasterizationState,
+            subpass::{PipelineRenderingCreateInfo, PipelineSubpassType},
             vertex_input::{Vertex, VertexDefinition},
             viewport::{Viewport, ViewportState},
             GraphicsPipelineCreateInfo,
@@ -35,16 +39,22 @@ use vulkano::{
         layout::PipelineDescriptorSetLayoutCreateInfo,
         GraphicsPipeline, PipelineLayout, PipelineShaderStageCreateInfo,
     },
+    query::{QueryControlFlags, QueryPool, QueryPoolCreateInfo, QueryResultFlags, QueryType},
     render_pass::{
         AttachmentDescription, AttachmentLoadOp, AttachmentReference, AttachmentStoreOp,
         Framebuffer, FramebufferCreateInfo, RenderPass, RenderPassCreateInfo, Subpass,
         SubpassDescription,
     },
     sync::{self, GpuFuture},
-    VulkanLibrary,
+    Version, VulkanLibrary,
 };
 
 fn main() {
+    let use_dynamic_rendering = std::env::args().any(|arg| arg == "--dynamic-rendering");
+
+    // The views that are rendered to. Each bit corresponds to one layer of the image.
+    let view_mask = 0b11;
+
     let library = VulkanLibrary::new().unwrap();
     let instance = Instance::new(
         library,
@@ -60,18 +70,26 @@ fn main() {
     )
     .unwrap();
 
-    let device_extensions = DeviceExtensions {
+    let mut device_extensions = DeviceExtensions {
         ..DeviceExtensions::empty()
     };
     let features = DeviceFeatures {
         // enabling the `multiview` feature will use the `VK_KHR_multiview` extension on Vulkan 1.0
         // and the device feature on Vulkan 1.1+.
         multiview: true,
+        dynamic_rendering: use_dynamic_rendering,
         ..DeviceFeatures::empty()
     };
     let (physical_device, queue_family_index) = instance
         .enumerate_physical_devices()
         .unwrap()
+        .filter(|p| {
+            // Dynamic rendering is core in Vulkan 1.3, and is otherwise provided by the
+            // `VK_KHR_dynamic_rendering` extension.
+            !use_dynamic_rendering
+                || p.api_version() >= Version::V1_3
+                || p.supported_extensions().khr_dynamic_rendering
+        })
         .filter(|p| p.supported_extensions().contains(&device_extensions))
         .filter(|p| p.supported_features().contains(&features))
         .filter(|p| {
@@ -110,6 +128,10 @@ fn main() {
         physical_device.properties().device_type,
     );
 
+    if use_dynamic_rendering && physical_device.api_version() < Version::V1_3 {
+        device_extensions.khr_dynamic_rendering = true;
+    }
+
     let (device, mut queues) = Device::new(
         physical_device,
         DeviceCreateInfo {
@@ -213,43 +235,47 @@ fn main() {
         }
     }
 
-    let render_pass_description = RenderPassCreateInfo {
-        attachments: vec![AttachmentDescription {
-            format: image.format(),
-            samples: SampleCount::Sample1,
-            load_op: AttachmentLoadOp::Clear,
-            store_op: AttachmentStoreOp::Store,
-            initial_layout: ImageLayout::ColorAttachmentOptimal,
-            final_layout: ImageLayout::ColorAttachmentOptimal,
-            ..Default::default()
-        }],
-        subpasses: vec![SubpassDescription {
-            // The view mask indicates which layers of the framebuffer should be rendered for each
-            // subpass.
-            view_mask: 0b11,
-            color_attachments: vec![Some(AttachmentReference {
-                attachment: 0,
-                layout: ImageLayout::ColorAttachmentOptimal,
+    // With dynamic rendering, there is no render pass or framebuffer to create. The view mask and
+    // the attachments are given when rendering begins instead.
+    let framebuffer = (!use_dynamic_rendering).then(|| {
+        let render_pass_description = RenderPassCreateInfo {
+            attachments: vec![AttachmentDescription {
+                format: image.format(),
+                samples: SampleCount::Sample1,
+                load_op: AttachmentLoadOp::Clear,
+                store_op: AttachmentStoreOp::Store,
+                initial_layout: ImageLayout::ColorAttachmentOptimal,
+                final_layout: ImageLayout::ColorAttachmentOptimal,
                 ..Default::default()
-            })],
+            }],
+            subpasses: vec![SubpassDescription {
+                // The view mask indicates which layers of the framebuffer should be rendered for
+                // each subpass.
+                view_mask,
+                color_attachments: vec![Some(AttachmentReference {
+                    attachment: 0,
+                    layout: ImageLayout::ColorAttachmentOptimal,
+                    ..Default::default()
+                })],
+                ..Default::default()
+            }],
+            // The correlated view masks indicate sets of views that may be more efficient to
+            // render concurrently. Dynamic rendering has no equivalent of this.
+            correlated_view_masks: vec![view_mask],
             ..Default::default()
-        }],
-        // The correlated view masks indicate sets of views that may be more efficient to render
-        // concurrently.
-        correlated_view_masks: vec![0b11],
-        ..Default::default()
-    };
+        };
 
-    let render_pass = RenderPass::new(device.clone(), render_pass_description).unwrap();
+        let render_pass = RenderPass::new(device.clone(), render_pass_description).unwrap();
 
-    let framebuffer = Framebuffer::new(
-        render_pass.clone(),
-        FramebufferCreateInfo {
-            attachments: vec![image_view],
-            ..Default::default()
-        },
-    )
-    .unwrap();
+        Framebuffer::new(
+            render_pass,
+            FramebufferCreateInfo {
+                attachments: vec![image_view.clone()],
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    });
 
     let pipeline = {
         let vs = vs::load(device.clone())
@@ -272,7 +298,20 @@ fn main() {
                 .unwrap(),
         )
         .unwrap();
-        let subpass = Subpass::from(render_pass, 0).unwrap();
+        let subpass: PipelineSubpassType = match &framebuffer {
+            Some(framebuffer) => Subpass::from(framebuffer.render_pass().clone(), 0)
+                .unwrap()
+                .into(),
+            // When rendering with dynamic rendering, the pipeline is given the view mask and the
+            // formats of the attachments directly, and they must match those given to
+            // `begin_rendering`.
+            None => PipelineRenderingCreateInfo {
+                view_mask,
+                color_attachment_formats: vec![Some(image.format())],
+                ..Default::default()
+            }
+            .into(),
+        };
 
         GraphicsPipeline::new(
             device.clone(),
@@ -294,16 +333,30 @@ fn main() {
                 rasterization_state: Some(RasterizationState::default()),
                 multisample_state: Some(MultisampleState::default()),
                 color_blend_state: Some(ColorBlendState::with_attachment_states(
-                    subpass.num_color_attachments(),
+                    1,
                     ColorBlendAttachmentState::default(),
                 )),
-                subpass: Some(subpass.into()),
+                subpass: Some(subpass),
                 ..GraphicsPipelineCreateInfo::layout(layout)
             },
         )
         .unwrap()
     };
 
+    // An occlusion query that is used inside a subpass or a dynamic rendering instance with
+    // multiview uses one query index for
+    // each view in the view mask, starting at the index that is given to `begin_query`. The query
+    // pool must have enough queries for all of them.
+    let query_range = QueryPool::queries_for_views(0, view_mask);
+    let query_pool = QueryPool::new(
+        device.clone(),
+        QueryPoolCreateInfo {
+            query_count: query_range.end,
+            ..QueryPoolCreateInfo::query_type(QueryType::Occlusion)
+        },
+    )
+    .unwrap();
+
     let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
         device.clone(),
         Default::default(),
@@ -340,37 +393,75 @@ fn main() {
     )
     .unwrap();
 
+    unsafe {
+        // All the queries that are used by the query must be reset, not only the first one.
+        builder
+            .reset_query_pool(query_pool.clone(), query_range.clone())
+            .unwrap();
+    }
+
+    let clear_value = Some([0.0, 0.0, 1.0, 1.0].into());
+
+    match &framebuffer {
+        Some(framebuffer) => builder
+            .begin_render_pass(
+                RenderPassBeginInfo {
+                    clear_values: vec![clear_value],
+                    ..RenderPassBeginInfo::framebuffer(framebuffer.clone())
+                },
+                Default::default(),
+            )
+            .unwrap(),
+        None => builder
+            .begin_rendering(RenderingInfo {
+                view_mask,
+                color_attachments: vec![Some(RenderingAttachmentInfo {
+                    load_op: AttachmentLoadOp::Clear,
+                    store_op: AttachmentStoreOp::Store,
+                    clear_value,
+                    ..RenderingAttachmentInfo::image_view(image_view.clone())
+                })],
+                ..Default::default()
+            })
+            .unwrap(),
+    };
+
     builder
-        .begin_render_pass(
-            RenderPassBeginInfo {
-                clear_values: vec![Some([0.0, 0.0, 1.0, 1.0].into())],
-                ..RenderPassBeginInfo::framebuffer(framebuffer)
-            },
-            Default::default(),
-        )
-        .unwrap()
         .bind_pipeline_graphics(pipeline)
         .unwrap()
         .bind_vertex_buffers(0, vertex_buffer.clone())
         .unwrap();
 
     unsafe {
-        // Drawing commands are broadcast to each view in the view mask of the active renderpass
+        // Drawing commands are broadcast to each view in the view mask of the active render pass
         // which means only a single draw call is needed to draw to multiple layers of the
         // framebuffer.
-        builder.draw(vertex_buffer.len() as u32, 1, 0, 0).unwrap();
+        builder
+            .begin_query(
+                query_pool.clone(),
+                query_range.start,
+                QueryControlFlags::empty(),
+            )
+            .unwrap()
+            .draw(vertex_buffer.len() as u32, 1, 0, 0)
+            .unwrap();
     }
 
-    builder.end_render_pass(Default::default()).unwrap();
+    builder
+        .end_query(query_pool.clone(), query_range.start)
+        .unwrap();
+
+    if framebuffer.is_some() {
+        builder.end_render_pass(Default::default()).unwrap();
+    } else {
+        builder.end_rendering().unwrap();
+    }
 
     // Copy the image layers to different buffers to save them as individual images to disk.
     builder
         .copy_image_to_buffer(CopyImageToBufferInfo {
             regions: [BufferImageCopy {
-                image_subresource: ImageSubresourceLayers {
-                    array_layers: 0..1,
-                    ..image.subresource_layers()
-                },
+                image_subresource: image.subresource_layers().layer(0),
                 image_extent: image.extent(),
                 ..Default::default()
             }]
@@ -380,10 +471,7 @@ fn main() {
         .unwrap()
         .copy_image_to_buffer(CopyImageToBufferInfo {
             regions: [BufferImageCopy {
-                image_subresource: ImageSubresourceLayers {
-                    array_layers: 1..2,
-                    ..image.subresource_layers()
-                },
+                image_subresource: image.subresource_layers().layer(1),
                 image_extent: image.extent(),
                 ..Default::default()
             }]
@@ -402,6 +490,18 @@ fn main() {
 
     future.wait(None).unwrap();
 
+    // How the result is distributed among the queries of the views depends on the
+    // implementation, but their sum is the number of samples that passed for all views together.
+    let mut query_results = vec![0u64; query_range.len()];
+    query_pool
+        .get_results(query_range, &mut query_results, QueryResultFlags::WAIT)
+        .unwrap();
+    println!(
+        "Samples passed: {} (per query: {:?})",
+        query_results.iter().sum::<u64>(),
+        query_results,
+    );
+
     // Write each layer to its own file.
     write_image_buffer_to_file(
         buffer1,
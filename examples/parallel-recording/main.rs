@@ -0,0 +1,415 @@
+// This example demonstrates how to record command buffers on multiple threads.
+//
+// The scene consists of 1000 small triangles, each drawn with its own draw call. The draws are
+// split between a number of worker threads, which each record their share into a secondary
+// command buffer. The main thread then executes all of the secondary command buffers from a
+// single primary command buffer.
+//
+// The rules that make this work are the following:
+//
+// - `StandardCommandBufferAllocator` is `Send` and `Sync`, so it can be shared between threads
+//   with an `Arc`. Internally, it keeps separate command pools for each thread, so that the
+//   threads don't need to lock anything to allocate.
+// - A `RecordingCommandBuffer` is neither `Send` nor `Sync`, so it stays on the thread that
+//   created it until recording is finished. This is also what happens with a task of a
+//   work-stealing thread pool such as rayon's: a task always runs to completion on one thread.
+// - Once recording is finished, the resulting `CommandBuffer` is `Send` and `Sync`. It can be
+//   handed to another thread, and it can be dropped on any thread, in which case it is returned to
+//   the pool that it was allocated from.
+
+use std::{num::NonZeroUsize, sync::Arc, thread};
+use vulkano::{
+    buffer::{Buffer, BufferCreateInfo, BufferUsage},
+    command_buffer::{
+        allocator::{StandardCommandBufferAllocator, StandardCommandBufferAllocatorCreateInfo},
+        CommandBuffer, CommandBufferBeginInfo, CommandBufferInheritanceInfo, CommandBufferLevel,
+        CommandBufferUsage, CopyImageToBufferInfo, RecordingCommandBuffer, RenderPassBeginInfo,
+        SubpassBeginInfo, SubpassContents,
+    },
+    device::{physical::PhysicalDeviceType, Device, DeviceCreateInfo, QueueCreateInfo, QueueFlags},
+    format::Format,
+    image::{view::ImageView, Image, ImageCreateInfo, ImageUsage},
+    instance::{Instance, InstanceCreateFlags, InstanceCreateInfo},
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
+    pipeline::{
+        graphics::{
+            color_blend::{ColorBlendAttachmentState, ColorBlendState},
+            input_assembly::InputAssemblyState,
+            multisample::MultisampleState,
+            rasterization::RasterizationState,
+            vertex_input::VertexInputState,
+            viewport::{Viewport, ViewportState},
+            GraphicsPipelineCreateInfo,
+        },
+        layout::PipelineDescriptorSetLayoutCreateInfo,
+        GraphicsPipeline, Pipeline, PipelineLayout, PipelineShaderStageCreateInfo,
+    },
+    render_pass::{Framebuffer, FramebufferCreateInfo, Subpass},
+    sync::GpuFuture,
+    VulkanLibrary,
+};
+
+const WIDTH: u32 = 800;
+const HEIGHT: u32 = 500;
+
+// The triangles are laid out in a grid of `COLUMNS` by `ROWS` cells.
+const COLUMNS: u32 = 40;
+const ROWS: u32 = 25;
+const DRAW_COUNT: u32 = COLUMNS * ROWS;
+
+fn main() {
+    // The start of this example is exactly the same as `offscreen`. You should read the `triangle`
+    // example if you haven't done so yet.
+
+    let library = VulkanLibrary::new().unwrap();
+
+    let instance = Instance::new(
+        library,
+        InstanceCreateInfo {
+            flags: InstanceCreateFlags::ENUMERATE_PORTABILITY,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let (physical_device, queue_family_index) = instance
+        .enumerate_physical_devices()
+        .unwrap()
+        .filter_map(|p| {
+            p.queue_family_properties()
+                .iter()
+                .position(|q| q.queue_flags.intersects(QueueFlags::GRAPHICS))
+                .map(|i| (p, i as u32))
+        })
+        .min_by_key(|(p, _)| match p.properties().device_type {
+            PhysicalDeviceType::DiscreteGpu => 0,
+            PhysicalDeviceType::IntegratedGpu => 1,
+            PhysicalDeviceType::VirtualGpu => 2,
+            PhysicalDeviceType::Cpu => 3,
+            PhysicalDeviceType::Other => 4,
+            _ => 5,
+        })
+        .expect("no suitable physical device found");
+
+    println!(
+        "Using device: {} (type: {:?})",
+        physical_device.properties().device_name,
+        physical_device.properties().device_type,
+    );
+
+    let (device, mut queues) = Device::new(
+        physical_device,
+        DeviceCreateInfo {
+            queue_create_infos: vec![QueueCreateInfo {
+                queue_family_index,
+                ..Default::default()
+            }],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let queue = queues.next().unwrap();
+
+    let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+    mod vs {
+        vulkano_shaders::shader! {
+            ty: "vertex",
+            src: r"
+                #version 450
+
+                layout(push_constant) uniform PushConstants {
+                    vec2 offset;
+                    vec2 scale;
+                };
+
+                const vec2 POSITIONS[3] = vec2[](
+                    vec2(0.0, -0.4),
+                    vec2(0.4, 0.4),
+                    vec2(-0.4, 0.4)
+                );
+
+                void main() {
+                    gl_Position = vec4(offset + POSITIONS[gl_VertexIndex] * scale, 0.0, 1.0);
+                }
+            ",
+        }
+    }
+
+    mod fs {
+        vulkano_shaders::shader! {
+            ty: "fragment",
+            src: r"
+                #version 450
+
+                layout(location = 0) out vec4 f_color;
+
+                void main() {
+                    f_color = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        }
+    }
+
+    let format = Format::R8G8B8A8_UNORM;
+
+    let render_pass = vulkano::single_pass_renderpass!(
+        device.clone(),
+        attachments: {
+            color: {
+                format: format,
+                samples: 1,
+                load_op: Clear,
+                store_op: Store,
+            },
+        },
+        pass: {
+            color: [color],
+            depth_stencil: {},
+        },
+    )
+    .unwrap();
+
+    let render_output_image = Image::new(
+        memory_allocator.clone(),
+        ImageCreateInfo {
+            format,
+            usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::TRANSFER_SRC,
+            extent: [WIDTH, HEIGHT, 1],
+            ..Default::default()
+        },
+        AllocationCreateInfo::default(),
+    )
+    .unwrap();
+
+    let framebuffer = Framebuffer::new(
+        render_pass.clone(),
+        FramebufferCreateInfo {
+            attachments: vec![ImageView::new_default(render_output_image.clone()).unwrap()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let subpass = Subpass::from(render_pass, 0).unwrap();
+
+    let pipeline = {
+        let vs = vs::load(device.clone())
+            .unwrap()
+            .entry_point("main")
+            .unwrap();
+        let fs = fs::load(device.clone())
+            .unwrap()
+            .entry_point("main")
+            .unwrap();
+
+        let stages = [
+            PipelineShaderStageCreateInfo::new(vs),
+            PipelineShaderStageCreateInfo::new(fs),
+        ];
+
+        let layout = PipelineLayout::new(
+            device.clone(),
+            PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+                .into_pipeline_layout_create_info(device.clone())
+                .unwrap(),
+        )
+        .unwrap();
+
+        GraphicsPipeline::new(
+            device.clone(),
+            None,
+            GraphicsPipelineCreateInfo {
+                stages: stages.into_iter().collect(),
+                // The vertex positions are generated in the vertex shader.
+                vertex_input_state: Some(VertexInputState::default()),
+                input_assembly_state: Some(InputAssemblyState::default()),
+                viewport_state: Some(ViewportState {
+                    viewports: [Viewport {
+                        offset: [0.0, 0.0],
+                        extent: [WIDTH as f32, HEIGHT as f32],
+                        depth_range: 0.0..=1.0,
+                    }]
+                    .into_iter()
+                    .collect(),
+                    ..Default::default()
+                }),
+                rasterization_state: Some(RasterizationState::default()),
+                multisample_state: Some(MultisampleState::default()),
+                color_blend_state: Some(ColorBlendState::with_attachment_states(
+                    subpass.num_color_attachments(),
+                    ColorBlendAttachmentState::default(),
+                )),
+                subpass: Some(subpass.clone().into()),
+                ..GraphicsPipelineCreateInfo::layout(layout)
+            },
+        )
+        .unwrap()
+    };
+
+    // One allocator is shared by all threads. Each worker thread only records a single secondary
+    // command buffer, so the pools don't need to hold more than that.
+    let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+        device.clone(),
+        StandardCommandBufferAllocatorCreateInfo {
+            primary_buffer_count: 1,
+            secondary_buffer_count: 1,
+            ..Default::default()
+        },
+    ));
+
+    let thread_count = thread::available_parallelism().map_or(4, NonZeroUsize::get) as u32;
+    let draws_per_thread = (DRAW_COUNT + thread_count - 1) / thread_count;
+
+    // Record the draws on the worker threads. Each thread returns its finished secondary command
+    // buffer to the main thread when it is joined.
+    let secondary_command_buffers: Vec<Arc<CommandBuffer>> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..thread_count)
+            .map(|thread_index| {
+                let start = (thread_index * draws_per_thread).min(DRAW_COUNT);
+                let end = (start + draws_per_thread).min(DRAW_COUNT);
+                let command_buffer_allocator = command_buffer_allocator.clone();
+                let pipeline = pipeline.clone();
+                let subpass = subpass.clone();
+
+                scope.spawn(move || {
+                    let mut builder = RecordingCommandBuffer::new(
+                        command_buffer_allocator,
+                        queue_family_index,
+                        CommandBufferLevel::Secondary,
+                        CommandBufferBeginInfo {
+                            usage: CommandBufferUsage::OneTimeSubmit,
+                            inheritance_info: Some(CommandBufferInheritanceInfo {
+                                render_pass: Some(subpass.into()),
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        },
+                    )
+                    .unwrap();
+
+                    builder.bind_pipeline_graphics(pipeline.clone()).unwrap();
+
+                    let scale = [1.0 / COLUMNS as f32, 1.0 / ROWS as f32];
+
+                    for draw_index in start..end {
+                        let column = draw_index % COLUMNS;
+                        let row = draw_index / COLUMNS;
+                        let push_constants = vs::PushConstants {
+                            offset: [
+                                (column as f32 + 0.5) * 2.0 * scale[0] - 1.0,
+                                (row as f32 + 0.5) * 2.0 * scale[1] - 1.0,
+                            ],
+                            scale,
+                        };
+
+                        builder
+                            .push_constants(pipeline.layout().clone(), 0, push_constants)
+                            .unwrap();
+
+                        unsafe { builder.draw(3, 1, 0, 0) }.unwrap();
+                    }
+
+                    // The builder never leaves this thread, but the finished command buffer can.
+                    builder.end().unwrap()
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .collect()
+    });
+
+    // Host-accessible buffer where the image's contents are copied to after rendering.
+    let render_output_buf = Buffer::from_iter(
+        memory_allocator,
+        BufferCreateInfo {
+            usage: BufferUsage::TRANSFER_DST,
+            ..Default::default()
+        },
+        AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+            ..Default::default()
+        },
+        (0..WIDTH * HEIGHT * 4).map(|_| 0u8),
+    )
+    .unwrap();
+
+    let mut builder = RecordingCommandBuffer::new(
+        command_buffer_allocator,
+        queue_family_index,
+        CommandBufferLevel::Primary,
+        CommandBufferBeginInfo {
+            usage: CommandBufferUsage::OneTimeSubmit,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    builder
+        .begin_render_pass(
+            RenderPassBeginInfo {
+                clear_values: vec![Some([0.0, 0.0, 1.0, 1.0].into())],
+                ..RenderPassBeginInfo::framebuffer(framebuffer)
+            },
+            SubpassBeginInfo {
+                // The contents of the subpass are recorded in secondary command buffers.
+                contents: SubpassContents::SecondaryCommandBuffers,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .execute_commands_from_vec(secondary_command_buffers)
+        .unwrap()
+        .end_render_pass(Default::default())
+        .unwrap()
+        .copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(
+            render_output_image,
+            render_output_buf.clone(),
+        ))
+        .unwrap();
+
+    let command_buffer = builder.end().unwrap();
+
+    command_buffer
+        .execute(queue)
+        .unwrap()
+        .then_signal_fence_and_flush()
+        .unwrap()
+        .wait(None)
+        .unwrap();
+
+    let buffer_content = render_output_buf.read().unwrap();
+    let pixel = |x: u32, y: u32| {
+        let offset = ((y * WIDTH + x) * 4) as usize;
+        &buffer_content[offset..offset + 4]
+    };
+
+    // Every triangle covers the center of its grid cell, no matter which thread recorded its
+    // draw, while the corners of the cells keep the clear color.
+    let cell_extent = [WIDTH / COLUMNS, HEIGHT / ROWS];
+
+    for row in 0..ROWS {
+        for column in 0..COLUMNS {
+            let [x, y] = [column * cell_extent[0], row * cell_extent[1]];
+            assert_eq!(
+                pixel(x + cell_extent[0] / 2, y + cell_extent[1] / 2),
+                [255, 0, 0, 255],
+            );
+            assert_eq!(pixel(x, y), [0, 0, 255, 255]);
+        }
+    }
+
+    let covered_pixels = buffer_content
+        .chunks_exact(4)
+        .filter(|pixel| pixel[0] == 255)
+        .count();
+
+    println!(
+        "Recorded {DRAW_COUNT} draws on {thread_count} threads, covering {covered_pixels} pixels",
+    );
+}
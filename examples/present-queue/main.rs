@@ -0,0 +1,340 @@
+// This example demonstrates how to present swapchain images on a queue of a different queue family
+// than the one that renders to them.
+//
+// Some devices have a queue family that can present, but not render, or the other way around. On
+// those devices, the images have to be rendered on one queue and presented on another. To make
+// sure that this works everywhere, this example always picks two different queue families if the
+// device has another queue family that can present, even if the graphics queue family could
+// present by itself.
+//
+// Each frame, the swapchain image is cleared on the graphics queue and then presented on the
+// present queue. When the present queue is different, vulkano makes the present operation wait
+// for the graphics queue with a semaphore. By default, the swapchain is created with exclusive
+// sharing, so vulkano also transfers the ownership of the image from the graphics queue family to
+// the present queue family before presenting it, and back again when the image is next acquired.
+// Run the example with `--concurrent` to create the swapchain with concurrent sharing instead, in
+// which case no ownership transfer is needed.
+
+use std::{env, error::Error, sync::Arc, time::Instant};
+use vulkano::{
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, ClearColorImageInfo, CommandBufferBeginInfo,
+        CommandBufferLevel, CommandBufferUsage, RecordingCommandBuffer,
+    },
+    device::{
+        physical::PhysicalDeviceType, Device, DeviceCreateInfo, DeviceExtensions, QueueCreateInfo,
+        QueueFlags,
+    },
+    format::ClearColorValue,
+    image::ImageUsage,
+    instance::{Instance, InstanceCreateFlags, InstanceCreateInfo},
+    swapchain::{
+        acquire_next_image, Surface, Swapchain, SwapchainCreateInfo, SwapchainPresentInfo,
+    },
+    sync::{self, GpuFuture, Sharing},
+    Validated, VulkanError, VulkanLibrary,
+};
+use winit::{
+    event::{Event, WindowEvent},
+    event_loop::{ControlFlow, EventLoop},
+    window::WindowBuilder,
+};
+
+fn main() -> Result<(), impl Error> {
+    let concurrent = env::args().any(|arg| arg == "--concurrent");
+
+    let event_loop = EventLoop::new().unwrap();
+
+    let library = VulkanLibrary::new().unwrap();
+    let required_extensions = Surface::required_extensions(&event_loop).unwrap();
+    let instance = Instance::new(
+        library,
+        InstanceCreateInfo {
+            flags: InstanceCreateFlags::ENUMERATE_PORTABILITY,
+            enabled_extensions: required_extensions,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let window = Arc::new(WindowBuilder::new().build(&event_loop).unwrap());
+    let surface = Surface::from_window(instance.clone(), window.clone()).unwrap();
+
+    let device_extensions = DeviceExtensions {
+        khr_swapchain: true,
+        ..DeviceExtensions::empty()
+    };
+    let (physical_device, graphics_family_index, present_family_index) = instance
+        .enumerate_physical_devices()
+        .unwrap()
+        .filter(|p| p.supported_extensions().contains(&device_extensions))
+        .filter_map(|p| {
+            let queue_family_properties = p.queue_family_properties();
+            let graphics_family_index = queue_family_properties
+                .iter()
+                .position(|q| q.queue_flags.intersects(QueueFlags::GRAPHICS))?
+                as u32;
+            let present_family_indices: Vec<u32> = (0..queue_family_properties.len() as u32)
+                .filter(|&i| p.surface_support(i, &surface).unwrap_or(false))
+                .collect();
+
+            // Prefer a present queue family that is different from the graphics queue family, so
+            // that the presentation always goes through a queue family ownership transfer when
+            // the device allows it.
+            let present_family_index = present_family_indices
+                .iter()
+                .copied()
+                .find(|&i| i != graphics_family_index)
+                .or_else(|| present_family_indices.first().copied())?;
+
+            Some((p, graphics_family_index, present_family_index))
+        })
+        .min_by_key(|(p, graphics_family_index, present_family_index)| {
+            (
+                // Prefer devices where the two queue families can be different.
+                graphics_family_index == present_family_index,
+                match p.properties().device_type {
+                    PhysicalDeviceType::DiscreteGpu => 0,
+                    PhysicalDeviceType::IntegratedGpu => 1,
+                    PhysicalDeviceType::VirtualGpu => 2,
+                    PhysicalDeviceType::Cpu => 3,
+                    PhysicalDeviceType::Other => 4,
+                    _ => 5,
+                },
+            )
+        })
+        .expect("no suitable physical device found");
+
+    println!(
+        "Using device: {} (type: {:?})",
+        physical_device.properties().device_name,
+        physical_device.properties().device_type,
+    );
+
+    let separate_families = graphics_family_index != present_family_index;
+
+    if separate_families {
+        println!(
+            "Rendering on queue family {graphics_family_index}, presenting on queue family \
+            {present_family_index}",
+        );
+    } else {
+        println!(
+            "No other queue family can present, so rendering and presenting on queue family \
+            {graphics_family_index}",
+        );
+    }
+
+    let queue_create_infos = if separate_families {
+        vec![
+            QueueCreateInfo {
+                queue_family_index: graphics_family_index,
+                ..Default::default()
+            },
+            QueueCreateInfo {
+                queue_family_index: present_family_index,
+                ..Default::default()
+            },
+        ]
+    } else {
+        // Use two queues of the same family if possible, so that the semaphore between them is
+        // still exercised.
+        let queue_count = physical_device.queue_family_properties()[graphics_family_index as usize]
+            .queue_count
+            .min(2);
+
+        vec![QueueCreateInfo {
+            queue_family_index: graphics_family_index,
+            queues: vec![0.5; queue_count as usize],
+            ..Default::default()
+        }]
+    };
+
+    let (device, mut queues) = Device::new(
+        physical_device,
+        DeviceCreateInfo {
+            enabled_extensions: device_extensions,
+            queue_create_infos,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let graphics_queue = queues.next().unwrap();
+    let present_queue = queues.next().unwrap_or_else(|| graphics_queue.clone());
+
+    let (mut swapchain, mut images) = {
+        let surface_capabilities = device
+            .physical_device()
+            .surface_capabilities(&surface, Default::default())
+            .unwrap();
+        let (image_format, _) = device
+            .physical_device()
+            .surface_formats(&surface, Default::default())
+            .unwrap()[0];
+
+        // With exclusive sharing, only one queue family may access an image at a time, so its
+        // ownership is transferred to the present queue family before it is presented, and back
+        // to the graphics queue family when it is acquired again. With concurrent sharing, both
+        // queue families may access the images without a transfer.
+        let image_sharing = if concurrent && separate_families {
+            println!("Using concurrent sharing");
+
+            Sharing::Concurrent(
+                [graphics_family_index, present_family_index]
+                    .into_iter()
+                    .collect(),
+            )
+        } else {
+            println!("Using exclusive sharing");
+
+            Sharing::Exclusive
+        };
+
+        Swapchain::new(
+            device.clone(),
+            surface,
+            SwapchainCreateInfo {
+                min_image_count: surface_capabilities.min_image_count.max(2),
+                image_format,
+                image_extent: window.inner_size().into(),
+                // The images are only cleared, so they only need to be a transfer destination.
+                image_usage: ImageUsage::TRANSFER_DST,
+                image_sharing,
+                composite_alpha: surface_capabilities
+                    .supported_composite_alpha
+                    .into_iter()
+                    .next()
+                    .unwrap(),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    };
+
+    let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+        device.clone(),
+        Default::default(),
+    ));
+
+    let start_time = Instant::now();
+    let mut recreate_swapchain = false;
+    let mut previous_frame_end = Some(sync::now(device.clone()).boxed());
+
+    event_loop.run(move |event, elwt| {
+        elwt.set_control_flow(ControlFlow::Poll);
+
+        match event {
+            Event::WindowEvent {
+                event: WindowEvent::CloseRequested,
+                ..
+            } => {
+                elwt.exit();
+            }
+            Event::WindowEvent {
+                event: WindowEvent::Resized(_),
+                ..
+            } => {
+                recreate_swapchain = true;
+            }
+            Event::WindowEvent {
+                event: WindowEvent::RedrawRequested,
+                ..
+            } => {
+                let image_extent: [u32; 2] = window.inner_size().into();
+
+                if image_extent.contains(&0) {
+                    return;
+                }
+
+                previous_frame_end.as_mut().unwrap().cleanup_finished();
+
+                if recreate_swapchain {
+                    let (new_swapchain, new_images) = swapchain
+                        .recreate(SwapchainCreateInfo {
+                            image_extent,
+                            ..swapchain.create_info()
+                        })
+                        .expect("failed to recreate swapchain");
+
+                    swapchain = new_swapchain;
+                    images = new_images;
+                    recreate_swapchain = false;
+                }
+
+                let (image_index, suboptimal, acquire_future) =
+                    match acquire_next_image(swapchain.clone(), None).map_err(Validated::unwrap) {
+                        Ok(r) => r,
+                        Err(VulkanError::OutOfDate) => {
+                            recreate_swapchain = true;
+                            return;
+                        }
+                        Err(e) => panic!("failed to acquire next image: {e}"),
+                    };
+
+                if suboptimal {
+                    recreate_swapchain = true;
+                }
+
+                let mut builder = RecordingCommandBuffer::new(
+                    command_buffer_allocator.clone(),
+                    graphics_queue.queue_family_index(),
+                    CommandBufferLevel::Primary,
+                    CommandBufferBeginInfo {
+                        usage: CommandBufferUsage::OneTimeSubmit,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+
+                builder
+                    .clear_color_image(ClearColorImageInfo {
+                        clear_value: ClearColorValue::Float(clear_color(start_time)),
+                        ..ClearColorImageInfo::image(images[image_index as usize].clone())
+                    })
+                    .unwrap();
+
+                let command_buffer = builder.end().unwrap();
+
+                // The command buffer is executed on the graphics queue, and the image is presented
+                // on the present queue. Vulkano inserts the semaphore and the ownership transfer
+                // between the two.
+                let future = previous_frame_end
+                    .take()
+                    .unwrap()
+                    .join(acquire_future)
+                    .then_execute(graphics_queue.clone(), command_buffer)
+                    .unwrap()
+                    .then_swapchain_present(
+                        present_queue.clone(),
+                        SwapchainPresentInfo::swapchain_image_index(swapchain.clone(), image_index),
+                    )
+                    .then_signal_fence_and_flush();
+
+                match future.map_err(Validated::unwrap) {
+                    Ok(future) => {
+                        previous_frame_end = Some(future.boxed());
+                    }
+                    Err(VulkanError::OutOfDate) => {
+                        recreate_swapchain = true;
+                        previous_frame_end = Some(sync::now(device.clone()).boxed());
+                    }
+                    Err(e) => {
+                        panic!("failed to flush future: {e}");
+                    }
+                }
+            }
+            Event::AboutToWait => window.request_redraw(),
+            _ => (),
+        }
+    })
+}
+
+/// Returns a color that slowly cycles through hues, so that it is easy to see that new frames are
+/// being presented.
+fn clear_color(start_time: Instant) -> [f32; 4] {
+    let time = start_time.elapsed().as_secs_f32();
+    let channel = |offset: f32| 0.5 + 0.5 * (time + offset).sin();
+
+    [channel(0.0), channel(2.0), channel(4.0), 1.0]
+}
@@ -11,8 +11,21 @@
 // ```
 //
 // Vulkano uses shaderc to build your shaders internally.
-
-use std::{error::Error, fs::File, io::Read, path::Path, sync::Arc};
+//
+// While the example is running, the `.spv` files are watched for changes. When one of them is
+// rebuilt, the graphics pipeline is recreated with the new shaders using
+// `GraphicsPipeline::recreate_with_stages`, which reuses all other parameters of the existing
+// pipeline. If the new shaders can't be loaded or are not compatible with the pipeline, the error
+// is printed and the previous pipeline keeps being used.
+
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
 use vulkano::{
     buffer::{Buffer, BufferContents, BufferCreateInfo, BufferUsage},
     command_buffer::{
@@ -40,7 +53,7 @@ use vulkano::{
         DynamicState, GraphicsPipeline, PipelineLayout, PipelineShaderStageCreateInfo,
     },
     render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass, Subpass},
-    shader::{ShaderModule, ShaderModuleCreateInfo},
+    shader::{EntryPoint, ShaderModule, ShaderModuleCreateInfo},
     swapchain::{
         acquire_next_image, Surface, Swapchain, SwapchainCreateInfo, SwapchainPresentInfo,
     },
@@ -166,26 +179,11 @@ fn main() -> Result<(), impl Error> {
     )
     .unwrap();
 
-    let graphics_pipeline = {
-        let vs = {
-            let code = read_spirv_words_from_file("vert.spv");
-
-            // Create a ShaderModule on a device the same Shader::load does it.
-            // NOTE: You will have to verify correctness of the data by yourself!
-            let module = unsafe {
-                ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(&code)).unwrap()
-            };
-            module.entry_point("main").unwrap()
-        };
-
-        let fs = {
-            let code = read_spirv_words_from_file("frag.spv");
+    let shader_paths = [shader_path("vert.spv"), shader_path("frag.spv")];
+    let mut shaders_modified = last_modified(&shader_paths);
 
-            let module = unsafe {
-                ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(&code)).unwrap()
-            };
-            module.entry_point("main").unwrap()
-        };
+    let mut graphics_pipeline = {
+        let [vs, fs] = load_shaders(&device, &shader_paths).unwrap();
 
         let vertex_input_state = Vertex::per_vertex().definition(&vs).unwrap();
         let stages = [
@@ -315,6 +313,30 @@ fn main() -> Result<(), impl Error> {
 
                 previous_frame_end.as_mut().unwrap().cleanup_finished();
 
+                // Reload the shaders if any of the files changed since they were last loaded.
+                let modified = last_modified(&shader_paths);
+
+                if modified != shaders_modified {
+                    shaders_modified = modified;
+
+                    match load_shaders(&device, &shader_paths) {
+                        Ok(entry_points) => {
+                            let stages = entry_points.map(PipelineShaderStageCreateInfo::new);
+
+                            // The old pipeline may still be in use by a previous frame, but the
+                            // command buffer of that frame keeps it alive until it's finished.
+                            match graphics_pipeline.recreate_with_stages(None, &stages) {
+                                Ok(pipeline) => {
+                                    println!("Reloaded shaders");
+                                    graphics_pipeline = pipeline;
+                                }
+                                Err(e) => println!("failed to recreate pipeline: {e}"),
+                            }
+                        }
+                        Err(e) => println!("failed to load shaders: {e}"),
+                    }
+                }
+
                 if recreate_swapchain {
                     let (new_swapchain, new_images) = swapchain
                         .recreate(SwapchainCreateInfo {
@@ -439,15 +461,48 @@ fn window_size_dependent_setup(
         .collect::<Vec<_>>()
 }
 
-fn read_spirv_words_from_file(path: impl AsRef<Path>) -> Vec<u32> {
+fn shader_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(name)
+}
+
+/// Returns the most recent modification time of the files.
+fn last_modified(paths: &[PathBuf]) -> Option<SystemTime> {
+    paths
+        .iter()
+        .filter_map(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .max()
+}
+
+fn load_shaders(
+    device: &Arc<Device>,
+    paths: &[PathBuf; 2],
+) -> Result<[EntryPoint; 2], Box<dyn Error>> {
+    let load = |path: &PathBuf| -> Result<EntryPoint, Box<dyn Error>> {
+        let code = read_spirv_words_from_file(path)?;
+
+        // Create a ShaderModule on a device the same Shader::load does it.
+        // NOTE: You will have to verify correctness of the data by yourself!
+        let module =
+            unsafe { ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(&code)) }
+                .map_err(|e| format!("file `{}`: {}", path.display(), e))?;
+
+        module
+            .entry_point("main")
+            .ok_or_else(|| format!("file `{}`: no `main` entry point", path.display()).into())
+    };
+
+    Ok([load(&paths[0])?, load(&paths[1])?])
+}
+
+fn read_spirv_words_from_file(path: &Path) -> Result<Vec<u32>, Box<dyn Error>> {
     // Read the file.
-    let path = path.as_ref();
     let mut bytes = vec![];
-    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(path);
-    let mut file = File::open(&path).unwrap();
-    file.read_to_end(&mut bytes).unwrap();
+    let mut file = File::open(path)?;
+    file.read_to_end(&mut bytes)?;
+
+    let words = vulkano::shader::spirv::bytes_to_words(&bytes)
+        .map_err(|err| format!("file `{}`: {}", path.display(), err))?
+        .into_owned();
 
-    vulkano::shader::spirv::bytes_to_words(&bytes)
-        .unwrap_or_else(|err| panic!("file `{}`: {}", path.display(), err))
-        .into_owned()
+    Ok(words)
 }
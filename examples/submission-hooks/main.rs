@@ -0,0 +1,214 @@
+// This example demonstrates how to observe the work that is submitted to the queues of a device,
+// for example to show it in a profiler.
+//
+// Vulkano calls the hooks that are set with `Device::set_submission_hooks` whenever command
+// buffers are submitted, swapchain images are presented, or fences are waited on. Here, the hooks
+// print what happens and how long the CPU spent waiting for each fence.
+//
+// When the example is run with `--features tracy`, the hooks also emit zones and messages for the
+// Tracy profiler, which can be connected to the example while it runs. RenderDoc captures can be
+// triggered the same way, by calling its in-application API from `on_present`.
+
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+use vulkano::{
+    buffer::{Buffer, BufferCreateInfo, BufferUsage},
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, CommandBufferBeginInfo, CommandBufferLevel,
+        CommandBufferUsage, RecordingCommandBuffer,
+    },
+    device::{
+        physical::PhysicalDeviceType, Device, DeviceCreateInfo, Queue, QueueCreateInfo, QueueFlags,
+        SubmissionHooks,
+    },
+    instance::{Instance, InstanceCreateFlags, InstanceCreateInfo},
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
+    swapchain::Swapchain,
+    sync::{self, fence::Fence, GpuFuture},
+    VulkanLibrary,
+};
+
+fn main() {
+    let library = VulkanLibrary::new().unwrap();
+    let instance = Instance::new(
+        library,
+        InstanceCreateInfo {
+            flags: InstanceCreateFlags::ENUMERATE_PORTABILITY,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let (physical_device, queue_family_index) = instance
+        .enumerate_physical_devices()
+        .unwrap()
+        .filter_map(|p| {
+            p.queue_family_properties()
+                .iter()
+                .position(|q| q.queue_flags.intersects(QueueFlags::TRANSFER))
+                .map(|i| (p, i as u32))
+        })
+        .min_by_key(|(p, _)| match p.properties().device_type {
+            PhysicalDeviceType::DiscreteGpu => 0,
+            PhysicalDeviceType::IntegratedGpu => 1,
+            PhysicalDeviceType::VirtualGpu => 2,
+            PhysicalDeviceType::Cpu => 3,
+            PhysicalDeviceType::Other => 4,
+            _ => 5,
+        })
+        .unwrap();
+
+    println!(
+        "Using device: {} (type: {:?})",
+        physical_device.properties().device_name,
+        physical_device.properties().device_type,
+    );
+
+    let (device, mut queues) = Device::new(
+        physical_device,
+        DeviceCreateInfo {
+            queue_create_infos: vec![QueueCreateInfo {
+                queue_family_index,
+                ..Default::default()
+            }],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let queue = queues.next().unwrap();
+
+    // The hooks can be set at any time, and apply to all the queues and fences of the device.
+    device.set_submission_hooks(Some(Arc::new(Hooks::new())));
+
+    let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+    let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+        device.clone(),
+        Default::default(),
+    ));
+
+    let buffer = Buffer::new_slice::<u32>(
+        memory_allocator,
+        BufferCreateInfo {
+            usage: BufferUsage::TRANSFER_DST,
+            ..Default::default()
+        },
+        AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
+            ..Default::default()
+        },
+        1 << 20,
+    )
+    .unwrap();
+
+    // Stand-ins for the frames of an application. Each one submits a command buffer and waits for
+    // it to finish executing, which calls `on_submit`, `on_submit_end`, `on_fence_wait_start` and
+    // `on_fence_wait_end` in that order.
+    for frame in 0..3 {
+        let mut builder = RecordingCommandBuffer::new(
+            command_buffer_allocator.clone(),
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        builder.fill_buffer(buffer.clone(), frame).unwrap();
+        let command_buffer = builder.end().unwrap();
+
+        sync::now(device.clone())
+            .then_execute(queue.clone(), command_buffer)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+    }
+
+    // Remove the hooks, so that nothing is reported anymore.
+    device.set_submission_hooks(None);
+
+    println!("Success");
+}
+
+struct Hooks {
+    submissions: AtomicU64,
+    #[cfg(feature = "tracy")]
+    client: tracy_client::Client,
+}
+
+thread_local! {
+    // A fence wait starts and ends on the same thread, so the state of the waits that are in
+    // progress can be kept there. This is also required for Tracy zones, which can't be sent to
+    // other threads.
+    static FENCE_WAITS: RefCell<Vec<FenceWait>> = const { RefCell::new(Vec::new()) };
+}
+
+struct FenceWait {
+    start: Instant,
+    #[cfg(feature = "tracy")]
+    _span: tracy_client::Span,
+}
+
+impl Hooks {
+    fn new() -> Self {
+        Hooks {
+            submissions: AtomicU64::new(0),
+            #[cfg(feature = "tracy")]
+            client: tracy_client::Client::start(),
+        }
+    }
+}
+
+impl SubmissionHooks for Hooks {
+    fn on_submit(&self, queue: &Queue, command_buffer_count: usize) {
+        let index = self.submissions.fetch_add(1, Ordering::Relaxed);
+        let message = format!(
+            "submission {index}: {command_buffer_count} command buffer(s) to queue {}.{}",
+            queue.queue_family_index(),
+            queue.queue_index(),
+        );
+        println!("{message}");
+
+        #[cfg(feature = "tracy")]
+        self.client.message(&message, 0);
+    }
+
+    fn on_present(&self, _queue: &Queue, _swapchain: &Swapchain, image_index: u32) {
+        println!("presenting swapchain image {image_index}");
+
+        // Each present ends a frame.
+        #[cfg(feature = "tracy")]
+        self.client.frame_mark();
+    }
+
+    fn on_fence_wait_start(&self, _fence: &Fence) {
+        let wait = FenceWait {
+            start: Instant::now(),
+            #[cfg(feature = "tracy")]
+            _span: self.client.span_alloc(
+                Some("fence wait"),
+                "on_fence_wait_start",
+                file!(),
+                line!(),
+                0,
+            ),
+        };
+
+        FENCE_WAITS.with(|waits| waits.borrow_mut().push(wait));
+    }
+
+    fn on_fence_wait_end(&self, _fence: &Fence) {
+        // Dropping the wait also ends its Tracy zone.
+        if let Some(wait) = FENCE_WAITS.with(|waits| waits.borrow_mut().pop()) {
+            println!("waited {:?} for a fence", wait.start.elapsed());
+        }
+    }
+}
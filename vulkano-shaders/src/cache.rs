@@ -0,0 +1,278 @@
+//! On-disk cache for the SPIR-V that shaderc produces.
+//!
+//! Proc macros are expanded again whenever anything in the crate that invokes them changes, so
+//! without a cache every shader would be compiled again after every edit. Entries are keyed by a
+//! hash of everything that influences the compilation, including the shaderc library itself. Each
+//! entry also records the files that were included and a hash of their contents, so that changing
+//! an included file invalidates it.
+
+use crate::{codegen, MacroInput};
+use shaderc::{Compiler, ShaderKind};
+use std::{
+    env, fs,
+    hash::{Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+    process,
+    sync::OnceLock,
+};
+
+/// The environment variable that overrides where the cache is stored.
+const CACHE_DIR_ENV: &str = "VULKANO_SHADERS_CACHE_DIR";
+
+/// Must be incremented whenever the format of the entries changes.
+const FORMAT_VERSION: u32 = 1;
+
+/// Compiles a GLSL shader, or loads the result of a previous compilation from the cache.
+///
+/// Returns the SPIR-V words and the paths of the files that were included.
+pub(super) fn compile(
+    input: &MacroInput,
+    path: Option<String>,
+    base_path: &Path,
+    code: &str,
+    shader_kind: ShaderKind,
+) -> Result<(Vec<u32>, Vec<String>), String> {
+    let Some(cache_dir) = cache_dir() else {
+        let (artifact, includes) = codegen::compile(input, path, base_path, code, shader_kind)?;
+
+        return Ok((artifact.as_binary().to_owned(), includes));
+    };
+
+    let key = key(input, path.as_deref(), base_path, code, shader_kind);
+    let entry_path = cache_dir.join(format!("{:016x}.spv", key));
+
+    if let Some(entry) = load(&entry_path) {
+        return Ok(entry);
+    }
+
+    let (artifact, includes) = codegen::compile(input, path, base_path, code, shader_kind)?;
+    let words = artifact.as_binary().to_owned();
+
+    // Failing to store the entry only means that the shader is compiled again next time.
+    let _ = store(&cache_dir, &entry_path, &words, &includes);
+
+    Ok((words, includes))
+}
+
+/// Returns the directory to store the cache in, or `None` if caching is disabled.
+///
+/// This is the value of `VULKANO_SHADERS_CACHE_DIR` if it is set, where an empty value disables
+/// the cache. Otherwise, a subdirectory of `OUT_DIR` is used if the crate invoking the macro has a
+/// build script.
+fn cache_dir() -> Option<PathBuf> {
+    match env::var_os(CACHE_DIR_ENV) {
+        Some(dir) if dir.is_empty() => None,
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => env::var_os("OUT_DIR").map(|dir| Path::new(&dir).join("vulkano-shaders-cache")),
+    }
+}
+
+fn key(
+    input: &MacroInput,
+    path: Option<&str>,
+    base_path: &Path,
+    code: &str,
+    shader_kind: ShaderKind,
+) -> u64 {
+    let mut hasher = StableHasher::new();
+
+    FORMAT_VERSION.hash(&mut hasher);
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    shaderc::get_spirv_version().hash(&mut hasher);
+    compiler_fingerprint().hash(&mut hasher);
+    cfg!(feature = "shaderc-debug").hash(&mut hasher);
+
+    format!("{:?}", shader_kind).hash(&mut hasher);
+    path.hash(&mut hasher);
+    base_path.hash(&mut hasher);
+    code.hash(&mut hasher);
+    input.include_directories.hash(&mut hasher);
+    input.macro_defines.hash(&mut hasher);
+    format!("{:?}", input.vulkan_version).hash(&mut hasher);
+    format!("{:?}", input.spirv_version).hash(&mut hasher);
+    input.enable_debug_printf.hash(&mut hasher);
+
+    hasher.finish()
+}
+
+/// Returns a hash that identifies the shaderc library that the macro is linked against.
+///
+/// shaderc has no way to query its own version, so this hashes the SPIR-V that it produces for a
+/// fixed shader instead. The header of the SPIR-V contains the version of glslang, and changes to
+/// the code generation show up in the instructions.
+fn compiler_fingerprint() -> Option<u64> {
+    const PROBE: &str = "
+        #version 450
+
+        layout(location = 0) out vec4 f_color;
+
+        void main() {
+            f_color = vec4(gl_FragCoord.xy, 0.0, 1.0);
+        }
+    ";
+
+    static FINGERPRINT: OnceLock<Option<u64>> = OnceLock::new();
+
+    *FINGERPRINT.get_or_init(|| {
+        let artifact = Compiler::new()?
+            .compile_into_spirv(PROBE, ShaderKind::Fragment, "probe.glsl", "main", None)
+            .ok()?;
+        let mut hasher = StableHasher::new();
+        hasher.write(artifact.as_binary_u8());
+
+        Some(hasher.finish())
+    })
+}
+
+fn hash_file(path: &str) -> Option<u64> {
+    let mut hasher = StableHasher::new();
+    hasher.write(&fs::read(path).ok()?);
+
+    Some(hasher.finish())
+}
+
+fn load(entry_path: &Path) -> Option<(Vec<u32>, Vec<String>)> {
+    let bytes = fs::read(entry_path).ok()?;
+    let mut reader = Reader(&bytes);
+
+    let include_count = reader.read_u32()?;
+    let mut includes = Vec::with_capacity(include_count as usize);
+
+    for _ in 0..include_count {
+        let len = reader.read_u32()?;
+        let include = String::from_utf8(reader.read_bytes(len as usize)?.to_owned()).ok()?;
+
+        if hash_file(&include)? != reader.read_u64()? {
+            return None;
+        }
+
+        includes.push(include);
+    }
+
+    let word_count = reader.read_u32()?;
+    let words = reader
+        .read_bytes(word_count as usize * 4)?
+        .chunks_exact(4)
+        .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+        .collect();
+
+    reader.0.is_empty().then_some((words, includes))
+}
+
+fn store(
+    cache_dir: &Path,
+    entry_path: &Path,
+    words: &[u32],
+    includes: &[String],
+) -> io::Result<()> {
+    let mut bytes = Vec::new();
+
+    bytes.extend((includes.len() as u32).to_le_bytes());
+
+    for include in includes {
+        let hash = hash_file(include).ok_or(io::ErrorKind::NotFound)?;
+        bytes.extend((include.len() as u32).to_le_bytes());
+        bytes.extend(include.as_bytes());
+        bytes.extend(hash.to_le_bytes());
+    }
+
+    bytes.extend((words.len() as u32).to_le_bytes());
+    bytes.extend(words.iter().flat_map(|word| word.to_le_bytes()));
+
+    fs::create_dir_all(cache_dir)?;
+
+    // Several invocations of the macro can run at the same time, so write to a temporary file
+    // first to make sure that no one reads a partially written entry.
+    let temp_path = entry_path.with_extension(format!("{}.tmp", process::id()));
+    fs::write(&temp_path, bytes)?;
+
+    if let Err(err) = fs::rename(&temp_path, entry_path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn read_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if len > self.0.len() {
+            return None;
+        }
+
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+
+        Some(bytes)
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap()))
+    }
+
+    fn read_u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.read_bytes(8)?.try_into().unwrap()))
+    }
+}
+
+/// FNV-1a, which unlike the hasher of the standard library produces the same result in every
+/// build.
+struct StableHasher(u64);
+
+impl StableHasher {
+    fn new() -> Self {
+        StableHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_and_load() {
+        let dir = env::temp_dir().join(format!("vulkano-shaders-cache-test-{}", process::id()));
+        let include = dir.join("include.glsl");
+        let include_name = include.to_str().unwrap().to_owned();
+        let entry_path = dir.join("entry.spv");
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&include, "#define FOO 1").unwrap();
+
+        let words = [0x0723_0203, 1, 2, 3];
+        store(&dir, &entry_path, &words, &[include_name.clone()]).unwrap();
+        assert_eq!(
+            load(&entry_path),
+            Some((words.to_vec(), vec![include_name])),
+        );
+
+        // Changing an included file invalidates the entry.
+        fs::write(&include, "#define FOO 2").unwrap();
+        assert_eq!(load(&entry_path), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn compiler_fingerprint_is_stable() {
+        let fingerprint = compiler_fingerprint();
+        assert!(fingerprint.is_some());
+        assert_eq!(compiler_fingerprint(), fingerprint);
+    }
+}
@@ -8,13 +8,14 @@ use quote::{format_ident, quote};
 pub use shaderc::{CompilationArtifact, IncludeType, ResolvedInclude, ShaderKind};
 use shaderc::{CompileOptions, Compiler, EnvVersion, TargetEnv};
 use std::{
+    borrow::Cow,
     cell::RefCell,
     fs,
     iter::Iterator,
     path::{Path, PathBuf},
 };
 use syn::{Error, LitStr};
-use vulkano::shader::spirv::Spirv;
+use vulkano::shader::spirv::{Instruction, Spirv};
 
 pub struct Shader {
     pub source: LitStr,
@@ -186,9 +187,15 @@ pub(super) fn compile(
     #[cfg(feature = "shaderc-debug")]
     compile_options.set_generate_debug_info();
 
+    let code = if input.enable_debug_printf {
+        Cow::Owned(enable_debug_printf(code))
+    } else {
+        Cow::Borrowed(code)
+    };
+
     let content = compiler
         .compile_into_spirv(
-            code,
+            &code,
             shader_kind,
             root_source_path,
             "main",
@@ -201,6 +208,37 @@ pub(super) fn compile(
     Ok((content, includes.into_inner()))
 }
 
+/// Inserts a directive enabling the `GL_EXT_debug_printf` extension after the `#version`
+/// directive, followed by a `#line` directive so that errors still refer to the original lines.
+fn enable_debug_printf(code: &str) -> String {
+    const EXTENSION_DIRECTIVE: &str = "#extension GL_EXT_debug_printf : enable\n";
+
+    let Some(version_line) = code
+        .split_inclusive('\n')
+        .position(|line| line.trim_start().starts_with("#version"))
+    else {
+        return format!("{EXTENSION_DIRECTIVE}#line 1\n{code}");
+    };
+
+    let mut output = String::with_capacity(code.len() + EXTENSION_DIRECTIVE.len() + 16);
+
+    for (index, line) in code.split_inclusive('\n').enumerate() {
+        output.push_str(line);
+
+        if index == version_line {
+            if !line.ends_with('\n') {
+                output.push('\n');
+            }
+
+            output.push_str(EXTENSION_DIRECTIVE);
+            // `#line` sets the number of the line that follows it.
+            output.push_str(&format!("#line {}\n", index + 2));
+        }
+    }
+
+    output
+}
+
 pub(super) fn reflect(
     input: &MacroInput,
     source: LitStr,
@@ -255,11 +293,90 @@ pub(super) fn reflect(
         }
     };
 
+    let entry_points = write_entry_points(&shader);
+    let shader_code = quote! {
+        #shader_code
+        #entry_points
+    };
+
     let structs = structs::write_structs(input, &shader, type_registry)?;
 
     Ok((shader_code, structs))
 }
 
+/// If the shader has more than one entry point, generates an `entry_points` module (or
+/// `entry_points_{name}` if the shader is named) with a submodule for each of them.
+fn write_entry_points(shader: &Shader) -> TokenStream {
+    let entry_points: Vec<_> = shader
+        .spirv
+        .entry_points()
+        .iter()
+        .filter_map(|instruction| match *instruction {
+            Instruction::EntryPoint {
+                execution_model,
+                ref name,
+                ..
+            } => Some((name.as_str(), execution_model)),
+            _ => None,
+        })
+        .collect();
+
+    if entry_points.len() <= 1 {
+        return TokenStream::new();
+    }
+
+    let modules = entry_points.iter().map(|&(name, execution_model)| {
+        let execution_model_name = format!("{:?}", execution_model);
+
+        // The same name can be used by entry points with different execution models.
+        let mut module_name = if entry_points.iter().filter(|(n, _)| *n == name).count() > 1 {
+            format!("{}_{}", name, execution_model_name).to_snake_case()
+        } else {
+            name.to_snake_case()
+        };
+
+        if !module_name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            module_name.insert(0, '_');
+        }
+
+        let module_name = format_ident!("{}", module_name);
+        let execution_model = format_ident!("{}", execution_model_name);
+
+        quote! {
+            pub mod #module_name {
+                /// The name of the entry point.
+                pub const NAME: &str = #name;
+
+                /// The execution model of the entry point.
+                pub const EXECUTION_MODEL: ::vulkano::shader::spirv::ExecutionModel =
+                    ::vulkano::shader::spirv::ExecutionModel::#execution_model;
+
+                /// Returns the entry point in `module`, which must have been loaded from this
+                /// shader.
+                #[inline]
+                pub fn entry_point(
+                    module: &::std::sync::Arc<::vulkano::shader::ShaderModule>,
+                ) -> ::std::option::Option<::vulkano::shader::EntryPoint> {
+                    module.entry_point_with_execution(NAME, EXECUTION_MODEL)
+                }
+            }
+        }
+    });
+
+    let entry_points_name = if shader.name.is_empty() {
+        format_ident!("entry_points")
+    } else {
+        format_ident!("entry_points_{}", shader.name.to_snake_case())
+    };
+
+    quote! {
+        /// The entry points of the shader.
+        pub mod #entry_points_name {
+            #( #modules )*
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -492,6 +609,44 @@ mod tests {
         .expect("setting shader macros did not work");
     }
 
+    #[test]
+    fn debug_printf() {
+        let prints = r#"
+            #version 450
+            layout(local_size_x = 64) in;
+            void main() {
+                debugPrintfEXT("thread %u", gl_GlobalInvocationID.x);
+            }
+        "#;
+
+        let without_extension = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            prints,
+            ShaderKind::Compute,
+        );
+        assert!(without_extension.is_err());
+
+        compile(
+            &MacroInput {
+                enable_debug_printf: true,
+                ..MacroInput::empty()
+            },
+            None,
+            Path::new(""),
+            prints,
+            ShaderKind::Compute,
+        )
+        .expect("enabling debug printf did not work");
+
+        assert_eq!(
+            enable_debug_printf("// comment\n#version 450\nvoid main() {}\n"),
+            "// comment\n#version 450\n#extension GL_EXT_debug_printf : enable\n#line 3\n\
+            void main() {}\n",
+        );
+    }
+
     /// `entrypoint1.frag.glsl`:
     /// ```glsl
     /// #version 450
@@ -636,6 +791,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn reflect_multiple_entrypoints() {
+        let insts = spv_to_words(include_bytes!("../tests/multiple_entrypoints.spv"));
+
+        let mut type_registry = TypeRegistry::default();
+        let (shader_code, _structs) = reflect(
+            &MacroInput::empty(),
+            LitStr::new("../tests/multiple_entrypoints.spv", Span::call_site()),
+            String::new(),
+            &insts,
+            Vec::new(),
+            &mut type_registry,
+        )
+        .expect("reflecting spv failed");
+
+        let file: File = syn::parse2(shader_code).unwrap();
+        let entry_points = file
+            .items
+            .iter()
+            .find_map(|item| match item {
+                Item::Mod(m) if m.ident == "entry_points" => Some(m),
+                _ => None,
+            })
+            .expect("no `entry_points` module");
+        let modules: Vec<_> = entry_points
+            .content
+            .as_ref()
+            .unwrap()
+            .1
+            .iter()
+            .filter_map(|item| match item {
+                Item::Mod(m) => Some(m.ident.to_string()),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(modules, ["entrypoint1", "entrypoint2"]);
+    }
+
     fn descriptor_calculation_with_multiple_functions_shader() -> (CompilationArtifact, Vec<String>)
     {
         compile(
@@ -745,4 +939,132 @@ mod tests {
             quote!({pub data: f32,}).to_string()
         );
     }
+
+    #[test]
+    fn reflect_push_constants() {
+        let (artifact, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(push_constant) uniform Constants {
+                    vec4 color;
+                    float scale;
+                } constants;
+
+                layout(location = 0) out vec4 f_color;
+
+                void main() {
+                    f_color = constants.color * constants.scale;
+                }
+            "#,
+            ShaderKind::Fragment,
+        )
+        .unwrap();
+
+        for push_constants_alias in [false, true] {
+            let mut type_registry = TypeRegistry::default();
+            let (_shader_code, structs) = reflect(
+                &MacroInput {
+                    push_constants_alias,
+                    ..MacroInput::empty()
+                },
+                LitStr::new("reflect_push_constants", Span::call_site()),
+                String::new(),
+                artifact.as_binary(),
+                Vec::new(),
+                &mut type_registry,
+            )
+            .expect("reflecting spv failed");
+
+            let file: File = syn::parse2(structs).unwrap();
+            let alias = file.items.iter().find_map(|item| match item {
+                Item::Type(t) if t.ident == "PushConstants" => Some(t),
+                _ => None,
+            });
+            let has_size_assert = file
+                .items
+                .iter()
+                .any(|item| matches!(item, Item::Const(c) if c.ident == "_"));
+
+            if push_constants_alias {
+                assert_eq!(
+                    alias.unwrap().ty.to_token_stream().to_string(),
+                    quote!(Constants).to_string()
+                );
+            } else {
+                assert!(alias.is_none());
+            }
+
+            assert!(has_size_assert);
+
+            let impls = type_registry.write_push_constants_impls().to_string();
+            assert!(impls.contains("for Constants"));
+            assert!(impls.contains("ShaderStages :: FRAGMENT"));
+        }
+    }
+
+    #[test]
+    fn reflect_buffer_reference() {
+        let (artifact, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+                #extension GL_EXT_buffer_reference : require
+
+                layout(buffer_reference, std430) buffer Node {
+                    Node next;
+                    uint value;
+                };
+
+                layout(push_constant) uniform Constants {
+                    Node head;
+                } constants;
+
+                void main() {
+                    constants.head.next.value = constants.head.value;
+                }
+            "#,
+            ShaderKind::Compute,
+        )
+        .unwrap();
+
+        let mut type_registry = TypeRegistry::default();
+        let (_shader_code, structs) = reflect(
+            &MacroInput::empty(),
+            LitStr::new("reflect_buffer_reference", Span::call_site()),
+            String::new(),
+            artifact.as_binary(),
+            Vec::new(),
+            &mut type_registry,
+        )
+        .expect("reflecting spv failed");
+
+        let file: File = syn::parse2(structs).unwrap();
+        let fields = |ident: &str| {
+            file.items
+                .iter()
+                .find_map(|item| match item {
+                    Item::Struct(s) if s.ident == ident => Some(s),
+                    _ => None,
+                })
+                .unwrap()
+                .fields
+                .to_token_stream()
+                .to_string()
+        };
+
+        assert_eq!(
+            fields("Constants"),
+            quote!({pub head: ::vulkano::DeviceAddressT<Node>,}).to_string(),
+        );
+        assert_eq!(
+            fields("Node"),
+            quote!({pub next: ::vulkano::DeviceAddressT<Node>, pub value: u32,}).to_string(),
+        );
+    }
 }
@@ -39,10 +39,21 @@
 //!   appropriate features enabled.
 //! - If the `shaders` option is used, then instead of one `load` constructor, there is one for
 //!   each shader. They are named based on the provided names, `load_first`, `load_second` etc.
+//! - If the shader data contains more than one entry point, an `entry_points` module
+//!   (`entry_points_first` etc. if the `shaders` option is used) with a submodule for each entry
+//!   point, named after it. Each submodule contains the `NAME` and `EXECUTION_MODEL` of the entry
+//!   point, and an `entry_point` function that looks it up in a loaded [`ShaderModule`]. If the
+//!   same name is used by multiple entry points, the execution model is appended to the name of
+//!   the submodule, for example `main_vertex` and `main_fragment`.
 //! - A Rust struct translated from each struct contained in the shader data. By default, each
 //!   structure has a `Clone` and a `Copy` implementation. This behavior could be customized
 //!   through the `custom_derives` macro option (see below for details). Each struct also has an
 //!   implementation of [`BufferContents`], so that it can be read from/written to a buffer.
+//! - For the struct of the push constant block, an implementation of [`PushConstants`] containing
+//!   the stages that use the block. The size of the struct is checked against the reflected block
+//!   at compile time. The struct can be passed to
+//!   [`RecordingCommandBuffer::push_constants_typed`], which checks that the pipeline layout
+//!   provides the block to those stages. See also the `push_constants_alias` option below.
 //!
 //! All of these generated items will be accessed through the module where the macro was invoked.
 //! If you wanted to store the `ShaderModule` in a struct of your own, you could do something like
@@ -172,6 +183,14 @@
 //! The generated code must be supported by the device at runtime. If not, then an error will be
 //! returned when calling `load`.
 //!
+//! ## `enable_debug_printf: true`
+//!
+//! Enables the `GL_EXT_debug_printf` extension in GLSL sources, by inserting the corresponding
+//! `#extension` directive after the `#version` directive. This allows the shader to call
+//! `debugPrintfEXT`. The output of these calls is reported by the validation layer when its
+//! [`ValidationFeatureEnable::DebugPrintf`] feature is enabled, and can be collected with a
+//! [`DebugPrintfCollector`].
+//!
 //! ## `generate_structs: true`
 //!
 //! Generate rust structs that represent the structs contained in the shader. They all implement
@@ -179,6 +198,12 @@
 //! the layout of the struct manually. However, some use-cases, such as Rust-GPU, may not have any
 //! use for such structs, and may choose to disable them.
 //!
+//! ## `push_constants_alias: true`
+//!
+//! Generates a `PushConstants` type alias for the struct of the push constant block
+//! (`FirstPushConstants` etc. if the `shaders` option is used). This is disabled by default,
+//! because the alias can collide with other items in the module where the macro is invoked.
+//!
 //! ## `custom_derives: [Clone, Default, PartialEq, ...]`
 //!
 //! Extends the list of derive macros that are added to the `derive` attribute of Rust structs that
@@ -208,6 +233,21 @@
 //!
 //! The crate fails to compile but prints the generated Rust code to stdout.
 //!
+//! # Caching
+//!
+//! Compiling GLSL can take a while, and the macro is expanded again whenever any file of the crate
+//! that invokes it changes. The macro therefore caches the SPIR-V that it compiles, so that a
+//! shader is only compiled again when its source, any file it includes, any of the options above
+//! or the version of vulkano-shaders or shaderc changes. The generated Rust code refers to the
+//! source and all included files with `include_bytes!`, so that changing them causes the macro to
+//! be expanded again.
+//!
+//! The cache is stored in the directory given by the `VULKANO_SHADERS_CACHE_DIR` environment
+//! variable. If it isn't set, the cache is stored in `OUT_DIR` if the crate has a build script,
+//! and is disabled otherwise. Setting the variable to an empty value disables the cache. The
+//! variable can be set for all builds of a project in the `[env]` section of
+//! `.cargo/config.toml`.
+//!
 //! # Cargo features
 //!
 //! | Feature                     | Description                                             |
@@ -222,6 +262,10 @@
 //! [`set_target_env`]: shaderc::CompileOptions::set_target_env
 //! [`set_target_spirv`]: shaderc::CompileOptions::set_target_spirv
 //! [`BufferContents`]: vulkano::buffer::BufferContents
+//! [`ValidationFeatureEnable::DebugPrintf`]: vulkano::instance::debug::ValidationFeatureEnable::DebugPrintf
+//! [`DebugPrintfCollector`]: vulkano::instance::debug::DebugPrintfCollector
+//! [`PushConstants`]: vulkano::pipeline::layout::PushConstants
+//! [`RecordingCommandBuffer::push_constants_typed`]: vulkano::command_buffer::RecordingCommandBuffer::push_constants_typed
 
 #![doc(html_logo_url = "https://raw.githubusercontent.com/vulkano-rs/vulkano/master/logo.png")]
 #![recursion_limit = "1024"]
@@ -242,6 +286,7 @@ use syn::{
     parse_macro_input, parse_quote, Error, Ident, LitBool, LitStr, Path as SynPath, Token,
 };
 
+mod cache;
 mod codegen;
 mod rust_gpu;
 mod structs;
@@ -289,7 +334,7 @@ fn shader_inner(mut input: MacroInput) -> Result<TokenStream> {
     for (name, (shader_kind, source_kind)) in shaders {
         let (code, types) = match source_kind {
             SourceKind::Src(source) => {
-                let (artifact, includes) = codegen::compile(
+                let (words, includes) = cache::compile(
                     &input,
                     None,
                     root_path,
@@ -298,9 +343,7 @@ fn shader_inner(mut input: MacroInput) -> Result<TokenStream> {
                 )
                 .map_err(|err| Error::new_spanned(&source, err))?;
 
-                let words = artifact.as_binary();
-
-                codegen::reflect(&input, source, name, words, includes, &mut type_registry)?
+                codegen::reflect(&input, source, name, &words, includes, &mut type_registry)?
             }
             SourceKind::Path(path) => {
                 let full_path = root_path.join(path.value());
@@ -316,7 +359,7 @@ fn shader_inner(mut input: MacroInput) -> Result<TokenStream> {
                 let source_code = fs::read_to_string(&full_path)
                     .or_else(|err| bail!(path, "failed to read source `{full_path:?}`: {err}"))?;
 
-                let (artifact, mut includes) = codegen::compile(
+                let (words, mut includes) = cache::compile(
                     &input,
                     Some(path.value()),
                     root_path,
@@ -325,11 +368,9 @@ fn shader_inner(mut input: MacroInput) -> Result<TokenStream> {
                 )
                 .map_err(|err| Error::new_spanned(&path, err))?;
 
-                let words = artifact.as_binary();
-
                 includes.push(full_path.into_os_string().into_string().unwrap());
 
-                codegen::reflect(&input, path, name, words, includes, &mut type_registry)?
+                codegen::reflect(&input, path, name, &words, includes, &mut type_registry)?
             }
             SourceKind::Bytes(path) => {
                 let full_path = root_path.join(path.value());
@@ -356,9 +397,12 @@ fn shader_inner(mut input: MacroInput) -> Result<TokenStream> {
         types_code.push(types);
     }
 
+    let push_constants_code = type_registry.write_push_constants_impls();
+
     let result = quote! {
         #( #shaders_code )*
         #( #types_code )*
+        #push_constants_code
     };
 
     if input.dump.value {
@@ -382,7 +426,9 @@ struct MacroInput {
     shaders: HashMap<String, (Option<ShaderKind>, SourceKind)>,
     spirv_version: Option<SpirvVersion>,
     vulkan_version: Option<EnvVersion>,
+    enable_debug_printf: bool,
     generate_structs: bool,
+    push_constants_alias: bool,
     custom_derives: Vec<SynPath>,
     linalg_type: LinAlgType,
     dump: LitBool,
@@ -398,7 +444,9 @@ impl MacroInput {
             shaders: HashMap::default(),
             vulkan_version: None,
             spirv_version: None,
+            enable_debug_printf: false,
             generate_structs: true,
+            push_constants_alias: false,
             custom_derives: Vec::new(),
             linalg_type: LinAlgType::default(),
             dump: LitBool::new(false, Span::call_site()),
@@ -416,7 +464,9 @@ impl Parse for MacroInput {
         let mut shaders = HashMap::default();
         let mut vulkan_version = None;
         let mut spirv_version = None;
+        let mut enable_debug_printf = None;
         let mut generate_structs = None;
+        let mut push_constants_alias = None;
         let mut custom_derives = None;
         let mut linalg_type = None;
         let mut dump = None;
@@ -654,6 +704,13 @@ impl Parse for MacroInput {
                         ),
                     });
                 }
+                "enable_debug_printf" => {
+                    let lit = input.parse::<LitBool>()?;
+                    if enable_debug_printf.is_some() {
+                        bail!(lit, "field `enable_debug_printf` is already defined");
+                    }
+                    enable_debug_printf = Some(lit.value);
+                }
                 "generate_structs" => {
                     let lit = input.parse::<LitBool>()?;
                     if generate_structs.is_some() {
@@ -661,6 +718,13 @@ impl Parse for MacroInput {
                     }
                     generate_structs = Some(lit.value);
                 }
+                "push_constants_alias" => {
+                    let lit = input.parse::<LitBool>()?;
+                    if push_constants_alias.is_some() {
+                        bail!(lit, "field `push_constants_alias` is already defined");
+                    }
+                    push_constants_alias = Some(lit.value);
+                }
                 "custom_derives" => {
                     let in_brackets;
                     bracketed!(in_brackets in input);
@@ -714,7 +778,8 @@ impl Parse for MacroInput {
                 field => bail!(
                     field_ident,
                     "expected `bytes`, `src`, `path`, `ty`, `shaders`, `define`, `include`, \
-                    `vulkan_version`, `spirv_version`, `generate_structs`, `custom_derives`, \
+                    `vulkan_version`, `spirv_version`, `enable_debug_printf`, \
+                    `generate_structs`, `push_constants_alias`, `custom_derives`, \
                     `linalg_type` or `dump` as a field, found `{field}`",
                 ),
             }
@@ -757,7 +822,9 @@ impl Parse for MacroInput {
                 .collect(),
             vulkan_version,
             spirv_version,
+            enable_debug_printf: enable_debug_printf.unwrap_or(false),
             generate_structs: generate_structs.unwrap_or(true),
+            push_constants_alias: push_constants_alias.unwrap_or(false),
             custom_derives: custom_derives.unwrap_or_else(|| {
                 vec![
                     parse_quote! { ::std::clone::Clone },
@@ -1,17 +1,61 @@
 use crate::{bail, codegen::Shader, LinAlgType, MacroInput};
 use ahash::HashMap;
+use heck::{ToShoutySnakeCase, ToUpperCamelCase};
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote, ToTokens, TokenStreamExt};
 use std::{cmp::Ordering, num::NonZeroUsize};
 use syn::{Error, Ident, Result};
-use vulkano::shader::spirv::{Decoration, Id, Instruction};
+use vulkano::{
+    pipeline::layout::PushConstantRange,
+    shader::{
+        reflect,
+        spirv::{Decoration, Id, Instruction, StorageClass},
+        ShaderStages,
+    },
+};
 
 #[derive(Default)]
 pub struct TypeRegistry {
     registered_structs: HashMap<Ident, RegisteredType>,
+    push_constant_structs: Vec<(Ident, ShaderStages)>,
 }
 
 impl TypeRegistry {
+    /// Writes an implementation of `PushConstants` for each struct that is used as a push
+    /// constant block by any of the shaders.
+    pub(super) fn write_push_constants_impls(&self) -> TokenStream {
+        let impls = self
+            .push_constant_structs
+            .iter()
+            .map(|(struct_ident, stages)| {
+                let stages = stages
+                    .into_iter()
+                    .map(|stage| format_ident!("{}", format!("{stage:?}").to_shouty_snake_case()));
+
+                quote! {
+                    impl ::vulkano::pipeline::layout::PushConstants for #struct_ident {
+                        const STAGES: ::vulkano::shader::ShaderStages =
+                            ::vulkano::shader::ShaderStages::empty()
+                                #( .union(::vulkano::shader::ShaderStages::#stages) )*;
+                    }
+                }
+            });
+
+        quote! { #( #impls )* }
+    }
+
+    fn register_push_constant_struct(&mut self, ty: &TypeStruct, stages: ShaderStages) {
+        if let Some((_, registered_stages)) = self
+            .push_constant_structs
+            .iter_mut()
+            .find(|(struct_ident, _)| *struct_ident == ty.ident)
+        {
+            *registered_stages |= stages;
+        } else {
+            self.push_constant_structs.push((ty.ident.clone(), stages));
+        }
+    }
+
     fn register_struct(&mut self, shader: &Shader, ty: &TypeStruct) -> Result<bool> {
         // Checking with registry if this struct is already registered by another shader, and if
         // their signatures match.
@@ -95,6 +139,7 @@ pub(super) fn write_structs(
     }
 
     let mut structs = TokenStream::new();
+    let push_constant_block = push_constant_block(shader);
 
     for (struct_id, member_type_ids) in shader
         .spirv
@@ -111,6 +156,15 @@ pub(super) fn write_structs(
     {
         let struct_ty = TypeStruct::new(shader, struct_id, member_type_ids)?;
 
+        if let Some((_, range)) = push_constant_block.filter(|&(id, _)| id == struct_id) {
+            type_registry.register_push_constant_struct(&struct_ty, range.stages);
+            structs.extend(write_push_constants_size_check(&struct_ty, range));
+
+            if input.push_constants_alias {
+                structs.extend(write_push_constants_alias(shader, &struct_ty));
+            }
+        }
+
         // Register the type if needed.
         if !type_registry.register_struct(shader, &struct_ty)? {
             continue;
@@ -134,6 +188,90 @@ pub(super) fn write_structs(
     Ok(structs)
 }
 
+/// Returns the struct type of the push constant block of the shader, together with the range
+/// and stages of all entry points that use it.
+///
+/// Returns `None` if there is no push constant block, or if the shader declares more than one, in
+/// which case it's not possible to tell which block an entry point uses.
+fn push_constant_block(shader: &Shader) -> Option<(Id, PushConstantRange)> {
+    let mut pointer_type_ids = shader
+        .spirv
+        .global_variables()
+        .iter()
+        .filter_map(|instruction| match *instruction {
+            Instruction::Variable {
+                result_type_id,
+                storage_class: StorageClass::PushConstant,
+                ..
+            } => Some(result_type_id),
+            _ => None,
+        });
+
+    let pointer_type_id = pointer_type_ids.next()?;
+
+    if pointer_type_ids.next().is_some() {
+        return None;
+    }
+
+    let struct_id = match *shader.spirv.id(pointer_type_id).instruction() {
+        Instruction::TypePointer { ty, .. } => ty,
+        _ => return None,
+    };
+
+    let range = reflect::entry_points(&shader.spirv)
+        .filter_map(|(_, info)| info.push_constant_requirements)
+        .reduce(|a, b| PushConstantRange {
+            stages: a.stages | b.stages,
+            offset: a.offset.min(b.offset),
+            size: (a.offset + a.size).max(b.offset + b.size) - a.offset.min(b.offset),
+        })?;
+
+    Some((struct_id, range))
+}
+
+/// Writes a `PushConstants` type alias for the push constant block of the shader.
+fn write_push_constants_alias(shader: &Shader, struct_ty: &TypeStruct) -> TokenStream {
+    let struct_ident = &struct_ty.ident;
+    let alias_ident = if shader.name.is_empty() {
+        format_ident!("PushConstants")
+    } else {
+        format_ident!("{}PushConstants", shader.name.to_upper_camel_case())
+    };
+
+    if *struct_ident == alias_ident {
+        return TokenStream::new();
+    }
+
+    quote! {
+        /// The push constant block of the shader.
+        pub type #alias_ident = #struct_ident;
+    }
+}
+
+/// Asserts at compile time that the size of the generated struct matches the reflected push
+/// constant block.
+fn write_push_constants_size_check(
+    struct_ty: &TypeStruct,
+    range: PushConstantRange,
+) -> TokenStream {
+    let struct_ident = &struct_ty.ident;
+    let block_size = align_up(
+        (range.offset + range.size) as usize,
+        struct_ty.scalar_alignment(),
+    );
+    let message = format!(
+        "the size of `{struct_ident}` does not match the size of the push constant block \
+        reflected from the shader ({block_size} bytes)",
+    );
+
+    quote! {
+        const _: () = ::std::assert!(
+            ::std::mem::size_of::<#struct_ident>() == #block_size,
+            #message,
+        );
+    }
+}
+
 fn has_defined_layout(shader: &Shader, struct_id: Id) -> bool {
     for member_info in shader.spirv.id(struct_id).members() {
         let mut offset_found = false;
@@ -235,7 +373,9 @@ impl Type {
             Instruction::TypeFloat { width, .. } => {
                 Type::Scalar(TypeScalar::Float(TypeFloat::new(shader, width)?))
             }
-            Instruction::TypePointer { .. } => Type::Pointer(TypePointer::new(shader)?),
+            Instruction::TypePointer {
+                storage_class, ty, ..
+            } => Type::Pointer(TypePointer::new(shader, storage_class, ty)?),
             Instruction::TypeVector {
                 component_type,
                 component_count,
@@ -440,11 +580,25 @@ impl ToTokens for TypeFloat {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-struct TypePointer;
+struct TypePointer {
+    // The struct that the pointer points to, if it is a `buffer_reference` block for which a Rust
+    // struct is generated.
+    pointee: Option<Ident>,
+}
 
 impl TypePointer {
-    fn new(_shader: &Shader) -> Result<Self> {
-        Ok(TypePointer)
+    fn new(shader: &Shader, storage_class: StorageClass, pointee_id: Id) -> Result<Self> {
+        // The pointee isn't translated with `Type::new`, because a `buffer_reference` block can
+        // contain a pointer to itself.
+        let pointee = (storage_class == StorageClass::PhysicalStorageBuffer
+            && matches!(
+                shader.spirv.id(pointee_id).instruction(),
+                Instruction::TypeStruct { .. }
+            )
+            && has_defined_layout(shader, pointee_id))
+        .then(|| struct_ident(shader, pointee_id));
+
+        Ok(TypePointer { pointee })
     }
 
     fn size(&self) -> usize {
@@ -458,7 +612,10 @@ impl TypePointer {
 
 impl ToTokens for TypePointer {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        tokens.extend(quote! { ::vulkano::DeviceAddress });
+        match &self.pointee {
+            Some(pointee) => tokens.extend(quote! { ::vulkano::DeviceAddressT<#pointee> }),
+            None => tokens.extend(quote! { ::vulkano::DeviceAddress }),
+        }
     }
 }
 
@@ -684,27 +841,7 @@ struct TypeStruct {
 impl TypeStruct {
     fn new(shader: &Shader, struct_id: Id, member_type_ids: &[Id]) -> Result<Self> {
         let id_info = shader.spirv.id(struct_id);
-
-        let ident = id_info
-            .names()
-            .iter()
-            .find_map(|instruction| match instruction {
-                Instruction::Name { name, .. } => {
-                    // Replace chars that could potentially cause the ident to be invalid with "_".
-                    // For example, Rust-GPU names structs by their fully qualified rust name (e.g.
-                    // "foo::bar::MyStruct") in which the ":" is an invalid character for idents.
-                    let mut name =
-                        name.replace(|c: char| !(c.is_ascii_alphanumeric() || c == '_'), "_");
-                    if name.starts_with(|c: char| !c.is_ascii_alphabetic()) {
-                        name.insert(0, '_');
-                    }
-
-                    // Worst case: invalid idents will get the UnnamedX name below
-                    syn::parse_str(&name).ok()
-                }
-                _ => None,
-            })
-            .unwrap_or_else(|| format_ident!("Unnamed{}", struct_id.as_raw()));
+        let ident = struct_ident(shader, struct_id);
 
         let mut members = Vec::<Member>::with_capacity(member_type_ids.len());
 
@@ -878,6 +1015,32 @@ impl TypeStruct {
     }
 }
 
+/// Returns the identifier of the Rust struct that is generated for the struct with the given ID.
+fn struct_ident(shader: &Shader, struct_id: Id) -> Ident {
+    shader
+        .spirv
+        .id(struct_id)
+        .names()
+        .iter()
+        .find_map(|instruction| match instruction {
+            Instruction::Name { name, .. } => {
+                // Replace chars that could potentially cause the ident to be invalid with "_".
+                // For example, Rust-GPU names structs by their fully qualified rust name (e.g.
+                // "foo::bar::MyStruct") in which the ":" is an invalid character for idents.
+                let mut name =
+                    name.replace(|c: char| !(c.is_ascii_alphanumeric() || c == '_'), "_");
+                if name.starts_with(|c: char| !c.is_ascii_alphabetic()) {
+                    name.insert(0, '_');
+                }
+
+                // Worst case: invalid idents will get the UnnamedX name below
+                syn::parse_str(&name).ok()
+            }
+            _ => None,
+        })
+        .unwrap_or_else(|| format_ident!("Unnamed{}", struct_id.as_raw()))
+}
+
 #[derive(Clone, Debug)]
 struct Member {
     ident: Ident,
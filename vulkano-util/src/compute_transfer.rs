@@ -0,0 +1,745 @@
+//! Buffer fills and copies implemented with compute shaders.
+//!
+//! [`fill_buffer`] requires the offset and size of the filled range to be multiples of 4 bytes,
+//! and both [`fill_buffer`] and [`copy_buffer`] require the buffers to have been created with
+//! transfer usages. The [`ComputeTransfer`] helper records fills and copies as compute dispatches
+//! instead, which work for arbitrary byte offsets and sizes. They can be recorded on any queue
+//! that supports compute operations.
+//!
+//! There are two ways to use it:
+//!
+//! - Call [`ComputeTransfer::fill_buffer_compute`] and [`ComputeTransfer::copy_buffer_compute`]
+//!   directly.
+//! - Opt in on a command buffer builder with [`set_transfer_fallback`]. [`fill_buffer`] and
+//!   [`copy_buffer`] then use the compute path for buffers that lack the transfer usages.
+//!
+//! Note that queue families that support compute operations implicitly support transfer
+//! operations as well, so [`copy_buffer`] can be used on them too. The compute-based copy is
+//! mainly useful for copying between ranges whose offsets differ modulo 4, where it avoids
+//! having to split the copy around a fill of the unaligned bytes.
+//!
+//! This module is only available when the `compute_transfer` feature is enabled.
+//!
+//! [`fill_buffer`]: vulkano::command_buffer::RecordingCommandBuffer::fill_buffer
+//! [`copy_buffer`]: vulkano::command_buffer::RecordingCommandBuffer::copy_buffer
+//! [`set_transfer_fallback`]: vulkano::command_buffer::RecordingCommandBuffer::set_transfer_fallback
+
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::{Arc, OnceLock},
+};
+use vulkano::{
+    buffer::{
+        AllocateBufferError, Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer,
+    },
+    command_buffer::{CopyBufferInfo, RecordingCommandBuffer, TransferFallback},
+    descriptor_set::{allocator::DescriptorSetAllocator, DescriptorSet, WriteDescriptorSet},
+    device::{Device, DeviceOwned},
+    memory::allocator::{AllocationCreateInfo, MemoryAllocator, MemoryTypeFilter},
+    pipeline::{
+        compute::ComputePipelineCreateInfo,
+        layout::{IntoPipelineLayoutCreateInfoError, PipelineDescriptorSetLayoutCreateInfo},
+        ComputePipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+        PipelineShaderStageCreateInfo,
+    },
+    shader::ShaderModule,
+    DeviceSize, Validated, ValidationError, VulkanError,
+};
+
+/// The local size of the utility shaders, which must match the `local_size_x` declared in them.
+const LOCAL_SIZE: u32 = 64;
+
+/// Records buffer fills and copies as compute dispatches.
+///
+/// The compute pipelines are created the first time that they are needed, and are then reused
+/// for every later command, so a single `ComputeTransfer` should be created for each device.
+///
+/// The buffers must have been created with the [`STORAGE_BUFFER`] usage. The commands bind a
+/// compute pipeline, descriptor set and push constants, so any compute state that was bound on
+/// the command buffer beforehand must be bound again afterwards.
+///
+/// Because the shaders access memory in units of 4 bytes, the last `buffer.size() % 4` bytes of
+/// a buffer can't be accessed by them. If a command includes some of those bytes, they are
+/// copied with [`copy_buffer`] after the dispatch instead, so in that case the buffer must also
+/// have been created with the [`TRANSFER_SRC`] or [`TRANSFER_DST`] usage. For fills, the bytes are
+/// copied from a small staging buffer that is allocated from the memory allocator.
+///
+/// [`STORAGE_BUFFER`]: BufferUsage::STORAGE_BUFFER
+/// [`TRANSFER_SRC`]: BufferUsage::TRANSFER_SRC
+/// [`TRANSFER_DST`]: BufferUsage::TRANSFER_DST
+/// [`copy_buffer`]: RecordingCommandBuffer::copy_buffer
+pub struct ComputeTransfer {
+    device: Arc<Device>,
+    memory_allocator: Arc<dyn MemoryAllocator>,
+    descriptor_set_allocator: Arc<dyn DescriptorSetAllocator>,
+    fill_pipeline: OnceLock<Arc<ComputePipeline>>,
+    copy_pipeline: OnceLock<Arc<ComputePipeline>>,
+}
+
+impl ComputeTransfer {
+    /// Creates a new `ComputeTransfer`. Staging buffers for the last bytes of a buffer are
+    /// allocated from `memory_allocator`, and descriptor sets for the commands are allocated from
+    /// `descriptor_set_allocator`.
+    #[inline]
+    pub fn new(
+        device: Arc<Device>,
+        memory_allocator: Arc<dyn MemoryAllocator>,
+        descriptor_set_allocator: Arc<dyn DescriptorSetAllocator>,
+    ) -> Self {
+        ComputeTransfer {
+            device,
+            memory_allocator,
+            descriptor_set_allocator,
+            fill_pipeline: OnceLock::new(),
+            copy_pipeline: OnceLock::new(),
+        }
+    }
+
+    /// Fills `dst_buffer` with repeated copies of `data`, like
+    /// [`fill_buffer`](RecordingCommandBuffer::fill_buffer), but with no requirements on the
+    /// offset and size of `dst_buffer`.
+    ///
+    /// The bytes of `data` are written in little-endian order, starting from the first byte of
+    /// `dst_buffer`.
+    pub fn fill_buffer_compute(
+        &self,
+        builder: &mut RecordingCommandBuffer,
+        dst_buffer: Subbuffer<[u8]>,
+        data: u32,
+    ) -> Result<(), ComputeTransferError> {
+        assert_eq!(self.device, *dst_buffer.device());
+
+        let size = dst_buffer.size();
+        let compute_size = accessible_size(&dst_buffer, size);
+
+        if compute_size != 0 {
+            let (dst_binding, dst_offset) =
+                self.binding_range(&dst_buffer, compute_size, "dst_buffer")?;
+            let pipeline =
+                Self::pipeline(&self.fill_pipeline, || fill_cs::load(self.device.clone()))?;
+            let set = DescriptorSet::new(
+                self.descriptor_set_allocator.clone(),
+                pipeline.layout().set_layouts()[0].clone(),
+                [WriteDescriptorSet::buffer(0, dst_binding)],
+                [],
+            )?;
+            let push_constants = fill_cs::PushConstants {
+                dst_offset,
+                size: compute_size as u32,
+                data,
+            };
+
+            self.dispatch(
+                builder,
+                &pipeline,
+                set,
+                push_constants,
+                dst_offset,
+                compute_size,
+            )?;
+        }
+
+        if compute_size < size {
+            // The byte at offset `i` of `dst_buffer` gets byte `i % 4` of `data`.
+            let data = data.to_le_bytes();
+            let staging_buffer = Buffer::from_iter(
+                self.memory_allocator.clone(),
+                BufferCreateInfo {
+                    usage: BufferUsage::TRANSFER_SRC,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                        | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                    ..Default::default()
+                },
+                (compute_size as usize..size as usize).map(|i| data[i % 4]),
+            )
+            .map_err(ComputeTransferError::StagingBuffer)?;
+
+            builder.copy_buffer(CopyBufferInfo::buffers(
+                staging_buffer,
+                dst_buffer.slice(compute_size..),
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Copies bytes from `src_buffer` to `dst_buffer`, like
+    /// [`copy_buffer`](RecordingCommandBuffer::copy_buffer), but with no requirements on the
+    /// alignment of the offsets of the buffers relative to each other.
+    ///
+    /// The number of bytes copied is the minimum of the sizes of the two buffers. The copied
+    /// ranges must not overlap in memory.
+    pub fn copy_buffer_compute(
+        &self,
+        builder: &mut RecordingCommandBuffer,
+        src_buffer: Subbuffer<[u8]>,
+        dst_buffer: Subbuffer<[u8]>,
+    ) -> Result<(), ComputeTransferError> {
+        assert_eq!(self.device, *src_buffer.device());
+        assert_eq!(self.device, *dst_buffer.device());
+
+        let size = src_buffer.size().min(dst_buffer.size());
+
+        if size == 0 {
+            return Ok(());
+        }
+
+        if src_buffer.buffer() == dst_buffer.buffer()
+            && src_buffer.offset() < dst_buffer.offset() + size
+            && dst_buffer.offset() < src_buffer.offset() + size
+        {
+            return Err(Box::new(ValidationError {
+                problem: "`src_buffer` and `dst_buffer` overlap in memory".into(),
+                ..Default::default()
+            })
+            .into());
+        }
+
+        let compute_size =
+            accessible_size(&src_buffer, size).min(accessible_size(&dst_buffer, size));
+
+        if compute_size != 0 {
+            let (src_binding, src_offset) =
+                self.binding_range(&src_buffer, compute_size, "src_buffer")?;
+            let (dst_binding, dst_offset) =
+                self.binding_range(&dst_buffer, compute_size, "dst_buffer")?;
+            let pipeline =
+                Self::pipeline(&self.copy_pipeline, || copy_cs::load(self.device.clone()))?;
+            let set = DescriptorSet::new(
+                self.descriptor_set_allocator.clone(),
+                pipeline.layout().set_layouts()[0].clone(),
+                [
+                    WriteDescriptorSet::buffer(0, src_binding),
+                    WriteDescriptorSet::buffer(1, dst_binding),
+                ],
+                [],
+            )?;
+            let push_constants = copy_cs::PushConstants {
+                src_offset,
+                dst_offset,
+                size: compute_size as u32,
+            };
+
+            self.dispatch(
+                builder,
+                &pipeline,
+                set,
+                push_constants,
+                dst_offset,
+                compute_size,
+            )?;
+        }
+
+        if compute_size < size {
+            builder.copy_buffer(CopyBufferInfo::buffers(
+                src_buffer.slice(compute_size..size),
+                dst_buffer.slice(compute_size..size),
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the range of the underlying buffer to bind for the first `size` bytes of
+    /// `buffer`, and the offset of `buffer` within that range.
+    ///
+    /// The range starts at an offset that satisfies the `min_storage_buffer_offset_alignment`
+    /// limit, and ends on a multiple of 4 bytes. `size` must not be greater than
+    /// `accessible_size(buffer, size)`.
+    fn binding_range(
+        &self,
+        buffer: &Subbuffer<[u8]>,
+        size: DeviceSize,
+        context: &'static str,
+    ) -> Result<(Subbuffer<[u8]>, u32), Box<ValidationError>> {
+        let properties = self.device.physical_device().properties();
+        let alignment = properties
+            .min_storage_buffer_offset_alignment
+            .as_devicesize()
+            .max(4);
+        let start = buffer.offset() - buffer.offset() % alignment;
+        let end = (buffer.offset() + size + 3) & !3;
+        debug_assert!(end <= buffer.buffer().size());
+
+        if end - start > properties.max_storage_buffer_range as DeviceSize {
+            return Err(Box::new(ValidationError {
+                context: context.into(),
+                problem: "is larger than the `max_storage_buffer_range` limit allows".into(),
+                ..Default::default()
+            }));
+        }
+
+        let binding = Subbuffer::from(buffer.buffer().clone()).slice(start..end);
+
+        Ok((binding, (buffer.offset() - start) as u32))
+    }
+
+    /// Returns the pipeline stored in `cell`, creating it first if needed.
+    fn pipeline(
+        cell: &OnceLock<Arc<ComputePipeline>>,
+        load: impl FnOnce() -> Result<Arc<ShaderModule>, Validated<VulkanError>>,
+    ) -> Result<Arc<ComputePipeline>, ComputeTransferError> {
+        if let Some(pipeline) = cell.get() {
+            return Ok(pipeline.clone());
+        }
+
+        let module = load()?;
+        let device = module.device().clone();
+        let stage = PipelineShaderStageCreateInfo::new(module.entry_point("main").unwrap());
+        let layout = PipelineLayout::new(
+            device.clone(),
+            PipelineDescriptorSetLayoutCreateInfo::from_stages([&stage])
+                .into_pipeline_layout_create_info(device.clone())
+                .map_err(ComputeTransferError::PipelineLayout)?,
+        )?;
+        let pipeline = ComputePipeline::new(
+            device,
+            None,
+            ComputePipelineCreateInfo::stage_layout(stage, layout),
+        )?;
+
+        // If another thread got there first, use its pipeline and drop ours.
+        Ok(cell.get_or_init(|| pipeline).clone())
+    }
+
+    /// Records the dispatch that processes the 4-byte words touched by `size` bytes starting at
+    /// `dst_offset`.
+    fn dispatch(
+        &self,
+        builder: &mut RecordingCommandBuffer,
+        pipeline: &Arc<ComputePipeline>,
+        set: Arc<DescriptorSet>,
+        push_constants: impl BufferContents,
+        dst_offset: u32,
+        size: DeviceSize,
+    ) -> Result<(), Validated<VulkanError>> {
+        let word_count = (dst_offset as DeviceSize + size + 3) / 4 - dst_offset as DeviceSize / 4;
+
+        // The shaders loop over the words, so the group count can be capped at the limit.
+        let max_group_count = self
+            .device
+            .physical_device()
+            .properties()
+            .max_compute_work_group_count[0];
+        let group_count = ((word_count + LOCAL_SIZE as DeviceSize - 1) / LOCAL_SIZE as DeviceSize)
+            .min(max_group_count as DeviceSize) as u32;
+
+        builder
+            .bind_pipeline_compute(pipeline.clone())?
+            .bind_descriptor_sets(
+                PipelineBindPoint::Compute,
+                pipeline.layout().clone(),
+                0,
+                set,
+            )?
+            .push_constants(pipeline.layout().clone(), 0, push_constants)?;
+
+        unsafe {
+            builder.dispatch([group_count, 1, 1])?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Records fills and copies for buffers that lack the transfer usages with compute dispatches.
+///
+/// [`TransferFallback`] can only return validation errors, so other errors are returned as a
+/// validation error that describes them. Call [`ComputeTransfer::fill_buffer_compute`] and
+/// [`ComputeTransfer::copy_buffer_compute`] directly to get a [`ComputeTransferError`] instead.
+impl TransferFallback for ComputeTransfer {
+    fn fill_buffer(
+        &self,
+        builder: &mut RecordingCommandBuffer,
+        dst_buffer: Subbuffer<[u8]>,
+        data: u32,
+    ) -> Result<(), Box<ValidationError>> {
+        self.fill_buffer_compute(builder, dst_buffer, data)
+            .map_err(ComputeTransferError::into_validation_error)
+    }
+
+    fn copy_buffer(
+        &self,
+        builder: &mut RecordingCommandBuffer,
+        src_buffer: Subbuffer<[u8]>,
+        dst_buffer: Subbuffer<[u8]>,
+    ) -> Result<(), Box<ValidationError>> {
+        self.copy_buffer_compute(builder, src_buffer, dst_buffer)
+            .map_err(ComputeTransferError::into_validation_error)
+    }
+}
+
+/// Returns how many of the first `size` bytes of `buffer` lie in whole 4-byte words of the
+/// underlying buffer, and can therefore be accessed by the shaders.
+fn accessible_size(buffer: &Subbuffer<[u8]>, size: DeviceSize) -> DeviceSize {
+    let accessible_end = buffer.buffer().size() & !3;
+
+    accessible_end.saturating_sub(buffer.offset()).min(size)
+}
+
+/// Error that can happen when recording a command with [`ComputeTransfer`].
+#[derive(Clone, Debug)]
+pub enum ComputeTransferError {
+    /// Creating the descriptor set layouts of a utility pipeline failed.
+    PipelineLayout(IntoPipelineLayoutCreateInfoError),
+
+    /// Allocating the staging buffer for the last bytes of a buffer failed.
+    StagingBuffer(Validated<AllocateBufferError>),
+
+    /// Creating a utility pipeline, allocating a descriptor set or recording a command failed.
+    Vulkan(Validated<VulkanError>),
+}
+
+impl ComputeTransferError {
+    fn into_validation_error(self) -> Box<ValidationError> {
+        match self {
+            Self::Vulkan(Validated::ValidationError(err))
+            | Self::StagingBuffer(Validated::ValidationError(err)) => err,
+            err => Box::new(ValidationError {
+                problem: match err.source() {
+                    Some(source) => format!("{}: {}", err, source).into(),
+                    None => err.to_string().into(),
+                },
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+impl Error for ComputeTransferError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::PipelineLayout(err) => Some(err),
+            Self::StagingBuffer(err) => Some(err),
+            Self::Vulkan(err) => Some(err),
+        }
+    }
+}
+
+impl Display for ComputeTransferError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::PipelineLayout(_) => {
+                write!(f, "creating the layout of a utility pipeline failed")
+            }
+            Self::StagingBuffer(_) => write!(f, "allocating a staging buffer failed"),
+            Self::Vulkan(_) => write!(f, "recording the command failed"),
+        }
+    }
+}
+
+impl From<Validated<VulkanError>> for ComputeTransferError {
+    fn from(err: Validated<VulkanError>) -> Self {
+        Self::Vulkan(err)
+    }
+}
+
+impl From<Box<ValidationError>> for ComputeTransferError {
+    fn from(err: Box<ValidationError>) -> Self {
+        Self::Vulkan(err.into())
+    }
+}
+
+mod fill_cs {
+    vulkano_shaders::shader! {
+        ty: "compute",
+        src: r"
+            #version 450
+
+            layout(local_size_x = 64, local_size_y = 1, local_size_z = 1) in;
+
+            layout(set = 0, binding = 0) buffer Dst {
+                uint dst[];
+            };
+
+            layout(push_constant) uniform PushConstants {
+                uint dst_offset;
+                uint size;
+                uint data;
+            };
+
+            void main() {
+                uint first_word = dst_offset / 4;
+                uint end_word = (dst_offset + size + 3) / 4;
+                uint stride = gl_NumWorkGroups.x * gl_WorkGroupSize.x;
+
+                for (uint word = first_word + gl_GlobalInvocationID.x; word < end_word; word += stride) {
+                    uint mask = 0;
+                    uint value = 0;
+
+                    for (uint i = 0; i < 4; i++) {
+                        uint byte_offset = word * 4 + i;
+
+                        if (byte_offset >= dst_offset && byte_offset < dst_offset + size) {
+                            uint data_byte = (byte_offset - dst_offset) % 4;
+                            mask |= 0xFFu << (i * 8);
+                            value |= ((data >> (data_byte * 8)) & 0xFFu) << (i * 8);
+                        }
+                    }
+
+                    if (mask == 0xFFFFFFFFu) {
+                        dst[word] = value;
+                    } else {
+                        // Leave the bytes outside the region untouched.
+                        dst[word] = (dst[word] & ~mask) | value;
+                    }
+                }
+            }
+        ",
+    }
+}
+
+mod copy_cs {
+    vulkano_shaders::shader! {
+        ty: "compute",
+        src: r"
+            #version 450
+
+            layout(local_size_x = 64, local_size_y = 1, local_size_z = 1) in;
+
+            layout(set = 0, binding = 0) readonly buffer Src {
+                uint src[];
+            };
+
+            layout(set = 0, binding = 1) buffer Dst {
+                uint dst[];
+            };
+
+            layout(push_constant) uniform PushConstants {
+                uint src_offset;
+                uint dst_offset;
+                uint size;
+            };
+
+            void main() {
+                uint first_word = dst_offset / 4;
+                uint end_word = (dst_offset + size + 3) / 4;
+                uint stride = gl_NumWorkGroups.x * gl_WorkGroupSize.x;
+
+                for (uint word = first_word + gl_GlobalInvocationID.x; word < end_word; word += stride) {
+                    uint mask = 0;
+                    uint value = 0;
+
+                    for (uint i = 0; i < 4; i++) {
+                        uint byte_offset = word * 4 + i;
+
+                        if (byte_offset >= dst_offset && byte_offset < dst_offset + size) {
+                            uint src_byte_offset = src_offset + (byte_offset - dst_offset);
+                            uint src_word = src[src_byte_offset / 4];
+                            mask |= 0xFFu << (i * 8);
+                            value |= ((src_word >> ((src_byte_offset % 4) * 8)) & 0xFFu) << (i * 8);
+                        }
+                    }
+
+                    if (mask == 0xFFFFFFFFu) {
+                        dst[word] = value;
+                    } else {
+                        // Leave the bytes outside the region untouched.
+                        dst[word] = (dst[word] & ~mask) | value;
+                    }
+                }
+            }
+        ",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vulkano::{
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, CommandBufferBeginInfo, CommandBufferLevel,
+        },
+        descriptor_set::allocator::StandardDescriptorSetAllocator,
+        device::{DeviceExtensions, DeviceFeatures, Queue},
+        memory::allocator::StandardMemoryAllocator,
+        sync::GpuFuture,
+        testing::test_device,
+    };
+
+    struct TestContext {
+        queue: Arc<Queue>,
+        memory_allocator: Arc<StandardMemoryAllocator>,
+        command_buffer_allocator: Arc<StandardCommandBufferAllocator>,
+        compute_transfer: Arc<ComputeTransfer>,
+    }
+
+    impl TestContext {
+        fn new() -> Option<Self> {
+            let (device, queue) =
+                test_device(&DeviceExtensions::empty(), &DeviceFeatures::empty())?;
+            let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+            let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+                device.clone(),
+                Default::default(),
+            ));
+            let descriptor_set_allocator = Arc::new(StandardDescriptorSetAllocator::new(
+                device.clone(),
+                Default::default(),
+            ));
+            let compute_transfer = Arc::new(ComputeTransfer::new(
+                device,
+                memory_allocator.clone(),
+                descriptor_set_allocator,
+            ));
+
+            Some(TestContext {
+                queue,
+                memory_allocator,
+                command_buffer_allocator,
+                compute_transfer,
+            })
+        }
+
+        /// Creates a host-visible buffer with the given contents.
+        fn buffer(
+            &self,
+            usage: BufferUsage,
+            data: impl IntoIterator<Item = u8>,
+        ) -> Subbuffer<[u8]> {
+            Buffer::from_iter(
+                self.memory_allocator.clone(),
+                BufferCreateInfo {
+                    usage,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                        | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                    ..Default::default()
+                },
+                data.into_iter().collect::<Vec<_>>(),
+            )
+            .unwrap()
+        }
+
+        fn builder(&self) -> RecordingCommandBuffer {
+            RecordingCommandBuffer::new(
+                self.command_buffer_allocator.clone(),
+                self.queue.queue_family_index(),
+                CommandBufferLevel::Primary,
+                CommandBufferBeginInfo::default(),
+            )
+            .unwrap()
+        }
+
+        fn execute(&self, builder: RecordingCommandBuffer) {
+            builder
+                .end()
+                .unwrap()
+                .execute(self.queue.clone())
+                .unwrap()
+                .then_signal_fence_and_flush()
+                .unwrap()
+                .wait(None)
+                .unwrap();
+        }
+    }
+
+    const USAGE: BufferUsage = BufferUsage::STORAGE_BUFFER
+        .union(BufferUsage::TRANSFER_SRC)
+        .union(BufferUsage::TRANSFER_DST);
+
+    #[test]
+    fn fill_buffer_tail_bytes() {
+        let Some(context) = TestContext::new() else {
+            return;
+        };
+
+        // 19 bytes, so the last 3 bytes don't form a whole word.
+        let buffer = context.buffer(USAGE, [0xAA; 19]);
+
+        let mut builder = context.builder();
+        context
+            .compute_transfer
+            .fill_buffer_compute(&mut builder, buffer.clone().slice(1..19), 0x04030201)
+            .unwrap();
+        context.execute(builder);
+
+        let expected: Vec<u8> = [0xAA]
+            .into_iter()
+            .chain([1, 2, 3, 4].into_iter().cycle().take(18))
+            .collect();
+        assert_eq!(*buffer.read().unwrap(), *expected);
+
+        // A fill that only covers the last bytes of the buffer.
+        let mut builder = context.builder();
+        context
+            .compute_transfer
+            .fill_buffer_compute(&mut builder, buffer.clone().slice(17..19), 0x0D0C0B0A)
+            .unwrap();
+        context.execute(builder);
+
+        assert_eq!(buffer.read().unwrap()[15..], [3, 4, 0x0A, 0x0B]);
+    }
+
+    #[test]
+    fn copy_buffer_tail_bytes() {
+        let Some(context) = TestContext::new() else {
+            return;
+        };
+
+        let src_buffer = context.buffer(USAGE, 0..23);
+        let dst_buffer = context.buffer(USAGE, [0xAA; 19]);
+
+        // The offsets differ modulo 4, and the copy reaches the end of `dst_buffer`.
+        let mut builder = context.builder();
+        context
+            .compute_transfer
+            .copy_buffer_compute(
+                &mut builder,
+                src_buffer.clone().slice(2..20),
+                dst_buffer.clone().slice(1..),
+            )
+            .unwrap();
+        context.execute(builder);
+
+        let expected: Vec<u8> = [0xAA].into_iter().chain(2..20).collect();
+        assert_eq!(*dst_buffer.read().unwrap(), *expected);
+
+        // Overlapping ranges are rejected.
+        let mut builder = context.builder();
+        assert!(matches!(
+            context.compute_transfer.copy_buffer_compute(
+                &mut builder,
+                src_buffer.clone().slice(0..8),
+                src_buffer.slice(4..12),
+            ),
+            Err(ComputeTransferError::Vulkan(Validated::ValidationError(_))),
+        ));
+    }
+
+    #[test]
+    fn transfer_fallback() {
+        let Some(context) = TestContext::new() else {
+            return;
+        };
+
+        let src_buffer = context.buffer(BufferUsage::STORAGE_BUFFER, 0..16);
+        let dst_buffer = context.buffer(BufferUsage::STORAGE_BUFFER, [0xAA; 16]);
+
+        let mut builder = context.builder();
+        assert!(builder
+            .fill_buffer(dst_buffer.clone().reinterpret(), 0)
+            .is_err());
+
+        builder.set_transfer_fallback(Some(context.compute_transfer.clone()));
+        builder
+            .fill_buffer(dst_buffer.clone().slice(12..).reinterpret(), 0x04030201)
+            .unwrap();
+        builder
+            .copy_buffer(CopyBufferInfo::buffers(
+                src_buffer.slice(0..12),
+                dst_buffer.clone().slice(0..12),
+            ))
+            .unwrap();
+        context.execute(builder);
+
+        let expected: Vec<u8> = (0..12).chain([1, 2, 3, 4]).collect();
+        assert_eq!(*dst_buffer.read().unwrap(), *expected);
+    }
+}
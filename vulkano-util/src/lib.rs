@@ -1,3 +1,6 @@
+#[cfg(feature = "compute_transfer")]
+pub mod compute_transfer;
 pub mod context;
 pub mod renderer;
+pub mod swapchain_state;
 pub mod window;
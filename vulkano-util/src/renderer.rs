@@ -1,4 +1,8 @@
-use crate::{context::VulkanoContext, window::WindowDescriptor};
+use crate::{
+    context::VulkanoContext,
+    swapchain_state::{FrameAction, SwapchainState},
+    window::WindowDescriptor,
+};
 use ahash::HashMap;
 use std::{sync::Arc, time::Duration};
 use vulkano::{
@@ -32,7 +36,9 @@ pub struct VulkanoWindowRenderer {
     /// Additional image views that you can add which are resized with the window.
     /// Use associated functions to get access to these.
     additional_image_views: HashMap<usize, Arc<ImageView>>,
-    recreate_swapchain: bool,
+    swapchain_state: SwapchainState,
+    /// The window extent that the swapchain was last created for.
+    swapchain_window_extent: [u32; 2],
     previous_frame_end: Option<Box<dyn GpuFuture>>,
     image_index: u32,
     present_mode: PresentMode,
@@ -58,6 +64,7 @@ impl VulkanoWindowRenderer {
             swapchain_create_info_modify,
         );
 
+        let swapchain_window_extent = window.inner_size().into();
         let previous_frame_end = Some(sync::now(vulkano_context.device().clone()).boxed());
 
         VulkanoWindowRenderer {
@@ -68,7 +75,8 @@ impl VulkanoWindowRenderer {
             final_views,
             memory_allocator: vulkano_context.memory_allocator().clone(),
             additional_image_views: HashMap::default(),
-            recreate_swapchain: false,
+            swapchain_state: SwapchainState::new(),
+            swapchain_window_extent,
             previous_frame_end,
             image_index: 0,
             present_mode: descriptor.present_mode,
@@ -88,15 +96,15 @@ impl VulkanoWindowRenderer {
             .physical_device()
             .surface_capabilities(&surface, Default::default())
             .unwrap();
-        let image_format = device
-            .physical_device()
-            .surface_formats(&surface, Default::default())
-            .unwrap()[0]
-            .0;
+        let (image_format, image_color_space) = surface
+            .choose_format(device.physical_device(), &Default::default())
+            .unwrap()
+            .unwrap();
         let (swapchain, images) = Swapchain::new(device, surface, {
             let mut create_info = SwapchainCreateInfo {
                 min_image_count: surface_capabilities.min_image_count.max(2),
                 image_format,
+                image_color_space,
                 image_extent: window.inner_size().into(),
                 image_usage: ImageUsage::COLOR_ATTACHMENT,
                 composite_alpha: surface_capabilities
@@ -125,7 +133,7 @@ impl VulkanoWindowRenderer {
     pub fn set_present_mode(&mut self, present_mode: PresentMode) {
         if self.present_mode != present_mode {
             self.present_mode = present_mode;
-            self.recreate_swapchain = true;
+            self.swapchain_state.request_recreate();
         }
     }
 
@@ -215,9 +223,12 @@ impl VulkanoWindowRenderer {
 
     /// Resize swapchain and camera view images at the beginning of next frame based on window
     /// size.
+    ///
+    /// This can be called at any point, including between [`acquire`](Self::acquire) and
+    /// [`present`](Self::present); the recreation is always deferred until the next `acquire`.
     #[inline]
     pub fn resize(&mut self) {
-        self.recreate_swapchain = true;
+        self.swapchain_state.request_recreate();
     }
 
     /// Add interim image view that resizes with window.
@@ -260,6 +271,10 @@ impl VulkanoWindowRenderer {
     /// after which the swapchain image has been acquired and previous frame ended.
     /// Execute your command buffers after calling this function and
     /// finish rendering by calling [`VulkanoWindowRenderer::present`].
+    ///
+    /// If the window currently has a zero extent, for example because it is minimized, or the
+    /// swapchain is out of date, then [`VulkanError::OutOfDate`] is returned. The frame should be
+    /// skipped in that case, and the swapchain is recreated on a later call once possible.
     #[inline]
     pub fn acquire(
         &mut self,
@@ -268,9 +283,46 @@ impl VulkanoWindowRenderer {
     ) -> Result<Box<dyn GpuFuture>, VulkanError> {
         // Recreate swap chain if needed (when resizing of window occurs or swapchain is outdated)
         // Also resize render views if needed
-        if self.recreate_swapchain {
-            self.recreate_swapchain_and_views();
-            on_recreate_swapchain(&self.final_views);
+        let window_extent: [u32; 2] = self.window.inner_size().into();
+
+        if self.swapchain_state.recreate_pending() || window_extent.contains(&0) {
+            // The surface limits are only needed to recreate the swapchain, and `begin_frame`
+            // skips the frame without looking at them while the window has a zero extent.
+            let [min_image_extent, max_image_extent] = if window_extent.contains(&0) {
+                [[0; 2]; 2]
+            } else {
+                let surface_capabilities = self
+                    .graphics_queue
+                    .device()
+                    .physical_device()
+                    .surface_capabilities(self.swapchain.surface(), Default::default())
+                    .map_err(Validated::unwrap)?;
+
+                [
+                    surface_capabilities.min_image_extent,
+                    surface_capabilities.max_image_extent,
+                ]
+            };
+
+            match self.swapchain_state.begin_frame(
+                window_extent,
+                min_image_extent,
+                max_image_extent,
+            ) {
+                FrameAction::Skip => {
+                    if let Some(previous_frame_end) = self.previous_frame_end.as_mut() {
+                        previous_frame_end.cleanup_finished();
+                    }
+
+                    return Err(VulkanError::OutOfDate);
+                }
+                FrameAction::Recreate { image_extent } => {
+                    self.recreate_swapchain_and_views(image_extent);
+                    self.swapchain_window_extent = window_extent;
+                    on_recreate_swapchain(&self.final_views);
+                }
+                FrameAction::Render => (),
+            }
         }
 
         // Acquire next image in the swapchain
@@ -279,15 +331,10 @@ impl VulkanoWindowRenderer {
                 .map_err(Validated::unwrap)
             {
                 Ok(r) => r,
-                Err(VulkanError::OutOfDate) => {
-                    self.recreate_swapchain = true;
-                    return Err(VulkanError::OutOfDate);
-                }
-                Err(e) => panic!("failed to acquire next image: {e}"),
+                // If the swapchain is out of date, this also schedules its recreation.
+                Err(e) => return Err(self.swapchain_state.acquired(Err(e)).unwrap_err()),
             };
-        if suboptimal {
-            self.recreate_swapchain = true;
-        }
+        self.swapchain_state.acquired(Ok(suboptimal))?;
         // Update our image index
         self.image_index = image_index;
 
@@ -323,14 +370,18 @@ impl VulkanoWindowRenderer {
                 }
 
                 self.previous_frame_end = Some(future.boxed());
-            }
-            Err(VulkanError::OutOfDate) => {
-                self.recreate_swapchain = true;
-                self.previous_frame_end =
-                    Some(sync::now(self.graphics_queue.device().clone()).boxed());
+
+                // The presentation succeeded, but futures don't report whether it was suboptimal.
+                // A window that was resized since the swapchain was created is what makes it so.
+                let window_extent: [u32; 2] = self.window.inner_size().into();
+                let is_suboptimal = window_extent != self.swapchain_window_extent;
+                self.swapchain_state.presented(Ok(is_suboptimal)).unwrap();
             }
             Err(e) => {
-                println!("failed to flush future: {e}");
+                if let Err(e) = self.swapchain_state.presented(Err(e)) {
+                    println!("failed to flush future: {e}");
+                }
+
                 self.previous_frame_end =
                     Some(sync::now(self.graphics_queue.device().clone()).boxed());
             }
@@ -338,13 +389,7 @@ impl VulkanoWindowRenderer {
     }
 
     /// Recreates swapchain images and image views which follow the window size.
-    fn recreate_swapchain_and_views(&mut self) {
-        let image_extent: [u32; 2] = self.window().inner_size().into();
-
-        if image_extent.contains(&0) {
-            return;
-        }
-
+    fn recreate_swapchain_and_views(&mut self, image_extent: [u32; 2]) {
         let (new_swapchain, new_images) = self
             .swapchain
             .recreate(SwapchainCreateInfo {
@@ -377,6 +422,6 @@ impl VulkanoWindowRenderer {
         unsafe {
             self.surface.update_ios_sublayer_on_resize();
         }
-        self.recreate_swapchain = false;
+        self.swapchain_state.recreated();
     }
 }
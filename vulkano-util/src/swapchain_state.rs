@@ -0,0 +1,314 @@
+//! Windowing-independent bookkeeping for when a swapchain needs to be recreated.
+//!
+//! [`SwapchainState`] tracks the swapchain-related decisions that have to be made every frame,
+//! without touching the window or the Vulkan objects themselves. This makes the tricky
+//! orderings testable without a window:
+//!
+//! - A window with a zero extent (for example a minimized window on Windows) can't have a
+//!   swapchain, so rendering is skipped until the window has a nonzero size again.
+//! - The extent of a recreated swapchain is clamped into the `min_image_extent` and
+//!   `max_image_extent` of the surface capabilities.
+//! - Recreation that is requested while a frame is in flight, whether by a resize event or by an
+//!   acquire or present result, is deferred until the start of the next frame.
+//! - [`VulkanError::OutOfDate`] and suboptimal results from both acquiring and presenting are
+//!   turned into a recreation request.
+//!
+//! [`VulkanoWindowRenderer`](crate::renderer::VulkanoWindowRenderer) uses this internally, but
+//! it can also be used by applications that manage their own swapchain.
+
+use vulkano::VulkanError;
+
+/// What should be done at the start of a frame, as returned by [`SwapchainState::begin_frame`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameAction {
+    /// The window currently has a zero extent. No swapchain image should be acquired this frame.
+    Skip,
+
+    /// The swapchain must be recreated with the given extent before acquiring an image. Once the
+    /// recreation has succeeded, call [`SwapchainState::recreated`].
+    Recreate {
+        /// The extent to recreate the swapchain with, already clamped into the surface limits.
+        image_extent: [u32; 2],
+    },
+
+    /// The swapchain is up to date and the frame can be rendered.
+    Render,
+}
+
+/// Windowing-independent state deciding when a swapchain must be recreated.
+///
+/// See the [module-level documentation](self) for more.
+#[derive(Clone, Debug, Default)]
+pub struct SwapchainState {
+    recreate_pending: bool,
+    frame_in_flight: bool,
+}
+
+impl SwapchainState {
+    /// Creates a new `SwapchainState` for a swapchain that was just created.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests that the swapchain be recreated at the start of the next frame.
+    ///
+    /// This never takes effect in the middle of a frame; a swapchain image that was already
+    /// acquired is still presented to the old swapchain.
+    #[inline]
+    pub fn request_recreate(&mut self) {
+        self.recreate_pending = true;
+    }
+
+    /// Returns whether a recreation has been requested and not yet carried out.
+    #[inline]
+    pub fn recreate_pending(&self) -> bool {
+        self.recreate_pending
+    }
+
+    /// Returns whether an image has been acquired and not yet presented.
+    #[inline]
+    pub fn frame_in_flight(&self) -> bool {
+        self.frame_in_flight
+    }
+
+    /// Decides what to do at the start of a frame.
+    ///
+    /// `window_extent` is the current inner size of the window, and `min_image_extent` and
+    /// `max_image_extent` are the limits from the surface capabilities. If a recreation is
+    /// needed, the extent to use is `window_extent` clamped into these limits.
+    ///
+    /// If the window extent, or the clamped extent, is zero in any dimension, then
+    /// [`FrameAction::Skip`] is returned and the swapchain will be recreated once the window has
+    /// a nonzero extent again.
+    pub fn begin_frame(
+        &mut self,
+        window_extent: [u32; 2],
+        min_image_extent: [u32; 2],
+        max_image_extent: [u32; 2],
+    ) -> FrameAction {
+        if window_extent.contains(&0) {
+            // Whatever happened while the window was hidden, the swapchain is unlikely to still
+            // match it once it becomes visible again.
+            self.recreate_pending = true;
+            return FrameAction::Skip;
+        }
+
+        if !self.recreate_pending {
+            return FrameAction::Render;
+        }
+
+        let image_extent = clamp_extent(window_extent, min_image_extent, max_image_extent);
+
+        if image_extent.contains(&0) {
+            return FrameAction::Skip;
+        }
+
+        FrameAction::Recreate { image_extent }
+    }
+
+    /// Records that the swapchain was successfully recreated after
+    /// [`FrameAction::Recreate`] was returned.
+    #[inline]
+    pub fn recreated(&mut self) {
+        self.recreate_pending = false;
+    }
+
+    /// Records the result of acquiring a swapchain image. `result` contains whether the acquired
+    /// image is suboptimal.
+    ///
+    /// A suboptimal image can still be rendered to and presented, so `Ok` is returned and the
+    /// swapchain is recreated at the start of the next frame. If the swapchain is out of date,
+    /// recreation is requested and the error is returned, since no image was acquired. Other
+    /// errors are returned unchanged.
+    pub fn acquired(&mut self, result: Result<bool, VulkanError>) -> Result<(), VulkanError> {
+        match result {
+            Ok(is_suboptimal) => {
+                if is_suboptimal {
+                    self.recreate_pending = true;
+                }
+
+                self.frame_in_flight = true;
+
+                Ok(())
+            }
+            Err(VulkanError::OutOfDate) => {
+                self.recreate_pending = true;
+
+                Err(VulkanError::OutOfDate)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Records the result of presenting the acquired image. `result` contains whether the
+    /// presentation was suboptimal.
+    ///
+    /// Both a suboptimal presentation and [`VulkanError::OutOfDate`] end the frame normally and
+    /// request recreation at the start of the next frame, so `Ok` is returned for them. Other
+    /// errors are returned unchanged.
+    pub fn presented(&mut self, result: Result<bool, VulkanError>) -> Result<(), VulkanError> {
+        self.frame_in_flight = false;
+
+        match result {
+            Ok(false) => Ok(()),
+            Ok(true) | Err(VulkanError::OutOfDate) => {
+                self.recreate_pending = true;
+
+                Ok(())
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Clamps `extent` component-wise into the range `min..=max`.
+///
+/// Unlike [`Ord::clamp`], this doesn't panic if `min` is greater than `max`, which some drivers
+/// report for a window that is being minimized. In that case, `max` takes precedence.
+#[inline]
+pub fn clamp_extent(extent: [u32; 2], min: [u32; 2], max: [u32; 2]) -> [u32; 2] {
+    [
+        extent[0].max(min[0]).min(max[0]),
+        extent[1].max(min[1]).min(max[1]),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN: [u32; 2] = [1, 1];
+    const MAX: [u32; 2] = [4096, 4096];
+
+    #[test]
+    fn render_when_up_to_date() {
+        let mut state = SwapchainState::new();
+        assert_eq!(state.begin_frame([800, 600], MIN, MAX), FrameAction::Render);
+    }
+
+    #[test]
+    fn zero_extent_skips_and_recreates_on_restore() {
+        let mut state = SwapchainState::new();
+        assert_eq!(state.begin_frame([0, 0], MIN, MAX), FrameAction::Skip);
+        assert_eq!(state.begin_frame([800, 0], MIN, MAX), FrameAction::Skip);
+        assert!(state.recreate_pending());
+
+        assert_eq!(
+            state.begin_frame([800, 600], MIN, MAX),
+            FrameAction::Recreate {
+                image_extent: [800, 600],
+            },
+        );
+        state.recreated();
+        assert_eq!(state.begin_frame([800, 600], MIN, MAX), FrameAction::Render);
+    }
+
+    #[test]
+    fn zero_max_extent_skips() {
+        let mut state = SwapchainState::new();
+        state.request_recreate();
+        assert_eq!(
+            state.begin_frame([800, 600], [0, 0], [0, 0]),
+            FrameAction::Skip
+        );
+        assert!(state.recreate_pending());
+    }
+
+    #[test]
+    fn extent_is_clamped() {
+        let mut state = SwapchainState::new();
+        state.request_recreate();
+        assert_eq!(
+            state.begin_frame([8000, 600], MIN, MAX),
+            FrameAction::Recreate {
+                image_extent: [4096, 600],
+            },
+        );
+
+        assert_eq!(clamp_extent([0, 5], [1, 1], [10, 10]), [1, 5]);
+        assert_eq!(clamp_extent([5, 5], [8, 8], [4, 4]), [4, 4]);
+    }
+
+    #[test]
+    fn resize_during_frame_is_deferred() {
+        let mut state = SwapchainState::new();
+        assert_eq!(state.begin_frame([800, 600], MIN, MAX), FrameAction::Render);
+        state.acquired(Ok(false)).unwrap();
+        assert!(state.frame_in_flight());
+
+        // A resize event arrives between acquire and present.
+        state.request_recreate();
+        state.presented(Ok(false)).unwrap();
+        assert!(!state.frame_in_flight());
+
+        assert_eq!(
+            state.begin_frame([1024, 768], MIN, MAX),
+            FrameAction::Recreate {
+                image_extent: [1024, 768],
+            },
+        );
+    }
+
+    #[test]
+    fn suboptimal_acquire_still_renders() {
+        let mut state = SwapchainState::new();
+        assert_eq!(state.begin_frame([800, 600], MIN, MAX), FrameAction::Render);
+        assert_eq!(state.acquired(Ok(true)), Ok(()));
+        assert!(state.frame_in_flight());
+        assert!(state.recreate_pending());
+        state.presented(Ok(false)).unwrap();
+
+        assert!(matches!(
+            state.begin_frame([800, 600], MIN, MAX),
+            FrameAction::Recreate { .. },
+        ));
+    }
+
+    #[test]
+    fn out_of_date_acquire() {
+        let mut state = SwapchainState::new();
+        assert_eq!(state.begin_frame([800, 600], MIN, MAX), FrameAction::Render);
+        assert_eq!(
+            state.acquired(Err(VulkanError::OutOfDate)),
+            Err(VulkanError::OutOfDate),
+        );
+        assert!(!state.frame_in_flight());
+
+        assert!(matches!(
+            state.begin_frame([800, 600], MIN, MAX),
+            FrameAction::Recreate { .. },
+        ));
+    }
+
+    #[test]
+    fn suboptimal_or_out_of_date_present() {
+        for result in [Ok(true), Err(VulkanError::OutOfDate)] {
+            let mut state = SwapchainState::new();
+            assert_eq!(state.begin_frame([800, 600], MIN, MAX), FrameAction::Render);
+            state.acquired(Ok(false)).unwrap();
+            assert_eq!(state.presented(result), Ok(()));
+            assert!(!state.frame_in_flight());
+
+            assert!(matches!(
+                state.begin_frame([800, 600], MIN, MAX),
+                FrameAction::Recreate { .. },
+            ));
+        }
+    }
+
+    #[test]
+    fn other_errors_are_passed_through() {
+        let mut state = SwapchainState::new();
+        assert_eq!(
+            state.acquired(Err(VulkanError::SurfaceLost)),
+            Err(VulkanError::SurfaceLost),
+        );
+        assert!(!state.recreate_pending());
+        assert_eq!(
+            state.presented(Err(VulkanError::DeviceLost)),
+            Err(VulkanError::DeviceLost),
+        );
+        assert!(!state.recreate_pending());
+    }
+}
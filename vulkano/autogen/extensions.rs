@@ -276,6 +276,23 @@ fn device_extensions_output(members: &[ExtensionsMember]) -> TokenStream {
             },
         );
 
+    let promoted_to_core_items =
+        members
+            .iter()
+            .filter_map(|ExtensionsMember { name, status, .. }| match status {
+                Some(ExtensionStatus::PromotedTo(Requires::APIVersion(major, minor))) => {
+                    let name_string = name.to_string();
+                    let version = format_ident!("V{}_{}", major, minor);
+
+                    Some(quote! {
+                        if self.#name && api_version >= crate::Version::#version {
+                            result.push((#name_string, crate::Version::#version));
+                        }
+                    })
+                }
+                _ => None,
+            });
+
     quote! {
         #common
 
@@ -300,6 +317,17 @@ fn device_extensions_output(members: &[ExtensionsMember]) -> TokenStream {
             ) {
                 #(#enable_dependencies_items)*
             }
+
+            /// Returns the extensions in `self` that were promoted to a core API version that is
+            /// not greater than `api_version`, together with that version.
+            pub(super) fn promoted_to_core(
+                &self,
+                api_version: crate::Version,
+            ) -> Vec<(&'static str, crate::Version)> {
+                let mut result = Vec::new();
+                #(#promoted_to_core_items)*
+                result
+            }
         }
     }
 }
@@ -576,6 +604,13 @@ fn extensions_common_output(struct_name: Ident, members: &[ExtensionsMember]) ->
             }
         });
 
+    let from_names_items = members.iter().map(|ExtensionsMember { name, raw, .. }| {
+        let raw = Literal::string(raw);
+        quote! {
+            #raw => extensions.#name = true,
+        }
+    });
+
     let from_extensions_for_vec_cstring_items =
         members.iter().map(|ExtensionsMember { name, raw, .. }| {
             quote! {
@@ -668,6 +703,38 @@ fn extensions_common_output(struct_name: Ident, members: &[ExtensionsMember]) ->
                     _ne: crate::NonExhaustive(()),
                 }
             }
+
+            /// Returns the Vulkan names of the extensions that are set in `self`, such as
+            /// `VK_KHR_swapchain`.
+            ///
+            /// The names can be turned back into extensions with
+            /// [`from_names`](Self::from_names).
+            #[inline]
+            pub fn iter_enabled(&self) -> impl Iterator<Item = &'static str> {
+                self.into_iter()
+                    .filter_map(|(name, enabled)| enabled.then_some(name))
+            }
+
+            /// Returns extensions with the members with the given Vulkan names set.
+            ///
+            /// Unlike the `FromIterator` implementation, which ignores names that it doesn't
+            /// recognize, this returns an error if a name doesn't match any extension.
+            pub fn from_names(names: &[&str]) -> Result<Self, crate::UnknownExtension> {
+                let mut extensions = Self::empty();
+
+                for &name in names {
+                    match name {
+                        #(#from_names_items)*
+                        _ => {
+                            return Err(crate::UnknownExtension {
+                                name: name.to_owned(),
+                            })
+                        }
+                    }
+                }
+
+                Ok(extensions)
+            }
         }
 
         impl std::ops::BitAnd for #struct_name {
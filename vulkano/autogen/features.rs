@@ -51,7 +51,7 @@ fn required_by_extensions(name: &str) -> &'static [(&'static str, &'static str)]
 }
 
 pub fn write(vk_data: &VkRegistryData<'_>) {
-    let features_output = features_output(&features_members(&vk_data.types));
+    let features_output = features_output(&features_members(&vk_data.types, &vk_data.extensions));
     let features_ffi_output =
         features_ffi_output(&features_ffi_members(&vk_data.types, &vk_data.extensions));
     write_file(
@@ -77,6 +77,8 @@ struct FeaturesMember {
     requires_features: Vec<Ident>,
     conflicts_features: Vec<Ident>,
     required_by_extensions: Vec<(Ident, Ident)>,
+    provided_by_versions: Vec<Ident>,
+    provided_by_extensions: Vec<Ident>,
     optional: bool,
 }
 
@@ -105,6 +107,35 @@ fn features_output(members: &[FeaturesMember]) -> TokenStream {
         }
     });
 
+    let extension_dependencies_items = members.iter().filter_map(
+        |FeaturesMember {
+             name,
+             provided_by_versions,
+             provided_by_extensions,
+             ..
+         }| {
+            if provided_by_versions.is_empty() && provided_by_extensions.is_empty() {
+                return None;
+            }
+
+            let name_string = name.to_string();
+
+            Some(quote! {
+                if self.#name
+                    && !(false #(|| api_version >= crate::Version::#provided_by_versions)*)
+                {
+                    dependencies.push((
+                        #name_string,
+                        DeviceExtensions {
+                            #(#provided_by_extensions: true,)*
+                            ..DeviceExtensions::empty()
+                        },
+                    ));
+                }
+            })
+        },
+    );
+
     let empty_items = members.iter().map(|FeaturesMember { name, .. }| {
         quote! {
             #name: false,
@@ -234,6 +265,13 @@ fn features_output(members: &[FeaturesMember]) -> TokenStream {
         },
     );
 
+    let from_names_items = members.iter().map(|FeaturesMember { name, .. }| {
+        let name_string = name.to_string();
+        quote! {
+            #name_string => features.#name = true,
+        }
+    });
+
     quote! {
         /// Represents all the features that are available on a physical device or enabled on
         /// a logical device.
@@ -287,6 +325,24 @@ fn features_output(members: &[FeaturesMember]) -> TokenStream {
                 Ok(())
             }
 
+            /// Returns the device extensions that are needed for the features in `self` to be
+            /// enabled on a device with the given API version.
+            ///
+            /// Each element contains the name of a feature in `self` that is not provided by
+            /// the core API of `api_version`, together with the device extensions that provide
+            /// it. One of these extensions must be enabled in order to enable the feature. If the
+            /// set of extensions is empty, then the feature is only available with a higher
+            /// API version.
+            pub fn extension_dependencies(
+                &self,
+                #[allow(unused_variables)] api_version: crate::Version,
+            ) -> Vec<(&'static str, DeviceExtensions)> {
+                #[allow(unused_mut)]
+                let mut dependencies = Vec::new();
+                #(#extension_dependencies_items)*
+                dependencies
+            }
+
             /// Returns a `DeviceFeatures` object with none of the members set.
             #[inline]
             pub const fn empty() -> Self {
@@ -352,6 +408,37 @@ fn features_output(members: &[FeaturesMember]) -> TokenStream {
                     _ne: crate::NonExhaustive(()),
                 }
             }
+
+            /// Returns the names of the members that are set in `self`.
+            ///
+            /// The names are the same as the names of the fields of `DeviceFeatures`, and can be
+            /// turned back into a `DeviceFeatures` with [`from_names`](Self::from_names).
+            #[inline]
+            pub fn iter_enabled(&self) -> impl Iterator<Item = &'static str> {
+                self.into_iter()
+                    .filter_map(|(name, enabled)| enabled.then_some(name))
+            }
+
+            /// Returns a `DeviceFeatures` with the members with the given names set.
+            ///
+            /// The names are the same as the names of the fields of `DeviceFeatures`. An error is
+            /// returned if a name doesn't match any of them.
+            pub fn from_names(names: &[&str]) -> Result<Self, UnknownFeature> {
+                let mut features = Self::empty();
+
+                for &name in names {
+                    match name {
+                        #(#from_names_items)*
+                        _ => {
+                            return Err(UnknownFeature {
+                                name: name.to_owned(),
+                            })
+                        }
+                    }
+                }
+
+                Ok(features)
+            }
         }
 
         impl std::ops::BitAnd for DeviceFeatures {
@@ -457,7 +544,10 @@ fn features_output(members: &[FeaturesMember]) -> TokenStream {
     }
 }
 
-fn features_members(types: &HashMap<&str, (&Type, Vec<&str>)>) -> Vec<FeaturesMember> {
+fn features_members(
+    types: &HashMap<&str, (&Type, Vec<&str>)>,
+    extensions: &IndexMap<&str, &Extension>,
+) -> Vec<FeaturesMember> {
     let mut features = HashMap::default();
     std::iter::once(&types["VkPhysicalDeviceFeatures"])
         .chain(sorted_structs(types))
@@ -466,9 +556,39 @@ fn features_members(types: &HashMap<&str, (&Type, Vec<&str>)>) -> Vec<FeaturesMe
                 || ty.structextends.as_deref()
                     == Some("VkPhysicalDeviceFeatures2,VkDeviceCreateInfo")
         })
-        .for_each(|(ty, _)| {
+        .for_each(|(ty, provided_by)| {
             let vulkan_ty_name = ty.name.as_ref().unwrap();
 
+            // The features of `VkPhysicalDeviceFeatures` are always available.
+            let (provided_by_versions, provided_by_extensions) =
+                if vulkan_ty_name == "VkPhysicalDeviceFeatures" {
+                    (vec![], vec![])
+                } else {
+                    let versions: Vec<_> = provided_by
+                        .iter()
+                        .filter_map(|provided_by| provided_by.strip_prefix("VK_VERSION_"))
+                        .map(|version| format_ident!("V{}", version))
+                        .collect();
+                    let device_extensions: Vec<_> = provided_by
+                        .iter()
+                        .filter(|&&provided_by| {
+                            extensions.get(provided_by).map_or(false, |extension| {
+                                extension.ext_type.as_deref() == Some("device")
+                            })
+                        })
+                        .map(|provided_by| {
+                            format_ident!(
+                                "{}",
+                                provided_by
+                                    .strip_prefix("VK_")
+                                    .unwrap()
+                                    .to_ascii_lowercase()
+                            )
+                        })
+                        .collect();
+                    (versions, device_extensions)
+                };
+
             let (ty_name, optional) = if vulkan_ty_name == "VkPhysicalDeviceFeatures" {
                 (
                     (format_ident!("features_vulkan10"), quote! { .features }),
@@ -514,13 +634,28 @@ fn features_members(types: &HashMap<&str, (&Type, Vec<&str>)>) -> Vec<FeaturesMe
                                     (version, name)
                                 })
                                 .collect(),
+                            provided_by_versions: provided_by_versions.clone(),
+                            provided_by_extensions: provided_by_extensions.clone(),
                             optional,
                         };
                         make_doc(&mut member, vulkan_ty_name);
                         entry.insert(member);
                     }
                     Entry::Occupied(entry) => {
-                        entry.into_mut().ffi_members.push(ty_name.clone());
+                        let member = entry.into_mut();
+                        member.ffi_members.push(ty_name.clone());
+
+                        for version in &provided_by_versions {
+                            if !member.provided_by_versions.contains(version) {
+                                member.provided_by_versions.push(version.clone());
+                            }
+                        }
+
+                        for extension in &provided_by_extensions {
+                            if !member.provided_by_extensions.contains(extension) {
+                                member.provided_by_extensions.push(extension.clone());
+                            }
+                        }
                     }
                 };
             });
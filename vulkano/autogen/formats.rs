@@ -41,6 +41,7 @@ struct FormatMember {
     compatibility: Ident,
     components: [u8; 4],
     compression: Option<Ident>,
+    packed: Option<u8>,
     planes: Vec<Ident>,
     texels_per_block: u8,
     numeric_format_color: Option<Ident>,
@@ -156,6 +157,38 @@ fn formats_output(members: &[FormatMember]) -> TokenStream {
             }
         },
     );
+    let texel_buffer_element_size_items = members.iter().filter_map(
+        |FormatMember {
+             name,
+             block_size,
+             components,
+             compression,
+             packed,
+             planes,
+             ..
+         }| {
+            let element_size = if let Some(bits) = packed {
+                *bits as u64 / 8
+            } else if compression.is_none()
+                && planes.is_empty()
+                && components[3] == 0
+                && components[0] != 0
+                && components[0] == components[1]
+                && components[1] == components[2]
+            {
+                components[0] as u64 / 8
+            } else {
+                *block_size
+            };
+
+            if element_size == *block_size {
+                None
+            } else {
+                let element_size = Literal::u64_unsuffixed(element_size);
+                Some(quote! { Self::#name => #element_size, })
+            }
+        },
+    );
     let compression_items = members.iter().filter_map(
         |FormatMember {
              name, compression, ..
@@ -238,6 +271,18 @@ fn formats_output(members: &[FormatMember]) -> TokenStream {
     let try_from_items = members.iter().map(|FormatMember { name, ffi_name, .. }| {
         quote! { ash::vk::Format::#ffi_name => Ok(Self::#name), }
     });
+    let all_items = members
+        .iter()
+        .filter(|FormatMember { ffi_name, .. }| ffi_name != "UNDEFINED")
+        .map(|FormatMember { name, .. }| quote! { Self::#name, });
+    let name_items = members.iter().map(|FormatMember { name, .. }| {
+        let name_string = name.to_string();
+        quote! { Self::#name => #name_string, }
+    });
+    let from_name_items = members.iter().map(|FormatMember { name, .. }| {
+        let name_string = name.to_string();
+        quote! { #name_string => Some(Self::#name), }
+    });
 
     let type_for_format_items = members.iter().filter_map(
         |FormatMember {
@@ -376,6 +421,25 @@ fn formats_output(members: &[FormatMember]) -> TokenStream {
         }
 
         impl Format {
+            /// All formats except `UNDEFINED`, in the order in which they are defined.
+            pub const ALL: &'static [Format] = &[#(#all_items)*];
+
+            /// Returns the name of the format, as it is written in Rust.
+            pub fn name(self) -> &'static str {
+                match self {
+                    #(#name_items)*
+                }
+            }
+
+            /// Returns the format with the given name, as it is written in Rust, or `None` if
+            /// there is no such format.
+            pub fn from_name(name: &str) -> Option<Format> {
+                match name {
+                    #(#from_name_items)*
+                    _ => None,
+                }
+            }
+
             /// Returns the aspects that images of this format have.
             pub fn aspects(self) -> ImageAspects {
                 match self {
@@ -414,6 +478,19 @@ fn formats_output(members: &[FormatMember]) -> TokenStream {
                 }
             }
 
+            /// Returns the size in bytes of a single element of this format, for the purpose of the
+            /// offset alignment of texel buffers.
+            ///
+            /// For packed formats, this is the size of the packed data type. For unpacked
+            /// three-component formats, this is the size of a single component. For all other
+            /// formats, this is equal to [`block_size`](Self::block_size).
+            pub fn texel_buffer_element_size(self) -> DeviceSize {
+                match self {
+                    #(#texel_buffer_element_size_items)*
+                    _ => self.block_size(),
+                }
+            }
+
             /// Returns the an opaque object representing the compatibility class of the format.
             /// This can be used to determine whether two formats are compatible for the purposes
             /// of certain Vulkan operations, such as image copying.
@@ -664,6 +741,7 @@ fn formats_members(
             compatibility: format_ident!("Undefined"),
             components: [0u8; 4],
             compression: None,
+            packed: None,
             planes: vec![],
             texels_per_block: 0,
             numeric_format_color: None,
@@ -714,6 +792,7 @@ fn formats_members(
                     .compressed
                     .as_ref()
                     .map(|c| format_ident!("{}", c.replace(' ', "_"))),
+                packed: format.packed.map(|bits| bits as u8),
                 planes: vec![],
                 texels_per_block: format.texelsPerBlock,
                 numeric_format_color: None,
@@ -13,15 +13,20 @@ use crate::{
         },
         DeviceAlignment,
     },
-    DeviceSize, NonZeroDeviceSize, Validated,
+    sync::semaphore::{Semaphore, SemaphoreType},
+    DeviceSize, NonZeroDeviceSize, Validated, ValidationError,
 };
 use crossbeam_queue::ArrayQueue;
 use std::{
     cell::UnsafeCell,
     cmp,
+    collections::VecDeque,
     hash::{Hash, Hasher},
     mem::ManuallyDrop,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+    },
 };
 
 const MAX_ARENAS: usize = 32;
@@ -70,6 +75,45 @@ const MAX_ARENAS: usize = 32;
 /// Download or device-only usage is much the same. Try to make the arenas fit all the data you
 /// need to store at once.
 ///
+/// A single allocator can serve allocations with different buffer usages or memory type filters,
+/// for example both uploads and readbacks, by using [`allocate_with`]. Each combination gets its
+/// own arenas.
+///
+/// [`allocate_with`]: Self::allocate_with
+///
+/// # Timeline recycling
+///
+/// By default, an arena is only returned to the pool once every subbuffer allocated from it has
+/// been dropped. Command buffers and the futures they are submitted with keep clones of the
+/// subbuffers they use, so holding on to those for longer than necessary means that arenas are
+/// not reused even though the device is long done with them, and new arenas keep being allocated.
+///
+/// To avoid this, you can call [`set_timeline`] with a timeline semaphore that your submissions
+/// signal, and call [`retire_frame`] at the end of each frame with the value that the semaphore
+/// will have once the device has finished the work of that frame. The arenas used during the
+/// frame are then reused as soon as the semaphore reaches that value, regardless of which
+/// subbuffers are still alive. It is then your responsibility to not use subbuffers from a retired
+/// frame on the device anymore. Reading or writing one from the host returns
+/// [`HostAccessError::Retired`].
+///
+/// [`set_timeline`]: Self::set_timeline
+/// [`retire_frame`]: Self::retire_frame
+/// [`HostAccessError::Retired`]: crate::sync::HostAccessError::Retired
+///
+/// If you already wait for a fence at the end of each frame, you can instead call [`reset`] after
+/// waiting for it, which makes every arena available for reuse right away.
+///
+/// [`reset`]: Self::reset
+///
+/// # Statistics
+///
+/// [`statistics`] returns how many arenas the allocator is keeping alive, and how much of them is
+/// used between calls to [`reset`]. The peak usage is a good value for the arena size. If the
+/// number of arenas keeps growing, then subbuffers are kept alive for too long, pinning the arenas
+/// they were allocated from.
+///
+/// [`statistics`]: Self::statistics
+///
 /// # Examples
 ///
 /// ```
@@ -147,33 +191,18 @@ where
             _ne: _,
         } = create_info;
 
-        let properties = memory_allocator.device().physical_device().properties();
-        let buffer_alignment = [
-            buffer_usage
-                .intersects(BufferUsage::UNIFORM_TEXEL_BUFFER | BufferUsage::STORAGE_TEXEL_BUFFER)
-                .then_some(properties.min_texel_buffer_offset_alignment),
-            buffer_usage
-                .contains(BufferUsage::UNIFORM_BUFFER)
-                .then_some(properties.min_uniform_buffer_offset_alignment),
-            buffer_usage
-                .contains(BufferUsage::STORAGE_BUFFER)
-                .then_some(properties.min_storage_buffer_offset_alignment),
-        ]
-        .into_iter()
-        .flatten()
-        .max()
-        .unwrap_or(DeviceAlignment::MIN);
-
         SubbufferAllocator {
             state: UnsafeCell::new(SubbufferAllocatorState {
                 memory_allocator,
                 buffer_usage,
                 memory_type_filter,
-                buffer_alignment,
                 arena_size,
-                arena: None,
-                free_start: 0,
-                reserve: None,
+                pools: Vec::new(),
+                timeline: None,
+                handed_out: Vec::new(),
+                arena_buffers: Vec::new(),
+                allocated_size: 0,
+                peak_allocated_size: 0,
             }),
         }
     }
@@ -190,8 +219,11 @@ where
     pub fn set_arena_size(&self, size: DeviceSize) {
         let state = unsafe { &mut *self.state.get() };
         state.arena_size = size;
-        state.arena = None;
-        state.reserve = None;
+
+        for pool in &mut state.pools {
+            pool.arena = None;
+            pool.reserve = None;
+        }
     }
 
     /// Ensures that the size of the current arena is at least `size`.
@@ -199,12 +231,189 @@ where
     /// If `size` is greater than the current arena size, then a new arena will be allocated with
     /// the new size, and all subsequently allocated arenas will also share the new size. Otherwise
     /// this has no effect.
+    ///
+    /// The new arena is allocated with the buffer usage and memory type filter given when
+    /// creating the allocator. The current arenas of other buffer usages and memory type filters
+    /// are used until they run out of space.
     pub fn reserve(&self, size: DeviceSize) -> Result<(), MemoryAllocatorError> {
         if size > self.arena_size() {
             let state = unsafe { &mut *self.state.get() };
             state.arena_size = size;
-            state.reserve = None;
-            state.arena = Some(state.next_arena()?);
+
+            for pool in &mut state.pools {
+                pool.reserve = None;
+            }
+
+            let pool_index = state.pool_index(state.buffer_usage, state.memory_type_filter);
+            let arena = state.next_arena(pool_index)?;
+            let pool = &mut state.pools[pool_index];
+            pool.arena = Some(arena);
+            pool.free_start = 0;
+        }
+
+        Ok(())
+    }
+
+    /// Switches the allocator to recycling arenas based on the counter value of `semaphore`,
+    /// instead of when all subbuffers allocated from them have been dropped.
+    ///
+    /// From then on, the arenas used since the last call to [`retire_frame`] are reused once
+    /// `semaphore` reaches the value given to `retire_frame`. Arenas that were in use before
+    /// calling this function are not reused.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `semaphore` was not created from the same device as `self`.
+    ///
+    /// [`retire_frame`]: Self::retire_frame
+    pub fn set_timeline(&self, semaphore: Arc<Semaphore>) -> Result<(), Box<ValidationError>> {
+        self.validate_set_timeline(&semaphore)?;
+
+        let state = unsafe { &mut *self.state.get() };
+
+        for pool in &mut state.pools {
+            pool.arena = None;
+            pool.reserve = None;
+        }
+
+        state.timeline = Some(TimelineState {
+            semaphore,
+            last_value: 0,
+            in_use: Vec::new(),
+            retired: VecDeque::new(),
+            free: Vec::new(),
+        });
+
+        Ok(())
+    }
+
+    fn validate_set_timeline(&self, semaphore: &Semaphore) -> Result<(), Box<ValidationError>> {
+        // VUID-VkSemaphoreWaitInfo-commonparent
+        assert_eq!(self.device(), semaphore.device());
+
+        if semaphore.semaphore_type() != SemaphoreType::Timeline {
+            return Err(Box::new(ValidationError {
+                context: "semaphore.semaphore_type()".into(),
+                problem: "is not `SemaphoreType::Timeline`".into(),
+                ..Default::default()
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Marks the end of a frame, with the arenas used during the frame becoming available for
+    /// reuse once the semaphore given to [`set_timeline`] reaches `value`.
+    ///
+    /// Subbuffers allocated after this call will come from a different arena than those allocated
+    /// before it. Once the semaphore has reached `value`, the subbuffers allocated before this
+    /// call must no longer be accessed, even if they are still alive.
+    ///
+    /// [`set_timeline`]: Self::set_timeline
+    pub fn retire_frame(&self, value: u64) -> Result<(), Box<ValidationError>> {
+        self.validate_retire_frame(value)?;
+
+        let state = unsafe { &mut *self.state.get() };
+        let timeline = state.timeline.as_mut().unwrap();
+        timeline.last_value = value;
+        timeline.retired.extend(
+            timeline
+                .in_use
+                .drain(..)
+                .map(|(pool_index, arena)| (value, pool_index, arena)),
+        );
+
+        for pool in &mut state.pools {
+            pool.arena = None;
+        }
+
+        Ok(())
+    }
+
+    /// Makes all arenas available for reuse, including the ones that subbuffers are still
+    /// allocated from, and starts a new period for the usage [statistics].
+    ///
+    /// This is meant to be called once per frame, after waiting for the fence of the previous
+    /// frame that used subbuffers from this allocator.
+    ///
+    /// # Safety
+    ///
+    /// - Subbuffers allocated before this call must no longer be accessed by the host or the
+    ///   device, even if they are still alive. In particular, all submissions that use them must
+    ///   have completed execution, for example by waiting for their fences.
+    ///
+    /// [statistics]: Self::statistics
+    pub unsafe fn reset(&self) {
+        let state = unsafe { &mut *self.state.get() };
+        state.peak_allocated_size = cmp::max(state.peak_allocated_size, state.allocated_size);
+        state.allocated_size = 0;
+
+        for pool in &mut state.pools {
+            pool.arena = None;
+        }
+
+        for arena in state.handed_out.drain(..) {
+            // Arenas that have been dropped already returned themselves to their reserve.
+            if let Some(arena) = arena.upgrade() {
+                if !arena.retired.swap(true, Ordering::Relaxed) {
+                    if let Some(reserve) = &arena.reserve {
+                        let _ = reserve.push(arena.buffer().clone());
+                    }
+                }
+            }
+        }
+
+        if let Some(timeline) = &mut state.timeline {
+            let arenas = timeline.in_use.drain(..).chain(
+                timeline
+                    .retired
+                    .drain(..)
+                    .map(|(_, pool_index, arena)| (pool_index, arena)),
+            );
+
+            for (pool_index, arena) in arenas {
+                arena.retired.store(true, Ordering::Relaxed);
+                timeline.free.push((pool_index, arena.buffer().clone()));
+            }
+        }
+    }
+
+    /// Returns statistics about the arenas of the allocator.
+    pub fn statistics(&self) -> SubbufferAllocatorStatistics {
+        let state = unsafe { &mut *self.state.get() };
+        state
+            .arena_buffers
+            .retain(|buffer| buffer.strong_count() != 0);
+
+        SubbufferAllocatorStatistics {
+            arena_count: state.arena_buffers.len(),
+            reserved_size: state
+                .arena_buffers
+                .iter()
+                .filter_map(Weak::upgrade)
+                .map(|buffer| buffer.size())
+                .sum(),
+            allocated_size: state.allocated_size,
+            peak_allocated_size: cmp::max(state.peak_allocated_size, state.allocated_size),
+        }
+    }
+
+    fn validate_retire_frame(&self, value: u64) -> Result<(), Box<ValidationError>> {
+        let state = unsafe { &*self.state.get() };
+
+        let Some(timeline) = &state.timeline else {
+            return Err(Box::new(ValidationError {
+                problem: "no timeline semaphore has been set with `set_timeline`".into(),
+                ..Default::default()
+            }));
+        };
+
+        if value < timeline.last_value {
+            return Err(Box::new(ValidationError {
+                context: "value".into(),
+                problem: "is less than the value of a previously retired frame".into(),
+                ..Default::default()
+            }));
         }
 
         Ok(())
@@ -217,8 +426,7 @@ where
     {
         let layout = T::LAYOUT.unwrap_sized();
 
-        unsafe { &mut *self.state.get() }
-            .allocate(layout)
+        self.allocate(layout)
             .map(|subbuffer| unsafe { subbuffer.reinterpret_unchecked() })
     }
 
@@ -246,14 +454,113 @@ where
         let len = NonZeroDeviceSize::new(len).expect("empty slices are not valid buffer contents");
         let layout = T::LAYOUT.layout_for_len(len).unwrap();
 
-        unsafe { &mut *self.state.get() }
-            .allocate(layout)
+        self.allocate(layout)
             .map(|subbuffer| unsafe { subbuffer.reinterpret_unchecked() })
     }
 
+    /// Allocates a subbuffer for sized data, and calls `f` to write the data.
+    ///
+    /// The subbuffer is not zeroed before `f` is called. Its memory can still contain the data of
+    /// subbuffers that were previously allocated from the same arena, so `f` must write every part
+    /// of the data that is going to be read.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the memory type of the arenas is not host-visible.
+    pub fn allocate_sized_with<T>(
+        &self,
+        f: impl FnOnce(&mut T),
+    ) -> Result<Subbuffer<T>, MemoryAllocatorError>
+    where
+        T: BufferContents,
+    {
+        let subbuffer = self.allocate_sized()?;
+        f(&mut subbuffer.write().unwrap());
+
+        Ok(subbuffer)
+    }
+
+    /// Allocates a subbuffer for a slice and writes all elements of `iter` in it.
+    ///
+    /// The elements are written one at a time. If the data is already in a slice or `Vec`, then
+    /// [`allocate_from_slice`] writes it with a single copy instead.
+    ///
+    /// [`allocate_from_slice`]: Self::allocate_from_slice
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the memory type of the arenas is not host-visible.
+    /// - Panics if `iter` is empty.
+    pub fn allocate_from_iter<T, I>(&self, iter: I) -> Result<Subbuffer<[T]>, MemoryAllocatorError>
+    where
+        T: BufferContents,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iter.into_iter();
+        let subbuffer = self.allocate_slice(iter.len().try_into().unwrap())?;
+
+        {
+            let mut write_guard = subbuffer.write().unwrap();
+
+            for (o, i) in write_guard.iter_mut().zip(iter) {
+                *o = i;
+            }
+        }
+
+        Ok(subbuffer)
+    }
+
+    /// Allocates a subbuffer for a slice and copies `data` into it.
+    ///
+    /// This has the same result as [`allocate_from_iter`] with `data.iter().copied()`, but copies
+    /// all of the data at once.
+    ///
+    /// [`allocate_from_iter`]: Self::allocate_from_iter
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the memory type of the arenas is not host-visible.
+    /// - Panics if `data` is empty.
+    pub fn allocate_from_slice<T>(&self, data: &[T]) -> Result<Subbuffer<[T]>, MemoryAllocatorError>
+    where
+        T: BufferContents + Copy,
+    {
+        let subbuffer = self.allocate_slice(data.len().try_into().unwrap())?;
+        subbuffer.write().unwrap().copy_from_slice(data);
+
+        Ok(subbuffer)
+    }
+
     /// Allocates a subbuffer with the given `layout`.
     pub fn allocate(&self, layout: DeviceLayout) -> Result<Subbuffer<[u8]>, MemoryAllocatorError> {
-        unsafe { &mut *self.state.get() }.allocate(layout)
+        self.allocate_with(layout, &SubbufferAllocateInfo::default())
+    }
+
+    /// Allocates a subbuffer with the given `layout`, using the buffer usage and memory type
+    /// filter of `allocate_info` instead of those given when creating the allocator.
+    ///
+    /// Each combination of buffer usage and memory type filter has its own arenas, so subbuffers
+    /// are only ever allocated from arenas that were created with exactly the requested
+    /// combination.
+    pub fn allocate_with(
+        &self,
+        layout: DeviceLayout,
+        allocate_info: &SubbufferAllocateInfo,
+    ) -> Result<Subbuffer<[u8]>, MemoryAllocatorError> {
+        let &SubbufferAllocateInfo {
+            buffer_usage,
+            memory_type_filter,
+            _ne: _,
+        } = allocate_info;
+
+        let state = unsafe { &mut *self.state.get() };
+        let pool_index = state.pool_index(
+            buffer_usage.unwrap_or(state.buffer_usage),
+            memory_type_filter.unwrap_or(state.memory_type_filter),
+        );
+
+        state.allocate(layout, pool_index)
     }
 }
 
@@ -269,12 +576,32 @@ where
 #[derive(Debug)]
 struct SubbufferAllocatorState<A> {
     memory_allocator: Arc<A>,
+    // The buffer usage and memory type filter used when an allocation doesn't override them.
     buffer_usage: BufferUsage,
     memory_type_filter: MemoryTypeFilter,
-    // The alignment required for the subbuffers.
-    buffer_alignment: DeviceAlignment,
     // The current size of the arenas.
     arena_size: DeviceSize,
+    // The arenas of each combination of buffer usage and memory type filter that has been
+    // allocated with. Pools are never removed, so that their indices stay valid.
+    pools: Vec<ArenaPool>,
+    // Set when arenas are recycled based on the progress of a timeline semaphore.
+    timeline: Option<TimelineState>,
+    // The arenas that were handed out since the last reset, when not using a timeline.
+    handed_out: Vec<Weak<Arena>>,
+    // The buffers of all arenas that were created, used to gather statistics.
+    arena_buffers: Vec<Weak<Buffer>>,
+    // The number of bytes that were allocated since the last reset, including padding.
+    allocated_size: DeviceSize,
+    // The greatest value of `allocated_size` before any of the previous resets.
+    peak_allocated_size: DeviceSize,
+}
+
+#[derive(Debug)]
+struct ArenaPool {
+    buffer_usage: BufferUsage,
+    memory_type_filter: MemoryTypeFilter,
+    // The alignment required for the subbuffers.
+    buffer_alignment: DeviceAlignment,
     // Contains the buffer that is currently being suballocated.
     arena: Option<Arc<Arena>>,
     // Offset pointing to the start of free memory within the arena.
@@ -283,78 +610,206 @@ struct SubbufferAllocatorState<A> {
     reserve: Option<Arc<ArrayQueue<Arc<Buffer>>>>,
 }
 
+#[derive(Debug)]
+struct TimelineState {
+    semaphore: Arc<Semaphore>,
+    // The value of the most recently retired frame.
+    last_value: u64,
+    // The arenas that were handed out since the last retired frame, with the index of the pool
+    // they belong to.
+    in_use: Vec<(usize, Arc<Arena>)>,
+    // The arenas of retired frames, ordered by the counter value they are waiting for.
+    retired: VecDeque<(u64, usize, Arc<Arena>)>,
+    // The buffers of arenas whose frame has completed on the device.
+    free: Vec<(usize, Arc<Buffer>)>,
+}
+
+impl TimelineState {
+    fn recycle(&mut self) {
+        if self.retired.is_empty() {
+            return;
+        }
+
+        // If querying the counter value fails, the device is most likely lost, in which case the
+        // arenas can't be reused anyway.
+        let Ok(counter_value) = (unsafe { self.semaphore.counter_value_unchecked() }) else {
+            return;
+        };
+
+        while let Some((value, _, _)) = self.retired.front() {
+            if *value > counter_value {
+                break;
+            }
+
+            let (_, pool_index, arena) = self.retired.pop_front().unwrap();
+            arena.retired.store(true, Ordering::Relaxed);
+            self.free.push((pool_index, arena.buffer().clone()));
+        }
+    }
+}
+
 impl<A> SubbufferAllocatorState<A>
 where
     A: MemoryAllocator,
 {
-    fn allocate(&mut self, layout: DeviceLayout) -> Result<Subbuffer<[u8]>, MemoryAllocatorError> {
+    /// Returns the index of the pool with the given buffer usage and memory type filter, creating
+    /// it if it doesn't exist yet.
+    fn pool_index(
+        &mut self,
+        buffer_usage: BufferUsage,
+        memory_type_filter: MemoryTypeFilter,
+    ) -> usize {
+        if let Some(index) = self.pools.iter().position(|pool| {
+            pool.buffer_usage == buffer_usage && pool.memory_type_filter == memory_type_filter
+        }) {
+            return index;
+        }
+
+        let properties = self
+            .memory_allocator
+            .device()
+            .physical_device()
+            .properties();
+        let buffer_alignment = [
+            buffer_usage
+                .intersects(BufferUsage::UNIFORM_TEXEL_BUFFER | BufferUsage::STORAGE_TEXEL_BUFFER)
+                .then_some(properties.min_texel_buffer_offset_alignment),
+            buffer_usage
+                .contains(BufferUsage::UNIFORM_BUFFER)
+                .then_some(properties.min_uniform_buffer_offset_alignment),
+            buffer_usage
+                .contains(BufferUsage::STORAGE_BUFFER)
+                .then_some(properties.min_storage_buffer_offset_alignment),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or(DeviceAlignment::MIN);
+
+        self.pools.push(ArenaPool {
+            buffer_usage,
+            memory_type_filter,
+            buffer_alignment,
+            arena: None,
+            free_start: 0,
+            reserve: None,
+        });
+
+        self.pools.len() - 1
+    }
+
+    fn allocate(
+        &mut self,
+        layout: DeviceLayout,
+        pool_index: usize,
+    ) -> Result<Subbuffer<[u8]>, MemoryAllocatorError> {
         let size = layout.size();
-        let alignment = cmp::max(layout.alignment(), self.buffer_alignment);
+        let alignment = cmp::max(layout.alignment(), self.pools[pool_index].buffer_alignment);
 
         loop {
-            if self.arena.is_none() {
+            if self.pools[pool_index].arena.is_none() {
                 // If the requested size is larger than the arenas, we need to resize them.
                 if self.arena_size < size {
                     self.arena_size = size * 2;
-                    // We need to drop our reference to the old pool to make sure the arenas are
-                    // dropped once no longer in use, and replace it with a new pool that will not
-                    // be polluted with the outdates arenas.
-                    self.reserve = None;
+                    // We need to drop our references to the old reserves to make sure the arenas
+                    // are dropped once no longer in use, and replace them with new ones that will
+                    // not be polluted with the outdated arenas.
+                    for pool in &mut self.pools {
+                        pool.reserve = None;
+                    }
                 }
-                self.arena = Some(self.next_arena()?);
-                self.free_start = 0;
+                let arena = self.next_arena(pool_index)?;
+                let pool = &mut self.pools[pool_index];
+                pool.arena = Some(arena);
+                pool.free_start = 0;
             }
 
-            let arena = self.arena.as_ref().unwrap();
+            let pool = &mut self.pools[pool_index];
+            let arena = pool.arena.as_ref().unwrap();
             let allocation = match arena.buffer.memory() {
                 BufferMemory::Normal(a) => a,
-                BufferMemory::Sparse | BufferMemory::External => unreachable!(),
+                BufferMemory::Sparse(_) | BufferMemory::External => unreachable!(),
             };
             let arena_offset = allocation.offset();
             let atom_size = allocation.atom_size().unwrap_or(DeviceAlignment::MIN);
 
             let alignment = cmp::max(alignment, atom_size);
-            let offset = align_up(arena_offset + self.free_start, alignment);
+            let offset = align_up(arena_offset + pool.free_start, alignment);
 
-            if offset + size <= arena_offset + self.arena_size {
+            // The arenas of other pools can be smaller than `self.arena_size`, if it was
+            // increased while they were in use.
+            if offset + size <= arena_offset + arena.buffer().size() {
                 let offset = offset - arena_offset;
-                self.free_start = offset + size;
+                self.allocated_size += offset + size - pool.free_start;
+                pool.free_start = offset + size;
 
                 return Ok(Subbuffer::from_arena(arena.clone(), offset, layout.size()));
             }
 
             // We reached the end of the arena, grab the next one.
-            self.arena = None;
+            pool.arena = None;
         }
     }
 
-    fn next_arena(&mut self) -> Result<Arc<Arena>, MemoryAllocatorError> {
-        if self.reserve.is_none() {
-            self.reserve = Some(Arc::new(ArrayQueue::new(MAX_ARENAS)));
+    fn next_arena(&mut self, pool_index: usize) -> Result<Arc<Arena>, MemoryAllocatorError> {
+        if let Some(timeline) = &mut self.timeline {
+            timeline.recycle();
+
+            // Arenas from before the last resize are too small, so we let them go.
+            let arena_size = self.arena_size;
+            timeline
+                .free
+                .retain(|(_, buffer)| buffer.size() >= arena_size);
+
+            let buffer = match timeline
+                .free
+                .iter()
+                .position(|&(index, _)| index == pool_index)
+            {
+                Some(index) => timeline.free.swap_remove(index).1,
+                None => self.create_arena(pool_index)?,
+            };
+            let arena = Arc::new(Arena {
+                buffer: ManuallyDrop::new(DeviceOwnedDebugWrapper(buffer)),
+                reserve: None,
+                retired: AtomicBool::new(false),
+            });
+            // `create_arena` borrows `self`, so we need to look up the timeline state again.
+            let timeline = self.timeline.as_mut().unwrap();
+            timeline.in_use.push((pool_index, arena.clone()));
+
+            return Ok(arena);
         }
-        let reserve = self.reserve.as_ref().unwrap();
-
-        reserve
-            .pop()
-            .map(Ok)
-            .unwrap_or_else(|| self.create_arena())
-            .map(|buffer| {
-                Arc::new(Arena {
-                    buffer: ManuallyDrop::new(DeviceOwnedDebugWrapper(buffer)),
-                    reserve: reserve.clone(),
-                })
-            })
-    }
-
-    fn create_arena(&self) -> Result<Arc<Buffer>, MemoryAllocatorError> {
-        Buffer::new(
+
+        let reserve = self.pools[pool_index]
+            .reserve
+            .get_or_insert_with(|| Arc::new(ArrayQueue::new(MAX_ARENAS)))
+            .clone();
+        let buffer = match reserve.pop() {
+            Some(buffer) => buffer,
+            None => self.create_arena(pool_index)?,
+        };
+        let arena = Arc::new(Arena {
+            buffer: ManuallyDrop::new(DeviceOwnedDebugWrapper(buffer)),
+            reserve: Some(reserve),
+            retired: AtomicBool::new(false),
+        });
+        self.handed_out.retain(|arena| arena.strong_count() != 0);
+        self.handed_out.push(Arc::downgrade(&arena));
+
+        Ok(arena)
+    }
+
+    fn create_arena(&mut self, pool_index: usize) -> Result<Arc<Buffer>, MemoryAllocatorError> {
+        let pool = &self.pools[pool_index];
+        let buffer = Buffer::new(
             self.memory_allocator.clone(),
             BufferCreateInfo {
-                usage: self.buffer_usage,
+                usage: pool.buffer_usage,
                 ..Default::default()
             },
             AllocationCreateInfo {
-                memory_type_filter: self.memory_type_filter,
+                memory_type_filter: pool.memory_type_filter,
                 ..Default::default()
             },
             DeviceLayout::from_size_alignment(self.arena_size, 1).unwrap(),
@@ -364,27 +819,45 @@ where
             // We don't use sparse-binding, concurrent sharing or external memory, therefore the
             // other errors can't happen.
             _ => unreachable!("{err:?}"),
-        })
+        })?;
+        self.arena_buffers
+            .retain(|buffer| buffer.strong_count() != 0);
+        self.arena_buffers.push(Arc::downgrade(&buffer));
+
+        Ok(buffer)
     }
 }
 
 #[derive(Debug)]
 pub(super) struct Arena {
     buffer: ManuallyDrop<DeviceOwnedDebugWrapper<Arc<Buffer>>>,
-    // Where we return the arena in our `Drop` impl.
-    reserve: Arc<ArrayQueue<Arc<Buffer>>>,
+    // Where we return the arena in our `Drop` impl. This is `None` when the arena is recycled
+    // based on the progress of a timeline semaphore instead.
+    reserve: Option<Arc<ArrayQueue<Arc<Buffer>>>>,
+    // Set once the buffer of the arena has been made available for reuse by timeline recycling or
+    // by a reset, in which case it must not be returned again when the arena is dropped.
+    retired: AtomicBool,
 }
 
 impl Arena {
     pub(super) fn buffer(&self) -> &Arc<Buffer> {
         &self.buffer
     }
+
+    pub(super) fn is_retired(&self) -> bool {
+        self.retired.load(Ordering::Relaxed)
+    }
 }
 
 impl Drop for Arena {
     fn drop(&mut self) {
         let buffer = unsafe { ManuallyDrop::take(&mut self.buffer) }.0;
-        let _ = self.reserve.push(buffer);
+
+        if let Some(reserve) = &self.reserve {
+            if !self.is_retired() {
+                let _ = reserve.push(buffer);
+            }
+        }
     }
 }
 
@@ -402,6 +875,25 @@ impl Hash for Arena {
     }
 }
 
+/// Statistics about the arenas of a [`SubbufferAllocator`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SubbufferAllocatorStatistics {
+    /// The number of arenas that are currently alive, either because they are available for reuse
+    /// or because subbuffers allocated from them are still alive.
+    pub arena_count: usize,
+
+    /// The total size of the arenas that are currently alive, in bytes.
+    pub reserved_size: DeviceSize,
+
+    /// The number of bytes that were allocated since the last call to
+    /// [`SubbufferAllocator::reset`], including the padding needed for alignment.
+    pub allocated_size: DeviceSize,
+
+    /// The greatest value that `allocated_size` has had before any call to
+    /// [`SubbufferAllocator::reset`], including the current value.
+    pub peak_allocated_size: DeviceSize,
+}
+
 /// Parameters to create a new [`SubbufferAllocator`].
 pub struct SubbufferAllocatorCreateInfo {
     /// Initial size of an arena in bytes.
@@ -438,9 +930,55 @@ impl Default for SubbufferAllocatorCreateInfo {
     }
 }
 
+/// Parameters to allocate a subbuffer with [`SubbufferAllocator::allocate_with`].
+#[derive(Clone, Debug)]
+pub struct SubbufferAllocateInfo {
+    /// The buffer usage that the arena of the subbuffer must be created with.
+    ///
+    /// If `None`, the [`buffer_usage`] given when creating the allocator is used.
+    ///
+    /// The default value is `None`.
+    ///
+    /// [`buffer_usage`]: SubbufferAllocatorCreateInfo::buffer_usage
+    pub buffer_usage: Option<BufferUsage>,
+
+    /// The memory type filter that the arena of the subbuffer must be allocated with.
+    ///
+    /// If `None`, the [`memory_type_filter`] given when creating the allocator is used.
+    ///
+    /// The default value is `None`.
+    ///
+    /// [`memory_type_filter`]: SubbufferAllocatorCreateInfo::memory_type_filter
+    pub memory_type_filter: Option<MemoryTypeFilter>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for SubbufferAllocateInfo {
+    #[inline]
+    fn default() -> Self {
+        SubbufferAllocateInfo {
+            buffer_usage: None,
+            memory_type_filter: None,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, CommandBufferBeginInfo, CommandBufferLevel,
+            CommandBufferUsage, CopyBufferInfo, RecordingCommandBuffer,
+        },
+        sync::{
+            now,
+            semaphore::{SemaphoreCreateInfo, SemaphoreSignalInfo},
+            GpuFuture, HostAccessError,
+        },
+    };
 
     #[test]
     fn reserve() {
@@ -477,4 +1015,319 @@ mod tests {
         buffer_allocator.allocate_sized::<u32>().unwrap();
         assert_eq!(buffer_allocator.arena_size(), 8);
     }
+
+    #[test]
+    fn allocate_and_write() {
+        let (device, _) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device));
+
+        let buffer_allocator = SubbufferAllocator::new(
+            memory_allocator,
+            SubbufferAllocatorCreateInfo {
+                arena_size: 64,
+                buffer_usage: BufferUsage::TRANSFER_SRC,
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                ..Default::default()
+            },
+        );
+
+        // Dirty the arena, so that the next arena handed out may contain stale data.
+        drop(
+            buffer_allocator
+                .allocate_from_slice(&[u32::MAX; 16])
+                .unwrap(),
+        );
+
+        let sized = buffer_allocator
+            .allocate_sized_with(|data: &mut [u32; 2]| *data = [1, 2])
+            .unwrap();
+        let from_iter = buffer_allocator.allocate_from_iter(3..6u32).unwrap();
+        let from_slice = buffer_allocator.allocate_from_slice(&[6u32, 7]).unwrap();
+
+        assert_eq!(*sized.read().unwrap(), [1, 2]);
+        assert_eq!(*from_iter.read().unwrap(), [3, 4, 5]);
+        assert_eq!(*from_slice.read().unwrap(), [6, 7]);
+    }
+
+    #[test]
+    fn timeline_recycling() {
+        let (device, _) = gfx_dev_and_queue!(timeline_semaphore);
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+        let semaphore = Arc::new(
+            Semaphore::new(
+                device,
+                SemaphoreCreateInfo {
+                    semaphore_type: SemaphoreType::Timeline,
+                    ..Default::default()
+                },
+            )
+            .unwrap(),
+        );
+
+        let buffer_allocator = SubbufferAllocator::new(
+            memory_allocator,
+            SubbufferAllocatorCreateInfo {
+                arena_size: 64,
+                buffer_usage: BufferUsage::TRANSFER_SRC,
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+        );
+        assert!(buffer_allocator.retire_frame(1).is_err());
+        buffer_allocator.set_timeline(semaphore.clone()).unwrap();
+
+        // Keep every subbuffer alive, like a future that is held for longer than needed would.
+        // With refcount-based recycling, each frame would need a new arena.
+        let mut held = Vec::new();
+        let mut arenas = Vec::new();
+
+        for frame in 1..=16 {
+            let subbuffer = buffer_allocator.allocate_sized::<[u32; 4]>().unwrap();
+            *subbuffer.write().unwrap() = [frame as u32; 4];
+
+            if !arenas.contains(subbuffer.buffer()) {
+                arenas.push(subbuffer.buffer().clone());
+            }
+
+            held.push(subbuffer);
+            buffer_allocator.retire_frame(frame).unwrap();
+
+            // Simulate the device finishing the previous frame.
+            if frame > 1 {
+                unsafe {
+                    semaphore
+                        .signal(SemaphoreSignalInfo {
+                            value: frame - 1,
+                            ..Default::default()
+                        })
+                        .unwrap();
+                }
+            }
+        }
+
+        assert_eq!(arenas.len(), 2);
+        assert!(buffer_allocator.retire_frame(0).is_err());
+    }
+
+    #[test]
+    fn timeline_recycling_submitted() {
+        let (device, queue) = gfx_dev_and_queue!(timeline_semaphore);
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+
+        let semaphore = Arc::new(
+            Semaphore::new(
+                device.clone(),
+                SemaphoreCreateInfo {
+                    semaphore_type: SemaphoreType::Timeline,
+                    ..Default::default()
+                },
+            )
+            .unwrap(),
+        );
+
+        let buffer_allocator = SubbufferAllocator::new(
+            memory_allocator.clone(),
+            SubbufferAllocatorCreateInfo {
+                arena_size: 64,
+                buffer_usage: BufferUsage::TRANSFER_SRC,
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+        );
+        buffer_allocator.set_timeline(semaphore.clone()).unwrap();
+
+        // The futures keep the command buffers, and with them the subbuffers, alive until the end.
+        let mut futures = Vec::new();
+        let mut sources = Vec::new();
+        let mut destinations = Vec::new();
+        let mut arenas = Vec::new();
+
+        for frame in 1..=8 {
+            let source = buffer_allocator.allocate_sized::<[u32; 4]>().unwrap();
+            *source.write().unwrap() = [frame as u32; 4];
+
+            if !arenas.contains(source.buffer()) {
+                arenas.push(source.buffer().clone());
+            }
+
+            let destination = Buffer::new_sized::<[u32; 4]>(
+                memory_allocator.clone(),
+                BufferCreateInfo {
+                    usage: BufferUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                        | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            let mut cbb = RecordingCommandBuffer::new(
+                cb_allocator.clone(),
+                queue.queue_family_index(),
+                CommandBufferLevel::Primary,
+                CommandBufferBeginInfo {
+                    usage: CommandBufferUsage::OneTimeSubmit,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            cbb.copy_buffer(CopyBufferInfo::buffers(source.clone(), destination.clone()))
+                .unwrap();
+
+            futures.push(
+                now(device.clone())
+                    .then_execute(queue.clone(), cbb.end().unwrap())
+                    .unwrap()
+                    .then_signal_fence_and_flush()
+                    .unwrap(),
+            );
+            sources.push(source);
+            destinations.push(destination);
+            buffer_allocator.retire_frame(frame).unwrap();
+
+            // Signal the semaphore once the device has finished the previous frame.
+            if frame > 1 {
+                futures[frame as usize - 2].wait(None).unwrap();
+
+                unsafe {
+                    semaphore
+                        .signal(SemaphoreSignalInfo {
+                            value: frame - 1,
+                            ..Default::default()
+                        })
+                        .unwrap();
+                }
+            }
+        }
+
+        for future in &futures {
+            future.wait(None).unwrap();
+        }
+
+        // The arenas were reused although the subbuffers are still alive.
+        assert_eq!(arenas.len(), 2);
+
+        for (frame, destination) in (1..).zip(&destinations) {
+            assert_eq!(*destination.read().unwrap(), [frame; 4]);
+        }
+
+        // The subbuffers of frames whose arena was handed out again can't be accessed anymore.
+        assert!(matches!(sources[0].read(), Err(HostAccessError::Retired)));
+        assert!(matches!(sources[0].write(), Err(HostAccessError::Retired)));
+        assert!(sources[7].read().is_ok());
+    }
+
+    #[test]
+    fn reset_and_statistics() {
+        let (device, _) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device));
+
+        let buffer_allocator = SubbufferAllocator::new(
+            memory_allocator,
+            SubbufferAllocatorCreateInfo {
+                arena_size: 64,
+                buffer_usage: BufferUsage::TRANSFER_SRC,
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            buffer_allocator.statistics(),
+            SubbufferAllocatorStatistics::default(),
+        );
+
+        // Keep every subbuffer alive, so that without resetting each frame would need a new arena.
+        let mut held = Vec::new();
+
+        for frame in 0..8 {
+            held.push(buffer_allocator.allocate_sized::<[u32; 4]>().unwrap());
+
+            if frame == 0 {
+                let statistics = buffer_allocator.statistics();
+                assert_eq!(statistics.arena_count, 1);
+                assert_eq!(statistics.reserved_size, 64);
+                assert!(statistics.allocated_size >= 16);
+            }
+
+            unsafe { buffer_allocator.reset() };
+        }
+
+        let statistics = buffer_allocator.statistics();
+        assert_eq!(statistics.arena_count, 1);
+        assert_eq!(statistics.allocated_size, 0);
+        assert!(statistics.peak_allocated_size >= 16);
+
+        // Without resetting, long-lived subbuffers pin their arenas.
+        held.clear();
+        for _ in 0..4 {
+            held.push(buffer_allocator.allocate_sized::<[u32; 16]>().unwrap());
+        }
+        assert_eq!(buffer_allocator.statistics().arena_count, 4);
+    }
+
+    #[test]
+    fn allocate_with() {
+        let (device, _) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device));
+
+        let buffer_allocator = SubbufferAllocator::new(
+            memory_allocator,
+            SubbufferAllocatorCreateInfo {
+                arena_size: 1024,
+                buffer_usage: BufferUsage::TRANSFER_SRC,
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+        );
+        let layout = DeviceLayout::from_size_alignment(4, 4).unwrap();
+        let readback_info = SubbufferAllocateInfo {
+            buffer_usage: Some(BufferUsage::TRANSFER_DST),
+            memory_type_filter: Some(
+                MemoryTypeFilter::PREFER_HOST | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+            ),
+            ..Default::default()
+        };
+        let random_access_info = SubbufferAllocateInfo {
+            memory_type_filter: readback_info.memory_type_filter,
+            ..Default::default()
+        };
+
+        let upload = buffer_allocator.allocate(layout).unwrap();
+        let readback = buffer_allocator
+            .allocate_with(layout, &readback_info)
+            .unwrap();
+        let random_access = buffer_allocator
+            .allocate_with(layout, &random_access_info)
+            .unwrap();
+        let upload2 = buffer_allocator
+            .allocate_with(layout, &SubbufferAllocateInfo::default())
+            .unwrap();
+        let readback2 = buffer_allocator
+            .allocate_with(layout, &readback_info)
+            .unwrap();
+
+        assert_eq!(upload.buffer().usage(), BufferUsage::TRANSFER_SRC);
+        assert_eq!(readback.buffer().usage(), BufferUsage::TRANSFER_DST);
+        assert_eq!(random_access.buffer().usage(), BufferUsage::TRANSFER_SRC);
+
+        // Each combination of usage and memory type filter gets its own arenas.
+        assert_ne!(upload.buffer(), random_access.buffer());
+        assert_ne!(readback.buffer(), random_access.buffer());
+        assert_eq!(upload.buffer(), upload2.buffer());
+        assert_eq!(readback.buffer(), readback2.buffer());
+        assert_eq!(buffer_allocator.statistics().arena_count, 3);
+    }
 }
@@ -0,0 +1,266 @@
+use super::{
+    sys::BufferCreateInfo, AllocateBufferError, Buffer, BufferContents, BufferUsage, Subbuffer,
+};
+use crate::{
+    command_buffer::{CopyBufferInfoTyped, RecordingCommandBuffer},
+    memory::allocator::{AllocationCreateInfo, MemoryAllocator},
+    DeviceSize, Validated,
+};
+use std::{cmp, sync::Arc};
+
+/// A buffer of elements of type `T` whose length can change, for data such as vertices or indices
+/// that change in size from frame to frame.
+///
+/// The elements are stored in a backing buffer whose capacity can be larger than the length. When
+/// [`resize`] is called with a length that exceeds the capacity, a new backing buffer is
+/// allocated, with at least twice the previous capacity so that repeated growth doesn't churn the
+/// allocator. The backing buffer is never shrunk.
+///
+/// The backing buffer always has the `TRANSFER_SRC` and `TRANSFER_DST` usages, in addition to the
+/// usage that was given when creating the `DynamicBuffer`, so that the contents can be copied to a
+/// new backing buffer.
+///
+/// # Examples
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use vulkano::{
+/// #     buffer::{BufferCreateInfo, BufferUsage, DynamicBuffer},
+/// #     command_buffer::RecordingCommandBuffer,
+/// #     memory::allocator::{AllocationCreateInfo, MemoryAllocator},
+/// # };
+/// # fn example(
+/// #     memory_allocator: Arc<dyn MemoryAllocator>,
+/// #     builder: &mut RecordingCommandBuffer,
+/// # ) {
+/// let mut vertices = DynamicBuffer::<[f32; 3]>::new(
+///     memory_allocator,
+///     BufferCreateInfo {
+///         usage: BufferUsage::VERTEX_BUFFER,
+///         ..Default::default()
+///     },
+///     AllocationCreateInfo::default(),
+///     1024,
+/// )
+/// .unwrap();
+///
+/// // The mesh grew this frame. Keep the existing vertices, and append the new ones afterwards.
+/// vertices.resize(4096, true, builder).unwrap();
+///
+/// builder.bind_vertex_buffers(0, vertices.buffer()).unwrap();
+/// # }
+/// ```
+///
+/// [`resize`]: Self::resize
+#[derive(Debug)]
+pub struct DynamicBuffer<T> {
+    allocator: Arc<dyn MemoryAllocator>,
+    create_info: BufferCreateInfo,
+    allocation_info: AllocationCreateInfo,
+    backing: Subbuffer<[T]>,
+    len: DeviceSize,
+}
+
+impl<T> DynamicBuffer<T>
+where
+    T: BufferContents,
+{
+    /// Creates a new `DynamicBuffer` with a length and capacity of `len` elements.
+    ///
+    /// `create_info.usage` is extended with `TRANSFER_SRC` and `TRANSFER_DST`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.size` is not zero.
+    /// - Panics if `len` is zero.
+    pub fn new(
+        allocator: Arc<dyn MemoryAllocator>,
+        mut create_info: BufferCreateInfo,
+        allocation_info: AllocationCreateInfo,
+        len: DeviceSize,
+    ) -> Result<Self, Validated<AllocateBufferError>> {
+        create_info.usage |= BufferUsage::TRANSFER_SRC | BufferUsage::TRANSFER_DST;
+
+        let backing = Buffer::new_slice(
+            allocator.clone(),
+            create_info.clone(),
+            allocation_info.clone(),
+            len,
+        )?;
+
+        Ok(DynamicBuffer {
+            allocator,
+            create_info,
+            allocation_info,
+            backing,
+            len,
+        })
+    }
+
+    /// Returns the number of elements in the buffer.
+    #[inline]
+    pub fn len(&self) -> DeviceSize {
+        self.len
+    }
+
+    /// Returns the number of elements that the buffer can hold without allocating a new backing
+    /// buffer.
+    #[inline]
+    pub fn capacity(&self) -> DeviceSize {
+        self.backing.len()
+    }
+
+    /// Returns a subbuffer spanning the first [`len`] elements of the backing buffer.
+    ///
+    /// The returned subbuffer is not affected by later calls to [`resize`], so it should be
+    /// retrieved again after resizing.
+    ///
+    /// [`len`]: Self::len
+    /// [`resize`]: Self::resize
+    #[inline]
+    pub fn buffer(&self) -> Subbuffer<[T]> {
+        self.backing.clone().slice(..self.len)
+    }
+
+    /// Returns a subbuffer spanning the whole backing buffer, including the elements past
+    /// [`len`].
+    ///
+    /// [`len`]: Self::len
+    #[inline]
+    pub fn backing_buffer(&self) -> &Subbuffer<[T]> {
+        &self.backing
+    }
+
+    /// Changes the length of the buffer to `new_len` elements.
+    ///
+    /// If `new_len` doesn't exceed the [capacity], only the length is changed, and `None` is
+    /// returned. Otherwise, a new backing buffer is allocated with a capacity of `new_len` or
+    /// twice the previous capacity, whichever is greater. If `preserve` is `true`, a copy of the
+    /// first [`len`] elements from the previous backing buffer to the new one is recorded to
+    /// `builder`. The contents of the buffer are undefined otherwise, and so are the elements
+    /// that were added in either case.
+    ///
+    /// When a new backing buffer is allocated, the previous one is returned. `builder` keeps the
+    /// previous backing buffer alive until the recorded copy has finished executing, so the
+    /// returned subbuffer can simply be dropped, unless it is still needed for something else.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `new_len` is zero.
+    ///
+    /// [capacity]: Self::capacity
+    /// [`len`]: Self::len
+    pub fn resize(
+        &mut self,
+        new_len: DeviceSize,
+        preserve: bool,
+        builder: &mut RecordingCommandBuffer,
+    ) -> Result<Option<Subbuffer<[T]>>, Validated<AllocateBufferError>> {
+        assert_ne!(new_len, 0);
+
+        if new_len <= self.capacity() {
+            self.len = new_len;
+
+            return Ok(None);
+        }
+
+        let new_capacity = cmp::max(new_len, self.capacity().saturating_mul(2));
+        let backing = Buffer::new_slice(
+            self.allocator.clone(),
+            self.create_info.clone(),
+            self.allocation_info.clone(),
+            new_capacity,
+        )?;
+
+        if preserve {
+            builder.copy_buffer(CopyBufferInfoTyped::buffers(
+                self.buffer(),
+                backing.clone().slice(..self.len),
+            ))?;
+        }
+
+        self.len = new_len;
+
+        Ok(Some(std::mem::replace(&mut self.backing, backing)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, CommandBufferBeginInfo, CommandBufferLevel,
+            CommandBufferUsage,
+        },
+        memory::allocator::{MemoryTypeFilter, StandardMemoryAllocator},
+        sync::{self, GpuFuture},
+    };
+
+    #[test]
+    fn resize() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+
+        let mut buffer = DynamicBuffer::<u32>::new(
+            memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::VERTEX_BUFFER,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                ..Default::default()
+            },
+            4,
+        )
+        .unwrap();
+        assert!(buffer
+            .backing_buffer()
+            .buffer()
+            .usage()
+            .contains(BufferUsage::TRANSFER_SRC | BufferUsage::TRANSFER_DST));
+        buffer
+            .buffer()
+            .write()
+            .unwrap()
+            .copy_from_slice(&[1, 2, 3, 4]);
+
+        let mut builder = RecordingCommandBuffer::new(
+            command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert!(buffer.resize(3, true, &mut builder).unwrap().is_none());
+        assert_eq!((buffer.len(), buffer.capacity()), (3, 4));
+
+        let previous = buffer.resize(5, true, &mut builder).unwrap();
+        assert!(previous.is_some());
+        assert_eq!((buffer.len(), buffer.capacity()), (5, 8));
+        drop(previous);
+
+        assert!(buffer.resize(8, true, &mut builder).unwrap().is_none());
+
+        let command_buffer = builder.end().unwrap();
+        sync::now(device)
+            .then_execute(queue, command_buffer)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        assert_eq!(&buffer.buffer().read().unwrap()[..3], &[1, 2, 3]);
+    }
+}
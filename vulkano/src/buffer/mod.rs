@@ -35,7 +35,9 @@
 //! need to download data very frequently, or if you need to allocate a lot of intermediary buffers
 //! that are only accessed by the device. On the other hand, if you need to upload some data just
 //! once, or you can keep reusing the same buffer (because its size is unchanging) it's best to
-//! use a dedicated `Buffer` for that.
+//! use a dedicated `Buffer` for that. If the data is kept on the device but changes in size, such
+//! as vertices of a mesh that changes every frame, a [`DynamicBuffer`] can be used to avoid
+//! allocating a new buffer each time.
 //!
 //! # Buffer usage
 //!
@@ -69,24 +71,35 @@
 //! [the `view` module]: view
 //! [the `shader` module documentation]: crate::shader
 
-pub use self::{subbuffer::*, sys::*, usage::*};
+pub use self::{dynamic::*, subbuffer::*, sys::*, usage::*};
 use crate::{
-    device::{physical::PhysicalDevice, Device, DeviceOwned},
+    command_buffer::{
+        allocator::CommandBufferAllocator, CommandBuffer, CommandBufferBeginInfo,
+        CommandBufferExecError, CommandBufferLevel, CommandBufferUsage, CopyBufferInfo,
+        RecordingCommandBuffer,
+    },
+    device::{physical::PhysicalDevice, Device, DeviceOwned, Queue},
     macros::{vulkan_bitflags, vulkan_enum},
     memory::{
         allocator::{
             AllocationCreateInfo, AllocationType, DeviceLayout, MemoryAllocator,
-            MemoryAllocatorError,
+            MemoryAllocatorError, MemoryTypeFilter,
         },
-        DedicatedAllocation, ExternalMemoryHandleType, ExternalMemoryHandleTypes,
-        ExternalMemoryProperties, MemoryRequirements, ResourceMemory,
+        DedicatedAllocation, DeviceAlignment, DeviceMemory, ExternalMemoryHandleType,
+        ExternalMemoryHandleTypes, ExternalMemoryProperties, MemoryRequirements, ResourceMemory,
+        SparseBufferMemoryBind,
     },
     range_map::RangeMap,
-    sync::{future::AccessError, AccessConflict, CurrentAccess, Sharing},
+    sync::{
+        self,
+        future::{AccessError, GpuFuture},
+        AccessConflict, CurrentAccess, Sharing,
+    },
     DeviceSize, NonNullDeviceAddress, NonZeroDeviceSize, Requires, RequiresAllOf, RequiresOneOf,
     Validated, ValidationError, Version, VulkanError, VulkanObject,
 };
-use parking_lot::{Mutex, MutexGuard};
+use once_cell::sync::OnceCell;
+use parking_lot::{Condvar, Mutex, MutexGuard};
 use smallvec::SmallVec;
 use std::{
     error::Error,
@@ -94,9 +107,11 @@ use std::{
     hash::{Hash, Hasher},
     ops::Range,
     sync::Arc,
+    time::Instant,
 };
 
 pub mod allocator;
+mod dynamic;
 pub mod subbuffer;
 pub mod sys;
 mod usage;
@@ -205,6 +220,10 @@ pub struct Buffer {
     inner: RawBuffer,
     memory: BufferMemory,
     state: Mutex<BufferState>,
+    // Notified whenever a lock in `state` is released.
+    state_released: Condvar,
+    // Set the first time the device address is queried, as it never changes.
+    device_address: OnceCell<NonNullDeviceAddress>,
 }
 
 /// The type of backing memory that a buffer can have.
@@ -216,15 +235,83 @@ pub enum BufferMemory {
     /// [`bind_memory`]: RawBuffer::bind_memory
     Normal(ResourceMemory),
 
-    /// The buffer is backed by sparse memory, bound with [`bind_sparse`].
+    /// The buffer is backed by sparse memory, bound with [`bind_sparse_buffer`].
     ///
-    /// [`bind_sparse`]: crate::device::QueueGuard::bind_sparse
-    Sparse,
+    /// [`bind_sparse_buffer`]: crate::device::Queue::bind_sparse_buffer
+    Sparse(SparseBufferMemory),
 
     /// The buffer is backed by memory not managed by vulkano.
     External,
 }
 
+/// The memory that is bound to a buffer that is backed by sparse memory.
+///
+/// This keeps track of which byte ranges of the buffer currently have memory bound to them, and
+/// keeps that memory alive for as long as it is bound.
+#[derive(Debug)]
+pub struct SparseBufferMemory {
+    // The value stores the bound memory and the offset into it that corresponds to offset 0 of
+    // the buffer (wrapping), so that splitting or merging ranges keeps the values correct.
+    bindings: Mutex<RangeMap<DeviceSize, (Arc<DeviceMemory>, DeviceSize)>>,
+}
+
+impl SparseBufferMemory {
+    pub(crate) fn new() -> Self {
+        SparseBufferMemory {
+            bindings: Mutex::new(RangeMap::new()),
+        }
+    }
+
+    /// Returns the ranges of the buffer that currently have memory bound to them, along with the
+    /// memory and the offset into that memory where each range starts.
+    pub fn bindings(&self) -> Vec<(Range<DeviceSize>, Arc<DeviceMemory>, DeviceSize)> {
+        self.bindings
+            .lock()
+            .iter()
+            .map(|(range, (memory, base))| {
+                (
+                    range.clone(),
+                    memory.clone(),
+                    base.wrapping_add(range.start),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns whether all of `range` currently has memory bound to it.
+    pub fn is_bound(&self, range: Range<DeviceSize>) -> bool {
+        let bindings = self.bindings.lock();
+        let mut next = range.start;
+
+        for (bound_range, _) in bindings.range(&range) {
+            if bound_range.start > next {
+                return false;
+            }
+
+            next = bound_range.end;
+        }
+
+        next >= range.end
+    }
+
+    /// Records the effect of `binds` having been executed on the device.
+    pub(crate) fn apply(&self, binds: &[SparseBufferMemoryBind]) {
+        let mut bindings = self.bindings.lock();
+
+        for bind in binds {
+            let range = bind.offset..bind.offset + bind.size;
+
+            match &bind.memory {
+                Some((memory, memory_offset)) => bindings.insert(
+                    range,
+                    (memory.clone(), memory_offset.wrapping_sub(bind.offset)),
+                ),
+                None => bindings.remove(range),
+            }
+        }
+    }
+}
+
 impl Buffer {
     /// Creates a new `Buffer` and writes `data` in it. Returns a [`Subbuffer`] spanning the whole
     /// buffer.
@@ -259,9 +346,16 @@ impl Buffer {
     /// Creates a new `Buffer` and writes all elements of `iter` in it. Returns a [`Subbuffer`]
     /// spanning the whole buffer.
     ///
+    /// The elements are written one at a time. If the data is already in a slice or `Vec`, then
+    /// [`from_slice`] writes it with a single copy instead, which is considerably faster for
+    /// large amounts of data.
+    ///
     /// > **Note**: This only works with memory types that are host-visible. If you want to upload
-    /// > data to a buffer allocated in device-local memory, you will need to create a staging
-    /// > buffer and copy the contents over.
+    /// > data to a buffer allocated in device-local memory, you can use
+    /// > [`from_iter_with_staging`] instead.
+    ///
+    /// [`from_slice`]: Self::from_slice
+    /// [`from_iter_with_staging`]: Self::from_iter_with_staging
     ///
     /// # Panics
     ///
@@ -298,6 +392,148 @@ impl Buffer {
         Ok(buffer)
     }
 
+    /// Creates a new `Buffer` and copies `data` into it. Returns a [`Subbuffer`] spanning the
+    /// whole buffer.
+    ///
+    /// This has the same result as [`from_iter`] with `data.iter().copied()`, but copies all of
+    /// the data at once.
+    ///
+    /// If the memory is not [host-coherent], the written range is flushed once the data has been
+    /// copied, as with [`Subbuffer::write`], so it is visible to the device without any further
+    /// action.
+    ///
+    /// > **Note**: This only works with memory types that are host-visible. If you want to upload
+    /// > data to a buffer allocated in device-local memory, you will need to create a staging
+    /// > buffer and copy the contents over.
+    ///
+    /// [`from_iter`]: Self::from_iter
+    /// [host-coherent]: crate::memory::MemoryPropertyFlags::HOST_COHERENT
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.size` is not zero.
+    /// - Panics if the chosen memory type is not host-visible.
+    /// - Panics if `data` is empty.
+    pub fn from_slice<T>(
+        allocator: Arc<dyn MemoryAllocator>,
+        create_info: BufferCreateInfo,
+        allocation_info: AllocationCreateInfo,
+        data: &[T],
+    ) -> Result<Subbuffer<[T]>, Validated<AllocateBufferError>>
+    where
+        T: BufferContents + Copy,
+    {
+        let buffer = Buffer::new_slice(
+            allocator,
+            create_info,
+            allocation_info,
+            data.len().try_into().unwrap(),
+        )?;
+
+        {
+            let mut write_guard = buffer.write().unwrap();
+            write_guard.copy_from_slice(data);
+        }
+
+        Ok(buffer)
+    }
+
+    /// Creates a new `Buffer` in device-local memory and uploads all elements of `iter` to it.
+    /// Returns a [`Subbuffer`] spanning the whole buffer, along with a future representing the
+    /// upload.
+    ///
+    /// The buffer is allocated with [`MemoryTypeFilter::PREFER_DEVICE`]. If the chosen memory
+    /// type happens to be host-visible, which is common on integrated GPUs, then the elements are
+    /// written to the buffer directly, and the returned future is already signaled. Otherwise,
+    /// the elements are written to a temporary host-visible staging buffer, and a command buffer
+    /// that copies them over is executed on `queue`.
+    ///
+    /// `BufferUsage::TRANSFER_DST` is added to `create_info.usage` automatically. The returned
+    /// future must be flushed and signaled before the buffer can be used. The staging buffer is
+    /// owned by the command buffer held inside the future, so it is freed as soon as the future
+    /// is cleaned up after the copy has finished, regardless of how long the returned
+    /// `Subbuffer` is kept alive.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.size` is not zero.
+    /// - Panics if `iter` is empty.
+    pub fn from_iter_with_staging<T, I>(
+        memory_allocator: Arc<dyn MemoryAllocator>,
+        command_buffer_allocator: Arc<dyn CommandBufferAllocator>,
+        queue: &Arc<Queue>,
+        mut create_info: BufferCreateInfo,
+        iter: I,
+    ) -> Result<(Subbuffer<[T]>, Box<dyn GpuFuture>), Validated<StagingUploadError>>
+    where
+        T: BufferContents,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iter.into_iter();
+        create_info.usage |= BufferUsage::TRANSFER_DST;
+
+        let buffer = Buffer::new_slice(
+            memory_allocator.clone(),
+            create_info,
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
+                ..Default::default()
+            },
+            iter.len().try_into().unwrap(),
+        )
+        .map_err(|err| err.map(StagingUploadError::AllocateBuffer))?;
+        let device = buffer.device().clone();
+
+        if buffer.mapped_slice().is_ok() {
+            {
+                let mut write_guard = buffer.write().unwrap();
+
+                for (o, i) in write_guard.iter_mut().zip(iter) {
+                    *o = i;
+                }
+            }
+
+            return Ok((buffer, sync::now(device).boxed()));
+        }
+
+        let staging_buffer = Buffer::from_iter(
+            memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_SRC,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+            iter,
+        )
+        .map_err(|err| err.map(StagingUploadError::AllocateBuffer))?;
+
+        let mut builder = RecordingCommandBuffer::new(
+            command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .map_err(|err| err.map(StagingUploadError::RecordCommandBuffer))?;
+        builder.copy_buffer(CopyBufferInfo::buffers(staging_buffer, buffer.clone()))?;
+        let command_buffer = builder
+            .end()
+            .map_err(|err| err.map(StagingUploadError::RecordCommandBuffer))?;
+
+        let future = sync::now(device)
+            .then_execute(queue.clone(), command_buffer)
+            .map_err(StagingUploadError::Execute)?;
+
+        Ok((buffer, future.boxed()))
+    }
+
     /// Creates a new uninitialized `Buffer` for sized data. Returns a [`Subbuffer`] spanning the
     /// whole buffer.
     ///
@@ -370,15 +606,128 @@ impl Buffer {
         Ok(unsafe { buffer.reinterpret_unchecked() })
     }
 
+    /// Creates a new `Buffer` for a slice, with all of its bytes set to zero. Returns a
+    /// [`Subbuffer`] spanning the `len` elements of the slice.
+    ///
+    /// Because `fill_buffer` can only fill a multiple of 4 bytes, the size of the buffer is
+    /// rounded up to a multiple of 4. The returned subbuffer leaves out this padding, so it may
+    /// not span the whole buffer.
+    ///
+    /// If the chosen memory type is host-visible, the buffer is zeroed from the host before it is
+    /// returned. Otherwise, a [`fill_buffer`] command that zeroes the buffer is recorded to
+    /// `builder`, and the buffer must not be accessed before that command has been executed.
+    /// In that case, `create_info.usage` must contain [`BufferUsage::TRANSFER_DST`], unless
+    /// `zero_info.add_transfer_dst_usage` is `true`.
+    ///
+    /// [`fill_buffer`]: RecordingCommandBuffer::fill_buffer
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.size` is not zero.
+    /// - Panics if `len` is zero.
+    pub fn new_zeroed_slice<T>(
+        allocator: Arc<dyn MemoryAllocator>,
+        create_info: BufferCreateInfo,
+        allocation_info: AllocationCreateInfo,
+        len: DeviceSize,
+        zero_info: ZeroInitializeInfo,
+        builder: &mut RecordingCommandBuffer,
+    ) -> Result<Subbuffer<[T]>, Validated<AllocateBufferError>>
+    where
+        T: BufferContents,
+    {
+        Buffer::new_zeroed_unsized(
+            allocator,
+            create_info,
+            allocation_info,
+            len,
+            zero_info,
+            builder,
+        )
+    }
+
+    /// Creates a new `Buffer` for unsized data, with all of its bytes set to zero. Returns a
+    /// [`Subbuffer`] spanning the data, which excludes the padding that the buffer may have.
+    ///
+    /// See [`new_zeroed_slice`] for how the buffer is zeroed and padded.
+    ///
+    /// [`new_zeroed_slice`]: Self::new_zeroed_slice
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.size` is not zero.
+    /// - Panics if `len` is zero.
+    pub fn new_zeroed_unsized<T>(
+        allocator: Arc<dyn MemoryAllocator>,
+        mut create_info: BufferCreateInfo,
+        allocation_info: AllocationCreateInfo,
+        len: DeviceSize,
+        zero_info: ZeroInitializeInfo,
+        builder: &mut RecordingCommandBuffer,
+    ) -> Result<Subbuffer<T>, Validated<AllocateBufferError>>
+    where
+        T: BufferContents + ?Sized,
+    {
+        let ZeroInitializeInfo {
+            add_transfer_dst_usage,
+            _ne: _,
+        } = zero_info;
+
+        if add_transfer_dst_usage {
+            create_info.usage |= BufferUsage::TRANSFER_DST;
+        }
+
+        let len = NonZeroDeviceSize::new(len).expect("empty slices are not valid buffer contents");
+        let layout = T::LAYOUT.layout_for_len(len).unwrap();
+        // `fill_buffer` can only fill a multiple of 4 bytes, so the buffer is padded to that.
+        let fill_layout = layout
+            .align_to(DeviceAlignment::new(4).unwrap())
+            .unwrap()
+            .pad_to_alignment();
+        let buffer = Subbuffer::new(Buffer::new(
+            allocator,
+            create_info,
+            allocation_info,
+            fill_layout,
+        )?);
+
+        if buffer.mapped_slice().is_ok() {
+            buffer.write().unwrap().fill(0);
+        } else {
+            if !buffer
+                .buffer()
+                .usage()
+                .intersects(BufferUsage::TRANSFER_DST)
+            {
+                return Err(Box::new(ValidationError {
+                    context: "create_info.usage".into(),
+                    problem: "does not contain `BufferUsage::TRANSFER_DST`, but the buffer was \
+                        allocated in memory that is not host-visible, so it must be zeroed with \
+                        `fill_buffer`"
+                        .into(),
+                    ..Default::default()
+                })
+                .into());
+            }
+
+            builder.fill_buffer(buffer.clone().reinterpret(), 0)?;
+        }
+
+        Ok(buffer.slice(..layout.size()).reinterpret())
+    }
+
     /// Creates a new uninitialized `Buffer` with the given `layout`.
     ///
+    /// If `create_info.flags` contains [`BufferCreateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY`], then
+    /// [`AllocationCreateInfo::device_address_capture_replay`] is set automatically.
+    ///
     /// # Panics
     ///
     /// - Panics if `create_info.size` is not zero.
     pub fn new(
         allocator: Arc<dyn MemoryAllocator>,
         mut create_info: BufferCreateInfo,
-        allocation_info: AllocationCreateInfo,
+        mut allocation_info: AllocationCreateInfo,
         layout: DeviceLayout,
     ) -> Result<Arc<Self>, Validated<AllocateBufferError>> {
         // TODO: Enable once sparse binding materializes
@@ -392,6 +741,13 @@ impl Buffer {
 
         create_info.size = layout.size();
 
+        if create_info
+            .flags
+            .intersects(BufferCreateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY)
+        {
+            allocation_info.device_address_capture_replay = true;
+        }
+
         let raw_buffer =
             RawBuffer::new(allocator.device().clone(), create_info).map_err(|err| match err {
                 Validated::Error(err) => Validated::Error(AllocateBufferError::CreateBuffer(err)),
@@ -428,6 +784,8 @@ impl Buffer {
             inner,
             memory,
             state,
+            state_released: Condvar::new(),
+            device_address: OnceCell::new(),
         }
     }
 
@@ -474,8 +832,14 @@ impl Buffer {
     }
 
     /// Returns the device address for this buffer.
-    // TODO: Caching?
+    ///
+    /// The address is queried from the device the first time this is called, and is cached
+    /// afterwards.
     pub fn device_address(&self) -> Result<NonNullDeviceAddress, Box<ValidationError>> {
+        if let Some(&device_address) = self.device_address.get() {
+            return Ok(device_address);
+        }
+
         self.validate_device_address()?;
 
         unsafe { Ok(self.device_address_unchecked()) }
@@ -508,6 +872,12 @@ impl Buffer {
 
     #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
     pub unsafe fn device_address_unchecked(&self) -> NonNullDeviceAddress {
+        *self
+            .device_address
+            .get_or_init(|| self.query_device_address())
+    }
+
+    unsafe fn query_device_address(&self) -> NonNullDeviceAddress {
         let device = self.device();
 
         let info_vk = ash::vk::BufferDeviceAddressInfo {
@@ -530,9 +900,100 @@ impl Buffer {
         NonNullDeviceAddress::new(ptr).unwrap()
     }
 
+    /// Returns the opaque capture address of the buffer.
+    ///
+    /// When replaying a capture, the value can be given as
+    /// [`BufferCreateInfo::opaque_capture_address`] to create a buffer with the same device
+    /// address. This is only meaningful if the buffer was created with the
+    /// [`BufferCreateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY`] flag.
+    ///
+    /// The device API version must be at least 1.2, or the [`khr_buffer_device_address`]
+    /// extension must be enabled on the device. The
+    /// [`buffer_device_address_capture_replay`][feature] feature must also be enabled.
+    ///
+    /// [`khr_buffer_device_address`]: crate::device::DeviceExtensions::khr_buffer_device_address
+    /// [feature]: crate::device::DeviceFeatures::buffer_device_address_capture_replay
+    pub fn opaque_capture_address(&self) -> Result<u64, Box<ValidationError>> {
+        self.validate_opaque_capture_address()?;
+
+        unsafe { Ok(self.opaque_capture_address_unchecked()) }
+    }
+
+    fn validate_opaque_capture_address(&self) -> Result<(), Box<ValidationError>> {
+        let device = self.device();
+
+        if !(device.api_version() >= Version::V1_2
+            || device.enabled_extensions().khr_buffer_device_address)
+        {
+            return Err(Box::new(ValidationError {
+                requires_one_of: RequiresOneOf(&[
+                    RequiresAllOf(&[Requires::APIVersion(Version::V1_2)]),
+                    RequiresAllOf(&[Requires::DeviceExtension("khr_buffer_device_address")]),
+                ]),
+                ..Default::default()
+            }));
+        }
+
+        if !device
+            .enabled_features()
+            .buffer_device_address_capture_replay
+        {
+            return Err(Box::new(ValidationError {
+                requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceFeature(
+                    "buffer_device_address_capture_replay",
+                )])]),
+                vuids: &["VUID-vkGetBufferOpaqueCaptureAddress-None-03326"],
+                ..Default::default()
+            }));
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn opaque_capture_address_unchecked(&self) -> u64 {
+        let device = self.device();
+
+        let info_vk = ash::vk::BufferDeviceAddressInfo {
+            buffer: self.handle(),
+            ..Default::default()
+        };
+
+        let fns = device.fns();
+        let f = if device.api_version() >= Version::V1_2 {
+            fns.v1_2.get_buffer_opaque_capture_address
+        } else {
+            fns.khr_buffer_device_address
+                .get_buffer_opaque_capture_address_khr
+        };
+        f(device.handle(), &info_vk)
+    }
+
     pub(crate) fn state(&self) -> MutexGuard<'_, BufferState> {
         self.state.lock()
     }
+
+    /// Wakes up the threads waiting for a lock on the buffer to be released. This must be called
+    /// after releasing a lock.
+    pub(crate) fn notify_state_released(&self) {
+        self.state_released.notify_all();
+    }
+
+    /// Blocks the current thread until a lock on the buffer is released, or until `deadline` is
+    /// reached. Returns `false` if the deadline was reached.
+    pub(crate) fn wait_state_released(
+        &self,
+        state: &mut MutexGuard<'_, BufferState>,
+        deadline: Option<Instant>,
+    ) -> bool {
+        match deadline {
+            Some(deadline) => !self.state_released.wait_until(state, deadline).timed_out(),
+            None => {
+                self.state_released.wait(state);
+                true
+            }
+        }
+    }
 }
 
 unsafe impl VulkanObject for Buffer {
@@ -566,6 +1027,30 @@ impl Hash for Buffer {
     }
 }
 
+/// Parameters for creating a zero-initialized buffer with [`Buffer::new_zeroed_slice`] or
+/// [`Buffer::new_zeroed_unsized`].
+#[derive(Clone, Debug)]
+pub struct ZeroInitializeInfo {
+    /// Whether to add [`BufferUsage::TRANSFER_DST`] to the usage of the buffer, so that it can
+    /// always be zeroed with `fill_buffer` if the chosen memory type is not host-visible. The
+    /// usage is added even if the buffer ends up being zeroed from the host.
+    ///
+    /// The default value is `false`.
+    pub add_transfer_dst_usage: bool,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for ZeroInitializeInfo {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            add_transfer_dst_usage: false,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
 /// Error that can happen when allocating a new buffer.
 #[derive(Clone, Debug)]
 pub enum AllocateBufferError {
@@ -600,10 +1085,50 @@ impl From<AllocateBufferError> for Validated<AllocateBufferError> {
     }
 }
 
+/// Error that can happen when uploading data to a new buffer with
+/// [`Buffer::from_iter_with_staging`].
+#[derive(Clone, Debug)]
+pub enum StagingUploadError {
+    AllocateBuffer(AllocateBufferError),
+    RecordCommandBuffer(VulkanError),
+    Execute(CommandBufferExecError),
+}
+
+impl Error for StagingUploadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::AllocateBuffer(err) => Some(err),
+            Self::RecordCommandBuffer(err) => Some(err),
+            Self::Execute(err) => Some(err),
+        }
+    }
+}
+
+impl Display for StagingUploadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AllocateBuffer(_) => write!(f, "allocating a buffer failed"),
+            Self::RecordCommandBuffer(_) => {
+                write!(f, "recording the command buffer for the copy failed")
+            }
+            Self::Execute(_) => write!(f, "executing the command buffer for the copy failed"),
+        }
+    }
+}
+
+impl From<StagingUploadError> for Validated<StagingUploadError> {
+    fn from(err: StagingUploadError) -> Self {
+        Self::Error(err)
+    }
+}
+
 /// The current state of a buffer.
 #[derive(Debug)]
 pub(crate) struct BufferState {
     ranges: RangeMap<DeviceSize, BufferRangeState>,
+    // The command buffers whose pending submission holds locks on the buffer, and has a fence that
+    // host accesses can wait for.
+    pending_command_buffers: Vec<Arc<CommandBuffer>>,
 }
 
 impl BufferState {
@@ -620,9 +1145,42 @@ impl BufferState {
             )]
             .into_iter()
             .collect(),
+            pending_command_buffers: Vec::new(),
         }
     }
 
+    /// Returns the command buffers whose pending submission holds locks on `range` of the buffer
+    /// that conflict with a host access, and whose fence can be waited for.
+    pub(crate) fn pending_command_buffers(
+        &self,
+        buffer: &Buffer,
+        range: Range<DeviceSize>,
+        write: bool,
+    ) -> Vec<Arc<CommandBuffer>> {
+        self.pending_command_buffers
+            .iter()
+            .filter(|command_buffer| {
+                let resources_usage = command_buffer.resources_usage();
+                let usage = &resources_usage.buffers[resources_usage.buffer_indices[buffer]];
+
+                usage
+                    .ranges
+                    .range(&range)
+                    .any(|(_range, range_usage)| write || range_usage.mutable)
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub(crate) fn add_pending_command_buffer(&mut self, command_buffer: Arc<CommandBuffer>) {
+        self.pending_command_buffers.push(command_buffer);
+    }
+
+    pub(crate) fn remove_pending_command_buffer(&mut self, command_buffer: &Arc<CommandBuffer>) {
+        self.pending_command_buffers
+            .retain(|pending| !Arc::ptr_eq(pending, command_buffer));
+    }
+
     pub(crate) fn check_cpu_read(&self, range: Range<DeviceSize>) -> Result<(), AccessConflict> {
         for (_range, state) in self.ranges.range(&range) {
             match &state.current_access {
@@ -635,7 +1193,12 @@ impl BufferState {
         Ok(())
     }
 
-    pub(crate) unsafe fn cpu_read_lock(&mut self, range: Range<DeviceSize>) {
+    pub(crate) unsafe fn cpu_read_lock(
+        &mut self,
+        range: Range<DeviceSize>,
+    ) -> Result<(), AccessConflict> {
+        self.check_cpu_read(range.clone())?;
+
         self.ranges.split_at(&range.start);
         self.ranges.split_at(&range.end);
 
@@ -644,9 +1207,11 @@ impl BufferState {
                 CurrentAccess::Shared { cpu_reads, .. } => {
                     *cpu_reads += 1;
                 }
-                _ => unreachable!("Buffer is being written by the CPU or GPU"),
+                _ => unreachable!("checked by `check_cpu_read`"),
             }
         }
+
+        Ok(())
     }
 
     pub(crate) unsafe fn cpu_read_unlock(&mut self, range: Range<DeviceSize>) {
@@ -680,13 +1245,20 @@ impl BufferState {
         Ok(())
     }
 
-    pub(crate) unsafe fn cpu_write_lock(&mut self, range: Range<DeviceSize>) {
+    pub(crate) unsafe fn cpu_write_lock(
+        &mut self,
+        range: Range<DeviceSize>,
+    ) -> Result<(), AccessConflict> {
+        self.check_cpu_write(range.clone())?;
+
         self.ranges.split_at(&range.start);
         self.ranges.split_at(&range.end);
 
         for (_range, state) in self.ranges.range_mut(&range) {
             state.current_access = CurrentAccess::CpuExclusive;
         }
+
+        Ok(())
     }
 
     pub(crate) unsafe fn cpu_write_unlock(&mut self, range: Range<DeviceSize>) {
@@ -717,7 +1289,33 @@ impl BufferState {
         Ok(())
     }
 
-    pub(crate) unsafe fn gpu_read_lock(&mut self, range: Range<DeviceSize>) {
+    /// Checks whether the range can be locked by the device, which is only prevented by
+    /// conflicting host access. Conflicts with other device accesses are handled by
+    /// synchronization instead.
+    pub(crate) fn check_gpu_lock(
+        &self,
+        range: Range<DeviceSize>,
+        mutable: bool,
+    ) -> Result<(), AccessConflict> {
+        for (_range, state) in self.ranges.range(&range) {
+            match state.current_access {
+                CurrentAccess::CpuExclusive => return Err(AccessConflict::HostWrite),
+                CurrentAccess::Shared { cpu_reads, .. } if mutable && cpu_reads > 0 => {
+                    return Err(AccessConflict::HostRead);
+                }
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) unsafe fn gpu_read_lock(
+        &mut self,
+        range: Range<DeviceSize>,
+    ) -> Result<(), AccessConflict> {
+        self.check_gpu_lock(range.clone(), false)?;
+
         self.ranges.split_at(&range.start);
         self.ranges.split_at(&range.end);
 
@@ -725,9 +1323,11 @@ impl BufferState {
             match &mut state.current_access {
                 CurrentAccess::GpuExclusive { gpu_reads, .. }
                 | CurrentAccess::Shared { gpu_reads, .. } => *gpu_reads += 1,
-                _ => unreachable!("Buffer is being written by the CPU"),
+                _ => unreachable!("checked by `check_gpu_lock`"),
             }
         }
+
+        Ok(())
     }
 
     pub(crate) unsafe fn gpu_read_unlock(&mut self, range: Range<DeviceSize>) {
@@ -757,7 +1357,12 @@ impl BufferState {
         Ok(())
     }
 
-    pub(crate) unsafe fn gpu_write_lock(&mut self, range: Range<DeviceSize>) {
+    pub(crate) unsafe fn gpu_write_lock(
+        &mut self,
+        range: Range<DeviceSize>,
+    ) -> Result<(), AccessConflict> {
+        self.check_gpu_lock(range.clone(), true)?;
+
         self.ranges.split_at(&range.start);
         self.ranges.split_at(&range.end);
 
@@ -773,9 +1378,11 @@ impl BufferState {
                         gpu_writes: 1,
                     }
                 }
-                _ => unreachable!("Buffer is being accessed by the CPU"),
+                _ => unreachable!("checked by `check_gpu_lock`"),
             }
         }
+
+        Ok(())
     }
 
     pub(crate) unsafe fn gpu_write_unlock(&mut self, range: Range<DeviceSize>) {
@@ -812,7 +1419,6 @@ vulkan_bitflags! {
     /// Flags specifying additional properties of a buffer.
     BufferCreateFlags = BufferCreateFlags(u32);
 
-    /* TODO: enable
     /// The buffer will be backed by sparse memory binding (through queue commands) instead of
     /// regular binding (through [`bind_memory`]).
     ///
@@ -820,7 +1426,7 @@ vulkan_bitflags! {
     ///
     /// [`bind_memory`]: sys::RawBuffer::bind_memory
     /// [`sparse_binding`]: crate::device::DeviceFeatures::sparse_binding
-    SPARSE_BINDING = SPARSE_BINDING,*/
+    SPARSE_BINDING = SPARSE_BINDING,
 
     /* TODO: enable
     /// The buffer can be used without being fully resident in memory at the time of use.
@@ -852,15 +1458,21 @@ vulkan_bitflags! {
         RequiresAllOf([APIVersion(V1_1)]),
     ]),*/
 
-    /* TODO: enable
-    /// The buffer's device address can be saved and reused on a subsequent run.
+    /// The buffer's device address can be saved and reused on a subsequent run, by passing the
+    /// [`opaque_capture_address`] of the buffer to [`BufferCreateInfo::opaque_capture_address`]
+    /// when creating the buffer again.
+    ///
+    /// The [`buffer_device_address_capture_replay`][feature] feature must be enabled on the
+    /// device.
     ///
-    /// The device API version must be at least 1.2, or either the [`khr_buffer_device_address`] or
-    /// [`ext_buffer_device_address`] extension must be enabled on the device.
-    DEVICE_ADDRESS_CAPTURE_REPLAY = DEVICE_ADDRESS_CAPTURE_REPLAY {
-        api_version: V1_2,
-        device_extensions: [khr_buffer_device_address, ext_buffer_device_address],
-    },*/
+    /// [`opaque_capture_address`]: Buffer::opaque_capture_address
+    /// [feature]: crate::device::DeviceFeatures::buffer_device_address_capture_replay
+    DEVICE_ADDRESS_CAPTURE_REPLAY = DEVICE_ADDRESS_CAPTURE_REPLAY
+    RequiresOneOf([
+        RequiresAllOf([APIVersion(V1_2)]),
+        RequiresAllOf([DeviceExtension(khr_buffer_device_address)]),
+        RequiresAllOf([DeviceExtension(ext_buffer_device_address)]),
+    ]),
 }
 
 /// The buffer configuration to query in [`PhysicalDevice::external_buffer_properties`].
@@ -1043,3 +1655,38 @@ impl From<Subbuffer<[u32]>> for IndexBuffer {
         Self::U32(value)
     }
 }
+
+/// A region of device memory that is referred to by its device address, split into elements of
+/// equal size.
+///
+/// This is what Vulkan commands that take device addresses instead of buffers need to know about a
+/// range of memory, such as the shader binding tables of ray tracing commands. It can be obtained
+/// with [`Subbuffer::device_address_region`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StridedDeviceAddressRegion {
+    /// The device address of the start of the region.
+    pub device_address: NonNullDeviceAddress,
+
+    /// The distance in bytes between the start of consecutive elements in the region.
+    pub stride: DeviceSize,
+
+    /// The size of the region in bytes.
+    pub size: DeviceSize,
+}
+
+impl From<StridedDeviceAddressRegion> for ash::vk::StridedDeviceAddressRegionKHR {
+    #[inline]
+    fn from(val: StridedDeviceAddressRegion) -> Self {
+        let StridedDeviceAddressRegion {
+            device_address,
+            stride,
+            size,
+        } = val;
+
+        ash::vk::StridedDeviceAddressRegionKHR {
+            device_address: device_address.get(),
+            stride,
+            size,
+        }
+    }
+}
@@ -1,18 +1,22 @@
 //! A subpart of a buffer.
 
-use super::{allocator::Arena, Buffer, BufferMemory};
+use super::{
+    allocator::Arena, Buffer, BufferMemory, SparseBufferMemory, StridedDeviceAddressRegion,
+};
 use crate::{
     device::{Device, DeviceOwned, DeviceOwnedDebugWrapper},
     macros::try_opt,
     memory::{
         self,
         allocator::{align_down, align_up, DeviceLayout},
-        is_aligned, DeviceAlignment, MappedMemoryRange,
+        is_aligned, DeviceAlignment, MappedMemoryRange, ResourceMemory,
     },
-    sync::HostAccessError,
-    DeviceSize, NonNullDeviceAddress, NonZeroDeviceSize, ValidationError,
+    sync::{AccessConflict, HostAccessError},
+    DeviceAddressT, DeviceSize, NonNullDeviceAddress, NonZeroDeviceSize, ValidationError,
+    VulkanError,
 };
 use bytemuck::AnyBitPattern;
+use parking_lot::MutexGuard;
 use std::{
     alloc::Layout,
     cmp,
@@ -23,6 +27,7 @@ use std::{
     ptr::{self, NonNull},
     sync::Arc,
     thread,
+    time::{Duration, Instant},
 };
 #[cfg(feature = "macros")]
 pub use vulkano_macros::BufferContents;
@@ -81,10 +86,15 @@ impl<T: ?Sized> Subbuffer<T> {
     }
 
     /// Returns the offset of the subbuffer, in bytes, relative to the [`DeviceMemory`] block.
+    ///
+    /// For a buffer backed by sparse memory, this is the offset relative to the buffer instead.
+    /// Memory is bound to sparse buffers in blocks that are aligned to the alignment of the
+    /// memory requirements, so this gives the same alignment as the offset in memory would.
     fn memory_offset(&self) -> DeviceSize {
         let allocation = match self.buffer().memory() {
             BufferMemory::Normal(a) => a,
-            BufferMemory::Sparse | BufferMemory::External => unreachable!(),
+            BufferMemory::Sparse(_) => return self.offset,
+            BufferMemory::External => unreachable!(),
         };
 
         allocation.offset() + self.offset
@@ -123,11 +133,17 @@ impl<T: ?Sized> Subbuffer<T> {
                 // SAFETY: `self.range()` is in bounds of the allocation.
                 unsafe { allocation.mapped_slice_unchecked(self.range()) }
             }
-            BufferMemory::Sparse | BufferMemory::External => unreachable!(),
+            BufferMemory::Sparse(memory) => Err(sparse_host_access_error(memory, self.range())),
+            BufferMemory::External => unreachable!(),
         }
     }
 
     /// Returns the device address for this subbuffer.
+    ///
+    /// This is the [device address of the buffer], plus the offset of the subbuffer. To get an
+    /// address that remembers the type of the subbuffer, use [`DeviceAddressT::try_from`].
+    ///
+    /// [device address of the buffer]: Buffer::device_address
     pub fn device_address(&self) -> Result<NonNullDeviceAddress, Box<ValidationError>> {
         self.buffer().device_address().map(|ptr| {
             // SAFETY: The original address came from the Vulkan implementation, and allocation
@@ -147,6 +163,40 @@ impl<T: ?Sized> Subbuffer<T> {
         )
     }
 
+    /// Returns the [device address] of the subbuffer together with its size, as a region of
+    /// elements that are `stride` bytes apart.
+    ///
+    /// If `stride` is `None`, the whole subbuffer is treated as one element, and the stride is
+    /// equal to the size of the subbuffer. Otherwise, `stride` must not be greater than the size
+    /// of the buffer.
+    ///
+    /// [device address]: Self::device_address
+    pub fn device_address_region(
+        &self,
+        stride: Option<DeviceSize>,
+    ) -> Result<StridedDeviceAddressRegion, Box<ValidationError>> {
+        let device_address = self.device_address()?;
+        let stride = stride.unwrap_or(self.size);
+
+        // VUID-VkStridedDeviceAddressRegionKHR-size-04631
+        // Ensured by using the range of the subbuffer.
+
+        if stride > self.buffer().size() {
+            return Err(Box::new(ValidationError {
+                context: "stride".into(),
+                problem: "is greater than the size of the buffer".into(),
+                vuids: &["VUID-VkStridedDeviceAddressRegionKHR-size-04632"],
+                ..Default::default()
+            }));
+        }
+
+        Ok(StridedDeviceAddressRegion {
+            device_address,
+            stride,
+            size: self.size,
+        })
+    }
+
     /// Casts the subbuffer to a slice of raw bytes.
     pub fn into_bytes(self) -> Subbuffer<[u8]> {
         unsafe { self.reinterpret_unchecked_inner() }
@@ -282,7 +332,12 @@ where
     /// buffer that uses it in exclusive mode will fail. You can still submit this subbuffer
     /// for non-exclusive accesses (ie. reads).
     ///
-    /// If the memory backing the buffer is not [host-coherent], then this function will lock a
+    /// If the memory backing the buffer is not [host-coherent], the locked range is invalidated
+    /// before the guard is returned, so that data written by the device is visible to the host
+    /// without having to call [`invalidate_range`] manually. If that fails,
+    /// [`HostAccessError::Invalidate`] is returned.
+    ///
+    /// If the memory backing the buffer is not host-coherent, then this function will lock a
     /// range that is potentially larger than the subbuffer, because the range given to
     /// [`invalidate_range`] must be aligned to the [`non_coherent_atom_size`]. This means that for
     /// example if your Vulkan implementation reports an atom size of 64, and you tried to put 2
@@ -294,7 +349,11 @@ where
     /// 64. [`SubbufferAllocator`] does this automatically.
     ///
     /// If the memory backing the buffer is not managed by vulkano, (i.e. this buffer was created
-    /// from [`RawBuffer::assume_bound`]), then it can't be read from using this function.
+    /// from [`RawBuffer::assume_bound`]), then it can't be read from using this function. The same
+    /// goes for buffers backed by sparse memory, and if any part of the subbuffer has no memory
+    /// bound to it, [`HostAccessError::Unbound`] is returned. If the subbuffer belongs to an arena
+    /// that the [`SubbufferAllocator`] has made available for reuse, [`HostAccessError::Retired`]
+    /// is returned.
     ///
     /// [host-coherent]: memory::MemoryPropertyFlags::HOST_COHERENT
     /// [`invalidate_range`]: memory::ResourceMemory::invalidate_range
@@ -305,31 +364,20 @@ where
     pub fn read(&self) -> Result<BufferReadGuard<'_, T>, HostAccessError> {
         assert!(T::LAYOUT.alignment().as_devicesize() <= 64);
 
+        self.check_not_retired()?;
+
         let allocation = match self.buffer().memory() {
             BufferMemory::Normal(a) => a,
-            BufferMemory::Sparse => todo!("`Subbuffer::read` doesn't support sparse binding yet"),
+            BufferMemory::Sparse(memory) => {
+                return Err(sparse_host_access_error(memory, self.range()));
+            }
             BufferMemory::External => return Err(HostAccessError::Unmanaged),
         };
 
-        let range = if let Some(atom_size) = allocation.atom_size() {
-            // This works because the memory allocator must align allocations to the non-coherent
-            // atom size when the memory is host-visible but not host-coherent.
-            let start = align_down(self.offset, atom_size);
-            let end = cmp::min(
-                align_up(self.offset + self.size, atom_size),
-                allocation.size(),
-            );
-
-            Range { start, end }
-        } else {
-            self.range()
-        };
+        let range = self.host_access_range(allocation);
 
         let mut state = self.buffer().state();
-        state
-            .check_cpu_read(range.clone())
-            .map_err(HostAccessError::AccessConflict)?;
-        unsafe { state.cpu_read_lock(range.clone()) };
+        unsafe { state.cpu_read_lock(range.clone()) }.map_err(HostAccessError::AccessConflict)?;
 
         let mapped_slice = self.mapped_slice()?;
 
@@ -375,6 +423,11 @@ where
     /// After this function successfully locks the buffer, any attempt to submit a command buffer
     /// that uses it and any attempt to call `read` will return an error.
     ///
+    /// For a subbuffer of a slice, the guard dereferences to `[T]`, so a whole slice of data can
+    /// be written at once with [`copy_from_slice`], which is much faster than assigning the
+    /// elements one by one. The flush of non-coherent memory happens when the guard is dropped,
+    /// regardless of how the data was written.
+    ///
     /// If the memory backing the buffer is not [host-coherent], then this function will lock a
     /// range that is potentially larger than the subbuffer, because the range given to
     /// [`flush_range`] must be aligned to the [`non_coherent_atom_size`]. This means that for
@@ -387,7 +440,11 @@ where
     /// does this automatically.
     ///
     /// If the memory backing the buffer is not managed by vulkano, (i.e. this buffer was created
-    /// from [`RawBuffer::assume_bound`]), then it can't be written to using this function.
+    /// from [`RawBuffer::assume_bound`]), then it can't be written to using this function. The
+    /// same goes for buffers backed by sparse memory, and if any part of the subbuffer has no
+    /// memory bound to it, [`HostAccessError::Unbound`] is returned. If the subbuffer belongs to
+    /// an arena that the [`SubbufferAllocator`] has made available for reuse,
+    /// [`HostAccessError::Retired`] is returned.
     ///
     /// [host-coherent]: memory::MemoryPropertyFlags::HOST_COHERENT
     /// [`flush_range`]: memory::ResourceMemory::flush_range
@@ -395,34 +452,24 @@ where
     /// [`read`]: Self::read
     /// [`SubbufferAllocator`]: super::allocator::SubbufferAllocator
     /// [`RawBuffer::assume_bound`]: crate::buffer::sys::RawBuffer::assume_bound
+    /// [`copy_from_slice`]: slice::copy_from_slice
     pub fn write(&self) -> Result<BufferWriteGuard<'_, T>, HostAccessError> {
         assert!(T::LAYOUT.alignment().as_devicesize() <= 64);
 
+        self.check_not_retired()?;
+
         let allocation = match self.buffer().memory() {
             BufferMemory::Normal(a) => a,
-            BufferMemory::Sparse => todo!("`Subbuffer::write` doesn't support sparse binding yet"),
+            BufferMemory::Sparse(memory) => {
+                return Err(sparse_host_access_error(memory, self.range()));
+            }
             BufferMemory::External => return Err(HostAccessError::Unmanaged),
         };
 
-        let range = if let Some(atom_size) = allocation.atom_size() {
-            // This works because the memory allocator must align allocations to the non-coherent
-            // atom size when the memory is host-visible but not host-coherent.
-            let start = align_down(self.offset, atom_size);
-            let end = cmp::min(
-                align_up(self.offset + self.size, atom_size),
-                allocation.size(),
-            );
-
-            Range { start, end }
-        } else {
-            self.range()
-        };
+        let range = self.host_access_range(allocation);
 
         let mut state = self.buffer().state();
-        state
-            .check_cpu_write(range.clone())
-            .map_err(HostAccessError::AccessConflict)?;
-        unsafe { state.cpu_write_lock(range.clone()) };
+        unsafe { state.cpu_write_lock(range.clone()) }.map_err(HostAccessError::AccessConflict)?;
 
         let mapped_slice = self.mapped_slice()?;
 
@@ -452,6 +499,163 @@ where
             range,
         })
     }
+
+    /// Same as [`read`], except that if the subbuffer is currently written by the device, this
+    /// function blocks until the device is done with it, or until `timeout` has elapsed, in which
+    /// case [`HostAccessError::Timeout`] is returned.
+    ///
+    /// This waits for the fence of each submission that uses the subbuffer, and then releases the
+    /// subbuffer without waiting for the future of the submission to be cleaned up. Command
+    /// buffers with the [`SimultaneousUse`] usage are the exception: their submissions only
+    /// release the subbuffer once their future is cleaned up, for example by calling
+    /// [`FenceSignalFuture::wait`] or [`GpuFuture::cleanup_finished`], or by dropping the future.
+    ///
+    /// If the subbuffer is locked by the host, this function returns an error immediately, like
+    /// `read` does, because the lock may be held by the current thread.
+    ///
+    /// [`read`]: Self::read
+    /// [`SimultaneousUse`]: crate::command_buffer::CommandBufferUsage::SimultaneousUse
+    /// [`FenceSignalFuture::wait`]: crate::sync::future::FenceSignalFuture::wait
+    /// [`GpuFuture::cleanup_finished`]: crate::sync::GpuFuture::cleanup_finished
+    pub fn read_blocking(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<BufferReadGuard<'_, T>, HostAccessError> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            self.wait_for_device(false, deadline)?;
+
+            // The device may have locked the subbuffer again in the meantime.
+            match self.read() {
+                Err(HostAccessError::AccessConflict(AccessConflict::DeviceWrite)) => {}
+                result => return result,
+            }
+        }
+    }
+
+    /// Same as [`write`], except that if the subbuffer is currently in use by the device, this
+    /// function blocks until the device is done with it, or until `timeout` has elapsed, in which
+    /// case [`HostAccessError::Timeout`] is returned.
+    ///
+    /// The same caveats as for [`read_blocking`] apply.
+    ///
+    /// [`write`]: Self::write
+    /// [`read_blocking`]: Self::read_blocking
+    pub fn write_blocking(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<BufferWriteGuard<'_, T>, HostAccessError> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            self.wait_for_device(true, deadline)?;
+
+            // The device may have locked the subbuffer again in the meantime.
+            match self.write() {
+                Err(HostAccessError::AccessConflict(
+                    AccessConflict::DeviceRead | AccessConflict::DeviceWrite,
+                )) => {}
+                result => return result,
+            }
+        }
+    }
+
+    /// Blocks until the device no longer holds a lock that conflicts with a host access of the
+    /// subbuffer. Locks held by the host are returned as an error right away.
+    fn wait_for_device(
+        &self,
+        write: bool,
+        deadline: Option<Instant>,
+    ) -> Result<(), HostAccessError> {
+        let allocation = match self.buffer().memory() {
+            BufferMemory::Normal(a) => a,
+            BufferMemory::Sparse(memory) => {
+                return Err(sparse_host_access_error(memory, self.range()));
+            }
+            BufferMemory::External => return Err(HostAccessError::Unmanaged),
+        };
+        let range = self.host_access_range(allocation);
+
+        let buffer = self.buffer();
+        let mut state = buffer.state();
+
+        loop {
+            let result = if write {
+                state.check_cpu_write(range.clone())
+            } else {
+                state.check_cpu_read(range.clone())
+            };
+
+            match result {
+                Err(AccessConflict::DeviceRead | AccessConflict::DeviceWrite) => {}
+                result => return result.map_err(HostAccessError::AccessConflict),
+            }
+
+            let command_buffers = state.pending_command_buffers(buffer, range.clone(), write);
+
+            if command_buffers.is_empty() {
+                // The locks can only be released by cleaning up the futures that hold them.
+                if !buffer.wait_state_released(&mut state, deadline) {
+                    return Err(HostAccessError::Timeout);
+                }
+            } else {
+                // Waiting for the fences releases the locks of the command buffers, which needs
+                // the state of the buffer.
+                MutexGuard::unlocked(&mut state, || {
+                    command_buffers.iter().try_for_each(|command_buffer| {
+                        command_buffer
+                            .wait_pending_submission(deadline)
+                            .map_err(|err| match err {
+                                VulkanError::Timeout => HostAccessError::Timeout,
+                                err => HostAccessError::Wait(err),
+                            })
+                    })
+                })?;
+            }
+        }
+    }
+
+    /// Returns an error if the subbuffer was allocated from an arena that a [`SubbufferAllocator`]
+    /// has made available for reuse, in which case its memory may belong to another subbuffer.
+    ///
+    /// [`SubbufferAllocator`]: super::allocator::SubbufferAllocator
+    fn check_not_retired(&self) -> Result<(), HostAccessError> {
+        match &self.parent {
+            SubbufferParent::Arena(arena) if arena.is_retired() => Err(HostAccessError::Retired),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the range of the buffer that needs to be locked for host access of the subbuffer.
+    fn host_access_range(&self, allocation: &ResourceMemory) -> Range<DeviceSize> {
+        if let Some(atom_size) = allocation.atom_size() {
+            // This works because the memory allocator must align allocations to the non-coherent
+            // atom size when the memory is host-visible but not host-coherent.
+            let start = align_down(self.offset, atom_size);
+            let end = cmp::min(
+                align_up(self.offset + self.size, atom_size),
+                allocation.size(),
+            );
+
+            Range { start, end }
+        } else {
+            self.range()
+        }
+    }
+}
+
+/// Host access is not supported for buffers backed by sparse memory, but ranges that have no
+/// memory bound to them are reported separately, so that they aren't mistaken for garbage data.
+fn sparse_host_access_error(
+    memory: &SparseBufferMemory,
+    range: Range<DeviceSize>,
+) -> HostAccessError {
+    if memory.is_bound(range) {
+        HostAccessError::Unmanaged
+    } else {
+        HostAccessError::Unbound
+    }
 }
 
 impl<T> Subbuffer<T> {
@@ -545,6 +749,45 @@ impl<T> Subbuffer<[T]> {
     }
 }
 
+impl<T> Subbuffer<[T]>
+where
+    T: BufferContents + Copy,
+{
+    /// Copies `data` into the subbuffer from the host.
+    ///
+    /// This locks the subbuffer with [`write`], copies the data, and then releases the lock
+    /// again, which flushes the written range if the memory is not host-coherent. The same
+    /// requirements and errors as for `write` apply.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the length of `data` is not equal to the length of the subbuffer.
+    ///
+    /// [`write`]: Self::write
+    pub fn copy_from_slice(&self, data: &[T]) -> Result<(), HostAccessError> {
+        assert_eq!(
+            data.len() as DeviceSize,
+            self.len(),
+            "the length of `data` must be equal to the length of the subbuffer",
+        );
+
+        self.write()?.copy_from_slice(data);
+
+        Ok(())
+    }
+
+    /// Copies the contents of the subbuffer to a new `Vec` on the host.
+    ///
+    /// This locks the subbuffer with [`read`], which invalidates the range if the memory is not
+    /// host-coherent, copies the data, and then releases the lock again. The same requirements
+    /// and errors as for `read` apply.
+    ///
+    /// [`read`]: Self::read
+    pub fn copy_to_vec(&self) -> Result<Vec<T>, HostAccessError> {
+        Ok(self.read()?.to_vec())
+    }
+}
+
 impl Subbuffer<[u8]> {
     /// Creates a new `Subbuffer<[u8]>` spanning the whole buffer.
     #[inline]
@@ -605,6 +848,24 @@ impl From<Arc<Buffer>> for Subbuffer<[u8]> {
     }
 }
 
+impl<T: ?Sized> TryFrom<&Subbuffer<T>> for DeviceAddressT<T> {
+    type Error = Box<ValidationError>;
+
+    #[inline]
+    fn try_from(subbuffer: &Subbuffer<T>) -> Result<Self, Self::Error> {
+        subbuffer.device_address().map(DeviceAddressT::from)
+    }
+}
+
+impl<T: ?Sized> TryFrom<Subbuffer<T>> for DeviceAddressT<T> {
+    type Error = Box<ValidationError>;
+
+    #[inline]
+    fn try_from(subbuffer: Subbuffer<T>) -> Result<Self, Self::Error> {
+        Self::try_from(&subbuffer)
+    }
+}
+
 impl<T: ?Sized> Clone for Subbuffer<T> {
     fn clone(&self) -> Self {
         Subbuffer {
@@ -652,6 +913,7 @@ impl<T: ?Sized> Drop for BufferReadGuard<'_, T> {
     fn drop(&mut self) {
         let mut state = self.subbuffer.buffer().state();
         unsafe { state.cpu_read_unlock(self.range.clone()) };
+        self.subbuffer.buffer().notify_state_released();
     }
 }
 
@@ -679,7 +941,7 @@ impl<T: ?Sized> Drop for BufferWriteGuard<'_, T> {
     fn drop(&mut self) {
         let allocation = match self.subbuffer.buffer().memory() {
             BufferMemory::Normal(a) => a,
-            BufferMemory::Sparse => unreachable!(),
+            BufferMemory::Sparse(_) => unreachable!(),
             BufferMemory::External => unreachable!(),
         };
 
@@ -695,6 +957,7 @@ impl<T: ?Sized> Drop for BufferWriteGuard<'_, T> {
 
         let mut state = self.subbuffer.buffer().state();
         unsafe { state.cpu_write_unlock(self.range.clone()) };
+        self.subbuffer.buffer().notify_state_released();
     }
 }
 
@@ -1143,15 +1406,21 @@ mod tests {
     use crate::{
         buffer::{
             sys::{BufferCreateInfo, RawBuffer},
-            BufferUsage,
+            BufferUsage, ZeroInitializeInfo,
+        },
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, CommandBufferBeginInfo, CommandBufferLevel,
+            CommandBufferUsage, CopyBufferInfo, RecordingCommandBuffer,
         },
         memory::{
             allocator::{
                 AllocationCreateInfo, AllocationType, DeviceLayout, MemoryAllocator,
-                StandardMemoryAllocator,
+                MemoryTypeFilter, StandardMemoryAllocator,
             },
-            MemoryRequirements, ResourceMemory,
+            MemoryPropertyFlags, MemoryRequirements, ResourceMemory,
         },
+        sync::GpuFuture,
+        testing::assert_contents_eq,
     };
 
     #[test]
@@ -1253,6 +1522,467 @@ mod tests {
         }
     }
 
+    #[test]
+    fn from_slice() {
+        let (device, _) = gfx_dev_and_queue!();
+        let allocator = Arc::new(StandardMemoryAllocator::new_default(device));
+        let data: Vec<u32> = (0..1000).collect();
+
+        let buffer = Buffer::from_slice(
+            allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_SRC,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+            &data,
+        )
+        .unwrap();
+        assert_eq!(buffer.len(), 1000);
+        assert_contents_eq(&buffer.read().unwrap(), &data, 0);
+
+        {
+            let mut write_guard = buffer.write().unwrap();
+            write_guard[..3].copy_from_slice(&[7, 8, 9]);
+        }
+        assert_contents_eq(&buffer.read().unwrap()[..4], &[7, 8, 9, 3], 0);
+    }
+
+    #[test]
+    fn copy_from_slice_and_to_vec() {
+        let (device, _) = gfx_dev_and_queue!();
+        let allocator = Arc::new(StandardMemoryAllocator::new_default(device));
+
+        let buffer = Buffer::new_slice::<u32>(
+            allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_SRC,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                ..Default::default()
+            },
+            16,
+        )
+        .unwrap();
+
+        buffer.copy_from_slice(&[5; 16]).unwrap();
+
+        // A subbuffer whose offset is not aligned to the non-coherent atom size.
+        let (_, tail) = buffer.clone().split_at(3);
+        tail.copy_from_slice(&(3..16).collect::<Vec<_>>()).unwrap();
+
+        let mut expected = vec![5, 5, 5];
+        expected.extend(3..16);
+        assert_eq!(buffer.copy_to_vec().unwrap(), expected);
+        assert_eq!(tail.copy_to_vec().unwrap()[..2], [3, 4]);
+
+        assert_should_panic!(
+            "the length of `data` must be equal to the length of the subbuffer",
+            {
+                let _ = buffer.copy_from_slice(&[0; 3]);
+            }
+        );
+    }
+
+    #[test]
+    fn write_blocking() {
+        let (device, _) = gfx_dev_and_queue!();
+        let allocator = Arc::new(StandardMemoryAllocator::new_default(device));
+
+        let buffer = Buffer::from_iter(
+            allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_SRC,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                ..Default::default()
+            },
+            0..16u32,
+        )
+        .unwrap();
+        let range = 0..buffer.buffer().size();
+
+        // Pretend that the device is using the buffer.
+        unsafe { buffer.buffer().state().gpu_read_lock(range.clone()) }.unwrap();
+
+        assert!(matches!(
+            buffer.write(),
+            Err(HostAccessError::AccessConflict(AccessConflict::DeviceRead)),
+        ));
+        assert!(matches!(
+            buffer.write_blocking(Some(Duration::from_millis(10))),
+            Err(HostAccessError::Timeout),
+        ));
+
+        // Reading doesn't conflict with the device reading.
+        drop(buffer.read_blocking(None).unwrap());
+
+        let releasing_thread = {
+            let buffer = buffer.buffer().clone();
+
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(10));
+                unsafe { buffer.state().gpu_read_unlock(range) };
+                buffer.notify_state_released();
+            })
+        };
+
+        buffer.write_blocking(None).unwrap()[0] = 42;
+        releasing_thread.join().unwrap();
+
+        // A lock held by the host is reported right away instead of deadlocking.
+        let read_guard = buffer.read().unwrap();
+        assert_eq!(read_guard[0], 42);
+        assert!(matches!(
+            buffer.write_blocking(None),
+            Err(HostAccessError::AccessConflict(AccessConflict::HostRead)),
+        ));
+    }
+
+    #[test]
+    fn write_blocking_waits_for_fence() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+
+        let buffer = Buffer::from_iter(
+            memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                ..Default::default()
+            },
+            0..4u32,
+        )
+        .unwrap();
+
+        let fill = |data| {
+            let mut builder = RecordingCommandBuffer::new(
+                command_buffer_allocator.clone(),
+                queue.queue_family_index(),
+                CommandBufferLevel::Primary,
+                CommandBufferBeginInfo {
+                    usage: CommandBufferUsage::OneTimeSubmit,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            builder.fill_buffer(buffer.clone(), data).unwrap();
+
+            builder.end().unwrap()
+        };
+
+        // The submission doesn't have a fence of its own, and the future is kept alive without
+        // being cleaned up while this thread waits.
+        let future = crate::sync::now(device.clone())
+            .then_execute(queue.clone(), fill(1))
+            .unwrap()
+            .then_signal_semaphore_and_flush()
+            .unwrap();
+
+        assert!(matches!(
+            buffer.read(),
+            Err(HostAccessError::AccessConflict(AccessConflict::DeviceWrite)),
+        ));
+        assert_eq!(*buffer.read_blocking(None).unwrap(), [1; 4]);
+
+        // Cleaning up the future afterwards must not release the locks a second time.
+        drop(future);
+
+        let future = crate::sync::now(device)
+            .then_execute(queue, fill(2))
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap();
+
+        {
+            let mut guard = buffer.write_blocking(None).unwrap();
+            assert_eq!(*guard, [2; 4]);
+            guard[0] = 3;
+        }
+
+        future.wait(None).unwrap();
+        assert_eq!(*buffer.read().unwrap(), [3, 2, 2, 2]);
+    }
+
+    #[test]
+    fn from_iter_with_staging() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device,
+            Default::default(),
+        ));
+        let data: Vec<u32> = (0..1000).collect();
+
+        let (buffer, future) = Buffer::from_iter_with_staging(
+            memory_allocator.clone(),
+            command_buffer_allocator.clone(),
+            &queue,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_SRC,
+                ..Default::default()
+            },
+            data.iter().copied(),
+        )
+        .unwrap();
+        assert_eq!(buffer.len(), 1000);
+        assert!(buffer.buffer().usage().contains(BufferUsage::TRANSFER_DST));
+
+        let readback_buffer = Buffer::new_slice::<u32>(
+            memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                ..Default::default()
+            },
+            1000,
+        )
+        .unwrap();
+
+        let mut builder = RecordingCommandBuffer::new(
+            command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        builder
+            .copy_buffer(CopyBufferInfo::buffers(buffer, readback_buffer.clone()))
+            .unwrap();
+        let command_buffer = builder.end().unwrap();
+
+        future
+            .then_execute(queue, command_buffer)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        assert_contents_eq(&readback_buffer.read().unwrap(), &data, 0);
+    }
+
+    #[test]
+    fn non_coherent_readback() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        // This is only meaningful with memory that is host-visible but not host-coherent, which
+        // not all devices have.
+        let Some(memory_type_index) = device
+            .physical_device()
+            .memory_properties()
+            .memory_types
+            .iter()
+            .position(|memory_type| {
+                memory_type
+                    .property_flags
+                    .contains(MemoryPropertyFlags::HOST_VISIBLE)
+                    && !memory_type
+                        .property_flags
+                        .intersects(MemoryPropertyFlags::HOST_COHERENT)
+            })
+        else {
+            return;
+        };
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+
+        let buffer = Buffer::from_iter(
+            memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                memory_type_bits: 1 << memory_type_index,
+                ..Default::default()
+            },
+            [0u32; 256],
+        )
+        .unwrap();
+
+        // Read the data once before the device writes it, so that stale data ends up in the host
+        // cache if the range isn't invalidated.
+        assert!(buffer.read().unwrap().iter().all(|&x| x == 0));
+
+        let mut builder = RecordingCommandBuffer::new(
+            command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        builder
+            .fill_buffer(buffer.clone().slice(1..), 0x12345678)
+            .unwrap();
+        let command_buffer = builder.end().unwrap();
+
+        crate::sync::now(device)
+            .then_execute(queue, command_buffer)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        // The subbuffer doesn't start at a multiple of the non-coherent atom size.
+        let subbuffer = buffer.clone().slice(1..);
+        assert!(subbuffer.read().unwrap().iter().all(|&x| x == 0x12345678));
+
+        let data = buffer.read().unwrap();
+        assert_eq!(data[0], 0);
+        assert!(data[1..].iter().all(|&x| x == 0x12345678));
+    }
+
+    #[test]
+    fn new_zeroed_slice() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+
+        let mut builder = RecordingCommandBuffer::new(
+            command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let mut readback_buffers = Vec::new();
+
+        // 7 is not a multiple of 4, which is all that `fill_buffer` can fill.
+        for len in [1000, 7] {
+            let buffer = Buffer::new_zeroed_slice::<u8>(
+                memory_allocator.clone(),
+                BufferCreateInfo {
+                    usage: BufferUsage::STORAGE_BUFFER | BufferUsage::TRANSFER_SRC,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
+                    ..Default::default()
+                },
+                len,
+                ZeroInitializeInfo {
+                    add_transfer_dst_usage: true,
+                    ..Default::default()
+                },
+                &mut builder,
+            )
+            .unwrap();
+            assert_eq!(buffer.len(), len);
+
+            let readback_buffer = Buffer::from_iter(
+                memory_allocator.clone(),
+                BufferCreateInfo {
+                    usage: BufferUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                        | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                    ..Default::default()
+                },
+                (0..len).map(|_| u8::MAX),
+            )
+            .unwrap();
+            builder
+                .copy_buffer(CopyBufferInfo::buffers(buffer, readback_buffer.clone()))
+                .unwrap();
+
+            readback_buffers.push(readback_buffer);
+        }
+
+        let command_buffer = builder.end().unwrap();
+        crate::sync::now(device)
+            .then_execute(queue, command_buffer)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        for readback_buffer in readback_buffers {
+            assert!(readback_buffer
+                .read()
+                .unwrap()
+                .iter()
+                .all(|&byte| byte == 0));
+        }
+    }
+
+    #[test]
+    fn device_address_region() {
+        let (device, _) = gfx_dev_and_queue!(buffer_device_address);
+        let allocator = Arc::new(StandardMemoryAllocator::new_default(device));
+
+        let buffer = Buffer::new_slice::<u32>(
+            allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::SHADER_DEVICE_ADDRESS,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+            16,
+        )
+        .unwrap();
+
+        let base = buffer.buffer().device_address().unwrap();
+        assert_eq!(buffer.buffer().device_address().unwrap(), base);
+
+        let slice = buffer.slice(4..12);
+        let region = slice.device_address_region(None).unwrap();
+        assert_eq!(region.device_address.get(), base.get() + slice.offset());
+        assert_eq!((region.stride, region.size), (32, 32));
+
+        let region = slice.device_address_region(Some(8)).unwrap();
+        assert_eq!((region.stride, region.size), (8, 32));
+
+        // The stride may be larger than the subbuffer, but not larger than the buffer.
+        let region = slice.device_address_region(Some(64)).unwrap();
+        assert_eq!((region.stride, region.size), (64, 32));
+        assert!(slice.device_address_region(Some(65)).is_err());
+    }
+
     #[test]
     fn cast_aligned() {
         let (device, _) = gfx_dev_and_queue!();
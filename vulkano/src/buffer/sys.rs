@@ -4,7 +4,7 @@
 //! other buffer types of this library, and all custom buffer types
 //! that you create must wrap around the types in this module.
 
-use super::{Buffer, BufferCreateFlags, BufferMemory, BufferUsage};
+use super::{Buffer, BufferCreateFlags, BufferMemory, BufferUsage, SparseBufferMemory};
 use crate::{
     buffer::ExternalBufferInfo,
     device::{Device, DeviceOwned},
@@ -84,6 +84,7 @@ impl RawBuffer {
             size,
             usage,
             external_memory_handle_types,
+            opaque_capture_address,
             _ne: _,
         } = &create_info;
 
@@ -106,6 +107,7 @@ impl RawBuffer {
             ..Default::default()
         };
         let mut external_memory_info_vk = None;
+        let mut opaque_capture_address_info_vk = None;
 
         if !external_memory_handle_types.is_empty() {
             let next = external_memory_info_vk.insert(ash::vk::ExternalMemoryBufferCreateInfo {
@@ -117,6 +119,18 @@ impl RawBuffer {
             create_info_vk.p_next = <*const _>::cast(next);
         }
 
+        if opaque_capture_address != 0 {
+            let next = opaque_capture_address_info_vk.insert(
+                ash::vk::BufferOpaqueCaptureAddressCreateInfo {
+                    opaque_capture_address,
+                    ..Default::default()
+                },
+            );
+
+            next.p_next = create_info_vk.p_next;
+            create_info_vk.p_next = <*const _>::cast(next);
+        }
+
         let handle = {
             let fns = device.fns();
             let mut output = MaybeUninit::uninit();
@@ -173,17 +187,22 @@ impl RawBuffer {
         create_info: BufferCreateInfo,
         needs_destruction: bool,
     ) -> Self {
+        let memory_requirements_cache = device.memory_requirements_cache();
+        let mut memory_requirements = memory_requirements_cache
+            .buffer_memory_requirements(memory_requirements_cache.buffer_key(&create_info), || {
+                Self::get_memory_requirements(&device, handle)
+            });
+
         let BufferCreateInfo {
             flags,
             size,
             usage,
             sharing,
             external_memory_handle_types,
+            opaque_capture_address: _,
             _ne: _,
         } = create_info;
 
-        let mut memory_requirements = Self::get_memory_requirements(&device, handle);
-
         debug_assert!(memory_requirements.layout.size() >= size);
         debug_assert!(memory_requirements.memory_type_bits != 0);
 
@@ -293,9 +312,14 @@ impl RawBuffer {
 
     /// Binds device memory to this buffer.
     ///
+    /// To bind memory to multiple buffers at once, with a separate result for each buffer, use
+    /// [`bind_memory_batched`] instead.
+    ///
     /// # Safety
     ///
     /// - The buffer must not already have memory bound to it.
+    ///
+    /// [`bind_memory_batched`]: Self::bind_memory_batched
     pub unsafe fn bind_memory(
         self,
         allocation: ResourceMemory,
@@ -308,6 +332,41 @@ impl RawBuffer {
             .map_err(|(err, buffer, allocation)| (err.into(), buffer, allocation))
     }
 
+    /// Converts this buffer into a [`Buffer`] that is backed by sparse memory.
+    ///
+    /// The buffer starts out without any memory bound to it. Memory can then be bound to it with
+    /// [`Queue::bind_sparse_buffer`].
+    ///
+    /// # Safety
+    ///
+    /// Vulkano doesn't check whether the ranges of the buffer that are accessed by the device have
+    /// memory bound to them. When a command buffer or other operation that accesses the buffer is
+    /// submitted to a queue:
+    ///
+    /// - Every range of the buffer that the operation accesses must have memory bound to it.
+    /// - That memory must stay bound until the operation has finished executing. Memory can still
+    ///   be unbound from the buffer after recording the operation, but before submitting it, as
+    ///   long as it is bound again before the submission.
+    ///
+    /// [`Queue::bind_sparse_buffer`]: crate::device::Queue::bind_sparse_buffer
+    pub unsafe fn into_sparse(self) -> Result<Buffer, (Box<ValidationError>, RawBuffer)> {
+        if !self.flags.intersects(BufferCreateFlags::SPARSE_BINDING) {
+            return Err((
+                Box::new(ValidationError {
+                    context: "self.flags()".into(),
+                    problem: "does not contain `BufferCreateFlags::SPARSE_BINDING`".into(),
+                    ..Default::default()
+                }),
+                self,
+            ));
+        }
+
+        Ok(Buffer::from_raw(
+            self,
+            BufferMemory::Sparse(SparseBufferMemory::new()),
+        ))
+    }
+
     /// Assume this buffer has memory bound to it.
     ///
     /// # Safety
@@ -337,9 +396,14 @@ impl RawBuffer {
         // VUID-VkBindBufferMemoryInfo-buffer-07459
         // Ensured by taking ownership of `RawBuffer`.
 
-        // VUID-VkBindBufferMemoryInfo-buffer-01030
-        // Currently ensured by not having sparse binding flags, but this needs to be checked once
-        // those are enabled.
+        if self.flags.intersects(BufferCreateFlags::SPARSE_BINDING) {
+            return Err(Box::new(ValidationError {
+                context: "self.flags()".into(),
+                problem: "contains `BufferCreateFlags::SPARSE_BINDING`".into(),
+                vuids: &["VUID-VkBindBufferMemoryInfo-buffer-01030"],
+                ..Default::default()
+            }));
+        }
 
         // VUID-VkBindBufferMemoryInfo-memoryOffset-01031
         // Assume that `allocation` was created correctly.
@@ -524,6 +588,27 @@ impl RawBuffer {
             }));
         }
 
+        if self
+            .flags
+            .intersects(BufferCreateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY)
+            && !memory
+                .flags()
+                .intersects(MemoryAllocateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY)
+        {
+            return Err(Box::new(ValidationError {
+                problem: "`self.flags()` contains \
+                    `BufferCreateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY`, but \
+                    `allocation.device_memory().flags()` does not contain \
+                    `MemoryAllocateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY`"
+                    .into(),
+                vuids: &[
+                    "VUID-vkBindBufferMemory-bufferDeviceAddressCaptureReplay-09200",
+                    "VUID-VkBindBufferMemoryInfo-bufferDeviceAddressCaptureReplay-09200",
+                ],
+                ..Default::default()
+            }));
+        }
+
         Ok(())
     }
 
@@ -540,26 +625,14 @@ impl RawBuffer {
         let result = if self.device.api_version() >= Version::V1_1
             || self.device.enabled_extensions().khr_bind_memory2
         {
-            let bind_infos_vk = [ash::vk::BindBufferMemoryInfo {
+            let mut bind_infos_vk = [ash::vk::BindBufferMemoryInfo {
                 buffer: self.handle,
                 memory: memory.handle(),
                 memory_offset,
                 ..Default::default()
             }];
 
-            if self.device.api_version() >= Version::V1_1 {
-                (fns.v1_1.bind_buffer_memory2)(
-                    self.device.handle(),
-                    bind_infos_vk.len() as u32,
-                    bind_infos_vk.as_ptr(),
-                )
-            } else {
-                (fns.khr_bind_memory2.bind_buffer_memory2_khr)(
-                    self.device.handle(),
-                    bind_infos_vk.len() as u32,
-                    bind_infos_vk.as_ptr(),
-                )
-            }
+            Self::bind_memory2(&self.device, &mut bind_infos_vk)[0]
         } else {
             (fns.v1_0.bind_buffer_memory)(
                 self.device.handle(),
@@ -577,6 +650,167 @@ impl RawBuffer {
         Ok(Buffer::from_raw(self, BufferMemory::Normal(allocation)))
     }
 
+    /// Binds device memory to multiple buffers at once.
+    ///
+    /// If the device API version is at least 1.1, or the [`khr_bind_memory2`] extension is
+    /// enabled, all bindings are performed with a single call to `vkBindBufferMemory2`.
+    /// Otherwise, each buffer is bound individually.
+    ///
+    /// On success, one result is returned for each element of `items`, in the same order. If the
+    /// device API version is at least 1.4, the [`khr_maintenance6`] extension is enabled, or the
+    /// bindings are performed individually, the binding of each buffer succeeds or fails
+    /// independently, and a buffer that failed to bind is returned along with its allocation.
+    /// Otherwise, a failure of any binding leaves all of the buffers in an undefined state, so
+    /// the whole call fails and the buffers and their allocations are dropped.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the buffers in `items` were not all created from the same device.
+    ///
+    /// # Safety
+    ///
+    /// - The buffers must not already have memory bound to them.
+    ///
+    /// [`khr_bind_memory2`]: crate::device::DeviceExtensions::khr_bind_memory2
+    /// [`khr_maintenance6`]: crate::device::DeviceExtensions::khr_maintenance6
+    pub unsafe fn bind_memory_batched(
+        items: impl IntoIterator<Item = (RawBuffer, ResourceMemory)>,
+    ) -> Result<Vec<Result<Buffer, (VulkanError, RawBuffer, ResourceMemory)>>, Validated<VulkanError>>
+    {
+        let items: Vec<_> = items.into_iter().collect();
+        Self::validate_bind_memory_batched(&items)?;
+
+        Ok(unsafe { Self::bind_memory_batched_unchecked(items) }?)
+    }
+
+    fn validate_bind_memory_batched(
+        items: &[(RawBuffer, ResourceMemory)],
+    ) -> Result<(), Box<ValidationError>> {
+        let Some((first_buffer, _)) = items.first() else {
+            return Ok(());
+        };
+
+        for (index, (buffer, allocation)) in items.iter().enumerate() {
+            // VUID-vkBindBufferMemory2-pBindInfos-parameter
+            assert_eq!(first_buffer.device(), buffer.device());
+
+            buffer
+                .validate_bind_memory(allocation)
+                .map_err(|err| err.add_context(format!("items[{}]", index)))?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn bind_memory_batched_unchecked(
+        items: impl IntoIterator<Item = (RawBuffer, ResourceMemory)>,
+    ) -> Result<Vec<Result<Buffer, (VulkanError, RawBuffer, ResourceMemory)>>, VulkanError> {
+        let items: Vec<_> = items.into_iter().collect();
+
+        let Some((first_buffer, _)) = items.first() else {
+            return Ok(Vec::new());
+        };
+        let device = first_buffer.device().clone();
+
+        if !(device.api_version() >= Version::V1_1 || device.enabled_extensions().khr_bind_memory2)
+        {
+            return Ok(items
+                .into_iter()
+                .map(|(buffer, allocation)| unsafe { buffer.bind_memory_unchecked(allocation) })
+                .collect());
+        }
+
+        let mut infos_vk: Vec<_> = items
+            .iter()
+            .map(|(buffer, allocation)| ash::vk::BindBufferMemoryInfo {
+                buffer: buffer.handle,
+                memory: allocation.device_memory().handle(),
+                memory_offset: allocation.offset(),
+                ..Default::default()
+            })
+            .collect();
+
+        let results_vk = Self::bind_memory2(&device, &mut infos_vk);
+
+        // Without Vulkan 1.4 or `khr_maintenance6`, a failure leaves all of the buffers in an
+        // undefined state.
+        if !(device.api_version() >= Version::V1_4 || device.enabled_extensions().khr_maintenance6)
+        {
+            if let Some(&result_vk) = results_vk.first() {
+                result_vk.result().map_err(VulkanError::from)?;
+            }
+        }
+
+        Ok(items
+            .into_iter()
+            .enumerate()
+            .map(
+                |(index, (buffer, allocation))| match results_vk[index].result() {
+                    Ok(()) => Ok(Buffer::from_raw(buffer, BufferMemory::Normal(allocation))),
+                    Err(err) => Err((VulkanError::from(err), buffer, allocation)),
+                },
+            )
+            .collect())
+    }
+
+    /// Calls `vkBindBufferMemory2` with `infos_vk`, and returns the result of each binding.
+    ///
+    /// If the device API version is at least 1.4, or the [`khr_maintenance6`] extension is
+    /// enabled, the result of each binding is reported separately. Otherwise, every binding gets
+    /// the result of the call as a whole.
+    ///
+    /// [`khr_maintenance6`]: crate::device::DeviceExtensions::khr_maintenance6
+    unsafe fn bind_memory2(
+        device: &Device,
+        infos_vk: &mut [ash::vk::BindBufferMemoryInfo],
+    ) -> SmallVec<[ash::vk::Result; 4]> {
+        let has_statuses =
+            device.api_version() >= Version::V1_4 || device.enabled_extensions().khr_maintenance6;
+        let mut results_vk: SmallVec<[_; 4]> = SmallVec::new();
+        let mut statuses_vk: SmallVec<[_; 4]> = SmallVec::new();
+
+        if has_statuses {
+            // The statuses start out as an error, in case the implementation doesn't write all of
+            // them when the call fails.
+            results_vk.resize(infos_vk.len(), ash::vk::Result::ERROR_UNKNOWN);
+            statuses_vk.extend(results_vk.iter_mut().map(|result_vk| {
+                ash::vk::BindMemoryStatusKHR {
+                    p_result: result_vk,
+                    ..Default::default()
+                }
+            }));
+
+            for (info_vk, status_vk) in infos_vk.iter_mut().zip(statuses_vk.iter_mut()) {
+                status_vk.p_next = info_vk.p_next;
+                info_vk.p_next = <*mut _>::cast(status_vk);
+            }
+        }
+
+        let fns = device.fns();
+        let result_vk = if device.api_version() >= Version::V1_1 {
+            (fns.v1_1.bind_buffer_memory2)(
+                device.handle(),
+                infos_vk.len() as u32,
+                infos_vk.as_ptr(),
+            )
+        } else {
+            (fns.khr_bind_memory2.bind_buffer_memory2_khr)(
+                device.handle(),
+                infos_vk.len() as u32,
+                infos_vk.as_ptr(),
+            )
+        };
+
+        // If the call as a whole succeeded, then so did every binding.
+        if !has_statuses || result_vk == ash::vk::Result::SUCCESS {
+            results_vk.clear();
+            results_vk.resize(infos_vk.len(), result_vk);
+        }
+
+        results_vk
+    }
+
     /// Returns the memory requirements for this buffer.
     pub fn memory_requirements(&self) -> &MemoryRequirements {
         &self.memory_requirements
@@ -681,6 +915,19 @@ pub struct BufferCreateInfo {
     /// [`khr_external_memory`]: crate::device::DeviceExtensions::khr_external_memory
     pub external_memory_handle_types: ExternalMemoryHandleTypes,
 
+    /// The opaque capture address to create the buffer with, to replay a capture.
+    ///
+    /// If this value is not zero, then `flags` must contain
+    /// [`BufferCreateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY`], and the device API version must be at
+    /// least 1.2, or the [`khr_buffer_device_address`] extension must be enabled on the device.
+    /// The value should have been returned by [`Buffer::opaque_capture_address`] for a buffer
+    /// that was created with the same parameters.
+    ///
+    /// The default value is `0`.
+    ///
+    /// [`khr_buffer_device_address`]: crate::device::DeviceExtensions::khr_buffer_device_address
+    pub opaque_capture_address: u64,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -693,6 +940,7 @@ impl Default for BufferCreateInfo {
             size: 0,
             usage: BufferUsage::empty(),
             external_memory_handle_types: ExternalMemoryHandleTypes::empty(),
+            opaque_capture_address: 0,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -706,6 +954,7 @@ impl BufferCreateInfo {
             size,
             usage,
             external_memory_handle_types,
+            opaque_capture_address,
             _ne: _,
         } = self;
 
@@ -737,12 +986,11 @@ impl BufferCreateInfo {
             }));
         }
 
-        /* Enable when sparse binding is properly handled
-        if let Some(sparse_level) = sparse {
+        if flags.intersects(BufferCreateFlags::SPARSE_BINDING) {
             if !device.enabled_features().sparse_binding {
                 return Err(Box::new(ValidationError {
-                    context: "sparse".into(),
-                    problem: "is `Some`".into(),
+                    context: "flags".into(),
+                    problem: "contains `BufferCreateFlags::SPARSE_BINDING`".into(),
                     requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::Feature(
                         "sparse_binding",
                     )])]),
@@ -750,6 +998,39 @@ impl BufferCreateInfo {
                 }));
             }
 
+            if size
+                > device
+                    .physical_device()
+                    .properties()
+                    .sparse_address_space_size
+            {
+                return Err(Box::new(ValidationError {
+                    problem: "`flags` contains `BufferCreateFlags::SPARSE_BINDING`, but `size` is \
+                        greater than the `sparse_address_space_size` limit"
+                        .into(),
+                    vuids: &["VUID-VkBufferCreateInfo-size-06409"],
+                    ..Default::default()
+                }));
+            }
+        }
+
+        if flags.intersects(BufferCreateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY)
+            && !device
+                .enabled_features()
+                .buffer_device_address_capture_replay
+        {
+            return Err(Box::new(ValidationError {
+                context: "flags".into(),
+                problem: "contains `BufferCreateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY`".into(),
+                requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceFeature(
+                    "buffer_device_address_capture_replay",
+                )])]),
+                vuids: &["VUID-VkBufferCreateInfo-flags-03338"],
+            }));
+        }
+
+        /* Enable when sparse residency is properly handled
+        if let Some(sparse_level) = sparse {
             if sparse_level.sparse_residency && !device.enabled_features().sparse_residency_buffer {
                 return Err(Box::new(ValidationError {
                     context: "sparse".into(),
@@ -793,15 +1074,13 @@ impl BufferCreateInfo {
                     device.physical_device().queue_family_properties().len() as u32;
 
                 for (index, &queue_family_index) in queue_family_indices.iter().enumerate() {
-                    if queue_family_indices[..index].contains(&queue_family_index) {
+                    if let Some(other_index) = queue_family_indices[..index]
+                        .iter()
+                        .position(|&other| other == queue_family_index)
+                    {
                         return Err(Box::new(ValidationError {
-                            context: "queue_family_indices".into(),
-                            problem: format!(
-                                "the queue family index in the list at index {} is contained in \
-                                the list more than once",
-                                index,
-                            )
-                            .into(),
+                            context: format!("sharing[{}]", index).into(),
+                            problem: format!("is equal to `sharing[{}]`", other_index).into(),
                             vuids: &["VUID-VkBufferCreateInfo-sharingMode-01419"],
                             ..Default::default()
                         }));
@@ -857,14 +1136,54 @@ impl BufferCreateInfo {
             // VUID-VkBufferCreateInfo-pNext-00920
         }
 
+        if opaque_capture_address != 0 {
+            if !(device.api_version() >= Version::V1_2
+                || device.enabled_extensions().khr_buffer_device_address)
+            {
+                return Err(Box::new(ValidationError {
+                    context: "opaque_capture_address".into(),
+                    problem: "is not zero".into(),
+                    requires_one_of: RequiresOneOf(&[
+                        RequiresAllOf(&[Requires::APIVersion(Version::V1_2)]),
+                        RequiresAllOf(&[Requires::DeviceExtension("khr_buffer_device_address")]),
+                    ]),
+                    ..Default::default()
+                }));
+            }
+
+            if !flags.intersects(BufferCreateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY) {
+                return Err(Box::new(ValidationError {
+                    problem: "`opaque_capture_address` is not zero, but `flags` does not contain \
+                        `BufferCreateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY`"
+                        .into(),
+                    vuids: &["VUID-VkBufferCreateInfo-opaqueCaptureAddress-03337"],
+                    ..Default::default()
+                }));
+            }
+        }
+
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{BufferCreateInfo, BufferUsage, RawBuffer};
-    use crate::device::DeviceOwned;
+    use super::{BufferCreateFlags, BufferCreateInfo, BufferUsage, RawBuffer};
+    use crate::{
+        buffer::{Buffer, BufferMemory},
+        device::{DeviceExtensions, DeviceFeatures, DeviceOwned},
+        memory::{
+            allocator::{
+                AllocationCreateInfo, AllocationType, MemoryAllocator, MemoryTypeFilter,
+                StandardMemoryAllocator,
+            },
+            DeviceMemory, MemoryAllocateFlags, MemoryAllocateInfo, ResourceMemory,
+        },
+        sync::Sharing,
+        Validated, Version, VulkanObject,
+    };
+    use smallvec::smallvec;
+    use std::sync::Arc;
 
     #[test]
     fn create() {
@@ -885,6 +1204,169 @@ mod tests {
         assert_eq!(buf.device(), &device);
     }
 
+    #[test]
+    fn memory_requirements_cache() {
+        let (device, _) = gfx_dev_and_queue!();
+        let create_info = BufferCreateInfo {
+            size: 256,
+            usage: BufferUsage::TRANSFER_SRC,
+            ..Default::default()
+        };
+
+        let stats_before = device.memory_requirements_cache_stats();
+        let buf1 = RawBuffer::new(device.clone(), create_info.clone()).unwrap();
+        let buf2 = RawBuffer::new(device.clone(), create_info.clone()).unwrap();
+        let stats_after = device.memory_requirements_cache_stats();
+
+        assert_eq!(stats_after.hits, stats_before.hits + 1);
+        assert_eq!(
+            buf1.memory_requirements().layout,
+            buf2.memory_requirements().layout,
+        );
+
+        device.set_memory_requirements_cache_enabled(false);
+        let _buf3 = RawBuffer::new(device.clone(), create_info).unwrap();
+        assert_eq!(device.memory_requirements_cache_stats(), stats_after);
+    }
+
+    #[test]
+    fn concurrent_sharing() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let create_buffer = |sharing| {
+            RawBuffer::new(
+                device.clone(),
+                BufferCreateInfo {
+                    sharing,
+                    size: 128,
+                    usage: BufferUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+            )
+        };
+
+        // Queues of the same family don't need concurrent sharing.
+        assert_eq!(Sharing::from_queues(&[&queue, &queue]), Sharing::Exclusive);
+
+        let family = queue.queue_family_index();
+        let family_count = device.physical_device().queue_family_properties().len() as u32;
+
+        for queue_family_indices in [
+            smallvec![family],
+            smallvec![family, family],
+            smallvec![family, family_count],
+        ] {
+            assert!(matches!(
+                create_buffer(Sharing::Concurrent(queue_family_indices)),
+                Err(Validated::ValidationError(_)),
+            ));
+        }
+
+        if family_count >= 2 {
+            let other_family = (family + 1) % family_count;
+            let buffer =
+                create_buffer(Sharing::Concurrent(smallvec![family, other_family])).unwrap();
+
+            assert_eq!(
+                buffer.sharing(),
+                &Sharing::Concurrent(smallvec![family, other_family]),
+            );
+        }
+    }
+
+    #[test]
+    fn bind_memory_batched() {
+        let (device, _) = gfx_dev_and_queue!();
+        let allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+        let items: Vec<_> = (1..=3)
+            .map(|index| {
+                let buffer = RawBuffer::new(
+                    device.clone(),
+                    BufferCreateInfo {
+                        size: 64 * index,
+                        usage: BufferUsage::TRANSFER_DST,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+                let allocation = allocator
+                    .allocate(
+                        *buffer.memory_requirements(),
+                        AllocationType::Linear,
+                        AllocationCreateInfo::default(),
+                        None,
+                    )
+                    .unwrap();
+                let allocation =
+                    unsafe { ResourceMemory::from_allocation(allocator.clone(), allocation) };
+
+                (buffer, allocation)
+            })
+            .collect();
+
+        let buffers = unsafe { RawBuffer::bind_memory_batched(items) }.unwrap();
+        assert_eq!(buffers.len(), 3);
+
+        for (index, buffer) in buffers.into_iter().enumerate() {
+            assert_eq!(buffer.unwrap().size(), 64 * (index as u64 + 1));
+        }
+
+        assert!(unsafe { RawBuffer::bind_memory_batched([]) }
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn bind_memory_batched_statuses() {
+        // With `khr_maintenance6`, each binding gets its own status.
+        let Some((device, _)) = crate::testing::test_device(
+            &DeviceExtensions {
+                khr_maintenance6: true,
+                ..DeviceExtensions::empty()
+            },
+            &DeviceFeatures::empty(),
+        ) else {
+            return;
+        };
+        let allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+        let mut items = Vec::new();
+        let mut infos_vk = Vec::new();
+
+        for _ in 0..3 {
+            let buffer = RawBuffer::new(
+                device.clone(),
+                BufferCreateInfo {
+                    size: 64,
+                    usage: BufferUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            let allocation = allocator
+                .allocate(
+                    *buffer.memory_requirements(),
+                    AllocationType::Linear,
+                    AllocationCreateInfo::default(),
+                    None,
+                )
+                .unwrap();
+            let allocation =
+                unsafe { ResourceMemory::from_allocation(allocator.clone(), allocation) };
+
+            infos_vk.push(ash::vk::BindBufferMemoryInfo {
+                buffer: buffer.handle(),
+                memory: allocation.device_memory().handle(),
+                memory_offset: allocation.offset(),
+                ..Default::default()
+            });
+            items.push((buffer, allocation));
+        }
+
+        let results_vk = unsafe { RawBuffer::bind_memory2(&device, &mut infos_vk) };
+        assert_eq!(results_vk.as_slice(), [ash::vk::Result::SUCCESS; 3]);
+    }
+
     /* Re-enable when sparse binding is properly implemented
     #[test]
     fn missing_feature_sparse_binding() {
@@ -955,6 +1437,146 @@ mod tests {
     }
     */
 
+    #[test]
+    fn capture_replay_requires_feature() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        // Otherwise the `SHADER_DEVICE_ADDRESS` usage is rejected before the capture replay checks.
+        if !(device.api_version() >= Version::V1_2
+            || device.enabled_extensions().khr_buffer_device_address)
+        {
+            return;
+        }
+
+        match RawBuffer::new(
+            device.clone(),
+            BufferCreateInfo {
+                flags: BufferCreateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY,
+                size: 128,
+                usage: BufferUsage::SHADER_DEVICE_ADDRESS,
+                ..Default::default()
+            },
+        ) {
+            Err(Validated::ValidationError(err)) => {
+                assert_eq!(err.vuids, ["VUID-VkBufferCreateInfo-flags-03338"]);
+            }
+            _ => panic!(),
+        }
+
+        match RawBuffer::new(
+            device,
+            BufferCreateInfo {
+                size: 128,
+                usage: BufferUsage::SHADER_DEVICE_ADDRESS,
+                opaque_capture_address: 0x1000,
+                ..Default::default()
+            },
+        ) {
+            Err(Validated::ValidationError(err)) => {
+                assert_eq!(
+                    err.vuids,
+                    ["VUID-VkBufferCreateInfo-opaqueCaptureAddress-03337"]
+                );
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn capture_replay_requires_capture_replay_memory() {
+        let (device, _) =
+            gfx_dev_and_queue!(buffer_device_address, buffer_device_address_capture_replay);
+
+        let buffer = RawBuffer::new(
+            device.clone(),
+            BufferCreateInfo {
+                flags: BufferCreateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY,
+                size: 128,
+                usage: BufferUsage::SHADER_DEVICE_ADDRESS,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let memory_type_index = buffer
+            .memory_requirements()
+            .memory_type_bits
+            .trailing_zeros();
+        let memory = DeviceMemory::allocate(
+            device,
+            MemoryAllocateInfo {
+                allocation_size: buffer.memory_requirements().layout.size(),
+                memory_type_index,
+                flags: MemoryAllocateFlags::DEVICE_ADDRESS,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        match unsafe { buffer.bind_memory(ResourceMemory::new_dedicated(memory)) } {
+            Err((Validated::ValidationError(err), _, _)) => assert_eq!(
+                err.vuids,
+                [
+                    "VUID-vkBindBufferMemory-bufferDeviceAddressCaptureReplay-09200",
+                    "VUID-VkBindBufferMemoryInfo-bufferDeviceAddressCaptureReplay-09200",
+                ],
+            ),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn capture_replay_round_trip() {
+        let (device, _) =
+            gfx_dev_and_queue!(buffer_device_address, buffer_device_address_capture_replay);
+        let allocator = Arc::new(StandardMemoryAllocator::new_default(device));
+
+        let create_buffer = |buffer_capture_address, memory_capture_address| {
+            Buffer::new_slice::<u32>(
+                allocator.clone(),
+                BufferCreateInfo {
+                    flags: BufferCreateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY,
+                    usage: BufferUsage::SHADER_DEVICE_ADDRESS,
+                    opaque_capture_address: buffer_capture_address,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
+                    opaque_capture_address: memory_capture_address,
+                    ..Default::default()
+                },
+                64,
+            )
+            .unwrap()
+        };
+        let memory_of = |buffer: &Buffer| match buffer.memory() {
+            BufferMemory::Normal(memory) => memory.device_memory().clone(),
+            _ => unreachable!(),
+        };
+
+        // Capture.
+        let (buffer_capture_address, memory_capture_address, device_address) = {
+            let buffer = create_buffer(0, 0);
+            let memory = memory_of(buffer.buffer());
+            assert!(memory
+                .flags()
+                .contains(MemoryAllocateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY));
+
+            (
+                buffer.buffer().opaque_capture_address().unwrap(),
+                memory.opaque_capture_address().unwrap(),
+                buffer.device_address().unwrap(),
+            )
+        };
+
+        // Replay, after the captured buffer and its memory have been freed.
+        let buffer = create_buffer(buffer_capture_address, memory_capture_address);
+        assert_eq!(buffer.device_address().unwrap(), device_address);
+        assert_eq!(
+            memory_of(buffer.buffer()).opaque_capture_address().unwrap(),
+            memory_capture_address,
+        );
+    }
+
     #[test]
     fn create_empty_buffer() {
         let (device, _) = gfx_dev_and_queue!();
@@ -38,7 +38,7 @@
 //! .unwrap();
 //! ```
 
-use super::{BufferUsage, Subbuffer};
+use super::{BufferContents, BufferUsage, Subbuffer};
 use crate::{
     device::{Device, DeviceOwned},
     format::{Format, FormatFeatures},
@@ -46,7 +46,13 @@ use crate::{
     memory::{is_aligned, DeviceAlignment},
     DeviceSize, Validated, ValidationError, Version, VulkanError, VulkanObject,
 };
-use std::{mem::MaybeUninit, num::NonZeroU64, ops::Range, ptr, sync::Arc};
+use std::{
+    mem::{size_of, MaybeUninit},
+    num::NonZeroU64,
+    ops::Range,
+    ptr,
+    sync::Arc,
+};
 
 /// Represents a way for the GPU to interpret buffer data. See the documentation of the
 /// `view` module.
@@ -74,6 +80,77 @@ impl BufferView {
         unsafe { Ok(Self::new_unchecked(subbuffer, create_info)?) }
     }
 
+    /// Creates a new `BufferView` of a slice of `T`, where each `T` is one texel block of
+    /// `format`.
+    ///
+    /// This is the same as [`new`], but additionally checks that the size of `T` equals the
+    /// [block size] of `format`, so that the elements of the slice line up with the texels that
+    /// shaders see.
+    ///
+    /// [`new`]: Self::new
+    /// [block size]: Format::block_size
+    pub fn new_typed<T>(
+        subbuffer: Subbuffer<[T]>,
+        format: Format,
+    ) -> Result<Arc<BufferView>, Validated<VulkanError>>
+    where
+        T: BufferContents,
+    {
+        let subbuffer = subbuffer.into_bytes();
+        let create_info = BufferViewCreateInfo {
+            format,
+            ..Default::default()
+        };
+        Self::validate_new(&subbuffer, &create_info)?;
+
+        if size_of::<T>() as DeviceSize != format.block_size() {
+            return Err(Box::new(ValidationError {
+                context: "format".into(),
+                problem: "has a block size that is not equal to the size of `T`".into(),
+                ..Default::default()
+            })
+            .into());
+        }
+
+        unsafe { Ok(Self::new_unchecked(subbuffer, create_info)?) }
+    }
+
+    /// Returns the formats that can be used to create a view of a buffer with the given `usage`
+    /// on `device`.
+    ///
+    /// A format is included if it is supported by `device`, and its buffer features include
+    /// [`UNIFORM_TEXEL_BUFFER`] if `usage` contains [`BufferUsage::UNIFORM_TEXEL_BUFFER`], and
+    /// [`STORAGE_TEXEL_BUFFER`] if `usage` contains [`BufferUsage::STORAGE_TEXEL_BUFFER`]. If
+    /// `usage` contains neither, no format can be used and the returned list is empty.
+    ///
+    /// [`UNIFORM_TEXEL_BUFFER`]: FormatFeatures::UNIFORM_TEXEL_BUFFER
+    /// [`STORAGE_TEXEL_BUFFER`]: FormatFeatures::STORAGE_TEXEL_BUFFER
+    pub fn supported_formats(device: &Device, usage: BufferUsage) -> Vec<Format> {
+        if !usage.intersects(BufferUsage::UNIFORM_TEXEL_BUFFER | BufferUsage::STORAGE_TEXEL_BUFFER)
+        {
+            return Vec::new();
+        }
+
+        let mut required_features = FormatFeatures::empty();
+
+        if usage.intersects(BufferUsage::UNIFORM_TEXEL_BUFFER) {
+            required_features |= FormatFeatures::UNIFORM_TEXEL_BUFFER;
+        }
+
+        if usage.intersects(BufferUsage::STORAGE_TEXEL_BUFFER) {
+            required_features |= FormatFeatures::STORAGE_TEXEL_BUFFER;
+        }
+
+        Format::ALL
+            .iter()
+            .copied()
+            .filter(|format| {
+                format.validate_device(device).is_ok()
+                    && unsafe { get_format_features(*format, device) }.contains(required_features)
+            })
+            .collect()
+    }
+
     fn validate_new(
         subbuffer: &Subbuffer<[u8]>,
         create_info: &BufferViewCreateInfo,
@@ -89,12 +166,7 @@ impl BufferView {
         let buffer = subbuffer.buffer();
         let properties = device.physical_device().properties();
 
-        let format_features = unsafe {
-            device
-                .physical_device()
-                .format_properties_unchecked(format)
-                .buffer_features
-        };
+        let format_features = unsafe { get_format_features(format, device) };
 
         if !buffer
             .usage()
@@ -149,8 +221,8 @@ impl BufferView {
             }));
         }
 
-        if ((subbuffer.size() / block_size) * texels_per_block as DeviceSize) as u32
-            > properties.max_texel_buffer_elements
+        if (subbuffer.size() / block_size) * texels_per_block as DeviceSize
+            > properties.max_texel_buffer_elements as DeviceSize
         {
             return Err(Box::new(ValidationError {
                 problem: "`subbuffer.size() / create_info.format.block_size() * \
@@ -164,12 +236,7 @@ impl BufferView {
 
         if device.api_version() >= Version::V1_3 || device.enabled_features().texel_buffer_alignment
         {
-            let element_size = DeviceAlignment::new(if block_size % 3 == 0 {
-                block_size / 3
-            } else {
-                block_size
-            })
-            .unwrap();
+            let element_size = DeviceAlignment::new(format.texel_buffer_element_size()).unwrap();
 
             if buffer.usage().intersects(BufferUsage::STORAGE_TEXEL_BUFFER) {
                 if properties
@@ -189,8 +256,8 @@ impl BufferView {
                                 `storage_texel_buffer_offset_single_texel_alignment` \
                                 property is `true`, but \
                                 `subbuffer.offset()` is not a multiple of the \
-                                minimum of `create_info.format.block_size()` and the \
-                                `storage_texel_buffer_offset_alignment_bytes` limit"
+                                minimum of `create_info.format.texel_buffer_element_size()` \
+                                and the `storage_texel_buffer_offset_alignment_bytes` limit"
                                 .into(),
                             vuids: &["VUID-VkBufferViewCreateInfo-buffer-02750"],
                             ..Default::default()
@@ -234,10 +301,10 @@ impl BufferView {
                             problem: "`subbuffer` was created with the \
                                 `BufferUsage::UNIFORM_TEXEL_BUFFER` usage, and the \
                                 `uniform_texel_buffer_offset_single_texel_alignment` \
-                                property is `false`, but \
+                                property is `true`, but \
                                 `subbuffer.offset()` is not a multiple of the \
-                                minimum of `create_info.format.block_size()` and the \
-                                `uniform_texel_buffer_offset_alignment_bytes` limit"
+                                minimum of `create_info.format.texel_buffer_element_size()` \
+                                and the `uniform_texel_buffer_offset_alignment_bytes` limit"
                                 .into(),
                             vuids: &["VUID-VkBufferViewCreateInfo-buffer-02751"],
                             ..Default::default()
@@ -330,13 +397,7 @@ impl BufferView {
     ) -> Arc<BufferView> {
         let &BufferViewCreateInfo { format, _ne: _ } = &create_info;
         let size = subbuffer.size();
-        let format_features = unsafe {
-            subbuffer
-                .device()
-                .physical_device()
-                .format_properties_unchecked(format)
-                .buffer_features
-        };
+        let format_features = unsafe { get_format_features(format, subbuffer.device()) };
 
         Arc::new(BufferView {
             handle,
@@ -371,6 +432,20 @@ impl BufferView {
     pub fn range(&self) -> Range<DeviceSize> {
         self.range.clone()
     }
+
+    /// Returns the number of texels that this view exposes, which is the number that shaders see
+    /// as the size of the texel buffer.
+    #[inline]
+    pub fn element_count(&self) -> DeviceSize {
+        let block_size = self.format.block_size();
+
+        if block_size == 0 {
+            return 0;
+        }
+
+        (self.range.end - self.range.start) / block_size
+            * self.format.texels_per_block() as DeviceSize
+    }
 }
 
 impl Drop for BufferView {
@@ -439,16 +514,121 @@ impl BufferViewCreateInfo {
     }
 }
 
+// https://registry.khronos.org/vulkan/specs/1.3-extensions/html/chap34.html#features-required-format-support
+unsafe fn get_format_features(format: Format, device: &Device) -> FormatFeatures {
+    // Use unchecked, because all validation should have been done before calling.
+    let mut format_features = device
+        .physical_device()
+        .format_properties_unchecked(format)
+        .buffer_features;
+
+    // Without `khr_format_feature_flags2`, the device can't report the "without format" features,
+    // so they are implied by the device features instead.
+    if !(device.api_version() >= Version::V1_3
+        || device.enabled_extensions().khr_format_feature_flags2)
+        && format.shader_storage_image_without_format()
+        && format_features.intersects(FormatFeatures::STORAGE_TEXEL_BUFFER)
+    {
+        if device
+            .enabled_features()
+            .shader_storage_image_read_without_format
+        {
+            format_features |= FormatFeatures::STORAGE_READ_WITHOUT_FORMAT;
+        }
+
+        if device
+            .enabled_features()
+            .shader_storage_image_write_without_format
+        {
+            format_features |= FormatFeatures::STORAGE_WRITE_WITHOUT_FORMAT;
+        }
+    }
+
+    format_features
+}
+
 #[cfg(test)]
 mod tests {
     use super::{BufferView, BufferViewCreateInfo};
     use crate::{
         buffer::{Buffer, BufferCreateInfo, BufferUsage},
-        format::Format,
+        format::{Format, FormatFeatures},
         memory::allocator::{AllocationCreateInfo, StandardMemoryAllocator},
+        Validated,
     };
     use std::sync::Arc;
 
+    #[test]
+    fn texel_buffer_element_size() {
+        assert_eq!(Format::R8G8B8A8_UNORM.texel_buffer_element_size(), 4);
+        assert_eq!(Format::R64_UINT.texel_buffer_element_size(), 8);
+        assert_eq!(Format::R32G32B32_SFLOAT.texel_buffer_element_size(), 4);
+        assert_eq!(Format::R16G16B16_UINT.texel_buffer_element_size(), 2);
+        assert_eq!(Format::R5G6B5_UNORM_PACK16.texel_buffer_element_size(), 2);
+        assert_eq!(
+            Format::B10G11R11_UFLOAT_PACK32.texel_buffer_element_size(),
+            4
+        );
+        assert_eq!(
+            Format::A2B10G10R10_UINT_PACK32.texel_buffer_element_size(),
+            4
+        );
+    }
+
+    #[test]
+    fn create_all_supported_formats() {
+        let (device, _) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+        // The formats of Vulkan 1.0.
+        for format in
+            (1..=184).filter_map(|raw| Format::try_from(ash::vk::Format::from_raw(raw)).ok())
+        {
+            let buffer_features = device
+                .physical_device()
+                .format_properties(format)
+                .unwrap()
+                .buffer_features;
+
+            for (feature, usage) in [
+                (
+                    FormatFeatures::UNIFORM_TEXEL_BUFFER,
+                    BufferUsage::UNIFORM_TEXEL_BUFFER,
+                ),
+                (
+                    FormatFeatures::STORAGE_TEXEL_BUFFER,
+                    BufferUsage::STORAGE_TEXEL_BUFFER,
+                ),
+            ] {
+                if !buffer_features.intersects(feature) {
+                    continue;
+                }
+
+                let buffer = Buffer::new_slice::<u8>(
+                    memory_allocator.clone(),
+                    BufferCreateInfo {
+                        usage,
+                        ..Default::default()
+                    },
+                    AllocationCreateInfo::default(),
+                    format.block_size() * 16,
+                )
+                .unwrap();
+                let view = BufferView::new(
+                    buffer,
+                    BufferViewCreateInfo {
+                        format,
+                        ..Default::default()
+                    },
+                )
+                .unwrap_or_else(|err| panic!("{format:?} with {usage:?}: {err}"));
+
+                assert_eq!(view.range(), 0..format.block_size() * 16);
+                assert_eq!(view.element_count(), 16 * format.texels_per_block() as u64,);
+            }
+        }
+    }
+
     #[test]
     fn create_uniform() {
         // `VK_FORMAT_R8G8B8A8_UNORM` guaranteed to be a supported format
@@ -585,4 +765,47 @@ mod tests {
             _ => panic!(),
         }
     }
+
+    #[test]
+    fn new_typed() {
+        let (device, _) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+        // `VK_FORMAT_R32_UINT` and `VK_FORMAT_R8G8B8A8_UNORM` are guaranteed to be supported.
+        let supported_formats =
+            BufferView::supported_formats(&device, BufferUsage::STORAGE_TEXEL_BUFFER);
+        assert!(supported_formats.contains(&Format::R32_UINT));
+        assert!(supported_formats.contains(&Format::R8G8B8A8_UNORM));
+        assert!(BufferView::supported_formats(&device, BufferUsage::TRANSFER_DST).is_empty());
+
+        let buffer = Buffer::new_slice::<u32>(
+            memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::STORAGE_TEXEL_BUFFER,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+            128,
+        )
+        .unwrap();
+
+        let view = BufferView::new_typed(buffer.clone(), Format::R32_UINT).unwrap();
+        assert_eq!(view.element_count(), 128);
+
+        // `VK_FORMAT_R32G32_UINT` is also guaranteed to be supported, but its texels are 8 bytes.
+        match BufferView::new_typed(buffer.clone(), Format::R32G32_UINT) {
+            Err(Validated::ValidationError(err)) => {
+                assert_eq!(err.context, "format");
+                assert_eq!(
+                    err.problem,
+                    "has a block size that is not equal to the size of `T`"
+                );
+            }
+            _ => panic!(),
+        }
+        assert!(
+            BufferView::new_typed(buffer.reinterpret::<[[u8; 4]]>(), Format::R8G8B8A8_UNORM)
+                .is_ok()
+        );
+    }
 }
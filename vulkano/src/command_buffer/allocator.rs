@@ -173,6 +173,20 @@ impl AllocationHandle {
 ///
 /// This allocator only needs to lock when a thread first allocates or when a thread that
 /// previously allocated exits. In all other cases, allocation is lock-free.
+///
+/// # Multi-threaded recording
+///
+/// The allocator is `Send` and `Sync`, so a single allocator can be shared between all threads
+/// that record command buffers, by wrapping it in an `Arc`. A [`RecordingCommandBuffer`] is
+/// neither `Send` nor `Sync`, which guarantees that it stays on the thread whose pool it was
+/// allocated from until recording is finished. This is compatible with work-stealing thread pools,
+/// because a task always runs to completion on the thread that started it. The finished
+/// [`CommandBuffer`] is `Send` and `Sync`, and can be dropped on any thread, which returns the
+/// command buffer to the pool that it was allocated from rather than to a pool of the current
+/// thread.
+///
+/// [`RecordingCommandBuffer`]: crate::command_buffer::RecordingCommandBuffer
+/// [`CommandBuffer`]: crate::command_buffer::CommandBuffer
 #[derive(Debug)]
 pub struct StandardCommandBufferAllocator {
     device: InstanceOwnedDebugWrapper<Arc<Device>>,
@@ -588,3 +602,73 @@ impl From<ResetCommandPoolError> for Validated<ResetCommandPoolError> {
         Self::Error(err)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_buffer::{
+        CommandBufferBeginInfo, CommandBufferUsage, RecordingCommandBuffer,
+    };
+    use std::{sync::mpsc, thread};
+
+    #[test]
+    fn deallocate_on_other_thread() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let queue_family_index = queue.queue_family_index();
+
+        // Small pools, so that pools are frequently reset and reused while command buffers that
+        // were allocated from them are being dropped on another thread.
+        let allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device,
+            StandardCommandBufferAllocatorCreateInfo {
+                primary_buffer_count: 2,
+                ..Default::default()
+            },
+        ));
+        let (sender, receiver) = mpsc::channel();
+
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                let allocator = allocator.clone();
+                let sender = sender.clone();
+
+                scope.spawn(move || {
+                    for _ in 0..256 {
+                        let builder = RecordingCommandBuffer::new(
+                            allocator.clone(),
+                            queue_family_index,
+                            CommandBufferLevel::Primary,
+                            CommandBufferBeginInfo {
+                                usage: CommandBufferUsage::OneTimeSubmit,
+                                ..Default::default()
+                            },
+                        )
+                        .unwrap();
+                        sender.send(builder.end().unwrap()).unwrap();
+                    }
+                });
+            }
+
+            drop(sender);
+
+            for command_buffer in receiver {
+                drop(command_buffer);
+            }
+        });
+
+        // The pools of the worker threads are reused by new threads.
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                RecordingCommandBuffer::new(
+                    allocator.clone(),
+                    queue_family_index,
+                    CommandBufferLevel::Primary,
+                    Default::default(),
+                )
+                .unwrap()
+                .end()
+                .unwrap();
+            });
+        });
+    }
+}
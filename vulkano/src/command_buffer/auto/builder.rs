@@ -1,20 +1,24 @@
 use super::{
-    CommandBuffer, CommandInfo, RenderPassCommand, Resource, ResourceUseRef2, SubmitState,
+    AttachmentOps, CommandBuffer, CommandInfo, RenderPassCommand, Resource, ResourceUseRef2,
+    SubmitState,
 };
 use crate::{
     buffer::{Buffer, IndexBuffer, Subbuffer},
     command_buffer::{
         allocator::CommandBufferAllocator,
+        commands::debug::CheckpointRecorder,
         sys::{CommandBufferBeginInfo, RawRecordingCommandBuffer},
-        CommandBufferBufferRangeUsage, CommandBufferBufferUsage, CommandBufferImageRangeUsage,
-        CommandBufferImageUsage, CommandBufferInheritanceInfo,
+        CheckpointGranularity, CommandBufferBufferRangeUsage, CommandBufferBufferUsage,
+        CommandBufferImageRangeUsage, CommandBufferImageUsage, CommandBufferInheritanceInfo,
         CommandBufferInheritanceRenderPassType, CommandBufferLevel, CommandBufferResourcesUsage,
-        CommandBufferUsage, RenderingInfo, ResourceUseRef, SecondaryCommandBufferBufferUsage,
-        SecondaryCommandBufferImageUsage, SecondaryCommandBufferResourcesUsage, SubpassContents,
+        CommandBufferUsage, RenderingInfo, ResourceInCommand, ResourceUseRef,
+        SecondaryCommandBufferBufferUsage, SecondaryCommandBufferImageUsage,
+        SecondaryCommandBufferResourcesUsage, SubpassContents, TransferFallback,
     },
     descriptor_set::{DescriptorSetResources, DescriptorSetWithOffsets},
     device::{Device, DeviceOwned},
     image::{view::ImageView, Image, ImageAspects, ImageLayout, ImageSubresourceRange},
+    instance::debug::{self, DebugUtilsMessageSeverity, DebugUtilsMessageType},
     pipeline::{
         graphics::{
             color_blend::LogicOp,
@@ -31,7 +35,7 @@ use crate::{
     query::{QueryControlFlags, QueryPool, QueryType},
     range_map::RangeMap,
     range_set::RangeSet,
-    render_pass::{Framebuffer, Subpass},
+    render_pass::{AttachmentLoadOp, AttachmentStoreOp, Framebuffer, Subpass},
     sync::{
         AccessFlags, BufferMemoryBarrier, DependencyFlags, DependencyInfo, ImageMemoryBarrier,
         PipelineStageAccessFlags, PipelineStages,
@@ -43,7 +47,7 @@ use parking_lot::{Mutex, RwLockReadGuard};
 use smallvec::SmallVec;
 use std::{
     collections::hash_map::Entry,
-    fmt::Debug,
+    fmt::{Debug, Write},
     mem::take,
     ops::{Range, RangeInclusive},
     sync::{atomic::AtomicBool, Arc},
@@ -56,6 +60,15 @@ use std::{
 ///
 /// Note that command buffers in the recording state don't implement the `Send` and `Sync` traits.
 /// Once a command buffer has finished recording, however, it *does* implement `Send` and `Sync`.
+/// To record on multiple threads, create a separate command buffer on each thread, and send the
+/// finished command buffers where they are needed.
+///
+/// ```compile_fail
+/// # use vulkano::command_buffer::RecordingCommandBuffer;
+/// fn assert_send<T: Send>() {}
+///
+/// assert_send::<RecordingCommandBuffer>();
+/// ```
 pub struct RecordingCommandBuffer {
     pub(in crate::command_buffer) inner: RawRecordingCommandBuffer,
     commands: Vec<(
@@ -63,6 +76,11 @@ pub struct RecordingCommandBuffer {
         Box<dyn Fn(&mut RawRecordingCommandBuffer) + Send + Sync + 'static>,
     )>,
     pub(in crate::command_buffer) builder_state: CommandBufferBuilderState,
+    pub(in crate::command_buffer) automatic_checkpoints: Option<CheckpointGranularity>,
+    // The names of the debug labels that were begun, only recorded if automatic checkpoints are
+    // enabled.
+    pub(in crate::command_buffer) debug_label_names: Vec<String>,
+    pub(in crate::command_buffer) transfer_fallback: Option<Arc<dyn TransferFallback>>,
 }
 
 impl RecordingCommandBuffer {
@@ -129,6 +147,9 @@ impl RecordingCommandBuffer {
             inner,
             commands: Vec::new(),
             builder_state,
+            automatic_checkpoints: None,
+            debug_label_names: Vec::new(),
+            transfer_fallback: None,
         })
     }
 
@@ -165,6 +186,18 @@ impl RecordingCommandBuffer {
 
     #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
     pub unsafe fn end_unchecked(mut self) -> Result<Arc<CommandBuffer>, Validated<VulkanError>> {
+        if self.device().performance_warnings() {
+            for warning in self.performance_warnings() {
+                debug::submit_message(
+                    self.device().instance(),
+                    DebugUtilsMessageSeverity::INFO,
+                    DebugUtilsMessageType::PERFORMANCE,
+                    warning.message_id_name,
+                    &warning.message,
+                );
+            }
+        }
+
         let mut auto_sync_state = AutoSyncState::new(
             self.device().clone(),
             self.inner.level(),
@@ -177,14 +210,26 @@ impl RecordingCommandBuffer {
         // Add barriers between the commands.
         for (command_info, _) in self.commands.iter() {
             auto_sync_state.add_command(command_info).map_err(|err| {
-                Box::new(ValidationError {
-                    problem: format!(
-                        "unsolvable resource conflict between:\n\
-                        command resource use: {:?}\n\
-                        previous conflicting command resource use: {:?}",
-                        err.current_use_ref, err.previous_use_ref,
+                let mut problem = format!(
+                    "unsolvable resource conflict between:\n\
+                    command resource use: {:?}\n\
+                    previous conflicting command resource use: {:?}",
+                    err.current_use_ref, err.previous_use_ref,
+                );
+
+                if let Some((previous_layout, current_layout)) = err.layouts {
+                    write!(
+                        problem,
+                        "\nthe command resource use requires the image to be in the {:?} \
+                        layout, but the previous conflicting command resource use leaves it in \
+                        the {:?} layout, and a layout transition can't be inserted between them",
+                        current_layout, previous_layout,
                     )
-                    .into(),
+                    .unwrap();
+                }
+
+                Box::new(ValidationError {
+                    problem: problem.into(),
                     ..Default::default()
                 })
             })?;
@@ -192,9 +237,19 @@ impl RecordingCommandBuffer {
 
         let (mut barriers, resources_usage, secondary_resources_usage) = auto_sync_state.build();
         let final_barrier_index = self.commands.len();
+        let mut checkpoint_recorder = self
+            .automatic_checkpoints
+            .map(|granularity| {
+                CheckpointRecorder::new(
+                    &mut self.inner,
+                    granularity,
+                    take(&mut self.debug_label_names),
+                )
+            })
+            .transpose()?;
 
         // Record all the commands and barriers to the inner command buffer.
-        for (command_index, (_, record_func)) in self.commands.iter().enumerate() {
+        for (command_index, (command_info, record_func)) in self.commands.iter().enumerate() {
             if let Some(barriers) = barriers.remove(&command_index) {
                 for dependency_info in barriers {
                     unsafe {
@@ -209,6 +264,14 @@ impl RecordingCommandBuffer {
                 }
             }
 
+            if let Some(checkpoint_recorder) = &mut checkpoint_recorder {
+                checkpoint_recorder.record_command(
+                    &mut self.inner,
+                    command_index,
+                    command_info.name,
+                );
+            }
+
             record_func(&mut self.inner);
         }
 
@@ -229,6 +292,11 @@ impl RecordingCommandBuffer {
 
         debug_assert!(barriers.is_empty());
 
+        let (checkpoints, checkpoint_marker_buffer) = checkpoint_recorder
+            .map_or_else(Default::default, |checkpoint_recorder| {
+                checkpoint_recorder.finish(&mut self.inner, final_barrier_index)
+            });
+
         let submit_state = match self.inner.usage() {
             CommandBufferUsage::MultipleSubmit => SubmitState::ExclusiveUse {
                 in_use: AtomicBool::new(false),
@@ -250,6 +318,8 @@ impl RecordingCommandBuffer {
             secondary_resources_usage,
             state: Mutex::new(Default::default()),
             submit_state,
+            checkpoints,
+            checkpoint_marker_buffer,
         }))
     }
 
@@ -277,17 +347,79 @@ impl RecordingCommandBuffer {
         ));
     }
 
+    /// Adds the index and name of the command that is being recorded to `err`.
+    pub(in crate::command_buffer) fn command_error(
+        &self,
+        err: Box<ValidationError>,
+        name: &'static str,
+    ) -> Box<ValidationError> {
+        err.add_command(self.commands.len(), name, &[])
+    }
+
+    /// Adds the index and name of the command that is being recorded to `err`, and the debug
+    /// names of the pipeline and the descriptor sets that are bound to `pipeline_bind_point`.
+    pub(in crate::command_buffer) fn pipeline_command_error(
+        &self,
+        err: Box<ValidationError>,
+        name: &'static str,
+        pipeline_bind_point: PipelineBindPoint,
+    ) -> Box<ValidationError> {
+        let device = self.device();
+        let mut objects = Vec::new();
+
+        let pipeline_name = match pipeline_bind_point {
+            PipelineBindPoint::Compute => self
+                .builder_state
+                .pipeline_compute
+                .as_ref()
+                .and_then(|pipeline| device.debug_utils_object_name(&**pipeline)),
+            PipelineBindPoint::Graphics => self
+                .builder_state
+                .pipeline_graphics
+                .as_ref()
+                .and_then(|pipeline| device.debug_utils_object_name(&**pipeline)),
+            _ => None,
+        };
+
+        if let Some(pipeline_name) = pipeline_name {
+            objects.push(("pipeline".into(), pipeline_name));
+        }
+
+        if let Some(state) = self.builder_state.descriptor_sets.get(&pipeline_bind_point) {
+            let mut descriptor_sets: Vec<_> = state
+                .descriptor_sets
+                .iter()
+                .filter_map(|(&set_num, set)| match set {
+                    SetOrPush::Set(set) => device
+                        .debug_utils_object_name(&**set.as_ref().0)
+                        .map(|name| (set_num, name)),
+                    SetOrPush::Push(_) => None,
+                })
+                .collect();
+            descriptor_sets.sort_unstable_by_key(|&(set_num, _)| set_num);
+
+            objects.extend(
+                descriptor_sets
+                    .into_iter()
+                    .map(|(set_num, name)| (format!("set {}", set_num).into(), name)),
+            );
+        }
+
+        err.add_command(self.commands.len(), name, &objects)
+    }
+
     pub(in crate::command_buffer) fn add_render_pass_begin(
         &mut self,
         name: &'static str,
         used_resources: Vec<(ResourceUseRef2, Resource)>,
+        attachment_ops: Vec<AttachmentOps>,
         record_func: impl Fn(&mut RawRecordingCommandBuffer) + Send + Sync + 'static,
     ) {
         self.commands.push((
             CommandInfo {
                 name,
                 used_resources,
-                render_pass: RenderPassCommand::Begin,
+                render_pass: RenderPassCommand::Begin(attachment_ops),
             },
             Box::new(record_func),
         ));
@@ -310,6 +442,187 @@ impl RecordingCommandBuffer {
     }
 }
 
+impl RecordingCommandBuffer {
+    /// Looks for attachment load and store operations in the recorded render pass instances that
+    /// waste memory bandwidth.
+    ///
+    /// See [`Device::set_performance_warnings`] for the problems that are reported.
+    pub(in crate::command_buffer) fn performance_warnings(&self) -> Vec<PerformanceWarning> {
+        let mut warnings = Vec::new();
+
+        for (begin_index, (command_info, _)) in self.commands.iter().enumerate() {
+            let RenderPassCommand::Begin(attachment_ops) = &command_info.render_pass else {
+                continue;
+            };
+
+            // The contents of the attachments are only stored when the render pass instance ends.
+            let end_index = self.commands[begin_index..]
+                .iter()
+                .position(|(command_info, _)| {
+                    matches!(command_info.render_pass, RenderPassCommand::End)
+                })
+                .map(|position| begin_index + position);
+
+            for ops in attachment_ops {
+                if ops.load_op == AttachmentLoadOp::Load {
+                    let reason = if ops.initial_layout == ImageLayout::Undefined {
+                        Some("its initial layout is `ImageLayout::Undefined`".to_owned())
+                    } else {
+                        self.commands[..begin_index]
+                            .iter()
+                            .enumerate()
+                            .rev()
+                            .find_map(|(index, (command_info, _))| {
+                                find_attachment_use(command_info, ops).map(|use_| (index, use_))
+                            })
+                            .and_then(|(index, use_)| match use_ {
+                                AttachmentUse::RenderPass(previous_ops)
+                                    if previous_ops.store_op == AttachmentStoreOp::DontCare
+                                        && previous_ops.render_area_covers =>
+                                {
+                                    Some(format!(
+                                        "the render pass instance begun by command {} (`{}`) \
+                                        does not store it",
+                                        index, self.commands[index].0.name,
+                                    ))
+                                }
+                                _ => None,
+                            })
+                    };
+
+                    if let Some(reason) = reason {
+                        warnings.push(PerformanceWarning {
+                            message_id_name: "Vulkano-Performance-LoadOpUndefinedContents",
+                            message: format!(
+                                "the {:?} aspects of {:?} of the render pass instance begun by \
+                                command {} (`{}`) are loaded with `AttachmentLoadOp::Load`, but \
+                                their contents are undefined because {}; use \
+                                `AttachmentLoadOp::DontCare` or `AttachmentLoadOp::Clear` \
+                                instead to avoid reading them from memory",
+                                ops.subresource_range.aspects,
+                                ops.resource_in_command,
+                                begin_index,
+                                command_info.name,
+                                reason,
+                            ),
+                        });
+                    }
+                }
+
+                if ops.store_op == AttachmentStoreOp::Store {
+                    let Some(end_index) = end_index else {
+                        continue;
+                    };
+
+                    let next_use = self.commands[end_index + 1..].iter().enumerate().find_map(
+                        |(index, (command_info, _))| {
+                            find_attachment_use(command_info, ops)
+                                .map(|use_| (end_index + 1 + index, use_))
+                        },
+                    );
+
+                    let discarded_by = match next_use {
+                        // `AttachmentLoadOp::None` preserves the contents, like `Load` does. The
+                        // contents outside of a partial render area are also preserved.
+                        Some((index, AttachmentUse::RenderPass(next_ops)))
+                            if matches!(
+                                next_ops.load_op,
+                                AttachmentLoadOp::Clear | AttachmentLoadOp::DontCare
+                            ) && next_ops.render_area_covers =>
+                        {
+                            Some(index)
+                        }
+                        Some((
+                            index,
+                            AttachmentUse::Other {
+                                memory_access,
+                                covers: true,
+                            },
+                        )) if memory_access == PipelineStageAccessFlags::Clear_TransferWrite => {
+                            Some(index)
+                        }
+                        _ => None,
+                    };
+
+                    if let Some(index) = discarded_by {
+                        warnings.push(PerformanceWarning {
+                            message_id_name: "Vulkano-Performance-StoreOpContentsDiscarded",
+                            message: format!(
+                                "the {:?} aspects of {:?} of the render pass instance begun by \
+                                command {} (`{}`) are stored with `AttachmentStoreOp::Store`, \
+                                but command {} (`{}`) overwrites their contents before they \
+                                are read; use `AttachmentStoreOp::DontCare` instead to avoid \
+                                writing them to memory",
+                                ops.subresource_range.aspects,
+                                ops.resource_in_command,
+                                begin_index,
+                                command_info.name,
+                                index,
+                                self.commands[index].0.name,
+                            ),
+                        });
+                    }
+                }
+            }
+        }
+
+        warnings
+    }
+}
+
+/// A performance problem that was found in a command buffer while building it.
+#[derive(Clone, Debug)]
+pub(in crate::command_buffer) struct PerformanceWarning {
+    pub(in crate::command_buffer) message_id_name: &'static str,
+    pub(in crate::command_buffer) message: String,
+}
+
+// How a command uses the image of an attachment of another render pass instance.
+enum AttachmentUse<'a> {
+    // The image is also an attachment of the render pass instance that the command begins.
+    RenderPass(&'a AttachmentOps),
+    // The image is used in another way. `covers` is whether the use includes every subresource
+    // of the attachment.
+    Other {
+        memory_access: PipelineStageAccessFlags,
+        covers: bool,
+    },
+}
+
+fn find_attachment_use<'a>(
+    command_info: &'a CommandInfo,
+    ops: &AttachmentOps,
+) -> Option<AttachmentUse<'a>> {
+    if let RenderPassCommand::Begin(attachment_ops) = &command_info.render_pass {
+        if let Some(other_ops) = attachment_ops.iter().find(|other_ops| {
+            other_ops.image == ops.image
+                && subresource_ranges_overlap(&other_ops.subresource_range, &ops.subresource_range)
+        }) {
+            return Some(AttachmentUse::RenderPass(other_ops));
+        }
+    }
+
+    command_info
+        .used_resources
+        .iter()
+        .find_map(|(_, resource)| match resource {
+            Resource::Image {
+                image,
+                subresource_range,
+                memory_access,
+                ..
+            } if *image == ops.image
+                && subresource_ranges_overlap(subresource_range, &ops.subresource_range) =>
+            {
+                Some(AttachmentUse::Other {
+                    memory_access: *memory_access,
+                    covers: subresource_range_contains(subresource_range, &ops.subresource_range),
+                })
+            }
+            _ => None,
+        })
+}
+
 unsafe impl DeviceOwned for RecordingCommandBuffer {
     #[inline]
     fn device(&self) -> &Arc<Device> {
@@ -493,7 +806,7 @@ impl AutoSyncState {
 
         match command_info.render_pass {
             RenderPassCommand::None => (),
-            RenderPassCommand::Begin => {
+            RenderPassCommand::Begin(_) => {
                 debug_assert!(self.latest_render_pass_enter.is_none());
                 self.latest_render_pass_enter = Some(self.command_index);
             }
@@ -518,7 +831,7 @@ impl AutoSyncState {
             ..
         } = command_info;
 
-        for (use_ref, resource) in used_resources {
+        for (resource_index, (use_ref, resource)) in used_resources.iter().enumerate() {
             match *resource {
                 Resource::Buffer {
                     ref buffer,
@@ -542,6 +855,7 @@ impl AutoSyncState {
                                 secondary_use_ref: use_ref.secondary_use_ref,
                             },
                             previous_use_ref,
+                            layouts: None,
                         });
                     }
                 }
@@ -566,7 +880,14 @@ impl AutoSyncState {
                     debug_assert!(end_layout != ImageLayout::Undefined);
                     debug_assert!(end_layout != ImageLayout::Preinitialized);
 
-                    if let Some(previous_use) = self.find_image_conflict(
+                    let current_use_ref = ResourceUseRef {
+                        command_index: self.command_index,
+                        command_name,
+                        resource_in_command: use_ref.resource_in_command,
+                        secondary_use_ref: use_ref.secondary_use_ref,
+                    };
+
+                    if let Some((previous_use_ref, previous_layout)) = self.find_image_conflict(
                         image,
                         subresource_range.clone(),
                         memory_access,
@@ -574,15 +895,56 @@ impl AutoSyncState {
                         end_layout,
                     ) {
                         return Err(UnsolvableResourceConflict {
-                            current_use_ref: ResourceUseRef {
-                                command_index: self.command_index,
-                                command_name,
-                                resource_in_command: use_ref.resource_in_command,
-                                secondary_use_ref: use_ref.secondary_use_ref,
-                            },
-                            previous_use_ref: previous_use,
+                            current_use_ref,
+                            previous_use_ref,
+                            layouts: (previous_layout != start_layout
+                                && start_layout != ImageLayout::Undefined)
+                                .then_some((previous_layout, start_layout)),
                         });
                     }
+
+                    // The layout of a descriptor is fixed when the descriptor set is written, so
+                    // if the command also uses the same subresources with another layout, then
+                    // one of the two uses will see the wrong layout.
+                    if matches!(
+                        use_ref.resource_in_command,
+                        ResourceInCommand::DescriptorSet { .. }
+                    ) && start_layout != ImageLayout::Undefined
+                    {
+                        let previous_use = used_resources[..resource_index].iter().find_map(
+                            |(other_use_ref, other_resource)| match *other_resource {
+                                Resource::Image {
+                                    image: ref other_image,
+                                    subresource_range: ref other_subresource_range,
+                                    start_layout: other_start_layout,
+                                    ..
+                                } if other_image == image
+                                    && other_start_layout != ImageLayout::Undefined
+                                    && other_start_layout != start_layout
+                                    && subresource_ranges_overlap(
+                                        subresource_range,
+                                        other_subresource_range,
+                                    ) =>
+                                {
+                                    Some((other_use_ref, other_start_layout))
+                                }
+                                _ => None,
+                            },
+                        );
+
+                        if let Some((other_use_ref, other_layout)) = previous_use {
+                            return Err(UnsolvableResourceConflict {
+                                current_use_ref,
+                                previous_use_ref: ResourceUseRef {
+                                    command_index: self.command_index,
+                                    command_name,
+                                    resource_in_command: other_use_ref.resource_in_command,
+                                    secondary_use_ref: other_use_ref.secondary_use_ref,
+                                },
+                                layouts: Some((other_layout, start_layout)),
+                            });
+                        }
+                    }
                 }
             }
         }
@@ -625,6 +987,14 @@ impl AutoSyncState {
                 {
                     return Some(use_ref);
                 }
+            } else if let Some((write_use_ref, _)) = state.last_write {
+                // A read that is not yet synchronized with the last write needs a barrier after
+                // that write.
+                if !state.memory_access.contains(memory_access)
+                    && write_use_ref.command_index >= last_allowed_barrier_index
+                {
+                    return Some(write_use_ref);
+                }
             }
         }
 
@@ -638,7 +1008,7 @@ impl AutoSyncState {
         memory_access: PipelineStageAccessFlags,
         start_layout: ImageLayout,
         _end_layout: ImageLayout,
-    ) -> Option<ResourceUseRef> {
+    ) -> Option<(ResourceUseRef, ImageLayout)> {
         // Barriers work differently in render passes, so if we're in one, we can only insert a
         // barrier before the start of the render pass.
         let last_allowed_barrier_index =
@@ -673,7 +1043,7 @@ impl AutoSyncState {
                     if let Some(&use_ref) = state.resource_uses.iter().find(|resource_use| {
                         resource_use.command_index >= last_allowed_barrier_index
                     }) {
-                        return Some(use_ref);
+                        return Some((use_ref, state.current_layout));
                     }
                 }
             }
@@ -777,6 +1147,7 @@ impl AutoSyncState {
                     BufferState {
                         resource_uses: Vec::new(),
                         memory_access: PipelineStageAccessFlags::empty(),
+                        last_write: None,
                         is_written: false,
                     },
                 )]
@@ -793,6 +1164,10 @@ impl AutoSyncState {
                 state.memory_access = memory_access;
                 state.is_written = memory_access.contains_write();
 
+                if state.is_written {
+                    state.last_write = Some((use_ref, memory_access));
+                }
+
                 match self.level {
                     CommandBufferLevel::Primary => {
                         // To be safe, we insert a barrier for all stages and accesses before
@@ -857,8 +1232,46 @@ impl AutoSyncState {
                     // Update state.
                     state.memory_access = memory_access;
                     state.is_written = true;
+
+                    if memory_access.contains_write() {
+                        state.last_write = Some((use_ref, memory_access));
+                    }
+                } else if let Some((write_use_ref, write_access)) = state
+                    .last_write
+                    .filter(|_| !state.memory_access.contains(memory_access))
+                {
+                    // Reads never collide with each other, but this read uses stages or accesses
+                    // that the barrier after the last write did not include, so it must be made
+                    // to wait for that write as well.
+                    if write_use_ref.command_index >= self.first_unflushed {
+                        self.barriers
+                            .entry(self.first_unflushed)
+                            .or_default()
+                            .push(take(&mut self.pending_barrier));
+                        self.first_unflushed = last_allowed_barrier_index;
+                    }
+
+                    let dst_memory_access = memory_access - state.memory_access;
+
+                    self.pending_barrier
+                        .buffer_memory_barriers
+                        .push(BufferMemoryBarrier {
+                            src_stages: PipelineStages::from(write_access)
+                                .into_supported(&self.device),
+                            src_access: AccessFlags::from(write_access)
+                                .into_supported(&self.device),
+                            dst_stages: PipelineStages::from(dst_memory_access)
+                                .into_supported(&self.device),
+                            dst_access: AccessFlags::from(dst_memory_access)
+                                .into_supported(&self.device),
+                            range: range.clone(),
+                            ..BufferMemoryBarrier::buffer(buffer.buffer().clone())
+                        });
+
+                    state.memory_access |= memory_access;
                 } else {
-                    // There is no collision. Simply merge the accesses.
+                    // There is no collision, and the accesses are already synchronized with any
+                    // previous write. Simply merge the accesses.
                     state.memory_access |= memory_access;
                 }
 
@@ -905,35 +1318,44 @@ impl AutoSyncState {
             subresource_range.aspects = ImageAspects::DEPTH | ImageAspects::STENCIL;
         }
 
-        let range_map = self.images.entry(image.clone()).or_insert_with(|| {
-            [(
-                0..image.range_size(),
-                match self.level {
-                    CommandBufferLevel::Primary => {
-                        // In a primary command buffer, the initial layout is determined
-                        // by the image.
-                        let initial_layout = if !image.is_layout_initialized() {
-                            unsafe {
-                                image.layout_initialized();
-                            }
-
-                            image.initial_layout()
-                        } else {
-                            image.initial_layout_requirement()
-                        };
+        let range_map = self
+            .images
+            .entry(image.clone())
+            .or_insert_with(|| match self.level {
+                CommandBufferLevel::Primary => {
+                    // In a primary command buffer, the initial layout is determined by the image.
+                    // Subresources whose layout hasn't been initialized yet are only transitioned
+                    // if the command buffer uses them, so that they can be initialized
+                    // independently, for example one mip level at a time.
+                    image
+                        .layout_initialized_ranges()
+                        .into_iter()
+                        .map(|(range, is_initialized)| {
+                            let layout = if is_initialized {
+                                image.initial_layout_requirement()
+                            } else {
+                                image.initial_layout()
+                            };
 
-                        ImageState {
-                            resource_uses: Vec::new(),
-                            memory_access: PipelineStageAccessFlags::empty(),
-                            is_written: false,
-                            initial_layout,
-                            current_layout: initial_layout,
-                            final_layout: image.final_layout_requirement(),
-                        }
-                    }
-                    CommandBufferLevel::Secondary => {
-                        // In a secondary command buffer, the initial layout is the layout
-                        // of the first use.
+                            (
+                                range,
+                                ImageState {
+                                    resource_uses: Vec::new(),
+                                    memory_access: PipelineStageAccessFlags::empty(),
+                                    is_written: false,
+                                    initial_layout: layout,
+                                    current_layout: layout,
+                                    final_layout: layout,
+                                },
+                            )
+                        })
+                        .collect()
+                }
+                CommandBufferLevel::Secondary => {
+                    // In a secondary command buffer, the initial layout is the layout
+                    // of the first use.
+                    [(
+                        0..image.range_size(),
                         ImageState {
                             resource_uses: Vec::new(),
                             memory_access: PipelineStageAccessFlags::empty(),
@@ -941,13 +1363,12 @@ impl AutoSyncState {
                             initial_layout: ImageLayout::Undefined,
                             current_layout: ImageLayout::Undefined,
                             final_layout: ImageLayout::Undefined,
-                        }
-                    }
-                },
-            )]
-            .into_iter()
-            .collect()
-        });
+                        },
+                    )]
+                    .into_iter()
+                    .collect()
+                }
+            });
 
         for range in image.iter_ranges(subresource_range) {
             range_map.split_at(&range.start);
@@ -966,6 +1387,13 @@ impl AutoSyncState {
 
                     match self.level {
                         CommandBufferLevel::Primary => {
+                            // The layout of an uninitialized range is `Undefined` or
+                            // `Preinitialized`, which an image never requires.
+                            if state.final_layout != image.final_layout_requirement() {
+                                unsafe { image.layout_initialized(range.clone()) };
+                                state.final_layout = image.final_layout_requirement();
+                            }
+
                             // To be safe, we insert a barrier for all stages and accesses before
                             // the first use, so that there are no hazards with any command buffer
                             // that was previously submitted to the same queue.
@@ -1122,6 +1550,25 @@ impl AutoSyncState {
 struct UnsolvableResourceConflict {
     current_use_ref: ResourceUseRef,
     previous_use_ref: ResourceUseRef,
+    // If the conflict is caused by the image layouts, the layout that the image is in after the
+    // previous use, and the layout that the current use requires.
+    layouts: Option<(ImageLayout, ImageLayout)>,
+}
+
+fn subresource_ranges_overlap(a: &ImageSubresourceRange, b: &ImageSubresourceRange) -> bool {
+    a.aspects.intersects(b.aspects)
+        && a.mip_levels.start < b.mip_levels.end
+        && b.mip_levels.start < a.mip_levels.end
+        && a.array_layers.start < b.array_layers.end
+        && b.array_layers.start < a.array_layers.end
+}
+
+fn subresource_range_contains(a: &ImageSubresourceRange, b: &ImageSubresourceRange) -> bool {
+    a.aspects.contains(b.aspects)
+        && a.mip_levels.start <= b.mip_levels.start
+        && b.mip_levels.end <= a.mip_levels.end
+        && a.array_layers.start <= b.array_layers.start
+        && b.array_layers.end <= a.array_layers.end
 }
 
 // State of a resource during the building of the command buffer.
@@ -1133,6 +1580,10 @@ struct BufferState {
     // Memory accesses performed since the last barrier.
     memory_access: PipelineStageAccessFlags,
 
+    // The use and memory accesses of the last command that wrote to the resource, if any.
+    // Reads that come after it must wait for it, even if they only conflict with each other.
+    last_write: Option<(ResourceUseRef, PipelineStageAccessFlags)>,
+
     // True if the resource was written to at any point during the command buffer.
     // Also true if an image layout transition or queue transfer has been performed.
     is_written: bool,
@@ -1174,7 +1625,8 @@ pub(in crate::command_buffer) struct CommandBufferBuilderState {
     pub(in crate::command_buffer) index_buffer: Option<IndexBuffer>,
     pub(in crate::command_buffer) pipeline_compute: Option<Arc<ComputePipeline>>,
     pub(in crate::command_buffer) pipeline_graphics: Option<Arc<GraphicsPipeline>>,
-    pub(in crate::command_buffer) vertex_buffers: HashMap<u32, Subbuffer<[u8]>>,
+    // `None` for the bindings that a null vertex buffer is bound to.
+    pub(in crate::command_buffer) vertex_buffers: HashMap<u32, Option<Subbuffer<[u8]>>>,
     pub(in crate::command_buffer) push_constants: RangeSet<u32>,
     pub(in crate::command_buffer) push_constants_pipeline_layout: Option<Arc<PipelineLayout>>,
 
@@ -1206,6 +1658,7 @@ pub(in crate::command_buffer) struct CommandBufferBuilderState {
     pub(in crate::command_buffer) stencil_test_enable: Option<bool>,
     pub(in crate::command_buffer) stencil_write_mask: StencilStateDynamic,
     pub(in crate::command_buffer) vertex_input: Option<VertexInputState>,
+    pub(in crate::command_buffer) vertex_input_binding_stride: HashMap<u32, u32>,
     pub(in crate::command_buffer) viewport: HashMap<u32, Viewport>,
     pub(in crate::command_buffer) viewport_with_count: Option<SmallVec<[Viewport; 2]>>,
     pub(in crate::command_buffer) conservative_rasterization_mode:
@@ -1261,7 +1714,7 @@ impl CommandBufferBuilderState {
                 DynamicState::StencilTestEnable => self.stencil_test_enable = None,
                 DynamicState::StencilWriteMask => self.stencil_write_mask = Default::default(),
                 DynamicState::VertexInput => self.vertex_input = None,
-                // DynamicState::VertexInputBindingStride => todo!(),
+                DynamicState::VertexInputBindingStride => self.vertex_input_binding_stride.clear(),
                 DynamicState::Viewport => self.viewport.clear(),
                 // DynamicState::ViewportCoarseSampleOrder => todo!(),
                 // DynamicState::ViewportShadingRatePalette => todo!(),
@@ -1415,6 +1868,15 @@ impl RenderPassState {
             },
         }
     }
+
+    /// Returns the index of the current subpass. This is always 0 when rendering was begun with
+    /// `begin_rendering`.
+    pub(in crate::command_buffer) fn subpass_index(&self) -> u32 {
+        match &self.render_pass {
+            RenderPassStateType::BeginRenderPass(state) => state.subpass.index(),
+            RenderPassStateType::BeginRendering(_) => 0,
+        }
+    }
 }
 
 pub(in crate::command_buffer) enum RenderPassStateType {
@@ -1655,3 +2117,396 @@ pub(in crate::command_buffer) struct QueryState {
     pub(in crate::command_buffer) flags: QueryControlFlags,
     pub(in crate::command_buffer) in_subpass: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{AutoSyncState, CommandInfo, RenderPassCommand, Resource, ResourceUseRef2};
+    use crate::{
+        buffer::{Buffer, BufferCreateInfo, BufferUsage, Subbuffer},
+        command_buffer::{CommandBufferLevel, ResourceInCommand},
+        device::Device,
+        format::Format,
+        image::{Image, ImageCreateInfo, ImageLayout, ImageSubresourceRange, ImageUsage},
+        memory::allocator::{AllocationCreateInfo, StandardMemoryAllocator},
+        sync::{
+            AccessFlags, BufferMemoryBarrier, ImageMemoryBarrier, PipelineStageAccessFlags,
+            PipelineStages,
+        },
+    };
+    use std::sync::Arc;
+
+    fn buffer_command(
+        buffer: &Subbuffer<[u8]>,
+        memory_access: PipelineStageAccessFlags,
+    ) -> CommandInfo {
+        CommandInfo {
+            name: "test",
+            used_resources: vec![(
+                ResourceUseRef2::from(ResourceInCommand::Source),
+                Resource::Buffer {
+                    buffer: buffer.clone(),
+                    range: 0..buffer.size(),
+                    memory_access,
+                },
+            )],
+            render_pass: RenderPassCommand::None,
+        }
+    }
+
+    fn image_command(
+        image: &Arc<Image>,
+        memory_access: PipelineStageAccessFlags,
+        layout: ImageLayout,
+    ) -> CommandInfo {
+        CommandInfo {
+            name: "test",
+            used_resources: vec![(
+                ResourceUseRef2::from(ResourceInCommand::Source),
+                Resource::Image {
+                    image: image.clone(),
+                    subresource_range: image.subresource_range(),
+                    memory_access,
+                    start_layout: layout,
+                    end_layout: layout,
+                },
+            )],
+            render_pass: RenderPassCommand::None,
+        }
+    }
+
+    fn test_buffer(device: &Arc<Device>) -> Subbuffer<[u8]> {
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+        Buffer::new_slice::<u8>(
+            memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST
+                    | BufferUsage::STORAGE_BUFFER
+                    | BufferUsage::VERTEX_BUFFER,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+            64,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn reads_in_different_stages_without_write() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let buffer = test_buffer(&device);
+
+        let mut state = AutoSyncState::new(device, CommandBufferLevel::Primary, false);
+        state
+            .add_command(&buffer_command(
+                &buffer,
+                PipelineStageAccessFlags::ComputeShader_ShaderStorageRead,
+            ))
+            .unwrap();
+        state
+            .add_command(&buffer_command(
+                &buffer,
+                PipelineStageAccessFlags::VertexAttributeInput_VertexAttributeRead,
+            ))
+            .unwrap();
+
+        // Only the barrier for the first use of the buffer, none between the two reads.
+        assert_eq!(state.pending_barrier.buffer_memory_barriers.len(), 1);
+        assert!(state.barriers.is_empty());
+    }
+
+    #[test]
+    fn reads_in_different_stages_after_write() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let buffer = test_buffer(&device);
+
+        let mut state = AutoSyncState::new(device, CommandBufferLevel::Primary, false);
+        state
+            .add_command(&buffer_command(
+                &buffer,
+                PipelineStageAccessFlags::Copy_TransferWrite,
+            ))
+            .unwrap();
+        state
+            .add_command(&buffer_command(
+                &buffer,
+                PipelineStageAccessFlags::ComputeShader_ShaderStorageRead,
+            ))
+            .unwrap();
+
+        // The first-use barrier was flushed before the write, and the read waits for the write.
+        assert_eq!(state.barriers[&0][0].buffer_memory_barriers.len(), 1);
+        assert_eq!(state.pending_barrier.buffer_memory_barriers.len(), 1);
+
+        // A read in another stage must also wait for the write.
+        state
+            .add_command(&buffer_command(
+                &buffer,
+                PipelineStageAccessFlags::VertexAttributeInput_VertexAttributeRead,
+            ))
+            .unwrap();
+        assert_eq!(state.pending_barrier.buffer_memory_barriers.len(), 2);
+
+        let barrier = &state.pending_barrier.buffer_memory_barriers[1];
+        assert!(barrier.src_access.intersects(AccessFlags::TRANSFER_WRITE));
+        assert!(barrier
+            .dst_access
+            .intersects(AccessFlags::VERTEX_ATTRIBUTE_READ));
+        assert!(!barrier
+            .dst_access
+            .intersects(AccessFlags::SHADER_STORAGE_READ));
+
+        // A read in a stage that was already synchronized needs nothing more.
+        state
+            .add_command(&buffer_command(
+                &buffer,
+                PipelineStageAccessFlags::ComputeShader_ShaderStorageRead,
+            ))
+            .unwrap();
+        assert_eq!(state.pending_barrier.buffer_memory_barriers.len(), 2);
+        assert_eq!(state.barriers.len(), 1);
+    }
+
+    #[test]
+    fn buffer_barrier_presets() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let buffer = test_buffer(&device);
+
+        let presets: [(fn(Subbuffer<[u8]>) -> BufferMemoryBarrier, _, _); 4] = [
+            (
+                BufferMemoryBarrier::compute_write_to_compute_read,
+                PipelineStageAccessFlags::ComputeShader_ShaderStorageWrite,
+                PipelineStageAccessFlags::ComputeShader_ShaderStorageRead,
+            ),
+            (
+                BufferMemoryBarrier::compute_write_to_indirect_read,
+                PipelineStageAccessFlags::ComputeShader_ShaderStorageWrite,
+                PipelineStageAccessFlags::DrawIndirect_IndirectCommandRead,
+            ),
+            (
+                BufferMemoryBarrier::compute_write_to_vertex_read,
+                PipelineStageAccessFlags::ComputeShader_ShaderStorageWrite,
+                PipelineStageAccessFlags::VertexAttributeInput_VertexAttributeRead,
+            ),
+            (
+                BufferMemoryBarrier::transfer_dst_to_vertex_read,
+                PipelineStageAccessFlags::Copy_TransferWrite,
+                PipelineStageAccessFlags::VertexAttributeInput_VertexAttributeRead,
+            ),
+        ];
+
+        for (preset, src, dst) in presets {
+            let mut state = AutoSyncState::new(device.clone(), CommandBufferLevel::Primary, false);
+            state.add_command(&buffer_command(&buffer, src)).unwrap();
+            state.add_command(&buffer_command(&buffer, dst)).unwrap();
+            let expected = state.pending_barrier.buffer_memory_barriers.last().unwrap();
+
+            let barrier = preset(buffer.clone());
+            assert_eq!(barrier.src_stages, expected.src_stages);
+            assert_eq!(barrier.src_access, expected.src_access);
+            assert_eq!(barrier.dst_stages, expected.dst_stages);
+            assert_eq!(barrier.dst_access, expected.dst_access);
+            assert_eq!(barrier.range, expected.range);
+        }
+    }
+
+    #[test]
+    fn image_barrier_presets() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let image = Image::new(
+            memory_allocator,
+            ImageCreateInfo {
+                format: Format::R8G8B8A8_UNORM,
+                extent: [16, 16, 1],
+                usage: ImageUsage::TRANSFER_SRC
+                    | ImageUsage::TRANSFER_DST
+                    | ImageUsage::SAMPLED
+                    | ImageUsage::STORAGE
+                    | ImageUsage::COLOR_ATTACHMENT,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+        )
+        .unwrap();
+
+        let presets: [(
+            fn(Arc<Image>, ImageSubresourceRange) -> ImageMemoryBarrier,
+            _,
+            _,
+        ); 4] = [
+            (
+                ImageMemoryBarrier::transfer_dst_to_sampled,
+                (
+                    PipelineStageAccessFlags::Copy_TransferWrite,
+                    ImageLayout::TransferDstOptimal,
+                ),
+                (
+                    PipelineStageAccessFlags::FragmentShader_ShaderSampledRead,
+                    ImageLayout::ShaderReadOnlyOptimal,
+                ),
+            ),
+            (
+                ImageMemoryBarrier::color_attachment_to_sampled,
+                (
+                    PipelineStageAccessFlags::ColorAttachmentOutput_ColorAttachmentWrite,
+                    ImageLayout::ColorAttachmentOptimal,
+                ),
+                (
+                    PipelineStageAccessFlags::FragmentShader_ShaderSampledRead,
+                    ImageLayout::ShaderReadOnlyOptimal,
+                ),
+            ),
+            (
+                ImageMemoryBarrier::color_attachment_to_transfer_src,
+                (
+                    PipelineStageAccessFlags::ColorAttachmentOutput_ColorAttachmentWrite,
+                    ImageLayout::ColorAttachmentOptimal,
+                ),
+                (
+                    PipelineStageAccessFlags::Copy_TransferRead,
+                    ImageLayout::TransferSrcOptimal,
+                ),
+            ),
+            (
+                ImageMemoryBarrier::compute_write_to_sampled,
+                (
+                    PipelineStageAccessFlags::ComputeShader_ShaderStorageWrite,
+                    ImageLayout::General,
+                ),
+                (
+                    PipelineStageAccessFlags::FragmentShader_ShaderSampledRead,
+                    ImageLayout::ShaderReadOnlyOptimal,
+                ),
+            ),
+        ];
+
+        for (preset, (src, src_layout), (dst, dst_layout)) in presets {
+            let mut state = AutoSyncState::new(device.clone(), CommandBufferLevel::Primary, false);
+            state
+                .add_command(&image_command(&image, src, src_layout))
+                .unwrap();
+            state
+                .add_command(&image_command(&image, dst, dst_layout))
+                .unwrap();
+            let expected = state.pending_barrier.image_memory_barriers.last().unwrap();
+
+            let barrier = preset(image.clone(), image.subresource_range());
+            assert_eq!(barrier.src_stages, expected.src_stages);
+            assert_eq!(barrier.src_access, expected.src_access);
+            assert_eq!(barrier.dst_stages, expected.dst_stages);
+            assert_eq!(barrier.dst_access, expected.dst_access);
+            assert_eq!(barrier.old_layout, expected.old_layout);
+            assert_eq!(barrier.new_layout, expected.new_layout);
+        }
+
+        // The automatic synchronization doesn't know what happened to an image before the
+        // command buffer, so it has nothing to compare this one with.
+        let barrier =
+            ImageMemoryBarrier::undefined_to_transfer_dst(image.clone(), image.subresource_range());
+        let next =
+            ImageMemoryBarrier::transfer_dst_to_sampled(image.clone(), image.subresource_range());
+        assert_eq!(barrier.src_stages, PipelineStages::empty());
+        assert_eq!(barrier.src_access, AccessFlags::empty());
+        assert_eq!(barrier.dst_stages, next.src_stages);
+        assert_eq!(barrier.dst_access, next.src_access);
+        assert_eq!(barrier.old_layout, ImageLayout::Undefined);
+        assert_eq!(barrier.new_layout, ImageLayout::TransferDstOptimal);
+    }
+
+    #[test]
+    fn descriptor_layout_mismatch_in_command() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let image = Image::new(
+            memory_allocator,
+            ImageCreateInfo {
+                format: Format::R8G8B8A8_UNORM,
+                extent: [16, 16, 1],
+                usage: ImageUsage::SAMPLED | ImageUsage::STORAGE,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+        )
+        .unwrap();
+
+        let descriptor = |binding, memory_access, layout| {
+            (
+                ResourceUseRef2::from(ResourceInCommand::DescriptorSet {
+                    set: 0,
+                    binding,
+                    index: 0,
+                }),
+                Resource::Image {
+                    image: image.clone(),
+                    subresource_range: image.subresource_range(),
+                    memory_access,
+                    start_layout: layout,
+                    end_layout: layout,
+                },
+            )
+        };
+
+        // The same image, written with a different layout in each descriptor.
+        let command_info = CommandInfo {
+            name: "dispatch",
+            used_resources: vec![
+                descriptor(
+                    0,
+                    PipelineStageAccessFlags::ComputeShader_ShaderSampledRead,
+                    ImageLayout::ShaderReadOnlyOptimal,
+                ),
+                descriptor(
+                    1,
+                    PipelineStageAccessFlags::ComputeShader_ShaderStorageRead,
+                    ImageLayout::General,
+                ),
+            ],
+            render_pass: RenderPassCommand::None,
+        };
+
+        let mut state = AutoSyncState::new(device.clone(), CommandBufferLevel::Primary, false);
+        let err = state.add_command(&command_info).unwrap_err();
+        assert_eq!(err.current_use_ref.command_index, 0);
+        assert_eq!(
+            err.current_use_ref.resource_in_command,
+            ResourceInCommand::DescriptorSet {
+                set: 0,
+                binding: 1,
+                index: 0,
+            },
+        );
+        assert_eq!(
+            err.previous_use_ref.resource_in_command,
+            ResourceInCommand::DescriptorSet {
+                set: 0,
+                binding: 0,
+                index: 0,
+            },
+        );
+        assert_eq!(
+            err.layouts,
+            Some((ImageLayout::ShaderReadOnlyOptimal, ImageLayout::General)),
+        );
+
+        // With the same layout in both descriptors, there is no conflict.
+        let command_info = CommandInfo {
+            used_resources: vec![
+                descriptor(
+                    0,
+                    PipelineStageAccessFlags::ComputeShader_ShaderSampledRead,
+                    ImageLayout::General,
+                ),
+                descriptor(
+                    1,
+                    PipelineStageAccessFlags::ComputeShader_ShaderStorageRead,
+                    ImageLayout::General,
+                ),
+            ],
+            ..command_info
+        };
+
+        let mut state = AutoSyncState::new(device, CommandBufferLevel::Primary, false);
+        state.add_command(&command_info).unwrap();
+    }
+}
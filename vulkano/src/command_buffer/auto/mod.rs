@@ -64,7 +64,7 @@ pub(in crate::command_buffer) use self::builder::{
 };
 use super::{
     sys::{RawCommandBuffer, RawRecordingCommandBuffer},
-    CommandBufferInheritanceInfo, CommandBufferLevel, CommandBufferResourcesUsage,
+    Checkpoint, CommandBufferInheritanceInfo, CommandBufferLevel, CommandBufferResourcesUsage,
     CommandBufferState, CommandBufferUsage, ResourceInCommand,
     SecondaryCommandBufferResourcesUsage, SecondaryResourceUseRef,
 };
@@ -72,6 +72,7 @@ use crate::{
     buffer::Subbuffer,
     device::{Device, DeviceOwned},
     image::{Image, ImageLayout, ImageSubresourceRange},
+    render_pass::{AttachmentLoadOp, AttachmentStoreOp},
     sync::PipelineStageAccessFlags,
     DeviceSize, ValidationError, VulkanObject,
 };
@@ -95,6 +96,8 @@ pub struct CommandBuffer {
     secondary_resources_usage: SecondaryCommandBufferResourcesUsage,
     state: Mutex<CommandBufferState>,
     submit_state: SubmitState,
+    checkpoints: Vec<Checkpoint>,
+    checkpoint_marker_buffer: Option<Subbuffer<[u32]>>,
 }
 
 unsafe impl VulkanObject for CommandBuffer {
@@ -152,6 +155,65 @@ impl CommandBuffer {
         self.inner.inheritance_info()
     }
 
+    /// Returns the number of times that this command buffer has been submitted to a queue.
+    ///
+    /// This is always 0 for secondary command buffers, which are not submitted directly.
+    #[inline]
+    pub fn submit_count(&self) -> u64 {
+        match self.level() {
+            CommandBufferLevel::Primary => self.state().submit_count(),
+            CommandBufferLevel::Secondary => 0,
+        }
+    }
+
+    /// Returns the checkpoints that were automatically inserted into the command buffer, in the
+    /// order that they are executed.
+    ///
+    /// This is empty unless [`RecordingCommandBuffer::set_automatic_checkpoints`] was called while
+    /// recording the command buffer.
+    #[inline]
+    pub fn checkpoints(&self) -> &[Checkpoint] {
+        &self.checkpoints
+    }
+
+    /// Returns the automatically inserted checkpoint with the given marker, if there is one in
+    /// this command buffer.
+    ///
+    /// This can be used to find out how far the execution of the command buffer got, after the
+    /// device was lost:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use vulkano::{command_buffer::CommandBuffer, device::Queue};
+    /// # let queue: Arc<Queue> = return;
+    /// # let command_buffer: Arc<CommandBuffer> = return;
+    /// // After an operation on the queue returned `VulkanError::DeviceLost`:
+    /// for checkpoint_data in unsafe { queue.checkpoint_data() }.unwrap() {
+    ///     if let Some(checkpoint) = command_buffer.checkpoint(checkpoint_data.marker) {
+    ///         println!(
+    ///             "{:?} last reached command {} (`{}`) in the region {:?}",
+    ///             checkpoint_data.stage,
+    ///             checkpoint.command_index,
+    ///             checkpoint.command_name,
+    ///             checkpoint.debug_labels,
+    ///         );
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn checkpoint(&self, marker: usize) -> Option<&Checkpoint> {
+        self.checkpoints
+            .binary_search_by_key(&marker, |checkpoint| checkpoint.marker)
+            .ok()
+            .map(|index| &self.checkpoints[index])
+    }
+
+    /// Returns the buffer that the automatic checkpoints are written to, if they are implemented
+    /// with the `amd_buffer_marker` extension.
+    pub(in crate::command_buffer) fn checkpoint_marker_buffer(&self) -> Option<&Subbuffer<[u32]>> {
+        self.checkpoint_marker_buffer.as_ref()
+    }
+
     pub(crate) fn state(&self) -> MutexGuard<'_, CommandBufferState> {
         debug_assert_eq!(self.level(), CommandBufferLevel::Primary);
 
@@ -296,10 +358,24 @@ struct CommandInfo {
 #[derive(Debug)]
 enum RenderPassCommand {
     None,
-    Begin,
+    Begin(Vec<AttachmentOps>),
     End,
 }
 
+/// The load and store operations of an aspect of an attachment of a render pass instance.
+#[derive(Clone, Debug)]
+pub(super) struct AttachmentOps {
+    pub(super) resource_in_command: ResourceInCommand,
+    pub(super) image: Arc<Image>,
+    pub(super) subresource_range: ImageSubresourceRange,
+    pub(super) load_op: AttachmentLoadOp,
+    pub(super) store_op: AttachmentStoreOp,
+    pub(super) initial_layout: ImageLayout,
+    // Whether the render area includes the whole attachment. The load and store operations only
+    // apply to the render area, so the contents outside of it are kept.
+    pub(super) render_area_covers: bool,
+}
+
 impl Debug for CommandInfo {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
         f.write_str(self.name)
@@ -309,11 +385,13 @@ impl Debug for CommandInfo {
 #[cfg(test)]
 mod tests {
     use crate::{
-        buffer::{Buffer, BufferCreateInfo, BufferUsage},
+        buffer::{Buffer, BufferCreateInfo, BufferUsage, Subbuffer},
         command_buffer::{
             allocator::{StandardCommandBufferAllocator, StandardCommandBufferAllocatorCreateInfo},
-            BufferCopy, CommandBufferBeginInfo, CommandBufferLevel, CommandBufferUsage,
-            CopyBufferInfoTyped, RecordingCommandBuffer,
+            BufferCopy, BufferImageCopy, Checkpoint, CheckpointGranularity, CommandBufferBeginInfo,
+            CommandBufferExecError, CommandBufferLevel, CommandBufferUsage, CopyBufferInfoTyped,
+            CopyBufferToImageInfo, CopyImageToBufferInfo, HangReport, HangReportCheckpoint,
+            RecordingCommandBuffer, RenderArea, RenderPassBeginInfo, TransferFallback,
         },
         descriptor_set::{
             allocator::StandardDescriptorSetAllocator,
@@ -323,14 +401,38 @@ mod tests {
             },
             DescriptorSet, WriteDescriptorSet,
         },
-        device::{Device, DeviceCreateInfo, QueueCreateInfo},
-        image::sampler::{Sampler, SamplerCreateInfo},
+        device::{CheckpointData, DeviceExtensions, DeviceFeatures, Queue},
+        format::Format,
+        image::{
+            mip_level_extent,
+            sampler::{Sampler, SamplerCreateInfo},
+            view::ImageView,
+            Image, ImageCreateInfo, ImageLayout, ImageLayoutPolicy, ImageSubresourceLayers,
+            ImageUsage,
+        },
         memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
-        pipeline::{layout::PipelineLayoutCreateInfo, PipelineBindPoint, PipelineLayout},
-        shader::ShaderStages,
-        sync::GpuFuture,
+        pipeline::{
+            compute::ComputePipelineCreateInfo,
+            layout::{PipelineDescriptorSetLayoutCreateInfo, PipelineLayoutCreateInfo},
+            ComputePipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+            PipelineShaderStageCreateInfo,
+        },
+        query::{QueryPool, QueryPoolCreateInfo, QueryResultFlags, QueryType},
+        render_pass::{AttachmentLoadOp, Framebuffer, FramebufferCreateInfo, RenderPass},
+        shader::{ShaderModule, ShaderModuleCreateInfo, ShaderStages},
+        single_pass_renderpass,
+        sync::{
+            future::{FenceSignalFuture, GpuFuture},
+            AccessConflict, HostAccessError, PipelineStages,
+        },
+        testing::{assert_contents_eq, test_device},
+        DeviceSize, Validated, ValidationError,
+    };
+    use std::{
+        iter,
+        ops::Range,
+        sync::{Arc, Mutex},
     };
-    use std::sync::Arc;
 
     #[test]
     fn basic_creation() {
@@ -355,26 +457,7 @@ mod tests {
 
     #[test]
     fn copy_buffer_dimensions() {
-        let instance = instance!();
-
-        let physical_device = match instance.enumerate_physical_devices().unwrap().next() {
-            Some(p) => p,
-            None => return,
-        };
-
-        let (device, mut queues) = Device::new(
-            physical_device,
-            DeviceCreateInfo {
-                queue_create_infos: vec![QueueCreateInfo {
-                    queue_family_index: 0,
-                    ..Default::default()
-                }],
-                ..Default::default()
-            },
-        )
-        .unwrap();
-
-        let queue = queues.next().unwrap();
+        let (device, queue) = gfx_dev_and_queue!();
         let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
 
         let source = Buffer::from_iter(
@@ -445,7 +528,7 @@ mod tests {
 
         let result = destination.read().unwrap();
 
-        assert_eq!(*result, [0_u32, 1, 2, 3, 4]);
+        assert_contents_eq(&result, &[0, 1, 2, 3, 4], 0);
     }
 
     #[test]
@@ -589,7 +672,7 @@ mod tests {
 
         let result = source.read().unwrap();
 
-        assert_eq!(*result, [0_u32, 1, 0, 1]);
+        assert_contents_eq(&result, &[0, 1, 0, 1], 0);
     }
 
     #[test]
@@ -641,6 +724,164 @@ mod tests {
             .is_err());
     }
 
+    #[test]
+    fn buffer_self_copy_defragment() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let source = Buffer::from_iter(
+            memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_SRC | BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                ..Default::default()
+            },
+            0_u32..8,
+        )
+        .unwrap();
+
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device,
+            Default::default(),
+        ));
+        let mut builder = RecordingCommandBuffer::new(
+            cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // The source of the first region overlaps with the destination of the second, which is
+        // larger.
+        let err = builder
+            .copy_buffer(CopyBufferInfoTyped {
+                regions: [
+                    BufferCopy {
+                        src_offset: 3,
+                        dst_offset: 7,
+                        size: 1,
+                        ..Default::default()
+                    },
+                    BufferCopy {
+                        src_offset: 4,
+                        dst_offset: 0,
+                        size: 4,
+                        ..Default::default()
+                    },
+                ]
+                .into(),
+                ..CopyBufferInfoTyped::buffers(source.clone(), source.clone())
+            })
+            .unwrap_err();
+        assert!(err
+            .problem
+            .contains("the source of `regions[0]` overlaps with the destination of `regions[1]`"));
+
+        // Moving the used ranges to the start of the buffer, where no source overlaps with any
+        // destination.
+        builder
+            .copy_buffer(CopyBufferInfoTyped {
+                regions: [
+                    BufferCopy {
+                        src_offset: 4,
+                        dst_offset: 0,
+                        size: 2,
+                        ..Default::default()
+                    },
+                    BufferCopy {
+                        src_offset: 7,
+                        dst_offset: 2,
+                        size: 1,
+                        ..Default::default()
+                    },
+                ]
+                .into(),
+                ..CopyBufferInfoTyped::buffers(source.clone(), source.clone())
+            })
+            .unwrap();
+
+        let cb = builder.end().unwrap();
+
+        let future = cb
+            .execute(queue)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap();
+        future.wait(None).unwrap();
+
+        let result = source.read().unwrap();
+
+        assert_contents_eq(&result, &[4, 5, 7, 3, 4, 5, 6, 7], 0);
+    }
+
+    #[test]
+    fn leaked_host_read_guard_blocks_device_write() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let buffer = Buffer::from_iter(
+            memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                ..Default::default()
+            },
+            0_u32..8,
+        )
+        .unwrap();
+
+        // The guard is never dropped, so the host read lock is never released.
+        std::mem::forget(buffer.read().unwrap());
+
+        assert!(matches!(
+            buffer.write(),
+            Err(HostAccessError::AccessConflict(AccessConflict::HostRead)),
+        ));
+
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device,
+            Default::default(),
+        ));
+        let mut builder = RecordingCommandBuffer::new(
+            cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        builder.fill_buffer(buffer.clone(), 0).unwrap();
+        let cb = builder.end().unwrap();
+
+        // Locking the buffer for the device write used to panic instead of returning an error.
+        let err = cb
+            .execute(queue)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap_err();
+
+        match err {
+            Validated::ValidationError(err) => assert!(err
+                .problem
+                .contains("the resource is already locked for reading by the host (CPU)")),
+            Validated::Error(err) => panic!("unexpected error: {}", err),
+        }
+    }
+
     #[test]
     fn secondary_conflicting_writes() {
         unsafe {
@@ -955,4 +1196,1129 @@ mod tests {
                 .map_or(false, |state| state.descriptor_sets.contains_key(&1)));
         }
     }
+
+    #[test]
+    fn copy_query_pool_results() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+
+        let query_pool = QueryPool::new(
+            device,
+            QueryPoolCreateInfo {
+                query_count: 4,
+                ..QueryPoolCreateInfo::query_type(QueryType::Timestamp)
+            },
+        )
+        .unwrap();
+
+        let create_buffer = |usage, len| {
+            Buffer::new_slice::<u64>(
+                memory_allocator.clone(),
+                BufferCreateInfo {
+                    usage,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+                len,
+            )
+            .unwrap()
+        };
+
+        let mut cbb = RecordingCommandBuffer::new(
+            cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let destination = create_buffer(BufferUsage::TRANSFER_DST, 8);
+
+        // Missing `TRANSFER_DST` usage.
+        assert!(cbb
+            .copy_query_pool_results(
+                query_pool.clone(),
+                0..4,
+                create_buffer(BufferUsage::STORAGE_BUFFER, 8),
+                QueryResultFlags::WAIT,
+            )
+            .is_err());
+
+        // Too small for the results and their availability.
+        assert!(cbb
+            .copy_query_pool_results(
+                query_pool.clone(),
+                0..4,
+                destination.clone().slice(..7),
+                QueryResultFlags::WAIT | QueryResultFlags::WITH_AVAILABILITY,
+            )
+            .is_err());
+
+        // Timestamp queries can't have partial results.
+        assert!(cbb
+            .copy_query_pool_results(
+                query_pool.clone(),
+                0..4,
+                destination.clone(),
+                QueryResultFlags::PARTIAL,
+            )
+            .is_err());
+
+        assert!(cbb
+            .copy_query_pool_results(
+                query_pool,
+                0..4,
+                destination,
+                QueryResultFlags::WAIT | QueryResultFlags::WITH_AVAILABILITY,
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn one_time_submit_twice() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device,
+            Default::default(),
+        ));
+
+        let buffer = Buffer::new_slice::<u32>(
+            memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+            4,
+        )
+        .unwrap();
+
+        let mut cbb = RecordingCommandBuffer::new(
+            cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        cbb.fill_buffer(buffer, 42).unwrap();
+        let cb = cbb.end().unwrap();
+
+        cb.clone()
+            .execute(queue.clone())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+        assert_eq!(cb.submit_count(), 1);
+
+        assert_eq!(
+            cb.clone().execute(queue).unwrap_err(),
+            CommandBufferExecError::OneTimeSubmitAlreadySubmitted,
+        );
+        assert_eq!(cb.submit_count(), 1);
+    }
+
+    #[test]
+    fn multiple_submit_resubmit() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device,
+            Default::default(),
+        ));
+
+        let buffer = Buffer::new_slice::<u32>(
+            memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+            4,
+        )
+        .unwrap();
+
+        let mut cbb = RecordingCommandBuffer::new(
+            cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::MultipleSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        cbb.fill_buffer(buffer, 42).unwrap();
+        let cb = cbb.end().unwrap();
+
+        let mut previous_future: Option<FenceSignalFuture<_>> = None;
+
+        for i in 0..1000 {
+            // Finding out that the fence is signaled must release the command buffer, the same as
+            // waiting on it does, even though the future is still alive.
+            if let Some(previous_future) = &previous_future {
+                while !previous_future.is_signaled().unwrap() {}
+            }
+
+            let future = cb
+                .clone()
+                .execute(queue.clone())
+                .unwrap()
+                .then_signal_fence_and_flush()
+                .unwrap();
+
+            if i % 2 == 0 {
+                future.wait(None).unwrap();
+                previous_future = None;
+            } else {
+                previous_future = Some(future);
+            }
+        }
+
+        assert_eq!(cb.submit_count(), 1000);
+    }
+
+    #[test]
+    fn streaming_mip_levels() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device,
+            Default::default(),
+        ));
+
+        let image = Image::new(
+            memory_allocator.clone(),
+            ImageCreateInfo {
+                format: Format::R8G8B8A8_UNORM,
+                extent: [4, 4, 1],
+                mip_levels: 3,
+                usage: ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST | ImageUsage::SAMPLED,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+        )
+        .unwrap();
+
+        let mip_level_size = |mip_level| {
+            let [width, height, _] = mip_level_extent(image.extent(), mip_level).unwrap();
+            (width * height * 4) as u64
+        };
+        let region = |mip_level, buffer_offset| BufferImageCopy {
+            buffer_offset,
+            image_subresource: ImageSubresourceLayers {
+                mip_level,
+                ..image.subresource_layers()
+            },
+            image_extent: mip_level_extent(image.extent(), mip_level).unwrap(),
+            ..Default::default()
+        };
+        let begin = || {
+            RecordingCommandBuffer::new(
+                cb_allocator.clone(),
+                queue.queue_family_index(),
+                CommandBufferLevel::Primary,
+                CommandBufferBeginInfo {
+                    usage: CommandBufferUsage::OneTimeSubmit,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+
+        // Each mip level is filled with its index plus one.
+        let upload = |mip_level: u32| {
+            let src_buffer = Buffer::from_iter(
+                memory_allocator.clone(),
+                BufferCreateInfo {
+                    usage: BufferUsage::TRANSFER_SRC,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                        | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                    ..Default::default()
+                },
+                iter::repeat(mip_level as u8 + 1).take(mip_level_size(mip_level) as usize),
+            )
+            .unwrap();
+
+            let mut cbb = begin();
+            cbb.copy_buffer_to_image(CopyBufferToImageInfo {
+                regions: [region(mip_level, 0)].into(),
+                ..CopyBufferToImageInfo::buffer_image(src_buffer, image.clone())
+            })
+            .unwrap();
+
+            cbb.end()
+                .unwrap()
+                .execute(queue.clone())
+                .unwrap()
+                .then_signal_fence_and_flush()
+                .unwrap()
+        };
+        let download = |mip_levels: Range<u32>| {
+            let dst_buffer = Buffer::new_slice::<u8>(
+                memory_allocator.clone(),
+                BufferCreateInfo {
+                    usage: BufferUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                        | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                    ..Default::default()
+                },
+                mip_levels.clone().map(mip_level_size).sum(),
+            )
+            .unwrap();
+
+            let mut buffer_offset = 0;
+            let regions = mip_levels
+                .map(|mip_level| {
+                    let region = region(mip_level, buffer_offset);
+                    buffer_offset += mip_level_size(mip_level);
+
+                    region
+                })
+                .collect();
+
+            let mut cbb = begin();
+            cbb.copy_image_to_buffer(CopyImageToBufferInfo {
+                regions,
+                ..CopyImageToBufferInfo::image_buffer(image.clone(), dst_buffer.clone())
+            })
+            .unwrap();
+
+            let future = cbb
+                .end()
+                .unwrap()
+                .execute(queue.clone())
+                .unwrap()
+                .then_signal_fence_and_flush()
+                .unwrap();
+
+            (future, dst_buffer)
+        };
+
+        // The first use of a mip level only initializes the layout of that mip level, so
+        // uploading the mip levels one at a time doesn't lock the other ones.
+        let upload_2 = upload(2);
+        let upload_1 = upload(1);
+        upload_2.wait(None).unwrap();
+        upload_1.wait(None).unwrap();
+
+        // Upload the last mip level while the others are read. Neither future knows about the
+        // other, the same as when submitting to different queues, so the access is only allowed
+        // because the subresources are disjoint.
+        let upload_0 = upload(0);
+        let (download_1_2, contents_1_2) = download(1..3);
+        upload_0.wait(None).unwrap();
+        download_1_2.wait(None).unwrap();
+
+        let (download_0, contents_0) = download(0..1);
+        download_0.wait(None).unwrap();
+
+        assert_contents_eq(&contents_0.read().unwrap(), &[1; 64], 0);
+        assert_contents_eq(
+            &contents_1_2.read().unwrap(),
+            &[[2; 16].as_slice(), &[3; 4]].concat(),
+            0,
+        );
+    }
+
+    /// Records two render pass instances that render to the same image, and returns the message ID
+    /// names of the performance warnings.
+    fn render_pass_warnings(
+        queue: &Arc<Queue>,
+        render_passes: [Arc<RenderPass>; 2],
+        render_areas: [RenderArea; 2],
+    ) -> Vec<&'static str> {
+        let device = queue.device();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+
+        let image = Image::new(
+            memory_allocator,
+            ImageCreateInfo {
+                format: Format::R8G8B8A8_UNORM,
+                extent: [4, 4, 1],
+                usage: ImageUsage::COLOR_ATTACHMENT,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+        )
+        .unwrap();
+        let image_view = ImageView::new_default(image).unwrap();
+
+        let mut cbb = RecordingCommandBuffer::new(
+            cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        for (render_pass, render_area) in render_passes.into_iter().zip(render_areas) {
+            let clear_value = (render_pass.attachments()[0].load_op == AttachmentLoadOp::Clear)
+                .then_some([0.0; 4].into());
+            let framebuffer = Framebuffer::new(
+                render_pass,
+                FramebufferCreateInfo {
+                    attachments: vec![image_view.clone()],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            cbb.begin_render_pass(
+                RenderPassBeginInfo {
+                    render_area,
+                    clear_values: vec![clear_value],
+                    ..RenderPassBeginInfo::framebuffer(framebuffer)
+                },
+                Default::default(),
+            )
+            .unwrap()
+            .end_render_pass(Default::default())
+            .unwrap();
+        }
+
+        cbb.performance_warnings()
+            .into_iter()
+            .map(|warning| warning.message_id_name)
+            .collect()
+    }
+
+    #[test]
+    fn performance_warnings() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let warnings =
+            |render_passes| render_pass_warnings(&queue, render_passes, Default::default());
+
+        // The first render pass instance loads contents that are undefined, and stores contents
+        // that the second one clears.
+        let wasteful = [
+            single_pass_renderpass!(
+                device.clone(),
+                attachments: {
+                    color: {
+                        format: Format::R8G8B8A8_UNORM,
+                        samples: 1,
+                        load_op: Load,
+                        store_op: Store,
+                        initial_layout: ImageLayout::Undefined,
+                    },
+                },
+                pass: {
+                    color: [color],
+                    depth_stencil: {},
+                },
+            )
+            .unwrap(),
+            single_pass_renderpass!(
+                device.clone(),
+                attachments: {
+                    color: {
+                        format: Format::R8G8B8A8_UNORM,
+                        samples: 1,
+                        load_op: Clear,
+                        store_op: Store,
+                    },
+                },
+                pass: {
+                    color: [color],
+                    depth_stencil: {},
+                },
+            )
+            .unwrap(),
+        ];
+        assert_eq!(
+            warnings(wasteful),
+            [
+                "Vulkano-Performance-LoadOpUndefinedContents",
+                "Vulkano-Performance-StoreOpContentsDiscarded",
+            ],
+        );
+
+        // The first render pass instance clears the image, and the second one loads what the
+        // first one stored.
+        let tuned = [
+            single_pass_renderpass!(
+                device.clone(),
+                attachments: {
+                    color: {
+                        format: Format::R8G8B8A8_UNORM,
+                        samples: 1,
+                        load_op: Clear,
+                        store_op: Store,
+                    },
+                },
+                pass: {
+                    color: [color],
+                    depth_stencil: {},
+                },
+            )
+            .unwrap(),
+            single_pass_renderpass!(
+                device,
+                attachments: {
+                    color: {
+                        format: Format::R8G8B8A8_UNORM,
+                        samples: 1,
+                        load_op: Load,
+                        store_op: Store,
+                    },
+                },
+                pass: {
+                    color: [color],
+                    depth_stencil: {},
+                },
+            )
+            .unwrap(),
+        ];
+        assert!(warnings(tuned).is_empty());
+    }
+
+    #[test]
+    fn performance_warnings_load_op_none() {
+        let Some((device, queue)) = test_device(
+            &DeviceExtensions {
+                ext_load_store_op_none: true,
+                ..DeviceExtensions::empty()
+            },
+            &DeviceFeatures::empty(),
+        ) else {
+            return;
+        };
+
+        // `AttachmentLoadOp::None` leaves the contents that the first render pass instance
+        // stored in place, so they are not discarded.
+        let render_passes = [
+            single_pass_renderpass!(
+                device.clone(),
+                attachments: {
+                    color: {
+                        format: Format::R8G8B8A8_UNORM,
+                        samples: 1,
+                        load_op: Clear,
+                        store_op: Store,
+                    },
+                },
+                pass: {
+                    color: [color],
+                    depth_stencil: {},
+                },
+            )
+            .unwrap(),
+            single_pass_renderpass!(
+                device,
+                attachments: {
+                    color: {
+                        format: Format::R8G8B8A8_UNORM,
+                        samples: 1,
+                        load_op: None,
+                        store_op: Store,
+                    },
+                },
+                pass: {
+                    color: [color],
+                    depth_stencil: {},
+                },
+            )
+            .unwrap(),
+        ];
+        assert!(render_pass_warnings(&queue, render_passes, Default::default()).is_empty());
+    }
+
+    #[test]
+    fn performance_warnings_partial_render_area() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        // The second render pass instance only clears part of the image, so the rest of what
+        // the first one stored is kept.
+        let render_pass = single_pass_renderpass!(
+            device,
+            attachments: {
+                color: {
+                    format: Format::R8G8B8A8_UNORM,
+                    samples: 1,
+                    load_op: Clear,
+                    store_op: Store,
+                },
+            },
+            pass: {
+                color: [color],
+                depth_stencil: {},
+            },
+        )
+        .unwrap();
+        let partial = RenderArea::Partial {
+            offset: [0, 0],
+            extent: [2, 2],
+        };
+
+        assert!(render_pass_warnings(
+            &queue,
+            [render_pass.clone(), render_pass.clone()],
+            [RenderArea::Full, partial],
+        )
+        .is_empty());
+        assert_eq!(
+            render_pass_warnings(
+                &queue,
+                [render_pass.clone(), render_pass],
+                [partial, RenderArea::Full],
+            ),
+            ["Vulkano-Performance-StoreOpContentsDiscarded"],
+        );
+    }
+
+    #[test]
+    fn validation_error_command() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device,
+            Default::default(),
+        ));
+
+        let create_buffer = |usage| {
+            Buffer::new_slice::<u32>(
+                memory_allocator.clone(),
+                BufferCreateInfo {
+                    usage,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+                4,
+            )
+            .unwrap()
+        };
+
+        let mut cbb = RecordingCommandBuffer::new(
+            cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        cbb.fill_buffer(create_buffer(BufferUsage::TRANSFER_DST), 0)
+            .unwrap();
+
+        // Missing `TRANSFER_DST` usage.
+        let err = cbb
+            .fill_buffer(create_buffer(BufferUsage::STORAGE_BUFFER), 0)
+            .unwrap_err();
+        assert!(err.problem.ends_with(", at command #1 (fill_buffer)"));
+    }
+
+    #[test]
+    fn automatic_checkpoints() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device,
+            Default::default(),
+        ));
+
+        let mut builder = RecordingCommandBuffer::new(
+            allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo::default(),
+        )
+        .unwrap();
+        assert!(builder
+            .set_automatic_checkpoints(CheckpointGranularity::Commands(2))
+            .is_err());
+
+        let (device, queue) = match test_device(
+            &DeviceExtensions {
+                nv_device_diagnostic_checkpoints: true,
+                ..DeviceExtensions::empty()
+            },
+            &DeviceFeatures::empty(),
+        ) {
+            Some(x) => x,
+            None => return,
+        };
+        let allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device,
+            Default::default(),
+        ));
+
+        let mut builder = RecordingCommandBuffer::new(
+            allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo::default(),
+        )
+        .unwrap();
+        assert!(builder
+            .set_automatic_checkpoints(CheckpointGranularity::Commands(0))
+            .is_err());
+        builder
+            .set_automatic_checkpoints(CheckpointGranularity::Commands(2))
+            .unwrap();
+
+        for marker in 0..3 {
+            builder.set_checkpoint(marker).unwrap();
+        }
+
+        assert!(builder
+            .set_checkpoint(Checkpoint::AUTOMATIC_MARKER_START)
+            .is_err());
+
+        assert!(builder
+            .set_automatic_checkpoints(CheckpointGranularity::DebugLabelRegions)
+            .is_err());
+
+        let command_buffer = builder.end().unwrap();
+        let checkpoints = command_buffer.checkpoints();
+        assert_eq!(
+            checkpoints
+                .iter()
+                .map(|checkpoint| (checkpoint.command_index, checkpoint.command_name))
+                .collect::<Vec<_>>(),
+            [(0, "set_checkpoint"), (2, "set_checkpoint"), (3, "end")],
+        );
+
+        for checkpoint in checkpoints {
+            assert!(checkpoint.marker >= Checkpoint::AUTOMATIC_MARKER_START);
+            assert_eq!(
+                command_buffer.checkpoint(checkpoint.marker),
+                Some(checkpoint)
+            );
+        }
+
+        // The device was lost after the first checkpoint completed, while executing the commands
+        // after the second one. A marker given to `set_checkpoint` isn't known to the report.
+        let user_checkpoint = CheckpointData {
+            stage: PipelineStages::ALL_TRANSFER,
+            marker: 1,
+        };
+        let report = HangReport::new(
+            &[
+                CheckpointData {
+                    stage: PipelineStages::TOP_OF_PIPE,
+                    marker: checkpoints[1].marker,
+                },
+                CheckpointData {
+                    stage: PipelineStages::BOTTOM_OF_PIPE,
+                    marker: checkpoints[0].marker,
+                },
+                user_checkpoint,
+            ],
+            &[command_buffer.clone()],
+        );
+        let report_checkpoint = |index: usize| HangReportCheckpoint {
+            command_buffer_index: 0,
+            checkpoint: checkpoints[index].clone(),
+        };
+        assert_eq!(report.last_started, Some(report_checkpoint(1)));
+        assert_eq!(report.last_completed, Some(report_checkpoint(0)));
+        assert_eq!(
+            report.stages,
+            [
+                (PipelineStages::TOP_OF_PIPE, report_checkpoint(1)),
+                (PipelineStages::BOTTOM_OF_PIPE, report_checkpoint(0)),
+            ],
+        );
+        assert_eq!(report.unknown, [user_checkpoint]);
+    }
+
+    #[test]
+    fn automatic_checkpoints_buffer_marker() {
+        let (device, queue) = match test_device(
+            &DeviceExtensions {
+                amd_buffer_marker: true,
+                ..DeviceExtensions::empty()
+            },
+            &DeviceFeatures::empty(),
+        ) {
+            Some(x) => x,
+            None => return,
+        };
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device,
+            Default::default(),
+        ));
+
+        let buffer = Buffer::new_slice::<u32>(
+            memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+            4,
+        )
+        .unwrap();
+
+        let mut builder = RecordingCommandBuffer::new(
+            allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo::default(),
+        )
+        .unwrap();
+        builder
+            .set_automatic_checkpoints(CheckpointGranularity::Commands(1))
+            .unwrap();
+
+        // Explicit checkpoints still require `nv_device_diagnostic_checkpoints`.
+        assert!(builder.set_checkpoint(0).is_err());
+
+        builder
+            .fill_buffer(buffer.clone(), 1)
+            .unwrap()
+            .fill_buffer(buffer, 2)
+            .unwrap();
+
+        let command_buffer = builder.end().unwrap();
+        let checkpoints = command_buffer.checkpoints();
+        assert_eq!(
+            checkpoints
+                .iter()
+                .map(|checkpoint| (checkpoint.command_index, checkpoint.command_name))
+                .collect::<Vec<_>>(),
+            [(0, "fill_buffer"), (1, "fill_buffer"), (2, "end")],
+        );
+
+        // Nothing has been executed yet.
+        assert_eq!(
+            HangReport::from_buffer_markers(&[command_buffer.clone()]),
+            HangReport::default(),
+        );
+
+        command_buffer
+            .clone()
+            .execute(queue)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        // After a complete execution, both ends of the pipeline have reached the last checkpoint.
+        let report_checkpoint = HangReportCheckpoint {
+            command_buffer_index: 0,
+            checkpoint: checkpoints[2].clone(),
+        };
+        let report = HangReport::from_buffer_markers(&[command_buffer.clone()]);
+        assert_eq!(report.last_started, Some(report_checkpoint.clone()));
+        assert_eq!(report.last_completed, Some(report_checkpoint.clone()));
+        assert_eq!(
+            report.stages,
+            [
+                (PipelineStages::TOP_OF_PIPE, report_checkpoint.clone()),
+                (PipelineStages::BOTTOM_OF_PIPE, report_checkpoint),
+            ],
+        );
+        assert!(report.unknown.is_empty());
+    }
+
+    #[test]
+    fn transfer_fallback() {
+        #[derive(Default)]
+        struct Fallback {
+            calls: Mutex<Vec<(&'static str, Range<DeviceSize>, Range<DeviceSize>)>>,
+        }
+
+        impl TransferFallback for Fallback {
+            fn fill_buffer(
+                &self,
+                _builder: &mut RecordingCommandBuffer,
+                dst_buffer: Subbuffer<[u8]>,
+                _data: u32,
+            ) -> Result<(), Box<ValidationError>> {
+                let dst_range = dst_buffer.offset()..dst_buffer.offset() + dst_buffer.size();
+                self.calls.lock().unwrap().push(("fill", 0..0, dst_range));
+
+                Ok(())
+            }
+
+            fn copy_buffer(
+                &self,
+                _builder: &mut RecordingCommandBuffer,
+                src_buffer: Subbuffer<[u8]>,
+                dst_buffer: Subbuffer<[u8]>,
+            ) -> Result<(), Box<ValidationError>> {
+                let src_range = src_buffer.offset()..src_buffer.offset() + src_buffer.size();
+                let dst_range = dst_buffer.offset()..dst_buffer.offset() + dst_buffer.size();
+                self.calls
+                    .lock()
+                    .unwrap()
+                    .push(("copy", src_range, dst_range));
+
+                Ok(())
+            }
+        }
+
+        let (device, queue) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device,
+            Default::default(),
+        ));
+
+        let storage_buffer = || {
+            Buffer::new_slice::<u32>(
+                memory_allocator.clone(),
+                BufferCreateInfo {
+                    usage: BufferUsage::STORAGE_BUFFER,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+                16,
+            )
+            .unwrap()
+        };
+        let transfer_buffer = Buffer::new_slice::<u32>(
+            memory_allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_SRC | BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+            16,
+        )
+        .unwrap();
+        let (src, dst) = (storage_buffer(), storage_buffer());
+
+        let mut builder = RecordingCommandBuffer::new(
+            allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo::default(),
+        )
+        .unwrap();
+
+        // Without a fallback, the usages are required.
+        assert!(builder.fill_buffer(dst.clone(), 0).is_err());
+        assert!(builder
+            .copy_buffer(CopyBufferInfoTyped::buffers(src.clone(), dst.clone()))
+            .is_err());
+
+        let fallback = Arc::new(Fallback::default());
+        builder.set_transfer_fallback(Some(fallback.clone()));
+
+        builder.fill_buffer(dst.clone().slice(2..6), 0).unwrap();
+        builder
+            .copy_buffer(CopyBufferInfoTyped {
+                regions: [
+                    BufferCopy {
+                        src_offset: 0,
+                        dst_offset: 4,
+                        size: 2,
+                        ..Default::default()
+                    },
+                    BufferCopy {
+                        src_offset: 8,
+                        dst_offset: 12,
+                        size: 4,
+                        ..Default::default()
+                    },
+                ]
+                .into(),
+                ..CopyBufferInfoTyped::buffers(src.clone(), dst.clone())
+            })
+            .unwrap();
+        assert!(builder
+            .copy_buffer(CopyBufferInfoTyped {
+                regions: [BufferCopy {
+                    src_offset: 8,
+                    dst_offset: 0,
+                    size: 16,
+                    ..Default::default()
+                }]
+                .into(),
+                ..CopyBufferInfoTyped::buffers(src.clone(), dst.clone())
+            })
+            .is_err());
+
+        // Buffers that can be used with transfer commands don't go through the fallback.
+        builder.fill_buffer(transfer_buffer.clone(), 0).unwrap();
+        builder
+            .copy_buffer(CopyBufferInfoTyped::buffers(
+                transfer_buffer.clone().slice(0..8),
+                transfer_buffer.slice(8..16),
+            ))
+            .unwrap();
+
+        assert_eq!(
+            *fallback.calls.lock().unwrap(),
+            [
+                ("fill", 0..0, 8..24),
+                ("copy", 0..8, 16..24),
+                ("copy", 32..48, 48..64),
+            ],
+        );
+        assert!(builder.transfer_fallback().is_some());
+    }
+
+    #[test]
+    fn image_layout_policy() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let cs = unsafe {
+            /*
+            #version 450
+
+            layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+            layout(set = 0, binding = 0) uniform texture2D sampled_image;
+            layout(set = 0, binding = 1, rgba8) uniform image2D storage_image;
+
+            void main() {
+                // Loads both images, so that both bindings are used.
+            }
+            */
+            const MODULE: [u32; 96] = [
+                119734787, 65536, 0, 14, 0, 131089, 1, 196622, 0, 1, 327695, 5, 10, 1852399981, 0,
+                393232, 10, 17, 1, 1, 1, 262215, 8, 34, 0, 262215, 8, 33, 0, 262215, 9, 34, 0,
+                262215, 9, 33, 1, 131091, 1, 196641, 2, 1, 196630, 3, 32, 589849, 4, 3, 1, 0, 0, 0,
+                1, 0, 589849, 5, 3, 1, 0, 0, 0, 2, 4, 262176, 6, 0, 4, 262176, 7, 0, 5, 262203, 6,
+                8, 0, 262203, 7, 9, 0, 327734, 1, 10, 0, 2, 131320, 11, 262205, 4, 12, 8, 262205,
+                5, 13, 9, 65789, 65592,
+            ];
+            let module =
+                ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(&MODULE)).unwrap();
+            module.entry_point("main").unwrap()
+        };
+
+        let pipeline = {
+            let stage = PipelineShaderStageCreateInfo::new(cs);
+            let layout = PipelineLayout::new(
+                device.clone(),
+                PipelineDescriptorSetLayoutCreateInfo::from_stages([&stage])
+                    .into_pipeline_layout_create_info(device.clone())
+                    .unwrap(),
+            )
+            .unwrap();
+            ComputePipeline::new(
+                device.clone(),
+                None,
+                ComputePipelineCreateInfo::stage_layout(stage, layout),
+            )
+            .unwrap()
+        };
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let create_view = |layout_policy| {
+            let image = Image::new(
+                memory_allocator.clone(),
+                ImageCreateInfo {
+                    format: Format::R8G8B8A8_UNORM,
+                    extent: [4, 4, 1],
+                    usage: ImageUsage::STORAGE | ImageUsage::SAMPLED,
+                    layout_policy,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+            )
+            .unwrap();
+
+            ImageView::new_default(image).unwrap()
+        };
+        let general = create_view(ImageLayoutPolicy::General);
+        let optimal = create_view(ImageLayoutPolicy::Optimal);
+
+        let ds_allocator = Arc::new(StandardDescriptorSetAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device,
+            Default::default(),
+        ));
+        let mut cbb = RecordingCommandBuffer::new(
+            cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Secondary,
+            CommandBufferBeginInfo {
+                inheritance_info: Some(Default::default()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        cbb.bind_pipeline_compute(pipeline.clone()).unwrap();
+
+        // Each image is used first as a sampled image and then as a storage image, or the other
+        // way around.
+        for (sampled, storage) in [(&general, &optimal), (&optimal, &general)] {
+            let set = DescriptorSet::new(
+                ds_allocator.clone(),
+                pipeline.layout().set_layouts()[0].clone(),
+                [
+                    WriteDescriptorSet::image_view(0, sampled.clone()),
+                    WriteDescriptorSet::image_view(1, storage.clone()),
+                ],
+                [],
+            )
+            .unwrap();
+            cbb.bind_descriptor_sets(
+                PipelineBindPoint::Compute,
+                pipeline.layout().clone(),
+                0,
+                set,
+            )
+            .unwrap();
+
+            unsafe {
+                cbb.dispatch([1, 1, 1]).unwrap();
+            }
+        }
+
+        let command_buffer = cbb.end().unwrap();
+        let resources_usage = command_buffer.resources_usage();
+        let layouts = |view: &Arc<ImageView>| {
+            let image_usage = &resources_usage.images[resources_usage.image_indices[view.image()]];
+
+            image_usage
+                .ranges
+                .iter()
+                .map(|(_, range_usage)| (range_usage.expected_layout, range_usage.final_layout))
+                .collect::<Vec<_>>()
+        };
+
+        // In a secondary command buffer, the recorded layouts are those of the first and the last
+        // use. The image with the `General` policy never changes layout, while the other one is
+        // first sampled in `ShaderReadOnlyOptimal`.
+        assert_eq!(
+            layouts(&general),
+            [(ImageLayout::General, ImageLayout::General)],
+        );
+        assert_eq!(
+            layouts(&optimal),
+            [(ImageLayout::ShaderReadOnlyOptimal, ImageLayout::General)],
+        );
+    }
 }
@@ -81,7 +81,8 @@ impl RecordingCommandBuffer {
         info: AccelerationStructureBuildGeometryInfo,
         build_range_infos: SmallVec<[AccelerationStructureBuildRangeInfo; 8]>,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_build_acceleration_structure(&info, &build_range_infos)?;
+        self.validate_build_acceleration_structure(&info, &build_range_infos)
+            .map_err(|err| self.command_error(err, "build_acceleration_structure"))?;
 
         Ok(self.build_acceleration_structure_unchecked(info, build_range_infos))
     }
@@ -198,7 +199,8 @@ impl RecordingCommandBuffer {
             &indirect_buffer,
             stride,
             &max_primitive_counts,
-        )?;
+        )
+        .map_err(|err| self.command_error(err, "build_acceleration_structure_indirect"))?;
 
         Ok(self.build_acceleration_structure_indirect_unchecked(
             info,
@@ -277,7 +279,8 @@ impl RecordingCommandBuffer {
         &mut self,
         info: CopyAccelerationStructureInfo,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_copy_acceleration_structure(&info)?;
+        self.validate_copy_acceleration_structure(&info)
+            .map_err(|err| self.command_error(err, "copy_acceleration_structure"))?;
 
         Ok(self.copy_acceleration_structure_unchecked(info))
     }
@@ -361,7 +364,8 @@ impl RecordingCommandBuffer {
         &mut self,
         info: CopyAccelerationStructureToMemoryInfo,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_copy_acceleration_structure_to_memory(&info)?;
+        self.validate_copy_acceleration_structure_to_memory(&info)
+            .map_err(|err| self.command_error(err, "copy_acceleration_structure_to_memory"))?;
 
         Ok(self.copy_acceleration_structure_to_memory_unchecked(info))
     }
@@ -448,7 +452,8 @@ impl RecordingCommandBuffer {
         &mut self,
         info: CopyMemoryToAccelerationStructureInfo,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_copy_memory_to_acceleration_structure(&info)?;
+        self.validate_copy_memory_to_acceleration_structure(&info)
+            .map_err(|err| self.command_error(err, "copy_memory_to_acceleration_structure"))?;
 
         Ok(self.copy_memory_to_acceleration_structure_unchecked(info))
     }
@@ -544,7 +549,8 @@ impl RecordingCommandBuffer {
             &acceleration_structures,
             &query_pool,
             first_query,
-        )?;
+        )
+        .map_err(|err| self.command_error(err, "write_acceleration_structures_properties"))?;
 
         Ok(self.write_acceleration_structures_properties_unchecked(
             acceleration_structures,
@@ -7,15 +7,16 @@ use crate::{
         DescriptorSetWithOffsets, DescriptorSetsCollection, DescriptorWriteInfo,
         WriteDescriptorSet,
     },
-    device::{DeviceOwned, QueueFlags},
+    device::{Device, DeviceOwned, QueueFlags},
     memory::is_aligned,
     pipeline::{
-        graphics::vertex_input::VertexBuffersCollection, ComputePipeline, GraphicsPipeline,
-        PipelineBindPoint, PipelineLayout,
+        graphics::vertex_input::VertexBuffersCollection, layout::PushConstants, ComputePipeline,
+        DynamicState, GraphicsPipeline, PipelineBindPoint, PipelineLayout,
     },
+    shader::ShaderStages,
     DeviceSize, Requires, RequiresAllOf, RequiresOneOf, ValidationError, Version, VulkanObject,
 };
-use smallvec::SmallVec;
+use smallvec::{smallvec, SmallVec};
 use std::{cmp::min, ffi::c_void, mem::size_of, ptr, sync::Arc};
 
 /// # Commands to bind or push state for pipeline execution commands.
@@ -36,7 +37,8 @@ impl RecordingCommandBuffer {
             &pipeline_layout,
             first_set,
             &descriptor_sets,
-        )?;
+        )
+        .map_err(|err| self.command_error(err, "bind_descriptor_sets"))?;
 
         unsafe {
             Ok(self.bind_descriptor_sets_unchecked(
@@ -114,7 +116,8 @@ impl RecordingCommandBuffer {
         index_buffer: impl Into<IndexBuffer>,
     ) -> Result<&mut Self, Box<ValidationError>> {
         let index_buffer = index_buffer.into();
-        self.validate_bind_index_buffer(&index_buffer)?;
+        self.validate_bind_index_buffer(&index_buffer)
+            .map_err(|err| self.command_error(err, "bind_index_buffer"))?;
 
         unsafe { Ok(self.bind_index_buffer_unchecked(index_buffer)) }
     }
@@ -151,7 +154,8 @@ impl RecordingCommandBuffer {
         &mut self,
         pipeline: Arc<ComputePipeline>,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_bind_pipeline_compute(&pipeline)?;
+        self.validate_bind_pipeline_compute(&pipeline)
+            .map_err(|err| self.command_error(err, "bind_pipeline_compute"))?;
 
         unsafe { Ok(self.bind_pipeline_compute_unchecked(pipeline)) }
     }
@@ -187,7 +191,8 @@ impl RecordingCommandBuffer {
         &mut self,
         pipeline: Arc<GraphicsPipeline>,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_bind_pipeline_graphics(&pipeline)?;
+        self.validate_bind_pipeline_graphics(&pipeline)
+            .map_err(|err| self.command_error(err, "bind_pipeline_graphics"))?;
 
         unsafe { Ok(self.bind_pipeline_graphics_unchecked(pipeline)) }
     }
@@ -232,7 +237,8 @@ impl RecordingCommandBuffer {
         vertex_buffers: impl VertexBuffersCollection,
     ) -> Result<&mut Self, Box<ValidationError>> {
         let vertex_buffers = vertex_buffers.into_vec();
-        self.validate_bind_vertex_buffers(first_binding, &vertex_buffers)?;
+        self.validate_bind_vertex_buffers(first_binding, &vertex_buffers)
+            .map_err(|err| self.command_error(err, "bind_vertex_buffers"))?;
 
         unsafe { Ok(self.bind_vertex_buffers_unchecked(first_binding, vertex_buffers)) }
     }
@@ -259,7 +265,7 @@ impl RecordingCommandBuffer {
         for (i, buffer) in vertex_buffers.iter().enumerate() {
             self.builder_state
                 .vertex_buffers
-                .insert(first_binding + i as u32, buffer.clone());
+                .insert(first_binding + i as u32, Some(buffer.clone()));
         }
 
         self.add_command(
@@ -273,6 +279,144 @@ impl RecordingCommandBuffer {
         self
     }
 
+    /// Binds vertex buffers for future draw calls, and sets the stride of each binding
+    /// dynamically.
+    ///
+    /// The strides replace the [`stride`] values of the vertex input state of graphics pipelines
+    /// that were created with [`DynamicState::VertexInputBindingStride`] enabled. This allows one
+    /// pipeline to read vertex data with different layouts. If the currently bound graphics
+    /// pipeline was created without this dynamic state, the call is rejected. `strides` must have
+    /// the same length as `vertex_buffers`. To bind only part of a buffer, pass a slice of the
+    /// subbuffer.
+    ///
+    /// The device API version must be at least 1.3, or the [`extended_dynamic_state`] feature
+    /// must be enabled on the device.
+    ///
+    /// [`stride`]: crate::pipeline::graphics::vertex_input::VertexInputBindingDescription::stride
+    /// [`extended_dynamic_state`]: crate::device::DeviceFeatures::extended_dynamic_state
+    pub fn bind_vertex_buffers_with_strides(
+        &mut self,
+        first_binding: u32,
+        vertex_buffers: impl VertexBuffersCollection,
+        strides: &[u32],
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        let vertex_buffers = vertex_buffers.into_vec();
+        self.validate_bind_vertex_buffers_with_strides(first_binding, &vertex_buffers, strides)
+            .map_err(|err| self.command_error(err, "bind_vertex_buffers_with_strides"))?;
+
+        unsafe {
+            Ok(self.bind_vertex_buffers_with_strides_unchecked(
+                first_binding,
+                vertex_buffers,
+                strides,
+            ))
+        }
+    }
+
+    fn validate_bind_vertex_buffers_with_strides(
+        &self,
+        first_binding: u32,
+        vertex_buffers: &[Subbuffer<[u8]>],
+        strides: &[u32],
+    ) -> Result<(), Box<ValidationError>> {
+        self.inner.validate_bind_vertex_buffers_with_strides(
+            first_binding,
+            vertex_buffers,
+            strides,
+        )?;
+
+        self.validate_graphics_pipeline_fixed_state(DynamicState::VertexInputBindingStride)?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn bind_vertex_buffers_with_strides_unchecked(
+        &mut self,
+        first_binding: u32,
+        vertex_buffers: impl VertexBuffersCollection,
+        strides: &[u32],
+    ) -> &mut Self {
+        let vertex_buffers = vertex_buffers.into_vec();
+        let strides: SmallVec<[u32; 2]> = strides.into();
+
+        for (i, (buffer, &stride)) in vertex_buffers.iter().zip(&strides).enumerate() {
+            let binding = first_binding + i as u32;
+            self.builder_state
+                .vertex_buffers
+                .insert(binding, Some(buffer.clone()));
+            self.builder_state
+                .vertex_input_binding_stride
+                .insert(binding, stride);
+        }
+
+        self.add_command(
+            "bind_vertex_buffers_with_strides",
+            Default::default(),
+            move |out: &mut RawRecordingCommandBuffer| {
+                out.bind_vertex_buffers_with_strides_unchecked(
+                    first_binding,
+                    &vertex_buffers,
+                    &strides,
+                );
+            },
+        );
+
+        self
+    }
+
+    /// Binds null vertex buffers to `binding_count` bindings, starting at `first_binding`.
+    ///
+    /// This can be used for bindings that the graphics pipeline has, but that are not needed for
+    /// the draw calls. Vertex attributes that are read from a null vertex buffer have the value
+    /// zero, with missing G, B and A components filled in with (0, 0, 1).
+    ///
+    /// The [`null_descriptor`] feature must be enabled on the device.
+    ///
+    /// [`null_descriptor`]: crate::device::DeviceFeatures::null_descriptor
+    pub fn bind_null_vertex_buffers(
+        &mut self,
+        first_binding: u32,
+        binding_count: u32,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.validate_bind_null_vertex_buffers(first_binding, binding_count)
+            .map_err(|err| self.command_error(err, "bind_null_vertex_buffers"))?;
+
+        unsafe { Ok(self.bind_null_vertex_buffers_unchecked(first_binding, binding_count)) }
+    }
+
+    fn validate_bind_null_vertex_buffers(
+        &self,
+        first_binding: u32,
+        binding_count: u32,
+    ) -> Result<(), Box<ValidationError>> {
+        self.inner
+            .validate_bind_null_vertex_buffers(first_binding, binding_count)?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn bind_null_vertex_buffers_unchecked(
+        &mut self,
+        first_binding: u32,
+        binding_count: u32,
+    ) -> &mut Self {
+        for binding in first_binding..first_binding + binding_count {
+            self.builder_state.vertex_buffers.insert(binding, None);
+        }
+
+        self.add_command(
+            "bind_null_vertex_buffers",
+            Default::default(),
+            move |out: &mut RawRecordingCommandBuffer| {
+                out.bind_null_vertex_buffers_unchecked(first_binding, binding_count);
+            },
+        );
+
+        self
+    }
+
     /// Sets push constants for future dispatch or draw calls.
     pub fn push_constants<Pc>(
         &mut self,
@@ -289,7 +433,8 @@ impl RecordingCommandBuffer {
             return Ok(self);
         }
 
-        self.validate_push_constants(&pipeline_layout, offset, &push_constants)?;
+        self.validate_push_constants(&pipeline_layout, offset, &push_constants)
+            .map_err(|err| self.command_error(err, "push_constants"))?;
 
         unsafe { Ok(self.push_constants_unchecked(pipeline_layout, offset, push_constants)) }
     }
@@ -337,6 +482,63 @@ impl RecordingCommandBuffer {
         self
     }
 
+    /// Sets push constants for future dispatch or draw calls, using a type that describes the
+    /// push constant block of a shader.
+    ///
+    /// The push constants are written starting at offset 0. In addition to the checks performed
+    /// by [`push_constants`], this checks that every byte of `push_constants` is within push
+    /// constant ranges of `pipeline_layout` that include all of the stages in `Pc::STAGES`.
+    ///
+    /// [`push_constants`]: Self::push_constants
+    pub fn push_constants_typed<Pc>(
+        &mut self,
+        pipeline_layout: Arc<PipelineLayout>,
+        push_constants: Pc,
+    ) -> Result<&mut Self, Box<ValidationError>>
+    where
+        Pc: PushConstants,
+    {
+        if size_of::<Pc>() == 0 {
+            return Ok(self);
+        }
+
+        self.validate_push_constants_typed(&pipeline_layout, &push_constants)?;
+
+        unsafe { Ok(self.push_constants_unchecked(pipeline_layout, 0, push_constants)) }
+    }
+
+    fn validate_push_constants_typed<Pc: PushConstants>(
+        &self,
+        pipeline_layout: &PipelineLayout,
+        push_constants: &Pc,
+    ) -> Result<(), Box<ValidationError>> {
+        self.inner
+            .validate_push_constants(pipeline_layout, 0, push_constants)?;
+
+        let size = size_of::<Pc>() as u32;
+
+        for range in pipeline_layout
+            .push_constant_ranges_disjoint()
+            .iter()
+            .filter(|range| range.offset < size)
+        {
+            if !range.stages.contains(Pc::STAGES) {
+                return Err(Box::new(ValidationError {
+                    problem: format!(
+                        "the push constant range of `pipeline_layout` from offset {} to {} \
+                        does not include all of the stages in `Pc::STAGES`",
+                        range.offset,
+                        range.offset + range.size,
+                    )
+                    .into(),
+                    ..Default::default()
+                }));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Pushes descriptor data directly into the command buffer for future dispatch or draw calls.
     pub fn push_descriptor_set(
         &mut self,
@@ -350,7 +552,8 @@ impl RecordingCommandBuffer {
             &pipeline_layout,
             set_num,
             &descriptor_writes,
-        )?;
+        )
+        .map_err(|err| self.command_error(err, "push_descriptor_set"))?;
 
         unsafe {
             Ok(self.push_descriptor_set_unchecked(
@@ -426,6 +629,141 @@ impl RecordingCommandBuffer {
 
         self
     }
+
+    /// Binds descriptor sets for future dispatch or draw calls, for all pipeline bind points that
+    /// correspond to `bind_descriptor_sets_info.stages`.
+    ///
+    /// The [`maintenance6`](crate::device::DeviceFeatures::maintenance6) feature must be enabled
+    /// on the device.
+    pub fn bind_descriptor_sets2(
+        &mut self,
+        bind_descriptor_sets_info: BindDescriptorSetsInfo,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.validate_bind_descriptor_sets2(&bind_descriptor_sets_info)
+            .map_err(|err| self.command_error(err, "bind_descriptor_sets2"))?;
+
+        unsafe { Ok(self.bind_descriptor_sets2_unchecked(bind_descriptor_sets_info)) }
+    }
+
+    fn validate_bind_descriptor_sets2(
+        &self,
+        bind_descriptor_sets_info: &BindDescriptorSetsInfo,
+    ) -> Result<(), Box<ValidationError>> {
+        self.inner
+            .validate_bind_descriptor_sets2(bind_descriptor_sets_info)?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn bind_descriptor_sets2_unchecked(
+        &mut self,
+        bind_descriptor_sets_info: BindDescriptorSetsInfo,
+    ) -> &mut Self {
+        let BindDescriptorSetsInfo {
+            stages: _,
+            ref layout,
+            first_set,
+            ref descriptor_sets,
+            _ne: _,
+        } = bind_descriptor_sets_info;
+
+        if descriptor_sets.is_empty() {
+            return self;
+        }
+
+        for pipeline_bind_point in bind_descriptor_sets_info.pipeline_bind_points() {
+            let state = self.builder_state.invalidate_descriptor_sets(
+                pipeline_bind_point,
+                layout.clone(),
+                first_set,
+                descriptor_sets.len() as u32,
+            );
+
+            for (set_num, set) in descriptor_sets.iter().enumerate() {
+                state
+                    .descriptor_sets
+                    .insert(first_set + set_num as u32, SetOrPush::Set(set.clone()));
+            }
+        }
+
+        self.add_command(
+            "bind_descriptor_sets2",
+            Default::default(),
+            move |out: &mut RawRecordingCommandBuffer| {
+                out.bind_descriptor_sets2_unchecked(&bind_descriptor_sets_info);
+            },
+        );
+
+        self
+    }
+
+    /// Sets push constants for future dispatch or draw calls, for the shader stages given in
+    /// `push_constants_info.stages`.
+    ///
+    /// Unlike [`push_constants`](Self::push_constants), the data is pushed with a single command
+    /// for all of the specified stages, rather than being split up per push constant range.
+    ///
+    /// The [`maintenance6`](crate::device::DeviceFeatures::maintenance6) feature must be enabled
+    /// on the device.
+    pub fn push_constants2<Pc>(
+        &mut self,
+        push_constants_info: PushConstantsInfo,
+        push_constants: Pc,
+    ) -> Result<&mut Self, Box<ValidationError>>
+    where
+        Pc: BufferContents,
+    {
+        if size_of::<Pc>() == 0 {
+            return Ok(self);
+        }
+
+        self.validate_push_constants2(&push_constants_info, &push_constants)
+            .map_err(|err| self.command_error(err, "push_constants2"))?;
+
+        unsafe { Ok(self.push_constants2_unchecked(push_constants_info, push_constants)) }
+    }
+
+    fn validate_push_constants2<Pc: BufferContents>(
+        &self,
+        push_constants_info: &PushConstantsInfo,
+        push_constants: &Pc,
+    ) -> Result<(), Box<ValidationError>> {
+        self.inner
+            .validate_push_constants2(push_constants_info, push_constants)?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn push_constants2_unchecked<Pc>(
+        &mut self,
+        push_constants_info: PushConstantsInfo,
+        push_constants: Pc,
+    ) -> &mut Self
+    where
+        Pc: BufferContents,
+    {
+        let offset = push_constants_info.offset;
+
+        // TODO: Push constant invalidations.
+        // See the comment in `push_constants_unchecked`.
+        self.builder_state
+            .push_constants
+            .insert(offset..offset + size_of::<Pc>() as u32);
+        self.builder_state.push_constants_pipeline_layout =
+            Some(push_constants_info.layout.clone());
+
+        self.add_command(
+            "push_constants2",
+            Default::default(),
+            move |out: &mut RawRecordingCommandBuffer| {
+                out.push_constants2_unchecked(&push_constants_info, &push_constants);
+            },
+        );
+
+        self
+    }
 }
 
 impl RawRecordingCommandBuffer {
@@ -949,6 +1287,80 @@ impl RawRecordingCommandBuffer {
         &mut self,
         first_binding: u32,
         vertex_buffers: &[Subbuffer<[u8]>],
+    ) -> &mut Self {
+        self.bind_vertex_buffers2_unchecked(first_binding, vertex_buffers, None)
+    }
+
+    #[inline]
+    pub unsafe fn bind_vertex_buffers_with_strides(
+        &mut self,
+        first_binding: u32,
+        vertex_buffers: &[Subbuffer<[u8]>],
+        strides: &[u32],
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.validate_bind_vertex_buffers_with_strides(first_binding, vertex_buffers, strides)?;
+
+        Ok(self.bind_vertex_buffers_with_strides_unchecked(first_binding, vertex_buffers, strides))
+    }
+
+    fn validate_bind_vertex_buffers_with_strides(
+        &self,
+        first_binding: u32,
+        vertex_buffers: &[Subbuffer<[u8]>],
+        strides: &[u32],
+    ) -> Result<(), Box<ValidationError>> {
+        if !(self.device().api_version() >= Version::V1_3
+            || self.device().enabled_features().extended_dynamic_state)
+        {
+            return Err(Box::new(ValidationError {
+                requires_one_of: RequiresOneOf(&[
+                    RequiresAllOf(&[Requires::APIVersion(Version::V1_3)]),
+                    RequiresAllOf(&[Requires::DeviceFeature("extended_dynamic_state")]),
+                ]),
+                ..Default::default()
+            }));
+        }
+
+        self.validate_bind_vertex_buffers(first_binding, vertex_buffers)?;
+
+        if strides.len() != vertex_buffers.len() {
+            return Err(Box::new(ValidationError {
+                problem: "`strides.len()` is not equal to `vertex_buffers.len()`".into(),
+                ..Default::default()
+            }));
+        }
+
+        let properties = self.device().physical_device().properties();
+
+        for (strides_index, &stride) in strides.iter().enumerate() {
+            if stride > properties.max_vertex_input_binding_stride {
+                return Err(Box::new(ValidationError {
+                    context: format!("strides[{}]", strides_index).into(),
+                    problem: "is greater than the `max_vertex_input_binding_stride` limit".into(),
+                    vuids: &["VUID-vkCmdBindVertexBuffers2-pStrides-03362"],
+                    ..Default::default()
+                }));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn bind_vertex_buffers_with_strides_unchecked(
+        &mut self,
+        first_binding: u32,
+        vertex_buffers: &[Subbuffer<[u8]>],
+        strides: &[u32],
+    ) -> &mut Self {
+        self.bind_vertex_buffers2_unchecked(first_binding, vertex_buffers, Some(strides))
+    }
+
+    unsafe fn bind_vertex_buffers2_unchecked(
+        &mut self,
+        first_binding: u32,
+        vertex_buffers: &[Subbuffer<[u8]>],
+        strides: Option<&[u32]>,
     ) -> &mut Self {
         if vertex_buffers.is_empty() {
             return self;
@@ -970,6 +1382,9 @@ impl RawRecordingCommandBuffer {
                 sizes_vk.push(buffer.size());
             }
 
+            let strides_vk: Option<SmallVec<[_; 2]>> =
+                strides.map(|strides| strides.iter().map(|&stride| stride as DeviceSize).collect());
+
             let fns = self.device().fns();
             let cmd_bind_vertex_buffers2 = if device.api_version() >= Version::V1_3 {
                 fns.v1_3.cmd_bind_vertex_buffers2
@@ -986,7 +1401,9 @@ impl RawRecordingCommandBuffer {
                 buffers_vk.as_ptr(),
                 offsets_vk.as_ptr(),
                 sizes_vk.as_ptr(),
-                ptr::null(),
+                strides_vk
+                    .as_ref()
+                    .map_or(ptr::null(), |strides_vk| strides_vk.as_ptr()),
             )
         } else {
             let mut buffers_vk: SmallVec<[_; 2]> = SmallVec::with_capacity(vertex_buffers.len());
@@ -1011,25 +1428,115 @@ impl RawRecordingCommandBuffer {
     }
 
     #[inline]
-    pub unsafe fn push_constants<Pc>(
+    pub unsafe fn bind_null_vertex_buffers(
         &mut self,
-        pipeline_layout: &PipelineLayout,
-        offset: u32,
-        push_constants: &Pc,
-    ) -> Result<&mut Self, Box<ValidationError>>
-    where
-        Pc: BufferContents,
-    {
-        self.validate_push_constants(pipeline_layout, offset, push_constants)?;
+        first_binding: u32,
+        binding_count: u32,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.validate_bind_null_vertex_buffers(first_binding, binding_count)?;
 
-        Ok(self.push_constants_unchecked(pipeline_layout, offset, push_constants))
+        Ok(self.bind_null_vertex_buffers_unchecked(first_binding, binding_count))
     }
 
-    fn validate_push_constants<Pc: BufferContents>(
+    fn validate_bind_null_vertex_buffers(
         &self,
-        pipeline_layout: &PipelineLayout,
-        offset: u32,
-        _push_constants: &Pc,
+        first_binding: u32,
+        binding_count: u32,
+    ) -> Result<(), Box<ValidationError>> {
+        if !self
+            .queue_family_properties()
+            .queue_flags
+            .intersects(QueueFlags::GRAPHICS)
+        {
+            return Err(Box::new(ValidationError {
+                problem: "the queue family of the command buffer does not support \
+                    graphics operations"
+                    .into(),
+                vuids: &["VUID-vkCmdBindVertexBuffers-commandBuffer-cmdpool"],
+                ..Default::default()
+            }));
+        }
+
+        if !self.device().enabled_features().null_descriptor {
+            return Err(Box::new(ValidationError {
+                requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceFeature(
+                    "null_descriptor",
+                )])]),
+                vuids: &["VUID-vkCmdBindVertexBuffers-pBuffers-04001"],
+                ..Default::default()
+            }));
+        }
+
+        if binding_count == 0 {
+            return Err(Box::new(ValidationError {
+                context: "binding_count".into(),
+                problem: "is zero".into(),
+                vuids: &["VUID-vkCmdBindVertexBuffers-bindingCount-arraylength"],
+                ..Default::default()
+            }));
+        }
+
+        let properties = self.device().physical_device().properties();
+
+        if first_binding + binding_count > properties.max_vertex_input_bindings {
+            return Err(Box::new(ValidationError {
+                problem: "`first_binding + binding_count` is greater than the \
+                    `max_vertex_input_bindings` limit"
+                    .into(),
+                vuids: &[
+                    "VUID-vkCmdBindVertexBuffers-firstBinding-00624",
+                    "VUID-vkCmdBindVertexBuffers-firstBinding-00625",
+                ],
+                ..Default::default()
+            }));
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn bind_null_vertex_buffers_unchecked(
+        &mut self,
+        first_binding: u32,
+        binding_count: u32,
+    ) -> &mut Self {
+        // VUID-vkCmdBindVertexBuffers-pBuffers-04002
+        let buffers_vk: SmallVec<[_; 2]> =
+            smallvec![ash::vk::Buffer::null(); binding_count as usize];
+        let offsets_vk: SmallVec<[_; 2]> = smallvec![0; binding_count as usize];
+
+        let fns = self.device().fns();
+        (fns.v1_0.cmd_bind_vertex_buffers)(
+            self.handle(),
+            first_binding,
+            buffers_vk.len() as u32,
+            buffers_vk.as_ptr(),
+            offsets_vk.as_ptr(),
+        );
+
+        self
+    }
+
+    #[inline]
+    pub unsafe fn push_constants<Pc>(
+        &mut self,
+        pipeline_layout: &PipelineLayout,
+        offset: u32,
+        push_constants: &Pc,
+    ) -> Result<&mut Self, Box<ValidationError>>
+    where
+        Pc: BufferContents,
+    {
+        self.validate_push_constants(pipeline_layout, offset, push_constants)?;
+
+        Ok(self.push_constants_unchecked(pipeline_layout, offset, push_constants))
+    }
+
+    fn validate_push_constants<Pc: BufferContents>(
+        &self,
+        pipeline_layout: &PipelineLayout,
+        offset: u32,
+        _push_constants: &Pc,
     ) -> Result<(), Box<ValidationError>> {
         let mut remaining_size = size_of::<Pc>();
 
@@ -1379,4 +1886,467 @@ impl RawRecordingCommandBuffer {
 
         self
     }
+
+    #[inline]
+    pub unsafe fn bind_descriptor_sets2(
+        &mut self,
+        bind_descriptor_sets_info: &BindDescriptorSetsInfo,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.validate_bind_descriptor_sets2(bind_descriptor_sets_info)?;
+
+        Ok(self.bind_descriptor_sets2_unchecked(bind_descriptor_sets_info))
+    }
+
+    fn validate_bind_descriptor_sets2(
+        &self,
+        bind_descriptor_sets_info: &BindDescriptorSetsInfo,
+    ) -> Result<(), Box<ValidationError>> {
+        if !self.device().enabled_features().maintenance6 {
+            return Err(Box::new(ValidationError {
+                requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceFeature(
+                    "maintenance6",
+                )])]),
+                vuids: &["VUID-vkCmdBindDescriptorSets2KHR-None-09495"],
+                ..Default::default()
+            }));
+        }
+
+        bind_descriptor_sets_info
+            .validate(self.device())
+            .map_err(|err| err.add_context("bind_descriptor_sets_info"))?;
+
+        let &BindDescriptorSetsInfo {
+            stages,
+            ref layout,
+            first_set,
+            ref descriptor_sets,
+            _ne: _,
+        } = bind_descriptor_sets_info;
+
+        let queue_family_properties = self.queue_family_properties();
+
+        if stages.intersects(ShaderStages::COMPUTE)
+            && !queue_family_properties
+                .queue_flags
+                .intersects(QueueFlags::COMPUTE)
+        {
+            return Err(Box::new(ValidationError {
+                context: "bind_descriptor_sets_info.stages".into(),
+                problem: "contains `ShaderStages::COMPUTE`, but \
+                    the queue family of the command buffer does not support \
+                    compute operations"
+                    .into(),
+                vuids: &["VUID-vkCmdBindDescriptorSets2KHR-pBindDescriptorSetsInfo-09467"],
+                ..Default::default()
+            }));
+        }
+
+        if stages.intersects(BindDescriptorSetsInfo::GRAPHICS_STAGES)
+            && !queue_family_properties
+                .queue_flags
+                .intersects(QueueFlags::GRAPHICS)
+        {
+            return Err(Box::new(ValidationError {
+                context: "bind_descriptor_sets_info.stages".into(),
+                problem: "contains a graphics shader stage, but \
+                    the queue family of the command buffer does not support \
+                    graphics operations"
+                    .into(),
+                vuids: &["VUID-vkCmdBindDescriptorSets2KHR-pBindDescriptorSetsInfo-09467"],
+                ..Default::default()
+            }));
+        }
+
+        // The remaining requirements are the same as for `vkCmdBindDescriptorSets`, and are
+        // independent of the bind point once the queue family has been checked.
+        for pipeline_bind_point in bind_descriptor_sets_info.pipeline_bind_points() {
+            self.validate_bind_descriptor_sets(
+                pipeline_bind_point,
+                layout,
+                first_set,
+                descriptor_sets,
+            )
+            .map_err(|err| err.add_context("bind_descriptor_sets_info"))?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn bind_descriptor_sets2_unchecked(
+        &mut self,
+        bind_descriptor_sets_info: &BindDescriptorSetsInfo,
+    ) -> &mut Self {
+        let &BindDescriptorSetsInfo {
+            stages,
+            ref layout,
+            first_set,
+            ref descriptor_sets,
+            _ne: _,
+        } = bind_descriptor_sets_info;
+
+        if descriptor_sets.is_empty() {
+            return self;
+        }
+
+        let descriptor_sets_vk: SmallVec<[_; 12]> = descriptor_sets
+            .iter()
+            .map(|x| x.as_ref().0.handle())
+            .collect();
+        let dynamic_offsets_vk: SmallVec<[_; 32]> = descriptor_sets
+            .iter()
+            .flat_map(|x| x.as_ref().1.iter().copied())
+            .collect();
+
+        let info_vk = ash::vk::BindDescriptorSetsInfoKHR {
+            stage_flags: stages.into(),
+            layout: layout.handle(),
+            first_set,
+            descriptor_set_count: descriptor_sets_vk.len() as u32,
+            p_descriptor_sets: descriptor_sets_vk.as_ptr(),
+            dynamic_offset_count: dynamic_offsets_vk.len() as u32,
+            p_dynamic_offsets: dynamic_offsets_vk.as_ptr(),
+            ..Default::default()
+        };
+
+        let fns = self.device().fns();
+        (fns.khr_maintenance6.cmd_bind_descriptor_sets2_khr)(self.handle(), &info_vk);
+
+        self
+    }
+
+    #[inline]
+    pub unsafe fn push_constants2<Pc>(
+        &mut self,
+        push_constants_info: &PushConstantsInfo,
+        push_constants: &Pc,
+    ) -> Result<&mut Self, Box<ValidationError>>
+    where
+        Pc: BufferContents,
+    {
+        self.validate_push_constants2(push_constants_info, push_constants)?;
+
+        Ok(self.push_constants2_unchecked(push_constants_info, push_constants))
+    }
+
+    fn validate_push_constants2<Pc: BufferContents>(
+        &self,
+        push_constants_info: &PushConstantsInfo,
+        _push_constants: &Pc,
+    ) -> Result<(), Box<ValidationError>> {
+        if !self.device().enabled_features().maintenance6 {
+            return Err(Box::new(ValidationError {
+                requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceFeature(
+                    "maintenance6",
+                )])]),
+                vuids: &["VUID-vkCmdPushConstants2KHR-None-09495"],
+                ..Default::default()
+            }));
+        }
+
+        push_constants_info
+            .validate(self.device(), size_of::<Pc>() as u32)
+            .map_err(|err| err.add_context("push_constants_info"))?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn push_constants2_unchecked<Pc>(
+        &mut self,
+        push_constants_info: &PushConstantsInfo,
+        push_constants: &Pc,
+    ) -> &mut Self
+    where
+        Pc: BufferContents,
+    {
+        let size = u32::try_from(size_of::<Pc>()).unwrap();
+
+        if size == 0 {
+            return self;
+        }
+
+        let &PushConstantsInfo {
+            ref layout,
+            stages,
+            offset,
+            _ne: _,
+        } = push_constants_info;
+
+        let info_vk = ash::vk::PushConstantsInfoKHR {
+            layout: layout.handle(),
+            stage_flags: stages.into(),
+            offset,
+            size,
+            p_values: <*const _>::cast::<c_void>(push_constants),
+            ..Default::default()
+        };
+
+        let fns = self.device().fns();
+        (fns.khr_maintenance6.cmd_push_constants2_khr)(self.handle(), &info_vk);
+
+        self
+    }
+}
+
+/// Parameters to bind descriptor sets for one or more shader stages.
+#[derive(Clone, Debug)]
+pub struct BindDescriptorSetsInfo {
+    /// The shader stages that the descriptor sets will be bound for.
+    ///
+    /// The descriptor sets are bound to every pipeline bind point that corresponds to a stage in
+    /// `stages`: [`PipelineBindPoint::Compute`] for [`ShaderStages::COMPUTE`], and
+    /// [`PipelineBindPoint::Graphics`] for the graphics, task and mesh stages.
+    ///
+    /// The default value is empty, which must be overridden.
+    pub stages: ShaderStages,
+
+    /// The pipeline layout that the descriptor sets will be bound for.
+    ///
+    /// There is no default value.
+    pub layout: Arc<PipelineLayout>,
+
+    /// The set number of the first descriptor set in `descriptor_sets`.
+    ///
+    /// The default value is 0.
+    pub first_set: u32,
+
+    /// The descriptor sets to bind, along with their dynamic offsets.
+    ///
+    /// The default value is empty.
+    pub descriptor_sets: Vec<DescriptorSetWithOffsets>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl BindDescriptorSetsInfo {
+    const GRAPHICS_STAGES: ShaderStages = ShaderStages::all_graphics()
+        .union(ShaderStages::TASK)
+        .union(ShaderStages::MESH);
+
+    /// Returns a `BindDescriptorSetsInfo` with the specified `layout`.
+    #[inline]
+    pub fn layout(layout: Arc<PipelineLayout>) -> Self {
+        Self {
+            stages: ShaderStages::empty(),
+            layout,
+            first_set: 0,
+            descriptor_sets: Vec::new(),
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+
+    pub(crate) fn pipeline_bind_points(&self) -> impl Iterator<Item = PipelineBindPoint> {
+        let stages = self.stages;
+
+        [
+            (ShaderStages::COMPUTE, PipelineBindPoint::Compute),
+            (Self::GRAPHICS_STAGES, PipelineBindPoint::Graphics),
+        ]
+        .into_iter()
+        .filter_map(move |(bind_point_stages, pipeline_bind_point)| {
+            stages
+                .intersects(bind_point_stages)
+                .then_some(pipeline_bind_point)
+        })
+    }
+
+    pub(crate) fn validate(&self, device: &Device) -> Result<(), Box<ValidationError>> {
+        let &Self {
+            stages,
+            ref layout,
+            first_set: _,
+            descriptor_sets: _,
+            _ne: _,
+        } = self;
+
+        stages.validate_device(device).map_err(|err| {
+            err.add_context("stages")
+                .set_vuids(&["VUID-VkBindDescriptorSetsInfoKHR-stageFlags-parameter"])
+        })?;
+
+        if stages.is_empty() {
+            return Err(Box::new(ValidationError {
+                context: "stages".into(),
+                problem: "is empty".into(),
+                vuids: &["VUID-VkBindDescriptorSetsInfoKHR-stageFlags-requiredbitmask"],
+                ..Default::default()
+            }));
+        }
+
+        if !(ShaderStages::COMPUTE | Self::GRAPHICS_STAGES).contains(stages) {
+            return Err(Box::new(ValidationError {
+                context: "stages".into(),
+                problem: "contains a shader stage that does not belong to the compute or \
+                    graphics pipeline bind point"
+                    .into(),
+                ..Default::default()
+            }));
+        }
+
+        // VUID-VkBindDescriptorSetsInfoKHR-commonparent
+        assert_eq!(device, layout.device().as_ref());
+
+        Ok(())
+    }
+}
+
+/// Parameters to set push constants for one or more shader stages.
+#[derive(Clone, Debug)]
+pub struct PushConstantsInfo {
+    /// The pipeline layout that the push constants will be set for.
+    ///
+    /// There is no default value.
+    pub layout: Arc<PipelineLayout>,
+
+    /// The shader stages that the push constants will be set for.
+    ///
+    /// For every byte that is pushed, `stages` must contain all the stages of every push
+    /// constant range in `layout` that includes that byte, and every stage in `stages` must be
+    /// included in a push constant range that includes that byte.
+    ///
+    /// The default value is empty, which must be overridden.
+    pub stages: ShaderStages,
+
+    /// The byte offset into the push constant data at which the push constants will be written.
+    ///
+    /// The default value is 0.
+    pub offset: u32,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl PushConstantsInfo {
+    /// Returns a `PushConstantsInfo` with the specified `layout`.
+    #[inline]
+    pub fn layout(layout: Arc<PipelineLayout>) -> Self {
+        Self {
+            layout,
+            stages: ShaderStages::empty(),
+            offset: 0,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+
+    pub(crate) fn validate(&self, device: &Device, size: u32) -> Result<(), Box<ValidationError>> {
+        let &Self {
+            ref layout,
+            stages,
+            offset,
+            _ne: _,
+        } = self;
+
+        // VUID-VkPushConstantsInfoKHR-commonparent
+        assert_eq!(device, layout.device().as_ref());
+
+        stages.validate_device(device).map_err(|err| {
+            err.add_context("stages")
+                .set_vuids(&["VUID-VkPushConstantsInfoKHR-stageFlags-parameter"])
+        })?;
+
+        if stages.is_empty() {
+            return Err(Box::new(ValidationError {
+                context: "stages".into(),
+                problem: "is empty".into(),
+                vuids: &["VUID-VkPushConstantsInfoKHR-stageFlags-requiredbitmask"],
+                ..Default::default()
+            }));
+        }
+
+        if offset % 4 != 0 {
+            return Err(Box::new(ValidationError {
+                context: "offset".into(),
+                problem: "is not a multiple of 4".into(),
+                vuids: &["VUID-VkPushConstantsInfoKHR-offset-00368"],
+                ..Default::default()
+            }));
+        }
+
+        if size % 4 != 0 {
+            return Err(Box::new(ValidationError {
+                problem: "the size of the push constants is not a multiple of 4".into(),
+                vuids: &["VUID-VkPushConstantsInfoKHR-size-00369"],
+                ..Default::default()
+            }));
+        }
+
+        let properties = device.physical_device().properties();
+
+        if offset >= properties.max_push_constants_size {
+            return Err(Box::new(ValidationError {
+                context: "offset".into(),
+                problem: "is not less than the `max_push_constants_size` limit".into(),
+                vuids: &["VUID-VkPushConstantsInfoKHR-offset-00370"],
+                ..Default::default()
+            }));
+        }
+
+        if offset as u64 + size as u64 > properties.max_push_constants_size as u64 {
+            return Err(Box::new(ValidationError {
+                problem: "`offset` + the size of the push constants is greater than the \
+                    `max_push_constants_size` limit"
+                    .into(),
+                vuids: &["VUID-VkPushConstantsInfoKHR-size-00371"],
+                ..Default::default()
+            }));
+        }
+
+        let end = offset + size;
+
+        for (range_index, range) in layout.push_constant_ranges().iter().enumerate() {
+            if range.offset < end && offset < range.offset + range.size {
+                if !stages.contains(range.stages) {
+                    return Err(Box::new(ValidationError {
+                        problem: format!(
+                            "the pushed bytes overlap with `layout.push_constant_ranges()[{}]`, \
+                            but `stages` does not contain all stages of that range",
+                            range_index,
+                        )
+                        .into(),
+                        vuids: &["VUID-VkPushConstantsInfoKHR-offset-01796"],
+                        ..Default::default()
+                    }));
+                }
+            }
+        }
+
+        for stage in stages {
+            let mut stage_ranges: SmallVec<[_; 4]> = layout
+                .push_constant_ranges()
+                .iter()
+                .filter(|range| range.stages.contains_enum(stage))
+                .collect();
+            stage_ranges.sort_unstable_by_key(|range| range.offset);
+
+            let mut covered_until = offset;
+
+            for range in stage_ranges {
+                if range.offset > covered_until {
+                    break;
+                }
+
+                covered_until = covered_until.max(range.offset + range.size);
+
+                if covered_until >= end {
+                    break;
+                }
+            }
+
+            if covered_until < end {
+                return Err(Box::new(ValidationError {
+                    problem: format!(
+                        "`stages` contains `ShaderStage::{:?}`, but one or more of the pushed \
+                        bytes are not within a push constant range of `layout` that includes \
+                        that stage",
+                        stage,
+                    )
+                    .into(),
+                    vuids: &["VUID-VkPushConstantsInfoKHR-offset-01795"],
+                    ..Default::default()
+                }));
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -20,7 +20,8 @@ impl RecordingCommandBuffer {
         &mut self,
         clear_info: ClearColorImageInfo,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_clear_color_image(&clear_info)?;
+        self.validate_clear_color_image(&clear_info)
+            .map_err(|err| self.command_error(err, "clear_color_image"))?;
 
         unsafe { Ok(self.clear_color_image_unchecked(clear_info)) }
     }
@@ -86,7 +87,8 @@ impl RecordingCommandBuffer {
         &mut self,
         clear_info: ClearDepthStencilImageInfo,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_clear_depth_stencil_image(&clear_info)?;
+        self.validate_clear_depth_stencil_image(&clear_info)
+            .map_err(|err| self.command_error(err, "clear_depth_stencil_image"))?;
 
         unsafe { Ok(self.clear_depth_stencil_image_unchecked(clear_info)) }
     }
@@ -151,12 +153,37 @@ impl RecordingCommandBuffer {
     ///
     /// This function is similar to the `memset` function in C. The `data` parameter is a number
     /// that will be repeatedly written through the entire buffer.
+    ///
+    /// If a fallback has been set with [`set_transfer_fallback`], and `dst_buffer` wasn't created
+    /// with the [`BufferUsage::TRANSFER_DST`] usage, then the buffer is filled with
+    /// [`TransferFallback::fill_buffer`] instead.
+    ///
+    /// [`set_transfer_fallback`]: Self::set_transfer_fallback
+    /// [`TransferFallback::fill_buffer`]: crate::command_buffer::TransferFallback::fill_buffer
     pub fn fill_buffer(
         &mut self,
         dst_buffer: Subbuffer<[u32]>,
         data: u32,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_fill_buffer(&dst_buffer, data)?;
+        if !dst_buffer
+            .buffer()
+            .usage()
+            .intersects(BufferUsage::TRANSFER_DST)
+        {
+            // Take the fallback out while it records, so that it can't end up calling itself.
+            if let Some(transfer_fallback) = self.transfer_fallback.take() {
+                // VUID-vkCmdFillBuffer-commonparent
+                assert_eq!(self.device(), dst_buffer.device());
+
+                let result = transfer_fallback.fill_buffer(self, dst_buffer.into_bytes(), data);
+                self.transfer_fallback = Some(transfer_fallback);
+
+                return result.map(|()| self);
+            }
+        }
+
+        self.validate_fill_buffer(&dst_buffer, data)
+            .map_err(|err| self.command_error(err, "fill_buffer"))?;
 
         unsafe { Ok(self.fill_buffer_unchecked(dst_buffer, data)) }
     }
@@ -218,7 +245,8 @@ impl RecordingCommandBuffer {
         self.validate_update_buffer(
             dst_buffer.as_bytes(),
             size_of_val(data.deref()) as DeviceSize,
-        )?;
+        )
+        .map_err(|err| self.command_error(err, "update_buffer"))?;
 
         unsafe { Ok(self.update_buffer_unchecked(dst_buffer, data)) }
     }
@@ -686,7 +714,7 @@ impl ClearColorImageInfo {
         let &Self {
             ref image,
             image_layout,
-            clear_value: _,
+            clear_value,
             ref regions,
             _ne: _,
         } = self;
@@ -751,6 +779,24 @@ impl ClearColorImageInfo {
             }));
         }
 
+        if let Some(numeric_format) = image.format().numeric_format_color() {
+            if clear_value.numeric_type() != numeric_format.numeric_type() {
+                return Err(Box::new(ValidationError {
+                    problem: format!(
+                        "`clear_value` is `ClearColorValue::{:?}`, but `image.format()` is \
+                        `Format::{:?}`, which requires a clear value of type \
+                        `ClearColorValue::{:?}` (`ClearColorValue::for_format` creates a clear \
+                        value of the right type)",
+                        clear_value.numeric_type(),
+                        image.format(),
+                        numeric_format.numeric_type(),
+                    )
+                    .into(),
+                    ..Default::default()
+                }));
+            }
+        }
+
         if !matches!(
             image_layout,
             ImageLayout::TransferDstOptimal | ImageLayout::General
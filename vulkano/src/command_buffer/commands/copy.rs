@@ -21,17 +21,76 @@ use std::{
 
 /// # Commands to transfer data between resources.
 impl RecordingCommandBuffer {
+    /// Sets the fallback that [`fill_buffer`] and [`copy_buffer`] use to record commands that
+    /// can't be recorded as transfer commands, or disables it if `None` is given.
+    ///
+    /// The fallback is used when a destination buffer was not created with the
+    /// [`BufferUsage::TRANSFER_DST`] usage, or a source buffer was not created with the
+    /// [`BufferUsage::TRANSFER_SRC`] usage. It is not used by default.
+    ///
+    /// A fallback may record any commands, including ones that bind pipeline state. Any state
+    /// that the fallback binds must be bound again afterwards.
+    ///
+    /// [`fill_buffer`]: Self::fill_buffer
+    /// [`copy_buffer`]: Self::copy_buffer
+    #[inline]
+    pub fn set_transfer_fallback(
+        &mut self,
+        transfer_fallback: Option<Arc<dyn TransferFallback>>,
+    ) -> &mut Self {
+        self.transfer_fallback = transfer_fallback;
+
+        self
+    }
+
+    /// Returns the fallback that was set with [`set_transfer_fallback`].
+    ///
+    /// [`set_transfer_fallback`]: Self::set_transfer_fallback
+    #[inline]
+    pub fn transfer_fallback(&self) -> Option<&Arc<dyn TransferFallback>> {
+        self.transfer_fallback.as_ref()
+    }
+
     /// Copies data from a buffer to another buffer.
     ///
+    /// If a fallback has been set with [`set_transfer_fallback`], and `src_buffer` wasn't created
+    /// with the [`BufferUsage::TRANSFER_SRC`] usage or `dst_buffer` wasn't created with the
+    /// [`BufferUsage::TRANSFER_DST`] usage, then each region is copied with
+    /// [`TransferFallback::copy_buffer`] instead.
+    ///
     /// # Panics
     ///
     /// - Panics if `src_buffer` or `dst_buffer` were not created from the same device as `self`.
+    ///
+    /// [`set_transfer_fallback`]: Self::set_transfer_fallback
     pub fn copy_buffer(
         &mut self,
         copy_buffer_info: impl Into<CopyBufferInfo>,
     ) -> Result<&mut Self, Box<ValidationError>> {
         let copy_buffer_info = copy_buffer_info.into();
-        self.validate_copy_buffer(&copy_buffer_info)?;
+
+        if !(copy_buffer_info
+            .src_buffer
+            .buffer()
+            .usage()
+            .intersects(BufferUsage::TRANSFER_SRC)
+            && copy_buffer_info
+                .dst_buffer
+                .buffer()
+                .usage()
+                .intersects(BufferUsage::TRANSFER_DST))
+        {
+            // Take the fallback out while it records, so that it can't end up calling itself.
+            if let Some(transfer_fallback) = self.transfer_fallback.take() {
+                let result = self.copy_buffer_fallback(&*transfer_fallback, copy_buffer_info);
+                self.transfer_fallback = Some(transfer_fallback);
+
+                return result.map(|()| self);
+            }
+        }
+
+        self.validate_copy_buffer(&copy_buffer_info)
+            .map_err(|err| self.command_error(err, "copy_buffer"))?;
 
         unsafe { Ok(self.copy_buffer_unchecked(copy_buffer_info)) }
     }
@@ -53,6 +112,77 @@ impl RecordingCommandBuffer {
         Ok(())
     }
 
+    fn copy_buffer_fallback(
+        &mut self,
+        transfer_fallback: &dyn TransferFallback,
+        copy_buffer_info: CopyBufferInfo,
+    ) -> Result<(), Box<ValidationError>> {
+        let CopyBufferInfo {
+            src_buffer,
+            dst_buffer,
+            regions,
+            _ne: _,
+        } = copy_buffer_info;
+
+        // VUID-VkCopyBufferInfo2-commonparent
+        assert_eq!(self.device(), src_buffer.device());
+        assert_eq!(self.device(), dst_buffer.device());
+
+        for (region_index, region) in regions.iter().enumerate() {
+            region
+                .validate(self.device())
+                .map_err(|err| err.add_context(format!("regions[{}]", region_index)))?;
+
+            let &BufferCopy {
+                src_offset,
+                dst_offset,
+                size,
+                _ne: _,
+            } = region;
+
+            if src_offset + size > src_buffer.size() {
+                return Err(Box::new(ValidationError {
+                    problem: format!(
+                        "`regions[{0}].src_offset + regions[{0}].size` is greater than \
+                        `src_buffer.size()`",
+                        region_index
+                    )
+                    .into(),
+                    ..Default::default()
+                }));
+            }
+
+            if dst_offset + size > dst_buffer.size() {
+                return Err(Box::new(ValidationError {
+                    problem: format!(
+                        "`regions[{0}].dst_offset + regions[{0}].size` is greater than \
+                        `dst_buffer.size()`",
+                        region_index
+                    )
+                    .into(),
+                    ..Default::default()
+                }));
+            }
+        }
+
+        for region in regions {
+            let BufferCopy {
+                src_offset,
+                dst_offset,
+                size,
+                _ne: _,
+            } = region;
+
+            transfer_fallback.copy_buffer(
+                self,
+                src_buffer.clone().slice(src_offset..src_offset + size),
+                dst_buffer.clone().slice(dst_offset..dst_offset + size),
+            )?;
+        }
+
+        Ok(())
+    }
+
     #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
     pub unsafe fn copy_buffer_unchecked(
         &mut self,
@@ -129,7 +259,8 @@ impl RecordingCommandBuffer {
         &mut self,
         copy_image_info: CopyImageInfo,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_copy_image(&copy_image_info)?;
+        self.validate_copy_image(&copy_image_info)
+            .map_err(|err| self.command_error(err, "copy_image"))?;
 
         unsafe { Ok(self.copy_image_unchecked(copy_image_info)) }
     }
@@ -213,7 +344,8 @@ impl RecordingCommandBuffer {
         &mut self,
         copy_buffer_to_image_info: CopyBufferToImageInfo,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_copy_buffer_to_image(&copy_buffer_to_image_info)?;
+        self.validate_copy_buffer_to_image(&copy_buffer_to_image_info)
+            .map_err(|err| self.command_error(err, "copy_buffer_to_image"))?;
 
         unsafe { Ok(self.copy_buffer_to_image_unchecked(copy_buffer_to_image_info)) }
     }
@@ -300,7 +432,8 @@ impl RecordingCommandBuffer {
         &mut self,
         copy_image_to_buffer_info: CopyImageToBufferInfo,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_copy_image_to_buffer(&copy_image_to_buffer_info)?;
+        self.validate_copy_image_to_buffer(&copy_image_to_buffer_info)
+            .map_err(|err| self.command_error(err, "copy_image_to_buffer"))?;
 
         unsafe { Ok(self.copy_image_to_buffer_unchecked(copy_image_to_buffer_info)) }
     }
@@ -416,7 +549,8 @@ impl RecordingCommandBuffer {
         &mut self,
         blit_image_info: BlitImageInfo,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_blit_image(&blit_image_info)?;
+        self.validate_blit_image(&blit_image_info)
+            .map_err(|err| self.command_error(err, "blit_image"))?;
 
         unsafe { Ok(self.blit_image_unchecked(blit_image_info)) }
     }
@@ -504,7 +638,8 @@ impl RecordingCommandBuffer {
         &mut self,
         resolve_image_info: ResolveImageInfo,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_resolve_image(&resolve_image_info)?;
+        self.validate_resolve_image(&resolve_image_info)
+            .map_err(|err| self.command_error(err, "resolve_image"))?;
 
         unsafe { Ok(self.resolve_image_unchecked(resolve_image_info)) }
     }
@@ -737,274 +872,11 @@ impl RawRecordingCommandBuffer {
             .validate(self.device())
             .map_err(|err| err.add_context("copy_image_info"))?;
 
-        let &CopyImageInfo {
-            ref src_image,
-            src_image_layout: _,
-            ref dst_image,
-            dst_image_layout: _,
-            ref regions,
-            _ne: _,
-        } = copy_image_info;
-
-        let src_image_format = src_image.format();
-        let src_image_format_subsampled_extent = src_image_format
-            .ycbcr_chroma_sampling()
-            .map_or(src_image.extent(), |s| {
-                s.subsampled_extent(src_image.extent())
-            });
-
-        let dst_image_format = dst_image.format();
-        let dst_image_format_subsampled_extent = dst_image_format
-            .ycbcr_chroma_sampling()
-            .map_or(dst_image.extent(), |s| {
-                s.subsampled_extent(dst_image.extent())
-            });
-
-        let min_image_transfer_granularity =
-            // `[1; 3]` means the granularity is 1x1x1 texel, so we can ignore it.
-            // Only check this if there are values greater than 1.
-            (queue_family_properties.min_image_transfer_granularity != [1; 3]).then(|| {
-                // `[0; 3]` means only the whole subresource can be copied.
-                (queue_family_properties.min_image_transfer_granularity != [0; 3]).then(|| {
-                    // Spec:
-                    // "The value returned in minImageTransferGranularity has a unit of
-                    // compressed texel blocks for images having a block-compressed format,
-                    // and a unit of texels otherwise.""
-
-                    let src_granularity = if src_image_format.compression().is_some() {
-                        let granularity = queue_family_properties.min_image_transfer_granularity;
-                        let block_extent = src_image_format.block_extent();
-
-                        [
-                            granularity[0] * block_extent[0],
-                            granularity[1] * block_extent[1],
-                            granularity[2] * block_extent[2],
-                        ]
-                    } else {
-                        queue_family_properties.min_image_transfer_granularity
-                    };
-
-                    let dst_granularity = if dst_image_format.compression().is_some() {
-                        let granularity = queue_family_properties.min_image_transfer_granularity;
-                        let block_extent = dst_image_format.block_extent();
-
-                        [
-                            granularity[0] * block_extent[0],
-                            granularity[1] * block_extent[1],
-                            granularity[2] * block_extent[2],
-                        ]
-                    } else {
-                        queue_family_properties.min_image_transfer_granularity
-                    };
-
-                    (src_granularity, dst_granularity)
-                })
-            });
-
-        if min_image_transfer_granularity.is_some() {
-            for (region_index, region) in regions.iter().enumerate() {
-                let &ImageCopy {
-                    ref src_subresource,
-                    src_offset,
-                    ref dst_subresource,
-                    dst_offset,
-                    extent,
-                    _ne: _,
-                } = region;
-
-                if let Some(min_image_transfer_granularity) = &min_image_transfer_granularity {
-                    let mut src_subresource_extent =
-                        mip_level_extent(src_image.extent(), src_subresource.mip_level).unwrap();
-
-                    if matches!(
-                        src_subresource.aspects,
-                        ImageAspects::PLANE_1 | ImageAspects::PLANE_2
-                    ) {
-                        src_subresource_extent = src_image_format_subsampled_extent;
-                    }
-
-                    let mut dst_subresource_extent =
-                        mip_level_extent(dst_image.extent(), dst_subresource.mip_level).unwrap();
-
-                    if matches!(
-                        dst_subresource.aspects,
-                        ImageAspects::PLANE_1 | ImageAspects::PLANE_2
-                    ) {
-                        dst_subresource_extent = dst_image_format_subsampled_extent;
-                    }
-
-                    if let Some((src_granularity, dst_granularity)) =
-                        &min_image_transfer_granularity
-                    {
-                        /*
-                           Check src
-                        */
-
-                        for i in 0..3 {
-                            if src_offset[i] % src_granularity[i] != 0 {
-                                return Err(Box::new(ValidationError {
-                                    context: "copy_image_info".into(),
-                                    problem: format!(
-                                        "the `min_image_transfer_granularity` property of the \
-                                    queue family of the command buffer is not `[0; 3]`, but \
-                                    `regions[{}].src_offset[{1}]` is not a multiple of \
-                                    `min_image_transfer_granularity[{1}]` texel blocks",
-                                        region_index, i,
-                                    )
-                                    .into(),
-                                    vuids: &["VUID-VkCopyImageInfo2-srcOffset-01783"],
-                                    ..Default::default()
-                                }));
-                            }
-
-                            if src_offset[i] + extent[i] != src_subresource_extent[i]
-                                && extent[i] % src_granularity[i] != 0
-                            {
-                                return Err(Box::new(ValidationError {
-                                    context: "copy_image_info".into(),
-                                    problem: format!(
-                                        "the `min_image_transfer_granularity` property of the \
-                                    queue family of the command buffer is not `[0; 3]`, and \
-                                    `regions[{0}].src_offset[{1}] + regions[{0}].extent[{1}]` \
-                                    is not equal to coordinate {1} of the extent of the \
-                                    subresource of `src_image` selected by \
-                                    `regions[{0}].src_subresource`, but \
-                                    `regions[{}].extent[{1}]` is not a multiple of \
-                                    `min_image_transfer_granularity[{1}]` texel blocks",
-                                        region_index, i,
-                                    )
-                                    .into(),
-                                    vuids: &["VUID-VkCopyImageInfo2-srcOffset-01783"],
-                                    ..Default::default()
-                                }));
-                            }
-                        }
-
-                        /*
-                           Check dst
-                        */
-
-                        for i in 0..3 {
-                            if dst_offset[i] % dst_granularity[i] != 0 {
-                                return Err(Box::new(ValidationError {
-                                    context: "copy_image_info".into(),
-                                    problem: format!(
-                                        "the `min_image_transfer_granularity` property of the \
-                                    queue family of the command buffer is not `[0; 3]`, but \
-                                    `regions[{}].dst_offset[{1}]` is not a multiple of \
-                                    `min_image_transfer_granularity[{1}]` texel blocks",
-                                        region_index, i,
-                                    )
-                                    .into(),
-                                    vuids: &["VUID-VkCopyImageInfo2-dstOffset-01784"],
-                                    ..Default::default()
-                                }));
-                            }
-
-                            if dst_offset[i] + extent[i] != dst_subresource_extent[i]
-                                && extent[i] % dst_granularity[i] != 0
-                            {
-                                return Err(Box::new(ValidationError {
-                                    context: "copy_image_info".into(),
-                                    problem: format!(
-                                        "the `min_image_transfer_granularity` property of the \
-                                    queue family of the command buffer is not `[0; 3]`, and \
-                                    `regions[{0}].dst_offset[{1}] + regions[{0}].extent[{1}]` \
-                                    is not equal to coordinate {1} of the extent of the \
-                                    subresource of `dst_image` selected by \
-                                    `regions[{0}].dst_subresource`, but \
-                                    `regions[{}].extent[{1}]` is not a multiple of \
-                                    `min_image_transfer_granularity[{1}]` texel blocks",
-                                        region_index, i,
-                                    )
-                                    .into(),
-                                    vuids: &["VUID-VkCopyImageInfo2-dstOffset-01784"],
-                                    ..Default::default()
-                                }));
-                            }
-                        }
-                    } else {
-                        /*
-                           Check src
-                        */
-
-                        for i in 0..3 {
-                            if src_offset[i] != 0 {
-                                return Err(Box::new(ValidationError {
-                                    context: "copy_image_info".into(),
-                                    problem: format!(
-                                        "the `min_image_transfer_granularity` property of the \
-                                    queue family of the command buffer is `[0; 3]`, but \
-                                    `regions[{}].src_offset[{}]` is not 0",
-                                        region_index, i,
-                                    )
-                                    .into(),
-                                    vuids: &["VUID-VkCopyImageInfo2-srcOffset-01783"],
-                                    ..Default::default()
-                                }));
-                            }
-
-                            if src_offset[i] + extent[i] != src_subresource_extent[i] {
-                                return Err(Box::new(ValidationError {
-                                    context: "copy_image_info".into(),
-                                    problem: format!(
-                                        "the `min_image_transfer_granularity` property of the \
-                                    queue family of the command buffer is `[0; 3]`, but \
-                                    `regions[{0}].src_offset[{1}] + regions[{0}].extent[{1}]` \
-                                    is not equal to coordinate {1} of the extent of the \
-                                    subresource of `src_image` selected by \
-                                    `regions[{0}].src_subresource`",
-                                        region_index, i,
-                                    )
-                                    .into(),
-                                    vuids: &["VUID-VkCopyImageInfo2-srcOffset-01783"],
-                                    ..Default::default()
-                                }));
-                            }
-                        }
-
-                        /*
-                           Check dst
-                        */
-
-                        for i in 0..3 {
-                            if dst_offset[i] != 0 {
-                                return Err(Box::new(ValidationError {
-                                    context: "copy_image_info".into(),
-                                    problem: format!(
-                                        "the `min_image_transfer_granularity` property of the \
-                                    queue family of the command buffer is `[0; 3]`, but \
-                                    `regions[{}].dst_offset[{}]` is not 0",
-                                        region_index, i,
-                                    )
-                                    .into(),
-                                    vuids: &["VUID-VkCopyImageInfo2-dstOffset-01784"],
-                                    ..Default::default()
-                                }));
-                            }
-
-                            if dst_offset[i] + extent[i] != dst_subresource_extent[i] {
-                                return Err(Box::new(ValidationError {
-                                    context: "copy_image_info".into(),
-                                    problem: format!(
-                                        "the `min_image_transfer_granularity` property of the \
-                                    queue family of the command buffer is `[0; 3]`, but \
-                                    `regions[{0}].dst_offset[{1}] + regions[{0}].extent[{1}]` \
-                                    is not equal to coordinate {1} of the extent of the \
-                                    subresource of `dst_image` selected by \
-                                    `regions[{0}].dst_subresource`",
-                                        region_index, i,
-                                    )
-                                    .into(),
-                                    vuids: &["VUID-VkCopyImageInfo2-dstOffset-01784"],
-                                    ..Default::default()
-                                }));
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        copy_image_info
+            .validate_min_image_transfer_granularity(
+                queue_family_properties.min_image_transfer_granularity,
+            )
+            .map_err(|err| err.add_context("copy_image_info"))?;
 
         Ok(())
     }
@@ -1161,47 +1033,20 @@ impl RawRecordingCommandBuffer {
             .validate(self.device())
             .map_err(|err| err.add_context("copy_buffer_to_image_info"))?;
 
+        copy_buffer_to_image_info
+            .validate_min_image_transfer_granularity(
+                queue_family_properties.min_image_transfer_granularity,
+            )
+            .map_err(|err| err.add_context("copy_buffer_to_image_info"))?;
+
         let &CopyBufferToImageInfo {
             src_buffer: _,
-            ref dst_image,
+            dst_image: _,
             dst_image_layout: _,
             ref regions,
             _ne,
         } = copy_buffer_to_image_info;
 
-        let dst_image_format = dst_image.format();
-        let dst_image_format_subsampled_extent = dst_image_format
-            .ycbcr_chroma_sampling()
-            .map_or(dst_image.extent(), |s| {
-                s.subsampled_extent(dst_image.extent())
-            });
-
-        let min_image_transfer_granularity =
-            // `[1; 3]` means the granularity is 1x1x1 texel, so we can ignore it.
-            // Only check this if there are values greater than 1.
-            (queue_family_properties.min_image_transfer_granularity != [1; 3]).then(|| {
-                // `[0; 3]` means only the whole subresource can be copied.
-                (queue_family_properties.min_image_transfer_granularity != [0; 3]).then(|| {
-                    // Spec:
-                    // "The value returned in minImageTransferGranularity has a unit of
-                    // compressed texel blocks for images having a block-compressed format,
-                    // and a unit of texels otherwise.""
-
-                    if dst_image_format.compression().is_some() {
-                        let granularity = queue_family_properties.min_image_transfer_granularity;
-                        let block_extent = dst_image_format.block_extent();
-
-                        [
-                            granularity[0] * block_extent[0],
-                            granularity[1] * block_extent[1],
-                            granularity[2] * block_extent[2],
-                        ]
-                    } else {
-                        queue_family_properties.min_image_transfer_granularity
-                    }
-                })
-            });
-
         let queue_family_no_graphics = !queue_family_properties
             .queue_flags
             .intersects(QueueFlags::GRAPHICS);
@@ -1209,15 +1054,15 @@ impl RawRecordingCommandBuffer {
             .queue_flags
             .intersects(QueueFlags::COMPUTE);
 
-        if min_image_transfer_granularity.is_some() || queue_family_no_graphics {
+        if queue_family_no_graphics {
             for (region_index, region) in regions.iter().enumerate() {
                 let &BufferImageCopy {
                     buffer_offset,
                     buffer_row_length: _,
                     buffer_image_height: _,
                     ref image_subresource,
-                    image_offset,
-                    image_extent,
+                    image_offset: _,
+                    image_extent: _,
                     _ne,
                 } = region;
 
@@ -1256,97 +1101,6 @@ impl RawRecordingCommandBuffer {
                         }));
                     }
                 }
-
-                if let Some(min_image_transfer_granularity) = &min_image_transfer_granularity {
-                    let mut image_subresource_extent =
-                        mip_level_extent(dst_image.extent(), image_subresource.mip_level).unwrap();
-
-                    if matches!(
-                        image_subresource.aspects,
-                        ImageAspects::PLANE_1 | ImageAspects::PLANE_2
-                    ) {
-                        image_subresource_extent = dst_image_format_subsampled_extent;
-                    }
-
-                    if let Some(dst_granularity) = &min_image_transfer_granularity {
-                        for i in 0..3 {
-                            if image_offset[i] % dst_granularity[i] != 0 {
-                                return Err(Box::new(ValidationError {
-                                    context: "copy_image_info".into(),
-                                    problem: format!(
-                                        "the `min_image_transfer_granularity` property of the \
-                                        queue family of the command buffer is not `[0; 3]`, but \
-                                        `regions[{}].image_offset[{1}]` is not a multiple of \
-                                        `min_image_transfer_granularity[{1}]` texel blocks",
-                                        region_index, i,
-                                    )
-                                    .into(),
-                                    vuids: &["VUID-vkCmdCopyBufferToImage2-imageOffset-07738"],
-                                    ..Default::default()
-                                }));
-                            }
-
-                            if image_offset[i] + image_extent[i] != image_subresource_extent[i]
-                                && image_extent[i] % dst_granularity[i] != 0
-                            {
-                                return Err(Box::new(ValidationError {
-                                    context: "copy_image_info".into(),
-                                    problem: format!(
-                                        "the `min_image_transfer_granularity` property of the \
-                                        queue family of the command buffer is not `[0; 3]`, and \
-                                        `regions[{0}].image_offset[{1}] + \
-                                        regions[{0}].image_extent[{1}]` \
-                                        is not equal to coordinate {1} of the extent of the \
-                                        subresource of `dst_image` selected by \
-                                        `regions[{0}].image_subresource`, but \
-                                        `regions[{}].image_extent[{1}]` is not a multiple of \
-                                        `min_image_transfer_granularity[{1}]` texel blocks",
-                                        region_index, i,
-                                    )
-                                    .into(),
-                                    vuids: &["VUID-vkCmdCopyBufferToImage2-imageOffset-07738"],
-                                    ..Default::default()
-                                }));
-                            }
-                        }
-                    } else {
-                        for i in 0..3 {
-                            if image_offset[i] != 0 {
-                                return Err(Box::new(ValidationError {
-                                    context: "copy_image_info".into(),
-                                    problem: format!(
-                                        "the `min_image_transfer_granularity` property of the \
-                                        queue family of the command buffer is `[0; 3]`, but \
-                                        `regions[{}].image_offset[{}]` is not 0",
-                                        region_index, i,
-                                    )
-                                    .into(),
-                                    vuids: &["VUID-vkCmdCopyBufferToImage2-imageOffset-07738"],
-                                    ..Default::default()
-                                }));
-                            }
-
-                            if image_offset[i] + image_extent[i] != image_subresource_extent[i] {
-                                return Err(Box::new(ValidationError {
-                                    context: "copy_image_info".into(),
-                                    problem: format!(
-                                        "the `min_image_transfer_granularity` property of the \
-                                        queue family of the command buffer is `[0; 3]`, but \
-                                        `regions[{0}].image_offset[{1}] + \
-                                        regions[{0}].image_extent[{1}]` \
-                                        is not equal to coordinate {1} of the extent of the \
-                                        subresource of `dst_image` selected by \
-                                        `regions[{0}].image_subresource`",
-                                        region_index, i,
-                                    )
-                                    .into(),
-                                    vuids: &["VUID-vkCmdCopyBufferToImage2-imageOffset-07738"],
-                                    ..Default::default()
-                                }));
-                            }
-                        }
-                    }
-                }
             }
         }
 
@@ -1504,47 +1258,20 @@ impl RawRecordingCommandBuffer {
             .validate(self.device())
             .map_err(|err| err.add_context("copy_image_to_buffer_info"))?;
 
+        copy_image_to_buffer_info
+            .validate_min_image_transfer_granularity(
+                queue_family_properties.min_image_transfer_granularity,
+            )
+            .map_err(|err| err.add_context("copy_image_to_buffer_info"))?;
+
         let &CopyImageToBufferInfo {
-            ref src_image,
+            src_image: _,
             src_image_layout: _,
             dst_buffer: _,
             ref regions,
             _ne,
         } = copy_image_to_buffer_info;
 
-        let src_image_format = src_image.format();
-        let src_image_format_subsampled_extent = src_image_format
-            .ycbcr_chroma_sampling()
-            .map_or(src_image.extent(), |s| {
-                s.subsampled_extent(src_image.extent())
-            });
-
-        let min_image_transfer_granularity =
-            // `[1; 3]` means the granularity is 1x1x1 texel, so we can ignore it.
-            // Only check this if there are values greater than 1.
-            (queue_family_properties.min_image_transfer_granularity != [1; 3]).then(|| {
-                // `[0; 3]` means only the whole subresource can be copied.
-                (queue_family_properties.min_image_transfer_granularity != [0; 3]).then(|| {
-                    // Spec:
-                    // "The value returned in minImageTransferGranularity has a unit of
-                    // compressed texel blocks for images having a block-compressed format,
-                    // and a unit of texels otherwise.""
-
-                    if src_image_format.compression().is_some() {
-                        let granularity = queue_family_properties.min_image_transfer_granularity;
-                        let block_extent = src_image_format.block_extent();
-
-                        [
-                            granularity[0] * block_extent[0],
-                            granularity[1] * block_extent[1],
-                            granularity[2] * block_extent[2],
-                        ]
-                    } else {
-                        queue_family_properties.min_image_transfer_granularity
-                    }
-                })
-            });
-
         let queue_family_no_graphics = !queue_family_properties
             .queue_flags
             .intersects(QueueFlags::GRAPHICS);
@@ -1552,15 +1279,15 @@ impl RawRecordingCommandBuffer {
             .queue_flags
             .intersects(QueueFlags::COMPUTE);
 
-        if min_image_transfer_granularity.is_some() || queue_family_no_graphics {
+        if queue_family_no_graphics {
             for (region_index, region) in regions.iter().enumerate() {
                 let &BufferImageCopy {
                     buffer_offset,
                     buffer_row_length: _,
                     buffer_image_height: _,
                     ref image_subresource,
-                    image_offset,
-                    image_extent,
+                    image_offset: _,
+                    image_extent: _,
                     _ne,
                 } = region;
 
@@ -1578,97 +1305,6 @@ impl RawRecordingCommandBuffer {
                         ..Default::default()
                     }));
                 }
-
-                if let Some(min_image_transfer_granularity) = &min_image_transfer_granularity {
-                    let mut image_subresource_extent =
-                        mip_level_extent(src_image.extent(), image_subresource.mip_level).unwrap();
-
-                    if matches!(
-                        image_subresource.aspects,
-                        ImageAspects::PLANE_1 | ImageAspects::PLANE_2
-                    ) {
-                        image_subresource_extent = src_image_format_subsampled_extent;
-                    }
-
-                    if let Some(src_granularity) = &min_image_transfer_granularity {
-                        for i in 0..3 {
-                            if image_offset[i] % src_granularity[i] != 0 {
-                                return Err(Box::new(ValidationError {
-                                    context: "copy_image_info".into(),
-                                    problem: format!(
-                                        "the `min_image_transfer_granularity` property of the \
-                                        queue family of the command buffer is not `[0; 3]`, but \
-                                        `regions[{}].image_offset[{1}]` is not a multiple of \
-                                        `min_image_transfer_granularity[{1}]` texel blocks",
-                                        region_index, i,
-                                    )
-                                    .into(),
-                                    vuids: &["VUID-vkCmdCopyImageToBuffer2-imageOffset-07747"],
-                                    ..Default::default()
-                                }));
-                            }
-
-                            if image_offset[i] + image_extent[i] != image_subresource_extent[i]
-                                && image_extent[i] % src_granularity[i] != 0
-                            {
-                                return Err(Box::new(ValidationError {
-                                    context: "copy_image_info".into(),
-                                    problem: format!(
-                                        "the `min_image_transfer_granularity` property of the \
-                                        queue family of the command buffer is not `[0; 3]`, and \
-                                        `regions[{0}].image_offset[{1}] + \
-                                        regions[{0}].image_extent[{1}]` \
-                                        is not equal to coordinate {1} of the extent of the \
-                                        subresource of `src_image` selected by \
-                                        `regions[{0}].image_subresource`, but \
-                                        `regions[{}].image_extent[{1}]` is not a multiple of \
-                                        `min_image_transfer_granularity[{1}]` texel blocks",
-                                        region_index, i,
-                                    )
-                                    .into(),
-                                    vuids: &["VUID-vkCmdCopyImageToBuffer2-imageOffset-07747"],
-                                    ..Default::default()
-                                }));
-                            }
-                        }
-                    } else {
-                        for i in 0..3 {
-                            if image_offset[i] != 0 {
-                                return Err(Box::new(ValidationError {
-                                    context: "copy_image_info".into(),
-                                    problem: format!(
-                                        "the `min_image_transfer_granularity` property of the \
-                                        queue family of the command buffer is `[0; 3]`, but \
-                                        `regions[{}].image_offset[{}]` is not 0",
-                                        region_index, i,
-                                    )
-                                    .into(),
-                                    vuids: &["VUID-vkCmdCopyImageToBuffer2-imageOffset-07747"],
-                                    ..Default::default()
-                                }));
-                            }
-
-                            if image_offset[i] + image_extent[i] != image_subresource_extent[i] {
-                                return Err(Box::new(ValidationError {
-                                    context: "copy_image_info".into(),
-                                    problem: format!(
-                                        "the `min_image_transfer_granularity` property of the \
-                                        queue family of the command buffer is `[0; 3]`, but \
-                                        `regions[{0}].image_offset[{1}] + \
-                                        regions[{0}].image_extent[{1}]` \
-                                        is not equal to coordinate {1} of the extent of the \
-                                        subresource of `src_image` selected by \
-                                        `regions[{0}].image_subresource`",
-                                        region_index, i,
-                                    )
-                                    .into(),
-                                    vuids: &["VUID-vkCmdCopyImageToBuffer2-imageOffset-07747"],
-                                    ..Default::default()
-                                }));
-                            }
-                        }
-                    }
-                }
             }
         }
 
@@ -2124,6 +1760,31 @@ impl RawRecordingCommandBuffer {
     }
 }
 
+/// Records buffer fills and copies for a [`RecordingCommandBuffer`] in a different way, when they
+/// can't be recorded as transfer commands.
+///
+/// A fallback is set with [`RecordingCommandBuffer::set_transfer_fallback`]. The `vulkano-util`
+/// crate provides an implementation that records the commands as compute shader dispatches.
+pub trait TransferFallback: Send + Sync {
+    /// Records commands into `builder` that fill `dst_buffer` with repeated copies of `data`, in
+    /// the same way as [`RecordingCommandBuffer::fill_buffer`].
+    fn fill_buffer(
+        &self,
+        builder: &mut RecordingCommandBuffer,
+        dst_buffer: Subbuffer<[u8]>,
+        data: u32,
+    ) -> Result<(), Box<ValidationError>>;
+
+    /// Records commands into `builder` that copy `src_buffer` to `dst_buffer`, in the same way as
+    /// [`RecordingCommandBuffer::copy_buffer`]. The two buffers have the same size.
+    fn copy_buffer(
+        &self,
+        builder: &mut RecordingCommandBuffer,
+        src_buffer: Subbuffer<[u8]>,
+        dst_buffer: Subbuffer<[u8]>,
+    ) -> Result<(), Box<ValidationError>>;
+}
+
 /// Parameters to copy data from a buffer to another buffer.
 ///
 /// The fields of `regions` represent bytes.
@@ -2259,7 +1920,9 @@ impl CopyBufferInfo {
                     src_buffer.offset() + src_offset..src_buffer.offset() + src_offset + size;
 
                 for (dst_region_index, dst_region) in regions.iter().enumerate() {
-                    let &BufferCopy { dst_offset, .. } = dst_region;
+                    let &BufferCopy {
+                        dst_offset, size, ..
+                    } = dst_region;
 
                     let dst_range =
                         dst_buffer.offset() + dst_offset..dst_buffer.offset() + dst_offset + size;
@@ -2269,7 +1932,9 @@ impl CopyBufferInfo {
                         continue;
                     }
 
-                    overlap_indices = Some((src_region_index, dst_region_index));
+                    if overlap_indices.is_none() {
+                        overlap_indices = Some((src_region_index, dst_region_index));
+                    }
                 }
             }
         }
@@ -2470,7 +2135,13 @@ impl CopyImageInfo {
             },
             extent: {
                 let src_extent = src_image.extent();
-                let dst_extent = dst_image.extent();
+                // `extent` is in texels of `src_image`, so the extent of `dst_image` must be
+                // converted if the block extents of the formats differ.
+                let dst_extent = size_compatible_dst_extent(
+                    dst_image.extent(),
+                    dst_image.format().block_extent(),
+                    src_image.format().block_extent(),
+                );
 
                 [
                     src_extent[0].min(dst_extent[0]),
@@ -3202,10 +2873,35 @@ impl CopyImageInfo {
                 }));
             }
 
-            if dst_offset[0] + extent[0] > dst_subresource_extent[0] {
+            let dst_subresource_format_block_extent = dst_subresource_format.block_extent();
+
+            // When copying between formats with different block extents, such as between a
+            // compressed and an uncompressed format, `extent` is in texels of `src_image`, and
+            // must be converted to texels of `dst_image`. The partial blocks at the edge of a
+            // compressed `dst_image` can then be written to as whole blocks.
+            let (dst_extent, dst_subresource_extent) =
+                if src_subresource_format_block_extent == dst_subresource_format_block_extent {
+                    (extent, dst_subresource_extent)
+                } else {
+                    (
+                        size_compatible_dst_extent(
+                            extent,
+                            src_subresource_format_block_extent,
+                            dst_subresource_format_block_extent,
+                        ),
+                        [0, 1, 2].map(|i| {
+                            (dst_subresource_extent[i] + dst_subresource_format_block_extent[i] - 1)
+                                / dst_subresource_format_block_extent[i]
+                                * dst_subresource_format_block_extent[i]
+                        }),
+                    )
+                };
+
+            if dst_offset[0] + dst_extent[0] > dst_subresource_extent[0] {
                 return Err(Box::new(ValidationError {
                     problem: format!(
-                        "`regions[{0}].dst_offset[0] + regions[{0}].extent[0]` is greater \
+                        "`regions[{0}].dst_offset[0]` plus coordinate 0 of `regions[{0}].extent` \
+                        in texels of `dst_image` is greater \
                         than coordinate 0 of the extent of the subresource of `dst_image` \
                         selected by `regions[{0}].dst_subresource`",
                         region_index,
@@ -3216,10 +2912,11 @@ impl CopyImageInfo {
                 }));
             }
 
-            if dst_offset[1] + extent[1] > dst_subresource_extent[1] {
+            if dst_offset[1] + dst_extent[1] > dst_subresource_extent[1] {
                 return Err(Box::new(ValidationError {
                     problem: format!(
-                        "`regions[{0}].dst_offset[1] + regions[{0}].extent[1]` is greater \
+                        "`regions[{0}].dst_offset[1]` plus coordinate 1 of `regions[{0}].extent` \
+                        in texels of `dst_image` is greater \
                         than coordinate 1 of the extent of the subresource of `dst_image` \
                         selected by `regions[{0}].dst_subresource`",
                         region_index,
@@ -3230,10 +2927,11 @@ impl CopyImageInfo {
                 }));
             }
 
-            if dst_offset[2] + extent[2] > dst_subresource_extent[2] {
+            if dst_offset[2] + dst_extent[2] > dst_subresource_extent[2] {
                 return Err(Box::new(ValidationError {
                     problem: format!(
-                        "`regions[{0}].dst_offset[2] + regions[{0}].extent[2]` is greater \
+                        "`regions[{0}].dst_offset[2]` plus coordinate 2 of `regions[{0}].extent` \
+                        in texels of `dst_image` is greater \
                         than coordinate 2 of the extent of the subresource of `dst_image` \
                         selected by `regions[{0}].dst_subresource`",
                         region_index,
@@ -3244,8 +2942,6 @@ impl CopyImageInfo {
                 }));
             }
 
-            let dst_subresource_format_block_extent = dst_subresource_format.block_extent();
-
             if dst_offset[0] % dst_subresource_format_block_extent[0] != 0 {
                 return Err(Box::new(ValidationError {
                     problem: format!(
@@ -3288,12 +2984,13 @@ impl CopyImageInfo {
                 }));
             }
 
-            if dst_offset[0] + extent[0] != dst_subresource_extent[0]
-                && (dst_offset[0] + extent[0]) % dst_subresource_format_block_extent[0] != 0
+            if dst_offset[0] + dst_extent[0] != dst_subresource_extent[0]
+                && (dst_offset[0] + dst_extent[0]) % dst_subresource_format_block_extent[0] != 0
             {
                 return Err(Box::new(ValidationError {
                     problem: format!(
-                        "`regions[{0}].dst_offset[0] + regions[{0}].extent[0]` is not \
+                        "`regions[{0}].dst_offset[0]` plus coordinate 0 of `regions[{0}].extent` \
+                        in texels of `dst_image` is not \
                         equal to the extent of the subresource of `dst_image` \
                         selected by `regions[{0}].dst_subresource`, but \
                         it is also not a multiple of coordinate 0 of the block extent of the \
@@ -3306,12 +3003,13 @@ impl CopyImageInfo {
                 }));
             }
 
-            if dst_offset[1] + extent[1] != dst_subresource_extent[1]
-                && (dst_offset[1] + extent[1]) % dst_subresource_format_block_extent[1] != 0
+            if dst_offset[1] + dst_extent[1] != dst_subresource_extent[1]
+                && (dst_offset[1] + dst_extent[1]) % dst_subresource_format_block_extent[1] != 0
             {
                 return Err(Box::new(ValidationError {
                     problem: format!(
-                        "`regions[{0}].dst_offset[1] + regions[{0}].extent[1]` is not \
+                        "`regions[{0}].dst_offset[1]` plus coordinate 1 of `regions[{0}].extent` \
+                        in texels of `dst_image` is not \
                         equal to the extent of the subresource of `dst_image` \
                         selected by `regions[{0}].dst_subresource`, but \
                         it is also not a multiple of coordinate 1 of the block extent of the \
@@ -3324,12 +3022,13 @@ impl CopyImageInfo {
                 }));
             }
 
-            if dst_offset[2] + extent[2] != dst_subresource_extent[2]
-                && (dst_offset[2] + extent[2]) % dst_subresource_format_block_extent[2] != 0
+            if dst_offset[2] + dst_extent[2] != dst_subresource_extent[2]
+                && (dst_offset[2] + dst_extent[2]) % dst_subresource_format_block_extent[2] != 0
             {
                 return Err(Box::new(ValidationError {
                     problem: format!(
-                        "`regions[{0}].dst_offset[2] + regions[{0}].extent[2]` is not \
+                        "`regions[{0}].dst_offset[2]` plus coordinate 2 of `regions[{0}].extent` \
+                        in texels of `dst_image` is not \
                         equal to the extent of the subresource of `dst_image` \
                         selected by `regions[{0}].dst_subresource`, but \
                         it is also not a multiple of coordinate 2 of the block extent of the \
@@ -3570,52 +3269,38 @@ impl CopyImageInfo {
             // VUID-VkCopyImageInfo2-pRegions-00124
             if is_same_image {
                 let src_region_index = region_index;
-                let src_subresource_axes = [
-                    src_subresource.mip_level..src_subresource.mip_level + 1,
-                    src_subresource.array_layers.start..src_subresource.array_layers.end,
-                ];
-                let src_extent_axes = [
-                    src_offset[0]..src_offset[0] + extent[0],
-                    src_offset[1]..src_offset[1] + extent[1],
-                    src_offset[2]..src_offset[2] + extent[2],
-                ];
+                // Compare whole blocks, so that regions of a compressed format that share a
+                // block are also caught.
+                let block_axes = |offset: [u32; 3], extent: [u32; 3]| {
+                    [0, 1, 2].map(|i| {
+                        let block_extent = src_subresource_format_block_extent[i];
+                        offset[i] / block_extent
+                            ..(offset[i] + extent[i] + block_extent - 1) / block_extent
+                    })
+                };
+                let src_extent_axes = block_axes(src_offset, extent);
 
                 for (dst_region_index, dst_region) in regions.iter().enumerate() {
                     let &ImageCopy {
                         ref dst_subresource,
                         dst_offset,
+                        extent,
                         ..
                     } = dst_region;
 
-                    // For a single-plane image, the aspects must always be identical anyway
-                    if src_image_format_aspects.intersects(ImageAspects::PLANE_0)
-                        && src_subresource.aspects != dst_subresource.aspects
-                    {
-                        continue;
-                    }
-
-                    let dst_subresource_axes = [
-                        dst_subresource.mip_level..dst_subresource.mip_level + 1,
-                        src_subresource.array_layers.start..src_subresource.array_layers.end,
-                    ];
-
-                    if src_subresource_axes.iter().zip(dst_subresource_axes).any(
-                        |(src_range, dst_range)| {
-                            src_range.start >= dst_range.end || dst_range.start >= src_range.end
-                        },
-                    ) {
+                    // Different aspects, such as the depth and stencil aspects, or different
+                    // planes, never overlap.
+                    if !src_subresource.overlaps(dst_subresource) {
                         continue;
                     }
 
                     // If the subresource axes all overlap, then the source and destination must
                     // have the same layout.
-                    overlap_subresource_indices = Some((src_region_index, dst_region_index));
+                    if overlap_subresource_indices.is_none() {
+                        overlap_subresource_indices = Some((src_region_index, dst_region_index));
+                    }
 
-                    let dst_extent_axes = [
-                        dst_offset[0]..dst_offset[0] + extent[0],
-                        dst_offset[1]..dst_offset[1] + extent[1],
-                        dst_offset[2]..dst_offset[2] + extent[2],
-                    ];
+                    let dst_extent_axes = block_axes(dst_offset, extent);
 
                     // There is only overlap if all of the axes overlap.
                     if src_extent_axes
@@ -3628,7 +3313,9 @@ impl CopyImageInfo {
                         continue;
                     }
 
-                    overlap_extent_indices = Some((src_region_index, dst_region_index));
+                    if overlap_extent_indices.is_none() {
+                        overlap_extent_indices = Some((src_region_index, dst_region_index));
+                    }
                 }
             }
         }
@@ -3648,22 +3335,293 @@ impl CopyImageInfo {
             }));
         }
 
-        if let Some((src_region_index, dst_region_index)) = overlap_subresource_indices {
-            if src_image_layout != dst_image_layout {
-                return Err(Box::new(ValidationError {
-                    problem: format!(
-                        "`src_image` is equal to `dst_image`, and `regions[{0}].src_subresource` \
-                        overlaps with `regions[{1}].dst_subresource`, but \
-                        `src_image_layout` does not equal `dst_image_layout`",
-                        src_region_index, dst_region_index
-                    )
-                    .into(),
-                    vuids: &[
-                        "VUID-VkCopyImageInfo2-srcImageLayout-00128",
-                        "VUID-VkCopyImageInfo2-dstImageLayout-00133",
-                    ],
-                    ..Default::default()
-                }));
+        if let Some((src_region_index, dst_region_index)) = overlap_subresource_indices {
+            if src_image_layout != dst_image_layout {
+                return Err(Box::new(ValidationError {
+                    problem: format!(
+                        "`src_image` is equal to `dst_image`, and `regions[{0}].src_subresource` \
+                        overlaps with `regions[{1}].dst_subresource`, but \
+                        `src_image_layout` does not equal `dst_image_layout`",
+                        src_region_index, dst_region_index
+                    )
+                    .into(),
+                    vuids: &[
+                        "VUID-VkCopyImageInfo2-srcImageLayout-00128",
+                        "VUID-VkCopyImageInfo2-dstImageLayout-00133",
+                    ],
+                    ..Default::default()
+                }));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the regions satisfy the `min_image_transfer_granularity` of the queue family
+    /// that the copy is recorded for.
+    pub(crate) fn validate_min_image_transfer_granularity(
+        &self,
+        queue_family_granularity: [u32; 3],
+    ) -> Result<(), Box<ValidationError>> {
+        let &Self {
+            ref src_image,
+            src_image_layout: _,
+            ref dst_image,
+            dst_image_layout: _,
+            ref regions,
+            _ne: _,
+        } = self;
+
+        let src_image_format = src_image.format();
+        let src_image_format_subsampled_extent = src_image_format
+            .ycbcr_chroma_sampling()
+            .map_or(src_image.extent(), |s| {
+                s.subsampled_extent(src_image.extent())
+            });
+
+        let dst_image_format = dst_image.format();
+        let dst_image_format_subsampled_extent = dst_image_format
+            .ycbcr_chroma_sampling()
+            .map_or(dst_image.extent(), |s| {
+                s.subsampled_extent(dst_image.extent())
+            });
+
+        let min_image_transfer_granularity =
+            // `[1; 3]` means the granularity is 1x1x1 texel, so we can ignore it.
+            // Only check this if there are values greater than 1.
+            (queue_family_granularity != [1; 3]).then(|| {
+                // `[0; 3]` means only the whole subresource can be copied.
+                (queue_family_granularity != [0; 3]).then(|| {
+                    // Spec:
+                    // "The value returned in minImageTransferGranularity has a unit of
+                    // compressed texel blocks for images having a block-compressed format,
+                    // and a unit of texels otherwise.""
+
+                    let src_granularity = if src_image_format.compression().is_some() {
+                        let block_extent = src_image_format.block_extent();
+
+                        [
+                            queue_family_granularity[0] * block_extent[0],
+                            queue_family_granularity[1] * block_extent[1],
+                            queue_family_granularity[2] * block_extent[2],
+                        ]
+                    } else {
+                        queue_family_granularity
+                    };
+
+                    let dst_granularity = if dst_image_format.compression().is_some() {
+                        let block_extent = dst_image_format.block_extent();
+
+                        [
+                            queue_family_granularity[0] * block_extent[0],
+                            queue_family_granularity[1] * block_extent[1],
+                            queue_family_granularity[2] * block_extent[2],
+                        ]
+                    } else {
+                        queue_family_granularity
+                    };
+
+                    (src_granularity, dst_granularity)
+                })
+            });
+
+        if let Some(min_image_transfer_granularity) = &min_image_transfer_granularity {
+            for (region_index, region) in regions.iter().enumerate() {
+                let &ImageCopy {
+                    ref src_subresource,
+                    src_offset,
+                    ref dst_subresource,
+                    dst_offset,
+                    extent,
+                    _ne: _,
+                } = region;
+
+                let mut src_subresource_extent =
+                    mip_level_extent(src_image.extent(), src_subresource.mip_level).unwrap();
+
+                if matches!(
+                    src_subresource.aspects,
+                    ImageAspects::PLANE_1 | ImageAspects::PLANE_2
+                ) {
+                    src_subresource_extent = src_image_format_subsampled_extent;
+                }
+
+                let mut dst_subresource_extent =
+                    mip_level_extent(dst_image.extent(), dst_subresource.mip_level).unwrap();
+
+                if matches!(
+                    dst_subresource.aspects,
+                    ImageAspects::PLANE_1 | ImageAspects::PLANE_2
+                ) {
+                    dst_subresource_extent = dst_image_format_subsampled_extent;
+                }
+
+                // `extent` is in texels of `src_image`.
+                let dst_extent = size_compatible_dst_extent(
+                    extent,
+                    src_image_format.block_extent(),
+                    dst_image_format.block_extent(),
+                );
+
+                if let Some((src_granularity, dst_granularity)) = &min_image_transfer_granularity {
+                    /*
+                       Check src
+                    */
+
+                    for i in 0..3 {
+                        if src_offset[i] % src_granularity[i] != 0 {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "the `min_image_transfer_granularity` property of the \
+                                    queue family of the command buffer is not `[0; 3]`, but \
+                                    `regions[{}].src_offset[{1}]` is not a multiple of \
+                                    `min_image_transfer_granularity[{1}]` texel blocks",
+                                    region_index, i,
+                                )
+                                .into(),
+                                vuids: &["VUID-VkCopyImageInfo2-srcOffset-01783"],
+                                ..Default::default()
+                            }));
+                        }
+
+                        if src_offset[i] + extent[i] != src_subresource_extent[i]
+                            && extent[i] % src_granularity[i] != 0
+                        {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "the `min_image_transfer_granularity` property of the \
+                                    queue family of the command buffer is not `[0; 3]`, and \
+                                    `regions[{0}].src_offset[{1}] + regions[{0}].extent[{1}]` \
+                                    is not equal to coordinate {1} of the extent of the \
+                                    subresource of `src_image` selected by \
+                                    `regions[{0}].src_subresource`, but \
+                                    `regions[{}].extent[{1}]` is not a multiple of \
+                                    `min_image_transfer_granularity[{1}]` texel blocks",
+                                    region_index, i,
+                                )
+                                .into(),
+                                vuids: &["VUID-VkCopyImageInfo2-srcOffset-01783"],
+                                ..Default::default()
+                            }));
+                        }
+                    }
+
+                    /*
+                       Check dst
+                    */
+
+                    for i in 0..3 {
+                        if dst_offset[i] % dst_granularity[i] != 0 {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "the `min_image_transfer_granularity` property of the \
+                                    queue family of the command buffer is not `[0; 3]`, but \
+                                    `regions[{}].dst_offset[{1}]` is not a multiple of \
+                                    `min_image_transfer_granularity[{1}]` texel blocks",
+                                    region_index, i,
+                                )
+                                .into(),
+                                vuids: &["VUID-VkCopyImageInfo2-dstOffset-01784"],
+                                ..Default::default()
+                            }));
+                        }
+
+                        if dst_offset[i] + dst_extent[i] != dst_subresource_extent[i]
+                            && dst_extent[i] % dst_granularity[i] != 0
+                        {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "the `min_image_transfer_granularity` property of the \
+                                    queue family of the command buffer is not `[0; 3]`, and \
+                                    `regions[{0}].dst_offset[{1}] + regions[{0}].extent[{1}]` \
+                                    is not equal to coordinate {1} of the extent of the \
+                                    subresource of `dst_image` selected by \
+                                    `regions[{0}].dst_subresource`, but \
+                                    `regions[{}].extent[{1}]` is not a multiple of \
+                                    `min_image_transfer_granularity[{1}]` texel blocks",
+                                    region_index, i,
+                                )
+                                .into(),
+                                vuids: &["VUID-VkCopyImageInfo2-dstOffset-01784"],
+                                ..Default::default()
+                            }));
+                        }
+                    }
+                } else {
+                    /*
+                       Check src
+                    */
+
+                    for i in 0..3 {
+                        if src_offset[i] != 0 {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "the `min_image_transfer_granularity` property of the \
+                                    queue family of the command buffer is `[0; 3]`, but \
+                                    `regions[{}].src_offset[{}]` is not 0",
+                                    region_index, i,
+                                )
+                                .into(),
+                                vuids: &["VUID-VkCopyImageInfo2-srcOffset-01783"],
+                                ..Default::default()
+                            }));
+                        }
+
+                        if src_offset[i] + extent[i] != src_subresource_extent[i] {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "the `min_image_transfer_granularity` property of the \
+                                    queue family of the command buffer is `[0; 3]`, but \
+                                    `regions[{0}].src_offset[{1}] + regions[{0}].extent[{1}]` \
+                                    is not equal to coordinate {1} of the extent of the \
+                                    subresource of `src_image` selected by \
+                                    `regions[{0}].src_subresource`",
+                                    region_index, i,
+                                )
+                                .into(),
+                                vuids: &["VUID-VkCopyImageInfo2-srcOffset-01783"],
+                                ..Default::default()
+                            }));
+                        }
+                    }
+
+                    /*
+                       Check dst
+                    */
+
+                    for i in 0..3 {
+                        if dst_offset[i] != 0 {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "the `min_image_transfer_granularity` property of the \
+                                    queue family of the command buffer is `[0; 3]`, but \
+                                    `regions[{}].dst_offset[{}]` is not 0",
+                                    region_index, i,
+                                )
+                                .into(),
+                                vuids: &["VUID-VkCopyImageInfo2-dstOffset-01784"],
+                                ..Default::default()
+                            }));
+                        }
+
+                        if dst_offset[i] + dst_extent[i] != dst_subresource_extent[i] {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "the `min_image_transfer_granularity` property of the \
+                                    queue family of the command buffer is `[0; 3]`, but \
+                                    `regions[{0}].dst_offset[{1}] + regions[{0}].extent[{1}]` \
+                                    is not equal to coordinate {1} of the extent of the \
+                                    subresource of `dst_image` selected by \
+                                    `regions[{0}].dst_subresource`",
+                                    region_index, i,
+                                )
+                                .into(),
+                                vuids: &["VUID-VkCopyImageInfo2-dstOffset-01784"],
+                                ..Default::default()
+                            }));
+                        }
+                    }
+                }
             }
         }
 
@@ -3671,6 +3629,22 @@ impl CopyImageInfo {
     }
 }
 
+/// Converts `extent`, in texels of a format with block extent `src_block_extent`, to texels of a
+/// size-compatible format with block extent `dst_block_extent`. This is needed when copying
+/// between a compressed and an uncompressed image.
+fn size_compatible_dst_extent(
+    extent: [u32; 3],
+    src_block_extent: [u32; 3],
+    dst_block_extent: [u32; 3],
+) -> [u32; 3] {
+    if src_block_extent == dst_block_extent {
+        return extent;
+    }
+
+    [0, 1, 2]
+        .map(|i| (extent[i] + src_block_extent[i] - 1) / src_block_extent[i] * dst_block_extent[i])
+}
+
 /// A region of data to copy between images.
 #[derive(Clone, Debug)]
 pub struct ImageCopy {
@@ -4406,6 +4380,154 @@ impl CopyBufferToImageInfo {
 
         Ok(())
     }
+
+    /// Checks that the regions satisfy the `min_image_transfer_granularity` of the queue family
+    /// that the copy is recorded for.
+    pub(crate) fn validate_min_image_transfer_granularity(
+        &self,
+        queue_family_granularity: [u32; 3],
+    ) -> Result<(), Box<ValidationError>> {
+        let &Self {
+            src_buffer: _,
+            ref dst_image,
+            dst_image_layout: _,
+            ref regions,
+            _ne,
+        } = self;
+
+        let dst_image_format = dst_image.format();
+        let dst_image_format_subsampled_extent = dst_image_format
+            .ycbcr_chroma_sampling()
+            .map_or(dst_image.extent(), |s| {
+                s.subsampled_extent(dst_image.extent())
+            });
+
+        let min_image_transfer_granularity =
+            // `[1; 3]` means the granularity is 1x1x1 texel, so we can ignore it.
+            // Only check this if there are values greater than 1.
+            (queue_family_granularity != [1; 3]).then(|| {
+                // `[0; 3]` means only the whole subresource can be copied.
+                (queue_family_granularity != [0; 3]).then(|| {
+                    // Spec:
+                    // "The value returned in minImageTransferGranularity has a unit of
+                    // compressed texel blocks for images having a block-compressed format,
+                    // and a unit of texels otherwise.""
+
+                    if dst_image_format.compression().is_some() {
+                        let block_extent = dst_image_format.block_extent();
+
+                        [
+                            queue_family_granularity[0] * block_extent[0],
+                            queue_family_granularity[1] * block_extent[1],
+                            queue_family_granularity[2] * block_extent[2],
+                        ]
+                    } else {
+                        queue_family_granularity
+                    }
+                })
+            });
+
+        if let Some(min_image_transfer_granularity) = &min_image_transfer_granularity {
+            for (region_index, region) in regions.iter().enumerate() {
+                let &BufferImageCopy {
+                    buffer_offset: _,
+                    buffer_row_length: _,
+                    buffer_image_height: _,
+                    ref image_subresource,
+                    image_offset,
+                    image_extent,
+                    _ne,
+                } = region;
+
+                let mut image_subresource_extent =
+                    mip_level_extent(dst_image.extent(), image_subresource.mip_level).unwrap();
+
+                if matches!(
+                    image_subresource.aspects,
+                    ImageAspects::PLANE_1 | ImageAspects::PLANE_2
+                ) {
+                    image_subresource_extent = dst_image_format_subsampled_extent;
+                }
+
+                if let Some(dst_granularity) = &min_image_transfer_granularity {
+                    for i in 0..3 {
+                        if image_offset[i] % dst_granularity[i] != 0 {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "the `min_image_transfer_granularity` property of the \
+                                    queue family of the command buffer is not `[0; 3]`, but \
+                                    `regions[{}].image_offset[{1}]` is not a multiple of \
+                                    `min_image_transfer_granularity[{1}]` texel blocks",
+                                    region_index, i,
+                                )
+                                .into(),
+                                vuids: &["VUID-vkCmdCopyBufferToImage2-imageOffset-07738"],
+                                ..Default::default()
+                            }));
+                        }
+
+                        if image_offset[i] + image_extent[i] != image_subresource_extent[i]
+                            && image_extent[i] % dst_granularity[i] != 0
+                        {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "the `min_image_transfer_granularity` property of the \
+                                    queue family of the command buffer is not `[0; 3]`, and \
+                                    `regions[{0}].image_offset[{1}] + \
+                                    regions[{0}].image_extent[{1}]` \
+                                    is not equal to coordinate {1} of the extent of the \
+                                    subresource of `dst_image` selected by \
+                                    `regions[{0}].image_subresource`, but \
+                                    `regions[{}].image_extent[{1}]` is not a multiple of \
+                                    `min_image_transfer_granularity[{1}]` texel blocks",
+                                    region_index, i,
+                                )
+                                .into(),
+                                vuids: &["VUID-vkCmdCopyBufferToImage2-imageOffset-07738"],
+                                ..Default::default()
+                            }));
+                        }
+                    }
+                } else {
+                    for i in 0..3 {
+                        if image_offset[i] != 0 {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "the `min_image_transfer_granularity` property of the \
+                                    queue family of the command buffer is `[0; 3]`, but \
+                                    `regions[{}].image_offset[{}]` is not 0",
+                                    region_index, i,
+                                )
+                                .into(),
+                                vuids: &["VUID-vkCmdCopyBufferToImage2-imageOffset-07738"],
+                                ..Default::default()
+                            }));
+                        }
+
+                        if image_offset[i] + image_extent[i] != image_subresource_extent[i] {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "the `min_image_transfer_granularity` property of the \
+                                    queue family of the command buffer is `[0; 3]`, but \
+                                    `regions[{0}].image_offset[{1}] + \
+                                    regions[{0}].image_extent[{1}]` \
+                                    is not equal to coordinate {1} of the extent of the \
+                                    subresource of `dst_image` selected by \
+                                    `regions[{0}].image_subresource`",
+                                    region_index, i,
+                                )
+                                .into(),
+                                vuids: &["VUID-vkCmdCopyBufferToImage2-imageOffset-07738"],
+                                ..Default::default()
+                            }));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Parameters to copy data from an image to a buffer.
@@ -5012,6 +5134,154 @@ impl CopyImageToBufferInfo {
 
         Ok(())
     }
+
+    /// Checks that the regions satisfy the `min_image_transfer_granularity` of the queue family
+    /// that the copy is recorded for.
+    pub(crate) fn validate_min_image_transfer_granularity(
+        &self,
+        queue_family_granularity: [u32; 3],
+    ) -> Result<(), Box<ValidationError>> {
+        let &Self {
+            ref src_image,
+            src_image_layout: _,
+            dst_buffer: _,
+            ref regions,
+            _ne,
+        } = self;
+
+        let src_image_format = src_image.format();
+        let src_image_format_subsampled_extent = src_image_format
+            .ycbcr_chroma_sampling()
+            .map_or(src_image.extent(), |s| {
+                s.subsampled_extent(src_image.extent())
+            });
+
+        let min_image_transfer_granularity =
+            // `[1; 3]` means the granularity is 1x1x1 texel, so we can ignore it.
+            // Only check this if there are values greater than 1.
+            (queue_family_granularity != [1; 3]).then(|| {
+                // `[0; 3]` means only the whole subresource can be copied.
+                (queue_family_granularity != [0; 3]).then(|| {
+                    // Spec:
+                    // "The value returned in minImageTransferGranularity has a unit of
+                    // compressed texel blocks for images having a block-compressed format,
+                    // and a unit of texels otherwise.""
+
+                    if src_image_format.compression().is_some() {
+                        let block_extent = src_image_format.block_extent();
+
+                        [
+                            queue_family_granularity[0] * block_extent[0],
+                            queue_family_granularity[1] * block_extent[1],
+                            queue_family_granularity[2] * block_extent[2],
+                        ]
+                    } else {
+                        queue_family_granularity
+                    }
+                })
+            });
+
+        if let Some(min_image_transfer_granularity) = &min_image_transfer_granularity {
+            for (region_index, region) in regions.iter().enumerate() {
+                let &BufferImageCopy {
+                    buffer_offset: _,
+                    buffer_row_length: _,
+                    buffer_image_height: _,
+                    ref image_subresource,
+                    image_offset,
+                    image_extent,
+                    _ne,
+                } = region;
+
+                let mut image_subresource_extent =
+                    mip_level_extent(src_image.extent(), image_subresource.mip_level).unwrap();
+
+                if matches!(
+                    image_subresource.aspects,
+                    ImageAspects::PLANE_1 | ImageAspects::PLANE_2
+                ) {
+                    image_subresource_extent = src_image_format_subsampled_extent;
+                }
+
+                if let Some(src_granularity) = &min_image_transfer_granularity {
+                    for i in 0..3 {
+                        if image_offset[i] % src_granularity[i] != 0 {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "the `min_image_transfer_granularity` property of the \
+                                    queue family of the command buffer is not `[0; 3]`, but \
+                                    `regions[{}].image_offset[{1}]` is not a multiple of \
+                                    `min_image_transfer_granularity[{1}]` texel blocks",
+                                    region_index, i,
+                                )
+                                .into(),
+                                vuids: &["VUID-vkCmdCopyImageToBuffer2-imageOffset-07747"],
+                                ..Default::default()
+                            }));
+                        }
+
+                        if image_offset[i] + image_extent[i] != image_subresource_extent[i]
+                            && image_extent[i] % src_granularity[i] != 0
+                        {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "the `min_image_transfer_granularity` property of the \
+                                    queue family of the command buffer is not `[0; 3]`, and \
+                                    `regions[{0}].image_offset[{1}] + \
+                                    regions[{0}].image_extent[{1}]` \
+                                    is not equal to coordinate {1} of the extent of the \
+                                    subresource of `src_image` selected by \
+                                    `regions[{0}].image_subresource`, but \
+                                    `regions[{}].image_extent[{1}]` is not a multiple of \
+                                    `min_image_transfer_granularity[{1}]` texel blocks",
+                                    region_index, i,
+                                )
+                                .into(),
+                                vuids: &["VUID-vkCmdCopyImageToBuffer2-imageOffset-07747"],
+                                ..Default::default()
+                            }));
+                        }
+                    }
+                } else {
+                    for i in 0..3 {
+                        if image_offset[i] != 0 {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "the `min_image_transfer_granularity` property of the \
+                                    queue family of the command buffer is `[0; 3]`, but \
+                                    `regions[{}].image_offset[{}]` is not 0",
+                                    region_index, i,
+                                )
+                                .into(),
+                                vuids: &["VUID-vkCmdCopyImageToBuffer2-imageOffset-07747"],
+                                ..Default::default()
+                            }));
+                        }
+
+                        if image_offset[i] + image_extent[i] != image_subresource_extent[i] {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "the `min_image_transfer_granularity` property of the \
+                                    queue family of the command buffer is `[0; 3]`, but \
+                                    `regions[{0}].image_offset[{1}] + \
+                                    regions[{0}].image_extent[{1}]` \
+                                    is not equal to coordinate {1} of the extent of the \
+                                    subresource of `src_image` selected by \
+                                    `regions[{0}].image_subresource`",
+                                    region_index, i,
+                                )
+                                .into(),
+                                vuids: &["VUID-vkCmdCopyImageToBuffer2-imageOffset-07747"],
+                                ..Default::default()
+                            }));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// A region of data to copy between a buffer and an image.
@@ -5928,7 +6198,7 @@ impl BlitImageInfo {
 
                     let dst_subresource_axes = [
                         dst_subresource.mip_level..dst_subresource.mip_level + 1,
-                        src_subresource.array_layers.start..src_subresource.array_layers.end,
+                        dst_subresource.array_layers.clone(),
                     ];
 
                     if src_subresource_axes.iter().zip(dst_subresource_axes).any(
@@ -6793,7 +7063,11 @@ impl ImageResolve {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::format::Format;
+    use crate::{
+        format::Format,
+        image::{ImageCreateInfo, ImageUsage},
+        memory::allocator::{AllocationCreateInfo, StandardMemoryAllocator},
+    };
 
     /// Computes the minimum required len in elements for buffer with image data in specified
     /// format of specified size.
@@ -6849,4 +7123,273 @@ mod tests {
             29584
         );
     }
+
+    #[test]
+    fn copy_image_compressed_uncompressed() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+        let create_image = |format, extent| {
+            Image::new(
+                memory_allocator.clone(),
+                ImageCreateInfo {
+                    format,
+                    extent,
+                    usage: ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+            )
+            .ok()
+        };
+
+        for (compressed_format, uncompressed_format) in [
+            (Format::BC1_RGBA_UNORM_BLOCK, Format::R32G32_UINT),
+            (Format::BC7_UNORM_BLOCK, Format::R32G32B32A32_UINT),
+        ] {
+            let (Some(compressed), Some(uncompressed)) = (
+                create_image(compressed_format, [64, 64, 1]),
+                create_image(uncompressed_format, [16, 16, 1]),
+            ) else {
+                continue;
+            };
+
+            // Each texel of the uncompressed image corresponds to a block of the compressed one.
+            let copy_image_info = CopyImageInfo::images(compressed.clone(), uncompressed.clone());
+            assert_eq!(copy_image_info.regions[0].extent, [64, 64, 1]);
+            assert!(copy_image_info.validate(&device).is_ok());
+
+            let copy_image_info = CopyImageInfo::images(uncompressed.clone(), compressed.clone());
+            assert_eq!(copy_image_info.regions[0].extent, [16, 16, 1]);
+            assert!(copy_image_info.validate(&device).is_ok());
+
+            let mut copy_image_info = CopyImageInfo::images(uncompressed, compressed);
+            copy_image_info.regions[0].dst_offset = [4, 0, 0];
+            assert!(copy_image_info.validate(&device).is_err());
+        }
+    }
+
+    #[test]
+    fn copy_image_same_image_overlap() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+        let image = Image::new(
+            memory_allocator,
+            ImageCreateInfo {
+                format: Format::R8G8B8A8_UNORM,
+                extent: [64, 64, 1],
+                array_layers: 2,
+                usage: ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+        )
+        .unwrap();
+
+        let region = |src_layer: u32, src_offset, dst_layer: u32, dst_offset| ImageCopy {
+            src_subresource: ImageSubresourceLayers {
+                array_layers: src_layer..src_layer + 1,
+                ..image.subresource_layers()
+            },
+            src_offset,
+            dst_subresource: ImageSubresourceLayers {
+                array_layers: dst_layer..dst_layer + 1,
+                ..image.subresource_layers()
+            },
+            dst_offset,
+            extent: [16, 16, 1],
+            ..Default::default()
+        };
+
+        // Copying between different array layers never overlaps.
+        let copy_image_info = CopyImageInfo {
+            regions: [region(0, [0; 3], 1, [0; 3])].into(),
+            ..CopyImageInfo::images(image.clone(), image.clone())
+        };
+        assert!(copy_image_info.validate(&device).is_ok());
+
+        // Within the same array layer, the regions must not overlap, and both layouts must be
+        // the same.
+        let copy_image_info = CopyImageInfo {
+            src_image_layout: ImageLayout::General,
+            dst_image_layout: ImageLayout::General,
+            regions: [
+                region(0, [0; 3], 0, [32, 32, 0]),
+                region(0, [32, 0, 0], 0, [8, 8, 0]),
+            ]
+            .into(),
+            ..CopyImageInfo::images(image.clone(), image.clone())
+        };
+        let err = copy_image_info.validate(&device).unwrap_err();
+        assert!(err
+            .problem
+            .contains("`regions[0].src_subresource` overlaps with `regions[1].dst_subresource`"));
+
+        let copy_image_info = CopyImageInfo {
+            src_image_layout: ImageLayout::General,
+            dst_image_layout: ImageLayout::General,
+            regions: [
+                region(0, [0; 3], 0, [32, 32, 0]),
+                region(0, [32, 0, 0], 0, [16, 48, 0]),
+            ]
+            .into(),
+            ..CopyImageInfo::images(image.clone(), image)
+        };
+        assert!(copy_image_info.validate(&device).is_ok());
+    }
+
+    #[test]
+    fn copy_image_min_image_transfer_granularity() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+        assert_eq!(
+            queue.min_image_transfer_granularity(),
+            device.physical_device().queue_family_properties()[queue.queue_family_index() as usize]
+                .min_image_transfer_granularity,
+        );
+
+        let create_image = || {
+            Image::new(
+                memory_allocator.clone(),
+                ImageCreateInfo {
+                    format: Format::R8G8B8A8_UNORM,
+                    extent: [64, 64, 1],
+                    usage: ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+            )
+            .unwrap()
+        };
+
+        let mut copy_image_info = CopyImageInfo::images(create_image(), create_image());
+        assert!(copy_image_info
+            .validate_min_image_transfer_granularity([0; 3])
+            .is_ok());
+
+        copy_image_info.regions[0].src_offset = [8, 8, 0];
+        copy_image_info.regions[0].dst_offset = [16, 0, 0];
+        copy_image_info.regions[0].extent = [16, 16, 1];
+        assert!(copy_image_info.validate(&device).is_ok());
+        assert!(copy_image_info
+            .validate_min_image_transfer_granularity([1; 3])
+            .is_ok());
+        assert!(copy_image_info
+            .validate_min_image_transfer_granularity([8, 8, 1])
+            .is_ok());
+        assert!(copy_image_info
+            .validate_min_image_transfer_granularity([16, 16, 1])
+            .is_err());
+        assert!(copy_image_info
+            .validate_min_image_transfer_granularity([0; 3])
+            .is_err());
+
+        // An extent that isn't a multiple of the granularity is allowed at the edge of the image.
+        copy_image_info.regions[0].src_offset = [56, 56, 0];
+        copy_image_info.regions[0].dst_offset = [56, 56, 0];
+        copy_image_info.regions[0].extent = [8, 8, 1];
+        assert!(copy_image_info
+            .validate_min_image_transfer_granularity([16, 16, 1])
+            .is_err());
+        copy_image_info.regions[0].src_offset = [48, 48, 0];
+        copy_image_info.regions[0].dst_offset = [48, 48, 0];
+        copy_image_info.regions[0].extent = [16, 16, 1];
+        assert!(copy_image_info
+            .validate_min_image_transfer_granularity([16, 16, 1])
+            .is_ok());
+    }
+
+    #[test]
+    fn blit_image_depth() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+        let create_image = |format, extent| {
+            Image::new(
+                memory_allocator.clone(),
+                ImageCreateInfo {
+                    format,
+                    extent,
+                    usage: ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+            )
+            .ok()
+            .filter(|image| {
+                image
+                    .format_features()
+                    .contains(FormatFeatures::BLIT_SRC | FormatFeatures::BLIT_DST)
+            })
+        };
+
+        let (Some(depth), Some(depth_half)) = (
+            create_image(Format::D32_SFLOAT, [64, 64, 1]),
+            create_image(Format::D32_SFLOAT, [32, 32, 1]),
+        ) else {
+            return;
+        };
+
+        // Downsampling a depth buffer, for example to build a depth pyramid.
+        let blit_image_info = BlitImageInfo::images(depth.clone(), depth_half.clone());
+        assert_eq!(blit_image_info.filter, Filter::Nearest);
+        assert!(blit_image_info.validate(&device).is_ok());
+
+        let blit_image_info = BlitImageInfo {
+            filter: Filter::Linear,
+            ..BlitImageInfo::images(depth.clone(), depth_half)
+        };
+        let err = blit_image_info.validate(&device).unwrap_err();
+        assert!(err.problem.contains("`Filter::Nearest`"));
+        assert_eq!(err.vuids, &["VUID-VkBlitImageInfo2-srcImage-00232"]);
+
+        if let Some(color) = create_image(Format::R32_SFLOAT, [32, 32, 1]) {
+            let err = BlitImageInfo::images(depth, color)
+                .validate(&device)
+                .unwrap_err();
+            assert_eq!(err.vuids, &["VUID-VkBlitImageInfo2-srcImage-00231"]);
+        }
+    }
+
+    #[test]
+    fn blit_image_integer() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+        let create_image = |format| {
+            Image::new(
+                memory_allocator.clone(),
+                ImageCreateInfo {
+                    format,
+                    extent: [16, 16, 1],
+                    usage: ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+            )
+            .unwrap()
+        };
+
+        // These formats are required to support blitting with optimal tiling.
+        let uint = create_image(Format::R8G8B8A8_UINT);
+        let uint16 = create_image(Format::R16G16B16A16_UINT);
+        let sint = create_image(Format::R8G8B8A8_SINT);
+
+        assert!(BlitImageInfo::images(uint.clone(), uint16)
+            .validate(&device)
+            .is_ok());
+
+        let err = BlitImageInfo::images(uint, sint)
+            .validate(&device)
+            .unwrap_err();
+        assert_eq!(
+            err.vuids,
+            &[
+                "VUID-VkBlitImageInfo2-srcImage-00229",
+                "VUID-VkBlitImageInfo2-srcImage-00230",
+            ],
+        );
+    }
 }
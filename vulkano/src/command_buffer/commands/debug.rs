@@ -1,10 +1,26 @@
+#[cfg(doc)]
+use crate::device::DeviceExtensions;
 use crate::{
-    command_buffer::{sys::RawRecordingCommandBuffer, RecordingCommandBuffer},
-    device::{DeviceOwned, QueueFlags},
+    buffer::{sys::BufferCreateInfo, BufferUsage, RawBuffer, Subbuffer},
+    command_buffer::{sys::RawRecordingCommandBuffer, CommandBuffer, RecordingCommandBuffer},
+    device::{CheckpointData, Device, DeviceOwned, QueueFlags},
     instance::debug::DebugUtilsLabel,
-    Requires, RequiresAllOf, RequiresOneOf, ValidationError, VulkanObject,
+    memory::{
+        DedicatedAllocation, DeviceMemory, MemoryAllocateInfo, MemoryMapInfo, MemoryPropertyFlags,
+        ResourceMemory,
+    },
+    sync::PipelineStages,
+    DeviceSize, Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError, VulkanError,
+    VulkanObject,
+};
+use std::{
+    ffi::{c_void, CString},
+    mem::size_of,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
-use std::ffi::CString;
 
 /// # Commands for debugging.
 ///
@@ -17,7 +33,8 @@ impl RecordingCommandBuffer {
         &mut self,
         label_info: DebugUtilsLabel,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_begin_debug_utils_label(&label_info)?;
+        self.validate_begin_debug_utils_label(&label_info)
+            .map_err(|err| self.command_error(err, "begin_debug_utils_label"))?;
 
         unsafe { Ok(self.begin_debug_utils_label_unchecked(label_info)) }
     }
@@ -36,6 +53,10 @@ impl RecordingCommandBuffer {
         &mut self,
         label_info: DebugUtilsLabel,
     ) -> &mut Self {
+        if self.automatic_checkpoints.is_some() {
+            self.debug_label_names.push(label_info.label_name.clone());
+        }
+
         self.add_command(
             "begin_debug_utils_label",
             Default::default(),
@@ -55,7 +76,8 @@ impl RecordingCommandBuffer {
     ///   region begun with `begin_debug_utils_label` in the queue, either within this command
     ///   buffer or a previously submitted one.
     pub unsafe fn end_debug_utils_label(&mut self) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_end_debug_utils_label()?;
+        self.validate_end_debug_utils_label()
+            .map_err(|err| self.command_error(err, "end_debug_utils_label"))?;
 
         Ok(self.end_debug_utils_label_unchecked())
     }
@@ -88,7 +110,8 @@ impl RecordingCommandBuffer {
         &mut self,
         label_info: DebugUtilsLabel,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_insert_debug_utils_label(&label_info)?;
+        self.validate_insert_debug_utils_label(&label_info)
+            .map_err(|err| self.command_error(err, "insert_debug_utils_label"))?;
 
         unsafe { Ok(self.insert_debug_utils_label_unchecked(label_info)) }
     }
@@ -119,6 +142,156 @@ impl RecordingCommandBuffer {
     }
 }
 
+/// # Commands for diagnosing device loss.
+///
+/// [`set_checkpoint`] requires the [`nv_device_diagnostic_checkpoints`] extension to be enabled on
+/// the device. Automatic checkpoints require either that extension or the [`amd_buffer_marker`]
+/// extension.
+///
+/// [`set_checkpoint`]: Self::set_checkpoint
+/// [`nv_device_diagnostic_checkpoints`]: DeviceExtensions::nv_device_diagnostic_checkpoints
+/// [`amd_buffer_marker`]: DeviceExtensions::amd_buffer_marker
+impl RecordingCommandBuffer {
+    /// Makes the command buffer automatically insert checkpoints while it is being built, at the
+    /// places given by `granularity`.
+    ///
+    /// The inserted checkpoints can be retrieved with [`CommandBuffer::checkpoints`] once the
+    /// command buffer has been built. If the device is lost while the command buffer is executing,
+    /// the markers returned by [`Queue::checkpoint_data`] can be looked up with
+    /// [`CommandBuffer::checkpoint`] to find out which command or debug label region each pipeline
+    /// stage of the queue had last reached. [`Queue::hang_report`] does this for all command
+    /// buffers that were submitted to the queue.
+    ///
+    /// If the [`nv_device_diagnostic_checkpoints`] extension is not enabled on the device, the
+    /// checkpoints are instead implemented with the [`amd_buffer_marker`] extension. The command
+    /// buffer then writes the checkpoints that the top and the bottom of the pipeline have
+    /// reached to a small host-visible buffer that it owns, which
+    /// [`HangReport::from_buffer_markers`] reads back. In this case there is no checkpoint
+    /// data for the other pipeline stages, and [`Queue::checkpoint_data`] can't be used.
+    ///
+    /// Each checkpoint costs one extra command when the command buffer is executed, which can
+    /// reduce performance if checkpoints are inserted frequently. Automatic checkpoints are
+    /// intended for debugging, and should usually not be enabled in release builds.
+    ///
+    /// This must be called before any command is recorded.
+    ///
+    /// [`CommandBuffer::checkpoints`]: crate::command_buffer::CommandBuffer::checkpoints
+    /// [`Queue::checkpoint_data`]: crate::device::Queue::checkpoint_data
+    /// [`CommandBuffer::checkpoint`]: crate::command_buffer::CommandBuffer::checkpoint
+    /// [`Queue::hang_report`]: crate::device::Queue::hang_report
+    /// [`nv_device_diagnostic_checkpoints`]: DeviceExtensions::nv_device_diagnostic_checkpoints
+    /// [`amd_buffer_marker`]: DeviceExtensions::amd_buffer_marker
+    pub fn set_automatic_checkpoints(
+        &mut self,
+        granularity: CheckpointGranularity,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.validate_set_automatic_checkpoints(granularity)?;
+
+        unsafe { Ok(self.set_automatic_checkpoints_unchecked(granularity)) }
+    }
+
+    fn validate_set_automatic_checkpoints(
+        &self,
+        granularity: CheckpointGranularity,
+    ) -> Result<(), Box<ValidationError>> {
+        let enabled_extensions = self.device().enabled_extensions();
+
+        if !(enabled_extensions.nv_device_diagnostic_checkpoints
+            || enabled_extensions.amd_buffer_marker)
+        {
+            return Err(Box::new(ValidationError {
+                requires_one_of: RequiresOneOf(&[
+                    RequiresAllOf(&[Requires::DeviceExtension(
+                        "nv_device_diagnostic_checkpoints",
+                    )]),
+                    RequiresAllOf(&[Requires::DeviceExtension("amd_buffer_marker")]),
+                ]),
+                ..Default::default()
+            }));
+        }
+
+        if !self
+            .inner
+            .queue_family_properties()
+            .queue_flags
+            .intersects(QueueFlags::GRAPHICS | QueueFlags::COMPUTE | QueueFlags::TRANSFER)
+        {
+            return Err(Box::new(ValidationError {
+                problem: "the queue family of the command buffer does not support \
+                    graphics, compute or transfer operations"
+                    .into(),
+                vuids: &[
+                    "VUID-vkCmdSetCheckpointNV-commandBuffer-cmdpool",
+                    "VUID-vkCmdWriteBufferMarkerAMD-commandBuffer-cmdpool",
+                ],
+                ..Default::default()
+            }));
+        }
+
+        if let CheckpointGranularity::Commands(0) = granularity {
+            return Err(Box::new(ValidationError {
+                context: "granularity".into(),
+                problem: "is `CheckpointGranularity::Commands(0)`".into(),
+                ..Default::default()
+            }));
+        }
+
+        if !self.commands.is_empty() {
+            return Err(Box::new(ValidationError {
+                problem: "commands have already been recorded to the command buffer".into(),
+                ..Default::default()
+            }));
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn set_automatic_checkpoints_unchecked(
+        &mut self,
+        granularity: CheckpointGranularity,
+    ) -> &mut Self {
+        self.automatic_checkpoints = Some(granularity);
+
+        self
+    }
+
+    /// Inserts a checkpoint with the given marker.
+    ///
+    /// If the device is lost, [`Queue::checkpoint_data`] returns the markers of the checkpoints
+    /// that each pipeline stage of the queue had last reached.
+    ///
+    /// `marker` must be less than [`Checkpoint::AUTOMATIC_MARKER_START`], because the markers
+    /// from that value onwards are reserved for automatic checkpoints.
+    ///
+    /// [`Queue::checkpoint_data`]: crate::device::Queue::checkpoint_data
+    pub fn set_checkpoint(&mut self, marker: usize) -> Result<&mut Self, Box<ValidationError>> {
+        self.validate_set_checkpoint(marker)
+            .map_err(|err| self.command_error(err, "set_checkpoint"))?;
+
+        unsafe { Ok(self.set_checkpoint_unchecked(marker)) }
+    }
+
+    fn validate_set_checkpoint(&self, marker: usize) -> Result<(), Box<ValidationError>> {
+        self.inner.validate_set_checkpoint(marker)?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn set_checkpoint_unchecked(&mut self, marker: usize) -> &mut Self {
+        self.add_command(
+            "set_checkpoint",
+            Default::default(),
+            move |out: &mut RawRecordingCommandBuffer| {
+                out.set_checkpoint_unchecked(marker);
+            },
+        );
+
+        self
+    }
+}
+
 impl RawRecordingCommandBuffer {
     #[inline]
     pub unsafe fn begin_debug_utils_label(
@@ -304,4 +477,466 @@ impl RawRecordingCommandBuffer {
 
         self
     }
+
+    #[inline]
+    pub unsafe fn set_checkpoint(
+        &mut self,
+        marker: usize,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.validate_set_checkpoint(marker)?;
+
+        Ok(self.set_checkpoint_unchecked(marker))
+    }
+
+    fn validate_set_checkpoint(&self, marker: usize) -> Result<(), Box<ValidationError>> {
+        if !self
+            .device()
+            .enabled_extensions()
+            .nv_device_diagnostic_checkpoints
+        {
+            return Err(Box::new(ValidationError {
+                requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceExtension(
+                    "nv_device_diagnostic_checkpoints",
+                )])]),
+                ..Default::default()
+            }));
+        }
+
+        if !self
+            .queue_family_properties()
+            .queue_flags
+            .intersects(QueueFlags::GRAPHICS | QueueFlags::COMPUTE | QueueFlags::TRANSFER)
+        {
+            return Err(Box::new(ValidationError {
+                problem: "the queue family of the command buffer does not support \
+                    graphics, compute or transfer operations"
+                    .into(),
+                vuids: &["VUID-vkCmdSetCheckpointNV-commandBuffer-cmdpool"],
+                ..Default::default()
+            }));
+        }
+
+        if marker >= Checkpoint::AUTOMATIC_MARKER_START {
+            return Err(Box::new(ValidationError {
+                context: "marker".into(),
+                problem: "is not less than `Checkpoint::AUTOMATIC_MARKER_START`, and is therefore \
+                    reserved for automatic checkpoints"
+                    .into(),
+                ..Default::default()
+            }));
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn set_checkpoint_unchecked(&mut self, marker: usize) -> &mut Self {
+        let fns = self.device().fns();
+        (fns.nv_device_diagnostic_checkpoints.cmd_set_checkpoint_nv)(
+            self.handle(),
+            marker as *const c_void,
+        );
+
+        self
+    }
+}
+
+/// Where a [`RecordingCommandBuffer`] automatically inserts checkpoints, as set with
+/// [`RecordingCommandBuffer::set_automatic_checkpoints`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CheckpointGranularity {
+    /// A checkpoint is inserted where each debug label region begins and ends.
+    DebugLabelRegions,
+
+    /// A checkpoint is inserted before every `n`th command, starting with the first.
+    ///
+    /// The value must not be 0.
+    Commands(u32),
+}
+
+/// A checkpoint that was automatically inserted into a command buffer.
+///
+/// A checkpoint is also inserted at the end of every command buffer that has automatic
+/// checkpoints enabled, so that it can be told whether the whole command buffer was executed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// The marker of the checkpoint. Markers of automatic checkpoints are unique within the
+    /// process, and are never less than [`Checkpoint::AUTOMATIC_MARKER_START`].
+    ///
+    /// If the checkpoints are implemented with the [`amd_buffer_marker`] extension, this marker
+    /// is not written by the device, but can still be looked up with
+    /// [`CommandBuffer::checkpoint`].
+    ///
+    /// [`amd_buffer_marker`]: DeviceExtensions::amd_buffer_marker
+    pub marker: usize,
+
+    /// The index of the command that follows the checkpoint, counting all recorded commands in
+    /// order. For the checkpoint at the end of the command buffer, this is the number of commands.
+    pub command_index: usize,
+
+    /// The name of the command that follows the checkpoint, or `"end"` for the checkpoint at the
+    /// end of the command buffer.
+    pub command_name: &'static str,
+
+    /// The names of the debug label regions that enclose the checkpoint, outermost first.
+    ///
+    /// The checkpoint before a `begin_debug_utils_label` or `end_debug_utils_label` command is
+    /// considered to be inside the region that is begun or ended.
+    pub debug_labels: Vec<String>,
+}
+
+impl Checkpoint {
+    /// The start of the range of markers that is reserved for automatic checkpoints.
+    ///
+    /// Markers given to [`set_checkpoint`] must be less than this value, so that they can't be
+    /// mistaken for the marker of an automatic checkpoint.
+    ///
+    /// [`set_checkpoint`]: RecordingCommandBuffer::set_checkpoint
+    pub const AUTOMATIC_MARKER_START: usize = 1 << (usize::BITS - 1);
+}
+
+/// Where the execution of command buffers had got to when the device was lost, as determined from
+/// their automatic checkpoints.
+///
+/// This is returned by [`Queue::hang_report`], or can be created from checkpoint data that was
+/// retrieved earlier with [`HangReport::new`], or from the marker buffers of the command buffers
+/// with [`HangReport::from_buffer_markers`].
+///
+/// [`Queue::hang_report`]: crate::device::Queue::hang_report
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HangReport {
+    /// The checkpoint that was last reached at the top of the pipeline. The commands after the
+    /// next checkpoint had not started executing yet.
+    pub last_started: Option<HangReportCheckpoint>,
+
+    /// The checkpoint that was last reached at the bottom of the pipeline. The commands before it
+    /// had completed, so a command that caused the device loss comes after it.
+    pub last_completed: Option<HangReportCheckpoint>,
+
+    /// For each pipeline stage that reported the marker of an automatic checkpoint, the checkpoint
+    /// that the stage last reached.
+    pub stages: Vec<(PipelineStages, HangReportCheckpoint)>,
+
+    /// The checkpoint data whose marker doesn't belong to an automatic checkpoint of the command
+    /// buffers, for example because it was given to [`set_checkpoint`].
+    ///
+    /// [`set_checkpoint`]: RecordingCommandBuffer::set_checkpoint
+    pub unknown: Vec<CheckpointData>,
+}
+
+impl HangReport {
+    /// Looks up the markers of `checkpoint_data` in the automatic checkpoints of
+    /// `command_buffers`.
+    pub fn new(checkpoint_data: &[CheckpointData], command_buffers: &[Arc<CommandBuffer>]) -> Self {
+        let mut report = HangReport::default();
+
+        for &checkpoint_data in checkpoint_data {
+            let checkpoint = command_buffers.iter().enumerate().find_map(
+                |(command_buffer_index, command_buffer)| {
+                    let checkpoint = command_buffer.checkpoint(checkpoint_data.marker)?;
+
+                    Some(HangReportCheckpoint {
+                        command_buffer_index,
+                        checkpoint: checkpoint.clone(),
+                    })
+                },
+            );
+            let Some(checkpoint) = checkpoint else {
+                report.unknown.push(checkpoint_data);
+                continue;
+            };
+
+            if checkpoint_data
+                .stage
+                .intersects(PipelineStages::TOP_OF_PIPE)
+            {
+                report.last_started = Some(checkpoint.clone());
+            }
+
+            if checkpoint_data
+                .stage
+                .intersects(PipelineStages::BOTTOM_OF_PIPE)
+            {
+                report.last_completed = Some(checkpoint.clone());
+            }
+
+            report.stages.push((checkpoint_data.stage, checkpoint));
+        }
+
+        report
+    }
+
+    /// Reads the checkpoints that `command_buffers` wrote to their marker buffers, if their
+    /// automatic checkpoints were implemented with the [`amd_buffer_marker`] extension.
+    ///
+    /// `command_buffers` should be the command buffers that were submitted to the queue and that
+    /// may not have completed, in the order that they were submitted in. Command buffers without a
+    /// marker buffer are ignored.
+    ///
+    /// Only [`last_started`] and [`last_completed`] can be determined this way, and `stages`
+    /// contains them with the `TOP_OF_PIPE` and `BOTTOM_OF_PIPE` stages respectively.
+    ///
+    /// [`amd_buffer_marker`]: DeviceExtensions::amd_buffer_marker
+    /// [`last_started`]: Self::last_started
+    /// [`last_completed`]: Self::last_completed
+    pub fn from_buffer_markers(command_buffers: &[Arc<CommandBuffer>]) -> Self {
+        let mut report = HangReport::default();
+
+        for (command_buffer_index, command_buffer) in command_buffers.iter().enumerate() {
+            let Some(marker_buffer) = command_buffer.checkpoint_marker_buffer() else {
+                continue;
+            };
+            let Ok(markers) = marker_buffer.read() else {
+                continue;
+            };
+
+            // A marker is the index of the checkpoint plus one, and 0 if no checkpoint has been
+            // reached.
+            let checkpoint = |marker: u32| {
+                let index = (marker as usize).checked_sub(1)?;
+                let checkpoint = command_buffer.checkpoints().get(index)?;
+
+                Some(HangReportCheckpoint {
+                    command_buffer_index,
+                    checkpoint: checkpoint.clone(),
+                })
+            };
+
+            if let Some(checkpoint) = checkpoint(markers[0]) {
+                report.last_started = Some(checkpoint);
+            }
+
+            if let Some(checkpoint) = checkpoint(markers[1]) {
+                report.last_completed = Some(checkpoint);
+            }
+        }
+
+        if let Some(checkpoint) = &report.last_started {
+            report
+                .stages
+                .push((PipelineStages::TOP_OF_PIPE, checkpoint.clone()));
+        }
+
+        if let Some(checkpoint) = &report.last_completed {
+            report
+                .stages
+                .push((PipelineStages::BOTTOM_OF_PIPE, checkpoint.clone()));
+        }
+
+        report
+    }
+}
+
+/// An automatic checkpoint in a [`HangReport`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HangReportCheckpoint {
+    /// The index of the command buffer that contains the checkpoint, in the command buffers that
+    /// the report was created from.
+    pub command_buffer_index: usize,
+
+    /// The checkpoint.
+    pub checkpoint: Checkpoint,
+}
+
+/// Inserts automatic checkpoints while the commands of a `RecordingCommandBuffer` are recorded
+/// to the raw command buffer.
+pub(in crate::command_buffer) struct CheckpointRecorder {
+    granularity: CheckpointGranularity,
+    debug_label_names: std::vec::IntoIter<String>,
+    debug_labels: Vec<String>,
+    checkpoints: Vec<Checkpoint>,
+    // If the `nv_device_diagnostic_checkpoints` extension isn't enabled, the checkpoints are
+    // written to this buffer with the `amd_buffer_marker` extension instead. It holds the
+    // markers reached by the top and the bottom of the pipeline.
+    marker_buffer: Option<Subbuffer<[u32]>>,
+}
+
+impl CheckpointRecorder {
+    pub(in crate::command_buffer) unsafe fn new(
+        out: &mut RawRecordingCommandBuffer,
+        granularity: CheckpointGranularity,
+        debug_label_names: Vec<String>,
+    ) -> Result<Self, Validated<VulkanError>> {
+        let marker_buffer = if out
+            .device()
+            .enabled_extensions()
+            .nv_device_diagnostic_checkpoints
+        {
+            None
+        } else {
+            let marker_buffer = create_marker_buffer(out.device())?;
+
+            // The command buffer may be executed more than once, so the markers of a previous
+            // execution have to be reset when it starts.
+            write_markers(out, &marker_buffer, 0);
+
+            Some(marker_buffer)
+        };
+
+        Ok(CheckpointRecorder {
+            granularity,
+            debug_label_names: debug_label_names.into_iter(),
+            debug_labels: Vec::new(),
+            checkpoints: Vec::new(),
+            marker_buffer,
+        })
+    }
+
+    /// Inserts a checkpoint before the command with the given index if the granularity calls for
+    /// one. Must be called for every command, in order.
+    pub(in crate::command_buffer) unsafe fn record_command(
+        &mut self,
+        out: &mut RawRecordingCommandBuffer,
+        command_index: usize,
+        command_name: &'static str,
+    ) {
+        let is_label_command = match command_name {
+            "begin_debug_utils_label" => {
+                let label_name = self.debug_label_names.next().unwrap_or_default();
+                self.debug_labels.push(label_name);
+                true
+            }
+            "end_debug_utils_label" => true,
+            _ => false,
+        };
+
+        let insert = match self.granularity {
+            CheckpointGranularity::DebugLabelRegions => is_label_command,
+            CheckpointGranularity::Commands(interval) => command_index % interval as usize == 0,
+        };
+
+        if insert {
+            self.insert(out, command_index, command_name);
+        }
+
+        if command_name == "end_debug_utils_label" {
+            self.debug_labels.pop();
+        }
+    }
+
+    /// Inserts the checkpoint at the end of the command buffer, and returns all checkpoints and
+    /// the marker buffer.
+    pub(in crate::command_buffer) unsafe fn finish(
+        mut self,
+        out: &mut RawRecordingCommandBuffer,
+        command_count: usize,
+    ) -> (Vec<Checkpoint>, Option<Subbuffer<[u32]>>) {
+        self.insert(out, command_count, "end");
+
+        (self.checkpoints, self.marker_buffer)
+    }
+
+    unsafe fn insert(
+        &mut self,
+        out: &mut RawRecordingCommandBuffer,
+        command_index: usize,
+        command_name: &'static str,
+    ) {
+        static NEXT_MARKER: AtomicUsize = AtomicUsize::new(0);
+
+        let marker =
+            Checkpoint::AUTOMATIC_MARKER_START | NEXT_MARKER.fetch_add(1, Ordering::Relaxed);
+
+        if let Some(marker_buffer) = &self.marker_buffer {
+            write_markers(out, marker_buffer, self.checkpoints.len() as u32 + 1);
+        } else {
+            out.set_checkpoint_unchecked(marker);
+        }
+
+        self.checkpoints.push(Checkpoint {
+            marker,
+            command_index,
+            command_name,
+            debug_labels: self.debug_labels.clone(),
+        });
+    }
+}
+
+/// Creates the buffer that the `amd_buffer_marker` checkpoints of a command buffer are written
+/// to. It is allocated from host-visible, host-coherent memory and stays mapped, so that it can
+/// still be read after the device is lost.
+fn create_marker_buffer(device: &Arc<Device>) -> Result<Subbuffer<[u32]>, Validated<VulkanError>> {
+    let raw_buffer = RawBuffer::new(
+        device.clone(),
+        BufferCreateInfo {
+            size: 2 * size_of::<u32>() as DeviceSize,
+            usage: BufferUsage::TRANSFER_DST,
+            ..Default::default()
+        },
+    )?;
+
+    let memory_requirements = raw_buffer.memory_requirements();
+    let device_coherent_memory = device.enabled_features().device_coherent_memory;
+    let find_memory_type = |required_flags: MemoryPropertyFlags| {
+        device
+            .physical_device()
+            .memory_properties()
+            .memory_types
+            .iter()
+            .zip(0..)
+            .find(|&(memory_type, index)| {
+                memory_requirements.memory_type_bits & (1 << index) != 0
+                    && memory_type.property_flags.contains(required_flags)
+                    && (device_coherent_memory
+                        || !memory_type
+                            .property_flags
+                            .intersects(MemoryPropertyFlags::DEVICE_COHERENT))
+            })
+            .map(|(_, index)| index)
+    };
+
+    // Device-coherent memory makes the markers of the commands that were executing visible when
+    // the device is lost. Every buffer can be bound to host-visible, host-coherent memory.
+    let host_flags = MemoryPropertyFlags::HOST_VISIBLE | MemoryPropertyFlags::HOST_COHERENT;
+    let memory_type_index = find_memory_type(host_flags | MemoryPropertyFlags::DEVICE_COHERENT)
+        .or_else(|| find_memory_type(host_flags))
+        .unwrap();
+
+    let allocation_size = memory_requirements.layout.size();
+    let mut memory = DeviceMemory::allocate(
+        device.clone(),
+        MemoryAllocateInfo {
+            allocation_size,
+            memory_type_index,
+            ..MemoryAllocateInfo::dedicated_allocation(DedicatedAllocation::Buffer(&raw_buffer))
+        },
+    )?;
+    memory.map(MemoryMapInfo {
+        offset: 0,
+        size: allocation_size,
+        ..Default::default()
+    })?;
+
+    let buffer = unsafe { raw_buffer.bind_memory(ResourceMemory::new_dedicated(memory)) }
+        .map_err(|(err, _, _)| err)?;
+    let marker_buffer = Subbuffer::new(Arc::new(buffer)).reinterpret::<[u32]>();
+    marker_buffer.write().unwrap().fill(0);
+
+    Ok(marker_buffer)
+}
+
+/// Writes `marker` to the marker buffer once the preceding commands have reached the top of the
+/// pipeline, and again once they have reached the bottom of the pipeline.
+unsafe fn write_markers(
+    out: &mut RawRecordingCommandBuffer,
+    marker_buffer: &Subbuffer<[u32]>,
+    marker: u32,
+) {
+    let fns = out.device().fns();
+
+    for (index, stage) in [
+        ash::vk::PipelineStageFlags::TOP_OF_PIPE,
+        ash::vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        (fns.amd_buffer_marker.cmd_write_buffer_marker_amd)(
+            out.handle(),
+            stage,
+            marker_buffer.buffer().handle(),
+            marker_buffer.offset() + (index * size_of::<u32>()) as DeviceSize,
+            marker,
+        );
+    }
 }
@@ -26,7 +26,7 @@ use std::ops::RangeInclusive;
 /// These commands require a queue with a pipeline type that uses the given state.
 impl RecordingCommandBuffer {
     // Helper function for dynamic state setting.
-    fn validate_graphics_pipeline_fixed_state(
+    pub(in crate::command_buffer) fn validate_graphics_pipeline_fixed_state(
         &self,
         state: DynamicState,
     ) -> Result<(), Box<ValidationError>> {
@@ -53,7 +53,8 @@ impl RecordingCommandBuffer {
         &mut self,
         constants: [f32; 4],
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_blend_constants(constants)?;
+        self.validate_set_blend_constants(constants)
+            .map_err(|err| self.command_error(err, "set_blend_constants"))?;
 
         unsafe { Ok(self.set_blend_constants_unchecked(constants)) }
     }
@@ -89,7 +90,8 @@ impl RecordingCommandBuffer {
         &mut self,
         enables: SmallVec<[bool; 4]>,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_color_write_enable(&enables)?;
+        self.validate_set_color_write_enable(&enables)
+            .map_err(|err| self.command_error(err, "set_color_write_enable"))?;
 
         unsafe { Ok(self.set_color_write_enable_unchecked(enables)) }
     }
@@ -145,7 +147,8 @@ impl RecordingCommandBuffer {
         &mut self,
         cull_mode: CullMode,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_cull_mode(cull_mode)?;
+        self.validate_set_cull_mode(cull_mode)
+            .map_err(|err| self.command_error(err, "set_cull_mode"))?;
 
         unsafe { Ok(self.set_cull_mode_unchecked(cull_mode)) }
     }
@@ -179,7 +182,8 @@ impl RecordingCommandBuffer {
         clamp: f32,
         slope_factor: f32,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_depth_bias(constant_factor, clamp, slope_factor)?;
+        self.validate_set_depth_bias(constant_factor, clamp, slope_factor)
+            .map_err(|err| self.command_error(err, "set_depth_bias"))?;
 
         unsafe { Ok(self.set_depth_bias_unchecked(constant_factor, clamp, slope_factor)) }
     }
@@ -226,7 +230,8 @@ impl RecordingCommandBuffer {
         &mut self,
         enable: bool,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_depth_bias_enable(enable)?;
+        self.validate_set_depth_bias_enable(enable)
+            .map_err(|err| self.command_error(err, "set_depth_bias_enable"))?;
 
         unsafe { Ok(self.set_depth_bias_enable_unchecked(enable)) }
     }
@@ -258,7 +263,8 @@ impl RecordingCommandBuffer {
         &mut self,
         bounds: RangeInclusive<f32>,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_depth_bounds(bounds.clone())?;
+        self.validate_set_depth_bounds(bounds.clone())
+            .map_err(|err| self.command_error(err, "set_depth_bounds"))?;
 
         unsafe { Ok(self.set_depth_bounds_unchecked(bounds)) }
     }
@@ -293,7 +299,8 @@ impl RecordingCommandBuffer {
         &mut self,
         enable: bool,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_depth_bounds_test_enable(enable)?;
+        self.validate_set_depth_bounds_test_enable(enable)
+            .map_err(|err| self.command_error(err, "set_depth_bounds_test_enable"))?;
 
         unsafe { Ok(self.set_depth_bounds_test_enable_unchecked(enable)) }
     }
@@ -328,7 +335,8 @@ impl RecordingCommandBuffer {
         &mut self,
         compare_op: CompareOp,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_depth_compare_op(compare_op)?;
+        self.validate_set_depth_compare_op(compare_op)
+            .map_err(|err| self.command_error(err, "set_depth_compare_op"))?;
 
         unsafe { Ok(self.set_depth_compare_op_unchecked(compare_op)) }
     }
@@ -363,7 +371,8 @@ impl RecordingCommandBuffer {
         &mut self,
         enable: bool,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_depth_test_enable(enable)?;
+        self.validate_set_depth_test_enable(enable)
+            .map_err(|err| self.command_error(err, "set_depth_test_enable"))?;
 
         unsafe { Ok(self.set_depth_test_enable_unchecked(enable)) }
     }
@@ -395,7 +404,8 @@ impl RecordingCommandBuffer {
         &mut self,
         enable: bool,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_depth_write_enable(enable)?;
+        self.validate_set_depth_write_enable(enable)
+            .map_err(|err| self.command_error(err, "set_depth_write_enable"))?;
 
         unsafe { Ok(self.set_depth_write_enable_unchecked(enable)) }
     }
@@ -428,7 +438,8 @@ impl RecordingCommandBuffer {
         first_rectangle: u32,
         rectangles: SmallVec<[Scissor; 2]>,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_discard_rectangle(first_rectangle, &rectangles)?;
+        self.validate_set_discard_rectangle(first_rectangle, &rectangles)
+            .map_err(|err| self.command_error(err, "set_discard_rectangle"))?;
 
         unsafe { Ok(self.set_discard_rectangle_unchecked(first_rectangle, rectangles)) }
     }
@@ -470,7 +481,8 @@ impl RecordingCommandBuffer {
 
     /// Sets the dynamic front face for future draw calls.
     pub fn set_front_face(&mut self, face: FrontFace) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_front_face(face)?;
+        self.validate_set_front_face(face)
+            .map_err(|err| self.command_error(err, "set_front_face"))?;
 
         unsafe { Ok(self.set_front_face_unchecked(face)) }
     }
@@ -503,7 +515,8 @@ impl RecordingCommandBuffer {
         factor: u32,
         pattern: u16,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_line_stipple(factor, pattern)?;
+        self.validate_set_line_stipple(factor, pattern)
+            .map_err(|err| self.command_error(err, "set_line_stipple"))?;
 
         unsafe { Ok(self.set_line_stipple_unchecked(factor, pattern)) }
     }
@@ -536,7 +549,8 @@ impl RecordingCommandBuffer {
 
     /// Sets the dynamic line width for future draw calls.
     pub fn set_line_width(&mut self, line_width: f32) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_line_width(line_width)?;
+        self.validate_set_line_width(line_width)
+            .map_err(|err| self.command_error(err, "set_line_width"))?;
 
         unsafe { Ok(self.set_line_width_unchecked(line_width)) }
     }
@@ -565,7 +579,8 @@ impl RecordingCommandBuffer {
 
     /// Sets the dynamic logic op for future draw calls.
     pub fn set_logic_op(&mut self, logic_op: LogicOp) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_logic_op(logic_op)?;
+        self.validate_set_logic_op(logic_op)
+            .map_err(|err| self.command_error(err, "set_logic_op"))?;
 
         unsafe { Ok(self.set_logic_op_unchecked(logic_op)) }
     }
@@ -597,7 +612,8 @@ impl RecordingCommandBuffer {
         &mut self,
         num: u32,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_patch_control_points(num)?;
+        self.validate_set_patch_control_points(num)
+            .map_err(|err| self.command_error(err, "set_patch_control_points"))?;
 
         unsafe { Ok(self.set_patch_control_points_unchecked(num)) }
     }
@@ -629,7 +645,8 @@ impl RecordingCommandBuffer {
         &mut self,
         enable: bool,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_primitive_restart_enable(enable)?;
+        self.validate_set_primitive_restart_enable(enable)
+            .map_err(|err| self.command_error(err, "set_primitive_restart_enable"))?;
 
         unsafe { Ok(self.set_primitive_restart_enable_unchecked(enable)) }
     }
@@ -664,7 +681,8 @@ impl RecordingCommandBuffer {
         &mut self,
         topology: PrimitiveTopology,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_primitive_topology(topology)?;
+        self.validate_set_primitive_topology(topology)
+            .map_err(|err| self.command_error(err, "set_primitive_topology"))?;
 
         unsafe { Ok(self.set_primitive_topology_unchecked(topology)) }
     }
@@ -702,7 +720,8 @@ impl RecordingCommandBuffer {
         &mut self,
         enable: bool,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_rasterizer_discard_enable(enable)?;
+        self.validate_set_rasterizer_discard_enable(enable)
+            .map_err(|err| self.command_error(err, "set_rasterizer_discard_enable"))?;
 
         unsafe { Ok(self.set_rasterizer_discard_enable_unchecked(enable)) }
     }
@@ -738,12 +757,13 @@ impl RecordingCommandBuffer {
         first_scissor: u32,
         scissors: SmallVec<[Scissor; 2]>,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_scissor(first_scissor, &scissors)?;
+        self.validate_set_scissor(first_scissor, &scissors)
+            .map_err(|err| self.command_error(err, "set_scissor"))?;
 
         unsafe { Ok(self.set_scissor_unchecked(first_scissor, scissors)) }
     }
 
-    fn validate_set_scissor(
+    pub(super) fn validate_set_scissor(
         &self,
         first_scissor: u32,
         scissors: &[Scissor],
@@ -784,7 +804,8 @@ impl RecordingCommandBuffer {
         &mut self,
         scissors: SmallVec<[Scissor; 2]>,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_scissor_with_count(&scissors)?;
+        self.validate_set_scissor_with_count(&scissors)
+            .map_err(|err| self.command_error(err, "set_scissor_with_count"))?;
 
         unsafe { Ok(self.set_scissor_with_count_unchecked(scissors)) }
     }
@@ -823,7 +844,8 @@ impl RecordingCommandBuffer {
         faces: StencilFaces,
         compare_mask: u32,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_stencil_compare_mask(faces, compare_mask)?;
+        self.validate_set_stencil_compare_mask(faces, compare_mask)
+            .map_err(|err| self.command_error(err, "set_stencil_compare_mask"))?;
 
         unsafe { Ok(self.set_stencil_compare_mask_unchecked(faces, compare_mask)) }
     }
@@ -877,7 +899,8 @@ impl RecordingCommandBuffer {
         depth_fail_op: StencilOp,
         compare_op: CompareOp,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_stencil_op(faces, fail_op, pass_op, depth_fail_op, compare_op)?;
+        self.validate_set_stencil_op(faces, fail_op, pass_op, depth_fail_op, compare_op)
+            .map_err(|err| self.command_error(err, "set_stencil_op"))?;
 
         unsafe {
             Ok(self.set_stencil_op_unchecked(faces, fail_op, pass_op, depth_fail_op, compare_op))
@@ -946,7 +969,8 @@ impl RecordingCommandBuffer {
         faces: StencilFaces,
         reference: u32,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_stencil_reference(faces, reference)?;
+        self.validate_set_stencil_reference(faces, reference)
+            .map_err(|err| self.command_error(err, "set_stencil_reference"))?;
 
         unsafe { Ok(self.set_stencil_reference_unchecked(faces, reference)) }
     }
@@ -996,7 +1020,8 @@ impl RecordingCommandBuffer {
         &mut self,
         enable: bool,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_stencil_test_enable(enable)?;
+        self.validate_set_stencil_test_enable(enable)
+            .map_err(|err| self.command_error(err, "set_stencil_test_enable"))?;
 
         unsafe { Ok(self.set_stencil_test_enable_unchecked(enable)) }
     }
@@ -1029,7 +1054,8 @@ impl RecordingCommandBuffer {
         faces: StencilFaces,
         write_mask: u32,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_stencil_write_mask(faces, write_mask)?;
+        self.validate_set_stencil_write_mask(faces, write_mask)
+            .map_err(|err| self.command_error(err, "set_stencil_write_mask"))?;
 
         unsafe { Ok(self.set_stencil_write_mask_unchecked(faces, write_mask)) }
     }
@@ -1080,7 +1106,8 @@ impl RecordingCommandBuffer {
         &mut self,
         vertex_input_state: VertexInputState,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_vertex_input(&vertex_input_state)?;
+        self.validate_set_vertex_input(&vertex_input_state)
+            .map_err(|err| self.command_error(err, "set_vertex_input"))?;
 
         unsafe { Ok(self.set_vertex_input_unchecked(vertex_input_state)) }
     }
@@ -1120,7 +1147,8 @@ impl RecordingCommandBuffer {
         first_viewport: u32,
         viewports: SmallVec<[Viewport; 2]>,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_viewport(first_viewport, &viewports)?;
+        self.validate_set_viewport(first_viewport, &viewports)
+            .map_err(|err| self.command_error(err, "set_viewport"))?;
 
         unsafe { Ok(self.set_viewport_unchecked(first_viewport, viewports)) }
     }
@@ -1165,7 +1193,8 @@ impl RecordingCommandBuffer {
         &mut self,
         viewports: SmallVec<[Viewport; 2]>,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_viewport_with_count(&viewports)?;
+        self.validate_set_viewport_with_count(&viewports)
+            .map_err(|err| self.command_error(err, "set_viewport_with_count"))?;
 
         unsafe { Ok(self.set_viewport_with_count_unchecked(viewports)) }
     }
@@ -1204,7 +1233,8 @@ impl RecordingCommandBuffer {
         &mut self,
         conservative_rasterization_mode: ConservativeRasterizationMode,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_conservative_rasterization_mode()?;
+        self.validate_set_conservative_rasterization_mode()
+            .map_err(|err| self.command_error(err, "set_conservative_rasterization_mode"))?;
 
         unsafe {
             Ok(self.set_conservative_rasterization_mode_unchecked(conservative_rasterization_mode))
@@ -1243,7 +1273,8 @@ impl RecordingCommandBuffer {
         &mut self,
         extra_primitive_overestimation_size: f32,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_set_extra_primitive_overestimation_size()?;
+        self.validate_set_extra_primitive_overestimation_size()
+            .map_err(|err| self.command_error(err, "set_extra_primitive_overestimation_size"))?;
 
         unsafe {
             Ok(self.set_extra_primitive_overestimation_size_unchecked(
@@ -2472,7 +2503,7 @@ impl RawRecordingCommandBuffer {
         Ok(self.set_scissor_unchecked(first_scissor, scissors))
     }
 
-    fn validate_set_scissor(
+    pub(super) fn validate_set_scissor(
         &self,
         first_scissor: u32,
         scissors: &[Scissor],
@@ -2526,6 +2557,44 @@ impl RawRecordingCommandBuffer {
             }
         }
 
+        for (index, scissor) in scissors.iter().enumerate() {
+            let &Scissor { offset, extent } = scissor;
+
+            if i32::try_from(offset[0])
+                .ok()
+                .zip(i32::try_from(extent[0]).ok())
+                .and_then(|(o, e)| o.checked_add(e))
+                .is_none()
+            {
+                return Err(Box::new(ValidationError {
+                    context: format!("scissors[{}]", index).into(),
+                    problem: "`offset[0] + extent[0]` is greater than `i32::MAX`".into(),
+                    vuids: &[
+                        "VUID-vkCmdSetScissor-x-00595",
+                        "VUID-vkCmdSetScissor-offset-00596",
+                    ],
+                    ..Default::default()
+                }));
+            }
+
+            if i32::try_from(offset[1])
+                .ok()
+                .zip(i32::try_from(extent[1]).ok())
+                .and_then(|(o, e)| o.checked_add(e))
+                .is_none()
+            {
+                return Err(Box::new(ValidationError {
+                    context: format!("scissors[{}]", index).into(),
+                    problem: "`offset[1] + extent[1]` is greater than `i32::MAX`".into(),
+                    vuids: &[
+                        "VUID-vkCmdSetScissor-x-00595",
+                        "VUID-vkCmdSetScissor-offset-00597",
+                    ],
+                    ..Default::default()
+                }));
+            }
+        }
+
         Ok(())
     }
 
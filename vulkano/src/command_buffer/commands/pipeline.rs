@@ -2,30 +2,34 @@
 use crate::device::{DeviceFeatures, DeviceProperties};
 use crate::{
     acceleration_structure::AccelerationStructure,
-    buffer::{view::BufferView, BufferUsage, Subbuffer},
+    buffer::{view::BufferView, BufferUsage, IndexBuffer, Subbuffer},
     command_buffer::{
         auto::{RenderPassState, RenderPassStateType, Resource, ResourceUseRef2},
         sys::RawRecordingCommandBuffer,
         DispatchIndirectCommand, DrawIndexedIndirectCommand, DrawIndirectCommand,
-        DrawMeshTasksIndirectCommand, RecordingCommandBuffer, ResourceInCommand, SubpassContents,
+        DrawMeshTasksIndirectCommand, RecordingCommandBuffer, ResourceInCommand,
     },
     descriptor_set::{
-        layout::DescriptorType, DescriptorBindingResources, DescriptorBufferInfo,
-        DescriptorImageViewInfo,
+        layout::{DescriptorBindingFlags, DescriptorType},
+        DescriptorBindingResources, DescriptorBufferInfo, DescriptorImageViewInfo,
     },
     device::{DeviceOwned, QueueFlags},
-    format::{FormatFeatures, NumericType},
-    image::{sampler::Sampler, view::ImageView, ImageAspects, ImageLayout, SampleCount},
+    format::FormatFeatures,
+    image::{sampler::Sampler, view::ImageView, ImageLayout},
     pipeline::{
         graphics::{
             input_assembly::PrimitiveTopology,
             subpass::PipelineSubpassType,
             vertex_input::{RequiredVertexInputsVUIDs, VertexInputRate},
+            viewport::Scissor,
         },
-        DynamicState, GraphicsPipeline, Pipeline, PipelineLayout,
+        DynamicState, GraphicsPipeline, Pipeline, PipelineBindPoint, PipelineLayout,
     },
     query::QueryType,
-    shader::{DescriptorBindingRequirements, DescriptorIdentifier, ShaderStages},
+    shader::{
+        DescriptorBindingRequirements, DescriptorIdentifier, ImageViewMismatch, SamplerMismatch,
+        ShaderStages,
+    },
     sync::{PipelineStageAccess, PipelineStageAccessFlags},
     DeviceSize, Requires, RequiresAllOf, RequiresOneOf, ValidationError, Version, VulkanObject,
 };
@@ -66,7 +70,9 @@ impl RecordingCommandBuffer {
         &mut self,
         group_counts: [u32; 3],
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_dispatch(group_counts)?;
+        self.validate_dispatch(group_counts).map_err(|err| {
+            self.pipeline_command_error(err, "dispatch", PipelineBindPoint::Compute)
+        })?;
 
         unsafe { Ok(self.dispatch_unchecked(group_counts)) }
     }
@@ -141,7 +147,10 @@ impl RecordingCommandBuffer {
         &mut self,
         indirect_buffer: Subbuffer<[DispatchIndirectCommand]>,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_dispatch_indirect(indirect_buffer.as_bytes())?;
+        self.validate_dispatch_indirect(indirect_buffer.as_bytes())
+            .map_err(|err| {
+                self.pipeline_command_error(err, "dispatch_indirect", PipelineBindPoint::Compute)
+            })?;
 
         unsafe { Ok(self.dispatch_indirect_unchecked(indirect_buffer)) }
     }
@@ -229,7 +238,8 @@ impl RecordingCommandBuffer {
         first_vertex: u32,
         first_instance: u32,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_draw(vertex_count, instance_count, first_vertex, first_instance)?;
+        self.validate_draw(vertex_count, instance_count, first_vertex, first_instance)
+            .map_err(|err| self.pipeline_command_error(err, "draw", PipelineBindPoint::Graphics))?;
 
         unsafe {
             Ok(self.draw_unchecked(vertex_count, instance_count, first_vertex, first_instance))
@@ -303,7 +313,11 @@ impl RecordingCommandBuffer {
             .unwrap_or_else(|| pipeline.vertex_input_state().unwrap());
 
         for (&binding_num, binding_desc) in &vertex_input_state.bindings {
-            let vertex_buffer = &self.builder_state.vertex_buffers[&binding_num];
+            // Reading from a null vertex buffer always returns zero.
+            let Some(vertex_buffer) = &self.builder_state.vertex_buffers[&binding_num] else {
+                continue;
+            };
+            let stride = self.vertex_binding_stride(pipeline, binding_num, binding_desc.stride);
 
             // Per spec:
             // https://registry.khronos.org/vulkan/specs/1.3-extensions/html/chap22.html#fxvertex-input-address-calculation
@@ -311,7 +325,7 @@ impl RecordingCommandBuffer {
                 VertexInputRate::Vertex => {
                     let max_vertex_offset = (first_vertex as DeviceSize
                         + vertex_count as DeviceSize)
-                        * binding_desc.stride as DeviceSize;
+                        * stride as DeviceSize;
 
                     if max_vertex_offset > vertex_buffer.size() {
                         return Err(Box::new(ValidationError {
@@ -330,11 +344,11 @@ impl RecordingCommandBuffer {
                 }
                 VertexInputRate::Instance { divisor } => {
                     let max_vertex_offset = if divisor == 0 {
-                        (first_instance as DeviceSize + 1) * binding_desc.stride as DeviceSize
+                        (first_instance as DeviceSize + 1) * stride as DeviceSize
                     } else {
                         (first_instance as DeviceSize
                             + instance_count as DeviceSize / divisor as DeviceSize)
-                            * binding_desc.stride as DeviceSize
+                            * stride as DeviceSize
                     };
 
                     if max_vertex_offset > vertex_buffer.size() {
@@ -420,7 +434,10 @@ impl RecordingCommandBuffer {
     ) -> Result<&mut Self, Box<ValidationError>> {
         let draw_count = indirect_buffer.len() as u32;
         let stride = size_of::<DrawIndirectCommand>() as u32;
-        self.validate_draw_indirect(indirect_buffer.as_bytes(), draw_count, stride)?;
+        self.validate_draw_indirect(indirect_buffer.as_bytes(), draw_count, stride)
+            .map_err(|err| {
+                self.pipeline_command_error(err, "draw_indirect", PipelineBindPoint::Graphics)
+            })?;
 
         unsafe { Ok(self.draw_indirect_unchecked(indirect_buffer, draw_count, stride)) }
     }
@@ -536,7 +553,10 @@ impl RecordingCommandBuffer {
             count_buffer.as_bytes(),
             max_draw_count,
             stride,
-        )?;
+        )
+        .map_err(|err| {
+            self.pipeline_command_error(err, "draw_indirect_count", PipelineBindPoint::Graphics)
+        })?;
 
         unsafe {
             Ok(self.draw_indirect_count_unchecked(
@@ -679,7 +699,10 @@ impl RecordingCommandBuffer {
             first_index,
             vertex_offset,
             first_instance,
-        )?;
+        )
+        .map_err(|err| {
+            self.pipeline_command_error(err, "draw_indexed", PipelineBindPoint::Graphics)
+        })?;
 
         unsafe {
             Ok(self.draw_indexed_unchecked(
@@ -744,6 +767,27 @@ impl RecordingCommandBuffer {
             })
         })?;
 
+        self.validate_draw_indexed_params(
+            pipeline,
+            index_buffer,
+            index_count,
+            instance_count,
+            first_index,
+            first_instance,
+        )
+    }
+
+    // The checks of `validate_draw_indexed` that depend on the parameters of the draw, rather than
+    // only on the current state.
+    fn validate_draw_indexed_params(
+        &self,
+        pipeline: &GraphicsPipeline,
+        index_buffer: &IndexBuffer,
+        index_count: u32,
+        instance_count: u32,
+        first_index: u32,
+        first_instance: u32,
+    ) -> Result<(), Box<ValidationError>> {
         let index_buffer_bytes = index_buffer.as_bytes();
 
         if !self.device().enabled_features().robust_buffer_access2 {
@@ -794,7 +838,11 @@ impl RecordingCommandBuffer {
             .unwrap_or_else(|| pipeline.vertex_input_state().unwrap());
 
         for (&binding_num, binding_desc) in &vertex_input_state.bindings {
-            let vertex_buffer = &self.builder_state.vertex_buffers[&binding_num];
+            // Reading from a null vertex buffer always returns zero.
+            let Some(vertex_buffer) = &self.builder_state.vertex_buffers[&binding_num] else {
+                continue;
+            };
+            let stride = self.vertex_binding_stride(pipeline, binding_num, binding_desc.stride);
 
             // Per spec:
             // https://registry.khronos.org/vulkan/specs/1.3-extensions/html/chap22.html#fxvertex-input-address-calculation
@@ -802,11 +850,11 @@ impl RecordingCommandBuffer {
                 VertexInputRate::Vertex => (),
                 VertexInputRate::Instance { divisor } => {
                     let max_vertex_offset = if divisor == 0 {
-                        (first_instance as DeviceSize + 1) * binding_desc.stride as DeviceSize
+                        (first_instance as DeviceSize + 1) * stride as DeviceSize
                     } else {
                         (first_instance as DeviceSize
                             + instance_count as DeviceSize / divisor as DeviceSize)
-                            * binding_desc.stride as DeviceSize
+                            * stride as DeviceSize
                     };
 
                     if max_vertex_offset > vertex_buffer.size() {
@@ -874,6 +922,182 @@ impl RecordingCommandBuffer {
         self
     }
 
+    /// Perform a list of draw operations using a primitive shading graphics pipeline, using an
+    /// index buffer, each with its own scissor.
+    ///
+    /// This is equivalent to calling [`set_scissor`](Self::set_scissor) with `first_scissor` 0
+    /// and then [`draw_indexed`](Self::draw_indexed) for each element of `draws`, but is much
+    /// faster to record when there are many draws, such as when rendering a GUI. The scissor is
+    /// only set again when it differs from the one of the previous draw, and the current state is
+    /// validated and tracked once for the whole list, instead of once for every draw.
+    ///
+    /// The `vertex_offset` of each [`DrawIndexedIndirectCommand`] holds the bits of an `i32`, the
+    /// same as in an indirect buffer. A negative offset can be stored with `vertex_offset as u32`.
+    ///
+    /// The bound graphics pipeline must have the [`DynamicState::Scissor`] dynamic state. If it
+    /// has more than one scissor, the others must have been set beforehand. When this command
+    /// returns, scissor 0 is set to the scissor of the last element of `draws`.
+    ///
+    /// # Safety
+    ///
+    /// - The [safety requirements of `draw_indexed`](Self::draw_indexed#safety) apply to each
+    ///   draw.
+    pub unsafe fn draw_scissored_batches(
+        &mut self,
+        draws: &[(Scissor, DrawIndexedIndirectCommand)],
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        let Some(&(first_scissor, _)) = draws.first() else {
+            return Ok(self);
+        };
+
+        // The draws are validated against the state as it is after the first scissor is set.
+        let previous_scissor = self.builder_state.scissor.insert(0, first_scissor);
+
+        if let Err(err) = self.validate_draw_scissored_batches(draws) {
+            match previous_scissor {
+                Some(scissor) => self.builder_state.scissor.insert(0, scissor),
+                None => self.builder_state.scissor.remove(&0),
+            };
+
+            return Err(self.pipeline_command_error(
+                err,
+                "draw_scissored_batches",
+                PipelineBindPoint::Graphics,
+            ));
+        }
+
+        Ok(self.draw_scissored_batches_unchecked(draws))
+    }
+
+    fn validate_draw_scissored_batches(
+        &self,
+        draws: &[(Scissor, DrawIndexedIndirectCommand)],
+    ) -> Result<(), Box<ValidationError>> {
+        // The current state is the same for every draw, so it only needs to be validated with the
+        // first one.
+        let &(_, first_draw) = draws.first().unwrap();
+        let DrawIndexedIndirectCommand {
+            index_count,
+            instance_count,
+            first_index,
+            vertex_offset: _,
+            first_instance,
+        } = first_draw;
+
+        self.validate_draw_indexed(
+            index_count,
+            instance_count,
+            first_index,
+            signed_vertex_offset(&first_draw),
+            first_instance,
+        )
+        .map_err(|err| err.add_context("draws[0].1"))?;
+
+        let pipeline = self
+            .builder_state
+            .pipeline_graphics
+            .as_ref()
+            .unwrap()
+            .as_ref();
+        let index_buffer = self.builder_state.index_buffer.as_ref().unwrap();
+
+        if !pipeline.dynamic_state().contains(&DynamicState::Scissor) {
+            return Err(Box::new(ValidationError {
+                problem: "the currently bound graphics pipeline does not have the \
+                    `DynamicState::Scissor` dynamic state"
+                    .into(),
+                ..Default::default()
+            }));
+        }
+
+        for (index, &(scissor, draw)) in draws.iter().enumerate() {
+            self.inner
+                .validate_set_scissor(0, &[scissor])
+                .map_err(|err| err.add_context(format!("draws[{}].0", index)))?;
+
+            if index == 0 {
+                continue;
+            }
+
+            let DrawIndexedIndirectCommand {
+                index_count,
+                instance_count,
+                first_index,
+                vertex_offset: _,
+                first_instance,
+            } = draw;
+
+            self.validate_draw_indexed_params(
+                pipeline,
+                index_buffer,
+                index_count,
+                instance_count,
+                first_index,
+                first_instance,
+            )
+            .map_err(|err| err.add_context(format!("draws[{}].1", index)))?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn draw_scissored_batches_unchecked(
+        &mut self,
+        draws: &[(Scissor, DrawIndexedIndirectCommand)],
+    ) -> &mut Self {
+        let Some(&(last_scissor, _)) = draws.last() else {
+            return self;
+        };
+
+        if let RenderPassStateType::BeginRendering(state) =
+            &mut self.builder_state.render_pass.as_mut().unwrap().render_pass
+        {
+            state.pipeline_used = true;
+        }
+
+        self.builder_state.scissor.insert(0, last_scissor);
+
+        let pipeline = self
+            .builder_state
+            .pipeline_graphics
+            .as_ref()
+            .unwrap()
+            .as_ref();
+
+        let mut used_resources = Vec::new();
+        self.add_descriptor_sets_resources(&mut used_resources, pipeline);
+        self.add_vertex_buffers_resources(&mut used_resources, pipeline);
+        self.add_index_buffer_resources(&mut used_resources);
+
+        let draws = draws.to_vec();
+
+        self.add_command(
+            "draw_scissored_batches",
+            used_resources,
+            move |out: &mut RawRecordingCommandBuffer| {
+                let mut current_scissor = None;
+
+                for &(scissor, draw) in &draws {
+                    if current_scissor != Some(scissor) {
+                        out.set_scissor_unchecked(0, &[scissor]);
+                        current_scissor = Some(scissor);
+                    }
+
+                    out.draw_indexed_unchecked(
+                        draw.index_count,
+                        draw.instance_count,
+                        draw.first_index,
+                        signed_vertex_offset(&draw),
+                        draw.first_instance,
+                    );
+                }
+            },
+        );
+
+        self
+    }
+
     /// Perform multiple draw operations using a primitive shading graphics pipeline,
     /// using an index buffer.
     ///
@@ -907,7 +1131,14 @@ impl RecordingCommandBuffer {
     ) -> Result<&mut Self, Box<ValidationError>> {
         let draw_count = indirect_buffer.len() as u32;
         let stride = size_of::<DrawIndexedIndirectCommand>() as u32;
-        self.validate_draw_indexed_indirect(indirect_buffer.as_bytes(), draw_count, stride)?;
+        self.validate_draw_indexed_indirect(indirect_buffer.as_bytes(), draw_count, stride)
+            .map_err(|err| {
+                self.pipeline_command_error(
+                    err,
+                    "draw_indexed_indirect",
+                    PipelineBindPoint::Graphics,
+                )
+            })?;
 
         unsafe { Ok(self.draw_indexed_indirect_unchecked(indirect_buffer, draw_count, stride)) }
     }
@@ -1038,7 +1269,14 @@ impl RecordingCommandBuffer {
             count_buffer.as_bytes(),
             max_draw_count,
             stride,
-        )?;
+        )
+        .map_err(|err| {
+            self.pipeline_command_error(
+                err,
+                "draw_indexed_indirect_count",
+                PipelineBindPoint::Graphics,
+            )
+        })?;
 
         unsafe {
             Ok(self.draw_indexed_indirect_count_unchecked(
@@ -1161,7 +1399,9 @@ impl RecordingCommandBuffer {
         &mut self,
         group_counts: [u32; 3],
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_draw_mesh_tasks(group_counts)?;
+        self.validate_draw_mesh_tasks(group_counts).map_err(|err| {
+            self.pipeline_command_error(err, "draw_mesh_tasks", PipelineBindPoint::Graphics)
+        })?;
 
         unsafe { Ok(self.draw_mesh_tasks_unchecked(group_counts)) }
     }
@@ -1359,7 +1599,14 @@ impl RecordingCommandBuffer {
     ) -> Result<&mut Self, Box<ValidationError>> {
         let draw_count = indirect_buffer.len() as u32;
         let stride = size_of::<DrawMeshTasksIndirectCommand>() as u32;
-        self.validate_draw_mesh_tasks_indirect(indirect_buffer.as_bytes(), draw_count, stride)?;
+        self.validate_draw_mesh_tasks_indirect(indirect_buffer.as_bytes(), draw_count, stride)
+            .map_err(|err| {
+                self.pipeline_command_error(
+                    err,
+                    "draw_mesh_tasks_indirect",
+                    PipelineBindPoint::Graphics,
+                )
+            })?;
 
         unsafe { Ok(self.draw_mesh_tasks_indirect_unchecked(indirect_buffer, draw_count, stride)) }
     }
@@ -1483,7 +1730,14 @@ impl RecordingCommandBuffer {
             count_buffer.as_bytes(),
             max_draw_count,
             stride,
-        )?;
+        )
+        .map_err(|err| {
+            self.pipeline_command_error(
+                err,
+                "draw_mesh_tasks_indirect_count",
+                PipelineBindPoint::Graphics,
+            )
+        })?;
 
         unsafe {
             Ok(self.draw_mesh_tasks_indirect_count_unchecked(
@@ -1602,6 +1856,8 @@ impl RecordingCommandBuffer {
             set_num: u32,
             binding_num: u32,
             binding_reqs: &DescriptorBindingRequirements,
+            partially_bound: bool,
+            is_null: impl Fn(u32) -> bool,
             elements: &[Option<T>],
             mut extra_check: impl FnMut(u32, u32, u32, &T) -> Result<(), Box<ValidationError>>,
         ) -> Result<(), Box<ValidationError>> {
@@ -1634,6 +1890,11 @@ impl RecordingCommandBuffer {
 
                 let element = match element {
                     Some(x) => x,
+                    // Descriptors in a partially bound binding only need to be valid if they are
+                    // dynamically used, which can't be known here.
+                    None if partially_bound => continue,
+                    // Null descriptors are valid, but don't have any resource to check.
+                    None if is_null(index) => continue,
                     None => {
                         return Err(Box::new(ValidationError {
                             problem: format!(
@@ -1706,6 +1967,28 @@ impl RecordingCommandBuffer {
                         .chain(binding_reqs.descriptors.get(&None))
                     {
                         if layout_binding.descriptor_type == DescriptorType::StorageTexelBuffer {
+                            // The SPIR-V Image Format is not compatible with the buffer view's
+                            // format.
+                            if let Some(required_format) = binding_reqs.image_format {
+                                let format = buffer_view.format();
+                                if format != required_format {
+                                    return Err(Box::new(ValidationError {
+                                        problem: format!(
+                                            "the currently bound pipeline declares the texel \
+                                            buffer at descriptor set {set_num}, binding \
+                                            {binding_num} with the image format `{}`, which is \
+                                            compatible only with `{required_format:?}`, but the \
+                                            buffer view bound to descriptor index {index} has \
+                                            the format `{format:?}`",
+                                            required_format.glsl_image_format_qualifier().unwrap(),
+                                        )
+                                        .into(),
+                                        // vuids?
+                                        ..Default::default()
+                                    }));
+                                }
+                            }
+
                             if binding_reqs.image_format.is_none()
                                 && !desc_reqs.memory_write.is_empty()
                                 && !buffer_view
@@ -1753,284 +2036,187 @@ impl RecordingCommandBuffer {
 
             let check_image_view_common =
                 |set_num: u32, binding_num: u32, index: u32, image_view: &Arc<ImageView>| {
-                    for desc_reqs in binding_reqs
-                        .descriptors
-                        .get(&Some(index))
-                        .into_iter()
-                        .chain(binding_reqs.descriptors.get(&None))
-                    {
-                        if desc_reqs.storage_image_atomic
-                            && !image_view
-                                .format_features()
-                                .intersects(FormatFeatures::STORAGE_IMAGE_ATOMIC)
-                        {
-                            return Err(Box::new(ValidationError {
-                                problem: format!(
-                                    "the currently bound pipeline performs atomic operations on \
-                                    the image view bound to descriptor set {set_num}, \
-                                    binding {binding_num}, descriptor index {index}, but \
-                                    the format features of the image view's format do \
-                                    not contain `FormatFeatures::STORAGE_IMAGE_ATOMIC`"
-                                )
-                                .into(),
-                                vuids: vuids!(vuid_type, "None-02691"),
-                                ..Default::default()
-                            }));
-                        }
-
-                        if layout_binding.descriptor_type == DescriptorType::StorageImage {
-                            if binding_reqs.image_format.is_none()
-                                && !desc_reqs.memory_write.is_empty()
-                                && !image_view
-                                    .format_features()
-                                    .intersects(FormatFeatures::STORAGE_WRITE_WITHOUT_FORMAT)
-                            {
-                                return Err(Box::new(ValidationError {
-                                    problem: format!(
+                    let storage_image =
+                        layout_binding.descriptor_type == DescriptorType::StorageImage;
+
+                    binding_reqs
+                        .check_image_view(index, image_view, storage_image)
+                        .map_err(|mismatch| {
+                            let (problem, vuids): (_, &[_]) = match mismatch {
+                                ImageViewMismatch::StorageImageAtomic => (
+                                    format!(
+                                        "the currently bound pipeline performs atomic operations \
+                                        on the image view bound to descriptor set {set_num}, \
+                                        binding {binding_num}, descriptor index {index}, but the \
+                                        format features of the image view's format do not \
+                                        contain `FormatFeatures::STORAGE_IMAGE_ATOMIC`"
+                                    ),
+                                    vuids!(vuid_type, "None-02691"),
+                                ),
+                                ImageViewMismatch::StorageWriteWithoutFormat => (
+                                    format!(
                                         "the currently bound pipeline writes to the image view \
                                         bound to descriptor set {set_num}, binding {binding_num}, \
                                         descriptor index {index}, without specifying a format, \
                                         but the format features of the image view's format do \
                                         not contain `FormatFeatures::STORAGE_WRITE_WITHOUT_FORMAT`"
-                                    )
-                                    .into(),
-                                    vuids: vuids!(vuid_type, "OpTypeImage-06423"),
-                                    ..Default::default()
-                                }));
-                            }
-
-                            if binding_reqs.image_format.is_none()
-                                && !desc_reqs.memory_read.is_empty()
-                                && !image_view
-                                    .format_features()
-                                    .intersects(FormatFeatures::STORAGE_READ_WITHOUT_FORMAT)
-                            {
-                                return Err(Box::new(ValidationError {
-                                    problem: format!(
+                                    ),
+                                    vuids!(vuid_type, "OpTypeImage-06423"),
+                                ),
+                                ImageViewMismatch::StorageReadWithoutFormat => (
+                                    format!(
                                         "the currently bound pipeline reads from the image view \
                                         bound to descriptor set {set_num}, binding {binding_num}, \
                                         descriptor index {index}, without specifying a format, \
                                         but the format features of the image view's format do \
                                         not contain `FormatFeatures::STORAGE_READ_WITHOUT_FORMAT`"
-                                    )
-                                    .into(),
-                                    vuids: vuids!(vuid_type, "OpTypeImage-06424"),
-                                    ..Default::default()
-                                }));
-                            }
-                        }
-                    }
-
-                    /*
-                       Instruction/Sampler/Image View Validation
-                       https://registry.khronos.org/vulkan/specs/1.3-extensions/html/chap16.html#textures-input-validation
-                    */
-
-                    // The SPIR-V Image Format is not compatible with the image view’s format.
-                    if let Some(required_format) = binding_reqs.image_format {
-                        let format = image_view.format();
-                        if format != required_format {
-                            return Err(Box::new(ValidationError {
-                                problem: format!(
-                                    "the currently bound pipeline requires the image view \
-                                    bound to descriptor set {set_num}, binding {binding_num}, \
-                                    descriptor index {index} to have a format of `{required_format:?}`, \
-                                    but the actual format is `{format:?}`"
-                                )
-                                .into(),
-                                // vuids?
-                                ..Default::default()
-                            }));
-                        }
-                    }
-
-                    // Rules for viewType
-                    if let Some(required_image_view_type) = binding_reqs.image_view_type {
-                        let image_view_type = image_view.view_type();
-                        if image_view_type != required_image_view_type {
-                            return Err(Box::new(ValidationError {
-                                problem: format!(
-                                    "the currently bound pipeline requires the image view \
-                                    bound to descriptor set {set_num}, binding {binding_num}, \
-                                    descriptor index {index} to have a view type of `{required_image_view_type:?}`, \
-                                    but the actual view type is `{image_view_type:?}`
-                                    "
-                                )
-                                .into(),
-                                vuids: vuids!(vuid_type, "viewType-07752"),
-                                ..Default::default()
-                            }));
-                        }
-                    }
-
-                    // - If the image was created with VkImageCreateInfo::samples equal to
-                    //   VK_SAMPLE_COUNT_1_BIT, the instruction must have MS = 0.
-                    // - If the image was created with VkImageCreateInfo::samples not equal to
-                    //   VK_SAMPLE_COUNT_1_BIT, the instruction must have MS = 1.
-                    if binding_reqs.image_multisampled
-                        && image_view.image().samples() == SampleCount::Sample1
-                    {
-                        return Err(Box::new(ValidationError {
-                            problem: format!(
-                                "the currently bound pipeline accesses the image view \
-                                bound to descriptor set {set_num}, binding {binding_num}, \
-                                descriptor index {index}, and the pipeline requires a \
-                                multisampled image, but the image view has only one sample"
-                            )
-                            .into(),
-                            // vuids?
-                            ..Default::default()
-                        }));
-                    } else if !binding_reqs.image_multisampled
-                        && image_view.image().samples() != SampleCount::Sample1
-                    {
-                        return Err(Box::new(ValidationError {
-                            problem: format!(
-                                "the currently bound pipeline accesses the image view \
-                                bound to descriptor set {set_num}, binding {binding_num}, \
-                                descriptor index {index}, and the pipeline requires a non-\
-                                multisampled image, but the image view has more than one sample"
-                            )
-                            .into(),
-                            // vuids?
-                            ..Default::default()
-                        }));
-                    }
-
-                    // - If the Sampled Type of the OpTypeImage does not match the numeric format of
-                    //   the image, as shown in the SPIR-V Sampled Type column of the Interpretation
-                    //   of Numeric Format table.
-                    // - If the signedness of any read or sample operation does not match the
-                    //   signedness of the image’s format.
-                    if let Some(shader_numeric_type) = binding_reqs.image_scalar_type {
-                        let aspects = image_view.subresource_range().aspects;
-                        let view_numeric_type = NumericType::from(
-                            if aspects.intersects(
-                                ImageAspects::COLOR
-                                    | ImageAspects::PLANE_0
-                                    | ImageAspects::PLANE_1
-                                    | ImageAspects::PLANE_2,
-                            ) {
-                                image_view.format().numeric_format_color().unwrap()
-                            } else if aspects.intersects(ImageAspects::DEPTH) {
-                                image_view.format().numeric_format_depth().unwrap()
-                            } else if aspects.intersects(ImageAspects::STENCIL) {
-                                image_view.format().numeric_format_stencil().unwrap()
-                            } else {
-                                // Per `ImageViewBuilder::aspects` and
-                                // VUID-VkDescriptorImageInfo-imageView-01976
-                                unreachable!()
-                            },
-                        );
+                                    ),
+                                    vuids!(vuid_type, "OpTypeImage-06424"),
+                                ),
+                                // The SPIR-V Image Format is not compatible with the image view’s
+                                // format.
+                                ImageViewMismatch::Format { required, actual } => (
+                                    format!(
+                                        "the currently bound pipeline declares the image at \
+                                        descriptor set {set_num}, binding {binding_num} with the \
+                                        image format `{}`, which is compatible only with \
+                                        `{required:?}`, but the image view bound to descriptor \
+                                        index {index} has the format `{actual:?}`",
+                                        required.glsl_image_format_qualifier().unwrap(),
+                                    ),
+                                    // vuids?
+                                    &[][..],
+                                ),
+                                ImageViewMismatch::ViewType { required, actual } => (
+                                    format!(
+                                        "the currently bound pipeline requires the image view \
+                                        bound to descriptor set {set_num}, binding {binding_num}, \
+                                        descriptor index {index} to have a view type of \
+                                        `{required:?}`, but the actual view type is `{actual:?}`"
+                                    ),
+                                    vuids!(vuid_type, "viewType-07752"),
+                                ),
+                                // - If the image was created with VkImageCreateInfo::samples equal
+                                //   to VK_SAMPLE_COUNT_1_BIT, the instruction must have MS = 0.
+                                // - If the image was created with VkImageCreateInfo::samples not
+                                //   equal to VK_SAMPLE_COUNT_1_BIT, the instruction must have MS =
+                                //   1.
+                                ImageViewMismatch::Multisampled { required: true } => (
+                                    format!(
+                                        "the currently bound pipeline accesses the image view \
+                                        bound to descriptor set {set_num}, binding {binding_num}, \
+                                        descriptor index {index}, and the pipeline requires a \
+                                        multisampled image, but the image view has only one sample"
+                                    ),
+                                    // vuids?
+                                    &[][..],
+                                ),
+                                ImageViewMismatch::Multisampled { required: false } => (
+                                    format!(
+                                        "the currently bound pipeline accesses the image view \
+                                        bound to descriptor set {set_num}, binding {binding_num}, \
+                                        descriptor index {index}, and the pipeline requires a \
+                                        non-multisampled image, but the image view has more than \
+                                        one sample"
+                                    ),
+                                    // vuids?
+                                    &[][..],
+                                ),
+                                // - If the Sampled Type of the OpTypeImage does not match the
+                                //   numeric format of the image, as shown in the SPIR-V Sampled
+                                //   Type column of the Interpretation of Numeric Format table.
+                                // - If the signedness of any read or sample operation does not
+                                //   match the signedness of the image’s format.
+                                ImageViewMismatch::NumericType { required, actual } => (
+                                    format!(
+                                        "the currently bound pipeline accesses the image view \
+                                        bound to descriptor set {set_num}, binding {binding_num}, \
+                                        descriptor index {index}, and the pipeline requires an \
+                                        image view whose format has a `{required:?}` numeric \
+                                        type, but the format of the image view has a \
+                                        `{actual:?}` numeric type"
+                                    ),
+                                    vuids!(vuid_type, "format-07753"),
+                                ),
+                            };
 
-                        if shader_numeric_type != view_numeric_type {
-                            return Err(Box::new(ValidationError {
-                                problem: format!(
-                                    "the currently bound pipeline accesses the image view \
-                                    bound to descriptor set {set_num}, binding {binding_num}, \
-                                    descriptor index {index}, and the pipeline requires an \
-                                    image view whose format has a `{shader_numeric_type:?}` \
-                                    numeric type, but the format of the image view has a \
-                                    `{view_numeric_type:?}` numeric type"
-                                )
-                                .into(),
-                                vuids: vuids!(vuid_type, "format-07753"),
+                            Box::new(ValidationError {
+                                problem: problem.into(),
+                                vuids,
                                 ..Default::default()
-                            }));
-                        }
-                    }
-
-                    Ok(())
+                            })
+                        })
                 };
 
             let check_sampler_common =
                 |set_num: u32, binding_num: u32, index: u32, sampler: &Arc<Sampler>| {
-                    for desc_reqs in binding_reqs
-                        .descriptors
-                        .get(&Some(index))
-                        .into_iter()
-                        .chain(binding_reqs.descriptors.get(&None))
-                    {
-                        if desc_reqs.sampler_no_unnormalized_coordinates
-                            && sampler.unnormalized_coordinates()
-                        {
-                            return Err(Box::new(ValidationError {
-                                problem: format!(
-                                    "the currently bound pipeline accesses the sampler bound to \
-                                    descriptor set {set_num}, binding {binding_num}, \
-                                    descriptor index {index}, in a way that does not support \
-                                    samplers with unnormalized coordinates, but \
-                                    the sampler currently bound to that descriptor \
-                                    uses unnormalized coordinates"
-                                )
-                                .into(),
-                                vuids: vuids!(vuid_type, "None-02703", "None-02704"),
-                                ..Default::default()
-                            }));
-                        }
-
-                        // - OpImageFetch, OpImageSparseFetch, OpImage*Gather, and
-                        //   OpImageSparse*Gather must not be used with a sampler that enables
-                        //   sampler Y′CBCR conversion.
-                        // - The ConstOffset and Offset operands must not be used with a sampler
-                        //   that enables sampler Y′CBCR conversion.
-                        if desc_reqs.sampler_no_ycbcr_conversion
-                            && sampler.sampler_ycbcr_conversion().is_some()
-                        {
-                            return Err(Box::new(ValidationError {
-                                problem: format!(
-                                    "the currently bound pipeline accesses the sampler bound to \
-                                    descriptor set {set_num}, binding {binding_num}, \
-                                    descriptor index {index}, in a way that does not support \
-                                    samplers with a sampler YCbCr conversion, but the sampler \
-                                    currently bound to that descriptor has a \
-                                    sampler YCbCr conversion"
-                                )
-                                .into(),
-                                vuids: vuids!(vuid_type, "None-06550", "ConstOffset-06551"),
-                                ..Default::default()
-                            }));
-                        }
-
-                        /*
-                            Instruction/Sampler/Image View Validation
-                            https://registry.khronos.org/vulkan/specs/1.3-extensions/html/chap16.html#textures-input-validation
-                        */
-
-                        if desc_reqs.sampler_compare && sampler.compare().is_none() {
-                            // - The SPIR-V instruction is one of the OpImage*Dref* instructions and
-                            //   the sampler compareEnable is VK_FALSE
-                            return Err(Box::new(ValidationError {
-                                problem: format!(
-                                    "the currently bound pipeline accesses the sampler bound to \
-                                    descriptor set {set_num}, binding {binding_num}, \
-                                    descriptor index {index}, in a way that requires a sampler \
-                                    with a compare operation, but the sampler currently bound to \
-                                    that descriptor does not have a compare operation"
-                                )
-                                .into(),
-                                // vuids?
-                                ..Default::default()
-                            }));
-                        } else if !desc_reqs.sampler_compare && sampler.compare().is_some() {
-                            // - The SPIR-V instruction is not one of the OpImage*Dref* instructions
-                            //   and the sampler compareEnable is VK_TRUE
-                            return Err(Box::new(ValidationError {
-                                problem: format!(
-                                    "the currently bound pipeline accesses the sampler bound to \
-                                    descriptor set {set_num}, binding {binding_num}, \
-                                    descriptor index {index}, in a way that requires a sampler \
-                                    without a compare operation, but the sampler currently bound \
-                                    to that descriptor has a compare operation"
-                                )
-                                .into(),
-                                // vuids?
-                                ..Default::default()
-                            }));
-                        }
-                    }
+                    binding_reqs
+                        .check_sampler(index, sampler)
+                        .map_err(|mismatch| {
+                            let (problem, vuids): (_, &[_]) = match mismatch {
+                                SamplerMismatch::UnnormalizedCoordinates => (
+                                    format!(
+                                        "the currently bound pipeline accesses the sampler bound \
+                                        to descriptor set {set_num}, binding {binding_num}, \
+                                        descriptor index {index}, in a way that does not support \
+                                        samplers with unnormalized coordinates, but the sampler \
+                                        currently bound to that descriptor uses unnormalized \
+                                        coordinates"
+                                    ),
+                                    vuids!(vuid_type, "None-02703", "None-02704"),
+                                ),
+                                // - OpImageFetch, OpImageSparseFetch, OpImage*Gather, and
+                                //   OpImageSparse*Gather must not be used with a sampler that
+                                //   enables sampler Y′CBCR conversion.
+                                // - The ConstOffset and Offset operands must not be used with a
+                                //   sampler that enables sampler Y′CBCR conversion.
+                                SamplerMismatch::YcbcrConversion => (
+                                    format!(
+                                        "the currently bound pipeline accesses the sampler bound \
+                                        to descriptor set {set_num}, binding {binding_num}, \
+                                        descriptor index {index}, in a way that does not support \
+                                        samplers with a sampler YCbCr conversion, but the sampler \
+                                        currently bound to that descriptor has a sampler YCbCr \
+                                        conversion"
+                                    ),
+                                    vuids!(vuid_type, "None-06550", "ConstOffset-06551"),
+                                ),
+                                // The SPIR-V instruction is one of the OpImage*Dref* instructions
+                                // and the sampler compareEnable is VK_FALSE
+                                SamplerMismatch::Compare { required: true } => (
+                                    format!(
+                                        "the currently bound pipeline accesses the sampler bound \
+                                        to descriptor set {set_num}, binding {binding_num}, \
+                                        descriptor index {index}, in a way that requires a \
+                                        sampler with a compare operation, but the sampler \
+                                        currently bound to that descriptor does not have a \
+                                        compare operation"
+                                    ),
+                                    // vuids?
+                                    &[][..],
+                                ),
+                                // The SPIR-V instruction is not one of the OpImage*Dref*
+                                // instructions and the sampler compareEnable is VK_TRUE
+                                SamplerMismatch::Compare { required: false } => (
+                                    format!(
+                                        "the currently bound pipeline accesses the sampler bound \
+                                        to descriptor set {set_num}, binding {binding_num}, \
+                                        descriptor index {index}, in a way that requires a \
+                                        sampler without a compare operation, but the sampler \
+                                        currently bound to that descriptor has a compare \
+                                        operation"
+                                    ),
+                                    // vuids?
+                                    &[][..],
+                                ),
+                            };
 
-                    Ok(())
+                            Box::new(ValidationError {
+                                problem: problem.into(),
+                                vuids,
+                                ..Default::default()
+                            })
+                        })
                 };
 
             let check_image_view =
@@ -2163,6 +2349,9 @@ impl RecordingCommandBuffer {
                 .resources();
 
             let binding_resources = set_resources.binding(binding_num).unwrap();
+            let partially_bound = layout_binding
+                .binding_flags
+                .intersects(DescriptorBindingFlags::PARTIALLY_BOUND);
 
             match binding_resources {
                 DescriptorBindingResources::None(elements) => {
@@ -2171,6 +2360,8 @@ impl RecordingCommandBuffer {
                         set_num,
                         binding_num,
                         binding_reqs,
+                        partially_bound,
+                        |index| set_resources.is_null(binding_num, index),
                         elements,
                         check_none,
                     )?;
@@ -2181,6 +2372,8 @@ impl RecordingCommandBuffer {
                         set_num,
                         binding_num,
                         binding_reqs,
+                        partially_bound,
+                        |index| set_resources.is_null(binding_num, index),
                         elements,
                         check_buffer,
                     )?;
@@ -2191,6 +2384,8 @@ impl RecordingCommandBuffer {
                         set_num,
                         binding_num,
                         binding_reqs,
+                        partially_bound,
+                        |index| set_resources.is_null(binding_num, index),
                         elements,
                         check_buffer_view,
                     )?;
@@ -2201,6 +2396,8 @@ impl RecordingCommandBuffer {
                         set_num,
                         binding_num,
                         binding_reqs,
+                        partially_bound,
+                        |index| set_resources.is_null(binding_num, index),
                         elements,
                         check_image_view,
                     )?;
@@ -2211,6 +2408,8 @@ impl RecordingCommandBuffer {
                         set_num,
                         binding_num,
                         binding_reqs,
+                        partially_bound,
+                        |index| set_resources.is_null(binding_num, index),
                         elements,
                         check_image_view_sampler,
                     )?;
@@ -2221,6 +2420,8 @@ impl RecordingCommandBuffer {
                         set_num,
                         binding_num,
                         binding_reqs,
+                        partially_bound,
+                        |index| set_resources.is_null(binding_num, index),
                         elements,
                         check_sampler,
                     )?;
@@ -2238,6 +2439,8 @@ impl RecordingCommandBuffer {
                         set_num,
                         binding_num,
                         binding_reqs,
+                        partially_bound,
+                        |index| set_resources.is_null(binding_num, index),
                         elements,
                         check_acceleration_structure,
                     )?;
@@ -2526,13 +2729,25 @@ impl RecordingCommandBuffer {
                     }
                 }
                 DynamicState::DepthBounds => {
-                    if self.builder_state.depth_bounds.is_none() {
+                    // The bounds are only used if the depth bounds test is enabled.
+                    let depth_bounds_test_enable = if pipeline
+                        .dynamic_state()
+                        .contains(&DynamicState::DepthBoundsTestEnable)
+                    {
+                        self.builder_state.depth_bounds_test_enable == Some(true)
+                    } else {
+                        pipeline
+                            .depth_stencil_state()
+                            .map_or(false, |state| state.depth_bounds.is_some())
+                    };
+
+                    if depth_bounds_test_enable && self.builder_state.depth_bounds.is_none() {
                         return Err(Box::new(ValidationError {
                             problem: format!(
                                 "the currently bound graphics pipeline requires the \
-                                `DynamicState::{:?}` dynamic state, but \
-                                this state was either not set, or it was overwritten by a \
-                                more recent `bind_pipeline_graphics` command",
+                                `DynamicState::{:?}` dynamic state, and the depth bounds test \
+                                is enabled, but this state was either not set, or it was \
+                                overwritten by a more recent `bind_pipeline_graphics` command",
                                 dynamic_state
                             )
                             .into(),
@@ -3117,7 +3332,65 @@ impl RecordingCommandBuffer {
                         }));
                     }
                 }
-                // DynamicState::VertexInputBindingStride => todo!(),
+                DynamicState::VertexInputBindingStride => {
+                    // The strides in a dynamic vertex input state take precedence.
+                    if pipeline
+                        .dynamic_state()
+                        .contains(&DynamicState::VertexInput)
+                    {
+                        continue;
+                    }
+
+                    let vertex_input_state = pipeline.vertex_input_state().unwrap();
+
+                    for &binding_num in vertex_input_state.bindings.keys() {
+                        let Some(&stride) = self
+                            .builder_state
+                            .vertex_input_binding_stride
+                            .get(&binding_num)
+                        else {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "the currently bound graphics pipeline requires the \
+                                    `DynamicState::{:?}` dynamic state, but \
+                                    no stride was set for vertex buffer binding {}, or it was \
+                                    overwritten by a more recent `bind_pipeline_graphics` \
+                                    command",
+                                    dynamic_state, binding_num,
+                                )
+                                .into(),
+                                vuids: vuids!(vuid_type, "pStrides-04884"),
+                                ..Default::default()
+                            }));
+                        };
+
+                        if stride == 0 {
+                            continue;
+                        }
+
+                        for attribute_desc in vertex_input_state.attributes.values() {
+                            if attribute_desc.binding == binding_num
+                                && attribute_desc.offset as DeviceSize
+                                    + attribute_desc.format.block_size()
+                                    > stride as DeviceSize
+                            {
+                                return Err(Box::new(ValidationError {
+                                    problem: format!(
+                                        "the currently bound graphics pipeline requires the \
+                                        `DynamicState::{:?}` dynamic state, but \
+                                        the dynamic stride of vertex buffer binding {} is not \
+                                        zero, and is less than the offset plus the format size \
+                                        of a vertex attribute that is read from that binding",
+                                        dynamic_state, binding_num,
+                                    )
+                                    .into(),
+                                    vuids: &["VUID-vkCmdBindVertexBuffers2-pStrides-06209"],
+                                    ..Default::default()
+                                }));
+                            }
+                        }
+                    }
+                }
                 DynamicState::Viewport => {
                     let viewport_state = pipeline.viewport_state().unwrap();
 
@@ -3167,8 +3440,8 @@ impl RecordingCommandBuffer {
                                             "the currently bound graphics pipeline requires both \
                                             the `DynamicState::ViewportWithCount` and the \
                                             `DynamicState::ScissorWithCount` dynamic states, but \
-                                            the dynamic scissor count is not equal to the \
-                                            dynamic scissor count "
+                                            the dynamic viewport count is not equal to the \
+                                            dynamic scissor count"
                                                 .into(),
                                         vuids: vuids!(vuid_type, "viewportCount-03419"),
                                         ..Default::default()
@@ -3268,11 +3541,15 @@ impl RecordingCommandBuffer {
         pipeline: &GraphicsPipeline,
         render_pass_state: &RenderPassState,
     ) -> Result<(), Box<ValidationError>> {
-        if render_pass_state.contents != SubpassContents::Inline {
+        if !render_pass_state.contents.allows_inline() {
             return Err(Box::new(ValidationError {
-                problem: "the contents of the current subpass instance is not \
-                    `SubpassContents::Inline`"
-                    .into(),
+                problem: format!(
+                    "the contents of subpass {} of the current render pass instance is \
+                    `SubpassContents::{:?}`, which does not allow inline commands",
+                    render_pass_state.subpass_index(),
+                    render_pass_state.contents,
+                )
+                .into(),
                 // vuids?
                 ..Default::default()
             }));
@@ -3403,6 +3680,28 @@ impl RecordingCommandBuffer {
                     }
                 }
 
+                if let Some(rasterization_samples) = render_pass_state
+                    .rendering_info
+                    .multisampled_render_to_single_sampled
+                {
+                    if pipeline
+                        .multisample_state()
+                        .map_or(false, |multisample_state| {
+                            multisample_state.rasterization_samples != rasterization_samples
+                        })
+                    {
+                        return Err(Box::new(ValidationError {
+                            problem: "the current render pass instance has \
+                                `multisampled_render_to_single_sampled` set, but the \
+                                `rasterization_samples` of the currently bound graphics pipeline \
+                                is not equal to it"
+                                .into(),
+                            vuids: vuids!(vuid_type, "multisampledRenderToSingleSampled-07285"),
+                            ..Default::default()
+                        }));
+                    }
+                }
+
                 // VUID-vkCmdDraw-imageView-06172
                 // VUID-vkCmdDraw-imageView-06173
                 // VUID-vkCmdDraw-imageView-06174
@@ -3463,8 +3762,6 @@ impl RecordingCommandBuffer {
                 continue;
             }
 
-            let default_image_layout = descriptor_type.default_image_layout();
-
             let use_iter = move |index: u32| {
                 let (stages_read, stages_write) = [Some(index), None]
                     .into_iter()
@@ -3571,7 +3868,7 @@ impl RecordingCommandBuffer {
                             } = image_view_info;
 
                             if image_layout == ImageLayout::Undefined {
-                                image_layout = default_image_layout;
+                                image_layout = image_view.descriptor_usage_layout(descriptor_type);
                             }
 
                             let (use_ref, memory_access) = use_iter(index as u32);
@@ -3598,7 +3895,7 @@ impl RecordingCommandBuffer {
                             } = image_view_info;
 
                             if image_layout == ImageLayout::Undefined {
-                                image_layout = default_image_layout;
+                                image_layout = image_view.descriptor_usage_layout(descriptor_type);
                             }
 
                             let (use_ref, memory_access) = use_iter(index as u32);
@@ -3639,6 +3936,30 @@ impl RecordingCommandBuffer {
         }
     }
 
+    /// Returns the stride that a draw command uses for a vertex buffer binding.
+    fn vertex_binding_stride(
+        &self,
+        pipeline: &GraphicsPipeline,
+        binding_num: u32,
+        pipeline_stride: u32,
+    ) -> u32 {
+        let dynamic_state = pipeline.dynamic_state();
+
+        if dynamic_state.contains(&DynamicState::VertexInputBindingStride)
+            && !dynamic_state.contains(&DynamicState::VertexInput)
+        {
+            // Draw commands are rejected if no stride was set, so only the unchecked commands can
+            // end up using the pipeline's stride.
+            self.builder_state
+                .vertex_input_binding_stride
+                .get(&binding_num)
+                .copied()
+                .unwrap_or(pipeline_stride)
+        } else {
+            pipeline_stride
+        }
+    }
+
     fn add_vertex_buffers_resources(
         &self,
         used_resources: &mut Vec<(ResourceUseRef2, Resource)>,
@@ -3650,18 +3971,23 @@ impl RecordingCommandBuffer {
             .then(|| self.builder_state.vertex_input.as_ref().unwrap())
             .unwrap_or_else(|| pipeline.vertex_input_state().unwrap());
 
-        used_resources.extend(vertex_input_state.bindings.iter().map(|(&binding, _)| {
-            let vertex_buffer = &self.builder_state.vertex_buffers[&binding];
-            (
-                ResourceInCommand::VertexBuffer { binding }.into(),
-                Resource::Buffer {
-                    buffer: vertex_buffer.clone(),
-                    range: 0..vertex_buffer.size(), // TODO:
-                    memory_access:
-                        PipelineStageAccessFlags::VertexAttributeInput_VertexAttributeRead,
-                },
-            )
-        }));
+        used_resources.extend(
+            vertex_input_state
+                .bindings
+                .iter()
+                .filter_map(|(&binding, _)| {
+                    let vertex_buffer = self.builder_state.vertex_buffers[&binding].as_ref()?;
+                    Some((
+                        ResourceInCommand::VertexBuffer { binding }.into(),
+                        Resource::Buffer {
+                            buffer: vertex_buffer.clone(),
+                            range: 0..vertex_buffer.size(), // TODO:
+                            memory_access:
+                                PipelineStageAccessFlags::VertexAttributeInput_VertexAttributeRead,
+                        },
+                    ))
+                }),
+        );
     }
 
     fn add_index_buffer_resources(&self, used_resources: &mut Vec<(ResourceUseRef2, Resource)>) {
@@ -4925,6 +5251,12 @@ impl RawRecordingCommandBuffer {
     }
 }
 
+// `DrawIndexedIndirectCommand::vertex_offset` has the layout of the `int32_t vertexOffset` member
+// of `VkDrawIndexedIndirectCommand`, so its bits are read back as an `i32`.
+fn signed_vertex_offset(draw: &DrawIndexedIndirectCommand) -> i32 {
+    i32::from_ne_bytes(draw.vertex_offset.to_ne_bytes())
+}
+
 #[derive(Clone, Copy)]
 enum VUIDType {
     Dispatch,
@@ -4939,3 +5271,818 @@ enum VUIDType {
     DrawMeshTasksIndirect,
     DrawMeshTasksIndirectCount,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        buffer::{Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer},
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, CommandBufferBeginInfo, CommandBufferLevel,
+            CommandBufferUsage, CopyImageToBufferInfo, DrawIndexedIndirectCommand,
+            RecordingCommandBuffer, RenderPassBeginInfo, RenderingAttachmentInfo, RenderingInfo,
+        },
+        descriptor_set::{
+            allocator::StandardDescriptorSetAllocator, DescriptorSet, WriteDescriptorSet,
+        },
+        device::{Device, DeviceExtensions, DeviceFeatures, Queue},
+        format::Format,
+        image::{
+            view::ImageView, Image, ImageCreateFlags, ImageCreateInfo, ImageUsage, SampleCount,
+        },
+        memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
+        pipeline::{
+            graphics::{
+                color_blend::{ColorBlendAttachmentState, ColorBlendState},
+                input_assembly::InputAssemblyState,
+                multisample::MultisampleState,
+                rasterization::RasterizationState,
+                subpass::{PipelineRenderingCreateInfo, PipelineSubpassType},
+                vertex_input::{
+                    VertexInputAttributeDescription, VertexInputBindingDescription,
+                    VertexInputRate, VertexInputState,
+                },
+                viewport::{Scissor, Viewport, ViewportState},
+                GraphicsPipelineCreateInfo,
+            },
+            layout::PipelineDescriptorSetLayoutCreateInfo,
+            DynamicState, GraphicsPipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+            PipelineShaderStageCreateInfo,
+        },
+        render_pass::{
+            AttachmentLoadOp, AttachmentStoreOp, Framebuffer, FramebufferCreateInfo, Subpass,
+        },
+        shader::{EntryPoint, ShaderModule, ShaderModuleCreateInfo},
+        single_pass_renderpass,
+        sync::{now, GpuFuture},
+        Validated,
+    };
+    use std::sync::Arc;
+
+    // Renders to a 2×1 color attachment, with a pipeline that reads a `vec2` position from
+    // vertex buffer binding 0 and outputs red.
+    struct DrawTest {
+        device: Arc<Device>,
+        queue: Arc<Queue>,
+        memory_allocator: Arc<StandardMemoryAllocator>,
+        color: Arc<ImageView>,
+        framebuffer: Arc<Framebuffer>,
+        vs: EntryPoint,
+        fs: EntryPoint,
+    }
+
+    impl DrawTest {
+        fn new(features: DeviceFeatures) -> Option<Self> {
+            Self::with_extensions(DeviceExtensions::empty(), features)
+        }
+
+        fn with_extensions(extensions: DeviceExtensions, features: DeviceFeatures) -> Option<Self> {
+            let (device, queue) = crate::testing::test_device(
+                &extensions,
+                &(features
+                    | DeviceFeatures {
+                        shader_clip_distance: true,
+                        shader_cull_distance: true,
+                        ..DeviceFeatures::empty()
+                    }),
+            )?;
+
+            let (vs, fs) = unsafe {
+                /*
+                #version 450
+
+                layout(location = 0) in vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+                */
+                const VS: [u32; 228] = [
+                    119734787, 65536, 524289, 27, 0, 131089, 1, 131089, 32, 131089, 33, 393227, 1,
+                    1280527431, 1685353262, 808793134, 0, 196622, 0, 1, 458767, 0, 4, 1852399981,
+                    0, 13, 18, 196611, 2, 450, 589828, 1096764487, 1935622738, 1918988389,
+                    1600484449, 1684105331, 1868526181, 1667590754, 29556, 589828, 1096764487,
+                    1935622738, 1768186216, 1818191726, 1969712737, 1600481121, 1882206772,
+                    7037793, 262149, 4, 1852399981, 0, 393221, 11, 1348430951, 1700164197,
+                    2019914866, 0, 393222, 11, 0, 1348430951, 1953067887, 7237481, 458758, 11, 1,
+                    1348430951, 1953393007, 1702521171, 0, 458758, 11, 2, 1130327143, 1148217708,
+                    1635021673, 6644590, 458758, 11, 3, 1130327143, 1147956341, 1635021673,
+                    6644590, 196613, 13, 0, 327685, 18, 1769172848, 1852795252, 0, 327752, 11, 0,
+                    11, 0, 327752, 11, 1, 11, 1, 327752, 11, 2, 11, 3, 327752, 11, 3, 11, 4,
+                    196679, 11, 2, 262215, 18, 30, 0, 131091, 2, 196641, 3, 2, 196630, 6, 32,
+                    262167, 7, 6, 4, 262165, 8, 32, 0, 262187, 8, 9, 1, 262172, 10, 6, 9, 393246,
+                    11, 7, 6, 10, 10, 262176, 12, 3, 11, 262203, 12, 13, 3, 262165, 14, 32, 1,
+                    262187, 14, 15, 0, 262167, 16, 6, 2, 262176, 17, 1, 16, 262203, 17, 18, 1,
+                    262187, 6, 20, 0, 262187, 6, 21, 1065353216, 262176, 25, 3, 7, 327734, 2, 4, 0,
+                    3, 131320, 5, 262205, 16, 19, 18, 327761, 6, 22, 19, 0, 327761, 6, 23, 19, 1,
+                    458832, 7, 24, 22, 23, 20, 21, 327745, 25, 26, 13, 15, 196670, 26, 24, 65789,
+                    65592,
+                ];
+                /*
+                #version 450
+
+                layout(location = 0) out vec4 f_color;
+
+                void main() {
+                    f_color = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+                */
+                const FS: [u32; 105] = [
+                    119734787, 65536, 524289, 13, 0, 131089, 1, 393227, 1, 1280527431, 1685353262,
+                    808793134, 0, 196622, 0, 1, 393231, 4, 4, 1852399981, 0, 9, 196624, 4, 7,
+                    196611, 2, 450, 589828, 1096764487, 1935622738, 1918988389, 1600484449,
+                    1684105331, 1868526181, 1667590754, 29556, 589828, 1096764487, 1935622738,
+                    1768186216, 1818191726, 1969712737, 1600481121, 1882206772, 7037793, 262149, 4,
+                    1852399981, 0, 262149, 9, 1868783462, 7499628, 262215, 9, 30, 0, 131091, 2,
+                    196641, 3, 2, 196630, 6, 32, 262167, 7, 6, 4, 262176, 8, 3, 7, 262203, 8, 9, 3,
+                    262187, 6, 10, 1065353216, 262187, 6, 11, 0, 458796, 7, 12, 10, 11, 11, 10,
+                    327734, 2, 4, 0, 3, 131320, 5, 196670, 9, 12, 65789, 65592,
+                ];
+                let vs =
+                    ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(&VS)).unwrap();
+                let fs =
+                    ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(&FS)).unwrap();
+
+                (
+                    vs.entry_point("main").unwrap(),
+                    fs.entry_point("main").unwrap(),
+                )
+            };
+
+            let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+            let color = ImageView::new_default(
+                Image::new(
+                    memory_allocator.clone(),
+                    ImageCreateInfo {
+                        format: Format::R8G8B8A8_UNORM,
+                        extent: [2, 1, 1],
+                        usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::TRANSFER_SRC,
+                        ..Default::default()
+                    },
+                    AllocationCreateInfo::default(),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            let render_pass = single_pass_renderpass!(
+                device.clone(),
+                attachments: {
+                    color: {
+                        format: Format::R8G8B8A8_UNORM,
+                        samples: 1,
+                        load_op: Clear,
+                        store_op: Store,
+                    },
+                },
+                pass: {
+                    color: [color],
+                    depth_stencil: {},
+                },
+            )
+            .unwrap();
+            let framebuffer = Framebuffer::new(
+                render_pass,
+                FramebufferCreateInfo {
+                    attachments: vec![color.clone()],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            Some(DrawTest {
+                device,
+                queue,
+                memory_allocator,
+                color,
+                framebuffer,
+                vs,
+                fs,
+            })
+        }
+
+        fn pipeline(&self, dynamic_state: &[DynamicState]) -> Arc<GraphicsPipeline> {
+            self.pipeline_with_fs(self.fs.clone(), dynamic_state)
+        }
+
+        fn pipeline_with_fs(
+            &self,
+            fs: EntryPoint,
+            dynamic_state: &[DynamicState],
+        ) -> Arc<GraphicsPipeline> {
+            let subpass = Subpass::from(self.framebuffer.render_pass().clone(), 0).unwrap();
+
+            self.pipeline_for_subpass(
+                fs,
+                dynamic_state,
+                subpass.into(),
+                MultisampleState::default(),
+            )
+        }
+
+        fn pipeline_for_subpass(
+            &self,
+            fs: EntryPoint,
+            dynamic_state: &[DynamicState],
+            subpass: PipelineSubpassType,
+            multisample_state: MultisampleState,
+        ) -> Arc<GraphicsPipeline> {
+            let stages = [
+                PipelineShaderStageCreateInfo::new(self.vs.clone()),
+                PipelineShaderStageCreateInfo::new(fs),
+            ];
+            let layout = PipelineLayout::new(
+                self.device.clone(),
+                PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+                    .into_pipeline_layout_create_info(self.device.clone())
+                    .unwrap(),
+            )
+            .unwrap();
+
+            GraphicsPipeline::new(
+                self.device.clone(),
+                None,
+                GraphicsPipelineCreateInfo {
+                    stages: stages.into_iter().collect(),
+                    vertex_input_state: Some(
+                        VertexInputState::new()
+                            .binding(
+                                0,
+                                VertexInputBindingDescription {
+                                    stride: 8,
+                                    input_rate: VertexInputRate::Vertex,
+                                    ..Default::default()
+                                },
+                            )
+                            .attribute(
+                                0,
+                                VertexInputAttributeDescription {
+                                    binding: 0,
+                                    format: Format::R32G32_SFLOAT,
+                                    offset: 0,
+                                    ..Default::default()
+                                },
+                            ),
+                    ),
+                    input_assembly_state: Some(InputAssemblyState::default()),
+                    viewport_state: Some(ViewportState {
+                        viewports: [Viewport {
+                            offset: [0.0, 0.0],
+                            extent: [2.0, 1.0],
+                            depth_range: 0.0..=1.0,
+                        }]
+                        .into_iter()
+                        .collect(),
+                        ..Default::default()
+                    }),
+                    rasterization_state: Some(RasterizationState::default()),
+                    multisample_state: Some(multisample_state),
+                    color_blend_state: Some(ColorBlendState::with_attachment_states(
+                        1,
+                        ColorBlendAttachmentState::default(),
+                    )),
+                    dynamic_state: dynamic_state.iter().copied().collect(),
+                    subpass: Some(subpass),
+                    ..GraphicsPipelineCreateInfo::layout(layout)
+                },
+            )
+            .unwrap()
+        }
+
+        fn buffer<T, I>(&self, usage: BufferUsage, data: I) -> Subbuffer<[T]>
+        where
+            T: BufferContents,
+            I: IntoIterator<Item = T>,
+            I::IntoIter: ExactSizeIterator,
+        {
+            Buffer::from_iter(
+                self.memory_allocator.clone(),
+                BufferCreateInfo {
+                    usage,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
+                        | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                    ..Default::default()
+                },
+                data,
+            )
+            .unwrap()
+        }
+
+        // A triangle that covers the whole attachment.
+        fn vertex_buffer(&self) -> Subbuffer<[[f32; 2]]> {
+            self.buffer(
+                BufferUsage::VERTEX_BUFFER,
+                [[-1.0f32, -1.0], [3.0, -1.0], [-1.0, 3.0]],
+            )
+        }
+
+        fn command_buffer(&self) -> RecordingCommandBuffer {
+            let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+                self.device.clone(),
+                Default::default(),
+            ));
+
+            RecordingCommandBuffer::new(
+                cb_allocator,
+                self.queue.queue_family_index(),
+                CommandBufferLevel::Primary,
+                CommandBufferBeginInfo {
+                    usage: CommandBufferUsage::OneTimeSubmit,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        }
+
+        // Returns a command buffer builder in which the render pass has begun, with the
+        // attachment cleared to black.
+        fn begin(&self) -> RecordingCommandBuffer {
+            let mut cbb = self.command_buffer();
+            cbb.begin_render_pass(
+                RenderPassBeginInfo {
+                    clear_values: vec![Some([0.0; 4].into())],
+                    ..RenderPassBeginInfo::framebuffer(self.framebuffer.clone())
+                },
+                Default::default(),
+            )
+            .unwrap();
+
+            cbb
+        }
+
+        // Ends the render pass, executes the command buffer, and returns the contents of the
+        // attachment.
+        fn finish(&self, mut cbb: RecordingCommandBuffer) -> Vec<u8> {
+            cbb.end_render_pass(Default::default()).unwrap();
+
+            self.read_back(cbb, self.color.image().clone())
+        }
+
+        // Executes the command buffer after copying `image` to a buffer, and returns the contents
+        // of the buffer. `image` must have the same format and extent as the color attachment.
+        fn read_back(&self, mut cbb: RecordingCommandBuffer, image: Arc<Image>) -> Vec<u8> {
+            let output_buffer = Buffer::new_slice::<u8>(
+                self.memory_allocator.clone(),
+                BufferCreateInfo {
+                    usage: BufferUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                        | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                    ..Default::default()
+                },
+                8,
+            )
+            .unwrap();
+
+            cbb.copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(
+                image,
+                output_buffer.clone(),
+            ))
+            .unwrap();
+
+            now(self.device.clone())
+                .then_execute(self.queue.clone(), cbb.end().unwrap())
+                .unwrap()
+                .then_signal_fence_and_flush()
+                .unwrap()
+                .wait(None)
+                .unwrap();
+
+            let output = output_buffer.read().unwrap();
+            output.to_vec()
+        }
+    }
+
+    #[test]
+    fn draw_scissored_batches() {
+        let Some(test) = DrawTest::new(DeviceFeatures::empty()) else {
+            return;
+        };
+        let left = Scissor {
+            offset: [0, 0],
+            extent: [1, 1],
+        };
+        let right = Scissor {
+            offset: [1, 0],
+            extent: [1, 1],
+        };
+        // The indices are one past the vertices, which a vertex offset of -1 compensates for.
+        let index_buffer = test.buffer(BufferUsage::INDEX_BUFFER, [1u32, 2, 3]);
+        let draw = DrawIndexedIndirectCommand {
+            index_count: 3,
+            instance_count: 1,
+            first_index: 0,
+            vertex_offset: -1i32 as u32,
+            first_instance: 0,
+        };
+        let empty_draw = DrawIndexedIndirectCommand {
+            index_count: 0,
+            ..draw
+        };
+
+        let mut cbb = test.begin();
+        cbb.bind_pipeline_graphics(test.pipeline(&[DynamicState::Scissor]))
+            .unwrap()
+            .bind_vertex_buffers(0, test.vertex_buffer())
+            .unwrap()
+            .bind_index_buffer(index_buffer)
+            .unwrap();
+
+        unsafe {
+            assert!(cbb.draw_scissored_batches(&[]).is_ok());
+
+            // Invalid scissors are rejected for every draw, not only the first one.
+            let invalid = Scissor {
+                offset: [i32::MAX as u32, 0],
+                extent: [1, 1],
+            };
+            assert!(cbb.draw_scissored_batches(&[(invalid, draw)]).is_err());
+            assert!(cbb
+                .draw_scissored_batches(&[(left, draw), (invalid, draw)])
+                .is_err());
+
+            // The scissor that was set for validation is unset again after an error.
+            assert!(cbb.draw_indexed(3, 1, 0, -1, 0).is_err());
+
+            // Only the last scissor applies to the drawn triangle.
+            cbb.draw_scissored_batches(&[(right, empty_draw), (left, draw)])
+                .unwrap();
+            assert_eq!(cbb.builder_state.scissor.get(&0), Some(&left));
+        }
+
+        assert_eq!(test.finish(cbb), [255, 0, 0, 255, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn draw_scissored_batches_requires_dynamic_scissor() {
+        let Some(test) = DrawTest::new(DeviceFeatures::empty()) else {
+            return;
+        };
+        let index_buffer = test.buffer(BufferUsage::INDEX_BUFFER, [0u32, 1, 2]);
+        let draw = DrawIndexedIndirectCommand {
+            index_count: 3,
+            instance_count: 1,
+            first_index: 0,
+            vertex_offset: 0,
+            first_instance: 0,
+        };
+
+        let mut cbb = test.begin();
+        cbb.bind_pipeline_graphics(test.pipeline(&[]))
+            .unwrap()
+            .bind_vertex_buffers(0, test.vertex_buffer())
+            .unwrap()
+            .bind_index_buffer(index_buffer)
+            .unwrap();
+
+        assert!(unsafe { cbb.draw_scissored_batches(&[(Scissor::default(), draw)]) }.is_err());
+    }
+
+    #[test]
+    fn draw_vertex_binding_stride() {
+        let Some(test) = DrawTest::new(DeviceFeatures {
+            extended_dynamic_state: true,
+            ..DeviceFeatures::empty()
+        }) else {
+            return;
+        };
+        // The vertex buffer holds 3 vertices of 8 bytes each.
+        let vertex_buffer = test.vertex_buffer();
+
+        // The strides can't be set if the bound pipeline has a fixed stride.
+        let mut cbb = test.begin();
+        cbb.bind_pipeline_graphics(test.pipeline(&[])).unwrap();
+        assert!(cbb
+            .bind_vertex_buffers_with_strides(0, vertex_buffer.clone(), &[8])
+            .is_err());
+
+        let mut cbb = test.begin();
+        cbb.bind_pipeline_graphics(test.pipeline(&[DynamicState::VertexInputBindingStride]))
+            .unwrap();
+
+        unsafe {
+            // No stride has been set yet.
+            cbb.bind_vertex_buffers(0, vertex_buffer.clone()).unwrap();
+            assert!(cbb.draw(3, 1, 0, 0).is_err());
+
+            // The dynamic stride is smaller than the size of the `vec2` attribute.
+            cbb.bind_vertex_buffers_with_strides(0, vertex_buffer.clone(), &[4])
+                .unwrap();
+            assert!(cbb.draw(3, 1, 0, 0).is_err());
+
+            // The size check uses the dynamic stride, with which the vertex buffer is too small
+            // for 3 vertices, although the pipeline's stride would allow it.
+            cbb.bind_vertex_buffers_with_strides(0, vertex_buffer.clone(), &[16])
+                .unwrap();
+            assert!(cbb.draw(3, 1, 0, 0).is_err());
+            assert!(cbb.draw(1, 1, 0, 0).is_ok());
+
+            cbb.bind_vertex_buffers_with_strides(0, vertex_buffer, &[8])
+                .unwrap();
+            cbb.draw(3, 1, 0, 0).unwrap();
+        }
+
+        assert_eq!(test.finish(cbb), [255, 0, 0, 255, 255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn draw_null_vertex_buffer() {
+        // Null vertex buffers can only be bound if `null_descriptor` is enabled.
+        let Some(test) = DrawTest::new(DeviceFeatures::empty()) else {
+            return;
+        };
+        let mut cbb = test.begin();
+        assert!(cbb.bind_null_vertex_buffers(0, 1).is_err());
+
+        let Some(test) = DrawTest::with_extensions(
+            DeviceExtensions {
+                ext_robustness2: true,
+                ..DeviceExtensions::empty()
+            },
+            DeviceFeatures {
+                null_descriptor: true,
+                ..DeviceFeatures::empty()
+            },
+        ) else {
+            return;
+        };
+        let mut cbb = test.begin();
+        cbb.bind_pipeline_graphics(test.pipeline(&[])).unwrap();
+
+        unsafe {
+            // Nothing is bound to the binding that the pipeline reads from.
+            assert!(cbb.draw(3, 1, 0, 0).is_err());
+
+            // A null vertex buffer has no size to check the vertex count against.
+            cbb.bind_null_vertex_buffers(0, 1).unwrap();
+            cbb.draw(6, 1, 0, 0).unwrap();
+        }
+
+        // Every vertex reads a zero position, so the triangles are degenerate.
+        assert_eq!(test.finish(cbb), [0; 8]);
+    }
+
+    /*
+    #version 450
+
+    layout(set = 0, binding = 0) uniform Data {
+        vec4 color;
+    } data;
+
+    layout(location = 0) out vec4 f_color;
+
+    void main() {
+        f_color = data.color + vec4(1.0, 0.0, 0.0, 1.0);
+    }
+    */
+    const UNIFORM_COLOR_FS: [u32; 123] = [
+        119734787, 65536, 0, 21, 0, 131089, 1, 196622, 0, 1, 393231, 4, 13, 1852399981, 0, 6,
+        196624, 13, 7, 262215, 6, 30, 0, 327752, 7, 0, 35, 0, 196679, 7, 2, 262215, 9, 34, 0,
+        262215, 9, 33, 0, 131091, 1, 196641, 2, 1, 196630, 3, 32, 262167, 4, 3, 4, 262176, 5, 3, 4,
+        262203, 5, 6, 3, 196638, 7, 4, 262176, 8, 2, 7, 262203, 8, 9, 2, 262165, 10, 32, 1, 262187,
+        10, 11, 0, 262176, 12, 2, 4, 262187, 3, 17, 1065353216, 262187, 3, 18, 0, 458796, 4, 19,
+        17, 18, 18, 17, 327734, 1, 13, 0, 2, 131320, 14, 327745, 12, 15, 9, 11, 262205, 4, 16, 15,
+        327809, 4, 20, 16, 19, 196670, 6, 20, 65789, 65592,
+    ];
+
+    #[test]
+    fn draw_null_descriptor() {
+        let descriptor_set = |test: &DrawTest, writes: Vec<WriteDescriptorSet>| {
+            let fs = unsafe {
+                ShaderModule::new(
+                    test.device.clone(),
+                    ShaderModuleCreateInfo::new(&UNIFORM_COLOR_FS),
+                )
+                .unwrap()
+            }
+            .entry_point("main")
+            .unwrap();
+            let pipeline = test.pipeline_with_fs(fs, &[]);
+            let allocator = Arc::new(StandardDescriptorSetAllocator::new(
+                test.device.clone(),
+                Default::default(),
+            ));
+            let set = DescriptorSet::new(
+                allocator,
+                pipeline.layout().set_layouts()[0].clone(),
+                writes,
+                [],
+            );
+
+            (pipeline, set)
+        };
+
+        // Null descriptors can only be written if `null_descriptor` is enabled.
+        let Some(test) = DrawTest::new(DeviceFeatures::empty()) else {
+            return;
+        };
+        let (_, set) = descriptor_set(&test, vec![WriteDescriptorSet::none(0)]);
+        assert!(set.is_err());
+
+        let Some(test) = DrawTest::with_extensions(
+            DeviceExtensions {
+                ext_robustness2: true,
+                ..DeviceExtensions::empty()
+            },
+            DeviceFeatures {
+                null_descriptor: true,
+                ..DeviceFeatures::empty()
+            },
+        ) else {
+            return;
+        };
+        let mut cbb = test.begin();
+
+        // The uniform buffer that the shader reads is not written.
+        let (pipeline, set) = descriptor_set(&test, Vec::new());
+        cbb.bind_pipeline_graphics(pipeline.clone())
+            .unwrap()
+            .bind_vertex_buffers(0, test.vertex_buffer())
+            .unwrap()
+            .bind_descriptor_sets(
+                PipelineBindPoint::Graphics,
+                pipeline.layout().clone(),
+                0,
+                set.unwrap(),
+            )
+            .unwrap();
+        assert!(unsafe { cbb.draw(3, 1, 0, 0) }.is_err());
+
+        // A null uniform buffer reads as zero, so the color is only the added constant.
+        let (pipeline, set) = descriptor_set(&test, vec![WriteDescriptorSet::none(0)]);
+        cbb.bind_pipeline_graphics(pipeline.clone())
+            .unwrap()
+            .bind_descriptor_sets(
+                PipelineBindPoint::Graphics,
+                pipeline.layout().clone(),
+                0,
+                set.unwrap(),
+            )
+            .unwrap();
+        unsafe { cbb.draw(3, 1, 0, 0) }.unwrap();
+
+        assert_eq!(test.finish(cbb), [255, 0, 0, 255, 255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn draw_buffer_min_size() {
+        let Some(test) = DrawTest::new(DeviceFeatures::empty()) else {
+            return;
+        };
+        let fs = unsafe {
+            ShaderModule::new(
+                test.device.clone(),
+                ShaderModuleCreateInfo::new(&UNIFORM_COLOR_FS),
+            )
+            .unwrap()
+        }
+        .entry_point("main")
+        .unwrap();
+        let pipeline = test.pipeline_with_fs(fs, &[]);
+        let allocator = Arc::new(StandardDescriptorSetAllocator::new(
+            test.device.clone(),
+            Default::default(),
+        ));
+        let layout = pipeline.layout().set_layouts()[0].clone();
+        let uniform_buffer =
+            |data: &[f32]| test.buffer(BufferUsage::UNIFORM_BUFFER, data.iter().copied());
+
+        // The block in the shader is a `vec4`, but only two floats are bound. This is valid as
+        // long as the shader doesn't access the rest, so it is only checked by `buffer_typed`.
+        DescriptorSet::new(
+            allocator.clone(),
+            layout.clone(),
+            [WriteDescriptorSet::buffer(0, uniform_buffer(&[0.0; 2]))],
+            [],
+        )
+        .unwrap();
+
+        match DescriptorSet::new(
+            allocator.clone(),
+            layout.clone(),
+            [WriteDescriptorSet::buffer_typed(
+                0,
+                uniform_buffer(&[0.0; 2]),
+            )],
+            [],
+        ) {
+            Err(Validated::ValidationError(err)) => {
+                assert!(err.context.contains("elements[0].range"));
+                assert!(err.problem.contains("is 8 bytes long"));
+                assert!(err.problem.contains("requires at least 16 bytes"));
+            }
+            _ => panic!(),
+        }
+
+        let descriptor_set = DescriptorSet::new(
+            allocator,
+            layout,
+            [WriteDescriptorSet::buffer_typed(
+                0,
+                uniform_buffer(&[0.0, 0.0, 1.0, 0.0]),
+            )],
+            [],
+        )
+        .unwrap();
+
+        let mut cbb = test.begin();
+        cbb.bind_pipeline_graphics(pipeline.clone())
+            .unwrap()
+            .bind_vertex_buffers(0, test.vertex_buffer())
+            .unwrap()
+            .bind_descriptor_sets(
+                PipelineBindPoint::Graphics,
+                pipeline.layout().clone(),
+                0,
+                descriptor_set,
+            )
+            .unwrap();
+        unsafe { cbb.draw(3, 1, 0, 0) }.unwrap();
+
+        assert_eq!(test.finish(cbb), [255, 0, 255, 255, 255, 0, 255, 255]);
+    }
+
+    #[test]
+    fn draw_multisampled_render_to_single_sampled() {
+        let Some(test) = DrawTest::with_extensions(
+            DeviceExtensions {
+                khr_dynamic_rendering: true,
+                ext_multisampled_render_to_single_sampled: true,
+                ..DeviceExtensions::empty()
+            },
+            DeviceFeatures {
+                dynamic_rendering: true,
+                multisampled_render_to_single_sampled: true,
+                ..DeviceFeatures::empty()
+            },
+        ) else {
+            return;
+        };
+        let samples = SampleCount::Sample4;
+        let image = Image::new(
+            test.memory_allocator.clone(),
+            ImageCreateInfo {
+                flags: ImageCreateFlags::MULTISAMPLED_RENDER_TO_SINGLE_SAMPLED,
+                format: Format::R8G8B8A8_UNORM,
+                extent: [2, 1, 1],
+                usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::TRANSFER_SRC,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+        )
+        .unwrap();
+        let pipeline = |multisampled_render_to_single_sampled, rasterization_samples| {
+            test.pipeline_for_subpass(
+                test.fs.clone(),
+                &[],
+                PipelineRenderingCreateInfo {
+                    color_attachment_formats: vec![Some(Format::R8G8B8A8_UNORM)],
+                    multisampled_render_to_single_sampled,
+                    ..Default::default()
+                }
+                .into(),
+                MultisampleState {
+                    rasterization_samples,
+                    ..Default::default()
+                },
+            )
+        };
+
+        let mut cbb = test.command_buffer();
+        cbb.begin_rendering(RenderingInfo {
+            color_attachments: vec![Some(RenderingAttachmentInfo {
+                load_op: AttachmentLoadOp::Clear,
+                store_op: AttachmentStoreOp::Store,
+                clear_value: Some([0.0; 4].into()),
+                ..RenderingAttachmentInfo::image_view(
+                    ImageView::new_default(image.clone()).unwrap(),
+                )
+            })],
+            multisampled_render_to_single_sampled: Some(samples),
+            ..Default::default()
+        })
+        .unwrap()
+        .bind_vertex_buffers(0, test.vertex_buffer())
+        .unwrap();
+
+        // The pipeline must rasterize with the number of samples of the render pass instance.
+        cbb.bind_pipeline_graphics(pipeline(None, SampleCount::Sample1))
+            .unwrap();
+        let err = unsafe { cbb.draw(3, 1, 0, 0) }.unwrap_err();
+        assert_eq!(
+            err.vuids,
+            ["VUID-vkCmdDraw-multisampledRenderToSingleSampled-07285"],
+        );
+
+        cbb.bind_pipeline_graphics(pipeline(Some(samples), samples))
+            .unwrap();
+        unsafe { cbb.draw(3, 1, 0, 0) }.unwrap();
+        cbb.end_rendering().unwrap();
+
+        // The single-sampled attachment is resolved implicitly.
+        assert_eq!(test.read_back(cbb, image), [255, 0, 0, 255, 255, 0, 0, 255],);
+    }
+}
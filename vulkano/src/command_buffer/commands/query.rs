@@ -18,6 +18,11 @@ impl RecordingCommandBuffer {
     ///
     /// The query will be active until [`end_query`](Self::end_query) is called for the same query.
     ///
+    /// If the query is begun inside a render subpass with a non-zero `view_mask`, then it uses
+    /// one query index for each view, starting at `query`. The query pool must have enough
+    /// queries for this, and all of them must be unavailable. See
+    /// [`QueryPool::queries_for_views`] for how the results are laid out.
+    ///
     /// # Safety
     ///
     /// The query must be unavailable, ensured by calling
@@ -28,7 +33,8 @@ impl RecordingCommandBuffer {
         query: u32,
         flags: QueryControlFlags,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_begin_query(&query_pool, query, flags)?;
+        self.validate_begin_query(&query_pool, query, flags)
+            .map_err(|err| self.command_error(err, "begin_query"))?;
 
         Ok(self.begin_query_unchecked(query_pool, query, flags))
     }
@@ -111,7 +117,8 @@ impl RecordingCommandBuffer {
         query_pool: Arc<QueryPool>,
         query: u32,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_end_query(&query_pool, query)?;
+        self.validate_end_query(&query_pool, query)
+            .map_err(|err| self.command_error(err, "end_query"))?;
 
         unsafe { Ok(self.end_query_unchecked(query_pool, query)) }
     }
@@ -178,6 +185,10 @@ impl RecordingCommandBuffer {
 
     /// Writes a timestamp to a timestamp query.
     ///
+    /// If the timestamp is written inside a render subpass with a non-zero `view_mask`, then it
+    /// uses one query index for each view, starting at `query`, as described in
+    /// [`QueryPool::queries_for_views`].
+    ///
     /// # Safety
     ///
     /// The query must be unavailable, ensured by calling
@@ -188,7 +199,8 @@ impl RecordingCommandBuffer {
         query: u32,
         stage: PipelineStage,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_write_timestamp(&query_pool, query, stage)?;
+        self.validate_write_timestamp(&query_pool, query, stage)
+            .map_err(|err| self.command_error(err, "write_timestamp"))?;
 
         Ok(self.write_timestamp_unchecked(query_pool, query, stage))
     }
@@ -249,7 +261,9 @@ impl RecordingCommandBuffer {
     /// 1 extra element per query if [`QueryResultFlags::WITH_AVAILABILITY`] is enabled.
     /// The provided buffer must be large enough to hold the data.
     ///
-    /// See also [`get_results`].
+    /// Unlike [`get_results`], this doesn't stall the host, so the results can be consumed by
+    /// later commands on the device, or read back once the command buffer has finished
+    /// executing. The write to `destination` is synchronized as a transfer write.
     ///
     /// [`query_pool.ty().result_len()`]: QueryPool::result_len
     /// [`get_results`]: QueryPool::get_results
@@ -263,7 +277,8 @@ impl RecordingCommandBuffer {
     where
         T: QueryResultElement,
     {
-        self.validate_copy_query_pool_results(&query_pool, queries.clone(), &destination, flags)?;
+        self.validate_copy_query_pool_results(&query_pool, queries.clone(), &destination, flags)
+            .map_err(|err| self.command_error(err, "copy_query_pool_results"))?;
 
         unsafe {
             Ok(self.copy_query_pool_results_unchecked(query_pool, queries, destination, flags))
@@ -305,13 +320,21 @@ impl RecordingCommandBuffer {
     where
         T: QueryResultElement,
     {
+        let per_query_len = query_pool.result_len(flags);
+        let written_size = per_query_len
+            * (queries.end - queries.start) as DeviceSize
+            * std::mem::size_of::<T>() as DeviceSize;
+
+        // Query pools aren't tracked as resources. The implementation orders earlier query
+        // commands on the same queue before the copy, so only the write to `destination` needs
+        // to be synchronized.
         self.add_command(
             "copy_query_pool_results",
             [(
                 ResourceInCommand::Destination.into(),
                 Resource::Buffer {
                     buffer: destination.as_bytes().clone(),
-                    range: 0..destination.size(), // TODO:
+                    range: 0..written_size,
                     memory_access: PipelineStageAccessFlags::Copy_TransferWrite,
                 },
             )]
@@ -343,7 +366,8 @@ impl RecordingCommandBuffer {
         query_pool: Arc<QueryPool>,
         queries: Range<u32>,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_reset_query_pool(&query_pool, queries.clone())?;
+        self.validate_reset_query_pool(&query_pool, queries.clone())
+            .map_err(|err| self.command_error(err, "reset_query_pool"))?;
 
         Ok(self.reset_query_pool_unchecked(query_pool, queries))
     }
@@ -444,9 +468,9 @@ impl RawRecordingCommandBuffer {
         // VUID-vkCmdBeginQuery-commonparent
         assert_eq!(device, query_pool.device());
 
-        if query > query_pool.query_count() {
+        if query >= query_pool.query_count() {
             return Err(Box::new(ValidationError {
-                problem: "`query` is greater than `query_pool.query_count()`".into(),
+                problem: "`query` is not less than `query_pool.query_count()`".into(),
                 vuids: &["VUID-vkCmdBeginQuery-query-00802"],
                 ..Default::default()
             }));
@@ -612,9 +636,9 @@ impl RawRecordingCommandBuffer {
         // VUID-vkCmdEndQuery-commonparent
         assert_eq!(device, query_pool.device());
 
-        if query > query_pool.query_count() {
+        if query >= query_pool.query_count() {
             return Err(Box::new(ValidationError {
-                problem: "`query` is greater than `query_pool.query_count()`".into(),
+                problem: "`query` is not less than `query_pool.query_count()`".into(),
                 vuids: &["VUID-vkCmdEndQuery-query-00810"],
                 ..Default::default()
             }));
@@ -850,9 +874,9 @@ impl RawRecordingCommandBuffer {
             }));
         }
 
-        if query > query_pool.query_count() {
+        if query >= query_pool.query_count() {
             return Err(Box::new(ValidationError {
-                problem: "`query` is greater than `query_pool.query_count()`".into(),
+                problem: "`query` is not less than `query_pool.query_count()`".into(),
                 vuids: &["VUID-vkCmdWriteTimestamp2-query-04903"],
                 ..Default::default()
             }));
@@ -939,9 +963,26 @@ impl RawRecordingCommandBuffer {
         assert_eq!(device, destination.buffer().device());
         assert_eq!(device, query_pool.device());
 
-        // VUID-vkCmdCopyQueryPoolResults-flags-00822
-        // VUID-vkCmdCopyQueryPoolResults-flags-00823
-        debug_assert!(destination.offset() % std::mem::size_of::<T>() as DeviceSize == 0);
+        flags.validate_device(device).map_err(|err| {
+            err.add_context("flags")
+                .set_vuids(&["VUID-vkCmdCopyQueryPoolResults-flags-parameter"])
+        })?;
+
+        if destination.offset() % std::mem::size_of::<T>() as DeviceSize != 0 {
+            return Err(Box::new(ValidationError {
+                problem: format!(
+                    "`destination.offset()` is not a multiple of {}, the size of the \
+                    result elements",
+                    std::mem::size_of::<T>(),
+                )
+                .into(),
+                vuids: &[
+                    "VUID-vkCmdCopyQueryPoolResults-flags-00822",
+                    "VUID-vkCmdCopyQueryPoolResults-flags-00823",
+                ],
+                ..Default::default()
+            }));
+        }
 
         if queries.end < queries.start {
             return Err(Box::new(ValidationError {
@@ -1,15 +1,18 @@
 use crate::{
     command_buffer::{
         auto::{
-            BeginRenderPassState, BeginRenderingState, RenderPassState, RenderPassStateAttachments,
-            RenderPassStateType, Resource,
+            AttachmentOps, BeginRenderPassState, BeginRenderingState, RenderPassState,
+            RenderPassStateAttachments, RenderPassStateType, Resource,
         },
         sys::RawRecordingCommandBuffer,
         CommandBufferLevel, RecordingCommandBuffer, ResourceInCommand, SubpassContents,
     },
     device::{Device, DeviceOwned, QueueFlags},
-    format::{ClearColorValue, ClearValue, NumericType},
-    image::{view::ImageView, ImageAspects, ImageLayout, ImageUsage, SampleCount},
+    format::{ClearColorValue, ClearValue, ClearValueType, NumericType},
+    image::{
+        mip_level_extent, view::ImageView, ImageAspects, ImageCreateFlags, ImageLayout,
+        ImageSubresourceRange, ImageUsage, SampleCount,
+    },
     pipeline::graphics::subpass::PipelineRenderingCreateInfo,
     render_pass::{
         AttachmentDescription, AttachmentLoadOp, AttachmentStoreOp, Framebuffer, RenderPass,
@@ -36,7 +39,8 @@ impl RecordingCommandBuffer {
         render_pass_begin_info: RenderPassBeginInfo,
         subpass_begin_info: SubpassBeginInfo,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_begin_render_pass(&render_pass_begin_info, &subpass_begin_info)?;
+        self.validate_begin_render_pass(&render_pass_begin_info, &subpass_begin_info)
+            .map_err(|err| self.command_error(err, "begin_render_pass"))?;
 
         unsafe { Ok(self.begin_render_pass_unchecked(render_pass_begin_info, subpass_begin_info)) }
     }
@@ -84,11 +88,12 @@ impl RecordingCommandBuffer {
         let &RenderPassBeginInfo {
             ref render_pass,
             ref framebuffer,
-            render_area_offset,
-            render_area_extent,
+            render_area: _,
             clear_values: _,
             _ne: _,
         } = &render_pass_begin_info;
+        let (render_area_offset, render_area_extent) =
+            render_pass_begin_info.render_area_offset_extent();
 
         let subpass = render_pass.clone().first_subpass();
         self.builder_state.render_pass = Some(RenderPassState {
@@ -138,6 +143,52 @@ impl RecordingCommandBuffer {
                     )
                 })
                 .collect(),
+            render_pass
+                .attachments()
+                .iter()
+                .zip(framebuffer.attachments())
+                .enumerate()
+                .flat_map(|(index, (desc, image_view))| {
+                    let aspects = image_view.subresource_range().aspects;
+
+                    [
+                        (
+                            aspects - ImageAspects::STENCIL,
+                            desc.load_op,
+                            desc.store_op,
+                            desc.initial_layout,
+                        ),
+                        (
+                            aspects & ImageAspects::STENCIL,
+                            desc.stencil_load_op.unwrap_or(desc.load_op),
+                            desc.stencil_store_op.unwrap_or(desc.store_op),
+                            desc.stencil_initial_layout.unwrap_or(desc.initial_layout),
+                        ),
+                    ]
+                    .into_iter()
+                    .filter(|(aspects, ..)| !aspects.is_empty())
+                    .map(move |(aspects, load_op, store_op, initial_layout)| {
+                        AttachmentOps {
+                            resource_in_command: ResourceInCommand::FramebufferAttachment {
+                                index: index as u32,
+                            },
+                            image: image_view.image().clone(),
+                            subresource_range: ImageSubresourceRange {
+                                aspects,
+                                ..image_view.subresource_range().clone()
+                            },
+                            load_op,
+                            store_op,
+                            initial_layout,
+                            render_area_covers: render_area_covers(
+                                image_view,
+                                render_area_offset,
+                                render_area_extent,
+                            ),
+                        }
+                    })
+                })
+                .collect(),
             move |out: &mut RawRecordingCommandBuffer| {
                 out.begin_render_pass_unchecked(&render_pass_begin_info, &subpass_begin_info);
             },
@@ -152,7 +203,8 @@ impl RecordingCommandBuffer {
         subpass_end_info: SubpassEndInfo,
         subpass_begin_info: SubpassBeginInfo,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_next_subpass(&subpass_end_info, &subpass_begin_info)?;
+        self.validate_next_subpass(&subpass_end_info, &subpass_begin_info)
+            .map_err(|err| self.command_error(err, "next_subpass"))?;
 
         unsafe { Ok(self.next_subpass_unchecked(subpass_end_info, subpass_begin_info)) }
     }
@@ -255,7 +307,8 @@ impl RecordingCommandBuffer {
         &mut self,
         subpass_end_info: SubpassEndInfo,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_end_render_pass(&subpass_end_info)?;
+        self.validate_end_render_pass(&subpass_end_info)
+            .map_err(|err| self.command_error(err, "end_render_pass"))?;
 
         unsafe { Ok(self.end_render_pass_unchecked(subpass_end_info)) }
     }
@@ -338,8 +391,9 @@ impl RecordingCommandBuffer {
         &mut self,
         mut rendering_info: RenderingInfo,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        rendering_info.set_auto_extent_layers();
-        self.validate_begin_rendering(&rendering_info)?;
+        rendering_info.set_auto_layers();
+        self.validate_begin_rendering(&rendering_info)
+            .map_err(|err| self.command_error(err, "begin_rendering"))?;
 
         unsafe { Ok(self.begin_rendering_unchecked(rendering_info)) }
     }
@@ -366,19 +420,22 @@ impl RecordingCommandBuffer {
         &mut self,
         mut rendering_info: RenderingInfo,
     ) -> &mut Self {
-        rendering_info.set_auto_extent_layers();
+        rendering_info.set_auto_layers();
 
         let &RenderingInfo {
-            render_area_offset,
-            render_area_extent,
+            render_area: _,
             layer_count: _,
             view_mask: _,
             ref color_attachments,
             ref depth_attachment,
             ref stencil_attachment,
             contents,
+            multisampled_render_to_single_sampled: _,
             _ne,
         } = &rendering_info;
+        let (render_area_offset, render_area_extent) = rendering_info
+            .render_area_offset_extent()
+            .unwrap_or_default();
 
         self.builder_state.render_pass = Some(RenderPassState {
             contents,
@@ -559,6 +616,65 @@ impl RecordingCommandBuffer {
                 .flatten()
             }))
             .collect(),
+            (color_attachments
+                .iter()
+                .enumerate()
+                .filter_map(|(index, attachment_info)| {
+                    attachment_info.as_ref().map(|attachment_info| {
+                        (
+                            ResourceInCommand::ColorAttachment {
+                                index: index as u32,
+                            },
+                            ImageAspects::COLOR,
+                            attachment_info,
+                        )
+                    })
+                }))
+            .chain(depth_attachment.iter().map(|attachment_info| {
+                (
+                    ResourceInCommand::DepthStencilAttachment,
+                    ImageAspects::DEPTH,
+                    attachment_info,
+                )
+            }))
+            .chain(stencil_attachment.iter().map(|attachment_info| {
+                (
+                    ResourceInCommand::DepthStencilAttachment,
+                    ImageAspects::STENCIL,
+                    attachment_info,
+                )
+            }))
+            .map(|(resource_in_command, aspects, attachment_info)| {
+                let &RenderingAttachmentInfo {
+                    ref image_view,
+                    image_layout,
+                    resolve_info: _,
+                    load_op,
+                    store_op,
+                    clear_value: _,
+                    _ne: _,
+                } = attachment_info;
+
+                AttachmentOps {
+                    resource_in_command,
+                    image: image_view.image().clone(),
+                    subresource_range: ImageSubresourceRange {
+                        aspects: image_view.subresource_range().aspects & aspects,
+                        ..image_view.subresource_range().clone()
+                    },
+                    load_op,
+                    store_op,
+                    // Dynamic rendering has no layout transition at the start of the render pass
+                    // instance.
+                    initial_layout: image_layout,
+                    render_area_covers: render_area_covers(
+                        image_view,
+                        render_area_offset,
+                        render_area_extent,
+                    ),
+                }
+            })
+            .collect(),
             move |out: &mut RawRecordingCommandBuffer| {
                 out.begin_rendering_unchecked(&rendering_info);
             },
@@ -569,7 +685,8 @@ impl RecordingCommandBuffer {
 
     /// Ends the render pass previously begun with `begin_rendering`.
     pub fn end_rendering(&mut self) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_end_rendering()?;
+        self.validate_end_rendering()
+            .map_err(|err| self.command_error(err, "end_rendering"))?;
 
         unsafe { Ok(self.end_rendering_unchecked()) }
     }
@@ -652,7 +769,8 @@ impl RecordingCommandBuffer {
         attachments: SmallVec<[ClearAttachment; 4]>,
         rects: SmallVec<[ClearRect; 4]>,
     ) -> Result<&mut Self, Box<ValidationError>> {
-        self.validate_clear_attachments(&attachments, &rects)?;
+        self.validate_clear_attachments(&attachments, &rects)
+            .map_err(|err| self.command_error(err, "clear_attachments"))?;
 
         unsafe { Ok(self.clear_attachments_unchecked(attachments, rects)) }
     }
@@ -672,11 +790,15 @@ impl RecordingCommandBuffer {
             })
         })?;
 
-        if render_pass_state.contents != SubpassContents::Inline {
+        if !render_pass_state.contents.allows_inline() {
             return Err(Box::new(ValidationError {
-                problem: "the contents of the current subpass instance is not \
-                    `SubpassContents::Inline`"
-                    .into(),
+                problem: format!(
+                    "the contents of subpass {} of the current render pass instance is \
+                    `SubpassContents::{:?}`, which does not allow inline commands",
+                    render_pass_state.subpass_index(),
+                    render_pass_state.contents,
+                )
+                .into(),
                 // vuids?
                 ..Default::default()
             }));
@@ -718,7 +840,9 @@ impl RecordingCommandBuffer {
                                     "`attachments[{0}].clear_value` is `ClearColorValue::{1:?}`, \
                                     but the color attachment specified by \
                                     `attachments[{0}].color_attachment` requires a clear value \
-                                    of type `ClearColorValue::{2:?}`",
+                                    of type `ClearColorValue::{2:?}` \
+                                    (`ClearColorValue::for_format` creates a clear value of the \
+                                    right type)",
                                     clear_index,
                                     clear_value.numeric_type(),
                                     required_numeric_type,
@@ -811,8 +935,8 @@ impl RecordingCommandBuffer {
                 if rect.offset[i] < render_pass_state.render_area_offset[i] {
                     return Err(Box::new(ValidationError {
                         problem: format!(
-                            "`rects[{0}].offset[{i}]` is less than \
-                            `render_area_offset[{i}]` of the current render pass instance",
+                            "`rects[{0}].offset[{i}]` is less than the offset of the render \
+                            area of the current render pass instance",
                             rect_index
                         )
                         .into(),
@@ -828,8 +952,8 @@ impl RecordingCommandBuffer {
                     return Err(Box::new(ValidationError {
                         problem: format!(
                             "`rects[{0}].offset[{i}] + rects[{0}].extent[{i}]` is \
-                            greater than `render_area_offset[{i}] + render_area_extent[{i}]` \
-                            of the current render pass instance",
+                            greater than the offset plus the extent of the render area of the \
+                            current render pass instance",
                             rect_index,
                         )
                         .into(),
@@ -937,8 +1061,7 @@ impl RawRecordingCommandBuffer {
         let RenderPassBeginInfo {
             render_pass,
             framebuffer,
-            render_area_offset: _,
-            render_area_extent: _,
+            render_area: _,
             clear_values: _,
             _ne: _,
         } = render_pass_begin_info;
@@ -1091,6 +1214,7 @@ impl RawRecordingCommandBuffer {
                 depth_stencil_resolve_attachment,
                 depth_resolve_mode: _,
                 stencil_resolve_mode: _,
+                multisampled_render_to_single_sampled: _,
                 preserve_attachments: _,
                 _ne: _,
             } = subpass_desc;
@@ -1245,11 +1369,12 @@ impl RawRecordingCommandBuffer {
         let &RenderPassBeginInfo {
             ref render_pass,
             ref framebuffer,
-            render_area_offset,
-            render_area_extent,
+            render_area: _,
             ref clear_values,
             _ne: _,
         } = render_pass_begin_info;
+        let (render_area_offset, render_area_extent) =
+            render_pass_begin_info.render_area_offset_extent();
 
         let clear_values_vk: SmallVec<[_; 4]> = clear_values
             .iter()
@@ -1526,23 +1651,23 @@ impl RawRecordingCommandBuffer {
             .map_err(|err| err.add_context("rendering_info"))?;
 
         let &RenderingInfo {
-            render_area_offset: _,
-            render_area_extent: _,
+            render_area: _,
             layer_count: _,
             view_mask: _,
             color_attachments: _,
             depth_attachment: _,
             stencil_attachment: _,
             contents,
+            multisampled_render_to_single_sampled: _,
             _ne: _,
         } = rendering_info;
 
         if self.level() == CommandBufferLevel::Secondary
-            && contents == SubpassContents::SecondaryCommandBuffers
+            && contents.allows_secondary_command_buffers()
         {
             return Err(Box::new(ValidationError {
                 problem: "this command buffer is a secondary command buffer, but \
-                    `rendering_info.contents` is `SubpassContents::SecondaryCommandBuffers`"
+                    `rendering_info.contents` allows secondary command buffers"
                     .into(),
                 vuids: &["VUID-vkCmdBeginRendering-commandBuffer-06068"],
                 ..Default::default()
@@ -1558,16 +1683,19 @@ impl RawRecordingCommandBuffer {
         rendering_info: &RenderingInfo,
     ) -> &mut Self {
         let &RenderingInfo {
-            render_area_offset,
-            render_area_extent,
+            render_area: _,
             layer_count,
             view_mask,
             ref color_attachments,
             ref depth_attachment,
             ref stencil_attachment,
             contents,
+            multisampled_render_to_single_sampled,
             _ne: _,
         } = rendering_info;
+        let (render_area_offset, render_area_extent) = rendering_info
+            .render_area_offset_extent()
+            .unwrap_or_default();
 
         let map_attachment_info = |attachment_info: &Option<_>| {
             if let Some(attachment_info) = attachment_info {
@@ -1622,7 +1750,7 @@ impl RawRecordingCommandBuffer {
         let depth_attachment_vk = map_attachment_info(depth_attachment);
         let stencil_attachment_vk = map_attachment_info(stencil_attachment);
 
-        let rendering_info = ash::vk::RenderingInfo {
+        let mut rendering_info_vk = ash::vk::RenderingInfo {
             flags: contents.into(),
             render_area: ash::vk::Rect2D {
                 offset: ash::vk::Offset2D {
@@ -1643,12 +1771,27 @@ impl RawRecordingCommandBuffer {
             ..Default::default()
         };
 
+        let mut multisampled_render_to_single_sampled_vk = None;
+
+        if let Some(rasterization_samples) = multisampled_render_to_single_sampled {
+            let next = multisampled_render_to_single_sampled_vk.insert(
+                ash::vk::MultisampledRenderToSingleSampledInfoEXT {
+                    multisampled_render_to_single_sampled_enable: ash::vk::TRUE,
+                    rasterization_samples: rasterization_samples.into(),
+                    ..Default::default()
+                },
+            );
+
+            next.p_next = rendering_info_vk.p_next;
+            rendering_info_vk.p_next = <*const _>::cast(next);
+        }
+
         let fns = self.device().fns();
 
         if self.device().api_version() >= Version::V1_3 {
-            (fns.v1_3.cmd_begin_rendering)(self.handle(), &rendering_info);
+            (fns.v1_3.cmd_begin_rendering)(self.handle(), &rendering_info_vk);
         } else {
-            (fns.khr_dynamic_rendering.cmd_begin_rendering_khr)(self.handle(), &rendering_info);
+            (fns.khr_dynamic_rendering.cmd_begin_rendering_khr)(self.handle(), &rendering_info_vk);
         }
 
         self
@@ -1809,6 +1952,75 @@ impl RawRecordingCommandBuffer {
     }
 }
 
+/// The area of the attachments that is rendered to during a render pass instance.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RenderArea {
+    /// The whole area that all attachments have in common.
+    ///
+    /// For [`RenderPassBeginInfo`], this is the extent of the framebuffer. For [`RenderingInfo`],
+    /// this is the largest area that fits into every attachment image view, taking the base mip
+    /// level of each view into account. This tracks the attachments automatically, so it can't go
+    /// stale when the attachments are recreated with a different size.
+    #[default]
+    Full,
+
+    /// Only a rectangular part of the attachments, starting at `offset` from the top left
+    /// corner.
+    ///
+    /// `offset + extent` must not be greater than the extent of any attachment. Unlike with
+    /// `Full`, this is not adjusted automatically, so a render area that was computed for
+    /// attachments of a different size results in an error.
+    ///
+    /// Choosing a partial area has consequences for the attachments:
+    /// - The load and store operations, including [`AttachmentLoadOp::Clear`], only apply to the
+    ///   render area. The contents of an attachment outside the render area are not cleared.
+    /// - Layout transitions apply to the whole image subresource. If an attachment is transitioned
+    ///   from [`ImageLayout::Undefined`], then its contents outside the render area become
+    ///   undefined.
+    /// - For render passes, the render area should be aligned to [`RenderPass::granularity`] for
+    ///   optimal performance.
+    ///
+    /// [`AttachmentLoadOp::Clear`]: crate::render_pass::AttachmentLoadOp::Clear
+    /// [`RenderPass::granularity`]: crate::render_pass::RenderPass::granularity
+    Partial {
+        /// The offset from the top left corner of the attachments.
+        offset: [u32; 2],
+
+        /// The size of the area. Both elements must be greater than 0.
+        extent: [u32; 2],
+    },
+}
+
+impl RenderArea {
+    /// Returns the offset and extent of the area, given the extent that `RenderArea::Full`
+    /// covers.
+    #[inline]
+    pub(crate) fn offset_extent(self, full_extent: [u32; 2]) -> ([u32; 2], [u32; 2]) {
+        match self {
+            RenderArea::Full => ([0, 0], full_extent),
+            RenderArea::Partial { offset, extent } => (offset, extent),
+        }
+    }
+}
+
+/// Returns whether the render area includes every texel of `image_view`.
+fn render_area_covers(
+    image_view: &ImageView,
+    render_area_offset: [u32; 2],
+    render_area_extent: [u32; 2],
+) -> bool {
+    let image = image_view.image();
+    let extent = mip_level_extent(
+        image.extent(),
+        image_view.subresource_range().mip_levels.start,
+    )
+    .unwrap();
+
+    render_area_offset == [0, 0]
+        && render_area_extent[0] >= extent[0]
+        && render_area_extent[1] >= extent[1]
+}
+
 /// Parameters to begin a new render pass.
 #[derive(Clone, Debug)]
 pub struct RenderPassBeginInfo {
@@ -1825,18 +2037,13 @@ pub struct RenderPassBeginInfo {
     /// There is no default value.
     pub framebuffer: Arc<Framebuffer>,
 
-    /// The offset from the top left corner of the framebuffer that will be rendered to.
+    /// The area of the framebuffer that will be rendered to.
     ///
-    /// The default value is `[0, 0]`.
-    pub render_area_offset: [u32; 2],
-
-    /// The size of the area that will be rendered to.
-    ///
-    /// `render_area_offset + render_area_extent` must not be greater than
+    /// If this is [`RenderArea::Partial`], then `offset + extent` must not be greater than
     /// [`framebuffer.extent()`].
     ///
-    /// The default value is [`framebuffer.extent()`].
-    pub render_area_extent: [u32; 2],
+    /// The default value is [`RenderArea::Full`].
+    pub render_area: RenderArea,
 
     /// Provides, for each attachment in `render_pass` that has a load operation of
     /// [`AttachmentLoadOp::Clear`], the clear values that should be used for the attachments in
@@ -1855,28 +2062,71 @@ pub struct RenderPassBeginInfo {
 impl RenderPassBeginInfo {
     #[inline]
     pub fn framebuffer(framebuffer: Arc<Framebuffer>) -> Self {
-        let render_area_extent = framebuffer.extent();
-
         Self {
             render_pass: framebuffer.render_pass().clone(),
             framebuffer,
-            render_area_offset: [0, 0],
-            render_area_extent,
+            render_area: RenderArea::Full,
             clear_values: Vec::new(),
             _ne: crate::NonExhaustive(()),
         }
     }
 
+    /// Sets `clear_values` from a list of `(attachment_index, clear_value)` pairs, filling in
+    /// `None` for every attachment that is not listed.
+    ///
+    /// This is a convenience for render passes where only some attachments are cleared, so that
+    /// the clear values do not need to be lined up positionally by hand. If an attachment index
+    /// appears more than once, the last value is used.
+    ///
+    /// Returns an error if an attachment index is not less than the number of attachments in
+    /// `render_pass`.
+    #[inline]
+    pub fn clear_value_map(
+        mut self,
+        clear_values: &[(u32, ClearValue)],
+    ) -> Result<Self, Box<ValidationError>> {
+        let attachment_count = self.render_pass.attachments().len();
+        self.clear_values = vec![None; attachment_count];
+
+        for (index, &(attachment_index, clear_value)) in clear_values.iter().enumerate() {
+            let slot = self
+                .clear_values
+                .get_mut(attachment_index as usize)
+                .ok_or_else(|| {
+                    Box::new(ValidationError {
+                        context: format!("clear_values[{}].0", index).into(),
+                        problem: format!(
+                            "is {}, which is not less than the number of attachments in \
+                            `render_pass` ({})",
+                            attachment_index, attachment_count,
+                        )
+                        .into(),
+                        ..Default::default()
+                    })
+                })?;
+            *slot = Some(clear_value);
+        }
+
+        Ok(self)
+    }
+
+    /// Returns the offset and extent of `render_area`.
+    #[inline]
+    pub(crate) fn render_area_offset_extent(&self) -> ([u32; 2], [u32; 2]) {
+        self.render_area.offset_extent(self.framebuffer.extent())
+    }
+
     pub(crate) fn validate(&self, device: &Device) -> Result<(), Box<ValidationError>> {
         let &Self {
             ref render_pass,
             ref framebuffer,
-            render_area_offset,
-            render_area_extent,
+            render_area: _,
             ref clear_values,
             _ne,
         } = self;
 
+        let (render_area_offset, render_area_extent) = self.render_area_offset_extent();
+
         // VUID-VkRenderPassBeginInfo-commonparent
         // VUID-vkCmdBeginRenderPass2-framebuffer-02779
         assert_eq!(device, framebuffer.device().as_ref());
@@ -1891,8 +2141,8 @@ impl RenderPassBeginInfo {
 
         if render_area_extent[0] == 0 {
             return Err(Box::new(ValidationError {
-                context: "render_area_extent[0]".into(),
-                problem: "is 0".into(),
+                context: "render_area".into(),
+                problem: "the width of the area is 0".into(),
                 vuids: &["VUID-VkRenderPassBeginInfo-None-08996"],
                 ..Default::default()
             }));
@@ -1900,29 +2150,30 @@ impl RenderPassBeginInfo {
 
         if render_area_extent[1] == 0 {
             return Err(Box::new(ValidationError {
-                context: "render_area_extent[1]".into(),
-                problem: "is 0".into(),
+                context: "render_area".into(),
+                problem: "the height of the area is 0".into(),
                 vuids: &["VUID-VkRenderPassBeginInfo-None-08997"],
                 ..Default::default()
             }));
         }
 
-        if render_area_offset[0] + render_area_extent[0] > framebuffer.extent()[0] {
-            return Err(Box::new(ValidationError {
-                problem: "`render_area_offset[0] + render_area_extent[0]` is greater than \
-                    `framebuffer.extent()[0]`"
-                    .into(),
-                vuids: &["VUID-VkRenderPassBeginInfo-pNext-02852"],
-                ..Default::default()
-            }));
-        }
-
-        if render_area_offset[1] + render_area_extent[1] > framebuffer.extent()[1] {
+        if render_area_offset[0].saturating_add(render_area_extent[0]) > framebuffer.extent()[0]
+            || render_area_offset[1].saturating_add(render_area_extent[1]) > framebuffer.extent()[1]
+        {
             return Err(Box::new(ValidationError {
-                problem: "`render_area_offset[1] + render_area_extent[1]` is greater than \
-                    `framebuffer.extent()[1]`"
-                    .into(),
-                vuids: &["VUID-VkRenderPassBeginInfo-pNext-02853"],
+                context: "render_area".into(),
+                problem: format!(
+                    "the area with offset {:?} and extent {:?} extends beyond `framebuffer`, \
+                    whose extent is {:?}",
+                    render_area_offset,
+                    render_area_extent,
+                    framebuffer.extent(),
+                )
+                .into(),
+                vuids: &[
+                    "VUID-VkRenderPassBeginInfo-pNext-02852",
+                    "VUID-VkRenderPassBeginInfo-pNext-02853",
+                ],
                 ..Default::default()
             }));
         }
@@ -1945,12 +2196,13 @@ impl RenderPassBeginInfo {
         {
             match (clear_value, attachment_desc.required_clear_value()) {
                 (None, None) => continue,
-                (None, Some(_)) => {
+                (None, Some(required_clear_value)) => {
                     return Err(Box::new(ValidationError {
                         problem: format!(
-                            "`render_pass.attachments()[{0}]` requires a clear value, but \
+                            "`render_pass.attachments()[{0}]` has the format `Format::{1:?}` and \
+                            requires a clear value of type `ClearValue::{2:?}`, but \
                             `clear_values[{0}]` is `None`",
-                            attachment_index
+                            attachment_index, attachment_desc.format, required_clear_value,
                         )
                         .into(),
                         ..Default::default()
@@ -1972,11 +2224,13 @@ impl RenderPassBeginInfo {
                         return Err(Box::new(ValidationError {
                             problem: format!(
                                 "`clear_values[{0}]` is `ClearValue::{1:?}`, but \
-                                `render_pass.attachments()[{0}]` requires a clear value of type \
-                                `ClearValue::{2:?}`",
+                                `render_pass.attachments()[{0}]` has the format `Format::{2:?}` \
+                                and requires a clear value of type `ClearValue::{3:?}`{4}",
                                 attachment_index,
                                 clear_value.clear_value_type(),
+                                attachment_desc.format,
                                 required_clear_value,
+                                required_clear_value.constructor_hint(),
                             )
                             .into(),
                             ..Default::default()
@@ -1999,6 +2253,9 @@ impl RenderPassBeginInfo {
 pub struct SubpassBeginInfo {
     /// What kinds of commands will be recorded in the subpass.
     ///
+    /// Each subpass of a render pass instance has its own contents, which are given when the
+    /// subpass is begun with `begin_render_pass` or `next_subpass`.
+    ///
     /// The default value is [`SubpassContents::Inline`].
     pub contents: SubpassContents,
 
@@ -2024,6 +2281,19 @@ impl SubpassBeginInfo {
                 .set_vuids(&["VUID-VkSubpassBeginInfo-contents-parameter"])
         })?;
 
+        if contents == SubpassContents::InlineAndSecondaryCommandBuffers
+            && !device.enabled_features().nested_command_buffer
+        {
+            return Err(Box::new(ValidationError {
+                context: "contents".into(),
+                problem: "is `SubpassContents::InlineAndSecondaryCommandBuffers`".into(),
+                requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceFeature(
+                    "nested_command_buffer",
+                )])]),
+                vuids: &["VUID-VkSubpassBeginInfo-contents-09382"],
+            }));
+        }
+
         Ok(())
     }
 }
@@ -2054,23 +2324,15 @@ impl SubpassEndInfo {
 /// Parameters to begin rendering.
 #[derive(Clone, Debug)]
 pub struct RenderingInfo {
-    /// The offset from the top left corner of the attachments that will be rendered to.
-    ///
-    /// This value must be smaller than the smallest width and height of the attachment images.
+    /// The area of the attachments that will be rendered to.
     ///
-    /// The default value is `[0, 0]`.
-    pub render_area_offset: [u32; 2],
-
-    /// The size of the area that will be rendered to.
-    ///
-    /// This value plus `render_area_offset` must be no larger than the smallest width and height
-    /// of the attachment images.
-    /// If one of the elements is set to 0, the extent will be calculated automatically from the
-    /// extents of the attachment images to be the largest allowed. At least one attachment image
-    /// must be specified in that case.
+    /// If this is [`RenderArea::Full`], then at least one attachment must be specified. If this is
+    /// [`RenderArea::Partial`], then `offset + extent` must be no larger than the smallest width
+    /// and height of the attachment image views, including resolve attachments, at their base mip
+    /// level.
     ///
-    /// The default value is `[0, 0]`.
-    pub render_area_extent: [u32; 2],
+    /// The default value is [`RenderArea::Full`].
+    pub render_area: RenderArea,
 
     /// The number of layers of the attachments that will be rendered to.
     ///
@@ -2119,14 +2381,32 @@ pub struct RenderingInfo {
     /// The default value is `None`.
     pub stencil_attachment: Option<RenderingAttachmentInfo>,
 
-    /// What kinds of commands will be recorded in the render pass: either inline draw commands, or
-    /// executions of secondary command buffers.
+    /// What kinds of commands will be recorded in the render pass: inline draw commands,
+    /// executions of secondary command buffers, or both.
     ///
     /// If recorded in a secondary command buffer, this must be [`SubpassContents::Inline`].
     ///
     /// The default value is [`SubpassContents::Inline`].
     pub contents: SubpassContents,
 
+    /// If `Some`, enables multisampled rendering to single-sampled attachments, with the given
+    /// number of rasterization samples.
+    ///
+    /// The attachment image views may then have either the given number of samples, or
+    /// [`SampleCount::Sample1`]. Single-sampled attachments are rendered to as if they had the
+    /// given number of samples, and are implicitly resolved at the end of rendering. Their images
+    /// must have been created with [`ImageCreateFlags::MULTISAMPLED_RENDER_TO_SINGLE_SAMPLED`].
+    ///
+    /// If this is `Some`, then the
+    /// [`multisampled_render_to_single_sampled`](crate::device::DeviceFeatures::multisampled_render_to_single_sampled)
+    /// feature must be enabled on the device, and the value must not be
+    /// [`SampleCount::Sample1`].
+    ///
+    /// The default value is `None`.
+    ///
+    /// [`ImageCreateFlags::MULTISAMPLED_RENDER_TO_SINGLE_SAMPLED`]: crate::image::ImageCreateFlags::MULTISAMPLED_RENDER_TO_SINGLE_SAMPLED
+    pub multisampled_render_to_single_sampled: Option<SampleCount>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -2134,106 +2414,79 @@ impl Default for RenderingInfo {
     #[inline]
     fn default() -> Self {
         Self {
-            render_area_offset: [0, 0],
-            render_area_extent: [0, 0],
+            render_area: RenderArea::Full,
             layer_count: 0,
             view_mask: 0,
             color_attachments: Vec::new(),
             depth_attachment: None,
             stencil_attachment: None,
             contents: SubpassContents::Inline,
+            multisampled_render_to_single_sampled: None,
             _ne: crate::NonExhaustive(()),
         }
     }
 }
 
 impl RenderingInfo {
-    pub(crate) fn set_auto_extent_layers(&mut self) {
-        let &mut RenderingInfo {
-            render_area_offset,
-            ref mut render_area_extent,
-            ref mut layer_count,
-            view_mask,
-            ref color_attachments,
-            ref depth_attachment,
-            ref stencil_attachment,
-            contents: _,
-            _ne: _,
-        } = self;
-
-        let is_auto_extent = render_area_extent[0] == 0 || render_area_extent[1] == 0;
-        let is_auto_layers = *layer_count == 0;
-
-        if (is_auto_extent || is_auto_layers)
-            && !(color_attachments.is_empty()
-                && depth_attachment.is_none()
-                && stencil_attachment.is_none())
-        {
-            let mut auto_extent = [u32::MAX, u32::MAX];
-            let mut auto_layers = if view_mask != 0 { 1 } else { u32::MAX };
-
-            if is_auto_extent {
-                *render_area_extent = [u32::MAX, u32::MAX];
-            }
+    // Returns an iterator over all attachment image views, including resolve attachments.
+    fn attachment_image_views(&self) -> impl Iterator<Item = &Arc<ImageView>> {
+        self.color_attachments
+            .iter()
+            .flatten()
+            .chain(self.depth_attachment.iter())
+            .chain(self.stencil_attachment.iter())
+            .flat_map(|attachment_info| {
+                Some(&attachment_info.image_view).into_iter().chain(
+                    attachment_info
+                        .resolve_info
+                        .as_ref()
+                        .map(|resolve_info| &resolve_info.image_view),
+                )
+            })
+    }
 
-            if is_auto_layers {
-                if view_mask != 0 {
-                    *layer_count = 1;
-                } else {
-                    *layer_count = u32::MAX;
-                }
-            }
+    /// Returns the offset and extent of `render_area`, or `None` if it is `RenderArea::Full` and
+    /// there are no attachments.
+    pub(crate) fn render_area_offset_extent(&self) -> Option<([u32; 2], [u32; 2])> {
+        let full_extent = self
+            .attachment_image_views()
+            .map(|image_view| {
+                let extent = image_view.extent();
+                [extent[0], extent[1]]
+            })
+            .reduce(|a, b| [a[0].min(b[0]), a[1].min(b[1])]);
 
-            for image_view in color_attachments
-                .iter()
-                .flatten()
-                .chain(depth_attachment.iter())
-                .chain(stencil_attachment.iter())
-                .flat_map(|attachment_info| {
-                    Some(&attachment_info.image_view).into_iter().chain(
-                        attachment_info
-                            .resolve_info
-                            .as_ref()
-                            .map(|resolve_info| &resolve_info.image_view),
-                    )
-                })
-            {
-                let image_view_extent = image_view.image().extent();
-                let image_view_array_layers =
-                    image_view.subresource_range().array_layers.len() as u32;
+        match self.render_area {
+            RenderArea::Full => full_extent.map(|full_extent| ([0, 0], full_extent)),
+            RenderArea::Partial { offset, extent } => Some((offset, extent)),
+        }
+    }
 
-                auto_extent[0] = auto_extent[0].min(image_view_extent[0]);
-                auto_extent[1] = auto_extent[1].min(image_view_extent[1]);
-                auto_layers = auto_layers.min(image_view_array_layers);
-            }
+    pub(crate) fn set_auto_layers(&mut self) {
+        if self.layer_count != 0 {
+            return;
+        }
 
-            if is_auto_extent {
-                // Subtract the offset from the calculated max extent.
-                // If there is an underflow, then the offset is too large, and validation should
-                // catch that later.
-                for i in 0..2 {
-                    render_area_extent[i] = auto_extent[i]
-                        .checked_sub(render_area_offset[i])
-                        .unwrap_or(1);
-                }
-            }
+        let auto_layers = self
+            .attachment_image_views()
+            .map(|image_view| image_view.subresource_range().array_layers.len() as u32)
+            .min();
 
-            if is_auto_layers {
-                *layer_count = auto_layers;
-            }
+        if let Some(auto_layers) = auto_layers {
+            self.layer_count = if self.view_mask != 0 { 1 } else { auto_layers };
         }
     }
 
     pub(crate) fn validate(&self, device: &Device) -> Result<(), Box<ValidationError>> {
         let &Self {
-            render_area_offset,
-            render_area_extent,
+            render_area: _,
             layer_count,
             view_mask,
             ref color_attachments,
             ref depth_attachment,
             ref stencil_attachment,
             contents,
+            multisampled_render_to_single_sampled,
             _ne: _,
         } = self;
 
@@ -2244,10 +2497,34 @@ impl RenderingInfo {
                 .set_vuids(&["VUID-VkRenderingInfo-flags-parameter"])
         })?;
 
+        if contents == SubpassContents::InlineAndSecondaryCommandBuffers
+            && !device.enabled_features().nested_command_buffer
+        {
+            return Err(Box::new(ValidationError {
+                context: "contents".into(),
+                problem: "is `SubpassContents::InlineAndSecondaryCommandBuffers`".into(),
+                requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceFeature(
+                    "nested_command_buffer",
+                )])]),
+                vuids: &["VUID-VkRenderingInfo-flags-09381"],
+            }));
+        }
+
+        let Some((render_area_offset, render_area_extent)) = self.render_area_offset_extent()
+        else {
+            return Err(Box::new(ValidationError {
+                context: "render_area".into(),
+                problem: "is `RenderArea::Full`, but there are no attachments to take the extent \
+                    from"
+                    .into(),
+                ..Default::default()
+            }));
+        };
+
         if render_area_extent[0] == 0 {
             return Err(Box::new(ValidationError {
-                context: "render_area_extent[0]".into(),
-                problem: "is 0".into(),
+                context: "render_area".into(),
+                problem: "the width of the area is 0".into(),
                 vuids: &["VUID-VkRenderingInfo-None-08994"],
                 ..Default::default()
             }));
@@ -2255,16 +2532,19 @@ impl RenderingInfo {
 
         if render_area_extent[1] == 0 {
             return Err(Box::new(ValidationError {
-                context: "render_area_extent[1]".into(),
-                problem: "is 0".into(),
+                context: "render_area".into(),
+                problem: "the height of the area is 0".into(),
                 vuids: &["VUID-VkRenderingInfo-None-08995"],
                 ..Default::default()
             }));
         }
 
-        if render_area_offset[0] + render_area_extent[0] > properties.max_framebuffer_width {
+        if render_area_offset[0].saturating_add(render_area_extent[0])
+            > properties.max_framebuffer_width
+        {
             return Err(Box::new(ValidationError {
-                problem: "`render_area_offset[0] + render_area_extent[0]` is greater than the \
+                context: "render_area".into(),
+                problem: "the offset plus the extent of the area is greater than the \
                     `max_framebuffer_width` limit"
                     .into(),
                 vuids: &["VUID-VkRenderingInfo-pNext-07815"],
@@ -2272,9 +2552,12 @@ impl RenderingInfo {
             }));
         }
 
-        if render_area_offset[1] + render_area_extent[1] > properties.max_framebuffer_height {
+        if render_area_offset[1].saturating_add(render_area_extent[1])
+            > properties.max_framebuffer_height
+        {
             return Err(Box::new(ValidationError {
-                problem: "`render_area_offset[1] + render_area_extent[1]` is greater than the \
+                context: "render_area".into(),
+                problem: "the offset plus the extent of the area is greater than the \
                     `max_framebuffer_height` limit"
                     .into(),
                 vuids: &["VUID-VkRenderingInfo-pNext-07816"],
@@ -2282,6 +2565,63 @@ impl RenderingInfo {
             }));
         }
 
+        let attachment_image_views = (color_attachments.iter().enumerate())
+            .filter_map(|(index, attachment_info)| {
+                attachment_info.as_ref().map(|attachment_info| {
+                    (format!("color_attachments[{}]", index), attachment_info)
+                })
+            })
+            .chain(
+                depth_attachment
+                    .iter()
+                    .map(|attachment_info| ("depth_attachment".to_owned(), attachment_info)),
+            )
+            .chain(
+                stencil_attachment
+                    .iter()
+                    .map(|attachment_info| ("stencil_attachment".to_owned(), attachment_info)),
+            )
+            .flat_map(|(name, attachment_info)| {
+                attachment_info
+                    .resolve_info
+                    .as_ref()
+                    .map(|resolve_info| {
+                        (
+                            format!("{}.resolve_info.image_view", name),
+                            &resolve_info.image_view,
+                        )
+                    })
+                    .into_iter()
+                    .chain([(format!("{}.image_view", name), &attachment_info.image_view)])
+            });
+
+        for (name, image_view) in attachment_image_views {
+            let image_view_extent = image_view.extent();
+
+            if render_area_offset[0].saturating_add(render_area_extent[0]) > image_view_extent[0]
+                || render_area_offset[1].saturating_add(render_area_extent[1])
+                    > image_view_extent[1]
+            {
+                return Err(Box::new(ValidationError {
+                    context: "render_area".into(),
+                    problem: format!(
+                        "the area with offset {:?} and extent {:?} extends beyond `{}`, whose \
+                        extent at its base mip level is {:?}",
+                        render_area_offset,
+                        render_area_extent,
+                        name,
+                        [image_view_extent[0], image_view_extent[1]],
+                    )
+                    .into(),
+                    vuids: &[
+                        "VUID-VkRenderingInfo-pNext-06079",
+                        "VUID-VkRenderingInfo-pNext-06080",
+                    ],
+                    ..Default::default()
+                }));
+            }
+        }
+
         // No VUID, but for sanity it makes sense to treat this the same as in framebuffers.
         if view_mask != 0 && layer_count != 1 {
             return Err(Box::new(ValidationError {
@@ -2315,6 +2655,148 @@ impl RenderingInfo {
             }));
         }
 
+        if view_mask != 0 {
+            for (context, attachment_info) in color_attachments
+                .iter()
+                .enumerate()
+                .filter_map(|(index, attachment_info)| {
+                    attachment_info.as_ref().map(|attachment_info| {
+                        (format!("color_attachments[{}]", index), attachment_info)
+                    })
+                })
+                .chain(
+                    depth_attachment
+                        .iter()
+                        .map(|attachment_info| ("depth_attachment".to_owned(), attachment_info)),
+                )
+                .chain(
+                    stencil_attachment
+                        .iter()
+                        .map(|attachment_info| ("stencil_attachment".to_owned(), attachment_info)),
+                )
+            {
+                for (context, image_view) in [(context.clone(), &attachment_info.image_view)]
+                    .into_iter()
+                    .chain(attachment_info.resolve_info.as_ref().map(|resolve_info| {
+                        (
+                            format!("{}.resolve_info", context),
+                            &resolve_info.image_view,
+                        )
+                    }))
+                {
+                    let array_layers = image_view.subresource_range().array_layers.len() as u32;
+
+                    if array_layers < highest_view_index {
+                        return Err(Box::new(ValidationError {
+                            problem: format!(
+                                "`view_mask` is not 0, and each view renders to the array \
+                                layer of `{0}.image_view` with the same index, but \
+                                `{0}.image_view` has {1} array layers, while the highest \
+                                enabled view index in `view_mask` is {2}",
+                                context,
+                                array_layers,
+                                highest_view_index - 1,
+                            )
+                            .into(),
+                            // vuids?
+                            ..Default::default()
+                        }));
+                    }
+                }
+            }
+        }
+
+        if let Some(rasterization_samples) = multisampled_render_to_single_sampled {
+            if !device
+                .enabled_features()
+                .multisampled_render_to_single_sampled
+            {
+                return Err(Box::new(ValidationError {
+                    context: "multisampled_render_to_single_sampled".into(),
+                    problem: "is `Some`".into(),
+                    requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceFeature(
+                        "multisampled_render_to_single_sampled",
+                    )])]),
+                    // vuids?
+                    ..Default::default()
+                }));
+            }
+
+            rasterization_samples.validate_device(device).map_err(|err| {
+                err.add_context("multisampled_render_to_single_sampled").set_vuids(&[
+                    "VUID-VkMultisampledRenderToSingleSampledInfoEXT-rasterizationSamples-parameter",
+                ])
+            })?;
+
+            if rasterization_samples == SampleCount::Sample1 {
+                return Err(Box::new(ValidationError {
+                    context: "multisampled_render_to_single_sampled".into(),
+                    problem: "is `Some(SampleCount::Sample1)`".into(),
+                    vuids: &[
+                        "VUID-VkMultisampledRenderToSingleSampledInfoEXT-rasterizationSamples-06878",
+                    ],
+                    ..Default::default()
+                }));
+            }
+
+            for (context, attachment_info) in color_attachments
+                .iter()
+                .enumerate()
+                .filter_map(|(index, attachment_info)| {
+                    attachment_info.as_ref().map(|attachment_info| {
+                        (format!("color_attachments[{}]", index), attachment_info)
+                    })
+                })
+                .chain(
+                    depth_attachment
+                        .iter()
+                        .map(|attachment_info| ("depth_attachment".to_owned(), attachment_info)),
+                )
+                .chain(
+                    stencil_attachment
+                        .iter()
+                        .map(|attachment_info| ("stencil_attachment".to_owned(), attachment_info)),
+                )
+            {
+                let image = attachment_info.image_view.image();
+
+                if image.samples() == SampleCount::Sample1 {
+                    if !image
+                        .flags()
+                        .intersects(ImageCreateFlags::MULTISAMPLED_RENDER_TO_SINGLE_SAMPLED)
+                    {
+                        return Err(Box::new(ValidationError {
+                            problem: format!(
+                                "`multisampled_render_to_single_sampled` is `Some`, and \
+                                `{0}.image_view.image().samples()` is `SampleCount::Sample1`, \
+                                but `{0}.image_view.image().flags()` does not contain \
+                                `ImageCreateFlags::MULTISAMPLED_RENDER_TO_SINGLE_SAMPLED`",
+                                context,
+                            )
+                            .into(),
+                            vuids: &["VUID-VkRenderingInfo-imageView-06859"],
+                            ..Default::default()
+                        }));
+                    }
+                } else if image.samples() != rasterization_samples {
+                    return Err(Box::new(ValidationError {
+                        problem: format!(
+                            "`multisampled_render_to_single_sampled` is `Some`, but \
+                            `{0}.image_view.image().samples()` is neither \
+                            `SampleCount::Sample1` nor equal to \
+                            `multisampled_render_to_single_sampled`",
+                            context,
+                        )
+                        .into(),
+                        vuids: &["VUID-VkRenderingInfo-imageView-06858"],
+                        ..Default::default()
+                    }));
+                }
+            }
+        }
+
+        // If multisampled rendering to single-sampled attachments is enabled, the number of
+        // samples was already checked above, and may differ between attachments.
         let mut samples = None;
 
         if color_attachments.len() > properties.max_color_attachments as usize {
@@ -2361,35 +2843,11 @@ impl RenderingInfo {
                 }));
             }
 
-            if render_area_offset[0] + render_area_extent[0] > image_view.image().extent()[0] {
-                return Err(Box::new(ValidationError {
-                    problem: format!(
-                        "`render_area_offset[0] + render_area_extent[0]` is greater than \
-                        `color_attachments[{}].image_view.image().extent()[0]`",
-                        attachment_index,
-                    )
-                    .into(),
-                    vuids: &["VUID-VkRenderingInfo-pNext-06079"],
-                    ..Default::default()
-                }));
-            }
-
-            if render_area_offset[1] + render_area_extent[1] > image_view.image().extent()[1] {
-                return Err(Box::new(ValidationError {
-                    problem: format!(
-                        "`render_area_offset[1] + render_area_extent[1]` is greater than \
-                        `color_attachments[{}].image_view.image().extent()[1]`",
-                        attachment_index,
-                    )
-                    .into(),
-                    vuids: &["VUID-VkRenderingInfo-pNext-06080"],
-                    ..Default::default()
-                }));
-            }
-
             match samples {
                 Some(samples) => {
-                    if samples != image_view.image().samples() {
+                    if multisampled_render_to_single_sampled.is_none()
+                        && samples != image_view.image().samples()
+                    {
                         return Err(Box::new(ValidationError {
                             problem: format!(
                                 "`color_attachments[{0}].image_view.image().samples()` \
@@ -2492,7 +2950,7 @@ impl RenderingInfo {
                 resolve_info,
                 load_op: _,
                 store_op: _,
-                clear_value: _,
+                clear_value,
                 _ne: _,
             } = attachment_info;
 
@@ -2509,6 +2967,26 @@ impl RenderingInfo {
                 }));
             }
 
+            if let Some(clear_value) = clear_value {
+                if !matches!(
+                    clear_value,
+                    ClearValue::Depth(_) | ClearValue::DepthStencil(_)
+                ) {
+                    return Err(Box::new(ValidationError {
+                        problem: format!(
+                            "`depth_attachment.clear_value` is `ClearValue::{:?}`, but \
+                            `depth_attachment.image_view.format()` is `Format::{:?}`, which \
+                            requires a clear value of type `ClearValue::Depth` or \
+                            `ClearValue::DepthStencil`",
+                            clear_value.clear_value_type(),
+                            image_view.format(),
+                        )
+                        .into(),
+                        ..Default::default()
+                    }));
+                }
+            }
+
             if !image_view
                 .usage()
                 .intersects(ImageUsage::DEPTH_STENCIL_ATTACHMENT)
@@ -2521,29 +2999,11 @@ impl RenderingInfo {
                 }));
             }
 
-            if render_area_offset[0] + render_area_extent[0] > image_view.image().extent()[0] {
-                return Err(Box::new(ValidationError {
-                    problem: "`render_area_offset[0] + render_area_extent[0]` is greater than \
-                        `depth_attachment.image_view.image().extent()[0]`"
-                        .into(),
-                    vuids: &["VUID-VkRenderingInfo-pNext-06079"],
-                    ..Default::default()
-                }));
-            }
-
-            if render_area_offset[1] + render_area_extent[1] > image_view.image().extent()[1] {
-                return Err(Box::new(ValidationError {
-                    problem: "`render_area_offset[1] + render_area_extent[1]` is greater than \
-                        `depth_attachment.image_view.image().extent()[1]`"
-                        .into(),
-                    vuids: &["VUID-VkRenderingInfo-pNext-06080"],
-                    ..Default::default()
-                }));
-            }
-
             match samples {
                 Some(samples) => {
-                    if samples != image_view.image().samples() {
+                    if multisampled_render_to_single_sampled.is_none()
+                        && samples != image_view.image().samples()
+                    {
                         return Err(Box::new(ValidationError {
                             problem: "`depth_attachment.image_view.image().samples()` \
                                 is not equal to the number of samples of the other attachments"
@@ -2616,7 +3076,7 @@ impl RenderingInfo {
                 resolve_info,
                 load_op: _,
                 store_op: _,
-                clear_value: _,
+                clear_value,
                 _ne: _,
             } = attachment_info;
 
@@ -2633,6 +3093,26 @@ impl RenderingInfo {
                 }));
             }
 
+            if let Some(clear_value) = clear_value {
+                if !matches!(
+                    clear_value,
+                    ClearValue::Stencil(_) | ClearValue::DepthStencil(_)
+                ) {
+                    return Err(Box::new(ValidationError {
+                        problem: format!(
+                            "`stencil_attachment.clear_value` is `ClearValue::{:?}`, but \
+                            `stencil_attachment.image_view.format()` is `Format::{:?}`, which \
+                            requires a clear value of type `ClearValue::Stencil` or \
+                            `ClearValue::DepthStencil`",
+                            clear_value.clear_value_type(),
+                            image_view.format(),
+                        )
+                        .into(),
+                        ..Default::default()
+                    }));
+                }
+            }
+
             if !image_view
                 .usage()
                 .intersects(ImageUsage::DEPTH_STENCIL_ATTACHMENT)
@@ -2645,28 +3125,10 @@ impl RenderingInfo {
                 }));
             }
 
-            if render_area_offset[0] + render_area_extent[0] > image_view.image().extent()[0] {
-                return Err(Box::new(ValidationError {
-                    problem: "`render_area_offset[0] + render_area_extent[0]` is greater than \
-                        `stencil_attachment.image_view.image().extent()[0]`"
-                        .into(),
-                    vuids: &["VUID-VkRenderingInfo-pNext-06079"],
-                    ..Default::default()
-                }));
-            }
-
-            if render_area_offset[1] + render_area_extent[1] > image_view.image().extent()[1] {
-                return Err(Box::new(ValidationError {
-                    problem: "`render_area_offset[1] + render_area_extent[1]` is greater than \
-                        `stencil_attachment.image_view.image().extent()[1]`"
-                        .into(),
-                    vuids: &["VUID-VkRenderingInfo-pNext-06080"],
-                    ..Default::default()
-                }));
-            }
-
             if let Some(samples) = samples {
-                if samples != image_view.image().samples() {
+                if multisampled_render_to_single_sampled.is_none()
+                    && samples != image_view.image().samples()
+                {
                     return Err(Box::new(ValidationError {
                         problem: "`stencil_attachment.image_view.image().samples()` \
                             is not equal to the number of samples of the other attachments"
@@ -2998,6 +3460,30 @@ impl RenderingAttachmentInfo {
                 clear_value
                     .validate(device)
                     .map_err(|err| err.add_context("clear_value"))?;
+
+                if let Some(numeric_format) = image_view.format().numeric_format_color() {
+                    let required_clear_value = match numeric_format.numeric_type() {
+                        NumericType::Float => ClearValueType::Float,
+                        NumericType::Int => ClearValueType::Int,
+                        NumericType::Uint => ClearValueType::Uint,
+                    };
+
+                    if clear_value.clear_value_type() != required_clear_value {
+                        return Err(Box::new(ValidationError {
+                            problem: format!(
+                                "`clear_value` is `ClearValue::{:?}`, but \
+                                `image_view.format()` is `Format::{:?}`, which requires a \
+                                clear value of type `ClearValue::{:?}`{}",
+                                clear_value.clear_value_type(),
+                                image_view.format(),
+                                required_clear_value,
+                                required_clear_value.constructor_hint(),
+                            )
+                            .into(),
+                            ..Default::default()
+                        }));
+                    }
+                }
             }
         };
 
@@ -3234,3 +3720,323 @@ pub struct ClearRect {
     /// The range of array layers to be cleared.
     pub array_layers: Range<u32>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ClearAttachment, ClearRect, RenderArea, RenderPassBeginInfo, RenderingAttachmentInfo,
+        RenderingInfo, SubpassBeginInfo,
+    };
+    use crate::{
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, ClearColorImageInfo, CommandBufferBeginInfo,
+            CommandBufferLevel, CommandBufferUsage, RecordingCommandBuffer, SubpassContents,
+        },
+        format::{ClearColorValue, ClearValue, Format},
+        image::{
+            view::{ImageView, ImageViewCreateInfo},
+            Image, ImageCreateInfo, ImageSubresourceRange, ImageUsage,
+        },
+        memory::allocator::{AllocationCreateInfo, StandardMemoryAllocator},
+        ordered_passes_renderpass,
+        render_pass::{AttachmentLoadOp, Framebuffer, FramebufferCreateInfo},
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn rendering_render_area() {
+        let (device, _) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+        let create_image = |extent, mip_levels| {
+            Image::new(
+                memory_allocator.clone(),
+                ImageCreateInfo {
+                    format: Format::R8G8B8A8_UNORM,
+                    extent,
+                    mip_levels,
+                    usage: ImageUsage::COLOR_ATTACHMENT,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+            )
+            .unwrap()
+        };
+
+        // The second mip level of a 128x128 image is 64x64.
+        let mipmapped_image = create_image([128, 128, 1], 2);
+        let mip_level_view = ImageView::new(
+            mipmapped_image.clone(),
+            ImageViewCreateInfo {
+                subresource_range: ImageSubresourceRange {
+                    mip_levels: 1..2,
+                    ..mipmapped_image.subresource_range()
+                },
+                ..ImageViewCreateInfo::from_image(&mipmapped_image)
+            },
+        )
+        .unwrap();
+        let small_view = ImageView::new_default(create_image([96, 48, 1], 1)).unwrap();
+        assert_eq!(mip_level_view.extent(), [64, 64, 1]);
+
+        let rendering_info = RenderingInfo {
+            color_attachments: vec![
+                Some(RenderingAttachmentInfo::image_view(mip_level_view)),
+                None,
+                Some(RenderingAttachmentInfo::image_view(small_view)),
+            ],
+            layer_count: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            rendering_info.render_area_offset_extent(),
+            Some(([0, 0], [64, 48])),
+        );
+        rendering_info.validate(&device).unwrap();
+
+        let err = RenderingInfo {
+            render_area: RenderArea::Partial {
+                offset: [0, 0],
+                extent: [64, 64],
+            },
+            ..rendering_info.clone()
+        }
+        .validate(&device)
+        .unwrap_err();
+        assert!(err
+            .problem
+            .contains("extends beyond `color_attachments[2].image_view`"));
+
+        RenderingInfo {
+            render_area: RenderArea::Partial {
+                offset: [16, 16],
+                extent: [48, 32],
+            },
+            ..rendering_info
+        }
+        .validate(&device)
+        .unwrap();
+
+        assert!(RenderingInfo::default().validate(&device).is_err());
+    }
+
+    #[test]
+    fn subpass_contents() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+
+        let render_pass = ordered_passes_renderpass!(
+            device.clone(),
+            attachments: {
+                color: {
+                    format: Format::R8G8B8A8_UNORM,
+                    samples: 1,
+                    load_op: DontCare,
+                    store_op: Store,
+                },
+            },
+            passes: [
+                { color: [color], depth_stencil: {}, input: [] },
+                { color: [color], depth_stencil: {}, input: [] },
+            ],
+        )
+        .unwrap();
+        let image = Image::new(
+            memory_allocator,
+            ImageCreateInfo {
+                format: Format::R8G8B8A8_UNORM,
+                extent: [4, 4, 1],
+                usage: ImageUsage::COLOR_ATTACHMENT,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+        )
+        .unwrap();
+        let framebuffer = Framebuffer::new(
+            render_pass,
+            FramebufferCreateInfo {
+                attachments: vec![ImageView::new_default(image).unwrap()],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let mut cbb = RecordingCommandBuffer::new(
+            cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let clear = [ClearAttachment::Color {
+            color_attachment: 0,
+            clear_value: ClearColorValue::Float([0.0; 4]),
+        }];
+        let rects = [ClearRect {
+            offset: [0, 0],
+            extent: [4, 4],
+            array_layers: 0..1,
+        }];
+
+        // Subpass 0 is recorded inline, subpass 1 with secondary command buffers.
+        cbb.begin_render_pass(
+            RenderPassBeginInfo::framebuffer(framebuffer),
+            SubpassBeginInfo {
+                contents: SubpassContents::Inline,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        cbb.clear_attachments(
+            clear.iter().copied().collect(),
+            rects.iter().cloned().collect(),
+        )
+        .unwrap();
+        cbb.next_subpass(
+            Default::default(),
+            SubpassBeginInfo {
+                contents: SubpassContents::SecondaryCommandBuffers,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let err = cbb
+            .clear_attachments(
+                clear.iter().copied().collect(),
+                rects.iter().cloned().collect(),
+            )
+            .unwrap_err();
+        assert!(err.problem.contains("subpass 1"));
+
+        // Mixed contents require the `nested_command_buffer` feature.
+        assert!(SubpassBeginInfo {
+            contents: SubpassContents::InlineAndSecondaryCommandBuffers,
+            ..Default::default()
+        }
+        .validate(&device)
+        .is_err());
+    }
+
+    #[test]
+    fn clear_value_types() {
+        let (device, _) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+        let create_view = |format, usage| {
+            let image = Image::new(
+                memory_allocator.clone(),
+                ImageCreateInfo {
+                    format,
+                    extent: [4, 4, 1],
+                    usage,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+            )
+            .unwrap();
+            ImageView::new_default(image).unwrap()
+        };
+
+        let render_pass = ordered_passes_renderpass!(
+            device.clone(),
+            attachments: {
+                uint: {
+                    format: Format::R8G8B8A8_UINT,
+                    samples: 1,
+                    load_op: DontCare,
+                    store_op: Store,
+                },
+                color: {
+                    format: Format::R8G8B8A8_UNORM,
+                    samples: 1,
+                    load_op: Clear,
+                    store_op: Store,
+                },
+            },
+            passes: [
+                { color: [uint, color], depth_stencil: {}, input: [] },
+            ],
+        )
+        .unwrap();
+        let uint_view = create_view(Format::R8G8B8A8_UINT, ImageUsage::COLOR_ATTACHMENT);
+        let framebuffer = Framebuffer::new(
+            render_pass,
+            FramebufferCreateInfo {
+                attachments: vec![
+                    uint_view.clone(),
+                    create_view(Format::R8G8B8A8_UNORM, ImageUsage::COLOR_ATTACHMENT),
+                ],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // Attachment indices past the end of the render pass are rejected.
+        let err = RenderPassBeginInfo::framebuffer(framebuffer.clone())
+            .clear_value_map(&[(2, ClearValue::Float([0.0; 4]))])
+            .unwrap_err();
+        assert_eq!(err.context, "clear_values[0].0");
+
+        // Only the listed attachment gets a clear value.
+        let begin_info = RenderPassBeginInfo::framebuffer(framebuffer.clone())
+            .clear_value_map(&[(1, ClearValue::Float([0.0; 4]))])
+            .unwrap();
+        assert_eq!(begin_info.clear_values.len(), 2);
+        assert!(begin_info.clear_values[0].is_none());
+        begin_info.validate(&device).unwrap();
+
+        // A missing clear value for a cleared attachment is reported.
+        let err = RenderPassBeginInfo::framebuffer(framebuffer.clone())
+            .clear_value_map(&[])
+            .unwrap()
+            .validate(&device)
+            .unwrap_err();
+        assert!(err.problem.contains("`clear_values[1]` is `None`"));
+
+        // The clear value must match the numeric type of the attachment format.
+        let err = RenderPassBeginInfo::framebuffer(framebuffer)
+            .clear_value_map(&[(1, ClearValue::Uint([0; 4]))])
+            .unwrap()
+            .validate(&device)
+            .unwrap_err();
+        assert!(err.problem.contains("`Format::R8G8B8A8_UNORM`"));
+        assert!(err.problem.contains("`ClearValue::Float`"));
+
+        // The same check applies to dynamic rendering attachments.
+        let attachment_info = RenderingAttachmentInfo {
+            load_op: AttachmentLoadOp::Clear,
+            clear_value: Some(ClearValue::Float([0.0; 4])),
+            ..RenderingAttachmentInfo::image_view(uint_view)
+        };
+        let err = attachment_info.validate(&device).unwrap_err();
+        assert!(err.problem.contains("`ClearValue::Uint`"));
+        RenderingAttachmentInfo {
+            clear_value: Some(ClearValue::Uint([0; 4])),
+            ..attachment_info
+        }
+        .validate(&device)
+        .unwrap();
+
+        // And to `clear_color_image`.
+        let image = create_view(Format::R8G8B8A8_UNORM, ImageUsage::TRANSFER_DST)
+            .image()
+            .clone();
+        let err = ClearColorImageInfo {
+            clear_value: ClearColorValue::Uint([0; 4]),
+            ..ClearColorImageInfo::image(image.clone())
+        }
+        .validate(&device)
+        .unwrap_err();
+        assert!(err.problem.contains("`Format::R8G8B8A8_UNORM`"));
+        ClearColorImageInfo::image(image).validate(&device).unwrap();
+    }
+}
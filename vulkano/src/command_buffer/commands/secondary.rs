@@ -4,7 +4,7 @@ use crate::{
         sys::{RawCommandBuffer, RawRecordingCommandBuffer},
         CommandBuffer, CommandBufferInheritanceRenderPassType, CommandBufferLevel,
         RecordingCommandBuffer, ResourceInCommand, SecondaryCommandBufferBufferUsage,
-        SecondaryCommandBufferImageUsage, SecondaryCommandBufferResourcesUsage, SubpassContents,
+        SecondaryCommandBufferImageUsage, SecondaryCommandBufferResourcesUsage,
     },
     device::{DeviceOwned, QueueFlags},
     query::QueryType,
@@ -28,7 +28,8 @@ impl RecordingCommandBuffer {
         command_buffer: Arc<CommandBuffer>,
     ) -> Result<&mut Self, Box<ValidationError>> {
         let command_buffer = DropUnlockCommandBuffer::new(command_buffer)?;
-        self.validate_execute_commands(iter::once(&**command_buffer))?;
+        self.validate_execute_commands(iter::once(&**command_buffer))
+            .map_err(|err| self.command_error(err, "execute_commands"))?;
 
         unsafe { Ok(self.execute_commands_locked(smallvec![command_buffer])) }
     }
@@ -48,7 +49,8 @@ impl RecordingCommandBuffer {
             .map(DropUnlockCommandBuffer::new)
             .collect::<Result<_, _>>()?;
 
-        self.validate_execute_commands(command_buffers.iter().map(|cb| &***cb))?;
+        self.validate_execute_commands(command_buffers.iter().map(|cb| &***cb))
+            .map_err(|err| self.command_error(err, "execute_commands"))?;
 
         unsafe { Ok(self.execute_commands_locked(command_buffers)) }
     }
@@ -61,11 +63,19 @@ impl RecordingCommandBuffer {
             .validate_execute_commands(command_buffers.clone().map(|cb| cb.inner()))?;
 
         if let Some(render_pass_state) = &self.builder_state.render_pass {
-            if render_pass_state.contents != SubpassContents::SecondaryCommandBuffers {
+            if !render_pass_state
+                .contents
+                .allows_secondary_command_buffers()
+            {
                 return Err(Box::new(ValidationError {
-                    problem: "a render pass instance is active, but its current subpass contents \
-                        is `SubpassContents::SecondaryCommandBuffers`"
-                        .into(),
+                    problem: format!(
+                        "the contents of subpass {} of the current render pass instance is \
+                        `SubpassContents::{:?}`, which does not allow executing secondary \
+                        command buffers",
+                        render_pass_state.subpass_index(),
+                        render_pass_state.contents,
+                    )
+                    .into(),
                     vuids: &[
                         "VUID-vkCmdExecuteCommands-contents-06018",
                         "VUID-vkCmdExecuteCommands-flags-06024",
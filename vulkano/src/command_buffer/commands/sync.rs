@@ -1,6 +1,7 @@
 use crate::{
-    command_buffer::sys::RawRecordingCommandBuffer,
+    command_buffer::{sys::RawRecordingCommandBuffer, RecordingCommandBuffer},
     device::{DeviceOwned, QueueFlags},
+    swapchain::Swapchain,
     sync::{
         event::Event, BufferMemoryBarrier, DependencyFlags, DependencyInfo, ImageMemoryBarrier,
         MemoryBarrier, PipelineStages,
@@ -10,6 +11,37 @@ use crate::{
 use smallvec::SmallVec;
 use std::{ptr, sync::Arc};
 
+impl RecordingCommandBuffer {
+    /// Records one half of a queue family ownership transfer of a swapchain image with exclusive
+    /// sharing, which stays in the `PresentSrc` layout. This is the release if the command buffer
+    /// belongs to `src_queue_family_index`, and the acquire otherwise.
+    ///
+    /// The image is not tracked by the command buffer, so the caller must make sure that the
+    /// transfer is synchronized with the other uses of the image.
+    pub(crate) unsafe fn swapchain_image_ownership_transfer_unchecked(
+        &mut self,
+        swapchain: Arc<Swapchain>,
+        image_index: u32,
+        src_queue_family_index: u32,
+        dst_queue_family_index: u32,
+    ) -> &mut Self {
+        self.add_command(
+            "swapchain_image_ownership_transfer",
+            Default::default(),
+            move |out: &mut RawRecordingCommandBuffer| {
+                out.swapchain_image_ownership_transfer_unchecked(
+                    &swapchain,
+                    image_index,
+                    src_queue_family_index,
+                    dst_queue_family_index,
+                );
+            },
+        );
+
+        self
+    }
+}
+
 impl RawRecordingCommandBuffer {
     #[inline]
     pub unsafe fn pipeline_barrier(
@@ -452,6 +484,65 @@ impl RawRecordingCommandBuffer {
         self
     }
 
+    pub(crate) unsafe fn swapchain_image_ownership_transfer_unchecked(
+        &mut self,
+        swapchain: &Swapchain,
+        image_index: u32,
+        src_queue_family_index: u32,
+        dst_queue_family_index: u32,
+    ) -> &mut Self {
+        // The release only needs to make the writes of the previous commands available, and the
+        // acquire only needs to happen before the present operation.
+        let (src_stage_mask, src_access_mask, dst_stage_mask) =
+            if self.queue_family_index() == src_queue_family_index {
+                (
+                    ash::vk::PipelineStageFlags::ALL_COMMANDS,
+                    ash::vk::AccessFlags::MEMORY_WRITE,
+                    ash::vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                )
+            } else {
+                (
+                    ash::vk::PipelineStageFlags::TOP_OF_PIPE,
+                    ash::vk::AccessFlags::empty(),
+                    ash::vk::PipelineStageFlags::ALL_COMMANDS,
+                )
+            };
+
+        let image_memory_barrier_vk = ash::vk::ImageMemoryBarrier {
+            src_access_mask,
+            dst_access_mask: ash::vk::AccessFlags::empty(),
+            old_layout: ash::vk::ImageLayout::PRESENT_SRC_KHR,
+            new_layout: ash::vk::ImageLayout::PRESENT_SRC_KHR,
+            src_queue_family_index,
+            dst_queue_family_index,
+            image: swapchain.image_handle(image_index),
+            subresource_range: ash::vk::ImageSubresourceRange {
+                aspect_mask: ash::vk::ImageAspectFlags::COLOR,
+                base_mip_level: 0,
+                level_count: 1,
+                base_array_layer: 0,
+                layer_count: swapchain.image_array_layers(),
+            },
+            ..Default::default()
+        };
+
+        let fns = self.device().fns();
+        (fns.v1_0.cmd_pipeline_barrier)(
+            self.handle(),
+            src_stage_mask,
+            dst_stage_mask,
+            ash::vk::DependencyFlags::empty(),
+            0,
+            ptr::null(),
+            0,
+            ptr::null(),
+            1,
+            &image_memory_barrier_vk,
+        );
+
+        self
+    }
+
     #[inline]
     pub unsafe fn set_event(
         &mut self,
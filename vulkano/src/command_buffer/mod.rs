@@ -96,8 +96,8 @@
 
 #[allow(unused_imports)] // everything is exported for future-proofing
 pub use self::commands::{
-    acceleration_structure::*, clear::*, copy::*, debug::*, dynamic_state::*, pipeline::*,
-    query::*, render_pass::*, secondary::*, sync::*,
+    acceleration_structure::*, bind_push::*, clear::*, copy::*, debug::*, dynamic_state::*,
+    pipeline::*, query::*, render_pass::*, secondary::*, sync::*,
 };
 pub use self::{
     auto::{CommandBuffer, RecordingCommandBuffer},
@@ -114,6 +114,7 @@ use crate::{
     range_map::RangeMap,
     render_pass::{Framebuffer, Subpass},
     sync::{
+        fence::Fence,
         semaphore::{Semaphore, SemaphoreType},
         PipelineStageAccessFlags, PipelineStages,
     },
@@ -238,6 +239,37 @@ vulkan_enum! {
 
     /// The subpass will only contain secondary command buffers invocations.
     SecondaryCommandBuffers = SECONDARY_COMMAND_BUFFERS,
+
+    /// The subpass can contain both commands and secondary command buffer invocations.
+    ///
+    /// The [`nested_command_buffer`](DeviceFeatures::nested_command_buffer) feature must be
+    /// enabled on the device.
+    InlineAndSecondaryCommandBuffers = INLINE_AND_SECONDARY_COMMAND_BUFFERS_EXT
+    RequiresOneOf([
+        RequiresAllOf([DeviceExtension(ext_nested_command_buffer)]),
+    ]),
+}
+
+impl SubpassContents {
+    /// Returns whether commands can be recorded directly in a subpass with these contents.
+    #[inline]
+    pub fn allows_inline(self) -> bool {
+        matches!(
+            self,
+            SubpassContents::Inline | SubpassContents::InlineAndSecondaryCommandBuffers
+        )
+    }
+
+    /// Returns whether secondary command buffers can be executed in a subpass with these
+    /// contents.
+    #[inline]
+    pub fn allows_secondary_command_buffers(self) -> bool {
+        matches!(
+            self,
+            SubpassContents::SecondaryCommandBuffers
+                | SubpassContents::InlineAndSecondaryCommandBuffers
+        )
+    }
 }
 
 impl From<SubpassContents> for ash::vk::RenderingFlags {
@@ -246,6 +278,9 @@ impl From<SubpassContents> for ash::vk::RenderingFlags {
         match val {
             SubpassContents::Inline => Self::empty(),
             SubpassContents::SecondaryCommandBuffers => Self::CONTENTS_SECONDARY_COMMAND_BUFFERS,
+            SubpassContents::InlineAndSecondaryCommandBuffers => {
+                Self::CONTENTS_INLINE_EXT | Self::CONTENTS_SECONDARY_COMMAND_BUFFERS
+            }
         }
     }
 }
@@ -1148,13 +1183,34 @@ impl SemaphoreSubmitInfo {
 
 #[derive(Debug, Default)]
 pub struct CommandBufferState {
-    has_been_submitted: bool,
+    submit_count: u64,
     pending_submits: u32,
+    // The fence that is signaled when the pending submission finishes. This is only tracked for
+    // command buffers that can't be pending more than once at a time.
+    pending_fence: Option<Arc<Fence>>,
+    resource_locks: ResourceLocks,
+}
+
+/// Who releases the locks that the pending submission of a command buffer holds on its
+/// resources.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum ResourceLocks {
+    /// The locks are released when the future of the submission is cleaned up.
+    #[default]
+    Held,
+    /// A host access waited for the fence of the submission, and is releasing the locks.
+    Releasing,
+    /// A host access waited for the fence of the submission, and has released the locks.
+    Released,
 }
 
 impl CommandBufferState {
+    pub(crate) fn submit_count(&self) -> u64 {
+        self.submit_count
+    }
+
     pub(crate) fn has_been_submitted(&self) -> bool {
-        self.has_been_submitted
+        self.submit_count != 0
     }
 
     pub(crate) fn is_submit_pending(&self) -> bool {
@@ -1162,12 +1218,30 @@ impl CommandBufferState {
     }
 
     pub(crate) unsafe fn add_queue_submit(&mut self) {
-        self.has_been_submitted = true;
+        self.submit_count += 1;
         self.pending_submits += 1;
     }
 
     pub(crate) unsafe fn set_submit_finished(&mut self) {
         self.pending_submits -= 1;
+        self.pending_fence = None;
+        self.resource_locks = ResourceLocks::Held;
+    }
+
+    pub(crate) fn pending_fence(&self) -> Option<&Arc<Fence>> {
+        self.pending_fence.as_ref()
+    }
+
+    pub(crate) unsafe fn set_pending_fence(&mut self, fence: Arc<Fence>) {
+        self.pending_fence = Some(fence);
+    }
+
+    pub(crate) fn resource_locks(&self) -> ResourceLocks {
+        self.resource_locks
+    }
+
+    pub(crate) unsafe fn set_resource_locks(&mut self, resource_locks: ResourceLocks) {
+        self.resource_locks = resource_locks;
     }
 }
 
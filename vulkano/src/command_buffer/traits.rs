@@ -1,4 +1,7 @@
-use super::{CommandBuffer, CommandBufferSubmitInfo, SemaphoreSubmitInfo, SubmitInfo};
+use super::{
+    CommandBuffer, CommandBufferSubmitInfo, CommandBufferUsage, ResourceLocks, SemaphoreSubmitInfo,
+    SubmitInfo,
+};
 use crate::{
     buffer::Buffer,
     device::{Device, DeviceOwned, Queue},
@@ -24,6 +27,7 @@ use std::{
         Arc,
     },
     thread,
+    time::Instant,
 };
 
 impl CommandBuffer {
@@ -102,6 +106,10 @@ impl CommandBuffer {
             assert_eq!(future.queue().unwrap(), queue);
         }
 
+        if self.usage() == CommandBufferUsage::OneTimeSubmit && self.state().has_been_submitted() {
+            return Err(CommandBufferExecError::OneTimeSubmitAlreadySubmitted);
+        }
+
         Ok(CommandBufferExecFuture {
             previous: future,
             command_buffer: self,
@@ -112,6 +120,83 @@ impl CommandBuffer {
     }
 }
 
+impl CommandBuffer {
+    /// Releases the locks that a finished submission of the command buffer holds on its
+    /// resources.
+    unsafe fn release_resource_locks(self: &Arc<Self>) {
+        let resource_usage = self.resources_usage();
+
+        for usage in &resource_usage.buffers {
+            let mut state = usage.buffer.state();
+
+            for (range, range_usage) in usage.ranges.iter() {
+                if range_usage.mutable {
+                    state.gpu_write_unlock(range.clone());
+                } else {
+                    state.gpu_read_unlock(range.clone());
+                }
+            }
+
+            state.remove_pending_command_buffer(self);
+            usage.buffer.notify_state_released();
+        }
+
+        for usage in &resource_usage.images {
+            let mut state = usage.image.state();
+
+            for (range, range_usage) in usage.ranges.iter() {
+                if range_usage.mutable {
+                    state.gpu_write_unlock(range.clone());
+                } else {
+                    state.gpu_read_unlock(range.clone());
+                }
+            }
+        }
+    }
+
+    /// Waits for the fence of the pending submission of the command buffer, if there is one, and
+    /// releases the locks that the submission holds on its resources, without waiting for its
+    /// future to be cleaned up. Returns `VulkanError::Timeout` if the fence wasn't signaled before
+    /// `deadline`.
+    ///
+    /// The caller must not hold the lock on the state of any of the resources of the command
+    /// buffer.
+    pub(crate) fn wait_pending_submission(
+        self: &Arc<Self>,
+        deadline: Option<Instant>,
+    ) -> Result<(), VulkanError> {
+        let fence = {
+            let state = self.state();
+
+            match (state.resource_locks(), state.pending_fence()) {
+                (ResourceLocks::Held, Some(fence)) => fence.clone(),
+                _ => return Ok(()),
+            }
+        };
+
+        fence.wait(deadline.map(|deadline| deadline.saturating_duration_since(Instant::now())))?;
+
+        let mut state = self.state();
+
+        // The submission may have been released in the meantime, in which case the command buffer
+        // may even have been submitted again.
+        if state.resource_locks() == ResourceLocks::Held
+            && state
+                .pending_fence()
+                .map_or(false, |pending_fence| Arc::ptr_eq(pending_fence, &fence))
+        {
+            unsafe {
+                state.set_resource_locks(ResourceLocks::Releasing);
+                drop(state);
+                self.release_resource_locks();
+                self.state().set_resource_locks(ResourceLocks::Released);
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Represents a command buffer being executed by the GPU and the moment when the execution
 /// finishes.
 #[derive(Debug)]
@@ -212,7 +297,7 @@ where
             match self.build_submission_impl()? {
                 SubmitAnyBuilder::Empty => {}
                 SubmitAnyBuilder::CommandBuffer(submit_info, fence) => {
-                    queue_submit(&self.queue, submit_info, fence, &self.previous).unwrap();
+                    queue_submit(&self.queue, submit_info, fence, &self.previous)?;
                 }
                 _ => unreachable!(),
             };
@@ -225,28 +310,26 @@ where
 
     unsafe fn signal_finished(&self) {
         if !self.finished.swap(true, Ordering::SeqCst) {
-            let resource_usage = self.command_buffer.resources_usage();
-
-            for usage in &resource_usage.buffers {
-                let mut state = usage.buffer.state();
-
-                for (range, range_usage) in usage.ranges.iter() {
-                    if range_usage.mutable {
-                        state.gpu_write_unlock(range.clone());
-                    } else {
-                        state.gpu_read_unlock(range.clone());
-                    }
-                }
-            }
-
-            for usage in &resource_usage.images {
-                let mut state = usage.image.state();
-
-                for (range, range_usage) in usage.ranges.iter() {
-                    if range_usage.mutable {
-                        state.gpu_write_unlock(range.clone());
-                    } else {
-                        state.gpu_read_unlock(range.clone());
+            if self.command_buffer.usage() == CommandBufferUsage::SimultaneousUse {
+                self.command_buffer.release_resource_locks();
+            } else {
+                loop {
+                    let mut state = self.command_buffer.state();
+
+                    match state.resource_locks() {
+                        ResourceLocks::Held => {
+                            state.set_resource_locks(ResourceLocks::Released);
+                            drop(state);
+                            self.command_buffer.release_resource_locks();
+                            break;
+                        }
+                        // A host access is releasing the locks, and the command buffer must not
+                        // be submitted again until it's done.
+                        ResourceLocks::Releasing => {
+                            drop(state);
+                            thread::yield_now();
+                        }
+                        ResourceLocks::Released => break,
                     }
                 }
             }
@@ -375,8 +458,12 @@ where
 {
     fn drop(&mut self) {
         if !*self.finished.get_mut() && !thread::panicking() {
-            // TODO: handle errors?
-            self.flush().unwrap();
+            // If flushing fails, then the command buffer was never submitted, and there is
+            // nothing to wait for.
+            if self.flush().is_err() {
+                return;
+            }
+
             // Block until the queue finished.
             self.queue.with(|mut q| q.wait_idle()).unwrap();
 
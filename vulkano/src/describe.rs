@@ -0,0 +1,209 @@
+//! A writer for the `describe` methods of objects such as pipelines and render passes.
+//!
+//! The output only contains the parameters that the object was created with, not handles or IDs,
+//! so that the descriptions of two objects can be compared with a text diff. Maps and sets are
+//! printed in a sorted order for the same reason.
+
+use crate::shader::{DescriptorBindingRequirements, DescriptorRequirements};
+use ahash::HashMap;
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Display, Write},
+};
+
+pub(crate) struct Describer {
+    output: String,
+    indent: usize,
+}
+
+impl Describer {
+    pub(crate) fn new(title: &str) -> Self {
+        Describer {
+            output: format!("{}\n", title),
+            indent: 1,
+        }
+    }
+
+    /// Writes a field whose value is printed with its alternate `Debug` implementation.
+    pub(crate) fn field(&mut self, name: impl Display, value: &dyn Debug) -> &mut Self {
+        let value = format!("{:#?}", value);
+        let mut lines = value
+            .lines()
+            // This field only exists to allow adding fields to the struct in the future.
+            .filter(|line| !line.trim_start().starts_with("_ne: NonExhaustive"));
+
+        self.write_indent();
+        writeln!(
+            self.output,
+            "{}: {}",
+            name,
+            lines.next().unwrap_or_default()
+        )
+        .unwrap();
+
+        for line in lines {
+            self.write_indent();
+            writeln!(self.output, "{}", line).unwrap();
+        }
+
+        self
+    }
+
+    /// Like [`field`](Self::field), but writes `(not set)` if `value` is `None`.
+    pub(crate) fn optional_field<T: Debug>(
+        &mut self,
+        name: impl Display,
+        value: Option<&T>,
+    ) -> &mut Self {
+        match value {
+            Some(value) => self.field(name, value),
+            None => self.field(name, &format_args!("(not set)")),
+        }
+    }
+
+    /// Writes a field whose value is written by `f`, one level of indentation deeper.
+    pub(crate) fn section(&mut self, name: impl Display, f: impl FnOnce(&mut Self)) -> &mut Self {
+        self.write_indent();
+        writeln!(self.output, "{}:", name).unwrap();
+
+        self.indent += 1;
+        f(self);
+        self.indent -= 1;
+
+        self
+    }
+
+    /// Writes a field for each element of `iter`, named after its index.
+    pub(crate) fn list<T>(
+        &mut self,
+        name: impl Display,
+        iter: impl IntoIterator<Item = T>,
+        mut f: impl FnMut(&mut Self, T),
+    ) -> &mut Self {
+        self.section(name, |describer| {
+            for (index, item) in iter.into_iter().enumerate() {
+                describer.section(format_args!("[{}]", index), |describer| f(describer, item));
+            }
+        })
+    }
+
+    /// Writes a field for each element of `iter`, named after its index, whose value is printed
+    /// with its alternate `Debug` implementation.
+    pub(crate) fn debug_list<T: Debug>(
+        &mut self,
+        name: impl Display,
+        iter: impl IntoIterator<Item = T>,
+    ) -> &mut Self {
+        self.section(name, |describer| {
+            for (index, item) in iter.into_iter().enumerate() {
+                describer.field(format_args!("[{}]", index), &item);
+            }
+        })
+    }
+
+    pub(crate) fn finish(&mut self) -> String {
+        std::mem::take(&mut self.output)
+    }
+
+    fn write_indent(&mut self) {
+        for _ in 0..self.indent {
+            self.output.push_str("  ");
+        }
+    }
+}
+
+/// Returns the items of `iter` sorted by their `Debug` representation, for sets of items that
+/// don't implement `Ord`.
+pub(crate) fn sorted_by_debug<T: Debug>(iter: impl IntoIterator<Item = T>) -> Vec<T> {
+    let mut items: Vec<_> = iter
+        .into_iter()
+        .map(|item| (format!("{:?}", item), item))
+        .collect();
+    items.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    items.into_iter().map(|(_, item)| item).collect()
+}
+
+/// Writes the descriptor requirements that were reflected from the shaders of a pipeline, sorted
+/// by set and binding number.
+pub(crate) fn descriptor_binding_requirements(
+    describer: &mut Describer,
+    requirements: &HashMap<(u32, u32), DescriptorBindingRequirements>,
+) {
+    let requirements: BTreeMap<_, _> = requirements.iter().collect();
+
+    describer.section("descriptor_binding_requirements", |describer| {
+        for (&(set, binding), binding_reqs) in requirements {
+            let &DescriptorBindingRequirements {
+                ref descriptor_types,
+                descriptor_count,
+                image_format,
+                image_multisampled,
+                image_scalar_type,
+                image_view_type,
+                buffer_min_size,
+                stages,
+                ref descriptors,
+            } = binding_reqs;
+
+            describer.section(
+                format_args!("set {} binding {}", set, binding),
+                |describer| {
+                    describer
+                        .field("descriptor_types", descriptor_types)
+                        .field("descriptor_count", &descriptor_count)
+                        .field("image_format", &image_format)
+                        .field("image_multisampled", &image_multisampled)
+                        .field("image_scalar_type", &image_scalar_type)
+                        .field("image_view_type", &image_view_type)
+                        .field("buffer_min_size", &buffer_min_size)
+                        .field("stages", &stages);
+
+                    let descriptors: BTreeMap<_, _> = descriptors.iter().collect();
+
+                    describer.section("descriptors", |describer| {
+                        for (index, desc_reqs) in descriptors {
+                            let &DescriptorRequirements {
+                                memory_read,
+                                memory_write,
+                                sampler_compare,
+                                sampler_no_unnormalized_coordinates,
+                                sampler_no_ycbcr_conversion,
+                                ref sampler_with_images,
+                                storage_image_atomic,
+                            } = desc_reqs;
+
+                            let mut sampler_with_images: Vec<_> = sampler_with_images
+                                .iter()
+                                .map(|id| (id.set, id.binding, id.index))
+                                .collect();
+                            sampler_with_images.sort_unstable();
+
+                            let name = match index {
+                                Some(index) => format!("[{}]", index),
+                                None => "[runtime index]".to_owned(),
+                            };
+
+                            describer.section(name, |describer| {
+                                describer
+                                    .field("memory_read", &memory_read)
+                                    .field("memory_write", &memory_write)
+                                    .field("sampler_compare", &sampler_compare)
+                                    .field(
+                                        "sampler_no_unnormalized_coordinates",
+                                        &sampler_no_unnormalized_coordinates,
+                                    )
+                                    .field(
+                                        "sampler_no_ycbcr_conversion",
+                                        &sampler_no_ycbcr_conversion,
+                                    )
+                                    .field("sampler_with_images", &sampler_with_images)
+                                    .field("storage_image_atomic", &storage_image_atomic);
+                            });
+                        }
+                    });
+                },
+            );
+        }
+    });
+}
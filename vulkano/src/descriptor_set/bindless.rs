@@ -0,0 +1,733 @@
+//! A global descriptor set that holds large arrays of images and samplers, indexed by shaders.
+//!
+//! A common pattern for renderers that draw many different materials is to put all of the images
+//! and samplers in use into arrays in a single descriptor set that is bound once, and to hand the
+//! index of each resource to the shaders, for example in a push constant or an instance buffer.
+//! [`BindlessHeap`] implements this pattern on top of the `descriptor_indexing` features.
+//!
+//! The descriptor set of the heap has the following bindings. Each binding is an array with a
+//! fixed number of descriptors, which is given by [`BindlessHeapCreateInfo`] clamped to the limits
+//! of the device. A binding whose number of descriptors is 0 is left out of the layout.
+//!
+//! | Binding                              | Descriptor type                 |
+//! |--------------------------------------|---------------------------------|
+//! | [`BindlessHeap::SAMPLED_IMAGES`]     | [`DescriptorType::SampledImage`] |
+//! | [`BindlessHeap::STORAGE_IMAGES`]     | [`DescriptorType::StorageImage`] |
+//! | [`BindlessHeap::SAMPLERS`]           | [`DescriptorType::Sampler`]      |
+//!
+//! In GLSL, this corresponds to the following declarations, where `N` is the set number that the
+//! heap is bound to. The arrays can be declared without a size, since the shader doesn't need to
+//! know the capacity of the heap:
+//!
+//! ```glsl
+//! #extension GL_EXT_nonuniform_qualifier : require
+//!
+//! layout(set = N, binding = 0) uniform texture2D sampled_images[];
+//! layout(set = N, binding = 1, rgba8) uniform image2D storage_images[];
+//! layout(set = N, binding = 2) uniform sampler samplers[];
+//! ```
+//!
+//! # Recycling slots
+//!
+//! When a resource is removed from the heap, command buffers that are still executing may be
+//! accessing its slot. The slot is therefore not reused right away, but only after
+//! [`BindlessHeap::next_frame`] has been called as many times as there are frames in flight. The
+//! intended usage is to call `next_frame` once per frame, after waiting for the fence of the frame
+//! that is about to be reused.
+//!
+//! # Required features
+//!
+//! Creating a heap requires the following device features to be enabled:
+//!
+//! - [`descriptor_binding_partially_bound`]
+//! - [`descriptor_binding_update_unused_while_pending`]
+//! - [`descriptor_binding_sampled_image_update_after_bind`]
+//! - [`descriptor_binding_storage_image_update_after_bind`]
+//! - [`runtime_descriptor_array`]
+//!
+//! To index the arrays with values that are not dynamically uniform, the
+//! [`shader_sampled_image_array_non_uniform_indexing`] and
+//! [`shader_storage_image_array_non_uniform_indexing`] features are needed as well.
+//!
+//! [`descriptor_binding_partially_bound`]: crate::device::DeviceFeatures::descriptor_binding_partially_bound
+//! [`descriptor_binding_update_unused_while_pending`]: crate::device::DeviceFeatures::descriptor_binding_update_unused_while_pending
+//! [`descriptor_binding_sampled_image_update_after_bind`]: crate::device::DeviceFeatures::descriptor_binding_sampled_image_update_after_bind
+//! [`descriptor_binding_storage_image_update_after_bind`]: crate::device::DeviceFeatures::descriptor_binding_storage_image_update_after_bind
+//! [`runtime_descriptor_array`]: crate::device::DeviceFeatures::runtime_descriptor_array
+//! [`shader_sampled_image_array_non_uniform_indexing`]: crate::device::DeviceFeatures::shader_sampled_image_array_non_uniform_indexing
+//! [`shader_storage_image_array_non_uniform_indexing`]: crate::device::DeviceFeatures::shader_storage_image_array_non_uniform_indexing
+
+use super::{
+    allocator::DescriptorSetAllocator,
+    layout::{
+        DescriptorBindingFlags, DescriptorSetLayout, DescriptorSetLayoutBinding,
+        DescriptorSetLayoutCreateFlags, DescriptorSetLayoutCreateInfo, DescriptorType,
+    },
+    DescriptorSet, WriteDescriptorSet,
+};
+use crate::{
+    device::{Device, DeviceOwned},
+    image::{sampler::Sampler, view::ImageView},
+    shader::ShaderStages,
+    Validated, ValidationError, VulkanError,
+};
+use parking_lot::Mutex;
+use std::{collections::BTreeMap, mem, sync::Arc};
+
+/// A descriptor set containing arrays of sampled images, storage images and samplers, whose
+/// elements are handed out as [`BindlessId`]s.
+///
+/// See the [module-level documentation] for more information.
+///
+/// [module-level documentation]: self
+#[derive(Debug)]
+pub struct BindlessHeap {
+    descriptor_set: Arc<DescriptorSet>,
+    state: Mutex<HeapState>,
+}
+
+impl BindlessHeap {
+    /// The binding number of the array of sampled images.
+    pub const SAMPLED_IMAGES: u32 = 0;
+
+    /// The binding number of the array of storage images.
+    pub const STORAGE_IMAGES: u32 = 1;
+
+    /// The binding number of the array of samplers.
+    pub const SAMPLERS: u32 = 2;
+
+    /// Creates a new `BindlessHeap`.
+    ///
+    /// The capacities in `create_info` are clamped to the update-after-bind limits of the device.
+    pub fn new(
+        allocator: Arc<dyn DescriptorSetAllocator>,
+        create_info: BindlessHeapCreateInfo,
+    ) -> Result<Arc<BindlessHeap>, Validated<VulkanError>> {
+        let device = allocator.device().clone();
+        Self::validate_new(&device, &create_info)?;
+
+        let BindlessHeapCreateInfo {
+            stages,
+            max_sampled_images,
+            max_storage_images,
+            max_samplers,
+            frames_in_flight,
+            _ne: _,
+        } = create_info;
+
+        let properties = device.physical_device().properties();
+        let sampled_image_count = max_sampled_images
+            .min(
+                properties
+                    .max_per_stage_descriptor_update_after_bind_sampled_images
+                    .unwrap_or(0),
+            )
+            .min(
+                properties
+                    .max_descriptor_set_update_after_bind_sampled_images
+                    .unwrap_or(0),
+            );
+        let storage_image_count = max_storage_images
+            .min(
+                properties
+                    .max_per_stage_descriptor_update_after_bind_storage_images
+                    .unwrap_or(0),
+            )
+            .min(
+                properties
+                    .max_descriptor_set_update_after_bind_storage_images
+                    .unwrap_or(0),
+            );
+        let sampler_count = max_samplers
+            .min(
+                properties
+                    .max_per_stage_descriptor_update_after_bind_samplers
+                    .unwrap_or(0),
+            )
+            .min(
+                properties
+                    .max_descriptor_set_update_after_bind_samplers
+                    .unwrap_or(0),
+            );
+
+        let binding_flags = DescriptorBindingFlags::UPDATE_AFTER_BIND
+            | DescriptorBindingFlags::UPDATE_UNUSED_WHILE_PENDING
+            | DescriptorBindingFlags::PARTIALLY_BOUND;
+        let bindings = [
+            (
+                Self::SAMPLED_IMAGES,
+                DescriptorType::SampledImage,
+                sampled_image_count,
+            ),
+            (
+                Self::STORAGE_IMAGES,
+                DescriptorType::StorageImage,
+                storage_image_count,
+            ),
+            (Self::SAMPLERS, DescriptorType::Sampler, sampler_count),
+        ]
+        .into_iter()
+        .filter(|&(_, _, descriptor_count)| descriptor_count != 0)
+        .map(|(binding, descriptor_type, descriptor_count)| {
+            (
+                binding,
+                DescriptorSetLayoutBinding {
+                    binding_flags,
+                    descriptor_count,
+                    stages,
+                    ..DescriptorSetLayoutBinding::descriptor_type(descriptor_type)
+                },
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+
+        let layout = DescriptorSetLayout::new(
+            device,
+            DescriptorSetLayoutCreateInfo {
+                flags: DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL,
+                bindings,
+                ..Default::default()
+            },
+        )?;
+        let descriptor_set = DescriptorSet::new(allocator, layout, [], [])?;
+
+        Ok(Arc::new(BindlessHeap {
+            descriptor_set,
+            state: Mutex::new(HeapState {
+                sampled_images: Slots::new(sampled_image_count),
+                storage_images: Slots::new(storage_image_count),
+                samplers: Slots::new(sampler_count),
+                retired: vec![Vec::new(); frames_in_flight as usize],
+                current_frame: 0,
+            }),
+        }))
+    }
+
+    fn validate_new(
+        device: &Device,
+        create_info: &BindlessHeapCreateInfo,
+    ) -> Result<(), Box<ValidationError>> {
+        create_info
+            .validate(device)
+            .map_err(|err| err.add_context("create_info"))?;
+
+        Ok(())
+    }
+
+    /// Returns the layout of the descriptor set of the heap.
+    ///
+    /// This layout should be used for the corresponding set number when creating pipeline
+    /// layouts.
+    #[inline]
+    pub fn layout(&self) -> &Arc<DescriptorSetLayout> {
+        self.descriptor_set.layout()
+    }
+
+    /// Returns the descriptor set of the heap, which must be bound when executing pipelines that
+    /// access the heap.
+    #[inline]
+    pub fn descriptor_set(&self) -> &Arc<DescriptorSet> {
+        &self.descriptor_set
+    }
+
+    /// Returns the number of sampled image slots in the heap.
+    #[inline]
+    pub fn sampled_image_capacity(&self) -> u32 {
+        self.state.lock().sampled_images.capacity
+    }
+
+    /// Returns the number of storage image slots in the heap.
+    #[inline]
+    pub fn storage_image_capacity(&self) -> u32 {
+        self.state.lock().storage_images.capacity
+    }
+
+    /// Returns the number of sampler slots in the heap.
+    #[inline]
+    pub fn sampler_capacity(&self) -> u32 {
+        self.state.lock().samplers.capacity
+    }
+
+    /// Writes `image_view` to a free slot of the sampled image array, and returns its ID.
+    ///
+    /// The image view is accessed in the [`ShaderReadOnlyOptimal`] layout.
+    ///
+    /// [`ShaderReadOnlyOptimal`]: crate::image::ImageLayout::ShaderReadOnlyOptimal
+    pub fn insert_image(
+        &self,
+        image_view: &Arc<ImageView>,
+    ) -> Result<BindlessId, Box<ValidationError>> {
+        self.insert(BindlessKind::SampledImage, |index| {
+            WriteDescriptorSet::image_view_array(Self::SAMPLED_IMAGES, index, [image_view.clone()])
+        })
+    }
+
+    /// Writes `image_view` to a free slot of the storage image array, and returns its ID.
+    ///
+    /// The image view is accessed in the [`General`] layout.
+    ///
+    /// [`General`]: crate::image::ImageLayout::General
+    pub fn insert_storage_image(
+        &self,
+        image_view: &Arc<ImageView>,
+    ) -> Result<BindlessId, Box<ValidationError>> {
+        self.insert(BindlessKind::StorageImage, |index| {
+            WriteDescriptorSet::image_view_array(Self::STORAGE_IMAGES, index, [image_view.clone()])
+        })
+    }
+
+    /// Writes `sampler` to a free slot of the sampler array, and returns its ID.
+    pub fn insert_sampler(
+        &self,
+        sampler: &Arc<Sampler>,
+    ) -> Result<BindlessId, Box<ValidationError>> {
+        self.insert(BindlessKind::Sampler, |index| {
+            WriteDescriptorSet::sampler_array(Self::SAMPLERS, index, [sampler.clone()])
+        })
+    }
+
+    fn insert(
+        &self,
+        kind: BindlessKind,
+        write: impl FnOnce(u32) -> WriteDescriptorSet,
+    ) -> Result<BindlessId, Box<ValidationError>> {
+        let mut state = self.state.lock();
+        let slots = state.slots_mut(kind);
+
+        let Some(index) = slots.allocate() else {
+            return Err(Box::new(ValidationError {
+                problem: format!(
+                    "the heap has no free {} slots; its capacity is {}",
+                    kind.name(),
+                    slots.capacity,
+                )
+                .into(),
+                ..Default::default()
+            }));
+        };
+
+        // SAFETY: Host access to the descriptor set is synchronized by the lock on the state.
+        // The slot is not in use by any pending command buffer, because it was either never
+        // written or was retired at least `frames_in_flight` frames ago.
+        if let Err(err) = unsafe { self.descriptor_set.update_by_ref([write(index)], []) } {
+            slots.free.push(index);
+            slots.occupied[index as usize] = false;
+
+            return Err(err);
+        }
+
+        Ok(BindlessId { kind, index })
+    }
+
+    /// Removes the resource with the given ID from the heap.
+    ///
+    /// The slot is not reused until [`next_frame`] has been called `frames_in_flight` times.
+    /// The resource itself is kept alive by the descriptor set until the slot is overwritten.
+    ///
+    /// [`next_frame`]: Self::next_frame
+    pub fn remove(&self, id: BindlessId) -> Result<(), Box<ValidationError>> {
+        let mut state = self.state.lock();
+        let slots = state.slots_mut(id.kind);
+
+        if !slots
+            .occupied
+            .get(id.index as usize)
+            .copied()
+            .unwrap_or(false)
+        {
+            return Err(Box::new(ValidationError {
+                context: "id".into(),
+                problem: "does not refer to a resource in the heap".into(),
+                ..Default::default()
+            }));
+        }
+
+        slots.occupied[id.index as usize] = false;
+
+        if state.retired.is_empty() {
+            state.slots_mut(id.kind).free.push(id.index);
+        } else {
+            let current_frame = state.current_frame;
+            state.retired[current_frame].push(id);
+        }
+
+        Ok(())
+    }
+
+    /// Advances the heap to the next frame, making the slots that were removed `frames_in_flight`
+    /// frames ago available again.
+    ///
+    /// This should be called once per frame, after waiting for the fence of the frame that is
+    /// about to be reused, so that no command buffer that may access the retired slots is still
+    /// executing.
+    pub fn next_frame(&self) {
+        let mut state = self.state.lock();
+
+        if state.retired.is_empty() {
+            return;
+        }
+
+        state.current_frame = (state.current_frame + 1) % state.retired.len();
+        let current_frame = state.current_frame;
+        let mut retired = mem::take(&mut state.retired[current_frame]);
+
+        for id in retired.drain(..) {
+            state.slots_mut(id.kind).free.push(id.index);
+        }
+
+        // Keep the allocation around for the next time this frame is used.
+        state.retired[current_frame] = retired;
+    }
+}
+
+unsafe impl DeviceOwned for BindlessHeap {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.descriptor_set.device()
+    }
+}
+
+/// Parameters to create a new `BindlessHeap`.
+#[derive(Clone, Debug)]
+pub struct BindlessHeapCreateInfo {
+    /// The shader stages that can access the heap.
+    ///
+    /// The default value is [`ShaderStages::all_graphics()`] plus [`ShaderStages::COMPUTE`].
+    pub stages: ShaderStages,
+
+    /// The maximum number of sampled images in the heap. This is clamped to the
+    /// `max_per_stage_descriptor_update_after_bind_sampled_images` and
+    /// `max_descriptor_set_update_after_bind_sampled_images` limits.
+    ///
+    /// If this is 0, the sampled image binding is left out of the layout.
+    ///
+    /// The default value is `16384`.
+    pub max_sampled_images: u32,
+
+    /// The maximum number of storage images in the heap. This is clamped to the
+    /// `max_per_stage_descriptor_update_after_bind_storage_images` and
+    /// `max_descriptor_set_update_after_bind_storage_images` limits.
+    ///
+    /// If this is 0, the storage image binding is left out of the layout.
+    ///
+    /// The default value is `1024`.
+    pub max_storage_images: u32,
+
+    /// The maximum number of samplers in the heap. This is clamped to the
+    /// `max_per_stage_descriptor_update_after_bind_samplers` and
+    /// `max_descriptor_set_update_after_bind_samplers` limits.
+    ///
+    /// If this is 0, the sampler binding is left out of the layout.
+    ///
+    /// The default value is `256`.
+    pub max_samplers: u32,
+
+    /// The number of frames that can be in flight at once, which is the number of calls to
+    /// [`BindlessHeap::next_frame`] after which a removed slot is reused.
+    ///
+    /// If this is 0, removed slots are reused immediately. This is only safe if the caller waits
+    /// for all command buffers accessing the heap to complete before removing resources.
+    ///
+    /// The default value is `2`.
+    pub frames_in_flight: u32,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for BindlessHeapCreateInfo {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            stages: ShaderStages::all_graphics() | ShaderStages::COMPUTE,
+            max_sampled_images: 16384,
+            max_storage_images: 1024,
+            max_samplers: 256,
+            frames_in_flight: 2,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+impl BindlessHeapCreateInfo {
+    pub(crate) fn validate(&self, device: &Device) -> Result<(), Box<ValidationError>> {
+        let &Self {
+            stages,
+            max_sampled_images,
+            max_storage_images,
+            max_samplers,
+            frames_in_flight: _,
+            _ne: _,
+        } = self;
+
+        stages
+            .validate_device(device)
+            .map_err(|err| err.add_context("stages"))?;
+
+        if stages.is_empty() {
+            return Err(Box::new(ValidationError {
+                context: "stages".into(),
+                problem: "is empty".into(),
+                ..Default::default()
+            }));
+        }
+
+        if max_sampled_images == 0 && max_storage_images == 0 && max_samplers == 0 {
+            return Err(Box::new(ValidationError {
+                problem: "`max_sampled_images`, `max_storage_images` and `max_samplers` are all 0"
+                    .into(),
+                ..Default::default()
+            }));
+        }
+
+        Ok(())
+    }
+}
+
+/// Identifies a resource in a [`BindlessHeap`].
+///
+/// The index can be passed to a shader to access the resource in the corresponding array of the
+/// heap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BindlessId {
+    kind: BindlessKind,
+    index: u32,
+}
+
+impl BindlessId {
+    /// Returns which array of the heap the resource is in.
+    #[inline]
+    pub fn kind(self) -> BindlessKind {
+        self.kind
+    }
+
+    /// Returns the index of the resource in its array.
+    #[inline]
+    pub fn index(self) -> u32 {
+        self.index
+    }
+}
+
+/// The kinds of resources that can be stored in a [`BindlessHeap`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BindlessKind {
+    /// A sampled image, in the [`BindlessHeap::SAMPLED_IMAGES`] binding.
+    SampledImage,
+
+    /// A storage image, in the [`BindlessHeap::STORAGE_IMAGES`] binding.
+    StorageImage,
+
+    /// A sampler, in the [`BindlessHeap::SAMPLERS`] binding.
+    Sampler,
+}
+
+impl BindlessKind {
+    fn name(self) -> &'static str {
+        match self {
+            BindlessKind::SampledImage => "sampled image",
+            BindlessKind::StorageImage => "storage image",
+            BindlessKind::Sampler => "sampler",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct HeapState {
+    sampled_images: Slots,
+    storage_images: Slots,
+    samplers: Slots,
+    retired: Vec<Vec<BindlessId>>,
+    current_frame: usize,
+}
+
+impl HeapState {
+    fn slots_mut(&mut self, kind: BindlessKind) -> &mut Slots {
+        match kind {
+            BindlessKind::SampledImage => &mut self.sampled_images,
+            BindlessKind::StorageImage => &mut self.storage_images,
+            BindlessKind::Sampler => &mut self.samplers,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Slots {
+    capacity: u32,
+    free: Vec<u32>,
+    occupied: Vec<bool>,
+}
+
+impl Slots {
+    fn new(capacity: u32) -> Self {
+        Slots {
+            capacity,
+            free: Vec::new(),
+            occupied: Vec::new(),
+        }
+    }
+
+    fn allocate(&mut self) -> Option<u32> {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None if (self.occupied.len() as u32) < self.capacity => {
+                self.occupied.push(false);
+                self.occupied.len() as u32 - 1
+            }
+            None => return None,
+        };
+        self.occupied[index as usize] = true;
+
+        Some(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BindlessHeap, BindlessHeapCreateInfo, BindlessKind, Slots};
+    use crate::{
+        descriptor_set::allocator::StandardDescriptorSetAllocator,
+        format::Format,
+        image::{
+            sampler::{Sampler, SamplerCreateInfo},
+            view::ImageView,
+            Image, ImageCreateInfo, ImageType, ImageUsage,
+        },
+        memory::allocator::{AllocationCreateInfo, StandardMemoryAllocator},
+        shader::ShaderStages,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn slots_recycling() {
+        let mut slots = Slots::new(2);
+        assert_eq!(slots.allocate(), Some(0));
+        assert_eq!(slots.allocate(), Some(1));
+        assert_eq!(slots.allocate(), None);
+
+        slots.occupied[0] = false;
+        slots.free.push(0);
+        assert_eq!(slots.allocate(), Some(0));
+        assert_eq!(slots.allocate(), None);
+    }
+
+    #[test]
+    fn create_validation() {
+        let (device, _queue) = gfx_dev_and_queue!(
+            descriptor_binding_partially_bound,
+            descriptor_binding_update_unused_while_pending,
+            descriptor_binding_sampled_image_update_after_bind,
+            descriptor_binding_storage_image_update_after_bind,
+            runtime_descriptor_array
+        );
+        let allocator = Arc::new(StandardDescriptorSetAllocator::new(
+            device,
+            Default::default(),
+        ));
+
+        assert!(BindlessHeap::new(
+            allocator.clone(),
+            BindlessHeapCreateInfo {
+                stages: ShaderStages::empty(),
+                ..Default::default()
+            },
+        )
+        .is_err());
+
+        assert!(BindlessHeap::new(
+            allocator,
+            BindlessHeapCreateInfo {
+                max_sampled_images: 0,
+                max_storage_images: 0,
+                max_samplers: 0,
+                ..Default::default()
+            },
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn insert_remove() {
+        let (device, _queue) = gfx_dev_and_queue!(
+            descriptor_binding_partially_bound,
+            descriptor_binding_update_unused_while_pending,
+            descriptor_binding_sampled_image_update_after_bind,
+            descriptor_binding_storage_image_update_after_bind,
+            runtime_descriptor_array
+        );
+        let allocator = Arc::new(StandardDescriptorSetAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+        let heap = BindlessHeap::new(
+            allocator,
+            BindlessHeapCreateInfo {
+                max_sampled_images: 2,
+                max_storage_images: 0,
+                max_samplers: u32::MAX,
+                frames_in_flight: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let properties = device.physical_device().properties();
+        let max_samplers = properties
+            .max_per_stage_descriptor_update_after_bind_samplers
+            .unwrap()
+            .min(
+                properties
+                    .max_descriptor_set_update_after_bind_samplers
+                    .unwrap(),
+            );
+        assert_eq!(heap.sampled_image_capacity(), 2);
+        assert_eq!(heap.storage_image_capacity(), 0);
+        assert_eq!(heap.sampler_capacity(), max_samplers);
+
+        let bindings = heap.layout().bindings();
+        assert_eq!(bindings[&BindlessHeap::SAMPLED_IMAGES].descriptor_count, 2);
+        assert!(!bindings.contains_key(&BindlessHeap::STORAGE_IMAGES));
+        assert_eq!(
+            bindings[&BindlessHeap::SAMPLERS].descriptor_count,
+            max_samplers
+        );
+
+        let image_view = ImageView::new_default(
+            Image::new(
+                memory_allocator,
+                ImageCreateInfo {
+                    image_type: ImageType::Dim2d,
+                    format: Format::R8G8B8A8_UNORM,
+                    extent: [1, 1, 1],
+                    usage: ImageUsage::SAMPLED,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let first = heap.insert_image(&image_view).unwrap();
+        let second = heap.insert_image(&image_view).unwrap();
+        assert_eq!(first.kind(), BindlessKind::SampledImage);
+        assert_eq!((first.index(), second.index()), (0, 1));
+
+        // The heap is full, and there is no storage image binding.
+        assert!(heap.insert_image(&image_view).is_err());
+        assert!(heap.insert_storage_image(&image_view).is_err());
+
+        // The removed slot is only reused after the frame has been advanced.
+        heap.remove(first).unwrap();
+        assert!(heap.remove(first).is_err());
+        assert!(heap.insert_image(&image_view).is_err());
+        heap.next_frame();
+        assert_eq!(heap.insert_image(&image_view).unwrap().index(), 0);
+
+        let sampler =
+            Sampler::new(device, SamplerCreateInfo::simple_repeat_linear_no_mipmap()).unwrap();
+        let sampler_id = heap.insert_sampler(&sampler).unwrap();
+        assert_eq!(sampler_id.kind(), BindlessKind::Sampler);
+        assert_eq!(sampler_id.index(), 0);
+    }
+}
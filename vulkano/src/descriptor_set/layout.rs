@@ -3,13 +3,14 @@
 //! When creating a new descriptor set, you must provide a *layout* object to create it from.
 
 use crate::{
+    describe::Describer,
     device::{Device, DeviceOwned},
     image::{sampler::Sampler, ImageLayout},
     instance::InstanceOwnedDebugWrapper,
     macros::{impl_id_counter, vulkan_bitflags, vulkan_enum},
     shader::{DescriptorBindingRequirements, ShaderStages},
-    Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError, Version, VulkanError,
-    VulkanObject,
+    DeviceSize, Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError, Version,
+    VulkanError, VulkanObject,
 };
 use ahash::HashMap;
 use std::{collections::BTreeMap, mem::MaybeUninit, num::NonZeroU64, ptr, sync::Arc};
@@ -108,6 +109,7 @@ impl DescriptorSetLayout {
                 descriptor_count,
                 stages,
                 ref immutable_samplers,
+                buffer_min_size: _,
                 _ne: _,
             } = binding;
 
@@ -262,6 +264,46 @@ impl DescriptorSetLayout {
     pub fn is_compatible_with(&self, other: &DescriptorSetLayout) -> bool {
         self == other || (self.flags == other.flags && self.bindings == other.bindings)
     }
+
+    /// Returns a human-readable description of the parameters that the descriptor set layout was
+    /// created with.
+    ///
+    /// Unlike the `Debug` output, the description contains no handles, and its format is stable,
+    /// so that the descriptions of two descriptor set layouts can be compared with a text diff.
+    /// Immutable samplers are only counted.
+    pub fn describe(&self) -> String {
+        let mut describer = Describer::new("DescriptorSetLayout");
+        self.describe_fields(&mut describer);
+
+        describer.finish()
+    }
+
+    pub(crate) fn describe_fields(&self, describer: &mut Describer) {
+        describer
+            .field("flags", &self.flags)
+            .section("bindings", |describer| {
+                for (binding_num, binding) in &self.bindings {
+                    let &DescriptorSetLayoutBinding {
+                        binding_flags,
+                        descriptor_type,
+                        descriptor_count,
+                        stages,
+                        ref immutable_samplers,
+                        buffer_min_size: _,
+                        _ne: _,
+                    } = binding;
+
+                    describer.section(binding_num, |describer| {
+                        describer
+                            .field("binding_flags", &binding_flags)
+                            .field("descriptor_type", &descriptor_type)
+                            .field("descriptor_count", &descriptor_count)
+                            .field("stages", &stages)
+                            .field("immutable_samplers", &immutable_samplers.len());
+                    });
+                }
+            });
+    }
 }
 
 impl Drop for DescriptorSetLayout {
@@ -345,6 +387,7 @@ impl DescriptorSetLayoutCreateInfo {
                 descriptor_count,
                 stages: _,
                 immutable_samplers: _,
+                buffer_min_size: _,
                 _ne: _,
             } = binding;
 
@@ -555,7 +598,7 @@ vulkan_bitflags! {
 }
 
 /// A binding in a descriptor set layout.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug)]
 pub struct DescriptorSetLayoutBinding {
     /// Specifies how to create the binding.
     ///
@@ -594,9 +637,44 @@ pub struct DescriptorSetLayoutBinding {
     /// The default value is empty.
     pub immutable_samplers: Vec<Arc<Sampler>>,
 
+    /// The minimum size in bytes of the range of buffers that are written to this binding with
+    /// [`WriteDescriptorSet::buffer_typed`].
+    ///
+    /// This is not passed to Vulkan, and is ignored when comparing layouts for compatibility.
+    /// When the binding is created from the [`DescriptorBindingRequirements`] of a shader, this is
+    /// the size of the block that the shader declares.
+    ///
+    /// The default value is `None`.
+    ///
+    /// [`WriteDescriptorSet::buffer_typed`]: crate::descriptor_set::WriteDescriptorSet::buffer_typed
+    pub buffer_min_size: Option<DeviceSize>,
+
     pub _ne: crate::NonExhaustive,
 }
 
+impl PartialEq for DescriptorSetLayoutBinding {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        let Self {
+            binding_flags,
+            descriptor_type,
+            descriptor_count,
+            stages,
+            immutable_samplers,
+            buffer_min_size: _,
+            _ne: _,
+        } = self;
+
+        *binding_flags == other.binding_flags
+            && *descriptor_type == other.descriptor_type
+            && *descriptor_count == other.descriptor_count
+            && *stages == other.stages
+            && *immutable_samplers == other.immutable_samplers
+    }
+}
+
+impl Eq for DescriptorSetLayoutBinding {}
+
 impl DescriptorSetLayoutBinding {
     /// Returns a `DescriptorSetLayoutBinding` with the given type.
     #[inline]
@@ -607,6 +685,7 @@ impl DescriptorSetLayoutBinding {
             descriptor_count: 1,
             stages: ShaderStages::empty(),
             immutable_samplers: Vec::new(),
+            buffer_min_size: None,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -625,6 +704,7 @@ impl DescriptorSetLayoutBinding {
             image_multisampled: _,
             image_scalar_type: _,
             image_view_type: _,
+            buffer_min_size: _,
             stages,
             descriptors: _,
         } = binding_requirements;
@@ -668,6 +748,7 @@ impl DescriptorSetLayoutBinding {
             descriptor_count,
             stages,
             ref immutable_samplers,
+            buffer_min_size: _,
             _ne: _,
         } = self;
 
@@ -1020,6 +1101,7 @@ impl From<&DescriptorBindingRequirements> for DescriptorSetLayoutBinding {
             descriptor_count: reqs.descriptor_count.unwrap_or(0),
             stages: reqs.stages,
             immutable_samplers: Vec::new(),
+            buffer_min_size: reqs.buffer_min_size,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -89,15 +89,17 @@ use crate::{
     image::{sampler::Sampler, ImageLayout},
     Validated, ValidationError, VulkanError, VulkanObject,
 };
-use ahash::HashMap;
+use ahash::{HashMap, HashSet};
 use parking_lot::{RwLock, RwLockReadGuard};
 use smallvec::{smallvec, SmallVec};
 use std::{
     hash::{Hash, Hasher},
+    ops::Range,
     sync::Arc,
 };
 
 pub mod allocator;
+pub mod bindless;
 mod collection;
 pub mod layout;
 pub mod pool;
@@ -321,6 +323,9 @@ impl Hash for DescriptorSet {
 #[derive(Clone, Debug)]
 pub struct DescriptorSetResources {
     binding_resources: HashMap<u32, DescriptorBindingResources>,
+    // The binding numbers and array indices of the descriptors that a null descriptor was
+    // written to. The resources of these descriptors are `None`.
+    null_descriptors: HashSet<(u32, u32)>,
 }
 
 impl DescriptorSetResources {
@@ -392,7 +397,10 @@ impl DescriptorSetResources {
             })
             .collect();
 
-        Self { binding_resources }
+        Self {
+            binding_resources,
+            null_descriptors: HashSet::default(),
+        }
     }
 
     /// Returns a reference to the bound resources for `binding`. Returns `None` if the binding
@@ -402,17 +410,42 @@ impl DescriptorSetResources {
         self.binding_resources.get(&binding)
     }
 
+    /// Returns whether a null descriptor was written to array element `index` of `binding`.
+    ///
+    /// The resource of such a descriptor is `None` in [`binding`](Self::binding), like the
+    /// resource of a descriptor that was never written, but the descriptor is valid.
+    #[inline]
+    pub fn is_null(&self, binding: u32, index: u32) -> bool {
+        self.null_descriptors.contains(&(binding, index))
+    }
+
     #[inline]
     pub(crate) fn write(&mut self, write: &WriteDescriptorSet, layout: &DescriptorSetLayout) {
+        let binding = write.binding();
         let descriptor_type = layout
             .bindings()
-            .get(&write.binding())
+            .get(&binding)
             .expect("descriptor write has invalid binding number")
             .descriptor_type;
-        self.binding_resources
-            .get_mut(&write.binding())
-            .expect("descriptor write has invalid binding number")
-            .write(write, descriptor_type)
+        let binding_resources = self
+            .binding_resources
+            .get_mut(&binding)
+            .expect("descriptor write has invalid binding number");
+        binding_resources.write(write, descriptor_type);
+
+        let first = write.first_array_element();
+        let indices = first..first + write.elements().len();
+
+        if matches!(write.elements(), WriteDescriptorSetElements::None(_))
+            && !matches!(binding_resources, DescriptorBindingResources::None(_))
+        {
+            self.null_descriptors
+                .extend(indices.map(|index| (binding, index)));
+        } else if !self.null_descriptors.is_empty() {
+            for index in indices {
+                self.null_descriptors.remove(&(binding, index));
+            }
+        }
     }
 
     #[inline]
@@ -431,6 +464,16 @@ impl DescriptorSetResources {
                 copy.dst_first_array_element,
                 copy.descriptor_count,
             );
+
+        for offset in 0..copy.descriptor_count {
+            let dst = (copy.dst_binding, copy.dst_first_array_element + offset);
+
+            if resources.is_null(copy.src_binding, copy.src_first_array_element + offset) {
+                self.null_descriptors.insert(dst);
+            } else {
+                self.null_descriptors.remove(&dst);
+            }
+        }
     }
 }
 
@@ -467,25 +510,53 @@ impl DescriptorBindingResources {
                 });
         }
 
-        let default_image_layout = descriptor_type.default_image_layout();
+        fn clear_resources<T>(resources: &mut [Option<T>], range: Range<usize>) {
+            resources
+                .get_mut(range)
+                .expect("descriptor write for binding out of bounds")
+                .iter_mut()
+                .for_each(|resource| *resource = None);
+        }
+
         let first = write.first_array_element() as usize;
 
         match write.elements() {
-            WriteDescriptorSetElements::None(num_elements) => match self {
-                DescriptorBindingResources::None(resources) => {
-                    resources
-                        .get_mut(first..first + *num_elements as usize)
-                        .expect("descriptor write for binding out of bounds")
-                        .iter_mut()
-                        .for_each(|resource| {
-                            *resource = Some(());
-                        });
+            WriteDescriptorSetElements::None(num_elements) => {
+                let range = first..first + *num_elements as usize;
+
+                match self {
+                    DescriptorBindingResources::None(resources) => {
+                        resources
+                            .get_mut(range)
+                            .expect("descriptor write for binding out of bounds")
+                            .iter_mut()
+                            .for_each(|resource| {
+                                *resource = Some(());
+                            });
+                    }
+                    // For other descriptor types, these are null descriptors, which don't
+                    // reference any resource.
+                    DescriptorBindingResources::Buffer(resources) => {
+                        clear_resources(resources, range)
+                    }
+                    DescriptorBindingResources::BufferView(resources) => {
+                        clear_resources(resources, range)
+                    }
+                    DescriptorBindingResources::ImageView(resources) => {
+                        clear_resources(resources, range)
+                    }
+                    DescriptorBindingResources::ImageViewSampler(resources) => {
+                        clear_resources(resources, range)
+                    }
+                    DescriptorBindingResources::AccelerationStructure(resources) => {
+                        clear_resources(resources, range)
+                    }
+                    _ => panic!(
+                        "descriptor write for binding {} has wrong resource type",
+                        write.binding(),
+                    ),
                 }
-                _ => panic!(
-                    "descriptor write for binding {} has wrong resource type",
-                    write.binding(),
-                ),
-            },
+            }
             WriteDescriptorSetElements::Buffer(elements) => match self {
                 DescriptorBindingResources::Buffer(resources) => {
                     write_resources(first, resources, elements, Clone::clone)
@@ -510,7 +581,8 @@ impl DescriptorBindingResources {
                         let mut element = element.clone();
 
                         if element.image_layout == ImageLayout::Undefined {
-                            element.image_layout = default_image_layout;
+                            element.image_layout =
+                                element.image_view.descriptor_usage_layout(descriptor_type);
                         }
 
                         element
@@ -527,7 +599,10 @@ impl DescriptorBindingResources {
                         let mut element = element.clone();
 
                         if element.0.image_layout == ImageLayout::Undefined {
-                            element.0.image_layout = default_image_layout;
+                            element.0.image_layout = element
+                                .0
+                                .image_view
+                                .descriptor_usage_layout(descriptor_type);
                         }
 
                         element
@@ -657,3 +732,79 @@ impl From<Arc<DescriptorSet>> for DescriptorSetWithOffsets {
         DescriptorSetWithOffsets::new(descriptor_set, std::iter::empty())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{DescriptorSet, WriteDescriptorSet};
+    use crate::{
+        descriptor_set::{
+            allocator::StandardDescriptorSetAllocator,
+            layout::{
+                DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo,
+                DescriptorType,
+            },
+        },
+        device::Device,
+        shader::ShaderStages,
+        Validated,
+    };
+    use std::sync::Arc;
+
+    fn storage_buffer_layout(device: Arc<Device>) -> Arc<DescriptorSetLayout> {
+        DescriptorSetLayout::new(
+            device,
+            DescriptorSetLayoutCreateInfo {
+                bindings: [(
+                    0,
+                    DescriptorSetLayoutBinding {
+                        descriptor_count: 2,
+                        stages: ShaderStages::COMPUTE,
+                        ..DescriptorSetLayoutBinding::descriptor_type(DescriptorType::StorageBuffer)
+                    },
+                )]
+                .into(),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn null_descriptor() {
+        let (device, _) = gfx_dev_and_queue!(null_descriptor);
+        let allocator = Arc::new(StandardDescriptorSetAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+        let set = DescriptorSet::new(
+            allocator,
+            storage_buffer_layout(device),
+            [WriteDescriptorSet::none(0)],
+            [],
+        )
+        .unwrap();
+
+        let resources = set.resources();
+        assert!(resources.is_null(0, 0));
+        assert!(!resources.is_null(0, 1));
+    }
+
+    #[test]
+    fn null_descriptor_feature() {
+        let (device, _) = gfx_dev_and_queue!();
+        let allocator = Arc::new(StandardDescriptorSetAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+
+        assert!(matches!(
+            DescriptorSet::new(
+                allocator,
+                storage_buffer_layout(device),
+                [WriteDescriptorSet::none(0)],
+                [],
+            ),
+            Err(Validated::ValidationError(_)),
+        ));
+    }
+}
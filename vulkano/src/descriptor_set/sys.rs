@@ -210,6 +210,8 @@ impl RawDescriptorSet {
 impl Drop for RawDescriptorSet {
     #[inline]
     fn drop(&mut self) {
+        self.device().forget_debug_utils_object_name(self.handle());
+
         let allocation = unsafe { ManuallyDrop::take(&mut self.allocation) };
         unsafe { self.allocator.deallocate(allocation) };
     }
@@ -5,7 +5,7 @@ use super::{
 };
 use crate::{
     acceleration_structure::{AccelerationStructure, AccelerationStructureType},
-    buffer::{view::BufferView, BufferUsage, Subbuffer},
+    buffer::{view::BufferView, BufferContents, BufferUsage, Subbuffer},
     descriptor_set::{
         layout::{DescriptorBindingFlags, DescriptorSetLayoutCreateFlags},
         pool::DescriptorPoolCreateFlags,
@@ -18,7 +18,7 @@ use crate::{
     },
     DeviceSize, Requires, RequiresAllOf, RequiresOneOf, ValidationError, VulkanObject,
 };
-use smallvec::SmallVec;
+use smallvec::{smallvec, SmallVec};
 use std::{ops::Range, ptr, sync::Arc};
 
 /// Represents a single write operation to the binding of a descriptor set.
@@ -35,17 +35,24 @@ pub struct WriteDescriptorSet {
     binding: u32,
     first_array_element: u32,
     elements: WriteDescriptorSetElements,
+    check_buffer_min_size: bool,
 }
 
 impl WriteDescriptorSet {
     /// Write an empty element to array element 0.
     ///
-    /// This is used for push descriptors in combination with `Sampler` descriptors that have
-    /// immutable samplers in the layout. The Vulkan spec requires these elements to be explicitly
-    /// written, but since there is no data to write, a dummy write is provided instead.
+    /// For `Sampler` descriptors that have immutable samplers in the layout, this is used for push
+    /// descriptors. The Vulkan spec requires these elements to be explicitly written, but since
+    /// there is no data to write, a dummy write is provided instead. For regular descriptor sets,
+    /// the data for such descriptors is automatically valid, and dummy writes are not allowed.
     ///
-    /// For regular descriptor sets, the data for such descriptors is automatically valid, and
-    /// dummy writes are not allowed.
+    /// For all other descriptor types, this writes a null descriptor, which the shader can access
+    /// without accessing any resource. Reads from a null descriptor return zero, and writes to it
+    /// are discarded. This requires the [`null_descriptor`] feature to be enabled on the device.
+    /// Null descriptors can't be written to `InputAttachment` and `InlineUniformBlock`
+    /// descriptors, or to `CombinedImageSampler` descriptors that don't have immutable samplers.
+    ///
+    /// [`null_descriptor`]: crate::device::DeviceFeatures::null_descriptor
     #[inline]
     pub fn none(binding: u32) -> Self {
         Self::none_array(binding, 0, 1)
@@ -61,6 +68,7 @@ impl WriteDescriptorSet {
             binding,
             first_array_element,
             elements: WriteDescriptorSetElements::None(num_elements),
+            check_buffer_min_size: false,
         }
     }
 
@@ -82,6 +90,31 @@ impl WriteDescriptorSet {
         )
     }
 
+    /// Write a single buffer to array element 0 like [`buffer`](Self::buffer), and check that it
+    /// is large enough for the block that the shader declares for the binding.
+    ///
+    /// If the binding in the descriptor set layout has a
+    /// [`buffer_min_size`](DescriptorSetLayoutBinding::buffer_min_size), which is the case for
+    /// layouts created from shader reflection, then the size of `buffer` must be at least that
+    /// many bytes. This catches a `T` that doesn't match the block when the descriptor set is
+    /// written. [`buffer`](Self::buffer) does not check this, because binding a smaller range is
+    /// valid as long as the shader does not access the rest of the block.
+    ///
+    /// The check is skipped if the
+    /// [`robust_buffer_access`](crate::device::DeviceFeatures::robust_buffer_access) or
+    /// [`robust_buffer_access2`](crate::device::DeviceFeatures::robust_buffer_access2) feature is
+    /// enabled on the device, as accesses outside the range are then well-defined.
+    #[inline]
+    pub fn buffer_typed<T>(binding: u32, buffer: Subbuffer<T>) -> Self
+    where
+        T: BufferContents + ?Sized,
+    {
+        Self {
+            check_buffer_min_size: true,
+            ..Self::buffer(binding, buffer)
+        }
+    }
+
     /// Write a number of consecutive buffer elements.
     ///
     /// See [`buffer`](Self::buffer) for more information.
@@ -126,6 +159,7 @@ impl WriteDescriptorSet {
             binding,
             first_array_element,
             elements: WriteDescriptorSetElements::Buffer(elements),
+            check_buffer_min_size: false,
         }
     }
 
@@ -148,6 +182,7 @@ impl WriteDescriptorSet {
             binding,
             first_array_element,
             elements: WriteDescriptorSetElements::BufferView(elements),
+            check_buffer_min_size: false,
         }
     }
 
@@ -205,6 +240,7 @@ impl WriteDescriptorSet {
             binding,
             first_array_element,
             elements: WriteDescriptorSetElements::ImageView(elements),
+            check_buffer_min_size: false,
         }
     }
 
@@ -279,6 +315,7 @@ impl WriteDescriptorSet {
             binding,
             first_array_element,
             elements: WriteDescriptorSetElements::ImageViewSampler(elements),
+            check_buffer_min_size: false,
         }
     }
 
@@ -301,6 +338,7 @@ impl WriteDescriptorSet {
             binding,
             first_array_element,
             elements: WriteDescriptorSetElements::Sampler(elements),
+            check_buffer_min_size: false,
         }
     }
 
@@ -312,6 +350,7 @@ impl WriteDescriptorSet {
             binding,
             first_array_element: offset,
             elements: WriteDescriptorSetElements::InlineUniformBlock(data),
+            check_buffer_min_size: false,
         }
     }
 
@@ -337,6 +376,7 @@ impl WriteDescriptorSet {
             binding,
             first_array_element,
             elements: WriteDescriptorSetElements::AccelerationStructure(elements),
+            check_buffer_min_size: false,
         }
     }
 
@@ -380,9 +420,11 @@ impl WriteDescriptorSet {
             binding,
             first_array_element,
             ref elements,
+            check_buffer_min_size,
         } = self;
 
         let device = layout.device();
+        let properties = device.physical_device().properties();
 
         let layout_binding = match layout.bindings().get(&binding) {
             Some(layout_binding) => layout_binding,
@@ -510,7 +552,124 @@ impl WriteDescriptorSet {
                 Ok(())
             };
 
-        let default_image_layout = layout_binding.descriptor_type.default_image_layout();
+        // VUID-VkWriteDescriptorSet-dstArrayElement-00321
+        if first_array_element + array_element_count > max_descriptor_count {
+            return Err(Box::new(ValidationError {
+                problem: "`first_array_element` + the number of provided elements is greater than \
+                    the number of descriptors in the descriptor set binding"
+                    .into(),
+                vuids: &["VUID-VkWriteDescriptorSet-dstArrayElement-00321"],
+                ..Default::default()
+            }));
+        }
+
+        // For all descriptor types other than samplers, `none` elements are null descriptors.
+        if matches!(elements, WriteDescriptorSetElements::None(_))
+            && layout_binding.descriptor_type != DescriptorType::Sampler
+        {
+            let vuids: &'static [_] = match layout_binding.descriptor_type {
+                DescriptorType::UniformBuffer
+                | DescriptorType::StorageBuffer
+                | DescriptorType::UniformBufferDynamic
+                | DescriptorType::StorageBufferDynamic => {
+                    &["VUID-VkDescriptorBufferInfo-buffer-02998"]
+                }
+                DescriptorType::UniformTexelBuffer | DescriptorType::StorageTexelBuffer => {
+                    &["VUID-VkWriteDescriptorSet-descriptorType-02995"]
+                }
+                DescriptorType::SampledImage | DescriptorType::StorageImage => {
+                    &["VUID-VkWriteDescriptorSet-descriptorType-02997"]
+                }
+                DescriptorType::CombinedImageSampler => {
+                    if layout_binding.immutable_samplers.is_empty() {
+                        return Err(Box::new(ValidationError {
+                            context: "elements".into(),
+                            problem: format!(
+                                "contains `none` elements, but descriptor set binding {} has \
+                                the descriptor type `DescriptorType::CombinedImageSampler` and no \
+                                immutable samplers, so a sampler must be provided",
+                                binding,
+                            )
+                            .into(),
+                            vuids: &["VUID-VkWriteDescriptorSet-descriptorType-00325"],
+                            ..Default::default()
+                        }));
+                    }
+
+                    &["VUID-VkWriteDescriptorSet-descriptorType-02997"]
+                }
+                DescriptorType::AccelerationStructure => &[
+                    "VUID-VkWriteDescriptorSetAccelerationStructureKHR-pAccelerationStructures-03580",
+                ],
+                DescriptorType::InputAttachment | DescriptorType::InlineUniformBlock => {
+                    return Err(Box::new(ValidationError {
+                        context: "elements".into(),
+                        problem: format!(
+                            "contains `none` elements, but descriptor set binding {} has the \
+                            descriptor type `DescriptorType::{:?}`, which can't hold null \
+                            descriptors",
+                            binding, layout_binding.descriptor_type,
+                        )
+                        .into(),
+                        vuids: if layout_binding.descriptor_type
+                            == DescriptorType::InputAttachment
+                        {
+                            &["VUID-VkWriteDescriptorSet-descriptorType-07683"]
+                        } else {
+                            &[]
+                        },
+                        ..Default::default()
+                    }));
+                }
+                DescriptorType::Sampler => unreachable!(),
+            };
+
+            if !device.enabled_features().null_descriptor {
+                return Err(Box::new(ValidationError {
+                    context: "elements".into(),
+                    problem: format!(
+                        "contains `none` elements, which write null descriptors to descriptor \
+                        set binding {}",
+                        binding,
+                    )
+                    .into(),
+                    requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceFeature(
+                        "null_descriptor",
+                    )])]),
+                    vuids,
+                }));
+            }
+
+            return Ok(());
+        }
+
+        if let (true, Some(buffer_min_size), WriteDescriptorSetElements::Buffer(elements)) = (
+            check_buffer_min_size,
+            layout_binding.buffer_min_size,
+            elements,
+        ) {
+            let robust_buffer_access = device.enabled_features().robust_buffer_access
+                || device.enabled_features().robust_buffer_access2;
+
+            if !robust_buffer_access {
+                for (index, buffer_info) in elements.iter().enumerate() {
+                    let range_size = buffer_info.range.end - buffer_info.range.start;
+
+                    if range_size < buffer_min_size {
+                        return Err(Box::new(ValidationError {
+                            context: format!("elements[{}].range", index).into(),
+                            problem: format!(
+                                "is {} bytes long, but descriptor set binding {} requires at \
+                                least {} bytes, the size of the block that the shader declares",
+                                range_size, binding, buffer_min_size,
+                            )
+                            .into(),
+                            ..Default::default()
+                        }));
+                    }
+                }
+            }
+        }
 
         match layout_binding.descriptor_type {
             DescriptorType::Sampler => {
@@ -626,7 +785,8 @@ impl WriteDescriptorSet {
                         } = image_view_info;
 
                         if image_layout == ImageLayout::Undefined {
-                            image_layout = default_image_layout;
+                            image_layout =
+                                image_view.descriptor_usage_layout(layout_binding.descriptor_type);
                         }
 
                         assert_eq!(device, image_view.device());
@@ -740,7 +900,8 @@ impl WriteDescriptorSet {
                         } = image_view_info;
 
                         if image_layout == ImageLayout::Undefined {
-                            image_layout = default_image_layout;
+                            image_layout =
+                                image_view.descriptor_usage_layout(layout_binding.descriptor_type);
                         }
 
                         assert_eq!(device, image_view.device());
@@ -813,7 +974,8 @@ impl WriteDescriptorSet {
                     } = image_view_info;
 
                     if image_layout == ImageLayout::Undefined {
-                        image_layout = default_image_layout;
+                        image_layout =
+                            image_view.descriptor_usage_layout(layout_binding.descriptor_type);
                     }
 
                     assert_eq!(device, image_view.device());
@@ -892,7 +1054,8 @@ impl WriteDescriptorSet {
                     } = image_view_info;
 
                     if image_layout == ImageLayout::Undefined {
-                        image_layout = default_image_layout;
+                        image_layout =
+                            image_view.descriptor_usage_layout(layout_binding.descriptor_type);
                     }
 
                     assert_eq!(device, image_view.device());
@@ -1077,6 +1240,17 @@ impl WriteDescriptorSet {
                             ..Default::default()
                         }));
                     }
+
+                    if range.end - range.start > properties.max_uniform_buffer_range as DeviceSize {
+                        return Err(Box::new(ValidationError {
+                            context: format!("elements[{}].range", index).into(),
+                            problem: "the length of the range is greater than the \
+                                `max_uniform_buffer_range` limit"
+                                .into(),
+                            vuids: &["VUID-VkWriteDescriptorSet-descriptorType-00332"],
+                            ..Default::default()
+                        }));
+                    }
                 }
             }
 
@@ -1130,6 +1304,17 @@ impl WriteDescriptorSet {
                             ..Default::default()
                         }));
                     }
+
+                    if range.end - range.start > properties.max_storage_buffer_range as DeviceSize {
+                        return Err(Box::new(ValidationError {
+                            context: format!("elements[{}].range", index).into(),
+                            problem: "the length of the range is greater than the \
+                                `max_storage_buffer_range` limit"
+                                .into(),
+                            vuids: &["VUID-VkWriteDescriptorSet-descriptorType-00333"],
+                            ..Default::default()
+                        }));
+                    }
                 }
             }
 
@@ -1158,7 +1343,8 @@ impl WriteDescriptorSet {
                     } = image_view_info;
 
                     if image_layout == ImageLayout::Undefined {
-                        image_layout = default_image_layout;
+                        image_layout =
+                            image_view.descriptor_usage_layout(layout_binding.descriptor_type);
                     }
 
                     assert_eq!(device, image_view.device());
@@ -1313,35 +1499,58 @@ impl WriteDescriptorSet {
             }
         }
 
-        // VUID-VkWriteDescriptorSet-dstArrayElement-00321
-        if first_array_element + array_element_count > max_descriptor_count {
-            return Err(Box::new(ValidationError {
-                problem: "`first_array_element` + the number of provided elements is greater than \
-                    the number of descriptors in the descriptor set binding"
-                    .into(),
-                vuids: &["VUID-VkWriteDescriptorSet-dstArrayElement-00321"],
-                ..Default::default()
-            }));
-        }
-
         Ok(())
     }
 
     pub(crate) fn to_vulkan_info(&self, descriptor_type: DescriptorType) -> DescriptorWriteInfo {
-        let default_image_layout = descriptor_type.default_image_layout();
-
         match &self.elements {
             WriteDescriptorSetElements::None(num_elements) => {
-                debug_assert!(matches!(descriptor_type, DescriptorType::Sampler));
-                DescriptorWriteInfo::Image(
-                    std::iter::repeat_with(|| ash::vk::DescriptorImageInfo {
-                        sampler: ash::vk::Sampler::null(),
-                        image_view: ash::vk::ImageView::null(),
-                        image_layout: ash::vk::ImageLayout::UNDEFINED,
-                    })
-                    .take(*num_elements as usize)
-                    .collect(),
-                )
+                let num_elements = *num_elements as usize;
+
+                match descriptor_type {
+                    DescriptorType::UniformBuffer
+                    | DescriptorType::StorageBuffer
+                    | DescriptorType::UniformBufferDynamic
+                    | DescriptorType::StorageBufferDynamic => DescriptorWriteInfo::Buffer(
+                        std::iter::repeat_with(|| ash::vk::DescriptorBufferInfo {
+                            buffer: ash::vk::Buffer::null(),
+                            offset: 0,
+                            range: ash::vk::WHOLE_SIZE,
+                        })
+                        .take(num_elements)
+                        .collect(),
+                    ),
+                    DescriptorType::UniformTexelBuffer | DescriptorType::StorageTexelBuffer => {
+                        DescriptorWriteInfo::BufferView(smallvec![
+                            ash::vk::BufferView::null();
+                            num_elements
+                        ])
+                    }
+                    DescriptorType::AccelerationStructure => {
+                        DescriptorWriteInfo::AccelerationStructure(smallvec![
+                            ash::vk::AccelerationStructureKHR::null();
+                            num_elements
+                        ])
+                    }
+                    _ => {
+                        debug_assert!(matches!(
+                            descriptor_type,
+                            DescriptorType::Sampler
+                                | DescriptorType::CombinedImageSampler
+                                | DescriptorType::SampledImage
+                                | DescriptorType::StorageImage
+                        ));
+                        DescriptorWriteInfo::Image(
+                            std::iter::repeat_with(|| ash::vk::DescriptorImageInfo {
+                                sampler: ash::vk::Sampler::null(),
+                                image_view: ash::vk::ImageView::null(),
+                                image_layout: ash::vk::ImageLayout::UNDEFINED,
+                            })
+                            .take(num_elements)
+                            .collect(),
+                        )
+                    }
+                }
             }
             WriteDescriptorSetElements::Buffer(elements) => {
                 debug_assert!(matches!(
@@ -1400,7 +1609,7 @@ impl WriteDescriptorSet {
                             } = image_view_info;
 
                             if image_layout == ImageLayout::Undefined {
-                                image_layout = default_image_layout;
+                                image_layout = image_view.descriptor_usage_layout(descriptor_type);
                             }
 
                             ash::vk::DescriptorImageInfo {
@@ -1427,7 +1636,7 @@ impl WriteDescriptorSet {
                             } = image_view_info;
 
                             if image_layout == ImageLayout::Undefined {
-                                image_layout = default_image_layout;
+                                image_layout = image_view.descriptor_usage_layout(descriptor_type);
                             }
 
                             ash::vk::DescriptorImageInfo {
@@ -100,7 +100,9 @@ pub(crate) use self::properties::DevicePropertiesFfi;
 use self::{physical::PhysicalDevice, queue::DeviceQueueInfo};
 pub use self::{
     properties::DeviceProperties,
-    queue::{Queue, QueueFamilyProperties, QueueFlags, QueueGuard},
+    queue::{
+        CheckpointData, Queue, QueueFamilyProperties, QueueFlags, QueueGuard, SubmissionHooks,
+    },
 };
 pub use crate::fns::DeviceFunctions;
 use crate::{
@@ -112,27 +114,32 @@ use crate::{
     descriptor_set::layout::{
         DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo, DescriptorSetLayoutSupport,
     },
-    image::{ImageCreateFlags, ImageCreateInfo, ImageTiling},
+    image::{ImageCompressionControl, ImageCreateFlags, ImageCreateInfo, ImageTiling},
     instance::{Instance, InstanceOwned, InstanceOwnedDebugWrapper},
     macros::{impl_id_counter, vulkan_bitflags},
-    memory::{allocator::DeviceLayout, ExternalMemoryHandleType, MemoryRequirements},
+    memory::{
+        allocator::DeviceLayout, ExternalMemoryHandleType, MemoryRequirements,
+        MemoryRequirementsCache, MemoryRequirementsCacheStats,
+    },
     sync::Sharing,
     Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError, Version, VulkanError,
     VulkanObject,
 };
+use ahash::HashMap;
 use ash::vk::Handle;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use smallvec::{smallvec, SmallVec};
 use std::{
+    error::Error,
     ffi::CString,
-    fmt::{Debug, Error as FmtError, Formatter},
+    fmt::{Debug, Display, Error as FmtError, Formatter},
     fs::File,
     mem::MaybeUninit,
     num::NonZeroU64,
     ops::Deref,
     ptr, slice,
     sync::{
-        atomic::{AtomicU32, Ordering},
+        atomic::{AtomicBool, AtomicU32, Ordering},
         Arc,
     },
 };
@@ -169,6 +176,15 @@ pub struct Device {
     fence_pool: Mutex<Vec<ash::vk::Fence>>,
     semaphore_pool: Mutex<Vec<ash::vk::Semaphore>>,
     event_pool: Mutex<Vec<ash::vk::Event>>,
+    memory_requirements_cache: MemoryRequirementsCache,
+    submission_hooks: RwLock<Option<Arc<dyn SubmissionHooks>>>,
+    // Whether `submission_hooks` is `Some`, so that submissions don't need to take the lock when
+    // no hooks are set.
+    has_submission_hooks: AtomicBool,
+    performance_warnings: AtomicBool,
+    // The names of pipelines and descriptor sets set with `set_debug_utils_object_name`, so that
+    // they can be shown in errors. Entries are removed when the object is destroyed.
+    object_names: Mutex<HashMap<(ash::vk::ObjectType, u64), String>>,
 }
 
 impl Device {
@@ -215,6 +231,11 @@ impl Device {
             }));
         }
 
+        create_info
+            .clone()
+            .enable_implicit_requirements(physical_device)
+            .map_err(|err| err.add_context("create_info"))?;
+
         Ok(())
     }
 
@@ -223,116 +244,8 @@ impl Device {
         physical_device: Arc<PhysicalDevice>,
         mut create_info: DeviceCreateInfo,
     ) -> Result<(Arc<Device>, impl ExactSizeIterator<Item = Arc<Queue>>), VulkanError> {
-        // VUID-vkCreateDevice-ppEnabledExtensionNames-01387
-        create_info.enabled_extensions.enable_dependencies(
-            physical_device.api_version(),
-            physical_device.supported_extensions(),
-        );
-
-        // VUID-VkDeviceCreateInfo-pProperties-04451
-        if physical_device
-            .supported_extensions()
-            .khr_portability_subset
-        {
-            create_info.enabled_extensions.khr_portability_subset = true;
-        }
-
-        macro_rules! enable_extension_required_features {
-            (
-                $extension:ident,
-                $feature_to_enable:ident $(,)?
-            ) => {
-                if create_info.enabled_extensions.$extension {
-                    assert!(
-                        physical_device.supported_features().$feature_to_enable,
-                        "The device extension `{}` is enabled, and it requires the `{}` device \
-                        feature to be also enabled, but the device does not support the required \
-                        feature. This is a bug in the Vulkan driver for this device.",
-                        stringify!($extension),
-                        stringify!($feature_to_enable),
-                    );
-                    create_info.enabled_features.$feature_to_enable = true;
-                }
-            };
-        }
-
-        if physical_device.api_version() >= Version::V1_1 {
-            // VUID-VkDeviceCreateInfo-ppEnabledExtensionNames-04476
-            enable_extension_required_features!(khr_shader_draw_parameters, shader_draw_parameters);
-        }
-
-        if physical_device.api_version() >= Version::V1_2 {
-            // VUID-VkDeviceCreateInfo-ppEnabledExtensionNames-02831
-            enable_extension_required_features!(khr_draw_indirect_count, draw_indirect_count);
-
-            // VUID-VkDeviceCreateInfo-ppEnabledExtensionNames-02832
-            enable_extension_required_features!(
-                khr_sampler_mirror_clamp_to_edge,
-                sampler_mirror_clamp_to_edge,
-            );
-
-            // VUID-VkDeviceCreateInfo-ppEnabledExtensionNames-02833
-            enable_extension_required_features!(ext_descriptor_indexing, descriptor_indexing);
-
-            // VUID-VkDeviceCreateInfo-ppEnabledExtensionNames-02834
-            enable_extension_required_features!(ext_sampler_filter_minmax, sampler_filter_minmax);
-
-            // VUID-VkDeviceCreateInfo-ppEnabledExtensionNames-02835
-            enable_extension_required_features!(
-                ext_shader_viewport_index_layer,
-                shader_output_layer,
-            );
-            enable_extension_required_features!(
-                ext_shader_viewport_index_layer,
-                shader_output_layer,
-            );
-        }
-
-        macro_rules! enable_feature_required_features {
-            (
-                $feature:ident,
-                $feature_to_enable:ident $(,)?
-            ) => {
-                if create_info.enabled_features.$feature {
-                    assert!(
-                        physical_device.supported_features().$feature_to_enable,
-                        "The device feature `{}` is enabled, and it requires the `{}` feature \
-                        to be also enabled, but the device does not support the required feature. \
-                        This is a bug in the Vulkan driver for this device.",
-                        stringify!($feature),
-                        stringify!($feature_to_enable),
-                    );
-                    create_info.enabled_features.$feature_to_enable = true;
-                }
-            };
-        }
-
-        // VUID-VkPhysicalDeviceVariablePointersFeatures-variablePointers-01431
-        enable_feature_required_features!(variable_pointers, variable_pointers_storage_buffer);
-
-        // VUID-VkPhysicalDeviceMultiviewFeatures-multiviewGeometryShader-00580
-        enable_feature_required_features!(multiview_geometry_shader, multiview);
-
-        // VUID-VkPhysicalDeviceMultiviewFeatures-multiviewTessellationShader-00581
-        enable_feature_required_features!(multiview_tessellation_shader, multiview);
-
-        // VUID-VkPhysicalDeviceMeshShaderFeaturesEXT-multiviewMeshShader-07032
-        enable_feature_required_features!(multiview_mesh_shader, multiview);
-
-        // VUID-VkPhysicalDeviceMeshShaderFeaturesEXT-primitiveFragmentShadingRateMeshShader-07033
-        enable_feature_required_features!(
-            primitive_fragment_shading_rate_mesh_shader,
-            primitive_fragment_shading_rate,
-        );
-
-        // VUID-VkPhysicalDeviceRayTracingPipelineFeaturesKHR-rayTracingPipelineShaderGroupHandleCaptureReplayMixed-03575
-        enable_feature_required_features!(
-            ray_tracing_pipeline_shader_group_handle_capture_replay_mixed,
-            ray_tracing_pipeline_shader_group_handle_capture_replay,
-        );
-
-        // VUID-VkPhysicalDeviceRobustness2FeaturesEXT-robustBufferAccess2-04000
-        enable_feature_required_features!(robust_buffer_access2, robust_buffer_access);
+        // `validate_new` checked that the required features are supported.
+        let _ = create_info.enable_implicit_requirements(&physical_device);
 
         let &DeviceCreateInfo {
             ref queue_create_infos,
@@ -532,6 +445,11 @@ impl Device {
             fence_pool: Mutex::new(Vec::new()),
             semaphore_pool: Mutex::new(Vec::new()),
             event_pool: Mutex::new(Vec::new()),
+            memory_requirements_cache: MemoryRequirementsCache::new(),
+            submission_hooks: RwLock::new(None),
+            has_submission_hooks: AtomicBool::new(false),
+            performance_warnings: AtomicBool::new(false),
+            object_names: Mutex::default(),
         });
 
         let queues_iter = {
@@ -634,6 +552,103 @@ impl Device {
         &self.event_pool
     }
 
+    pub(crate) fn memory_requirements_cache(&self) -> &MemoryRequirementsCache {
+        &self.memory_requirements_cache
+    }
+
+    /// Returns whether the memory requirements of newly created buffers and images are cached.
+    ///
+    /// See [`set_memory_requirements_cache_enabled`](Self::set_memory_requirements_cache_enabled)
+    /// for more information.
+    #[inline]
+    pub fn memory_requirements_cache_enabled(&self) -> bool {
+        self.memory_requirements_cache.is_enabled()
+    }
+
+    /// Enables or disables caching of the memory requirements of newly created buffers and
+    /// images.
+    ///
+    /// When enabled, the memory requirements of a buffer or image are only queried from the
+    /// implementation the first time a resource with the same create info is created, and are
+    /// reused for all later resources. Resources with external memory handle types, and images
+    /// with a DRM format modifier, are never cached.
+    ///
+    /// The cache is enabled by default. Disabling it does not clear the entries that are already
+    /// cached, so they will be used again if the cache is re-enabled.
+    #[inline]
+    pub fn set_memory_requirements_cache_enabled(&self, enabled: bool) {
+        self.memory_requirements_cache.set_enabled(enabled);
+    }
+
+    /// Returns the number of cache hits and misses of the memory requirements cache.
+    #[inline]
+    pub fn memory_requirements_cache_stats(&self) -> MemoryRequirementsCacheStats {
+        self.memory_requirements_cache.stats()
+    }
+
+    /// Returns the hooks that are called when work is submitted to the queues of the device, if
+    /// any were set.
+    #[inline]
+    pub fn submission_hooks(&self) -> Option<Arc<dyn SubmissionHooks>> {
+        if !self.has_submission_hooks.load(Ordering::Acquire) {
+            return None;
+        }
+
+        self.submission_hooks.read().clone()
+    }
+
+    /// Sets the hooks that are called when work is submitted to the queues of the device, or
+    /// when a fence of the device is waited on. This replaces any hooks that were set before.
+    ///
+    /// Pass `None` to remove the hooks. When no hooks are set, the only cost is checking a flag
+    /// once per submission or wait, without taking a lock.
+    #[inline]
+    pub fn set_submission_hooks(&self, hooks: Option<Arc<dyn SubmissionHooks>>) {
+        let mut submission_hooks = self.submission_hooks.write();
+        self.has_submission_hooks
+            .store(hooks.is_some(), Ordering::Release);
+        *submission_hooks = hooks;
+    }
+
+    /// Returns whether command buffers that are recorded for this device are analyzed for
+    /// performance problems when they are built.
+    ///
+    /// See [`set_performance_warnings`](Self::set_performance_warnings) for more information.
+    #[inline]
+    pub fn performance_warnings(&self) -> bool {
+        self.performance_warnings.load(Ordering::Relaxed)
+    }
+
+    /// Enables or disables analyzing command buffers for performance problems when they are
+    /// built.
+    ///
+    /// When enabled, [`RecordingCommandBuffer::end`] looks at the render pass instances that were
+    /// recorded, and reports attachment load and store operations that waste memory bandwidth.
+    /// This is especially costly on tile-based GPUs. The following are reported:
+    ///
+    /// - An attachment is stored, but the next use of its contents in the same command buffer
+    ///   clears or discards them without reading them first.
+    /// - An attachment is loaded, but its contents are undefined at that point, because the
+    ///   initial layout is [`ImageLayout::Undefined`] or because the previous render pass instance
+    ///   in the same command buffer did not store it.
+    ///
+    /// The warnings are sent to the debug messengers of the instance with
+    /// [`DebugUtilsMessageSeverity::INFO`] and [`DebugUtilsMessageType::PERFORMANCE`]. Their
+    /// message ID names start with `Vulkano-`. The [`ext_debug_utils`] extension must be enabled
+    /// on the instance for the warnings to be sent anywhere.
+    ///
+    /// This is disabled by default, because the analysis takes time on every build.
+    ///
+    /// [`RecordingCommandBuffer::end`]: crate::command_buffer::RecordingCommandBuffer::end
+    /// [`ImageLayout::Undefined`]: crate::image::ImageLayout::Undefined
+    /// [`DebugUtilsMessageSeverity::INFO`]: crate::instance::debug::DebugUtilsMessageSeverity::INFO
+    /// [`DebugUtilsMessageType::PERFORMANCE`]: crate::instance::debug::DebugUtilsMessageType::PERFORMANCE
+    /// [`ext_debug_utils`]: crate::instance::InstanceExtensions::ext_debug_utils
+    #[inline]
+    pub fn set_performance_warnings(&self, enabled: bool) {
+        self.performance_warnings.store(enabled, Ordering::Relaxed);
+    }
+
     /// For the given acceleration structure build info and primitive counts, returns the
     /// minimum size required to build the acceleration structure, and the minimum size of the
     /// scratch buffer used during the build operation.
@@ -936,6 +951,7 @@ impl Device {
                 descriptor_count,
                 stages,
                 ref immutable_samplers,
+                buffer_min_size: _,
                 _ne: _,
             } = binding;
 
@@ -1033,7 +1049,7 @@ impl Device {
             return Err(Box::new(ValidationError {
                 requires_one_of: RequiresOneOf(&[
                     RequiresAllOf(&[Requires::APIVersion(Version::V1_3)]),
-                    RequiresAllOf(&[Requires::DeviceExtension("khr_maintenance")]),
+                    RequiresAllOf(&[Requires::DeviceExtension("khr_maintenance4")]),
                 ]),
                 ..Default::default()
             }));
@@ -1057,6 +1073,7 @@ impl Device {
             size,
             usage,
             external_memory_handle_types,
+            opaque_capture_address: _,
             _ne: _,
         } = &create_info;
 
@@ -1173,7 +1190,7 @@ impl Device {
             return Err(Box::new(ValidationError {
                 requires_one_of: RequiresOneOf(&[
                     RequiresAllOf(&[Requires::APIVersion(Version::V1_3)]),
-                    RequiresAllOf(&[Requires::DeviceExtension("khr_maintenance")]),
+                    RequiresAllOf(&[Requires::DeviceExtension("khr_maintenance4")]),
                 ]),
                 ..Default::default()
             }));
@@ -1199,7 +1216,9 @@ impl Device {
             initial_layout: _,
             ref drm_format_modifiers,
             drm_format_modifier_plane_layouts: _,
+            layout_policy: _,
             external_memory_handle_types: _,
+            compression_control: _,
             _ne: _,
         } = create_info;
 
@@ -1298,7 +1317,9 @@ impl Device {
             initial_layout,
             ref drm_format_modifiers,
             drm_format_modifier_plane_layouts: _,
+            layout_policy: _,
             external_memory_handle_types,
+            ref compression_control,
             _ne: _,
         } = &create_info;
 
@@ -1331,12 +1352,39 @@ impl Device {
             initial_layout: initial_layout.into(),
             ..Default::default()
         };
+        let mut compression_control_vk = None;
+        let mut compression_control_fixed_rate_flags_vk: SmallVec<[_; 3]>;
         let mut drm_format_modifier_list_info_vk = None;
         let mut external_memory_info_vk = None;
         let mut format_list_info_vk = None;
         let format_list_view_formats_vk: Vec<_>;
         let mut stencil_usage_info_vk = None;
 
+        if let Some(compression_control) = compression_control {
+            let &ImageCompressionControl {
+                flags,
+                ref fixed_rate_flags,
+                _ne: _,
+            } = compression_control;
+
+            compression_control_fixed_rate_flags_vk = fixed_rate_flags
+                .iter()
+                .copied()
+                .map(ash::vk::ImageCompressionFixedRateFlagsEXT::from)
+                .collect();
+
+            let next = compression_control_vk.insert(ash::vk::ImageCompressionControlEXT {
+                flags: flags.into(),
+                compression_control_plane_count: compression_control_fixed_rate_flags_vk.len()
+                    as u32,
+                p_fixed_rate_flags: compression_control_fixed_rate_flags_vk.as_mut_ptr(),
+                ..Default::default()
+            });
+
+            next.p_next = create_info_vk.p_next;
+            create_info_vk.p_next = <*const _>::cast(next);
+        }
+
         if !drm_format_modifiers.is_empty() {
             let next = drm_format_modifier_list_info_vk.insert(
                 ash::vk::ImageDrmFormatModifierListCreateInfoEXT {
@@ -1586,9 +1634,47 @@ impl Device {
                 .map_err(VulkanError::from)?;
         }
 
+        if matches!(
+            T::Handle::TYPE,
+            ash::vk::ObjectType::PIPELINE | ash::vk::ObjectType::DESCRIPTOR_SET
+        ) {
+            let key = (T::Handle::TYPE, object.handle().as_raw());
+            let mut object_names = self.object_names.lock();
+
+            match object_name {
+                Some(object_name) => {
+                    object_names.insert(key, object_name.to_owned());
+                }
+                None => {
+                    object_names.remove(&key);
+                }
+            }
+        }
+
         Ok(())
     }
 
+    /// Returns the name that was assigned to the pipeline or descriptor set `object` with
+    /// [`set_debug_utils_object_name`], if any.
+    ///
+    /// [`set_debug_utils_object_name`]: Self::set_debug_utils_object_name
+    pub(crate) fn debug_utils_object_name<T: VulkanObject>(&self, object: &T) -> Option<String> {
+        self.object_names
+            .lock()
+            .get(&(T::Handle::TYPE, object.handle().as_raw()))
+            .cloned()
+    }
+
+    /// Forgets the name of a pipeline or descriptor set, when it is destroyed or returned to its
+    /// pool.
+    pub(crate) fn forget_debug_utils_object_name<T: Handle>(&self, handle: T) {
+        let mut object_names = self.object_names.lock();
+
+        if !object_names.is_empty() {
+            object_names.remove(&(T::TYPE, handle.as_raw()));
+        }
+    }
+
     /// Waits until all work on this device has finished. You should never need to call
     /// this function, but it can be useful for debugging or benchmarking purposes.
     ///
@@ -1629,6 +1715,11 @@ impl Debug for Device {
             fence_pool: _,
             semaphore_pool: _,
             event_pool: _,
+            memory_requirements_cache: _,
+            submission_hooks: _,
+            has_submission_hooks: _,
+            performance_warnings: _,
+            object_names: _,
         } = self;
 
         f.debug_struct("Device")
@@ -1773,6 +1864,222 @@ impl Default for DeviceCreateInfo {
 }
 
 impl DeviceCreateInfo {
+    /// Completes `enabled_extensions` with everything that is needed by `enabled_features` and
+    /// `enabled_extensions` on `physical_device`, and checks that all of it is supported.
+    ///
+    /// For each enabled feature that is not part of the core API of the physical device, one of
+    /// the device extensions that provides it is enabled, unless one is already enabled. After
+    /// that, the extensions that the enabled extensions depend on are enabled as well.
+    ///
+    /// If anything that was requested is not supported by the physical device, an error is
+    /// returned listing all such items, rather than just the first one.
+    ///
+    /// This does not validate the rest of the create info; that still happens when it is passed
+    /// to [`Device::new`].
+    pub fn resolve_requirements(
+        mut self,
+        physical_device: &PhysicalDevice,
+    ) -> Result<Self, UnsupportedRequirementsError> {
+        let api_version = physical_device.api_version();
+        let supported_extensions = physical_device.supported_extensions();
+        let mut error = UnsupportedRequirementsError {
+            extensions: self.enabled_extensions - *supported_extensions,
+            features: self.enabled_features - *physical_device.supported_features(),
+            feature_extensions: Vec::new(),
+        };
+
+        for (feature, extensions) in self.enabled_features.extension_dependencies(api_version) {
+            if self.enabled_extensions.intersects(&extensions) {
+                continue;
+            }
+
+            match (extensions & *supported_extensions)
+                .into_iter()
+                .find_map(|(name, enabled)| enabled.then_some(name))
+            {
+                Some(extension) => {
+                    self.enabled_extensions |= DeviceExtensions::from_iter([extension]);
+                }
+                None => error.feature_extensions.push((feature, extensions)),
+            }
+        }
+
+        if !error.is_empty() {
+            return Err(error);
+        }
+
+        self.enabled_extensions
+            .enable_dependencies(api_version, supported_extensions);
+
+        Ok(self)
+    }
+
+    /// Performs all the validation that [`Device::new`] would perform with `physical_device`, and
+    /// returns what would be enabled on the device, without creating it.
+    ///
+    /// This can be used to check a configuration against the physical devices of a system, for
+    /// example in a launcher, before the device is created in another process.
+    pub fn dry_run(
+        &self,
+        physical_device: &PhysicalDevice,
+    ) -> Result<DeviceCreationReport, Box<ValidationError>> {
+        Device::validate_new(physical_device, self)?;
+
+        let mut create_info = self.clone();
+        create_info.enable_implicit_requirements(physical_device)?;
+
+        let api_version = physical_device.api_version();
+
+        Ok(DeviceCreationReport {
+            api_version,
+            enabled_extensions: create_info.enabled_extensions,
+            enabled_features: create_info.enabled_features,
+            implicit_extensions: create_info.enabled_extensions - self.enabled_extensions,
+            implicit_features: create_info.enabled_features - self.enabled_features,
+            promoted_extensions: self.enabled_extensions.promoted_to_core(api_version),
+        })
+    }
+
+    /// Enables the extensions and features that vulkano always enables on `physical_device`
+    /// along with the ones in `self`, as [`Device::new`] does.
+    ///
+    /// Returns an error if one of the features that must be enabled is not supported by
+    /// `physical_device`. Everything is still enabled in that case.
+    fn enable_implicit_requirements(
+        &mut self,
+        physical_device: &PhysicalDevice,
+    ) -> Result<(), Box<ValidationError>> {
+        let mut result = Ok(());
+        // VUID-vkCreateDevice-ppEnabledExtensionNames-01387
+        self.enabled_extensions.enable_dependencies(
+            physical_device.api_version(),
+            physical_device.supported_extensions(),
+        );
+
+        // VUID-VkDeviceCreateInfo-pProperties-04451
+        if physical_device
+            .supported_extensions()
+            .khr_portability_subset
+        {
+            self.enabled_extensions.khr_portability_subset = true;
+        }
+
+        macro_rules! enable_extension_required_features {
+            (
+                $extension:ident,
+                $feature_to_enable:ident $(,)?
+            ) => {
+                if self.enabled_extensions.$extension {
+                    if !physical_device.supported_features().$feature_to_enable
+                        && result.is_ok()
+                    {
+                        result = Err(Box::new(ValidationError {
+                            problem: format!(
+                                "the device extension `{}` is enabled, and it requires the `{}` \
+                                device feature to be also enabled, but the device does not \
+                                support the required feature. This is a bug in the Vulkan \
+                                driver for this device",
+                                stringify!($extension),
+                                stringify!($feature_to_enable),
+                            )
+                            .into(),
+                            ..Default::default()
+                        }));
+                    }
+
+                    self.enabled_features.$feature_to_enable = true;
+                }
+            };
+        }
+
+        if physical_device.api_version() >= Version::V1_1 {
+            // VUID-VkDeviceCreateInfo-ppEnabledExtensionNames-04476
+            enable_extension_required_features!(khr_shader_draw_parameters, shader_draw_parameters);
+        }
+
+        if physical_device.api_version() >= Version::V1_2 {
+            // VUID-VkDeviceCreateInfo-ppEnabledExtensionNames-02831
+            enable_extension_required_features!(khr_draw_indirect_count, draw_indirect_count);
+
+            // VUID-VkDeviceCreateInfo-ppEnabledExtensionNames-02832
+            enable_extension_required_features!(
+                khr_sampler_mirror_clamp_to_edge,
+                sampler_mirror_clamp_to_edge,
+            );
+
+            // VUID-VkDeviceCreateInfo-ppEnabledExtensionNames-02833
+            enable_extension_required_features!(ext_descriptor_indexing, descriptor_indexing);
+
+            // VUID-VkDeviceCreateInfo-ppEnabledExtensionNames-02834
+            enable_extension_required_features!(ext_sampler_filter_minmax, sampler_filter_minmax);
+
+            // VUID-VkDeviceCreateInfo-ppEnabledExtensionNames-02835
+            enable_extension_required_features!(
+                ext_shader_viewport_index_layer,
+                shader_output_layer,
+            );
+            enable_extension_required_features!(
+                ext_shader_viewport_index_layer,
+                shader_output_layer,
+            );
+        }
+
+        macro_rules! enable_feature_required_features {
+            (
+                $feature:ident,
+                $feature_to_enable:ident $(,)?
+            ) => {
+                if self.enabled_features.$feature {
+                    if !physical_device.supported_features().$feature_to_enable && result.is_ok() {
+                        result = Err(Box::new(ValidationError {
+                            problem: format!(
+                                "the device feature `{}` is enabled, and it requires the `{}` \
+                                feature to be also enabled, but the device does not support the \
+                                required feature. This is a bug in the Vulkan driver for this \
+                                device",
+                                stringify!($feature),
+                                stringify!($feature_to_enable),
+                            )
+                            .into(),
+                            ..Default::default()
+                        }));
+                    }
+
+                    self.enabled_features.$feature_to_enable = true;
+                }
+            };
+        }
+
+        // VUID-VkPhysicalDeviceVariablePointersFeatures-variablePointers-01431
+        enable_feature_required_features!(variable_pointers, variable_pointers_storage_buffer);
+
+        // VUID-VkPhysicalDeviceMultiviewFeatures-multiviewGeometryShader-00580
+        enable_feature_required_features!(multiview_geometry_shader, multiview);
+
+        // VUID-VkPhysicalDeviceMultiviewFeatures-multiviewTessellationShader-00581
+        enable_feature_required_features!(multiview_tessellation_shader, multiview);
+
+        // VUID-VkPhysicalDeviceMeshShaderFeaturesEXT-multiviewMeshShader-07032
+        enable_feature_required_features!(multiview_mesh_shader, multiview);
+
+        // VUID-VkPhysicalDeviceMeshShaderFeaturesEXT-primitiveFragmentShadingRateMeshShader-07033
+        enable_feature_required_features!(
+            primitive_fragment_shading_rate_mesh_shader,
+            primitive_fragment_shading_rate,
+        );
+
+        // VUID-VkPhysicalDeviceRayTracingPipelineFeaturesKHR-rayTracingPipelineShaderGroupHandleCaptureReplayMixed-03575
+        enable_feature_required_features!(
+            ray_tracing_pipeline_shader_group_handle_capture_replay_mixed,
+            ray_tracing_pipeline_shader_group_handle_capture_replay,
+        );
+
+        // VUID-VkPhysicalDeviceRobustness2FeaturesEXT-robustBufferAccess2-04000
+        enable_feature_required_features!(robust_buffer_access2, robust_buffer_access);
+
+        result
+    }
+
     pub(crate) fn validate(
         &self,
         physical_device: &PhysicalDevice,
@@ -1859,6 +2166,38 @@ impl DeviceCreateInfo {
         // VUID-VkDeviceCreateInfo-ppEnabledExtensionNames-00374
         // Ensured because `DeviceExtensions` doesn't contain obsoleted extensions.
 
+        // The structure of a feature that is provided by an extension is only passed to the
+        // driver if that extension is enabled, so the feature would be silently ignored.
+        for (feature, extensions) in
+            enabled_features.extension_dependencies(physical_device.api_version())
+        {
+            if dependency_extensions.intersects(&extensions) {
+                continue;
+            }
+
+            return Err(Box::new(ValidationError {
+                context: "enabled_features".into(),
+                problem: if extensions == DeviceExtensions::empty() {
+                    format!(
+                        "contains `{}`, but this feature is not available with the API version \
+                        of the physical device",
+                        feature,
+                    )
+                    .into()
+                } else {
+                    format!(
+                        "contains `{}`, but with the API version of the physical device, this \
+                        feature requires one of the device extensions {:?} to be enabled, and \
+                        `enabled_extensions` does not contain any of them",
+                        feature, extensions,
+                    )
+                    .into()
+                },
+                vuids: &["VUID-VkDeviceCreateInfo-pNext-pNext"],
+                ..Default::default()
+            }));
+        }
+
         if enabled_extensions.ext_buffer_device_address {
             if enabled_extensions.khr_buffer_device_address {
                 return Err(Box::new(ValidationError {
@@ -2093,6 +2432,144 @@ impl DeviceCreateInfo {
     }
 }
 
+/// Error returned by [`DeviceCreateInfo::resolve_requirements`], listing everything that was
+/// requested but is not supported by the physical device.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsupportedRequirementsError {
+    /// The requested device extensions that are not supported by the physical device.
+    pub extensions: DeviceExtensions,
+
+    /// The requested device features that are not supported by the physical device.
+    pub features: DeviceFeatures,
+
+    /// Requested device features that need a device extension with the API version of the
+    /// physical device, together with the extensions that could provide them, none of which are
+    /// supported. If the set of extensions is empty, the feature needs a higher API version.
+    pub feature_extensions: Vec<(&'static str, DeviceExtensions)>,
+}
+
+impl UnsupportedRequirementsError {
+    fn is_empty(&self) -> bool {
+        self.extensions == DeviceExtensions::empty()
+            && self.features == DeviceFeatures::empty()
+            && self.feature_extensions.is_empty()
+    }
+}
+
+impl Error for UnsupportedRequirementsError {}
+
+impl Display for UnsupportedRequirementsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(
+            f,
+            "the physical device does not support all requested items:"
+        )?;
+
+        if self.extensions != DeviceExtensions::empty() {
+            write!(f, " unsupported extensions: {:?};", self.extensions)?;
+        }
+
+        if self.features != DeviceFeatures::empty() {
+            write!(f, " unsupported features: {:?};", self.features)?;
+        }
+
+        for (feature, extensions) in &self.feature_extensions {
+            if *extensions == DeviceExtensions::empty() {
+                write!(
+                    f,
+                    " feature `{}` is not available with the API version;",
+                    feature,
+                )?;
+            } else {
+                write!(
+                    f,
+                    " feature `{}` requires one of the unsupported extensions {:?};",
+                    feature, extensions,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The result of [`DeviceCreateInfo::dry_run`], describing what would be enabled on a device
+/// that is created with the same parameters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeviceCreationReport {
+    /// The API version that the device would have.
+    pub api_version: Version,
+
+    /// All the extensions that would be enabled on the device.
+    pub enabled_extensions: DeviceExtensions,
+
+    /// All the features that would be enabled on the device.
+    pub enabled_features: DeviceFeatures,
+
+    /// The extensions in `enabled_extensions` that were not requested, but that are enabled
+    /// because the requested extensions depend on them, or because vulkano always enables them.
+    pub implicit_extensions: DeviceExtensions,
+
+    /// The features in `enabled_features` that were not requested, but that are enabled because
+    /// the enabled extensions or features require them.
+    pub implicit_features: DeviceFeatures,
+
+    /// Requested extensions that were promoted to a core API version that is not greater than
+    /// `api_version`, together with that version. Vulkano uses the core functionality instead of
+    /// the extension for these.
+    pub promoted_extensions: Vec<(&'static str, Version)>,
+}
+
+impl Display for DeviceCreationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        writeln!(f, "API version: {}", self.api_version)?;
+        writeln!(f, "enabled extensions: {:?}", self.enabled_extensions)?;
+        writeln!(f, "enabled features: {:?}", self.enabled_features)?;
+
+        if self.implicit_extensions != DeviceExtensions::empty() {
+            writeln!(
+                f,
+                "implicitly enabled extensions: {:?}",
+                self.implicit_extensions
+            )?;
+        }
+
+        if self.implicit_features != DeviceFeatures::empty() {
+            writeln!(
+                f,
+                "implicitly enabled features: {:?}",
+                self.implicit_features
+            )?;
+        }
+
+        for (extension, version) in &self.promoted_extensions {
+            writeln!(
+                f,
+                "`{}` was requested, but the device API version is at least {}, so the core \
+                functionality will be used",
+                extension, version,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Error returned by [`DeviceFeatures::from_names`] when a name doesn't match any feature.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownFeature {
+    /// The name that was not recognized.
+    pub name: String,
+}
+
+impl Error for UnknownFeature {}
+
+impl Display for UnknownFeature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(f, "`{}` is not the name of a device feature", self.name)
+    }
+}
+
 /// Parameters to create queues in a new `Device`.
 #[derive(Clone, Debug)]
 pub struct QueueCreateInfo {
@@ -2299,8 +2776,12 @@ pub struct MemoryFdProperties {
 
 #[cfg(test)]
 mod tests {
-    use crate::device::{
-        Device, DeviceCreateInfo, DeviceExtensions, DeviceFeatures, QueueCreateInfo,
+    use crate::{
+        device::{
+            physical, Device, DeviceCreateInfo, DeviceExtensions, DeviceFeatures, QueueCreateInfo,
+            QueueFlags,
+        },
+        Version,
     };
     use std::{ffi::CString, sync::Arc};
 
@@ -2310,6 +2791,41 @@ mod tests {
         assert!(d.is_empty());
     }
 
+    #[test]
+    fn names_round_trip() {
+        let extensions = DeviceExtensions {
+            khr_swapchain: true,
+            khr_maintenance1: true,
+            ..DeviceExtensions::empty()
+        };
+        let mut names: Vec<_> = extensions.iter_enabled().collect();
+        names.sort_unstable();
+        assert_eq!(names, ["VK_KHR_maintenance1", "VK_KHR_swapchain"]);
+        assert_eq!(DeviceExtensions::from_names(&names), Ok(extensions));
+        assert_eq!(
+            DeviceExtensions::from_names(&["VK_KHR_swapchain", "VK_FOO_bar"])
+                .unwrap_err()
+                .name,
+            "VK_FOO_bar",
+        );
+
+        let features = DeviceFeatures {
+            multiview: true,
+            shader_int64: true,
+            ..DeviceFeatures::empty()
+        };
+        let mut names: Vec<_> = features.iter_enabled().collect();
+        names.sort_unstable();
+        assert_eq!(names, ["multiview", "shader_int64"]);
+        assert_eq!(DeviceFeatures::from_names(&names), Ok(features));
+        assert_eq!(
+            DeviceFeatures::from_names(&["multiview", "multi_view"])
+                .unwrap_err()
+                .name,
+            "multi_view",
+        );
+    }
+
     #[test]
     fn extensions_into_iter() {
         let extensions = DeviceExtensions {
@@ -2340,6 +2856,191 @@ mod tests {
         }
     }
 
+    #[test]
+    fn feature_extension_dependencies() {
+        let features = DeviceFeatures {
+            dynamic_rendering: true,
+            geometry_shader: true,
+            ..DeviceFeatures::empty()
+        };
+
+        let dependencies = features.extension_dependencies(Version::V1_2);
+        assert_eq!(dependencies.len(), 1);
+        assert_eq!(dependencies[0].0, "dynamic_rendering");
+        assert!(dependencies[0].1.khr_dynamic_rendering);
+
+        assert!(features.extension_dependencies(Version::V1_3).is_empty());
+    }
+
+    #[test]
+    fn feature_requires_extension() {
+        let instance = instance!();
+        let physical_device = match instance.enumerate_physical_devices().unwrap().next() {
+            Some(p) => p,
+            None => return,
+        };
+
+        // This feature is only provided by `ext_extended_dynamic_state3`.
+        if !physical_device
+            .supported_features()
+            .extended_dynamic_state3_tessellation_domain_origin
+        {
+            return;
+        }
+
+        let create_info = DeviceCreateInfo {
+            queue_create_infos: vec![QueueCreateInfo {
+                queue_family_index: 0,
+                ..Default::default()
+            }],
+            enabled_features: DeviceFeatures {
+                extended_dynamic_state3_tessellation_domain_origin: true,
+                ..DeviceFeatures::empty()
+            },
+            ..Default::default()
+        };
+
+        let err = create_info.dry_run(&physical_device).unwrap_err();
+        assert_eq!(err.context, "create_info.enabled_features");
+        assert_eq!(err.vuids, ["VUID-VkDeviceCreateInfo-pNext-pNext"]);
+
+        let create_info = DeviceCreateInfo {
+            enabled_extensions: DeviceExtensions {
+                ext_extended_dynamic_state3: true,
+                ..DeviceExtensions::empty()
+            },
+            ..create_info
+        };
+        assert!(create_info.dry_run(&physical_device).is_ok());
+    }
+
+    #[test]
+    fn resolve_requirements() {
+        let instance = instance!();
+        let physical_device = match instance.enumerate_physical_devices().unwrap().next() {
+            Some(p) => p,
+            None => return,
+        };
+
+        let create_info = DeviceCreateInfo {
+            queue_create_infos: vec![QueueCreateInfo {
+                queue_family_index: 0,
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+        .resolve_requirements(&physical_device)
+        .unwrap();
+        assert_eq!(create_info.enabled_extensions, DeviceExtensions::empty());
+
+        let features = DeviceFeatures::all();
+        if physical_device.supported_features().contains(&features) {
+            return;
+        }
+
+        let error = DeviceCreateInfo {
+            enabled_features: features,
+            ..Default::default()
+        }
+        .resolve_requirements(&physical_device)
+        .unwrap_err();
+        assert!(!physical_device
+            .supported_features()
+            .intersects(&error.features));
+        assert_ne!(error.features, DeviceFeatures::empty());
+    }
+
+    #[test]
+    fn dry_run() {
+        let instance = instance!();
+        let physical_device = match instance.enumerate_physical_devices().unwrap().next() {
+            Some(p) => p,
+            None => return,
+        };
+
+        assert!(DeviceCreateInfo::default()
+            .dry_run(&physical_device)
+            .is_err());
+
+        let create_info = DeviceCreateInfo {
+            queue_create_infos: vec![QueueCreateInfo {
+                queue_family_index: 0,
+                ..Default::default()
+            }],
+            enabled_extensions: DeviceExtensions {
+                khr_dynamic_rendering: physical_device.supported_extensions().khr_dynamic_rendering,
+                ..DeviceExtensions::empty()
+            },
+            ..Default::default()
+        };
+        let report = create_info.dry_run(&physical_device).unwrap();
+
+        assert_eq!(report.api_version, physical_device.api_version());
+        assert!(report
+            .enabled_extensions
+            .contains(&create_info.enabled_extensions));
+        assert_eq!(
+            report.implicit_extensions,
+            report.enabled_extensions - create_info.enabled_extensions,
+        );
+
+        if create_info.enabled_extensions.khr_dynamic_rendering
+            && physical_device.api_version() >= Version::V1_3
+        {
+            assert_eq!(
+                report.promoted_extensions,
+                [("khr_dynamic_rendering", Version::V1_3)],
+            );
+        } else {
+            assert!(report.promoted_extensions.is_empty());
+        }
+    }
+
+    #[test]
+    fn select_physical_device() {
+        let instance = instance!();
+        let device_count = instance.enumerate_physical_devices().unwrap().len();
+
+        if device_count == 0 {
+            return;
+        }
+
+        let (physical_device, queue_family_indices) = physical::select_physical_device(
+            &instance,
+            &physical::DeviceSelectionCriteria {
+                queue_requirements: vec![physical::QueueRequirement {
+                    queue_flags: QueueFlags::TRANSFER,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(queue_family_indices.len(), 1);
+        assert!(
+            physical_device.queue_family_properties()[queue_family_indices[0] as usize]
+                .queue_flags
+                .intersects(QueueFlags::TRANSFER)
+        );
+
+        match physical::select_physical_device(
+            &instance,
+            &physical::DeviceSelectionCriteria {
+                api_version: Version::major_minor(u32::MAX, 0),
+                ..Default::default()
+            },
+        ) {
+            Err(physical::SelectionFailure::NoSuitableDevice(rejected)) => {
+                assert_eq!(rejected.len(), device_count);
+                assert!(rejected.iter().all(|rejected_device| matches!(
+                    rejected_device.reasons[..],
+                    [physical::RejectionReason::ApiVersion { .. }],
+                )));
+            }
+            _ => panic!(),
+        }
+    }
+
     #[test]
     fn one_ref() {
         let (mut device, _) = gfx_dev_and_queue!();
@@ -2449,4 +3150,46 @@ mod tests {
             panic!();
         }
     }
+
+    #[test]
+    fn vendor_properties() {
+        let instance = instance!();
+        let physical_device = match instance.enumerate_physical_devices().unwrap().next() {
+            Some(p) => p,
+            None => return,
+        };
+
+        // The vendor-specific property structs are queried like any other, whenever the
+        // extension that provides them is supported.
+        if physical_device.api_version() < Version::V1_1 {
+            return;
+        }
+
+        let extensions = physical_device.supported_extensions();
+        let properties = physical_device.properties();
+        assert_eq!(
+            properties.shader_sm_count.is_some(),
+            extensions.nv_shader_sm_builtins,
+        );
+        assert_eq!(
+            properties.shader_warps_per_sm.is_some(),
+            extensions.nv_shader_sm_builtins,
+        );
+        assert_eq!(
+            properties.compute_units_per_shader_array.is_some(),
+            extensions.amd_shader_core_properties,
+        );
+        assert_eq!(
+            properties.active_compute_unit_count.is_some(),
+            extensions.amd_shader_core_properties2,
+        );
+        assert_eq!(
+            properties.shader_core_count.is_some(),
+            extensions.arm_shader_core_builtins,
+        );
+        assert_eq!(
+            properties.fma_rate.is_some(),
+            extensions.arm_shader_core_properties,
+        );
+    }
 }
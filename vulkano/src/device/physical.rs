@@ -1,4 +1,4 @@
-use super::QueueFamilyProperties;
+use super::{QueueFamilyProperties, QueueFlags};
 use crate::{
     buffer::{ExternalBufferInfo, ExternalBufferProperties},
     cache::{OnceCache, WeakArcOnceCache},
@@ -14,7 +14,7 @@ use crate::{
     },
     instance::{Instance, InstanceOwned},
     macros::{impl_id_counter, vulkan_bitflags, vulkan_enum},
-    memory::{ExternalMemoryHandleType, MemoryProperties},
+    memory::{ExternalMemoryHandleType, MemoryHeapFlags, MemoryProperties, MemoryPropertyFlags},
     swapchain::{
         ColorSpace, FullScreenExclusive, PresentMode, Surface, SurfaceApi, SurfaceCapabilities,
         SurfaceInfo, SurfaceTransforms,
@@ -24,8 +24,8 @@ use crate::{
         semaphore::{ExternalSemaphoreInfo, ExternalSemaphoreProperties, SemaphoreType},
         Sharing,
     },
-    DebugWrapper, ExtensionProperties, Requires, RequiresAllOf, RequiresOneOf, Validated,
-    ValidationError, Version, VulkanError, VulkanObject,
+    DebugWrapper, DeviceSize, ExtensionProperties, Requires, RequiresAllOf, RequiresOneOf,
+    Validated, ValidationError, Version, VulkanError, VulkanObject,
 };
 use bytemuck::cast_slice;
 use parking_lot::RwLock;
@@ -414,6 +414,31 @@ impl PhysicalDevice {
         &self.memory_properties
     }
 
+    /// Returns the size of the largest memory heap containing a memory type that is both
+    /// [`DEVICE_LOCAL`] and [`HOST_VISIBLE`], or `None` if there is no such memory type.
+    ///
+    /// On devices with resizable BAR or unified memory, this is usually the size of all of VRAM.
+    /// Otherwise it is usually around 256 MiB, which is only suitable for small allocations that
+    /// the host updates frequently. See also [`MemoryTypeFilter::PREFER_DEVICE_HOST_VISIBLE`].
+    ///
+    /// [`DEVICE_LOCAL`]: crate::memory::MemoryPropertyFlags::DEVICE_LOCAL
+    /// [`HOST_VISIBLE`]: crate::memory::MemoryPropertyFlags::HOST_VISIBLE
+    /// [`MemoryTypeFilter::PREFER_DEVICE_HOST_VISIBLE`]: crate::memory::allocator::MemoryTypeFilter::PREFER_DEVICE_HOST_VISIBLE
+    pub fn has_device_local_host_visible_memory(&self) -> Option<DeviceSize> {
+        let memory_properties = &self.memory_properties;
+
+        memory_properties
+            .memory_types
+            .iter()
+            .filter(|memory_type| {
+                memory_type
+                    .property_flags
+                    .contains(MemoryPropertyFlags::DEVICE_LOCAL | MemoryPropertyFlags::HOST_VISIBLE)
+            })
+            .map(|memory_type| memory_properties.memory_heaps[memory_type.heap_index as usize].size)
+            .max()
+    }
+
     /// Returns the queue family properties reported by the physical device.
     #[inline]
     pub fn queue_family_properties(&self) -> &[QueueFamilyProperties] {
@@ -1280,15 +1305,24 @@ impl PhysicalDevice {
             let mut drm_format_modifier_properties_list2_vk = None;
             let mut drm_format_modifier_properties2_vk = Vec::new();
 
-            if self.api_version() >= Version::V1_3
-                || self.supported_extensions().khr_format_feature_flags2
+            // The extension structs can only be filled in if `vkGetPhysicalDeviceFormatProperties2`
+            // is available. Otherwise, they would stay zeroed and no features would be reported.
+            let has_properties2 = self.api_version() >= Version::V1_1
+                || self
+                    .instance
+                    .enabled_extensions()
+                    .khr_get_physical_device_properties2;
+
+            if has_properties2
+                && (self.api_version() >= Version::V1_3
+                    || self.supported_extensions().khr_format_feature_flags2)
             {
                 let next = format_properties3_vk.insert(ash::vk::FormatProperties3KHR::default());
                 next.p_next = format_properties2_vk.p_next;
                 format_properties2_vk.p_next = <*mut _>::cast(next);
             }
 
-            if self.supported_extensions().ext_image_drm_format_modifier {
+            if has_properties2 && self.supported_extensions().ext_image_drm_format_modifier {
                 let next = drm_format_modifier_properties_list_vk
                     .insert(ash::vk::DrmFormatModifierPropertiesListEXT::default());
                 next.p_next = format_properties2_vk.p_next;
@@ -3323,6 +3357,402 @@ unsafe impl InstanceOwned for PhysicalDevice {
 
 impl_id_counter!(PhysicalDevice);
 
+/// Selects the most suitable physical device of `instance` according to `criteria`.
+///
+/// All physical devices that satisfy every requirement in `criteria` are ranked by
+/// [`preferred_device_types`], and the best one is returned together with a queue family index
+/// for each element of [`queue_requirements`], in the same order. Devices with the same rank are
+/// ordered as they were enumerated.
+///
+/// If no device is suitable, the returned [`SelectionFailure`] lists, for each enumerated
+/// device, every requirement that it failed. Its `Display` implementation is intended to be
+/// shown to the user or included in bug reports.
+///
+/// [`preferred_device_types`]: DeviceSelectionCriteria::preferred_device_types
+/// [`queue_requirements`]: DeviceSelectionCriteria::queue_requirements
+pub fn select_physical_device(
+    instance: &Arc<Instance>,
+    criteria: &DeviceSelectionCriteria,
+) -> Result<(Arc<PhysicalDevice>, Vec<u32>), SelectionFailure> {
+    let physical_devices = instance
+        .enumerate_physical_devices()
+        .map_err(SelectionFailure::Enumeration)?;
+    let mut suitable = Vec::new();
+    let mut rejected = Vec::new();
+
+    for physical_device in physical_devices {
+        match criteria.check(&physical_device) {
+            Ok(queue_family_indices) => suitable.push((physical_device, queue_family_indices)),
+            Err(reasons) => rejected.push(RejectedDevice {
+                physical_device,
+                reasons,
+            }),
+        }
+    }
+
+    suitable
+        .into_iter()
+        .min_by_key(|(physical_device, _)| criteria.rank(physical_device))
+        .ok_or(SelectionFailure::NoSuitableDevice(rejected))
+}
+
+/// Requirements and preferences for [`select_physical_device`].
+#[derive(Clone, Debug)]
+pub struct DeviceSelectionCriteria {
+    /// The minimum API version that the physical device must support.
+    ///
+    /// The default value is [`Version::V1_0`].
+    pub api_version: Version,
+
+    /// The device extensions that the physical device must support.
+    ///
+    /// The default value is [`DeviceExtensions::empty()`].
+    pub required_extensions: DeviceExtensions,
+
+    /// The device features that the physical device must support.
+    ///
+    /// The default value is [`DeviceFeatures::empty()`].
+    pub required_features: DeviceFeatures,
+
+    /// The queue families that the physical device must have.
+    ///
+    /// A queue family index is selected for each element, and the same queue family may be
+    /// selected for multiple elements.
+    ///
+    /// The default value is empty.
+    pub queue_requirements: Vec<QueueRequirement>,
+
+    /// The minimum total size, in bytes, of the memory heaps of the physical device that have
+    /// the [`DEVICE_LOCAL`] flag.
+    ///
+    /// The default value is `0`.
+    ///
+    /// [`DEVICE_LOCAL`]: crate::memory::MemoryHeapFlags::DEVICE_LOCAL
+    pub min_device_local_memory: DeviceSize,
+
+    /// The device types to prefer, from most to least preferred. Devices with a type that is not
+    /// in the list are only selected if there is no suitable device with a listed type.
+    ///
+    /// The default value contains, in order, [`DiscreteGpu`], [`IntegratedGpu`],
+    /// [`VirtualGpu`], [`Cpu`] and [`Other`].
+    ///
+    /// [`DiscreteGpu`]: PhysicalDeviceType::DiscreteGpu
+    /// [`IntegratedGpu`]: PhysicalDeviceType::IntegratedGpu
+    /// [`VirtualGpu`]: PhysicalDeviceType::VirtualGpu
+    /// [`Cpu`]: PhysicalDeviceType::Cpu
+    /// [`Other`]: PhysicalDeviceType::Other
+    pub preferred_device_types: Vec<PhysicalDeviceType>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for DeviceSelectionCriteria {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            api_version: Version::V1_0,
+            required_extensions: DeviceExtensions::empty(),
+            required_features: DeviceFeatures::empty(),
+            queue_requirements: Vec::new(),
+            min_device_local_memory: 0,
+            preferred_device_types: vec![
+                PhysicalDeviceType::DiscreteGpu,
+                PhysicalDeviceType::IntegratedGpu,
+                PhysicalDeviceType::VirtualGpu,
+                PhysicalDeviceType::Cpu,
+                PhysicalDeviceType::Other,
+            ],
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+impl DeviceSelectionCriteria {
+    fn check(&self, physical_device: &PhysicalDevice) -> Result<Vec<u32>, Vec<RejectionReason>> {
+        let &Self {
+            api_version,
+            ref required_extensions,
+            ref required_features,
+            ref queue_requirements,
+            min_device_local_memory,
+            preferred_device_types: _,
+            _ne: _,
+        } = self;
+
+        let mut reasons = Vec::new();
+
+        if physical_device.api_version() < api_version {
+            reasons.push(RejectionReason::ApiVersion {
+                required: api_version,
+                supported: physical_device.api_version(),
+            });
+        }
+
+        let missing_extensions = *required_extensions - *physical_device.supported_extensions();
+
+        if missing_extensions != DeviceExtensions::empty() {
+            reasons.push(RejectionReason::MissingExtensions(missing_extensions));
+        }
+
+        let missing_features = *required_features - *physical_device.supported_features();
+
+        if missing_features != DeviceFeatures::empty() {
+            reasons.push(RejectionReason::MissingFeatures(missing_features));
+        }
+
+        let device_local_memory: DeviceSize = physical_device
+            .memory_properties()
+            .memory_heaps
+            .iter()
+            .filter(|heap| heap.flags.intersects(MemoryHeapFlags::DEVICE_LOCAL))
+            .map(|heap| heap.size)
+            .sum();
+
+        if device_local_memory < min_device_local_memory {
+            reasons.push(RejectionReason::InsufficientMemory {
+                required: min_device_local_memory,
+                available: device_local_memory,
+            });
+        }
+
+        let mut queue_family_indices = Vec::with_capacity(queue_requirements.len());
+
+        for (requirement_index, requirement) in queue_requirements.iter().enumerate() {
+            match requirement.find_queue_family(physical_device) {
+                Ok(queue_family_index) => queue_family_indices.push(queue_family_index),
+                Err(error) => reasons.push(RejectionReason::NoQueueFamily {
+                    requirement_index,
+                    error,
+                }),
+            }
+        }
+
+        if reasons.is_empty() {
+            Ok(queue_family_indices)
+        } else {
+            Err(reasons)
+        }
+    }
+
+    fn rank(&self, physical_device: &PhysicalDevice) -> usize {
+        let device_type = physical_device.properties().device_type;
+
+        self.preferred_device_types
+            .iter()
+            .position(|&preferred| preferred == device_type)
+            .unwrap_or(self.preferred_device_types.len())
+    }
+}
+
+/// A queue family that must be present on a physical device, as part of
+/// [`DeviceSelectionCriteria`].
+#[derive(Clone, Debug)]
+pub struct QueueRequirement {
+    /// The flags that the queue family must have.
+    ///
+    /// The default value is empty.
+    pub queue_flags: QueueFlags,
+
+    /// If `Some`, the queue family must support presenting to this surface.
+    ///
+    /// The default value is `None`.
+    pub surface: Option<Arc<Surface>>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for QueueRequirement {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            queue_flags: QueueFlags::empty(),
+            surface: None,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+impl QueueRequirement {
+    fn find_queue_family(
+        &self,
+        physical_device: &PhysicalDevice,
+    ) -> Result<u32, Option<Validated<VulkanError>>> {
+        let mut last_error = None;
+
+        for (index, properties) in physical_device.queue_family_properties().iter().enumerate() {
+            if !properties.queue_flags.contains(self.queue_flags) {
+                continue;
+            }
+
+            if let Some(surface) = &self.surface {
+                match physical_device.surface_support(index as u32, surface) {
+                    Ok(true) => (),
+                    Ok(false) => continue,
+                    Err(err) => {
+                        last_error = Some(err);
+                        continue;
+                    }
+                }
+            }
+
+            return Ok(index as u32);
+        }
+
+        Err(last_error)
+    }
+}
+
+/// Error returned by [`select_physical_device`].
+#[derive(Clone, Debug)]
+pub enum SelectionFailure {
+    /// Enumerating the physical devices of the instance failed.
+    Enumeration(VulkanError),
+
+    /// No physical device satisfies the criteria. Contains every enumerated device, with the
+    /// reasons why it was rejected.
+    NoSuitableDevice(Vec<RejectedDevice>),
+}
+
+impl std::error::Error for SelectionFailure {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Enumeration(err) => Some(err),
+            Self::NoSuitableDevice(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for SelectionFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::Enumeration(_) => write!(f, "enumerating the physical devices failed"),
+            Self::NoSuitableDevice(rejected) => {
+                if rejected.is_empty() {
+                    return write!(f, "no physical devices are available");
+                }
+
+                write!(
+                    f,
+                    "none of the available physical devices satisfy the selection criteria",
+                )?;
+
+                for rejected_device in rejected {
+                    write!(f, "\n{}", rejected_device)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A physical device that was rejected by [`select_physical_device`].
+#[derive(Clone, Debug)]
+pub struct RejectedDevice {
+    /// The physical device that was rejected.
+    pub physical_device: Arc<PhysicalDevice>,
+
+    /// The reasons why the physical device was rejected. This is never empty.
+    pub reasons: Vec<RejectionReason>,
+}
+
+impl std::fmt::Display for RejectedDevice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let properties = self.physical_device.properties();
+
+        write!(
+            f,
+            "- `{}` ({:?}, Vulkan {}, driver {}):",
+            properties.device_name,
+            properties.device_type,
+            self.physical_device.api_version(),
+            properties.driver_info.as_deref().unwrap_or("unknown"),
+        )?;
+
+        for reason in &self.reasons {
+            write!(f, "\n    - {}", reason)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A requirement of [`DeviceSelectionCriteria`] that a physical device failed.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum RejectionReason {
+    /// The API version of the physical device is too low.
+    ApiVersion {
+        required: Version,
+        supported: Version,
+    },
+
+    /// The physical device does not support these required extensions.
+    MissingExtensions(DeviceExtensions),
+
+    /// The physical device does not support these required features.
+    MissingFeatures(DeviceFeatures),
+
+    /// The physical device does not have enough device-local memory.
+    InsufficientMemory {
+        required: DeviceSize,
+        available: DeviceSize,
+    },
+
+    /// No queue family of the physical device satisfies the element of
+    /// [`queue_requirements`](DeviceSelectionCriteria::queue_requirements) at
+    /// `requirement_index`. If querying surface support returned an error, it is included.
+    NoQueueFamily {
+        requirement_index: usize,
+        error: Option<Validated<VulkanError>>,
+    },
+}
+
+impl std::fmt::Display for RejectionReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::ApiVersion {
+                required,
+                supported,
+            } => write!(
+                f,
+                "requires API version {}, but the device supports {}",
+                required, supported,
+            ),
+            Self::MissingExtensions(extensions) => {
+                write!(f, "does not support the extensions {:?}", extensions)
+            }
+            Self::MissingFeatures(features) => {
+                write!(f, "does not support the features {:?}", features)
+            }
+            Self::InsufficientMemory {
+                required,
+                available,
+            } => write!(
+                f,
+                "requires {} bytes of device-local memory, but the device has {}",
+                required, available,
+            ),
+            Self::NoQueueFamily {
+                requirement_index,
+                error,
+            } => {
+                write!(
+                    f,
+                    "has no queue family satisfying queue requirement {}",
+                    requirement_index,
+                )?;
+
+                if let Some(err) = error {
+                    write!(f, " (querying surface support failed: {})", err)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
 /// Properties of a group of physical devices that can be used to create a single logical device.
 #[derive(Clone, Debug)]
 #[non_exhaustive]
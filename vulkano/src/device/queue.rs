@@ -1,19 +1,30 @@
 use super::{Device, DeviceOwned, QueueCreateFlags};
 use crate::{
-    command_buffer::{CommandBufferSubmitInfo, SemaphoreSubmitInfo, SubmitInfo},
+    buffer::{Buffer, BufferMemory, BufferState, Subbuffer},
+    command_buffer::{
+        CommandBuffer, CommandBufferSubmitInfo, HangReport, SemaphoreSubmitInfo, SubmitInfo,
+    },
     instance::{debug::DebugUtilsLabel, InstanceOwnedDebugWrapper},
     macros::vulkan_bitflags,
     memory::{
-        BindSparseInfo, SparseBufferMemoryBind, SparseImageMemoryBind, SparseImageOpaqueMemoryBind,
+        is_aligned, BindSparseInfo, MemoryPropertyFlags, SparseBufferMemoryBind,
+        SparseImageMemoryBind, SparseImageOpaqueMemoryBind,
+    },
+    swapchain::{PresentInfo, SemaphorePresentInfo, Swapchain, SwapchainPresentInfo},
+    sync::{
+        fence::{Fence, FenceCreateInfo},
+        semaphore::SemaphoreType,
+        PipelineStages,
     },
-    swapchain::{PresentInfo, SemaphorePresentInfo, SwapchainPresentInfo},
-    sync::{fence::Fence, semaphore::SemaphoreType, PipelineStages},
     Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError, Version, VulkanError,
     VulkanObject,
 };
+#[cfg(doc)]
+use crate::{command_buffer::RecordingCommandBuffer, device::DeviceExtensions};
 use parking_lot::{Mutex, MutexGuard};
 use smallvec::SmallVec;
 use std::{
+    cmp,
     ffi::CString,
     hash::{Hash, Hasher},
     mem::MaybeUninit,
@@ -119,6 +130,154 @@ impl Queue {
         self.queue_index
     }
 
+    /// Returns the `min_image_transfer_granularity` property of the queue family that this queue
+    /// belongs to.
+    ///
+    /// Image copy commands recorded for this queue must have their offsets and extents aligned to
+    /// this granularity, unless they reach the edge of the image subresource. `[0; 3]` means that
+    /// only whole image subresources can be copied.
+    #[inline]
+    pub fn min_image_transfer_granularity(&self) -> [u32; 3] {
+        self.device.physical_device().queue_family_properties()[self.queue_family_index as usize]
+            .min_image_transfer_granularity
+    }
+
+    /// Returns, for each pipeline stage of the queue, the marker of the checkpoint that was most
+    /// recently executed in that stage.
+    ///
+    /// Checkpoints are inserted into command buffers with [`set_checkpoint`], or automatically
+    /// with [`set_automatic_checkpoints`]. The returned markers can be used to find out which
+    /// commands were executing when the device was lost.
+    ///
+    /// The [`nv_device_diagnostic_checkpoints`] extension must be enabled on the device.
+    ///
+    /// # Safety
+    ///
+    /// - The device must be lost, which is indicated by an operation returning
+    ///   [`VulkanError::DeviceLost`].
+    ///
+    /// [`set_checkpoint`]: RecordingCommandBuffer::set_checkpoint
+    /// [`set_automatic_checkpoints`]: RecordingCommandBuffer::set_automatic_checkpoints
+    /// [`nv_device_diagnostic_checkpoints`]: DeviceExtensions::nv_device_diagnostic_checkpoints
+    #[inline]
+    pub unsafe fn checkpoint_data(&self) -> Result<Vec<CheckpointData>, Box<ValidationError>> {
+        self.validate_checkpoint_data()?;
+
+        Ok(self.checkpoint_data_unchecked())
+    }
+
+    fn validate_checkpoint_data(&self) -> Result<(), Box<ValidationError>> {
+        if !self
+            .device
+            .enabled_extensions()
+            .nv_device_diagnostic_checkpoints
+        {
+            return Err(Box::new(ValidationError {
+                requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceExtension(
+                    "nv_device_diagnostic_checkpoints",
+                )])]),
+                ..Default::default()
+            }));
+        }
+
+        // VUID-vkGetQueueCheckpointDataNV-queue-02025
+        // Ensured by the caller.
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn checkpoint_data_unchecked(&self) -> Vec<CheckpointData> {
+        let fns = self.device.fns();
+        let mut count = 0;
+        (fns.nv_device_diagnostic_checkpoints
+            .get_queue_checkpoint_data_nv)(self.handle, &mut count, ptr::null_mut());
+
+        let mut checkpoint_data_vk = vec![ash::vk::CheckpointDataNV::default(); count as usize];
+        (fns.nv_device_diagnostic_checkpoints
+            .get_queue_checkpoint_data_nv)(
+            self.handle,
+            &mut count,
+            checkpoint_data_vk.as_mut_ptr(),
+        );
+        checkpoint_data_vk.truncate(count as usize);
+
+        checkpoint_data_vk
+            .into_iter()
+            .map(|checkpoint_data_vk| CheckpointData {
+                stage: ash::vk::PipelineStageFlags2::from_raw(
+                    checkpoint_data_vk.stage.as_raw().into(),
+                )
+                .into(),
+                marker: checkpoint_data_vk.p_checkpoint_marker as usize,
+            })
+            .collect()
+    }
+
+    /// Returns where the execution of `command_buffers` had got to when the device was lost.
+    ///
+    /// This looks up the markers returned by [`checkpoint_data`] in the automatic checkpoints of
+    /// `command_buffers`, which should be the command buffers that were submitted to the queue
+    /// and that may not have completed, in the order that they were submitted in.
+    ///
+    /// Either the [`nv_device_diagnostic_checkpoints`] or the [`amd_buffer_marker`] extension must
+    /// be enabled on the device. If only the latter is enabled, the report is instead read from
+    /// the marker buffers of `command_buffers` with [`HangReport::from_buffer_markers`].
+    ///
+    /// # Safety
+    ///
+    /// - The device must be lost, which is indicated by an operation returning
+    ///   [`VulkanError::DeviceLost`].
+    ///
+    /// [`checkpoint_data`]: Self::checkpoint_data
+    /// [`nv_device_diagnostic_checkpoints`]: DeviceExtensions::nv_device_diagnostic_checkpoints
+    /// [`amd_buffer_marker`]: DeviceExtensions::amd_buffer_marker
+    #[inline]
+    pub unsafe fn hang_report(
+        &self,
+        command_buffers: &[Arc<CommandBuffer>],
+    ) -> Result<HangReport, Box<ValidationError>> {
+        self.validate_hang_report()?;
+
+        Ok(self.hang_report_unchecked(command_buffers))
+    }
+
+    fn validate_hang_report(&self) -> Result<(), Box<ValidationError>> {
+        let enabled_extensions = self.device.enabled_extensions();
+
+        if !(enabled_extensions.nv_device_diagnostic_checkpoints
+            || enabled_extensions.amd_buffer_marker)
+        {
+            return Err(Box::new(ValidationError {
+                requires_one_of: RequiresOneOf(&[
+                    RequiresAllOf(&[Requires::DeviceExtension(
+                        "nv_device_diagnostic_checkpoints",
+                    )]),
+                    RequiresAllOf(&[Requires::DeviceExtension("amd_buffer_marker")]),
+                ]),
+                ..Default::default()
+            }));
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn hang_report_unchecked(
+        &self,
+        command_buffers: &[Arc<CommandBuffer>],
+    ) -> HangReport {
+        if self
+            .device
+            .enabled_extensions()
+            .nv_device_diagnostic_checkpoints
+        {
+            HangReport::new(&self.checkpoint_data_unchecked(), command_buffers)
+        } else {
+            HangReport::from_buffer_markers(command_buffers)
+        }
+    }
+
     /// Locks the queue and then calls the provided closure, providing it with an object that
     /// can be used to perform operations on the queue, such as command buffer submissions.
     #[inline]
@@ -128,6 +287,260 @@ impl Queue {
             _state: self.state.lock(),
         })
     }
+
+    /// Binds or unbinds memory to ranges of a buffer that is backed by sparse memory.
+    ///
+    /// The bind operations are executed on this queue, and this function waits for them to
+    /// finish before returning. The bindings are then recorded in the [`SparseBufferMemory`] of
+    /// the buffer, which keeps the bound memory alive.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `buffer` or the memory in `binds` were not created from the same device as
+    ///   `self`.
+    pub fn bind_sparse_buffer(
+        self: &Arc<Self>,
+        buffer: &Arc<Buffer>,
+        binds: &[SparseBufferMemoryBind],
+    ) -> Result<(), Validated<VulkanError>> {
+        self.with(|queue| {
+            // The state of the buffer stays locked until the binds have finished executing, so
+            // that the buffer can't start being used between validating the binds and executing
+            // them. The queue is locked first, in the same order as when submitting to it.
+            let state = buffer.state();
+            self.validate_bind_sparse_buffer(buffer, binds, &state)?;
+
+            unsafe { Ok(Self::bind_sparse_buffer_locked(queue, buffer, binds)?) }
+        })
+    }
+
+    fn validate_bind_sparse_buffer(
+        &self,
+        buffer: &Buffer,
+        binds: &[SparseBufferMemoryBind],
+        state: &BufferState,
+    ) -> Result<(), Box<ValidationError>> {
+        let physical_device = self.device.physical_device();
+
+        // VUID-vkQueueBindSparse-commonparent
+        assert_eq!(self.device(), buffer.device());
+
+        if !physical_device.queue_family_properties()[self.queue_family_index as usize]
+            .queue_flags
+            .intersects(QueueFlags::SPARSE_BINDING)
+        {
+            return Err(Box::new(ValidationError {
+                problem: "the queue family of the queue does not support sparse binding \
+                    operations"
+                    .into(),
+                vuids: &["VUID-vkQueueBindSparse-queuetype"],
+                ..Default::default()
+            }));
+        }
+
+        if !matches!(buffer.memory(), BufferMemory::Sparse(_)) {
+            return Err(Box::new(ValidationError {
+                context: "buffer.memory()".into(),
+                problem: "is not `BufferMemory::Sparse`".into(),
+                ..Default::default()
+            }));
+        }
+
+        let memory_requirements = buffer.memory_requirements();
+        let resource_size = memory_requirements.layout.size();
+        let alignment = memory_requirements.layout.alignment();
+
+        for (index, bind) in binds.iter().enumerate() {
+            let &SparseBufferMemoryBind {
+                offset,
+                size,
+                ref memory,
+            } = bind;
+
+            if size == 0 {
+                return Err(Box::new(ValidationError {
+                    context: format!("binds[{}].size", index).into(),
+                    problem: "is zero".into(),
+                    vuids: &["VUID-VkSparseMemoryBind-size-01098"],
+                    ..Default::default()
+                }));
+            }
+
+            if offset >= resource_size {
+                return Err(Box::new(ValidationError {
+                    context: format!("binds[{}].offset", index).into(),
+                    problem: "is not less than the size of the memory requirements of `buffer`"
+                        .into(),
+                    vuids: &["VUID-VkSparseMemoryBind-resourceOffset-01099"],
+                    ..Default::default()
+                }));
+            }
+
+            if size > resource_size - offset {
+                return Err(Box::new(ValidationError {
+                    problem: format!(
+                        "`binds[{0}].offset + binds[{0}].size` is greater than the size of the \
+                        memory requirements of `buffer`",
+                        index
+                    )
+                    .into(),
+                    vuids: &["VUID-VkSparseMemoryBind-size-01100"],
+                    ..Default::default()
+                }));
+            }
+
+            if !is_aligned(offset, alignment) || !is_aligned(size, alignment) {
+                return Err(Box::new(ValidationError {
+                    problem: format!(
+                        "`binds[{0}].offset` or `binds[{0}].size` is not a multiple of the \
+                        alignment of the memory requirements of `buffer`",
+                        index
+                    )
+                    .into(),
+                    vuids: &["VUID-VkSparseMemoryBind-resourceOffset-09491"],
+                    ..Default::default()
+                }));
+            }
+
+            if let Some((memory, memory_offset)) = memory {
+                let memory_offset = *memory_offset;
+
+                // VUID-VkSparseMemoryBind-commonparent
+                assert_eq!(self.device(), memory.device());
+
+                if memory_offset >= memory.allocation_size() {
+                    return Err(Box::new(ValidationError {
+                        context: format!("binds[{}].memory", index).into(),
+                        problem: "the memory offset is not less than the allocation size of the \
+                            memory"
+                            .into(),
+                        vuids: &["VUID-VkSparseMemoryBind-memoryOffset-01101"],
+                        ..Default::default()
+                    }));
+                }
+
+                if size > memory.allocation_size() - memory_offset {
+                    return Err(Box::new(ValidationError {
+                        problem: format!(
+                            "`binds[{0}].size` is greater than the allocation size of the memory \
+                            in `binds[{0}].memory` minus the memory offset",
+                            index
+                        )
+                        .into(),
+                        vuids: &["VUID-VkSparseMemoryBind-size-01102"],
+                        ..Default::default()
+                    }));
+                }
+
+                if !is_aligned(memory_offset, alignment) {
+                    return Err(Box::new(ValidationError {
+                        context: format!("binds[{}].memory", index).into(),
+                        problem: "the memory offset is not a multiple of the alignment of the \
+                            memory requirements of `buffer`"
+                            .into(),
+                        vuids: &["VUID-VkSparseMemoryBind-resourceOffset-09491"],
+                        ..Default::default()
+                    }));
+                }
+
+                if memory_requirements.memory_type_bits & (1 << memory.memory_type_index()) == 0 {
+                    return Err(Box::new(ValidationError {
+                        context: format!("binds[{}].memory", index).into(),
+                        problem: "the memory type index of the memory is not a bit set in the \
+                            `memory_type_bits` of the memory requirements of `buffer`"
+                            .into(),
+                        vuids: &["VUID-VkSparseMemoryBind-memory-01096"],
+                        ..Default::default()
+                    }));
+                }
+
+                if physical_device.memory_properties().memory_types
+                    [memory.memory_type_index() as usize]
+                    .property_flags
+                    .intersects(MemoryPropertyFlags::LAZILY_ALLOCATED)
+                {
+                    return Err(Box::new(ValidationError {
+                        context: format!("binds[{}].memory", index).into(),
+                        problem: "the memory was allocated from a memory type that has the \
+                            `MemoryPropertyFlags::LAZILY_ALLOCATED` flag"
+                            .into(),
+                        vuids: &["VUID-VkSparseMemoryBind-memory-01097"],
+                        ..Default::default()
+                    }));
+                }
+            }
+
+            let range = offset..offset + size;
+
+            for (other_index, other_bind) in binds[..index].iter().enumerate() {
+                let other_range = other_bind.offset..other_bind.offset + other_bind.size;
+
+                if range.start < other_range.end && other_range.start < range.end {
+                    return Err(Box::new(ValidationError {
+                        problem: format!(
+                            "the ranges of `binds[{}]` and `binds[{}]` overlap",
+                            other_index, index
+                        )
+                        .into(),
+                        ..Default::default()
+                    }));
+                }
+            }
+
+            // The range can extend beyond the end of the buffer, up to the size of the memory
+            // requirements, but that part is never accessed.
+            let buffer_range = range.start..cmp::min(range.end, buffer.size());
+
+            if !buffer_range.is_empty() && state.check_cpu_write(buffer_range).is_err() {
+                return Err(Box::new(ValidationError {
+                    problem: format!(
+                        "the range of `buffer` given by `binds[{}]` is currently in use by the \
+                        host or device",
+                        index
+                    )
+                    .into(),
+                    ..Default::default()
+                }));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn bind_sparse_buffer_unchecked(
+        self: &Arc<Self>,
+        buffer: &Arc<Buffer>,
+        binds: &[SparseBufferMemoryBind],
+    ) -> Result<(), VulkanError> {
+        self.with(|queue| unsafe { Self::bind_sparse_buffer_locked(queue, buffer, binds) })
+    }
+
+    /// Executes the binds on the locked queue, and waits for them to finish. The queue is
+    /// unlocked while waiting.
+    unsafe fn bind_sparse_buffer_locked(
+        mut queue: QueueGuard<'_>,
+        buffer: &Arc<Buffer>,
+        binds: &[SparseBufferMemoryBind],
+    ) -> Result<(), VulkanError> {
+        let fence = Arc::new(unsafe {
+            Fence::new_unchecked(buffer.device().clone(), FenceCreateInfo::default())
+        }?);
+        let bind_info = BindSparseInfo {
+            buffer_binds: vec![(Subbuffer::new(buffer.clone()), binds.to_vec())],
+            ..Default::default()
+        };
+
+        unsafe { queue.bind_sparse_unchecked(&[bind_info], Some(&fence)) }?;
+        drop(queue);
+        fence.wait(None)?;
+
+        if let BufferMemory::Sparse(memory) = buffer.memory() {
+            memory.apply(binds);
+        }
+
+        Ok(())
+    }
 }
 
 impl Drop for Queue {
@@ -490,8 +903,7 @@ impl<'a> QueueGuard<'a> {
     ///
     /// For every element of `present_info.swapchain_infos`:
     /// - `swapchain` must be kept alive while the command is being executed.
-    /// - `image_index` must be an index previously acquired from the swapchain, and the present
-    ///   operation must happen-after the acquire operation.
+    /// - The present operation must happen-after the acquire operation of `image_index`.
     /// - The swapchain image indicated by `swapchain` and `image_index` must be in the
     ///   [`ImageLayout::PresentSrc`] layout when the presentation operation is executed.
     /// - The swapchain image indicated by `swapchain` and `image_index` must not be accessed after
@@ -508,6 +920,31 @@ impl<'a> QueueGuard<'a> {
     {
         self.validate_present(present_info)?;
 
+        // Another thread may have presented one of the images since it was validated, so
+        // release them atomically, and undo it if one of them was released already.
+        for (index, swapchain_info) in present_info.swapchains.iter().enumerate() {
+            if !swapchain_info
+                .swapchain
+                .try_release_image(swapchain_info.image_index)
+            {
+                for swapchain_info in &present_info.swapchains[..index] {
+                    swapchain_info
+                        .swapchain
+                        .image_acquired(swapchain_info.image_index);
+                }
+
+                return Err(Box::new(ValidationError {
+                    context: format!("present_info.swapchain_infos[{}].image_index", index).into(),
+                    problem: "the swapchain image was presented by another thread while this \
+                        present was being validated"
+                        .into(),
+                    vuids: &["VUID-VkPresentInfoKHR-pImageIndices-01430"],
+                    ..Default::default()
+                })
+                .into());
+            }
+        }
+
         Ok(self.present_unchecked(present_info)?)
     }
 
@@ -536,13 +973,27 @@ impl<'a> QueueGuard<'a> {
         for (index, swapchain_info) in swapchains.iter().enumerate() {
             let &SwapchainPresentInfo {
                 ref swapchain,
-                image_index: _,
+                image_index,
                 present_id: _,
                 present_mode: _,
                 present_regions: _,
                 _ne: _,
             } = swapchain_info;
 
+            if !swapchain.is_image_acquired(image_index) {
+                return Err(Box::new(ValidationError {
+                    context: format!("present_info.swapchain_infos[{}].image_index", index).into(),
+                    problem: format!(
+                        "the swapchain image with index {} is not currently acquired; it was \
+                        either never acquired, or has already been presented",
+                        image_index
+                    )
+                    .into(),
+                    vuids: &["VUID-VkPresentInfoKHR-pImageIndices-01430"],
+                    ..Default::default()
+                }));
+            }
+
             if unsafe {
                 !device
                     .physical_device
@@ -615,6 +1066,9 @@ impl<'a> QueueGuard<'a> {
                 _ne: _,
             } = swapchain_info;
 
+            // The image is no longer acquired once it has been presented.
+            swapchain.try_release_image(image_index);
+
             swapchains_vk.push(swapchain.handle());
             image_indices_vk.push(image_index);
             present_ids_vk.push(present_id.map_or(0, u64::from));
@@ -694,6 +1148,16 @@ impl<'a> QueueGuard<'a> {
             info_vk.p_next = <*const _>::cast(next);
         }
 
+        if let Some(hooks) = self.queue.device.submission_hooks() {
+            for swapchain_info in swapchains {
+                hooks.on_present(
+                    self.queue,
+                    &swapchain_info.swapchain,
+                    swapchain_info.image_index,
+                );
+            }
+        }
+
         let fns = self.queue.device().fns();
         let result = (fns.khr_swapchain.queue_present_khr)(self.queue.handle, &info_vk);
 
@@ -894,7 +1358,17 @@ impl<'a> QueueGuard<'a> {
         submit_infos: &[SubmitInfo],
         fence: Option<&Arc<Fence>>,
     ) -> Result<(), VulkanError> {
-        if self.queue.device.enabled_features().synchronization2 {
+        let hooks = self.queue.device.submission_hooks();
+
+        if let Some(hooks) = &hooks {
+            let command_buffer_count = submit_infos
+                .iter()
+                .map(|submit_info| submit_info.command_buffers.len())
+                .sum();
+            hooks.on_submit(self.queue, command_buffer_count);
+        }
+
+        let result = if self.queue.device.enabled_features().synchronization2 {
             struct PerSubmitInfo {
                 wait_semaphore_infos_vk: SmallVec<[ash::vk::SemaphoreSubmitInfo<'static>; 4]>,
                 command_buffer_infos_vk: SmallVec<[ash::vk::CommandBufferSubmitInfo<'static>; 4]>,
@@ -1192,7 +1666,13 @@ impl<'a> QueueGuard<'a> {
             )
             .result()
             .map_err(VulkanError::from)
+        };
+
+        if let Some(hooks) = &hooks {
+            hooks.on_submit_end(self.queue);
         }
+
+        result
     }
 
     /// Opens a queue debug label region.
@@ -1364,6 +1844,94 @@ impl<'a> QueueGuard<'a> {
 #[derive(Debug, Default)]
 struct QueueState {}
 
+/// The checkpoint that was most recently executed in a pipeline stage of a queue, as returned by
+/// [`Queue::checkpoint_data`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CheckpointData {
+    /// The pipeline stage that executed the checkpoint.
+    pub stage: PipelineStages,
+
+    /// The marker of the checkpoint.
+    pub marker: usize,
+}
+
+/// Hooks that are called at well-defined points of the work that is done on the queues of a
+/// device, for example to emit zones or frame markers in an external profiler.
+///
+/// Hooks are set with [`Device::set_submission_hooks`]. They are called from the thread that
+/// performs the operation, while the queue is locked in the case of queue operations, so they
+/// should return quickly. Every method has an empty default implementation, so that only the
+/// points that are of interest need to be implemented.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::{
+///     atomic::{AtomicU64, Ordering},
+///     Arc,
+/// };
+/// use vulkano::{
+///     device::{Device, Queue, SubmissionHooks},
+///     swapchain::Swapchain,
+/// };
+///
+/// #[derive(Default)]
+/// struct FrameCounter {
+///     frames: AtomicU64,
+/// }
+///
+/// impl SubmissionHooks for FrameCounter {
+///     fn on_present(&self, _queue: &Queue, _swapchain: &Swapchain, _image_index: u32) {
+///         self.frames.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// # let device: Arc<Device> = return;
+/// device.set_submission_hooks(Some(Arc::new(FrameCounter::default())));
+/// ```
+///
+/// The `submission-hooks` example forwards the hooks to the Tracy profiler.
+pub trait SubmissionHooks: Send + Sync {
+    /// Called right before command buffers are submitted to `queue`.
+    ///
+    /// `command_buffer_count` is the total number of command buffers in the submission, across
+    /// all of its batches.
+    #[inline]
+    fn on_submit(&self, queue: &Queue, command_buffer_count: usize) {
+        let _ = (queue, command_buffer_count);
+    }
+
+    /// Called right after command buffers have been submitted to `queue`, whether the submission
+    /// succeeded or not.
+    #[inline]
+    fn on_submit_end(&self, queue: &Queue) {
+        let _ = queue;
+    }
+
+    /// Called right before `image_index` of `swapchain` is queued for presentation on `queue`.
+    ///
+    /// If several swapchains are presented at once, this is called once for each of them.
+    #[inline]
+    fn on_present(&self, queue: &Queue, swapchain: &Swapchain, image_index: u32) {
+        let _ = (queue, swapchain, image_index);
+    }
+
+    /// Called right before the current thread starts waiting for `fence` to be signaled.
+    ///
+    /// If several fences are waited on at once, this is called once for each of them.
+    #[inline]
+    fn on_fence_wait_start(&self, fence: &Fence) {
+        let _ = fence;
+    }
+
+    /// Called right after the current thread has stopped waiting for `fence`, whether it was
+    /// signaled or not.
+    #[inline]
+    fn on_fence_wait_end(&self, fence: &Fence) {
+        let _ = fence;
+    }
+}
+
 /// Properties of a queue family in a physical device.
 #[derive(Clone, Debug)]
 #[non_exhaustive]
@@ -1449,8 +2017,22 @@ vulkan_bitflags! {
 
 #[cfg(test)]
 mod tests {
-    use crate::sync::fence::Fence;
-    use std::{sync::Arc, time::Duration};
+    use super::{Queue, QueueFlags, SubmissionHooks};
+    use crate::{
+        buffer::{
+            BufferCreateFlags, BufferCreateInfo, BufferMemory, BufferUsage, RawBuffer, Subbuffer,
+        },
+        memory::{DeviceMemory, MemoryAllocateInfo, SparseBufferMemoryBind},
+        sync::{fence::Fence, HostAccessError},
+        Validated,
+    };
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
 
     #[test]
     fn empty_submit() {
@@ -1477,4 +2059,194 @@ mod tests {
             assert!(fence.is_signaled().unwrap());
         }
     }
+
+    #[test]
+    fn submission_hooks() {
+        #[derive(Default)]
+        struct Counter {
+            submits: AtomicUsize,
+            command_buffers: AtomicUsize,
+            submit_ends: AtomicUsize,
+            fence_wait_starts: AtomicUsize,
+            fence_wait_ends: AtomicUsize,
+        }
+
+        impl SubmissionHooks for Counter {
+            fn on_submit(&self, _queue: &Queue, command_buffer_count: usize) {
+                self.submits.fetch_add(1, Ordering::Relaxed);
+                self.command_buffers
+                    .fetch_add(command_buffer_count, Ordering::Relaxed);
+            }
+
+            fn on_submit_end(&self, _queue: &Queue) {
+                self.submit_ends.fetch_add(1, Ordering::Relaxed);
+            }
+
+            fn on_fence_wait_start(&self, _fence: &Fence) {
+                self.fence_wait_starts.fetch_add(1, Ordering::Relaxed);
+            }
+
+            fn on_fence_wait_end(&self, _fence: &Fence) {
+                self.fence_wait_ends.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        unsafe {
+            let (device, queue) = gfx_dev_and_queue!();
+            assert!(device.submission_hooks().is_none());
+
+            let counter = Arc::new(Counter::default());
+            device.set_submission_hooks(Some(counter.clone()));
+            assert!(device.submission_hooks().is_some());
+
+            let fence = Arc::new(Fence::new(device.clone(), Default::default()).unwrap());
+            queue
+                .with(|mut q| q.submit(&[Default::default(), Default::default()], Some(&fence)))
+                .unwrap();
+            fence.wait(Some(Duration::from_secs(5))).unwrap();
+
+            assert_eq!(counter.submits.load(Ordering::Relaxed), 1);
+            assert_eq!(counter.command_buffers.load(Ordering::Relaxed), 0);
+            assert_eq!(counter.submit_ends.load(Ordering::Relaxed), 1);
+            assert_eq!(counter.fence_wait_starts.load(Ordering::Relaxed), 1);
+            assert_eq!(counter.fence_wait_ends.load(Ordering::Relaxed), 1);
+
+            // Once the hooks are removed, they are no longer called.
+            device.set_submission_hooks(None);
+            assert!(device.submission_hooks().is_none());
+
+            queue
+                .with(|mut q| q.submit(&[Default::default()], None))
+                .unwrap();
+            assert_eq!(counter.submits.load(Ordering::Relaxed), 1);
+            assert_eq!(counter.submit_ends.load(Ordering::Relaxed), 1);
+        }
+    }
+
+    #[test]
+    fn bind_sparse_buffer() {
+        let (device, queue) = gfx_dev_and_queue!(sparse_binding);
+
+        if !device.physical_device().queue_family_properties()[queue.queue_family_index() as usize]
+            .queue_flags
+            .intersects(QueueFlags::SPARSE_BINDING)
+        {
+            return;
+        }
+
+        let raw_buffer = RawBuffer::new(
+            device.clone(),
+            BufferCreateInfo {
+                flags: BufferCreateFlags::SPARSE_BINDING,
+                size: 1 << 22,
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        // SAFETY: The buffer is never accessed by the device.
+        let buffer = Arc::new(unsafe { raw_buffer.into_sparse() }.unwrap());
+        let memory_requirements = *buffer.memory_requirements();
+        let page_size = memory_requirements.layout.alignment().as_devicesize();
+        let memory = Arc::new(
+            DeviceMemory::allocate(
+                device,
+                MemoryAllocateInfo {
+                    allocation_size: 2 * page_size,
+                    memory_type_index: memory_requirements.memory_type_bits.trailing_zeros(),
+                    ..Default::default()
+                },
+            )
+            .unwrap(),
+        );
+        let sparse_memory = match buffer.memory() {
+            BufferMemory::Sparse(sparse_memory) => sparse_memory,
+            _ => unreachable!(),
+        };
+
+        queue
+            .bind_sparse_buffer(
+                &buffer,
+                &[
+                    SparseBufferMemoryBind {
+                        offset: 0,
+                        size: page_size,
+                        memory: Some((memory.clone(), page_size)),
+                    },
+                    SparseBufferMemoryBind {
+                        offset: page_size,
+                        size: page_size,
+                        memory: Some((memory.clone(), 0)),
+                    },
+                ],
+            )
+            .unwrap();
+        assert!(sparse_memory.is_bound(0..2 * page_size));
+        assert!(!sparse_memory.is_bound(0..3 * page_size));
+        assert_eq!(sparse_memory.bindings().len(), 2);
+
+        // Host access to unbound ranges must not hand out garbage.
+        assert!(matches!(
+            Subbuffer::new(buffer.clone()).read(),
+            Err(HostAccessError::Unbound),
+        ));
+
+        // Reinterpreting checks the alignment of the memory offset, which sparse buffers have
+        // no single allocation for.
+        let subbuffer = Subbuffer::new(buffer.clone())
+            .slice(4..)
+            .reinterpret::<[u32]>();
+        assert_eq!(subbuffer.offset(), 4);
+
+        queue
+            .bind_sparse_buffer(
+                &buffer,
+                &[SparseBufferMemoryBind {
+                    offset: 0,
+                    size: page_size,
+                    memory: None,
+                }],
+            )
+            .unwrap();
+        assert!(!sparse_memory.is_bound(0..page_size));
+        assert!(sparse_memory.is_bound(page_size..2 * page_size));
+        assert_eq!(
+            sparse_memory.bindings(),
+            [(page_size..2 * page_size, memory.clone(), 0)],
+        );
+
+        match queue.bind_sparse_buffer(
+            &buffer,
+            &[SparseBufferMemoryBind {
+                offset: page_size / 2,
+                size: page_size,
+                memory: Some((memory.clone(), 0)),
+            }],
+        ) {
+            Err(Validated::ValidationError(err))
+                if err
+                    .vuids
+                    .contains(&"VUID-VkSparseMemoryBind-resourceOffset-09491") => {}
+            _ => panic!(),
+        }
+
+        match queue.bind_sparse_buffer(
+            &buffer,
+            &[
+                SparseBufferMemoryBind {
+                    offset: 0,
+                    size: 2 * page_size,
+                    memory: Some((memory.clone(), 0)),
+                },
+                SparseBufferMemoryBind {
+                    offset: page_size,
+                    size: page_size,
+                    memory: None,
+                },
+            ],
+        ) {
+            Err(Validated::ValidationError(_)) => {}
+            _ => panic!(),
+        }
+    }
 }
@@ -1,4 +1,8 @@
 use bytemuck::cast_slice;
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+};
 
 /// Properties of an extension in the loader or a physical device.
 #[derive(Clone, Debug)]
@@ -23,3 +27,20 @@ impl From<ash::vk::ExtensionProperties> for ExtensionProperties {
         }
     }
 }
+
+/// Error returned by the `from_names` method of
+/// [`InstanceExtensions`](crate::instance::InstanceExtensions) and
+/// [`DeviceExtensions`](crate::device::DeviceExtensions) when a name doesn't match any extension.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownExtension {
+    /// The name that was not recognized.
+    pub name: String,
+}
+
+impl Error for UnknownExtension {}
+
+impl Display for UnknownExtension {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(f, "`{}` is not the name of an extension", self.name)
+    }
+}
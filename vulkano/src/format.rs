@@ -85,7 +85,7 @@
 //! method on a format.
 
 use crate::{
-    device::Device,
+    device::{physical::PhysicalDevice, Device},
     image::{ImageAspects, ImageTiling},
     macros::vulkan_bitflags,
     shader::spirv::ImageFormat,
@@ -147,6 +147,122 @@ impl Format {
                 | Format::R8_UINT
         )
     }
+
+    /// Returns the GLSL layout qualifier, such as `rgba8` or `r32f`, that declares a storage image
+    /// or storage texel buffer of this format in a shader.
+    ///
+    /// Returns `None` if the format has no corresponding SPIR-V image format, and can therefore
+    /// only be accessed in a shader without specifying a format.
+    #[inline]
+    pub fn glsl_image_format_qualifier(self) -> Option<&'static str> {
+        match self {
+            Format::R32G32B32A32_SFLOAT => Some("rgba32f"),
+            Format::R16G16B16A16_SFLOAT => Some("rgba16f"),
+            Format::R32_SFLOAT => Some("r32f"),
+            Format::R8G8B8A8_UNORM => Some("rgba8"),
+            Format::R8G8B8A8_SNORM => Some("rgba8_snorm"),
+            Format::R32G32_SFLOAT => Some("rg32f"),
+            Format::R16G16_SFLOAT => Some("rg16f"),
+            Format::B10G11R11_UFLOAT_PACK32 => Some("r11f_g11f_b10f"),
+            Format::R16_SFLOAT => Some("r16f"),
+            Format::R16G16B16A16_UNORM => Some("rgba16"),
+            Format::A2B10G10R10_UNORM_PACK32 => Some("rgb10_a2"),
+            Format::R16G16_UNORM => Some("rg16"),
+            Format::R8G8_UNORM => Some("rg8"),
+            Format::R16_UNORM => Some("r16"),
+            Format::R8_UNORM => Some("r8"),
+            Format::R16G16B16A16_SNORM => Some("rgba16_snorm"),
+            Format::R16G16_SNORM => Some("rg16_snorm"),
+            Format::R8G8_SNORM => Some("rg8_snorm"),
+            Format::R16_SNORM => Some("r16_snorm"),
+            Format::R8_SNORM => Some("r8_snorm"),
+            Format::R32G32B32A32_SINT => Some("rgba32i"),
+            Format::R16G16B16A16_SINT => Some("rgba16i"),
+            Format::R8G8B8A8_SINT => Some("rgba8i"),
+            Format::R32_SINT => Some("r32i"),
+            Format::R32G32_SINT => Some("rg32i"),
+            Format::R16G16_SINT => Some("rg16i"),
+            Format::R8G8_SINT => Some("rg8i"),
+            Format::R16_SINT => Some("r16i"),
+            Format::R8_SINT => Some("r8i"),
+            Format::R32G32B32A32_UINT => Some("rgba32ui"),
+            Format::R16G16B16A16_UINT => Some("rgba16ui"),
+            Format::R8G8B8A8_UINT => Some("rgba8ui"),
+            Format::R32_UINT => Some("r32ui"),
+            Format::A2B10G10R10_UINT_PACK32 => Some("rgb10_a2ui"),
+            Format::R32G32_UINT => Some("rg32ui"),
+            Format::R16G16_UINT => Some("rg16ui"),
+            Format::R8G8_UINT => Some("rg8ui"),
+            Format::R16_UINT => Some("r16ui"),
+            Format::R8_UINT => Some("r8ui"),
+            Format::R64_UINT => Some("r64ui"),
+            Format::R64_SINT => Some("r64i"),
+            _ => None,
+        }
+    }
+
+    /// Returns a canonical list of formats that can be used interchangeably with this format for
+    /// the same color texture, ordered from most to least preferred.
+    ///
+    /// Not every device supports every family of compressed formats. Desktop devices usually
+    /// support the BC formats, while mobile devices usually support ASTC or ETC2 instead. An
+    /// application that ships its textures pre-encoded in several formats can pass this list to
+    /// [`best_supported_alternative`](Self::best_supported_alternative) to pick one of them. The
+    /// list currently ends with an uncompressed format that every device supports for sampling:
+    ///
+    /// - `BC7_UNORM_BLOCK`, `ASTC_4x4_UNORM_BLOCK`, `ETC2_R8G8B8A8_UNORM_BLOCK`, `R8G8B8A8_UNORM`
+    /// - `BC7_SRGB_BLOCK`, `ASTC_4x4_SRGB_BLOCK`, `ETC2_R8G8B8A8_SRGB_BLOCK`, `R8G8B8A8_SRGB`
+    ///
+    /// Returns an empty slice if the format is not in one of these lists.
+    pub fn universal_alternatives(self) -> &'static [Format] {
+        const UNORM: [Format; 4] = [
+            Format::BC7_UNORM_BLOCK,
+            Format::ASTC_4x4_UNORM_BLOCK,
+            Format::ETC2_R8G8B8A8_UNORM_BLOCK,
+            Format::R8G8B8A8_UNORM,
+        ];
+        const SRGB: [Format; 4] = [
+            Format::BC7_SRGB_BLOCK,
+            Format::ASTC_4x4_SRGB_BLOCK,
+            Format::ETC2_R8G8B8A8_SRGB_BLOCK,
+            Format::R8G8B8A8_SRGB,
+        ];
+
+        if UNORM.contains(&self) {
+            &UNORM
+        } else if SRGB.contains(&self) {
+            &SRGB
+        } else {
+            &[]
+        }
+    }
+
+    /// Returns the first format out of `self` and then `candidates` that supports all of
+    /// `features` with optimal tiling on `physical_device`.
+    ///
+    /// Formats that can't be used with `physical_device` at all, for example because they require
+    /// an extension that it doesn't support, are skipped. Returns `None` if none of the formats
+    /// are supported.
+    ///
+    /// See [`universal_alternatives`](Self::universal_alternatives) for a canonical list of
+    /// candidates.
+    pub fn best_supported_alternative(
+        self,
+        physical_device: &PhysicalDevice,
+        features: FormatFeatures,
+        candidates: &[Format],
+    ) -> Option<Format> {
+        [self]
+            .into_iter()
+            .chain(candidates.iter().copied())
+            .find(|&format| {
+                physical_device
+                    .format_properties(format)
+                    .map_or(false, |format_properties| {
+                        format_properties.optimal_tiling_features.contains(features)
+                    })
+            })
+    }
 }
 
 impl From<Format> for ash::vk::Format {
@@ -620,6 +736,19 @@ pub(crate) enum ClearValueType {
     DepthStencil,
 }
 
+impl ClearValueType {
+    /// A suffix for error messages about clear values of the wrong type, which points to a
+    /// constructor that picks the right type.
+    pub(crate) fn constructor_hint(&self) -> &'static str {
+        match self {
+            ClearValueType::Float | ClearValueType::Int | ClearValueType::Uint => {
+                " (`ClearColorValue::for_format` creates a clear value of the right type)"
+            }
+            ClearValueType::Depth | ClearValueType::Stencil | ClearValueType::DepthStencil => "",
+        }
+    }
+}
+
 /// A value that will be used to clear a color image.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ClearColorValue {
@@ -640,6 +769,88 @@ impl ClearColorValue {
             ClearColorValue::Uint(_) => NumericType::Uint,
         }
     }
+
+    /// Returns a `Float` clear value from a color that is encoded in the sRGB color space, such as
+    /// a color taken from an image editor.
+    ///
+    /// Clear values of floating-point formats are always linear, and are converted to sRGB by the
+    /// implementation when the format has the `SRGB` numeric format. This function converts the
+    /// R, G and B components to linear, so that clearing an `SRGB` image stores exactly `rgba`.
+    /// The A component is never encoded as sRGB, so it is only normalized.
+    pub fn from_srgb(rgba: [u8; 4]) -> Self {
+        let [r, g, b, a] = rgba.map(|component| f32::from(component) / 255.0);
+
+        Self::Float([srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a])
+    }
+
+    /// Returns a clear value of the type that `format` requires, from an RGBA color.
+    ///
+    /// For formats with a numeric type of `Float`, `rgba` is used as is. For integer formats,
+    /// each component of `rgba` must be an integer that fits in the corresponding component of
+    /// `format`. Components that `format` doesn't have are not checked.
+    ///
+    /// Returns an error if `format` is not a color format, or if a component of `rgba` can't be
+    /// represented by `format`.
+    pub fn for_format(format: Format, rgba: [f64; 4]) -> Result<Self, Box<ValidationError>> {
+        let numeric_format = format.numeric_format_color().ok_or_else(|| {
+            Box::new(ValidationError {
+                context: "format".into(),
+                problem: "is not a color format".into(),
+                ..Default::default()
+            })
+        })?;
+        let numeric_type = numeric_format.numeric_type();
+
+        if numeric_type == NumericType::Float {
+            return Ok(Self::Float(rgba.map(|component| component as f32)));
+        }
+
+        for (index, (component, bits)) in rgba.into_iter().zip(format.components()).enumerate() {
+            if bits == 0 {
+                continue;
+            }
+
+            // The clear value has 32 bits per component, so wider components can't be cleared to
+            // values that don't fit in 32 bits anyway.
+            let bits = i32::from(bits.min(32));
+            let range = match numeric_type {
+                NumericType::Int => -(2f64.powi(bits - 1))..=2f64.powi(bits - 1) - 1.0,
+                NumericType::Uint => 0.0..=2f64.powi(bits) - 1.0,
+                NumericType::Float => unreachable!(),
+            };
+
+            if component.fract() != 0.0 || !range.contains(&component) {
+                return Err(Box::new(ValidationError {
+                    context: format!("rgba[{}]", index).into(),
+                    problem: format!(
+                        "is not an integer between {} and {} inclusive, which is the range of \
+                        component {} of `Format::{:?}`",
+                        range.start(),
+                        range.end(),
+                        index,
+                        format,
+                    )
+                    .into(),
+                    ..Default::default()
+                }));
+            }
+        }
+
+        Ok(match numeric_type {
+            NumericType::Int => Self::Int(rgba.map(|component| component as i32)),
+            NumericType::Uint => Self::Uint(rgba.map(|component| component as u32)),
+            NumericType::Float => unreachable!(),
+        })
+    }
+}
+
+// https://registry.khronos.org/DataFormat/specs/1.3/dataformat.1.3.html#TRANSFER_SRGB
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
 }
 
 impl From<ClearColorValue> for ash::vk::ClearColorValue {
@@ -836,6 +1047,21 @@ impl FormatProperties {
         }
     }
 
+    /// Returns the image tiling that supports all of `features`, preferring optimal tiling over
+    /// linear tiling.
+    ///
+    /// Returns `None` if neither tiling supports all of `features`.
+    #[inline]
+    pub fn preferred_tiling(&self, features: FormatFeatures) -> Option<ImageTiling> {
+        if self.optimal_tiling_features.contains(features) {
+            Some(ImageTiling::Optimal)
+        } else if self.linear_tiling_features.contains(features) {
+            Some(ImageTiling::Linear)
+        } else {
+            None
+        }
+    }
+
     /// Returns the potential format features, following the definition of
     /// <https://registry.khronos.org/vulkan/specs/1.3-extensions/html/chap47.html#potential-format-features>.
     #[inline]
@@ -1181,3 +1407,155 @@ impl From<ash::vk::FormatFeatureFlags> for FormatFeatures {
         Self::from(ash::vk::FormatFeatureFlags2::from_raw(val.as_raw() as u64))
     }
 }
+
+/// Serializes the format as its name.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Format {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.name())
+    }
+}
+
+/// Deserializes the format from its name.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Format {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let name = <String as serde::Deserialize>::deserialize(deserializer)?;
+
+        Format::from_name(&name)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown `Format` value `{}`", name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClearColorValue, Format, FormatFeatures, NumericFormat};
+
+    #[test]
+    fn names() {
+        assert_eq!(Format::R8G8B8A8_UNORM.name(), "R8G8B8A8_UNORM");
+        assert_eq!(
+            Format::from_name("R8G8B8A8_UNORM"),
+            Some(Format::R8G8B8A8_UNORM),
+        );
+        assert_eq!(Format::from_name("R8G8B8A8"), None);
+
+        let features = FormatFeatures::BLIT_SRC | FormatFeatures::SAMPLED_IMAGE;
+        assert_eq!(
+            features.iter().collect::<Vec<_>>(),
+            [FormatFeatures::SAMPLED_IMAGE, FormatFeatures::BLIT_SRC],
+        );
+        assert_eq!(
+            FormatFeatures::from_name("BLIT_SRC"),
+            Some(FormatFeatures::BLIT_SRC),
+        );
+        assert_eq!(FormatFeatures::from_name("BLIT"), None);
+    }
+
+    #[test]
+    fn clear_color_value_from_srgb() {
+        let ClearColorValue::Float([r, g, b, a]) = ClearColorValue::from_srgb([0, 128, 255, 128])
+        else {
+            unreachable!()
+        };
+
+        assert_eq!(r, 0.0);
+        assert!((g - 0.2158).abs() < 1e-4);
+        assert_eq!(b, 1.0);
+        assert!((a - 128.0 / 255.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn clear_color_value_for_format() {
+        assert_eq!(
+            ClearColorValue::for_format(Format::R8G8B8A8_SRGB, [0.5, 0.25, 0.0, 1.0]).unwrap(),
+            ClearColorValue::Float([0.5, 0.25, 0.0, 1.0]),
+        );
+        assert_eq!(
+            ClearColorValue::for_format(Format::R8G8B8A8_UINT, [255.0, 0.0, 7.0, 1.0]).unwrap(),
+            ClearColorValue::Uint([255, 0, 7, 1]),
+        );
+        assert_eq!(
+            ClearColorValue::for_format(Format::R16_SINT, [-32768.0, 1e9, 0.0, 1.0]).unwrap(),
+            ClearColorValue::Int([-32768, 1_000_000_000, 0, 1]),
+        );
+
+        // Out of range of the component.
+        assert!(
+            ClearColorValue::for_format(Format::R8G8B8A8_UINT, [256.0, 0.0, 0.0, 1.0]).is_err()
+        );
+        assert!(ClearColorValue::for_format(Format::R8_SINT, [-129.0, 0.0, 0.0, 1.0]).is_err());
+        assert!(ClearColorValue::for_format(Format::R32_UINT, [-1.0, 0.0, 0.0, 1.0]).is_err());
+
+        // Not an integer.
+        assert!(ClearColorValue::for_format(Format::R8G8B8A8_UINT, [0.5, 0.0, 0.0, 1.0]).is_err());
+
+        // Not a color format.
+        assert!(ClearColorValue::for_format(Format::D32_SFLOAT, [1.0; 4]).is_err());
+    }
+    #[test]
+    fn universal_alternatives() {
+        let unorm = Format::BC7_UNORM_BLOCK.universal_alternatives();
+        assert_eq!(unorm.last(), Some(&Format::R8G8B8A8_UNORM));
+        assert_eq!(Format::R8G8B8A8_UNORM.universal_alternatives(), unorm);
+        assert!(unorm
+            .iter()
+            .all(|format| format.numeric_format_color() == Some(NumericFormat::UNORM)));
+
+        let srgb = Format::ETC2_R8G8B8A8_SRGB_BLOCK.universal_alternatives();
+        assert_eq!(srgb.last(), Some(&Format::R8G8B8A8_SRGB));
+        assert_eq!(Format::ASTC_4x4_SRGB_BLOCK.universal_alternatives(), srgb);
+        assert!(srgb
+            .iter()
+            .all(|format| format.numeric_format_color() == Some(NumericFormat::SRGB)));
+
+        assert!(Format::R8G8B8A8_UINT.universal_alternatives().is_empty());
+        assert!(Format::D32_SFLOAT.universal_alternatives().is_empty());
+    }
+
+    #[test]
+    fn best_supported_alternative() {
+        let instance = instance!();
+        let physical_device = match instance.enumerate_physical_devices().unwrap().next() {
+            Some(p) => p,
+            None => return,
+        };
+
+        let format = Format::BC7_UNORM_BLOCK;
+        let features = FormatFeatures::SAMPLED_IMAGE;
+        let best = format
+            .best_supported_alternative(&physical_device, features, format.universal_alternatives())
+            .unwrap();
+        assert!(physical_device
+            .format_properties(best)
+            .unwrap()
+            .optimal_tiling_features
+            .contains(features));
+
+        // `self` comes first if it is supported.
+        assert_eq!(
+            Format::R8G8B8A8_UNORM.best_supported_alternative(
+                &physical_device,
+                features,
+                &[Format::BC7_UNORM_BLOCK],
+            ),
+            Some(Format::R8G8B8A8_UNORM),
+        );
+
+        // A color format never supports depth/stencil attachments, and there are no candidates.
+        assert_eq!(
+            Format::R8G8B8A8_UINT.best_supported_alternative(
+                &physical_device,
+                FormatFeatures::DEPTH_STENCIL_ATTACHMENT,
+                &[],
+            ),
+            None,
+        );
+    }
+}
@@ -288,3 +288,33 @@ impl ImageLayout {
         }
     }
 }
+
+/// Which layout an image is used in when it is accessed through a descriptor, if the descriptor
+/// write doesn't specify a layout. It is set when the image is created, with
+/// [`ImageCreateInfo::layout_policy`](super::ImageCreateInfo::layout_policy).
+///
+/// An image that is used both as a storage image and as a sampled image in the same command
+/// buffer is transitioned between [`ImageLayout::General`] and
+/// [`ImageLayout::ShaderReadOnlyOptimal`] every time it switches between the two uses. Keeping it
+/// in `General` avoids these transitions, at the cost of possibly slower sampled reads on some
+/// implementations.
+///
+/// The layouts of input attachments always match those of the render pass, and layouts that are
+/// given explicitly in a descriptor write are always used as they are.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ImageLayoutPolicy {
+    /// Use the optimal layout for each descriptor type: [`ImageLayout::ShaderReadOnlyOptimal`]
+    /// for sampled images, and [`ImageLayout::General`] for storage images.
+    Optimal,
+
+    /// Use [`ImageLayout::General`] for both sampled images and storage images.
+    General,
+}
+
+impl Default for ImageLayoutPolicy {
+    #[inline]
+    fn default() -> Self {
+        ImageLayoutPolicy::Optimal
+    }
+}
@@ -54,34 +54,37 @@
 
 pub use self::{aspect::*, layout::*, sys::ImageCreateInfo, usage::*};
 use self::{sys::RawImage, view::ImageViewType};
+#[cfg(doc)]
+use crate::device::{DeviceExtensions, DeviceFeatures};
 use crate::{
     device::{physical::PhysicalDevice, Device, DeviceOwned},
     format::{Format, FormatFeatures},
     macros::{vulkan_bitflags, vulkan_bitflags_enum, vulkan_enum},
     memory::{
-        allocator::{AllocationCreateInfo, MemoryAllocator, MemoryAllocatorError},
+        allocator::{
+            align_down, align_up, AllocationCreateInfo, MemoryAllocator, MemoryAllocatorError,
+        },
         DedicatedAllocation, ExternalMemoryHandleType, ExternalMemoryHandleTypes,
-        ExternalMemoryProperties, MemoryRequirements, ResourceMemory,
+        ExternalMemoryProperties, MappedMemoryRange, MemoryRequirements, ResourceMemory,
     },
     range_map::RangeMap,
     swapchain::Swapchain,
-    sync::{future::AccessError, AccessConflict, CurrentAccess, Sharing},
+    sync::{future::AccessError, AccessConflict, CurrentAccess, HostAccessError, Sharing},
     DeviceSize, Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError, Version,
     VulkanError, VulkanObject,
 };
 use parking_lot::{Mutex, MutexGuard};
 use smallvec::SmallVec;
 use std::{
-    cmp::max,
+    cmp::{self, max},
     error::Error,
     fmt::{Display, Formatter},
     hash::{Hash, Hasher},
     iter::{FusedIterator, Peekable},
-    ops::Range,
-    sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc,
-    },
+    ops::{Bound, Range, RangeBounds},
+    ptr::NonNull,
+    sync::Arc,
+    thread,
 };
 
 mod aspect;
@@ -110,7 +113,7 @@ pub struct Image {
 
     state: Mutex<ImageState>,
     layout: ImageLayout,
-    is_layout_initialized: AtomicBool,
+    layout_initialized: Mutex<RangeMap<DeviceSize, bool>>,
 }
 
 /// The type of backing memory that an image can have.
@@ -194,7 +197,7 @@ impl Image {
             range_size,
 
             state,
-            is_layout_initialized: AtomicBool::new(false),
+            layout_initialized: Mutex::new([(0..range_size, false)].into_iter().collect()),
             layout,
         }
     }
@@ -219,9 +222,11 @@ impl Image {
             stencil_usage: None,
             sharing: swapchain.image_sharing().clone(),
             initial_layout: ImageLayout::Undefined,
+            layout_policy: ImageLayoutPolicy::Optimal,
             drm_format_modifiers: Vec::new(),
             drm_format_modifier_plane_layouts: Vec::new(),
             external_memory_handle_types: ExternalMemoryHandleTypes::empty(),
+            compression_control: swapchain.compression_control().cloned(),
             _ne: crate::NonExhaustive(()),
         };
 
@@ -311,6 +316,13 @@ impl Image {
         self.inner.initial_layout()
     }
 
+    /// Returns the layout policy that the image was created with, which determines the layout
+    /// that descriptors use for the image by default.
+    #[inline]
+    pub fn layout_policy(&self) -> ImageLayoutPolicy {
+        self.inner.layout_policy()
+    }
+
     /// Returns the number of samples for the image.
     #[inline]
     pub fn samples(&self) -> SampleCount {
@@ -356,6 +368,12 @@ impl Image {
         self.inner.external_memory_handle_types()
     }
 
+    /// Returns the compression control that the image was created with.
+    #[inline]
+    pub fn compression_control(&self) -> Option<&ImageCompressionControl> {
+        self.inner.compression_control()
+    }
+
     /// Returns an `ImageSubresourceLayers` covering the first mip level of the image. All aspects
     /// of the image are selected, or `plane0` if the image is multi-planar.
     #[inline]
@@ -405,6 +423,235 @@ impl Image {
             .subresource_layout_unchecked(aspect, mip_level, array_layer)
     }
 
+    /// Queries the compression that was applied to a single subresource of the image.
+    ///
+    /// This can be used to detect when the implementation silently disabled compression for the
+    /// image, which can have a significant performance cost.
+    ///
+    /// The [`ext_image_compression_control`] extension must be enabled on the device.
+    ///
+    /// [`ext_image_compression_control`]: DeviceExtensions::ext_image_compression_control
+    #[inline]
+    pub fn compression_properties(
+        &self,
+        aspect: ImageAspect,
+        mip_level: u32,
+        array_layer: u32,
+    ) -> Result<ImageCompressionProperties, Box<ValidationError>> {
+        self.inner
+            .compression_properties(aspect, mip_level, array_layer)
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn compression_properties_unchecked(
+        &self,
+        aspect: ImageAspect,
+        mip_level: u32,
+        array_layer: u32,
+    ) -> ImageCompressionProperties {
+        self.inner
+            .compression_properties_unchecked(aspect, mip_level, array_layer)
+    }
+
+    /// Locks a subresource of the image in order to read its content from the host.
+    ///
+    /// The image must have been created with [`ImageTiling::Linear`], and must be backed by
+    /// host-visible memory that vulkano manages. Host access is only well-defined while the
+    /// subresource is in the [`ImageLayout::General`] or [`ImageLayout::Preinitialized`] layout.
+    /// An image that is to be written from the host before the device has used it should
+    /// therefore be created with an `initial_layout` of `ImageLayout::Preinitialized`.
+    ///
+    /// If the subresource is currently written by the device, or if you called [`write`] on it
+    /// and haven't dropped the lock, this function will return an error. While the returned guard
+    /// is alive, any attempt to submit a command buffer that writes to the subresource will fail.
+    ///
+    /// If the memory backing the image is not [host-coherent], then this function will lock the
+    /// whole image, because the range given to [`invalidate_range`] must be aligned to the
+    /// [`non_coherent_atom_size`], and can therefore overlap other subresources.
+    ///
+    /// [`write`]: Self::write
+    /// [host-coherent]: crate::memory::MemoryPropertyFlags::HOST_COHERENT
+    /// [`invalidate_range`]: ResourceMemory::invalidate_range
+    /// [`non_coherent_atom_size`]: crate::device::DeviceProperties::non_coherent_atom_size
+    pub fn read(
+        &self,
+        aspect: ImageAspect,
+        mip_level: u32,
+        array_layer: u32,
+    ) -> Result<ImageReadGuard<'_>, Validated<HostAccessError>> {
+        let access = self.host_access(aspect, mip_level, array_layer)?;
+
+        let mut state = self.state();
+        state.check_host_layout(access.subresource.clone())?;
+        unsafe { state.cpu_read_lock(access.range.clone()) }
+            .map_err(|err| Validated::Error(HostAccessError::AccessConflict(err)))?;
+
+        // SAFETY: The subresource is locked, so the device can't write to it while the host cache
+        // is invalidated.
+        if let Err(err) = unsafe { access.invalidate() } {
+            unsafe { state.cpu_read_unlock(access.range) };
+            return Err(Validated::Error(HostAccessError::Invalidate(err)));
+        }
+
+        Ok(ImageReadGuard {
+            image: self,
+            // SAFETY: The subresource is locked for reading, so nothing can write to the memory
+            // while the guard is alive.
+            data: unsafe { access.data.as_ref() },
+            rows: access.rows,
+            range: access.range,
+        })
+    }
+
+    /// Locks a subresource of the image in order to write its content from the host.
+    ///
+    /// The same requirements as for [`read`] apply. If the subresource is currently in use by
+    /// the device, or if you called `read` on it and haven't dropped the lock, this function will
+    /// return an error. While the returned guard is alive, any attempt to submit a command buffer
+    /// that uses the subresource, and any attempt to call `read`, will fail.
+    ///
+    /// If the memory backing the image is not [host-coherent], then this function will lock the
+    /// whole image, because the range given to [`flush_range`] must be aligned to the
+    /// [`non_coherent_atom_size`], and can therefore overlap other subresources. The flush happens
+    /// when the guard is dropped.
+    ///
+    /// [`read`]: Self::read
+    /// [host-coherent]: crate::memory::MemoryPropertyFlags::HOST_COHERENT
+    /// [`flush_range`]: ResourceMemory::flush_range
+    /// [`non_coherent_atom_size`]: crate::device::DeviceProperties::non_coherent_atom_size
+    pub fn write(
+        &self,
+        aspect: ImageAspect,
+        mip_level: u32,
+        array_layer: u32,
+    ) -> Result<ImageWriteGuard<'_>, Validated<HostAccessError>> {
+        let mut access = self.host_access(aspect, mip_level, array_layer)?;
+
+        let mut state = self.state();
+        state.check_host_layout(access.subresource.clone())?;
+        unsafe { state.cpu_write_lock(access.range.clone()) }
+            .map_err(|err| Validated::Error(HostAccessError::AccessConflict(err)))?;
+
+        // SAFETY: The subresource is locked, so the device can't access it while the host cache
+        // is invalidated.
+        if let Err(err) = unsafe { access.invalidate() } {
+            unsafe { state.cpu_write_unlock(access.range) };
+            return Err(Validated::Error(HostAccessError::Invalidate(err)));
+        }
+
+        Ok(ImageWriteGuard {
+            image: self,
+            allocation: access.allocation,
+            // SAFETY: The subresource is locked for writing, so nothing else can access the
+            // memory while the guard is alive.
+            data: unsafe { access.data.as_mut() },
+            rows: access.rows,
+            range: access.range,
+            memory_range: access.memory_range,
+        })
+    }
+
+    fn host_access(
+        &self,
+        aspect: ImageAspect,
+        mip_level: u32,
+        array_layer: u32,
+    ) -> Result<HostAccess<'_>, Validated<HostAccessError>> {
+        let allocations = match &self.memory {
+            ImageMemory::Normal(allocations) => allocations,
+            ImageMemory::Sparse(_) | ImageMemory::Swapchain { .. } | ImageMemory::External => {
+                return Err(Validated::Error(HostAccessError::Unmanaged));
+            }
+        };
+
+        if self.tiling() != ImageTiling::Linear {
+            return Err(Box::new(ValidationError {
+                context: "self.tiling()".into(),
+                problem: "is not `ImageTiling::Linear`".into(),
+                ..Default::default()
+            })
+            .into());
+        }
+
+        let layout = self
+            .subresource_layout(aspect, mip_level, array_layer)
+            .map_err(|err| err.add_context("subresource_layout"))?;
+
+        let format = self.format();
+        let mut extent = mip_level_extent(self.extent(), mip_level).unwrap();
+        let (allocation, format) = match aspect {
+            ImageAspect::Plane0 | ImageAspect::Plane1 | ImageAspect::Plane2 => {
+                let plane = match aspect {
+                    ImageAspect::Plane0 => 0,
+                    ImageAspect::Plane1 => 1,
+                    _ => 2,
+                };
+
+                if plane != 0 {
+                    if let Some(chroma_sampling) = format.ycbcr_chroma_sampling() {
+                        extent = chroma_sampling.subsampled_extent(extent);
+                    }
+                }
+
+                let allocation = if self.flags().intersects(ImageCreateFlags::DISJOINT) {
+                    &allocations[plane]
+                } else {
+                    &allocations[0]
+                };
+
+                (allocation, format.planes()[plane])
+            }
+            _ => (&allocations[0], format),
+        };
+
+        let block_extent = format.block_extent();
+        let [width, height, depth] =
+            [0, 1, 2].map(|i| (extent[i] + block_extent[i] - 1) / block_extent[i]);
+        let rows = HostRows {
+            row_size: (width as DeviceSize * format.block_size()) as usize,
+            row_pitch: layout.row_pitch as usize,
+            height,
+            depth,
+            depth_pitch: layout.depth_pitch.unwrap_or(0) as usize,
+        };
+
+        let subresource_range = ImageSubresourceRange {
+            aspects: aspect.into(),
+            mip_levels: mip_level..mip_level + 1,
+            array_layers: array_layer..array_layer + 1,
+        };
+
+        let subresource = self.iter_ranges(subresource_range).next().unwrap();
+        let (range, memory_range) = if let Some(atom_size) = allocation.atom_size() {
+            // This works because the memory allocator must align allocations to the non-coherent
+            // atom size when the memory is host-visible but not host-coherent.
+            let start = align_down(layout.offset, atom_size);
+            let end = cmp::min(
+                align_up(layout.offset + layout.size, atom_size),
+                allocation.size(),
+            );
+
+            (0..self.range_size, Some(start..end))
+        } else {
+            (subresource.clone(), None)
+        };
+
+        let data = allocation
+            .mapped_slice(layout.offset..layout.offset + layout.size)
+            .unwrap_or(Err(HostAccessError::OutOfMappedRange))
+            .map_err(Validated::Error)?;
+
+        Ok(HostAccess {
+            allocation,
+            data,
+            rows,
+            subresource,
+            range,
+            memory_range,
+        })
+    }
+
     pub(crate) fn range_size(&self) -> DeviceSize {
         self.range_size
     }
@@ -510,10 +757,12 @@ impl Image {
         self.layout
     }
 
-    pub(crate) unsafe fn layout_initialized(&self) {
+    /// Marks the layout of the subresources in `range` as initialized, meaning that they have
+    /// been, or are going to be, transitioned to the layout that the image requires.
+    pub(crate) unsafe fn layout_initialized(&self, range: Range<DeviceSize>) {
         match &self.memory {
             ImageMemory::Normal(..) | ImageMemory::Sparse(..) | ImageMemory::External => {
-                self.is_layout_initialized.store(true, Ordering::Release);
+                self.layout_initialized.lock().insert(range, true);
             }
             ImageMemory::Swapchain {
                 swapchain,
@@ -524,15 +773,22 @@ impl Image {
         }
     }
 
-    pub(crate) fn is_layout_initialized(&self) -> bool {
+    /// Returns the ranges of subresources, and whether their layout has been initialized.
+    pub(crate) fn layout_initialized_ranges(&self) -> Vec<(Range<DeviceSize>, bool)> {
         match &self.memory {
-            ImageMemory::Normal(..) | ImageMemory::Sparse(..) | ImageMemory::External => {
-                self.is_layout_initialized.load(Ordering::Acquire)
-            }
+            ImageMemory::Normal(..) | ImageMemory::Sparse(..) | ImageMemory::External => self
+                .layout_initialized
+                .lock()
+                .iter()
+                .map(|(range, &is_initialized)| (range, is_initialized))
+                .collect(),
             ImageMemory::Swapchain {
                 swapchain,
                 image_index,
-            } => swapchain.is_image_layout_initialized(*image_index),
+            } => vec![(
+                0..self.range_size,
+                swapchain.is_image_layout_initialized(*image_index),
+            )],
         }
     }
 }
@@ -568,6 +824,198 @@ impl Hash for Image {
     }
 }
 
+/// The information needed to access a subresource of an image from the host.
+struct HostAccess<'a> {
+    allocation: &'a ResourceMemory,
+    data: NonNull<[u8]>,
+    rows: HostRows,
+    subresource: Range<DeviceSize>,
+    range: Range<DeviceSize>,
+    memory_range: Option<Range<DeviceSize>>,
+}
+
+impl HostAccess<'_> {
+    unsafe fn invalidate(&self) -> Result<(), VulkanError> {
+        if let Some(memory_range) = self.memory_range.clone() {
+            self.allocation
+                .invalidate_range_unchecked(MappedMemoryRange {
+                    offset: memory_range.start,
+                    size: memory_range.end - memory_range.start,
+                    _ne: crate::NonExhaustive(()),
+                })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The layout of the rows of texel blocks in a subresource.
+#[derive(Clone, Copy, Debug)]
+struct HostRows {
+    row_size: usize,
+    row_pitch: usize,
+    height: u32,
+    depth: u32,
+    depth_pitch: usize,
+}
+
+impl HostRows {
+    fn count(&self) -> u32 {
+        self.height * self.depth
+    }
+
+    fn range(&self, y: u32) -> Range<usize> {
+        assert!(y < self.count(), "row {} is out of bounds", y);
+
+        let start = (y / self.height) as usize * self.depth_pitch
+            + (y % self.height) as usize * self.row_pitch;
+
+        start..start + self.row_size
+    }
+}
+
+/// RAII structure used to release the CPU access of an image subresource when dropped.
+///
+/// This structure is created by the [`read`] method on [`Image`].
+///
+/// The data of the subresource is accessed as rows of texel blocks. For 3D images, the rows of
+/// each depth slice follow those of the previous slice, which is the same order as in a buffer
+/// that an image is copied to.
+///
+/// [`read`]: Image::read
+#[derive(Debug)]
+pub struct ImageReadGuard<'a> {
+    image: &'a Image,
+    data: &'a [u8],
+    rows: HostRows,
+    range: Range<DeviceSize>,
+}
+
+impl ImageReadGuard<'_> {
+    /// Returns the number of rows in the subresource.
+    #[inline]
+    pub fn row_count(&self) -> u32 {
+        self.rows.count()
+    }
+
+    /// Returns the data of row `y`, without the padding at the end of the row.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `y` is not less than [`row_count`](Self::row_count).
+    #[inline]
+    pub fn row(&self, y: u32) -> &[u8] {
+        &self.data[self.rows.range(y)]
+    }
+
+    /// Appends the data of the subresource to `dst`, with all rows tightly packed.
+    pub fn copy_to_tightly_packed(&self, dst: &mut Vec<u8>) {
+        copy_to_tightly_packed(self.data, self.rows, dst);
+    }
+}
+
+impl Drop for ImageReadGuard<'_> {
+    fn drop(&mut self) {
+        let mut state = self.image.state();
+        unsafe { state.cpu_read_unlock(self.range.clone()) };
+    }
+}
+
+/// RAII structure used to release the CPU write access of an image subresource when dropped.
+///
+/// This structure is created by the [`write`] method on [`Image`].
+///
+/// The data of the subresource is accessed as rows of texel blocks, in the same order as for
+/// [`ImageReadGuard`].
+///
+/// [`write`]: Image::write
+#[derive(Debug)]
+pub struct ImageWriteGuard<'a> {
+    image: &'a Image,
+    allocation: &'a ResourceMemory,
+    data: &'a mut [u8],
+    rows: HostRows,
+    range: Range<DeviceSize>,
+    memory_range: Option<Range<DeviceSize>>,
+}
+
+impl ImageWriteGuard<'_> {
+    /// Returns the number of rows in the subresource.
+    #[inline]
+    pub fn row_count(&self) -> u32 {
+        self.rows.count()
+    }
+
+    /// Returns the data of row `y`, without the padding at the end of the row.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `y` is not less than [`row_count`](Self::row_count).
+    #[inline]
+    pub fn row(&self, y: u32) -> &[u8] {
+        &self.data[self.rows.range(y)]
+    }
+
+    /// Returns the data of row `y` mutably, without the padding at the end of the row.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `y` is not less than [`row_count`](Self::row_count).
+    #[inline]
+    pub fn row_mut(&mut self, y: u32) -> &mut [u8] {
+        &mut self.data[self.rows.range(y)]
+    }
+
+    /// Appends the data of the subresource to `dst`, with all rows tightly packed.
+    pub fn copy_to_tightly_packed(&self, dst: &mut Vec<u8>) {
+        copy_to_tightly_packed(self.data, self.rows, dst);
+    }
+
+    /// Overwrites the data of the subresource with `src`, in which all rows are tightly packed.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the length of `src` is not the size of the subresource when tightly packed.
+    pub fn copy_from_tightly_packed(&mut self, src: &[u8]) {
+        assert_eq!(
+            src.len(),
+            self.rows.row_size * self.rows.count() as usize,
+            "`src` doesn't have the size of the tightly packed subresource",
+        );
+
+        for (y, src) in (0..self.rows.count()).zip(src.chunks_exact(self.rows.row_size)) {
+            self.row_mut(y).copy_from_slice(src);
+        }
+    }
+}
+
+impl Drop for ImageWriteGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(memory_range) = self.memory_range.clone() {
+            if !thread::panicking() {
+                let memory_range = MappedMemoryRange {
+                    offset: memory_range.start,
+                    size: memory_range.end - memory_range.start,
+                    _ne: crate::NonExhaustive(()),
+                };
+
+                unsafe { self.allocation.flush_range_unchecked(memory_range).unwrap() };
+            }
+        }
+
+        let mut state = self.image.state();
+        unsafe { state.cpu_write_unlock(self.range.clone()) };
+    }
+}
+
+fn copy_to_tightly_packed(data: &[u8], rows: HostRows, dst: &mut Vec<u8>) {
+    dst.reserve(rows.row_size * rows.count() as usize);
+
+    for y in 0..rows.count() {
+        dst.extend_from_slice(&data[rows.range(y)]);
+    }
+}
+
 /// Error that can happen when allocating a new image.
 #[derive(Clone, Debug)]
 pub enum AllocateImageError {
@@ -626,7 +1074,6 @@ impl ImageState {
         }
     }
 
-    #[allow(dead_code)]
     pub(crate) fn check_cpu_read(&self, range: Range<DeviceSize>) -> Result<(), AccessConflict> {
         for (_range, state) in self.ranges.range(&range) {
             match &state.current_access {
@@ -639,8 +1086,12 @@ impl ImageState {
         Ok(())
     }
 
-    #[allow(dead_code)]
-    pub(crate) unsafe fn cpu_read_lock(&mut self, range: Range<DeviceSize>) {
+    pub(crate) unsafe fn cpu_read_lock(
+        &mut self,
+        range: Range<DeviceSize>,
+    ) -> Result<(), AccessConflict> {
+        self.check_cpu_read(range.clone())?;
+
         self.ranges.split_at(&range.start);
         self.ranges.split_at(&range.end);
 
@@ -649,12 +1100,13 @@ impl ImageState {
                 CurrentAccess::Shared { cpu_reads, .. } => {
                     *cpu_reads += 1;
                 }
-                _ => unreachable!("Image is being written by the CPU or GPU"),
+                _ => unreachable!("checked by `check_cpu_read`"),
             }
         }
+
+        Ok(())
     }
 
-    #[allow(dead_code)]
     pub(crate) unsafe fn cpu_read_unlock(&mut self, range: Range<DeviceSize>) {
         self.ranges.split_at(&range.start);
         self.ranges.split_at(&range.end);
@@ -667,7 +1119,6 @@ impl ImageState {
         }
     }
 
-    #[allow(dead_code)]
     pub(crate) fn check_cpu_write(&self, range: Range<DeviceSize>) -> Result<(), AccessConflict> {
         for (_range, state) in self.ranges.range(&range) {
             match &state.current_access {
@@ -687,17 +1138,22 @@ impl ImageState {
         Ok(())
     }
 
-    #[allow(dead_code)]
-    pub(crate) unsafe fn cpu_write_lock(&mut self, range: Range<DeviceSize>) {
+    pub(crate) unsafe fn cpu_write_lock(
+        &mut self,
+        range: Range<DeviceSize>,
+    ) -> Result<(), AccessConflict> {
+        self.check_cpu_write(range.clone())?;
+
         self.ranges.split_at(&range.start);
         self.ranges.split_at(&range.end);
 
         for (_range, state) in self.ranges.range_mut(&range) {
             state.current_access = CurrentAccess::CpuExclusive;
         }
+
+        Ok(())
     }
 
-    #[allow(dead_code)]
     pub(crate) unsafe fn cpu_write_unlock(&mut self, range: Range<DeviceSize>) {
         self.ranges.split_at(&range.start);
         self.ranges.split_at(&range.end);
@@ -715,6 +1171,31 @@ impl ImageState {
         }
     }
 
+    pub(crate) fn check_host_layout(
+        &self,
+        range: Range<DeviceSize>,
+    ) -> Result<(), Box<ValidationError>> {
+        for (_range, state) in self.ranges.range(&range) {
+            if !matches!(
+                state.layout,
+                ImageLayout::General | ImageLayout::Preinitialized
+            ) {
+                return Err(Box::new(ValidationError {
+                    problem: format!(
+                        "the subresource is in the `ImageLayout::{:?}` layout, but host access \
+                        requires the `ImageLayout::General` or `ImageLayout::Preinitialized` \
+                        layout",
+                        state.layout,
+                    )
+                    .into(),
+                    ..Default::default()
+                }));
+            }
+        }
+
+        Ok(())
+    }
+
     pub(crate) fn check_gpu_read(
         &self,
         range: Range<DeviceSize>,
@@ -737,7 +1218,33 @@ impl ImageState {
         Ok(())
     }
 
-    pub(crate) unsafe fn gpu_read_lock(&mut self, range: Range<DeviceSize>) {
+    /// Checks whether the range can be locked by the device, which is only prevented by
+    /// conflicting host access. Conflicts with other device accesses are handled by
+    /// synchronization instead.
+    pub(crate) fn check_gpu_lock(
+        &self,
+        range: Range<DeviceSize>,
+        mutable: bool,
+    ) -> Result<(), AccessConflict> {
+        for (_range, state) in self.ranges.range(&range) {
+            match state.current_access {
+                CurrentAccess::CpuExclusive => return Err(AccessConflict::HostWrite),
+                CurrentAccess::Shared { cpu_reads, .. } if mutable && cpu_reads > 0 => {
+                    return Err(AccessConflict::HostRead);
+                }
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) unsafe fn gpu_read_lock(
+        &mut self,
+        range: Range<DeviceSize>,
+    ) -> Result<(), AccessConflict> {
+        self.check_gpu_lock(range.clone(), false)?;
+
         self.ranges.split_at(&range.start);
         self.ranges.split_at(&range.end);
 
@@ -745,9 +1252,11 @@ impl ImageState {
             match &mut state.current_access {
                 CurrentAccess::GpuExclusive { gpu_reads, .. }
                 | CurrentAccess::Shared { gpu_reads, .. } => *gpu_reads += 1,
-                _ => unreachable!("Image is being written by the CPU"),
+                _ => unreachable!("checked by `check_gpu_lock`"),
             }
         }
+
+        Ok(())
     }
 
     pub(crate) unsafe fn gpu_read_unlock(&mut self, range: Range<DeviceSize>) {
@@ -792,12 +1301,14 @@ impl ImageState {
         &mut self,
         range: Range<DeviceSize>,
         destination_layout: ImageLayout,
-    ) {
+    ) -> Result<(), AccessConflict> {
         debug_assert!(!matches!(
             destination_layout,
             ImageLayout::Undefined | ImageLayout::Preinitialized
         ));
 
+        self.check_gpu_lock(range.clone(), true)?;
+
         self.ranges.split_at(&range.start);
         self.ranges.split_at(&range.end);
 
@@ -813,11 +1324,13 @@ impl ImageState {
                         gpu_writes: 1,
                     }
                 }
-                _ => unreachable!("Image is being accessed by the CPU"),
+                _ => unreachable!("checked by `check_gpu_lock`"),
             }
 
             state.layout = destination_layout;
         }
+
+        Ok(())
     }
 
     pub(crate) unsafe fn gpu_write_unlock(&mut self, range: Range<DeviceSize>) {
@@ -1377,6 +1890,75 @@ impl ImageSubresourceLayers {
             array_layers: 0..array_layers,
         }
     }
+
+    /// A special value for the layer count in the Vulkan API, which selects all array layers from
+    /// the base array layer until the last one. It can be given to [`layers_from_base_count`].
+    ///
+    /// [`layers_from_base_count`]: Self::layers_from_base_count
+    pub const REMAINING_ARRAY_LAYERS: u32 = ash::vk::REMAINING_ARRAY_LAYERS;
+
+    /// Returns `self` with `mip_level` replaced by `level`.
+    #[inline]
+    pub fn mip(mut self, level: u32) -> Self {
+        self.mip_level = level;
+        self
+    }
+
+    /// Returns `self` with `array_layers` replaced by the single array layer `layer`.
+    #[inline]
+    pub fn layer(mut self, layer: u32) -> Self {
+        self.array_layers = layer..layer + 1;
+        self
+    }
+
+    /// Returns `self` with `array_layers` replaced by `layers`. An unbounded start or end keeps
+    /// the current start or end of `array_layers`.
+    #[inline]
+    pub fn layers(mut self, layers: impl RangeBounds<u32>) -> Self {
+        self.array_layers = replace_range(&self.array_layers, layers);
+        self
+    }
+
+    /// Returns `self` with `array_layers` replaced by the range starting at `base` and containing
+    /// `count` layers. If `count` is [`REMAINING_ARRAY_LAYERS`], the range extends until the
+    /// current end of `array_layers`.
+    ///
+    /// [`REMAINING_ARRAY_LAYERS`]: Self::REMAINING_ARRAY_LAYERS
+    #[inline]
+    pub fn layers_from_base_count(mut self, base: u32, count: u32) -> Self {
+        self.array_layers = range_from_base_count(&self.array_layers, base, count);
+        self
+    }
+
+    /// Returns the start and the length of `array_layers`.
+    #[inline]
+    pub fn array_layers_base_count(&self) -> (u32, u32) {
+        range_to_base_count(&self.array_layers)
+    }
+
+    /// Returns the subresources that are selected by both `self` and `other`, or `None` if there
+    /// are none.
+    #[inline]
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let aspects = self.aspects & other.aspects;
+        let array_layers = intersect_ranges(&self.array_layers, &other.array_layers);
+
+        (!aspects.is_empty() && self.mip_level == other.mip_level && !array_layers.is_empty()).then(
+            || Self {
+                aspects,
+                mip_level: self.mip_level,
+                array_layers,
+            },
+        )
+    }
+
+    /// Returns whether `self` and `other` select any of the same subresources.
+    #[inline]
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.aspects.intersects(other.aspects)
+            && self.mip_level == other.mip_level
+            && ranges_overlap(&self.array_layers, &other.array_layers)
+    }
 }
 
 impl ImageSubresourceLayers {
@@ -1510,6 +2092,114 @@ impl ImageSubresourceRange {
         }
     }
 
+    /// A special value for the level count in the Vulkan API, which selects all mip levels from
+    /// the base mip level until the last one. It can be given to [`mips_from_base_count`].
+    ///
+    /// [`mips_from_base_count`]: Self::mips_from_base_count
+    pub const REMAINING_MIP_LEVELS: u32 = ash::vk::REMAINING_MIP_LEVELS;
+
+    /// A special value for the layer count in the Vulkan API, which selects all array layers from
+    /// the base array layer until the last one. It can be given to [`layers_from_base_count`].
+    ///
+    /// [`layers_from_base_count`]: Self::layers_from_base_count
+    pub const REMAINING_ARRAY_LAYERS: u32 = ash::vk::REMAINING_ARRAY_LAYERS;
+
+    /// Returns `self` with `mip_levels` replaced by the single mip level `level`.
+    #[inline]
+    pub fn mip(mut self, level: u32) -> Self {
+        self.mip_levels = level..level + 1;
+        self
+    }
+
+    /// Returns `self` with `mip_levels` replaced by `levels`. An unbounded start or end keeps the
+    /// current start or end of `mip_levels`.
+    ///
+    /// ```
+    /// use vulkano::{format::Format, image::ImageSubresourceRange};
+    ///
+    /// let range = ImageSubresourceRange::from_parameters(Format::R8G8B8A8_UNORM, 10, 6);
+    ///
+    /// assert_eq!(range.clone().mips(2..).mip_levels, 2..10);
+    /// assert_eq!(range.mips(..4).layers(1..2).array_layers, 1..2);
+    /// ```
+    #[inline]
+    pub fn mips(mut self, levels: impl RangeBounds<u32>) -> Self {
+        self.mip_levels = replace_range(&self.mip_levels, levels);
+        self
+    }
+
+    /// Returns `self` with `array_layers` replaced by the single array layer `layer`.
+    #[inline]
+    pub fn layer(mut self, layer: u32) -> Self {
+        self.array_layers = layer..layer + 1;
+        self
+    }
+
+    /// Returns `self` with `array_layers` replaced by `layers`. An unbounded start or end keeps
+    /// the current start or end of `array_layers`.
+    #[inline]
+    pub fn layers(mut self, layers: impl RangeBounds<u32>) -> Self {
+        self.array_layers = replace_range(&self.array_layers, layers);
+        self
+    }
+
+    /// Returns `self` with `mip_levels` replaced by the range starting at `base` and containing
+    /// `count` levels. If `count` is [`REMAINING_MIP_LEVELS`], the range extends until the
+    /// current end of `mip_levels`.
+    ///
+    /// [`REMAINING_MIP_LEVELS`]: Self::REMAINING_MIP_LEVELS
+    #[inline]
+    pub fn mips_from_base_count(mut self, base: u32, count: u32) -> Self {
+        self.mip_levels = range_from_base_count(&self.mip_levels, base, count);
+        self
+    }
+
+    /// Returns `self` with `array_layers` replaced by the range starting at `base` and containing
+    /// `count` layers. If `count` is [`REMAINING_ARRAY_LAYERS`], the range extends until the
+    /// current end of `array_layers`.
+    ///
+    /// [`REMAINING_ARRAY_LAYERS`]: Self::REMAINING_ARRAY_LAYERS
+    #[inline]
+    pub fn layers_from_base_count(mut self, base: u32, count: u32) -> Self {
+        self.array_layers = range_from_base_count(&self.array_layers, base, count);
+        self
+    }
+
+    /// Returns the start and the length of `mip_levels`.
+    #[inline]
+    pub fn mip_levels_base_count(&self) -> (u32, u32) {
+        range_to_base_count(&self.mip_levels)
+    }
+
+    /// Returns the start and the length of `array_layers`.
+    #[inline]
+    pub fn array_layers_base_count(&self) -> (u32, u32) {
+        range_to_base_count(&self.array_layers)
+    }
+
+    /// Returns the subresources that are selected by both `self` and `other`, or `None` if there
+    /// are none.
+    #[inline]
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let aspects = self.aspects & other.aspects;
+        let mip_levels = intersect_ranges(&self.mip_levels, &other.mip_levels);
+        let array_layers = intersect_ranges(&self.array_layers, &other.array_layers);
+
+        (!aspects.is_empty() && !mip_levels.is_empty() && !array_layers.is_empty()).then(|| Self {
+            aspects,
+            mip_levels,
+            array_layers,
+        })
+    }
+
+    /// Returns whether `self` and `other` select any of the same subresources.
+    #[inline]
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.aspects.intersects(other.aspects)
+            && ranges_overlap(&self.mip_levels, &other.mip_levels)
+            && ranges_overlap(&self.array_layers, &other.array_layers)
+    }
+
     pub(crate) fn validate(&self, device: &Device) -> Result<(), Box<ValidationError>> {
         let &Self {
             aspects,
@@ -1608,6 +2298,42 @@ impl From<ImageSubresourceLayers> for ImageSubresourceRange {
     }
 }
 
+fn replace_range(current: &Range<u32>, range: impl RangeBounds<u32>) -> Range<u32> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.saturating_add(1),
+        Bound::Unbounded => current.start,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.saturating_add(1),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => current.end,
+    };
+
+    start..end
+}
+
+fn range_from_base_count(current: &Range<u32>, base: u32, count: u32) -> Range<u32> {
+    // `REMAINING_MIP_LEVELS` and `REMAINING_ARRAY_LAYERS` have the same value.
+    if count == ash::vk::REMAINING_ARRAY_LAYERS {
+        base..current.end
+    } else {
+        base..base.saturating_add(count)
+    }
+}
+
+fn range_to_base_count(range: &Range<u32>) -> (u32, u32) {
+    (range.start, range.end.saturating_sub(range.start))
+}
+
+fn intersect_ranges(a: &Range<u32>, b: &Range<u32>) -> Range<u32> {
+    max(a.start, b.start)..cmp::min(a.end, b.end)
+}
+
+fn ranges_overlap(a: &Range<u32>, b: &Range<u32>) -> bool {
+    a.start < b.end && b.start < a.end && !a.is_empty() && !b.is_empty()
+}
+
 /// Describes the memory layout of a single subresource of an image.
 ///
 /// The address of a texel at `(x, y, z, layer)` is `layer * array_pitch + z * depth_pitch +
@@ -1634,6 +2360,241 @@ pub struct SubresourceLayout {
     pub depth_pitch: Option<DeviceSize>,
 }
 
+/// Controls the compression of an image.
+///
+/// The [`image_compression_control`] feature must be enabled on the device.
+///
+/// [`image_compression_control`]: DeviceFeatures::image_compression_control
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageCompressionControl {
+    /// The kind of compression to apply.
+    ///
+    /// At most one flag can be set. If no flag is set, the implementation applies its default
+    /// lossless compression.
+    ///
+    /// The default value is empty.
+    pub flags: ImageCompressionFlags,
+
+    /// If `flags` contains [`ImageCompressionFlags::FIXED_RATE_EXPLICIT`], the fixed-rate
+    /// compression rates that are allowed for each plane of the image. There must be one element
+    /// for each plane of the format, or a single element if the format is not multi-planar.
+    /// The implementation picks the lowest allowed rate that it supports.
+    ///
+    /// If `flags` does not contain `FIXED_RATE_EXPLICIT`, this is ignored.
+    ///
+    /// The default value is empty.
+    pub fixed_rate_flags: SmallVec<[ImageCompressionFixedRateFlags; 3]>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for ImageCompressionControl {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            flags: ImageCompressionFlags::empty(),
+            fixed_rate_flags: SmallVec::new(),
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+impl ImageCompressionControl {
+    pub(crate) fn validate(&self, device: &Device) -> Result<(), Box<ValidationError>> {
+        let &Self {
+            flags,
+            ref fixed_rate_flags,
+            _ne: _,
+        } = self;
+
+        flags.validate_device(device).map_err(|err| {
+            err.add_context("flags")
+                .set_vuids(&["VUID-VkImageCompressionControlEXT-flags-06747"])
+        })?;
+
+        if flags.count() > 1 {
+            return Err(Box::new(ValidationError {
+                context: "flags".into(),
+                problem: "contains more than one flag".into(),
+                vuids: &["VUID-VkImageCompressionControlEXT-flags-06747"],
+                ..Default::default()
+            }));
+        }
+
+        if flags.intersects(ImageCompressionFlags::FIXED_RATE_EXPLICIT) {
+            if fixed_rate_flags.is_empty() {
+                return Err(Box::new(ValidationError {
+                    problem: "`flags` contains `ImageCompressionFlags::FIXED_RATE_EXPLICIT`, but \
+                        `fixed_rate_flags` is empty"
+                        .into(),
+                    vuids: &["VUID-VkImageCompressionControlEXT-flags-06748"],
+                    ..Default::default()
+                }));
+            }
+
+            for (plane, fixed_rate_flags) in fixed_rate_flags.iter().enumerate() {
+                fixed_rate_flags.validate_device(device).map_err(|err| {
+                    err.add_context(format!("fixed_rate_flags[{}]", plane))
+                        .set_vuids(&["VUID-VkImageCompressionControlEXT-flags-06748"])
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that `fixed_rate_flags` has the right number of elements for an image with
+    /// `format`.
+    pub(crate) fn validate_plane_count(&self, format: Format) -> Result<(), Box<ValidationError>> {
+        if !self
+            .flags
+            .intersects(ImageCompressionFlags::FIXED_RATE_EXPLICIT)
+        {
+            return Ok(());
+        }
+
+        let plane_count = format.planes().len();
+
+        if plane_count == 0 {
+            if self.fixed_rate_flags.len() != 1 {
+                return Err(Box::new(ValidationError {
+                    problem: "`format` is not a multi-planar format, and \
+                        `compression_control.flags` contains \
+                        `ImageCompressionFlags::FIXED_RATE_EXPLICIT`, but \
+                        `compression_control.fixed_rate_flags` does not contain exactly one \
+                        element"
+                        .into(),
+                    vuids: &["VUID-VkImageCreateInfo-pNext-06744"],
+                    ..Default::default()
+                }));
+            }
+        } else if self.fixed_rate_flags.len() != plane_count {
+            return Err(Box::new(ValidationError {
+                problem: "`format` is a multi-planar format, and \
+                    `compression_control.flags` contains \
+                    `ImageCompressionFlags::FIXED_RATE_EXPLICIT`, but the length of \
+                    `compression_control.fixed_rate_flags` does not equal the number of planes \
+                    in `format`"
+                    .into(),
+                vuids: &["VUID-VkImageCreateInfo-pNext-06743"],
+                ..Default::default()
+            }));
+        }
+
+        Ok(())
+    }
+}
+
+vulkan_bitflags! {
+    #[non_exhaustive]
+
+    /// Flags specifying the kind of compression that is applied to an image.
+    ImageCompressionFlags = ImageCompressionFlagsEXT(u32);
+
+    /// Fixed-rate compression is applied, at a rate chosen by the implementation.
+    FIXED_RATE_DEFAULT = FIXED_RATE_DEFAULT,
+
+    /// Fixed-rate compression is applied, at a rate chosen from the rates allowed by
+    /// [`ImageCompressionControl::fixed_rate_flags`].
+    FIXED_RATE_EXPLICIT = FIXED_RATE_EXPLICIT,
+
+    /// All compression is disabled.
+    DISABLED = DISABLED,
+}
+
+vulkan_bitflags! {
+    #[non_exhaustive]
+
+    /// Flags specifying fixed-rate compression rates, in bits per component.
+    ImageCompressionFixedRateFlags = ImageCompressionFixedRateFlagsEXT(u32);
+
+    /// A fixed-rate compression of 1 bit per component.
+    TYPE_1BPC = TYPE_1BPC,
+
+    /// A fixed-rate compression of 2 bits per component.
+    TYPE_2BPC = TYPE_2BPC,
+
+    /// A fixed-rate compression of 3 bits per component.
+    TYPE_3BPC = TYPE_3BPC,
+
+    /// A fixed-rate compression of 4 bits per component.
+    TYPE_4BPC = TYPE_4BPC,
+
+    /// A fixed-rate compression of 5 bits per component.
+    TYPE_5BPC = TYPE_5BPC,
+
+    /// A fixed-rate compression of 6 bits per component.
+    TYPE_6BPC = TYPE_6BPC,
+
+    /// A fixed-rate compression of 7 bits per component.
+    TYPE_7BPC = TYPE_7BPC,
+
+    /// A fixed-rate compression of 8 bits per component.
+    TYPE_8BPC = TYPE_8BPC,
+
+    /// A fixed-rate compression of 9 bits per component.
+    TYPE_9BPC = TYPE_9BPC,
+
+    /// A fixed-rate compression of 10 bits per component.
+    TYPE_10BPC = TYPE_10BPC,
+
+    /// A fixed-rate compression of 11 bits per component.
+    TYPE_11BPC = TYPE_11BPC,
+
+    /// A fixed-rate compression of 12 bits per component.
+    TYPE_12BPC = TYPE_12BPC,
+
+    /// A fixed-rate compression of 13 bits per component.
+    TYPE_13BPC = TYPE_13BPC,
+
+    /// A fixed-rate compression of 14 bits per component.
+    TYPE_14BPC = TYPE_14BPC,
+
+    /// A fixed-rate compression of 15 bits per component.
+    TYPE_15BPC = TYPE_15BPC,
+
+    /// A fixed-rate compression of 16 bits per component.
+    TYPE_16BPC = TYPE_16BPC,
+
+    /// A fixed-rate compression of 17 bits per component.
+    TYPE_17BPC = TYPE_17BPC,
+
+    /// A fixed-rate compression of 18 bits per component.
+    TYPE_18BPC = TYPE_18BPC,
+
+    /// A fixed-rate compression of 19 bits per component.
+    TYPE_19BPC = TYPE_19BPC,
+
+    /// A fixed-rate compression of 20 bits per component.
+    TYPE_20BPC = TYPE_20BPC,
+
+    /// A fixed-rate compression of 21 bits per component.
+    TYPE_21BPC = TYPE_21BPC,
+
+    /// A fixed-rate compression of 22 bits per component.
+    TYPE_22BPC = TYPE_22BPC,
+
+    /// A fixed-rate compression of 23 bits per component.
+    TYPE_23BPC = TYPE_23BPC,
+
+    /// A fixed-rate compression of 24 bits per component.
+    TYPE_24BPC = TYPE_24BPC,
+}
+
+/// The compression that was applied to a subresource of an image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImageCompressionProperties {
+    /// The kind of compression that was applied.
+    ///
+    /// If this is empty, the subresource uses the default lossless compression of the
+    /// implementation. If this contains [`ImageCompressionFlags::DISABLED`], then no compression
+    /// is applied at all, which may happen even if it was not requested.
+    pub flags: ImageCompressionFlags,
+
+    /// If fixed-rate compression was applied, the rate that was chosen.
+    pub fixed_rate_flags: ImageCompressionFixedRateFlags,
+}
+
 /// The image configuration to query in
 /// [`PhysicalDevice::image_format_properties`].
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -2033,16 +2994,14 @@ impl ImageDrmFormatModifierInfo {
                 let queue_family_count = physical_device.queue_family_properties().len() as u32;
 
                 for (index, &queue_family_index) in queue_family_indices.iter().enumerate() {
-                    if queue_family_indices[..index].contains(&queue_family_index) {
+                    if let Some(other_index) = queue_family_indices[..index]
+                        .iter()
+                        .position(|&other| other == queue_family_index)
+                    {
                         return Err(Box::new(ValidationError {
-                            context: "queue_family_indices".into(),
-                            problem: format!(
-                                "the queue family index in the list at index {} is contained in \
-                                the list more than once",
-                                index,
-                            )
-                            .into(),
-                            vuids: &[" VUID-VkPhysicalDeviceImageDrmFormatModifierInfoEXT-sharingMode-02316"],
+                            context: format!("sharing[{}]", index).into(),
+                            problem: format!("is equal to `sharing[{}]`", other_index).into(),
+                            vuids: &["VUID-VkPhysicalDeviceImageDrmFormatModifierInfoEXT-sharingMode-02316"],
                             ..Default::default()
                         }));
                     }
@@ -2052,7 +3011,7 @@ impl ImageDrmFormatModifierInfo {
                             context: format!("sharing[{}]", index).into(),
                             problem: "is not less than the number of queue families in the device"
                                 .into(),
-                            vuids: &[" VUID-VkPhysicalDeviceImageDrmFormatModifierInfoEXT-sharingMode-02316"],
+                            vuids: &["VUID-VkPhysicalDeviceImageDrmFormatModifierInfoEXT-sharingMode-02316"],
                             ..Default::default()
                         }));
                     }
@@ -2292,6 +3251,88 @@ pub struct SparseImageMemoryRequirements {
 
 #[cfg(test)]
 mod tests {
+    use super::{
+        Image, ImageAspect, ImageAspects, ImageCreateInfo, ImageLayout, ImageSubresourceLayers,
+        ImageSubresourceRange, ImageTiling, ImageUsage,
+    };
+    use crate::{
+        format::Format,
+        memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
+        sync::{AccessConflict, HostAccessError},
+        Validated,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn host_access() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device));
+        let create_image = |initial_layout| {
+            Image::new(
+                memory_allocator.clone(),
+                ImageCreateInfo {
+                    format: Format::R8G8B8A8_UNORM,
+                    extent: [5, 3, 1],
+                    tiling: ImageTiling::Linear,
+                    usage: ImageUsage::TRANSFER_SRC,
+                    initial_layout,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                        | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+
+        let image = create_image(ImageLayout::Preinitialized);
+        let data: Vec<u8> = (0..5 * 3 * 4).collect();
+
+        {
+            let mut guard = image.write(ImageAspect::Color, 0, 0).unwrap();
+            assert_eq!(guard.row_count(), 3);
+            assert_eq!(guard.row(0).len(), 5 * 4);
+            guard.copy_from_tightly_packed(&data);
+            guard.row_mut(2)[0] = 255;
+
+            assert!(matches!(
+                image.read(ImageAspect::Color, 0, 0),
+                Err(Validated::Error(HostAccessError::AccessConflict(
+                    AccessConflict::HostWrite
+                ))),
+            ));
+        }
+
+        let mut expected = data;
+        expected[2 * 5 * 4] = 255;
+
+        {
+            let guard = image.read(ImageAspect::Color, 0, 0).unwrap();
+            assert_eq!(guard.row(1), &expected[5 * 4..2 * 5 * 4]);
+
+            let mut contents = Vec::new();
+            guard.copy_to_tightly_packed(&mut contents);
+            assert_eq!(contents, expected);
+
+            assert!(matches!(
+                image.write(ImageAspect::Color, 0, 0),
+                Err(Validated::Error(HostAccessError::AccessConflict(
+                    AccessConflict::HostRead
+                ))),
+            ));
+            assert!(image.read(ImageAspect::Color, 0, 0).is_ok());
+        }
+
+        // Host access isn't well-defined for the `Undefined` layout.
+        let image = create_image(ImageLayout::Undefined);
+        assert!(matches!(
+            image.write(ImageAspect::Color, 0, 0),
+            Err(Validated::ValidationError(_)),
+        ));
+    }
+
     #[test]
     fn max_mip_levels() {
         assert_eq!(super::max_mip_levels([2, 1, 1]), 2);
@@ -2313,4 +3354,115 @@ mod tests {
         assert_eq!(super::mip_level_extent(extent, 8), Some([1, 1, 1]));
         assert_eq!(super::mip_level_extent(extent, 9), None);
     }
+
+    #[test]
+    fn subresource_range_combinators() {
+        let range = ImageSubresourceRange::from_parameters(Format::D24_UNORM_S8_UINT, 10, 6);
+
+        assert_eq!(range.clone().mip(3).mip_levels, 3..4);
+        assert_eq!(range.clone().layer(5).array_layers, 5..6);
+        assert_eq!(range.clone().mips(2..=4).mip_levels, 2..5);
+        assert_eq!(range.clone().mips(..3).mip_levels, 0..3);
+        assert_eq!(range.clone().mips(..).mip_levels, 0..10);
+
+        let chained = range.clone().mips(0..1).layers(1..2);
+        assert_eq!((chained.mip_levels, chained.array_layers), (0..1, 1..2));
+
+        // Unbounded ends are relative to the current range, not the whole image.
+        assert_eq!(range.clone().layers(2..4).layers(3..).array_layers, 3..4);
+    }
+
+    #[test]
+    fn subresource_range_base_count() {
+        let range = ImageSubresourceRange::from_parameters(Format::R8G8B8A8_UNORM, 10, 6);
+
+        let sub = range
+            .clone()
+            .mips_from_base_count(2, ImageSubresourceRange::REMAINING_MIP_LEVELS)
+            .layers_from_base_count(1, 3);
+        assert_eq!(
+            (sub.mip_levels.clone(), sub.array_layers.clone()),
+            (2..10, 1..4)
+        );
+        assert_eq!(sub.mip_levels_base_count(), (2, 8));
+        assert_eq!(sub.array_layers_base_count(), (1, 3));
+
+        // `REMAINING_*` extends to the end of the current range.
+        let sub = range
+            .clone()
+            .layers(0..4)
+            .layers_from_base_count(1, ImageSubresourceRange::REMAINING_ARRAY_LAYERS);
+        assert_eq!(sub.array_layers, 1..4);
+
+        // A base past the end with `REMAINING_*` gives an empty range.
+        let sub = range
+            .clone()
+            .mips_from_base_count(10, ImageSubresourceRange::REMAINING_MIP_LEVELS);
+        assert!(sub.mip_levels.is_empty());
+        assert_eq!(sub.mip_levels_base_count(), (10, 0));
+
+        // A large explicit count does not overflow.
+        let sub = range.mips_from_base_count(5, u32::MAX - 1);
+        assert_eq!(sub.mip_levels, 5..u32::MAX);
+
+        let layers = ImageSubresourceLayers::from_parameters(Format::R8G8B8A8_UNORM, 6).mip(2);
+        let sub = layers
+            .clone()
+            .layers_from_base_count(4, ImageSubresourceLayers::REMAINING_ARRAY_LAYERS);
+        assert_eq!((sub.mip_level, sub.array_layers.clone()), (2, 4..6));
+        assert_eq!(sub.array_layers_base_count(), (4, 2));
+        assert_eq!(layers.layer(3).array_layers_base_count(), (3, 1));
+    }
+
+    #[test]
+    fn subresource_range_intersection() {
+        let range = ImageSubresourceRange::from_parameters(Format::D24_UNORM_S8_UINT, 10, 6);
+        let a = range.clone().mips(0..4).layers(2..5);
+        let b = range.clone().mips(3..6).layers(4..6);
+
+        assert!(a.overlaps(&b));
+        assert_eq!(
+            a.intersection(&b),
+            Some(ImageSubresourceRange {
+                aspects: ImageAspects::DEPTH | ImageAspects::STENCIL,
+                mip_levels: 3..4,
+                array_layers: 4..5,
+            }),
+        );
+
+        // Adjacent ranges don't overlap.
+        let c = range.clone().mips(4..6);
+        assert!(!a.overlaps(&c));
+        assert_eq!(a.intersection(&c), None);
+
+        // Different aspects don't overlap.
+        let depth = ImageSubresourceRange {
+            aspects: ImageAspects::DEPTH,
+            ..range.clone()
+        };
+        let stencil = ImageSubresourceRange {
+            aspects: ImageAspects::STENCIL,
+            ..range
+        };
+        assert!(!depth.overlaps(&stencil));
+        assert_eq!(depth.intersection(&stencil), None);
+
+        let layers = ImageSubresourceLayers::from_parameters(Format::R8G8B8A8_UNORM, 6);
+        assert!(layers
+            .clone()
+            .layers(0..3)
+            .overlaps(&layers.clone().layer(2)));
+        assert!(!layers
+            .clone()
+            .layers(0..3)
+            .overlaps(&layers.clone().layer(3)));
+        assert!(!layers.clone().mip(1).overlaps(&layers));
+        assert_eq!(
+            layers
+                .clone()
+                .layers(1..4)
+                .intersection(&layers.clone().layers(3..)),
+            Some(layers.layers(3..4)),
+        );
+    }
 }
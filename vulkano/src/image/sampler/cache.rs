@@ -0,0 +1,317 @@
+//! A cache that deduplicates samplers by the parameters they were created with.
+//!
+//! Implementations limit the total number of samplers that can exist on a device at the same time,
+//! with the [`max_sampler_allocation_count`] limit, which can be as low as 4000. Applications that
+//! create a sampler for every material or every texture can easily exceed this limit, even though
+//! most of those samplers are identical. A [`SamplerCache`] avoids this by returning the same
+//! sampler for identical create infos.
+//!
+//! [`max_sampler_allocation_count`]: crate::device::DeviceProperties::max_sampler_allocation_count
+
+use super::{
+    ycbcr::SamplerYcbcrConversion, BorderColor, Filter, Sampler, SamplerAddressMode,
+    SamplerCreateInfo, SamplerMipmapMode, SamplerReductionMode,
+};
+use crate::{
+    device::{Device, DeviceOwned},
+    pipeline::graphics::depth_stencil::CompareOp,
+    Validated, VulkanError,
+};
+use ahash::HashMap;
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+/// A thread-safe cache of samplers, keyed by their create info.
+///
+/// [`get_or_create`](Self::get_or_create) returns a sampler that was created with an identical
+/// create info if there is one in the cache, and creates a new one otherwise. All fields of
+/// [`SamplerCreateInfo`] are compared. Floating-point fields are compared by their bit patterns,
+/// so that `0.0` and `-0.0` are different keys, and NaN values are equal to themselves.
+///
+/// The cache holds up to `capacity` samplers. When a new sampler would exceed the capacity, the
+/// least recently used sampler that is not used outside of the cache is evicted. Samplers that
+/// are still in use elsewhere are never evicted, so the cache can grow beyond its capacity if all
+/// of them are in use.
+///
+/// # Examples
+///
+/// ```
+/// use vulkano::image::sampler::{cache::SamplerCache, SamplerCreateInfo};
+///
+/// # let device: std::sync::Arc<vulkano::device::Device> = return;
+/// let cache = SamplerCache::new(device.clone(), 256);
+///
+/// let a = cache
+///     .get_or_create(SamplerCreateInfo::simple_repeat_linear())
+///     .unwrap();
+/// let b = cache
+///     .get_or_create(SamplerCreateInfo::simple_repeat_linear())
+///     .unwrap();
+/// assert_eq!(a, b);
+/// ```
+#[derive(Debug)]
+pub struct SamplerCache {
+    device: Arc<Device>,
+    capacity: usize,
+    state: Mutex<SamplerCacheState>,
+}
+
+#[derive(Debug, Default)]
+struct SamplerCacheState {
+    samplers: HashMap<SamplerKey, CacheEntry>,
+    // Incremented on every access, to find the least recently used entry.
+    clock: u64,
+    stats: SamplerCacheStats,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    sampler: Arc<Sampler>,
+    last_used: u64,
+}
+
+impl SamplerCache {
+    /// Creates a new `SamplerCache` that holds up to `capacity` unused samplers.
+    #[inline]
+    pub fn new(device: Arc<Device>, capacity: usize) -> Self {
+        SamplerCache {
+            device,
+            capacity,
+            state: Mutex::new(SamplerCacheState::default()),
+        }
+    }
+
+    /// Returns the maximum number of samplers that the cache holds before it starts evicting
+    /// unused samplers.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of samplers that are currently in the cache.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.state.lock().samplers.len()
+    }
+
+    /// Returns whether the cache currently holds no samplers.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a sampler created with `create_info`, creating it if there is none in the cache.
+    pub fn get_or_create(
+        &self,
+        create_info: SamplerCreateInfo,
+    ) -> Result<Arc<Sampler>, Validated<VulkanError>> {
+        let key = SamplerKey::new(&create_info);
+        let mut state = self.state.lock();
+        state.clock += 1;
+        let clock = state.clock;
+
+        if let Some(entry) = state.samplers.get_mut(&key) {
+            entry.last_used = clock;
+            let sampler = entry.sampler.clone();
+            state.stats.hits += 1;
+
+            return Ok(sampler);
+        }
+
+        state.stats.misses += 1;
+
+        if state.samplers.len() >= self.capacity {
+            state.evict_one();
+        }
+
+        let sampler = Sampler::new(self.device.clone(), create_info)?;
+        state.samplers.insert(
+            key,
+            CacheEntry {
+                sampler: sampler.clone(),
+                last_used: clock,
+            },
+        );
+
+        Ok(sampler)
+    }
+
+    /// Removes all samplers from the cache that are not used outside of it, and returns how many
+    /// were removed.
+    pub fn evict_unused(&self) -> usize {
+        let mut state = self.state.lock();
+        let len = state.samplers.len();
+        state
+            .samplers
+            .retain(|_, entry| Arc::strong_count(&entry.sampler) > 1);
+        let evicted = len - state.samplers.len();
+        state.stats.evictions += evicted as u64;
+
+        evicted
+    }
+
+    /// Returns statistics about the use of the cache.
+    #[inline]
+    pub fn stats(&self) -> SamplerCacheStats {
+        self.state.lock().stats
+    }
+}
+
+unsafe impl DeviceOwned for SamplerCache {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
+impl SamplerCacheState {
+    fn evict_one(&mut self) {
+        let least_recently_used = self
+            .samplers
+            .iter()
+            .filter(|(_, entry)| Arc::strong_count(&entry.sampler) == 1)
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(key, _)| key.clone());
+
+        if let Some(key) = least_recently_used {
+            self.samplers.remove(&key);
+            self.stats.evictions += 1;
+        }
+    }
+}
+
+/// Statistics about the use of a [`SamplerCache`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SamplerCacheStats {
+    /// The number of times that a sampler was returned from the cache.
+    pub hits: u64,
+
+    /// The number of times that a new sampler had to be created because there was none in the
+    /// cache.
+    pub misses: u64,
+
+    /// The number of samplers that were removed from the cache to make room for new ones, or by
+    /// [`SamplerCache::evict_unused`].
+    pub evictions: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct SamplerKey {
+    mag_filter: Filter,
+    min_filter: Filter,
+    mipmap_mode: SamplerMipmapMode,
+    address_mode: [SamplerAddressMode; 3],
+    mip_lod_bias: u32,
+    anisotropy: Option<u32>,
+    compare: Option<CompareOp>,
+    lod: [u32; 2],
+    border_color: BorderColor,
+    unnormalized_coordinates: bool,
+    reduction_mode: SamplerReductionMode,
+    sampler_ycbcr_conversion: Option<Arc<SamplerYcbcrConversion>>,
+}
+
+impl SamplerKey {
+    fn new(create_info: &SamplerCreateInfo) -> Self {
+        // Destructure every field, so that adding a field to `SamplerCreateInfo` without adding
+        // it to the key is a compile error.
+        let &SamplerCreateInfo {
+            mag_filter,
+            min_filter,
+            mipmap_mode,
+            address_mode,
+            mip_lod_bias,
+            anisotropy,
+            compare,
+            ref lod,
+            border_color,
+            unnormalized_coordinates,
+            reduction_mode,
+            ref sampler_ycbcr_conversion,
+            _ne: _,
+        } = create_info;
+
+        SamplerKey {
+            mag_filter,
+            min_filter,
+            mipmap_mode,
+            address_mode,
+            mip_lod_bias: mip_lod_bias.to_bits(),
+            anisotropy: anisotropy.map(f32::to_bits),
+            compare,
+            lod: [lod.start().to_bits(), lod.end().to_bits()],
+            border_color,
+            unnormalized_coordinates,
+            reduction_mode,
+            sampler_ycbcr_conversion: sampler_ycbcr_conversion.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SamplerCache;
+    use crate::image::sampler::{Filter, SamplerCreateInfo};
+    use std::sync::Arc;
+
+    #[test]
+    fn deduplicate() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let cache = SamplerCache::new(device, 16);
+
+        let a = cache
+            .get_or_create(SamplerCreateInfo::simple_repeat_linear())
+            .unwrap();
+        let b = cache
+            .get_or_create(SamplerCreateInfo::simple_repeat_linear())
+            .unwrap();
+        let c = cache
+            .get_or_create(SamplerCreateInfo {
+                mip_lod_bias: -0.0,
+                ..SamplerCreateInfo::default()
+            })
+            .unwrap();
+        let d = cache.get_or_create(SamplerCreateInfo::default()).unwrap();
+
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+        assert!(!Arc::ptr_eq(&c, &d));
+        assert_eq!(cache.len(), 3);
+
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 3);
+    }
+
+    #[test]
+    fn evict_least_recently_used() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let cache = SamplerCache::new(device, 2);
+
+        let nearest = SamplerCreateInfo::default;
+        let linear = || SamplerCreateInfo {
+            mag_filter: Filter::Linear,
+            ..SamplerCreateInfo::default()
+        };
+
+        let in_use = cache.get_or_create(nearest()).unwrap();
+        drop(cache.get_or_create(linear()).unwrap());
+
+        // `in_use` is the least recently used sampler, but it can't be evicted.
+        drop(
+            cache
+                .get_or_create(SamplerCreateInfo::simple_repeat_linear())
+                .unwrap(),
+        );
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.stats().evictions, 1);
+        assert!(Arc::ptr_eq(
+            &in_use,
+            &cache.get_or_create(nearest()).unwrap()
+        ));
+
+        drop(in_use);
+        assert_eq!(cache.evict_unused(), 2);
+        assert!(cache.is_empty());
+    }
+}
@@ -35,6 +35,7 @@
 //! - Positive: **minification**. The rendered object is further from the viewer, and each pixel in
 //!   the texture corresponds to less than one framebuffer pixel.
 
+pub mod cache;
 pub mod ycbcr;
 
 use self::ycbcr::SamplerYcbcrConversion;
@@ -9,12 +9,11 @@
 //! [the parent module-level documentation]: super
 
 use super::{
-    Image, ImageAspect, ImageAspects, ImageCreateFlags, ImageLayout, ImageMemory,
-    ImageSubresourceLayers, ImageSubresourceRange, ImageTiling, ImageUsage, SampleCount,
-    SparseImageMemoryRequirements, SubresourceLayout,
+    Image, ImageAspect, ImageAspects, ImageCompressionControl, ImageCompressionProperties,
+    ImageCreateFlags, ImageLayout, ImageLayoutPolicy, ImageMemory, ImageSubresourceLayers,
+    ImageSubresourceRange, ImageTiling, ImageUsage, SampleCount, SparseImageMemoryRequirements,
+    SubresourceLayout,
 };
-#[cfg(doc)]
-use crate::format::DrmFormatModifierProperties;
 use crate::{
     cache::OnceCache,
     device::{Device, DeviceOwned},
@@ -34,6 +33,11 @@ use crate::{
     Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError, Version, VulkanError,
     VulkanObject,
 };
+#[cfg(doc)]
+use crate::{
+    device::{DeviceExtensions, DeviceFeatures},
+    format::DrmFormatModifierProperties,
+};
 use smallvec::{smallvec, SmallVec};
 use std::{mem::MaybeUninit, num::NonZeroU64, ptr, sync::Arc};
 
@@ -66,8 +70,10 @@ pub struct RawImage {
     stencil_usage: Option<ImageUsage>,
     sharing: Sharing<SmallVec<[u32; 4]>>,
     initial_layout: ImageLayout,
+    layout_policy: ImageLayoutPolicy,
     drm_format_modifier: Option<(u64, u32)>,
     external_memory_handle_types: ExternalMemoryHandleTypes,
+    compression_control: Option<ImageCompressionControl>,
 
     memory_requirements: SmallVec<[MemoryRequirements; 4]>,
     needs_destruction: bool, // `vkDestroyImage` is called only if true.
@@ -117,9 +123,11 @@ impl RawImage {
             stencil_usage,
             ref sharing,
             initial_layout,
+            layout_policy: _,
             ref drm_format_modifiers,
             ref drm_format_modifier_plane_layouts,
             external_memory_handle_types,
+            ref compression_control,
             _ne: _,
         } = &create_info;
 
@@ -152,6 +160,8 @@ impl RawImage {
             initial_layout: initial_layout.into(),
             ..Default::default()
         };
+        let mut compression_control_vk = None;
+        let mut compression_control_fixed_rate_flags_vk: SmallVec<[_; 3]>;
         let mut drm_format_modifier_explicit_info_vk = None;
         let drm_format_modifier_plane_layouts_vk: SmallVec<[_; 4]>;
         let mut drm_format_modifier_list_info_vk = None;
@@ -160,6 +170,31 @@ impl RawImage {
         let format_list_view_formats_vk: Vec<_>;
         let mut stencil_usage_info_vk = None;
 
+        if let Some(compression_control) = compression_control {
+            let &ImageCompressionControl {
+                flags,
+                ref fixed_rate_flags,
+                _ne: _,
+            } = compression_control;
+
+            compression_control_fixed_rate_flags_vk = fixed_rate_flags
+                .iter()
+                .copied()
+                .map(ash::vk::ImageCompressionFixedRateFlagsEXT::from)
+                .collect();
+
+            let next = compression_control_vk.insert(ash::vk::ImageCompressionControlEXT {
+                flags: flags.into(),
+                compression_control_plane_count: compression_control_fixed_rate_flags_vk.len()
+                    as u32,
+                p_fixed_rate_flags: compression_control_fixed_rate_flags_vk.as_mut_ptr(),
+                ..Default::default()
+            });
+
+            next.p_next = create_info_vk.p_next;
+            create_info_vk.p_next = <*const _>::cast(next);
+        }
+
         if !drm_format_modifiers.is_empty() {
             if drm_format_modifier_plane_layouts.is_empty() {
                 let next = drm_format_modifier_list_info_vk.insert(
@@ -302,6 +337,8 @@ impl RawImage {
         create_info: ImageCreateInfo,
         needs_destruction: bool,
     ) -> Result<Self, VulkanError> {
+        let memory_requirements_key = device.memory_requirements_cache().image_key(&create_info);
+
         let ImageCreateInfo {
             flags,
             image_type,
@@ -316,9 +353,11 @@ impl RawImage {
             stencil_usage,
             sharing,
             initial_layout,
+            layout_policy,
             drm_format_modifiers: _,
             drm_format_modifier_plane_layouts: _,
             external_memory_handle_types,
+            compression_control,
             _ne: _,
         } = create_info;
 
@@ -345,23 +384,31 @@ impl RawImage {
         };
 
         let memory_requirements = if needs_destruction {
-            if flags.intersects(ImageCreateFlags::DISJOINT) {
-                // VUID-VkImageMemoryRequirementsInfo2-image-01589
-                // VUID-VkImageMemoryRequirementsInfo2-image-02279
-                let plane_count = drm_format_modifier.map_or_else(
-                    || format.planes().len(),
-                    |(_, plane_count)| plane_count as usize,
-                );
-
-                (0..plane_count)
-                    .map(|plane| {
-                        Self::get_memory_requirements(&device, handle, Some((plane, tiling)))
-                    })
-                    .collect()
-            } else {
-                // VUID-VkImageMemoryRequirementsInfo2-image-01590
-                smallvec![Self::get_memory_requirements(&device, handle, None)]
-            }
+            device
+                .memory_requirements_cache()
+                .image_memory_requirements(memory_requirements_key, || {
+                    if flags.intersects(ImageCreateFlags::DISJOINT) {
+                        // VUID-VkImageMemoryRequirementsInfo2-image-01589
+                        // VUID-VkImageMemoryRequirementsInfo2-image-02279
+                        let plane_count = drm_format_modifier.map_or_else(
+                            || format.planes().len(),
+                            |(_, plane_count)| plane_count as usize,
+                        );
+
+                        (0..plane_count)
+                            .map(|plane| {
+                                Self::get_memory_requirements(
+                                    &device,
+                                    handle,
+                                    Some((plane, tiling)),
+                                )
+                            })
+                            .collect()
+                    } else {
+                        // VUID-VkImageMemoryRequirementsInfo2-image-01590
+                        smallvec![Self::get_memory_requirements(&device, handle, None)]
+                    }
+                })
         } else {
             smallvec![]
         };
@@ -380,6 +427,7 @@ impl RawImage {
             array_layers,
             mip_levels,
             initial_layout,
+            layout_policy,
             samples,
             tiling,
             usage,
@@ -387,6 +435,7 @@ impl RawImage {
             sharing,
             drm_format_modifier,
             external_memory_handle_types,
+            compression_control,
 
             memory_requirements,
             needs_destruction,
@@ -717,9 +766,18 @@ impl RawImage {
     ///   `ImageTiling::DrmFormatModifier`, then `allocations` must contain exactly
     ///   `self.drm_format_modifier().unwrap().1` elements.
     ///
+    /// If the device API version is at least 1.4, or the [`khr_maintenance6`] extension is
+    /// enabled, and binding the planes of a disjoint image fails, the returned error is that of
+    /// the plane that failed. To bind memory to
+    /// multiple images at once, with a separate result for each image, use
+    /// [`bind_memory_batched`] instead.
+    ///
     /// # Safety
     ///
     /// - The image must not already have memory bound to it.
+    ///
+    /// [`khr_maintenance6`]: crate::device::DeviceExtensions::khr_maintenance6
+    /// [`bind_memory_batched`]: Self::bind_memory_batched
     pub unsafe fn bind_memory(
         self,
         allocations: impl IntoIterator<Item = ResourceMemory>,
@@ -1080,11 +1138,17 @@ impl RawImage {
     ///
     /// - The image must be backed by suitable memory allocations.
     pub unsafe fn assume_bound(self) -> Image {
+        let layout = self.default_layout();
+        Image::from_raw(self, ImageMemory::External, layout)
+    }
+
+    /// Returns the layout that the image is tracked as being in once memory is bound to it.
+    fn default_layout(&self) -> ImageLayout {
         let usage = self
             .usage
             .difference(ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST);
 
-        let layout = if usage.intersects(ImageUsage::SAMPLED | ImageUsage::INPUT_ATTACHMENT)
+        if usage.intersects(ImageUsage::SAMPLED | ImageUsage::INPUT_ATTACHMENT)
             && usage
                 .difference(ImageUsage::SAMPLED | ImageUsage::INPUT_ATTACHMENT)
                 .is_empty()
@@ -1102,9 +1166,7 @@ impl RawImage {
             ImageLayout::DepthStencilAttachmentOptimal
         } else {
             ImageLayout::General
-        };
-
-        Image::from_raw(self, ImageMemory::External, layout)
+        }
     }
 
     /// # Safety
@@ -1200,19 +1262,12 @@ impl RawImage {
                 info_vk.p_next = <*mut _>::cast(plane_info_vk);
             }
 
-            if self.device.api_version() >= Version::V1_1 {
-                (fns.v1_1.bind_image_memory2)(
-                    self.device.handle(),
-                    infos_vk.len() as u32,
-                    infos_vk.as_ptr(),
-                )
-            } else {
-                (fns.khr_bind_memory2.bind_image_memory2_khr)(
-                    self.device.handle(),
-                    infos_vk.len() as u32,
-                    infos_vk.as_ptr(),
-                )
-            }
+            // With Vulkan 1.4 or `khr_maintenance6`, this is the error of the plane that failed to
+            // bind, rather than that of the call as a whole.
+            Self::bind_memory2(&self.device, &mut infos_vk)
+                .into_iter()
+                .find(|&result_vk| result_vk != ash::vk::Result::SUCCESS)
+                .unwrap_or(ash::vk::Result::SUCCESS)
         } else {
             debug_assert_eq!(allocations.len(), 1);
 
@@ -1233,35 +1288,192 @@ impl RawImage {
             return Err((VulkanError::from(err), self, allocations.into_iter()));
         }
 
-        let usage = self
-            .usage
-            .difference(ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST);
+        let layout = self.default_layout();
+        Ok(Image::from_raw(
+            self,
+            ImageMemory::Normal(allocations),
+            layout,
+        ))
+    }
 
-        let layout = if usage.intersects(ImageUsage::SAMPLED | ImageUsage::INPUT_ATTACHMENT)
-            && usage
-                .difference(ImageUsage::SAMPLED | ImageUsage::INPUT_ATTACHMENT)
-                .is_empty()
-        {
-            ImageLayout::ShaderReadOnlyOptimal
-        } else if usage.intersects(ImageUsage::COLOR_ATTACHMENT)
-            && usage.difference(ImageUsage::COLOR_ATTACHMENT).is_empty()
+    /// Binds device memory to multiple images at once.
+    ///
+    /// Each image is bound to a single allocation, so none of the images can have been created
+    /// with [`ImageCreateFlags::DISJOINT`]. Disjoint images must be bound with
+    /// [`bind_memory`](RawImage::bind_memory) instead.
+    ///
+    /// If the device API version is at least 1.1, or the [`khr_bind_memory2`] extension is
+    /// enabled, all bindings are performed with a single call to `vkBindImageMemory2`.
+    /// Otherwise, each image is bound individually.
+    ///
+    /// On success, one result is returned for each element of `items`, in the same order. If the
+    /// device API version is at least 1.4, the [`khr_maintenance6`] extension is enabled, or the
+    /// bindings are performed individually, the binding of each image succeeds or fails
+    /// independently, and an image that failed to bind is returned along with its allocation.
+    /// Otherwise, a failure of any binding leaves all of the images in an undefined state, so
+    /// the whole call fails and the images and their allocations are dropped.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the images in `items` were not all created from the same device.
+    ///
+    /// # Safety
+    ///
+    /// - The images must not already have memory bound to them.
+    ///
+    /// [`khr_bind_memory2`]: crate::device::DeviceExtensions::khr_bind_memory2
+    /// [`khr_maintenance6`]: crate::device::DeviceExtensions::khr_maintenance6
+    pub unsafe fn bind_memory_batched(
+        items: impl IntoIterator<Item = (RawImage, ResourceMemory)>,
+    ) -> Result<Vec<Result<Image, (VulkanError, RawImage, ResourceMemory)>>, Validated<VulkanError>>
+    {
+        let items: Vec<_> = items.into_iter().collect();
+        Self::validate_bind_memory_batched(&items)?;
+
+        Ok(unsafe { Self::bind_memory_batched_unchecked(items) }?)
+    }
+
+    fn validate_bind_memory_batched(
+        items: &[(RawImage, ResourceMemory)],
+    ) -> Result<(), Box<ValidationError>> {
+        let Some((first_image, _)) = items.first() else {
+            return Ok(());
+        };
+
+        for (index, (image, allocation)) in items.iter().enumerate() {
+            // VUID-vkBindImageMemory2-pBindInfos-parameter
+            assert_eq!(first_image.device(), image.device());
+
+            if image.flags.intersects(ImageCreateFlags::DISJOINT) {
+                return Err(Box::new(ValidationError {
+                    context: format!("items[{}].0.flags()", index).into(),
+                    problem: "contains `ImageCreateFlags::DISJOINT`".into(),
+                    ..Default::default()
+                }));
+            }
+
+            image
+                .validate_bind_memory(std::slice::from_ref(allocation))
+                .map_err(|err| err.add_context(format!("items[{}]", index)))?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn bind_memory_batched_unchecked(
+        items: impl IntoIterator<Item = (RawImage, ResourceMemory)>,
+    ) -> Result<Vec<Result<Image, (VulkanError, RawImage, ResourceMemory)>>, VulkanError> {
+        let items: Vec<_> = items.into_iter().collect();
+
+        let Some((first_image, _)) = items.first() else {
+            return Ok(Vec::new());
+        };
+        let device = first_image.device().clone();
+
+        if !(device.api_version() >= Version::V1_1 || device.enabled_extensions().khr_bind_memory2)
         {
-            ImageLayout::ColorAttachmentOptimal
-        } else if usage.intersects(ImageUsage::DEPTH_STENCIL_ATTACHMENT)
-            && usage
-                .difference(ImageUsage::DEPTH_STENCIL_ATTACHMENT)
-                .is_empty()
+            return Ok(items
+                .into_iter()
+                .map(|(image, allocation)| {
+                    unsafe { image.bind_memory_unchecked([allocation]) }.map_err(
+                        |(err, image, mut allocations)| (err, image, allocations.next().unwrap()),
+                    )
+                })
+                .collect());
+        }
+
+        let mut infos_vk: Vec<_> = items
+            .iter()
+            .map(|(image, allocation)| ash::vk::BindImageMemoryInfo {
+                image: image.handle,
+                memory: allocation.device_memory().handle(),
+                memory_offset: allocation.offset(),
+                ..Default::default()
+            })
+            .collect();
+
+        let results_vk = Self::bind_memory2(&device, &mut infos_vk);
+
+        // Without Vulkan 1.4 or `khr_maintenance6`, a failure leaves all of the images in an
+        // undefined state.
+        if !(device.api_version() >= Version::V1_4 || device.enabled_extensions().khr_maintenance6)
         {
-            ImageLayout::DepthStencilAttachmentOptimal
+            if let Some(&result_vk) = results_vk.first() {
+                result_vk.result().map_err(VulkanError::from)?;
+            }
+        }
+
+        Ok(items
+            .into_iter()
+            .enumerate()
+            .map(
+                |(index, (image, allocation))| match results_vk[index].result() {
+                    Ok(()) => {
+                        let layout = image.default_layout();
+                        Ok(Image::from_raw(
+                            image,
+                            ImageMemory::Normal(smallvec![allocation]),
+                            layout,
+                        ))
+                    }
+                    Err(err) => Err((VulkanError::from(err), image, allocation)),
+                },
+            )
+            .collect())
+    }
+
+    /// Calls `vkBindImageMemory2` with `infos_vk`, and returns the result of each binding.
+    ///
+    /// If the device API version is at least 1.4, or the [`khr_maintenance6`] extension is
+    /// enabled, the result of each binding is reported separately. Otherwise, every binding gets
+    /// the result of the call as a whole.
+    ///
+    /// [`khr_maintenance6`]: crate::device::DeviceExtensions::khr_maintenance6
+    unsafe fn bind_memory2(
+        device: &Device,
+        infos_vk: &mut [ash::vk::BindImageMemoryInfo],
+    ) -> SmallVec<[ash::vk::Result; 4]> {
+        let has_statuses =
+            device.api_version() >= Version::V1_4 || device.enabled_extensions().khr_maintenance6;
+        let mut results_vk: SmallVec<[_; 4]> = SmallVec::new();
+        let mut statuses_vk: SmallVec<[_; 4]> = SmallVec::new();
+
+        if has_statuses {
+            // The statuses start out as an error, in case the implementation doesn't write all of
+            // them when the call fails.
+            results_vk.resize(infos_vk.len(), ash::vk::Result::ERROR_UNKNOWN);
+            statuses_vk.extend(results_vk.iter_mut().map(|result_vk| {
+                ash::vk::BindMemoryStatusKHR {
+                    p_result: result_vk,
+                    ..Default::default()
+                }
+            }));
+
+            for (info_vk, status_vk) in infos_vk.iter_mut().zip(statuses_vk.iter_mut()) {
+                status_vk.p_next = info_vk.p_next;
+                info_vk.p_next = <*mut _>::cast(status_vk);
+            }
+        }
+
+        let fns = device.fns();
+        let result_vk = if device.api_version() >= Version::V1_1 {
+            (fns.v1_1.bind_image_memory2)(device.handle(), infos_vk.len() as u32, infos_vk.as_ptr())
         } else {
-            ImageLayout::General
+            (fns.khr_bind_memory2.bind_image_memory2_khr)(
+                device.handle(),
+                infos_vk.len() as u32,
+                infos_vk.as_ptr(),
+            )
         };
 
-        Ok(Image::from_raw(
-            self,
-            ImageMemory::Normal(allocations),
-            layout,
-        ))
+        // If the call as a whole succeeded, then so did every binding.
+        if !has_statuses || result_vk == ash::vk::Result::SUCCESS {
+            results_vk.clear();
+            results_vk.resize(infos_vk.len(), result_vk);
+        }
+
+        results_vk
     }
 
     /// Returns the memory requirements for this image.
@@ -1374,6 +1586,18 @@ impl RawImage {
         self.external_memory_handle_types
     }
 
+    /// Returns the layout policy that the image was created with.
+    #[inline]
+    pub fn layout_policy(&self) -> ImageLayoutPolicy {
+        self.layout_policy
+    }
+
+    /// Returns the compression control that the image was created with.
+    #[inline]
+    pub fn compression_control(&self) -> Option<&ImageCompressionControl> {
+        self.compression_control.as_ref()
+    }
+
     /// Returns an `ImageSubresourceLayers` covering the first mip level of the image. All aspects
     /// of the image are selected, or `plane0` if the image is multi-planar.
     #[inline]
@@ -1672,6 +1896,158 @@ impl RawImage {
             },
         )
     }
+
+    /// Queries the compression that was applied to a single subresource of the image.
+    ///
+    /// Compression may be disabled by the implementation for some combinations of image
+    /// parameters, even if it was not disabled in [`ImageCreateInfo::compression_control`].
+    /// Unlike [`subresource_layout`], this can be used with images of any tiling.
+    ///
+    /// The [`ext_image_compression_control`] extension must be enabled on the device.
+    ///
+    /// [`subresource_layout`]: Self::subresource_layout
+    /// [`ext_image_compression_control`]: DeviceExtensions::ext_image_compression_control
+    pub fn compression_properties(
+        &self,
+        aspect: ImageAspect,
+        mip_level: u32,
+        array_layer: u32,
+    ) -> Result<ImageCompressionProperties, Box<ValidationError>> {
+        self.validate_compression_properties(aspect, mip_level, array_layer)?;
+
+        unsafe { Ok(self.compression_properties_unchecked(aspect, mip_level, array_layer)) }
+    }
+
+    fn validate_compression_properties(
+        &self,
+        aspect: ImageAspect,
+        mip_level: u32,
+        array_layer: u32,
+    ) -> Result<(), Box<ValidationError>> {
+        if !self
+            .device
+            .enabled_extensions()
+            .ext_image_compression_control
+        {
+            return Err(Box::new(ValidationError {
+                requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceExtension(
+                    "ext_image_compression_control",
+                )])]),
+                ..Default::default()
+            }));
+        }
+
+        aspect.validate_device(&self.device).map_err(|err| {
+            err.add_context("aspect")
+                .set_vuids(&["VUID-VkImageSubresource-aspectMask-parameter"])
+        })?;
+
+        if mip_level >= self.mip_levels {
+            return Err(Box::new(ValidationError {
+                context: "mip_level".into(),
+                problem: "is greater than the number of mip levels in the image".into(),
+                vuids: &["VUID-vkGetImageSubresourceLayout2KHR-mipLevel-01716"],
+                ..Default::default()
+            }));
+        }
+
+        if array_layer >= self.array_layers {
+            return Err(Box::new(ValidationError {
+                context: "array_layer".into(),
+                problem: "is greater than the number of array layers in the image".into(),
+                vuids: &["VUID-vkGetImageSubresourceLayout2KHR-arrayLayer-01717"],
+                ..Default::default()
+            }));
+        }
+
+        if let Some((_, drm_format_modifier_plane_count)) = self.drm_format_modifier {
+            let memory_planes = [
+                ImageAspect::MemoryPlane0,
+                ImageAspect::MemoryPlane1,
+                ImageAspect::MemoryPlane2,
+                ImageAspect::MemoryPlane3,
+            ];
+
+            if !memory_planes[..drm_format_modifier_plane_count as usize].contains(&aspect) {
+                return Err(Box::new(ValidationError {
+                    problem: "the image has a DRM format modifier, but `aspect` is not one of \
+                        the memory planes of the image"
+                        .into(),
+                    vuids: &["VUID-vkGetImageSubresourceLayout2KHR-tiling-02271"],
+                    ..Default::default()
+                }));
+            }
+        } else if !self.format.planes().is_empty() {
+            let planes = [
+                ImageAspect::Plane0,
+                ImageAspect::Plane1,
+                ImageAspect::Plane2,
+            ];
+
+            if !planes[..self.format.planes().len()].contains(&aspect) {
+                return Err(Box::new(ValidationError {
+                    problem: "`self.format()` is a multi-planar format, but `aspect` is not one \
+                        of the planes of the format"
+                        .into(),
+                    vuids: &["VUID-vkGetImageSubresourceLayout2KHR-tiling-08717"],
+                    ..Default::default()
+                }));
+            }
+        } else if !self.format.aspects().contains(aspect.into()) {
+            return Err(Box::new(ValidationError {
+                problem: "`self.format()` does not have the aspect `aspect`".into(),
+                vuids: &[
+                    "VUID-vkGetImageSubresourceLayout2KHR-format-08886",
+                    "VUID-vkGetImageSubresourceLayout2KHR-format-04462",
+                    "VUID-vkGetImageSubresourceLayout2KHR-format-04463",
+                    "VUID-vkGetImageSubresourceLayout2KHR-format-04464",
+                ],
+                ..Default::default()
+            }));
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn compression_properties_unchecked(
+        &self,
+        aspect: ImageAspect,
+        mip_level: u32,
+        array_layer: u32,
+    ) -> ImageCompressionProperties {
+        let fns = self.device.fns();
+
+        let subresource_vk = ash::vk::ImageSubresource2KHR {
+            image_subresource: ash::vk::ImageSubresource {
+                aspect_mask: aspect.into(),
+                mip_level,
+                array_layer,
+            },
+            ..Default::default()
+        };
+
+        let mut compression_properties_vk = ash::vk::ImageCompressionPropertiesEXT::default();
+        let mut layout_vk = ash::vk::SubresourceLayout2KHR {
+            p_next: <*mut _>::cast(&mut compression_properties_vk),
+            ..Default::default()
+        };
+
+        (fns.ext_image_compression_control
+            .get_image_subresource_layout2_ext)(
+            self.device.handle(),
+            self.handle,
+            &subresource_vk,
+            &mut layout_vk,
+        );
+
+        ImageCompressionProperties {
+            flags: compression_properties_vk.image_compression_flags.into(),
+            fixed_rate_flags: compression_properties_vk
+                .image_compression_fixed_rate_flags
+                .into(),
+        }
+    }
 }
 
 impl Drop for RawImage {
@@ -1811,6 +2187,15 @@ pub struct ImageCreateInfo {
     /// The default value is [`ImageLayout::Undefined`].
     pub initial_layout: ImageLayout,
 
+    /// The layout that descriptors use for the image, if they are written without specifying a
+    /// layout.
+    ///
+    /// This is not passed to Vulkan. It only affects the layouts that Vulkano chooses, which also
+    /// determines which layout transitions the image needs when it is used in a command buffer.
+    ///
+    /// The default value is [`ImageLayoutPolicy::Optimal`].
+    pub layout_policy: ImageLayoutPolicy,
+
     /// A list of possible Linux DRM format modifiers that the image may be created with. If
     /// `tiling` is [`ImageTiling::DrmFormatModifier`], then at least one DRM format modifier must
     /// be provided. Otherwise, this must be empty.
@@ -1853,6 +2238,21 @@ pub struct ImageCreateInfo {
     /// The default value is empty.
     pub external_memory_handle_types: ExternalMemoryHandleTypes,
 
+    /// Controls the compression that is applied to the image.
+    ///
+    /// If this is `Some`, the
+    /// [`ext_image_compression_control`](DeviceExtensions::ext_image_compression_control)
+    /// extension must be enabled on the device, and the
+    /// [`image_compression_control`](DeviceFeatures::image_compression_control)
+    /// feature must be enabled as well. This must be `None` if `drm_format_modifier_plane_layouts`
+    /// is not empty.
+    ///
+    /// The compression that was actually applied can be queried with
+    /// [`RawImage::compression_properties`].
+    ///
+    /// The default value is `None`.
+    pub compression_control: Option<ImageCompressionControl>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -1873,15 +2273,107 @@ impl Default for ImageCreateInfo {
             stencil_usage: None,
             sharing: Sharing::Exclusive,
             initial_layout: ImageLayout::Undefined,
+            layout_policy: ImageLayoutPolicy::Optimal,
             external_memory_handle_types: ExternalMemoryHandleTypes::empty(),
             drm_format_modifiers: Vec::new(),
             drm_format_modifier_plane_layouts: Vec::new(),
+            compression_control: None,
             _ne: crate::NonExhaustive(()),
         }
     }
 }
 
 impl ImageCreateInfo {
+    /// Shortcut for creating a 2D image that is sampled in shaders, with a full chain of mip
+    /// levels.
+    ///
+    /// The usage is `SAMPLED | TRANSFER_SRC | TRANSFER_DST`, so that the image can be uploaded
+    /// to, and the lower mip levels can be generated by blitting each one from the previous one.
+    #[inline]
+    pub fn sampled_2d(format: Format, extent: [u32; 2]) -> Self {
+        let extent = [extent[0], extent[1], 1];
+
+        Self {
+            format,
+            extent,
+            mip_levels: max_mip_levels(extent),
+            usage: ImageUsage::SAMPLED | ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST,
+            ..Default::default()
+        }
+    }
+
+    /// Shortcut for creating a 2D image that is sampled in shaders, with only one mip level.
+    ///
+    /// The usage is `SAMPLED | TRANSFER_DST`, so that the image can be uploaded to.
+    #[inline]
+    pub fn sampled_2d_no_mipmap(format: Format, extent: [u32; 2]) -> Self {
+        Self {
+            format,
+            extent: [extent[0], extent[1], 1],
+            usage: ImageUsage::SAMPLED | ImageUsage::TRANSFER_DST,
+            ..Default::default()
+        }
+    }
+
+    /// Shortcut for creating a 2D image that is used as a color attachment.
+    ///
+    /// The usage is `COLOR_ATTACHMENT`.
+    #[inline]
+    pub fn color_attachment(format: Format, extent: [u32; 2]) -> Self {
+        Self {
+            format,
+            extent: [extent[0], extent[1], 1],
+            usage: ImageUsage::COLOR_ATTACHMENT,
+            ..Default::default()
+        }
+    }
+
+    /// Shortcut for creating a 2D image that is used as a depth/stencil attachment, and whose
+    /// contents are not needed outside of a render pass.
+    ///
+    /// The usage is `DEPTH_STENCIL_ATTACHMENT | TRANSIENT_ATTACHMENT`. If the image needs to be
+    /// accessed in other ways, such as being sampled as a shadow map, then the usage must be
+    /// overridden.
+    #[inline]
+    pub fn depth_stencil_attachment(format: Format, extent: [u32; 2]) -> Self {
+        Self {
+            format,
+            extent: [extent[0], extent[1], 1],
+            usage: ImageUsage::DEPTH_STENCIL_ATTACHMENT | ImageUsage::TRANSIENT_ATTACHMENT,
+            ..Default::default()
+        }
+    }
+
+    /// Shortcut for creating a 2D image that is used as a storage image.
+    ///
+    /// The usage is `STORAGE`.
+    #[inline]
+    pub fn storage_2d(format: Format, extent: [u32; 2]) -> Self {
+        Self {
+            format,
+            extent: [extent[0], extent[1], 1],
+            usage: ImageUsage::STORAGE,
+            ..Default::default()
+        }
+    }
+
+    /// Shortcut for creating an image that can be viewed as a cube map, with faces of `size` by
+    /// `size` texels.
+    ///
+    /// The image has the `CUBE_COMPATIBLE` flag and 6 array layers, one for each face. The usage
+    /// is `SAMPLED | TRANSFER_DST`, so that the faces can be uploaded to.
+    #[inline]
+    pub fn cubemap(format: Format, size: u32) -> Self {
+        Self {
+            flags: ImageCreateFlags::CUBE_COMPATIBLE,
+            format,
+            extent: [size, size, 1],
+            array_layers: 6,
+            usage: ImageUsage::SAMPLED | ImageUsage::TRANSFER_DST,
+            ..Default::default()
+        }
+    }
+
     pub(crate) fn validate(&self, device: &Device) -> Result<(), Box<ValidationError>> {
         let &Self {
             flags,
@@ -1897,9 +2389,11 @@ impl ImageCreateInfo {
             stencil_usage,
             ref sharing,
             initial_layout,
+            layout_policy: _,
             ref drm_format_modifiers,
             ref drm_format_modifier_plane_layouts,
             external_memory_handle_types,
+            ref compression_control,
             _ne: _,
         } = self;
 
@@ -2666,15 +3160,13 @@ impl ImageCreateInfo {
                 let queue_family_count = physical_device.queue_family_properties().len() as u32;
 
                 for (index, &queue_family_index) in queue_family_indices.iter().enumerate() {
-                    if queue_family_indices[..index].contains(&queue_family_index) {
+                    if let Some(other_index) = queue_family_indices[..index]
+                        .iter()
+                        .position(|&other| other == queue_family_index)
+                    {
                         return Err(Box::new(ValidationError {
-                            context: "queue_family_indices".into(),
-                            problem: format!(
-                                "the queue family index in the list at index {} is contained in \
-                                the list more than once",
-                                index,
-                            )
-                            .into(),
+                            context: format!("sharing[{}]", index).into(),
+                            problem: format!("is equal to `sharing[{}]`", other_index).into(),
                             vuids: &["VUID-VkImageCreateInfo-sharingMode-01420"],
                             ..Default::default()
                         }));
@@ -2855,6 +3347,45 @@ impl ImageCreateInfo {
             }
         }
 
+        if let Some(compression_control) = compression_control {
+            if !device.enabled_extensions().ext_image_compression_control {
+                return Err(Box::new(ValidationError {
+                    context: "compression_control".into(),
+                    problem: "is `Some`".into(),
+                    requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceExtension(
+                        "ext_image_compression_control",
+                    )])]),
+                    vuids: &["VUID-VkImageCreateInfo-pNext-pNext"],
+                }));
+            }
+
+            if !device.enabled_features().image_compression_control {
+                return Err(Box::new(ValidationError {
+                    context: "compression_control".into(),
+                    problem: "is `Some`".into(),
+                    requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceFeature(
+                        "image_compression_control",
+                    )])]),
+                    vuids: &["VUID-VkImageCreateInfo-pNext-06746"],
+                }));
+            }
+
+            compression_control
+                .validate(device)
+                .map_err(|err| err.add_context("compression_control"))?;
+            compression_control.validate_plane_count(format)?;
+
+            if !drm_format_modifier_plane_layouts.is_empty() {
+                return Err(Box::new(ValidationError {
+                    problem: "`compression_control` is `Some`, but \
+                        `drm_format_modifier_plane_layouts` is not empty"
+                        .into(),
+                    vuids: &["VUID-VkImageCreateInfo-pNext-06745"],
+                    ..Default::default()
+                }));
+            }
+        }
+
         /*
             Some device limits can be exceeded, but only for particular image configurations, which
             must be queried with `image_format_properties`. See:
@@ -3012,14 +3543,24 @@ impl ImageCreateInfo {
 mod tests {
     use super::{ImageCreateInfo, ImageUsage, RawImage};
     use crate::{
-        format::Format,
+        device::{DeviceExtensions, DeviceFeatures},
+        format::{Format, FormatFeatures},
         image::{
-            ImageAspect, ImageAspects, ImageCreateFlags, ImageSubresourceRange, ImageType,
-            SampleCount, SubresourceRangeIterator,
+            ImageAspect, ImageAspects, ImageCompressionControl, ImageCompressionFlags,
+            ImageCreateFlags, ImageMemory, ImageSubresourceRange, ImageType, SampleCount,
+            SubresourceRangeIterator,
+        },
+        memory::{
+            allocator::{
+                AllocationCreateInfo, AllocationType, MemoryAllocator, StandardMemoryAllocator,
+            },
+            ResourceMemory,
         },
         DeviceSize, Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError,
+        VulkanObject,
     };
     use smallvec::SmallVec;
+    use std::sync::Arc;
 
     #[test]
     fn create_sampled() {
@@ -3038,6 +3579,201 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn bind_memory_batched() {
+        let (device, _) = gfx_dev_and_queue!();
+        let allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+        let items: Vec<_> = (1..=3)
+            .map(|index| {
+                let image = RawImage::new(
+                    device.clone(),
+                    ImageCreateInfo {
+                        format: Format::R8G8B8A8_UNORM,
+                        extent: [16 * index, 16, 1],
+                        usage: ImageUsage::SAMPLED,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+                let allocation = allocator
+                    .allocate(
+                        image.memory_requirements()[0],
+                        AllocationType::NonLinear,
+                        AllocationCreateInfo::default(),
+                        None,
+                    )
+                    .unwrap();
+                let allocation =
+                    unsafe { ResourceMemory::from_allocation(allocator.clone(), allocation) };
+
+                (image, allocation)
+            })
+            .collect();
+
+        let images = unsafe { RawImage::bind_memory_batched(items) }.unwrap();
+        assert_eq!(images.len(), 3);
+
+        for (index, image) in images.into_iter().enumerate() {
+            assert_eq!(image.unwrap().extent(), [16 * (index as u32 + 1), 16, 1]);
+        }
+
+        assert!(unsafe { RawImage::bind_memory_batched([]) }
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn bind_memory_batched_statuses() {
+        // With `khr_maintenance6`, each binding gets its own status.
+        let Some((device, _)) = crate::testing::test_device(
+            &DeviceExtensions {
+                khr_maintenance6: true,
+                ..DeviceExtensions::empty()
+            },
+            &DeviceFeatures::empty(),
+        ) else {
+            return;
+        };
+        let allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+        let mut items = Vec::new();
+        let mut infos_vk = Vec::new();
+
+        for _ in 0..3 {
+            let image = RawImage::new(
+                device.clone(),
+                ImageCreateInfo {
+                    format: Format::R8G8B8A8_UNORM,
+                    extent: [16, 16, 1],
+                    usage: ImageUsage::SAMPLED,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            let allocation = allocator
+                .allocate(
+                    image.memory_requirements()[0],
+                    AllocationType::NonLinear,
+                    AllocationCreateInfo::default(),
+                    None,
+                )
+                .unwrap();
+            let allocation =
+                unsafe { ResourceMemory::from_allocation(allocator.clone(), allocation) };
+
+            infos_vk.push(ash::vk::BindImageMemoryInfo {
+                image: image.handle(),
+                memory: allocation.device_memory().handle(),
+                memory_offset: allocation.offset(),
+                ..Default::default()
+            });
+            items.push((image, allocation));
+        }
+
+        let results_vk = unsafe { RawImage::bind_memory2(&device, &mut infos_vk) };
+        assert_eq!(results_vk.as_slice(), [ash::vk::Result::SUCCESS; 3]);
+    }
+
+    #[test]
+    fn bind_memory_disjoint() {
+        let (device, _) = gfx_dev_and_queue!(sampler_ycbcr_conversion);
+        let allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+        let format = Format::G8_B8R8_2PLANE_420_UNORM;
+        let format_properties = device.physical_device().format_properties(format).unwrap();
+
+        if !format_properties
+            .optimal_tiling_features
+            .intersects(FormatFeatures::DISJOINT)
+        {
+            return;
+        }
+
+        let create_image = || {
+            let image = RawImage::new(
+                device.clone(),
+                ImageCreateInfo {
+                    flags: ImageCreateFlags::DISJOINT,
+                    format,
+                    extent: [16, 16, 1],
+                    usage: ImageUsage::SAMPLED,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            let allocations: Vec<_> = image
+                .memory_requirements()
+                .iter()
+                .map(|&memory_requirements| {
+                    let allocation = allocator
+                        .allocate(
+                            memory_requirements,
+                            AllocationType::NonLinear,
+                            AllocationCreateInfo::default(),
+                            None,
+                        )
+                        .unwrap();
+
+                    unsafe { ResourceMemory::from_allocation(allocator.clone(), allocation) }
+                })
+                .collect();
+
+            (image, allocations)
+        };
+
+        // Each plane is bound to its own allocation.
+        let (image, allocations) = create_image();
+        assert_eq!(allocations.len(), 2);
+        let image = unsafe { image.bind_memory(allocations) }.unwrap();
+        assert!(matches!(
+            image.memory(),
+            ImageMemory::Normal(allocations) if allocations.len() == 2,
+        ));
+
+        // Disjoint images can't be bound in a batch.
+        let (image, mut allocations) = create_image();
+        assert!(matches!(
+            unsafe { RawImage::bind_memory_batched([(image, allocations.remove(0))]) },
+            Err(Validated::ValidationError(_)),
+        ));
+    }
+
+    #[test]
+    fn create_info_shortcuts() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let create_info = ImageCreateInfo::sampled_2d(Format::R8G8B8A8_UNORM, [256, 100]);
+        assert_eq!(create_info.extent, [256, 100, 1]);
+        assert_eq!(create_info.mip_levels, 9);
+        let image = RawImage::new(device.clone(), create_info).unwrap();
+        assert!(image.usage().contains(ImageUsage::TRANSFER_DST));
+
+        let create_info = ImageCreateInfo::sampled_2d_no_mipmap(Format::R8G8B8A8_UNORM, [256, 100]);
+        assert_eq!(create_info.mip_levels, 1);
+        RawImage::new(device.clone(), create_info).unwrap();
+
+        let create_info = ImageCreateInfo::cubemap(Format::R8G8B8A8_UNORM, 64);
+        assert_eq!(create_info.extent, [64, 64, 1]);
+        let image = RawImage::new(device.clone(), create_info).unwrap();
+        assert!(image.flags().intersects(ImageCreateFlags::CUBE_COMPATIBLE));
+        assert_eq!(image.array_layers(), 6);
+
+        RawImage::new(
+            device.clone(),
+            ImageCreateInfo::color_attachment(Format::R8G8B8A8_UNORM, [32, 32]),
+        )
+        .unwrap();
+        RawImage::new(
+            device,
+            ImageCreateInfo {
+                usage: ImageUsage::STORAGE | ImageUsage::TRANSFER_SRC,
+                ..ImageCreateInfo::storage_2d(Format::R8G8B8A8_UNORM, [32, 32])
+            },
+        )
+        .unwrap();
+    }
+
     #[test]
     fn create_transient() {
         let (device, _) = gfx_dev_and_queue!();
@@ -3129,6 +3865,108 @@ mod tests {
         };
     }
 
+    #[test]
+    fn compression_control_requires_extension() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let res = RawImage::new(
+            device.clone(),
+            ImageCreateInfo {
+                compression_control: Some(ImageCompressionControl {
+                    flags: ImageCompressionFlags::FIXED_RATE_DEFAULT,
+                    ..Default::default()
+                }),
+                ..ImageCreateInfo::color_attachment(Format::R8G8B8A8_UNORM, [32, 32])
+            },
+        );
+
+        match res {
+            Err(Validated::ValidationError(err))
+                if matches!(
+                    *err,
+                    ValidationError {
+                        requires_one_of: RequiresOneOf([RequiresAllOf([
+                            Requires::DeviceExtension("ext_image_compression_control")
+                        ])],),
+                        ..
+                    }
+                ) => {}
+            _ => panic!(),
+        };
+
+        let image = RawImage::new(
+            device,
+            ImageCreateInfo::color_attachment(Format::R8G8B8A8_UNORM, [32, 32]),
+        )
+        .unwrap();
+        assert!(image
+            .compression_properties(ImageAspect::Color, 0, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn compression_control() {
+        let enabled_extensions = DeviceExtensions {
+            ext_image_compression_control: true,
+            ..DeviceExtensions::empty()
+        };
+        let create_info = ImageCreateInfo {
+            compression_control: Some(ImageCompressionControl {
+                flags: ImageCompressionFlags::DISABLED,
+                ..Default::default()
+            }),
+            ..ImageCreateInfo::color_attachment(Format::R8G8B8A8_UNORM, [32, 32])
+        };
+
+        {
+            let Some((device, _)) =
+                crate::testing::test_device(&enabled_extensions, &DeviceFeatures::empty())
+            else {
+                return;
+            };
+
+            match RawImage::new(device, create_info.clone()) {
+                Err(Validated::ValidationError(err))
+                    if matches!(
+                        *err,
+                        ValidationError {
+                            requires_one_of: RequiresOneOf([RequiresAllOf([
+                                Requires::DeviceFeature("image_compression_control")
+                            ])],),
+                            vuids: ["VUID-VkImageCreateInfo-pNext-06746"],
+                            ..
+                        }
+                    ) => {}
+                _ => panic!(),
+            };
+        }
+
+        {
+            let Some((device, _)) = crate::testing::test_device(
+                &enabled_extensions,
+                &DeviceFeatures {
+                    image_compression_control: true,
+                    ..DeviceFeatures::empty()
+                },
+            ) else {
+                return;
+            };
+
+            device
+                .image_memory_requirements(create_info.clone(), None)
+                .unwrap();
+
+            let image = RawImage::new(device, create_info).unwrap();
+            assert_eq!(
+                image.compression_control().unwrap().flags,
+                ImageCompressionFlags::DISABLED,
+            );
+            image
+                .compression_properties(ImageAspect::Color, 0, 0)
+                .unwrap();
+        }
+    }
+
     #[test]
     fn compressed_not_color_attachment() {
         let (device, _) = gfx_dev_and_queue!();
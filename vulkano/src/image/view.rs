@@ -8,8 +8,12 @@
 //!
 //! [the parent module-level documentation]: super
 
-use super::{mip_level_extent, Image, ImageFormatInfo, ImageSubresourceRange, ImageUsage};
+use super::{
+    mip_level_extent, Image, ImageFormatInfo, ImageLayout, ImageLayoutPolicy,
+    ImageSubresourceRange, ImageUsage,
+};
 use crate::{
+    descriptor_set::layout::DescriptorType,
     device::{Device, DeviceOwned, DeviceOwnedDebugWrapper},
     format::{ChromaSampling, Format, FormatFeatures},
     image::{
@@ -634,6 +638,60 @@ impl ImageView {
         Self::new(image, create_info)
     }
 
+    /// Creates six `ImageViewType::Dim2d` views of the faces of a cube in `image`.
+    ///
+    /// The cube with index `base_cube_index` consists of the array layers
+    /// `base_cube_index * 6..(base_cube_index + 1) * 6` of `image`, and element `i` of the
+    /// returned array is a view of array layer `base_cube_index * 6 + i`. The faces are in the
+    /// order +X, -X, +Y, -Y, +Z, -Z. Each view covers all mip levels and aspects of the image.
+    ///
+    /// This is useful for rendering to each face of a cubemap separately, by using each view as
+    /// an attachment of a framebuffer. To render to all faces at once using multiview, create a
+    /// single `ImageViewType::Dim2dArray` view of the six layers instead.
+    pub fn cube_faces(
+        image: &Arc<Image>,
+        base_cube_index: u32,
+    ) -> Result<[Arc<ImageView>; 6], Validated<VulkanError>> {
+        let base_array_layer = base_cube_index
+            .checked_mul(6)
+            .filter(|&base_array_layer| {
+                base_array_layer
+                    .checked_add(6)
+                    .map_or(false, |end| end <= image.array_layers())
+            })
+            .ok_or_else(|| {
+                Box::new(ValidationError {
+                    context: "base_cube_index".into(),
+                    problem: format!(
+                        "the array layers of the cube, `base_cube_index * 6..\
+                        (base_cube_index + 1) * 6`, are not all less than \
+                        `image.array_layers()` ({})",
+                        image.array_layers(),
+                    )
+                    .into(),
+                    ..Default::default()
+                })
+            })?;
+
+        let mut faces: SmallVec<[_; 6]> = SmallVec::new();
+
+        for array_layer in base_array_layer..base_array_layer + 6 {
+            faces.push(ImageView::new(
+                image.clone(),
+                ImageViewCreateInfo {
+                    view_type: ImageViewType::Dim2d,
+                    subresource_range: ImageSubresourceRange {
+                        array_layers: array_layer..array_layer + 1,
+                        ..image.subresource_range()
+                    },
+                    ..ImageViewCreateInfo::from_image(image)
+                },
+            )?);
+        }
+
+        Ok(faces.into_inner().unwrap())
+    }
+
     /// Creates a new `ImageView` from a raw object handle.
     ///
     /// # Safety
@@ -738,12 +796,41 @@ impl ImageView {
         &self.subresource_range
     }
 
+    /// Returns the extent of the base mip level of the view's subresource range.
+    ///
+    /// This is the size of the view when it is used as a framebuffer or rendering attachment.
+    #[inline]
+    pub fn extent(&self) -> [u32; 3] {
+        mip_level_extent(self.image.extent(), self.subresource_range.mip_levels.start).unwrap()
+    }
+
     /// Returns the usage of the image view.
     #[inline]
     pub fn usage(&self) -> ImageUsage {
         self.usage
     }
 
+    /// Returns the layout that a descriptor of type `descriptor_type` uses for the image view, if
+    /// the descriptor write doesn't specify a layout.
+    ///
+    /// This depends on the [`layout_policy`] of the image. Returns [`ImageLayout::Undefined`] if
+    /// `descriptor_type` is not a descriptor type for images.
+    ///
+    /// [`layout_policy`]: Image::layout_policy
+    pub fn descriptor_usage_layout(&self, descriptor_type: DescriptorType) -> ImageLayout {
+        match descriptor_type {
+            DescriptorType::CombinedImageSampler | DescriptorType::SampledImage => {
+                match self.image.layout_policy() {
+                    ImageLayoutPolicy::Optimal => ImageLayout::ShaderReadOnlyOptimal,
+                    ImageLayoutPolicy::General => ImageLayout::General,
+                }
+            }
+            // Input attachments must be in the same layout as in the render pass, so the policy
+            // doesn't apply to them.
+            _ => descriptor_type.default_image_layout(),
+        }
+    }
+
     /// Returns the sampler YCbCr conversion that this image view was created with, if any.
     #[inline]
     pub fn sampler_ycbcr_conversion(&self) -> Option<&Arc<SamplerYcbcrConversion>> {
@@ -974,9 +1061,16 @@ impl ImageViewCreateInfo {
             ImageViewType::Cube => {
                 if subresource_range.array_layers.len() != 6 {
                     return Err(Box::new(ValidationError {
-                        problem: "`view_type` is `ImageViewType::Cube`, but \
-                            the length of `subresource_range.array_layers` is not 6"
-                            .into(),
+                        problem: format!(
+                            "`view_type` is `ImageViewType::Cube`, but \
+                            the length of `subresource_range.array_layers` is {} instead of 6; \
+                            a cube view uses one array layer for each of the six faces, so for \
+                            this `array_layers.start`, `array_layers` must be `{}..{}`",
+                            subresource_range.array_layers.len(),
+                            subresource_range.array_layers.start,
+                            subresource_range.array_layers.start.saturating_add(6),
+                        )
+                        .into(),
                         vuids: &["VUID-VkImageViewCreateInfo-viewType-02960"],
                         ..Default::default()
                     }));
@@ -996,10 +1090,14 @@ impl ImageViewCreateInfo {
 
                 if subresource_range.array_layers.len() % 6 != 0 {
                     return Err(Box::new(ValidationError {
-                        problem: "`view_type` is `ImageViewType::CubeArray`, but \
-                            the length of `subresource_range.array_layers` is not \
-                            a multiple of 6"
-                            .into(),
+                        problem: format!(
+                            "`view_type` is `ImageViewType::CubeArray`, but \
+                            the length of `subresource_range.array_layers` is {}, which is not \
+                            a multiple of 6; each cube in a cube array view uses six \
+                            consecutive array layers, one for each face",
+                            subresource_range.array_layers.len(),
+                        )
+                        .into(),
                         vuids: &["VUID-VkImageViewCreateInfo-viewType-02961"],
                         ..Default::default()
                     }));
@@ -1190,3 +1288,120 @@ unsafe fn get_format_features(view_format: Format, image: &Image) -> FormatFeatu
 
     format_features
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ImageView, ImageViewCreateInfo, ImageViewType};
+    use crate::{
+        descriptor_set::layout::DescriptorType,
+        format::Format,
+        image::{
+            Image, ImageCreateFlags, ImageCreateInfo, ImageLayout, ImageLayoutPolicy,
+            ImageSubresourceRange, ImageUsage,
+        },
+        memory::allocator::{AllocationCreateInfo, StandardMemoryAllocator},
+        Validated,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn descriptor_usage_layout() {
+        let (device, _) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device));
+
+        let create_view = |layout_policy| {
+            let image = Image::new(
+                memory_allocator.clone(),
+                ImageCreateInfo {
+                    format: Format::R8G8B8A8_UNORM,
+                    extent: [16, 16, 1],
+                    usage: ImageUsage::STORAGE | ImageUsage::SAMPLED | ImageUsage::INPUT_ATTACHMENT,
+                    layout_policy,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+            )
+            .unwrap();
+
+            ImageView::new_default(image).unwrap()
+        };
+
+        let view = create_view(ImageLayoutPolicy::Optimal);
+
+        assert_eq!(
+            view.descriptor_usage_layout(DescriptorType::SampledImage),
+            ImageLayout::ShaderReadOnlyOptimal,
+        );
+        assert_eq!(
+            view.descriptor_usage_layout(DescriptorType::StorageImage),
+            ImageLayout::General,
+        );
+
+        let view = create_view(ImageLayoutPolicy::General);
+
+        assert_eq!(
+            view.descriptor_usage_layout(DescriptorType::SampledImage),
+            ImageLayout::General,
+        );
+        assert_eq!(
+            view.descriptor_usage_layout(DescriptorType::InputAttachment),
+            ImageLayout::ShaderReadOnlyOptimal,
+        );
+    }
+
+    #[test]
+    fn cube_faces() {
+        let (device, _) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device));
+
+        let image = Image::new(
+            memory_allocator,
+            ImageCreateInfo {
+                flags: ImageCreateFlags::CUBE_COMPATIBLE,
+                format: Format::R8G8B8A8_UNORM,
+                extent: [16, 16, 1],
+                array_layers: 12,
+                usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::SAMPLED,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+        )
+        .unwrap();
+
+        let faces = ImageView::cube_faces(&image, 1).unwrap();
+
+        for (face, view) in faces.iter().enumerate() {
+            assert_eq!(view.view_type(), ImageViewType::Dim2d);
+            assert_eq!(
+                view.subresource_range().array_layers,
+                6 + face as u32..7 + face as u32,
+            );
+        }
+
+        assert!(matches!(
+            ImageView::cube_faces(&image, 2),
+            Err(Validated::ValidationError(_)),
+        ));
+
+        let err = ImageView::new(
+            image.clone(),
+            ImageViewCreateInfo {
+                view_type: ImageViewType::Cube,
+                subresource_range: ImageSubresourceRange {
+                    array_layers: 2..10,
+                    ..image.subresource_range()
+                },
+                ..ImageViewCreateInfo::from_image(&image)
+            },
+        )
+        .unwrap_err();
+
+        match err {
+            Validated::ValidationError(err) => {
+                assert!(err.problem.contains("is 8 instead of 6"));
+                assert!(err.problem.contains("`2..8`"));
+            }
+            _ => panic!(),
+        }
+    }
+}
@@ -5,8 +5,9 @@
 //! invalid API usages or performance problems by calling this callback. The callback can also
 //! be called by the driver or by whatever intermediate layer is activated.
 //!
-//! Note that the vulkano library can also emit messages to warn you about performance issues.
-//! TODO: ^ that's not the case yet, need to choose whether we keep this idea
+//! Note that the vulkano library can also emit messages to warn you about performance issues, if
+//! this is enabled with [`Device::set_performance_warnings`]. The message ID names of these
+//! messages start with `Vulkano-`.
 //!
 //! # Examples
 //!
@@ -34,17 +35,20 @@
 //! Note that you must keep the `_callback` object alive for as long as you want your callback to
 //! be callable. If you don't store the return value of `DebugUtilsMessenger`'s constructor in a
 //! variable, it will be immediately destroyed and your callback will not work.
+//!
+//! [`Device::set_performance_warnings`]: crate::device::Device::set_performance_warnings
 
-use super::{Instance, InstanceExtensions};
+use super::{Instance, InstanceCreateInfo, InstanceExtensions};
 use crate::{
     macros::{vulkan_bitflags, vulkan_enum},
     DebugWrapper, Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError, Version,
     VulkanError, VulkanObject,
 };
+use parking_lot::Mutex;
 use std::{
-    ffi::{c_void, CStr},
+    ffi::{c_void, CStr, CString},
     fmt::{Debug, Error as FmtError, Formatter},
-    mem::MaybeUninit,
+    mem::{self, MaybeUninit},
     panic::{catch_unwind, AssertUnwindSafe, RefUnwindSafe},
     ptr, slice,
     sync::Arc,
@@ -164,6 +168,106 @@ impl Debug for DebugUtilsMessenger {
     }
 }
 
+/// Collects the messages that shaders print with `debugPrintfEXT`.
+///
+/// Debug printf is implemented by the validation layer, which must be enabled on the instance
+/// together with its [`ValidationFeatureEnable::DebugPrintf`] feature. This can be done with
+/// [`configure_instance`]. The collector registers a debug messenger that keeps the messages
+/// generated by debug printf, until they are retrieved with [`drain`], for example once per frame.
+///
+/// The device must also support shaders that use the `NonSemantic.DebugPrintf` instruction set,
+/// which requires the [`khr_shader_non_semantic_info`] device extension or Vulkan 1.3.
+///
+/// [`configure_instance`]: Self::configure_instance
+/// [`drain`]: Self::drain
+/// [`khr_shader_non_semantic_info`]: crate::device::DeviceExtensions::khr_shader_non_semantic_info
+#[derive(Debug)]
+pub struct DebugPrintfCollector {
+    messages: Arc<Mutex<Vec<String>>>,
+    _messenger: DebugUtilsMessenger,
+}
+
+impl DebugPrintfCollector {
+    const VALIDATION_LAYER: &'static str = "VK_LAYER_KHRONOS_validation";
+
+    /// Adds the layer, extensions and validation feature that debug printf needs to
+    /// `create_info`.
+    ///
+    /// The validation layer must be installed on the system, because it also provides the
+    /// `ext_validation_features` extension.
+    pub fn configure_instance(create_info: &mut InstanceCreateInfo) {
+        if !create_info
+            .enabled_layers
+            .iter()
+            .any(|name| name == Self::VALIDATION_LAYER)
+        {
+            create_info
+                .enabled_layers
+                .push(Self::VALIDATION_LAYER.to_owned());
+        }
+
+        create_info.enabled_extensions.ext_debug_utils = true;
+        create_info.enabled_extensions.ext_validation_features = true;
+
+        if !create_info
+            .enabled_validation_features
+            .contains(&ValidationFeatureEnable::DebugPrintf)
+        {
+            create_info
+                .enabled_validation_features
+                .push(ValidationFeatureEnable::DebugPrintf);
+        }
+    }
+
+    /// Registers a debug messenger on `instance` that collects the messages generated by debug
+    /// printf.
+    ///
+    /// The `ext_debug_utils` extension must be enabled on the instance.
+    pub fn new(instance: Arc<Instance>) -> Result<Self, Validated<VulkanError>> {
+        let messages: Arc<Mutex<Vec<String>>> = Arc::default();
+
+        // SAFETY: The callback doesn't make any calls to the Vulkan API.
+        let user_callback = unsafe {
+            // The lock isn't poisoned by a panic, and the only thing that could panic while it is
+            // held is the push, which leaves the `Vec` valid.
+            let messages = AssertUnwindSafe(messages.clone());
+
+            DebugUtilsMessengerCallback::new(move |_, _, callback_data| {
+                // Depending on the version of the validation layer, the message ID name is
+                // `UNASSIGNED-DEBUG-PRINTF` or `WARNING-DEBUG-PRINTF`.
+                if callback_data
+                    .message_id_name
+                    .map_or(false, |name| name.contains("DEBUG-PRINTF"))
+                {
+                    messages.lock().push(callback_data.message.to_owned());
+                }
+            })
+        };
+
+        let messenger = DebugUtilsMessenger::new(
+            instance,
+            DebugUtilsMessengerCreateInfo {
+                message_severity: DebugUtilsMessageSeverity::ERROR
+                    | DebugUtilsMessageSeverity::WARNING
+                    | DebugUtilsMessageSeverity::INFO,
+                message_type: DebugUtilsMessageType::GENERAL | DebugUtilsMessageType::VALIDATION,
+                ..DebugUtilsMessengerCreateInfo::user_callback(user_callback)
+            },
+        )?;
+
+        Ok(DebugPrintfCollector {
+            messages,
+            _messenger: messenger,
+        })
+    }
+
+    /// Returns the messages that were collected since the previous call, in the order they were
+    /// received.
+    pub fn drain(&self) -> Vec<String> {
+        mem::take(&mut *self.messages.lock())
+    }
+}
+
 /// Parameters to create a `DebugUtilsMessenger`.
 #[derive(Clone)]
 pub struct DebugUtilsMessengerCreateInfo {
@@ -379,6 +483,40 @@ pub(super) unsafe extern "system" fn trampoline(
     ash::vk::FALSE
 }
 
+/// Sends a message that was generated by Vulkano to the debug messengers of `instance`.
+///
+/// Does nothing if the `ext_debug_utils` extension is not enabled on the instance.
+pub(crate) fn submit_message(
+    instance: &Instance,
+    message_severity: DebugUtilsMessageSeverity,
+    message_type: DebugUtilsMessageType,
+    message_id_name: &str,
+    message: &str,
+) {
+    if !instance.enabled_extensions().ext_debug_utils {
+        return;
+    }
+
+    let message_id_name_vk = CString::new(message_id_name).unwrap();
+    let message_vk = CString::new(message.replace('\0', "")).unwrap();
+
+    let callback_data_vk = ash::vk::DebugUtilsMessengerCallbackDataEXT {
+        p_message_id_name: message_id_name_vk.as_ptr(),
+        p_message: message_vk.as_ptr(),
+        ..Default::default()
+    };
+
+    unsafe {
+        let fns = instance.fns();
+        (fns.ext_debug_utils.submit_debug_utils_message_ext)(
+            instance.handle(),
+            message_severity.into(),
+            message_type.into(),
+            &callback_data_vk,
+        );
+    }
+}
+
 /// The data of a message received by the user callback.
 #[non_exhaustive]
 pub struct DebugUtilsMessengerCallbackData<'a> {
@@ -615,10 +753,7 @@ vulkan_enum! {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{
-        instance::{InstanceCreateInfo, InstanceExtensions},
-        VulkanLibrary,
-    };
+    use crate::{instance::InstanceExtensions, VulkanLibrary};
     use std::thread;
 
     #[test]
@@ -665,4 +800,70 @@ mod tests {
             drop(callback);
         });
     }
+
+    #[test]
+    fn debug_printf_collector() {
+        let instance = {
+            let library = match VulkanLibrary::new() {
+                Ok(x) => x,
+                Err(_) => return,
+            };
+
+            match Instance::new(
+                library,
+                InstanceCreateInfo {
+                    enabled_extensions: InstanceExtensions {
+                        ext_debug_utils: true,
+                        ..InstanceExtensions::empty()
+                    },
+                    ..Default::default()
+                },
+            ) {
+                Ok(x) => x,
+                Err(_) => return,
+            }
+        };
+
+        let collector = DebugPrintfCollector::new(instance.clone()).unwrap();
+
+        submit_message(
+            &instance,
+            DebugUtilsMessageSeverity::INFO,
+            DebugUtilsMessageType::VALIDATION,
+            "WARNING-DEBUG-PRINTF",
+            "thread 0",
+        );
+        submit_message(
+            &instance,
+            DebugUtilsMessageSeverity::WARNING,
+            DebugUtilsMessageType::PERFORMANCE,
+            "Vulkano-Test",
+            "not printed by a shader",
+        );
+        submit_message(
+            &instance,
+            DebugUtilsMessageSeverity::INFO,
+            DebugUtilsMessageType::VALIDATION,
+            "UNASSIGNED-DEBUG-PRINTF",
+            "thread 1",
+        );
+
+        assert_eq!(collector.drain(), ["thread 0", "thread 1"]);
+        assert!(collector.drain().is_empty());
+    }
+
+    #[test]
+    fn debug_printf_configure_instance() {
+        let mut create_info = InstanceCreateInfo::default();
+        DebugPrintfCollector::configure_instance(&mut create_info);
+        DebugPrintfCollector::configure_instance(&mut create_info);
+
+        assert_eq!(create_info.enabled_layers, ["VK_LAYER_KHRONOS_validation"]);
+        assert!(create_info.enabled_extensions.ext_debug_utils);
+        assert!(create_info.enabled_extensions.ext_validation_features);
+        assert_eq!(
+            create_info.enabled_validation_features,
+            [ValidationFeatureEnable::DebugPrintf],
+        );
+    }
 }
@@ -96,7 +96,7 @@ use std::{
     borrow::Cow,
     ffi::CString,
     fmt::{Debug, Error as FmtError, Formatter},
-    mem::MaybeUninit,
+    mem::{self, MaybeUninit},
     num::NonZeroU64,
     ops::Deref,
     panic::{RefUnwindSafe, UnwindSafe},
@@ -267,6 +267,8 @@ pub struct Instance {
     api_version: Version,
     enabled_extensions: InstanceExtensions,
     enabled_layers: Vec<String>,
+    missing_layers: Vec<String>,
+    missing_optional_extensions: InstanceExtensions,
     library: Arc<VulkanLibrary>,
     max_api_version: Version,
     _user_callbacks: Vec<Arc<DebugUtilsMessengerCallback>>,
@@ -294,10 +296,61 @@ impl Instance {
                 Version::HEADER_VERSION
             }
         });
+        let missing = Self::resolve_optional(&library, &mut create_info)?;
 
         Self::validate_new(&library, &create_info)?;
 
-        unsafe { Ok(Self::new_unchecked(library, create_info)?) }
+        unsafe { Ok(Self::new_resolved(library, create_info, missing)?) }
+    }
+
+    /// Applies `enabled_layers_filter` and `optional_extensions`, so that only the layers and
+    /// extensions that are actually going to be enabled remain in `create_info`, and returns the
+    /// ones that were left out.
+    fn resolve_optional(
+        library: &VulkanLibrary,
+        create_info: &mut InstanceCreateInfo,
+    ) -> Result<MissingOptional, VulkanError> {
+        let mut missing = MissingOptional::default();
+
+        if !create_info.enabled_layers.is_empty() {
+            let supported_layers = library.supported_layers()?;
+
+            match create_info.enabled_layers_filter {
+                LayerFilter::Strict => {
+                    if !create_info
+                        .enabled_layers
+                        .iter()
+                        .all(|layer| supported_layers.contains(layer))
+                    {
+                        return Err(VulkanError::LayerNotPresent);
+                    }
+                }
+                LayerFilter::IgnoreMissing => {
+                    let (enabled_layers, missing_layers): (Vec<_>, Vec<_>) =
+                        mem::take(&mut create_info.enabled_layers)
+                            .into_iter()
+                            .partition(|layer| supported_layers.contains(layer));
+                    create_info.enabled_layers = enabled_layers;
+                    missing.layers = missing_layers;
+                }
+            }
+        }
+
+        if !create_info.optional_extensions.is_empty() {
+            let supported_extensions = library.supported_extensions_with_layers(
+                create_info.enabled_layers.iter().map(String::as_str),
+            )?;
+            create_info.enabled_extensions = create_info.enabled_extensions.union(
+                &create_info
+                    .optional_extensions
+                    .intersection(&supported_extensions),
+            );
+            missing.extensions = create_info
+                .optional_extensions
+                .difference(&supported_extensions);
+        }
+
+        Ok(missing)
     }
 
     fn validate_new(
@@ -317,7 +370,9 @@ impl Instance {
             engine_version: _,
             max_api_version,
             ref enabled_layers,
+            enabled_layers_filter: _,
             ref enabled_extensions,
+            optional_extensions: _,
             debug_utils_messengers: _,
             enabled_validation_features: _,
             disabled_validation_features: _,
@@ -355,6 +410,16 @@ impl Instance {
                 Version::HEADER_VERSION
             }
         });
+        let missing = Self::resolve_optional(&library, &mut create_info)?;
+
+        Self::new_resolved(library, create_info, missing)
+    }
+
+    unsafe fn new_resolved(
+        library: Arc<VulkanLibrary>,
+        mut create_info: InstanceCreateInfo,
+        missing: MissingOptional,
+    ) -> Result<Arc<Instance>, VulkanError> {
         create_info.enabled_extensions.enable_dependencies(
             std::cmp::min(
                 create_info.max_api_version.unwrap_or_default(),
@@ -375,7 +440,9 @@ impl Instance {
             engine_version,
             max_api_version,
             ref enabled_layers,
+            enabled_layers_filter: _,
             ref enabled_extensions,
+            optional_extensions: _,
             ref debug_utils_messengers,
             ref enabled_validation_features,
             ref disabled_validation_features,
@@ -511,7 +578,12 @@ impl Instance {
             output.assume_init()
         };
 
-        Ok(Self::from_handle(library, handle, create_info))
+        Ok(Self::from_handle_inner(
+            library,
+            handle,
+            create_info,
+            missing,
+        ))
     }
 
     /// Creates a new `Instance` from a raw object handle.
@@ -521,9 +593,18 @@ impl Instance {
     /// - `handle` must be a valid Vulkan object handle created from `library`.
     /// - `create_info` must match the info used to create the object.
     pub unsafe fn from_handle(
+        library: Arc<VulkanLibrary>,
+        handle: ash::vk::Instance,
+        create_info: InstanceCreateInfo,
+    ) -> Arc<Self> {
+        Self::from_handle_inner(library, handle, create_info, MissingOptional::default())
+    }
+
+    unsafe fn from_handle_inner(
         library: Arc<VulkanLibrary>,
         handle: ash::vk::Instance,
         mut create_info: InstanceCreateInfo,
+        missing: MissingOptional,
     ) -> Arc<Self> {
         create_info.max_api_version.get_or_insert_with(|| {
             let api_version = library.api_version();
@@ -542,7 +623,9 @@ impl Instance {
             engine_version: _,
             max_api_version,
             enabled_layers,
+            enabled_layers_filter: _,
             enabled_extensions,
+            optional_extensions: _,
             debug_utils_messengers,
             enabled_validation_features: _,
             disabled_validation_features: _,
@@ -565,6 +648,8 @@ impl Instance {
             api_version,
             enabled_extensions,
             enabled_layers,
+            missing_layers: missing.layers,
+            missing_optional_extensions: missing.extensions,
             library,
             max_api_version,
             _user_callbacks: debug_utils_messengers
@@ -626,6 +711,20 @@ impl Instance {
         &self.enabled_layers
     }
 
+    /// Returns the layers in [`InstanceCreateInfo::enabled_layers`] that were not enabled,
+    /// because they are not present and [`LayerFilter::IgnoreMissing`] was used.
+    #[inline]
+    pub fn missing_layers(&self) -> &[String] {
+        &self.missing_layers
+    }
+
+    /// Returns the extensions in [`InstanceCreateInfo::optional_extensions`] that were not
+    /// enabled, because they are not supported.
+    #[inline]
+    pub fn missing_optional_extensions(&self) -> &InstanceExtensions {
+        &self.missing_optional_extensions
+    }
+
     /// Returns an iterator that enumerates the physical devices available.
     ///
     /// # Examples
@@ -878,6 +977,8 @@ impl Debug for Instance {
             api_version,
             enabled_extensions,
             enabled_layers,
+            missing_layers,
+            missing_optional_extensions,
             library,
             max_api_version,
             _user_callbacks: _,
@@ -894,6 +995,8 @@ impl Debug for Instance {
             .field("api_version", api_version)
             .field("enabled_extensions", enabled_extensions)
             .field("enabled_layers", enabled_layers)
+            .field("missing_layers", missing_layers)
+            .field("missing_optional_extensions", missing_optional_extensions)
             .field("library", library)
             .field("max_api_version", max_api_version)
             .finish_non_exhaustive()
@@ -940,6 +1043,15 @@ pub struct InstanceCreateInfo {
     /// The default value is empty.
     pub enabled_layers: Vec<String>,
 
+    /// What to do with layers in `enabled_layers` that are not present on the system.
+    ///
+    /// The layers that were actually enabled can be retrieved afterwards with
+    /// [`Instance::enabled_layers`], and the ones that were left out with
+    /// [`Instance::missing_layers`].
+    ///
+    /// The default value is [`LayerFilter::Strict`].
+    pub enabled_layers_filter: LayerFilter,
+
     /// The extensions to enable on the instance.
     ///
     /// You only need to enable the extensions that you need. If the extensions you specified
@@ -948,6 +1060,18 @@ pub struct InstanceCreateInfo {
     /// The default value is [`InstanceExtensions::empty()`].
     pub enabled_extensions: InstanceExtensions,
 
+    /// Extensions to enable on the instance if they are supported, in addition to
+    /// `enabled_extensions`.
+    ///
+    /// Unlike `enabled_extensions`, extensions in this list that are not supported by the library
+    /// or by any of the enabled layers are skipped, instead of causing an error. The extensions
+    /// that were actually enabled can be retrieved afterwards with
+    /// [`Instance::enabled_extensions`], and the ones that were skipped with
+    /// [`Instance::missing_optional_extensions`].
+    ///
+    /// The default value is [`InstanceExtensions::empty()`].
+    pub optional_extensions: InstanceExtensions,
+
     /// Creation parameters for debug messengers,
     /// to use during the creation and destruction of the instance.
     ///
@@ -988,7 +1112,9 @@ impl Default for InstanceCreateInfo {
             engine_version: Version::major_minor(0, 0),
             max_api_version: None,
             enabled_layers: Vec::new(),
+            enabled_layers_filter: LayerFilter::Strict,
             enabled_extensions: InstanceExtensions::empty(),
+            optional_extensions: InstanceExtensions::empty(),
             debug_utils_messengers: Vec::new(),
             enabled_validation_features: Vec::new(),
             disabled_validation_features: Vec::new(),
@@ -1027,7 +1153,9 @@ impl InstanceCreateInfo {
             engine_version: _,
             max_api_version,
             enabled_layers: _,
+            enabled_layers_filter: _,
             ref enabled_extensions,
+            optional_extensions: _,
             ref debug_utils_messengers,
             ref enabled_validation_features,
             ref disabled_validation_features,
@@ -1153,6 +1281,31 @@ impl InstanceCreateInfo {
     }
 }
 
+/// The layers and optional extensions that were left out when creating an instance.
+#[derive(Default)]
+struct MissingOptional {
+    layers: Vec<String>,
+    extensions: InstanceExtensions,
+}
+
+/// Specifies how layers that are not present on the system are handled when creating an
+/// instance.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LayerFilter {
+    /// If any of the layers is not present, instance creation fails with
+    /// [`VulkanError::LayerNotPresent`].
+    #[default]
+    Strict,
+
+    /// Layers that are not present are removed from the list, and the instance is created with
+    /// the remaining layers.
+    ///
+    /// This is useful for layers that are only wanted when they are available, such as the
+    /// validation layer, which is usually only installed on development machines.
+    IgnoreMissing,
+}
+
 vulkan_bitflags! {
     #[non_exhaustive]
 
@@ -1238,7 +1391,10 @@ impl<T> Deref for InstanceOwnedDebugWrapper<T> {
 
 #[cfg(test)]
 mod tests {
-    use crate::instance::InstanceExtensions;
+    use crate::{
+        instance::{Instance, InstanceCreateInfo, InstanceExtensions, LayerFilter},
+        Validated, VulkanError, VulkanLibrary,
+    };
     use std::ffi::CString;
 
     #[test]
@@ -1266,4 +1422,77 @@ mod tests {
     fn create_instance() {
         let _ = instance!();
     }
+
+    #[test]
+    fn missing_layer() {
+        let library = match VulkanLibrary::new() {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+        let enabled_layers = vec!["VK_LAYER_VULKANO_does_not_exist".to_owned()];
+
+        match Instance::new(
+            library.clone(),
+            InstanceCreateInfo {
+                enabled_layers: enabled_layers.clone(),
+                ..Default::default()
+            },
+        ) {
+            Err(Validated::Error(VulkanError::LayerNotPresent)) => (),
+            _ => panic!(),
+        }
+
+        let instance = match Instance::new(
+            library,
+            InstanceCreateInfo {
+                enabled_layers,
+                enabled_layers_filter: LayerFilter::IgnoreMissing,
+                ..Default::default()
+            },
+        ) {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+        assert!(instance.enabled_layers().is_empty());
+        assert_eq!(
+            instance.missing_layers(),
+            ["VK_LAYER_VULKANO_does_not_exist".to_owned()],
+        );
+    }
+
+    #[test]
+    fn optional_extensions() {
+        let library = match VulkanLibrary::new() {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+        let optional_extensions = InstanceExtensions {
+            ext_debug_utils: true,
+            khr_display: true,
+            ..InstanceExtensions::empty()
+        };
+
+        let instance = match Instance::new(
+            library.clone(),
+            InstanceCreateInfo {
+                optional_extensions,
+                ..Default::default()
+            },
+        ) {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+
+        let supported_extensions = library.supported_extensions();
+        assert_eq!(
+            instance
+                .enabled_extensions()
+                .intersection(&optional_extensions),
+            optional_extensions.intersection(supported_extensions),
+        );
+        assert_eq!(
+            *instance.missing_optional_extensions(),
+            optional_extensions.difference(supported_extensions),
+        );
+    }
 }
@@ -95,6 +95,7 @@
 //! | `macros`             | Include reexports from [`vulkano-macros`]. Enabled by default. |
 //! | `document_unchecked` | Include `_unchecked` functions in the generated documentation. |
 //! | `serde`              | Enables (de)serialization of certain types using [`serde`].    |
+//! | `testing`            | Include the `testing` module, with utilities for tests.        |
 //!
 //! [`Instance`]: instance::Instance
 //! [`Surface`]: swapchain::Surface
@@ -121,18 +122,20 @@
 
 pub use ash::vk::Handle;
 use bytemuck::{Pod, Zeroable};
-pub use extensions::ExtensionProperties;
+pub use extensions::{ExtensionProperties, UnknownExtension};
 pub use half;
 pub use library::{LoadingError, VulkanLibrary};
 use std::{
     borrow::Cow,
     error::Error,
-    fmt::{Debug, Display, Error as FmtError, Formatter},
+    fmt::{Debug, Display, Error as FmtError, Formatter, Write},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
     num::NonZeroU64,
     ops::Deref,
     sync::Arc,
 };
-pub use version::Version;
+pub use version::{ParseVersionError, Version};
 
 #[macro_use]
 mod tests;
@@ -142,6 +145,7 @@ pub mod acceleration_structure;
 pub mod buffer;
 pub mod command_buffer;
 pub mod deferred;
+mod describe;
 pub mod descriptor_set;
 pub mod device;
 pub mod display;
@@ -164,6 +168,9 @@ pub mod range_set;
 pub mod shader;
 pub mod swapchain;
 pub mod sync;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod transfer;
 
 /// Represents memory size and offset values on a Vulkan device.
 /// Analogous to the Rust `usize` type on the host.
@@ -178,6 +185,109 @@ pub use ash::vk::DeviceAddress;
 /// A [`DeviceAddress`] that is known not to equal zero.
 pub type NonNullDeviceAddress = NonZeroU64;
 
+/// A [`DeviceAddress`] that points to a value of type `T`.
+///
+/// This has the same layout as a `DeviceAddress`, so it can be used in its place in structs that
+/// are written to buffers or passed as push constants, to pass a pointer to a shader. Shaders
+/// compiled with [`vulkano-shaders`] use this type for GLSL `buffer_reference` blocks. The type
+/// parameter only associates the address with the type of the data on the Rust side. It is not
+/// checked against what the memory at the address actually contains.
+///
+/// A `DeviceAddressT` can be created from a [`Subbuffer`] of the same type with `try_from`.
+///
+/// [`vulkano-shaders`]: https://docs.rs/vulkano-shaders
+/// [`Subbuffer`]: buffer::Subbuffer
+#[repr(transparent)]
+pub struct DeviceAddressT<T: ?Sized> {
+    address: DeviceAddress,
+    _marker: PhantomData<fn() -> *const T>,
+}
+
+impl<T: ?Sized> DeviceAddressT<T> {
+    /// Returns a new `DeviceAddressT` with the given address.
+    #[inline]
+    pub const fn new(address: DeviceAddress) -> Self {
+        DeviceAddressT {
+            address,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a `DeviceAddressT` with an address of zero.
+    #[inline]
+    pub const fn null() -> Self {
+        Self::new(0)
+    }
+
+    /// Returns the address.
+    #[inline]
+    pub const fn address(self) -> DeviceAddress {
+        self.address
+    }
+
+    /// Returns whether the address is zero.
+    #[inline]
+    pub const fn is_null(self) -> bool {
+        self.address == 0
+    }
+}
+
+impl<T: ?Sized> Clone for DeviceAddressT<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for DeviceAddressT<T> {}
+
+impl<T: ?Sized> Debug for DeviceAddressT<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(f, "0x{:x}", self.address)
+    }
+}
+
+impl<T: ?Sized> Default for DeviceAddressT<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::null()
+    }
+}
+
+impl<T: ?Sized> PartialEq for DeviceAddressT<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.address == other.address
+    }
+}
+
+impl<T: ?Sized> Eq for DeviceAddressT<T> {}
+
+impl<T: ?Sized> Hash for DeviceAddressT<T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.address.hash(state);
+    }
+}
+
+unsafe impl<T: ?Sized + 'static> Zeroable for DeviceAddressT<T> {}
+
+unsafe impl<T: ?Sized + 'static> Pod for DeviceAddressT<T> {}
+
+impl<T: ?Sized> From<NonNullDeviceAddress> for DeviceAddressT<T> {
+    #[inline]
+    fn from(address: NonNullDeviceAddress) -> Self {
+        Self::new(address.get())
+    }
+}
+
+impl<T: ?Sized> From<DeviceAddressT<T>> for DeviceAddress {
+    #[inline]
+    fn from(address: DeviceAddressT<T>) -> Self {
+        address.address
+    }
+}
+
 /// Holds 24 bits in the least significant bits of memory,
 /// and 8 bytes in the most significant bits of that memory,
 /// occupying a single [`u32`] in total.
@@ -498,6 +608,30 @@ impl ValidationError {
         self.vuids = vuids;
         self
     }
+
+    /// Adds the index and name of a command in a command buffer, and the debug names of the
+    /// objects that it used, to the end of the problem description.
+    fn add_command(
+        mut self: Box<Self>,
+        index: usize,
+        name: &str,
+        objects: &[(Cow<'static, str>, String)],
+    ) -> Box<Self> {
+        let mut problem = self.problem.into_owned();
+
+        if !problem.is_empty() {
+            problem.push_str(", ");
+        }
+
+        write!(problem, "at command #{} ({})", index, name).unwrap();
+
+        for (role, object_name) in objects {
+            write!(problem, ", {} {:?}", role, object_name).unwrap();
+        }
+
+        self.problem = problem.into();
+        self
+    }
 }
 
 impl Debug for ValidationError {
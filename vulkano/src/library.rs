@@ -14,6 +14,7 @@ use crate::{
     instance::{InstanceExtensions, LayerProperties},
     ExtensionProperties, SafeDeref, Version, VulkanError,
 };
+use ahash::HashSet;
 use libloading::{Error as LibloadingError, Library};
 use std::{
     error::Error,
@@ -256,6 +257,32 @@ impl VulkanLibrary {
             .map(|p| LayerProperties { props: p }))
     }
 
+    /// Returns the names of the layers that are available when creating an instance.
+    ///
+    /// This is a shortcut for collecting the names of the layers returned by
+    /// [`layer_properties`](Self::layer_properties), which is useful to check whether a layer is
+    /// present before enabling it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use vulkano::VulkanLibrary;
+    ///
+    /// let library = VulkanLibrary::new().unwrap();
+    /// let supported_layers = library.supported_layers().unwrap();
+    ///
+    /// if supported_layers.contains("VK_LAYER_KHRONOS_validation") {
+    ///     println!("The validation layer is available");
+    /// }
+    /// ```
+    #[inline]
+    pub fn supported_layers(&self) -> Result<HashSet<String>, VulkanError> {
+        Ok(self
+            .layer_properties()?
+            .map(|layer| layer.name().to_owned())
+            .collect())
+    }
+
     /// Returns the extension properties that are reported by the given layer.
     #[inline]
     pub fn layer_extension_properties(
@@ -213,6 +213,8 @@ macro_rules! vulkan_bitflags {
                 self.complement()
             }
         }
+
+        crate::macros::vulkan_bitflags_names!($ty, $($flag_name),+);
     };
 
     {
@@ -551,6 +553,8 @@ macro_rules! vulkan_bitflags {
                 *self = self.difference(rhs);
             }
         }
+
+        crate::macros::vulkan_bitflags_names!($ty, $($flag_name),*);
     };
 }
 
@@ -607,6 +611,8 @@ macro_rules! vulkan_enum {
                 })
             }
         }
+
+        crate::macros::vulkan_enum_names!($ty, $($flag_name),+);
     };
 
     {
@@ -815,6 +821,129 @@ macro_rules! vulkan_enum {
             })
         }
     }
+
+    crate::macros::vulkan_enum_names!($ty, $($flag_name),+);
+    };
+}
+
+// Items that convert the flags of a `vulkan_bitflags!` type to and from their names.
+macro_rules! vulkan_bitflags_names {
+    ($ty:ident, $($flag_name:ident),* $(,)?) => {
+        impl $ty {
+            /// The names of all the flags, as they are written in Rust, together with their values.
+            pub const FLAG_NAMES: &'static [(&'static str, Self)] = &[
+                $((stringify!($flag_name), Self::$flag_name),)*
+            ];
+
+            /// Returns an iterator over the flags that are set in `self`.
+            #[inline]
+            pub fn iter(self) -> impl Iterator<Item = Self> {
+                Self::FLAG_NAMES
+                    .iter()
+                    .map(|&(_, flag)| flag)
+                    .filter(move |&flag| self.contains(flag))
+            }
+
+            /// Returns the flag with the given name, as it is written in Rust, or `None` if there
+            /// is no such flag.
+            #[inline]
+            pub fn from_name(name: &str) -> Option<Self> {
+                Self::FLAG_NAMES
+                    .iter()
+                    .find(|&&(flag_name, _)| flag_name == name)
+                    .map(|&(_, flag)| flag)
+            }
+        }
+
+        /// Serializes the flags as a sequence of the names of the flags that are set.
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for $ty {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.collect_seq(
+                    Self::FLAG_NAMES
+                        .iter()
+                        .filter(|&&(_, flag)| self.contains(flag))
+                        .map(|&(name, _)| name),
+                )
+            }
+        }
+
+        /// Deserializes the flags from a sequence of flag names.
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for $ty {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let names = <Vec<String> as serde::Deserialize>::deserialize(deserializer)?;
+
+                names.iter().try_fold(Self::empty(), |flags, name| {
+                    Self::from_name(name).map(|flag| flags.union(flag)).ok_or_else(|| {
+                        serde::de::Error::custom(format!(
+                            concat!("unknown `", stringify!($ty), "` flag `{}`"),
+                            name,
+                        ))
+                    })
+                })
+            }
+        }
+    };
+}
+
+// Items that convert the values of a `vulkan_enum!` type to and from their names.
+macro_rules! vulkan_enum_names {
+    ($ty:ident, $($flag_name:ident),+ $(,)?) => {
+        impl $ty {
+            /// Returns the name of `self`, as it is written in Rust.
+            #[inline]
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Self::$flag_name => stringify!($flag_name),)+
+                }
+            }
+
+            /// Returns the value with the given name, as it is written in Rust, or `None` if there
+            /// is no such value.
+            #[inline]
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $(stringify!($flag_name) => Some(Self::$flag_name),)+
+                    _ => None,
+                }
+            }
+        }
+
+        /// Serializes the value as its name.
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for $ty {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_str(self.name())
+            }
+        }
+
+        /// Deserializes the value from its name.
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for $ty {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let name = <String as serde::Deserialize>::deserialize(deserializer)?;
+
+                Self::from_name(&name).ok_or_else(|| {
+                    serde::de::Error::custom(format!(
+                        concat!("unknown `", stringify!($ty), "` value `{}`"),
+                        name,
+                    ))
+                })
+            }
+        }
     };
 }
 
@@ -1003,4 +1132,6 @@ pub(crate) use impl_id_counter;
 pub(crate) use try_opt;
 pub(crate) use vulkan_bitflags;
 pub(crate) use vulkan_bitflags_enum;
+pub(crate) use vulkan_bitflags_names;
 pub(crate) use vulkan_enum;
+pub(crate) use vulkan_enum_names;
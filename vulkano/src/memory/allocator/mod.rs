@@ -208,19 +208,21 @@
 
 mod layout;
 pub mod suballocator;
+mod tracking;
 
-use self::{array_vec::ArrayVec, suballocator::Region};
+use self::{array_vec::ArrayVec, suballocator::Region, tracking::AllocationTracker};
 pub use self::{
     layout::DeviceLayout,
     suballocator::{
         AllocationType, BuddyAllocator, BumpAllocator, FreeListAllocator, Suballocation,
         Suballocator, SuballocatorError,
     },
+    tracking::{LiveAllocation, LiveAllocationGroup},
 };
 use super::{
     DedicatedAllocation, DeviceAlignment, DeviceMemory, ExternalMemoryHandleTypes,
-    MemoryAllocateFlags, MemoryAllocateInfo, MemoryMapInfo, MemoryProperties, MemoryPropertyFlags,
-    MemoryRequirements, MemoryType,
+    MemoryAllocateFlags, MemoryAllocateInfo, MemoryHeapFlags, MemoryMapInfo, MemoryProperties,
+    MemoryPropertyFlags, MemoryRequirements, MemoryType,
 };
 use crate::{
     device::{Device, DeviceOwned},
@@ -236,7 +238,10 @@ use std::{
     mem,
     ops::BitOr,
     ptr, slice,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
 
 /// General-purpose memory allocators which allocate from any memory type dynamically as needed.
@@ -620,6 +625,37 @@ impl MemoryTypeFilter {
         not_preferred_flags: MemoryPropertyFlags::empty(),
     };
 
+    /// Prefers picking a memory type with the [`DEVICE_LOCAL`] flag, and guarantees picking one
+    /// with the [`HOST_VISIBLE`] flag. This is the same as combining [`PREFER_DEVICE`] with
+    /// [`HOST_SEQUENTIAL_WRITE`].
+    ///
+    /// This filter is best suited for data that the host writes sequentially and the device reads
+    /// directly, such as uniform buffers or dynamic vertex buffers that are updated every frame.
+    ///
+    /// The memory type is selected as follows:
+    ///
+    /// - If there is a memory type that is both `DEVICE_LOCAL` and `HOST_VISIBLE`, it is picked.
+    ///   On devices with resizable BAR and on devices with unified memory, this is usually all of
+    ///   VRAM. Otherwise it is usually a heap of around 256 MiB that is shared with the driver.
+    ///   Memory types without the [`HOST_CACHED`] flag are preferred among them.
+    /// - Otherwise, a memory type that is `HOST_VISIBLE` but not `DEVICE_LOCAL` is picked, which
+    ///   means that the device reads the data over the PCIe bus.
+    ///
+    /// Because the `DEVICE_LOCAL` and `HOST_VISIBLE` heap can be small without resizable BAR,
+    /// [`GenericMemoryAllocator`] only places allocations larger than
+    /// [`GenericMemoryAllocatorCreateInfo::small_bar_allocation_threshold`] in it if there is no
+    /// other suitable memory type. You can use
+    /// [`PhysicalDevice::has_device_local_host_visible_memory`] to decide on a strategy yourself.
+    ///
+    /// [`DEVICE_LOCAL`]: MemoryPropertyFlags::DEVICE_LOCAL
+    /// [`HOST_VISIBLE`]: MemoryPropertyFlags::HOST_VISIBLE
+    /// [`HOST_CACHED`]: MemoryPropertyFlags::HOST_CACHED
+    /// [`PREFER_DEVICE`]: Self::PREFER_DEVICE
+    /// [`HOST_SEQUENTIAL_WRITE`]: Self::HOST_SEQUENTIAL_WRITE
+    /// [`PhysicalDevice::has_device_local_host_visible_memory`]: crate::device::physical::PhysicalDevice::has_device_local_host_visible_memory
+    pub const PREFER_DEVICE_HOST_VISIBLE: Self =
+        Self::PREFER_DEVICE.union(Self::HOST_SEQUENTIAL_WRITE);
+
     /// Returns a `MemoryTypeFilter` with none of the flags set.
     #[inline]
     pub const fn empty() -> Self {
@@ -672,6 +708,45 @@ pub struct AllocationCreateInfo {
     /// The default value is [`MemoryAllocatePreference::Unknown`].
     pub allocate_preference: MemoryAllocatePreference,
 
+    /// A name for the allocation, which is used to identify it when the allocator keeps track of
+    /// its live allocations.
+    ///
+    /// This is ignored unless the allocator was created with [`track_allocations`] enabled.
+    ///
+    /// The default value is `None`.
+    ///
+    /// [`track_allocations`]: GenericMemoryAllocatorCreateInfo::track_allocations
+    pub name: Option<Arc<str>>,
+
+    /// Whether the memory should be allocated with the
+    /// [`MemoryAllocateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY`] flag, which is needed to bind
+    /// buffers created with [`BufferCreateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY`] to it.
+    ///
+    /// The opaque capture address belongs to a whole block of [`DeviceMemory`], so this always
+    /// results in a dedicated allocation. [`Buffer::new`] sets this automatically for buffers
+    /// that have the flag.
+    ///
+    /// If set to `true`, the [`buffer_device_address_capture_replay`] feature must be enabled on
+    /// the device.
+    ///
+    /// The default value is `false`.
+    ///
+    /// [`Buffer::new`]: crate::buffer::Buffer::new
+    /// [`BufferCreateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY`]: crate::buffer::BufferCreateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY
+    /// [`buffer_device_address_capture_replay`]: crate::device::DeviceFeatures::buffer_device_address_capture_replay
+    pub device_address_capture_replay: bool,
+
+    /// The opaque capture address to allocate the memory with, when replaying a capture.
+    ///
+    /// If not zero, [`device_address_capture_replay`] must be `true`. The value should have
+    /// been returned by [`DeviceMemory::opaque_capture_address`] for the memory that the resource
+    /// was bound to when the capture was made.
+    ///
+    /// The default value is `0`.
+    ///
+    /// [`device_address_capture_replay`]: Self::device_address_capture_replay
+    pub opaque_capture_address: u64,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -682,6 +757,9 @@ impl Default for AllocationCreateInfo {
             memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
             memory_type_bits: u32::MAX,
             allocate_preference: MemoryAllocatePreference::Unknown,
+            name: None,
+            device_address_capture_replay: false,
+            opaque_capture_address: 0,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -814,6 +892,14 @@ pub enum MemoryAllocatorError {
     /// This is returned when using [`MemoryAllocatePreference::NeverAllocate`] and the allocation
     /// size exceeded the block size for all heaps of suitable memory types.
     BlockSizeExceeded,
+
+    /// The maximum number of blocks of the pool was reached.
+    ///
+    /// This is returned by a pool created with [`GenericMemoryAllocator::create_pool`] when
+    /// [`PoolCreateInfo::max_blocks`] is set, and allocating the memory would require a new
+    /// `DeviceMemory` block beyond that number. Freeing allocations of the pool and calling
+    /// [`GenericMemoryAllocator::trim`] makes room for new blocks.
+    MaxBlocksExceeded,
 }
 
 impl Error for MemoryAllocatorError {
@@ -838,6 +924,7 @@ impl Display for MemoryAllocatorError {
                 "the allocation size was greater than the block size for all heaps of suitable \
                 memory types and dedicated allocations were explicitly forbidden"
             }
+            Self::MaxBlocksExceeded => "the maximum number of blocks of the pool was reached",
         };
 
         f.write_str(msg)
@@ -912,8 +999,9 @@ impl StandardMemoryAllocator {
 ///
 /// Every time a new `DeviceMemory` block is allocated, it is mapped in full automatically as long
 /// as it resides in host-visible memory. It remains mapped until it is dropped, which only happens
-/// if the allocator is dropped. In other words, all eligible blocks are persistently mapped, so
-/// you don't need to worry about whether or not your host-visible allocations are host-accessible.
+/// if the allocator is dropped, or if the block is empty when [`trim`] is called. In other words,
+/// all eligible blocks are persistently mapped, so you don't need to worry about whether or not
+/// your host-visible allocations are host-accessible.
 ///
 /// # `DeviceMemory` allocation
 ///
@@ -929,6 +1017,15 @@ impl StandardMemoryAllocator {
 /// another block. No `DeviceMemory` is allocated when the allocator is created, the blocks are
 /// only allocated once they are needed.
 ///
+/// # Pools
+///
+/// An allocator can be split into isolated pools with [`create_pool`], for example to keep the
+/// memory of streamed resources apart from everything else. A pool has its own blocks, block size
+/// and memory types, and can be limited to a maximum number of blocks, so that the fragmentation
+/// and the memory usage of its allocations don't affect the rest of the application.
+///
+/// [`trim`]: Self::trim
+/// [`create_pool`]: Self::create_pool
 /// [memory allocator]: MemoryAllocator
 /// [a pool of `DeviceMemory` blocks]: DeviceMemoryPool
 /// [suballocate]: Suballocator
@@ -946,6 +1043,14 @@ pub struct GenericMemoryAllocator<S> {
     flags: MemoryAllocateFlags,
     // How many `DeviceMemory` allocations should be allowed before restricting them.
     max_allocations: u32,
+    // Memory types that are both device-local and host-visible, but reside in a heap smaller than
+    // the largest device-local heap.
+    small_bar_memory_type_bits: u32,
+    small_bar_allocation_threshold: DeviceSize,
+    tracker: Option<AllocationTracker>,
+    // The number of `DeviceMemory` allocations that are alive, including dedicated allocations.
+    device_memory_count: AtomicUsize,
+    max_device_memory_count: Option<usize>,
 }
 
 impl<S> GenericMemoryAllocator<S> {
@@ -974,6 +1079,8 @@ impl<S> GenericMemoryAllocator<S> {
             dedicated_allocation,
             export_handle_types,
             mut device_address,
+            small_bar_allocation_threshold,
+            track_allocations,
             _ne: _,
         } = create_info;
 
@@ -1045,6 +1152,9 @@ impl<S> GenericMemoryAllocator<S> {
             .max_memory_allocation_count;
         let max_allocations = max_memory_allocation_count / 4 * 3;
 
+        let small_bar_memory_type_bits =
+            small_bar_memory_type_bits(device.physical_device().memory_properties());
+
         GenericMemoryAllocator {
             device: InstanceOwnedDebugWrapper(device),
             buffer_image_granularity,
@@ -1054,6 +1164,107 @@ impl<S> GenericMemoryAllocator<S> {
             flags,
             memory_type_bits,
             max_allocations,
+            small_bar_memory_type_bits,
+            small_bar_allocation_threshold,
+            tracker: track_allocations.then(AllocationTracker::default),
+            device_memory_count: AtomicUsize::new(0),
+            max_device_memory_count: None,
+        }
+    }
+
+    /// Creates a new pool, which is an allocator that is isolated from `self`, but shares its
+    /// configuration.
+    ///
+    /// The pool has its own [`DeviceMemory`] blocks, of size `create_info.block_size`, which it
+    /// suballocates using the suballocator `T`. It can only allocate from the memory types that
+    /// are allowed by both `self` and `create_info.memory_type_bits`. Its blocks are never shared
+    /// with `self` or with other pools, and it has its own [statistics], and is [trimmed]
+    /// separately. Like any other allocator, the pool can be passed to [`Buffer::new`] and
+    /// [`Image::new`], and must be kept alive as long as its allocations are.
+    ///
+    /// The suballocator of the pool is the allocation strategy that the pool uses, and doesn't
+    /// need to be the same as the one of `self`:
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use vulkano::{
+    /// #     device::DeviceOwned,
+    /// #     memory::{
+    /// #         allocator::{BuddyAllocator, PoolCreateInfo, StandardMemoryAllocator},
+    /// #         MemoryPropertyFlags,
+    /// #     },
+    /// # };
+    /// # let memory_allocator: Arc<StandardMemoryAllocator> = return;
+    /// let memory_types = &memory_allocator
+    ///     .device()
+    ///     .physical_device()
+    ///     .memory_properties()
+    ///     .memory_types;
+    /// let device_local_bits = memory_types
+    ///     .iter()
+    ///     .enumerate()
+    ///     .filter(|(_, memory_type)| memory_type.property_flags == MemoryPropertyFlags::DEVICE_LOCAL)
+    ///     .fold(0, |bits, (index, _)| bits | (1 << index));
+    ///
+    /// let texture_pool = Arc::new(
+    ///     memory_allocator.create_pool::<BuddyAllocator>(PoolCreateInfo {
+    ///         memory_type_bits: device_local_bits,
+    ///         block_size: 64 * 1024 * 1024,
+    ///         max_blocks: Some(16),
+    ///         ..Default::default()
+    ///     }),
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.block_size` is zero.
+    /// - Panics if `create_info.max_blocks` is `Some(0)`.
+    ///
+    /// [statistics]: GenericMemoryAllocator::statistics
+    /// [trimmed]: GenericMemoryAllocator::trim
+    /// [`Buffer::new`]: crate::buffer::Buffer::new
+    /// [`Image::new`]: crate::image::Image::new
+    pub fn create_pool<T>(&self, create_info: PoolCreateInfo) -> GenericMemoryAllocator<T> {
+        let PoolCreateInfo {
+            memory_type_bits,
+            block_size,
+            max_blocks,
+            _ne: _,
+        } = create_info;
+
+        assert_ne!(block_size, 0, "`create_info.block_size` must not be zero");
+        assert_ne!(
+            max_blocks,
+            Some(0),
+            "`create_info.max_blocks` must not be `Some(0)`",
+        );
+
+        let mut pools = ArrayVec::new(
+            self.pools.len(),
+            [GenericMemoryAllocator::<T>::EMPTY_POOL; MAX_MEMORY_TYPES],
+        );
+
+        for (pool, template) in pools.iter_mut().zip(self.pools.iter()) {
+            pool.property_flags = template.property_flags;
+            pool.atom_size = template.atom_size;
+            pool.block_size = block_size;
+        }
+
+        GenericMemoryAllocator {
+            device: InstanceOwnedDebugWrapper(self.device().clone()),
+            buffer_image_granularity: self.buffer_image_granularity,
+            pools,
+            memory_type_bits: self.memory_type_bits & memory_type_bits,
+            dedicated_allocation: self.dedicated_allocation,
+            export_handle_types: self.export_handle_types,
+            flags: self.flags,
+            max_allocations: self.max_allocations,
+            small_bar_memory_type_bits: self.small_bar_memory_type_bits,
+            small_bar_allocation_threshold: self.small_bar_allocation_threshold,
+            tracker: self.tracker.as_ref().map(|_| AllocationTracker::default()),
+            device_memory_count: AtomicUsize::new(0),
+            max_device_memory_count: max_blocks,
         }
     }
 
@@ -1064,6 +1275,174 @@ impl<S> GenericMemoryAllocator<S> {
         &self.pools
     }
 
+    /// Returns the allocations of the allocator that haven't been deallocated yet, in no
+    /// particular order.
+    ///
+    /// Returns an empty list if the allocator was not created with [`track_allocations`] enabled.
+    ///
+    /// [`track_allocations`]: GenericMemoryAllocatorCreateInfo::track_allocations
+    pub fn live_allocations(&self) -> Vec<LiveAllocation> {
+        self.tracker
+            .as_ref()
+            .map_or_else(Vec::new, AllocationTracker::live_allocations)
+    }
+
+    /// Returns the allocations of the allocator that haven't been deallocated yet, grouped by
+    /// their name and the backtrace they were allocated from. The groups are sorted by their total
+    /// size, largest first.
+    ///
+    /// Returns an empty list if the allocator was not created with [`track_allocations`] enabled.
+    ///
+    /// [`track_allocations`]: GenericMemoryAllocatorCreateInfo::track_allocations
+    pub fn live_allocation_groups(&self) -> Vec<LiveAllocationGroup> {
+        self.tracker
+            .as_ref()
+            .map_or_else(Vec::new, AllocationTracker::live_allocation_groups)
+    }
+
+    /// Returns a human-readable report of the allocations of the allocator that haven't been
+    /// deallocated yet, in the same grouping and order as [`live_allocation_groups`].
+    ///
+    /// This is meant for finding leaks: calling it once all resources should have been dropped,
+    /// for example at the end of a test, lists the places where the remaining ones were created.
+    ///
+    /// Returns an empty string if the allocator was not created with [`track_allocations`]
+    /// enabled.
+    ///
+    /// [`live_allocation_groups`]: Self::live_allocation_groups
+    /// [`track_allocations`]: GenericMemoryAllocatorCreateInfo::track_allocations
+    pub fn live_allocations_report(&self) -> String {
+        self.tracker
+            .as_ref()
+            .map_or_else(String::new, AllocationTracker::live_allocations_report)
+    }
+
+    /// Returns statistics about the memory that the allocator currently has allocated.
+    ///
+    /// The statistics of a pool created with [`create_pool`] only include the memory of the pool,
+    /// and those of the allocator it was created from don't include the memory of the pool.
+    ///
+    /// # Locking behavior
+    ///
+    /// This locks the pool of each memory type in turn, so it shouldn't be called while holding
+    /// an iterator returned by [`DeviceMemoryPool::blocks`].
+    ///
+    /// [`create_pool`]: Self::create_pool
+    pub fn statistics(&self) -> MemoryAllocatorStatistics {
+        let mut statistics = MemoryAllocatorStatistics::default();
+
+        for pool in self.pools.iter() {
+            for block in pool.blocks.lock().iter() {
+                statistics.block_count += 1;
+                statistics.total_block_size += block.device_memory.allocation_size();
+                statistics.free_size += block.free_size();
+                statistics.suballocation_count += block.allocation_count;
+            }
+        }
+
+        statistics.dedicated_allocation_count = self
+            .device_memory_count
+            .load(Ordering::Relaxed)
+            .saturating_sub(statistics.block_count);
+
+        statistics
+    }
+
+    /// Frees the [`DeviceMemory`] blocks that have no allocations left in them, and returns the
+    /// total size of the freed blocks in bytes.
+    ///
+    /// Blocks are otherwise kept until the allocator is dropped, so that they can be reused by
+    /// later allocations. This only frees the blocks of `self`, so trimming a pool created with
+    /// [`create_pool`] doesn't affect the allocator it was created from, and vice versa.
+    ///
+    /// # Locking behavior
+    ///
+    /// This locks the pool of each memory type in turn, so it shouldn't be called while holding
+    /// an iterator returned by [`DeviceMemoryPool::blocks`].
+    ///
+    /// [`create_pool`]: Self::create_pool
+    pub fn trim(&self) -> DeviceSize {
+        let mut freed_size = 0;
+
+        for pool in self.pools.iter() {
+            pool.blocks.lock().retain(|block| {
+                if block.allocation_count != 0 {
+                    return true;
+                }
+
+                freed_size += block.device_memory.allocation_size();
+                self.device_memory_count.fetch_sub(1, Ordering::Relaxed);
+
+                false
+            });
+        }
+
+        freed_size
+    }
+
+    /// Accounts for a new `DeviceMemory` allocation, failing if that would exceed the maximum
+    /// number of blocks.
+    fn reserve_device_memory(&self) -> Result<(), MemoryAllocatorError> {
+        match self.max_device_memory_count {
+            Some(max) => self
+                .device_memory_count
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+                    (count < max).then_some(count + 1)
+                })
+                .map(|_| ())
+                .map_err(|_| MemoryAllocatorError::MaxBlocksExceeded),
+            None => {
+                self.device_memory_count.fetch_add(1, Ordering::Relaxed);
+
+                Ok(())
+            }
+        }
+    }
+
+    fn release_device_memory(&self) {
+        self.device_memory_count.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    #[cold]
+    fn allocate_dedicated_with_flags(
+        &self,
+        memory_type_index: u32,
+        allocation_size: DeviceSize,
+        dedicated_allocation: Option<DedicatedAllocation<'_>>,
+        export_handle_types: ExternalMemoryHandleTypes,
+        flags: MemoryAllocateFlags,
+        opaque_capture_address: u64,
+    ) -> Result<MemoryAlloc, MemoryAllocatorError> {
+        self.reserve_device_memory()?;
+
+        let device_memory = self
+            .allocate_device_memory(
+                memory_type_index,
+                allocation_size,
+                dedicated_allocation,
+                export_handle_types,
+                flags,
+                opaque_capture_address,
+            )
+            .map_err(|err| {
+                self.release_device_memory();
+
+                MemoryAllocatorError::AllocateDeviceMemory(err)
+            })?;
+
+        let allocation = MemoryAlloc {
+            device_memory,
+            suballocation: None,
+            allocation_handle: AllocationHandle::null(),
+        };
+
+        if let Some(tracker) = &self.tracker {
+            tracker.insert(&allocation, AllocationType::Unknown);
+        }
+
+        Ok(allocation)
+    }
+
     #[cold]
     fn allocate_device_memory(
         &self,
@@ -1071,6 +1450,8 @@ impl<S> GenericMemoryAllocator<S> {
         allocation_size: DeviceSize,
         dedicated_allocation: Option<DedicatedAllocation<'_>>,
         export_handle_types: ExternalMemoryHandleTypes,
+        flags: MemoryAllocateFlags,
+        opaque_capture_address: u64,
     ) -> Result<Arc<DeviceMemory>, Validated<VulkanError>> {
         let mut memory = DeviceMemory::allocate(
             self.device.clone(),
@@ -1079,7 +1460,8 @@ impl<S> GenericMemoryAllocator<S> {
                 memory_type_index,
                 dedicated_allocation,
                 export_handle_types,
-                flags: self.flags,
+                flags,
+                opaque_capture_address,
                 ..Default::default()
             },
         )?;
@@ -1096,6 +1478,7 @@ impl<S> GenericMemoryAllocator<S> {
                 memory.map_unchecked(MemoryMapInfo {
                     offset: 0,
                     size: memory.allocation_size(),
+                    placed_address: None,
                     _ne: crate::NonExhaustive(()),
                 })?;
             }
@@ -1111,26 +1494,11 @@ unsafe impl<S: Suballocator + Send + 'static> MemoryAllocator for GenericMemoryA
         memory_type_bits: u32,
         filter: MemoryTypeFilter,
     ) -> Option<u32> {
-        let required_flags = filter.required_flags.into();
-        let preferred_flags = filter.preferred_flags.into();
-        let not_preferred_flags = filter.not_preferred_flags.into();
-
-        self.pools
-            .iter()
-            .map(|pool| ash::vk::MemoryPropertyFlags::from(pool.property_flags))
-            .enumerate()
-            // Filter out memory types which are supported by the memory type bits and have the
-            // required flags set.
-            .filter(|&(index, flags)| {
-                memory_type_bits & (1 << index) != 0 && flags & required_flags == required_flags
-            })
-            // Rank memory types with more of the preferred flags higher, and ones with more of the
-            // not preferred flags lower.
-            .min_by_key(|&(_, flags)| {
-                (!flags & preferred_flags).as_raw().count_ones()
-                    + (flags & not_preferred_flags).as_raw().count_ones()
-            })
-            .map(|(index, _)| index as u32)
+        select_memory_type(
+            self.pools.iter().map(|pool| pool.property_flags),
+            memory_type_bits,
+            filter,
+        )
     }
 
     /// Allocates memory from a specific memory type.
@@ -1188,6 +1556,10 @@ unsafe impl<S: Suballocator + Send + 'static> MemoryAllocator for GenericMemoryA
             if let Ok(allocation) =
                 block.allocate(layout, allocation_type, self.buffer_image_granularity)
             {
+                if let Some(tracker) = &self.tracker {
+                    tracker.insert(&allocation, allocation_type);
+                }
+
                 return Ok(allocation);
             }
         }
@@ -1197,6 +1569,8 @@ unsafe impl<S: Suballocator + Send + 'static> MemoryAllocator for GenericMemoryA
         }
 
         // The pool doesn't have enough real estate, so we need a new block.
+        self.reserve_device_memory()?;
+
         let block = {
             let export_handle_types = if !self.export_handle_types.is_empty() {
                 self.export_handle_types[memory_type_index as usize]
@@ -1213,6 +1587,8 @@ unsafe impl<S: Suballocator + Send + 'static> MemoryAllocator for GenericMemoryA
                     allocation_size,
                     None,
                     export_handle_types,
+                    self.flags,
+                    0,
                 ) {
                     Ok(device_memory) => {
                         break DeviceMemoryBlock::new(device_memory);
@@ -1224,7 +1600,11 @@ unsafe impl<S: Suballocator + Send + 'static> MemoryAllocator for GenericMemoryA
                     )) if i < 3 && pool.block_size >> (i + 1) >= size => {
                         i += 1;
                     }
-                    Err(err) => return Err(MemoryAllocatorError::AllocateDeviceMemory(err)),
+                    Err(err) => {
+                        self.release_device_memory();
+
+                        return Err(MemoryAllocatorError::AllocateDeviceMemory(err));
+                    }
                 }
             }
         };
@@ -1233,7 +1613,13 @@ unsafe impl<S: Suballocator + Send + 'static> MemoryAllocator for GenericMemoryA
         let block = blocks.last_mut().unwrap();
 
         match block.allocate(layout, allocation_type, self.buffer_image_granularity) {
-            Ok(allocation) => Ok(allocation),
+            Ok(allocation) => {
+                if let Some(tracker) = &self.tracker {
+                    tracker.insert(&allocation, allocation_type);
+                }
+
+                Ok(allocation)
+            }
             // This can't happen as we always allocate a block of sufficient size.
             Err(SuballocatorError::OutOfRegionMemory) => unreachable!(),
             // This can't happen as the block is fresher than Febreze and we're still holding an
@@ -1317,11 +1703,26 @@ unsafe impl<S: Suballocator + Send + 'static> MemoryAllocator for GenericMemoryA
             memory_type_filter,
             memory_type_bits: _,
             allocate_preference,
+            name,
+            device_address_capture_replay,
+            opaque_capture_address,
             _ne: _,
         } = create_info;
 
         let size = layout.size();
 
+        // Keep large allocations out of a small BAR heap, unless nothing else is suitable.
+        if size > self.small_bar_allocation_threshold {
+            let without_small_bar = memory_type_bits & !self.small_bar_memory_type_bits;
+
+            if self
+                .find_memory_type_index(without_small_bar, memory_type_filter)
+                .is_some()
+            {
+                memory_type_bits = without_small_bar;
+            }
+        }
+
         let mut memory_type_index = self
             .find_memory_type_index(memory_type_bits, memory_type_filter)
             .ok_or(MemoryAllocatorError::FindMemoryType)?;
@@ -1339,82 +1740,106 @@ unsafe impl<S: Suballocator + Send + 'static> MemoryAllocator for GenericMemoryA
         loop {
             let pool = &self.pools[memory_type_index as usize];
 
-            let res = match allocate_preference {
-                MemoryAllocatePreference::Unknown => {
-                    // VUID-vkBindBufferMemory-buffer-01444
-                    // VUID-vkBindImageMemory-image-01445
-                    if requires_dedicated_allocation {
-                        self.allocate_dedicated(
-                            memory_type_index,
-                            size,
-                            dedicated_allocation,
-                            export_handle_types,
-                        )
-                    } else {
-                        if size > pool.block_size / 2 {
-                            prefers_dedicated_allocation = true;
-                        }
-                        if self.device.allocation_count() > self.max_allocations
-                            && size <= pool.block_size
-                        {
-                            prefers_dedicated_allocation = false;
-                        }
+            // The opaque capture address belongs to the `DeviceMemory`, so it can't be shared.
+            let res = if device_address_capture_replay || opaque_capture_address != 0 {
+                let mut flags = self.flags;
 
-                        if prefers_dedicated_allocation {
+                if device_address_capture_replay {
+                    flags |= MemoryAllocateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY;
+                }
+
+                self.allocate_dedicated_with_flags(
+                    memory_type_index,
+                    size,
+                    dedicated_allocation,
+                    export_handle_types,
+                    flags,
+                    opaque_capture_address,
+                )
+            } else {
+                match allocate_preference {
+                    MemoryAllocatePreference::Unknown => {
+                        // VUID-vkBindBufferMemory-buffer-01444
+                        // VUID-vkBindImageMemory-image-01445
+                        if requires_dedicated_allocation {
                             self.allocate_dedicated(
                                 memory_type_index,
                                 size,
                                 dedicated_allocation,
                                 export_handle_types,
                             )
-                            // Fall back to suballocation.
-                            .or_else(|err| {
-                                self.allocate_from_type(
-                                    memory_type_index,
-                                    layout,
-                                    allocation_type,
-                                    true, // A dedicated allocation already failed.
-                                )
-                                .map_err(|_| err)
-                            })
                         } else {
-                            self.allocate_from_type(
-                                memory_type_index,
-                                layout,
-                                allocation_type,
-                                false,
-                            )
-                            // Fall back to dedicated allocation. It is possible that the 1/8
-                            // block size tried was greater than the allocation size, so
-                            // there's hope.
-                            .or_else(|_| {
+                            if size > pool.block_size / 2 {
+                                prefers_dedicated_allocation = true;
+                            }
+                            if self.device.allocation_count() > self.max_allocations
+                                && size <= pool.block_size
+                            {
+                                prefers_dedicated_allocation = false;
+                            }
+
+                            if prefers_dedicated_allocation {
                                 self.allocate_dedicated(
                                     memory_type_index,
                                     size,
                                     dedicated_allocation,
                                     export_handle_types,
                                 )
-                            })
+                                // Fall back to suballocation.
+                                .or_else(|err| {
+                                    self.allocate_from_type(
+                                        memory_type_index,
+                                        layout,
+                                        allocation_type,
+                                        true, // A dedicated allocation already failed.
+                                    )
+                                    .map_err(|_| err)
+                                })
+                            } else {
+                                self.allocate_from_type(
+                                    memory_type_index,
+                                    layout,
+                                    allocation_type,
+                                    false,
+                                )
+                                // Fall back to dedicated allocation. It is possible that the 1/8
+                                // block size tried was greater than the allocation size, so
+                                // there's hope.
+                                .or_else(|_| {
+                                    self.allocate_dedicated(
+                                        memory_type_index,
+                                        size,
+                                        dedicated_allocation,
+                                        export_handle_types,
+                                    )
+                                })
+                            }
                         }
                     }
-                }
-                MemoryAllocatePreference::NeverAllocate => {
-                    if requires_dedicated_allocation {
-                        return Err(MemoryAllocatorError::DedicatedAllocationRequired);
-                    }
+                    MemoryAllocatePreference::NeverAllocate => {
+                        if requires_dedicated_allocation {
+                            return Err(MemoryAllocatorError::DedicatedAllocationRequired);
+                        }
 
-                    self.allocate_from_type(memory_type_index, layout, allocation_type, true)
+                        self.allocate_from_type(memory_type_index, layout, allocation_type, true)
+                    }
+                    MemoryAllocatePreference::AlwaysAllocate => self.allocate_dedicated(
+                        memory_type_index,
+                        size,
+                        dedicated_allocation,
+                        export_handle_types,
+                    ),
                 }
-                MemoryAllocatePreference::AlwaysAllocate => self.allocate_dedicated(
-                    memory_type_index,
-                    size,
-                    dedicated_allocation,
-                    export_handle_types,
-                ),
             };
 
             match res {
-                Ok(allocation) => return Ok(allocation),
+                Ok(allocation) => {
+                    if let Some(tracker) = &self.tracker {
+                        tracker.annotate(&allocation, allocation_type, name);
+                    }
+
+                    return Ok(allocation);
+                }
                 // Try a different memory type.
                 Err(err) => {
                     memory_type_bits &= !(1 << memory_type_index);
@@ -1434,23 +1859,21 @@ unsafe impl<S: Suballocator + Send + 'static> MemoryAllocator for GenericMemoryA
         dedicated_allocation: Option<DedicatedAllocation<'_>>,
         export_handle_types: ExternalMemoryHandleTypes,
     ) -> Result<MemoryAlloc, MemoryAllocatorError> {
-        let device_memory = self
-            .allocate_device_memory(
-                memory_type_index,
-                allocation_size,
-                dedicated_allocation,
-                export_handle_types,
-            )
-            .map_err(MemoryAllocatorError::AllocateDeviceMemory)?;
-
-        Ok(MemoryAlloc {
-            device_memory,
-            suballocation: None,
-            allocation_handle: AllocationHandle::null(),
-        })
+        self.allocate_dedicated_with_flags(
+            memory_type_index,
+            allocation_size,
+            dedicated_allocation,
+            export_handle_types,
+            self.flags,
+            0,
+        )
     }
 
     unsafe fn deallocate(&self, allocation: MemoryAlloc) {
+        if let Some(tracker) = &self.tracker {
+            tracker.remove(&allocation);
+        }
+
         if let Some(suballocation) = allocation.suballocation {
             let memory_type_index = allocation.device_memory.memory_type_index();
             let pool = self.pools[memory_type_index as usize].blocks.lock();
@@ -1468,9 +1891,11 @@ unsafe impl<S: Suballocator + Send + 'static> MemoryAllocator for GenericMemoryA
             // SAFETY: The caller must guarantee that `allocation` refers to one allocated by
             // `self`, therefore `block_ptr` must be the same one we gave out on allocation. We
             // know that this pointer must be valid, because all blocks are boxed and pinned in
-            // memory and because a block isn't dropped until the allocator itself is dropped, at
-            // which point it would be impossible to call this method. We also know that it must be
-            // valid to create a reference to the block, because we locked the pool it belongs to.
+            // memory and because a block isn't dropped until either the allocator itself is
+            // dropped, at which point it would be impossible to call this method, or the block is
+            // trimmed, which only happens once it has no allocations left. We also know that it
+            // must be valid to create a reference to the block, because we locked the pool it
+            // belongs to.
             let block = &mut *block_ptr;
 
             // SAFETY: The caller must guarantee that `allocation` refers to a currently allocated
@@ -1478,10 +1903,68 @@ unsafe impl<S: Suballocator + Send + 'static> MemoryAllocator for GenericMemoryA
             block.deallocate(suballocation);
 
             drop(pool);
+        } else {
+            // The `DeviceMemory` of a dedicated allocation is freed once `allocation` is dropped.
+            self.release_device_memory();
         }
     }
 }
 
+/// Finds the most suitable memory type among `memory_types` that is allowed by
+/// `memory_type_bits` and satisfies `filter`.
+fn select_memory_type(
+    memory_types: impl IntoIterator<Item = MemoryPropertyFlags>,
+    memory_type_bits: u32,
+    filter: MemoryTypeFilter,
+) -> Option<u32> {
+    let required_flags = filter.required_flags.into();
+    let preferred_flags = filter.preferred_flags.into();
+    let not_preferred_flags = filter.not_preferred_flags.into();
+
+    memory_types
+        .into_iter()
+        .map(ash::vk::MemoryPropertyFlags::from)
+        .enumerate()
+        // Filter out memory types which are supported by the memory type bits and have the
+        // required flags set.
+        .filter(|&(index, flags)| {
+            memory_type_bits & (1 << index) != 0 && flags & required_flags == required_flags
+        })
+        // Rank memory types with more of the preferred flags higher, and ones with more of the
+        // not preferred flags lower.
+        .min_by_key(|&(_, flags)| {
+            (!flags & preferred_flags).as_raw().count_ones()
+                + (flags & not_preferred_flags).as_raw().count_ones()
+        })
+        .map(|(index, _)| index as u32)
+}
+
+/// Returns the memory types that are both device-local and host-visible, but reside in a heap
+/// that is smaller than the largest device-local heap. Without resizable BAR, this is the small
+/// window of VRAM that the host can map directly.
+fn small_bar_memory_type_bits(memory_properties: &MemoryProperties) -> u32 {
+    let max_device_local_heap_size = memory_properties
+        .memory_heaps
+        .iter()
+        .filter(|heap| heap.flags.intersects(MemoryHeapFlags::DEVICE_LOCAL))
+        .map(|heap| heap.size)
+        .max()
+        .unwrap_or(0);
+
+    memory_properties
+        .memory_types
+        .iter()
+        .enumerate()
+        .filter(|(_, memory_type)| {
+            memory_type
+                .property_flags
+                .contains(MemoryPropertyFlags::DEVICE_LOCAL | MemoryPropertyFlags::HOST_VISIBLE)
+                && memory_properties.memory_heaps[memory_type.heap_index as usize].size
+                    < max_device_local_heap_size
+        })
+        .fold(0, |bits, (index, _)| bits | (1 << index))
+}
+
 unsafe impl<T: MemoryAllocator> MemoryAllocator for Arc<T> {
     fn find_memory_type_index(
         &self,
@@ -1820,6 +2303,38 @@ pub struct GenericMemoryAllocatorCreateInfo<'a> {
     /// [`khr_device_group`]: crate::device::DeviceExtensions::khr_device_group
     pub device_address: bool,
 
+    /// The maximum size of an allocation that the allocator places in a small BAR heap, if there
+    /// is another suitable memory type.
+    ///
+    /// A small BAR heap is a heap containing memory types that are both [`DEVICE_LOCAL`] and
+    /// [`HOST_VISIBLE`], that is smaller than the largest device-local heap. Without resizable
+    /// BAR, this is usually a window of around 256 MiB of VRAM, which is best reserved for small,
+    /// frequently updated data such as uniform buffers. Larger allocations are placed in another
+    /// memory type that satisfies the [`MemoryTypeFilter`], for example host-visible system
+    /// memory, and only fall back to the small BAR heap if no such memory type exists. With
+    /// resizable BAR or unified memory, there is no small BAR heap and this has no effect.
+    ///
+    /// The default value is 1 MiB.
+    ///
+    /// [`DEVICE_LOCAL`]: MemoryPropertyFlags::DEVICE_LOCAL
+    /// [`HOST_VISIBLE`]: MemoryPropertyFlags::HOST_VISIBLE
+    pub small_bar_allocation_threshold: DeviceSize,
+
+    /// Whether the allocator should keep a record of each allocation it hands out until it is
+    /// deallocated, including the backtrace it was allocated from.
+    ///
+    /// The records can be retrieved with [`live_allocations`], [`live_allocation_groups`] and
+    /// [`live_allocations_report`], which is useful to find out where leaked buffers and images
+    /// were created. Capturing a backtrace for every allocation is slow, so this should only be
+    /// enabled for debugging. When disabled, the allocator doesn't do any extra work.
+    ///
+    /// The default value is `false`.
+    ///
+    /// [`live_allocations`]: GenericMemoryAllocator::live_allocations
+    /// [`live_allocation_groups`]: GenericMemoryAllocator::live_allocation_groups
+    /// [`live_allocations_report`]: GenericMemoryAllocator::live_allocations_report
+    pub track_allocations: bool,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -1832,11 +2347,75 @@ impl Default for GenericMemoryAllocatorCreateInfo<'_> {
             dedicated_allocation: true,
             export_handle_types: &[],
             device_address: true,
+            small_bar_allocation_threshold: 1024 * 1024,
+            track_allocations: false,
             _ne: crate::NonExhaustive(()),
         }
     }
 }
 
+/// Parameters to create a new pool with [`GenericMemoryAllocator::create_pool`].
+#[derive(Clone, Debug)]
+pub struct PoolCreateInfo {
+    /// The mask of memory type indices that the pool may allocate from. This is combined with the
+    /// mask of the allocator that the pool is created from.
+    ///
+    /// The default value is [`u32::MAX`].
+    pub memory_type_bits: u32,
+
+    /// The size of the [`DeviceMemory`] blocks of the pool, for all memory types.
+    ///
+    /// This has the same meaning as the elements of
+    /// [`GenericMemoryAllocatorCreateInfo::block_sizes`].
+    ///
+    /// The default value is 64 MiB.
+    pub block_size: DeviceSize,
+
+    /// The maximum number of `DeviceMemory` allocations that the pool may have at the same time,
+    /// if any.
+    ///
+    /// This counts both the blocks of the pool and its dedicated allocations. Once the maximum is
+    /// reached, allocations that would need a new `DeviceMemory` allocation fail with
+    /// [`MemoryAllocatorError::MaxBlocksExceeded`].
+    ///
+    /// The default value is `None`.
+    pub max_blocks: Option<usize>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for PoolCreateInfo {
+    #[inline]
+    fn default() -> Self {
+        PoolCreateInfo {
+            memory_type_bits: u32::MAX,
+            block_size: 64 * 1024 * 1024,
+            max_blocks: None,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// Statistics about the memory allocated by a [`GenericMemoryAllocator`], as returned by
+/// [`GenericMemoryAllocator::statistics`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryAllocatorStatistics {
+    /// The number of [`DeviceMemory`] blocks that are used for suballocation.
+    pub block_count: usize,
+
+    /// The total size of the blocks, in bytes.
+    pub total_block_size: DeviceSize,
+
+    /// The total size of the free space in the blocks, in bytes.
+    pub free_size: DeviceSize,
+
+    /// The number of suballocations in the blocks.
+    pub suballocation_count: usize,
+
+    /// The number of dedicated allocations, which each have their own `DeviceMemory`.
+    pub dedicated_allocation_count: usize,
+}
+
 /// > **Note**: Returns `0` on overflow.
 #[inline(always)]
 pub(crate) const fn align_up(val: DeviceSize, alignment: DeviceAlignment) -> DeviceSize {
@@ -1883,3 +2462,212 @@ mod array_vec {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryHeap;
+
+    const MIB: DeviceSize = 1024 * 1024;
+
+    fn synthetic_memory_properties(
+        memory_heaps: &[(DeviceSize, MemoryHeapFlags)],
+        memory_types: &[(MemoryPropertyFlags, u32)],
+    ) -> MemoryProperties {
+        MemoryProperties {
+            memory_types: memory_types
+                .iter()
+                .map(|&(property_flags, heap_index)| MemoryType {
+                    property_flags,
+                    heap_index,
+                })
+                .collect(),
+            memory_heaps: memory_heaps
+                .iter()
+                .map(|&(size, flags)| MemoryHeap { size, flags })
+                .collect(),
+        }
+    }
+
+    fn select(memory_properties: &MemoryProperties, memory_type_bits: u32) -> Option<u32> {
+        select_memory_type(
+            memory_properties
+                .memory_types
+                .iter()
+                .map(|memory_type| memory_type.property_flags),
+            memory_type_bits,
+            MemoryTypeFilter::PREFER_DEVICE_HOST_VISIBLE,
+        )
+    }
+
+    #[test]
+    fn small_bar() {
+        const DEVICE_LOCAL: MemoryPropertyFlags = MemoryPropertyFlags::DEVICE_LOCAL;
+        const HOST: MemoryPropertyFlags =
+            MemoryPropertyFlags::HOST_VISIBLE.union(MemoryPropertyFlags::HOST_COHERENT);
+
+        // A dedicated GPU without resizable BAR.
+        let memory_properties = synthetic_memory_properties(
+            &[
+                (8192 * MIB, MemoryHeapFlags::DEVICE_LOCAL),
+                (16384 * MIB, MemoryHeapFlags::empty()),
+                (256 * MIB, MemoryHeapFlags::DEVICE_LOCAL),
+            ],
+            &[
+                (DEVICE_LOCAL, 0),
+                (HOST, 1),
+                (HOST.union(MemoryPropertyFlags::HOST_CACHED), 1),
+                (DEVICE_LOCAL.union(HOST), 2),
+            ],
+        );
+        let small_bar_bits = small_bar_memory_type_bits(&memory_properties);
+        assert_eq!(small_bar_bits, 1 << 3);
+        assert_eq!(select(&memory_properties, u32::MAX), Some(3));
+        assert_eq!(select(&memory_properties, !small_bar_bits), Some(1));
+        assert_eq!(select(&memory_properties, 1 << 3), Some(3));
+        assert_eq!(select(&memory_properties, 1 << 0), None);
+
+        // A dedicated GPU with resizable BAR.
+        let memory_properties = synthetic_memory_properties(
+            &[
+                (8192 * MIB, MemoryHeapFlags::DEVICE_LOCAL),
+                (16384 * MIB, MemoryHeapFlags::empty()),
+            ],
+            &[(DEVICE_LOCAL, 0), (HOST, 1), (DEVICE_LOCAL.union(HOST), 0)],
+        );
+        assert_eq!(small_bar_memory_type_bits(&memory_properties), 0);
+        assert_eq!(select(&memory_properties, u32::MAX), Some(2));
+
+        // Unified memory.
+        let memory_properties = synthetic_memory_properties(
+            &[(16384 * MIB, MemoryHeapFlags::DEVICE_LOCAL)],
+            &[(DEVICE_LOCAL, 0), (DEVICE_LOCAL.union(HOST), 0)],
+        );
+        assert_eq!(small_bar_memory_type_bits(&memory_properties), 0);
+        assert_eq!(select(&memory_properties, u32::MAX), Some(1));
+
+        // No device-local host-visible memory at all.
+        let memory_properties = synthetic_memory_properties(
+            &[
+                (8192 * MIB, MemoryHeapFlags::DEVICE_LOCAL),
+                (16384 * MIB, MemoryHeapFlags::empty()),
+            ],
+            &[(DEVICE_LOCAL, 0), (HOST, 1)],
+        );
+        assert_eq!(small_bar_memory_type_bits(&memory_properties), 0);
+        assert_eq!(select(&memory_properties, u32::MAX), Some(1));
+    }
+
+    #[test]
+    fn track_allocations() {
+        use crate::buffer::{Buffer, BufferCreateInfo, BufferUsage};
+
+        let (device, _) = gfx_dev_and_queue!();
+        let block_sizes: Vec<_> = device
+            .physical_device()
+            .memory_properties()
+            .memory_types
+            .iter()
+            .map(|_| 64 * MIB)
+            .collect();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new(
+            device,
+            GenericMemoryAllocatorCreateInfo {
+                block_sizes: &block_sizes,
+                track_allocations: true,
+                ..Default::default()
+            },
+        ));
+
+        let create_buffer = |name: &str| {
+            Buffer::new_slice::<u32>(
+                memory_allocator.clone(),
+                BufferCreateInfo {
+                    usage: BufferUsage::STORAGE_BUFFER,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    name: Some(name.into()),
+                    ..Default::default()
+                },
+                256,
+            )
+            .unwrap()
+        };
+
+        let buffers: Vec<_> = (0..2).map(|_| create_buffer("leaked")).collect();
+        drop(create_buffer("dropped"));
+
+        let groups = memory_allocator.live_allocation_groups();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].name.as_deref(), Some("leaked"));
+        assert_eq!(groups[0].count, 2);
+        assert!(groups[0].total_size >= 2 * 256 * 4);
+        assert!(memory_allocator
+            .live_allocations()
+            .iter()
+            .all(|allocation| allocation.allocation_type == AllocationType::Linear));
+        assert!(memory_allocator
+            .live_allocations_report()
+            .starts_with("2 live allocations"));
+
+        drop(buffers);
+        assert!(memory_allocator.live_allocations().is_empty());
+    }
+
+    #[test]
+    fn pool() {
+        use crate::buffer::{AllocateBufferError, Buffer, BufferCreateInfo, BufferUsage};
+
+        let (device, _) = gfx_dev_and_queue!();
+        let memory_allocator = StandardMemoryAllocator::new_default(device);
+        let pool = Arc::new(
+            memory_allocator.create_pool::<FreeListAllocator>(PoolCreateInfo {
+                block_size: MIB,
+                max_blocks: Some(1),
+                ..Default::default()
+            }),
+        );
+
+        let create_buffer = |size| {
+            Buffer::new_slice::<u8>(
+                pool.clone(),
+                BufferCreateInfo {
+                    usage: BufferUsage::STORAGE_BUFFER,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+                size,
+            )
+        };
+
+        let buffers = [create_buffer(1024).unwrap(), create_buffer(1024).unwrap()];
+        let statistics = pool.statistics();
+        assert_eq!(statistics.block_count, 1);
+        assert_eq!(statistics.suballocation_count, 2);
+        assert_eq!(statistics.dedicated_allocation_count, 0);
+        assert_eq!(
+            memory_allocator.statistics(),
+            MemoryAllocatorStatistics::default()
+        );
+
+        // The only block is taken, so the large buffer can't get a dedicated allocation.
+        assert!(matches!(
+            create_buffer(2 * MIB),
+            Err(Validated::Error(AllocateBufferError::AllocateMemory(
+                MemoryAllocatorError::MaxBlocksExceeded,
+            ))),
+        ));
+
+        drop(buffers);
+        assert_eq!(pool.statistics().suballocation_count, 0);
+        assert_eq!(pool.statistics().block_count, 1);
+        assert_ne!(pool.trim(), 0);
+        assert_eq!(pool.statistics().block_count, 0);
+
+        let buffer = create_buffer(2 * MIB).unwrap();
+        assert_eq!(pool.statistics().dedicated_allocation_count, 1);
+        drop(buffer);
+        assert_eq!(pool.statistics(), MemoryAllocatorStatistics::default());
+    }
+}
@@ -0,0 +1,187 @@
+use super::{AllocationType, MemoryAlloc};
+use crate::DeviceSize;
+use ahash::HashMap;
+use parking_lot::Mutex;
+use std::{
+    backtrace::Backtrace,
+    fmt::{Debug, Error as FmtError, Formatter, Write},
+    sync::Arc,
+};
+
+/// An allocation that is currently alive, as recorded by a [`GenericMemoryAllocator`] that was
+/// created with [`track_allocations`] enabled.
+///
+/// [`GenericMemoryAllocator`]: super::GenericMemoryAllocator
+/// [`track_allocations`]: super::GenericMemoryAllocatorCreateInfo::track_allocations
+#[derive(Clone, Debug)]
+pub struct LiveAllocation {
+    /// The type of resources that the allocation was made for. This is [`AllocationType::Linear`]
+    /// for buffers and linear images, and [`AllocationType::NonLinear`] for optimal images.
+    pub allocation_type: AllocationType,
+
+    /// The size of the allocation in bytes.
+    pub size: DeviceSize,
+
+    /// The index of the memory type that the allocation was made from.
+    pub memory_type_index: u32,
+
+    /// Whether the allocation has its own block of [`DeviceMemory`], rather than being
+    /// suballocated from a shared block.
+    ///
+    /// [`DeviceMemory`]: crate::memory::DeviceMemory
+    pub dedicated: bool,
+
+    /// The name that was given in [`AllocationCreateInfo::name`], if any.
+    ///
+    /// [`AllocationCreateInfo::name`]: super::AllocationCreateInfo::name
+    pub name: Option<Arc<str>>,
+
+    /// The backtrace of the thread at the time the allocation was made.
+    pub backtrace: Arc<Backtrace>,
+}
+
+/// A group of [live allocations] that share the same name and backtrace, as returned by
+/// [`live_allocation_groups`].
+///
+/// [live allocations]: LiveAllocation
+/// [`live_allocation_groups`]: super::GenericMemoryAllocator::live_allocation_groups
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LiveAllocationGroup {
+    /// The name that was given in [`AllocationCreateInfo::name`], if any.
+    ///
+    /// [`AllocationCreateInfo::name`]: super::AllocationCreateInfo::name
+    pub name: Option<Arc<str>>,
+
+    /// The formatted backtrace that the allocations of the group were made from.
+    pub backtrace: String,
+
+    /// The number of allocations in the group.
+    pub count: usize,
+
+    /// The total size of the allocations in the group, in bytes.
+    pub total_size: DeviceSize,
+}
+
+/// Keeps a record of each allocation that is handed out by an allocator, until it is deallocated.
+#[derive(Default)]
+pub(super) struct AllocationTracker {
+    // Allocations are identified by their block of device memory and their offset within it.
+    allocations: Mutex<HashMap<(usize, DeviceSize), LiveAllocation>>,
+}
+
+impl Debug for AllocationTracker {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        f.debug_struct("AllocationTracker")
+            .field("live_allocations", &self.allocations.lock().len())
+            .finish()
+    }
+}
+
+impl AllocationTracker {
+    fn key(allocation: &MemoryAlloc) -> (usize, DeviceSize) {
+        (
+            Arc::as_ptr(&allocation.device_memory) as usize,
+            allocation
+                .suballocation
+                .as_ref()
+                .map_or(0, |suballocation| suballocation.offset),
+        )
+    }
+
+    #[cold]
+    pub(super) fn insert(&self, allocation: &MemoryAlloc, allocation_type: AllocationType) {
+        let record = LiveAllocation {
+            allocation_type,
+            size: allocation.suballocation.as_ref().map_or(
+                allocation.device_memory.allocation_size(),
+                |suballocation| suballocation.size,
+            ),
+            memory_type_index: allocation.device_memory.memory_type_index(),
+            dedicated: allocation.suballocation.is_none(),
+            name: None,
+            backtrace: Arc::new(Backtrace::force_capture()),
+        };
+
+        self.allocations
+            .lock()
+            .insert(Self::key(allocation), record);
+    }
+
+    /// Fills in the information about an allocation that is only known by the caller of the
+    /// method that recorded it.
+    #[cold]
+    pub(super) fn annotate(
+        &self,
+        allocation: &MemoryAlloc,
+        allocation_type: AllocationType,
+        name: Option<Arc<str>>,
+    ) {
+        if let Some(record) = self.allocations.lock().get_mut(&Self::key(allocation)) {
+            record.allocation_type = allocation_type;
+            record.name = name;
+        }
+    }
+
+    #[cold]
+    pub(super) fn remove(&self, allocation: &MemoryAlloc) {
+        self.allocations.lock().remove(&Self::key(allocation));
+    }
+
+    pub(super) fn live_allocations(&self) -> Vec<LiveAllocation> {
+        self.allocations.lock().values().cloned().collect()
+    }
+
+    pub(super) fn live_allocation_groups(&self) -> Vec<LiveAllocationGroup> {
+        let mut groups: HashMap<(Option<Arc<str>>, String), (usize, DeviceSize)> =
+            HashMap::default();
+
+        for allocation in self.allocations.lock().values() {
+            let (count, total_size) = groups
+                .entry((allocation.name.clone(), allocation.backtrace.to_string()))
+                .or_default();
+            *count += 1;
+            *total_size += allocation.size;
+        }
+
+        let mut groups: Vec<_> = groups
+            .into_iter()
+            .map(
+                |((name, backtrace), (count, total_size))| LiveAllocationGroup {
+                    name,
+                    backtrace,
+                    count,
+                    total_size,
+                },
+            )
+            .collect();
+        groups.sort_by(|a, b| b.total_size.cmp(&a.total_size).then(b.count.cmp(&a.count)));
+
+        groups
+    }
+
+    pub(super) fn live_allocations_report(&self) -> String {
+        let groups = self.live_allocation_groups();
+        let count: usize = groups.iter().map(|group| group.count).sum();
+        let total_size: DeviceSize = groups.iter().map(|group| group.total_size).sum();
+        let mut report = format!(
+            "{} live allocations, {} bytes in total\n",
+            count, total_size,
+        );
+
+        for group in groups {
+            let _ = write!(
+                report,
+                "\n{} allocations, {} bytes",
+                group.count, group.total_size,
+            );
+
+            if let Some(name) = &group.name {
+                let _ = write!(report, ", named `{}`", name);
+            }
+
+            let _ = write!(report, ", allocated at:\n{}\n", group.backtrace);
+        }
+
+        report
+    }
+}
@@ -157,6 +157,7 @@ impl DeviceMemory {
             dedicated_allocation,
             export_handle_types,
             flags,
+            opaque_capture_address,
             _ne: _,
         } = allocate_info;
 
@@ -171,6 +172,7 @@ impl DeviceMemory {
         let mut import_fd_info_vk = None;
         let mut import_win32_handle_info_vk = None;
         let mut flags_info_vk = None;
+        let mut opaque_capture_address_info_vk = None;
 
         // VUID-VkMemoryDedicatedAllocateInfo-image-01432
         if let Some(dedicated_allocation) = dedicated_allocation {
@@ -256,6 +258,18 @@ impl DeviceMemory {
             allocate_info_vk.p_next = <*const _>::cast(next);
         }
 
+        if opaque_capture_address != 0 {
+            let next = opaque_capture_address_info_vk.insert(
+                ash::vk::MemoryOpaqueCaptureAddressAllocateInfo {
+                    opaque_capture_address,
+                    ..Default::default()
+                },
+            );
+
+            next.p_next = allocate_info_vk.p_next;
+            allocate_info_vk.p_next = <*const _>::cast(next);
+        }
+
         // VUID-vkAllocateMemory-maxMemoryAllocationCount-04101
         let max_allocations = device
             .physical_device()
@@ -329,6 +343,7 @@ impl DeviceMemory {
             dedicated_allocation,
             export_handle_types,
             flags,
+            opaque_capture_address: _,
             _ne: _,
         } = allocate_info;
 
@@ -399,6 +414,87 @@ impl DeviceMemory {
         self.flags
     }
 
+    /// Returns the opaque capture address of the memory allocation.
+    ///
+    /// When replaying a capture, the value can be given as
+    /// [`MemoryAllocateInfo::opaque_capture_address`] to allocate memory with the same device
+    /// address. This is only meaningful if the memory was allocated with the
+    /// [`MemoryAllocateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY`] flag.
+    ///
+    /// The device API version must be at least 1.2, or the [`khr_buffer_device_address`]
+    /// extension must be enabled on the device. The
+    /// [`buffer_device_address_capture_replay`][feature] feature must also be enabled, and
+    /// `self.flags()` must contain [`MemoryAllocateFlags::DEVICE_ADDRESS`].
+    ///
+    /// [`khr_buffer_device_address`]: crate::device::DeviceExtensions::khr_buffer_device_address
+    /// [feature]: crate::device::DeviceFeatures::buffer_device_address_capture_replay
+    #[inline]
+    pub fn opaque_capture_address(&self) -> Result<u64, Box<ValidationError>> {
+        self.validate_opaque_capture_address()?;
+
+        unsafe { Ok(self.opaque_capture_address_unchecked()) }
+    }
+
+    fn validate_opaque_capture_address(&self) -> Result<(), Box<ValidationError>> {
+        let device = self.device();
+
+        if !(device.api_version() >= Version::V1_2
+            || device.enabled_extensions().khr_buffer_device_address)
+        {
+            return Err(Box::new(ValidationError {
+                requires_one_of: RequiresOneOf(&[
+                    RequiresAllOf(&[Requires::APIVersion(Version::V1_2)]),
+                    RequiresAllOf(&[Requires::DeviceExtension("khr_buffer_device_address")]),
+                ]),
+                ..Default::default()
+            }));
+        }
+
+        if !device
+            .enabled_features()
+            .buffer_device_address_capture_replay
+        {
+            return Err(Box::new(ValidationError {
+                requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceFeature(
+                    "buffer_device_address_capture_replay",
+                )])]),
+                vuids: &["VUID-vkGetDeviceMemoryOpaqueCaptureAddress-None-03334"],
+                ..Default::default()
+            }));
+        }
+
+        if !self.flags.intersects(MemoryAllocateFlags::DEVICE_ADDRESS) {
+            return Err(Box::new(ValidationError {
+                context: "self.flags()".into(),
+                problem: "does not contain `MemoryAllocateFlags::DEVICE_ADDRESS`".into(),
+                vuids: &["VUID-VkDeviceMemoryOpaqueCaptureAddressInfo-memory-03336"],
+                ..Default::default()
+            }));
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn opaque_capture_address_unchecked(&self) -> u64 {
+        let device = self.device();
+
+        let info_vk = ash::vk::DeviceMemoryOpaqueCaptureAddressInfo {
+            memory: self.handle,
+            ..Default::default()
+        };
+
+        let fns = device.fns();
+        let f = if device.api_version() >= Version::V1_2 {
+            fns.v1_2.get_device_memory_opaque_capture_address
+        } else {
+            fns.khr_buffer_device_address
+                .get_device_memory_opaque_capture_address_khr
+        };
+        f(device.handle(), &info_vk)
+    }
+
     /// Returns the current mapping state, or [`None`] if the memory is not currently host-mapped.
     #[inline]
     pub fn mapping_state(&self) -> Option<&MappingState> {
@@ -416,13 +512,62 @@ impl DeviceMemory {
     /// Maps a range of memory to be accessed by the host.
     ///
     /// `self` must not be host-mapped already and must be allocated from host-visible memory.
+    /// [`map_info.placed_address`] must be `None`; use [`map_placed`] to map the memory at a
+    /// specific address.
+    ///
+    /// [`map_info.placed_address`]: MemoryMapInfo::placed_address
+    /// [`map_placed`]: Self::map_placed
     #[inline]
     pub fn map(&mut self, map_info: MemoryMapInfo) -> Result<(), Validated<VulkanError>> {
+        if map_info.placed_address.is_some() {
+            return Err(Box::new(ValidationError {
+                context: "map_info.placed_address".into(),
+                problem: "is `Some`; placed mappings must be created with `map_placed`".into(),
+                ..Default::default()
+            })
+            .into());
+        }
+
         self.validate_map(&map_info)?;
 
         unsafe { Ok(self.map_unchecked(map_info)?) }
     }
 
+    /// Maps a range of memory to be accessed by the host, at the host virtual address given in
+    /// [`map_info.placed_address`].
+    ///
+    /// The same requirements as for [`map`] apply. In addition, [`map_info.placed_address`] must
+    /// be `Some`, and the [`memory_map_placed`] feature must be enabled on the device.
+    ///
+    /// # Safety
+    ///
+    /// - The range of host virtual addresses starting at [`map_info.placed_address`], with the
+    ///   size of the mapping, must have been reserved by the caller, and must not be in use by
+    ///   anything else. Any existing mapping in that range is replaced.
+    ///
+    /// [`map_info.placed_address`]: MemoryMapInfo::placed_address
+    /// [`map`]: Self::map
+    /// [`memory_map_placed`]: crate::device::DeviceFeatures::memory_map_placed
+    #[inline]
+    pub unsafe fn map_placed(
+        &mut self,
+        map_info: MemoryMapInfo,
+    ) -> Result<(), Validated<VulkanError>> {
+        if map_info.placed_address.is_none() {
+            return Err(Box::new(ValidationError {
+                context: "map_info.placed_address".into(),
+                problem: "is `None`".into(),
+                vuids: &["VUID-VkMemoryMapPlacedInfoEXT-flags-09576"],
+                ..Default::default()
+            })
+            .into());
+        }
+
+        self.validate_map(&map_info)?;
+
+        Ok(self.map_unchecked(map_info)?)
+    }
+
     fn validate_map(&self, map_info: &MemoryMapInfo) -> Result<(), Box<ValidationError>> {
         if self.mapping_state.is_some() {
             return Err(Box::new(ValidationError {
@@ -463,6 +608,7 @@ impl DeviceMemory {
         let MemoryMapInfo {
             offset,
             size,
+            placed_address,
             _ne: _,
         } = map_info;
 
@@ -476,13 +622,26 @@ impl DeviceMemory {
             let mut output = MaybeUninit::uninit();
 
             if device.enabled_extensions().khr_map_memory2 {
-                let map_info_vk = ash::vk::MemoryMapInfoKHR {
+                let mut map_info_vk = ash::vk::MemoryMapInfoKHR {
                     flags: ash::vk::MemoryMapFlags::empty(),
                     memory: self.handle(),
                     offset,
                     size,
                     ..Default::default()
                 };
+                let mut map_placed_info_vk = None;
+
+                if let Some(placed_address) = placed_address {
+                    map_info_vk.flags |= ash::vk::MemoryMapFlags::PLACED_EXT;
+
+                    let next = map_placed_info_vk.insert(ash::vk::MemoryMapPlacedInfoEXT {
+                        p_placed_address: placed_address.as_ptr(),
+                        ..Default::default()
+                    });
+
+                    next.p_next = map_info_vk.p_next;
+                    map_info_vk.p_next = <*const _>::cast(next);
+                }
 
                 (fns.khr_map_memory2.map_memory2_khr)(
                     device.handle(),
@@ -900,6 +1059,16 @@ pub struct MemoryAllocateInfo<'d> {
     /// The default value is [`MemoryAllocateFlags::empty()`].
     pub flags: MemoryAllocateFlags,
 
+    /// The opaque capture address to allocate the memory with, to replay a capture.
+    ///
+    /// If this value is not zero, then `flags` must contain
+    /// [`MemoryAllocateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY`]. The value should have been returned
+    /// by [`DeviceMemory::opaque_capture_address`] for memory that was allocated with the same
+    /// parameters.
+    ///
+    /// The default value is `0`.
+    pub opaque_capture_address: u64,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -912,6 +1081,7 @@ impl Default for MemoryAllocateInfo<'static> {
             dedicated_allocation: None,
             export_handle_types: ExternalMemoryHandleTypes::empty(),
             flags: MemoryAllocateFlags::empty(),
+            opaque_capture_address: 0,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -927,6 +1097,7 @@ impl<'d> MemoryAllocateInfo<'d> {
             dedicated_allocation: Some(dedicated_allocation),
             export_handle_types: ExternalMemoryHandleTypes::empty(),
             flags: MemoryAllocateFlags::empty(),
+            opaque_capture_address: 0,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -938,6 +1109,7 @@ impl<'d> MemoryAllocateInfo<'d> {
             ref dedicated_allocation,
             export_handle_types,
             flags,
+            opaque_capture_address,
             _ne: _,
         } = self;
 
@@ -1107,6 +1279,44 @@ impl<'d> MemoryAllocateInfo<'d> {
                     }));
                 }
             }
+
+            if flags.intersects(MemoryAllocateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY) {
+                if !((device.api_version() >= Version::V1_2
+                    || device.enabled_extensions().khr_buffer_device_address)
+                    && device
+                        .enabled_features()
+                        .buffer_device_address_capture_replay)
+                {
+                    return Err(Box::new(ValidationError {
+                        context: "flags".into(),
+                        problem: "contains `MemoryAllocateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY`"
+                            .into(),
+                        requires_one_of: RequiresOneOf(&[
+                            RequiresAllOf(&[
+                                Requires::APIVersion(Version::V1_2),
+                                Requires::DeviceFeature("buffer_device_address_capture_replay"),
+                            ]),
+                            RequiresAllOf(&[
+                                Requires::DeviceExtension("khr_buffer_device_address"),
+                                Requires::DeviceFeature("buffer_device_address_capture_replay"),
+                            ]),
+                        ]),
+                        vuids: &["VUID-VkMemoryAllocateInfo-flags-03330"],
+                    }));
+                }
+            }
+        }
+
+        if opaque_capture_address != 0
+            && !flags.intersects(MemoryAllocateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY)
+        {
+            return Err(Box::new(ValidationError {
+                problem: "`opaque_capture_address` is not zero, but `flags` does not contain \
+                    `MemoryAllocateFlags::DEVICE_ADDRESS_CAPTURE_REPLAY`"
+                    .into(),
+                vuids: &["VUID-VkMemoryAllocateInfo-opaqueCaptureAddress-03329"],
+                ..Default::default()
+            }));
         }
 
         Ok(())
@@ -1356,8 +1566,15 @@ vulkan_bitflags! {
     /// [`ext_buffer_device_address`]: crate::device::DeviceExtensions::ext_buffer_device_address
     DEVICE_ADDRESS = DEVICE_ADDRESS,
 
-    /* TODO: enable
-    DEVICE_ADDRESS_CAPTURE_REPLAY = DEVICE_ADDRESS_CAPTURE_REPLAY,*/
+    /// Specifies that the device address of the allocated memory can be saved and reused on a
+    /// subsequent run, by passing the [`opaque_capture_address`] of the memory to
+    /// [`MemoryAllocateInfo::opaque_capture_address`] when allocating the memory again. This
+    /// requires that the [`buffer_device_address_capture_replay`][feature] feature is enabled on
+    /// the device.
+    ///
+    /// [`opaque_capture_address`]: DeviceMemory::opaque_capture_address
+    /// [feature]: crate::device::DeviceFeatures::buffer_device_address_capture_replay
+    DEVICE_ADDRESS_CAPTURE_REPLAY = DEVICE_ADDRESS_CAPTURE_REPLAY,
 }
 
 /// Parameters of a memory map operation.
@@ -1388,6 +1605,22 @@ pub struct MemoryMapInfo {
     /// [`non_coherent_atom_size`]: crate::device::DeviceProperties::non_coherent_atom_size
     pub size: DeviceSize,
 
+    /// If `Some`, the memory is mapped at this host virtual address, instead of at an address
+    /// chosen by the implementation. This can only be used with [`DeviceMemory::map_placed`].
+    ///
+    /// If `Some`, the [`memory_map_placed`] feature must be enabled on the device, and the
+    /// address must be a multiple of the [`min_placed_memory_map_alignment`] device property.
+    /// If the [`memory_map_range_placed`] feature is not enabled on the device, then `offset`
+    /// must be `0` and `size` must be the allocation size of the device memory. Otherwise,
+    /// `offset` and `size` must be multiples of [`min_placed_memory_map_alignment`].
+    ///
+    /// The default value is `None`.
+    ///
+    /// [`memory_map_placed`]: crate::device::DeviceFeatures::memory_map_placed
+    /// [`memory_map_range_placed`]: crate::device::DeviceFeatures::memory_map_range_placed
+    /// [`min_placed_memory_map_alignment`]: crate::device::DeviceProperties::min_placed_memory_map_alignment
+    pub placed_address: Option<NonNull<c_void>>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -1396,6 +1629,7 @@ impl MemoryMapInfo {
         let &Self {
             offset,
             size,
+            placed_address,
             _ne: _,
         } = self;
 
@@ -1450,6 +1684,108 @@ impl MemoryMapInfo {
             }));
         }
 
+        if let Some(placed_address) = placed_address {
+            let device = memory.device();
+
+            if !device.enabled_features().memory_map_placed {
+                return Err(Box::new(ValidationError {
+                    context: "placed_address".into(),
+                    problem: "is `Some`".into(),
+                    requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceFeature(
+                        "memory_map_placed",
+                    )])]),
+                    vuids: &["VUID-VkMemoryMapInfoKHR-flags-09569"],
+                }));
+            }
+
+            let alignment = DeviceAlignment::new(
+                device
+                    .physical_device()
+                    .properties()
+                    .min_placed_memory_map_alignment
+                    .unwrap(),
+            )
+            .unwrap();
+
+            if device.enabled_features().memory_map_range_placed {
+                if !is_aligned(offset, alignment) {
+                    return Err(Box::new(ValidationError {
+                        problem: "`placed_address` is `Some`, but `offset` is not a multiple of \
+                            the `min_placed_memory_map_alignment` device property"
+                            .into(),
+                        vuids: &["VUID-VkMemoryMapInfoKHR-flags-09573"],
+                        ..Default::default()
+                    }));
+                }
+
+                if !is_aligned(size, alignment) {
+                    return Err(Box::new(ValidationError {
+                        problem: "`placed_address` is `Some`, but `size` is not a multiple of \
+                            the `min_placed_memory_map_alignment` device property"
+                            .into(),
+                        vuids: &["VUID-VkMemoryMapInfoKHR-flags-09574"],
+                        ..Default::default()
+                    }));
+                }
+            } else {
+                if offset != 0 {
+                    return Err(Box::new(ValidationError {
+                        problem: "`placed_address` is `Some`, but `offset` is not 0".into(),
+                        requires_one_of: RequiresOneOf(&[RequiresAllOf(&[
+                            Requires::DeviceFeature("memory_map_range_placed"),
+                        ])]),
+                        vuids: &["VUID-VkMemoryMapInfoKHR-flags-09571"],
+                        ..Default::default()
+                    }));
+                }
+
+                if size != memory.allocation_size() {
+                    return Err(Box::new(ValidationError {
+                        problem: "`placed_address` is `Some`, but `size` is not equal to \
+                            `self.allocation_size()`"
+                            .into(),
+                        requires_one_of: RequiresOneOf(&[RequiresAllOf(&[
+                            Requires::DeviceFeature("memory_map_range_placed"),
+                        ])]),
+                        vuids: &["VUID-VkMemoryMapInfoKHR-flags-09572"],
+                        ..Default::default()
+                    }));
+                }
+
+                if !is_aligned(size, alignment) {
+                    return Err(Box::new(ValidationError {
+                        problem: "`placed_address` is `Some`, but `self.allocation_size()` is \
+                            not a multiple of the `min_placed_memory_map_alignment` device \
+                            property"
+                            .into(),
+                        vuids: &["VUID-VkMemoryMapInfoKHR-flags-09651"],
+                        ..Default::default()
+                    }));
+                }
+            }
+
+            if !is_aligned(placed_address.as_ptr() as DeviceSize, alignment) {
+                return Err(Box::new(ValidationError {
+                    context: "placed_address".into(),
+                    problem: "is not a multiple of the `min_placed_memory_map_alignment` \
+                        device property"
+                        .into(),
+                    vuids: &["VUID-VkMemoryMapPlacedInfoEXT-flags-09577"],
+                    ..Default::default()
+                }));
+            }
+
+            if memory.imported_handle_type == Some(ExternalMemoryHandleType::HostAllocation) {
+                return Err(Box::new(ValidationError {
+                    problem: "`placed_address` is `Some`, but the device memory was imported \
+                        from a host allocation"
+                        .into(),
+                    vuids: &["VUID-VkMemoryMapInfoKHR-flags-09575"],
+                    ..Default::default()
+                }));
+            }
+        }
+
         Ok(())
     }
 }
@@ -1460,6 +1796,7 @@ impl Default for MemoryMapInfo {
         MemoryMapInfo {
             offset: 0,
             size: 0,
+            placed_address: None,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -2141,8 +2478,45 @@ unsafe impl Sync for MappedDeviceMemory {}
 
 #[cfg(test)]
 mod tests {
-    use super::MemoryAllocateInfo;
-    use crate::memory::{DeviceMemory, MemoryPropertyFlags};
+    use super::{MemoryAllocateInfo, MemoryMapInfo};
+    use crate::{
+        device::{Device, DeviceExtensions, DeviceFeatures},
+        memory::{DeviceMemory, MemoryPropertyFlags},
+        DeviceSize, Validated,
+    };
+    use std::{ptr::NonNull, sync::Arc};
+
+    // Allocates `allocation_size` bytes of host-visible memory.
+    fn host_visible_memory(device: Arc<Device>, allocation_size: DeviceSize) -> DeviceMemory {
+        let memory_type_index = device
+            .physical_device()
+            .memory_properties()
+            .memory_types
+            .iter()
+            .position(|t| {
+                t.property_flags
+                    .intersects(MemoryPropertyFlags::HOST_VISIBLE)
+            })
+            .unwrap() as u32;
+
+        DeviceMemory::allocate(
+            device,
+            MemoryAllocateInfo {
+                allocation_size,
+                memory_type_index,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    }
+
+    // Returns the VUIDs of the validation error that `result` holds.
+    fn vuids(result: Result<(), Validated<crate::VulkanError>>) -> &'static [&'static str] {
+        match result {
+            Err(Validated::ValidationError(err)) => err.vuids,
+            _ => panic!("expected a validation error"),
+        }
+    }
 
     #[test]
     fn create() {
@@ -2271,4 +2645,99 @@ mod tests {
         }
         assert_eq!(device.allocation_count(), 1);
     }
+
+    #[test]
+    fn map_placed_requires_feature() {
+        let (device, _) = gfx_dev_and_queue!();
+        let mut memory = host_visible_memory(device, 65536);
+
+        let size = memory.allocation_size();
+        let map_info = || MemoryMapInfo {
+            size,
+            placed_address: Some(NonNull::dangling()),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            memory.map(map_info()),
+            Err(Validated::ValidationError(err)) if err.context == "map_info.placed_address",
+        ));
+        assert_eq!(
+            vuids(unsafe { memory.map_placed(map_info()) }),
+            ["VUID-VkMemoryMapInfoKHR-flags-09569"],
+        );
+        assert!(memory.mapping_state().is_none());
+    }
+
+    #[test]
+    fn map_placed_validation() {
+        let Some((device, _)) = crate::testing::test_device(
+            &DeviceExtensions {
+                ext_map_memory_placed: true,
+                ..DeviceExtensions::empty()
+            },
+            &DeviceFeatures {
+                memory_map_placed: true,
+                ..DeviceFeatures::empty()
+            },
+        ) else {
+            return;
+        };
+
+        let alignment = device
+            .physical_device()
+            .properties()
+            .min_placed_memory_map_alignment
+            .unwrap();
+        let mut memory = host_visible_memory(device, alignment.max(65536) * 2);
+        let size = memory.allocation_size();
+
+        // `map_placed` needs an address.
+        assert_eq!(
+            vuids(unsafe {
+                memory.map_placed(MemoryMapInfo {
+                    size,
+                    ..Default::default()
+                })
+            }),
+            ["VUID-VkMemoryMapPlacedInfoEXT-flags-09576"],
+        );
+
+        // Without `memory_map_range_placed`, the whole allocation must be mapped.
+        assert_eq!(
+            vuids(unsafe {
+                memory.map_placed(MemoryMapInfo {
+                    offset: size / 2,
+                    size: size / 2,
+                    placed_address: Some(NonNull::dangling()),
+                    ..Default::default()
+                })
+            }),
+            ["VUID-VkMemoryMapInfoKHR-flags-09571"],
+        );
+        assert_eq!(
+            vuids(unsafe {
+                memory.map_placed(MemoryMapInfo {
+                    size: size / 2,
+                    placed_address: Some(NonNull::dangling()),
+                    ..Default::default()
+                })
+            }),
+            ["VUID-VkMemoryMapInfoKHR-flags-09572"],
+        );
+
+        // A dangling pointer to `c_void` has an alignment of 1.
+        assert_eq!(
+            vuids(unsafe {
+                memory.map_placed(MemoryMapInfo {
+                    size,
+                    placed_address: Some(NonNull::dangling()),
+                    ..Default::default()
+                })
+            }),
+            ["VUID-VkMemoryMapPlacedInfoEXT-flags-09577"],
+        );
+
+        assert!(memory.mapping_state().is_none());
+    }
 }
@@ -92,7 +92,8 @@ use self::allocator::{
     align_up, AllocationHandle, AllocationType, DeviceLayout, MemoryAlloc, MemoryAllocator,
     Suballocation,
 };
-pub use self::{alignment::*, device_memory::*};
+pub(crate) use self::requirements_cache::MemoryRequirementsCache;
+pub use self::{alignment::*, device_memory::*, requirements_cache::MemoryRequirementsCacheStats};
 use crate::{
     buffer::{sys::RawBuffer, Subbuffer},
     device::{Device, DeviceOwned, DeviceOwnedDebugWrapper},
@@ -113,6 +114,7 @@ use std::{
 mod alignment;
 pub mod allocator;
 mod device_memory;
+mod requirements_cache;
 
 /// Memory that can be bound to resources.
 ///
@@ -0,0 +1,224 @@
+use super::MemoryRequirements;
+use crate::{
+    buffer::{BufferCreateFlags, BufferCreateInfo, BufferUsage},
+    cache::OnceCache,
+    format::Format,
+    image::{ImageCreateFlags, ImageCreateInfo, ImageTiling, ImageType, ImageUsage, SampleCount},
+    sync::Sharing,
+    DeviceSize,
+};
+use smallvec::SmallVec;
+use std::{
+    hash::Hash,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+/// A per-device cache of the memory requirements of buffers and images, keyed by the parameters
+/// they were created with.
+///
+/// Vulkan guarantees that resources created with identical parameters on the same device have
+/// identical memory requirements, so entries never need to be invalidated.
+#[derive(Debug)]
+pub(crate) struct MemoryRequirementsCache {
+    enabled: AtomicBool,
+    buffers: OnceCache<BufferKey, MemoryRequirements>,
+    images: OnceCache<ImageKey, SmallVec<[MemoryRequirements; 4]>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl MemoryRequirementsCache {
+    pub(crate) fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(true),
+            buffers: OnceCache::new(),
+            images: OnceCache::new(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub(crate) fn stats(&self) -> MemoryRequirementsCacheStats {
+        MemoryRequirementsCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns the cache key for a buffer created with `create_info`, or `None` if the cache is
+    /// disabled or the buffer is not eligible for caching.
+    pub(crate) fn buffer_key(&self, create_info: &BufferCreateInfo) -> Option<BufferKey> {
+        let &BufferCreateInfo {
+            flags,
+            ref sharing,
+            size,
+            usage,
+            external_memory_handle_types,
+            opaque_capture_address: _,
+            _ne: _,
+        } = create_info;
+
+        (self.is_enabled() && external_memory_handle_types.is_empty()).then(|| BufferKey {
+            flags,
+            size,
+            usage,
+            sharing: canonical_sharing(sharing),
+        })
+    }
+
+    /// Returns the cache key for an image created with `create_info`, or `None` if the cache is
+    /// disabled or the image is not eligible for caching.
+    pub(crate) fn image_key(&self, create_info: &ImageCreateInfo) -> Option<ImageKey> {
+        let &ImageCreateInfo {
+            flags,
+            image_type,
+            format,
+            ref view_formats,
+            extent,
+            array_layers,
+            mip_levels,
+            samples,
+            tiling,
+            usage,
+            stencil_usage,
+            ref sharing,
+            initial_layout: _,
+            drm_format_modifiers: _,
+            drm_format_modifier_plane_layouts: _,
+            layout_policy: _,
+            external_memory_handle_types,
+            ref compression_control,
+            _ne: _,
+        } = create_info;
+
+        // The memory requirements of images with a DRM format modifier depend on the modifier
+        // that the implementation chose, which is not part of the create info. Compressed images
+        // are rare enough that they are not worth adding to the key.
+        (self.is_enabled()
+            && tiling != ImageTiling::DrmFormatModifier
+            && external_memory_handle_types.is_empty()
+            && compression_control.is_none())
+        .then(|| ImageKey {
+            flags,
+            image_type,
+            format,
+            view_formats: view_formats.iter().copied().collect(),
+            extent,
+            array_layers,
+            mip_levels,
+            samples,
+            tiling,
+            usage,
+            stencil_usage,
+            sharing: canonical_sharing(sharing),
+        })
+    }
+
+    /// Returns the memory requirements for `key`, calling `query` to retrieve them if they aren't
+    /// cached yet. If `key` is `None`, `query` is always called.
+    pub(crate) fn buffer_memory_requirements(
+        &self,
+        key: Option<BufferKey>,
+        query: impl FnOnce() -> MemoryRequirements,
+    ) -> MemoryRequirements {
+        match key {
+            Some(key) => self.get_or_insert(&self.buffers, key, query),
+            None => query(),
+        }
+    }
+
+    /// Returns the memory requirements for `key`, calling `query` to retrieve them if they aren't
+    /// cached yet. If `key` is `None`, `query` is always called.
+    pub(crate) fn image_memory_requirements(
+        &self,
+        key: Option<ImageKey>,
+        query: impl FnOnce() -> SmallVec<[MemoryRequirements; 4]>,
+    ) -> SmallVec<[MemoryRequirements; 4]> {
+        match key {
+            Some(key) => self.get_or_insert(&self.images, key, query),
+            None => query(),
+        }
+    }
+
+    fn get_or_insert<K, V>(&self, cache: &OnceCache<K, V>, key: K, query: impl FnOnce() -> V) -> V
+    where
+        K: Eq + Hash,
+        V: Clone,
+    {
+        let mut missed = false;
+        let value = cache.get_or_insert(key, |_| {
+            missed = true;
+            query()
+        });
+
+        if missed {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        }
+
+        value
+    }
+}
+
+fn canonical_sharing(sharing: &Sharing<SmallVec<[u32; 4]>>) -> Sharing<SmallVec<[u32; 4]>> {
+    match sharing {
+        Sharing::Exclusive => Sharing::Exclusive,
+        Sharing::Concurrent(queue_family_indices) => {
+            let mut queue_family_indices = queue_family_indices.clone();
+            queue_family_indices.sort_unstable();
+            queue_family_indices.dedup();
+            Sharing::Concurrent(queue_family_indices)
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct BufferKey {
+    flags: BufferCreateFlags,
+    size: DeviceSize,
+    usage: BufferUsage,
+    sharing: Sharing<SmallVec<[u32; 4]>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct ImageKey {
+    flags: ImageCreateFlags,
+    image_type: ImageType,
+    format: Format,
+    view_formats: SmallVec<[Format; 4]>,
+    extent: [u32; 3],
+    array_layers: u32,
+    mip_levels: u32,
+    samples: SampleCount,
+    tiling: ImageTiling,
+    usage: ImageUsage,
+    stencil_usage: Option<ImageUsage>,
+    sharing: Sharing<SmallVec<[u32; 4]>>,
+}
+
+/// Statistics about the memory requirements cache of a device.
+///
+/// See [`Device::memory_requirements_cache_stats`] for more information.
+///
+/// [`Device::memory_requirements_cache_stats`]: crate::device::Device::memory_requirements_cache_stats
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryRequirementsCacheStats {
+    /// The number of times that memory requirements were retrieved from the cache.
+    pub hits: u64,
+
+    /// The number of times that memory requirements had to be queried from the implementation
+    /// because they were not in the cache yet.
+    ///
+    /// Resources that are not eligible for caching, and resources created while the cache is
+    /// disabled, are not counted.
+    pub misses: u64,
+}
@@ -12,15 +12,32 @@
 //! of [`get_data`](PipelineCache::get_data) for example of how to store
 //! the data on the disk, and [`new`](PipelineCache::new) for how to reload
 //! it.
+//!
+//! The [`from_file`](PipelineCache::from_file) and [`save_to_file`](PipelineCache::save_to_file)
+//! methods take care of this for the common case where the cache is stored in a single file. When
+//! loading, the header of the data is checked against the physical device first, so that data
+//! that was stored by a different device or driver version results in an empty cache instead of
+//! being passed to the Vulkan implementation.
 
 use crate::{
-    device::{Device, DeviceOwned},
+    device::{physical::PhysicalDevice, Device, DeviceOwned},
     instance::InstanceOwnedDebugWrapper,
     macros::{impl_id_counter, vulkan_bitflags},
     Validated, ValidationError, VulkanError, VulkanObject,
 };
 use smallvec::SmallVec;
-use std::{mem::MaybeUninit, num::NonZeroU64, ptr, sync::Arc};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    mem::MaybeUninit,
+    num::NonZeroU64,
+    path::Path,
+    process, ptr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 /// Opaque cache that contains pipeline objects.
 ///
@@ -140,6 +157,76 @@ impl PipelineCache {
         Ok(Self::from_handle(device, handle, create_info))
     }
 
+    /// Builds a new pipeline cache from data that was previously retrieved using
+    /// [`get_data`](PipelineCache::get_data).
+    ///
+    /// The header of `data` is checked against the physical device of `device` first. If the
+    /// data is too short to contain a header, or if it was produced by a different device or
+    /// driver version, then it is discarded and an empty cache is created instead.
+    ///
+    /// # Safety
+    ///
+    /// - If the header of `data` matches the physical device, then the rest of `data` must be
+    ///   unmodified data that was previously retrieved using `get_data`.
+    pub unsafe fn new_with_data(
+        device: Arc<Device>,
+        data: Vec<u8>,
+    ) -> Result<Arc<PipelineCache>, Validated<VulkanError>> {
+        let is_compatible = PipelineCacheHeader::from_data(&data).map_or(false, |header| {
+            header.is_compatible(device.physical_device())
+        });
+
+        Self::new(
+            device,
+            PipelineCacheCreateInfo {
+                initial_data: if is_compatible { data } else { Vec::new() },
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Builds a new pipeline cache from the contents of the file at `path`, which was previously
+    /// written using [`save_to_file`](PipelineCache::save_to_file).
+    ///
+    /// If the file doesn't exist, or its contents are not compatible with `device`, then an empty
+    /// cache is created instead. See [`new_with_data`](PipelineCache::new_with_data) for more
+    /// information. Any other error that happens while reading the file is returned, as is an
+    /// error creating the cache, which is wrapped in an [`io::Error`] of kind
+    /// [`Other`](io::ErrorKind::Other).
+    ///
+    /// # Safety
+    ///
+    /// - The same requirements as for `new_with_data` apply to the contents of the file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use vulkano::device::Device;
+    /// use vulkano::pipeline::cache::PipelineCache;
+    /// # let device: Arc<Device> = return;
+    ///
+    /// // This is unsafe because there is no way to be sure that the file contains valid data.
+    /// let cache = unsafe { PipelineCache::from_file(device.clone(), "pipeline_cache.bin") }.unwrap();
+    ///
+    /// // Create pipelines using the cache here...
+    ///
+    /// // If an error happens (eg. no permission for the file) we simply skip storing the cache.
+    /// let _ = cache.save_to_file("pipeline_cache.bin");
+    /// ```
+    pub unsafe fn from_file(
+        device: Arc<Device>,
+        path: impl AsRef<Path>,
+    ) -> io::Result<Arc<PipelineCache>> {
+        let data = match fs::read(path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+
+        Self::new_with_data(device, data).map_err(|err| io::Error::new(io::ErrorKind::Other, err))
+    }
+
     /// Creates a new `PipelineCache` from a raw object handle.
     ///
     /// # Safety
@@ -232,11 +319,75 @@ impl PipelineCache {
         Ok(data)
     }
 
+    /// Writes the data of the cache to the file at `path`, replacing it if it already exists.
+    ///
+    /// The data is first written to a temporary file next to `path`, which is then renamed to
+    /// `path`. This ensures that the file is never left partially written, even if the
+    /// application is interrupted while saving. The temporary file has a unique name, so that
+    /// several threads or processes can save to the same `path` at once without interfering with
+    /// each other; whichever rename happens last wins.
+    ///
+    /// The file can be loaded again with [`from_file`](PipelineCache::from_file).
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let data = self
+            .get_data()
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+
+        static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let path = path.as_ref();
+        let (tmp_path, file) = loop {
+            let mut tmp_path = path.as_os_str().to_owned();
+            tmp_path.push(format!(
+                ".{}.{}.tmp",
+                process::id(),
+                TMP_COUNTER.fetch_add(1, Ordering::Relaxed),
+            ));
+
+            match OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&tmp_path)
+            {
+                Ok(file) => break (tmp_path, file),
+                // Left behind by an earlier process that happened to have the same id.
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err),
+            }
+        };
+
+        let write = |mut file: File| -> io::Result<()> {
+            file.write_all(&data)?;
+            file.sync_all()?;
+            // The file must be closed before it can be renamed on Windows.
+            drop(file);
+            fs::rename(&tmp_path, path)
+        };
+
+        write(file).map_err(|err| {
+            let _ = fs::remove_file(&tmp_path);
+            err
+        })
+    }
+
     /// Merges other pipeline caches into this one.
     ///
     /// It is `self` that is modified here. The pipeline caches passed as parameter are untouched.
+    /// This can be used to combine caches that were filled by different threads, before
+    /// storing the data of the combined cache.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use vulkano::pipeline::cache::PipelineCache;
+    /// # let main_cache: Arc<PipelineCache> = return;
+    /// # let thread_caches: Vec<Arc<PipelineCache>> = return;
+    /// main_cache
+    ///     .merge(thread_caches.iter().map(AsRef::as_ref))
+    ///     .unwrap();
+    /// ```
     // FIXME: vkMergePipelineCaches is not thread safe for the destination cache
-    // TODO: write example
     pub fn merge<'a>(
         &self,
         src_caches: impl IntoIterator<Item = &'a PipelineCache>,
@@ -249,6 +400,9 @@ impl PipelineCache {
 
     fn validate_merge(&self, src_caches: &[&PipelineCache]) -> Result<(), Box<ValidationError>> {
         for (index, &src_cache) in src_caches.iter().enumerate() {
+            // VUID-vkMergePipelineCaches-pSrcCaches-parent
+            assert_eq!(self.device(), src_cache.device());
+
             if src_cache == self {
                 return Err(Box::new(ValidationError {
                     context: format!("src_caches[{}]", index).into(),
@@ -327,7 +481,8 @@ pub struct PipelineCacheCreateInfo {
     ///
     /// The data passed to this function will most likely be blindly trusted by the Vulkan
     /// implementation. Therefore you can easily crash your application or the system by passing
-    /// wrong data.
+    /// wrong data. [`PipelineCache::new_with_data`] checks the header of the data before using
+    /// it, which guards against data from a different device or driver version.
     ///
     /// The default value is empty.
     pub initial_data: Vec<u8>,
@@ -363,6 +518,73 @@ impl PipelineCacheCreateInfo {
     }
 }
 
+/// The header at the start of the data of a pipeline cache.
+///
+/// The header is the only part of the data whose layout is defined by Vulkan. It identifies the
+/// device and driver that produced the data, so that incompatible data can be detected before it
+/// is passed to the Vulkan implementation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PipelineCacheHeader {
+    /// The version of the header. Vulkano only understands version 1
+    /// (`VK_PIPELINE_CACHE_HEADER_VERSION_ONE`).
+    pub header_version: u32,
+
+    /// The [`vendor_id`](crate::device::DeviceProperties::vendor_id) of the physical device that
+    /// produced the data.
+    pub vendor_id: u32,
+
+    /// The [`device_id`](crate::device::DeviceProperties::device_id) of the physical device that
+    /// produced the data.
+    pub device_id: u32,
+
+    /// The [`pipeline_cache_uuid`](crate::device::DeviceProperties::pipeline_cache_uuid) of the
+    /// physical device that produced the data.
+    pub pipeline_cache_uuid: [u8; 16],
+}
+
+impl PipelineCacheHeader {
+    const VERSION_ONE: u32 = ash::vk::PipelineCacheHeaderVersion::ONE.as_raw() as u32;
+    const VERSION_ONE_SIZE: usize = 32;
+
+    /// Parses the header at the start of `data`, which was retrieved using
+    /// [`PipelineCache::get_data`].
+    ///
+    /// Returns `None` if `data` is too short to contain a header, or if the header length that
+    /// is stored in the data is invalid.
+    pub fn from_data(data: &[u8]) -> Option<Self> {
+        // All fields are stored with the least significant byte first, regardless of the byte
+        // order of the host.
+        let read_u32 = |offset: usize| {
+            data.get(offset..offset + 4)
+                .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        };
+
+        let header_length = read_u32(0)? as usize;
+
+        if header_length < Self::VERSION_ONE_SIZE || header_length > data.len() {
+            return None;
+        }
+
+        Some(Self {
+            header_version: read_u32(4)?,
+            vendor_id: read_u32(8)?,
+            device_id: read_u32(12)?,
+            pipeline_cache_uuid: data[16..32].try_into().unwrap(),
+        })
+    }
+
+    /// Returns whether data with this header was produced by `physical_device`, with the same
+    /// driver version, and can therefore be used to create a pipeline cache for it.
+    pub fn is_compatible(&self, physical_device: &PhysicalDevice) -> bool {
+        let properties = physical_device.properties();
+
+        self.header_version == Self::VERSION_ONE
+            && self.vendor_id == properties.vendor_id
+            && self.device_id == properties.device_id
+            && self.pipeline_cache_uuid == properties.pipeline_cache_uuid
+    }
+}
+
 vulkan_bitflags! {
     #[non_exhaustive]
 
@@ -382,9 +604,10 @@ vulkan_bitflags! {
 mod tests {
     use crate::{
         pipeline::{
-            cache::PipelineCache, compute::ComputePipelineCreateInfo,
-            layout::PipelineDescriptorSetLayoutCreateInfo, ComputePipeline, PipelineLayout,
-            PipelineShaderStageCreateInfo,
+            cache::{PipelineCache, PipelineCacheHeader},
+            compute::ComputePipelineCreateInfo,
+            layout::PipelineDescriptorSetLayoutCreateInfo,
+            ComputePipeline, PipelineLayout, PipelineShaderStageCreateInfo,
         },
         shader::{ShaderModule, ShaderModuleCreateInfo},
     };
@@ -399,6 +622,123 @@ mod tests {
         }
     }
 
+    #[test]
+    fn header_too_short() {
+        assert!(PipelineCacheHeader::from_data(&[]).is_none());
+        assert!(PipelineCacheHeader::from_data(&[0; 16]).is_none());
+
+        // The header length stored in the data is larger than the data itself.
+        let mut data = [0; 32];
+        data[0] = 64;
+        assert!(PipelineCacheHeader::from_data(&data).is_none());
+    }
+
+    #[test]
+    fn header_compatible_with_device() {
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        let cache = unsafe { PipelineCache::new(device.clone(), Default::default()).unwrap() };
+        let data = cache.get_data().unwrap();
+
+        let header = PipelineCacheHeader::from_data(&data).unwrap();
+        assert!(header.is_compatible(device.physical_device()));
+    }
+
+    #[test]
+    fn new_with_data_discards_incompatible() {
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        let cache = unsafe { PipelineCache::new(device.clone(), Default::default()).unwrap() };
+        let mut data = cache.get_data().unwrap();
+
+        // Change the pipeline cache UUID, as if the data came from a different driver version.
+        data[16] ^= 0xff;
+        let header = PipelineCacheHeader::from_data(&data).unwrap();
+        assert!(!header.is_compatible(device.physical_device()));
+
+        let _cache = unsafe { PipelineCache::new_with_data(device, data).unwrap() };
+    }
+
+    #[test]
+    fn merge_and_save_to_file() {
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        let cs = unsafe {
+            /*
+             * #version 450
+             * void main() {
+             * }
+             */
+            const MODULE: [u32; 48] = [
+                119734787, 65536, 524298, 6, 0, 131089, 1, 393227, 1, 1280527431, 1685353262,
+                808793134, 0, 196622, 0, 1, 327695, 5, 4, 1852399981, 0, 393232, 4, 17, 1, 1, 1,
+                196611, 2, 450, 262149, 4, 1852399981, 0, 131091, 2, 196641, 3, 2, 327734, 2, 4, 0,
+                3, 131320, 5, 65789, 65592,
+            ];
+            let module =
+                ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(&MODULE)).unwrap();
+            module.entry_point("main").unwrap()
+        };
+
+        let caches: Vec<_> = (0..2)
+            .map(|_| {
+                let cache =
+                    unsafe { PipelineCache::new(device.clone(), Default::default()).unwrap() };
+
+                let stage = PipelineShaderStageCreateInfo::new(cs.clone());
+                let layout = PipelineLayout::new(
+                    device.clone(),
+                    PipelineDescriptorSetLayoutCreateInfo::from_stages([&stage])
+                        .into_pipeline_layout_create_info(device.clone())
+                        .unwrap(),
+                )
+                .unwrap();
+                ComputePipeline::new(
+                    device.clone(),
+                    Some(cache.clone()),
+                    ComputePipelineCreateInfo::stage_layout(stage, layout),
+                )
+                .unwrap();
+
+                cache
+            })
+            .collect();
+
+        let merged = unsafe { PipelineCache::new(device.clone(), Default::default()).unwrap() };
+        merged.merge(caches.iter().map(AsRef::as_ref)).unwrap();
+
+        let path = std::env::temp_dir().join(format!(
+            "vulkano_pipeline_cache_test_{}.bin",
+            std::process::id(),
+        ));
+        merged.save_to_file(&path).unwrap();
+
+        let data = std::fs::read(&path).unwrap();
+        let _loaded = unsafe { PipelineCache::from_file(device.clone(), &path).unwrap() };
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(data, merged.get_data().unwrap());
+        let header = PipelineCacheHeader::from_data(&data).unwrap();
+        assert!(header.is_compatible(device.physical_device()));
+    }
+
+    #[test]
+    fn from_file_errors() {
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        // A missing file results in an empty cache.
+        let path = std::env::temp_dir().join(format!(
+            "vulkano_pipeline_cache_missing_{}.bin",
+            std::process::id(),
+        ));
+        let _ = std::fs::remove_file(&path);
+        let _cache = unsafe { PipelineCache::from_file(device.clone(), &path).unwrap() };
+
+        // Any other error reading the file is returned, here because the path is a directory.
+        let err = unsafe { PipelineCache::from_file(device, std::env::temp_dir()) }.unwrap_err();
+        assert_ne!(err.kind(), std::io::ErrorKind::NotFound);
+    }
+
     #[test]
     fn cache_returns_same_data() {
         let (device, _queue) = gfx_dev_and_queue!();
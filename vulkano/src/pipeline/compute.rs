@@ -15,6 +15,7 @@
 
 use super::{PipelineCreateFlags, PipelineShaderStageCreateInfo};
 use crate::{
+    describe::{self, Describer},
     device::{Device, DeviceOwned, DeviceOwnedDebugWrapper},
     instance::InstanceOwnedDebugWrapper,
     macros::impl_id_counter,
@@ -236,6 +237,30 @@ impl ComputePipeline {
         })
     }
 
+    /// Creates a new `ComputePipeline` with the same parameters as `self`, but with `stage` as
+    /// the shader stage.
+    ///
+    /// This is useful to reload shaders at runtime. The new shader must be compatible with the
+    /// layout of `self`. `self` is not modified, and can keep being used until the new pipeline
+    /// replaces it.
+    ///
+    /// The new pipeline is not derived from any base pipeline, so
+    /// [`PipelineCreateFlags::DERIVATIVE`] is removed from the flags of `self`.
+    pub fn recreate_with_stage(
+        &self,
+        cache: Option<Arc<PipelineCache>>,
+        stage: PipelineShaderStageCreateInfo,
+    ) -> Result<Arc<ComputePipeline>, Validated<VulkanError>> {
+        ComputePipeline::new(
+            self.device().clone(),
+            cache,
+            ComputePipelineCreateInfo {
+                flags: self.flags - PipelineCreateFlags::DERIVATIVE,
+                ..ComputePipelineCreateInfo::stage_layout(stage, self.layout().clone())
+            },
+        )
+    }
+
     /// Returns the `Device` that the pipeline was created with.
     #[inline]
     pub fn device(&self) -> &Arc<Device> {
@@ -247,6 +272,24 @@ impl ComputePipeline {
     pub fn flags(&self) -> PipelineCreateFlags {
         self.flags
     }
+
+    /// Returns a human-readable description of the parameters that the pipeline was created with,
+    /// including its layout and the descriptor requirements of its shader.
+    ///
+    /// Unlike the `Debug` output, the description contains no handles, and its format is stable,
+    /// so that the descriptions of two pipelines can be compared with a text diff.
+    pub fn describe(&self) -> String {
+        let mut describer = Describer::new("ComputePipeline");
+        describer
+            .field("flags", &self.flags)
+            .section("layout", |describer| self.layout.describe_fields(describer));
+        describe::descriptor_binding_requirements(
+            &mut describer,
+            &self.descriptor_binding_requirements,
+        );
+
+        describer.finish()
+    }
 }
 
 impl Pipeline for ComputePipeline {
@@ -294,6 +337,8 @@ unsafe impl DeviceOwned for ComputePipeline {
 impl Drop for ComputePipeline {
     #[inline]
     fn drop(&mut self) {
+        self.device.forget_debug_utils_object_name(self.handle);
+
         unsafe {
             let fns = self.device.fns();
             (fns.v1_0.destroy_pipeline)(self.device.handle(), self.handle, ptr::null());
@@ -444,7 +489,10 @@ impl ComputePipelineCreateInfo {
 #[cfg(test)]
 mod tests {
     use crate::{
-        buffer::{Buffer, BufferCreateInfo, BufferUsage},
+        buffer::{
+            view::{BufferView, BufferViewCreateInfo},
+            Buffer, BufferCreateInfo, BufferUsage,
+        },
         command_buffer::{
             allocator::StandardCommandBufferAllocator, CommandBufferBeginInfo, CommandBufferLevel,
             CommandBufferUsage, RecordingCommandBuffer,
@@ -452,17 +500,47 @@ mod tests {
         descriptor_set::{
             allocator::StandardDescriptorSetAllocator, DescriptorSet, WriteDescriptorSet,
         },
+        device::{Device, DeviceExtensions, DeviceFeatures},
+        format::Format,
+        image::{view::ImageView, Image, ImageCreateInfo, ImageUsage},
         memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
         pipeline::{
             compute::ComputePipelineCreateInfo, layout::PipelineDescriptorSetLayoutCreateInfo,
-            ComputePipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+            ComputePipeline, Pipeline, PipelineBindPoint, PipelineCreateFlags, PipelineLayout,
             PipelineShaderStageCreateInfo,
         },
         shader::{ShaderModule, ShaderModuleCreateInfo, ShaderStages},
         sync::{now, GpuFuture},
+        Validated, Version, VulkanError, VulkanObject,
     };
     use std::sync::Arc;
 
+    fn create_pipeline(
+        device: &Arc<Device>,
+        code: &[u32],
+    ) -> Result<Arc<ComputePipeline>, Validated<VulkanError>> {
+        let cs = unsafe {
+            ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(code))
+                .unwrap()
+                .entry_point("main")
+                .unwrap()
+        };
+        let stage = PipelineShaderStageCreateInfo::new(cs);
+        let layout = PipelineLayout::new(
+            device.clone(),
+            PipelineDescriptorSetLayoutCreateInfo::from_stages([&stage])
+                .into_pipeline_layout_create_info(device.clone())
+                .unwrap(),
+        )
+        .unwrap();
+
+        ComputePipeline::new(
+            device.clone(),
+            None,
+            ComputePipelineCreateInfo::stage_layout(stage, layout),
+        )
+    }
+
     // TODO: test for basic creation
     // TODO: test for pipeline layout error
 
@@ -750,4 +828,266 @@ mod tests {
         let data_buffer_content = data_buffer.read().unwrap();
         assert_eq!(*data_buffer_content, subgroup_size);
     }
+
+    #[test]
+    fn storage_image_without_format() {
+        // Unused storage images without a format, which are checked at pipeline creation whether
+        // the shader accesses them or not.
+
+        /*
+        #version 450
+        #extension GL_EXT_shader_image_load_formatted : require
+
+        layout(set = 0, binding = 0) uniform image2D image;
+
+        void main() {}
+        */
+        const WITHOUT_FORMAT: [u32; 63] = [
+            119734787, 65536, 0, 9, 0, 131089, 1, 196622, 0, 1, 327695, 5, 7, 1852399981, 0,
+            393232, 7, 17, 1, 1, 1, 262215, 6, 34, 0, 262215, 6, 33, 0, 131091, 1, 196641, 2, 1,
+            196630, 3, 32, 589849, 4, 3, 1, 0, 0, 0, 2, 0, 262176, 5, 0, 4, 262203, 5, 6, 0,
+            327734, 1, 7, 0, 2, 131320, 8, 65789, 65592,
+        ];
+
+        /*
+        #version 450
+        #extension GL_EXT_shader_image_load_formatted : require
+
+        layout(set = 0, binding = 0) readonly uniform image2D image;
+
+        void main() {}
+        */
+        const READONLY_WITHOUT_FORMAT: [u32; 66] = [
+            119734787, 65536, 0, 9, 0, 131089, 1, 196622, 0, 1, 327695, 5, 7, 1852399981, 0,
+            393232, 7, 17, 1, 1, 1, 262215, 6, 34, 0, 262215, 6, 33, 0, 196679, 6, 24, 131091, 1,
+            196641, 2, 1, 196630, 3, 32, 589849, 4, 3, 1, 0, 0, 0, 2, 0, 262176, 5, 0, 4, 262203,
+            5, 6, 0, 327734, 1, 7, 0, 2, 131320, 8, 65789, 65592,
+        ];
+
+        let (device, _) = gfx_dev_and_queue!();
+
+        // The features are only needed if the format features can't be queried per format.
+        let physical_device = device.physical_device();
+        let needs_features = physical_device.api_version() < Version::V1_3
+            && !physical_device
+                .supported_extensions()
+                .khr_format_feature_flags2;
+
+        match create_pipeline(&device, &WITHOUT_FORMAT) {
+            Err(Validated::ValidationError(err)) => {
+                assert!(needs_features);
+                assert!(err.problem.contains("`NonWritable`"));
+            }
+            result => assert!(!needs_features && result.is_ok()),
+        }
+
+        match create_pipeline(&device, &READONLY_WITHOUT_FORMAT) {
+            Err(Validated::ValidationError(err)) => {
+                assert!(needs_features);
+                assert!(err.problem.contains("`NonReadable`"));
+            }
+            result => assert!(!needs_features && result.is_ok()),
+        }
+
+        let Some((device, _)) = crate::testing::test_device(
+            &DeviceExtensions::empty(),
+            &DeviceFeatures {
+                shader_storage_image_read_without_format: true,
+                shader_storage_image_write_without_format: true,
+                ..DeviceFeatures::empty()
+            },
+        ) else {
+            return;
+        };
+
+        create_pipeline(&device, &WITHOUT_FORMAT).unwrap();
+        create_pipeline(&device, &READONLY_WITHOUT_FORMAT).unwrap();
+    }
+
+    #[test]
+    fn storage_format_mismatch() {
+        /*
+        #version 450
+
+        layout(set = 0, binding = 0, rgba8) uniform image2D image;
+        layout(set = 0, binding = 1, r32f) uniform imageBuffer texels;
+
+        void main() {
+            imageLoad(image, ivec2(0));
+            imageLoad(texels, 0);
+        }
+        */
+        const MODULE: [u32; 129] = [
+            119734787, 65536, 0, 21, 0, 131089, 1, 131089, 47, 196622, 0, 1, 327695, 5, 15,
+            1852399981, 0, 393232, 15, 17, 1, 1, 1, 262215, 9, 34, 0, 262215, 9, 33, 0, 262215, 12,
+            34, 0, 262215, 12, 33, 1, 131091, 1, 196641, 2, 1, 196630, 3, 32, 262167, 4, 3, 4,
+            262165, 5, 32, 1, 262167, 6, 5, 2, 589849, 7, 3, 1, 0, 0, 0, 2, 4, 262176, 8, 0, 7,
+            262203, 8, 9, 0, 589849, 10, 3, 5, 0, 0, 0, 2, 3, 262176, 11, 0, 10, 262203, 11, 12, 0,
+            262187, 5, 13, 0, 327724, 6, 14, 13, 13, 327734, 1, 15, 0, 2, 131320, 16, 262205, 7,
+            17, 9, 327778, 4, 18, 17, 14, 262205, 10, 19, 12, 327778, 4, 20, 19, 13, 65789, 65592,
+        ];
+
+        let (device, queue) = gfx_dev_and_queue!();
+        let pipeline = create_pipeline(&device, &MODULE).unwrap();
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let ds_allocator = Arc::new(StandardDescriptorSetAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+
+        let image_view = |format| {
+            let image = Image::new(
+                memory_allocator.clone(),
+                ImageCreateInfo {
+                    format,
+                    extent: [1, 1, 1],
+                    usage: ImageUsage::STORAGE,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+            )
+            .unwrap();
+            ImageView::new_default(image).unwrap()
+        };
+        let buffer_view = |format| {
+            let buffer = Buffer::new_slice::<u8>(
+                memory_allocator.clone(),
+                BufferCreateInfo {
+                    usage: BufferUsage::STORAGE_TEXEL_BUFFER,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+                16,
+            )
+            .unwrap();
+            BufferView::new(
+                buffer,
+                BufferViewCreateInfo {
+                    format,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+        let dispatch = |image_format, texel_format| {
+            let set = DescriptorSet::new(
+                ds_allocator.clone(),
+                pipeline.layout().set_layouts()[0].clone(),
+                [
+                    WriteDescriptorSet::image_view(0, image_view(image_format)),
+                    WriteDescriptorSet::buffer_view(1, buffer_view(texel_format)),
+                ],
+                [],
+            )
+            .unwrap();
+
+            let mut cbb = RecordingCommandBuffer::new(
+                cb_allocator.clone(),
+                queue.queue_family_index(),
+                CommandBufferLevel::Primary,
+                CommandBufferBeginInfo {
+                    usage: CommandBufferUsage::OneTimeSubmit,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            cbb.bind_pipeline_compute(pipeline.clone())
+                .unwrap()
+                .bind_descriptor_sets(
+                    PipelineBindPoint::Compute,
+                    pipeline.layout().clone(),
+                    0,
+                    set,
+                )
+                .unwrap();
+
+            unsafe { cbb.dispatch([1, 1, 1]) }.map(|_| ())
+        };
+
+        let err = dispatch(Format::R32_SFLOAT, Format::R32_SFLOAT).unwrap_err();
+        assert!(err
+            .problem
+            .contains("binding 0 with the image format `rgba8`"));
+        assert!(err.problem.contains("`R32_SFLOAT`"));
+
+        let err = dispatch(Format::R8G8B8A8_UNORM, Format::R8G8B8A8_UNORM).unwrap_err();
+        assert!(err
+            .problem
+            .contains("binding 1 with the image format `r32f`"));
+        assert!(err.problem.contains("`R8G8B8A8_UNORM`"));
+
+        dispatch(Format::R8G8B8A8_UNORM, Format::R32_SFLOAT).unwrap();
+    }
+
+    #[test]
+    fn recreate_with_stage() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let module = unsafe {
+            /*
+            #version 450
+
+            layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+            layout(constant_id = 83) const int VALUE = 0xdeadbeef;
+
+            layout(set = 0, binding = 0) buffer Output {
+                int write;
+            } write;
+
+            void main() {
+                write.write = VALUE;
+            }
+            */
+            const MODULE: [u32; 120] = [
+                119734787, 65536, 524289, 14, 0, 131089, 1, 393227, 1, 1280527431, 1685353262,
+                808793134, 0, 196622, 0, 1, 327695, 5, 4, 1852399981, 0, 393232, 4, 17, 1, 1, 1,
+                196611, 2, 450, 262149, 4, 1852399981, 0, 262149, 7, 1886680399, 29813, 327686, 7,
+                0, 1953067639, 101, 262149, 9, 1953067639, 101, 262149, 11, 1431060822, 69, 327752,
+                7, 0, 35, 0, 196679, 7, 3, 262215, 9, 34, 0, 262215, 9, 33, 0, 262215, 11, 1, 83,
+                131091, 2, 196641, 3, 2, 262165, 6, 32, 1, 196638, 7, 6, 262176, 8, 2, 7, 262203,
+                8, 9, 2, 262187, 6, 10, 0, 262194, 6, 11, 3735928559, 262176, 12, 2, 6, 327734, 2,
+                4, 0, 3, 131320, 5, 327745, 12, 13, 9, 10, 196670, 13, 11, 65789, 65592,
+            ];
+            ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(&MODULE)).unwrap()
+        };
+
+        let stage = PipelineShaderStageCreateInfo::new(module.entry_point("main").unwrap());
+        let layout = PipelineLayout::new(
+            device.clone(),
+            PipelineDescriptorSetLayoutCreateInfo::from_stages([&stage])
+                .into_pipeline_layout_create_info(device.clone())
+                .unwrap(),
+        )
+        .unwrap();
+        let pipeline = ComputePipeline::new(
+            device.clone(),
+            None,
+            ComputePipelineCreateInfo {
+                flags: PipelineCreateFlags::DISABLE_OPTIMIZATION,
+                ..ComputePipelineCreateInfo::stage_layout(stage, layout)
+            },
+        )
+        .unwrap();
+
+        // Reload the shader with a different value for the specialization constant.
+        let stage = PipelineShaderStageCreateInfo::new(
+            module
+                .specialize([(83, 0x12345678i32.into())].into_iter().collect())
+                .unwrap()
+                .entry_point("main")
+                .unwrap(),
+        );
+        let recreated = pipeline.recreate_with_stage(None, stage).unwrap();
+
+        assert_ne!(recreated.handle(), pipeline.handle());
+        assert!(Arc::ptr_eq(recreated.layout(), pipeline.layout()));
+        assert_eq!(recreated.flags(), PipelineCreateFlags::DISABLE_OPTIMIZATION);
+        assert_eq!(recreated.describe(), pipeline.describe());
+    }
 }
@@ -224,6 +224,19 @@ impl ColorBlendState {
         Ok(())
     }
 
+    /// Returns whether any of the attachments has blending enabled with a blend factor that reads
+    /// from the second source of dual-source blending.
+    pub(crate) fn uses_dual_source_blending(&self) -> bool {
+        self.attachments.iter().any(|state| {
+            state.blend.as_ref().map_or(false, |blend| {
+                blend.src_color_blend_factor.is_dual_source()
+                    || blend.dst_color_blend_factor.is_dual_source()
+                    || blend.src_alpha_blend_factor.is_dual_source()
+                    || blend.dst_alpha_blend_factor.is_dual_source()
+            })
+        })
+    }
+
     pub(crate) fn validate_required_fragment_outputs(
         &self,
         subpass: &PipelineSubpassType,
@@ -968,6 +981,18 @@ vulkan_enum! {
 }
 
 impl BlendFactor {
+    /// Returns whether `self` reads from the second source of dual-source blending.
+    #[inline]
+    pub const fn is_dual_source(self) -> bool {
+        matches!(
+            self,
+            BlendFactor::Src1Color
+                | BlendFactor::OneMinusSrc1Color
+                | BlendFactor::Src1Alpha
+                | BlendFactor::OneMinusSrc1Alpha
+        )
+    }
+
     const fn source_components_used(self, output_component: usize) -> [ColorComponents; 2] {
         match self {
             BlendFactor::Zero
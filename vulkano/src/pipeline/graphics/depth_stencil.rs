@@ -36,11 +36,27 @@ pub struct DepthStencilState {
     pub depth: Option<DepthState>,
 
     /// The minimum and maximum depth values to use for the depth bounds test.
-    /// Fragments with values outside this range are discarded.
+    /// Fragments are discarded if the value in the depth buffer at their location is outside this
+    /// range.
     ///
     /// If set to `None`, the depth bounds test is disabled, all fragments will pass.
     ///
+    /// If set to `Some`, the [`depth_bounds`] feature must be enabled on the device. Unless the
+    /// [`ext_depth_range_unrestricted`] extension is enabled, both values must be between 0.0 and
+    /// 1.0 inclusive.
+    ///
+    /// If [`DynamicState::DepthBounds`] is used, the range is ignored and must be set with
+    /// [`set_depth_bounds`] instead. If [`DynamicState::DepthBoundsTestEnable`] is used, the test
+    /// is enabled or disabled with [`set_depth_bounds_test_enable`], and this must be `Some`.
+    ///
     /// The default value is `None`.
+    ///
+    /// [`depth_bounds`]: crate::device::DeviceFeatures::depth_bounds
+    /// [`ext_depth_range_unrestricted`]: crate::device::DeviceExtensions::ext_depth_range_unrestricted
+    /// [`DynamicState::DepthBounds`]: crate::pipeline::DynamicState::DepthBounds
+    /// [`DynamicState::DepthBoundsTestEnable`]: crate::pipeline::DynamicState::DepthBoundsTestEnable
+    /// [`set_depth_bounds`]: crate::command_buffer::RecordingCommandBuffer::set_depth_bounds
+    /// [`set_depth_bounds_test_enable`]: crate::command_buffer::RecordingCommandBuffer::set_depth_bounds_test_enable
     pub depth_bounds: Option<RangeInclusive<f32>>,
 
     /// The state of the stencil test.
@@ -109,43 +125,15 @@ impl DepthStencilState {
                 .map_err(|err| err.add_context("depth"))?;
         }
 
-        if let Some(depth_bounds) = depth_bounds {
-            if !device.enabled_features().depth_bounds {
-                return Err(Box::new(ValidationError {
-                    context: "depth_bounds".into(),
-                    problem: "is `Some`".into(),
-                    requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceFeature(
-                        "depth_bounds",
-                    )])]),
-                    vuids: &[
-                        "VUID-VkPipelineDepthStencilStateCreateInfo-depthBoundsTestEnable-00598",
-                    ],
-                }));
-            }
-
-            if !device.enabled_extensions().ext_depth_range_unrestricted {
-                if !(0.0..1.0).contains(depth_bounds.start()) {
-                    return Err(Box::new(ValidationError {
-                        context: "depth_bounds.start".into(),
-                        problem: "is not between 0.0 and 1.0 inclusive".into(),
-                        requires_one_of: RequiresOneOf(&[RequiresAllOf(&[
-                            Requires::DeviceExtension("ext_depth_range_unrestricted"),
-                        ])]),
-                        vuids: &["VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-02510"],
-                    }));
-                }
-
-                if !(0.0..1.0).contains(depth_bounds.end()) {
-                    return Err(Box::new(ValidationError {
-                        context: "depth_bounds.end".into(),
-                        problem: "is not between 0.0 and 1.0 inclusive".into(),
-                        requires_one_of: RequiresOneOf(&[RequiresAllOf(&[
-                            Requires::DeviceExtension("ext_depth_range_unrestricted"),
-                        ])]),
-                        vuids: &["VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-02510"],
-                    }));
-                }
-            }
+        if depth_bounds.is_some() && !device.enabled_features().depth_bounds {
+            return Err(Box::new(ValidationError {
+                context: "depth_bounds".into(),
+                problem: "is `Some`".into(),
+                requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceFeature(
+                    "depth_bounds",
+                )])]),
+                vuids: &["VUID-VkPipelineDepthStencilStateCreateInfo-depthBoundsTestEnable-00598"],
+            }));
         }
 
         if let Some(stencil_state) = stencil {
@@ -488,3 +476,329 @@ vulkan_enum! {
     /// The test always passes.
     Always = ALWAYS,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        buffer::{Buffer, BufferCreateInfo, BufferUsage},
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, ClearAttachment, ClearRect,
+            CommandBufferBeginInfo, CommandBufferLevel, CommandBufferUsage, CopyImageToBufferInfo,
+            RecordingCommandBuffer, RenderPassBeginInfo,
+        },
+        format::Format,
+        image::{view::ImageView, Image, ImageCreateInfo, ImageUsage},
+        memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
+        pipeline::{
+            graphics::{
+                color_blend::{ColorBlendAttachmentState, ColorBlendState},
+                depth_stencil::DepthStencilState,
+                input_assembly::InputAssemblyState,
+                multisample::MultisampleState,
+                rasterization::RasterizationState,
+                vertex_input::{
+                    VertexInputAttributeDescription, VertexInputBindingDescription,
+                    VertexInputRate, VertexInputState,
+                },
+                viewport::{Viewport, ViewportState},
+                GraphicsPipelineCreateInfo,
+            },
+            layout::PipelineDescriptorSetLayoutCreateInfo,
+            DynamicState, GraphicsPipeline, PipelineLayout, PipelineShaderStageCreateInfo,
+        },
+        render_pass::{Framebuffer, FramebufferCreateInfo, Subpass},
+        shader::{ShaderModule, ShaderModuleCreateInfo},
+        single_pass_renderpass,
+        sync::{now, GpuFuture},
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn depth_bounds_culling() {
+        // Draws a triangle that covers two pixels, whose depth values are 0.25 and 0.75. The
+        // depth bounds exclude the second one, so only the first pixel is drawn.
+
+        let (device, queue) =
+            gfx_dev_and_queue!(depth_bounds, shader_clip_distance, shader_cull_distance);
+
+        let (vs, fs) = unsafe {
+            /*
+            #version 450
+
+            layout(location = 0) in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+            */
+            const VS: [u32; 228] = [
+                119734787, 65536, 524289, 27, 0, 131089, 1, 131089, 32, 131089, 33, 393227, 1,
+                1280527431, 1685353262, 808793134, 0, 196622, 0, 1, 458767, 0, 4, 1852399981, 0,
+                13, 18, 196611, 2, 450, 589828, 1096764487, 1935622738, 1918988389, 1600484449,
+                1684105331, 1868526181, 1667590754, 29556, 589828, 1096764487, 1935622738,
+                1768186216, 1818191726, 1969712737, 1600481121, 1882206772, 7037793, 262149, 4,
+                1852399981, 0, 393221, 11, 1348430951, 1700164197, 2019914866, 0, 393222, 11, 0,
+                1348430951, 1953067887, 7237481, 458758, 11, 1, 1348430951, 1953393007, 1702521171,
+                0, 458758, 11, 2, 1130327143, 1148217708, 1635021673, 6644590, 458758, 11, 3,
+                1130327143, 1147956341, 1635021673, 6644590, 196613, 13, 0, 327685, 18, 1769172848,
+                1852795252, 0, 327752, 11, 0, 11, 0, 327752, 11, 1, 11, 1, 327752, 11, 2, 11, 3,
+                327752, 11, 3, 11, 4, 196679, 11, 2, 262215, 18, 30, 0, 131091, 2, 196641, 3, 2,
+                196630, 6, 32, 262167, 7, 6, 4, 262165, 8, 32, 0, 262187, 8, 9, 1, 262172, 10, 6,
+                9, 393246, 11, 7, 6, 10, 10, 262176, 12, 3, 11, 262203, 12, 13, 3, 262165, 14, 32,
+                1, 262187, 14, 15, 0, 262167, 16, 6, 2, 262176, 17, 1, 16, 262203, 17, 18, 1,
+                262187, 6, 20, 0, 262187, 6, 21, 1065353216, 262176, 25, 3, 7, 327734, 2, 4, 0, 3,
+                131320, 5, 262205, 16, 19, 18, 327761, 6, 22, 19, 0, 327761, 6, 23, 19, 1, 458832,
+                7, 24, 22, 23, 20, 21, 327745, 25, 26, 13, 15, 196670, 26, 24, 65789, 65592,
+            ];
+            /*
+            #version 450
+
+            layout(location = 0) out vec4 f_color;
+
+            void main() {
+                f_color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+            */
+            const FS: [u32; 105] = [
+                119734787, 65536, 524289, 13, 0, 131089, 1, 393227, 1, 1280527431, 1685353262,
+                808793134, 0, 196622, 0, 1, 393231, 4, 4, 1852399981, 0, 9, 196624, 4, 7, 196611,
+                2, 450, 589828, 1096764487, 1935622738, 1918988389, 1600484449, 1684105331,
+                1868526181, 1667590754, 29556, 589828, 1096764487, 1935622738, 1768186216,
+                1818191726, 1969712737, 1600481121, 1882206772, 7037793, 262149, 4, 1852399981, 0,
+                262149, 9, 1868783462, 7499628, 262215, 9, 30, 0, 131091, 2, 196641, 3, 2, 196630,
+                6, 32, 262167, 7, 6, 4, 262176, 8, 3, 7, 262203, 8, 9, 3, 262187, 6, 10,
+                1065353216, 262187, 6, 11, 0, 458796, 7, 12, 10, 11, 11, 10, 327734, 2, 4, 0, 3,
+                131320, 5, 196670, 9, 12, 65789, 65592,
+            ];
+            let vs = ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(&VS)).unwrap();
+            let fs = ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(&FS)).unwrap();
+
+            (
+                vs.entry_point("main").unwrap(),
+                fs.entry_point("main").unwrap(),
+            )
+        };
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let new_image = |format, usage| {
+            let image = Image::new(
+                memory_allocator.clone(),
+                ImageCreateInfo {
+                    format,
+                    extent: [2, 1, 1],
+                    usage,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+            )
+            .unwrap();
+
+            ImageView::new_default(image).unwrap()
+        };
+        let color = new_image(
+            Format::R8G8B8A8_UNORM,
+            ImageUsage::COLOR_ATTACHMENT | ImageUsage::TRANSFER_SRC,
+        );
+        let depth = new_image(Format::D32_SFLOAT, ImageUsage::DEPTH_STENCIL_ATTACHMENT);
+
+        let render_pass = single_pass_renderpass!(
+            device.clone(),
+            attachments: {
+                color: {
+                    format: Format::R8G8B8A8_UNORM,
+                    samples: 1,
+                    load_op: Clear,
+                    store_op: Store,
+                },
+                depth: {
+                    format: Format::D32_SFLOAT,
+                    samples: 1,
+                    load_op: Clear,
+                    store_op: DontCare,
+                },
+            },
+            pass: {
+                color: [color],
+                depth_stencil: {depth},
+            },
+        )
+        .unwrap();
+        let framebuffer = Framebuffer::new(
+            render_pass.clone(),
+            FramebufferCreateInfo {
+                attachments: vec![color.clone(), depth],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let pipeline = {
+            let stages = [
+                PipelineShaderStageCreateInfo::new(vs),
+                PipelineShaderStageCreateInfo::new(fs),
+            ];
+            let layout = PipelineLayout::new(
+                device.clone(),
+                PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+                    .into_pipeline_layout_create_info(device.clone())
+                    .unwrap(),
+            )
+            .unwrap();
+            let subpass = Subpass::from(render_pass, 0).unwrap();
+
+            GraphicsPipeline::new(
+                device.clone(),
+                None,
+                GraphicsPipelineCreateInfo {
+                    stages: stages.into_iter().collect(),
+                    vertex_input_state: Some(
+                        VertexInputState::new()
+                            .binding(
+                                0,
+                                VertexInputBindingDescription {
+                                    stride: 8,
+                                    input_rate: VertexInputRate::Vertex,
+                                    ..Default::default()
+                                },
+                            )
+                            .attribute(
+                                0,
+                                VertexInputAttributeDescription {
+                                    binding: 0,
+                                    format: Format::R32G32_SFLOAT,
+                                    offset: 0,
+                                    ..Default::default()
+                                },
+                            ),
+                    ),
+                    input_assembly_state: Some(InputAssemblyState::default()),
+                    viewport_state: Some(ViewportState {
+                        viewports: [Viewport {
+                            offset: [0.0, 0.0],
+                            extent: [2.0, 1.0],
+                            depth_range: 0.0..=1.0,
+                        }]
+                        .into_iter()
+                        .collect(),
+                        ..Default::default()
+                    }),
+                    rasterization_state: Some(RasterizationState::default()),
+                    multisample_state: Some(MultisampleState::default()),
+                    // The range is ignored, because the bounds are set dynamically.
+                    depth_stencil_state: Some(DepthStencilState {
+                        depth_bounds: Some(0.0..=1.0),
+                        ..Default::default()
+                    }),
+                    color_blend_state: Some(ColorBlendState::with_attachment_states(
+                        1,
+                        ColorBlendAttachmentState::default(),
+                    )),
+                    dynamic_state: [DynamicState::DepthBounds].into_iter().collect(),
+                    subpass: Some(subpass.into()),
+                    ..GraphicsPipelineCreateInfo::layout(layout)
+                },
+            )
+            .unwrap()
+        };
+
+        let vertex_buffer = Buffer::from_iter(
+            memory_allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::VERTEX_BUFFER,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
+                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+            [[-1.0f32, -1.0], [3.0, -1.0], [-1.0, 3.0]],
+        )
+        .unwrap();
+        let output_buffer = Buffer::new_slice::<u8>(
+            memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                ..Default::default()
+            },
+            8,
+        )
+        .unwrap();
+
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+        let mut cbb = RecordingCommandBuffer::new(
+            cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        cbb.begin_render_pass(
+            RenderPassBeginInfo {
+                clear_values: vec![Some([0.0; 4].into()), Some(0.25.into())],
+                ..RenderPassBeginInfo::framebuffer(framebuffer)
+            },
+            Default::default(),
+        )
+        .unwrap()
+        .clear_attachments(
+            [ClearAttachment::Depth(0.75)].into_iter().collect(),
+            [ClearRect {
+                offset: [1, 0],
+                extent: [1, 1],
+                array_layers: 0..1,
+            }]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap()
+        .bind_pipeline_graphics(pipeline)
+        .unwrap()
+        .bind_vertex_buffers(0, vertex_buffer)
+        .unwrap();
+
+        // The depth bounds test is enabled, so the bounds must be set before drawing.
+        assert!(unsafe { cbb.draw(3, 1, 0, 0) }.is_err());
+
+        cbb.set_depth_bounds(0.0..=0.5).unwrap();
+
+        unsafe {
+            cbb.draw(3, 1, 0, 0).unwrap();
+        }
+
+        cbb.end_render_pass(Default::default())
+            .unwrap()
+            .copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(
+                color.image().clone(),
+                output_buffer.clone(),
+            ))
+            .unwrap();
+
+        let cb = cbb.end().unwrap();
+
+        now(device)
+            .then_execute(queue, cb)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        assert_eq!(
+            &*output_buffer.read().unwrap(),
+            &[255, 0, 0, 255, 0, 0, 0, 0]
+        );
+    }
+}
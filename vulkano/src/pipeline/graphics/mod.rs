@@ -70,6 +70,40 @@
 //!    logical operations can be applied to combine incoming pixel data with data already present
 //!    in the framebuffer.
 //!
+//! # Creating a graphics pipeline
+//!
+//! A graphics pipeline is created from a [`GraphicsPipelineCreateInfo`]. The create info owns
+//! all of its states, so it doesn't need to be written as a single struct literal: it can be
+//! started with [`GraphicsPipelineCreateInfo::layout`] and then passed around, with different
+//! parts of an application each filling in the states they are responsible for. Whether the
+//! combination of states is valid is checked once, when the pipeline is created.
+//!
+//! ```
+//! use vulkano::pipeline::graphics::{
+//!     depth_stencil::{DepthState, DepthStencilState},
+//!     rasterization::{CullMode, RasterizationState},
+//!     GraphicsPipelineCreateInfo,
+//! };
+//!
+//! fn apply_opaque_material(create_info: &mut GraphicsPipelineCreateInfo) {
+//!     create_info.rasterization_state = Some(RasterizationState {
+//!         cull_mode: CullMode::Back,
+//!         ..Default::default()
+//!     });
+//!     create_info.depth_stencil_state = Some(DepthStencilState {
+//!         depth: Some(DepthState::simple()),
+//!         ..Default::default()
+//!     });
+//! }
+//! ```
+//!
+//! Mesh shading pipelines must leave [`vertex_input_state`] and [`input_assembly_state`] as
+//! `None`. Primitive shading pipelines must provide both, unless the vertex input state is
+//! dynamic.
+//!
+//! [`vertex_input_state`]: GraphicsPipelineCreateInfo::vertex_input_state
+//! [`input_assembly_state`]: GraphicsPipelineCreateInfo::input_assembly_state
+//!
 //! # Using a graphics pipeline
 //!
 //! Once a graphics pipeline has been created, you can execute it by first *binding* it in a
@@ -93,11 +127,12 @@ use self::{
 use super::{
     cache::PipelineCache,
     inout_interface::{shader_interface_location_info, ShaderInterfaceLocationInfo},
-    shader::inout_interface::validate_interfaces_compatible,
+    shader::inout_interface::{input_output_map, validate_interfaces_compatible, InputOutputKey},
     DynamicState, Pipeline, PipelineBindPoint, PipelineCreateFlags, PipelineLayout,
     PipelineShaderStageCreateInfo,
 };
 use crate::{
+    describe::{self, Describer},
     device::{Device, DeviceOwned, DeviceOwnedDebugWrapper},
     format::FormatFeatures,
     image::{ImageAspect, ImageAspects},
@@ -115,15 +150,21 @@ use crate::{
         },
     },
     shader::{
-        spirv::{ExecutionMode, ExecutionModel, Instruction, StorageClass},
-        DescriptorBindingRequirements, ShaderStage, ShaderStages,
+        spirv::{BuiltIn, ExecutionMode, ExecutionModel, Instruction, StorageClass},
+        DescriptorBindingRequirements, GeometryShaderOutput, ShaderStage, ShaderStages,
     },
-    Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError, VulkanError, VulkanObject,
+    Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError, Version, VulkanError,
+    VulkanObject,
 };
 use ahash::{HashMap, HashSet};
 use smallvec::SmallVec;
 use std::{
-    collections::hash_map::Entry, ffi::CString, fmt::Debug, mem::MaybeUninit, num::NonZeroU64, ptr,
+    collections::{hash_map::Entry, BTreeMap},
+    ffi::CString,
+    fmt::Debug,
+    mem::MaybeUninit,
+    num::NonZeroU64,
+    ptr,
     sync::Arc,
 };
 
@@ -781,6 +822,7 @@ impl GraphicsPipeline {
                     ref color_attachment_formats,
                     depth_attachment_format,
                     stencil_attachment_format,
+                    multisampled_render_to_single_sampled: _,
                     _ne: _,
                 } = rendering_info;
 
@@ -1055,6 +1097,10 @@ impl GraphicsPipeline {
 
         let mut fixed_state: HashSet<DynamicState> = Default::default();
 
+        if vertex_input_state.is_some() {
+            fixed_state.extend([DynamicState::VertexInputBindingStride]);
+        }
+
         if input_assembly_state.is_some() {
             fixed_state.extend([
                 DynamicState::PrimitiveTopology,
@@ -1155,6 +1201,45 @@ impl GraphicsPipeline {
         })
     }
 
+    /// Creates a new `GraphicsPipeline` with the same parameters as `self`, but with `stages` as
+    /// the shader stages.
+    ///
+    /// This is useful to reload shaders at runtime, without having to keep the original
+    /// [`GraphicsPipelineCreateInfo`] around. The new pipeline is validated in full, so the new
+    /// shaders must be compatible with the states and the layout of `self`. `self` is not
+    /// modified, and can keep being used until the new pipeline replaces it.
+    ///
+    /// The new pipeline is not derived from any base pipeline, so
+    /// [`PipelineCreateFlags::DERIVATIVE`] is removed from the flags of `self`.
+    pub fn recreate_with_stages(
+        &self,
+        cache: Option<Arc<PipelineCache>>,
+        stages: &[PipelineShaderStageCreateInfo],
+    ) -> Result<Arc<GraphicsPipeline>, Validated<VulkanError>> {
+        GraphicsPipeline::new(
+            self.device().clone(),
+            cache,
+            GraphicsPipelineCreateInfo {
+                flags: self.flags - PipelineCreateFlags::DERIVATIVE,
+                stages: stages.iter().cloned().collect(),
+                vertex_input_state: self.vertex_input_state.clone(),
+                input_assembly_state: self.input_assembly_state,
+                tessellation_state: self.tessellation_state,
+                viewport_state: self.viewport_state.clone(),
+                rasterization_state: Some(self.rasterization_state.clone()),
+                multisample_state: self.multisample_state,
+                depth_stencil_state: self.depth_stencil_state.clone(),
+                color_blend_state: self.color_blend_state.clone(),
+                dynamic_state: self.dynamic_state.clone(),
+                subpass: Some(self.subpass.clone()),
+                base_pipeline: None,
+                discard_rectangle_state: self.discard_rectangle_state.clone(),
+                conservative_rasterization_state: self.conservative_rasterization_state.clone(),
+                ..GraphicsPipelineCreateInfo::layout(self.layout().clone())
+            },
+        )
+    }
+
     /// Returns the device used to create this pipeline.
     #[inline]
     pub fn device(&self) -> &Arc<Device> {
@@ -1256,6 +1341,87 @@ impl GraphicsPipeline {
         self.fragment_tests_stages
     }
 
+    /// Returns a human-readable description of the parameters that the pipeline was created with,
+    /// including its layout, its subpass and the interface of its shaders.
+    ///
+    /// Unlike the `Debug` output, the description contains no handles, and its format is stable,
+    /// so that the descriptions of two pipelines can be compared with a text diff. States that
+    /// were not provided when the pipeline was created are shown as `(not set)`.
+    pub fn describe(&self) -> String {
+        let mut describer = Describer::new("GraphicsPipeline");
+        describer
+            .field("flags", &self.flags)
+            .field("shader_stages", &self.shader_stages);
+
+        match &self.vertex_input_state {
+            Some(vertex_input_state) => {
+                let bindings: BTreeMap<_, _> = vertex_input_state.bindings.iter().collect();
+                let attributes: BTreeMap<_, _> = vertex_input_state.attributes.iter().collect();
+
+                describer.section("vertex_input_state", |describer| {
+                    describer
+                        .field("bindings", &bindings)
+                        .field("attributes", &attributes);
+                });
+            }
+            None => {
+                describer.optional_field("vertex_input_state", None::<&VertexInputState>);
+            }
+        }
+
+        describer
+            .optional_field("input_assembly_state", self.input_assembly_state.as_ref())
+            .optional_field("tessellation_state", self.tessellation_state.as_ref())
+            .optional_field("viewport_state", self.viewport_state.as_ref())
+            .field("rasterization_state", &self.rasterization_state)
+            .optional_field("multisample_state", self.multisample_state.as_ref())
+            .optional_field("depth_stencil_state", self.depth_stencil_state.as_ref())
+            .optional_field("color_blend_state", self.color_blend_state.as_ref())
+            .optional_field(
+                "discard_rectangle_state",
+                self.discard_rectangle_state.as_ref(),
+            )
+            .optional_field(
+                "conservative_rasterization_state",
+                self.conservative_rasterization_state.as_ref(),
+            )
+            .field(
+                "dynamic_state",
+                &describe::sorted_by_debug(&self.dynamic_state),
+            );
+
+        match &self.subpass {
+            PipelineSubpassType::BeginRenderPass(subpass) => {
+                describer.section("subpass", |describer| {
+                    describer.field("index", &subpass.index()).section(
+                        "render_pass",
+                        |describer| {
+                            subpass.render_pass().describe_fields(describer);
+                        },
+                    );
+                });
+            }
+            PipelineSubpassType::BeginRendering(rendering_info) => {
+                describer.field("rendering_info", rendering_info);
+            }
+        }
+
+        describer.section("layout", |describer| self.layout.describe_fields(describer));
+
+        if let Some(required_vertex_inputs) = &self.required_vertex_inputs {
+            let required_vertex_inputs: BTreeMap<_, _> = required_vertex_inputs.iter().collect();
+            describer.field("vertex_shader_inputs", &required_vertex_inputs);
+        }
+
+        describe::descriptor_binding_requirements(
+            &mut describer,
+            &self.descriptor_binding_requirements,
+        );
+        describer.optional_field("fragment_tests_stages", self.fragment_tests_stages.as_ref());
+
+        describer.finish()
+    }
+
     /// Returns the dynamic states that are not dynamic in this pipeline.
     #[inline]
     pub(crate) fn fixed_state(&self) -> &HashSet<DynamicState> {
@@ -1314,6 +1480,8 @@ unsafe impl VulkanObject for GraphicsPipeline {
 impl Drop for GraphicsPipeline {
     #[inline]
     fn drop(&mut self) {
+        self.device.forget_debug_utils_object_name(self.handle);
+
         unsafe {
             let fns = self.device.fns();
             (fns.v1_0.destroy_pipeline)(self.device.handle(), self.handle, ptr::null());
@@ -1544,7 +1712,7 @@ impl GraphicsPipelineCreateInfo {
 
         const PRIMITIVE_SHADING_STAGES: ShaderStages = ShaderStages::VERTEX
             .union(ShaderStages::TESSELLATION_CONTROL)
-            .union(ShaderStages::TESSELLATION_CONTROL)
+            .union(ShaderStages::TESSELLATION_EVALUATION)
             .union(ShaderStages::GEOMETRY);
         const MESH_SHADING_STAGES: ShaderStages = ShaderStages::MESH.union(ShaderStages::TASK);
 
@@ -1554,6 +1722,22 @@ impl GraphicsPipelineCreateInfo {
             let stage_flag =
                 ShaderStages::from(ShaderStage::from(stage.entry_point.info().execution_model));
 
+            if !(PRIMITIVE_SHADING_STAGES | MESH_SHADING_STAGES | ShaderStages::FRAGMENT)
+                .contains(stage_flag)
+            {
+                return Err(Box::new(ValidationError {
+                    context: "stages".into(),
+                    problem: format!(
+                        "contains an element whose entry point has the execution model \
+                        `ExecutionModel::{:?}`, which is not a graphics shader stage",
+                        stage.entry_point.info().execution_model
+                    )
+                    .into(),
+                    // vuids?
+                    ..Default::default()
+                }));
+            }
+
             if stages_present.intersects(stage_flag) {
                 return Err(Box::new(ValidationError {
                     context: "stages".into(),
@@ -2233,6 +2417,38 @@ impl GraphicsPipelineCreateInfo {
             })?;
         }
 
+        if !(device.api_version() >= Version::V1_3
+            || device.enabled_features().extended_dynamic_state)
+        {
+            if let Some(&dynamic_state) = dynamic_state.iter().find(|dynamic_state| {
+                matches!(
+                    dynamic_state,
+                    DynamicState::CullMode
+                        | DynamicState::FrontFace
+                        | DynamicState::PrimitiveTopology
+                        | DynamicState::ViewportWithCount
+                        | DynamicState::ScissorWithCount
+                        | DynamicState::DepthTestEnable
+                        | DynamicState::DepthWriteEnable
+                        | DynamicState::DepthCompareOp
+                        | DynamicState::DepthBoundsTestEnable
+                        | DynamicState::StencilTestEnable
+                        | DynamicState::StencilOp
+                        | DynamicState::VertexInputBindingStride
+                )
+            }) {
+                return Err(Box::new(ValidationError {
+                    context: "dynamic_state".into(),
+                    problem: format!("contains `DynamicState::{:?}`", dynamic_state).into(),
+                    requires_one_of: RequiresOneOf(&[
+                        RequiresAllOf(&[Requires::APIVersion(Version::V1_3)]),
+                        RequiresAllOf(&[Requires::DeviceFeature("extended_dynamic_state")]),
+                    ]),
+                    vuids: &["VUID-VkGraphicsPipelineCreateInfo-None-03378"],
+                }));
+            }
+        }
+
         /*
             Check dynamic states against other things
         */
@@ -2248,6 +2464,16 @@ impl GraphicsPipelineCreateInfo {
                 }));
             }
 
+            if dynamic_state.contains(&DynamicState::VertexInputBindingStride) {
+                return Err(Box::new(ValidationError {
+                    problem: "`stages` includes a mesh shader, but `dynamic_state` contains \
+                        `DynamicState::VertexInputBindingStride`"
+                        .into(),
+                    vuids: &["VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-07065"],
+                    ..Default::default()
+                }));
+            }
+
             if dynamic_state.contains(&DynamicState::PrimitiveRestartEnable) {
                 return Err(Box::new(ValidationError {
                     problem: "`stages` includes a mesh shader, but `dynamic_state` contains \
@@ -2291,6 +2517,32 @@ impl GraphicsPipelineCreateInfo {
                 _ne: _,
             } = viewport_state;
 
+            if dynamic_state.contains(&DynamicState::Viewport)
+                && dynamic_state.contains(&DynamicState::ViewportWithCount)
+            {
+                return Err(Box::new(ValidationError {
+                    context: "dynamic_state".into(),
+                    problem: "contains both `DynamicState::Viewport` and \
+                        `DynamicState::ViewportWithCount`"
+                        .into(),
+                    vuids: &["VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-04132"],
+                    ..Default::default()
+                }));
+            }
+
+            if dynamic_state.contains(&DynamicState::Scissor)
+                && dynamic_state.contains(&DynamicState::ScissorWithCount)
+            {
+                return Err(Box::new(ValidationError {
+                    context: "dynamic_state".into(),
+                    problem: "contains both `DynamicState::Scissor` and \
+                        `DynamicState::ScissorWithCount`"
+                        .into(),
+                    vuids: &["VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-04133"],
+                    ..Default::default()
+                }));
+            }
+
             if dynamic_state.contains(&DynamicState::ViewportWithCount) {
                 if !viewports.is_empty() {
                     return Err(Box::new(ValidationError {
@@ -2432,8 +2684,6 @@ impl GraphicsPipelineCreateInfo {
             // TODO:
             // VUID-VkGraphicsPipelineCreateInfo-pStages-00740
             // VUID-VkGraphicsPipelineCreateInfo-renderPass-06049
-            // VUID-VkGraphicsPipelineCreateInfo-renderPass-06050
-            // VUID-VkGraphicsPipelineCreateInfo-renderPass-06059
         }
 
         if let Some(_multisample_state) = multisample_state {
@@ -2470,6 +2720,34 @@ impl GraphicsPipelineCreateInfo {
                 }));
             }
 
+            if let Some(depth_bounds) = depth_bounds {
+                if !dynamic_state.contains(&DynamicState::DepthBounds)
+                    && !device.enabled_extensions().ext_depth_range_unrestricted
+                {
+                    if !(0.0..=1.0).contains(depth_bounds.start()) {
+                        return Err(Box::new(ValidationError {
+                            context: "depth_stencil_state.depth_bounds.start()".into(),
+                            problem: "is not between 0.0 and 1.0 inclusive".into(),
+                            requires_one_of: RequiresOneOf(&[RequiresAllOf(&[
+                                Requires::DeviceExtension("ext_depth_range_unrestricted"),
+                            ])]),
+                            vuids: &["VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-02510"],
+                        }));
+                    }
+
+                    if !(0.0..=1.0).contains(depth_bounds.end()) {
+                        return Err(Box::new(ValidationError {
+                            context: "depth_stencil_state.depth_bounds.end()".into(),
+                            problem: "is not between 0.0 and 1.0 inclusive".into(),
+                            requires_one_of: RequiresOneOf(&[RequiresAllOf(&[
+                                Requires::DeviceExtension("ext_depth_range_unrestricted"),
+                            ])]),
+                            vuids: &["VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-02510"],
+                        }));
+                    }
+                }
+            }
+
             if dynamic_state.contains(&DynamicState::StencilTestEnable) && stencil.is_none() {
                 return Err(Box::new(ValidationError {
                     problem: "`dynamic_state` contains `DynamicState::StencilTestEnable`, but \
@@ -2539,40 +2817,23 @@ impl GraphicsPipelineCreateInfo {
             geometry_stage,
             input_assembly_state,
         ) {
-            let spirv = geometry_stage.entry_point.module().spirv();
-            let entry_point_function = spirv.function(geometry_stage.entry_point.id());
-
-            let input = entry_point_function
-                .execution_modes()
-                .iter()
-                .find_map(|instruction| {
-                    if let Instruction::ExecutionMode { mode, .. } = *instruction {
-                        match mode {
-                            ExecutionMode::InputPoints => Some(GeometryShaderInput::Points),
-                            ExecutionMode::InputLines => Some(GeometryShaderInput::Lines),
-                            ExecutionMode::InputLinesAdjacency => {
-                                Some(GeometryShaderInput::LinesWithAdjacency)
-                            }
-                            ExecutionMode::Triangles => Some(GeometryShaderInput::Triangles),
-                            ExecutionMode::InputTrianglesAdjacency => {
-                                Some(GeometryShaderInput::TrianglesWithAdjacency)
-                            }
-                            _ => None,
-                        }
-                    } else {
-                        None
-                    }
-                })
-                .unwrap();
-
-            if !input.is_compatible_with(input_assembly_state.topology) {
-                return Err(Box::new(ValidationError {
-                    problem: "`input_assembly_state.topology` is not compatible with the \
-                        input topology of the geometry shader"
+            if let Some(geometry) = &geometry_stage.entry_point.info().geometry {
+                if !geometry
+                    .input
+                    .is_compatible_with(input_assembly_state.topology)
+                {
+                    return Err(Box::new(ValidationError {
+                        problem: format!(
+                            "the input primitive type of the geometry shader is \
+                            `GeometryShaderInput::{:?}`, but `input_assembly_state.topology` is \
+                            `PrimitiveTopology::{:?}`, which is not compatible with it",
+                            geometry.input, input_assembly_state.topology,
+                        )
                         .into(),
-                    vuids: &["VUID-VkGraphicsPipelineCreateInfo-pStages-00738"],
-                    ..Default::default()
-                }));
+                        vuids: &["VUID-VkGraphicsPipelineCreateInfo-pStages-00738"],
+                        ..Default::default()
+                    }));
+                }
             }
         }
 
@@ -2613,21 +2874,15 @@ impl GraphicsPipelineCreateInfo {
                 }
 
                 if let (Some(geometry_stage), Some(_)) = (geometry_stage, input_assembly_state) {
-                    let spirv = geometry_stage.entry_point.module().spirv();
-                    let entry_point_function = spirv.function(geometry_stage.entry_point.id());
-
                     let invalid_output =
-                        entry_point_function
-                            .execution_modes()
-                            .iter()
-                            .any(|instruction| {
+                        geometry_stage
+                            .entry_point
+                            .info()
+                            .geometry
+                            .map_or(false, |geometry| {
                                 matches!(
-                                    instruction,
-                                    Instruction::ExecutionMode {
-                                        mode: ExecutionMode::OutputPoints
-                                            | ExecutionMode::OutputLineStrip,
-                                        ..
-                                    },
+                                    geometry.output,
+                                    GeometryShaderOutput::Points | GeometryShaderOutput::LineStrip,
                                 )
                             });
 
@@ -2699,6 +2954,30 @@ impl GraphicsPipelineCreateInfo {
                     err
                 })?;
 
+            if color_blend_state.uses_dual_source_blending() {
+                let max_fragment_dual_src_attachments = device
+                    .physical_device()
+                    .properties()
+                    .max_fragment_dual_src_attachments;
+
+                if let Some(&location) = fragment_shader_outputs
+                    .keys()
+                    .find(|&&location| location >= max_fragment_dual_src_attachments)
+                {
+                    return Err(Box::new(ValidationError {
+                        problem: format!(
+                            "`color_blend_state` uses dual-source blending, but the fragment \
+                            shader in `stages` writes to location {}, which is not less than the \
+                            `max_fragment_dual_src_attachments` device limit",
+                            location,
+                        )
+                        .into(),
+                        vuids: &["VUID-RuntimeSpirv-Fragment-06427"],
+                        ..Default::default()
+                    }));
+                }
+            }
+
             // TODO:
             // VUID-VkGraphicsPipelineCreateInfo-pStages-01565
             // VUID-VkGraphicsPipelineCreateInfo-renderPass-06038
@@ -2751,9 +3030,13 @@ impl GraphicsPipelineCreateInfo {
                             return Err(Box::new(ValidationError {
                                 problem: "`multisample_state.rasterization_samples` does not \
                                     equal the number of samples in the color and depth/stencil \
-                                    attachments of `subpass`"
+                                    attachments of `subpass`, or the number of samples of \
+                                    `subpass.subpass_desc().multisampled_render_to_single_sampled`"
                                     .into(),
-                                vuids: &["VUID-VkGraphicsPipelineCreateInfo-subpass-00757"],
+                                vuids: &[
+                                    "VUID-VkGraphicsPipelineCreateInfo-subpass-00757",
+                                    "VUID-VkGraphicsPipelineCreateInfo-multisampledRenderToSingleSampled-06853",
+                                ],
                                 ..Default::default()
                             }));
                         }
@@ -2765,9 +3048,23 @@ impl GraphicsPipelineCreateInfo {
                     // VUID-VkGraphicsPipelineCreateInfo-subpass-01411
                     // VUID-VkGraphicsPipelineCreateInfo-subpass-01412
                 }
-                PipelineSubpassType::BeginRendering(_) => {
+                PipelineSubpassType::BeginRendering(rendering_info) => {
                     // No equivalent VUIDs for dynamic rendering, as no sample count information
-                    // is provided until `begin_rendering`.
+                    // is provided until `begin_rendering`. The number of samples that the
+                    // pipeline declares must match however, as it could never be used otherwise.
+                    // VUID-vkCmdDraw-multisampledRenderToSingleSampled-07285 checks this for the
+                    // render pass instance.
+                    if let Some(samples) = rendering_info.multisampled_render_to_single_sampled {
+                        if multisample_state.rasterization_samples != samples {
+                            return Err(Box::new(ValidationError {
+                                problem: "`subpass.multisampled_render_to_single_sampled` is \
+                                    `Some`, but `multisample_state.rasterization_samples` does \
+                                    not equal it"
+                                    .into(),
+                                ..Default::default()
+                            }));
+                        }
+                    }
                 }
             }
         }
@@ -2931,6 +3228,24 @@ impl GraphicsPipelineCreateInfo {
                         ..Default::default()
                     }));
                 }
+
+                for (stage_index, stage) in stages.iter().enumerate() {
+                    if writes_layer_built_in(stage) {
+                        return Err(Box::new(ValidationError {
+                            problem: format!(
+                                "`subpass` has a non-zero `view_mask`, but the shader of \
+                                `stages[{}]` writes to the `Layer` built-in output",
+                                stage_index
+                            )
+                            .into(),
+                            vuids: &[
+                                "VUID-VkGraphicsPipelineCreateInfo-renderPass-06050",
+                                "VUID-VkGraphicsPipelineCreateInfo-renderPass-06059",
+                            ],
+                            ..Default::default()
+                        }));
+                    }
+                }
             }
         }
 
@@ -3005,50 +3320,475 @@ impl GraphicsPipelineCreateInfo {
     }
 }
 
-/// The input primitive type that is expected by a geometry shader.
+/// The fragment tests stages that will be executed in a fragment shader.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-enum GeometryShaderInput {
-    Points,
-    Lines,
-    LinesWithAdjacency,
-    Triangles,
-    TrianglesWithAdjacency,
+pub enum FragmentTestsStages {
+    Early,
+    Late,
+    EarlyAndLate,
 }
 
-impl GeometryShaderInput {
-    /// Returns true if the given primitive topology can be used as input for this geometry shader.
-    #[inline]
-    fn is_compatible_with(self, topology: PrimitiveTopology) -> bool {
-        match self {
-            Self::Points => matches!(topology, PrimitiveTopology::PointList),
-            Self::Lines => matches!(
-                topology,
-                PrimitiveTopology::LineList | PrimitiveTopology::LineStrip
-            ),
-            Self::LinesWithAdjacency => matches!(
-                topology,
-                PrimitiveTopology::LineListWithAdjacency
-                    | PrimitiveTopology::LineStripWithAdjacency
-            ),
-            Self::Triangles => matches!(
-                topology,
-                PrimitiveTopology::TriangleList
-                    | PrimitiveTopology::TriangleStrip
-                    | PrimitiveTopology::TriangleFan,
-            ),
-            Self::TrianglesWithAdjacency => matches!(
-                topology,
-                PrimitiveTopology::TriangleListWithAdjacency
-                    | PrimitiveTopology::TriangleStripWithAdjacency,
+// Returns whether the entry point of `stage` has an output variable, or a member of an output
+// block, that is decorated with the `Layer` built-in.
+fn writes_layer_built_in(stage: &PipelineShaderStageCreateInfo) -> bool {
+    let entry_point = &stage.entry_point;
+    let spirv = entry_point.module().spirv();
+    let interface = match spirv.function(entry_point.id()).entry_point() {
+        Some(Instruction::EntryPoint { interface, .. }) => interface,
+        _ => unreachable!(),
+    };
+
+    interface.iter().any(|&variable_id| {
+        input_output_map(
+            spirv,
+            entry_point.info().execution_model,
+            variable_id,
+            StorageClass::Output,
+            |key, _| match key {
+                InputOutputKey::BuiltIn(BuiltIn::Layer) => Err(()),
+                _ => Ok(()),
+            },
+        )
+        .is_err()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        color_blend::{AttachmentBlend, BlendFactor, ColorBlendAttachmentState, ColorBlendState},
+        input_assembly::InputAssemblyState,
+        multisample::MultisampleState,
+        rasterization::{CullMode, RasterizationState},
+        vertex_input::{
+            VertexInputAttributeDescription, VertexInputBindingDescription, VertexInputRate,
+            VertexInputState,
+        },
+        viewport::{Viewport, ViewportState},
+        GraphicsPipeline, GraphicsPipelineCreateInfo,
+    };
+    use crate::{
+        device::{
+            Device, DeviceCreateInfo, DeviceExtensions, DeviceFeatures, QueueCreateInfo, QueueFlags,
+        },
+        format::Format,
+        image::{ImageLayout, SampleCount},
+        instance::{Instance, InstanceCreateInfo},
+        pipeline::{
+            layout::PipelineDescriptorSetLayoutCreateInfo, DynamicState, Pipeline,
+            PipelineCreateFlags, PipelineLayout, PipelineShaderStageCreateInfo,
+        },
+        render_pass::{
+            AttachmentDescription, AttachmentLoadOp, AttachmentReference, AttachmentStoreOp,
+            RenderPass, RenderPassCreateInfo, Subpass, SubpassDescription,
+        },
+        shader::{ShaderModule, ShaderModuleCreateInfo},
+        single_pass_renderpass, Validated, Version, VulkanLibrary, VulkanObject,
+    };
+    use std::sync::Arc;
+
+    /// Returns the create info of a pipeline that draws `vec2` positions with a solid color into
+    /// a single `R8G8B8A8_UNORM` attachment.
+    fn test_pipeline_create_info(device: &Arc<Device>) -> GraphicsPipelineCreateInfo {
+        let (vs, fs) = unsafe {
+            /*
+            #version 450
+
+            layout(location = 0) in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+            */
+            const VS: [u32; 228] = [
+                119734787, 65536, 524289, 27, 0, 131089, 1, 131089, 32, 131089, 33, 393227, 1,
+                1280527431, 1685353262, 808793134, 0, 196622, 0, 1, 458767, 0, 4, 1852399981, 0,
+                13, 18, 196611, 2, 450, 589828, 1096764487, 1935622738, 1918988389, 1600484449,
+                1684105331, 1868526181, 1667590754, 29556, 589828, 1096764487, 1935622738,
+                1768186216, 1818191726, 1969712737, 1600481121, 1882206772, 7037793, 262149, 4,
+                1852399981, 0, 393221, 11, 1348430951, 1700164197, 2019914866, 0, 393222, 11, 0,
+                1348430951, 1953067887, 7237481, 458758, 11, 1, 1348430951, 1953393007, 1702521171,
+                0, 458758, 11, 2, 1130327143, 1148217708, 1635021673, 6644590, 458758, 11, 3,
+                1130327143, 1147956341, 1635021673, 6644590, 196613, 13, 0, 327685, 18, 1769172848,
+                1852795252, 0, 327752, 11, 0, 11, 0, 327752, 11, 1, 11, 1, 327752, 11, 2, 11, 3,
+                327752, 11, 3, 11, 4, 196679, 11, 2, 262215, 18, 30, 0, 131091, 2, 196641, 3, 2,
+                196630, 6, 32, 262167, 7, 6, 4, 262165, 8, 32, 0, 262187, 8, 9, 1, 262172, 10, 6,
+                9, 393246, 11, 7, 6, 10, 10, 262176, 12, 3, 11, 262203, 12, 13, 3, 262165, 14, 32,
+                1, 262187, 14, 15, 0, 262167, 16, 6, 2, 262176, 17, 1, 16, 262203, 17, 18, 1,
+                262187, 6, 20, 0, 262187, 6, 21, 1065353216, 262176, 25, 3, 7, 327734, 2, 4, 0, 3,
+                131320, 5, 262205, 16, 19, 18, 327761, 6, 22, 19, 0, 327761, 6, 23, 19, 1, 458832,
+                7, 24, 22, 23, 20, 21, 327745, 25, 26, 13, 15, 196670, 26, 24, 65789, 65592,
+            ];
+            /*
+            #version 450
+
+            layout(location = 0) out vec4 f_color;
+
+            void main() {
+                f_color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+            */
+            const FS: [u32; 105] = [
+                119734787, 65536, 524289, 13, 0, 131089, 1, 393227, 1, 1280527431, 1685353262,
+                808793134, 0, 196622, 0, 1, 393231, 4, 4, 1852399981, 0, 9, 196624, 4, 7, 196611,
+                2, 450, 589828, 1096764487, 1935622738, 1918988389, 1600484449, 1684105331,
+                1868526181, 1667590754, 29556, 589828, 1096764487, 1935622738, 1768186216,
+                1818191726, 1969712737, 1600481121, 1882206772, 7037793, 262149, 4, 1852399981, 0,
+                262149, 9, 1868783462, 7499628, 262215, 9, 30, 0, 131091, 2, 196641, 3, 2, 196630,
+                6, 32, 262167, 7, 6, 4, 262176, 8, 3, 7, 262203, 8, 9, 3, 262187, 6, 10,
+                1065353216, 262187, 6, 11, 0, 458796, 7, 12, 10, 11, 11, 10, 327734, 2, 4, 0, 3,
+                131320, 5, 196670, 9, 12, 65789, 65592,
+            ];
+            let vs = ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(&VS)).unwrap();
+            let fs = ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(&FS)).unwrap();
+
+            (
+                vs.entry_point("main").unwrap(),
+                fs.entry_point("main").unwrap(),
+            )
+        };
+
+        let render_pass = single_pass_renderpass!(
+            device.clone(),
+            attachments: {
+                color: {
+                    format: Format::R8G8B8A8_UNORM,
+                    samples: 1,
+                    load_op: Clear,
+                    store_op: Store,
+                },
+            },
+            pass: {
+                color: [color],
+                depth_stencil: {},
+            },
+        )
+        .unwrap();
+
+        let stages = vec![
+            PipelineShaderStageCreateInfo::new(vs),
+            PipelineShaderStageCreateInfo::new(fs),
+        ];
+        let layout = PipelineLayout::new(
+            device.clone(),
+            PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+                .into_pipeline_layout_create_info(device.clone())
+                .unwrap(),
+        )
+        .unwrap();
+
+        GraphicsPipelineCreateInfo {
+            stages: stages.into_iter().collect(),
+            vertex_input_state: Some(
+                VertexInputState::new()
+                    .binding(
+                        0,
+                        VertexInputBindingDescription {
+                            stride: 8,
+                            input_rate: VertexInputRate::Vertex,
+                            ..Default::default()
+                        },
+                    )
+                    .attribute(
+                        0,
+                        VertexInputAttributeDescription {
+                            binding: 0,
+                            format: Format::R32G32_SFLOAT,
+                            offset: 0,
+                            ..Default::default()
+                        },
+                    ),
             ),
+            input_assembly_state: Some(InputAssemblyState::default()),
+            viewport_state: Some(ViewportState {
+                viewports: [Viewport {
+                    offset: [0.0, 0.0],
+                    extent: [1.0, 1.0],
+                    depth_range: 0.0..=1.0,
+                }]
+                .into_iter()
+                .collect(),
+                ..Default::default()
+            }),
+            rasterization_state: Some(RasterizationState::default()),
+            multisample_state: Some(MultisampleState::default()),
+            color_blend_state: Some(ColorBlendState::with_attachment_states(
+                1,
+                ColorBlendAttachmentState::default(),
+            )),
+            subpass: Some(Subpass::from(render_pass, 0).unwrap().into()),
+            ..GraphicsPipelineCreateInfo::layout(layout)
         }
     }
-}
 
-/// The fragment tests stages that will be executed in a fragment shader.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub enum FragmentTestsStages {
-    Early,
-    Late,
-    EarlyAndLate,
+    #[test]
+    fn extended_dynamic_state_requires_feature() {
+        // Limiting the API version to 1.2 makes the states of `ext_extended_dynamic_state`
+        // depend on the `extended_dynamic_state` feature, which is not enabled here.
+        let Ok(library) = VulkanLibrary::new() else {
+            return;
+        };
+        let Ok(instance) = Instance::new(
+            library,
+            InstanceCreateInfo {
+                max_api_version: Some(Version::V1_2),
+                ..Default::default()
+            },
+        ) else {
+            return;
+        };
+        let enabled_extensions = DeviceExtensions {
+            ext_extended_dynamic_state: true,
+            ..DeviceExtensions::empty()
+        };
+        let enabled_features = DeviceFeatures {
+            shader_clip_distance: true,
+            shader_cull_distance: true,
+            ..DeviceFeatures::empty()
+        };
+        let Some((physical_device, queue_family_index)) = instance
+            .enumerate_physical_devices()
+            .unwrap()
+            .filter(|p| {
+                p.supported_extensions().contains(&enabled_extensions)
+                    && p.supported_features().contains(&enabled_features)
+            })
+            .find_map(|p| {
+                let queue_family_index = p
+                    .queue_family_properties()
+                    .iter()
+                    .position(|q| q.queue_flags.intersects(QueueFlags::GRAPHICS))?;
+
+                Some((p, queue_family_index as u32))
+            })
+        else {
+            return;
+        };
+        let (device, _) = Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index,
+                    ..Default::default()
+                }],
+                enabled_extensions,
+                enabled_features,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(device.api_version() < Version::V1_3);
+
+        let create_info = test_pipeline_create_info(&device);
+
+        for dynamic_state in [
+            DynamicState::CullMode,
+            DynamicState::FrontFace,
+            DynamicState::PrimitiveTopology,
+            DynamicState::ViewportWithCount,
+            DynamicState::ScissorWithCount,
+            DynamicState::DepthTestEnable,
+            DynamicState::DepthWriteEnable,
+            DynamicState::DepthCompareOp,
+            DynamicState::DepthBoundsTestEnable,
+            DynamicState::StencilTestEnable,
+            DynamicState::StencilOp,
+            DynamicState::VertexInputBindingStride,
+        ] {
+            match GraphicsPipeline::new(
+                device.clone(),
+                None,
+                GraphicsPipelineCreateInfo {
+                    dynamic_state: [dynamic_state].into_iter().collect(),
+                    ..create_info.clone()
+                },
+            ) {
+                Err(Validated::ValidationError(err)) => {
+                    assert_eq!(err.vuids, ["VUID-VkGraphicsPipelineCreateInfo-None-03378"]);
+                    assert_eq!(
+                        err.problem,
+                        format!("contains `DynamicState::{:?}`", dynamic_state),
+                    );
+                }
+                _ => panic!("{:?} was not rejected", dynamic_state),
+            }
+        }
+    }
+
+    #[test]
+    fn recreate_with_stages() {
+        let (device, _) = gfx_dev_and_queue!(shader_clip_distance, shader_cull_distance);
+
+        let create_info = test_pipeline_create_info(&device);
+        let stages: Vec<_> = create_info.stages.iter().cloned().collect();
+        let pipeline = GraphicsPipeline::new(
+            device,
+            None,
+            GraphicsPipelineCreateInfo {
+                flags: PipelineCreateFlags::DISABLE_OPTIMIZATION,
+                rasterization_state: Some(RasterizationState {
+                    cull_mode: CullMode::Back,
+                    ..Default::default()
+                }),
+                dynamic_state: [DynamicState::Viewport].into_iter().collect(),
+                ..create_info
+            },
+        )
+        .unwrap();
+
+        let recreated = pipeline.recreate_with_stages(None, &stages).unwrap();
+
+        assert_ne!(recreated.handle(), pipeline.handle());
+        assert!(Arc::ptr_eq(recreated.layout(), pipeline.layout()));
+        assert_eq!(recreated.flags(), PipelineCreateFlags::DISABLE_OPTIMIZATION);
+        assert_eq!(recreated.rasterization_state().cull_mode, CullMode::Back);
+        assert!(recreated.dynamic_state().contains(&DynamicState::Viewport));
+        assert_eq!(recreated.describe(), pipeline.describe());
+    }
+
+    #[test]
+    fn multiview_layer_output() {
+        let (device, _) = gfx_dev_and_queue!(
+            multiview,
+            shader_clip_distance,
+            shader_cull_distance,
+            shader_output_layer
+        );
+
+        let vs = unsafe {
+            // Assembled by hand with the `ShaderLayer` capability of SPIR-V 1.5, from:
+            /*
+            #version 450
+            #extension GL_ARB_shader_viewport_layer_array : require
+
+            layout(location = 0) in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+                gl_Layer = 0;
+            }
+            */
+            const VS: [u32; 124] = [
+                119734787, 66816, 0, 22, 0, 131089, 1, 131089, 69, 196622, 0, 1, 524303, 0, 1,
+                1852399981, 0, 11, 12, 13, 262215, 11, 11, 0, 262215, 12, 11, 9, 262215, 13, 30, 0,
+                131091, 2, 196641, 3, 2, 196630, 4, 32, 262167, 5, 4, 4, 262167, 6, 4, 2, 262165,
+                7, 32, 1, 262176, 8, 3, 5, 262176, 9, 3, 7, 262176, 10, 1, 6, 262203, 8, 11, 3,
+                262203, 9, 12, 3, 262203, 10, 13, 1, 262187, 4, 14, 0, 262187, 4, 15, 1065353216,
+                262187, 7, 16, 0, 327734, 2, 1, 0, 3, 131320, 17, 262205, 6, 18, 13, 327761, 4, 19,
+                18, 0, 327761, 4, 20, 18, 1, 458832, 5, 21, 19, 20, 14, 15, 196670, 11, 21, 196670,
+                12, 16, 65789, 65592,
+            ];
+            let module =
+                ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(&VS)).unwrap();
+            module.entry_point("main").unwrap()
+        };
+
+        let render_pass = RenderPass::new(
+            device.clone(),
+            RenderPassCreateInfo {
+                attachments: vec![AttachmentDescription {
+                    format: Format::R8G8B8A8_UNORM,
+                    samples: SampleCount::Sample1,
+                    load_op: AttachmentLoadOp::Clear,
+                    store_op: AttachmentStoreOp::Store,
+                    initial_layout: ImageLayout::ColorAttachmentOptimal,
+                    final_layout: ImageLayout::ColorAttachmentOptimal,
+                    ..Default::default()
+                }],
+                subpasses: vec![SubpassDescription {
+                    view_mask: 0b11,
+                    color_attachments: vec![Some(AttachmentReference {
+                        attachment: 0,
+                        layout: ImageLayout::ColorAttachmentOptimal,
+                        ..Default::default()
+                    })],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let mut create_info = test_pipeline_create_info(&device);
+        create_info.stages[0] = PipelineShaderStageCreateInfo::new(vs);
+        create_info.subpass = Some(Subpass::from(render_pass, 0).unwrap().into());
+
+        match GraphicsPipeline::new(device, None, create_info) {
+            Err(Validated::ValidationError(err)) => {
+                assert_eq!(
+                    err.vuids,
+                    [
+                        "VUID-VkGraphicsPipelineCreateInfo-renderPass-06050",
+                        "VUID-VkGraphicsPipelineCreateInfo-renderPass-06059",
+                    ],
+                );
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn dual_source_blending_output_location() {
+        let (device, _) =
+            gfx_dev_and_queue!(dual_src_blend, shader_clip_distance, shader_cull_distance);
+
+        // The shader writes to location 1, which is only out of range with the minimum limit.
+        if device
+            .physical_device()
+            .properties()
+            .max_fragment_dual_src_attachments
+            != 1
+        {
+            return;
+        }
+
+        let fs = unsafe {
+            // Assembled by hand, from:
+            /*
+            #version 450
+
+            layout(location = 0, index = 0) out vec4 f_color;
+            layout(location = 0, index = 1) out vec4 f_blend;
+            layout(location = 1) out vec4 f_extra;
+
+            void main() {
+                f_color = vec4(1.0);
+                f_blend = vec4(1.0);
+                f_extra = vec4(1.0);
+            }
+            */
+            const FS: [u32; 98] = [
+                119734787, 65536, 0, 13, 0, 131089, 1, 196622, 0, 1, 524303, 4, 1, 1852399981, 0,
+                7, 8, 9, 196624, 1, 7, 262215, 7, 30, 0, 262215, 7, 32, 0, 262215, 8, 30, 0,
+                262215, 8, 32, 1, 262215, 9, 30, 1, 131091, 2, 196641, 3, 2, 196630, 4, 32, 262167,
+                5, 4, 4, 262176, 6, 3, 5, 262203, 6, 7, 3, 262203, 6, 8, 3, 262203, 6, 9, 3,
+                262187, 4, 10, 1065353216, 458796, 5, 11, 10, 10, 10, 10, 327734, 2, 1, 0, 3,
+                131320, 12, 196670, 7, 11, 196670, 8, 11, 196670, 9, 11, 65789, 65592,
+            ];
+            let module =
+                ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(&FS)).unwrap();
+            module.entry_point("main").unwrap()
+        };
+
+        let mut create_info = test_pipeline_create_info(&device);
+        create_info.stages[1] = PipelineShaderStageCreateInfo::new(fs);
+        create_info.color_blend_state = Some(ColorBlendState::with_attachment_states(
+            1,
+            ColorBlendAttachmentState {
+                blend: Some(AttachmentBlend {
+                    src_color_blend_factor: BlendFactor::One,
+                    dst_color_blend_factor: BlendFactor::Src1Color,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        ));
+
+        match GraphicsPipeline::new(device, None, create_info) {
+            Err(Validated::ValidationError(err)) => {
+                assert_eq!(err.vuids, ["VUID-RuntimeSpirv-Fragment-06427"]);
+            }
+            _ => panic!(),
+        }
+    }
 }
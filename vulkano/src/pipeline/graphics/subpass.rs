@@ -2,7 +2,7 @@ use crate::{
     command_buffer::{CommandBufferInheritanceRenderingInfo, RenderingInfo},
     device::Device,
     format::{Format, FormatFeatures},
-    image::ImageAspects,
+    image::{ImageAspects, SampleCount},
     render_pass::Subpass,
     Requires, RequiresAllOf, RequiresOneOf, ValidationError,
 };
@@ -62,6 +62,23 @@ pub struct PipelineRenderingCreateInfo {
     /// The default value is `None`.
     pub stencil_attachment_format: Option<Format>,
 
+    /// If `Some`, indicates that the pipeline will be used when multisampled rendering to
+    /// single-sampled attachments is enabled, with the given number of rasterization samples.
+    ///
+    /// This must match [`RenderingInfo::multisampled_render_to_single_sampled`] of the render
+    /// pass instance that the pipeline is used in, and
+    /// [`MultisampleState::rasterization_samples`] must be equal to it.
+    ///
+    /// If this is `Some`, then the
+    /// [`multisampled_render_to_single_sampled`](crate::device::DeviceFeatures::multisampled_render_to_single_sampled)
+    /// feature must be enabled on the device, and the value must not be
+    /// [`SampleCount::Sample1`].
+    ///
+    /// The default value is `None`.
+    ///
+    /// [`MultisampleState::rasterization_samples`]: crate::pipeline::graphics::multisample::MultisampleState::rasterization_samples
+    pub multisampled_render_to_single_sampled: Option<SampleCount>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -73,6 +90,7 @@ impl Default for PipelineRenderingCreateInfo {
             color_attachment_formats: Vec::new(),
             depth_attachment_format: None,
             stencil_attachment_format: None,
+            multisampled_render_to_single_sampled: None,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -108,6 +126,8 @@ impl PipelineRenderingCreateInfo {
                     rp_attachments[depth_stencil_attachment.attachment as usize].format
                 })
                 .filter(|format| format.aspects().intersects(ImageAspects::STENCIL)),
+            multisampled_render_to_single_sampled: subpass_desc
+                .multisampled_render_to_single_sampled,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -132,6 +152,7 @@ impl PipelineRenderingCreateInfo {
                 .stencil_attachment
                 .as_ref()
                 .map(|atch_info| atch_info.image_view.format()),
+            multisampled_render_to_single_sampled: info.multisampled_render_to_single_sampled,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -144,6 +165,7 @@ impl PipelineRenderingCreateInfo {
             color_attachment_formats: info.color_attachment_formats.clone(),
             depth_attachment_format: info.depth_attachment_format,
             stencil_attachment_format: info.stencil_attachment_format,
+            multisampled_render_to_single_sampled: None,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -154,6 +176,7 @@ impl PipelineRenderingCreateInfo {
             ref color_attachment_formats,
             depth_attachment_format,
             stencil_attachment_format,
+            multisampled_render_to_single_sampled,
             _ne: _,
         } = self;
 
@@ -308,6 +331,34 @@ impl PipelineRenderingCreateInfo {
             }
         }
 
+        if let Some(rasterization_samples) = multisampled_render_to_single_sampled {
+            if !device
+                .enabled_features()
+                .multisampled_render_to_single_sampled
+            {
+                return Err(Box::new(ValidationError {
+                    context: "multisampled_render_to_single_sampled".into(),
+                    problem: "is `Some`".into(),
+                    requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceFeature(
+                        "multisampled_render_to_single_sampled",
+                    )])]),
+                    ..Default::default()
+                }));
+            }
+
+            rasterization_samples
+                .validate_device(device)
+                .map_err(|err| err.add_context("multisampled_render_to_single_sampled"))?;
+
+            if rasterization_samples == SampleCount::Sample1 {
+                return Err(Box::new(ValidationError {
+                    context: "multisampled_render_to_single_sampled".into(),
+                    problem: "is `Some(SampleCount::Sample1)`".into(),
+                    ..Default::default()
+                }));
+            }
+        }
+
         Ok(())
     }
 }
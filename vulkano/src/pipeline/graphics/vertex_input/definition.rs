@@ -6,8 +6,8 @@ use crate::{
     pipeline::{
         graphics::vertex_input::VertexInputState,
         inout_interface::{
-            input_output_map, InputOutputData, InputOutputKey, InputOutputUserKey,
-            InputOutputVariableBlock,
+            input_output_map, interface_variable_name, type_name, InputOutputData, InputOutputKey,
+            InputOutputUserKey,
         },
     },
     shader::{
@@ -108,30 +108,13 @@ unsafe impl VertexDefinition for &[VertexBufferDescription] {
                         variable_id,
                         pointer_type_id: _,
                         block,
-                        type_id: _,
+                        type_id,
                     } = data;
 
                     // Find the name of the variable defined in the shader,
                     // or use a default placeholder.
-                    let names = if let Some(block) = block {
-                        let InputOutputVariableBlock {
-                            type_id,
-                            member_index,
-                        } = block;
-
-                        spirv.id(type_id).members()[member_index].names()
-                    } else {
-                        spirv.id(variable_id).names()
-                    };
-                    let name = names
-                        .iter()
-                        .find_map(|instruction| match *instruction {
-                            Instruction::Name { ref name, .. }
-                            | Instruction::MemberName { ref name, .. } => {
-                                Some(Cow::Borrowed(name.as_str()))
-                            }
-                            _ => None,
-                        })
+                    let name = interface_variable_name(spirv, variable_id, block.as_ref())
+                        .map(Cow::Borrowed)
                         .unwrap_or_else(|| Cow::Owned(format!("vertex_input_{}", location)));
 
                     // Find a vertex member whose name matches the one in the shader.
@@ -148,9 +131,12 @@ unsafe impl VertexDefinition for &[VertexBufferDescription] {
                             Box::new(ValidationError {
                                 problem: format!(
                                     "the shader interface contains an input variable named \"{}\" \
-                                    (location {}, component {}), but no such attribute exists in \
-                                    the vertex definition",
-                                    name, location, component,
+                                    of type `{}` (location {}, component {}), but no such \
+                                    attribute exists in the vertex definition",
+                                    name,
+                                    type_name(spirv, type_id),
+                                    location,
+                                    component,
                                 )
                                 .into(),
                                 ..Default::default()
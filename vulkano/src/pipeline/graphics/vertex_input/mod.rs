@@ -334,6 +334,19 @@ impl VertexInputState {
         vuids: RequiredVertexInputsVUIDs,
     ) -> Result<(), Box<ValidationError>> {
         for (&location, location_info) in vertex_shader_inputs {
+            // Only built when there is an error, because this also runs on every draw when the
+            // vertex input state is dynamic.
+            let variable_description = || match &location_info.variable_name {
+                Some(name) => format!(
+                    "the vertex shader input variable `{}` of type `{}`",
+                    name, location_info.variable_type_name,
+                ),
+                None => format!(
+                    "the unnamed vertex shader input variable of type `{}`",
+                    location_info.variable_type_name,
+                ),
+            };
+
             let (is_previous, attribute_desc) = self
                 .attributes
                 .get(&location)
@@ -351,8 +364,9 @@ impl VertexInputState {
                 .ok_or_else(|| {
                     Box::new(ValidationError {
                         problem: format!(
-                            "the vertex shader has an input variable with location {0}, but \
-                            the vertex input attributes do not contain {0}",
+                            "{0} has location {1}, but the vertex input attributes do not \
+                            contain {1}",
+                            variable_description(),
                             location,
                         )
                         .into(),
@@ -370,10 +384,15 @@ impl VertexInputState {
             if attribute_numeric_type != location_info.numeric_type {
                 return Err(Box::new(ValidationError {
                     problem: format!(
-                        "the numeric type of the format of vertex input attribute {0} ({1:?}) \
-                        does not equal the numeric type of the vertex shader input variable with \
-                        location {0} ({2:?})",
-                        location, attribute_numeric_type, location_info.numeric_type,
+                        "the numeric type of the format of vertex input attribute {0} \
+                        (binding {1}, format {2:?}, numeric type {3:?}) does not equal the \
+                        numeric type of {4} at location {0} ({5:?})",
+                        location,
+                        attribute_desc.binding,
+                        attribute_desc.format,
+                        attribute_numeric_type,
+                        variable_description(),
+                        location_info.numeric_type,
                     )
                     .into(),
                     vuids: vuids.numeric_type,
@@ -389,9 +408,13 @@ impl VertexInputState {
                     if attribute_components[0] > 32 {
                         return Err(Box::new(ValidationError {
                             problem: format!(
-                                "the vertex shader input variable location {0} requires a non-64-bit \
-                                format, but the format of vertex input attribute {0} is 64-bit",
+                                "{1} at location {0} requires a non-64-bit format, but the \
+                                format of vertex input attribute {0} (binding {2}, format {3:?}) \
+                                is 64-bit",
                                 location,
+                                variable_description(),
+                                attribute_desc.binding,
+                                attribute_desc.format,
                             )
                             .into(),
                             vuids: vuids.requires32,
@@ -403,9 +426,13 @@ impl VertexInputState {
                     if attribute_components[0] <= 32 {
                         return Err(Box::new(ValidationError {
                             problem: format!(
-                                "the vertex shader input variable location {0} requires a 64-bit \
-                                format, but the format of vertex input attribute {0} is not 64-bit",
+                                "{1} at location {0} requires a 64-bit format, but the format \
+                                of vertex input attribute {0} (binding {2}, format {3:?}) is not \
+                                64-bit",
                                 location,
+                                variable_description(),
+                                attribute_desc.binding,
+                                attribute_desc.format,
                             )
                             .into(),
                             vuids: vuids.requires64,
@@ -424,12 +451,16 @@ impl VertexInputState {
                         if attribute_components[second_half_attribute_component] != 64 {
                             return Err(Box::new(ValidationError {
                                 problem: format!(
-                                    "the vertex shader input variable location {0} requires a format \
-                                    with at least {1} 64-bit components, but the format of \
-                                    vertex input attribute {0} contains only {2} components",
+                                    "{3} at location {0} requires a format with at least {1} \
+                                    64-bit components, but the format of vertex input \
+                                    attribute {0} (binding {4}, format {5:?}) contains only {2} \
+                                    components",
                                     location,
                                     second_half_attribute_component + 1,
                                     attribute_components.into_iter().filter(|&c| c != 0).count(),
+                                    variable_description(),
+                                    attribute_desc.binding,
+                                    attribute_desc.format,
                                 )
                                 .into(),
                                 vuids: vuids.requires_second_half,
@@ -19,11 +19,22 @@
 //! If, however, you use a geometry shader, you can specify multiple viewports and scissor boxes.
 //! Then in your geometry shader you can specify in which viewport and scissor box the primitive
 //! should be written to. In GLSL this is done by writing to the special variable
-//! `gl_ViewportIndex`.
+//! `gl_ViewportIndex`. This requires the [`multi_viewport`] feature to be enabled on the device,
+//! and the number of viewports can be at most the [`max_viewports`] limit.
+//!
+//! Writing to `gl_ViewportIndex` from a vertex or tessellation shader additionally requires the
+//! [`shader_output_viewport_index`] feature, or the [`ext_shader_viewport_index_layer`]
+//! extension. If this is not enabled, creating the shader module returns an error that names the
+//! missing SPIR-V capability and the feature that enables it.
 //!
 //! If you don't use a geometry shader or use a geometry shader where don't set which viewport to
 //! use, then the first viewport and scissor box will be used.
 //!
+//! [`multi_viewport`]: crate::device::DeviceFeatures::multi_viewport
+//! [`max_viewports`]: crate::device::DeviceProperties::max_viewports
+//! [`shader_output_viewport_index`]: crate::device::DeviceFeatures::shader_output_viewport_index
+//! [`ext_shader_viewport_index_layer`]: crate::device::DeviceExtensions::ext_shader_viewport_index_layer
+//!
 //! # Dynamic and fixed
 //!
 //! Vulkan allows four different setups:
@@ -37,6 +48,18 @@
 //!   command.
 //!
 //! In all cases the number of viewports and scissor boxes must be the same.
+//!
+//! With [`DynamicState::ViewportWithCount`] and [`DynamicState::ScissorWithCount`], the number of
+//! viewports and scissor boxes can also be made dynamic. In that case, `viewports` and `scissors`
+//! of [`ViewportState`] must be left empty, and the values are set with
+//! [`set_viewport_with_count`] and [`set_scissor_with_count`] instead. This requires Vulkan 1.3,
+//! or the [`extended_dynamic_state`] feature.
+//!
+//! [`DynamicState::ViewportWithCount`]: crate::pipeline::DynamicState::ViewportWithCount
+//! [`DynamicState::ScissorWithCount`]: crate::pipeline::DynamicState::ScissorWithCount
+//! [`set_viewport_with_count`]: crate::command_buffer::RecordingCommandBuffer::set_viewport_with_count
+//! [`set_scissor_with_count`]: crate::command_buffer::RecordingCommandBuffer::set_scissor_with_count
+//! [`extended_dynamic_state`]: crate::device::DeviceFeatures::extended_dynamic_state
 
 use crate::{device::Device, Requires, RequiresAllOf, RequiresOneOf, ValidationError, Version};
 use smallvec::{smallvec, SmallVec};
@@ -57,6 +57,8 @@
 
 use super::PipelineShaderStageCreateInfo;
 use crate::{
+    buffer::BufferContents,
+    describe::Describer,
     descriptor_set::layout::{
         DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateFlags,
         DescriptorSetLayoutCreateInfo, DescriptorType,
@@ -310,6 +312,42 @@ impl PipelineLayout {
             })
     }
 
+    /// Returns a human-readable description of the parameters that the pipeline layout was
+    /// created with, including the descriptor set layouts.
+    ///
+    /// Unlike the `Debug` output, the description contains no handles, and its format is stable,
+    /// so that the descriptions of two pipeline layouts can be compared with a text diff.
+    pub fn describe(&self) -> String {
+        let mut describer = Describer::new("PipelineLayout");
+        self.describe_fields(&mut describer);
+
+        describer.finish()
+    }
+
+    pub(crate) fn describe_fields(&self, describer: &mut Describer) {
+        describer
+            .field("flags", &self.flags)
+            .list("set_layouts", &self.set_layouts, |describer, set_layout| {
+                set_layout.describe_fields(describer);
+            })
+            .list(
+                "push_constant_ranges",
+                &self.push_constant_ranges,
+                |describer, range| {
+                    let &PushConstantRange {
+                        stages,
+                        offset,
+                        size,
+                    } = range;
+
+                    describer
+                        .field("stages", &stages)
+                        .field("offset", &offset)
+                        .field("size", &size);
+                },
+            );
+    }
+
     /// Makes sure that `self` is a superset of the provided descriptor set layouts and push
     /// constant ranges. Returns an `Err` if this is not the case.
     pub(crate) fn ensure_compatible_with_shader<'a>(
@@ -718,6 +756,7 @@ impl PipelineLayoutCreateInfo {
                     descriptor_count,
                     stages,
                     immutable_samplers: _,
+                    buffer_min_size: _,
                     _ne: _,
                 } = layout_binding;
 
@@ -1031,6 +1070,20 @@ impl PushConstantRange {
     }
 }
 
+/// A Rust type that has the same layout as the push constant block of one or more shaders.
+///
+/// This trait is implemented by the `shader!` macro of `vulkano-shaders` for each struct that is
+/// used as a push constant block. Values of such a type can be pushed with
+/// [`RecordingCommandBuffer::push_constants_typed`], which additionally checks that the pipeline
+/// layout provides the block to all of the stages in [`STAGES`].
+///
+/// [`RecordingCommandBuffer::push_constants_typed`]: crate::command_buffer::RecordingCommandBuffer::push_constants_typed
+/// [`STAGES`]: Self::STAGES
+pub trait PushConstants: BufferContents + Sized {
+    /// The shader stages that access the push constant block.
+    const STAGES: ShaderStages;
+}
+
 /// Parameters to create a new `PipelineLayout` as well as its accompanying `DescriptorSetLayout`
 /// objects.
 #[derive(Clone, Debug)]
@@ -1198,9 +1251,14 @@ mod tests {
 
     use super::PipelineLayout;
     use crate::{
+        descriptor_set::layout::{
+            DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo,
+            DescriptorType,
+        },
         pipeline::layout::{PipelineLayoutCreateInfo, PushConstantRange},
         shader::ShaderStages,
     };
+    use std::collections::BTreeMap;
 
     #[test]
     fn push_constant_ranges_disjoint() {
@@ -1367,6 +1425,63 @@ mod tests {
             assert_eq!(layout.push_constant_ranges_disjoint.as_slice(), expected);
         }
     }
+
+    #[test]
+    fn describe() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let set_layout = DescriptorSetLayout::new(
+            device.clone(),
+            DescriptorSetLayoutCreateInfo {
+                bindings: BTreeMap::from([(
+                    0,
+                    DescriptorSetLayoutBinding {
+                        stages: ShaderStages::COMPUTE,
+                        ..DescriptorSetLayoutBinding::descriptor_type(DescriptorType::StorageBuffer)
+                    },
+                )]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let layout = PipelineLayout::new(
+            device,
+            PipelineLayoutCreateInfo {
+                set_layouts: vec![set_layout],
+                push_constant_ranges: vec![PushConstantRange {
+                    stages: ShaderStages::COMPUTE,
+                    offset: 0,
+                    size: 16,
+                }],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // This is a snapshot of the format, which must only change deliberately.
+        assert_eq!(
+            layout.describe(),
+            "\
+PipelineLayout
+  flags: empty()
+  set_layouts:
+    [0]:
+      flags: empty()
+      bindings:
+        0:
+          binding_flags: empty()
+          descriptor_type: StorageBuffer
+          descriptor_count: 1
+          stages: COMPUTE
+          immutable_samplers: 0
+  push_constant_ranges:
+    [0]:
+      stages: COMPUTE
+      offset: 0
+      size: 16
+",
+        );
+    }
 }
 
 /* TODO: restore
@@ -428,13 +428,17 @@ vulkan_enum! {
         RequiresAllOf([DeviceExtension(ext_extended_dynamic_state)]),
     ]),
 
-    /* TODO: enable
-    // TODO: document
+    /// The value of
+    /// [`VertexInputBindingDescription::stride`](graphics::vertex_input::VertexInputBindingDescription::stride)
+    /// for each binding.
+    ///
+    /// Set with
+    /// [`bind_vertex_buffers_with_strides`](crate::command_buffer::RecordingCommandBuffer::bind_vertex_buffers_with_strides).
     VertexInputBindingStride = VERTEX_INPUT_BINDING_STRIDE
     RequiresOneOf([
         RequiresAllOf([APIVersion(V1_3)]),
         RequiresAllOf([DeviceExtension(ext_extended_dynamic_state)]),
-    ]),*/
+    ]),
 
     /// The `Option` variant of
     /// [`DepthStencilState::depth`](crate::pipeline::graphics::depth_stencil::DepthStencilState::depth).
@@ -38,10 +38,13 @@ pub(crate) fn validate_interfaces_compatible(
             .ok_or_else(|| {
                 Box::new(ValidationError {
                     problem: format!(
-                        "the input interface includes a variable at location {}, component {}, \
-                        but the output interface does not contain a variable with the same \
-                        location and component",
-                        location, component,
+                        "the input interface includes {} of type `{}` at location {}, \
+                        component {}, but the output interface does not contain a variable with \
+                        the same location and component",
+                        describe_variable("variable", in_variable_info.name),
+                        type_name(in_spirv, in_variable_info.type_id),
+                        location,
+                        component,
                     )
                     .into(),
                     vuids: &["VUID-RuntimeSpirv-OpEntryPoint-08743"],
@@ -49,6 +52,16 @@ pub(crate) fn validate_interfaces_compatible(
                 })
             })?;
 
+        let key_description = || {
+            format!(
+                "interface location {}, component {} ({}, {})",
+                location,
+                component,
+                describe_variable("output variable", out_variable_info.name),
+                describe_variable("input variable", in_variable_info.name),
+            )
+        };
+
         if !are_interface_decoration_sets_compatible(
             out_spirv,
             out_variable_info.variable_decorations,
@@ -58,9 +71,9 @@ pub(crate) fn validate_interfaces_compatible(
         ) {
             return Err(Box::new(ValidationError {
                 problem: format!(
-                    "for interface location {}, component {}, \
-                    the input variable doesn't have the same decorations as the output variable",
-                    location, component,
+                    "for {}, the input variable doesn't have the same decorations as the output \
+                    variable",
+                    key_description(),
                 )
                 .into(),
                 vuids: &["VUID-RuntimeSpirv-OpVariable-08746"],
@@ -77,10 +90,9 @@ pub(crate) fn validate_interfaces_compatible(
         ) {
             return Err(Box::new(ValidationError {
                 problem: format!(
-                    "for interface location {}, component {}, \
-                    the input variable's pointer type doesn't have the same decorations as \
-                    the output variable's pointer type",
-                    location, component,
+                    "for {}, the input variable's pointer type doesn't have the same decorations \
+                    as the output variable's pointer type",
+                    key_description(),
                 )
                 .into(),
                 // vuids?
@@ -88,6 +100,25 @@ pub(crate) fn validate_interfaces_compatible(
             }));
         }
 
+        let type_mismatch_error = || {
+            Box::new(ValidationError {
+                problem: format!(
+                    "for {}, the input variable doesn't have the same or a compatible type as \
+                    the output variable: the output variable has type `{}`, but the input \
+                    variable has type `{}`",
+                    key_description(),
+                    type_name(out_spirv, out_variable_info.type_id),
+                    type_name(in_spirv, in_variable_info.type_id),
+                )
+                .into(),
+                vuids: &[
+                    "VUID-RuntimeSpirv-OpEntryPoint-07754",
+                    "VUID-RuntimeSpirv-maintenance4-06817",
+                ],
+                ..Default::default()
+            })
+        };
+
         match (
             &out_variable_info.block_type_info,
             &in_variable_info.block_type_info,
@@ -102,10 +133,9 @@ pub(crate) fn validate_interfaces_compatible(
                 ) {
                     return Err(Box::new(ValidationError {
                         problem: format!(
-                            "for interface location {}, component {}, \
-                            the input block structure type doesn't have the same decorations \
-                            as the output block structure type",
-                            location, component,
+                            "for {}, the input block structure type doesn't have the same \
+                            decorations as the output block structure type",
+                            key_description(),
                         )
                         .into(),
                         vuids: &["VUID-RuntimeSpirv-OpVariable-08746"],
@@ -122,10 +152,9 @@ pub(crate) fn validate_interfaces_compatible(
                 ) {
                     return Err(Box::new(ValidationError {
                         problem: format!(
-                            "for interface location {}, component {}, \
-                            the input block structure member doesn't have the same decorations as \
-                            the output block structure member",
-                            location, component,
+                            "for {}, the input block structure member doesn't have the same \
+                            decorations as the output block structure member",
+                            key_description(),
                         )
                         .into(),
                         vuids: &["VUID-RuntimeSpirv-OpVariable-08746"],
@@ -137,20 +166,7 @@ pub(crate) fn validate_interfaces_compatible(
             (Some(_), None) | (None, Some(_)) => {
                 // TODO: this may be allowed, depending on the outcome of this discussion:
                 // https://github.com/KhronosGroup/Vulkan-Docs/issues/2242
-                return Err(Box::new(ValidationError {
-                    problem: format!(
-                        "for interface location {}, component {}, \
-                        the input variable doesn't have the same or a compatible type as \
-                        the output variable",
-                        location, component,
-                    )
-                    .into(),
-                    vuids: &[
-                        "VUID-RuntimeSpirv-OpEntryPoint-07754",
-                        "VUID-RuntimeSpirv-maintenance4-06817",
-                    ],
-                    ..Default::default()
-                }));
+                return Err(type_mismatch_error());
             }
         }
 
@@ -161,27 +177,24 @@ pub(crate) fn validate_interfaces_compatible(
             in_variable_info.type_id,
             allow_larger_output_vector,
         ) {
-            return Err(Box::new(ValidationError {
-                problem: format!(
-                    "for interface location {}, component {}, \
-                    the input variable doesn't have the same or a compatible type as \
-                    the output variable",
-                    location, component,
-                )
-                .into(),
-                vuids: &[
-                    "VUID-RuntimeSpirv-OpEntryPoint-07754",
-                    "VUID-RuntimeSpirv-maintenance4-06817",
-                ],
-                ..Default::default()
-            }));
+            return Err(type_mismatch_error());
         }
     }
 
     Ok(())
 }
 
+/// Describes an interface variable for use in an error message, such as
+/// ``output variable `v_color` `` or `unnamed output variable`.
+fn describe_variable(kind: &str, name: Option<&str>) -> String {
+    match name {
+        Some(name) => format!("{} `{}`", kind, name),
+        None => format!("unnamed {}", kind),
+    }
+}
+
 struct InterfaceVariableInfo<'a> {
+    name: Option<&'a str>,
     variable_decorations: &'a [Instruction],
     pointer_type_decorations: &'a [Instruction],
     block_type_info: Option<InterfaceVariableBlockInfo<'a>>,
@@ -226,6 +239,7 @@ fn get_variables_by_key<'a>(
                     variables_by_key.insert(
                         (location, component),
                         InterfaceVariableInfo {
+                            name: interface_variable_name(spirv, variable_id, block.as_ref()),
                             variable_decorations: spirv.id(variable_id).decorations(),
                             pointer_type_decorations: spirv.id(pointer_type_id).decorations(),
                             block_type_info: block.map(|block| {
@@ -251,6 +265,132 @@ fn get_variables_by_key<'a>(
     variables_by_key
 }
 
+/// Returns the name of an interface variable from the `OpName` instruction in `spirv`, or the name
+/// of the block member from the `OpMemberName` instruction if `block` is provided.
+pub(crate) fn interface_variable_name<'a>(
+    spirv: &'a Spirv,
+    variable_id: Id,
+    block: Option<&InputOutputVariableBlock>,
+) -> Option<&'a str> {
+    let names = if let Some(&InputOutputVariableBlock {
+        type_id,
+        member_index,
+    }) = block
+    {
+        spirv.id(type_id).members()[member_index].names()
+    } else {
+        spirv.id(variable_id).names()
+    };
+
+    names.iter().find_map(|instruction| match *instruction {
+        Instruction::Name { ref name, .. } | Instruction::MemberName { ref name, .. } => {
+            Some(name.as_str())
+        }
+        _ => None,
+    })
+}
+
+/// Returns a GLSL-like name for the type `type_id`, such as `vec3`, `uint` or `mat4x3`, for use
+/// in error messages.
+pub(crate) fn type_name(spirv: &Spirv, type_id: Id) -> String {
+    let id_info = spirv.id(type_id);
+
+    // The prefix of vector and matrix type names for the given scalar type.
+    let prefix = |scalar_type_id: Id| match *spirv.id(scalar_type_id).instruction() {
+        Instruction::TypeBool { .. } => "b".to_owned(),
+        Instruction::TypeInt {
+            width: 32,
+            signedness,
+            ..
+        } => (if signedness == 1 { "i" } else { "u" }).to_owned(),
+        Instruction::TypeInt {
+            width, signedness, ..
+        } => format!("{}{}", if signedness == 1 { "i" } else { "u" }, width),
+        Instruction::TypeFloat { width: 32, .. } => String::new(),
+        Instruction::TypeFloat { width: 64, .. } => "d".to_owned(),
+        Instruction::TypeFloat { width, .. } => format!("f{}", width),
+        _ => "?".to_owned(),
+    };
+
+    match *id_info.instruction() {
+        Instruction::TypeBool { .. } => "bool".to_owned(),
+        Instruction::TypeInt {
+            width: 32,
+            signedness,
+            ..
+        } => (if signedness == 1 { "int" } else { "uint" }).to_owned(),
+        Instruction::TypeInt {
+            width, signedness, ..
+        } => format!("{}int{}_t", if signedness == 1 { "" } else { "u" }, width),
+        Instruction::TypeFloat { width: 32, .. } => "float".to_owned(),
+        Instruction::TypeFloat { width: 64, .. } => "double".to_owned(),
+        Instruction::TypeFloat { width, .. } => format!("float{}_t", width),
+        Instruction::TypeVector {
+            component_type,
+            component_count,
+            ..
+        } => format!("{}vec{}", prefix(component_type), component_count),
+        Instruction::TypeMatrix {
+            column_type,
+            column_count,
+            ..
+        } => match *spirv.id(column_type).instruction() {
+            Instruction::TypeVector {
+                component_type,
+                component_count,
+                ..
+            } => {
+                if column_count == component_count {
+                    format!("{}mat{}", prefix(component_type), column_count)
+                } else {
+                    format!(
+                        "{}mat{}x{}",
+                        prefix(component_type),
+                        column_count,
+                        component_count,
+                    )
+                }
+            }
+            _ => unreachable!(),
+        },
+        Instruction::TypeArray {
+            element_type,
+            length,
+            ..
+        } => match get_constant(spirv, length) {
+            Some(length) => format!("{}[{}]", type_name(spirv, element_type), length),
+            None => format!("{}[]", type_name(spirv, element_type)),
+        },
+        Instruction::TypeRuntimeArray { element_type, .. } => {
+            format!("{}[]", type_name(spirv, element_type))
+        }
+        Instruction::TypeStruct {
+            ref member_types, ..
+        } => {
+            let struct_name = id_info
+                .names()
+                .iter()
+                .find_map(|instruction| match *instruction {
+                    Instruction::Name { ref name, .. } => Some(name.as_str()),
+                    _ => None,
+                });
+
+            match struct_name {
+                Some(struct_name) => format!("struct {}", struct_name),
+                None => format!(
+                    "struct {{ {} }}",
+                    member_types
+                        .iter()
+                        .map(|&member_type| type_name(spirv, member_type))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+            }
+        }
+        _ => format!("<type {}>", type_id),
+    }
+}
+
 fn are_interface_types_compatible(
     out_spirv: &Spirv,
     out_type_id: Id,
@@ -638,11 +778,15 @@ fn decoration_filter_variable(instruction: &Instruction) -> bool {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub(crate) struct ShaderInterfaceLocationInfo {
     pub(crate) numeric_type: NumericType,
     pub(crate) width: ShaderInterfaceLocationWidth,
     pub(crate) components: [ColorComponents; 2], // Index 0 and 1
+
+    // The first variable found at this location, for use in error messages.
+    pub(crate) variable_name: Option<String>,
+    pub(crate) variable_type_name: String,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -706,6 +850,8 @@ pub(crate) fn shader_interface_location_info(
                 numeric_type,
                 width,
                 components: [ColorComponents::empty(); 2],
+                variable_name: None,
+                variable_type_name: String::new(),
             }),
         };
         let components = &mut location_info.components[index as usize];
@@ -722,6 +868,8 @@ pub(crate) fn shader_interface_location_info(
         *components |= components_to_add;
     };
 
+    let mut variables: HashMap<u32, (Option<&str>, Id)> = HashMap::default();
+
     for &variable_id in interface {
         input_output_map(
             spirv,
@@ -730,8 +878,23 @@ pub(crate) fn shader_interface_location_info(
             filter_storage_class,
             |key, data| -> Result<(), Infallible> {
                 if let InputOutputKey::User(key) = key {
-                    let InputOutputData { type_id, .. } = data;
-                    shader_interface_analyze_type(spirv, type_id, key, &mut scalar_func);
+                    let InputOutputData {
+                        variable_id,
+                        block,
+                        type_id,
+                        ..
+                    } = data;
+                    let name = interface_variable_name(spirv, variable_id, block.as_ref());
+
+                    shader_interface_analyze_type(
+                        spirv,
+                        type_id,
+                        key,
+                        &mut |key, width, numeric_type| {
+                            variables.entry(key.location).or_insert((name, type_id));
+                            scalar_func(key, width, numeric_type);
+                        },
+                    );
                 }
 
                 Ok(())
@@ -740,6 +903,12 @@ pub(crate) fn shader_interface_location_info(
         .unwrap();
     }
 
+    for (location, (name, type_id)) in variables {
+        let location_info = locations.get_mut(&location).unwrap();
+        location_info.variable_name = name.map(ToOwned::to_owned);
+        location_info.variable_type_name = type_name(spirv, type_id);
+    }
+
     locations
 }
 
@@ -1052,3 +1221,103 @@ fn strip_array(
         pointed_type_id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::validate_interfaces_compatible;
+    use crate::shader::spirv::{ExecutionModel, Instruction, Spirv};
+
+    #[test]
+    fn interface_mismatch_messages() {
+        /*
+            ; SPIR-V
+            ; Version: 1.0
+            ; Bound: 14
+            ; Schema: 0
+            OpCapability Shader
+            OpMemoryModel Logical GLSL450
+            OpEntryPoint Vertex %main_vs "main_vs" %v_color
+            OpEntryPoint Fragment %main_fs "main_fs" %f_color
+            OpExecutionMode %main_fs OriginUpperLeft
+            OpName %v_color "v_color"
+            OpName %f_color "f_color"
+            OpDecorate %v_color Location 1
+            OpDecorate %f_color Location 1
+            %void = OpTypeVoid
+            %fn_void = OpTypeFunction %void
+            %float = OpTypeFloat 32
+            %v3float = OpTypeVector %float 3
+            %v4float = OpTypeVector %float 4
+            %_ptr_Output_v3float = OpTypePointer Output %v3float
+            %_ptr_Input_v4float = OpTypePointer Input %v4float
+            %v_color = OpVariable %_ptr_Output_v3float Output
+            %f_color = OpVariable %_ptr_Input_v4float Input
+            %main_vs = OpFunction %void None %fn_void
+                %main_vs_label = OpLabel
+                OpReturn
+            OpFunctionEnd
+            %main_fs = OpFunction %void None %fn_void
+                %main_fs_label = OpLabel
+                OpReturn
+            OpFunctionEnd
+        */
+        const MODULE: [u32; 91] = [
+            119734787, 65536, 0, 14, 0, 131089, 1, 196622, 0, 1, 393231, 0, 10, 1852399981,
+            7566943, 8, 393231, 4, 12, 1852399981, 7562847, 9, 196624, 12, 7, 262149, 8,
+            1868783478, 7499628, 262149, 9, 1868783462, 7499628, 262215, 8, 30, 1, 262215, 9, 30,
+            1, 131091, 1, 196641, 2, 1, 196630, 3, 32, 262167, 4, 3, 3, 262167, 5, 3, 4, 262176, 6,
+            3, 4, 262176, 7, 1, 5, 262203, 6, 8, 3, 262203, 7, 9, 1, 327734, 1, 10, 0, 2, 131320,
+            11, 65789, 65592, 327734, 1, 12, 0, 2, 131320, 13, 65789, 65592,
+        ];
+        let spirv = Spirv::new(&MODULE).unwrap();
+        let interface = |execution_model: ExecutionModel| {
+            spirv
+                .entry_points()
+                .iter()
+                .find_map(|instruction| match instruction {
+                    Instruction::EntryPoint {
+                        execution_model: e,
+                        interface,
+                        ..
+                    } if *e == execution_model => Some(interface.as_slice()),
+                    _ => None,
+                })
+                .unwrap()
+        };
+
+        let err = validate_interfaces_compatible(
+            &spirv,
+            ExecutionModel::Vertex,
+            interface(ExecutionModel::Vertex),
+            &spirv,
+            ExecutionModel::Fragment,
+            interface(ExecutionModel::Fragment),
+            true,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.problem,
+            "for interface location 1, component 0 (output variable `v_color`, input variable \
+            `f_color`), the input variable doesn't have the same or a compatible type as the \
+            output variable: the output variable has type `vec3`, but the input variable has \
+            type `vec4`",
+        );
+
+        let err = validate_interfaces_compatible(
+            &spirv,
+            ExecutionModel::Vertex,
+            &[],
+            &spirv,
+            ExecutionModel::Fragment,
+            interface(ExecutionModel::Fragment),
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.problem,
+            "the input interface includes variable `f_color` of type `vec4` at location 1, \
+            component 0, but the output interface does not contain a variable with the same \
+            location and component",
+        );
+    }
+}
@@ -126,9 +126,44 @@ impl PipelineShaderStageCreateInfo {
                     }));
                 }
 
-                // TODO:
+                let Some(geometry) = entry_point.info().geometry else {
+                    return Err(Box::new(ValidationError {
+                        context: "entry_point".into(),
+                        problem: "is a geometry shader entry point, but it does not have both an \
+                            input primitive and an output primitive execution mode"
+                            .into(),
+                        // vuids?
+                        ..Default::default()
+                    }));
+                };
+
+                if geometry.invocations == 0 {
+                    return Err(Box::new(ValidationError {
+                        context: "entry_point".into(),
+                        problem: "the invocation count of the `ExecutionMode::Invocations` \
+                            is zero"
+                            .into(),
+                        vuids: &["VUID-VkPipelineShaderStageCreateInfo-stage-00715"],
+                        ..Default::default()
+                    }));
+                }
+
+                if geometry.invocations > properties.max_geometry_shader_invocations {
+                    return Err(Box::new(ValidationError {
+                        context: "entry_point".into(),
+                        problem: format!(
+                            "the invocation count of the `ExecutionMode::Invocations` ({}) is \
+                            greater than the `max_geometry_shader_invocations` device limit ({})",
+                            geometry.invocations, properties.max_geometry_shader_invocations,
+                        )
+                        .into(),
+                        vuids: &["VUID-VkPipelineShaderStageCreateInfo-stage-00715"],
+                        ..Default::default()
+                    }));
+                }
+
                 // VUID-VkPipelineShaderStageCreateInfo-stage-00714
-                // VUID-VkPipelineShaderStageCreateInfo-stage-00715
+                // Checked below, together with tessellation shaders.
             }
             ShaderStage::Fragment => {
                 // TODO:
@@ -326,10 +361,13 @@ impl PipelineShaderStageCreateInfo {
                         if vertex_count > properties.max_geometry_output_vertices {
                             return Err(Box::new(ValidationError {
                                 context: "entry_point".into(),
-                                problem: "the `vertex_count` of the \
-                                    `ExecutionMode::OutputVertices` is greater than the \
-                                    `max_geometry_output_vertices` device limit"
-                                    .into(),
+                                problem: format!(
+                                    "the `vertex_count` of the `ExecutionMode::OutputVertices` \
+                                    ({}) is greater than the `max_geometry_output_vertices` \
+                                    device limit ({})",
+                                    vertex_count, properties.max_geometry_output_vertices,
+                                )
+                                .into(),
                                 vuids: &["VUID-VkPipelineShaderStageCreateInfo-stage-00714"],
                                 ..Default::default()
                             }));
@@ -7,7 +7,8 @@ use crate::{
     shader::{
         reflect::{
             get_constant, get_constant_composite, get_constant_composite_composite,
-            get_constant_float_composite, get_constant_maybe_composite, size_of_type,
+            get_constant_float_composite, get_constant_maybe_composite, output_component_count,
+            size_of_type,
         },
         spirv::{
             Capability, Decoration, Dim, ExecutionMode, ExecutionModel, FunctionInfo, Id,
@@ -38,6 +39,7 @@ pub(crate) fn validate_runtime(
     let mut validator = RuntimeValidator {
         device,
         spirv,
+        entry_point,
         entry_point_info,
         execution_model,
         interface,
@@ -60,6 +62,7 @@ pub(crate) fn validate_runtime(
 struct RuntimeValidator<'a> {
     device: &'a Device,
     spirv: &'a Spirv,
+    entry_point: Id,
     entry_point_info: &'a FunctionInfo,
     execution_model: ExecutionModel,
     interface: &'a [Id],
@@ -1595,7 +1598,7 @@ impl<'a> RuntimeValidator<'a> {
                     }
                 ) {
                     if !has_non_writable
-                        && self
+                        && !self
                             .device
                             .enabled_features()
                             .shader_storage_image_write_without_format
@@ -1613,7 +1616,9 @@ impl<'a> RuntimeValidator<'a> {
                                 RequiresAllOf(&[Requires::DeviceExtension(
                                     "khr_format_feature_flags2",
                                 )]),
-                                RequiresAllOf(&[Requires::DeviceFeature("storage_input_output16")]),
+                                RequiresAllOf(&[Requires::DeviceFeature(
+                                    "shader_storage_image_write_without_format",
+                                )]),
                             ]),
                             vuids: &["VUID-RuntimeSpirv-apiVersion-07954"],
                             ..Default::default()
@@ -1621,7 +1626,7 @@ impl<'a> RuntimeValidator<'a> {
                     }
 
                     if !has_non_readable
-                        && self
+                        && !self
                             .device
                             .enabled_features()
                             .shader_storage_image_read_without_format
@@ -1639,7 +1644,9 @@ impl<'a> RuntimeValidator<'a> {
                                 RequiresAllOf(&[Requires::DeviceExtension(
                                     "khr_format_feature_flags2",
                                 )]),
-                                RequiresAllOf(&[Requires::DeviceFeature("storage_input_output16")]),
+                                RequiresAllOf(&[Requires::DeviceFeature(
+                                    "shader_storage_image_read_without_format",
+                                )]),
                             ]),
                             vuids: &["VUID-RuntimeSpirv-apiVersion-07955"],
                             ..Default::default()
@@ -1802,7 +1809,40 @@ impl<'a> RuntimeValidator<'a> {
                     }));
                 }
 
-                // TODO: max_geometry_total_output_components
+                let output_vertices = self
+                    .entry_point_info
+                    .execution_modes()
+                    .iter()
+                    .find_map(|instruction| match *instruction {
+                        Instruction::ExecutionMode {
+                            mode: ExecutionMode::OutputVertices { vertex_count },
+                            ..
+                        } => Some(vertex_count),
+                        _ => None,
+                    })
+                    .unwrap_or(0);
+                let output_components = output_component_count(self.spirv, self.entry_point);
+                let total_output_components =
+                    u64::from(output_components) * u64::from(output_vertices);
+
+                if total_output_components
+                    > u64::from(properties.max_geometry_total_output_components)
+                {
+                    return Err(Box::new(ValidationError {
+                        problem: format!(
+                            "the entry point's execution model is `Geometry`, but the number of \
+                            output components written per vertex ({}) multiplied by the \
+                            `vertex_count` of the `ExecutionMode::OutputVertices` ({}) is greater \
+                            than the `max_geometry_total_output_components` device limit ({})",
+                            output_components,
+                            output_vertices,
+                            properties.max_geometry_total_output_components,
+                        )
+                        .into(),
+                        // vuids?
+                        ..Default::default()
+                    }));
+                }
             }
             ExecutionModel::Fragment => {
                 if input_locations_required > properties.max_fragment_input_components / 4 {
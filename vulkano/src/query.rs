@@ -12,6 +12,7 @@ use crate::{
     DeviceSize, Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError, Version,
     VulkanError, VulkanObject,
 };
+use smallvec::SmallVec;
 use std::{
     mem::{size_of_val, MaybeUninit},
     num::NonZeroU64,
@@ -155,6 +156,24 @@ impl QueryPool {
         }) + result_flags.intersects(QueryResultFlags::WITH_AVAILABILITY) as DeviceSize
     }
 
+    /// Returns the range of queries that are used by the query at index `query`, when it is
+    /// begun or written inside a render subpass with the given `view_mask`.
+    ///
+    /// When multiview is used, each query uses one query index for each view in `view_mask`,
+    /// starting at `query`, so the next free index is at the end of the returned range. How the
+    /// results are distributed among these queries depends on the implementation. Some write the
+    /// result of each view to its own query, while others write the whole result to the first
+    /// query and zero to the others. Only the sum of the results of all the queries in the range
+    /// is guaranteed to be the result of the query over all views. For timestamp queries, each
+    /// query in the range holds a timestamp, or zero.
+    ///
+    /// The returned range can be given to [`get_results`](Self::get_results) or
+    /// [`get_results_into`](Self::get_results_into).
+    #[inline]
+    pub fn queries_for_views(query: u32, view_mask: u32) -> Range<u32> {
+        query..query + view_mask.count_ones().max(1)
+    }
+
     /// Copies the results of a range of queries to a buffer on the CPU.
     ///
     /// [`self.ty().result_len()`] will be written for each query in the range, plus 1 extra
@@ -165,6 +184,9 @@ impl QueryPool {
     /// is returned if some results were not yet available; these will not be written to the
     /// buffer.
     ///
+    /// The results of a query that was used inside a render subpass with multiview are spread
+    /// over several queries; see [`queries_for_views`](Self::queries_for_views).
+    ///
     /// See also [`copy_query_pool_results`].
     ///
     /// [`self.ty().result_len()`]: QueryPool::result_len
@@ -194,11 +216,6 @@ impl QueryPool {
     where
         T: QueryResultElement,
     {
-        flags.validate_device(&self.device).map_err(|err| {
-            err.add_context("flags")
-                .set_vuids(&["VUID-vkGetQueryPoolResults-flags-parameter"])
-        })?;
-
         if destination.is_empty() {
             return Err(Box::new(ValidationError {
                 context: "destination".into(),
@@ -208,25 +225,7 @@ impl QueryPool {
             }));
         }
 
-        if range.is_empty() {
-            return Err(Box::new(ValidationError {
-                context: "range".into(),
-                problem: "is empty".into(),
-                // vuids?
-                ..Default::default()
-            }));
-        }
-
-        if range.end > self.query_count {
-            return Err(Box::new(ValidationError {
-                problem: "`range.end` is greater than `self.query_count`".into(),
-                vuids: &[
-                    "VUID-vkGetQueryPoolResults-firstQuery-00813",
-                    "VUID-vkGetQueryPoolResults-firstQuery-00816",
-                ],
-                ..Default::default()
-            }));
-        }
+        self.validate_get_results_range_flags(range.clone(), flags)?;
 
         // VUID-vkGetQueryPoolResults-flags-02828
         // VUID-vkGetQueryPoolResults-flags-00815
@@ -249,6 +248,39 @@ impl QueryPool {
             }));
         }
 
+        Ok(())
+    }
+
+    fn validate_get_results_range_flags(
+        &self,
+        range: Range<u32>,
+        flags: QueryResultFlags,
+    ) -> Result<(), Box<ValidationError>> {
+        flags.validate_device(&self.device).map_err(|err| {
+            err.add_context("flags")
+                .set_vuids(&["VUID-vkGetQueryPoolResults-flags-parameter"])
+        })?;
+
+        if range.is_empty() {
+            return Err(Box::new(ValidationError {
+                context: "range".into(),
+                problem: "is empty".into(),
+                // vuids?
+                ..Default::default()
+            }));
+        }
+
+        if range.end > self.query_count {
+            return Err(Box::new(ValidationError {
+                problem: "`range.end` is greater than `self.query_count`".into(),
+                vuids: &[
+                    "VUID-vkGetQueryPoolResults-firstQuery-00813",
+                    "VUID-vkGetQueryPoolResults-firstQuery-00816",
+                ],
+                ..Default::default()
+            }));
+        }
+
         if self.query_type == QueryType::Timestamp {
             if flags.intersects(QueryResultFlags::PARTIAL) {
                 return Err(Box::new(ValidationError {
@@ -298,6 +330,112 @@ impl QueryPool {
         }
     }
 
+    /// Copies the results of a range of queries into typed [`QueryResult`]s on the CPU.
+    ///
+    /// This is a wrapper around [`get_results`] that takes care of the layout of the results:
+    /// one element of `destination` is written for each query in `range`, holding all of the
+    /// values of that query. If `WITH_AVAILABILITY` is `true`, then
+    /// [`QueryResultFlags::WITH_AVAILABILITY`] is added to `flags`, and the availability of each
+    /// query is written to [`QueryResult::available`]. `flags` must not contain
+    /// `WITH_AVAILABILITY` itself.
+    ///
+    /// The values of a query that are not available are only written if `flags` contains
+    /// [`QueryResultFlags::PARTIAL`]. Otherwise, [`QueryResult::values`] is `None` for every query
+    /// that is known not to be available. Without `WITH_AVAILABILITY`, it can't be known which
+    /// queries are available when not all of them are, so then `values` is `None` for every
+    /// query in `range`.
+    ///
+    /// The return value is the same as for [`get_results`].
+    ///
+    /// [`get_results`]: Self::get_results
+    #[inline]
+    pub fn get_results_into<R, const WITH_AVAILABILITY: bool>(
+        &self,
+        range: Range<u32>,
+        destination: &mut [QueryResult<R>],
+        flags: QueryResultFlags,
+    ) -> Result<bool, Validated<VulkanError>>
+    where
+        R: QueryResultElement + Copy + Default + PartialEq,
+    {
+        self.validate_get_results_into(range.clone(), destination, flags)?;
+
+        unsafe {
+            Ok(self.get_results_into_unchecked::<R, WITH_AVAILABILITY>(
+                range,
+                destination,
+                flags,
+            )?)
+        }
+    }
+
+    fn validate_get_results_into<R>(
+        &self,
+        range: Range<u32>,
+        destination: &[QueryResult<R>],
+        flags: QueryResultFlags,
+    ) -> Result<(), Box<ValidationError>>
+    where
+        R: QueryResultElement,
+    {
+        self.validate_get_results_range_flags(range.clone(), flags)?;
+
+        if flags.intersects(QueryResultFlags::WITH_AVAILABILITY) {
+            return Err(Box::new(ValidationError {
+                context: "flags".into(),
+                problem: "contains `QueryResultFlags::WITH_AVAILABILITY`; the \
+                    `WITH_AVAILABILITY` generic parameter must be used instead"
+                    .into(),
+                ..Default::default()
+            }));
+        }
+
+        if destination.len() < range.len() {
+            return Err(Box::new(ValidationError {
+                context: "destination.len()".into(),
+                problem: "is less than the number of queries in `range`".into(),
+                ..Default::default()
+            }));
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn get_results_into_unchecked<R, const WITH_AVAILABILITY: bool>(
+        &self,
+        range: Range<u32>,
+        destination: &mut [QueryResult<R>],
+        mut flags: QueryResultFlags,
+    ) -> Result<bool, VulkanError>
+    where
+        R: QueryResultElement + Copy + Default + PartialEq,
+    {
+        if WITH_AVAILABILITY {
+            flags |= QueryResultFlags::WITH_AVAILABILITY;
+        }
+
+        let value_count = self.result_len(QueryResultFlags::empty()) as usize;
+        let per_query_len = self.result_len(flags) as usize;
+        let mut data = vec![R::default(); per_query_len * range.len()];
+        let all_available = unsafe { self.get_results_unchecked(range, &mut data, flags) }?;
+
+        for (result, data) in destination.iter_mut().zip(data.chunks_exact(per_query_len)) {
+            let (values, rest) = data.split_at(value_count);
+            let available = WITH_AVAILABILITY.then(|| rest[0] != R::default());
+            let values_written = all_available
+                || available == Some(true)
+                || flags.intersects(QueryResultFlags::PARTIAL);
+
+            *result = QueryResult {
+                values: values_written.then(|| values.iter().copied().collect()),
+                available,
+            };
+        }
+
+        Ok(all_available)
+    }
+
     /// Resets a range of queries.
     ///
     /// The [`host_query_reset`] feature must be enabled on the device.
@@ -737,6 +875,69 @@ unsafe impl QueryResultElement for u64 {
     const FLAG: ash::vk::QueryResultFlags = ash::vk::QueryResultFlags::TYPE_64;
 }
 
+/// The result of a single query, as written by [`QueryPool::get_results_into`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueryResult<R> {
+    /// The values of the query, or `None` if they were not written because the query was not
+    /// available.
+    ///
+    /// For [`QueryType::PipelineStatistics`], there is one value for each statistic that was
+    /// enabled on the query pool, in the order of the bits of [`QueryPipelineStatisticFlags`].
+    /// Use [`statistic`](Self::statistic) to look up a specific one. All other query types have
+    /// a single value.
+    ///
+    /// If [`QueryResultFlags::PARTIAL`] was given and the query was not available, then the
+    /// values are intermediate results.
+    pub values: Option<SmallVec<[R; 1]>>,
+
+    /// Whether the query was available, or `None` if availability was not requested.
+    pub available: Option<bool>,
+}
+
+impl<R> QueryResult<R>
+where
+    R: Copy,
+{
+    /// Returns the first value of the query, if the values were written.
+    ///
+    /// This is the whole result for all query types other than
+    /// [`QueryType::PipelineStatistics`].
+    #[inline]
+    pub fn value(&self) -> Option<R> {
+        self.values
+            .as_ref()
+            .and_then(|values| values.first().copied())
+    }
+
+    /// For a [`QueryType::PipelineStatistics`] query, returns the value of `statistic`, if the
+    /// values were written and `statistic` is contained in `pipeline_statistics`.
+    ///
+    /// `pipeline_statistics` must be the value of [`QueryPool::pipeline_statistics`] for the
+    /// pool that the result was retrieved from, and `statistic` must contain exactly one flag.
+    #[inline]
+    pub fn statistic(
+        &self,
+        pipeline_statistics: QueryPipelineStatisticFlags,
+        statistic: QueryPipelineStatisticFlags,
+    ) -> Option<R> {
+        debug_assert_eq!(statistic.count(), 1);
+
+        if !pipeline_statistics.contains(statistic) {
+            return None;
+        }
+
+        // Values are stored in bit order, so the index is the number of enabled statistics with
+        // a lower bit.
+        let statistic = ash::vk::QueryPipelineStatisticFlags::from(statistic).as_raw();
+        let lower_bits = ash::vk::QueryPipelineStatisticFlags::from(pipeline_statistics).as_raw()
+            & (statistic - 1);
+
+        self.values
+            .as_ref()
+            .and_then(|values| values.get(lower_bits.count_ones() as usize).copied())
+    }
+}
+
 vulkan_bitflags! {
     #[non_exhaustive]
 
@@ -771,9 +972,10 @@ vulkan_bitflags! {
 mod tests {
     use super::QueryPoolCreateInfo;
     use crate::{
-        query::{QueryPool, QueryType},
+        query::{QueryPipelineStatisticFlags, QueryPool, QueryResult, QueryResultFlags, QueryType},
         Validated,
     };
+    use smallvec::smallvec;
 
     #[test]
     fn pipeline_statistics_feature() {
@@ -789,4 +991,72 @@ mod tests {
             Err(Validated::ValidationError(_)),
         ));
     }
+
+    #[test]
+    fn get_results_into_validation() {
+        let (device, _) = gfx_dev_and_queue!();
+        let query_pool = QueryPool::new(
+            device,
+            QueryPoolCreateInfo {
+                query_count: 4,
+                ..QueryPoolCreateInfo::query_type(QueryType::Occlusion)
+            },
+        )
+        .unwrap();
+
+        let mut results = vec![QueryResult::<u64>::default(); 4];
+        assert!(matches!(
+            query_pool.get_results_into::<_, false>(
+                0..4,
+                &mut results,
+                QueryResultFlags::WITH_AVAILABILITY,
+            ),
+            Err(Validated::ValidationError(_)),
+        ));
+        assert!(matches!(
+            query_pool.get_results_into::<_, true>(0..4, &mut results[..3], Default::default()),
+            Err(Validated::ValidationError(_)),
+        ));
+    }
+
+    #[test]
+    fn queries_for_views() {
+        assert_eq!(QueryPool::queries_for_views(3, 0), 3..4);
+        assert_eq!(QueryPool::queries_for_views(3, 0b1), 3..4);
+        assert_eq!(QueryPool::queries_for_views(3, 0b1010), 3..5);
+    }
+
+    #[test]
+    fn query_result_statistic() {
+        let pipeline_statistics = QueryPipelineStatisticFlags::INPUT_ASSEMBLY_VERTICES
+            | QueryPipelineStatisticFlags::CLIPPING_INVOCATIONS
+            | QueryPipelineStatisticFlags::COMPUTE_SHADER_INVOCATIONS;
+        let result = QueryResult::<u32> {
+            values: Some(smallvec![10, 20, 30]),
+            available: Some(true),
+        };
+
+        assert_eq!(result.value(), Some(10));
+        assert_eq!(
+            result.statistic(
+                pipeline_statistics,
+                QueryPipelineStatisticFlags::CLIPPING_INVOCATIONS,
+            ),
+            Some(20),
+        );
+        assert_eq!(
+            result.statistic(
+                pipeline_statistics,
+                QueryPipelineStatisticFlags::COMPUTE_SHADER_INVOCATIONS,
+            ),
+            Some(30),
+        );
+        assert_eq!(
+            result.statistic(
+                pipeline_statistics,
+                QueryPipelineStatisticFlags::VERTEX_SHADER_INVOCATIONS,
+            ),
+            None,
+        );
+    }
 }
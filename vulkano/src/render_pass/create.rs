@@ -94,6 +94,8 @@ impl RenderPass {
             per_depth_stencil_resolve_attachment_vk: PerAttachmentReferenceVk,
             depth_stencil_resolve_vk:
                 Option<ash::vk::SubpassDescriptionDepthStencilResolve<'static>>,
+            multisampled_render_to_single_sampled_vk:
+                Option<ash::vk::MultisampledRenderToSingleSampledInfoEXT<'static>>,
         }
 
         #[derive(Default)]
@@ -115,6 +117,7 @@ impl RenderPass {
                         ref depth_stencil_resolve_attachment,
                         depth_resolve_mode,
                         stencil_resolve_mode,
+                        multisampled_render_to_single_sampled,
                         ref preserve_attachments,
                         _ne: _,
                     } = subpass;
@@ -285,15 +288,27 @@ impl RenderPass {
                         )
                     };
 
-                    let depth_stencil_resolve_vk = depth_stencil_resolve_attachment
-                        .is_some()
-                        .then_some(ash::vk::SubpassDescriptionDepthStencilResolve {
-                            depth_resolve_mode: depth_resolve_mode
-                                .map_or(ash::vk::ResolveModeFlags::NONE, Into::into),
-                            stencil_resolve_mode: stencil_resolve_mode
-                                .map_or(ash::vk::ResolveModeFlags::NONE, Into::into),
-                            p_depth_stencil_resolve_attachment: ptr::null(),
-                            ..Default::default()
+                    // With multisampled rendering to single-sampled attachments, the resolve modes
+                    // are also used without a resolve attachment.
+                    let depth_stencil_resolve_vk = (depth_stencil_resolve_attachment.is_some()
+                        || (multisampled_render_to_single_sampled.is_some()
+                            && (depth_resolve_mode.is_some() || stencil_resolve_mode.is_some())))
+                    .then_some(ash::vk::SubpassDescriptionDepthStencilResolve {
+                        depth_resolve_mode: depth_resolve_mode
+                            .map_or(ash::vk::ResolveModeFlags::NONE, Into::into),
+                        stencil_resolve_mode: stencil_resolve_mode
+                            .map_or(ash::vk::ResolveModeFlags::NONE, Into::into),
+                        p_depth_stencil_resolve_attachment: ptr::null(),
+                        ..Default::default()
+                    });
+
+                    let multisampled_render_to_single_sampled_vk =
+                        multisampled_render_to_single_sampled.map(|rasterization_samples| {
+                            ash::vk::MultisampledRenderToSingleSampledInfoEXT {
+                                multisampled_render_to_single_sampled_enable: ash::vk::TRUE,
+                                rasterization_samples: rasterization_samples.into(),
+                                ..Default::default()
+                            }
                         });
 
                     (
@@ -325,6 +340,7 @@ impl RenderPass {
                             depth_stencil_resolve_attachment_vk,
                             per_depth_stencil_resolve_attachment_vk,
                             depth_stencil_resolve_vk,
+                            multisampled_render_to_single_sampled_vk,
                         },
                     )
                 })
@@ -341,6 +357,7 @@ impl RenderPass {
                 depth_stencil_resolve_attachment_vk,
                 per_depth_stencil_resolve_attachment_vk,
                 depth_stencil_resolve_vk,
+                multisampled_render_to_single_sampled_vk,
             } = per_subpass_vk;
 
             for (input_attachment_vk, per_input_attachment_vk) in input_attachments_vk
@@ -408,6 +425,13 @@ impl RenderPass {
                 depth_stencil_resolve_vk.p_next = subpass_vk.p_next;
                 subpass_vk.p_next = <*const _>::cast(depth_stencil_resolve_vk);
             }
+
+            if let Some(multisampled_render_to_single_sampled_vk) =
+                multisampled_render_to_single_sampled_vk
+            {
+                multisampled_render_to_single_sampled_vk.p_next = subpass_vk.p_next;
+                subpass_vk.p_next = <*const _>::cast(multisampled_render_to_single_sampled_vk);
+            }
         }
 
         struct PerSubpassDependencyVk {
@@ -586,6 +610,7 @@ impl RenderPass {
                     depth_stencil_resolve_attachment: _,
                     depth_resolve_mode: _,
                     stencil_resolve_mode: _,
+                    multisampled_render_to_single_sampled: _,
                     ref preserve_attachments,
                     _ne: _,
                 } = subpass;
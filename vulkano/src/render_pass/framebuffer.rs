@@ -3,7 +3,7 @@ use crate::{
     device::{Device, DeviceOwned, DeviceOwnedDebugWrapper},
     image::{
         view::{ImageView, ImageViewType},
-        ImageAspects, ImageType, ImageUsage,
+        ImageAspects, ImageCreateFlags, ImageType, ImageUsage, SampleCount,
     },
     macros::{impl_id_counter, vulkan_bitflags},
     Validated, ValidationError, VulkanError, VulkanObject,
@@ -171,7 +171,37 @@ impl Framebuffer {
                 }));
             }
 
-            let image_view_extent = image_view.image().extent();
+            if attachment_desc.samples == SampleCount::Sample1
+                && !image_view
+                    .image()
+                    .flags()
+                    .intersects(ImageCreateFlags::MULTISAMPLED_RENDER_TO_SINGLE_SAMPLED)
+                && render_pass.subpasses().iter().any(|subpass_desc| {
+                    subpass_desc.multisampled_render_to_single_sampled.is_some()
+                        && subpass_desc
+                            .color_attachments
+                            .iter()
+                            .flatten()
+                            .chain(subpass_desc.depth_stencil_attachment.iter())
+                            .chain(subpass_desc.input_attachments.iter().flatten())
+                            .any(|attachment_ref| attachment_ref.attachment as usize == index)
+                })
+            {
+                return Err(Box::new(ValidationError {
+                    problem: format!(
+                        "`render_pass` uses `create_info.attachments[{}]` as a single-sampled \
+                        attachment in a subpass where `multisampled_render_to_single_sampled` \
+                        is `Some`, but its image was not created with the \
+                        `ImageCreateFlags::MULTISAMPLED_RENDER_TO_SINGLE_SAMPLED` flag",
+                        index,
+                    )
+                    .into(),
+                    vuids: &["VUID-VkFramebufferCreateInfo-samples-07009"],
+                    ..Default::default()
+                }));
+            }
+
+            let image_view_extent = image_view.extent();
             let image_view_array_layers = image_view.subresource_range().array_layers.end
                 - image_view.subresource_range().array_layers.start;
 
@@ -184,7 +214,8 @@ impl Framebuffer {
                         problem: format!(
                             "`render_pass` uses `create_info.attachments[{}]` as an input, color, \
                             depth or stencil attachment, but \
-                            its width and height are less than `create_info.extent`",
+                            its width and height at its base mip level are less than \
+                            `create_info.extent`",
                             index,
                         )
                         .into(),
@@ -418,10 +449,11 @@ pub struct FramebufferCreateInfo {
 
     /// The extent (width and height) of the framebuffer.
     ///
-    /// This must be no larger than the smallest width and height of the images in `attachments`.
+    /// This must be no larger than the smallest width and height of the image views in
+    /// `attachments`, at their base mip level.
     /// If one of the elements is set to 0, the extent will be calculated automatically from the
-    /// extents of the attachment images to be the largest allowed. At least one attachment image
-    /// must be specified in that case.
+    /// extents of the attachment image views to be the largest allowed. At least one attachment
+    /// image must be specified in that case.
     ///
     /// The extent, whether automatically calculated or specified explicitly, must not be larger
     /// than the [`max_framebuffer_width`](crate::device::DeviceProperties::max_framebuffer_width)
@@ -487,7 +519,7 @@ impl FramebufferCreateInfo {
             };
 
             for image_view in attachments.iter() {
-                let image_view_extent = image_view.image().extent();
+                let image_view_extent = image_view.extent();
                 let image_view_array_layers =
                     image_view.subresource_range().array_layers.len() as u32;
 
@@ -18,6 +18,7 @@
 
 pub use self::framebuffer::{Framebuffer, FramebufferCreateFlags, FramebufferCreateInfo};
 use crate::{
+    describe::Describer,
     device::{Device, DeviceOwned, QueueFlags},
     format::{ClearValueType, Format, FormatFeatures, NumericType},
     image::{ImageAspects, ImageLayout, SampleCount},
@@ -222,6 +223,7 @@ impl RenderPass {
                 ref depth_stencil_resolve_attachment,
                 depth_resolve_mode: _,
                 stencil_resolve_mode: _,
+                multisampled_render_to_single_sampled: _,
                 preserve_attachments: _,
                 _ne: _,
             } = subpass_desc;
@@ -310,6 +312,27 @@ impl RenderPass {
         &self.correlated_view_masks
     }
 
+    /// Returns a human-readable description of the parameters that the render pass was created
+    /// with.
+    ///
+    /// Unlike the `Debug` output, the description contains no handles, and its format is stable,
+    /// so that the descriptions of two render passes can be compared with a text diff.
+    pub fn describe(&self) -> String {
+        let mut describer = Describer::new("RenderPass");
+        self.describe_fields(&mut describer);
+
+        describer.finish()
+    }
+
+    pub(crate) fn describe_fields(&self, describer: &mut Describer) {
+        describer
+            .field("flags", &self.flags)
+            .debug_list("attachments", &self.attachments)
+            .debug_list("subpasses", &self.subpasses)
+            .debug_list("dependencies", &self.dependencies)
+            .field("correlated_view_masks", &self.correlated_view_masks);
+    }
+
     /// If the render pass has multiview enabled, returns the number of views used by the render
     /// pass. Returns 0 if multiview is not enabled.
     #[inline]
@@ -494,6 +517,7 @@ impl RenderPass {
                     depth_stencil_resolve_attachment: depth_stencil_resolve_attachment1,
                     depth_resolve_mode: depth_resolve_mode1,
                     stencil_resolve_mode: stencil_resolve_mode1,
+                    multisampled_render_to_single_sampled: multisampled_render_to_single_sampled1,
                     preserve_attachments: _,
                     _ne: _,
                 } = subpass1;
@@ -507,6 +531,7 @@ impl RenderPass {
                     depth_stencil_resolve_attachment: depth_stencil_resolve_attachment2,
                     depth_resolve_mode: depth_resolve_mode2,
                     stencil_resolve_mode: stencil_resolve_mode2,
+                    multisampled_render_to_single_sampled: multisampled_render_to_single_sampled2,
                     preserve_attachments: _,
                     _ne: _,
                 } = subpass2;
@@ -572,6 +597,11 @@ impl RenderPass {
                     return false;
                 }
 
+                if multisampled_render_to_single_sampled1 != multisampled_render_to_single_sampled2
+                {
+                    return false;
+                }
+
                 if view_mask1 != view_mask2 {
                     return false;
                 }
@@ -693,10 +723,19 @@ impl Subpass {
 
     /// Returns the number of samples in the color and/or depth/stencil attachments. Returns `None`
     /// if there is no such attachment in this subpass.
+    ///
+    /// If [`multisampled_render_to_single_sampled`] is enabled for this subpass, then the number
+    /// of rasterization samples it specifies is returned instead.
+    ///
+    /// [`multisampled_render_to_single_sampled`]: SubpassDescription::multisampled_render_to_single_sampled
     #[inline]
     pub fn num_samples(&self) -> Option<SampleCount> {
         let subpass_desc = self.subpass_desc();
 
+        if let Some(rasterization_samples) = subpass_desc.multisampled_render_to_single_sampled {
+            return Some(rasterization_samples);
+        }
+
         // TODO: chain input attachments as well?
         subpass_desc
             .color_attachments
@@ -850,8 +889,9 @@ impl RenderPassCreateInfo {
                 ref color_resolve_attachments,
                 ref depth_stencil_attachment,
                 ref depth_stencil_resolve_attachment,
-                depth_resolve_mode: _,
-                stencil_resolve_mode: _,
+                depth_resolve_mode,
+                stencil_resolve_mode,
+                multisampled_render_to_single_sampled,
                 ref preserve_attachments,
                 _ne: _,
             } = subpass_desc;
@@ -939,23 +979,43 @@ impl RenderPassCreateInfo {
                     }));
                 }
 
-                match color_samples {
-                    Some(samples) => {
-                        if samples != attachment_desc.samples {
-                            return Err(Box::new(ValidationError {
-                                problem: format!(
-                                    "`subpasses[{0}].color_attachments[{1}]` uses \
-                                    an attachment with a different number of samples than other \
-                                    color and depth/stencil attachments in the subpass",
-                                    subpass_index, ref_index
-                                )
-                                .into(),
-                                vuids: &["VUID-VkSubpassDescription2-pColorAttachments-03069"],
-                                ..Default::default()
-                            }));
+                if let Some(rasterization_samples) = multisampled_render_to_single_sampled {
+                    if attachment_desc.samples != SampleCount::Sample1
+                        && attachment_desc.samples != rasterization_samples
+                    {
+                        return Err(Box::new(ValidationError {
+                            problem: format!(
+                                "`subpasses[{0}].multisampled_render_to_single_sampled` is \
+                                `Some`, but `subpasses[{0}].color_attachments[{1}]` uses an \
+                                attachment whose number of samples is neither \
+                                `SampleCount::Sample1` nor equal to \
+                                `subpasses[{0}].multisampled_render_to_single_sampled`",
+                                subpass_index, ref_index
+                            )
+                            .into(),
+                            vuids: &["VUID-VkSubpassDescription2-pNext-06870"],
+                            ..Default::default()
+                        }));
+                    }
+                } else {
+                    match color_samples {
+                        Some(samples) => {
+                            if samples != attachment_desc.samples {
+                                return Err(Box::new(ValidationError {
+                                    problem: format!(
+                                        "`subpasses[{0}].color_attachments[{1}]` uses \
+                                        an attachment with a different number of samples than \
+                                        other color and depth/stencil attachments in the subpass",
+                                        subpass_index, ref_index
+                                    )
+                                    .into(),
+                                    vuids: &["VUID-VkSubpassDescription2-pColorAttachments-03069"],
+                                    ..Default::default()
+                                }));
+                            }
                         }
+                        None => color_samples = Some(attachment_desc.samples),
                     }
-                    None => color_samples = Some(attachment_desc.samples),
                 }
 
                 if let Some(color_resolve_attachment) = color_resolve_attachments
@@ -1117,7 +1177,59 @@ impl RenderPassCreateInfo {
                     }));
                 }
 
-                if let Some(samples) = color_samples {
+                if let Some(rasterization_samples) = multisampled_render_to_single_sampled {
+                    if attachment_desc.samples == SampleCount::Sample1 {
+                        // The attachment is implicitly resolved using the resolve modes.
+                        if depth_stencil_resolve_attachment.is_some() {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "`subpasses[{0}].multisampled_render_to_single_sampled` is \
+                                    `Some`, and `subpasses[{0}].depth_stencil_attachment` uses \
+                                    an attachment whose number of samples is \
+                                    `SampleCount::Sample1`, but \
+                                    `subpasses[{0}].depth_stencil_resolve_attachment` is `Some`",
+                                    subpass_index,
+                                )
+                                .into(),
+                                vuids: &["VUID-VkSubpassDescription2-pNext-06871"],
+                                ..Default::default()
+                            }));
+                        }
+
+                        if depth_resolve_mode.is_none() && stencil_resolve_mode.is_none() {
+                            return Err(Box::new(ValidationError {
+                                problem: format!(
+                                    "`subpasses[{0}].multisampled_render_to_single_sampled` is \
+                                    `Some`, and `subpasses[{0}].depth_stencil_attachment` uses \
+                                    an attachment whose number of samples is \
+                                    `SampleCount::Sample1`, but \
+                                    `subpasses[{0}].depth_resolve_mode` and \
+                                    `subpasses[{0}].stencil_resolve_mode` are both `None`",
+                                    subpass_index,
+                                )
+                                .into(),
+                                vuids: &[
+                                    "VUID-VkSubpassDescriptionDepthStencilResolve-pNext-06873",
+                                ],
+                                ..Default::default()
+                            }));
+                        }
+                    } else if attachment_desc.samples != rasterization_samples {
+                        return Err(Box::new(ValidationError {
+                            problem: format!(
+                                "`subpasses[{0}].multisampled_render_to_single_sampled` is \
+                                `Some`, but `subpasses[{0}].depth_stencil_attachment` uses an \
+                                attachment whose number of samples is neither \
+                                `SampleCount::Sample1` nor equal to \
+                                `subpasses[{0}].multisampled_render_to_single_sampled`",
+                                subpass_index,
+                            )
+                            .into(),
+                            vuids: &["VUID-VkSubpassDescription2-pNext-06870"],
+                            ..Default::default()
+                        }));
+                    }
+                } else if let Some(samples) = color_samples {
                     if samples != attachment_desc.samples {
                         return Err(Box::new(ValidationError {
                             problem: format!(
@@ -1356,6 +1468,26 @@ impl RenderPassCreateInfo {
                     })
                 })?;
 
+                if let Some(rasterization_samples) = multisampled_render_to_single_sampled {
+                    if attachment_desc.samples != SampleCount::Sample1
+                        && attachment_desc.samples != rasterization_samples
+                    {
+                        return Err(Box::new(ValidationError {
+                            problem: format!(
+                                "`subpasses[{0}].multisampled_render_to_single_sampled` is \
+                                `Some`, but `subpasses[{0}].input_attachments[{1}]` uses an \
+                                attachment whose number of samples is neither \
+                                `SampleCount::Sample1` nor equal to \
+                                `subpasses[{0}].multisampled_render_to_single_sampled`",
+                                subpass_index, ref_index
+                            )
+                            .into(),
+                            vuids: &["VUID-VkSubpassDescription2-pNext-06870"],
+                            ..Default::default()
+                        }));
+                    }
+                }
+
                 let format_aspects = attachment_desc.format.aspects();
                 let is_first_use = !replace(&mut attachment_is_used[attachment as usize], true);
 
@@ -2251,6 +2383,27 @@ pub struct SubpassDescription {
     /// The default value is `None`.
     pub stencil_resolve_mode: Option<ResolveMode>,
 
+    /// If `Some`, enables multisampled rendering to single-sampled attachments, with the given
+    /// number of rasterization samples.
+    ///
+    /// Color, depth/stencil and input attachments of the subpass may then have either the given
+    /// number of samples, or [`SampleCount::Sample1`]. Single-sampled attachments are rendered to
+    /// as if they had the given number of samples, and are implicitly resolved at the end of the
+    /// subpass. A single-sampled depth/stencil attachment is resolved with `depth_resolve_mode`
+    /// and `stencil_resolve_mode`, at least one of which must then be `Some`, while
+    /// `depth_stencil_resolve_attachment` must be `None`.
+    ///
+    /// If this is `Some`, then the
+    /// [`multisampled_render_to_single_sampled`](crate::device::DeviceFeatures::multisampled_render_to_single_sampled)
+    /// feature must be enabled on the device, and the value must not be
+    /// [`SampleCount::Sample1`]. Images that are used as single-sampled attachments must have
+    /// been created with [`ImageCreateFlags::MULTISAMPLED_RENDER_TO_SINGLE_SAMPLED`].
+    ///
+    /// The default value is `None`.
+    ///
+    /// [`ImageCreateFlags::MULTISAMPLED_RENDER_TO_SINGLE_SAMPLED`]: crate::image::ImageCreateFlags::MULTISAMPLED_RENDER_TO_SINGLE_SAMPLED
+    pub multisampled_render_to_single_sampled: Option<SampleCount>,
+
     /// The indices of attachments of the render pass that will be preserved during this subpass.
     ///
     /// The referenced attachments must not be used as any other attachment type in the subpass.
@@ -2273,6 +2426,7 @@ impl Default for SubpassDescription {
             depth_stencil_resolve_attachment: None,
             depth_resolve_mode: None,
             stencil_resolve_mode: None,
+            multisampled_render_to_single_sampled: None,
             input_attachments: Vec::new(),
             preserve_attachments: Vec::new(),
             _ne: crate::NonExhaustive(()),
@@ -2294,6 +2448,7 @@ impl SubpassDescription {
             ref depth_stencil_resolve_attachment,
             depth_resolve_mode,
             stencil_resolve_mode,
+            multisampled_render_to_single_sampled,
             ref preserve_attachments,
             _ne: _,
         } = self;
@@ -2313,6 +2468,77 @@ impl SubpassDescription {
             }));
         }
 
+        if let Some(rasterization_samples) = multisampled_render_to_single_sampled {
+            if !device
+                .enabled_features()
+                .multisampled_render_to_single_sampled
+            {
+                return Err(Box::new(ValidationError {
+                    context: "multisampled_render_to_single_sampled".into(),
+                    problem: "is `Some`".into(),
+                    requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceFeature(
+                        "multisampled_render_to_single_sampled",
+                    )])]),
+                    // vuids?
+                    ..Default::default()
+                }));
+            }
+
+            rasterization_samples.validate_device(device).map_err(|err| {
+                err.add_context("multisampled_render_to_single_sampled").set_vuids(&[
+                    "VUID-VkMultisampledRenderToSingleSampledInfoEXT-rasterizationSamples-parameter",
+                ])
+            })?;
+
+            if rasterization_samples == SampleCount::Sample1 {
+                return Err(Box::new(ValidationError {
+                    context: "multisampled_render_to_single_sampled".into(),
+                    problem: "is `Some(SampleCount::Sample1)`".into(),
+                    vuids: &[
+                        "VUID-VkMultisampledRenderToSingleSampledInfoEXT-rasterizationSamples-06878",
+                    ],
+                    ..Default::default()
+                }));
+            }
+
+            if depth_stencil_attachment.is_some() && depth_stencil_resolve_attachment.is_none() {
+                // The resolve modes are used for the implicit resolve of a single-sampled
+                // depth/stencil attachment.
+                match (depth_resolve_mode, stencil_resolve_mode) {
+                    (None, None) => (),
+                    (None, Some(_)) | (Some(_), None) => {
+                        if !properties.independent_resolve_none.unwrap_or(false) {
+                            return Err(Box::new(ValidationError {
+                                problem: "`multisampled_render_to_single_sampled` is `Some`, and \
+                                    the `independent_resolve_none` device property is \
+                                    `false`, but one of `depth_resolve_mode` and \
+                                    `stencil_resolve_mode` is `Some` while the other is `None`"
+                                    .into(),
+                                // vuids?
+                                ..Default::default()
+                            }));
+                        }
+                    }
+                    (Some(depth_resolve_mode), Some(stencil_resolve_mode)) => {
+                        if depth_resolve_mode != stencil_resolve_mode
+                            && !properties.independent_resolve.unwrap_or(false)
+                        {
+                            return Err(Box::new(ValidationError {
+                                problem: "`multisampled_render_to_single_sampled` is `Some`, and \
+                                    `depth_resolve_mode` and `stencil_resolve_mode` are both \
+                                    `Some`, and the `independent_resolve` device property is \
+                                    `false`, but `depth_resolve_mode` does not equal \
+                                    `stencil_resolve_mode`"
+                                    .into(),
+                                // vuids?
+                                ..Default::default()
+                            }));
+                        }
+                    }
+                }
+            }
+        }
+
         // Track the layout of each attachment used in this subpass
         #[derive(PartialEq, Eq)]
         struct Layouts {
@@ -2768,10 +2994,13 @@ impl SubpassDescription {
         }
 
         if let Some(depth_resolve_mode) = depth_resolve_mode {
-            if depth_stencil_resolve_attachment.is_none() {
+            if depth_stencil_resolve_attachment.is_none()
+                && multisampled_render_to_single_sampled.is_none()
+            {
                 return Err(Box::new(ValidationError {
                     problem: "`depth_resolve_mode` is `Some`, but \
-                        `depth_stencil_resolve_attachment` is `None`"
+                        `depth_stencil_resolve_attachment` and \
+                        `multisampled_render_to_single_sampled` are both `None`"
                         .into(),
                     ..Default::default()
                 }));
@@ -2797,10 +3026,13 @@ impl SubpassDescription {
         }
 
         if let Some(stencil_resolve_mode) = stencil_resolve_mode {
-            if depth_stencil_resolve_attachment.is_none() {
+            if depth_stencil_resolve_attachment.is_none()
+                && multisampled_render_to_single_sampled.is_none()
+            {
                 return Err(Box::new(ValidationError {
                     problem: "`stencil_resolve_mode` is `Some`, but \
-                        `depth_stencil_resolve_attachment` is `None`"
+                        `depth_stencil_resolve_attachment` and \
+                        `multisampled_render_to_single_sampled` are both `None`"
                         .into(),
                     ..Default::default()
                 }));
@@ -3612,8 +3844,17 @@ pub(crate) struct AttachmentUse {
 
 #[cfg(test)]
 mod tests {
-    use super::{RenderPassCreateInfo, SubpassDescription};
-    use crate::{format::Format, render_pass::RenderPass};
+    use super::{
+        AttachmentDescription, AttachmentLoadOp, AttachmentReference, AttachmentStoreOp,
+        RenderPassCreateInfo, Subpass, SubpassDescription,
+    };
+    use crate::{
+        device::{DeviceExtensions, DeviceFeatures},
+        format::Format,
+        image::{ImageLayout, SampleCount},
+        pipeline::graphics::subpass::PipelineRenderingCreateInfo,
+        render_pass::RenderPass,
+    };
 
     #[test]
     fn empty() {
@@ -3678,4 +3919,72 @@ mod tests {
         assert_ne!(granularity[0], 0);
         assert_ne!(granularity[1], 0);
     }
+
+    #[test]
+    fn multisampled_render_to_single_sampled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let attachment = |samples| AttachmentDescription {
+            format: Format::R8G8B8A8_UNORM,
+            samples,
+            load_op: AttachmentLoadOp::Clear,
+            store_op: AttachmentStoreOp::Store,
+            initial_layout: ImageLayout::Undefined,
+            final_layout: ImageLayout::ColorAttachmentOptimal,
+            ..Default::default()
+        };
+        let color_attachment = |attachment| {
+            Some(AttachmentReference {
+                attachment,
+                layout: ImageLayout::ColorAttachmentOptimal,
+                ..Default::default()
+            })
+        };
+        let create_info = |multisampled_render_to_single_sampled| RenderPassCreateInfo {
+            attachments: vec![
+                attachment(SampleCount::Sample4),
+                attachment(SampleCount::Sample1),
+            ],
+            subpasses: vec![SubpassDescription {
+                color_attachments: vec![color_attachment(0), color_attachment(1)],
+                multisampled_render_to_single_sampled,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        // Mixed sample counts are not allowed normally.
+        assert!(RenderPass::new(device.clone(), create_info(None)).is_err());
+
+        // They are allowed with multisampled rendering to single-sampled attachments, which
+        // requires a feature.
+        if !device
+            .enabled_features()
+            .multisampled_render_to_single_sampled
+        {
+            assert!(RenderPass::new(device, create_info(Some(SampleCount::Sample4))).is_err());
+        }
+
+        let Some((device, _)) = crate::testing::test_device(
+            &DeviceExtensions {
+                ext_multisampled_render_to_single_sampled: true,
+                ..DeviceExtensions::empty()
+            },
+            &DeviceFeatures {
+                multisampled_render_to_single_sampled: true,
+                ..DeviceFeatures::empty()
+            },
+        ) else {
+            return;
+        };
+
+        let render_pass = RenderPass::new(device, create_info(Some(SampleCount::Sample4))).unwrap();
+        let subpass = Subpass::from(render_pass, 0).unwrap();
+        assert_eq!(subpass.num_samples(), Some(SampleCount::Sample4));
+        assert_eq!(
+            PipelineRenderingCreateInfo::from_subpass(&subpass)
+                .multisampled_render_to_single_sampled,
+            Some(SampleCount::Sample4),
+        );
+    }
 }
@@ -407,25 +407,30 @@ use crate::{
     acceleration_structure::BuildAccelerationStructureFlags,
     descriptor_set::layout::DescriptorBindingFlags,
     device::{physical::PhysicalDevice, DeviceFeatures, DeviceProperties},
-    format::FormatFeatures,
     image::{
-        sampler::{Filter, Sampler, SamplerCreateInfo, SamplerMipmapMode, SamplerReductionMode},
-        view::ImageView,
+        sampler::{Filter, SamplerCreateInfo, SamplerMipmapMode, SamplerReductionMode},
         Image, ImageFormatProperties,
     },
 };
 use crate::{
     descriptor_set::layout::DescriptorType,
     device::{Device, DeviceOwned},
-    format::{Format, NumericType},
-    image::view::ImageViewType,
+    format::{Format, FormatFeatures, NumericType},
+    image::{
+        sampler::Sampler,
+        view::{ImageView, ImageViewType},
+        ImageAspects, ImageUsage, SampleCount,
+    },
     instance::InstanceOwnedDebugWrapper,
     macros::{impl_id_counter, vulkan_bitflags_enum},
-    pipeline::layout::PushConstantRange,
+    pipeline::{
+        graphics::{color_blend::ColorComponents, input_assembly::PrimitiveTopology},
+        layout::PushConstantRange,
+    },
     shader::spirv::{Capability, Spirv},
     sync::PipelineStages,
-    Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError, Version, VulkanError,
-    VulkanObject,
+    DeviceSize, Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError, Version,
+    VulkanError, VulkanObject,
 };
 use ahash::{HashMap, HashSet};
 use bytemuck::bytes_of;
@@ -642,6 +647,28 @@ impl ShaderModule {
         SpecializedShaderModule::new_unchecked(self.clone(), specialization_info)
     }
 
+    /// Returns the name and execution model of each entry point in the module.
+    ///
+    /// A module can contain multiple entry points, and the same name can be used by multiple
+    /// entry points with different execution models. Use [`entry_point_with_execution`] to
+    /// select one of these unambiguously.
+    ///
+    /// [`entry_point_with_execution`]: Self::entry_point_with_execution
+    #[inline]
+    pub fn entry_points(&self) -> impl Iterator<Item = (&str, ExecutionModel)> + '_ {
+        self.spirv
+            .entry_points()
+            .iter()
+            .filter_map(|instruction| match *instruction {
+                Instruction::EntryPoint {
+                    execution_model,
+                    ref name,
+                    ..
+                } => Some((name.as_str(), execution_model)),
+                _ => None,
+            })
+    }
+
     /// Equivalent to calling [`specialize`] with empty specialization info,
     /// and then calling [`SpecializedShaderModule::entry_point`].
     ///
@@ -1066,6 +1093,14 @@ impl SpecializedShaderModule {
         self.spirv.as_ref().unwrap_or(&self.base_module.spirv)
     }
 
+    /// Returns the name and execution model of each entry point in the module.
+    #[inline]
+    pub fn entry_points(&self) -> impl ExactSizeIterator<Item = (&str, ExecutionModel)> + '_ {
+        self.entry_point_infos
+            .iter()
+            .map(|(_, info)| (info.name.as_str(), info.execution_model))
+    }
+
     /// Returns information about the entry point with the provided name. Returns `None` if no
     /// entry point with that name exists in the shader module or if multiple entry points with
     /// the same name exist.
@@ -1149,6 +1184,119 @@ pub struct EntryPointInfo {
     pub execution_model: ExecutionModel,
     pub descriptor_binding_requirements: HashMap<(u32, u32), DescriptorBindingRequirements>,
     pub push_constant_requirements: Option<PushConstantRange>,
+
+    /// For fragment shader entry points, the user-defined output variables that the entry point
+    /// writes to, sorted by location and index. This is empty for other shader stages.
+    pub fragment_outputs: Vec<FragmentOutput>,
+
+    /// For geometry shader entry points, the primitive types and counts declared by the
+    /// execution modes of the entry point. This is `None` for other shader stages.
+    pub geometry: Option<GeometryShaderExecution>,
+}
+
+/// A user-defined output of a fragment shader entry point.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FragmentOutput {
+    /// The `Location` decoration of the output, which corresponds to the index of the color
+    /// attachment in the subpass.
+    pub location: u32,
+
+    /// The `Index` decoration of the output. This is `1` for the second source of
+    /// [dual-source blending], and `0` otherwise.
+    ///
+    /// [dual-source blending]: crate::pipeline::graphics::color_blend::BlendFactor::Src1Color
+    pub index: u32,
+
+    /// The components of the location that are written.
+    pub components: ColorComponents,
+
+    /// The numeric type of the output.
+    pub numeric_type: NumericType,
+}
+
+/// The execution modes of a geometry shader entry point.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GeometryShaderExecution {
+    /// The type of primitive that the geometry shader takes as input.
+    pub input: GeometryShaderInput,
+
+    /// The type of primitive that the geometry shader outputs.
+    pub output: GeometryShaderOutput,
+
+    /// The number of times that the geometry shader is invoked for each input primitive, as
+    /// given by the `Invocations` execution mode. This is 1 if the execution mode is not present.
+    pub invocations: u32,
+
+    /// The maximum number of vertices that a single invocation outputs, as given by the
+    /// `OutputVertices` execution mode.
+    pub max_output_vertices: u32,
+
+    /// The number of components of the user-defined output variables of each vertex. 64-bit
+    /// components count as two components. Built-in output variables are not counted.
+    pub output_components: u32,
+}
+
+/// The input primitive type that is expected by a geometry shader.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GeometryShaderInput {
+    /// The `InputPoints` execution mode.
+    Points,
+
+    /// The `InputLines` execution mode.
+    Lines,
+
+    /// The `InputLinesAdjacency` execution mode.
+    LinesWithAdjacency,
+
+    /// The `Triangles` execution mode.
+    Triangles,
+
+    /// The `InputTrianglesAdjacency` execution mode.
+    TrianglesWithAdjacency,
+}
+
+impl GeometryShaderInput {
+    /// Returns whether the given primitive topology can be used as input for this geometry
+    /// shader.
+    #[inline]
+    pub fn is_compatible_with(self, topology: PrimitiveTopology) -> bool {
+        match self {
+            Self::Points => matches!(topology, PrimitiveTopology::PointList),
+            Self::Lines => matches!(
+                topology,
+                PrimitiveTopology::LineList | PrimitiveTopology::LineStrip
+            ),
+            Self::LinesWithAdjacency => matches!(
+                topology,
+                PrimitiveTopology::LineListWithAdjacency
+                    | PrimitiveTopology::LineStripWithAdjacency
+            ),
+            Self::Triangles => matches!(
+                topology,
+                PrimitiveTopology::TriangleList
+                    | PrimitiveTopology::TriangleStrip
+                    | PrimitiveTopology::TriangleFan,
+            ),
+            Self::TrianglesWithAdjacency => matches!(
+                topology,
+                PrimitiveTopology::TriangleListWithAdjacency
+                    | PrimitiveTopology::TriangleStripWithAdjacency,
+            ),
+        }
+    }
+}
+
+/// The output primitive type of a geometry shader.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GeometryShaderOutput {
+    /// The `OutputPoints` execution mode.
+    Points,
+
+    /// The `OutputLineStrip` execution mode.
+    LineStrip,
+
+    /// The `OutputTriangleStrip` execution mode.
+    TriangleStrip,
 }
 
 /// Represents a shader entry point in a shader module.
@@ -1209,6 +1357,11 @@ pub struct DescriptorBindingRequirements {
     /// This is `None` for non-image bindings.
     pub image_view_type: Option<ImageViewType>,
 
+    /// The minimum size in bytes of the range of buffers bound to this binding, which is the size
+    /// of the block declared in the shader. If the block ends in a runtime-sized array, the array
+    /// is not included. This is `None` for non-buffer bindings.
+    pub buffer_min_size: Option<DeviceSize>,
+
     /// The shader stages that the binding must be declared for.
     pub stages: ShaderStages,
 
@@ -1269,6 +1422,7 @@ impl DescriptorBindingRequirements {
             image_multisampled,
             image_scalar_type,
             image_view_type,
+            buffer_min_size,
             stages,
             descriptors,
         } = self;
@@ -1289,7 +1443,11 @@ impl DescriptorBindingRequirements {
         if let (Some(first), Some(second)) = (*image_format, other.image_format) {
             if first != second {
                 return Err(Box::new(ValidationError {
-                    problem: "the descriptors require different formats".into(),
+                    problem: format!(
+                        "the descriptors require different formats (`{:?}` and `{:?}`)",
+                        first, second,
+                    )
+                    .into(),
                     ..Default::default()
                 }));
             }
@@ -1328,6 +1486,7 @@ impl DescriptorBindingRequirements {
         *image_format = image_format.or(other.image_format);
         *image_scalar_type = image_scalar_type.or(other.image_scalar_type);
         *image_view_type = image_view_type.or(other.image_view_type);
+        *buffer_min_size = (*buffer_min_size).max(other.buffer_min_size);
         *stages |= other.stages;
 
         for (&index, other) in &other.descriptors {
@@ -1343,6 +1502,300 @@ impl DescriptorBindingRequirements {
 
         Ok(())
     }
+
+    /// Checks whether `image_view`, and `sampler` if provided, satisfy the requirements of the
+    /// descriptor at `index` in this binding.
+    ///
+    /// This performs the same checks on an image view and sampler that are performed when a
+    /// pipeline using this binding is used in a draw or dispatch command. It can be used to
+    /// catch incompatible resources earlier, for example when writing them to a descriptor set,
+    /// or offline against the requirements reflected from a shader. `sampler` should be the
+    /// sampler that the image view is combined with, if there is one.
+    pub fn is_satisfied_by_image(
+        &self,
+        index: u32,
+        image_view: &ImageView,
+        sampler: Option<&Sampler>,
+    ) -> Result<(), Box<ValidationError>> {
+        let usage = image_view.usage();
+
+        if !self.descriptor_types.iter().any(|&descriptor_type| {
+            let required_usage = match descriptor_type {
+                DescriptorType::SampledImage | DescriptorType::CombinedImageSampler => {
+                    ImageUsage::SAMPLED
+                }
+                DescriptorType::StorageImage => ImageUsage::STORAGE,
+                DescriptorType::InputAttachment => ImageUsage::INPUT_ATTACHMENT,
+                _ => return false,
+            };
+
+            usage.intersects(required_usage)
+        }) {
+            return Err(Box::new(ValidationError {
+                context: "image_view.usage()".into(),
+                problem: format!(
+                    "does not contain the usage that is required for any of the allowed \
+                    descriptor types ({:?})",
+                    self.descriptor_types,
+                )
+                .into(),
+                ..Default::default()
+            }));
+        }
+
+        let storage_image = self
+            .descriptor_types
+            .contains(&DescriptorType::StorageImage);
+
+        self.check_image_view(index, image_view, storage_image)
+            .map_err(|mismatch| {
+                let (context, problem) = match mismatch {
+                    ImageViewMismatch::StorageImageAtomic => (
+                        "image_view.format_features()",
+                        "does not contain `FormatFeatures::STORAGE_IMAGE_ATOMIC`, but the \
+                        shader performs atomic operations on the image"
+                            .into(),
+                    ),
+                    ImageViewMismatch::StorageWriteWithoutFormat => (
+                        "image_view.format_features()",
+                        "does not contain `FormatFeatures::STORAGE_WRITE_WITHOUT_FORMAT`, but \
+                        the shader writes to the image without specifying a format"
+                            .into(),
+                    ),
+                    ImageViewMismatch::StorageReadWithoutFormat => (
+                        "image_view.format_features()",
+                        "does not contain `FormatFeatures::STORAGE_READ_WITHOUT_FORMAT`, but \
+                        the shader reads from the image without specifying a format"
+                            .into(),
+                    ),
+                    ImageViewMismatch::Format { required, actual } => (
+                        "image_view.format()",
+                        format!("is `{actual:?}`, but the shader requires `{required:?}`").into(),
+                    ),
+                    ImageViewMismatch::ViewType { required, actual } => (
+                        "image_view.view_type()",
+                        format!("is `{actual:?}`, but the shader requires `{required:?}`").into(),
+                    ),
+                    ImageViewMismatch::Multisampled { required: true } => (
+                        "image_view.image().samples()",
+                        "is `SampleCount::Sample1`, but the shader requires a multisampled image"
+                            .into(),
+                    ),
+                    ImageViewMismatch::Multisampled { required: false } => (
+                        "image_view.image().samples()",
+                        "is not `SampleCount::Sample1`, but the shader requires a \
+                        non-multisampled image"
+                            .into(),
+                    ),
+                    ImageViewMismatch::NumericType { required, actual } => (
+                        "image_view.format()",
+                        format!(
+                            "has a `{actual:?}` numeric type, but the shader requires a \
+                            `{required:?}` numeric type",
+                        )
+                        .into(),
+                    ),
+                };
+
+                Box::new(ValidationError {
+                    context: context.into(),
+                    problem,
+                    ..Default::default()
+                })
+            })?;
+
+        if let Some(sampler) = sampler {
+            self.check_sampler(index, sampler).map_err(|mismatch| {
+                let (context, problem) = match mismatch {
+                    SamplerMismatch::UnnormalizedCoordinates => (
+                        "sampler.unnormalized_coordinates()",
+                        "is `true`, but the shader samples in a way that does not support \
+                        unnormalized coordinates",
+                    ),
+                    SamplerMismatch::YcbcrConversion => (
+                        "sampler.sampler_ycbcr_conversion()",
+                        "is `Some`, but the shader samples in a way that does not support a \
+                        sampler YCbCr conversion",
+                    ),
+                    SamplerMismatch::Compare { required: true } => (
+                        "sampler.compare()",
+                        "is `None`, but the shader performs depth comparison operations",
+                    ),
+                    SamplerMismatch::Compare { required: false } => (
+                        "sampler.compare()",
+                        "is `Some`, but the shader does not perform depth comparison operations",
+                    ),
+                };
+
+                Box::new(ValidationError {
+                    context: context.into(),
+                    problem: problem.into(),
+                    ..Default::default()
+                })
+            })?;
+        }
+
+        if let Some(sampler) = sampler {
+            sampler.check_can_sample(image_view)?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks the requirements of the descriptor at `index` that apply to the image view bound to
+    /// it. `storage_image` is whether the image view is bound as a storage image.
+    ///
+    /// This is shared by [`is_satisfied_by_image`] and the checks made when recording a draw or
+    /// dispatch command, which report the mismatch in their own way.
+    ///
+    /// [`is_satisfied_by_image`]: Self::is_satisfied_by_image
+    pub(crate) fn check_image_view(
+        &self,
+        index: u32,
+        image_view: &ImageView,
+        storage_image: bool,
+    ) -> Result<(), ImageViewMismatch> {
+        let format_features = image_view.format_features();
+
+        for desc_reqs in self
+            .descriptors
+            .get(&Some(index))
+            .into_iter()
+            .chain(self.descriptors.get(&None))
+        {
+            if desc_reqs.storage_image_atomic
+                && !format_features.intersects(FormatFeatures::STORAGE_IMAGE_ATOMIC)
+            {
+                return Err(ImageViewMismatch::StorageImageAtomic);
+            }
+
+            if storage_image && self.image_format.is_none() {
+                if !desc_reqs.memory_write.is_empty()
+                    && !format_features.intersects(FormatFeatures::STORAGE_WRITE_WITHOUT_FORMAT)
+                {
+                    return Err(ImageViewMismatch::StorageWriteWithoutFormat);
+                }
+
+                if !desc_reqs.memory_read.is_empty()
+                    && !format_features.intersects(FormatFeatures::STORAGE_READ_WITHOUT_FORMAT)
+                {
+                    return Err(ImageViewMismatch::StorageReadWithoutFormat);
+                }
+            }
+        }
+
+        if let Some(required) = self.image_format {
+            let actual = image_view.format();
+
+            if actual != required {
+                return Err(ImageViewMismatch::Format { required, actual });
+            }
+        }
+
+        if let Some(required) = self.image_view_type {
+            let actual = image_view.view_type();
+
+            if actual != required {
+                return Err(ImageViewMismatch::ViewType { required, actual });
+            }
+        }
+
+        if self.image_multisampled != (image_view.image().samples() != SampleCount::Sample1) {
+            return Err(ImageViewMismatch::Multisampled {
+                required: self.image_multisampled,
+            });
+        }
+
+        if let Some(required) = self.image_scalar_type {
+            let aspects = image_view.subresource_range().aspects;
+            let format = image_view.format();
+            let numeric_format = if aspects.intersects(
+                ImageAspects::COLOR
+                    | ImageAspects::PLANE_0
+                    | ImageAspects::PLANE_1
+                    | ImageAspects::PLANE_2,
+            ) {
+                format.numeric_format_color()
+            } else if aspects.intersects(ImageAspects::DEPTH) {
+                format.numeric_format_depth()
+            } else if aspects.intersects(ImageAspects::STENCIL) {
+                format.numeric_format_stencil()
+            } else {
+                None
+            };
+
+            if let Some(actual) = numeric_format.map(NumericType::from) {
+                if actual != required {
+                    return Err(ImageViewMismatch::NumericType { required, actual });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the requirements of the descriptor at `index` that apply to the sampler that is
+    /// bound to it, or combined with the image view bound to it.
+    pub(crate) fn check_sampler(
+        &self,
+        index: u32,
+        sampler: &Sampler,
+    ) -> Result<(), SamplerMismatch> {
+        for desc_reqs in self
+            .descriptors
+            .get(&Some(index))
+            .into_iter()
+            .chain(self.descriptors.get(&None))
+        {
+            if desc_reqs.sampler_no_unnormalized_coordinates && sampler.unnormalized_coordinates() {
+                return Err(SamplerMismatch::UnnormalizedCoordinates);
+            }
+
+            if desc_reqs.sampler_no_ycbcr_conversion && sampler.sampler_ycbcr_conversion().is_some()
+            {
+                return Err(SamplerMismatch::YcbcrConversion);
+            }
+
+            if desc_reqs.sampler_compare != sampler.compare().is_some() {
+                return Err(SamplerMismatch::Compare {
+                    required: desc_reqs.sampler_compare,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A requirement of a descriptor that an image view doesn't satisfy.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum ImageViewMismatch {
+    StorageImageAtomic,
+    StorageWriteWithoutFormat,
+    StorageReadWithoutFormat,
+    Format {
+        required: Format,
+        actual: Format,
+    },
+    ViewType {
+        required: ImageViewType,
+        actual: ImageViewType,
+    },
+    Multisampled {
+        required: bool,
+    },
+    NumericType {
+        required: NumericType,
+        actual: NumericType,
+    },
+}
+
+/// A requirement of a descriptor that a sampler doesn't satisfy.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum SamplerMismatch {
+    UnnormalizedCoordinates,
+    YcbcrConversion,
+    Compare { required: bool },
 }
 
 impl DescriptorRequirements {
@@ -1551,3 +2004,98 @@ impl From<ShaderStages> for PipelineStages {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{DescriptorBindingRequirements, DescriptorRequirements};
+    use crate::{
+        descriptor_set::layout::DescriptorType,
+        format::{Format, NumericType},
+        image::{
+            sampler::{Sampler, SamplerCreateInfo},
+            view::{ImageView, ImageViewType},
+            Image, ImageCreateInfo, ImageUsage,
+        },
+        memory::allocator::{AllocationCreateInfo, StandardMemoryAllocator},
+        pipeline::graphics::depth_stencil::CompareOp,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn image_satisfies_binding_requirements() {
+        let (device, _) = gfx_dev_and_queue!();
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+        let image_view = ImageView::new_default(
+            Image::new(
+                memory_allocator,
+                ImageCreateInfo {
+                    format: Format::R8G8B8A8_UNORM,
+                    extent: [16, 16, 1],
+                    usage: ImageUsage::SAMPLED,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let sampler = Sampler::new(device.clone(), SamplerCreateInfo::default()).unwrap();
+
+        // A `sampler2D` in the shader.
+        let sampler_2d = DescriptorBindingRequirements {
+            descriptor_types: vec![DescriptorType::CombinedImageSampler],
+            descriptor_count: Some(1),
+            image_scalar_type: Some(NumericType::Float),
+            image_view_type: Some(ImageViewType::Dim2d),
+            descriptors: [(Some(0), DescriptorRequirements::default())]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+        sampler_2d
+            .is_satisfied_by_image(0, &image_view, Some(&sampler))
+            .unwrap();
+
+        // A `sampler2DArray` in the shader.
+        let err = DescriptorBindingRequirements {
+            image_view_type: Some(ImageViewType::Dim2dArray),
+            ..sampler_2d.clone()
+        }
+        .is_satisfied_by_image(0, &image_view, Some(&sampler))
+        .unwrap_err();
+        assert_eq!(err.context, "image_view.view_type()");
+
+        // A `usampler2D` in the shader.
+        let err = DescriptorBindingRequirements {
+            image_scalar_type: Some(NumericType::Uint),
+            ..sampler_2d.clone()
+        }
+        .is_satisfied_by_image(0, &image_view, Some(&sampler))
+        .unwrap_err();
+        assert_eq!(err.context, "image_view.format()");
+
+        // An `image2D` in the shader, but the image doesn't have storage usage.
+        let err = DescriptorBindingRequirements {
+            descriptor_types: vec![DescriptorType::StorageImage],
+            ..sampler_2d.clone()
+        }
+        .is_satisfied_by_image(0, &image_view, None)
+        .unwrap_err();
+        assert_eq!(err.context, "image_view.usage()");
+
+        // The shader doesn't perform depth comparisons, but the sampler has a compare operation.
+        let compare_sampler = Sampler::new(
+            device,
+            SamplerCreateInfo {
+                compare: Some(CompareOp::Less),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let err = sampler_2d
+            .is_satisfied_by_image(0, &image_view, Some(&compare_sampler))
+            .unwrap_err();
+        assert_eq!(err.context, "sampler.compare()");
+    }
+}
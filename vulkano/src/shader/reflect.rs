@@ -4,11 +4,14 @@ use super::DescriptorBindingRequirements;
 use crate::{
     descriptor_set::layout::DescriptorType,
     image::view::ImageViewType,
-    pipeline::layout::PushConstantRange,
+    pipeline::{inout_interface::shader_interface_location_info, layout::PushConstantRange},
     shader::{
-        spirv::{Decoration, Dim, Id, Instruction, Spirv, StorageClass},
-        DescriptorIdentifier, DescriptorRequirements, EntryPointInfo, NumericType, ShaderStage,
-        ShaderStages, SpecializationConstant,
+        spirv::{
+            Decoration, Dim, ExecutionMode, ExecutionModel, Id, Instruction, Spirv, StorageClass,
+        },
+        DescriptorIdentifier, DescriptorRequirements, EntryPointInfo, FragmentOutput,
+        GeometryShaderExecution, GeometryShaderInput, GeometryShaderOutput, NumericType,
+        ShaderStage, ShaderStages, SpecializationConstant,
     },
     DeviceSize, Version,
 };
@@ -46,6 +49,16 @@ pub fn entry_points(spirv: &Spirv) -> impl Iterator<Item = (Id, EntryPointInfo)>
             stage,
             entry_point,
         );
+        let fragment_outputs = if execution_model == ExecutionModel::Fragment {
+            fragment_outputs(spirv, entry_point)
+        } else {
+            Vec::new()
+        };
+        let geometry = if execution_model == ExecutionModel::Geometry {
+            geometry_execution(spirv, entry_point)
+        } else {
+            None
+        };
 
         Some((
             entry_point,
@@ -54,11 +67,91 @@ pub fn entry_points(spirv: &Spirv) -> impl Iterator<Item = (Id, EntryPointInfo)>
                 execution_model,
                 descriptor_binding_requirements,
                 push_constant_requirements,
+                fragment_outputs,
+                geometry,
             },
         ))
     })
 }
 
+/// Returns the execution modes of the geometry shader entry point `entry_point`, or `None` if the
+/// input or output primitive type is missing.
+fn geometry_execution(spirv: &Spirv, entry_point: Id) -> Option<GeometryShaderExecution> {
+    let mut input = None;
+    let mut output = None;
+    let mut invocations = 1;
+    let mut max_output_vertices = 0;
+
+    for instruction in spirv.function(entry_point).execution_modes() {
+        let Instruction::ExecutionMode { ref mode, .. } = *instruction else {
+            continue;
+        };
+
+        match *mode {
+            ExecutionMode::InputPoints => input = Some(GeometryShaderInput::Points),
+            ExecutionMode::InputLines => input = Some(GeometryShaderInput::Lines),
+            ExecutionMode::InputLinesAdjacency => {
+                input = Some(GeometryShaderInput::LinesWithAdjacency)
+            }
+            ExecutionMode::Triangles => input = Some(GeometryShaderInput::Triangles),
+            ExecutionMode::InputTrianglesAdjacency => {
+                input = Some(GeometryShaderInput::TrianglesWithAdjacency)
+            }
+            ExecutionMode::OutputPoints => output = Some(GeometryShaderOutput::Points),
+            ExecutionMode::OutputLineStrip => output = Some(GeometryShaderOutput::LineStrip),
+            ExecutionMode::OutputTriangleStrip => {
+                output = Some(GeometryShaderOutput::TriangleStrip)
+            }
+            ExecutionMode::Invocations {
+                number_of_invocation_invocations,
+            } => invocations = number_of_invocation_invocations,
+            ExecutionMode::OutputVertices { vertex_count } => max_output_vertices = vertex_count,
+            _ => (),
+        }
+    }
+
+    Some(GeometryShaderExecution {
+        input: input?,
+        output: output?,
+        invocations,
+        max_output_vertices,
+        output_components: output_component_count(spirv, entry_point),
+    })
+}
+
+/// Returns the number of components of the user-defined output variables of `entry_point`.
+/// 64-bit components count as two components.
+pub(crate) fn output_component_count(spirv: &Spirv, entry_point: Id) -> u32 {
+    shader_interface_location_info(spirv, entry_point, StorageClass::Output)
+        .values()
+        .flat_map(|info| info.components)
+        .map(|components| components.count())
+        .sum()
+}
+
+/// Returns the user-defined outputs of the fragment shader entry point `entry_point`.
+fn fragment_outputs(spirv: &Spirv, entry_point: Id) -> Vec<FragmentOutput> {
+    let mut outputs: Vec<_> =
+        shader_interface_location_info(spirv, entry_point, StorageClass::Output)
+            .into_iter()
+            .flat_map(|(location, info)| {
+                (0..2).filter_map(move |index| {
+                    let components = info.components[index];
+
+                    (!components.is_empty()).then_some(FragmentOutput {
+                        location,
+                        index: index as u32,
+                        components,
+                        numeric_type: info.numeric_type,
+                    })
+                })
+            })
+            .collect();
+    outputs.sort_unstable_by_key(|output| (output.location, output.index));
+
+    outputs
+}
+
 #[derive(Clone, Debug, Default)]
 struct InterfaceVariables {
     descriptor_binding: HashMap<Id, DescriptorBindingVariable>,
@@ -642,6 +735,8 @@ fn descriptor_binding_requirements_of(spirv: &Spirv, variable_id: Id) -> Descrip
                     BufferBlock",
                 );
 
+                reqs.buffer_min_size = block_min_size(spirv, id);
+
                 if decoration_buffer_block || decoration_block && is_storage_buffer {
                     reqs.descriptor_types = vec![
                         DescriptorType::StorageBuffer,
@@ -1100,10 +1195,8 @@ pub(crate) fn size_of_type(spirv: &Spirv, id: Id) -> Option<DeviceSize> {
                 _ => None,
             })
             .or_else(|| size_of_type(spirv, element_type))
-            .map(|stride| {
-                let length = get_constant(spirv, length).unwrap();
-                stride * length
-            }),
+            // The length can be a specialization constant, which isn't known here.
+            .and_then(|stride| get_constant(spirv, length).map(|length| stride * length)),
         Instruction::TypeRuntimeArray { .. } => None,
         Instruction::TypeStruct {
             ref member_types, ..
@@ -1143,6 +1236,37 @@ pub(crate) fn size_of_type(spirv: &Spirv, id: Id) -> Option<DeviceSize> {
     }
 }
 
+/// Returns the minimum size of a buffer that is bound to a block of type `id`. If the block ends
+/// in a runtime-sized array, this is the offset of the array.
+fn block_min_size(spirv: &Spirv, id: Id) -> Option<DeviceSize> {
+    size_of_type(spirv, id).or_else(|| {
+        let id_info = spirv.id(id);
+        let member_types = match id_info.instruction() {
+            Instruction::TypeStruct { member_types, .. } => member_types,
+            _ => return None,
+        };
+        let (&last_member, last_member_info) = member_types.iter().zip(id_info.members()).last()?;
+
+        if !matches!(
+            spirv.id(last_member).instruction(),
+            Instruction::TypeRuntimeArray { .. },
+        ) {
+            return None;
+        }
+
+        last_member_info
+            .decorations()
+            .iter()
+            .find_map(|instruction| match *instruction {
+                Instruction::MemberDecorate {
+                    decoration: Decoration::Offset { byte_offset },
+                    ..
+                } => Some(byte_offset as DeviceSize),
+                _ => None,
+            })
+    })
+}
+
 /// Returns the smallest offset of all members of a struct, or 0 if `id` is not a struct.
 fn offset_of_struct(spirv: &Spirv, id: Id) -> u32 {
     spirv
@@ -1268,7 +1392,10 @@ pub(crate) fn get_constant_composite_composite(
 
 #[cfg(test)]
 mod tests {
-    use super::{HashMap, PushConstantRange, ShaderStages, Version};
+    use super::{
+        GeometryShaderExecution, GeometryShaderInput, GeometryShaderOutput, HashMap,
+        PushConstantRange, ShaderStages, Version,
+    };
 
     #[test]
     fn push_constant_range() {
@@ -1463,4 +1590,60 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn geometry_output_components() {
+        /*
+            ; SPIR-V
+            ; Version: 1.0
+            ; Bound: 14
+            ; Schema: 0
+            OpCapability Geometry
+            OpMemoryModel Logical GLSL450
+            OpEntryPoint Geometry %main "main" %a %b %c
+            OpExecutionMode %main Triangles
+            OpExecutionMode %main OutputTriangleStrip
+            OpExecutionMode %main OutputVertices 4
+            OpDecorate %a Location 0
+            OpDecorate %b Location 1
+            OpDecorate %c Location 2
+            %void = OpTypeVoid
+            %fn_void = OpTypeFunction %void
+            %float = OpTypeFloat 32
+            %v2float = OpTypeVector %float 2
+            %v3float = OpTypeVector %float 3
+            %_ptr_Output_float = OpTypePointer Output %float
+            %_ptr_Output_v2float = OpTypePointer Output %v2float
+            %_ptr_Output_v3float = OpTypePointer Output %v3float
+            %a = OpVariable %_ptr_Output_float Output
+            %b = OpVariable %_ptr_Output_v2float Output
+            %c = OpVariable %_ptr_Output_v3float Output
+            %main = OpFunction %void None %fn_void
+                %main_label = OpLabel
+                OpReturn
+            OpFunctionEnd
+        */
+        const MODULE: [u32; 89] = [
+            119734787, 65536, 0, 14, 0, 131089, 2, 196622, 0, 1, 524303, 3, 12, 1852399981, 0, 9,
+            10, 11, 196624, 12, 22, 196624, 12, 29, 262160, 12, 26, 4, 262215, 9, 30, 0, 262215,
+            10, 30, 1, 262215, 11, 30, 2, 131091, 1, 196641, 2, 1, 196630, 3, 32, 262167, 4, 3, 2,
+            262167, 5, 3, 3, 262176, 6, 3, 3, 262176, 7, 3, 4, 262176, 8, 3, 5, 262203, 6, 9, 3,
+            262203, 7, 10, 3, 262203, 8, 11, 3, 327734, 1, 12, 0, 2, 131320, 13, 65789, 65592,
+        ];
+        let spirv = crate::shader::spirv::Spirv::new(&MODULE).unwrap();
+        let (_, main) = super::entry_points(&spirv).next().unwrap();
+
+        // Each location is only partially used, so this is less than 3 locations of 4
+        // components.
+        assert_eq!(
+            main.geometry,
+            Some(GeometryShaderExecution {
+                input: GeometryShaderInput::Triangles,
+                output: GeometryShaderOutput::TriangleStrip,
+                invocations: 1,
+                max_output_vertices: 4,
+                output_components: 6,
+            })
+        );
+    }
 }
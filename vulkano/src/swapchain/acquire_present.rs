@@ -1,16 +1,26 @@
-use super::{PresentMode, Swapchain};
+use super::{PresentMode, ReturnTransfer, Swapchain};
 use crate::{
     buffer::Buffer,
+    command_buffer::{
+        CommandBuffer, CommandBufferBeginInfo, CommandBufferLevel, CommandBufferSubmitInfo,
+        CommandBufferUsage, RecordingCommandBuffer, SemaphoreSubmitInfo, SubmitInfo,
+    },
     device::{Device, DeviceOwned, Queue},
     image::{Image, ImageLayout},
     sync::{
         fence::Fence,
-        future::{queue_present, AccessCheckError, AccessError, GpuFuture, SubmitAnyBuilder},
+        future::{
+            queue_present, submit_and_signal, AccessCheckError, AccessError, GpuFuture,
+            SubmitAnyBuilder,
+        },
         semaphore::{Semaphore, SemaphoreType},
+        Sharing,
     },
     DeviceSize, Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError, VulkanError,
     VulkanObject,
 };
+use once_cell::sync::OnceCell;
+use parking_lot::Mutex;
 use smallvec::smallvec;
 use std::{
     fmt::Debug,
@@ -127,6 +137,12 @@ impl AcquireNextImageInfo {
 /// The second field in the tuple in the Ok result is a bool represent if the acquisition was
 /// suboptimal. In this case the acquired image is still usable, but the swapchain should be
 /// recreated as the Surface's properties no longer match the swapchain.
+///
+/// If the image has exclusive sharing, and was last presented on a queue of another queue family
+/// than the one that rendered to it, then its ownership is transferred back to that queue family
+/// before the returned future is signaled. See [presenting from a different queue] for details.
+///
+/// [presenting from a different queue]: crate::swapchain#presenting-from-a-different-queue
 pub fn acquire_next_image(
     swapchain: Arc<Swapchain>,
     timeout: Option<Duration>,
@@ -146,6 +162,25 @@ pub fn acquire_next_image(
         })?
     };
 
+    let (semaphore, return_transfer) = match swapchain.take_return_transfer(image_index) {
+        Some(return_transfer) => {
+            let return_transfer = unsafe {
+                QueueTransfer::return_ownership(
+                    &swapchain,
+                    image_index,
+                    return_transfer,
+                    semaphore,
+                )?
+            };
+
+            (
+                return_transfer.semaphores.last().unwrap().clone(),
+                Some(return_transfer),
+            )
+        }
+        None => (semaphore, None),
+    };
+
     Ok((
         image_index,
         is_suboptimal,
@@ -153,6 +188,7 @@ pub fn acquire_next_image(
             swapchain,
             semaphore: Some(semaphore),
             fence: Some(fence),
+            return_transfer,
             image_index,
             finished: AtomicBool::new(false),
         },
@@ -207,8 +243,11 @@ pub unsafe fn acquire_next_image_raw(
         err => return Err(VulkanError::from(err).into()),
     };
 
+    let image_index = out.assume_init();
+    swapchain.image_acquired(image_index);
+
     Ok(AcquiredImage {
-        image_index: out.assume_init(),
+        image_index,
         is_suboptimal,
     })
 }
@@ -237,6 +276,9 @@ pub struct SwapchainAcquireFuture {
     // Fence that is signalled when the acquire is complete. Empty if the acquire has already
     // happened.
     fence: Option<Arc<Fence>>,
+    // The submissions that transfer the ownership of the image back from the queue family that
+    // last presented it, if needed. `semaphore` is then signaled by the last of them.
+    return_transfer: Option<QueueTransfer>,
     finished: AtomicBool,
 }
 
@@ -366,6 +408,16 @@ impl Drop for SwapchainAcquireFuture {
             self.semaphore = None;
         }
 
+        if !*self.finished.get_mut() {
+            if let Some(return_transfer) = &self.return_transfer {
+                // Block until the command buffers of the ownership transfer are no longer in use.
+                return_transfer
+                    .signal_queue
+                    .with(|mut q| q.wait_idle())
+                    .unwrap();
+            }
+        }
+
         // TODO: if this future is destroyed without being presented, then eventually acquiring
         // a new image will block forever ; difficulty: hard
     }
@@ -401,6 +453,8 @@ where
         previous: before,
         queue,
         swapchain_info,
+        queue_transfer: Mutex::new(None),
+        present_results: OnceCell::new(),
         flushed: AtomicBool::new(false),
         finished: AtomicBool::new(false),
     }
@@ -459,7 +513,7 @@ impl PresentInfo {
                 .map_err(|err| err.add_context(format!("swapchain_infos[{}]", index)))?;
 
             let &SwapchainPresentInfo {
-                swapchain: _,
+                ref swapchain,
                 image_index: _,
                 present_id: _,
                 present_mode,
@@ -467,6 +521,22 @@ impl PresentInfo {
                 _ne: _,
             } = swapchain_info;
 
+            if let Some(other_index) = swapchain_infos[..index]
+                .iter()
+                .position(|other| other.swapchain == *swapchain)
+            {
+                return Err(Box::new(ValidationError {
+                    problem: format!(
+                        "`swapchain_infos[{}].swapchain` and `swapchain_infos[{}].swapchain` are \
+                        the same swapchain",
+                        other_index, index
+                    )
+                    .into(),
+                    vuids: &["VUID-VkPresentInfoKHR-pSwapchain-09231"],
+                    ..Default::default()
+                }));
+            }
+
             if has_present_mode {
                 if present_mode.is_none() {
                     return Err(Box::new(ValidationError {
@@ -779,6 +849,11 @@ where
     previous: P,
     queue: Arc<Queue>,
     swapchain_info: SwapchainPresentInfo,
+    // The submissions that were made to hand the image over from the queue of `previous`, if it
+    // is a different queue.
+    queue_transfer: Mutex<Option<QueueTransfer>>,
+    // The result of each swapchain of the present operation, once `flush()` has submitted it.
+    present_results: OnceCell<Vec<Result<bool, VulkanError>>>,
     // True if `flush()` has been called on the future, which means that the present command has
     // been submitted.
     flushed: AtomicBool,
@@ -800,6 +875,254 @@ where
     pub fn swapchain(&self) -> &Arc<Swapchain> {
         &self.swapchain_info.swapchain
     }
+
+    /// Returns the result of presenting each swapchain, or `None` if the present operation
+    /// hasn't been submitted by flushing this future yet.
+    ///
+    /// When [`then_swapchain_present`] is called several times in a row, the swapchains are
+    /// presented in one operation, which this future submits. The results are then in the order
+    /// in which the swapchains were added, with the swapchain of this future last. `Ok(true)`
+    /// means that the image was presented, but the swapchain is suboptimal. Flushing only returns
+    /// the first error, so this can be used to find out which of the swapchains need to be
+    /// recreated.
+    ///
+    /// The results are only recorded if this future itself is flushed. If it is flushed by a
+    /// future that was created from it, such as with [`then_signal_fence_and_flush`], call
+    /// [`flush`] on this future first.
+    ///
+    /// [`then_swapchain_present`]: GpuFuture::then_swapchain_present
+    /// [`then_signal_fence_and_flush`]: GpuFuture::then_signal_fence_and_flush
+    /// [`flush`]: GpuFuture::flush
+    #[inline]
+    pub fn present_results(&self) -> Option<&[Result<bool, VulkanError>]> {
+        self.present_results.get().map(Vec::as_slice)
+    }
+
+    /// Submits the submission of `previous` on `previous_queue`, followed by the release of the
+    /// image to the queue family of `self.queue` if needed, and the acquire on `self.queue`.
+    /// Returns the semaphore that the present operation must wait for.
+    ///
+    /// If this was already done by an earlier call, then the semaphore returned by that call may
+    /// have been waited on already, so a new one is signaled after the earlier submissions.
+    unsafe fn submit_queue_transfer(
+        &self,
+        previous_queue: &Arc<Queue>,
+    ) -> Result<Arc<Semaphore>, Validated<VulkanError>> {
+        let mut queue_transfer = self.queue_transfer.lock();
+
+        let queue_transfer = match &mut *queue_transfer {
+            // An earlier call failed after the release was submitted on `previous_queue`. Only the
+            // acquire remains to be submitted, `self.previous` must not be submitted again.
+            Some(queue_transfer) if queue_transfer.pending_acquire.is_some() => queue_transfer,
+            Some(queue_transfer) => {
+                let semaphore = submit_signal(&queue_transfer.signal_queue, None, None)?;
+                queue_transfer.semaphores.push(semaphore.clone());
+
+                return Ok(semaphore);
+            }
+            slot @ None => {
+                let swapchain = &self.swapchain_info.swapchain;
+                let image_index = self.swapchain_info.image_index;
+                let src_queue_family_index = previous_queue.queue_family_index();
+                let dst_queue_family_index = self.queue.queue_family_index();
+
+                // Images with exclusive sharing must be released by the queue family that last
+                // used them, and acquired by the queue family that presents them.
+                let mut command_buffers = if matches!(swapchain.image_sharing(), Sharing::Exclusive)
+                    && src_queue_family_index != dst_queue_family_index
+                {
+                    [src_queue_family_index, dst_queue_family_index]
+                        .into_iter()
+                        .map(|queue_family_index| {
+                            record_ownership_transfer(
+                                swapchain,
+                                image_index,
+                                queue_family_index,
+                                src_queue_family_index,
+                                dst_queue_family_index,
+                            )
+                        })
+                        .collect::<Result<Vec<_>, _>>()?
+                } else {
+                    Vec::new()
+                };
+
+                let semaphore = Arc::new(Semaphore::new(
+                    swapchain.device().clone(),
+                    Default::default(),
+                )?);
+                submit_and_signal(
+                    &self.previous,
+                    command_buffers
+                        .first()
+                        .cloned()
+                        .map(CommandBufferSubmitInfo::new)
+                        .into_iter()
+                        .collect(),
+                    &[semaphore.clone()],
+                )?;
+
+                // `self.previous` has been submitted now, so this is recorded before anything
+                // else can fail.
+                let pending_acquire = command_buffers
+                    .pop()
+                    .filter(|_| !command_buffers.is_empty());
+                slot.insert(QueueTransfer {
+                    _command_buffers: command_buffers,
+                    semaphores: vec![semaphore],
+                    signal_queue: previous_queue.clone(),
+                    pending_acquire,
+                })
+            }
+        };
+
+        if let Some(command_buffer) = &queue_transfer.pending_acquire {
+            let semaphore = submit_signal(
+                &self.queue,
+                queue_transfer.semaphores.last().cloned(),
+                Some(command_buffer.clone()),
+            )?;
+            queue_transfer.semaphores.push(semaphore);
+            queue_transfer.signal_queue = self.queue.clone();
+            queue_transfer
+                ._command_buffers
+                .extend(queue_transfer.pending_acquire.take());
+
+            // The image has to be given back to the queue family that rendered to it before it
+            // is used again.
+            self.swapchain_info.swapchain.set_return_transfer(
+                self.swapchain_info.image_index,
+                ReturnTransfer {
+                    present_queue: self.queue.clone(),
+                    queue: previous_queue.clone(),
+                },
+            );
+        }
+
+        Ok(queue_transfer.semaphores.last().unwrap().clone())
+    }
+}
+
+// The command buffers and semaphores that hand a swapchain image over from one queue to another.
+// They are kept alive until the queue operations that use them have finished.
+#[derive(Debug)]
+struct QueueTransfer {
+    _command_buffers: Vec<Arc<CommandBuffer>>,
+    semaphores: Vec<Arc<Semaphore>>,
+    // The queue that signals the last semaphore.
+    signal_queue: Arc<Queue>,
+    // The acquire half of the ownership transfer, if it has not been submitted yet.
+    pending_acquire: Option<Arc<CommandBuffer>>,
+}
+
+impl QueueTransfer {
+    /// Transfers the ownership of an acquired image back from the queue family that presented it,
+    /// after `acquire_semaphore` is signaled.
+    unsafe fn return_ownership(
+        swapchain: &Arc<Swapchain>,
+        image_index: u32,
+        return_transfer: ReturnTransfer,
+        acquire_semaphore: Arc<Semaphore>,
+    ) -> Result<Self, Validated<VulkanError>> {
+        let ReturnTransfer {
+            present_queue,
+            queue,
+        } = return_transfer;
+        let src_queue_family_index = present_queue.queue_family_index();
+        let dst_queue_family_index = queue.queue_family_index();
+
+        let command_buffers = [src_queue_family_index, dst_queue_family_index]
+            .into_iter()
+            .map(|queue_family_index| {
+                record_ownership_transfer(
+                    swapchain,
+                    image_index,
+                    queue_family_index,
+                    src_queue_family_index,
+                    dst_queue_family_index,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let release_semaphore = submit_signal(
+            &present_queue,
+            Some(acquire_semaphore),
+            Some(command_buffers[0].clone()),
+        )?;
+        let semaphore = submit_signal(
+            &queue,
+            Some(release_semaphore.clone()),
+            Some(command_buffers[1].clone()),
+        )?;
+
+        Ok(QueueTransfer {
+            _command_buffers: command_buffers,
+            semaphores: vec![release_semaphore, semaphore],
+            signal_queue: queue,
+            pending_acquire: None,
+        })
+    }
+}
+
+// Records one half of the queue family ownership transfer of a swapchain image, to be submitted on
+// a queue of `queue_family_index`.
+fn record_ownership_transfer(
+    swapchain: &Arc<Swapchain>,
+    image_index: u32,
+    queue_family_index: u32,
+    src_queue_family_index: u32,
+    dst_queue_family_index: u32,
+) -> Result<Arc<CommandBuffer>, Validated<VulkanError>> {
+    let mut builder = RecordingCommandBuffer::new(
+        swapchain.ownership_transfer_allocator().clone(),
+        queue_family_index,
+        CommandBufferLevel::Primary,
+        CommandBufferBeginInfo {
+            usage: CommandBufferUsage::OneTimeSubmit,
+            ..Default::default()
+        },
+    )?;
+
+    unsafe {
+        builder.swapchain_image_ownership_transfer_unchecked(
+            swapchain.clone(),
+            image_index,
+            src_queue_family_index,
+            dst_queue_family_index,
+        );
+    }
+
+    builder.end()
+}
+
+// Submits `command_buffer` on `queue` after `wait_semaphore` is signaled, and returns a new
+// semaphore that is signaled afterwards.
+unsafe fn submit_signal(
+    queue: &Queue,
+    wait_semaphore: Option<Arc<Semaphore>>,
+    command_buffer: Option<Arc<CommandBuffer>>,
+) -> Result<Arc<Semaphore>, Validated<VulkanError>> {
+    let semaphore = Arc::new(Semaphore::new(queue.device().clone(), Default::default())?);
+
+    queue.with(|mut queue_guard| {
+        queue_guard.submit(
+            &[SubmitInfo {
+                wait_semaphores: wait_semaphore
+                    .into_iter()
+                    .map(SemaphoreSubmitInfo::new)
+                    .collect(),
+                command_buffers: command_buffer
+                    .into_iter()
+                    .map(CommandBufferSubmitInfo::new)
+                    .collect(),
+                signal_semaphores: vec![SemaphoreSubmitInfo::new(semaphore.clone())],
+                ..Default::default()
+            }],
+            None,
+        )
+    })?;
+
+    Ok(semaphore)
 }
 
 unsafe impl<P> GpuFuture for PresentFuture<P>
@@ -831,10 +1154,21 @@ where
             swapchain_info.present_regions = Default::default();
         }
 
-        let _queue = self.previous.queue();
+        // If the previous submission is made on another queue, the present operation has to wait
+        // for it with a semaphore, and the image may have to change queue family ownership.
+        if let Some(previous_queue) = self
+            .previous
+            .queue()
+            .filter(|previous_queue| *previous_queue != self.queue)
+        {
+            let semaphore = self.submit_queue_transfer(&previous_queue)?;
 
-        // TODO: if the swapchain image layout is not PRESENT, should add a transition command
-        // buffer
+            return Ok(SubmitAnyBuilder::QueuePresent(PresentInfo {
+                wait_semaphores: vec![SemaphorePresentInfo::new(semaphore)],
+                swapchains: vec![self.swapchain_info.clone()],
+                ..Default::default()
+            }));
+        }
 
         Ok(match self.previous.build_submission()? {
             SubmitAnyBuilder::Empty => SubmitAnyBuilder::QueuePresent(PresentInfo {
@@ -945,9 +1279,15 @@ where
                         }
                     }
 
-                    Ok(queue_present(&self.queue, present_info)?
+                    let present_results: Vec<_> =
+                        queue_present(&self.queue, present_info)?.collect();
+                    let result = present_results
+                        .iter()
                         .map(|r| r.map(|_| ()))
-                        .fold(Ok(()), Result::and)?)
+                        .fold(Ok(()), Result::and);
+                    let _ = self.present_results.set(present_results);
+
+                    Ok(result?)
                 }
                 _ => unreachable!(),
             }
@@ -965,11 +1305,6 @@ where
     }
 
     fn queue(&self) -> Option<Arc<Queue>> {
-        debug_assert!(match self.previous.queue() {
-            None => true,
-            Some(q) => q == self.queue,
-        });
-
         Some(self.queue.clone())
     }
 
@@ -316,6 +316,24 @@
 //!     }
 //! }
 //! ```
+//!
+//! ## Presenting from a different queue
+//!
+//! On some devices, the queue family that renders to the swapchain images can't present them, so
+//! they have to be presented on a queue of another family. The queue to present on is given to
+//! [`then_swapchain_present`], and when it is different from the queue that the previous
+//! submission was made on, the present operation waits for that submission with a semaphore.
+//!
+//! If the swapchain was created with [`Sharing::Exclusive`], and the two queues belong to
+//! different queue families, the ownership of the image must also be transferred to the queue
+//! family that presents it. This is done automatically: a release barrier is submitted on the
+//! queue of the previous submission, and an acquire barrier on the present queue, before the
+//! image is presented. When the image is next acquired with [`acquire_next_image`], the ownership
+//! is transferred back in the same way, from the present queue to the queue that rendered to the
+//! image, before the acquire future is signaled. Alternatively, the swapchain can be created with
+//! [`Sharing::Concurrent`] for both queue families, in which case no ownership transfer is needed.
+//!
+//! [`then_swapchain_present`]: crate::sync::GpuFuture::then_swapchain_present
 
 pub use self::{acquire_present::*, surface::*};
 #[cfg(target_os = "ios")]
@@ -324,16 +342,25 @@ pub use surface::IOSMetalLayer;
 mod acquire_present;
 mod surface;
 
+#[cfg(doc)]
+use crate::device::{DeviceExtensions, DeviceFeatures};
 use crate::{
-    device::{Device, DeviceOwned},
+    command_buffer::allocator::{
+        StandardCommandBufferAllocator, StandardCommandBufferAllocatorCreateInfo,
+    },
+    device::{Device, DeviceOwned, Queue},
     format::Format,
-    image::{Image, ImageCreateFlags, ImageFormatInfo, ImageTiling, ImageType, ImageUsage},
+    image::{
+        Image, ImageCompressionControl, ImageCompressionFlags, ImageCreateFlags, ImageFormatInfo,
+        ImageTiling, ImageType, ImageUsage,
+    },
     instance::InstanceOwnedDebugWrapper,
     macros::{impl_id_counter, vulkan_bitflags, vulkan_bitflags_enum, vulkan_enum},
     sync::Sharing,
     Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError, Version, VulkanError,
     VulkanObject,
 };
+use once_cell::sync::OnceCell;
 use parking_lot::Mutex;
 use smallvec::SmallVec;
 use std::{
@@ -374,9 +401,13 @@ pub struct Swapchain {
     present_gravity: Option<[PresentGravity; 2]>,
     full_screen_exclusive: FullScreenExclusive,
     win32_monitor: Option<Win32Monitor>,
+    compression_control: Option<ImageCompressionControl>,
 
     prev_present_id: AtomicU64,
 
+    // The `min_image_count` of the surface capabilities, at the time the swapchain was created.
+    surface_min_image_count: u32,
+
     // Whether full-screen exclusive is currently held.
     full_screen_exclusive_held: AtomicBool,
 
@@ -390,12 +421,33 @@ pub struct Swapchain {
     // We use a `Mutex` instead of an `AtomicBool` because we want to keep that locked while
     // we acquire the image.
     is_retired: Mutex<bool>,
+
+    // Allocates the command buffers that transfer the ownership of images to the queue family that
+    // presents them, if the images have exclusive sharing. Only created once it is needed.
+    ownership_transfer_allocator: OnceCell<Arc<StandardCommandBufferAllocator>>,
 }
 
 #[derive(Debug)]
 struct ImageEntry {
     handle: ash::vk::Image,
     layout_initialized: AtomicBool,
+
+    // Whether the image has been acquired and not yet presented. This is set when
+    // `acquire_next_image` returns the image, and cleared again when it is presented.
+    acquired: AtomicBool,
+
+    // If the image has exclusive sharing and was last presented on a queue of another queue
+    // family than the one that rendered to it, the queues to transfer its ownership back with
+    // when it is next acquired.
+    return_transfer: Mutex<Option<ReturnTransfer>>,
+}
+
+// The queues of a queue family ownership transfer of a swapchain image, back from the queue family
+// that presented it.
+#[derive(Debug)]
+pub(crate) struct ReturnTransfer {
+    pub(crate) present_queue: Arc<Queue>,
+    pub(crate) queue: Arc<Queue>,
 }
 
 impl Swapchain {
@@ -438,6 +490,23 @@ impl Swapchain {
     ///
     /// Use this when a swapchain has become invalidated, such as due to window resizes.
     ///
+    /// `create_info` replaces all of the parameters that the swapchain was created with. To keep
+    /// the current parameters and only change some of them, start from
+    /// [`create_info`](Self::create_info):
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use vulkano::swapchain::{Swapchain, SwapchainCreateInfo};
+    /// # let swapchain: Arc<Swapchain> = return;
+    /// # let new_extent = [1024, 768];
+    /// let (new_swapchain, new_images) = swapchain
+    ///     .recreate(SwapchainCreateInfo {
+    ///         image_extent: new_extent,
+    ///         ..swapchain.create_info()
+    ///     })
+    ///     .unwrap();
+    /// ```
+    ///
     /// # Panics
     ///
     /// - Panics if `create_info.usage` is empty.
@@ -445,7 +514,7 @@ impl Swapchain {
         self: &Arc<Self>,
         create_info: SwapchainCreateInfo,
     ) -> Result<(Arc<Swapchain>, Vec<Arc<Image>>), Validated<VulkanError>> {
-        Self::validate_new_inner(&self.device, &self.surface, &create_info)?;
+        self.validate_recreate(&create_info)?;
 
         {
             let mut is_retired = self.is_retired.lock();
@@ -474,6 +543,29 @@ impl Swapchain {
         unsafe { Ok(self.recreate_unchecked(create_info)?) }
     }
 
+    fn validate_recreate(
+        &self,
+        create_info: &SwapchainCreateInfo,
+    ) -> Result<(), Box<ValidationError>> {
+        Self::validate_new_inner(&self.device, &self.surface, create_info)?;
+
+        // The new swapchain inherits full-screen exclusive mode from this one, so it must be able
+        // to release it again.
+        if self.full_screen_exclusive_held.load(Ordering::Relaxed)
+            && create_info.full_screen_exclusive != FullScreenExclusive::ApplicationControlled
+        {
+            return Err(Box::new(ValidationError {
+                problem: "`self` currently holds full-screen exclusive mode, but \
+                    `create_info.full_screen_exclusive` is not \
+                    `FullScreenExclusive::ApplicationControlled`"
+                    .into(),
+                ..Default::default()
+            }));
+        }
+
+        Ok(())
+    }
+
     #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
     pub unsafe fn recreate_unchecked(
         self: &Arc<Self>,
@@ -501,7 +593,9 @@ impl Swapchain {
             create_info,
         )?;
 
-        if self.full_screen_exclusive == FullScreenExclusive::ApplicationControlled {
+        if self.full_screen_exclusive == FullScreenExclusive::ApplicationControlled
+            && swapchain.full_screen_exclusive == FullScreenExclusive::ApplicationControlled
+        {
             Arc::get_mut(&mut swapchain)
                 .unwrap()
                 .full_screen_exclusive_held =
@@ -548,6 +642,7 @@ impl Swapchain {
             present_gravity,
             full_screen_exclusive,
             win32_monitor,
+            compression_control: _,
             _ne: _,
         } = create_info;
 
@@ -999,6 +1094,7 @@ impl Swapchain {
             present_gravity,
             full_screen_exclusive,
             win32_monitor,
+            ref compression_control,
             _ne: _,
         } = create_info;
 
@@ -1036,6 +1132,8 @@ impl Swapchain {
         };
         let mut format_list_info_vk = None;
         let format_list_view_formats_vk: Vec<_>;
+        let mut compression_control_vk = None;
+        let mut compression_control_fixed_rate_flags_vk: SmallVec<[_; 3]>;
         let mut full_screen_exclusive_info_vk = None;
         let mut full_screen_exclusive_win32_info_vk = None;
         let mut present_modes_info_vk = None;
@@ -1059,6 +1157,31 @@ impl Swapchain {
             create_info_vk.p_next = <*const _>::cast(next);
         }
 
+        if let Some(compression_control) = compression_control {
+            let &ImageCompressionControl {
+                flags,
+                ref fixed_rate_flags,
+                _ne: _,
+            } = compression_control;
+
+            compression_control_fixed_rate_flags_vk = fixed_rate_flags
+                .iter()
+                .copied()
+                .map(ash::vk::ImageCompressionFixedRateFlagsEXT::from)
+                .collect();
+
+            let next = compression_control_vk.insert(ash::vk::ImageCompressionControlEXT {
+                flags: flags.into(),
+                compression_control_plane_count: compression_control_fixed_rate_flags_vk.len()
+                    as u32,
+                p_fixed_rate_flags: compression_control_fixed_rate_flags_vk.as_mut_ptr(),
+                ..Default::default()
+            });
+
+            next.p_next = create_info_vk.p_next;
+            create_info_vk.p_next = <*const _>::cast(next);
+        }
+
         if full_screen_exclusive != FullScreenExclusive::Default {
             let next =
                 full_screen_exclusive_info_vk.insert(ash::vk::SurfaceFullScreenExclusiveInfoEXT {
@@ -1192,9 +1315,31 @@ impl Swapchain {
             present_gravity,
             full_screen_exclusive,
             win32_monitor,
+            compression_control,
             _ne: _,
         } = create_info;
 
+        // This is only used for validation, so if the query fails, fall back to the lowest
+        // possible value, which allows the most images to be acquired.
+        let surface_min_image_count = device
+            .physical_device()
+            .surface_capabilities_unchecked(
+                &surface,
+                SurfaceInfo {
+                    present_mode: device
+                        .enabled_extensions()
+                        .ext_swapchain_maintenance1
+                        .then_some(present_mode),
+                    full_screen_exclusive,
+                    win32_monitor: win32_monitor
+                        .filter(|_| full_screen_exclusive != FullScreenExclusive::Default),
+                    ..Default::default()
+                },
+            )
+            .map_or(1, |surface_capabilities| {
+                surface_capabilities.min_image_count
+            });
+
         let swapchain = Arc::new(Swapchain {
             handle,
             device: InstanceOwnedDebugWrapper(device),
@@ -1219,17 +1364,22 @@ impl Swapchain {
             present_gravity,
             full_screen_exclusive,
             win32_monitor,
+            compression_control,
 
             prev_present_id: Default::default(),
+            surface_min_image_count,
             full_screen_exclusive_held: AtomicBool::new(false),
             images: image_handles
                 .into_iter()
                 .map(|handle| ImageEntry {
                     handle,
                     layout_initialized: AtomicBool::new(false),
+                    acquired: AtomicBool::new(false),
+                    return_transfer: Mutex::new(None),
                 })
                 .collect(),
             is_retired: Mutex::new(false),
+            ownership_transfer_allocator: OnceCell::new(),
         });
 
         let swapchain_images = swapchain
@@ -1270,6 +1420,7 @@ impl Swapchain {
             present_gravity: self.present_gravity,
             full_screen_exclusive: self.full_screen_exclusive,
             win32_monitor: self.win32_monitor,
+            compression_control: self.compression_control.clone(),
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -1301,6 +1452,29 @@ impl Swapchain {
         self.images.len() as u32
     }
 
+    /// Returns the indices of the images that are currently acquired, and have not been presented
+    /// yet.
+    ///
+    /// Other threads may acquire or present images concurrently, so the result may already be out
+    /// of date when this function returns. It is intended for debugging.
+    #[inline]
+    pub fn acquired_image_indices(&self) -> impl Iterator<Item = u32> + '_ {
+        self.images
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.acquired.load(Ordering::Acquire))
+            .map(|(image_index, _)| image_index as u32)
+    }
+
+    /// Returns the number of images that are currently acquired, and have not been presented yet.
+    ///
+    /// Other threads may acquire or present images concurrently, so the result may already be out
+    /// of date when this function returns. It is intended for debugging.
+    #[inline]
+    pub fn images_in_flight(&self) -> u32 {
+        self.acquired_image_indices().count() as u32
+    }
+
     /// Returns the format of the images of the swapchain.
     #[inline]
     pub fn image_format(&self) -> Format {
@@ -1391,6 +1565,12 @@ impl Swapchain {
         self.full_screen_exclusive
     }
 
+    /// Returns the compression control that the swapchain images were created with.
+    #[inline]
+    pub fn compression_control(&self) -> Option<&ImageCompressionControl> {
+        self.compression_control.as_ref()
+    }
+
     /// Acquires temporary ownership of a swapchain image.
     ///
     /// The function returns the index of the image in the array of images that was returned
@@ -1401,12 +1581,18 @@ impl Swapchain {
     /// be accessed by the host or device. After this, the image must be *presented* back to the
     /// swapchain, using the [`present`] queue command.
     ///
+    /// The swapchain keeps track of which images are acquired, so acquiring and presenting can
+    /// happen on different threads: one thread can acquire an image and send its index to another
+    /// thread, which renders to it and presents it. Acquiring images is serialized internally.
+    /// If `acquire_info.timeout` is `None`, then acquiring is only allowed while no more than
+    /// [`image_count`] minus the `min_image_count` of the surface capabilities images are
+    /// acquired, because otherwise the implementation may never make an image available. See
+    /// [`images_in_flight`] and [`acquired_image_indices`].
+    ///
     /// # Safety
     ///
     /// - `self` must be kept alive until either `acquire_info.semaphore` or `acquire_info.fence`
     ///   is signaled.
-    /// - If all images from `self` are currently acquired, and have not been presented yet, then
-    ///   `acquire_info.timeout` must not be `None`.
     ///
     /// If `acquire_info.semaphore` is `Some`:
     /// - The semaphore must be kept alive until it is signaled.
@@ -1418,6 +1604,9 @@ impl Swapchain {
     ///   still executing.
     ///
     /// [`present`]: crate::device::QueueGuard::present
+    /// [`image_count`]: Self::image_count
+    /// [`images_in_flight`]: Self::images_in_flight
+    /// [`acquired_image_indices`]: Self::acquired_image_indices
     #[inline]
     pub unsafe fn acquire_next_image(
         &self,
@@ -1446,8 +1635,32 @@ impl Swapchain {
             }));
         }
 
+        let images_in_flight = self.images_in_flight();
+
+        if acquire_info.timeout.is_none()
+            && images_in_flight
+                > self
+                    .image_count()
+                    .saturating_sub(self.surface_min_image_count)
+        {
+            return Err(Box::new(ValidationError {
+                context: "acquire_info.timeout".into(),
+                problem: format!(
+                    "is `None`, but {} images of the swapchain are already acquired and have not \
+                    been presented, which is more than the swapchain image count ({}) minus the \
+                    `min_image_count` of the surface capabilities ({}); an image may never become \
+                    available",
+                    images_in_flight,
+                    self.image_count(),
+                    self.surface_min_image_count,
+                )
+                .into(),
+                vuids: &["VUID-vkAcquireNextImage2KHR-surface-07784"],
+                ..Default::default()
+            }));
+        }
+
         // unsafe
-        // VUID-vkAcquireNextImage2KHR-surface-07784
         // VUID-VkAcquireNextImageInfoKHR-semaphore-01288
         // VUID-VkAcquireNextImageInfoKHR-semaphore-01781
         // VUID-VkAcquireNextImageInfoKHR-fence-01289
@@ -1521,8 +1734,11 @@ impl Swapchain {
             }
         };
 
+        let image_index = output.assume_init();
+        self.image_acquired(image_index);
+
         Ok(AcquiredImage {
-            image_index: output.assume_init(),
+            image_index,
             is_suboptimal,
         })
     }
@@ -1751,6 +1967,31 @@ impl Swapchain {
         }
     }
 
+    pub(crate) fn image_acquired(&self, image_index: u32) {
+        if let Some(image_entry) = self.images.get(image_index as usize) {
+            image_entry.acquired.store(true, Ordering::Release);
+        }
+    }
+
+    // Marks the image as no longer acquired, returning whether it was acquired before.
+    // This is done atomically, so that if several threads present the same image, only one of
+    // them succeeds.
+    pub(crate) fn try_release_image(&self, image_index: u32) -> bool {
+        self.images
+            .get(image_index as usize)
+            .map_or(false, |image_entry| {
+                image_entry.acquired.swap(false, Ordering::AcqRel)
+            })
+    }
+
+    pub(crate) fn is_image_acquired(&self, image_index: u32) -> bool {
+        self.images
+            .get(image_index as usize)
+            .map_or(false, |image_entry| {
+                image_entry.acquired.load(Ordering::Acquire)
+            })
+    }
+
     pub(crate) fn is_image_layout_initialized(&self, image_index: u32) -> bool {
         let image_entry = self.images.get(image_index as usize);
         if let Some(image_entry) = image_entry {
@@ -1760,6 +2001,33 @@ impl Swapchain {
         }
     }
 
+    pub(crate) fn image_handle(&self, image_index: u32) -> ash::vk::Image {
+        self.images[image_index as usize].handle
+    }
+
+    pub(crate) fn set_return_transfer(&self, image_index: u32, return_transfer: ReturnTransfer) {
+        *self.images[image_index as usize].return_transfer.lock() = Some(return_transfer);
+    }
+
+    pub(crate) fn take_return_transfer(&self, image_index: u32) -> Option<ReturnTransfer> {
+        self.images[image_index as usize]
+            .return_transfer
+            .lock()
+            .take()
+    }
+
+    pub(crate) fn ownership_transfer_allocator(&self) -> &Arc<StandardCommandBufferAllocator> {
+        self.ownership_transfer_allocator.get_or_init(|| {
+            Arc::new(StandardCommandBufferAllocator::new(
+                self.device.clone(),
+                StandardCommandBufferAllocatorCreateInfo {
+                    primary_buffer_count: 2,
+                    ..Default::default()
+                },
+            ))
+        })
+    }
+
     #[inline]
     pub(crate) unsafe fn full_screen_exclusive_held(&self) -> &AtomicBool {
         &self.full_screen_exclusive_held
@@ -1953,6 +2221,18 @@ pub struct SwapchainCreateInfo {
     /// The default value is `None`.
     pub win32_monitor: Option<Win32Monitor>,
 
+    /// Controls the compression that is applied to the swapchain images.
+    ///
+    /// If this is `Some`, then the [`ext_image_compression_control_swapchain`][extension]
+    /// extension must be enabled on the device, and the
+    /// [`image_compression_control_swapchain`][feature] feature must be enabled as well.
+    ///
+    /// The default value is `None`.
+    ///
+    /// [extension]: DeviceExtensions::ext_image_compression_control_swapchain
+    /// [feature]: DeviceFeatures::image_compression_control_swapchain
+    pub compression_control: Option<ImageCompressionControl>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -1978,6 +2258,7 @@ impl Default for SwapchainCreateInfo {
             present_gravity: None,
             full_screen_exclusive: FullScreenExclusive::Default,
             win32_monitor: None,
+            compression_control: None,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -2004,6 +2285,7 @@ impl SwapchainCreateInfo {
             present_gravity,
             full_screen_exclusive,
             win32_monitor,
+            ref compression_control,
             _ne: _,
         } = self;
 
@@ -2087,15 +2369,13 @@ impl SwapchainCreateInfo {
                     device.physical_device().queue_family_properties().len() as u32;
 
                 for (index, &queue_family_index) in queue_family_indices.iter().enumerate() {
-                    if queue_family_indices[..index].contains(&queue_family_index) {
+                    if let Some(other_index) = queue_family_indices[..index]
+                        .iter()
+                        .position(|&other| other == queue_family_index)
+                    {
                         return Err(Box::new(ValidationError {
-                            context: "queue_family_indices".into(),
-                            problem: format!(
-                                "the queue family index in the list at index {} is contained in \
-                                the list more than once",
-                                index,
-                            )
-                            .into(),
+                            context: format!("image_sharing[{}]", index).into(),
+                            problem: format!("is equal to `image_sharing[{}]`", other_index).into(),
                             vuids: &["VUID-VkSwapchainCreateInfoKHR-imageSharingMode-01428"],
                             ..Default::default()
                         }));
@@ -2103,7 +2383,7 @@ impl SwapchainCreateInfo {
 
                     if queue_family_index >= queue_family_count {
                         return Err(Box::new(ValidationError {
-                            context: format!("queue_family_indices[{}]", index).into(),
+                            context: format!("image_sharing[{}]", index).into(),
                             problem: "is not less than the number of queue families in the \
                                 physical device"
                                 .into(),
@@ -2115,36 +2395,6 @@ impl SwapchainCreateInfo {
             }
         };
 
-        let image_format_properties = unsafe {
-            device
-                .physical_device()
-                .image_format_properties_unchecked(ImageFormatInfo {
-                    format: image_format,
-                    image_type: ImageType::Dim2d,
-                    tiling: ImageTiling::Optimal,
-                    usage: image_usage,
-                    ..Default::default()
-                })
-                .map_err(|_err| {
-                    Box::new(ValidationError {
-                        problem: "`PhysicalDevice::image_format_properties` \
-                            returned an error"
-                            .into(),
-                        ..Default::default()
-                    })
-                })?
-        };
-
-        if image_format_properties.is_none() {
-            return Err(Box::new(ValidationError {
-                problem: "the combination of `image_format` and `image_usage` is not supported \
-                    for images by the physical device"
-                    .into(),
-                vuids: &["VUID-VkSwapchainCreateInfoKHR-imageFormat-01778"],
-                ..Default::default()
-            }));
-        }
-
         if flags.intersects(SwapchainCreateFlags::MUTABLE_FORMAT)
             && !image_view_formats.contains(&image_format)
         {
@@ -2204,6 +2454,41 @@ impl SwapchainCreateInfo {
             }
         }
 
+        let image_format_properties = unsafe {
+            device
+                .physical_device()
+                .image_format_properties_unchecked(ImageFormatInfo {
+                    // The implied image create flags and view formats must be included, because
+                    // they can allow usages that the format itself doesn't support, such as
+                    // storage usage for an sRGB format with a UNORM view format.
+                    flags: flags.into(),
+                    format: image_format,
+                    view_formats: image_view_formats.clone(),
+                    image_type: ImageType::Dim2d,
+                    tiling: ImageTiling::Optimal,
+                    usage: image_usage,
+                    ..Default::default()
+                })
+                .map_err(|_err| {
+                    Box::new(ValidationError {
+                        problem: "`PhysicalDevice::image_format_properties` \
+                            returned an error"
+                            .into(),
+                        ..Default::default()
+                    })
+                })?
+        };
+
+        if image_format_properties.is_none() {
+            return Err(Box::new(ValidationError {
+                problem: "the combination of `image_format` and `image_usage` is not supported \
+                    for images by the physical device"
+                    .into(),
+                vuids: &["VUID-VkSwapchainCreateInfoKHR-imageFormat-01778"],
+                ..Default::default()
+            }));
+        }
+
         if !present_modes.is_empty() {
             if !device.enabled_extensions().ext_swapchain_maintenance1 {
                 return Err(Box::new(ValidationError {
@@ -2326,6 +2611,56 @@ impl SwapchainCreateInfo {
             }));
         }
 
+        if let Some(compression_control) = compression_control {
+            if !device
+                .enabled_extensions()
+                .ext_image_compression_control_swapchain
+            {
+                return Err(Box::new(ValidationError {
+                    context: "compression_control".into(),
+                    problem: "is `Some`".into(),
+                    requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceExtension(
+                        "ext_image_compression_control_swapchain",
+                    )])]),
+                    vuids: &["VUID-VkSwapchainCreateInfoKHR-pNext-pNext"],
+                }));
+            }
+
+            if !device
+                .enabled_features()
+                .image_compression_control_swapchain
+            {
+                return Err(Box::new(ValidationError {
+                    context: "compression_control".into(),
+                    problem: "is `Some`".into(),
+                    requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceFeature(
+                        "image_compression_control_swapchain",
+                    )])]),
+                    vuids: &["VUID-VkSwapchainCreateInfoKHR-pNext-06752"],
+                }));
+            }
+
+            compression_control
+                .validate(device)
+                .map_err(|err| err.add_context("compression_control"))?;
+
+            // Swapchain images never have a multi-planar format.
+            if compression_control
+                .flags
+                .intersects(ImageCompressionFlags::FIXED_RATE_EXPLICIT)
+                && compression_control.fixed_rate_flags.len() != 1
+            {
+                return Err(Box::new(ValidationError {
+                    problem: "`compression_control.flags` contains \
+                        `ImageCompressionFlags::FIXED_RATE_EXPLICIT`, but \
+                        `compression_control.fixed_rate_flags` does not contain exactly one \
+                        element"
+                        .into(),
+                    ..Default::default()
+                }));
+            }
+        }
+
         Ok(())
     }
 }
@@ -2477,3 +2812,738 @@ impl Win32Monitor {
 // Winit's `MonitorHandle` is Send on Win32, so this seems safe.
 unsafe impl Send for Win32Monitor {}
 unsafe impl Sync for Win32Monitor {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, ClearColorImageInfo, CommandBufferBeginInfo,
+            CommandBufferLevel, CommandBufferUsage, RecordingCommandBuffer,
+        },
+        device::{
+            physical::PhysicalDevice, DeviceCreateInfo, DeviceExtensions, QueueCreateInfo,
+            QueueFlags,
+        },
+        instance::{Instance, InstanceCreateInfo, InstanceExtensions},
+        sync::future::{GpuFuture, SubmitAnyBuilder},
+        VulkanLibrary,
+    };
+    use std::thread;
+
+    #[test]
+    fn acquire_and_present_on_different_threads() {
+        let Ok(library) = VulkanLibrary::new() else {
+            return;
+        };
+        let instance_extensions = InstanceExtensions {
+            khr_surface: true,
+            ext_headless_surface: true,
+            ..InstanceExtensions::empty()
+        };
+
+        if !library
+            .supported_extensions()
+            .contains(&instance_extensions)
+        {
+            return;
+        }
+
+        let instance = Instance::new(
+            library,
+            InstanceCreateInfo {
+                enabled_extensions: instance_extensions,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let surface = Surface::headless(instance.clone(), None).unwrap();
+        let device_extensions = DeviceExtensions {
+            khr_swapchain: true,
+            ..DeviceExtensions::empty()
+        };
+        let Some((physical_device, queue_family_index)) = instance
+            .enumerate_physical_devices()
+            .unwrap()
+            .filter(|p| p.supported_extensions().contains(&device_extensions))
+            .find_map(|p| {
+                (0..p.queue_family_properties().len() as u32)
+                    .find(|&i| p.surface_support(i, &surface).unwrap_or(false))
+                    .map(|i| (p, i))
+            })
+        else {
+            return;
+        };
+
+        let surface_capabilities = physical_device
+            .surface_capabilities(&surface, Default::default())
+            .unwrap();
+
+        if !surface_capabilities
+            .supported_usage_flags
+            .intersects(ImageUsage::TRANSFER_DST)
+        {
+            return;
+        }
+
+        let (device, mut queues) = Device::new(
+            physical_device.clone(),
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index,
+                    ..Default::default()
+                }],
+                enabled_extensions: device_extensions,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let queue = queues.next().unwrap();
+
+        let (image_format, _) = physical_device
+            .surface_formats(&surface, Default::default())
+            .unwrap()[0];
+        let (swapchain, images) = Swapchain::new(
+            device.clone(),
+            surface,
+            SwapchainCreateInfo {
+                min_image_count: surface_capabilities.min_image_count.max(2),
+                image_format,
+                image_extent: surface_capabilities.current_extent.unwrap_or([64, 64]),
+                image_usage: ImageUsage::TRANSFER_DST,
+                composite_alpha: surface_capabilities
+                    .supported_composite_alpha
+                    .into_iter()
+                    .next()
+                    .unwrap(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // Presenting an image that was never acquired.
+        assert!(matches!(
+            queue.with(|mut q| unsafe {
+                q.present(&PresentInfo {
+                    swapchains: vec![SwapchainPresentInfo::swapchain_image_index(
+                        swapchain.clone(),
+                        0,
+                    )],
+                    ..Default::default()
+                })
+                .map(|_| ())
+            }),
+            Err(Validated::ValidationError(_)),
+        ));
+
+        // Acquire on another thread, then render and present on this one.
+        let (image_index, _, acquire_future) = thread::scope(|scope| {
+            scope
+                .spawn(|| acquire_next_image(swapchain.clone(), None).unwrap())
+                .join()
+                .unwrap()
+        });
+        assert_eq!(
+            swapchain.acquired_image_indices().collect::<Vec<_>>(),
+            [image_index],
+        );
+        assert_eq!(swapchain.images_in_flight(), 1);
+
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+        let mut cbb = RecordingCommandBuffer::new(
+            cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        cbb.clear_color_image(ClearColorImageInfo::image(
+            images[image_index as usize].clone(),
+        ))
+        .unwrap();
+        let command_buffer = cbb.end().unwrap();
+
+        acquire_future
+            .then_execute(queue.clone(), command_buffer)
+            .unwrap()
+            .then_swapchain_present(
+                queue.clone(),
+                SwapchainPresentInfo::swapchain_image_index(swapchain.clone(), image_index),
+            )
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+        assert_eq!(swapchain.images_in_flight(), 0);
+
+        // Presenting the same image a second time.
+        assert!(matches!(
+            queue.with(|mut q| unsafe {
+                q.present(&PresentInfo {
+                    swapchains: vec![SwapchainPresentInfo::swapchain_image_index(
+                        swapchain.clone(),
+                        image_index,
+                    )],
+                    ..Default::default()
+                })
+                .map(|_| ())
+            }),
+            Err(Validated::ValidationError(_)),
+        ));
+
+        // Acquire as many images as the implementation guarantees to make available. Acquiring
+        // another one without a timeout could block forever.
+        let mut acquire_futures = Vec::new();
+
+        while swapchain.images_in_flight()
+            <= swapchain.image_count() - surface_capabilities.min_image_count
+        {
+            let (_, _, acquire_future) = acquire_next_image(swapchain.clone(), None).unwrap();
+            acquire_futures.push(acquire_future);
+        }
+
+        assert!(matches!(
+            acquire_next_image(swapchain.clone(), None),
+            Err(Validated::ValidationError(_)),
+        ));
+    }
+
+    #[test]
+    fn present_on_another_queue() {
+        let Ok(library) = VulkanLibrary::new() else {
+            return;
+        };
+        let instance_extensions = InstanceExtensions {
+            khr_surface: true,
+            ext_headless_surface: true,
+            ..InstanceExtensions::empty()
+        };
+
+        if !library
+            .supported_extensions()
+            .contains(&instance_extensions)
+        {
+            return;
+        }
+
+        let instance = Instance::new(
+            library,
+            InstanceCreateInfo {
+                enabled_extensions: instance_extensions,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let surface = Surface::headless(instance.clone(), None).unwrap();
+        let device_extensions = DeviceExtensions {
+            khr_swapchain: true,
+            ..DeviceExtensions::empty()
+        };
+
+        // Prefer a graphics queue and a present queue of different queue families, so that the
+        // ownership transfer is exercised. Otherwise, use two queues of the same family, which
+        // still needs the semaphore between them.
+        let find_queues = |p: &PhysicalDevice, separate_families: bool| {
+            let queue_family_properties = p.queue_family_properties();
+            let graphics_families = (0..queue_family_properties.len() as u32).filter(|&i| {
+                queue_family_properties[i as usize]
+                    .queue_flags
+                    .intersects(QueueFlags::GRAPHICS)
+            });
+
+            graphics_families
+                .flat_map(|g| (0..queue_family_properties.len() as u32).map(move |i| (g, i)))
+                .find(|&(g, i)| {
+                    p.surface_support(i, &surface).unwrap_or(false)
+                        && if separate_families {
+                            g != i
+                        } else {
+                            g == i && queue_family_properties[i as usize].queue_count >= 2
+                        }
+                })
+        };
+        let physical_devices: Vec<_> = instance
+            .enumerate_physical_devices()
+            .unwrap()
+            .filter(|p| p.supported_extensions().contains(&device_extensions))
+            .collect();
+        let Some((physical_device, (graphics_family, present_family))) =
+            [true, false].into_iter().find_map(|separate_families| {
+                physical_devices.iter().find_map(|p| {
+                    find_queues(p, separate_families).map(|queues| (p.clone(), queues))
+                })
+            })
+        else {
+            return;
+        };
+
+        let surface_capabilities = physical_device
+            .surface_capabilities(&surface, Default::default())
+            .unwrap();
+
+        if !surface_capabilities
+            .supported_usage_flags
+            .intersects(ImageUsage::TRANSFER_DST)
+        {
+            return;
+        }
+
+        let queue_create_infos = if graphics_family == present_family {
+            vec![QueueCreateInfo {
+                queue_family_index: graphics_family,
+                queues: vec![0.5; 2],
+                ..Default::default()
+            }]
+        } else {
+            vec![
+                QueueCreateInfo {
+                    queue_family_index: graphics_family,
+                    ..Default::default()
+                },
+                QueueCreateInfo {
+                    queue_family_index: present_family,
+                    ..Default::default()
+                },
+            ]
+        };
+        let (device, mut queues) = Device::new(
+            physical_device.clone(),
+            DeviceCreateInfo {
+                queue_create_infos,
+                enabled_extensions: device_extensions,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let graphics_queue = queues.next().unwrap();
+        let present_queue = queues.next().unwrap();
+
+        let (image_format, _) = physical_device
+            .surface_formats(&surface, Default::default())
+            .unwrap()[0];
+        let (swapchain, images) = Swapchain::new(
+            device.clone(),
+            surface,
+            SwapchainCreateInfo {
+                min_image_count: surface_capabilities.min_image_count.max(2),
+                image_format,
+                image_extent: surface_capabilities.current_extent.unwrap_or([64, 64]),
+                image_usage: ImageUsage::TRANSFER_DST,
+                composite_alpha: surface_capabilities
+                    .supported_composite_alpha
+                    .into_iter()
+                    .next()
+                    .unwrap(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(swapchain.image_sharing(), &Sharing::Exclusive);
+
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+
+        // Present every image more than once, so that each of them is also transferred back to
+        // the graphics queue family when it is acquired again.
+        for frame in 0..swapchain.image_count() * 2 {
+            let (image_index, _, acquire_future) =
+                acquire_next_image(swapchain.clone(), None).unwrap();
+            assert!(swapchain.images[image_index as usize]
+                .return_transfer
+                .lock()
+                .is_none());
+
+            let mut cbb = RecordingCommandBuffer::new(
+                cb_allocator.clone(),
+                graphics_queue.queue_family_index(),
+                CommandBufferLevel::Primary,
+                CommandBufferBeginInfo {
+                    usage: CommandBufferUsage::OneTimeSubmit,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            cbb.clear_color_image(ClearColorImageInfo::image(
+                images[image_index as usize].clone(),
+            ))
+            .unwrap();
+            let command_buffer = cbb.end().unwrap();
+
+            let present_future = acquire_future
+                .then_execute(graphics_queue.clone(), command_buffer)
+                .unwrap()
+                .then_swapchain_present(
+                    present_queue.clone(),
+                    SwapchainPresentInfo::swapchain_image_index(swapchain.clone(), image_index),
+                );
+
+            if frame == 0 {
+                // Building the submission again must not reuse a semaphore that may have been
+                // waited on already.
+                unsafe {
+                    let SubmitAnyBuilder::QueuePresent(first) =
+                        present_future.build_submission().unwrap()
+                    else {
+                        panic!()
+                    };
+                    let SubmitAnyBuilder::QueuePresent(second) =
+                        present_future.build_submission().unwrap()
+                    else {
+                        panic!()
+                    };
+                    assert_ne!(
+                        first.wait_semaphores[0].semaphore,
+                        second.wait_semaphores[0].semaphore,
+                    );
+                }
+            }
+
+            present_future
+                .then_signal_fence_and_flush()
+                .unwrap()
+                .wait(None)
+                .unwrap();
+
+            assert_eq!(
+                swapchain.images[image_index as usize]
+                    .return_transfer
+                    .lock()
+                    .is_some(),
+                graphics_family != present_family,
+            );
+        }
+    }
+
+    #[test]
+    fn concurrent_sharing_with_present_family() {
+        let Ok(library) = VulkanLibrary::new() else {
+            return;
+        };
+        let instance_extensions = InstanceExtensions {
+            khr_surface: true,
+            ext_headless_surface: true,
+            ..InstanceExtensions::empty()
+        };
+
+        if !library
+            .supported_extensions()
+            .contains(&instance_extensions)
+        {
+            return;
+        }
+
+        let instance = Instance::new(
+            library,
+            InstanceCreateInfo {
+                enabled_extensions: instance_extensions,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let surface = Surface::headless(instance.clone(), None).unwrap();
+        let device_extensions = DeviceExtensions {
+            khr_swapchain: true,
+            ..DeviceExtensions::empty()
+        };
+
+        // This needs a device where a queue family other than the graphics one can present.
+        let Some((physical_device, graphics_family, present_family)) = instance
+            .enumerate_physical_devices()
+            .unwrap()
+            .filter(|p| p.supported_extensions().contains(&device_extensions))
+            .find_map(|p| {
+                let graphics_family = p
+                    .queue_family_properties()
+                    .iter()
+                    .position(|q| q.queue_flags.intersects(QueueFlags::GRAPHICS))?
+                    as u32;
+                let present_family = (0..p.queue_family_properties().len() as u32).find(|&i| {
+                    i != graphics_family && p.surface_support(i, &surface).unwrap_or(false)
+                })?;
+
+                Some((p, graphics_family, present_family))
+            })
+        else {
+            return;
+        };
+
+        let surface_capabilities = physical_device
+            .surface_capabilities(&surface, Default::default())
+            .unwrap();
+
+        if !surface_capabilities
+            .supported_usage_flags
+            .intersects(ImageUsage::TRANSFER_DST)
+        {
+            return;
+        }
+
+        let (device, mut queues) = Device::new(
+            physical_device.clone(),
+            DeviceCreateInfo {
+                queue_create_infos: vec![
+                    QueueCreateInfo {
+                        queue_family_index: graphics_family,
+                        ..Default::default()
+                    },
+                    QueueCreateInfo {
+                        queue_family_index: present_family,
+                        ..Default::default()
+                    },
+                ],
+                enabled_extensions: device_extensions,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let graphics_queue = queues.next().unwrap();
+        let present_queue = queues.next().unwrap();
+
+        let (image_format, _) = physical_device
+            .surface_formats(&surface, Default::default())
+            .unwrap()[0];
+        let create_info = SwapchainCreateInfo {
+            min_image_count: surface_capabilities.min_image_count.max(2),
+            image_format,
+            image_extent: surface_capabilities.current_extent.unwrap_or([64, 64]),
+            image_usage: ImageUsage::TRANSFER_DST,
+            image_sharing: Sharing::from_queues(&[&graphics_queue, &present_queue]),
+            composite_alpha: surface_capabilities
+                .supported_composite_alpha
+                .into_iter()
+                .next()
+                .unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(
+            create_info.image_sharing,
+            Sharing::Concurrent([graphics_family, present_family].into_iter().collect()),
+        );
+
+        // The same queue family twice.
+        assert!(matches!(
+            Swapchain::new(
+                device.clone(),
+                surface.clone(),
+                SwapchainCreateInfo {
+                    image_sharing: Sharing::Concurrent(
+                        [graphics_family, graphics_family].into_iter().collect()
+                    ),
+                    ..create_info.clone()
+                },
+            ),
+            Err(Validated::ValidationError(_)),
+        ));
+
+        let (swapchain, images) =
+            Swapchain::new(device.clone(), surface, create_info.clone()).unwrap();
+        assert_eq!(swapchain.image_sharing(), &create_info.image_sharing);
+        assert!(images
+            .iter()
+            .all(|image| image.sharing() == &create_info.image_sharing));
+
+        // Render on the graphics queue and present on the present queue, without any ownership
+        // transfer.
+        let (image_index, _, acquire_future) = acquire_next_image(swapchain.clone(), None).unwrap();
+
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+        let mut cbb = RecordingCommandBuffer::new(
+            cb_allocator,
+            graphics_family,
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        cbb.clear_color_image(ClearColorImageInfo::image(
+            images[image_index as usize].clone(),
+        ))
+        .unwrap();
+        let command_buffer = cbb.end().unwrap();
+
+        acquire_future
+            .then_execute(graphics_queue, command_buffer)
+            .unwrap()
+            .then_swapchain_present(
+                present_queue,
+                SwapchainPresentInfo::swapchain_image_index(swapchain, image_index),
+            )
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+    }
+
+    #[test]
+    fn present_results() {
+        let Ok(library) = VulkanLibrary::new() else {
+            return;
+        };
+        let instance_extensions = InstanceExtensions {
+            khr_surface: true,
+            ext_headless_surface: true,
+            ..InstanceExtensions::empty()
+        };
+
+        if !library
+            .supported_extensions()
+            .contains(&instance_extensions)
+        {
+            return;
+        }
+
+        let instance = Instance::new(
+            library,
+            InstanceCreateInfo {
+                enabled_extensions: instance_extensions,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let surfaces = [
+            Surface::headless(instance.clone(), None).unwrap(),
+            Surface::headless(instance.clone(), None).unwrap(),
+        ];
+        let device_extensions = DeviceExtensions {
+            khr_swapchain: true,
+            ..DeviceExtensions::empty()
+        };
+
+        let Some((physical_device, queue_family_index)) = instance
+            .enumerate_physical_devices()
+            .unwrap()
+            .filter(|p| p.supported_extensions().contains(&device_extensions))
+            .find_map(|p| {
+                let queue_family_index =
+                    (0..p.queue_family_properties().len() as u32).find(|&i| {
+                        p.queue_family_properties()[i as usize]
+                            .queue_flags
+                            .intersects(QueueFlags::GRAPHICS)
+                            && surfaces
+                                .iter()
+                                .all(|surface| p.surface_support(i, surface).unwrap_or(false))
+                    })?;
+
+                Some((p, queue_family_index))
+            })
+        else {
+            return;
+        };
+
+        let surface_capabilities = physical_device
+            .surface_capabilities(&surfaces[0], Default::default())
+            .unwrap();
+
+        if !surface_capabilities
+            .supported_usage_flags
+            .intersects(ImageUsage::TRANSFER_DST)
+        {
+            return;
+        }
+
+        let (device, mut queues) = Device::new(
+            physical_device.clone(),
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index,
+                    ..Default::default()
+                }],
+                enabled_extensions: device_extensions,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let queue = queues.next().unwrap();
+
+        let (image_format, _) = physical_device
+            .surface_formats(&surfaces[0], Default::default())
+            .unwrap()[0];
+        let swapchains = surfaces.map(|surface| {
+            Swapchain::new(
+                device.clone(),
+                surface,
+                SwapchainCreateInfo {
+                    min_image_count: surface_capabilities.min_image_count.max(2),
+                    image_format,
+                    image_extent: surface_capabilities.current_extent.unwrap_or([64, 64]),
+                    image_usage: ImageUsage::TRANSFER_DST,
+                    composite_alpha: surface_capabilities
+                        .supported_composite_alpha
+                        .into_iter()
+                        .next()
+                        .unwrap(),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        });
+
+        let [(swapchain0, images0), (swapchain1, images1)] = swapchains;
+        let (image_index0, _, acquire_future0) =
+            acquire_next_image(swapchain0.clone(), None).unwrap();
+        let (image_index1, _, acquire_future1) =
+            acquire_next_image(swapchain1.clone(), None).unwrap();
+
+        let mut cbb = RecordingCommandBuffer::new(
+            Arc::new(StandardCommandBufferAllocator::new(
+                device.clone(),
+                Default::default(),
+            )),
+            queue_family_index,
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        cbb.clear_color_image(ClearColorImageInfo::image(
+            images0[image_index0 as usize].clone(),
+        ))
+        .unwrap();
+        cbb.clear_color_image(ClearColorImageInfo::image(
+            images1[image_index1 as usize].clone(),
+        ))
+        .unwrap();
+        let command_buffer = cbb.end().unwrap();
+
+        // Both swapchains are presented in one operation, which gives a result for each of them.
+        let present_future = acquire_future0
+            .join(acquire_future1)
+            .then_execute(queue.clone(), command_buffer)
+            .unwrap()
+            .then_swapchain_present(
+                queue.clone(),
+                SwapchainPresentInfo::swapchain_image_index(swapchain0, image_index0),
+            )
+            .then_swapchain_present(
+                queue,
+                SwapchainPresentInfo::swapchain_image_index(swapchain1, image_index1),
+            );
+        assert!(present_future.present_results().is_none());
+
+        present_future.flush().unwrap();
+        let present_results = present_future.present_results().unwrap();
+        assert_eq!(present_results.len(), 2);
+        assert!(present_results.iter().all(Result::is_ok));
+
+        present_future
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+    }
+}
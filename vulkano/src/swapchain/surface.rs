@@ -3,7 +3,7 @@ use crate::{
     cache::OnceCache,
     device::physical::PhysicalDevice,
     display::{DisplayMode, DisplayPlaneAlpha},
-    format::Format,
+    format::{Format, FormatFeatures},
     image::ImageUsage,
     instance::{Instance, InstanceExtensions, InstanceOwned},
     macros::{impl_id_counter, vulkan_bitflags_enum, vulkan_enum},
@@ -69,13 +69,23 @@ impl Surface {
             RawDisplayHandle::Wayland(_) => extensions.khr_wayland_surface = true,
             RawDisplayHandle::Xcb(_) => extensions.khr_xcb_surface = true,
             RawDisplayHandle::Xlib(_) => extensions.khr_xlib_surface = true,
-            _ => unimplemented!(),
+            _ => return Err(HandleError::NotSupported),
         }
 
         Ok(extensions)
     }
 
     /// Creates a new `Surface` from the given `window`.
+    ///
+    /// The surface keeps `window` alive for as long as the surface exists, so that the window
+    /// can't be destroyed while Vulkan still uses it.
+    ///
+    /// The window can be created by any windowing library or toolkit that implements the
+    /// [`raw-window-handle`] traits, including child windows such as Win32 child `HWND`s or
+    /// Wayland subsurfaces. Handles of windowing systems that have no corresponding Vulkan surface
+    /// extension result in [`FromWindowError::UnsupportedWindowHandle`].
+    ///
+    /// [`raw-window-handle`]: raw_window_handle
     pub fn from_window(
         instance: Arc<Instance>,
         window: Arc<impl HasWindowHandle + HasDisplayHandle + Any + Send + Sync>,
@@ -89,6 +99,11 @@ impl Surface {
     /// Creates a new `Surface` from the given `window` without ensuring that the window outlives
     /// the surface.
     ///
+    /// If the window is owned by something that can't be put in an `Arc`, such as a window
+    /// created by another toolkit, you can instead create the surface with one of the
+    /// platform-specific constructors and pass an object that keeps the window alive as the
+    /// `object` parameter.
+    ///
     /// # Safety
     ///
     /// - The given `window` must outlive the created surface.
@@ -130,29 +145,49 @@ impl Surface {
                 )
             }
             (RawWindowHandle::Win32(window), RawDisplayHandle::Windows(_display)) => {
+                // Child windows created by other toolkits don't always report the `HINSTANCE`,
+                // but Vulkan requires it.
+                let hinstance = window
+                    .hinstance
+                    .ok_or(FromWindowError::RetrieveHandle(HandleError::Unavailable))?;
+
                 Self::from_win32(
                     instance,
-                    window.hinstance.unwrap().get() as ash::vk::HINSTANCE,
+                    hinstance.get() as ash::vk::HINSTANCE,
                     window.hwnd.get() as ash::vk::HWND,
                     None,
                 )
             }
-            (RawWindowHandle::Xcb(window), RawDisplayHandle::Xcb(display)) => Self::from_xcb(
-                instance,
-                display.connection.unwrap().as_ptr().cast(),
-                window.window.get() as ash::vk::xcb_window_t,
-                None,
-            ),
-            (RawWindowHandle::Xlib(window), RawDisplayHandle::Xlib(display)) => Self::from_xlib(
-                instance,
-                display.display.unwrap().as_ptr().cast(),
-                window.window as ash::vk::Window,
-                None,
-            ),
-            _ => unimplemented!(
-                "the window was created with a windowing API that is not supported \
-                by Vulkan/Vulkano"
-            ),
+            (RawWindowHandle::Xcb(window), RawDisplayHandle::Xcb(display)) => {
+                let connection = display
+                    .connection
+                    .ok_or(FromWindowError::RetrieveHandle(HandleError::Unavailable))?;
+
+                Self::from_xcb(
+                    instance,
+                    connection.as_ptr().cast(),
+                    window.window.get() as ash::vk::xcb_window_t,
+                    None,
+                )
+            }
+            (RawWindowHandle::Xlib(window), RawDisplayHandle::Xlib(display)) => {
+                let display = display
+                    .display
+                    .ok_or(FromWindowError::RetrieveHandle(HandleError::Unavailable))?;
+
+                Self::from_xlib(
+                    instance,
+                    display.as_ptr().cast(),
+                    window.window as ash::vk::Window,
+                    None,
+                )
+            }
+            (window, display) => {
+                return Err(FromWindowError::UnsupportedWindowHandle {
+                    window: raw_window_handle_name(&window),
+                    display: raw_display_handle_name(&display),
+                });
+            }
         }
         .map_err(FromWindowError::CreateSurface)
     }
@@ -165,6 +200,10 @@ impl Surface {
     /// - `handle` must have been created using the function specified by `api`.
     /// - The window object that `handle` was created from must outlive the created `Surface`. The
     ///   `object` parameter can be used to ensure this.
+    ///
+    /// This is the escape hatch for surfaces that were created outside of Vulkano, for example
+    /// with a surface extension that Vulkano doesn't support yet. The surface is destroyed when
+    /// the returned `Surface` is dropped, and `object` is dropped only after that.
     pub unsafe fn from_handle(
         instance: Arc<Instance>,
         handle: ash::vk::SurfaceKHR,
@@ -1438,6 +1477,51 @@ impl Surface {
         self.object.as_ref()
     }
 
+    /// Chooses a combination of format and color space for a swapchain on this surface, based
+    /// on `preferences`.
+    ///
+    /// The combinations returned by [`PhysicalDevice::surface_formats`] are ranked in the order
+    /// described by [`FormatPreferences`], and the best one is returned. `None` is returned if
+    /// no combination is acceptable, which can only happen if
+    /// [`require_storage`](FormatPreferences::require_storage) is `true`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the physical device and the surface don't belong to the same instance.
+    pub fn choose_format(
+        &self,
+        physical_device: &PhysicalDevice,
+        preferences: &FormatPreferences,
+    ) -> Result<Option<(Format, ColorSpace)>, Validated<VulkanError>> {
+        let surface_formats = physical_device.surface_formats(self, Default::default())?;
+
+        if preferences.require_storage
+            && !physical_device
+                .surface_capabilities(self, Default::default())?
+                .supported_usage_flags
+                .intersects(ImageUsage::STORAGE)
+        {
+            return Ok(None);
+        }
+
+        let mut supports_storage = Vec::with_capacity(surface_formats.len());
+
+        for &(format, _) in &surface_formats {
+            supports_storage.push(
+                physical_device
+                    .format_properties(format)?
+                    .optimal_tiling_features
+                    .intersects(FormatFeatures::STORAGE_IMAGE),
+            );
+        }
+
+        Ok(choose_surface_format(
+            &surface_formats,
+            preferences,
+            |index| supports_storage[index],
+        ))
+    }
+
     /// Resizes the sublayer bounds on iOS.
     /// It may not be necessary if original window size matches device's, but often it does not.
     /// Thus this should be called after a resize has occurred abd swapchain has been recreated.
@@ -2133,6 +2217,144 @@ vulkan_enum! {
     ]),
 }
 
+/// Preferences for [`Surface::choose_format`].
+///
+/// The combinations of format and color space that are supported by the surface are ranked in
+/// the following order, from best to worst:
+///
+/// 1. The preferred color space, with a format in `prefer_formats`. Formats that come earlier in
+///    `prefer_formats` are ranked higher.
+/// 2. The preferred color space, with an 8-bit sRGB format such as `B8G8R8A8_SRGB`.
+/// 3. The preferred color space, with an 8-bit UNORM format that has an sRGB equivalent, such as
+///    `B8G8R8A8_UNORM`.
+/// 4. The preferred color space, with any other format.
+/// 5. [`ColorSpace::SrgbNonLinear`], with an 8-bit sRGB format, then an 8-bit UNORM format, then
+///    any other format.
+/// 6. Any other combination, with the formats ranked in the same way.
+///
+/// Combinations with the same rank are ranked in the order that the surface reports them in. If
+/// `require_storage` is `true`, then combinations whose format doesn't support storage images
+/// are never chosen.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormatPreferences {
+    /// The color space that should be chosen.
+    ///
+    /// The default value is [`ColorSpacePreference::SrgbNonLinear`].
+    pub color_space: ColorSpacePreference,
+
+    /// Whether the chosen format must support being used as a storage image, so that the
+    /// swapchain images can be written to from a compute shader.
+    ///
+    /// Note that 8-bit sRGB formats rarely support this, so a UNORM format is usually chosen
+    /// instead. You must then convert to sRGB yourself in the shader.
+    ///
+    /// The default value is `false`.
+    pub require_storage: bool,
+
+    /// Formats that should be chosen before any other in the preferred color space, in order of
+    /// preference.
+    ///
+    /// The default value is empty.
+    pub prefer_formats: Vec<Format>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for FormatPreferences {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            color_space: ColorSpacePreference::SrgbNonLinear,
+            require_storage: false,
+            prefer_formats: Vec::new(),
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// The color space that [`Surface::choose_format`] should choose.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ColorSpacePreference {
+    /// [`ColorSpace::SrgbNonLinear`], which is supported everywhere and is what compositors
+    /// expect for regular content.
+    #[default]
+    SrgbNonLinear,
+
+    /// [`ColorSpace::Hdr10St2084`], for HDR output.
+    Hdr10,
+
+    /// [`ColorSpace::PassThrough`], where the presentation engine does not interpret the
+    /// contents of the images.
+    PassThrough,
+}
+
+impl From<ColorSpacePreference> for ColorSpace {
+    #[inline]
+    fn from(val: ColorSpacePreference) -> Self {
+        match val {
+            ColorSpacePreference::SrgbNonLinear => ColorSpace::SrgbNonLinear,
+            ColorSpacePreference::Hdr10 => ColorSpace::Hdr10St2084,
+            ColorSpacePreference::PassThrough => ColorSpace::PassThrough,
+        }
+    }
+}
+
+/// Implements the ranking of [`FormatPreferences`]. `supports_storage` is called with an index
+/// into `surface_formats`.
+fn choose_surface_format(
+    surface_formats: &[(Format, ColorSpace)],
+    preferences: &FormatPreferences,
+    supports_storage: impl Fn(usize) -> bool,
+) -> Option<(Format, ColorSpace)> {
+    const SRGB_FORMATS: [Format; 3] = [
+        Format::B8G8R8A8_SRGB,
+        Format::R8G8B8A8_SRGB,
+        Format::A8B8G8R8_SRGB_PACK32,
+    ];
+    const UNORM_FORMATS: [Format; 3] = [
+        Format::B8G8R8A8_UNORM,
+        Format::R8G8B8A8_UNORM,
+        Format::A8B8G8R8_UNORM_PACK32,
+    ];
+
+    let preferred_color_space = ColorSpace::from(preferences.color_space);
+    let position = |formats: &[Format], format| formats.iter().position(|&f| f == format);
+    let format_rank = |format| {
+        if let Some(position) = position(&SRGB_FORMATS, format) {
+            (0, position)
+        } else if let Some(position) = position(&UNORM_FORMATS, format) {
+            (1, position)
+        } else {
+            (2, 0)
+        }
+    };
+
+    surface_formats
+        .iter()
+        .enumerate()
+        .filter(|&(index, _)| !preferences.require_storage || supports_storage(index))
+        .min_by_key(|&(index, &(format, color_space))| {
+            let rank = if color_space == preferred_color_space {
+                0
+            } else if color_space == ColorSpace::SrgbNonLinear {
+                1
+            } else {
+                2
+            };
+            let (format_rank, position) = match position(&preferences.prefer_formats, format) {
+                Some(position) if rank == 0 => (0, position),
+                _ => {
+                    let (format_rank, position) = format_rank(format);
+                    (format_rank + 1, position)
+                }
+            };
+
+            (rank, format_rank, position, index)
+        })
+        .map(|(_, &surface_format)| surface_format)
+}
+
 /// Parameters for [`PhysicalDevice::surface_capabilities`] and
 /// [`PhysicalDevice::surface_formats`].
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -2348,9 +2570,18 @@ pub struct SurfaceCapabilities {
 
 /// Error that can happen when creating a [`Surface`] from a window.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum FromWindowError {
     /// Retrieving the window or display handle failed.
     RetrieveHandle(HandleError),
+    /// The window or display handle belongs to a windowing system that has no corresponding
+    /// Vulkan surface extension, or that is not supported on the current platform.
+    UnsupportedWindowHandle {
+        /// The name of the `RawWindowHandle` variant of the window.
+        window: &'static str,
+        /// The name of the `RawDisplayHandle` variant of the window.
+        display: &'static str,
+    },
     /// Creating the surface failed.
     CreateSurface(Validated<VulkanError>),
 }
@@ -2359,6 +2590,7 @@ impl Error for FromWindowError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
             Self::RetrieveHandle(err) => Some(err),
+            Self::UnsupportedWindowHandle { .. } => None,
             Self::CreateSurface(err) => Some(err),
         }
     }
@@ -2370,17 +2602,242 @@ impl Display for FromWindowError {
             Self::RetrieveHandle(_) => {
                 write!(f, "retrieving the window or display handle has failed")
             }
+            Self::UnsupportedWindowHandle { window, display } => write!(
+                f,
+                "creating a surface from a `RawWindowHandle::{}` window with a \
+                `RawDisplayHandle::{}` display is not supported",
+                window, display,
+            ),
             Self::CreateSurface(_) => write!(f, "creating the surface has failed"),
         }
     }
 }
 
+fn raw_window_handle_name(handle: &RawWindowHandle) -> &'static str {
+    match handle {
+        RawWindowHandle::UiKit(_) => "UiKit",
+        RawWindowHandle::AppKit(_) => "AppKit",
+        RawWindowHandle::Orbital(_) => "Orbital",
+        RawWindowHandle::Xlib(_) => "Xlib",
+        RawWindowHandle::Xcb(_) => "Xcb",
+        RawWindowHandle::Wayland(_) => "Wayland",
+        RawWindowHandle::Drm(_) => "Drm",
+        RawWindowHandle::Gbm(_) => "Gbm",
+        RawWindowHandle::Win32(_) => "Win32",
+        RawWindowHandle::WinRt(_) => "WinRt",
+        RawWindowHandle::Web(_) => "Web",
+        RawWindowHandle::WebCanvas(_) => "WebCanvas",
+        RawWindowHandle::WebOffscreenCanvas(_) => "WebOffscreenCanvas",
+        RawWindowHandle::AndroidNdk(_) => "AndroidNdk",
+        RawWindowHandle::Haiku(_) => "Haiku",
+        _ => "unknown",
+    }
+}
+
+fn raw_display_handle_name(handle: &RawDisplayHandle) -> &'static str {
+    match handle {
+        RawDisplayHandle::UiKit(_) => "UiKit",
+        RawDisplayHandle::AppKit(_) => "AppKit",
+        RawDisplayHandle::Orbital(_) => "Orbital",
+        RawDisplayHandle::Xlib(_) => "Xlib",
+        RawDisplayHandle::Xcb(_) => "Xcb",
+        RawDisplayHandle::Wayland(_) => "Wayland",
+        RawDisplayHandle::Drm(_) => "Drm",
+        RawDisplayHandle::Gbm(_) => "Gbm",
+        RawDisplayHandle::Windows(_) => "Windows",
+        RawDisplayHandle::Web(_) => "Web",
+        RawDisplayHandle::Android(_) => "Android",
+        RawDisplayHandle::Haiku(_) => "Haiku",
+        _ => "unknown",
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::{choose_surface_format, ColorSpacePreference, FormatPreferences};
     use crate::{
-        swapchain::Surface, Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError,
+        format::Format,
+        swapchain::{ColorSpace, FromWindowError, Surface},
+        Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError,
+    };
+    use raw_window_handle::{
+        DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, OrbitalDisplayHandle,
+        OrbitalWindowHandle, WindowHandle,
     };
-    use std::ptr;
+    use std::{ptr, ptr::NonNull, sync::Arc};
+
+    // Lists of surface formats, in the order that they are reported on some common platforms.
+
+    // Windows, NVIDIA, with HDR enabled in the display settings.
+    const WINDOWS_NVIDIA: &[(Format, ColorSpace)] = &[
+        (Format::B8G8R8A8_UNORM, ColorSpace::SrgbNonLinear),
+        (Format::B8G8R8A8_SRGB, ColorSpace::SrgbNonLinear),
+        (Format::A2B10G10R10_UNORM_PACK32, ColorSpace::SrgbNonLinear),
+        (Format::R16G16B16A16_SFLOAT, ColorSpace::ExtendedSrgbLinear),
+        (Format::A2B10G10R10_UNORM_PACK32, ColorSpace::Hdr10St2084),
+    ];
+
+    // Wayland, Mesa.
+    const WAYLAND_MESA: &[(Format, ColorSpace)] = &[
+        (Format::A2R10G10B10_UNORM_PACK32, ColorSpace::SrgbNonLinear),
+        (Format::A2B10G10R10_UNORM_PACK32, ColorSpace::SrgbNonLinear),
+        (Format::R8G8B8A8_SRGB, ColorSpace::SrgbNonLinear),
+        (Format::R8G8B8A8_UNORM, ColorSpace::SrgbNonLinear),
+        (Format::B8G8R8A8_SRGB, ColorSpace::SrgbNonLinear),
+        (Format::B8G8R8A8_UNORM, ColorSpace::SrgbNonLinear),
+        (Format::R5G6B5_UNORM_PACK16, ColorSpace::SrgbNonLinear),
+    ];
+
+    // macOS, MoltenVK, with `ext_swapchain_colorspace` enabled.
+    const MACOS_MOLTENVK: &[(Format, ColorSpace)] = &[
+        (Format::B8G8R8A8_UNORM, ColorSpace::SrgbNonLinear),
+        (Format::B8G8R8A8_SRGB, ColorSpace::SrgbNonLinear),
+        (Format::R16G16B16A16_SFLOAT, ColorSpace::SrgbNonLinear),
+        (Format::A2B10G10R10_UNORM_PACK32, ColorSpace::SrgbNonLinear),
+        (Format::B8G8R8A8_UNORM, ColorSpace::DisplayP3NonLinear),
+        (Format::R16G16B16A16_SFLOAT, ColorSpace::ExtendedSrgbLinear),
+        (Format::R16G16B16A16_SFLOAT, ColorSpace::Hdr10St2084),
+        (Format::A2B10G10R10_UNORM_PACK32, ColorSpace::Hdr10St2084),
+        (Format::B8G8R8A8_UNORM, ColorSpace::PassThrough),
+    ];
+
+    #[test]
+    fn choose_format() {
+        let choose = |surface_formats: &[(Format, ColorSpace)], preferences: &FormatPreferences| {
+            // Only the UNORM and floating-point formats support storage.
+            choose_surface_format(surface_formats, preferences, |index| {
+                !matches!(
+                    surface_formats[index].0,
+                    Format::B8G8R8A8_SRGB
+                        | Format::R8G8B8A8_SRGB
+                        | Format::A2R10G10B10_UNORM_PACK32
+                )
+            })
+        };
+
+        // By default, an 8-bit sRGB format in the sRGB color space, wherever the surface lists
+        // it.
+        let default = FormatPreferences::default();
+        assert_eq!(
+            choose(WINDOWS_NVIDIA, &default),
+            Some((Format::B8G8R8A8_SRGB, ColorSpace::SrgbNonLinear)),
+        );
+        assert_eq!(
+            choose(WAYLAND_MESA, &default),
+            Some((Format::B8G8R8A8_SRGB, ColorSpace::SrgbNonLinear)),
+        );
+        assert_eq!(
+            choose(MACOS_MOLTENVK, &default),
+            Some((Format::B8G8R8A8_SRGB, ColorSpace::SrgbNonLinear)),
+        );
+
+        // Storage rules out the sRGB formats.
+        let storage = FormatPreferences {
+            require_storage: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            choose(WAYLAND_MESA, &storage),
+            Some((Format::B8G8R8A8_UNORM, ColorSpace::SrgbNonLinear)),
+        );
+        assert_eq!(
+            choose(&WAYLAND_MESA[..3], &storage),
+            Some((Format::A2B10G10R10_UNORM_PACK32, ColorSpace::SrgbNonLinear)),
+        );
+        assert_eq!(choose(&WAYLAND_MESA[..1], &storage), None);
+
+        // An explicitly preferred format wins, but only in the preferred color space.
+        let ten_bit = FormatPreferences {
+            prefer_formats: vec![
+                Format::A2R10G10B10_UNORM_PACK32,
+                Format::A2B10G10R10_UNORM_PACK32,
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            choose(WINDOWS_NVIDIA, &ten_bit),
+            Some((Format::A2B10G10R10_UNORM_PACK32, ColorSpace::SrgbNonLinear)),
+        );
+        assert_eq!(
+            choose(WAYLAND_MESA, &ten_bit),
+            Some((Format::A2R10G10B10_UNORM_PACK32, ColorSpace::SrgbNonLinear)),
+        );
+
+        // HDR10 falls back to the sRGB color space if it isn't supported.
+        let hdr = FormatPreferences {
+            color_space: ColorSpacePreference::Hdr10,
+            prefer_formats: vec![Format::R16G16B16A16_SFLOAT],
+            ..Default::default()
+        };
+        assert_eq!(
+            choose(WINDOWS_NVIDIA, &hdr),
+            Some((Format::A2B10G10R10_UNORM_PACK32, ColorSpace::Hdr10St2084)),
+        );
+        assert_eq!(
+            choose(MACOS_MOLTENVK, &hdr),
+            Some((Format::R16G16B16A16_SFLOAT, ColorSpace::Hdr10St2084)),
+        );
+        assert_eq!(
+            choose(WAYLAND_MESA, &hdr),
+            Some((Format::B8G8R8A8_SRGB, ColorSpace::SrgbNonLinear)),
+        );
+        assert_eq!(
+            choose(
+                WAYLAND_MESA,
+                &FormatPreferences {
+                    require_storage: true,
+                    ..hdr.clone()
+                },
+            ),
+            Some((Format::B8G8R8A8_UNORM, ColorSpace::SrgbNonLinear)),
+        );
+
+        // The preferred formats don't apply to the fallback color space.
+        assert_eq!(
+            choose(&MACOS_MOLTENVK[..4], &hdr),
+            Some((Format::B8G8R8A8_SRGB, ColorSpace::SrgbNonLinear)),
+        );
+
+        let pass_through = FormatPreferences {
+            color_space: ColorSpacePreference::PassThrough,
+            ..Default::default()
+        };
+        assert_eq!(
+            choose(MACOS_MOLTENVK, &pass_through),
+            Some((Format::B8G8R8A8_UNORM, ColorSpace::PassThrough)),
+        );
+    }
+
+    #[test]
+    fn from_window_unsupported_handle() {
+        // Orbital has no Vulkan surface extension.
+        struct OrbitalWindow;
+
+        impl HasWindowHandle for OrbitalWindow {
+            fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+                let handle = OrbitalWindowHandle::new(NonNull::dangling());
+
+                Ok(unsafe { WindowHandle::borrow_raw(handle.into()) })
+            }
+        }
+
+        impl HasDisplayHandle for OrbitalWindow {
+            fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+                let handle = OrbitalDisplayHandle::new();
+
+                Ok(unsafe { DisplayHandle::borrow_raw(handle.into()) })
+            }
+        }
+
+        let instance = instance!();
+        match Surface::from_window(instance, Arc::new(OrbitalWindow)) {
+            Err(FromWindowError::UnsupportedWindowHandle {
+                window: "Orbital",
+                display: "Orbital",
+            }) => {}
+            _ => panic!(),
+        }
+    }
 
     #[test]
     fn khr_win32_surface_ext_missing() {
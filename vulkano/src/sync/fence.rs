@@ -248,6 +248,12 @@ impl Fence {
                 .saturating_add(timeout.subsec_nanos() as u64)
         });
 
+        let hooks = self.device.submission_hooks();
+
+        if let Some(hooks) = &hooks {
+            hooks.on_fence_wait_start(self);
+        }
+
         let result = unsafe {
             let fns = self.device.fns();
             (fns.v1_0.wait_for_fences)(
@@ -259,6 +265,10 @@ impl Fence {
             )
         };
 
+        if let Some(hooks) = &hooks {
+            hooks.on_fence_wait_end(self);
+        }
+
         match result {
             ash::vk::Result::SUCCESS => Ok(()),
             err => Err(VulkanError::from(err)),
@@ -326,6 +336,14 @@ impl Fence {
                 .saturating_add(timeout.subsec_nanos() as u64)
         });
 
+        let hooks = device.submission_hooks();
+
+        if let Some(hooks) = &hooks {
+            for fence in &fences {
+                hooks.on_fence_wait_start(fence);
+            }
+        }
+
         let result = {
             let fns = device.fns();
             (fns.v1_0.wait_for_fences)(
@@ -337,6 +355,12 @@ impl Fence {
             )
         };
 
+        if let Some(hooks) = &hooks {
+            for fence in &fences {
+                hooks.on_fence_wait_end(fence);
+            }
+        }
+
         match result {
             ash::vk::Result::SUCCESS => Ok(()),
             err => Err(VulkanError::from(err)),
@@ -131,16 +131,32 @@ where
     F: GpuFuture,
 {
     /// Returns true if the fence is signaled by the GPU.
+    ///
+    /// If the fence is signaled, this function also cleans any resource locked by previous
+    /// submissions, in the same way as [`wait`](Self::wait).
     pub fn is_signaled(&self) -> Result<bool, VulkanError> {
-        let state = self.state.lock();
+        let mut state = self.state.lock();
 
-        match &*state {
+        let is_signaled = match &*state {
             FenceSignalFutureState::Pending(_, fence)
-            | FenceSignalFutureState::PartiallyFlushed(_, fence)
-            | FenceSignalFutureState::Flushed(_, fence) => fence.is_signaled(),
-            FenceSignalFutureState::Cleaned => Ok(true),
+            | FenceSignalFutureState::PartiallyFlushed(_, fence) => return fence.is_signaled(),
+            FenceSignalFutureState::Flushed(_, fence) => fence.is_signaled()?,
+            FenceSignalFutureState::Cleaned => return Ok(true),
             FenceSignalFutureState::Poisoned => unreachable!(),
+        };
+
+        if is_signaled {
+            // The submission has finished, so the resources that it used can be released now.
+            if let FenceSignalFutureState::Flushed(previous, _) =
+                replace(&mut *state, FenceSignalFutureState::Cleaned)
+            {
+                unsafe {
+                    previous.signal_finished();
+                }
+            }
         }
+
+        Ok(is_signaled)
     }
 
     /// Blocks the current thread until the fence is signaled by the GPU. Performs a flush if
@@ -70,7 +70,9 @@
 //! and want to see the result in another queue. However in some situations you want to start
 //! multiple operations on several different queues.
 //!
-//! TODO: this is not yet implemented
+//! This can be done by turning the future into a [`SharedGpuFuture`] with `shared()`, and cloning
+//! it once for each queue. Each clone can be followed by operations on a different queue, and the
+//! operations all start after the event of the original future.
 //!
 //! # Fences
 //!
@@ -81,11 +83,13 @@
 //!
 //! Signalling a fence is kind of a "terminator" to a chain of futures
 
+pub(crate) use self::semaphore_signal::submit_and_signal;
 pub use self::{
     fence_signal::{FenceSignalFuture, FenceSignalFutureBehavior},
     join::JoinFuture,
     now::{now, NowFuture},
     semaphore_signal::SemaphoreSignalFuture,
+    shared::SharedGpuFuture,
 };
 use super::{fence::Fence, semaphore::Semaphore};
 use crate::{
@@ -93,7 +97,7 @@ use crate::{
     command_buffer::{
         CommandBuffer, CommandBufferExecError, CommandBufferExecFuture,
         CommandBufferResourcesUsage, CommandBufferState, CommandBufferSubmitInfo,
-        CommandBufferUsage, SubmitInfo,
+        CommandBufferUsage, ResourceUseRef, SubmitInfo,
     },
     device::{DeviceOwned, Queue},
     image::{Image, ImageLayout, ImageState},
@@ -115,6 +119,7 @@ mod fence_signal;
 mod join;
 mod now;
 mod semaphore_signal;
+mod shared;
 
 /// Represents an event that will happen on the GPU in the future.
 ///
@@ -334,7 +339,17 @@ pub unsafe trait GpuFuture: DeviceOwned {
     /// You should only ever do this indirectly after a `SwapchainAcquireFuture` of the same image,
     /// otherwise an error will occur when flushing.
     ///
+    /// Calling this several times in a row, for example to present to several windows, presents
+    /// all of the images in one operation. Flushing returns the first error of any of them, and
+    /// the result for each swapchain is available from [`PresentFuture::present_results`].
+    ///
+    /// `queue` doesn't need to be the queue of this future. If it isn't, the present operation
+    /// waits for this future with a semaphore, and transfers the ownership of the image to the
+    /// queue family of `queue` if needed. See [presenting from a different queue] for details.
+    ///
     /// > **Note**: This is just a shortcut for the `Swapchain::present()` function.
+    ///
+    /// [presenting from a different queue]: crate::swapchain#presenting-from-a-different-queue
     #[inline]
     fn then_swapchain_present(
         self,
@@ -347,6 +362,25 @@ pub unsafe trait GpuFuture: DeviceOwned {
         swapchain::present(self, queue, swapchain_info)
     }
 
+    /// Turns the current future into a [`SharedGpuFuture`], which can be cloned so that several
+    /// later operations can depend on it.
+    ///
+    /// This is useful when the result of one submission is used by several later submissions,
+    /// possibly on different queues. The submission is only flushed once, and each clone waits on
+    /// its own semaphore, so there is no need to wait for the submission on the CPU.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `self` is not associated with a queue, such as the future returned by
+    ///   [`now`](crate::sync::now).
+    #[inline]
+    fn shared(self) -> SharedGpuFuture<Self>
+    where
+        Self: Sized,
+    {
+        shared::shared(self)
+    }
+
     /// Turn the current future into a `Box<dyn GpuFuture>`.
     ///
     /// This is a helper function that calls `Box::new(yourFuture) as Box<dyn GpuFuture>`.
@@ -660,16 +694,7 @@ pub(crate) unsafe fn queue_submit(
                         queue,
                     ) {
                         Err(AccessCheckError::Denied(error)) => {
-                            return Err(Box::new(ValidationError {
-                                problem: format!(
-                                    "access to a resource has been denied \
-                                    (resource use: {:?}, error: {})",
-                                    range_usage.first_use, error
-                                )
-                                .into(),
-                                ..Default::default()
-                            })
-                            .into());
+                            return Err(access_denied_error(range_usage.first_use, error));
                         }
                         Err(AccessCheckError::Unknown) => {
                             let result = if range_usage.mutable {
@@ -679,19 +704,19 @@ pub(crate) unsafe fn queue_submit(
                             };
 
                             if let Err(error) = result {
-                                return Err(Box::new(ValidationError {
-                                    problem: format!(
-                                        "access to a resource has been denied \
-                                        (resource use: {:?}, error: {})",
-                                        range_usage.first_use, error
-                                    )
-                                    .into(),
-                                    ..Default::default()
-                                })
-                                .into());
+                                return Err(access_denied_error(range_usage.first_use, error));
+                            }
+                        }
+                        Ok(()) => {
+                            // The future only knows about device accesses, so also make sure
+                            // that the host isn't holding on to the resource, for example
+                            // through a leaked guard.
+                            if let Err(error) =
+                                state.check_gpu_lock(range.clone(), range_usage.mutable)
+                            {
+                                return Err(access_denied_error(range_usage.first_use, error));
                             }
                         }
-                        _ => (),
                     }
                 }
             }
@@ -708,16 +733,7 @@ pub(crate) unsafe fn queue_submit(
                         queue,
                     ) {
                         Err(AccessCheckError::Denied(error)) => {
-                            return Err(Box::new(ValidationError {
-                                problem: format!(
-                                    "access to a resource has been denied \
-                                    (resource use: {:?}, error: {})",
-                                    range_usage.first_use, error
-                                )
-                                .into(),
-                                ..Default::default()
-                            })
-                            .into());
+                            return Err(access_denied_error(range_usage.first_use, error));
                         }
                         Err(AccessCheckError::Unknown) => {
                             let result = if range_usage.mutable {
@@ -727,25 +743,35 @@ pub(crate) unsafe fn queue_submit(
                             };
 
                             if let Err(error) = result {
-                                return Err(Box::new(ValidationError {
-                                    problem: format!(
-                                        "access to a resource has been denied \
-                                        (resource use: {:?}, error: {})",
-                                        range_usage.first_use, error
-                                    )
-                                    .into(),
-                                    ..Default::default()
-                                })
-                                .into());
+                                return Err(access_denied_error(range_usage.first_use, error));
+                            }
+                        }
+                        Ok(()) => {
+                            // The future only knows about device accesses, so also make sure
+                            // that the host isn't holding on to the resource, for example
+                            // through a leaked guard.
+                            if let Err(error) =
+                                state.check_gpu_lock(range.clone(), range_usage.mutable)
+                            {
+                                return Err(access_denied_error(range_usage.first_use, error));
                             }
                         }
-                        _ => (),
                     };
                 }
             }
         }
     }
 
+    // Host accesses of the buffers that the command buffers use can wait for the submission to
+    // finish with a fence, so make sure that there is one.
+    let fence = match fence {
+        Some(fence) => Some(fence),
+        None if tracks_pending_fence(&submit_infos) => {
+            Some(Arc::new(Fence::from_pool(queue.device().clone())?))
+        }
+        None => None,
+    };
+
     queue.with(|mut queue_guard| queue_guard.submit(&submit_infos, fence.as_ref()))?;
 
     for submit_info in &submit_infos {
@@ -768,6 +794,14 @@ pub(crate) unsafe fn queue_submit(
                 .unwrap();
             state.add_queue_submit();
 
+            let track_pending_fence = command_buffer.usage() != CommandBufferUsage::SimultaneousUse;
+
+            if track_pending_fence {
+                if let Some(fence) = &fence {
+                    state.set_pending_fence(fence.clone());
+                }
+            }
+
             let CommandBufferResourcesUsage {
                 buffers,
                 images,
@@ -779,11 +813,17 @@ pub(crate) unsafe fn queue_submit(
                 let state = states.buffers.get_mut(&usage.buffer.handle()).unwrap();
 
                 for (range, range_usage) in usage.ranges.iter() {
+                    // The access was checked before submitting, while holding the same locks.
                     if range_usage.mutable {
-                        state.gpu_write_lock(range.clone());
+                        state.gpu_write_lock(range.clone())
                     } else {
-                        state.gpu_read_lock(range.clone());
+                        state.gpu_read_lock(range.clone())
                     }
+                    .expect("checked before submitting");
+                }
+
+                if track_pending_fence {
+                    state.add_pending_command_buffer(command_buffer.clone());
                 }
             }
 
@@ -791,11 +831,13 @@ pub(crate) unsafe fn queue_submit(
                 let state = states.images.get_mut(&usage.image.handle()).unwrap();
 
                 for (range, range_usage) in usage.ranges.iter() {
+                    // The access was checked before submitting, while holding the same locks.
                     if range_usage.mutable {
-                        state.gpu_write_lock(range.clone(), range_usage.final_layout);
+                        state.gpu_write_lock(range.clone(), range_usage.final_layout)
                     } else {
-                        state.gpu_read_lock(range.clone());
+                        state.gpu_read_lock(range.clone())
                     }
+                    .expect("checked before submitting");
                 }
             }
         }
@@ -804,6 +846,36 @@ pub(crate) unsafe fn queue_submit(
     Ok(())
 }
 
+// Returns whether one of the command buffers can't be pending more than once at a time, and uses
+// buffers, in which case the fence of the submission is recorded in the state of the command
+// buffer.
+fn tracks_pending_fence(submit_infos: &[SubmitInfo]) -> bool {
+    submit_infos
+        .iter()
+        .flat_map(|submit_info| &submit_info.command_buffers)
+        .any(|command_buffer_submit_info| {
+            let command_buffer = &command_buffer_submit_info.command_buffer;
+
+            command_buffer.usage() != CommandBufferUsage::SimultaneousUse
+                && !command_buffer.resources_usage().buffers.is_empty()
+        })
+}
+
+fn access_denied_error(
+    first_use: Option<ResourceUseRef>,
+    error: impl Display,
+) -> Validated<VulkanError> {
+    Box::new(ValidationError {
+        problem: format!(
+            "access to a resource has been denied (resource use: {:?}, error: {})",
+            first_use, error
+        )
+        .into(),
+        ..Default::default()
+    })
+    .into()
+}
+
 // This struct exists to ensure that every object gets locked exactly once.
 // Otherwise we get deadlocks.
 #[derive(Debug)]
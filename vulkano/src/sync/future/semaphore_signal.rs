@@ -1,7 +1,7 @@
 use super::{queue_present, AccessCheckError, GpuFuture, SubmitAnyBuilder};
 use crate::{
     buffer::Buffer,
-    command_buffer::{SemaphoreSubmitInfo, SubmitInfo},
+    command_buffer::{CommandBufferSubmitInfo, SemaphoreSubmitInfo, SubmitInfo},
     device::{Device, DeviceOwned, Queue},
     image::{Image, ImageLayout},
     swapchain::Swapchain,
@@ -80,112 +80,7 @@ where
                 return Ok(());
             }
 
-            let queue = self.previous.queue().unwrap();
-
-            match self.previous.build_submission()? {
-                SubmitAnyBuilder::Empty => {
-                    queue_submit(
-                        &queue,
-                        SubmitInfo {
-                            signal_semaphores: vec![SemaphoreSubmitInfo::new(
-                                self.semaphore.clone(),
-                            )],
-                            ..Default::default()
-                        },
-                        None,
-                        &self.previous,
-                    )?;
-                }
-                SubmitAnyBuilder::SemaphoresWait(semaphores) => {
-                    queue_submit(
-                        &queue,
-                        SubmitInfo {
-                            wait_semaphores: semaphores
-                                .into_iter()
-                                .map(|semaphore| {
-                                    SemaphoreSubmitInfo {
-                                        // TODO: correct stages ; hard
-                                        stages: PipelineStages::ALL_COMMANDS,
-                                        ..SemaphoreSubmitInfo::new(semaphore)
-                                    }
-                                })
-                                .collect(),
-                            signal_semaphores: vec![SemaphoreSubmitInfo::new(
-                                self.semaphore.clone(),
-                            )],
-                            ..Default::default()
-                        },
-                        None,
-                        &self.previous,
-                    )?;
-                }
-                SubmitAnyBuilder::CommandBuffer(mut submit_info, fence) => {
-                    debug_assert!(submit_info.signal_semaphores.is_empty());
-
-                    submit_info
-                        .signal_semaphores
-                        .push(SemaphoreSubmitInfo::new(self.semaphore.clone()));
-
-                    queue_submit(&queue, submit_info, fence, &self.previous)?;
-                }
-                SubmitAnyBuilder::BindSparse(_, _) => {
-                    unimplemented!() // TODO: how to do that?
-                                     /*debug_assert_eq!(builder.num_signal_semaphores(), 0);
-                                     builder.add_signal_semaphore(&self.semaphore);
-                                     builder.submit(&queue)?;*/
-                }
-                SubmitAnyBuilder::QueuePresent(present_info) => {
-                    for swapchain_info in &present_info.swapchains {
-                        if swapchain_info.present_id.map_or(false, |present_id| {
-                            !swapchain_info.swapchain.try_claim_present_id(present_id)
-                        }) {
-                            return Err(Box::new(ValidationError {
-                                problem: "the provided `present_id` was not greater than any \
-                                    `present_id` passed previously for the same swapchain"
-                                    .into(),
-                                vuids: &["VUID-VkPresentIdKHR-presentIds-04999"],
-                                ..Default::default()
-                            })
-                            .into());
-                        }
-
-                        match self.previous.check_swapchain_image_acquired(
-                            &swapchain_info.swapchain,
-                            swapchain_info.image_index,
-                            true,
-                        ) {
-                            Ok(_) => (),
-                            Err(AccessCheckError::Unknown) => {
-                                return Err(Box::new(ValidationError::from_error(
-                                    AccessError::SwapchainImageNotAcquired,
-                                ))
-                                .into());
-                            }
-                            Err(AccessCheckError::Denied(err)) => {
-                                return Err(Box::new(ValidationError::from_error(err)).into());
-                            }
-                        }
-                    }
-
-                    queue_present(&queue, present_info)?
-                        .map(|r| r.map(|_| ()))
-                        .fold(Ok(()), Result::and)?;
-
-                    // FIXME: problematic because if we return an error and flush() is called again,
-                    // then we'll submit the present twice
-                    queue_submit(
-                        &queue,
-                        SubmitInfo {
-                            signal_semaphores: vec![SemaphoreSubmitInfo::new(
-                                self.semaphore.clone(),
-                            )],
-                            ..Default::default()
-                        },
-                        None,
-                        &self.previous,
-                    )?;
-                }
-            };
+            submit_and_signal(&self.previous, Vec::new(), &[self.semaphore.clone()])?;
 
             // Only write `true` here in order to try again next time if an error occurs.
             *wait_submitted = true;
@@ -242,6 +137,124 @@ where
     }
 }
 
+/// Submits the submission of `previous`, followed by executing `command_buffers` and signaling
+/// `semaphores`, on the queue of `previous`.
+pub(crate) unsafe fn submit_and_signal(
+    previous: &dyn GpuFuture,
+    command_buffers: Vec<CommandBufferSubmitInfo>,
+    semaphores: &[Arc<Semaphore>],
+) -> Result<(), Validated<VulkanError>> {
+    let signal_semaphores: Vec<_> = semaphores
+        .iter()
+        .cloned()
+        .map(SemaphoreSubmitInfo::new)
+        .collect();
+    let queue = previous.queue().unwrap();
+
+    match previous.build_submission()? {
+        SubmitAnyBuilder::Empty => {
+            queue_submit(
+                &queue,
+                SubmitInfo {
+                    command_buffers,
+                    signal_semaphores,
+                    ..Default::default()
+                },
+                None,
+                previous,
+            )?;
+        }
+        SubmitAnyBuilder::SemaphoresWait(semaphores) => {
+            queue_submit(
+                &queue,
+                SubmitInfo {
+                    wait_semaphores: semaphores
+                        .into_iter()
+                        .map(|semaphore| {
+                            SemaphoreSubmitInfo {
+                                // TODO: correct stages ; hard
+                                stages: PipelineStages::ALL_COMMANDS,
+                                ..SemaphoreSubmitInfo::new(semaphore)
+                            }
+                        })
+                        .collect(),
+                    command_buffers,
+                    signal_semaphores,
+                    ..Default::default()
+                },
+                None,
+                previous,
+            )?;
+        }
+        SubmitAnyBuilder::CommandBuffer(mut submit_info, fence) => {
+            debug_assert!(submit_info.signal_semaphores.is_empty());
+
+            submit_info.command_buffers.extend(command_buffers);
+            submit_info.signal_semaphores.extend(signal_semaphores);
+
+            queue_submit(&queue, submit_info, fence, previous)?;
+        }
+        SubmitAnyBuilder::BindSparse(_, _) => {
+            unimplemented!() // TODO: how to do that?
+                             /*debug_assert_eq!(builder.num_signal_semaphores(), 0);
+                             builder.add_signal_semaphore(&self.semaphore);
+                             builder.submit(&queue)?;*/
+        }
+        SubmitAnyBuilder::QueuePresent(present_info) => {
+            for swapchain_info in &present_info.swapchains {
+                if swapchain_info.present_id.map_or(false, |present_id| {
+                    !swapchain_info.swapchain.try_claim_present_id(present_id)
+                }) {
+                    return Err(Box::new(ValidationError {
+                        problem: "the provided `present_id` was not greater than any \
+                            `present_id` passed previously for the same swapchain"
+                            .into(),
+                        vuids: &["VUID-VkPresentIdKHR-presentIds-04999"],
+                        ..Default::default()
+                    })
+                    .into());
+                }
+
+                match previous.check_swapchain_image_acquired(
+                    &swapchain_info.swapchain,
+                    swapchain_info.image_index,
+                    true,
+                ) {
+                    Ok(_) => (),
+                    Err(AccessCheckError::Unknown) => {
+                        return Err(Box::new(ValidationError::from_error(
+                            AccessError::SwapchainImageNotAcquired,
+                        ))
+                        .into());
+                    }
+                    Err(AccessCheckError::Denied(err)) => {
+                        return Err(Box::new(ValidationError::from_error(err)).into());
+                    }
+                }
+            }
+
+            queue_present(&queue, present_info)?
+                .map(|r| r.map(|_| ()))
+                .fold(Ok(()), Result::and)?;
+
+            // FIXME: problematic because if we return an error and flush() is called again,
+            // then we'll submit the present twice
+            queue_submit(
+                &queue,
+                SubmitInfo {
+                    command_buffers,
+                    signal_semaphores,
+                    ..Default::default()
+                },
+                None,
+                previous,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
 unsafe impl<F> DeviceOwned for SemaphoreSignalFuture<F>
 where
     F: GpuFuture,
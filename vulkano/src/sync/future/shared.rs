@@ -0,0 +1,370 @@
+use super::{semaphore_signal::submit_and_signal, AccessCheckError, GpuFuture, SubmitAnyBuilder};
+use crate::{
+    buffer::Buffer,
+    device::{Device, DeviceOwned, Queue},
+    image::{Image, ImageLayout},
+    swapchain::Swapchain,
+    sync::semaphore::Semaphore,
+    DeviceSize, Validated, VulkanError,
+};
+use parking_lot::Mutex;
+use smallvec::smallvec;
+use std::{
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+/// Builds a new shared future.
+///
+/// # Panics
+///
+/// - Panics if `future` is not associated with a queue, such as the future returned by
+///   [`now`](crate::sync::now).
+pub fn shared<F>(future: F) -> SharedGpuFuture<F>
+where
+    F: GpuFuture,
+{
+    // The semaphores are signaled by a submission on that queue, and the queue is waited on when
+    // the last clone is dropped.
+    assert!(
+        future.queue().is_some(),
+        "a future must be associated with a queue to be shared",
+    );
+
+    SharedGpuFuture {
+        inner: Arc::new(SharedInner {
+            previous: future,
+            state: Mutex::new(SharedState {
+                flushed: false,
+                semaphores: Vec::new(),
+            }),
+            finished: AtomicBool::new(false),
+        }),
+        semaphore: Mutex::new(None),
+    }
+}
+
+/// Represents an event that several later operations can depend on.
+///
+/// A `SharedGpuFuture` is created with [`GpuFuture::shared`], and can be cloned. Every clone can
+/// be used as the dependency of a different chain of futures, and can be joined with other
+/// futures. The submission of the original future is flushed only once, when the first clone is
+/// flushed or submitted after.
+///
+/// When the submission is flushed, one semaphore is signaled for each clone that exists at that
+/// point, so that every one of them can be followed by operations on any queue. Clones that are
+/// created after the submission was flushed can only be followed by operations on the same queue
+/// as the submission.
+///
+/// Because the operations that follow each clone can execute at the same time, the clones only
+/// grant shared access to the resources that the original future grants access to. Writing to
+/// these resources, or changing the layout of these images, after a clone is not possible until
+/// the original submission has finished.
+#[must_use = "Dropping this object will immediately block the thread until the GPU has finished \
+              processing the submission"]
+pub struct SharedGpuFuture<F>
+where
+    F: GpuFuture,
+{
+    inner: Arc<SharedInner<F>>,
+    // The semaphore that this clone claimed for the operations that follow it.
+    semaphore: Mutex<Option<Arc<Semaphore>>>,
+}
+
+struct SharedInner<F>
+where
+    F: GpuFuture,
+{
+    previous: F,
+    state: Mutex<SharedState>,
+    finished: AtomicBool,
+}
+
+struct SharedState {
+    // True if the submission has already been flushed.
+    flushed: bool,
+    // The semaphores that were signaled when the submission was flushed, and that no clone has
+    // claimed yet.
+    semaphores: Vec<Arc<Semaphore>>,
+}
+
+impl<F> Clone for SharedGpuFuture<F>
+where
+    F: GpuFuture,
+{
+    fn clone(&self) -> Self {
+        SharedGpuFuture {
+            inner: self.inner.clone(),
+            semaphore: Mutex::new(None),
+        }
+    }
+}
+
+unsafe impl<F> GpuFuture for SharedGpuFuture<F>
+where
+    F: GpuFuture,
+{
+    fn cleanup_finished(&mut self) {
+        // The previous future can only be cleaned up when no other clone can access it.
+        if let Some(inner) = Arc::get_mut(&mut self.inner) {
+            inner.previous.cleanup_finished();
+        }
+    }
+
+    unsafe fn build_submission(&self) -> Result<SubmitAnyBuilder, Validated<VulkanError>> {
+        self.flush()?;
+
+        let mut semaphore = self.semaphore.lock();
+
+        if semaphore.is_none() {
+            *semaphore = self.inner.state.lock().semaphores.pop();
+        }
+
+        Ok(match &*semaphore {
+            Some(semaphore) => SubmitAnyBuilder::SemaphoresWait(smallvec![semaphore.clone()]),
+            // The submission was flushed before this clone was created, so the operations that
+            // follow it must be on the same queue.
+            None => SubmitAnyBuilder::Empty,
+        })
+    }
+
+    fn flush(&self) -> Result<(), Validated<VulkanError>> {
+        self.inner.flush(Arc::strong_count(&self.inner))
+    }
+
+    unsafe fn signal_finished(&self) {
+        self.inner.signal_finished();
+    }
+
+    fn queue_change_allowed(&self) -> bool {
+        if self.semaphore.lock().is_some() {
+            return true;
+        }
+
+        let state = self.inner.state.lock();
+        !state.flushed || !state.semaphores.is_empty()
+    }
+
+    fn queue(&self) -> Option<Arc<Queue>> {
+        self.inner.previous.queue()
+    }
+
+    fn check_buffer_access(
+        &self,
+        buffer: &Buffer,
+        range: Range<DeviceSize>,
+        exclusive: bool,
+        queue: &Queue,
+    ) -> Result<(), AccessCheckError> {
+        if exclusive {
+            return Err(AccessCheckError::Unknown);
+        }
+
+        self.inner
+            .previous
+            .check_buffer_access(buffer, range, exclusive, queue)
+    }
+
+    fn check_image_access(
+        &self,
+        image: &Image,
+        range: Range<DeviceSize>,
+        exclusive: bool,
+        expected_layout: ImageLayout,
+        queue: &Queue,
+    ) -> Result<(), AccessCheckError> {
+        if exclusive {
+            return Err(AccessCheckError::Unknown);
+        }
+
+        self.inner
+            .previous
+            .check_image_access(image, range, exclusive, expected_layout, queue)
+    }
+
+    #[inline]
+    fn check_swapchain_image_acquired(
+        &self,
+        _swapchain: &Swapchain,
+        _image_index: u32,
+        _before: bool,
+    ) -> Result<(), AccessCheckError> {
+        // Only one of the clones could present the image.
+        Err(AccessCheckError::Unknown)
+    }
+}
+
+unsafe impl<F> DeviceOwned for SharedGpuFuture<F>
+where
+    F: GpuFuture,
+{
+    fn device(&self) -> &Arc<Device> {
+        self.inner.previous.device()
+    }
+}
+
+impl<F> SharedInner<F>
+where
+    F: GpuFuture,
+{
+    fn flush(&self, semaphore_count: usize) -> Result<(), Validated<VulkanError>> {
+        let mut state = self.state.lock();
+
+        if state.flushed {
+            return Ok(());
+        }
+
+        // These semaphores may never be waited on if a clone is dropped without being used, so
+        // they can't be put back in the pool.
+        let semaphores = (0..semaphore_count)
+            .map(|_| {
+                Semaphore::new(self.previous.device().clone(), Default::default()).map(Arc::new)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        unsafe { submit_and_signal(&self.previous, Vec::new(), &semaphores)? };
+
+        // Only write `true` here in order to try again next time if an error occurs.
+        state.flushed = true;
+        state.semaphores = semaphores;
+
+        Ok(())
+    }
+
+    unsafe fn signal_finished(&self) {
+        if !self.finished.swap(true, Ordering::SeqCst) {
+            self.previous.signal_finished();
+        }
+    }
+}
+
+impl<F> Drop for SharedInner<F>
+where
+    F: GpuFuture,
+{
+    fn drop(&mut self) {
+        if !*self.finished.get_mut() && !thread::panicking() {
+            // If the submission can't be flushed, it never reached the queue, so there is nothing
+            // to wait for here. `previous` is dropped right after and cleans up after itself, the
+            // same as if it had never been shared.
+            if self.flush(0).is_err() {
+                return;
+            }
+
+            // Block until the queue finished. `shared` checked that there is a queue.
+            self.previous
+                .queue()
+                .unwrap()
+                .with(|mut q| q.wait_idle())
+                .unwrap();
+
+            unsafe {
+                self.signal_finished();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        buffer::{Buffer, BufferCreateInfo, BufferUsage},
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, CommandBufferBeginInfo, CommandBufferLevel,
+            CommandBufferUsage, CopyBufferInfo, RecordingCommandBuffer,
+        },
+        memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
+        sync::{now, GpuFuture},
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn shared_by_two_submissions() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+
+        let new_buffer = || {
+            Buffer::new_slice::<u32>(
+                memory_allocator.clone(),
+                BufferCreateInfo {
+                    usage: BufferUsage::TRANSFER_SRC | BufferUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                        | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                    ..Default::default()
+                },
+                4,
+            )
+            .unwrap()
+        };
+        let begin = || {
+            RecordingCommandBuffer::new(
+                cb_allocator.clone(),
+                queue.queue_family_index(),
+                CommandBufferLevel::Primary,
+                CommandBufferBeginInfo {
+                    usage: CommandBufferUsage::OneTimeSubmit,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+
+        let src = new_buffer();
+        let mut cbb = begin();
+        cbb.fill_buffer(src.clone(), 42).unwrap();
+        let fill = cbb.end().unwrap();
+
+        let shared = now(device)
+            .then_execute(queue.clone(), fill)
+            .unwrap()
+            .shared();
+
+        // Both copies only read the buffer that was filled, so they can depend on the same
+        // submission.
+        let copy = |dst| {
+            let mut cbb = begin();
+            cbb.copy_buffer(CopyBufferInfo::buffers(src.clone(), dst))
+                .unwrap();
+            cbb.end().unwrap()
+        };
+        let (dst_a, dst_b) = (new_buffer(), new_buffer());
+        let future_a = shared
+            .clone()
+            .then_execute(queue.clone(), copy(dst_a.clone()))
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap();
+        let future_b = shared
+            .then_execute(queue, copy(dst_b.clone()))
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap();
+
+        future_a.wait(None).unwrap();
+        future_b.wait(None).unwrap();
+
+        assert_eq!(&*dst_a.read().unwrap(), &[42; 4]);
+        assert_eq!(&*dst_b.read().unwrap(), &[42; 4]);
+    }
+
+    #[test]
+    fn shared_requires_queue() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        assert_should_panic!("associated with a queue", {
+            let _ = now(device).shared();
+        });
+    }
+}
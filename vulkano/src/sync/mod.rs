@@ -17,6 +17,7 @@ pub use self::{
     },
 };
 use crate::{device::Queue, VulkanError};
+use smallvec::SmallVec;
 use std::{
     error::Error,
     fmt::{Display, Formatter},
@@ -71,9 +72,35 @@ where
     /// The resource is used is only one queue family.
     Exclusive,
     /// The resource is used in multiple queue families. Can be slower than `Exclusive`.
+    ///
+    /// The list must contain at least two queue family indices, each of which must exist on the
+    /// physical device and appear only once. Resources with this sharing mode never need a queue
+    /// family ownership transfer to be used by a queue of one of these families.
     Concurrent(I),
 }
 
+impl Sharing<SmallVec<[u32; 4]>> {
+    /// Returns the sharing mode for a resource that is used by `queues`.
+    ///
+    /// The queue family indices of the queues are deduplicated. If only one queue family remains,
+    /// or `queues` is empty, `Exclusive` is returned.
+    pub fn from_queues(queues: &[&Arc<Queue>]) -> Self {
+        let mut queue_family_indices = SmallVec::<[u32; 4]>::new();
+
+        for queue in queues {
+            if !queue_family_indices.contains(&queue.queue_family_index()) {
+                queue_family_indices.push(queue.queue_family_index());
+            }
+        }
+
+        if queue_family_indices.len() < 2 {
+            Sharing::Exclusive
+        } else {
+            Sharing::Concurrent(queue_family_indices)
+        }
+    }
+}
+
 /// How the memory of a resource is currently being accessed.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum CurrentAccess {
@@ -98,6 +125,10 @@ pub enum HostAccessError {
     Unmanaged,
     NotHostMapped,
     OutOfMappedRange,
+    Unbound,
+    Timeout,
+    Wait(VulkanError),
+    Retired,
 }
 
 impl Error for HostAccessError {
@@ -105,6 +136,7 @@ impl Error for HostAccessError {
         match self {
             Self::AccessConflict(err) => Some(err),
             Self::Invalidate(err) => Some(err),
+            Self::Wait(err) => Some(err),
             _ => None,
         }
     }
@@ -125,6 +157,23 @@ impl Display for HostAccessError {
                 f,
                 "the requested range is not within the currently mapped range of device memory",
             ),
+            HostAccessError::Unbound => write!(
+                f,
+                "the requested range of the resource does not have memory bound to it",
+            ),
+            HostAccessError::Timeout => write!(
+                f,
+                "the timeout was reached before the device released the resource",
+            ),
+            HostAccessError::Wait(_) => write!(
+                f,
+                "waiting for the device to finish using the resource failed",
+            ),
+            HostAccessError::Retired => write!(
+                f,
+                "the subbuffer was allocated during a frame that has been retired, or before its \
+                allocator was reset, and its memory may have been handed out again",
+            ),
         }
     }
 }
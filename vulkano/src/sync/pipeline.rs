@@ -1,7 +1,7 @@
 use crate::{
-    buffer::Buffer,
+    buffer::{Buffer, Subbuffer},
     descriptor_set::layout::DescriptorType,
-    device::{Device, QueueFlags},
+    device::{Device, DeviceOwned, QueueFlags},
     image::{
         Image, ImageAspects, ImageCreateFlags, ImageLayout, ImageSubresourceRange, ImageUsage,
     },
@@ -2402,6 +2402,71 @@ impl BufferMemoryBarrier {
         }
     }
 
+    /// Returns a barrier that makes writes to `subbuffer` from storage buffers in a compute
+    /// shader visible to reads from storage buffers in a later compute shader.
+    #[inline]
+    pub fn compute_write_to_compute_read(subbuffer: Subbuffer<impl ?Sized>) -> Self {
+        Self::from_pipeline_stage_access(
+            subbuffer,
+            PipelineStageAccessFlags::ComputeShader_ShaderStorageWrite,
+            PipelineStageAccessFlags::ComputeShader_ShaderStorageRead,
+        )
+    }
+
+    /// Returns a barrier that makes writes to `subbuffer` from storage buffers in a compute
+    /// shader visible to a later indirect draw or dispatch command that reads its parameters
+    /// from `subbuffer`.
+    #[inline]
+    pub fn compute_write_to_indirect_read(subbuffer: Subbuffer<impl ?Sized>) -> Self {
+        Self::from_pipeline_stage_access(
+            subbuffer,
+            PipelineStageAccessFlags::ComputeShader_ShaderStorageWrite,
+            PipelineStageAccessFlags::DrawIndirect_IndirectCommandRead,
+        )
+    }
+
+    /// Returns a barrier that makes writes to `subbuffer` from storage buffers in a compute
+    /// shader visible to a later draw command that uses `subbuffer` as a vertex buffer.
+    #[inline]
+    pub fn compute_write_to_vertex_read(subbuffer: Subbuffer<impl ?Sized>) -> Self {
+        Self::from_pipeline_stage_access(
+            subbuffer,
+            PipelineStageAccessFlags::ComputeShader_ShaderStorageWrite,
+            PipelineStageAccessFlags::VertexAttributeInput_VertexAttributeRead,
+        )
+    }
+
+    /// Returns a barrier that makes writes to `subbuffer` from a copy command visible to a later
+    /// draw command that uses `subbuffer` as a vertex buffer.
+    #[inline]
+    pub fn transfer_dst_to_vertex_read(subbuffer: Subbuffer<impl ?Sized>) -> Self {
+        Self::from_pipeline_stage_access(
+            subbuffer,
+            PipelineStageAccessFlags::Copy_TransferWrite,
+            PipelineStageAccessFlags::VertexAttributeInput_VertexAttributeRead,
+        )
+    }
+
+    // Uses the same stages and accesses as the automatic synchronization of command buffers
+    // would, replacing the ones that the device doesn't support.
+    fn from_pipeline_stage_access(
+        subbuffer: Subbuffer<impl ?Sized>,
+        src: PipelineStageAccessFlags,
+        dst: PipelineStageAccessFlags,
+    ) -> Self {
+        let device = subbuffer.device();
+        let range = subbuffer.offset()..subbuffer.offset() + subbuffer.size();
+
+        Self {
+            src_stages: PipelineStages::from(src).into_supported(device),
+            src_access: AccessFlags::from(src).into_supported(device),
+            dst_stages: PipelineStages::from(dst).into_supported(device),
+            dst_access: AccessFlags::from(dst).into_supported(device),
+            range,
+            ..Self::buffer(subbuffer.buffer().clone())
+        }
+    }
+
     pub(crate) fn validate(&self, device: &Device) -> Result<(), Box<ValidationError>> {
         let &Self {
             src_stages,
@@ -3078,6 +3143,130 @@ impl ImageMemoryBarrier {
         }
     }
 
+    /// Returns a barrier that discards the contents of `subresource_range` of `image`, and
+    /// transitions it to be written by a later copy command.
+    #[inline]
+    pub fn undefined_to_transfer_dst(
+        image: Arc<Image>,
+        subresource_range: ImageSubresourceRange,
+    ) -> Self {
+        Self::from_pipeline_stage_access(
+            image,
+            subresource_range,
+            (PipelineStageAccessFlags::empty(), ImageLayout::Undefined),
+            (
+                PipelineStageAccessFlags::Copy_TransferWrite,
+                ImageLayout::TransferDstOptimal,
+            ),
+        )
+    }
+
+    /// Returns a barrier that makes writes to `subresource_range` of `image` from a copy command
+    /// visible to sampling in a later fragment shader.
+    #[inline]
+    pub fn transfer_dst_to_sampled(
+        image: Arc<Image>,
+        subresource_range: ImageSubresourceRange,
+    ) -> Self {
+        Self::from_pipeline_stage_access(
+            image,
+            subresource_range,
+            (
+                PipelineStageAccessFlags::Copy_TransferWrite,
+                ImageLayout::TransferDstOptimal,
+            ),
+            (
+                PipelineStageAccessFlags::FragmentShader_ShaderSampledRead,
+                ImageLayout::ShaderReadOnlyOptimal,
+            ),
+        )
+    }
+
+    /// Returns a barrier that makes writes to `subresource_range` of `image` as a color
+    /// attachment visible to sampling in a later fragment shader.
+    #[inline]
+    pub fn color_attachment_to_sampled(
+        image: Arc<Image>,
+        subresource_range: ImageSubresourceRange,
+    ) -> Self {
+        Self::from_pipeline_stage_access(
+            image,
+            subresource_range,
+            (
+                PipelineStageAccessFlags::ColorAttachmentOutput_ColorAttachmentWrite,
+                ImageLayout::ColorAttachmentOptimal,
+            ),
+            (
+                PipelineStageAccessFlags::FragmentShader_ShaderSampledRead,
+                ImageLayout::ShaderReadOnlyOptimal,
+            ),
+        )
+    }
+
+    /// Returns a barrier that makes writes to `subresource_range` of `image` as a color
+    /// attachment visible to a later copy command that reads from it.
+    #[inline]
+    pub fn color_attachment_to_transfer_src(
+        image: Arc<Image>,
+        subresource_range: ImageSubresourceRange,
+    ) -> Self {
+        Self::from_pipeline_stage_access(
+            image,
+            subresource_range,
+            (
+                PipelineStageAccessFlags::ColorAttachmentOutput_ColorAttachmentWrite,
+                ImageLayout::ColorAttachmentOptimal,
+            ),
+            (
+                PipelineStageAccessFlags::Copy_TransferRead,
+                ImageLayout::TransferSrcOptimal,
+            ),
+        )
+    }
+
+    /// Returns a barrier that makes writes to `subresource_range` of `image` from storage images
+    /// in a compute shader visible to sampling in a later fragment shader.
+    #[inline]
+    pub fn compute_write_to_sampled(
+        image: Arc<Image>,
+        subresource_range: ImageSubresourceRange,
+    ) -> Self {
+        Self::from_pipeline_stage_access(
+            image,
+            subresource_range,
+            (
+                PipelineStageAccessFlags::ComputeShader_ShaderStorageWrite,
+                ImageLayout::General,
+            ),
+            (
+                PipelineStageAccessFlags::FragmentShader_ShaderSampledRead,
+                ImageLayout::ShaderReadOnlyOptimal,
+            ),
+        )
+    }
+
+    // Uses the same stages and accesses as the automatic synchronization of command buffers
+    // would, replacing the ones that the device doesn't support.
+    fn from_pipeline_stage_access(
+        image: Arc<Image>,
+        subresource_range: ImageSubresourceRange,
+        (src, old_layout): (PipelineStageAccessFlags, ImageLayout),
+        (dst, new_layout): (PipelineStageAccessFlags, ImageLayout),
+    ) -> Self {
+        let device = image.device();
+
+        Self {
+            src_stages: PipelineStages::from(src).into_supported(device),
+            src_access: AccessFlags::from(src).into_supported(device),
+            dst_stages: PipelineStages::from(dst).into_supported(device),
+            dst_access: AccessFlags::from(dst).into_supported(device),
+            old_layout,
+            new_layout,
+            subresource_range,
+            ..Self::image(image)
+        }
+    }
+
     pub(crate) fn validate(&self, device: &Device) -> Result<(), Box<ValidationError>> {
         let &Self {
             src_stages,
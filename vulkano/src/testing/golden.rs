@@ -0,0 +1,373 @@
+//! Comparing rendered images against reference images stored on disk.
+//!
+//! A golden image is a PNG file that contains the expected result of rendering a known scene.
+//! A test renders the scene, reads the result back with [`read_image_layer`], and passes the data
+//! to [`assert_image_matches`], which compares it against the golden image:
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use vulkano::{device::Queue, image::Image};
+//! use vulkano::testing::golden::{assert_image_matches, read_image_layer};
+//!
+//! # let queue: Arc<Queue> = return;
+//! # let image: Arc<Image> = return;
+//! let [width, height, _] = image.extent();
+//! let data = read_image_layer(&queue, &image, 0);
+//!
+//! assert_image_matches(&data, width, height, "tests/golden/scene.png", [1; 4]);
+//! ```
+//!
+//! If the [`UPDATE_GOLDEN_ENV`] environment variable is set, the image is written to the golden
+//! path instead of being compared. This is also how a golden image is created for a new test. The
+//! new golden image should then be inspected, and committed along with the test. A missing golden
+//! image is an error otherwise, so that a test can't pass without checking anything.
+//!
+//! When the comparison fails, two images are written next to the golden image to help figuring
+//! out what went wrong: `<name>.actual.png` contains the rendered image, and `<name>.diff.png`
+//! contains the absolute difference of each texel that is outside the tolerance, with all other
+//! texels black.
+
+use crate::{
+    buffer::{Buffer, BufferCreateInfo, BufferUsage},
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, BufferImageCopy, CommandBufferBeginInfo,
+        CommandBufferLevel, CommandBufferUsage, CopyImageToBufferInfo, RecordingCommandBuffer,
+    },
+    device::{DeviceOwned, Queue},
+    format::Format,
+    image::{Image, ImageSubresourceLayers, ImageUsage},
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
+    sync::{self, GpuFuture},
+    DeviceSize,
+};
+use std::{
+    env,
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// The environment variable that makes [`assert_image_matches`] overwrite golden images.
+///
+/// If set, the image that is passed to [`assert_image_matches`] is written to the golden path,
+/// regardless of whether a golden image already exists.
+pub const UPDATE_GOLDEN_ENV: &str = "VULKANO_UPDATE_GOLDEN";
+
+/// Copies an array layer of the first mip level of `image` to the host, and returns its texels.
+///
+/// The image must have an `R8G8B8A8` format with the `TRANSFER_SRC` usage, and must not be in use
+/// by the device. The returned data is tightly packed, one row after the other, and can be passed
+/// to [`assert_image_matches`].
+///
+/// # Panics
+///
+/// - Panics if `image` doesn't have an `R8G8B8A8_UNORM`, `R8G8B8A8_SRGB` or `R8G8B8A8_UINT`
+///   format.
+/// - Panics if `array_layer` is not less than the number of array layers of `image`.
+/// - Panics if copying the image or reading the copy fails.
+#[track_caller]
+pub fn read_image_layer(queue: &Arc<Queue>, image: &Arc<Image>, array_layer: u32) -> Vec<u8> {
+    assert!(
+        matches!(
+            image.format(),
+            Format::R8G8B8A8_UNORM | Format::R8G8B8A8_SRGB | Format::R8G8B8A8_UINT,
+        ),
+        "the format of `image` must be an `R8G8B8A8` format",
+    );
+    assert!(
+        image.usage().intersects(ImageUsage::TRANSFER_SRC),
+        "the usage of `image` must contain `ImageUsage::TRANSFER_SRC`",
+    );
+    assert!(
+        array_layer < image.array_layers(),
+        "`array_layer` must be less than the number of array layers of `image`",
+    );
+
+    let device = queue.device();
+    let [width, height, depth] = image.extent();
+    let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+    let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+        device.clone(),
+        Default::default(),
+    ));
+
+    let buffer = Buffer::new_slice::<u8>(
+        memory_allocator,
+        BufferCreateInfo {
+            usage: BufferUsage::TRANSFER_DST,
+            ..Default::default()
+        },
+        AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+            ..Default::default()
+        },
+        width as DeviceSize * height as DeviceSize * depth as DeviceSize * 4,
+    )
+    .unwrap();
+
+    let mut builder = RecordingCommandBuffer::new(
+        command_buffer_allocator,
+        queue.queue_family_index(),
+        CommandBufferLevel::Primary,
+        CommandBufferBeginInfo {
+            usage: CommandBufferUsage::OneTimeSubmit,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    builder
+        .copy_image_to_buffer(CopyImageToBufferInfo {
+            regions: [BufferImageCopy {
+                image_subresource: ImageSubresourceLayers {
+                    array_layers: array_layer..array_layer + 1,
+                    ..image.subresource_layers()
+                },
+                image_extent: image.extent(),
+                ..Default::default()
+            }]
+            .into(),
+            ..CopyImageToBufferInfo::image_buffer(image.clone(), buffer.clone())
+        })
+        .unwrap();
+    let command_buffer = builder.end().unwrap();
+
+    sync::now(device.clone())
+        .then_execute(queue.clone(), command_buffer)
+        .unwrap()
+        .then_signal_fence_and_flush()
+        .unwrap()
+        .wait(None)
+        .unwrap();
+
+    let data = buffer.read().unwrap();
+
+    data.to_vec()
+}
+
+/// Asserts that an image matches the golden image at `golden_path`, allowing each component of
+/// each texel to differ by at most the corresponding element of `tolerance`.
+///
+/// `image_bytes` must contain `width * height` tightly packed texels, with 8-bit red, green, blue
+/// and alpha components, as returned by [`read_image_layer`]. The golden image must be an 8-bit
+/// RGBA PNG file.
+///
+/// If the [`UPDATE_GOLDEN_ENV`] environment variable is set, the image is written to
+/// `golden_path` instead, and the assertion succeeds.
+///
+/// # Panics
+///
+/// - Panics if `image_bytes` doesn't contain exactly `width * height * 4` bytes.
+/// - Panics if no file exists at `golden_path`, unless [`UPDATE_GOLDEN_ENV`] is set.
+/// - Panics if the golden image couldn't be read or written, or if it isn't an 8-bit RGBA image.
+/// - Panics if the golden image has a different size than `width` and `height`, or if any of the
+///   components differs by more than the tolerance. In that case, the actual image and a
+///   difference image are written next to the golden image before panicking.
+#[track_caller]
+pub fn assert_image_matches(
+    image_bytes: &[u8],
+    width: u32,
+    height: u32,
+    golden_path: impl AsRef<Path>,
+    tolerance: [u8; 4],
+) {
+    let golden_path = golden_path.as_ref();
+
+    assert_eq!(
+        image_bytes.len(),
+        width as usize * height as usize * 4,
+        "`image_bytes` doesn't have the size implied by `width` and `height`",
+    );
+
+    if env::var_os(UPDATE_GOLDEN_ENV).is_some() {
+        if let Some(parent) = golden_path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+
+        write_png(golden_path, image_bytes, width, height);
+
+        return;
+    }
+
+    if !golden_path.exists() {
+        let actual_path = sibling_path(golden_path, "actual");
+        write_png(&actual_path, image_bytes, width, height);
+
+        panic!(
+            "the golden image `{}` doesn't exist; run the test with `{}` set to create it (actual \
+            image written to `{}`)",
+            golden_path.display(),
+            UPDATE_GOLDEN_ENV,
+            actual_path.display(),
+        );
+    }
+
+    let (golden, golden_width, golden_height) = read_png(golden_path);
+    let actual_path = sibling_path(golden_path, "actual");
+
+    if [golden_width, golden_height] != [width, height] {
+        write_png(&actual_path, image_bytes, width, height);
+
+        panic!(
+            "the image is {}x{}, but the golden image `{}` is {}x{} (actual image written to `{}`)",
+            width,
+            height,
+            golden_path.display(),
+            golden_width,
+            golden_height,
+            actual_path.display(),
+        );
+    }
+
+    let mut diff = vec![0u8; image_bytes.len()];
+    let mut mismatch_count = 0usize;
+    let mut first_mismatch = None;
+
+    for (index, ((actual, expected), diff)) in image_bytes
+        .chunks_exact(4)
+        .zip(golden.chunks_exact(4))
+        .zip(diff.chunks_exact_mut(4))
+        .enumerate()
+    {
+        diff[3] = u8::MAX;
+
+        if (0..4).any(|c| actual[c].abs_diff(expected[c]) > tolerance[c]) {
+            for c in 0..3 {
+                diff[c] = actual[c].abs_diff(expected[c]);
+            }
+
+            mismatch_count += 1;
+            first_mismatch.get_or_insert(index);
+        }
+    }
+
+    if let Some(index) = first_mismatch {
+        let diff_path = sibling_path(golden_path, "diff");
+        write_png(&actual_path, image_bytes, width, height);
+        write_png(&diff_path, &diff, width, height);
+
+        let texel = index * 4..index * 4 + 4;
+
+        panic!(
+            "{} texels differ from the golden image `{}` by more than {:?}; the first is at \
+            [{}, {}], which is {:?}, but {:?} was expected (actual image written to `{}`, \
+            difference image written to `{}`)",
+            mismatch_count,
+            golden_path.display(),
+            tolerance,
+            index % width as usize,
+            index / width as usize,
+            &image_bytes[texel.clone()],
+            &golden[texel],
+            actual_path.display(),
+            diff_path.display(),
+        );
+    }
+}
+
+/// Returns the path of a file next to `path`, with `suffix` inserted before the extension.
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    path.with_extension(format!("{}.png", suffix))
+}
+
+#[track_caller]
+fn read_png(path: &Path) -> (Vec<u8>, u32, u32) {
+    let file = File::open(path).unwrap_or_else(|err| {
+        panic!(
+            "failed to open the golden image `{}`: {}",
+            path.display(),
+            err
+        )
+    });
+    let mut reader = png::Decoder::new(BufReader::new(file))
+        .read_info()
+        .unwrap_or_else(|err| {
+            panic!(
+                "failed to decode the golden image `{}`: {}",
+                path.display(),
+                err
+            )
+        });
+    let mut data = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data).unwrap_or_else(|err| {
+        panic!(
+            "failed to decode the golden image `{}`: {}",
+            path.display(),
+            err
+        )
+    });
+
+    assert!(
+        info.color_type == png::ColorType::Rgba && info.bit_depth == png::BitDepth::Eight,
+        "the golden image `{}` must be an 8-bit RGBA image",
+        path.display(),
+    );
+
+    data.truncate(info.buffer_size());
+
+    (data, info.width, info.height)
+}
+
+#[track_caller]
+fn write_png(path: &Path, data: &[u8], width: u32, height: u32) {
+    let file = File::create(path)
+        .unwrap_or_else(|err| panic!("failed to create `{}`: {}", path.display(), err));
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header().unwrap();
+    writer.write_image_data(data).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_matches() {
+        let dir = env::temp_dir().join(format!("vulkano-golden-{}", std::process::id()));
+        let golden_path = dir.join("image.png");
+        let expected = [10u8, 20, 30, 255].repeat(4);
+        fs::create_dir_all(&dir).unwrap();
+
+        // A missing golden image is an error, unless `UPDATE_GOLDEN_ENV` is set.
+        assert_should_panic!("doesn't exist", {
+            assert_image_matches(&expected, 2, 2, &golden_path, [0; 4]);
+        });
+        assert!(!golden_path.exists());
+
+        write_png(&golden_path, &expected, 2, 2);
+        assert_image_matches(&expected, 2, 2, &golden_path, [0; 4]);
+
+        let mut actual = expected.clone();
+        actual[4] = 12;
+        assert_image_matches(&actual, 2, 2, &golden_path, [2, 0, 0, 0]);
+        assert!(!sibling_path(&golden_path, "diff").exists());
+
+        actual[3 * 4 + 3] = 0;
+        assert_should_panic!("2 texels differ from the golden image", {
+            assert_image_matches(&actual, 2, 2, &golden_path, [1; 4]);
+        });
+
+        let (diff, _, _) = read_png(&sibling_path(&golden_path, "diff"));
+        assert_eq!(
+            diff,
+            [
+                [0, 0, 0, 255],
+                [2, 0, 0, 255],
+                [0, 0, 0, 255],
+                [0, 0, 0, 255],
+            ]
+            .concat(),
+        );
+        assert!(sibling_path(&golden_path, "actual").exists());
+
+        assert_should_panic!("but the golden image", {
+            assert_image_matches(&expected[..4], 1, 1, &golden_path, [0; 4]);
+        });
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,289 @@
+//! Utilities for writing tests that use Vulkano.
+//!
+//! This module is available when the `testing` cargo feature is enabled. Vulkano's own unit tests
+//! are built on top of it, so they can serve as an example of how to use it.
+//!
+//! # Acquiring a device
+//!
+//! [`test_instance`] and [`test_device`] return `None` instead of failing when no Vulkan
+//! implementation or no suitable physical device is available. A test can then return early,
+//! so that the test suite still passes on machines without a GPU:
+//!
+//! ```
+//! use vulkano::{
+//!     device::{DeviceExtensions, DeviceFeatures},
+//!     testing::test_device,
+//! };
+//!
+//! let Some((device, queue)) = test_device(&DeviceExtensions::empty(), &DeviceFeatures::empty())
+//! else {
+//!     return;
+//! };
+//! ```
+//!
+//! # Running on a software implementation
+//!
+//! The results of a test shouldn't depend on which GPU happens to be installed on the machine
+//! running it. For reproducible results, for example in CI, tests can be run on a software
+//! implementation such as [lavapipe], which is part of Mesa. By default, [`test_device`] prefers
+//! hardware devices over software ones. If the [`TEST_DEVICE_ENV`] environment variable is set,
+//! only physical devices whose name contains its value are considered, so setting
+//! `VULKANO_TEST_DEVICE=llvmpipe` selects lavapipe:
+//!
+//! ```sh
+//! VK_ICD_FILENAMES=/usr/share/vulkan/icd.d/lvp_icd.x86_64.json \
+//!     VULKANO_TEST_DEVICE=llvmpipe cargo test
+//! ```
+//!
+//! # Comparing results
+//!
+//! Different implementations don't produce bit-identical results for many operations, such as
+//! filtering, blending or floating-point arithmetic in shaders. [`assert_contents_eq`] and
+//! [`assert_image_contents_eq`] compare data that was read back from the device, allowing each
+//! element to differ by a tolerance.
+//!
+//! The [`golden`] module compares rendered images against reference images stored as PNG files,
+//! and writes a difference image when they don't match.
+//!
+//! [lavapipe]: https://docs.mesa3d.org/drivers/llvmpipe.html
+
+use crate::{
+    device::{
+        physical::PhysicalDeviceType, Device, DeviceCreateInfo, DeviceExtensions, DeviceFeatures,
+        Queue, QueueCreateInfo, QueueFlags,
+    },
+    instance::Instance,
+    VulkanLibrary,
+};
+use std::{env, fmt::Debug, sync::Arc};
+
+pub mod golden;
+
+/// The environment variable that restricts which physical device [`test_device`] selects.
+///
+/// If set, only physical devices whose name contains the value of the variable are considered.
+pub const TEST_DEVICE_ENV: &str = "VULKANO_TEST_DEVICE";
+
+/// Creates an instance for use in tests.
+///
+/// Returns `None` if the Vulkan library couldn't be loaded, or if instance creation failed.
+pub fn test_instance() -> Option<Arc<Instance>> {
+    let library = VulkanLibrary::new().ok()?;
+
+    Instance::new(library, Default::default()).ok()
+}
+
+/// Creates a device with the given extensions and features enabled, and a queue that supports
+/// graphics operations.
+///
+/// If the [`TEST_DEVICE_ENV`] environment variable is set, only physical devices whose name
+/// contains its value are considered. Among the remaining physical devices that support the
+/// extensions and features, discrete GPUs are preferred, then integrated, virtual and CPU
+/// devices.
+///
+/// Returns `None` if there is no Vulkan implementation, no suitable physical device, or if device
+/// creation failed.
+pub fn test_device(
+    enabled_extensions: &DeviceExtensions,
+    enabled_features: &DeviceFeatures,
+) -> Option<(Arc<Device>, Arc<Queue>)> {
+    let instance = test_instance()?;
+    let device_name_filter = env::var(TEST_DEVICE_ENV).ok();
+
+    let (physical_device, queue_family_index) = instance
+        .enumerate_physical_devices()
+        .ok()?
+        .filter(|p| {
+            device_name_filter
+                .as_deref()
+                .map_or(true, |name| p.properties().device_name.contains(name))
+        })
+        .filter(|p| {
+            p.supported_extensions().contains(enabled_extensions)
+                && p.supported_features().contains(enabled_features)
+        })
+        .filter_map(|p| {
+            p.queue_family_properties()
+                .iter()
+                .position(|q| q.queue_flags.intersects(QueueFlags::GRAPHICS))
+                .map(|i| (p, i as u32))
+        })
+        .min_by_key(|(p, _)| match p.properties().device_type {
+            PhysicalDeviceType::DiscreteGpu => 0,
+            PhysicalDeviceType::IntegratedGpu => 1,
+            PhysicalDeviceType::VirtualGpu => 2,
+            PhysicalDeviceType::Cpu => 3,
+            PhysicalDeviceType::Other => 4,
+        })?;
+
+    let (device, mut queues) = Device::new(
+        physical_device,
+        DeviceCreateInfo {
+            queue_create_infos: vec![QueueCreateInfo {
+                queue_family_index,
+                ..Default::default()
+            }],
+            enabled_extensions: *enabled_extensions,
+            enabled_features: *enabled_features,
+            ..Default::default()
+        },
+    )
+    .ok()?;
+
+    Some((device, queues.next().unwrap()))
+}
+
+/// A type whose values can be compared with a tolerance.
+pub trait ApproxEq: Copy + Debug {
+    /// Returns whether `self` and `other` differ by at most `tolerance`.
+    fn approx_eq(self, other: Self, tolerance: Self) -> bool;
+}
+
+macro_rules! impl_approx_eq_int {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl ApproxEq for $ty {
+                #[inline]
+                fn approx_eq(self, other: Self, tolerance: Self) -> bool {
+                    (self as i128 - other as i128).abs() <= tolerance as i128
+                }
+            }
+        )+
+    };
+}
+
+impl_approx_eq_int!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+macro_rules! impl_approx_eq_float {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl ApproxEq for $ty {
+                #[inline]
+                fn approx_eq(self, other: Self, tolerance: Self) -> bool {
+                    (self - other).abs() <= tolerance
+                }
+            }
+        )+
+    };
+}
+
+impl_approx_eq_float!(f32, f64);
+
+/// Returns the index of the first element of `actual` that differs from the corresponding
+/// element of `expected` by more than `tolerance`.
+///
+/// If the slices have different lengths, the length of the shorter slice is returned, unless a
+/// mismatch is found before that.
+pub fn find_mismatch<T: ApproxEq>(actual: &[T], expected: &[T], tolerance: T) -> Option<usize> {
+    actual
+        .iter()
+        .zip(expected)
+        .position(|(&a, &e)| !a.approx_eq(e, tolerance))
+        .or_else(|| (actual.len() != expected.len()).then(|| actual.len().min(expected.len())))
+}
+
+/// Asserts that `actual` and `expected` have the same length, and that each element differs by
+/// at most `tolerance`.
+///
+/// # Panics
+///
+/// - Panics with the index and values of the first mismatching element if the assertion fails.
+#[track_caller]
+pub fn assert_contents_eq<T: ApproxEq>(actual: &[T], expected: &[T], tolerance: T) {
+    assert_eq!(
+        actual.len(),
+        expected.len(),
+        "the contents have a different length than expected",
+    );
+
+    if let Some(index) = find_mismatch(actual, expected, tolerance) {
+        panic!(
+            "element {} is {:?}, but {:?} was expected (tolerance {:?})",
+            index, actual[index], expected[index], tolerance,
+        );
+    }
+}
+
+/// Asserts that the texel data in `actual` matches `expected`, allowing each component to differ
+/// by at most `tolerance`.
+///
+/// The data must be tightly packed, in the layout that a copy from an image with the given
+/// `extent` to a buffer produces. `texel_size` is the number of components of type `T` in each
+/// texel.
+///
+/// # Panics
+///
+/// - Panics if `actual` or `expected` doesn't contain exactly the number of components implied by
+///   `extent` and `texel_size`.
+/// - Panics with the coordinates and values of the first mismatching texel if the assertion fails.
+#[track_caller]
+pub fn assert_image_contents_eq<T: ApproxEq>(
+    actual: &[T],
+    expected: &[T],
+    extent: [u32; 3],
+    texel_size: usize,
+    tolerance: T,
+) {
+    let len = extent.iter().map(|&e| e as usize).product::<usize>() * texel_size;
+    assert_eq!(
+        actual.len(),
+        len,
+        "`actual` doesn't have the size implied by `extent` and `texel_size`",
+    );
+    assert_eq!(
+        expected.len(),
+        len,
+        "`expected` doesn't have the size implied by `extent` and `texel_size`",
+    );
+
+    if let Some(index) = find_mismatch(actual, expected, tolerance) {
+        let texel_index = index / texel_size;
+        let x = texel_index % extent[0] as usize;
+        let y = texel_index / extent[0] as usize % extent[1] as usize;
+        let z = texel_index / (extent[0] as usize * extent[1] as usize);
+        let texel = texel_index * texel_size..(texel_index + 1) * texel_size;
+
+        panic!(
+            "the texel at [{}, {}, {}] is {:?}, but {:?} was expected (tolerance {:?})",
+            x,
+            y,
+            z,
+            &actual[texel.clone()],
+            &expected[texel],
+            tolerance,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contents_eq() {
+        assert_eq!(find_mismatch(&[1u8, 2, 3], &[1, 3, 3], 1), None);
+        assert_eq!(find_mismatch(&[1u8, 2, 3], &[1, 4, 3], 1), Some(1));
+        assert_eq!(find_mismatch(&[1u8, 2], &[1, 2, 3], 0), Some(2));
+        assert_eq!(find_mismatch(&[0i32, i32::MAX], &[0, i32::MIN], 0), Some(1));
+        assert_eq!(find_mismatch(&[0.5f32, 1.0], &[0.5, 1.001], 0.01), None);
+        assert_eq!(find_mismatch(&[f32::NAN], &[f32::NAN], 1.0), Some(0));
+
+        assert_contents_eq(&[0.5f32, 1.0], &[0.5, 1.001], 0.01);
+        assert_should_panic!("element 1 is 2", {
+            assert_contents_eq(&[1u8, 2, 3], &[1, 4, 3], 1);
+        });
+    }
+
+    #[test]
+    fn image_contents_eq() {
+        let expected = [0u8; 2 * 2 * 2 * 4];
+        let mut actual = expected;
+        actual[3] = 2;
+        assert_image_contents_eq(&actual, &expected, [2, 2, 2], 4, 2);
+
+        actual[5 * 4 + 1] = 3;
+        assert_should_panic!("the texel at [1, 0, 1]", {
+            assert_image_contents_eq(&actual, &expected, [2, 2, 2], 4, 2);
+        });
+    }
+}
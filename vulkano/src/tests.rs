@@ -3,29 +3,19 @@
 /// Creates an instance or returns if initialization fails.
 macro_rules! instance {
     () => {{
-        use crate::{instance::Instance, VulkanLibrary};
-
-        let library = match VulkanLibrary::new() {
-            Ok(x) => x,
-            Err(_) => return,
-        };
-
-        match Instance::new(library, Default::default()) {
-            Ok(x) => x,
-            Err(_) => return,
+        match crate::testing::test_instance() {
+            Some(x) => x,
+            None => return,
         }
     }};
 }
 
-/// Creates a device and a queue for graphics operations.
+/// Creates a device and a queue for graphics operations, or returns if there is no suitable
+/// device.
 macro_rules! gfx_dev_and_queue {
     ($($feature:ident),*) => ({
-        use crate::device::physical::PhysicalDeviceType;
-        use crate::device::{Device, DeviceCreateInfo, DeviceExtensions, QueueCreateInfo};
-        use crate::device::DeviceFeatures;
+        use crate::device::{DeviceExtensions, DeviceFeatures};
 
-        let instance = instance!();
-        let enabled_extensions = DeviceExtensions::empty();
         let enabled_features = DeviceFeatures {
             $(
                 $feature: true,
@@ -33,51 +23,10 @@ macro_rules! gfx_dev_and_queue {
             .. DeviceFeatures::empty()
         };
 
-        let select = match instance.enumerate_physical_devices() {
-            Ok(x) => x,
-            Err(_) => return,
-        }
-            .filter(|p| {
-                p.supported_extensions().contains(&enabled_extensions) &&
-                p.supported_features().contains(&enabled_features)
-            })
-            .filter_map(|p| {
-                p.queue_family_properties().iter()
-                    .position(|q| q.queue_flags.intersects(crate::device::QueueFlags::GRAPHICS))
-                    .map(|i| (p, i as u32))
-            })
-            .min_by_key(|(p, _)| {
-                match p.properties().device_type {
-                    PhysicalDeviceType::DiscreteGpu => 0,
-                    PhysicalDeviceType::IntegratedGpu => 1,
-                    PhysicalDeviceType::VirtualGpu => 2,
-                    PhysicalDeviceType::Cpu => 3,
-                    PhysicalDeviceType::Other => 4,
-                }
-            });
-
-        let (physical_device, queue_family_index) = match select {
+        match crate::testing::test_device(&DeviceExtensions::empty(), &enabled_features) {
             Some(x) => x,
             None => return,
-        };
-
-        let (device, mut queues) = match Device::new(
-            physical_device,
-            DeviceCreateInfo {
-                queue_create_infos: vec![QueueCreateInfo {
-                    queue_family_index,
-                    ..Default::default()
-                }],
-                enabled_extensions,
-                enabled_features,
-                ..Default::default()
-            }
-        ) {
-            Ok(r) => r,
-            Err(_) => return,
-        };
-
-        (device, queues.next().unwrap())
+        }
     });
 }
 
@@ -0,0 +1,901 @@
+//! Uploading data to buffers and images from a dedicated transfer queue.
+//!
+//! Many devices have a queue family that only supports transfer operations, and that can copy data
+//! while other queues are busy rendering. The [`Uploader`] records the copies from a staging
+//! buffer to their destination into a command buffer, and submits them to its queue in batches.
+//! Each upload returns an [`UploadTicket`], which can be polled to find out whether the upload
+//! has finished, or turned into a future that the operations using the destination can be
+//! chained after.
+//!
+//! The uploader signals a timeline semaphore with a new value after each batch, and uses it to
+//! find out which batches have finished and to recycle their staging memory. The device must
+//! therefore have the [`timeline_semaphore`] feature enabled.
+//!
+//! # Queue families
+//!
+//! Vulkano doesn't record queue family ownership transfers for you. A resource that is uploaded
+//! on a queue of one family and then used on a queue of another family must therefore be created
+//! with [`Sharing::Concurrent`], listing both families. Resources with [`Sharing::Exclusive`] can
+//! only be uploaded if all queues of the device belong to the same family as the queue of the
+//! `Uploader`, because the queue that uses the resource afterwards can't be known in advance.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use vulkano::{
+//! #     buffer::Subbuffer,
+//! #     command_buffer::allocator::StandardCommandBufferAllocator,
+//! #     device::Queue,
+//! #     memory::allocator::StandardMemoryAllocator,
+//! #     sync::GpuFuture,
+//! # };
+//! use vulkano::transfer::Uploader;
+//!
+//! # fn example(
+//! #     transfer_queue: Arc<Queue>,
+//! #     memory_allocator: Arc<StandardMemoryAllocator>,
+//! #     command_buffer_allocator: Arc<StandardCommandBufferAllocator>,
+//! #     buffer: Subbuffer<[u32]>,
+//! #     future: Box<dyn GpuFuture>,
+//! # ) {
+//! let mut uploader =
+//!     Uploader::new(transfer_queue, memory_allocator, command_buffer_allocator).unwrap();
+//!
+//! let ticket = uploader.upload_buffer(&[1, 2, 3, 4], buffer).unwrap();
+//! uploader.flush().unwrap();
+//!
+//! // The first submission that uses the buffer waits for the upload to finish.
+//! let future = future.join(ticket.into_future().unwrap());
+//! # }
+//! ```
+//!
+//! [`timeline_semaphore`]: crate::device::DeviceFeatures::timeline_semaphore
+
+use crate::{
+    buffer::{
+        allocator::{SubbufferAllocator, SubbufferAllocatorCreateInfo},
+        BufferContents, BufferUsage, Subbuffer,
+    },
+    command_buffer::{
+        allocator::CommandBufferAllocator, BufferImageCopy, CommandBufferBeginInfo,
+        CommandBufferExecError, CommandBufferExecFuture, CommandBufferLevel, CommandBufferUsage,
+        CopyBufferInfo, CopyBufferToImageInfo, RecordingCommandBuffer, SemaphoreSubmitInfo,
+        SubmitInfo,
+    },
+    device::{DeviceOwned, Queue, QueueFlags},
+    format::Format,
+    image::{mip_level_extent, Image, ImageAspects, ImageSubresourceLayers},
+    memory::allocator::{
+        DeviceLayout, MemoryAllocatorError, MemoryTypeFilter, StandardMemoryAllocator,
+    },
+    sync::{
+        future::{NowFuture, SharedGpuFuture},
+        semaphore::{Semaphore, SemaphoreCreateInfo, SemaphoreType},
+        GpuFuture, Sharing,
+    },
+    DeviceSize, Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError, VulkanError,
+};
+use parking_lot::Mutex;
+use smallvec::{smallvec, SmallVec};
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+    mem,
+    sync::Arc,
+};
+
+/// Records uploads to buffers and images, and submits them to a queue in batches.
+///
+/// The data of each upload is first copied into a staging buffer, and the copy from the staging
+/// buffer to the destination is recorded into a command buffer. Nothing is submitted until
+/// [`flush`] is called, which submits everything that was recorded since the previous flush as
+/// one batch.
+///
+/// See also [the module-level documentation](self).
+///
+/// [`flush`]: Self::flush
+pub struct Uploader {
+    queue: Arc<Queue>,
+    command_buffer_allocator: Arc<dyn CommandBufferAllocator>,
+    staging_allocator: SubbufferAllocator,
+    // Signaled with a new value after each batch, once the batch has finished executing.
+    semaphore: Arc<Semaphore>,
+    // The value that the most recently flushed batch signals.
+    semaphore_value: u64,
+    // The command buffer that the uploads of `batch` are recorded into, created by the first
+    // upload after a flush.
+    builder: Option<RecordingCommandBuffer>,
+    batch: Arc<UploadBatch>,
+    // The batches that were flushed, and whose submission may not have finished yet.
+    in_flight: Vec<Arc<UploadBatch>>,
+}
+
+impl Uploader {
+    /// Creates a new `Uploader` that submits its uploads to `queue`.
+    ///
+    /// `queue` should usually be a queue of a family that only supports transfer operations, if
+    /// the device has one. The [`timeline_semaphore`] feature must be enabled on the device.
+    ///
+    /// [`timeline_semaphore`]: crate::device::DeviceFeatures::timeline_semaphore
+    pub fn new(
+        queue: Arc<Queue>,
+        memory_allocator: Arc<StandardMemoryAllocator>,
+        command_buffer_allocator: Arc<dyn CommandBufferAllocator>,
+    ) -> Result<Self, Validated<VulkanError>> {
+        Self::validate_new(&queue, &memory_allocator, &command_buffer_allocator)?;
+
+        let semaphore = Arc::new(Semaphore::new(
+            queue.device().clone(),
+            SemaphoreCreateInfo {
+                semaphore_type: SemaphoreType::Timeline,
+                ..Default::default()
+            },
+        )?);
+
+        let staging_allocator = SubbufferAllocator::new(
+            memory_allocator,
+            SubbufferAllocatorCreateInfo {
+                buffer_usage: BufferUsage::TRANSFER_SRC,
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+        );
+        // The staging memory of a batch is reused once the batch has finished executing.
+        staging_allocator.set_timeline(semaphore.clone())?;
+
+        Ok(Uploader {
+            queue,
+            command_buffer_allocator,
+            staging_allocator,
+            semaphore,
+            semaphore_value: 0,
+            builder: None,
+            batch: Arc::default(),
+            in_flight: Vec::new(),
+        })
+    }
+
+    fn validate_new(
+        queue: &Queue,
+        memory_allocator: &StandardMemoryAllocator,
+        command_buffer_allocator: &Arc<dyn CommandBufferAllocator>,
+    ) -> Result<(), Box<ValidationError>> {
+        // Graphics and compute queues implicitly support transfer operations.
+        let queue_family_properties = &queue.device().physical_device().queue_family_properties()
+            [queue.queue_family_index() as usize];
+
+        if !queue_family_properties
+            .queue_flags
+            .intersects(QueueFlags::TRANSFER | QueueFlags::GRAPHICS | QueueFlags::COMPUTE)
+        {
+            return Err(Box::new(ValidationError {
+                context: "queue".into(),
+                problem: "the queue family of the queue does not support transfer operations"
+                    .into(),
+                ..Default::default()
+            }));
+        }
+
+        if !queue.device().enabled_features().timeline_semaphore {
+            return Err(Box::new(ValidationError {
+                context: "queue".into(),
+                problem: "the device of the queue does not have the `timeline_semaphore` feature \
+                    enabled, which the uploader uses to track the completion of uploads"
+                    .into(),
+                requires_one_of: RequiresOneOf(&[RequiresAllOf(&[Requires::DeviceFeature(
+                    "timeline_semaphore",
+                )])]),
+                ..Default::default()
+            }));
+        }
+
+        if memory_allocator.device() != queue.device() {
+            return Err(Box::new(ValidationError {
+                problem: "`memory_allocator` and `queue` do not belong to the same device".into(),
+                ..Default::default()
+            }));
+        }
+
+        if command_buffer_allocator.device() != queue.device() {
+            return Err(Box::new(ValidationError {
+                problem: "`command_buffer_allocator` and `queue` do not belong to the same device"
+                    .into(),
+                ..Default::default()
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the queue that the uploads are submitted to.
+    #[inline]
+    pub fn queue(&self) -> &Arc<Queue> {
+        &self.queue
+    }
+
+    /// Records an upload of `data` to `dst_buffer`.
+    ///
+    /// The upload is submitted by the next call to [`flush`](Self::flush).
+    pub fn upload_buffer<T>(
+        &mut self,
+        data: &[T],
+        dst_buffer: Subbuffer<[T]>,
+    ) -> Result<UploadTicket, Validated<UploadError>>
+    where
+        T: BufferContents + Copy,
+    {
+        self.validate_upload_buffer(data, &dst_buffer)?;
+
+        let src_buffer = self
+            .staging_allocator
+            .allocate_from_slice(data)
+            .map_err(UploadError::AllocateStagingBuffer)?;
+
+        self.builder()?
+            .copy_buffer(CopyBufferInfo::buffers(src_buffer, dst_buffer))?;
+
+        Ok(self.ticket())
+    }
+
+    fn validate_upload_buffer<T>(
+        &self,
+        data: &[T],
+        dst_buffer: &Subbuffer<[T]>,
+    ) -> Result<(), Box<ValidationError>> {
+        if data.len() as u64 != dst_buffer.len() {
+            return Err(Box::new(ValidationError {
+                problem: "the length of `data` is not equal to the length of `dst_buffer`".into(),
+                ..Default::default()
+            }));
+        }
+
+        validate_sharing(dst_buffer.buffer().sharing(), &self.queue)
+            .map_err(|err| err.add_context("dst_buffer"))?;
+
+        Ok(())
+    }
+
+    /// Records an upload of `data` to `subresource` of `dst_image`.
+    ///
+    /// `data` must contain the whole extent of the mip level of `subresource`, for each of its
+    /// array layers, tightly packed. Its length must therefore be exactly the size of that data.
+    ///
+    /// The upload is submitted by the next call to [`flush`](Self::flush).
+    pub fn upload_image(
+        &mut self,
+        data: &[u8],
+        dst_image: &Arc<Image>,
+        subresource: ImageSubresourceLayers,
+    ) -> Result<UploadTicket, Validated<UploadError>> {
+        self.validate_upload_image(data, dst_image, &subresource)?;
+
+        let (format, image_extent) = subresource_format_extent(dst_image, &subresource);
+
+        // The offset of the staging data must be a multiple of both the block size of the format
+        // and 4. That isn't a power of two for formats with a block size of 3, 6 or 12 bytes, so
+        // the data is placed at the first such offset of a slightly larger allocation instead.
+        let alignment = staging_alignment(format.block_size());
+        let layout =
+            DeviceLayout::from_size_alignment(data.len() as DeviceSize + alignment - 4, 4).unwrap();
+        let staging_buffer = self
+            .staging_allocator
+            .allocate(layout)
+            .map_err(UploadError::AllocateStagingBuffer)?;
+        let start = (alignment - staging_buffer.offset() % alignment) % alignment;
+        let src_buffer = staging_buffer.slice(start..start + data.len() as DeviceSize);
+        src_buffer.write().unwrap().copy_from_slice(data);
+
+        self.builder()?
+            .copy_buffer_to_image(CopyBufferToImageInfo {
+                regions: smallvec![BufferImageCopy {
+                    image_subresource: subresource,
+                    image_extent,
+                    ..Default::default()
+                }],
+                ..CopyBufferToImageInfo::buffer_image(src_buffer, dst_image.clone())
+            })?;
+
+        Ok(self.ticket())
+    }
+
+    fn validate_upload_image(
+        &self,
+        data: &[u8],
+        dst_image: &Image,
+        subresource: &ImageSubresourceLayers,
+    ) -> Result<(), Box<ValidationError>> {
+        if subresource.mip_level >= dst_image.mip_levels() {
+            return Err(Box::new(ValidationError {
+                problem: "`subresource.mip_level` is not less than `dst_image.mip_levels()`".into(),
+                vuids: &["VUID-VkCopyBufferToImageInfo2-imageSubresource-07967"],
+                ..Default::default()
+            }));
+        }
+
+        if subresource.array_layers.is_empty() {
+            return Err(Box::new(ValidationError {
+                context: "subresource.array_layers".into(),
+                problem: "is empty".into(),
+                vuids: &["VUID-VkImageSubresourceLayers-layerCount-01700"],
+                ..Default::default()
+            }));
+        }
+
+        let (format, image_extent) = subresource_format_extent(dst_image, subresource);
+        let subresource_size = BufferImageCopy {
+            image_subresource: subresource.clone(),
+            image_extent,
+            ..Default::default()
+        }
+        .buffer_copy_size(format);
+
+        if data.len() as DeviceSize != subresource_size {
+            return Err(Box::new(ValidationError {
+                problem: format!(
+                    "the length of `data` is {}, but `subresource` of `dst_image` requires {} \
+                    bytes of data",
+                    data.len(),
+                    subresource_size,
+                )
+                .into(),
+                ..Default::default()
+            }));
+        }
+
+        validate_sharing(dst_image.sharing(), &self.queue)
+            .map_err(|err| err.add_context("dst_image"))?;
+
+        Ok(())
+    }
+
+    /// Submits the uploads that were recorded since the previous flush.
+    ///
+    /// Does nothing if no uploads were recorded. If an error is returned, the uploads that were
+    /// recorded since the previous flush are discarded, and their tickets never complete.
+    pub fn flush(&mut self) -> Result<(), Validated<UploadError>> {
+        self.cleanup_finished();
+
+        let Some(builder) = self.builder.take() else {
+            return Ok(());
+        };
+        let batch = mem::take(&mut self.batch);
+
+        let command_buffer = builder
+            .end()
+            .map_err(|err| err.map(UploadError::VulkanError))?;
+        let future = command_buffer
+            .execute(self.queue.clone())
+            .map_err(UploadError::Execute)?
+            .shared();
+
+        // One clone for each ticket that is still alive, so that every ticket can be turned into
+        // a future that may be followed by operations on a different queue. The clones must exist
+        // before the submission is flushed, because that's when their semaphores are created.
+        let ticket_count = Arc::strong_count(&batch) - 1;
+        let mut futures: Vec<_> = (1..ticket_count).map(|_| future.clone()).collect();
+        futures.push(future);
+        futures[0]
+            .flush()
+            .map_err(|err| err.map(UploadError::VulkanError))?;
+
+        // No semaphore was created for this clone, as it didn't exist yet when the submission was
+        // flushed. It is only kept to release the resources of the submission once it finishes.
+        let future = futures[0].clone();
+        futures.truncate(ticket_count);
+
+        // A semaphore signal operation includes all work previously submitted to the queue, so this
+        // is signaled once the command buffer has finished executing.
+        let semaphore_value = self.semaphore_value + 1;
+        let submit_info = SubmitInfo {
+            signal_semaphores: vec![SemaphoreSubmitInfo {
+                value: semaphore_value,
+                ..SemaphoreSubmitInfo::new(self.semaphore.clone())
+            }],
+            ..Default::default()
+        };
+        // SAFETY: Only the uploader signals the semaphore, always with a greater value than the
+        // previous time.
+        self.queue
+            .with(|mut q| unsafe { q.submit(&[submit_info], None) })
+            .map_err(|err| err.map(UploadError::VulkanError))?;
+        self.semaphore_value = semaphore_value;
+        self.staging_allocator
+            .retire_frame(semaphore_value)
+            .unwrap();
+
+        *batch.state.lock() = Some(FlushedBatch {
+            future,
+            futures,
+            semaphore: self.semaphore.clone(),
+            semaphore_value,
+        });
+        self.in_flight.push(batch);
+
+        Ok(())
+    }
+
+    /// Releases the batches whose submission has finished executing.
+    ///
+    /// This is called by [`flush`](Self::flush), but can be called more often to release the
+    /// staging memory and the resources of the uploads sooner.
+    pub fn cleanup_finished(&mut self) {
+        self.in_flight
+            .retain(|batch| !matches!(batch.is_complete(), Ok(true)));
+    }
+
+    fn builder(&mut self) -> Result<&mut RecordingCommandBuffer, Validated<UploadError>> {
+        if self.builder.is_none() {
+            let builder = RecordingCommandBuffer::new(
+                self.command_buffer_allocator.clone(),
+                self.queue.queue_family_index(),
+                CommandBufferLevel::Primary,
+                CommandBufferBeginInfo {
+                    usage: CommandBufferUsage::OneTimeSubmit,
+                    ..Default::default()
+                },
+            )
+            .map_err(|err| err.map(UploadError::VulkanError))?;
+            self.builder = Some(builder);
+        }
+
+        Ok(self.builder.as_mut().unwrap())
+    }
+
+    fn ticket(&self) -> UploadTicket {
+        UploadTicket {
+            batch: self.batch.clone(),
+        }
+    }
+}
+
+// Returns the alignment of staging data for a format with the given block size, which is the
+// least common multiple of the block size and 4.
+fn staging_alignment(block_size: DeviceSize) -> DeviceSize {
+    match block_size % 4 {
+        0 => block_size,
+        2 => block_size * 2,
+        _ => block_size * 4,
+    }
+}
+
+// Returns the format and the extent of the mip level of `subresource`, taking the planes of
+// multi-planar formats into account.
+fn subresource_format_extent(
+    image: &Image,
+    subresource: &ImageSubresourceLayers,
+) -> (Format, [u32; 3]) {
+    let image_format = image.format();
+    let plane = match subresource.aspects {
+        ImageAspects::PLANE_1 => 1,
+        ImageAspects::PLANE_2 => 2,
+        _ => 0,
+    };
+
+    let (format, extent) = match image_format.planes().get(plane) {
+        Some(&plane_format) if plane != 0 => (
+            plane_format,
+            image_format
+                .ycbcr_chroma_sampling()
+                .map_or(image.extent(), |s| s.subsampled_extent(image.extent())),
+        ),
+        Some(&plane_format) => (plane_format, image.extent()),
+        None => (image_format, image.extent()),
+    };
+
+    (
+        format,
+        mip_level_extent(extent, subresource.mip_level).unwrap(),
+    )
+}
+
+fn validate_sharing(
+    sharing: &Sharing<SmallVec<[u32; 4]>>,
+    queue: &Queue,
+) -> Result<(), Box<ValidationError>> {
+    match sharing {
+        Sharing::Exclusive => {
+            // Without an ownership transfer, a resource with exclusive sharing can only be used on
+            // queues of the family that it was first used on.
+            if queue
+                .device()
+                .active_queue_family_indices()
+                .iter()
+                .all(|&index| index == queue.queue_family_index())
+            {
+                Ok(())
+            } else {
+                Err(Box::new(ValidationError {
+                    problem: "the resource was created with exclusive sharing, but the device has \
+                        queues of other queue families than the one of the uploader's queue, \
+                        which the resource could not be used on after the upload"
+                        .into(),
+                    ..Default::default()
+                }))
+            }
+        }
+        Sharing::Concurrent(queue_family_indices) => {
+            if queue_family_indices.contains(&queue.queue_family_index()) {
+                Ok(())
+            } else {
+                Err(Box::new(ValidationError {
+                    problem: "the resource was created with concurrent sharing, but the queue \
+                        family of the uploader's queue is not one of its queue families"
+                        .into(),
+                    ..Default::default()
+                }))
+            }
+        }
+    }
+}
+
+/// The future of the submission of an upload, returned by [`UploadTicket::into_future`].
+pub type UploadFuture = SharedGpuFuture<CommandBufferExecFuture<NowFuture>>;
+
+/// Identifies an upload that was recorded by an [`Uploader`].
+///
+/// All uploads that were recorded between two flushes share the same submission, so their tickets
+/// complete at the same time.
+pub struct UploadTicket {
+    batch: Arc<UploadBatch>,
+}
+
+impl UploadTicket {
+    /// Returns whether the upload has been submitted to the queue of the uploader.
+    #[inline]
+    pub fn is_flushed(&self) -> bool {
+        self.batch.state.lock().is_some()
+    }
+
+    /// Returns whether the upload has finished executing.
+    ///
+    /// Once this returns `true`, the destination of the upload can be used without waiting for
+    /// anything.
+    #[inline]
+    pub fn is_complete(&self) -> Result<bool, VulkanError> {
+        self.batch.is_complete()
+    }
+
+    /// Turns the ticket into a future that represents the submission of the upload.
+    ///
+    /// The future can be joined with the futures of other queues, so that the first submission
+    /// that uses the destination of the upload waits for it.
+    ///
+    /// Returns an error if the upload has not been flushed yet.
+    pub fn into_future(self) -> Result<UploadFuture, Box<ValidationError>> {
+        match &mut *self.batch.state.lock() {
+            // `flush` created one future for each ticket of the batch, and tickets can't be
+            // cloned.
+            Some(flushed) => Ok(flushed.futures.pop().unwrap()),
+            None => Err(Box::new(ValidationError {
+                problem: "the upload has not been flushed yet".into(),
+                ..Default::default()
+            })),
+        }
+    }
+}
+
+#[derive(Default)]
+struct UploadBatch {
+    // `None` until the batch is flushed.
+    state: Mutex<Option<FlushedBatch>>,
+}
+
+struct FlushedBatch {
+    // Used to release the resources of the submission once it has finished.
+    future: UploadFuture,
+    // The futures that haven't been taken by a ticket yet.
+    futures: Vec<UploadFuture>,
+    // The semaphore of the uploader, and the value that it has once the batch has finished.
+    semaphore: Arc<Semaphore>,
+    semaphore_value: u64,
+}
+
+impl UploadBatch {
+    fn is_complete(&self) -> Result<bool, VulkanError> {
+        match &*self.state.lock() {
+            Some(flushed) => {
+                if flushed
+                    .semaphore
+                    .counter_value()
+                    .map_err(Validated::unwrap)?
+                    < flushed.semaphore_value
+                {
+                    return Ok(false);
+                }
+
+                // Release the resources of the submission, as it has finished.
+                unsafe { flushed.future.signal_finished() };
+
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+/// Error that can happen when uploading data or flushing an [`Uploader`].
+#[derive(Debug)]
+pub enum UploadError {
+    /// Allocating the staging buffer that holds the data failed.
+    AllocateStagingBuffer(MemoryAllocatorError),
+
+    /// Creating, ending or submitting the command buffer failed.
+    VulkanError(VulkanError),
+
+    /// Executing the command buffer failed.
+    Execute(CommandBufferExecError),
+}
+
+impl Error for UploadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::AllocateStagingBuffer(err) => Some(err),
+            Self::VulkanError(err) => Some(err),
+            Self::Execute(err) => Some(err),
+        }
+    }
+}
+
+impl Display for UploadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AllocateStagingBuffer(_) => write!(f, "allocating the staging buffer failed"),
+            Self::VulkanError(_) => write!(f, "a runtime error occurred"),
+            Self::Execute(_) => write!(f, "executing the command buffer failed"),
+        }
+    }
+}
+
+impl From<UploadError> for Validated<UploadError> {
+    fn from(err: UploadError) -> Self {
+        Self::Error(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Uploader;
+    use crate::{
+        buffer::{Buffer, BufferCreateInfo, BufferUsage},
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, CommandBufferBeginInfo, CommandBufferLevel,
+            CommandBufferUsage, CopyImageToBufferInfo, RecordingCommandBuffer,
+        },
+        format::{Format, FormatFeatures},
+        image::{Image, ImageCreateInfo, ImageSubresourceLayers, ImageUsage},
+        memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
+        sync::GpuFuture,
+        Validated,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn upload_buffer() {
+        let (device, queue) = gfx_dev_and_queue!(timeline_semaphore);
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device,
+            Default::default(),
+        ));
+        let mut uploader = Uploader::new(queue, memory_allocator.clone(), cb_allocator).unwrap();
+
+        let buffer = Buffer::new_slice::<u32>(
+            memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                ..Default::default()
+            },
+            4,
+        )
+        .unwrap();
+
+        let ticket = uploader
+            .upload_buffer(&[1, 2, 3, 4], buffer.clone())
+            .unwrap();
+        assert!(!ticket.is_flushed());
+        uploader.flush().unwrap();
+        assert!(ticket.is_flushed());
+
+        ticket
+            .into_future()
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        assert_eq!(&*buffer.read().unwrap(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn upload_image() {
+        let (device, queue) = gfx_dev_and_queue!(timeline_semaphore);
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+        let mut uploader = Uploader::new(
+            queue.clone(),
+            memory_allocator.clone(),
+            cb_allocator.clone(),
+        )
+        .unwrap();
+
+        let image = Image::new(
+            memory_allocator.clone(),
+            ImageCreateInfo {
+                format: Format::R8G8B8A8_UNORM,
+                extent: [2, 2, 1],
+                array_layers: 2,
+                usage: ImageUsage::TRANSFER_DST | ImageUsage::TRANSFER_SRC,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+        )
+        .unwrap();
+        let subresource = ImageSubresourceLayers::from_parameters(image.format(), 2);
+
+        // 2 layers of 2x2 texels of 4 bytes each, plus one byte too many.
+        let data: Vec<u8> = (0..33).collect();
+
+        for len in [0, 16, 31, 33] {
+            assert!(matches!(
+                uploader.upload_image(&data[..len], &image, subresource.clone()),
+                Err(Validated::ValidationError(_)),
+            ));
+        }
+
+        let ticket = uploader
+            .upload_image(&data[..32], &image, subresource)
+            .unwrap();
+        uploader.flush().unwrap();
+
+        let buffer = Buffer::new_slice::<u8>(
+            memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                ..Default::default()
+            },
+            32,
+        )
+        .unwrap();
+        let mut cbb = RecordingCommandBuffer::new(
+            cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        cbb.copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(image, buffer.clone()))
+            .unwrap();
+
+        ticket
+            .into_future()
+            .unwrap()
+            .then_execute(queue, cbb.end().unwrap())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        assert_eq!(&*buffer.read().unwrap(), &data[..32]);
+    }
+
+    #[test]
+    fn upload_image_3_byte_block() {
+        let (device, queue) = gfx_dev_and_queue!(timeline_semaphore);
+
+        let format = Format::R8G8B8_UNORM;
+
+        if !device
+            .physical_device()
+            .format_properties(format)
+            .unwrap()
+            .optimal_tiling_features
+            .contains(FormatFeatures::TRANSFER_SRC | FormatFeatures::TRANSFER_DST)
+        {
+            return;
+        }
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let cb_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+        let mut uploader = Uploader::new(
+            queue.clone(),
+            memory_allocator.clone(),
+            cb_allocator.clone(),
+        )
+        .unwrap();
+
+        let new_buffer = |len| {
+            Buffer::new_slice::<u8>(
+                memory_allocator.clone(),
+                BufferCreateInfo {
+                    usage: BufferUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                        | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                    ..Default::default()
+                },
+                len,
+            )
+            .unwrap()
+        };
+
+        // Upload 4 bytes first, so that the staging data of the image doesn't start at an offset
+        // that happens to be a multiple of 3.
+        let buffer_ticket = uploader
+            .upload_buffer(&[1, 2, 3, 4], new_buffer(4))
+            .unwrap();
+
+        let image = Image::new(
+            memory_allocator.clone(),
+            ImageCreateInfo {
+                format,
+                extent: [2, 2, 1],
+                usage: ImageUsage::TRANSFER_DST | ImageUsage::TRANSFER_SRC,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+        )
+        .unwrap();
+        let subresource = ImageSubresourceLayers::from_parameters(format, 1);
+
+        // 2x2 texels of 3 bytes each.
+        let data: Vec<u8> = (0..12).collect();
+        let ticket = uploader.upload_image(&data, &image, subresource).unwrap();
+        assert!(!buffer_ticket.is_complete().unwrap());
+        uploader.flush().unwrap();
+
+        let buffer = new_buffer(12);
+        let mut cbb = RecordingCommandBuffer::new(
+            cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferLevel::Primary,
+            CommandBufferBeginInfo {
+                usage: CommandBufferUsage::OneTimeSubmit,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        cbb.copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(image, buffer.clone()))
+            .unwrap();
+
+        ticket
+            .into_future()
+            .unwrap()
+            .then_execute(queue, cbb.end().unwrap())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        // The fence covers the submission that signals the semaphore of the uploader.
+        assert!(buffer_ticket.is_complete().unwrap());
+        assert_eq!(&*buffer.read().unwrap(), &data);
+    }
+}
@@ -1,6 +1,9 @@
 // The `Version` object is reexported from the `instance` module.
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
+    error::Error,
     fmt::{Debug, Display, Error as FmtError, Formatter},
     num::ParseIntError,
     str::FromStr,
@@ -10,6 +13,15 @@ use std::{
 include!(concat!(env!("OUT_DIR"), "/version.rs"));
 
 /// Represents an API version of Vulkan.
+///
+/// A `Version` doesn't include the variant of the version, which is zero for versions of the
+/// Vulkan API itself, but which may be nonzero for versions that are reported by other APIs such
+/// as OpenXR. Conversions from and to `u32` ignore the variant, and comparisons only consider the
+/// major, minor and patch numbers. Use [`from_vulkan_version_with_variant`] and
+/// [`to_vulkan_version_with_variant`] to preserve the variant.
+///
+/// [`from_vulkan_version_with_variant`]: Version::from_vulkan_version_with_variant
+/// [`to_vulkan_version_with_variant`]: Version::to_vulkan_version_with_variant
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Version {
     /// Major version number.
@@ -38,8 +50,63 @@ impl Version {
             patch: 0,
         }
     }
+
+    /// Returns the version of the Vulkan headers of the `ash` crate that Vulkano was built
+    /// against.
+    ///
+    /// This is normally equal to [`Version::HEADER_VERSION`], which is the version of the
+    /// `vk.xml` file that Vulkano's generated code is based on.
+    #[inline]
+    pub const fn from_header() -> Version {
+        let version = ash::vk::HEADER_VERSION_COMPLETE;
+
+        Version {
+            major: ash::vk::api_version_major(version),
+            minor: ash::vk::api_version_minor(version),
+            patch: ash::vk::api_version_patch(version),
+        }
+    }
+
+    /// Decodes a version number in the format used by Vulkan, and returns the variant along with
+    /// the version.
+    #[inline]
+    pub const fn from_vulkan_version_with_variant(version: u32) -> (u32, Version) {
+        (
+            ash::vk::api_version_variant(version),
+            Version {
+                major: ash::vk::api_version_major(version),
+                minor: ash::vk::api_version_minor(version),
+                patch: ash::vk::api_version_patch(version),
+            },
+        )
+    }
+
+    /// Encodes the version, with the given variant, as a version number in the format used by
+    /// Vulkan.
+    ///
+    /// Returns `None` if `variant` is greater than 0x7, `self.major` or `self.minor` is greater
+    /// than 0x3ff, or `self.patch` is greater than 0xfff.
+    #[inline]
+    pub const fn to_vulkan_version_with_variant(self, variant: u32) -> Option<u32> {
+        if variant <= MAX_VARIANT
+            && self.major <= MAX_MAJOR
+            && self.minor <= MAX_MINOR
+            && self.patch <= MAX_PATCH
+        {
+            Some(ash::vk::make_api_version(
+                variant, self.major, self.minor, self.patch,
+            ))
+        } else {
+            None
+        }
+    }
 }
 
+const MAX_VARIANT: u32 = 0x7;
+const MAX_MAJOR: u32 = 0x3ff;
+const MAX_MINOR: u32 = 0x3ff;
+const MAX_PATCH: u32 = 0xfff;
+
 impl Default for Version {
     #[inline]
     fn default() -> Self {
@@ -47,34 +114,29 @@ impl Default for Version {
     }
 }
 
+/// Decodes a version number in the format used by Vulkan, ignoring the variant.
 impl From<u32> for Version {
     #[inline]
     fn from(val: u32) -> Self {
-        Version {
-            major: ash::vk::api_version_major(val),
-            minor: ash::vk::api_version_minor(val),
-            patch: ash::vk::api_version_patch(val),
-        }
+        Version::from_vulkan_version_with_variant(val).1
     }
 }
 
+/// Encodes the version as a version number in the format used by Vulkan, with variant 0.
 impl TryFrom<Version> for u32 {
     type Error = ();
 
     #[inline]
     fn try_from(val: Version) -> Result<Self, Self::Error> {
-        if val.major <= 0x3ff && val.minor <= 0x3ff && val.patch <= 0xfff {
-            Ok(ash::vk::make_api_version(
-                0, val.major, val.minor, val.patch,
-            ))
-        } else {
-            Err(())
-        }
+        val.to_vulkan_version_with_variant(0).ok_or(())
     }
 }
 
+/// Parses a version from a string of the form `major[.minor[.patch]]`.
+///
+/// Components that are too large to be encoded in the format used by Vulkan are rejected.
 impl FromStr for Version {
-    type Err = ParseIntError;
+    type Err = ParseVersionError;
 
     #[inline]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -83,6 +145,10 @@ impl FromStr for Version {
         let minor: u32 = iter.next().map_or(Ok(0), |n| n.parse())?;
         let patch: u32 = iter.next().map_or(Ok(0), |n| n.parse())?;
 
+        if major > MAX_MAJOR || minor > MAX_MINOR || patch > MAX_PATCH {
+            return Err(ParseVersionError::OutOfRange);
+        }
+
         Ok(Version {
             major,
             minor,
@@ -91,6 +157,44 @@ impl FromStr for Version {
     }
 }
 
+/// Error that can happen when parsing a [`Version`] from a string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseVersionError {
+    /// A component of the version is not a valid integer.
+    InvalidComponent(ParseIntError),
+
+    /// A component of the version is too large to be encoded in the format used by Vulkan.
+    OutOfRange,
+}
+
+impl Error for ParseVersionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidComponent(err) => Some(err),
+            Self::OutOfRange => None,
+        }
+    }
+}
+
+impl Display for ParseVersionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::InvalidComponent(_) => write!(f, "a component is not a valid integer"),
+            Self::OutOfRange => write!(
+                f,
+                "a component is too large to be encoded as a Vulkan version number",
+            ),
+        }
+    }
+}
+
+impl From<ParseIntError> for ParseVersionError {
+    #[inline]
+    fn from(err: ParseIntError) -> Self {
+        Self::InvalidComponent(err)
+    }
+}
+
 impl Debug for Version {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
         write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
@@ -104,9 +208,33 @@ impl Display for Version {
     }
 }
 
+/// Serializes the version as a string of the form `major.minor.patch`.
+#[cfg(feature = "serde")]
+impl Serialize for Version {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// Deserializes the version from a string of the form `major[.minor[.patch]]`.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Version;
+    use super::{ParseVersionError, Version};
 
     #[test]
     fn into_vk_version() {
@@ -194,5 +322,70 @@ mod tests {
         assert!("1.1.1.1".parse::<Version>().is_err());
         assert!("foobar".parse::<Version>().is_err());
         assert!("1.bar".parse::<Version>().is_err());
+
+        assert!(matches!(
+            "1023.1023.4095".parse::<Version>(),
+            Ok(Version {
+                major: 1023,
+                minor: 1023,
+                patch: 4095,
+            })
+        ));
+        assert_eq!(
+            "1024.0.0".parse::<Version>(),
+            Err(ParseVersionError::OutOfRange),
+        );
+        assert_eq!(
+            "1.1024".parse::<Version>(),
+            Err(ParseVersionError::OutOfRange),
+        );
+        assert_eq!(
+            "1.0.4096".parse::<Version>(),
+            Err(ParseVersionError::OutOfRange),
+        );
+    }
+
+    #[test]
+    fn variant_round_trip() {
+        let version = Version {
+            major: 1,
+            minor: 2,
+            patch: 3,
+        };
+
+        for variant in 0..=7 {
+            let encoded = version.to_vulkan_version_with_variant(variant).unwrap();
+            assert_eq!(encoded, ash::vk::make_api_version(variant, 1, 2, 3));
+            assert_eq!(
+                Version::from_vulkan_version_with_variant(encoded),
+                (variant, version),
+            );
+            assert_eq!(Version::from(encoded), version);
+        }
+
+        assert_eq!(version.to_vulkan_version_with_variant(8), None);
+        assert_eq!(
+            Version {
+                major: 1024,
+                minor: 0,
+                patch: 0,
+            }
+            .to_vulkan_version_with_variant(0),
+            None,
+        );
+
+        // `TryFrom` always encodes variant 0.
+        let encoded = ash::vk::make_api_version(1, 1, 2, 3);
+        assert_ne!(u32::try_from(Version::from(encoded)).unwrap(), encoded);
+    }
+
+    #[test]
+    fn from_header() {
+        let version = Version::from_header();
+        assert_eq!(
+            u32::try_from(version).unwrap(),
+            ash::vk::HEADER_VERSION_COMPLETE,
+        );
+        assert_eq!(version.patch, ash::vk::HEADER_VERSION);
     }
 }
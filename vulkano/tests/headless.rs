@@ -0,0 +1,480 @@
+// Headless versions of some of the examples, that render a known scene and compare the result
+// against a golden image in `tests/golden`.
+//
+// The tests return early if there is no suitable device, unless `VULKANO_REQUIRE_DEVICE` is set,
+// in which case they fail. To get reproducible results, run them on lavapipe, as described in the
+// documentation of the `testing` module. After an intentional change
+// to the output, regenerate the golden images by running the tests with `VULKANO_UPDATE_GOLDEN=1`.
+//
+// The tests need the `testing` feature: `cargo test --features testing --test headless`.
+
+use std::{env, path::PathBuf, sync::Arc};
+use vulkano::{
+    buffer::{Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer},
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, CommandBufferBeginInfo, CommandBufferLevel,
+        CommandBufferUsage, RecordingCommandBuffer, RenderPassBeginInfo,
+    },
+    descriptor_set::{
+        allocator::StandardDescriptorSetAllocator, DescriptorSet, WriteDescriptorSet,
+    },
+    device::{Device, DeviceExtensions, DeviceFeatures, Queue},
+    format::Format,
+    image::{view::ImageView, Image, ImageCreateInfo, ImageLayout, ImageUsage, SampleCount},
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
+    pipeline::{
+        compute::ComputePipelineCreateInfo,
+        graphics::{
+            color_blend::{ColorBlendAttachmentState, ColorBlendState},
+            input_assembly::InputAssemblyState,
+            multisample::MultisampleState,
+            rasterization::RasterizationState,
+            vertex_input::{Vertex, VertexDefinition},
+            viewport::{Viewport, ViewportState},
+            GraphicsPipelineCreateInfo,
+        },
+        layout::PipelineDescriptorSetLayoutCreateInfo,
+        ComputePipeline, GraphicsPipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+        PipelineShaderStageCreateInfo,
+    },
+    render_pass::{
+        AttachmentDescription, AttachmentLoadOp, AttachmentReference, AttachmentStoreOp,
+        Framebuffer, FramebufferCreateInfo, RenderPass, RenderPassCreateInfo, Subpass,
+        SubpassDescription,
+    },
+    shader::{EntryPoint, ShaderModule},
+    sync::{self, GpuFuture},
+    testing::{
+        golden::{assert_image_matches, read_image_layer},
+        test_device,
+    },
+};
+
+const EXTENT: [u32; 3] = [64, 64, 1];
+
+// If set, the tests fail instead of being skipped when the device doesn't support them. CI sets it
+// when running the tests on lavapipe.
+const REQUIRE_DEVICE_ENV: &str = "VULKANO_REQUIRE_DEVICE";
+
+// Rasterization rules leave some room for implementations along the edges of primitives, so allow
+// a small difference in each component.
+const TOLERANCE: [u8; 4] = [2; 4];
+
+#[derive(BufferContents, Vertex)]
+#[repr(C)]
+struct MyVertex {
+    #[format(R32G32_SFLOAT)]
+    position: [f32; 2],
+}
+
+mod vs {
+    vulkano_shaders::shader! {
+        ty: "vertex",
+        src: r"
+            #version 450
+
+            layout(location = 0) in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+    }
+}
+
+// Offsets the triangle based on the view index, like the `multiview` example.
+mod vs_multiview {
+    vulkano_shaders::shader! {
+        ty: "vertex",
+        src: r"
+            #version 450
+            #extension GL_EXT_multiview : enable
+
+            layout(location = 0) in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0) + gl_ViewIndex * vec4(0.25, 0.25, 0.0, 0.0);
+            }
+        ",
+    }
+}
+
+mod fs {
+    vulkano_shaders::shader! {
+        ty: "fragment",
+        src: r"
+            #version 450
+
+            layout(location = 0) out vec4 f_color;
+
+            void main() {
+                f_color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+    }
+}
+
+#[track_caller]
+fn skip(reason: &str) {
+    assert!(
+        env::var_os(REQUIRE_DEVICE_ENV).is_none(),
+        "{reason}, but `{REQUIRE_DEVICE_ENV}` is set",
+    );
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "tests", "golden", name]
+        .iter()
+        .collect()
+}
+
+fn command_buffer_builder(queue: &Arc<Queue>) -> RecordingCommandBuffer {
+    RecordingCommandBuffer::new(
+        Arc::new(StandardCommandBufferAllocator::new(
+            queue.device().clone(),
+            Default::default(),
+        )),
+        queue.queue_family_index(),
+        CommandBufferLevel::Primary,
+        CommandBufferBeginInfo {
+            usage: CommandBufferUsage::OneTimeSubmit,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+fn submit_and_wait(queue: &Arc<Queue>, builder: RecordingCommandBuffer) {
+    sync::now(queue.device().clone())
+        .then_execute(queue.clone(), builder.end().unwrap())
+        .unwrap()
+        .then_signal_fence_and_flush()
+        .unwrap()
+        .wait(None)
+        .unwrap();
+}
+
+fn entry_point(module: &Arc<ShaderModule>) -> EntryPoint {
+    module.entry_point("main").unwrap()
+}
+
+fn vertex_buffer(memory_allocator: Arc<StandardMemoryAllocator>) -> Subbuffer<[MyVertex]> {
+    Buffer::from_iter(
+        memory_allocator,
+        BufferCreateInfo {
+            usage: BufferUsage::VERTEX_BUFFER,
+            ..Default::default()
+        },
+        AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
+                | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+            ..Default::default()
+        },
+        [
+            MyVertex {
+                position: [-0.5, -0.25],
+            },
+            MyVertex {
+                position: [0.0, 0.5],
+            },
+            MyVertex {
+                position: [0.25, -0.1],
+            },
+        ],
+    )
+    .unwrap()
+}
+
+/// Renders the triangle from the `triangle` and `multiview` examples to an image with
+/// `array_layers` layers, using a render pass with the given view mask.
+fn render_triangle(
+    device: &Arc<Device>,
+    queue: &Arc<Queue>,
+    vs: EntryPoint,
+    array_layers: u32,
+    view_mask: u32,
+) -> Arc<Image> {
+    let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+
+    let image = Image::new(
+        memory_allocator.clone(),
+        ImageCreateInfo {
+            format: Format::R8G8B8A8_UNORM,
+            extent: EXTENT,
+            array_layers,
+            usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::TRANSFER_SRC,
+            ..Default::default()
+        },
+        AllocationCreateInfo::default(),
+    )
+    .unwrap();
+
+    let render_pass = RenderPass::new(
+        device.clone(),
+        RenderPassCreateInfo {
+            attachments: vec![AttachmentDescription {
+                format: image.format(),
+                samples: SampleCount::Sample1,
+                load_op: AttachmentLoadOp::Clear,
+                store_op: AttachmentStoreOp::Store,
+                initial_layout: ImageLayout::ColorAttachmentOptimal,
+                final_layout: ImageLayout::ColorAttachmentOptimal,
+                ..Default::default()
+            }],
+            subpasses: vec![SubpassDescription {
+                view_mask,
+                color_attachments: vec![Some(AttachmentReference {
+                    attachment: 0,
+                    layout: ImageLayout::ColorAttachmentOptimal,
+                    ..Default::default()
+                })],
+                ..Default::default()
+            }],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let framebuffer = Framebuffer::new(
+        render_pass.clone(),
+        FramebufferCreateInfo {
+            attachments: vec![ImageView::new_default(image.clone()).unwrap()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let fs = entry_point(&fs::load(device.clone()).unwrap());
+    let vertex_input_state = MyVertex::per_vertex().definition(&vs).unwrap();
+    let stages = [
+        PipelineShaderStageCreateInfo::new(vs),
+        PipelineShaderStageCreateInfo::new(fs),
+    ];
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+            .into_pipeline_layout_create_info(device.clone())
+            .unwrap(),
+    )
+    .unwrap();
+    let subpass = Subpass::from(render_pass, 0).unwrap();
+    let pipeline = GraphicsPipeline::new(
+        device.clone(),
+        None,
+        GraphicsPipelineCreateInfo {
+            stages: stages.into_iter().collect(),
+            vertex_input_state: Some(vertex_input_state),
+            input_assembly_state: Some(InputAssemblyState::default()),
+            viewport_state: Some(ViewportState {
+                viewports: [Viewport {
+                    offset: [0.0, 0.0],
+                    extent: [EXTENT[0] as f32, EXTENT[1] as f32],
+                    depth_range: 0.0..=1.0,
+                }]
+                .into_iter()
+                .collect(),
+                ..Default::default()
+            }),
+            rasterization_state: Some(RasterizationState::default()),
+            multisample_state: Some(MultisampleState::default()),
+            color_blend_state: Some(ColorBlendState::with_attachment_states(
+                subpass.num_color_attachments(),
+                ColorBlendAttachmentState::default(),
+            )),
+            subpass: Some(subpass.into()),
+            ..GraphicsPipelineCreateInfo::layout(layout)
+        },
+    )
+    .unwrap();
+
+    let vertex_buffer = vertex_buffer(memory_allocator);
+
+    let mut builder = command_buffer_builder(queue);
+    builder
+        .begin_render_pass(
+            RenderPassBeginInfo {
+                clear_values: vec![Some([0.0, 0.0, 1.0, 1.0].into())],
+                ..RenderPassBeginInfo::framebuffer(framebuffer)
+            },
+            Default::default(),
+        )
+        .unwrap()
+        .bind_pipeline_graphics(pipeline)
+        .unwrap()
+        .bind_vertex_buffers(0, vertex_buffer.clone())
+        .unwrap();
+
+    unsafe {
+        builder.draw(vertex_buffer.len() as u32, 1, 0, 0).unwrap();
+    }
+
+    builder.end_render_pass(Default::default()).unwrap();
+    submit_and_wait(queue, builder);
+
+    image
+}
+
+#[test]
+fn triangle() {
+    let Some((device, queue)) = test_device(&DeviceExtensions::empty(), &DeviceFeatures::empty())
+    else {
+        skip("no suitable device was found");
+        return;
+    };
+
+    let image = render_triangle(
+        &device,
+        &queue,
+        entry_point(&vs::load(device.clone()).unwrap()),
+        1,
+        0,
+    );
+
+    assert_image_matches(
+        &read_image_layer(&queue, &image, 0),
+        EXTENT[0],
+        EXTENT[1],
+        golden_path("triangle.png"),
+        TOLERANCE,
+    );
+}
+
+#[test]
+fn multiview() {
+    let features = DeviceFeatures {
+        multiview: true,
+        ..DeviceFeatures::empty()
+    };
+    let Some((device, queue)) = test_device(&DeviceExtensions::empty(), &features) else {
+        skip("no device supporting multiview was found");
+        return;
+    };
+
+    if device
+        .physical_device()
+        .properties()
+        .max_multiview_view_count
+        .unwrap_or(0)
+        < 2
+    {
+        skip("the device doesn't support two views");
+        return;
+    }
+
+    // Each view renders the triangle with a different offset, so the layers must not be equal.
+    let image = render_triangle(
+        &device,
+        &queue,
+        entry_point(&vs_multiview::load(device.clone()).unwrap()),
+        2,
+        0b11,
+    );
+
+    for (array_layer, name) in [(0, "multiview0.png"), (1, "multiview1.png")] {
+        assert_image_matches(
+            &read_image_layer(&queue, &image, array_layer),
+            EXTENT[0],
+            EXTENT[1],
+            golden_path(name),
+            TOLERANCE,
+        );
+    }
+}
+
+#[test]
+fn compute_image_write() {
+    let Some((device, queue)) = test_device(&DeviceExtensions::empty(), &DeviceFeatures::empty())
+    else {
+        skip("no suitable device was found");
+        return;
+    };
+
+    mod cs {
+        vulkano_shaders::shader! {
+            ty: "compute",
+            src: r"
+                #version 450
+
+                layout(local_size_x = 8, local_size_y = 8, local_size_z = 1) in;
+
+                layout(set = 0, binding = 0, rgba8) uniform writeonly image2D img;
+
+                void main() {
+                    vec2 uv = vec2(gl_GlobalInvocationID.xy) / vec2(imageSize(img) - 1);
+                    bool checker = ((gl_GlobalInvocationID.x ^ gl_GlobalInvocationID.y) & 8) != 0;
+
+                    imageStore(img, ivec2(gl_GlobalInvocationID.xy), vec4(uv, float(checker), 1.0));
+                }
+            ",
+        }
+    }
+
+    let stage = PipelineShaderStageCreateInfo::new(entry_point(&cs::load(device.clone()).unwrap()));
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages([&stage])
+            .into_pipeline_layout_create_info(device.clone())
+            .unwrap(),
+    )
+    .unwrap();
+    let pipeline = ComputePipeline::new(
+        device.clone(),
+        None,
+        ComputePipelineCreateInfo::stage_layout(stage, layout),
+    )
+    .unwrap();
+
+    let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+    let image = Image::new(
+        memory_allocator,
+        ImageCreateInfo {
+            format: Format::R8G8B8A8_UNORM,
+            extent: EXTENT,
+            usage: ImageUsage::STORAGE | ImageUsage::TRANSFER_SRC,
+            ..Default::default()
+        },
+        AllocationCreateInfo::default(),
+    )
+    .unwrap();
+
+    let descriptor_set_allocator = Arc::new(StandardDescriptorSetAllocator::new(
+        device.clone(),
+        Default::default(),
+    ));
+    let set = DescriptorSet::new(
+        descriptor_set_allocator,
+        pipeline.layout().set_layouts()[0].clone(),
+        [WriteDescriptorSet::image_view(
+            0,
+            ImageView::new_default(image.clone()).unwrap(),
+        )],
+        [],
+    )
+    .unwrap();
+
+    let mut builder = command_buffer_builder(&queue);
+    builder
+        .bind_pipeline_compute(pipeline.clone())
+        .unwrap()
+        .bind_descriptor_sets(
+            PipelineBindPoint::Compute,
+            pipeline.layout().clone(),
+            0,
+            set,
+        )
+        .unwrap();
+
+    unsafe {
+        builder.dispatch([EXTENT[0] / 8, EXTENT[1] / 8, 1]).unwrap();
+    }
+
+    submit_and_wait(&queue, builder);
+
+    assert_image_matches(
+        &read_image_layer(&queue, &image, 0),
+        EXTENT[0],
+        EXTENT[1],
+        golden_path("compute_image_write.png"),
+        // The result is computed exactly, apart from the rounding of the conversion to UNORM.
+        [1; 4],
+    );
+}